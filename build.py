@@ -117,6 +117,11 @@ def build(do_clean=False, exclude_runner=True, create_zip_artifact=False):
             result = subprocess.run(['strip', dst], stdout=subprocess.PIPE)
         os.system("chmod 755 " + dst) # grant executable 
 
+        # Copy the Runner's interface string catalogs
+        src = os.path.join(app_dir, 'whitebox-runner/locales')
+        dst = os.path.join(output_dir, 'locales')
+        copytree(src, dst)
+
     src = os.path.join(app_dir, 'whitebox_tools.py')
     dst = os.path.join(output_dir, 'whitebox_tools.py')
     copyfile(src, dst)
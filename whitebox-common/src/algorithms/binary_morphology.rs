@@ -0,0 +1,472 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+/// The shape of the neighbourhood used by `dilate_mask` and `erode_mask`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StructuringElement {
+    /// All cells within `radius` rows and `radius` columns (a (2r+1) x (2r+1) square).
+    Square,
+    /// All cells whose centres are within `radius` cells (Euclidean) of the centre cell.
+    Circle,
+}
+
+/// Dilates a binary mask, stored in row-major order, by a structuring element of the given
+/// radius (in cells): a cell is set in the output if any set cell of `mask` lies within its
+/// neighbourhood. The result is the same as a single pass with the full structuring element,
+/// and for `Square` the same as `radius` passes of a 3 x 3 dilation, but runs in time
+/// proportional to the number of cells regardless of the radius. A square element is applied
+/// as separate row and column dilations; a circular element thresholds the exact Euclidean
+/// distance transform of the mask (Felzenszwalb and Huttenlocher's lower-envelope algorithm).
+pub fn dilate_mask(
+    mask: &[bool],
+    rows: usize,
+    columns: usize,
+    radius: usize,
+    element: StructuringElement,
+) -> Vec<bool> {
+    assert_eq!(
+        mask.len(),
+        rows * columns,
+        "mask size does not match rows x columns"
+    );
+    if radius == 0 || rows == 0 || columns == 0 {
+        return mask.to_vec();
+    }
+
+    // Vertical distance from each cell to the nearest set cell in its column, capped at radius + 1.
+    let far = radius as u32 + 1;
+    let mut vertical = vec![far; rows * columns];
+    for col in 0..columns {
+        let mut d = far;
+        for row in 0..rows {
+            d = if mask[row * columns + col] {
+                0
+            } else {
+                (d + 1).min(far)
+            };
+            vertical[row * columns + col] = d;
+        }
+        d = far;
+        for row in (0..rows).rev() {
+            let i = row * columns + col;
+            d = if mask[i] { 0 } else { (d + 1).min(far) };
+            vertical[i] = vertical[i].min(d);
+        }
+    }
+
+    let mut output = vec![false; rows * columns];
+    match element {
+        StructuringElement::Square => {
+            // A cell is set if a cell within `radius` columns is within `radius` rows of a set cell.
+            for row in 0..rows {
+                let line = &vertical[row * columns..(row + 1) * columns];
+                let mut d = far;
+                let mut left = vec![far; columns];
+                for col in 0..columns {
+                    d = if line[col] < far { 0 } else { (d + 1).min(far) };
+                    left[col] = d;
+                }
+                d = far;
+                for col in (0..columns).rev() {
+                    d = if line[col] < far { 0 } else { (d + 1).min(far) };
+                    output[row * columns + col] = left[col].min(d) < far;
+                }
+            }
+        }
+        StructuringElement::Circle => {
+            let r2 = (radius * radius) as f64;
+            let mut f = vec![f64::INFINITY; columns];
+            let mut dist = vec![0f64; columns];
+            for row in 0..rows {
+                for col in 0..columns {
+                    let v = vertical[row * columns + col];
+                    f[col] = if v < far {
+                        (v as f64) * (v as f64)
+                    } else {
+                        f64::INFINITY
+                    };
+                }
+                squared_distance_1d(&f, &mut dist);
+                for col in 0..columns {
+                    output[row * columns + col] = dist[col] <= r2;
+                }
+            }
+        }
+    }
+    output
+}
+
+/// Erodes a binary mask, stored in row-major order, by a structuring element of the given radius
+/// (in cells): a cell remains set only if every cell of its neighbourhood is set. Cells beyond the
+/// edges of the grid count as unset, so the mask is eroded inwards from the grid edges as well.
+pub fn erode_mask(
+    mask: &[bool],
+    rows: usize,
+    columns: usize,
+    radius: usize,
+    element: StructuringElement,
+) -> Vec<bool> {
+    assert_eq!(
+        mask.len(),
+        rows * columns,
+        "mask size does not match rows x columns"
+    );
+    if radius == 0 || rows == 0 || columns == 0 {
+        return mask.to_vec();
+    }
+
+    // Erosion is the complement of the dilated complement. The complement is padded with a ring
+    // of set cells for the area beyond the grid edges; the nearest cell beyond an edge always
+    // lies in that ring.
+    let (prows, pcolumns) = (rows + 2, columns + 2);
+    let mut complement = vec![true; prows * pcolumns];
+    for row in 0..rows {
+        for col in 0..columns {
+            complement[(row + 1) * pcolumns + col + 1] = !mask[row * columns + col];
+        }
+    }
+    let dilated = dilate_mask(&complement, prows, pcolumns, radius, element);
+    let mut output = vec![false; rows * columns];
+    for row in 0..rows {
+        for col in 0..columns {
+            output[row * columns + col] = !dilated[(row + 1) * pcolumns + col + 1];
+        }
+    }
+    output
+}
+
+/// Returns the Euclidean distance, in map units, from the centre of each cell of a binary mask
+/// (stored in row-major order) to the centre of the nearest set cell, for cells of the given
+/// `cell_size` (`[x, y]`, which need not be square). The distances are exact and computed in time
+/// proportional to the number of cells, whatever their spread, so all set cells are sources of
+/// a single pass. Every distance is infinite when no cell is set.
+pub fn distance_transform(
+    mask: &[bool],
+    rows: usize,
+    columns: usize,
+    cell_size: [f64; 2],
+) -> Vec<f64> {
+    assert_eq!(
+        mask.len(),
+        rows * columns,
+        "mask size does not match rows x columns"
+    );
+    if rows == 0 || columns == 0 {
+        return vec![];
+    }
+
+    // Vertical distance, in rows, from each cell to the nearest set cell in its column.
+    let far = (rows + 1) as u32;
+    let mut vertical = vec![far; rows * columns];
+    for col in 0..columns {
+        let mut d = far;
+        for row in 0..rows {
+            d = if mask[row * columns + col] {
+                0
+            } else {
+                (d + 1).min(far)
+            };
+            vertical[row * columns + col] = d;
+        }
+        d = far;
+        for row in (0..rows).rev() {
+            let i = row * columns + col;
+            d = if mask[i] { 0 } else { (d + 1).min(far) };
+            vertical[i] = vertical[i].min(d);
+        }
+    }
+
+    // The row pass works in units of the cell width, so the vertical distances are rescaled.
+    let [res_x, res_y] = cell_size;
+    let ratio = res_y / res_x;
+    let mut output = vec![f64::INFINITY; rows * columns];
+    let mut f = vec![f64::INFINITY; columns];
+    let mut dist = vec![0f64; columns];
+    for row in 0..rows {
+        for col in 0..columns {
+            let v = vertical[row * columns + col];
+            f[col] = if v < far {
+                let dy = v as f64 * ratio;
+                dy * dy
+            } else {
+                f64::INFINITY
+            };
+        }
+        squared_distance_1d(&f, &mut dist);
+        for col in 0..columns {
+            output[row * columns + col] = dist[col].sqrt() * res_x;
+        }
+    }
+    output
+}
+
+/// The 1-D squared distance transform of a sampled function `f` (0 at set cells, infinity
+/// elsewhere, or the squared vertical distances of a 2-D transform): `d[q] = min_p (q - p)^2 + f[p]`,
+/// computed from the lower envelope of the parabolas rooted at the finite samples of `f`.
+fn squared_distance_1d(f: &[f64], d: &mut [f64]) {
+    let n = f.len();
+    let mut v: Vec<usize> = Vec::with_capacity(n); // parabola roots in the lower envelope
+    let mut z: Vec<f64> = Vec::with_capacity(n); // left boundary of each parabola's interval
+    for q in 0..n {
+        if !f[q].is_finite() {
+            continue;
+        }
+        let qf = q as f64;
+        while let Some(&p) = v.last() {
+            let pf = p as f64;
+            let s = ((f[q] + qf * qf) - (f[p] + pf * pf)) / (2.0 * (qf - pf));
+            if s <= z[z.len() - 1] {
+                v.pop();
+                z.pop();
+            } else {
+                v.push(q);
+                z.push(s);
+                break;
+            }
+        }
+        if v.is_empty() {
+            v.push(q);
+            z.push(f64::NEG_INFINITY);
+        }
+    }
+
+    if v.is_empty() {
+        d.iter_mut().for_each(|x| *x = f64::INFINITY);
+        return;
+    }
+    let mut k = 0;
+    for (q, dq) in d.iter_mut().enumerate() {
+        let qf = q as f64;
+        while k + 1 < v.len() && z[k + 1] < qf {
+            k += 1;
+        }
+        let offset = qf - v[k] as f64;
+        *dq = offset * offset + f[v[k]];
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dilate_mask, distance_transform, erode_mask, StructuringElement};
+
+    // Random masks from a simple linear congruential generator, for repeatable tests.
+    fn random_mask(rows: usize, columns: usize, density: f64, seed: u64) -> Vec<bool> {
+        let mut state = seed;
+        (0..rows * columns)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                ((state >> 33) as f64 / (1u64 << 31) as f64) < density
+            })
+            .collect()
+    }
+
+    fn in_element(dr: isize, dc: isize, radius: usize, element: StructuringElement) -> bool {
+        let r = radius as isize;
+        match element {
+            StructuringElement::Square => dr.abs() <= r && dc.abs() <= r,
+            StructuringElement::Circle => dr * dr + dc * dc <= r * r,
+        }
+    }
+
+    // Applies the structuring element cell by cell. Off-grid cells are unset when dilating and
+    // also unset (so any off-grid neighbour clears the cell) when eroding.
+    fn brute_force(
+        mask: &[bool],
+        rows: usize,
+        columns: usize,
+        radius: usize,
+        element: StructuringElement,
+        erode: bool,
+    ) -> Vec<bool> {
+        let r = radius as isize;
+        let mut output = vec![false; rows * columns];
+        for row in 0..rows as isize {
+            for col in 0..columns as isize {
+                let mut any = false;
+                let mut all = true;
+                for dr in -r..=r {
+                    for dc in -r..=r {
+                        if !in_element(dr, dc, radius, element) {
+                            continue;
+                        }
+                        let (rn, cn) = (row + dr, col + dc);
+                        let set = rn >= 0
+                            && cn >= 0
+                            && rn < rows as isize
+                            && cn < columns as isize
+                            && mask[rn as usize * columns + cn as usize];
+                        any |= set;
+                        all &= set;
+                    }
+                }
+                output[row as usize * columns + col as usize] = if erode { all } else { any };
+            }
+        }
+        output
+    }
+
+    #[test]
+    fn test_square_dilation_equals_iterated_3x3_dilation() {
+        for (seed, density) in [(1u64, 0.02), (2, 0.1), (3, 0.4)] {
+            let (rows, columns) = (23, 31);
+            let mask = random_mask(rows, columns, density, seed);
+            let mut iterated = mask.clone();
+            for radius in 1..=6 {
+                iterated = brute_force(
+                    &iterated,
+                    rows,
+                    columns,
+                    1,
+                    StructuringElement::Square,
+                    false,
+                );
+                let dilated = dilate_mask(&mask, rows, columns, radius, StructuringElement::Square);
+                assert_eq!(dilated, iterated, "seed {} radius {}", seed, radius);
+            }
+        }
+    }
+
+    #[test]
+    fn test_circle_dilation_equals_brute_force() {
+        for (seed, density) in [(4u64, 0.01), (5, 0.05), (6, 0.3)] {
+            let (rows, columns) = (29, 17);
+            let mask = random_mask(rows, columns, density, seed);
+            for radius in 1..=7 {
+                let expected = brute_force(
+                    &mask,
+                    rows,
+                    columns,
+                    radius,
+                    StructuringElement::Circle,
+                    false,
+                );
+                let dilated = dilate_mask(&mask, rows, columns, radius, StructuringElement::Circle);
+                assert_eq!(dilated, expected, "seed {} radius {}", seed, radius);
+            }
+        }
+    }
+
+    #[test]
+    fn test_erosion_equals_brute_force() {
+        for element in [StructuringElement::Square, StructuringElement::Circle] {
+            for (seed, density) in [(7u64, 0.7), (8, 0.9), (9, 0.97)] {
+                let (rows, columns) = (19, 26);
+                let mask = random_mask(rows, columns, density, seed);
+                for radius in 1..=4 {
+                    let expected = brute_force(&mask, rows, columns, radius, element, true);
+                    let eroded = erode_mask(&mask, rows, columns, radius, element);
+                    assert_eq!(
+                        eroded, expected,
+                        "{:?} seed {} radius {}",
+                        element, seed, radius
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance_transform_equals_brute_force() {
+        for (seed, density) in [(10u64, 0.005), (11, 0.05), (12, 0.3)] {
+            let (rows, columns) = (21, 34);
+            let mask = random_mask(rows, columns, density, seed);
+            for cell_size in [[1.0, 1.0], [10.0, 10.0], [2.5, 4.0], [30.0, 7.5]] {
+                let distances = distance_transform(&mask, rows, columns, cell_size);
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let mut expected = f64::INFINITY;
+                        for (i, _) in mask.iter().enumerate().filter(|(_, &set)| set) {
+                            let dx = (col as f64 - (i % columns) as f64) * cell_size[0];
+                            let dy = (row as f64 - (i / columns) as f64) * cell_size[1];
+                            expected = expected.min((dx * dx + dy * dy).sqrt());
+                        }
+                        let d = distances[row * columns + col];
+                        assert!(
+                            (d - expected).abs() <= 1e-9 * expected.max(1.0),
+                            "seed {} cell size {:?} at ({}, {}): {} != {}",
+                            seed,
+                            cell_size,
+                            row,
+                            col,
+                            d,
+                            expected
+                        );
+                    }
+                }
+            }
+        }
+        assert!(distance_transform(&[false; 12], 3, 4, [1.0, 1.0])
+            .iter()
+            .all(|d| d.is_infinite()));
+    }
+
+    #[test]
+    fn test_erosion_peels_boundary_layers() {
+        let cells = |mask: &[bool], columns: usize| -> Vec<(usize, usize)> {
+            (0..mask.len())
+                .filter(|&i| mask[i])
+                .map(|i| (i / columns, i % columns))
+                .collect()
+        };
+        // A 5 x 5 block in the middle of a 7 x 7 grid, with a one-cell spur of noise above it.
+        let mut mask = vec![false; 49];
+        for row in 1..6 {
+            for col in 1..6 {
+                mask[row * 7 + col] = true;
+            }
+        }
+        mask[3] = true;
+
+        let erode = |radius: usize| erode_mask(&mask, 7, 7, radius, StructuringElement::Square);
+        assert_eq!(cells(&erode(0), 7).len(), 26);
+        let eroded = cells(&erode(1), 7);
+        assert_eq!(eroded.len(), 9);
+        assert_eq!(eroded.first(), Some(&(2, 2)));
+        assert_eq!(eroded.last(), Some(&(4, 4)));
+        assert_eq!(cells(&erode(2), 7), vec![(3, 3)]);
+        assert_eq!(cells(&erode(3), 7), vec![]);
+        assert_eq!(cells(&erode(10), 7), vec![]);
+
+        // The grid edge bounds the mask as its outside does.
+        let mut full = vec![true; 16];
+        let eroded = erode_mask(&full, 4, 4, 1, StructuringElement::Square);
+        assert_eq!(cells(&eroded, 4), vec![(1, 1), (1, 2), (2, 1), (2, 2)]);
+        // A notch is eroded around its corner as well as along its sides.
+        full[0] = false;
+        let eroded = erode_mask(&full, 4, 4, 1, StructuringElement::Square);
+        assert_eq!(cells(&eroded, 4), vec![(1, 2), (2, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_empty_and_zero_radius() {
+        let mask = vec![false; 12];
+        assert_eq!(
+            dilate_mask(&mask, 3, 4, 3, StructuringElement::Circle),
+            mask
+        );
+        let full = vec![true; 12];
+        assert_eq!(
+            dilate_mask(&full, 3, 4, 0, StructuringElement::Square),
+            full
+        );
+        // a 3 x 4 grid eroded by radius 2 loses every cell, as each is within 2 cells of an edge
+        assert_eq!(
+            erode_mask(&full, 3, 4, 2, StructuringElement::Square),
+            vec![false; 12]
+        );
+        assert_eq!(
+            erode_mask(&full, 3, 4, 1, StructuringElement::Square)
+                .iter()
+                .filter(|&&v| v)
+                .count(),
+            2
+        );
+    }
+}
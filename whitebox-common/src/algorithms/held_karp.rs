@@ -0,0 +1,190 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+/// The largest number of locations accepted by `held_karp_tour`. The dynamic
+/// programming table grows as O(2^n * n), so larger problems should be solved
+/// heuristically.
+pub const HELD_KARP_MAX_LOCATIONS: usize = 20;
+
+/// Finds the optimal closed tour through `n` locations using the Held-Karp
+/// dynamic programming algorithm. `cost(i, j)` returns the cost of travelling
+/// from location `i` to location `j`. The returned tour starts at location 0 and
+/// does not repeat the starting location at the end; the returned length includes
+/// the closing leg back to the start.
+///
+/// Returns `None` if `n` exceeds `HELD_KARP_MAX_LOCATIONS`.
+pub fn held_karp_tour<F>(n: usize, cost: F) -> Option<(Vec<usize>, f64)>
+where
+    F: Fn(usize, usize) -> f64,
+{
+    if n > HELD_KARP_MAX_LOCATIONS {
+        return None;
+    }
+    if n == 0 {
+        return Some((vec![], 0f64));
+    }
+    if n == 1 {
+        return Some((vec![0], 0f64));
+    }
+
+    // Location 0 is the fixed start; subsets are encoded over locations 1..n.
+    let m = n - 1;
+    let num_subsets = 1usize << m;
+    let mut dist = vec![vec![0f64; n]; n];
+    for (i, row) in dist.iter_mut().enumerate() {
+        for (j, d) in row.iter_mut().enumerate() {
+            if i != j {
+                *d = cost(i, j);
+            }
+        }
+    }
+
+    // best[subset * m + k] is the cost of the shortest path starting at 0, visiting
+    // every location in subset exactly once, and ending at location k + 1.
+    let mut best = vec![f64::INFINITY; num_subsets * m];
+    let mut parent = vec![usize::MAX; num_subsets * m];
+    for k in 0..m {
+        best[(1 << k) * m + k] = dist[0][k + 1];
+    }
+    for subset in 1..num_subsets {
+        for k in 0..m {
+            if subset & (1 << k) == 0 {
+                continue;
+            }
+            let prev_subset = subset ^ (1 << k);
+            if prev_subset == 0 {
+                continue;
+            }
+            let mut min_cost = f64::INFINITY;
+            let mut min_parent = usize::MAX;
+            for j in 0..m {
+                if prev_subset & (1 << j) == 0 {
+                    continue;
+                }
+                let c = best[prev_subset * m + j] + dist[j + 1][k + 1];
+                if c < min_cost {
+                    min_cost = c;
+                    min_parent = j;
+                }
+            }
+            best[subset * m + k] = min_cost;
+            parent[subset * m + k] = min_parent;
+        }
+    }
+
+    let full = num_subsets - 1;
+    let mut tour_len = f64::INFINITY;
+    let mut last = 0usize;
+    for k in 0..m {
+        let c = best[full * m + k] + dist[k + 1][0];
+        if c < tour_len {
+            tour_len = c;
+            last = k;
+        }
+    }
+
+    // walk the parent pointers back to the start
+    let mut tour = Vec::with_capacity(n);
+    let mut subset = full;
+    let mut k = last;
+    while k != usize::MAX {
+        tour.push(k + 1);
+        let next = parent[subset * m + k];
+        subset ^= 1 << k;
+        k = next;
+    }
+    tour.push(0);
+    tour.reverse();
+
+    Some((tour, tour_len))
+}
+
+#[cfg(test)]
+mod test {
+    use super::held_karp_tour;
+
+    fn euclidean(pts: &[(f64, f64)]) -> impl Fn(usize, usize) -> f64 + '_ {
+        move |i, j| ((pts[i].0 - pts[j].0).powi(2) + (pts[i].1 - pts[j].1).powi(2)).sqrt()
+    }
+
+    fn brute_force(n: usize, cost: &dyn Fn(usize, usize) -> f64) -> f64 {
+        fn permute(
+            rest: &mut Vec<usize>,
+            k: usize,
+            cost: &dyn Fn(usize, usize) -> f64,
+            best: &mut f64,
+        ) {
+            if k == rest.len() {
+                let mut len = cost(0, rest[0]);
+                for w in rest.windows(2) {
+                    len += cost(w[0], w[1]);
+                }
+                len += cost(*rest.last().unwrap(), 0);
+                if len < *best {
+                    *best = len;
+                }
+                return;
+            }
+            for i in k..rest.len() {
+                rest.swap(k, i);
+                permute(rest, k + 1, cost, best);
+                rest.swap(k, i);
+            }
+        }
+        let mut rest: Vec<usize> = (1..n).collect();
+        let mut best = f64::INFINITY;
+        permute(&mut rest, 0, cost, &mut best);
+        best
+    }
+
+    #[test]
+    fn test_square_tour() {
+        // the optimal tour around a unit square visits the corners in order
+        let pts = [(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)];
+        let (tour, len) = held_karp_tour(pts.len(), euclidean(&pts)).unwrap();
+        assert_eq!(tour.len(), 4);
+        assert_eq!(tour[0], 0);
+        assert!((len - 4.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let pts = [
+            (3.0, 7.0),
+            (9.5, 1.0),
+            (4.2, 4.4),
+            (0.3, 8.8),
+            (6.6, 2.1),
+            (8.0, 9.0),
+            (1.7, 0.4),
+            (5.5, 6.3),
+        ];
+        let cost = euclidean(&pts);
+        let (tour, len) = held_karp_tour(pts.len(), &cost).unwrap();
+        assert!((len - brute_force(pts.len(), &cost)).abs() < 1e-9);
+
+        // every location is visited exactly once and the reported length matches the tour
+        let mut sorted = tour.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..pts.len()).collect::<Vec<usize>>());
+        let mut tour_len = cost(*tour.last().unwrap(), tour[0]);
+        for w in tour.windows(2) {
+            tour_len += cost(w[0], w[1]);
+        }
+        assert!((tour_len - len).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_trivial_and_oversized_inputs() {
+        assert_eq!(held_karp_tour(1, |_, _| 1.0).unwrap().0, vec![0]);
+        let (tour, len) = held_karp_tour(2, |_, _| 2.5).unwrap();
+        assert_eq!(tour, vec![0, 1]);
+        assert!((len - 5.0).abs() < 1e-9);
+        assert!(held_karp_tour(21, |_, _| 1.0).is_none());
+    }
+}
@@ -6,9 +6,11 @@ Last Modified: 25/09/2018
 License: MIT
 */
 // private sub-module defined in other files
+mod binary_morphology;
 mod calculate_rotation_degrees;
 mod convex_hull;
 mod delaunay_triangulation;
+mod held_karp;
 mod is_clockwise_order;
 mod line_ops;
 // mod lzw;
@@ -16,13 +18,22 @@ mod minimum_bounding_box;
 mod poly_area;
 mod poly_ops;
 mod poly_perimeter;
+mod raster_sampling;
+mod rasterize;
 mod smallest_enclosing_circle;
+mod stream_links;
+mod synthetic_hydro;
+mod zone_boundaries;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::binary_morphology::{
+    dilate_mask, distance_transform, erode_mask, StructuringElement,
+};
 pub use self::calculate_rotation_degrees::calculate_rotation_degrees;
 pub use self::convex_hull::convex_hull;
 pub use self::delaunay_triangulation::EMPTY;
 pub use self::delaunay_triangulation::{triangulate, Triangulation};
+pub use self::held_karp::{held_karp_tour, HELD_KARP_MAX_LOCATIONS};
 pub use self::is_clockwise_order::is_clockwise_order;
 pub use self::line_ops::{
     do_polylines_intersect, find_line_intersections, find_split_points_at_line_intersections,
@@ -35,4 +46,14 @@ pub use self::poly_ops::{
     interior_point, point_in_poly, poly_in_poly, poly_is_convex, poly_overlaps_poly, winding_number,
 };
 pub use self::poly_perimeter::polygon_perimeter;
+pub use self::raster_sampling::{
+    sample_bilinear, sample_cubic, sample_grid, GridGeometry, InterpolationMethod, NodataPolicy,
+};
+pub use self::rasterize::{rasterize_point, rasterize_polygon, rasterize_polyline};
 pub use self::smallest_enclosing_circle::smallest_enclosing_circle;
+pub use self::stream_links::{d8_path_length, trace_stream_links, D8Walker, D8_DX, D8_DY};
+pub use self::synthetic_hydro::{
+    synthetic_hydro_dataset, SyntheticHydroDataset, SyntheticHydroParams, SyntheticTerrain,
+    SYNTHETIC_NODATA,
+};
+pub use self::zone_boundaries::{trace_zone_boundaries, ZoneRing};
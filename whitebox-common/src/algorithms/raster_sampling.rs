@@ -0,0 +1,281 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+/// The grid geometry needed to convert map coordinates into fractional
+/// cell positions. Cell values are assumed to represent cell centres, i.e.
+/// the centre of cell (row, column) lies at
+/// `(west + (column + 0.5) * resolution_x, north - (row + 0.5) * resolution_y)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GridGeometry {
+    pub rows: usize,
+    pub columns: usize,
+    pub west: f64,
+    pub north: f64,
+    pub resolution_x: f64,
+    pub resolution_y: f64,
+    pub nodata: f64,
+}
+
+/// Determines how nodata cells within the interpolation kernel are treated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NodataPolicy {
+    /// Return `None` if any contributing cell is nodata.
+    Strict,
+    /// Drop nodata cells and renormalize the weights of the remaining cells.
+    /// Returns `None` only if no valid cell contributes to the estimate.
+    WeightedPartial,
+}
+
+/// The interpolation kernel used by `sample_grid`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InterpolationMethod {
+    Bilinear,
+    Cubic,
+}
+
+impl GridGeometry {
+    /// Converts a map coordinate into fractional (row, column) cell-centre
+    /// coordinates. Returns `None` if the point lies outside the grid extent.
+    fn fractional_position(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        if self.rows == 0
+            || self.columns == 0
+            || self.resolution_x <= 0f64
+            || self.resolution_y <= 0f64
+            || !x.is_finite()
+            || !y.is_finite()
+        {
+            return None;
+        }
+        let col = (x - self.west) / self.resolution_x;
+        let row = (self.north - y) / self.resolution_y;
+        if col < 0f64 || row < 0f64 || col > self.columns as f64 || row > self.rows as f64 {
+            return None;
+        }
+        // Within the outer half-cell the estimate is clamped to the edge cell centres.
+        let max_row = (self.rows - 1) as f64;
+        let max_col = (self.columns - 1) as f64;
+        Some((
+            (row - 0.5).max(0f64).min(max_row),
+            (col - 0.5).max(0f64).min(max_col),
+        ))
+    }
+}
+
+/// Samples a grid at map coordinate (`x`, `y`) using bilinear interpolation
+/// between the four surrounding cell centres. `get_value` returns the value
+/// of the cell at (row, column); it is only called for in-grid cells.
+pub fn sample_bilinear<F>(
+    geometry: &GridGeometry,
+    x: f64,
+    y: f64,
+    policy: NodataPolicy,
+    get_value: F,
+) -> Option<f64>
+where
+    F: Fn(isize, isize) -> f64,
+{
+    let (fr, fc) = geometry.fractional_position(x, y)?;
+    let r0 = fr.floor() as isize;
+    let c0 = fc.floor() as isize;
+    let ty = fr - r0 as f64;
+    let tx = fc - c0 as f64;
+    let max_row = geometry.rows as isize - 1;
+    let max_col = geometry.columns as isize - 1;
+
+    let mut sum = 0f64;
+    let mut weight_sum = 0f64;
+    for (dr, wy) in [(0isize, 1f64 - ty), (1isize, ty)] {
+        for (dc, wx) in [(0isize, 1f64 - tx), (1isize, tx)] {
+            let w = wy * wx;
+            let z = get_value((r0 + dr).min(max_row), (c0 + dc).min(max_col));
+            if z == geometry.nodata || !z.is_finite() {
+                // a cell with zero weight does not contribute to the estimate
+                if policy == NodataPolicy::Strict && w > 0f64 {
+                    return None;
+                }
+                continue;
+            }
+            sum += w * z;
+            weight_sum += w;
+        }
+    }
+    finalize(sum, weight_sum, policy)
+}
+
+/// Samples a grid at map coordinate (`x`, `y`) using cubic convolution
+/// (Keys, 1981; a = -0.5) over the surrounding 4 x 4 cell neighbourhood.
+/// Edge rows and columns are replicated where the kernel extends beyond the grid.
+pub fn sample_cubic<F>(
+    geometry: &GridGeometry,
+    x: f64,
+    y: f64,
+    policy: NodataPolicy,
+    get_value: F,
+) -> Option<f64>
+where
+    F: Fn(isize, isize) -> f64,
+{
+    let (fr, fc) = geometry.fractional_position(x, y)?;
+    let r0 = fr.floor() as isize;
+    let c0 = fc.floor() as isize;
+    let ty = fr - r0 as f64;
+    let tx = fc - c0 as f64;
+    let max_row = geometry.rows as isize - 1;
+    let max_col = geometry.columns as isize - 1;
+
+    let mut sum = 0f64;
+    let mut weight_sum = 0f64;
+    for dr in -1isize..=2 {
+        let wy = cubic_weight(dr as f64 - ty);
+        let row = (r0 + dr).max(0).min(max_row);
+        for dc in -1isize..=2 {
+            let wx = cubic_weight(dc as f64 - tx);
+            let w = wy * wx;
+            let column = (c0 + dc).max(0).min(max_col);
+            let z = get_value(row, column);
+            if z == geometry.nodata || !z.is_finite() {
+                if policy == NodataPolicy::Strict && w != 0f64 {
+                    return None;
+                }
+                continue;
+            }
+            sum += w * z;
+            weight_sum += w;
+        }
+    }
+    finalize(sum, weight_sum, policy)
+}
+
+/// Samples a grid using the specified interpolation method.
+pub fn sample_grid<F>(
+    geometry: &GridGeometry,
+    x: f64,
+    y: f64,
+    method: InterpolationMethod,
+    policy: NodataPolicy,
+    get_value: F,
+) -> Option<f64>
+where
+    F: Fn(isize, isize) -> f64,
+{
+    match method {
+        InterpolationMethod::Bilinear => sample_bilinear(geometry, x, y, policy, get_value),
+        InterpolationMethod::Cubic => sample_cubic(geometry, x, y, policy, get_value),
+    }
+}
+
+fn finalize(sum: f64, weight_sum: f64, policy: NodataPolicy) -> Option<f64> {
+    match policy {
+        NodataPolicy::Strict => Some(sum),
+        NodataPolicy::WeightedPartial => {
+            // Cubic weights can be negative, so a near-zero total means the
+            // valid cells cannot support a meaningful estimate.
+            if weight_sum.abs() < 1e-9 {
+                None
+            } else {
+                Some(sum / weight_sum)
+            }
+        }
+    }
+}
+
+/// The Keys cubic convolution kernel with a = -0.5 (Catmull-Rom).
+fn cubic_weight(t: f64) -> f64 {
+    let a = -0.5f64;
+    let t = t.abs();
+    if t <= 1f64 {
+        ((a + 2f64) * t - (a + 3f64)) * t * t + 1f64
+    } else if t < 2f64 {
+        ((a * t - 5f64 * a) * t + 8f64 * a) * t - 4f64 * a
+    } else {
+        0f64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sample_bilinear, sample_cubic, GridGeometry, NodataPolicy};
+
+    const NODATA: f64 = -32768f64;
+
+    // A 10 x 10 grid with 2 m cells whose values follow z = x + 2y at cell centres.
+    fn geometry() -> GridGeometry {
+        GridGeometry {
+            rows: 10,
+            columns: 10,
+            west: 100f64,
+            north: 520f64,
+            resolution_x: 2f64,
+            resolution_y: 2f64,
+            nodata: NODATA,
+        }
+    }
+
+    fn analytic(row: isize, column: isize) -> f64 {
+        let g = geometry();
+        let x = g.west + (column as f64 + 0.5) * g.resolution_x;
+        let y = g.north - (row as f64 + 0.5) * g.resolution_y;
+        x + 2f64 * y
+    }
+
+    #[test]
+    fn test_bilinear_recovers_plane() {
+        let g = geometry();
+        for &(x, y) in &[(103.3, 512.7), (110.0, 505.0), (117.9, 502.1), (101.0, 519.0)] {
+            let z = sample_bilinear(&g, x, y, NodataPolicy::Strict, analytic).unwrap();
+            assert!((z - (x + 2f64 * y)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_cubic_recovers_plane() {
+        let g = geometry();
+        for &(x, y) in &[(105.3, 512.7), (110.0, 505.0), (113.9, 506.1)] {
+            let z = sample_cubic(&g, x, y, NodataPolicy::Strict, analytic).unwrap();
+            assert!((z - (x + 2f64 * y)).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_at_cell_centre_returns_cell_value() {
+        let g = geometry();
+        let (x, y) = (100f64 + 3.5 * 2f64, 520f64 - 6.5 * 2f64);
+        let zb = sample_bilinear(&g, x, y, NodataPolicy::Strict, analytic).unwrap();
+        let zc = sample_cubic(&g, x, y, NodataPolicy::Strict, analytic).unwrap();
+        assert!((zb - analytic(6, 3)).abs() < 1e-9);
+        assert!((zc - analytic(6, 3)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_outside_extent_is_none() {
+        let g = geometry();
+        assert!(sample_bilinear(&g, 99.0, 510.0, NodataPolicy::Strict, analytic).is_none());
+        assert!(sample_cubic(&g, 110.0, 521.0, NodataPolicy::Strict, analytic).is_none());
+    }
+
+    #[test]
+    fn test_nodata_handling() {
+        let g = geometry();
+        let with_hole = |row: isize, column: isize| {
+            if row == 4 && column == 4 {
+                NODATA
+            } else {
+                analytic(row, column)
+            }
+        };
+        // (109.5, 510.5) lies between the centres of rows 4-5 and columns 4-5
+        assert!(sample_bilinear(&g, 109.5, 510.5, NodataPolicy::Strict, with_hole).is_none());
+        assert!(sample_cubic(&g, 109.5, 510.5, NodataPolicy::Strict, with_hole).is_none());
+        let z = sample_bilinear(&g, 109.5, 510.5, NodataPolicy::WeightedPartial, with_hole);
+        assert!(z.is_some());
+        let all_nodata = |_row: isize, _column: isize| NODATA;
+        assert!(
+            sample_bilinear(&g, 109.5, 510.5, NodataPolicy::WeightedPartial, all_nodata).is_none()
+        );
+    }
+}
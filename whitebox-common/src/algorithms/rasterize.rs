@@ -0,0 +1,441 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::GridGeometry;
+use crate::structures::Point2D;
+
+// The rasterizers below work in fractional grid space, where u is the
+// column coordinate and v is the row coordinate, both measured from the
+// north-west corner of the grid. Cell (r, c) spans [c, c + 1) x [r, r + 1).
+// The nodata value of the grid geometry is not used.
+
+fn to_grid_space(geometry: &GridGeometry, x: f64, y: f64) -> (f64, f64) {
+    (
+        (x - geometry.west) / geometry.resolution_x,
+        (geometry.north - y) / geometry.resolution_y,
+    )
+}
+
+fn is_valid_grid(geometry: &GridGeometry) -> bool {
+    geometry.rows > 0
+        && geometry.columns > 0
+        && geometry.resolution_x > 0f64
+        && geometry.resolution_y > 0f64
+}
+
+/// Returns the (row, column) of the cell containing the point (`x`, `y`), or
+/// `None` if the point lies outside the grid. Points lying exactly on the
+/// east or south edge of the grid are assigned to the last column or row.
+pub fn rasterize_point(geometry: &GridGeometry, x: f64, y: f64) -> Option<(isize, isize)> {
+    if !is_valid_grid(geometry) || !x.is_finite() || !y.is_finite() {
+        return None;
+    }
+    let (u, v) = to_grid_space(geometry, x, y);
+    if u < 0f64 || v < 0f64 || u > geometry.columns as f64 || v > geometry.rows as f64 {
+        return None;
+    }
+    let column = (u.floor() as isize).min(geometry.columns as isize - 1);
+    let row = (v.floor() as isize).min(geometry.rows as isize - 1);
+    Some((row, column))
+}
+
+/// Visits every grid cell crossed by the polyline defined by `points`. When
+/// `radius` is greater than zero, all cells within `radius` cells of a crossed
+/// cell are also visited, thickening the line. Diagonal steps through exact cell
+/// corners do not visit the two corner-adjacent cells. Cells may be visited more
+/// than once.
+pub fn rasterize_polyline<F>(
+    geometry: &GridGeometry,
+    points: &[Point2D],
+    radius: usize,
+    mut visit: F,
+) where
+    F: FnMut(isize, isize),
+{
+    if !is_valid_grid(geometry) || points.is_empty() {
+        return;
+    }
+    let rows = geometry.rows as isize;
+    let columns = geometry.columns as isize;
+    let r2 = (radius * radius) as isize;
+    let radius = radius as isize;
+    let mut burn = |row: isize, column: isize| {
+        if radius == 0 {
+            visit(row, column);
+            return;
+        }
+        for dr in -radius..=radius {
+            for dc in -radius..=radius {
+                if dr * dr + dc * dc > r2 {
+                    continue;
+                }
+                let (rn, cn) = (row + dr, column + dc);
+                if rn >= 0 && cn >= 0 && rn < rows && cn < columns {
+                    visit(rn, cn);
+                }
+            }
+        }
+    };
+
+    if points.len() == 1 {
+        if let Some((row, column)) = rasterize_point(geometry, points[0].x, points[0].y) {
+            burn(row, column);
+        }
+        return;
+    }
+    for i in 0..points.len() - 1 {
+        let (u0, v0) = to_grid_space(geometry, points[i].x, points[i].y);
+        let (u1, v1) = to_grid_space(geometry, points[i + 1].x, points[i + 1].y);
+        traverse_segment(geometry.rows, geometry.columns, u0, v0, u1, v1, &mut burn);
+    }
+}
+
+/// Visits the grid cells covered by a polygon made up of one or more `rings`.
+/// Rings are combined with the even-odd rule, so holes may be supplied as
+/// additional rings without regard to their winding order. Rings may be open
+/// or closed (repeating the first vertex).
+///
+/// By default a cell is covered when its centre falls inside the polygon. When
+/// `all_touched` is true, every cell touched by the polygon, including its
+/// boundary, is covered. Cells may be visited more than once.
+pub fn rasterize_polygon<F>(
+    geometry: &GridGeometry,
+    rings: &[&[Point2D]],
+    all_touched: bool,
+    mut visit: F,
+) where
+    F: FnMut(isize, isize),
+{
+    if !is_valid_grid(geometry) {
+        return;
+    }
+    let rows = geometry.rows as isize;
+    let columns = geometry.columns as isize;
+
+    let grid_rings: Vec<Vec<(f64, f64)>> = rings
+        .iter()
+        .map(|ring| {
+            ring.iter()
+                .map(|p| to_grid_space(geometry, p.x, p.y))
+                .collect()
+        })
+        .collect();
+
+    let mut min_v = f64::INFINITY;
+    let mut max_v = f64::NEG_INFINITY;
+    for ring in &grid_rings {
+        for &(_, v) in ring {
+            min_v = min_v.min(v);
+            max_v = max_v.max(v);
+        }
+    }
+
+    if min_v.is_finite() && max_v.is_finite() {
+        // scan each row whose cell-centre line crosses the polygon's extent
+        let start_row = ((min_v - 0.5).ceil() as isize).max(0);
+        let end_row = ((max_v - 0.5).floor() as isize).min(rows - 1);
+        let mut crossings: Vec<f64> = vec![];
+        for row in start_row..=end_row {
+            let vc = row as f64 + 0.5;
+            crossings.clear();
+            for ring in &grid_rings {
+                let n = ring.len();
+                for i in 0..n {
+                    let (pu, pv) = ring[i];
+                    let (qu, qv) = ring[(i + 1) % n];
+                    if (pv > vc) != (qv > vc) {
+                        crossings.push(pu + (vc - pv) * (qu - pu) / (qv - pv));
+                    }
+                }
+            }
+            crossings.sort_by(|a, b| a.total_cmp(b));
+            for pair in crossings.chunks(2) {
+                if pair.len() < 2 {
+                    break;
+                }
+                let start_col = ((pair[0] - 0.5).ceil() as isize).max(0);
+                let end_col = ((pair[1] - 0.5).ceil() as isize - 1).min(columns - 1);
+                for column in start_col..=end_col {
+                    visit(row, column);
+                }
+            }
+        }
+    }
+
+    if all_touched {
+        // Any cell that is partly covered but whose centre is outside the
+        // polygon must be crossed by the polygon's boundary.
+        for ring in &grid_rings {
+            let n = ring.len();
+            for i in 0..n {
+                let (u0, v0) = ring[i];
+                let (u1, v1) = ring[(i + 1) % n];
+                traverse_segment(geometry.rows, geometry.columns, u0, v0, u1, v1, &mut visit);
+            }
+        }
+    }
+}
+
+/// Walks the cells crossed by the segment (u0, v0)-(u1, v1) in grid space,
+/// after clipping it to the grid (Amanatides & Woo, 1987).
+fn traverse_segment<F>(
+    rows: usize,
+    columns: usize,
+    u0: f64,
+    v0: f64,
+    u1: f64,
+    v1: f64,
+    visit: &mut F,
+) where
+    F: FnMut(isize, isize),
+{
+    if !(u0.is_finite() && v0.is_finite() && u1.is_finite() && v1.is_finite()) {
+        return;
+    }
+    let du = u1 - u0;
+    let dv = v1 - v0;
+
+    // Liang-Barsky clipping against [0, columns] x [0, rows]
+    let mut t0 = 0f64;
+    let mut t1 = 1f64;
+    for &(p, q) in &[
+        (-du, u0),
+        (du, columns as f64 - u0),
+        (-dv, v0),
+        (dv, rows as f64 - v0),
+    ] {
+        if p == 0f64 {
+            if q < 0f64 {
+                return;
+            }
+        } else {
+            let t = q / p;
+            if p < 0f64 {
+                if t > t1 {
+                    return;
+                }
+                t0 = t0.max(t);
+            } else {
+                if t < t0 {
+                    return;
+                }
+                t1 = t1.min(t);
+            }
+        }
+    }
+    let (su, sv) = (u0 + t0 * du, v0 + t0 * dv);
+    let (eu, ev) = (u0 + t1 * du, v0 + t1 * dv);
+
+    let max_col = columns as isize - 1;
+    let max_row = rows as isize - 1;
+    let mut column = (su.floor() as isize).max(0).min(max_col);
+    let mut row = (sv.floor() as isize).max(0).min(max_row);
+    let end_col = (eu.floor() as isize).max(0).min(max_col);
+    let end_row = (ev.floor() as isize).max(0).min(max_row);
+
+    let step_col: isize = if du > 0f64 {
+        1
+    } else if du < 0f64 {
+        -1
+    } else {
+        0
+    };
+    let step_row: isize = if dv > 0f64 {
+        1
+    } else if dv < 0f64 {
+        -1
+    } else {
+        0
+    };
+    let t_delta_col = if du != 0f64 {
+        1f64 / du.abs()
+    } else {
+        f64::INFINITY
+    };
+    let t_delta_row = if dv != 0f64 {
+        1f64 / dv.abs()
+    } else {
+        f64::INFINITY
+    };
+    let mut t_max_col = if du > 0f64 {
+        t0 + ((column + 1) as f64 - su) / du
+    } else if du < 0f64 {
+        t0 + (column as f64 - su) / du
+    } else {
+        f64::INFINITY
+    };
+    let mut t_max_row = if dv > 0f64 {
+        t0 + ((row + 1) as f64 - sv) / dv
+    } else if dv < 0f64 {
+        t0 + (row as f64 - sv) / dv
+    } else {
+        f64::INFINITY
+    };
+
+    let max_steps = (end_col - column).abs() + (end_row - row).abs() + 1;
+    for _ in 0..=max_steps {
+        visit(row, column);
+        if row == end_row && column == end_col {
+            break;
+        }
+        if (t_max_col - t_max_row).abs() < 1e-12 {
+            // passing exactly through a cell corner
+            column += step_col;
+            row += step_row;
+            t_max_col += t_delta_col;
+            t_max_row += t_delta_row;
+        } else if t_max_col < t_max_row {
+            column += step_col;
+            t_max_col += t_delta_col;
+        } else {
+            row += step_row;
+            t_max_row += t_delta_row;
+        }
+        if column < 0 || row < 0 || column > max_col || row > max_row {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{rasterize_point, rasterize_polygon, rasterize_polyline};
+    use crate::algorithms::{point_in_poly, GridGeometry};
+    use crate::structures::Point2D;
+    use std::collections::HashSet;
+
+    // A 10 x 10 grid of unit cells covering (0, 0) to (10, 10).
+    fn geometry() -> GridGeometry {
+        GridGeometry {
+            rows: 10,
+            columns: 10,
+            west: 0f64,
+            north: 10f64,
+            resolution_x: 1f64,
+            resolution_y: 1f64,
+            nodata: -32768f64,
+        }
+    }
+
+    fn collect_polygon(rings: &[&[Point2D]], all_touched: bool) -> HashSet<(isize, isize)> {
+        let mut cells = HashSet::new();
+        rasterize_polygon(&geometry(), rings, all_touched, |r, c| {
+            cells.insert((r, c));
+        });
+        cells
+    }
+
+    fn triangle_with_hole() -> (Vec<Point2D>, Vec<Point2D>) {
+        let outer = vec![
+            Point2D::new(0.0, 0.0),
+            Point2D::new(5.0, 10.0),
+            Point2D::new(10.0, 0.0),
+            Point2D::new(0.0, 0.0),
+        ];
+        let hole = vec![
+            Point2D::new(4.0, 2.0),
+            Point2D::new(6.0, 2.0),
+            Point2D::new(6.0, 4.0),
+            Point2D::new(4.0, 4.0),
+            Point2D::new(4.0, 2.0),
+        ];
+        (outer, hole)
+    }
+
+    #[test]
+    fn test_triangle_with_hole_cell_centres() {
+        let (outer, hole) = triangle_with_hole();
+        let cells = collect_polygon(&[&outer, &hole], false);
+
+        let mut expected = HashSet::new();
+        for r in 0..10isize {
+            for c in 0..10isize {
+                let p = Point2D::new(c as f64 + 0.5, 10.0 - (r as f64 + 0.5));
+                if point_in_poly(&p, &outer) && !point_in_poly(&p, &hole) {
+                    expected.insert((r, c));
+                }
+            }
+        }
+        assert_eq!(cells, expected);
+        // the hole spans rows 6-7 and columns 4-5
+        assert!(!cells.contains(&(6, 4)));
+        assert!(!cells.contains(&(7, 5)));
+        assert!(cells.contains(&(7, 3)));
+    }
+
+    #[test]
+    fn test_triangle_all_touched() {
+        let (outer, hole) = triangle_with_hole();
+        let centres = collect_polygon(&[&outer, &hole], false);
+        let touched = collect_polygon(&[&outer, &hole], true);
+        assert!(touched.is_superset(&centres));
+        // the apex at (5, 10) only clips the top row
+        assert!(!centres.contains(&(0, 4)) && !centres.contains(&(0, 5)));
+        assert!(touched.contains(&(0, 4)) && touched.contains(&(0, 5)));
+        // cells fully outside the triangle are never touched
+        assert!(!touched.contains(&(0, 0)));
+        assert!(!touched.contains(&(0, 9)));
+    }
+
+    #[test]
+    fn test_diagonal_line() {
+        let mut cells = vec![];
+        let line = [Point2D::new(0.5, 9.5), Point2D::new(9.5, 0.5)];
+        rasterize_polyline(&geometry(), &line, 0, |r, c| cells.push((r, c)));
+        let expected: Vec<(isize, isize)> = (0..10).map(|i| (i, i)).collect();
+        assert_eq!(cells, expected);
+
+        // a shallower line visits a 4-connected run of cells
+        let mut cells = HashSet::new();
+        let line = [Point2D::new(0.2, 9.9), Point2D::new(9.8, 6.1)];
+        rasterize_polyline(&geometry(), &line, 0, |r, c| {
+            cells.insert((r, c));
+        });
+        assert!(cells.contains(&(0, 0)) && cells.contains(&(3, 9)));
+        for c in 0..10 {
+            assert!(cells.iter().any(|&(_, cc)| cc == c));
+        }
+    }
+
+    #[test]
+    fn test_line_clipped_and_thickened() {
+        let mut cells = HashSet::new();
+        let line = [Point2D::new(-5.0, 5.5), Point2D::new(15.0, 5.5)];
+        rasterize_polyline(&geometry(), &line, 0, |r, c| {
+            cells.insert((r, c));
+        });
+        assert_eq!(cells.len(), 10);
+        assert!(cells.iter().all(|&(r, _)| r == 4));
+
+        let mut cells = HashSet::new();
+        rasterize_polyline(&geometry(), &line, 1, |r, c| {
+            cells.insert((r, c));
+        });
+        assert_eq!(cells.len(), 30);
+    }
+
+    #[test]
+    fn test_coincident_points() {
+        let p = Point2D::new(3.5, 3.5);
+        assert_eq!(rasterize_point(&geometry(), p.x, p.y), Some((6, 3)));
+        assert_eq!(rasterize_point(&geometry(), 10.0, 0.0), Some((9, 9)));
+        assert_eq!(rasterize_point(&geometry(), -0.1, 5.0), None);
+
+        // a zero-length polyline burns the single cell that contains it
+        let mut cells = vec![];
+        rasterize_polyline(&geometry(), &[p, p, p], 0, |r, c| cells.push((r, c)));
+        assert!(!cells.is_empty());
+        assert!(cells.iter().all(|&cell| cell == (6, 3)));
+
+        // a degenerate polygon covers no cell centres, but touches its cell
+        assert!(collect_polygon(&[&[p, p, p]], false).is_empty());
+        let touched = collect_polygon(&[&[p, p, p]], true);
+        assert_eq!(touched.len(), 1);
+        assert!(touched.contains(&(6, 3)));
+    }
+}
@@ -0,0 +1,293 @@
+/// Column offsets of the eight D8 neighbours, clockwise from the north-east.
+pub const D8_DX: [isize; 8] = [1, 1, 1, 0, -1, -1, -1, 0];
+/// Row offsets of the eight D8 neighbours, clockwise from the north-east.
+pub const D8_DY: [isize; 8] = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+const NO_FLOW: u8 = 8;
+const INVALID_POINTER: u8 = 9;
+
+/// Walks the flow network described by a D8 pointer grid, downstream along the pointers or
+/// upstream through the neighbours that drain into a cell. Pointer values follow the Whitebox
+/// scheme (1 = north-east, doubling clockwise to 128 = north) unless `esri_style` is set (1 =
+/// east, doubling clockwise to 128 = north-east).
+pub struct D8Walker {
+    directions: Vec<u8>,
+    rows: isize,
+    columns: isize,
+}
+
+impl D8Walker {
+    /// Decodes the pointer value `pointer(row, column)` of every cell of a `rows` by `columns`
+    /// grid. Cells that hold `nodata`, zero, or a negative value have no flow direction.
+    pub fn new<F>(
+        rows: isize,
+        columns: isize,
+        nodata: f64,
+        esri_style: bool,
+        pointer: F,
+    ) -> D8Walker
+    where
+        F: Fn(isize, isize) -> f64,
+    {
+        let rows = rows.max(0);
+        let columns = columns.max(0);
+        let mut directions = Vec::with_capacity((rows * columns) as usize);
+        for row in 0..rows {
+            for col in 0..columns {
+                let value = pointer(row, col);
+                directions.push(if value == nodata || value <= 0f64 {
+                    NO_FLOW
+                } else if value > 128f64
+                    || value.fract() != 0f64
+                    || !(value as usize).is_power_of_two()
+                {
+                    INVALID_POINTER
+                } else {
+                    let exponent = (value as usize).trailing_zeros() as u8;
+                    if esri_style {
+                        (exponent + 1) % 8
+                    } else {
+                        exponent
+                    }
+                });
+            }
+        }
+        D8Walker {
+            directions,
+            rows,
+            columns,
+        }
+    }
+
+    /// Returns true if (`row`, `col`) lies on the grid.
+    pub fn in_grid(&self, row: isize, col: isize) -> bool {
+        row >= 0 && row < self.rows && col >= 0 && col < self.columns
+    }
+
+    fn code(&self, row: isize, col: isize) -> u8 {
+        if self.in_grid(row, col) {
+            self.directions[(row * self.columns + col) as usize]
+        } else {
+            NO_FLOW
+        }
+    }
+
+    /// The index into `D8_DX` and `D8_DY` of the direction a cell drains in, or `None` for a cell
+    /// that is off the grid, has no flow direction, or holds an invalid pointer value.
+    pub fn direction(&self, row: isize, col: isize) -> Option<usize> {
+        match self.code(row, col) {
+            dir if dir < 8 => Some(dir as usize),
+            _ => None,
+        }
+    }
+
+    /// Returns true if (`row`, `col`) holds a positive pointer value that is not one of the eight
+    /// D8 directions, i.e. the pointer grid was not created by the D8 or Rho8 tools.
+    pub fn has_invalid_pointer(&self, row: isize, col: isize) -> bool {
+        self.code(row, col) == INVALID_POINTER
+    }
+
+    /// The cell that (`row`, `col`) drains into, if it has a flow direction and the cell lies on
+    /// the grid.
+    pub fn downstream(&self, row: isize, col: isize) -> Option<(isize, isize)> {
+        let dir = self.direction(row, col)?;
+        let (r, c) = (row + D8_DY[dir], col + D8_DX[dir]);
+        if self.in_grid(r, c) {
+            Some((r, c))
+        } else {
+            None
+        }
+    }
+
+    /// The neighbours that drain into (`row`, `col`), clockwise from the north-east.
+    pub fn inflows(&self, row: isize, col: isize) -> Vec<(isize, isize)> {
+        (0..8)
+            .map(|n| (row + D8_DY[n], col + D8_DX[n]))
+            .filter(|&(r, c)| self.downstream(r, c) == Some((row, col)))
+            .collect()
+    }
+
+    /// Splits the stream network of the cells for which `is_stream(row, column)` holds into links
+    /// along the pointers, as `trace_stream_links` does.
+    pub fn stream_links<S>(&self, is_stream: S) -> Vec<Vec<(isize, isize)>>
+    where
+        S: Fn(isize, isize) -> bool,
+    {
+        trace_stream_links(self.rows, self.columns, is_stream, |row, col| {
+            self.downstream(row, col)
+        })
+    }
+}
+
+/// Returns the length of a path through the centres of a sequence of
+/// 8-connected grid cells, given as (row, column) pairs. Diagonal steps are
+/// `sqrt(resolution_x^2 + resolution_y^2)` long; cells are assumed to be adjacent.
+pub fn d8_path_length(cells: &[(isize, isize)], resolution_x: f64, resolution_y: f64) -> f64 {
+    let diagonal = (resolution_x * resolution_x + resolution_y * resolution_y).sqrt();
+    let mut length = 0f64;
+    for w in cells.windows(2) {
+        let dr = w[1].0 != w[0].0;
+        let dc = w[1].1 != w[0].1;
+        length += match (dr, dc) {
+            (true, true) => diagonal,
+            (true, false) => resolution_y,
+            (false, true) => resolution_x,
+            (false, false) => 0f64,
+        };
+    }
+    length
+}
+
+/// Splits a stream network into links by walking downstream from junction to
+/// junction. `is_stream(row, column)` identifies stream cells and
+/// `downstream(row, column)` returns the cell that a stream cell drains to, if any.
+///
+/// A link starts at a channel head (a stream cell without inflowing stream
+/// cells) or at a junction (a stream cell with two or more inflowing stream
+/// cells) and continues downstream until the next junction, the edge of the
+/// network, or the raster edge. Every stream cell reachable from a channel head
+/// belongs to exactly one link. Links are returned in row-major order of their
+/// upstream-most cell, with cells ordered from upstream to downstream.
+pub fn trace_stream_links<S, D>(
+    rows: isize,
+    columns: isize,
+    is_stream: S,
+    downstream: D,
+) -> Vec<Vec<(isize, isize)>>
+where
+    S: Fn(isize, isize) -> bool,
+    D: Fn(isize, isize) -> Option<(isize, isize)>,
+{
+    if rows <= 0 || columns <= 0 {
+        return vec![];
+    }
+    let in_grid = |r: isize, c: isize| r >= 0 && c >= 0 && r < rows && c < columns;
+    let idx = |r: isize, c: isize| (r * columns + c) as usize;
+    let stream_downstream = |r: isize, c: isize| match downstream(r, c) {
+        Some((rn, cn)) if in_grid(rn, cn) && is_stream(rn, cn) => Some((rn, cn)),
+        _ => None,
+    };
+
+    let mut num_inflowing = vec![0u8; (rows * columns) as usize];
+    for row in 0..rows {
+        for col in 0..columns {
+            if is_stream(row, col) {
+                if let Some((rn, cn)) = stream_downstream(row, col) {
+                    num_inflowing[idx(rn, cn)] = num_inflowing[idx(rn, cn)].saturating_add(1);
+                }
+            }
+        }
+    }
+
+    let mut links = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if !is_stream(row, col) || num_inflowing[idx(row, col)] == 1 {
+                continue;
+            }
+            let mut link = vec![(row, col)];
+            let (mut r, mut c) = (row, col);
+            while let Some((rn, cn)) = stream_downstream(r, c) {
+                if num_inflowing[idx(rn, cn)] != 1 || link.len() > (rows * columns) as usize {
+                    // the next cell starts a new link (or the path loops)
+                    break;
+                }
+                link.push((rn, cn));
+                r = rn;
+                c = cn;
+            }
+            links.push(link);
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod test {
+    use super::{d8_path_length, trace_stream_links, D8Walker};
+
+    #[test]
+    fn test_d8_path_length() {
+        let cells = [(0, 0), (0, 1), (1, 2), (2, 2), (2, 2)];
+        let len = d8_path_length(&cells, 3.0, 4.0);
+        assert!((len - (3.0 + 5.0 + 4.0)).abs() < 1e-12);
+        assert_eq!(d8_path_length(&cells[..1], 3.0, 4.0), 0.0);
+    }
+
+    #[test]
+    fn test_trace_stream_links_y_network() {
+        // Two tributaries in columns 0 and 4 flow diagonally to a confluence
+        // at (2, 2), which then flows south to the bottom edge.
+        //   X . . . X
+        //   . X . X .
+        //   . . X . .
+        //   . . X . .
+        let stream_cells = [(0, 0), (1, 1), (0, 4), (1, 3), (2, 2), (3, 2)];
+        let is_stream = |r: isize, c: isize| stream_cells.contains(&(r, c));
+        let downstream = |r: isize, c: isize| match (r, c) {
+            (0, 0) => Some((1, 1)),
+            (1, 1) => Some((2, 2)),
+            (0, 4) => Some((1, 3)),
+            (1, 3) => Some((2, 2)),
+            (2, 2) => Some((3, 2)),
+            _ => Some((r + 1, c)),
+        };
+        let links = trace_stream_links(4, 5, is_stream, downstream);
+        assert_eq!(
+            links,
+            vec![
+                vec![(0, 0), (1, 1)],
+                vec![(0, 4), (1, 3)],
+                vec![(2, 2), (3, 2)]
+            ]
+        );
+    }
+
+    #[test]
+    fn test_pointer_schemes() {
+        // In the Whitebox scheme every edge cell drains into the centre, which drains east.
+        let mut pntr = [
+            [4f64, 8f64, 16f64],
+            [2f64, 2f64, 32f64],
+            [1f64, 128f64, 64f64],
+        ];
+        let walker = D8Walker::new(3, 3, -32768f64, false, |r, c| pntr[r as usize][c as usize]);
+        assert_eq!(walker.downstream(0, 0), Some((1, 1)));
+        assert_eq!(walker.downstream(1, 1), Some((1, 2)));
+        assert_eq!(walker.downstream(1, 2), Some((1, 1)));
+        assert_eq!(walker.inflows(1, 1).len(), 8);
+        assert_eq!(walker.inflows(0, 0), vec![]);
+
+        let esri = D8Walker::new(3, 3, -32768f64, true, |r, c| pntr[r as usize][c as usize]);
+        // 4 is south in the Esri scheme and 2 is south-east
+        assert_eq!(esri.downstream(0, 0), Some((1, 0)));
+        assert_eq!(esri.downstream(1, 1), Some((2, 2)));
+        // 128 is north-east in the Esri scheme
+        assert_eq!(esri.downstream(2, 1), Some((1, 2)));
+
+        pntr[0] = [-32768f64, 0f64, 3f64];
+        pntr[2][2] = 2.5f64;
+        let walker = D8Walker::new(3, 3, -32768f64, false, |r, c| pntr[r as usize][c as usize]);
+        assert_eq!(walker.direction(0, 0), None);
+        assert_eq!(walker.direction(0, 1), None);
+        assert_eq!(walker.direction(0, 2), None);
+        assert_eq!(walker.direction(5, 5), None);
+        assert!(!walker.has_invalid_pointer(0, 0));
+        assert!(!walker.has_invalid_pointer(0, 1));
+        assert!(walker.has_invalid_pointer(0, 2));
+        assert!(walker.has_invalid_pointer(2, 2));
+        assert_eq!(walker.inflows(1, 1).len(), 4);
+    }
+
+    #[test]
+    fn test_walker_stream_links() {
+        // A stream running east along the top row, joined at (0, 2) by one from the south.
+        let pntr = [[2f64, 2f64, 2f64, 2f64], [0f64, 0f64, 128f64, 0f64]];
+        let walker = D8Walker::new(2, 4, -32768f64, false, |r, c| pntr[r as usize][c as usize]);
+        let links = walker.stream_links(|r, c| r == 0 || c == 2);
+        assert_eq!(
+            links,
+            vec![vec![(0, 0), (0, 1)], vec![(0, 2), (0, 3)], vec![(1, 2)]]
+        );
+    }
+}
@@ -0,0 +1,752 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::collections::VecDeque;
+use std::io::{Error, ErrorKind};
+
+/// The NoData value of the `chnjnt` and `watershed` grids of a synthetic dataset.
+pub const SYNTHETIC_NODATA: f64 = -32768f64;
+
+// Whitebox-style D8 pointer values and their (row, column) offsets, in the order that
+// `D8Pointer` tests them (NE, E, SE, S, SW, W, NW, N); ties go to the first direction.
+const PNTR_VALUES: [f64; 8] = [1f64, 2f64, 4f64, 8f64, 16f64, 32f64, 64f64, 128f64];
+const DR: [isize; 8] = [-1, 0, 1, 1, 1, 0, -1, -1];
+const DC: [isize; 8] = [1, 1, 1, 0, -1, -1, -1, 0];
+
+/// The terrain of a synthetic hydrological dataset.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SyntheticTerrain {
+    /// A plane dipping south onto a single channel along the bottom row, which drains east to an
+    /// outlet in the bottom-right corner.
+    InclinedPlane,
+    /// Two planar flanks dipping towards a single channel down the centre column, which starts a
+    /// quarter of the way down the grid and drains south to an outlet on the bottom row.
+    VValley,
+    /// A main channel down the centre column joined by three straight tributaries, alternately
+    /// from the west and the east, at three junctions. Tributaries are three times as steep as the
+    /// main channel and the hillslopes rise away from the nearest channel.
+    Dendritic,
+}
+
+impl SyntheticTerrain {
+    /// Parses a terrain name (`inclined_plane`, `v_valley`, or `dendritic`).
+    pub fn from_name(name: &str) -> Option<SyntheticTerrain> {
+        match name.trim().to_lowercase().replace('-', "_").as_str() {
+            "inclined_plane" | "plane" => Some(SyntheticTerrain::InclinedPlane),
+            "v_valley" | "valley" => Some(SyntheticTerrain::VValley),
+            "dendritic" => Some(SyntheticTerrain::Dendritic),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            SyntheticTerrain::InclinedPlane => "inclined_plane",
+            SyntheticTerrain::VValley => "v_valley",
+            SyntheticTerrain::Dendritic => "dendritic",
+        }
+    }
+
+    /// The smallest grid, as (rows, columns), that the terrain can be generated on.
+    pub fn min_size(&self) -> (usize, usize) {
+        match self {
+            SyntheticTerrain::InclinedPlane => (3, 3),
+            SyntheticTerrain::VValley => (4, 3),
+            SyntheticTerrain::Dendritic => (11, 7),
+        }
+    }
+}
+
+/// The parameters of a synthetic hydrological dataset. Slopes are in metres per metre.
+#[derive(Clone, Debug)]
+pub struct SyntheticHydroParams {
+    pub rows: usize,
+    pub columns: usize,
+    pub resolution: f64,
+    pub west: f64,
+    pub north: f64,
+    pub base_elevation: f64, // elevation of the outlet cell
+    pub channel_slope: f64,
+    pub hillslope_slope: f64,
+}
+
+impl Default for SyntheticHydroParams {
+    fn default() -> SyntheticHydroParams {
+        SyntheticHydroParams {
+            rows: 21,
+            columns: 21,
+            resolution: 10f64,
+            west: 0f64,
+            north: 1000f64,
+            base_elevation: 100f64,
+            channel_slope: 0.01,
+            hillslope_slope: 0.1,
+        }
+    }
+}
+
+/// A synthetic DEM with its D8 pointer, streams, channel junction, stream order, and watershed
+/// grids, all stored in row-major order with `params.rows` x `params.columns` cells.
+///
+/// The D8 pointer (Whitebox-style) is the steepest descent direction of the DEM, as `D8Pointer`
+/// would produce, except at the outlet, which lies on the grid edge and points off the grid so
+/// that every cell in the watershed has a valid pointer. Streams are 1 on channel cells and 0
+/// elsewhere; the channel junction grid holds the number of stream cells flowing into each
+/// stream cell (0 at channel heads) and NoData elsewhere; stream order is the Strahler order of
+/// stream cells and 0 elsewhere; and the watershed is 1 on the cells that drain to the outlet and
+/// NoData elsewhere.
+#[derive(Clone, Debug)]
+pub struct SyntheticHydroDataset {
+    pub terrain: SyntheticTerrain,
+    pub params: SyntheticHydroParams,
+    pub dem: Vec<f64>,
+    pub d8_pntr: Vec<f64>,
+    pub streams: Vec<f64>,
+    pub chnjnt: Vec<f64>,
+    pub order: Vec<f64>,
+    pub watershed: Vec<f64>,
+    pub outlet: (usize, usize), // (row, column)
+}
+
+/// Generates a synthetic hydrological dataset for tests and checks its internal consistency
+/// (see `SyntheticHydroDataset::check_consistency`). The hillslopes must be steeper than the
+/// channels (at least 2.5 times the channel slope, and 9 times for the dendritic terrain, whose
+/// tributaries are three times as steep as its main channel) so that D8 flow stays in the channels.
+pub fn synthetic_hydro_dataset(
+    terrain: SyntheticTerrain,
+    params: &SyntheticHydroParams,
+) -> Result<SyntheticHydroDataset, Error> {
+    let (min_rows, min_columns) = terrain.min_size();
+    if params.rows < min_rows || params.columns < min_columns {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The {} terrain needs at least {} rows and {} columns.",
+                terrain.name(),
+                min_rows,
+                min_columns
+            ),
+        ));
+    }
+    if !(params.resolution > 0f64 && params.resolution.is_finite()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The resolution must be positive.",
+        ));
+    }
+    if !(params.channel_slope > 0f64 && params.channel_slope.is_finite()) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The channel slope must be positive.",
+        ));
+    }
+    let min_ratio = match terrain {
+        SyntheticTerrain::Dendritic => 9f64,
+        _ => 2.5,
+    };
+    if !(params.hillslope_slope >= min_ratio * params.channel_slope
+        && params.hillslope_slope.is_finite())
+    {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "The hillslope slope must be at least {} times the channel slope for the {} terrain.",
+                min_ratio,
+                terrain.name()
+            ),
+        ));
+    }
+
+    let (rows, columns) = (params.rows, params.columns);
+    let res = params.resolution;
+    let base = params.base_elevation;
+    let (chan, hill) = (params.channel_slope, params.hillslope_slope);
+    let mut streams = vec![0f64; rows * columns];
+    let mut dem = vec![0f64; rows * columns];
+    let outlet;
+    let outlet_pntr;
+    match terrain {
+        SyntheticTerrain::InclinedPlane => {
+            for row in 0..rows {
+                for col in 0..columns {
+                    let mut z = base + chan * (columns - 1 - col) as f64 * res;
+                    if row == rows - 1 {
+                        streams[row * columns + col] = 1f64;
+                    } else {
+                        z += hill * (rows - 1 - row) as f64 * res;
+                    }
+                    dem[row * columns + col] = z;
+                }
+            }
+            outlet = (rows - 1, columns - 1);
+            outlet_pntr = 2f64; // east
+        }
+        SyntheticTerrain::VValley => {
+            let centre = columns / 2;
+            let head = rows / 4;
+            for row in 0..rows {
+                for col in 0..columns {
+                    let across = (col as f64 - centre as f64).abs();
+                    dem[row * columns + col] =
+                        base + chan * (rows - 1 - row) as f64 * res + hill * across * res;
+                    if col == centre && row >= head {
+                        streams[row * columns + col] = 1f64;
+                    }
+                }
+            }
+            outlet = (rows - 1, centre);
+            outlet_pntr = 8f64; // south
+        }
+        SyntheticTerrain::Dendritic => {
+            let centre = columns / 2;
+            let head = (rows / 8).max(1);
+            let span = rows - 1 - head;
+            // channel cells and their elevations
+            let mut channel: Vec<(usize, usize, f64)> = vec![];
+            for row in head..rows {
+                channel.push((row, centre, base + chan * (rows - 1 - row) as f64 * res));
+            }
+            for k in 1..=3 {
+                let row = head + span * k / 4;
+                let z_junction = base + chan * (rows - 1 - row) as f64 * res;
+                let cols: Vec<usize> = if k == 2 {
+                    (centre + 1..columns - 1).collect()
+                } else {
+                    (1..centre).collect()
+                };
+                for col in cols {
+                    let along = (col as f64 - centre as f64).abs();
+                    channel.push((row, col, z_junction + 3f64 * chan * along * res));
+                }
+            }
+            for &(row, col, _) in &channel {
+                streams[row * columns + col] = 1f64;
+            }
+            // Hillslope cells rise at the hillslope slope from the cheapest channel cell.
+            for row in 0..rows {
+                for col in 0..columns {
+                    let i = row * columns + col;
+                    if streams[i] == 1f64 {
+                        continue;
+                    }
+                    dem[i] = channel
+                        .iter()
+                        .map(|&(r, c, z)| {
+                            let dr = r as f64 - row as f64;
+                            let dc = c as f64 - col as f64;
+                            z + hill * (dr * dr + dc * dc).sqrt() * res
+                        })
+                        .fold(f64::INFINITY, f64::min);
+                }
+            }
+            for &(row, col, z) in &channel {
+                dem[row * columns + col] = z;
+            }
+            outlet = (rows - 1, centre);
+            outlet_pntr = 8f64; // south
+        }
+    }
+
+    let d8_pntr = steepest_descent_pointer(&dem, rows, columns, res, outlet, outlet_pntr)?;
+    for row in 0..rows {
+        for col in 0..columns {
+            let i = row * columns + col;
+            if streams[i] != 1f64 || (row, col) == outlet {
+                continue;
+            }
+            match downstream_cell(d8_pntr[i], row, col, rows, columns) {
+                Some((r, c)) if streams[r * columns + c] == 1f64 => {}
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "The steepest descent at channel cell (row {}, column {}) leaves the channel; use a steeper hillslope slope.",
+                            row, col
+                        ),
+                    ))
+                }
+            }
+        }
+    }
+
+    let chnjnt = channel_junctions(&d8_pntr, &streams, rows, columns);
+    let order = strahler_order(&d8_pntr, &streams, &chnjnt, rows, columns);
+    let watershed = drains_to_outlet(&d8_pntr, rows, columns, outlet)
+        .iter()
+        .map(|&inside| if inside { 1f64 } else { SYNTHETIC_NODATA })
+        .collect();
+
+    let dataset = SyntheticHydroDataset {
+        terrain,
+        params: params.clone(),
+        dem,
+        d8_pntr,
+        streams,
+        chnjnt,
+        order,
+        watershed,
+        outlet,
+    };
+    dataset.check_consistency()?;
+    Ok(dataset)
+}
+
+impl SyntheticHydroDataset {
+    /// The x coordinate of the centre of a column.
+    pub fn x(&self, col: usize) -> f64 {
+        self.params.west + (col as f64 + 0.5) * self.params.resolution
+    }
+
+    /// The y coordinate of the centre of a row.
+    pub fn y(&self, row: usize) -> f64 {
+        self.params.north - (row as f64 + 0.5) * self.params.resolution
+    }
+
+    /// The number of channel heads (stream cells without inflowing stream cells).
+    pub fn num_channel_heads(&self) -> usize {
+        self.chnjnt.iter().filter(|&&v| v == 0f64).count()
+    }
+
+    /// The number of junctions (stream cells with two or more inflowing stream cells).
+    pub fn num_junctions(&self) -> usize {
+        self.chnjnt
+            .iter()
+            .filter(|&&v| v != SYNTHETIC_NODATA && v >= 2f64)
+            .count()
+    }
+
+    /// Checks that the grids agree with one another: every pointer descends to a lower in-grid
+    /// cell except at the outlet, which points off the grid; every stream cell drains to another
+    /// stream cell or is the outlet; the channel junction grid counts the inflowing stream cells;
+    /// stream order is at least 1 on streams, 0 elsewhere, and never decreases downstream; and
+    /// the watershed holds exactly the cells that drain to the outlet.
+    pub fn check_consistency(&self) -> Result<(), Error> {
+        let (rows, columns) = (self.params.rows, self.params.columns);
+        let n = rows * columns;
+        for (name, grid) in [
+            ("dem", &self.dem),
+            ("d8_pntr", &self.d8_pntr),
+            ("streams", &self.streams),
+            ("chnjnt", &self.chnjnt),
+            ("order", &self.order),
+            ("watershed", &self.watershed),
+        ] {
+            if grid.len() != n {
+                return Err(inconsistent(format!(
+                    "the {} grid has {} cells rather than {}",
+                    name,
+                    grid.len(),
+                    n
+                )));
+            }
+        }
+        let (outlet_row, outlet_col) = self.outlet;
+        if outlet_row >= rows || outlet_col >= columns {
+            return Err(inconsistent(
+                "the outlet is outside of the grid".to_string(),
+            ));
+        }
+        if self.streams[outlet_row * columns + outlet_col] != 1f64 {
+            return Err(inconsistent("the outlet is not a stream cell".to_string()));
+        }
+
+        let mut inflows = vec![0f64; n];
+        for row in 0..rows {
+            for col in 0..columns {
+                let i = row * columns + col;
+                let p = self.d8_pntr[i];
+                if !PNTR_VALUES.contains(&p) {
+                    return Err(inconsistent(format!(
+                        "invalid D8 pointer {} at (row {}, column {})",
+                        p, row, col
+                    )));
+                }
+                let next = downstream_cell(p, row, col, rows, columns);
+                if (row, col) == self.outlet {
+                    if next.is_some() {
+                        return Err(inconsistent(
+                            "the outlet does not point off the grid".to_string(),
+                        ));
+                    }
+                    continue;
+                }
+                let (r, c) = match next {
+                    Some(cell) => cell,
+                    None => {
+                        return Err(inconsistent(format!(
+                            "the pointer at (row {}, column {}) leaves the grid",
+                            row, col
+                        )))
+                    }
+                };
+                let j = r * columns + c;
+                if self.dem[j] >= self.dem[i] {
+                    return Err(inconsistent(format!(
+                        "the pointer at (row {}, column {}) does not descend",
+                        row, col
+                    )));
+                }
+                if self.streams[i] == 1f64 {
+                    if self.streams[j] != 1f64 {
+                        return Err(inconsistent(format!(
+                            "stream cell (row {}, column {}) drains to a non-stream cell",
+                            row, col
+                        )));
+                    }
+                    inflows[j] += 1f64;
+                    if self.order[j] < self.order[i] {
+                        return Err(inconsistent(format!(
+                            "stream order decreases downstream of (row {}, column {})",
+                            row, col
+                        )));
+                    }
+                }
+            }
+        }
+
+        let inside = drains_to_outlet(&self.d8_pntr, rows, columns, self.outlet);
+        for i in 0..n {
+            let (row, col) = (i / columns, i % columns);
+            let is_stream = self.streams[i] == 1f64;
+            if !is_stream && self.streams[i] != 0f64 {
+                return Err(inconsistent(format!(
+                    "invalid streams value {} at (row {}, column {})",
+                    self.streams[i], row, col
+                )));
+            }
+            let expected = if is_stream {
+                inflows[i]
+            } else {
+                SYNTHETIC_NODATA
+            };
+            if self.chnjnt[i] != expected {
+                return Err(inconsistent(format!(
+                    "the chnjnt value at (row {}, column {}) is {} rather than {}",
+                    row, col, self.chnjnt[i], expected
+                )));
+            }
+            if (is_stream && self.order[i] < 1f64) || (!is_stream && self.order[i] != 0f64) {
+                return Err(inconsistent(format!(
+                    "invalid stream order {} at (row {}, column {})",
+                    self.order[i], row, col
+                )));
+            }
+            let expected = if inside[i] { 1f64 } else { SYNTHETIC_NODATA };
+            if self.watershed[i] != expected {
+                return Err(inconsistent(format!(
+                    "the watershed value at (row {}, column {}) is {} rather than {}",
+                    row, col, self.watershed[i], expected
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+fn inconsistent(msg: String) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Inconsistent synthetic dataset: {}.", msg),
+    )
+}
+
+/// The in-grid cell that a Whitebox-style D8 pointer value points to, if any.
+fn downstream_cell(
+    pntr: f64,
+    row: usize,
+    col: usize,
+    rows: usize,
+    columns: usize,
+) -> Option<(usize, usize)> {
+    let dir = PNTR_VALUES.iter().position(|&p| p == pntr)?;
+    let r = row as isize + DR[dir];
+    let c = col as isize + DC[dir];
+    if r < 0 || c < 0 || r >= rows as isize || c >= columns as isize {
+        None
+    } else {
+        Some((r as usize, c as usize))
+    }
+}
+
+/// The D8 steepest descent pointer of each cell, among its in-grid neighbours. The outlet is
+/// given `outlet_pntr`; any other cell without a lower neighbour is an error.
+fn steepest_descent_pointer(
+    dem: &[f64],
+    rows: usize,
+    columns: usize,
+    resolution: f64,
+    outlet: (usize, usize),
+    outlet_pntr: f64,
+) -> Result<Vec<f64>, Error> {
+    let mut pntr = vec![0f64; rows * columns];
+    for row in 0..rows {
+        for col in 0..columns {
+            let i = row * columns + col;
+            if (row, col) == outlet {
+                pntr[i] = outlet_pntr;
+                continue;
+            }
+            let mut max_slope = 0f64;
+            for dir in 0..8 {
+                let r = row as isize + DR[dir];
+                let c = col as isize + DC[dir];
+                if r < 0 || c < 0 || r >= rows as isize || c >= columns as isize {
+                    continue;
+                }
+                let distance = if DR[dir] != 0 && DC[dir] != 0 {
+                    resolution * 2f64.sqrt()
+                } else {
+                    resolution
+                };
+                let slope = (dem[i] - dem[r as usize * columns + c as usize]) / distance;
+                if slope > max_slope {
+                    max_slope = slope;
+                    pntr[i] = PNTR_VALUES[dir];
+                }
+            }
+            if pntr[i] == 0f64 {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The synthetic DEM has a pit at (row {}, column {}).",
+                        row, col
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(pntr)
+}
+
+/// The number of stream cells flowing into each stream cell, and NoData off the streams.
+fn channel_junctions(d8_pntr: &[f64], streams: &[f64], rows: usize, columns: usize) -> Vec<f64> {
+    let mut chnjnt: Vec<f64> = streams
+        .iter()
+        .map(|&s| if s == 1f64 { 0f64 } else { SYNTHETIC_NODATA })
+        .collect();
+    for row in 0..rows {
+        for col in 0..columns {
+            if streams[row * columns + col] != 1f64 {
+                continue;
+            }
+            if let Some((r, c)) =
+                downstream_cell(d8_pntr[row * columns + col], row, col, rows, columns)
+            {
+                if streams[r * columns + c] == 1f64 {
+                    chnjnt[r * columns + c] += 1f64;
+                }
+            }
+        }
+    }
+    chnjnt
+}
+
+/// The Strahler order of each stream cell, and 0 off the streams, found by visiting stream cells
+/// downstream from the channel heads once all of their inflows are known.
+fn strahler_order(
+    d8_pntr: &[f64],
+    streams: &[f64],
+    chnjnt: &[f64],
+    rows: usize,
+    columns: usize,
+) -> Vec<f64> {
+    let n = rows * columns;
+    let mut order = vec![0f64; n];
+    let mut max_inflow = vec![0f64; n];
+    let mut num_max_inflow = vec![0usize; n];
+    let mut remaining: Vec<usize> = chnjnt
+        .iter()
+        .map(|&v| if v == SYNTHETIC_NODATA { 0 } else { v as usize })
+        .collect();
+    let mut queue: VecDeque<usize> = (0..n)
+        .filter(|&i| streams[i] == 1f64 && remaining[i] == 0)
+        .collect();
+    while let Some(i) = queue.pop_front() {
+        order[i] = match num_max_inflow[i] {
+            0 => 1f64,
+            1 => max_inflow[i],
+            _ => max_inflow[i] + 1f64,
+        };
+        let (row, col) = (i / columns, i % columns);
+        if let Some((r, c)) = downstream_cell(d8_pntr[i], row, col, rows, columns) {
+            let j = r * columns + c;
+            if streams[j] != 1f64 {
+                continue;
+            }
+            if order[i] > max_inflow[j] {
+                max_inflow[j] = order[i];
+                num_max_inflow[j] = 1;
+            } else if order[i] == max_inflow[j] {
+                num_max_inflow[j] += 1;
+            }
+            remaining[j] -= 1;
+            if remaining[j] == 0 {
+                queue.push_back(j);
+            }
+        }
+    }
+    order
+}
+
+/// Whether each cell's flow path reaches the outlet.
+fn drains_to_outlet(
+    d8_pntr: &[f64],
+    rows: usize,
+    columns: usize,
+    outlet: (usize, usize),
+) -> Vec<bool> {
+    // 0 = unknown, 1 = drains to the outlet, 2 = does not (or is on the current path)
+    let mut state = vec![0u8; rows * columns];
+    state[outlet.0 * columns + outlet.1] = 1;
+    for start in 0..rows * columns {
+        let mut path = vec![];
+        let mut i = start;
+        let result = loop {
+            if state[i] != 0 {
+                break state[i];
+            }
+            state[i] = 2; // visited on this path; a loop back to it does not reach the outlet
+            path.push(i);
+            match downstream_cell(d8_pntr[i], i / columns, i % columns, rows, columns) {
+                Some((r, c)) => i = r * columns + c,
+                None => break 2,
+            }
+        };
+        for j in path {
+            state[j] = result;
+        }
+    }
+    state.iter().map(|&s| s == 1).collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        synthetic_hydro_dataset, SyntheticHydroParams, SyntheticTerrain, SYNTHETIC_NODATA,
+    };
+
+    fn dataset(
+        terrain: SyntheticTerrain,
+        rows: usize,
+        columns: usize,
+    ) -> super::SyntheticHydroDataset {
+        let params = SyntheticHydroParams {
+            rows,
+            columns,
+            ..Default::default()
+        };
+        synthetic_hydro_dataset(terrain, &params).unwrap()
+    }
+
+    #[test]
+    fn test_inclined_plane() {
+        let d = dataset(SyntheticTerrain::InclinedPlane, 5, 4);
+        assert_eq!(d.outlet, (4, 3));
+        assert_eq!(d.num_channel_heads(), 1);
+        assert_eq!(d.num_junctions(), 0);
+        // every hillslope cell flows south and the channel flows east
+        for row in 0..5 {
+            for col in 0..4 {
+                let expected = if row < 4 { 8f64 } else { 2f64 };
+                assert_eq!(d.d8_pntr[row * 4 + col], expected);
+                assert_eq!(d.streams[row * 4 + col], if row == 4 { 1f64 } else { 0f64 });
+            }
+        }
+        assert!(d.watershed.iter().all(|&v| v == 1f64));
+        assert_eq!(d.dem[4 * 4 + 3], 100f64);
+        assert!((d.dem[0] - (100f64 + 0.01 * 30f64 + 0.1 * 40f64)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_v_valley() {
+        let d = dataset(SyntheticTerrain::VValley, 12, 9);
+        assert_eq!(d.outlet, (11, 4));
+        assert_eq!(d.num_channel_heads(), 1);
+        assert_eq!(d.num_junctions(), 0);
+        assert_eq!(d.chnjnt[3 * 9 + 4], 0f64); // the head, a quarter of the way down
+        assert_eq!(d.chnjnt[2 * 9 + 4], SYNTHETIC_NODATA);
+        assert_eq!(d.d8_pntr[5 * 9], 2f64);
+        assert_eq!(d.d8_pntr[5 * 9 + 8], 32f64);
+        assert!(d.order.iter().all(|&v| v <= 1f64));
+    }
+
+    #[test]
+    fn test_dendritic() {
+        let d = dataset(SyntheticTerrain::Dendritic, 21, 21);
+        assert_eq!(d.num_channel_heads(), 4);
+        assert_eq!(d.num_junctions(), 3);
+        let (r, c) = d.outlet;
+        assert_eq!(d.order[r * 21 + c], 2f64);
+        assert_eq!(d.order.iter().cloned().fold(0f64, f64::max), 2f64);
+    }
+
+    #[test]
+    fn test_consistent_across_sizes() {
+        for terrain in [
+            SyntheticTerrain::InclinedPlane,
+            SyntheticTerrain::VValley,
+            SyntheticTerrain::Dendritic,
+        ] {
+            let (min_rows, min_columns) = terrain.min_size();
+            for rows in min_rows..min_rows + 14 {
+                for columns in min_columns..min_columns + 14 {
+                    let params = SyntheticHydroParams {
+                        rows,
+                        columns,
+                        ..Default::default()
+                    };
+                    let d = synthetic_hydro_dataset(terrain, &params).unwrap();
+                    let expected_junctions = match terrain {
+                        SyntheticTerrain::Dendritic => 3,
+                        _ => 0,
+                    };
+                    assert_eq!(d.num_junctions(), expected_junctions, "{:?}", params);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters_and_corruption() {
+        let params = SyntheticHydroParams {
+            rows: 10,
+            ..Default::default()
+        };
+        assert!(synthetic_hydro_dataset(SyntheticTerrain::Dendritic, &params).is_err());
+        let params = SyntheticHydroParams {
+            hillslope_slope: 0.05,
+            ..Default::default()
+        };
+        assert!(synthetic_hydro_dataset(SyntheticTerrain::Dendritic, &params).is_err());
+        assert!(synthetic_hydro_dataset(SyntheticTerrain::VValley, &params).is_ok());
+
+        // Turning a channel cell's pointer off the channel is caught.
+        let mut d = dataset(SyntheticTerrain::VValley, 12, 9);
+        d.d8_pntr[6 * 9 + 4] = 2f64;
+        assert!(d.check_consistency().is_err());
+        let mut d = dataset(SyntheticTerrain::VValley, 12, 9);
+        d.chnjnt[6 * 9 + 4] = 2f64;
+        assert!(d.check_consistency().is_err());
+    }
+
+    #[test]
+    fn test_terrain_names() {
+        for terrain in [
+            SyntheticTerrain::InclinedPlane,
+            SyntheticTerrain::VValley,
+            SyntheticTerrain::Dendritic,
+        ] {
+            assert_eq!(SyntheticTerrain::from_name(terrain.name()), Some(terrain));
+        }
+        assert_eq!(
+            SyntheticTerrain::from_name("V-Valley"),
+            Some(SyntheticTerrain::VValley)
+        );
+        assert_eq!(SyntheticTerrain::from_name("mountain"), None);
+    }
+}
@@ -0,0 +1,297 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::collections::HashMap;
+
+/// A closed boundary ring of a zone, as traced by `trace_zone_boundaries`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ZoneRing<T> {
+    pub zone: T,
+    /// The region, i.e. edge-connected group of cells of the zone, that the ring bounds. Regions
+    /// are numbered from 0 in the row-major order of their first cell.
+    pub region: usize,
+    /// The corners at which the boundary turns, as (row, column) grid corners, corner (r, c)
+    /// being the north-west corner of cell (r, c). The first vertex is repeated at the end.
+    pub vertices: Vec<(usize, usize)>,
+    /// True for a ring around a hole in the zone. Exterior rings run clockwise and holes
+    /// counter-clockwise with north up, as in Shapefiles.
+    pub is_hole: bool,
+}
+
+type Corner = (usize, usize);
+
+/// Traces the boundaries of the zones of a grid into closed rings along the cell edges.
+/// `zone_of(row, column)` gives the zone of each cell, or `None` for cells outside every zone
+/// (e.g. nodata). The cells of a zone are first labelled into regions of cells sharing an edge;
+/// cells that only touch at a corner are in different regions unless connected otherwise, and
+/// their rings meet at that corner but stay separate, so each ring is simple. Each region has one
+/// exterior ring and a hole ring for every enclosed area that is not part of the region, which
+/// may hold other zones.
+///
+/// The rings are returned region by region, in the row-major order of each region's first cell,
+/// with the exterior ring first and then the holes in the order of their first edge. This is the
+/// order in which `VectorWriter` expects the parts of a polygon, and it is repeatable. This is
+/// the machinery for vectorizing a raster of zones, e.g. a footprint or a subcatchment raster;
+/// the caller maps the grid corners to map coordinates. The region labels take four bytes per
+/// cell.
+pub fn trace_zone_boundaries<T, F>(rows: usize, columns: usize, zone_of: F) -> Vec<ZoneRing<T>>
+where
+    T: Copy + PartialEq,
+    F: Fn(usize, usize) -> Option<T>,
+{
+    // Label the regions by flood fill.
+    const UNLABELLED: u32 = u32::MAX;
+    let mut region_of = vec![UNLABELLED; rows * columns];
+    let mut zones: Vec<T> = vec![];
+    let mut stack: Vec<(usize, usize)> = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if region_of[row * columns + col] != UNLABELLED {
+                continue;
+            }
+            let zone = match zone_of(row, col) {
+                Some(zone) => zone,
+                None => continue,
+            };
+            let region = zones.len() as u32;
+            zones.push(zone);
+            region_of[row * columns + col] = region;
+            stack.push((row, col));
+            while let Some((r, c)) = stack.pop() {
+                let neighbours = [
+                    (r.wrapping_sub(1), c),
+                    (r, c + 1),
+                    (r + 1, c),
+                    (r, c.wrapping_sub(1)),
+                ];
+                for (nr, nc) in neighbours {
+                    if nr < rows
+                        && nc < columns
+                        && region_of[nr * columns + nc] == UNLABELLED
+                        && zone_of(nr, nc) == Some(zone)
+                    {
+                        region_of[nr * columns + nc] = region;
+                        stack.push((nr, nc));
+                    }
+                }
+            }
+        }
+    }
+
+    // The directed boundary edges of each region, oriented with the region on their right
+    // (north up). The first is the top edge of the region's first cell, on its exterior ring.
+    let mut region_edges: Vec<Vec<(Corner, Corner)>> = vec![vec![]; zones.len()];
+    let differs = |region: u32, row: usize, col: usize| region_of[row * columns + col] != region;
+    for row in 0..rows {
+        for col in 0..columns {
+            let region = region_of[row * columns + col];
+            if region == UNLABELLED {
+                continue;
+            }
+            let edges = &mut region_edges[region as usize];
+            if row == 0 || differs(region, row - 1, col) {
+                edges.push(((row, col), (row, col + 1)));
+            }
+            if col + 1 == columns || differs(region, row, col + 1) {
+                edges.push(((row, col + 1), (row + 1, col + 1)));
+            }
+            if row + 1 == rows || differs(region, row + 1, col) {
+                edges.push(((row + 1, col + 1), (row + 1, col)));
+            }
+            if col == 0 || differs(region, row, col - 1) {
+                edges.push(((row + 1, col), (row, col)));
+            }
+        }
+    }
+
+    let mut rings = vec![];
+    for (region, (zone, edges)) in zones.into_iter().zip(region_edges).enumerate() {
+        for vertices in link_edges(&edges) {
+            let is_hole = signed_area(&vertices) > 0f64;
+            rings.push(ZoneRing {
+                zone,
+                region,
+                vertices,
+                is_hole,
+            });
+        }
+    }
+    rings
+}
+
+/// The direction of an edge, as (row, column) steps.
+fn direction((start, end): (Corner, Corner)) -> (isize, isize) {
+    (
+        end.0 as isize - start.0 as isize,
+        end.1 as isize - start.1 as isize,
+    )
+}
+
+/// Links the directed edges of a region into closed rings of their turning corners. Where the
+/// boundary of a region meets itself at a corner, it turns left, i.e. away from the region, so
+/// that no ring passes through a corner twice: an area enclosed except at that corner becomes a
+/// hole touching the exterior ring there, as the simple features specification requires.
+fn link_edges(edges: &[(Corner, Corner)]) -> Vec<Vec<Corner>> {
+    let mut outgoing: HashMap<Corner, Vec<usize>> = HashMap::new();
+    for (i, &(start, _)) in edges.iter().enumerate() {
+        outgoing.entry(start).or_default().push(i);
+    }
+    // Only a corner where the region meets itself has two outgoing edges, one turning left
+    // and one turning right.
+    let turns_left = |from: (isize, isize), to: (isize, isize)| to == (-from.1, from.0);
+
+    let mut used = vec![false; edges.len()];
+    let mut rings = vec![];
+    for first in 0..edges.len() {
+        if used[first] {
+            continue;
+        }
+        used[first] = true;
+        let mut corners: Vec<Corner> = vec![];
+        let mut current = first;
+        loop {
+            let dir = direction(edges[current]);
+            let end = edges[current].1;
+            let next = outgoing[&end]
+                .iter()
+                .copied()
+                .filter(|&e| !used[e] || e == first)
+                .max_by_key(|&e| turns_left(dir, direction(edges[e])))
+                .expect("The boundary edges of a zone form closed rings.");
+            if direction(edges[next]) != dir {
+                corners.push(end);
+            }
+            if next == first {
+                break;
+            }
+            used[next] = true;
+            current = next;
+        }
+        // Start from the corner at which the ring was closed, i.e. the first edge's start when
+        // the ring turns there.
+        corners.rotate_right(1);
+        corners.push(corners[0]);
+        rings.push(corners);
+    }
+    rings
+}
+
+/// Twice the signed area of a closed ring of (row, column) corners with north up: positive for
+/// counter-clockwise rings.
+fn signed_area(vertices: &[Corner]) -> f64 {
+    vertices
+        .windows(2)
+        .map(|w| {
+            let (x0, y0) = (w[0].1 as f64, -(w[0].0 as f64));
+            let (x1, y1) = (w[1].1 as f64, -(w[1].0 as f64));
+            x0 * y1 - x1 * y0
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{trace_zone_boundaries, ZoneRing};
+
+    fn grid<'a>(rows: &'a [&'a str]) -> impl Fn(usize, usize) -> Option<char> + 'a {
+        move |r, c| match rows[r].as_bytes()[c] {
+            b'.' => None,
+            b => Some(b as char),
+        }
+    }
+
+    #[test]
+    fn test_single_cell() {
+        let rings = trace_zone_boundaries(1, 1, |_, _| Some(()));
+        assert_eq!(
+            rings,
+            vec![ZoneRing {
+                zone: (),
+                region: 0,
+                vertices: vec![(0, 0), (0, 1), (1, 1), (1, 0), (0, 0)],
+                is_hole: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_hole() {
+        let rows = ["aaa", "a.a", "aaa"];
+        let rings = trace_zone_boundaries(3, 3, grid(&rows));
+        assert_eq!(rings.len(), 2);
+        assert_eq!(
+            rings[0].vertices,
+            vec![(0, 0), (0, 3), (3, 3), (3, 0), (0, 0)]
+        );
+        assert!(!rings[0].is_hole);
+        // The hole runs counter-clockwise, i.e. with the zone on its right.
+        assert_eq!(
+            rings[1].vertices,
+            vec![(1, 2), (1, 1), (2, 1), (2, 2), (1, 2)]
+        );
+        assert!(rings[1].is_hole);
+    }
+
+    #[test]
+    fn test_cells_touching_at_a_corner() {
+        let rows = ["a.", ".a"];
+        let rings = trace_zone_boundaries(2, 2, grid(&rows));
+        assert_eq!(rings.len(), 2);
+        assert!(rings.iter().all(|r| !r.is_hole && r.vertices.len() == 5));
+        assert_eq!(
+            rings[1].vertices,
+            vec![(1, 1), (1, 2), (2, 2), (2, 1), (1, 1)]
+        );
+
+        // Cells around the centre that touch only at corners do not enclose it as a hole.
+        let rows = [".a.", "a.a", ".aa"];
+        let rings = trace_zone_boundaries(3, 3, grid(&rows));
+        assert_eq!(rings.len(), 3);
+        assert!(rings.iter().all(|r| !r.is_hole));
+    }
+
+    #[test]
+    fn test_region_order() {
+        // An island in the hole of a region of the same zone follows the region's hole.
+        let rows = ["aaaaa", "a...a", "a.a.a", "a...a", "aaaaa"];
+        let rings = trace_zone_boundaries(5, 5, grid(&rows));
+        let parts: Vec<(usize, bool)> = rings.iter().map(|r| (r.region, r.is_hole)).collect();
+        assert_eq!(parts, vec![(0, false), (0, true), (1, false)]);
+        assert_eq!(
+            rings[2].vertices,
+            vec![(2, 2), (2, 3), (3, 3), (3, 2), (2, 2)]
+        );
+
+        // A region meeting itself at corner (1, 2) encloses a hole touching its exterior there.
+        let rows = ["aa.", "a.a", "aaa"];
+        let rings = trace_zone_boundaries(3, 3, grid(&rows));
+        let parts: Vec<(usize, bool)> = rings.iter().map(|r| (r.region, r.is_hole)).collect();
+        assert_eq!(parts, vec![(0, false), (0, true)]);
+        assert_eq!(
+            rings[1].vertices,
+            vec![(1, 2), (1, 1), (2, 1), (2, 2), (1, 2)]
+        );
+    }
+
+    #[test]
+    fn test_zones() {
+        let rows = ["aab", "abb", "ccc"];
+        let rings = trace_zone_boundaries(3, 3, grid(&rows));
+        let zones: Vec<(char, usize)> = rings.iter().map(|r| (r.zone, r.region)).collect();
+        assert_eq!(zones, vec![('a', 0), ('b', 1), ('c', 2)]);
+        assert_eq!(
+            rings[0].vertices,
+            vec![(0, 0), (0, 2), (1, 2), (1, 1), (2, 1), (2, 0), (0, 0)]
+        );
+        assert_eq!(
+            rings[2].vertices,
+            vec![(2, 0), (2, 3), (3, 3), (3, 0), (2, 0)]
+        );
+        assert!(rings.iter().all(|r| !r.is_hole));
+    }
+}
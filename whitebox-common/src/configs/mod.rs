@@ -4,6 +4,7 @@ use std::fs;
 use std::fs::File;
 use std::io::prelude::*;
 use std::io::Error;
+use std::path::{Path, PathBuf};
 // use std::path;
 
 /// A structure to hold environment settings. Backed by settings.json file in same directory
@@ -27,21 +28,19 @@ impl Configs {
 }
 
 pub fn get_configs() -> std::result::Result<Configs, Error> {
-    let mut exe_path = std::env::current_exe().unwrap();
-    exe_path.pop();
-    if exe_path.ends_with("plugins") {
-        exe_path.pop();
-    }
-    if exe_path.ends_with("whitebox_tools") || exe_path.ends_with("whitebox_tools.exe") {
-        exe_path.pop();
-    }
-    let config_file = exe_path.join("settings.json");
+    let config_file = exe_settings_file(&std::env::current_exe().unwrap());
     let config_file = config_file
         .to_str()
         .unwrap_or("No configs path found.")
         .to_string();
 
-    let configs: Configs = match fs::read_to_string(config_file) {
+    // A settings.json beside the executable takes precedence over one in the platform's
+    // configuration directory, which is used when the install location is not writable.
+    let contents = fs::read_to_string(config_file).or_else(|e| match platform_settings_file() {
+        Some(file) => fs::read_to_string(file),
+        None => Err(e),
+    });
+    let configs: Configs = match contents {
         Ok(contents) => {
             serde_json::from_str(&contents).expect("Failed to parse config_file.json file.")
         }
@@ -50,10 +49,10 @@ pub fn get_configs() -> std::result::Result<Configs, Error> {
     Ok(configs)
 }
 
-pub fn save_configs<'a>(configs: &Configs) -> std::result::Result<(), Error> {
-    let configs_json =
-        serde_json::to_string_pretty(&configs).expect("Error converting Configs object to JSON.");
-    let mut exe_path = std::env::current_exe().unwrap();
+/// The settings.json read by the WhiteboxTools executable `exe`, or by a plugin executable in its
+/// `plugins` directory, which sits beside the executables.
+pub fn exe_settings_file(exe: &Path) -> PathBuf {
+    let mut exe_path = exe.to_path_buf();
     exe_path.pop();
     if exe_path.ends_with("plugins") {
         exe_path.pop();
@@ -61,7 +60,33 @@ pub fn save_configs<'a>(configs: &Configs) -> std::result::Result<(), Error> {
     if exe_path.ends_with("whitebox_tools") || exe_path.ends_with("whitebox_tools.exe") {
         exe_path.pop();
     }
-    let config_file = exe_path.join("settings.json");
+    exe_path.join("settings.json")
+}
+
+/// The settings.json in the platform's configuration directory: `%APPDATA%\whitebox_tools` on
+/// Windows, `~/Library/Application Support/whitebox_tools` on macOS, and
+/// `$XDG_CONFIG_HOME/whitebox_tools` (by default `~/.config/whitebox_tools`) elsewhere. Returns
+/// `None` when the directory cannot be determined from the environment.
+pub fn platform_settings_file() -> Option<PathBuf> {
+    let env_dir = |name: &str| {
+        std::env::var_os(name)
+            .filter(|v| !v.is_empty())
+            .map(PathBuf::from)
+    };
+    let config_dir = if cfg!(windows) {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library").join("Application Support"))
+    } else {
+        env_dir("XDG_CONFIG_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".config")))
+    }?;
+    Some(config_dir.join("whitebox_tools").join("settings.json"))
+}
+
+pub fn save_configs<'a>(configs: &Configs) -> std::result::Result<(), Error> {
+    let configs_json =
+        serde_json::to_string_pretty(&configs).expect("Error converting Configs object to JSON.");
+    let config_file = exe_settings_file(&std::env::current_exe().unwrap());
     let config_file = config_file
         .to_str()
         .unwrap_or("No configs path found.")
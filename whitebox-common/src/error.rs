@@ -0,0 +1,222 @@
+use std::fmt;
+use std::io;
+
+/// A classified error for tool `run()` implementations, telling user errors, input data
+/// errors, and internal bugs apart.
+///
+/// The `WhiteboxTool` trait still returns `io::Error`, so a `WbtError` is converted into an
+/// `io::Error` that carries it as its payload (with the matching `ErrorKind`), and converting
+/// that `io::Error` back into a `WbtError`, e.g. with `?` in a function returning `WbtError`,
+/// recovers the original variant. Other `io::Error`s are classified by their kind.
+///
+/// ```
+/// use whitebox_common::error::{ErrorContext, WbtError};
+///
+/// fn read_order(value: &str) -> Result<u8, WbtError> {
+///     value
+///         .parse::<u8>()
+///         .map_err(|_| WbtError::invalid_argument(format!("'{}' is not an order", value)))
+///         .context("--min_order")
+/// }
+///
+/// let err = read_order("x").unwrap_err();
+/// assert!(matches!(err, WbtError::InvalidArgument(_)));
+/// assert_eq!(err.to_string(), "--min_order: 'x' is not an order");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WbtError {
+    /// Missing, malformed, or contradictory arguments.
+    InvalidArgument(String),
+    /// An input file that does not exist.
+    FileNotFound(String),
+    /// Input rasters that do not align, or that do not describe a usable area.
+    GeometryMismatch(String),
+    /// Input data that could be read but not used, e.g. a corrupt or unsupported file.
+    DataError(String),
+    /// A coordinate that could not be converted into the grid, e.g. a lon/lat outside the
+    /// area of the raster's projection.
+    ConversionFailure(String),
+    /// An analysis that ran but found no result, e.g. no outlet could be traced. Unlike the
+    /// other failures, a retry with relaxed options may succeed.
+    TraceFailure(String),
+    /// A failure that is not the user's or the data's fault.
+    Internal(String),
+}
+
+impl WbtError {
+    pub fn invalid_argument<S: Into<String>>(message: S) -> WbtError {
+        WbtError::InvalidArgument(message.into())
+    }
+
+    pub fn file_not_found<S: Into<String>>(message: S) -> WbtError {
+        WbtError::FileNotFound(message.into())
+    }
+
+    pub fn geometry_mismatch<S: Into<String>>(message: S) -> WbtError {
+        WbtError::GeometryMismatch(message.into())
+    }
+
+    pub fn data_error<S: Into<String>>(message: S) -> WbtError {
+        WbtError::DataError(message.into())
+    }
+
+    pub fn conversion_failure<S: Into<String>>(message: S) -> WbtError {
+        WbtError::ConversionFailure(message.into())
+    }
+
+    pub fn trace_failure<S: Into<String>>(message: S) -> WbtError {
+        WbtError::TraceFailure(message.into())
+    }
+
+    pub fn internal<S: Into<String>>(message: S) -> WbtError {
+        WbtError::Internal(message.into())
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            WbtError::InvalidArgument(message)
+            | WbtError::FileNotFound(message)
+            | WbtError::GeometryMismatch(message)
+            | WbtError::DataError(message)
+            | WbtError::ConversionFailure(message)
+            | WbtError::TraceFailure(message)
+            | WbtError::Internal(message) => message,
+        }
+    }
+
+    /// Prefixes the message with `context`, e.g. the flag or file the error concerns, keeping
+    /// the variant. Contexts added later come first: `--dem: dem.tif: No such file`.
+    pub fn context<C: fmt::Display>(self, context: C) -> WbtError {
+        let with_context = |message: String| format!("{}: {}", context, message);
+        match self {
+            WbtError::InvalidArgument(m) => WbtError::InvalidArgument(with_context(m)),
+            WbtError::FileNotFound(m) => WbtError::FileNotFound(with_context(m)),
+            WbtError::GeometryMismatch(m) => WbtError::GeometryMismatch(with_context(m)),
+            WbtError::DataError(m) => WbtError::DataError(with_context(m)),
+            WbtError::ConversionFailure(m) => WbtError::ConversionFailure(with_context(m)),
+            WbtError::TraceFailure(m) => WbtError::TraceFailure(with_context(m)),
+            WbtError::Internal(m) => WbtError::Internal(with_context(m)),
+        }
+    }
+
+    /// The `WbtError` carried by an `io::Error` returned by a tool, if any.
+    pub fn from_io_ref(err: &io::Error) -> Option<&WbtError> {
+        err.get_ref().and_then(|e| e.downcast_ref::<WbtError>())
+    }
+
+    fn io_kind(&self) -> io::ErrorKind {
+        match self {
+            WbtError::InvalidArgument(_)
+            | WbtError::GeometryMismatch(_)
+            | WbtError::ConversionFailure(_) => io::ErrorKind::InvalidInput,
+            // A failed trace keeps the NotFound kind that scripts check to retry it, but its
+            // payload, not its kind, classifies it when converted back.
+            WbtError::FileNotFound(_) | WbtError::TraceFailure(_) => io::ErrorKind::NotFound,
+            WbtError::DataError(_) => io::ErrorKind::InvalidData,
+            WbtError::Internal(_) => io::ErrorKind::Other,
+        }
+    }
+}
+
+impl fmt::Display for WbtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message())
+    }
+}
+
+impl std::error::Error for WbtError {}
+
+impl From<io::Error> for WbtError {
+    fn from(err: io::Error) -> WbtError {
+        if let Some(wbt_error) = WbtError::from_io_ref(&err) {
+            return wbt_error.clone();
+        }
+        let message = err.to_string();
+        match err.kind() {
+            io::ErrorKind::NotFound => WbtError::FileNotFound(message),
+            io::ErrorKind::InvalidInput => WbtError::InvalidArgument(message),
+            io::ErrorKind::InvalidData | io::ErrorKind::UnexpectedEof => {
+                WbtError::DataError(message)
+            }
+            _ => WbtError::Internal(message),
+        }
+    }
+}
+
+impl From<WbtError> for io::Error {
+    fn from(err: WbtError) -> io::Error {
+        io::Error::new(err.io_kind(), err)
+    }
+}
+
+/// Adds `context` to the error of a `Result`, converting it into a `WbtError`.
+pub trait ErrorContext<T> {
+    fn context<C: fmt::Display>(self, context: C) -> Result<T, WbtError>;
+}
+
+impl<T, E: Into<WbtError>> ErrorContext<T> for Result<T, E> {
+    fn context<C: fmt::Display>(self, context: C) -> Result<T, WbtError> {
+        self.map_err(|e| e.into().context(context))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ErrorContext, WbtError};
+    use std::io;
+
+    #[test]
+    fn test_io_round_trip() {
+        let err: io::Error = WbtError::geometry_mismatch("Rasters differ.").into();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+        assert_eq!(err.to_string(), "Rasters differ.");
+        assert_eq!(
+            WbtError::from_io_ref(&err),
+            Some(&WbtError::geometry_mismatch("Rasters differ."))
+        );
+        assert_eq!(
+            WbtError::from(err),
+            WbtError::geometry_mismatch("Rasters differ.")
+        );
+    }
+
+    #[test]
+    fn test_trace_failure_round_trip() {
+        let err: io::Error = WbtError::trace_failure("No outlet could be traced.").into();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(
+            WbtError::from(err),
+            WbtError::trace_failure("No outlet could be traced.")
+        );
+        let result: Result<(), io::Error> = Err(WbtError::trace_failure("No outlet.").into());
+        assert_eq!(
+            result.context("--pour_pts").unwrap_err(),
+            WbtError::trace_failure("--pour_pts: No outlet.")
+        );
+    }
+
+    #[test]
+    fn test_from_io_kind() {
+        let not_found = io::Error::new(io::ErrorKind::NotFound, "No such file");
+        assert!(WbtError::from_io_ref(&not_found).is_none());
+        assert_eq!(
+            WbtError::from(not_found),
+            WbtError::file_not_found("No such file")
+        );
+        let eof = io::Error::new(io::ErrorKind::UnexpectedEof, "Truncated");
+        assert_eq!(WbtError::from(eof), WbtError::data_error("Truncated"));
+        let denied = io::Error::new(io::ErrorKind::PermissionDenied, "Denied");
+        assert_eq!(WbtError::from(denied), WbtError::internal("Denied"));
+    }
+
+    #[test]
+    fn test_context() {
+        let result: Result<(), io::Error> =
+            Err(io::Error::new(io::ErrorKind::NotFound, "No such file"));
+        let err = result.context("dem.tif").context("--dem").unwrap_err();
+        assert_eq!(
+            err,
+            WbtError::file_not_found("--dem: dem.tif: No such file")
+        );
+    }
+}
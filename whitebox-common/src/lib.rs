@@ -1,9 +1,12 @@
 pub mod algorithms;
 pub mod configs;
+pub mod error;
 pub mod plugins;
 pub mod rendering;
 pub mod spatial_ref_system;
 pub mod structures;
+#[cfg(any(test, feature = "test-support"))]
+pub mod test_support;
 pub mod utils;
 
 use nalgebra as na;
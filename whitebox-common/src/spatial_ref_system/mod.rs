@@ -1,3 +1,7 @@
 mod epsg_to_wkt;
+mod projection;
+mod wkt_to_epsg;
 
 pub use self::epsg_to_wkt::esri_wkt_from_epsg;
+pub use self::projection::{Projection, ProjectionMethod};
+pub use self::wkt_to_epsg::epsg_from_wkt;
@@ -0,0 +1,672 @@
+use super::esri_wkt_from_epsg;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+/// The map projection methods supported by `Projection`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ProjectionMethod {
+    /// Unprojected longitude/latitude.
+    Geographic,
+    /// Transverse Mercator, including UTM, Gauss-Kruger, and the Transverse Mercator State Plane
+    /// zones.
+    TransverseMercator,
+    /// Lambert Conformal Conic, with one or two standard parallels.
+    LambertConformalConic,
+    /// Albers Equal Area Conic.
+    Albers,
+    /// Ellipsoidal Mercator.
+    Mercator,
+    /// Spherical ("Web") Mercator, e.g. EPSG:3857.
+    WebMercator,
+}
+
+/// A projected (or geographic) coordinate system, as described by its WKT, that can convert
+/// longitude/latitude to map coordinates and back.
+///
+/// Only the projection is applied; no datum transformation is made. Coordinates on the WGS84
+/// datum may be used with NAD83 and ETRS89 systems to within a couple of metres, but not with
+/// e.g. NAD27 systems, whose datum differs by tens of metres or more.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Projection {
+    pub method: ProjectionMethod,
+    /// The semi-major axis of the ellipsoid, in metres.
+    pub semi_major_axis: f64,
+    /// The flattening of the ellipsoid (0 for a sphere).
+    pub flattening: f64,
+    /// The projection parameters, in degrees.
+    pub central_meridian: f64,
+    pub latitude_of_origin: f64,
+    pub standard_parallel_1: f64,
+    pub standard_parallel_2: f64,
+    pub scale_factor: f64,
+    /// The false easting and northing, in the linear unit.
+    pub false_easting: f64,
+    pub false_northing: f64,
+    /// The length of the linear unit of the map coordinates in metres, e.g. 0.3048006096012192
+    /// for US survey feet.
+    pub linear_unit: f64,
+}
+
+impl Projection {
+    /// Returns the projection of an EPSG code, using its WKT from `esri_wkt_from_epsg`.
+    pub fn from_epsg(epsg: u16) -> Option<Projection> {
+        let wkt = esri_wkt_from_epsg(epsg);
+        if wkt == "Unknown EPSG Code" {
+            return None;
+        }
+        Projection::from_wkt(&wkt)
+    }
+
+    /// Reads the projection of an OGC or ESRI WKT coordinate system, e.g. the contents of a
+    /// .prj file. Returns `None` if the WKT cannot be parsed or its projection method is not
+    /// supported.
+    pub fn from_wkt(wkt: &str) -> Option<Projection> {
+        let root = WktNode::parse(wkt)?;
+        let (geogcs, method) = match root.keyword.as_str() {
+            "GEOGCS" => (&root, ProjectionMethod::Geographic),
+            "PROJCS" => {
+                let name = root.child("PROJECTION")?.text(0)?.to_lowercase();
+                let method = match name.as_str() {
+                    "transverse_mercator" | "gauss_kruger" => ProjectionMethod::TransverseMercator,
+                    "lambert_conformal_conic"
+                    | "lambert_conformal_conic_1sp"
+                    | "lambert_conformal_conic_2sp" => ProjectionMethod::LambertConformalConic,
+                    "albers" | "albers_conic_equal_area" => ProjectionMethod::Albers,
+                    "mercator" | "mercator_1sp" | "mercator_2sp" => ProjectionMethod::Mercator,
+                    "mercator_auxiliary_sphere" | "popular_visualisation_pseudo_mercator" => {
+                        ProjectionMethod::WebMercator
+                    }
+                    _ => return None,
+                };
+                (root.child("GEOGCS")?, method)
+            }
+            _ => return None,
+        };
+        let spheroid = geogcs.child("DATUM")?.child("SPHEROID")?;
+        let semi_major_axis = spheroid.number(1)?;
+        let inverse_flattening = spheroid.number(2)?;
+
+        let parameter = |names: &[&str]| -> Option<f64> {
+            root.children().find_map(|node| {
+                if node.keyword != "PARAMETER" {
+                    return None;
+                }
+                let name = node.text(0)?;
+                if names.iter().any(|n| n.eq_ignore_ascii_case(name)) {
+                    node.number(1)
+                } else {
+                    None
+                }
+            })
+        };
+        let latitude_of_origin =
+            parameter(&["latitude_of_origin", "latitude_of_center"]).unwrap_or(0f64);
+        let standard_parallel_1 = parameter(&["standard_parallel_1"]).unwrap_or(latitude_of_origin);
+        let standard_parallel_2 =
+            parameter(&["standard_parallel_2"]).unwrap_or(standard_parallel_1);
+        let linear_unit = match method {
+            ProjectionMethod::Geographic => 1f64,
+            _ => root.child("UNIT").and_then(|unit| unit.number(1))?,
+        };
+
+        Some(Projection {
+            method,
+            semi_major_axis,
+            flattening: if inverse_flattening == 0f64 {
+                0f64
+            } else {
+                1f64 / inverse_flattening
+            },
+            central_meridian: parameter(&[
+                "central_meridian",
+                "longitude_of_center",
+                "longitude_of_origin",
+            ])
+            .unwrap_or(0f64),
+            latitude_of_origin,
+            standard_parallel_1,
+            standard_parallel_2,
+            scale_factor: parameter(&["scale_factor"]).unwrap_or(1f64),
+            false_easting: parameter(&["false_easting"]).unwrap_or(0f64),
+            false_northing: parameter(&["false_northing"]).unwrap_or(0f64),
+            linear_unit,
+        })
+    }
+
+    /// Converts a longitude/latitude, in degrees, to map coordinates (x, y). Geographic systems
+    /// return the longitude and latitude unchanged. Returns `None` for a location that cannot be
+    /// projected, e.g. a pole in the Mercator projection.
+    pub fn forward(&self, lon: f64, lat: f64) -> Option<(f64, f64)> {
+        if self.method == ProjectionMethod::Geographic {
+            return Some((lon, lat));
+        }
+        if !lon.is_finite() || !lat.is_finite() || lat.abs() > 90f64 {
+            return None;
+        }
+        let a = self.semi_major_axis;
+        let e2 = self.eccentricity_squared();
+        let e = e2.sqrt();
+        let phi = lat.to_radians();
+        // The longitude from the central meridian, wrapped to [-180, 180).
+        let lambda =
+            ((lon - self.central_meridian + 540f64).rem_euclid(360f64) - 180f64).to_radians();
+
+        let (x, y) = match self.method {
+            ProjectionMethod::TransverseMercator => {
+                let ep2 = e2 / (1f64 - e2);
+                let n = a / (1f64 - e2 * phi.sin().powi(2)).sqrt();
+                let tt = phi.tan().powi(2);
+                let c = ep2 * phi.cos().powi(2);
+                let aa = lambda * phi.cos();
+                let k0 = self.scale_factor;
+                let x = k0
+                    * n
+                    * (aa
+                        + (1f64 - tt + c) * aa.powi(3) / 6f64
+                        + (5f64 - 18f64 * tt + tt * tt + 72f64 * c - 58f64 * ep2) * aa.powi(5)
+                            / 120f64);
+                let y = k0
+                    * (meridian_arc(a, e2, phi)
+                        - meridian_arc(a, e2, self.latitude_of_origin.to_radians())
+                        + n * phi.tan()
+                            * (aa * aa / 2f64
+                                + (5f64 - tt + 9f64 * c + 4f64 * c * c) * aa.powi(4) / 24f64
+                                + (61f64 - 58f64 * tt + tt * tt + 600f64 * c - 330f64 * ep2)
+                                    * aa.powi(6)
+                                    / 720f64));
+                (x, y)
+            }
+            ProjectionMethod::LambertConformalConic => {
+                let (n, f, rho0) = self.lambert_constants();
+                let rho = if (phi.abs() - FRAC_PI_2).abs() < 1e-12 && phi * n > 0f64 {
+                    0f64
+                } else {
+                    a * f * t(e, phi).powf(n) * self.scale_factor
+                };
+                let theta = n * lambda;
+                (rho * theta.sin(), rho0 - rho * theta.cos())
+            }
+            ProjectionMethod::Albers => {
+                let (n, c, rho0) = self.albers_constants();
+                let rho = a * (c - n * q(e, phi)).max(0f64).sqrt() / n;
+                let theta = n * lambda;
+                (rho * theta.sin(), rho0 - rho * theta.cos())
+            }
+            ProjectionMethod::Mercator => {
+                if (lat.abs() - 90f64).abs() < 1e-10 {
+                    return None;
+                }
+                let k0 = self.scale_factor * m(e2, self.standard_parallel_1.to_radians());
+                (a * k0 * lambda, -a * k0 * t(e, phi).ln())
+            }
+            ProjectionMethod::WebMercator => {
+                if (lat.abs() - 90f64).abs() < 1e-10 {
+                    return None;
+                }
+                (a * lambda, a * (FRAC_PI_4 + phi / 2f64).tan().ln())
+            }
+            ProjectionMethod::Geographic => unreachable!(),
+        };
+        if !x.is_finite() || !y.is_finite() {
+            return None;
+        }
+        Some((
+            self.false_easting + x / self.linear_unit,
+            self.false_northing + y / self.linear_unit,
+        ))
+    }
+
+    /// Converts map coordinates (x, y) to a longitude/latitude, in degrees; the inverse of
+    /// `forward`. Geographic systems return the coordinates unchanged. Returns `None` for
+    /// coordinates that do not correspond to a location.
+    pub fn inverse(&self, x: f64, y: f64) -> Option<(f64, f64)> {
+        if self.method == ProjectionMethod::Geographic {
+            return Some((x, y));
+        }
+        if !x.is_finite() || !y.is_finite() {
+            return None;
+        }
+        let a = self.semi_major_axis;
+        let e2 = self.eccentricity_squared();
+        let e = e2.sqrt();
+        let x = (x - self.false_easting) * self.linear_unit;
+        let y = (y - self.false_northing) * self.linear_unit;
+
+        let (lambda, phi) = match self.method {
+            ProjectionMethod::TransverseMercator => {
+                // Snyder (1987), eqs. 3-26 and 8-18 to 8-25.
+                let ep2 = e2 / (1f64 - e2);
+                let k0 = self.scale_factor;
+                let arc = meridian_arc(a, e2, self.latitude_of_origin.to_radians()) + y / k0;
+                let (e4, e6) = (e2 * e2, e2 * e2 * e2);
+                let mu = arc / (a * (1f64 - e2 / 4f64 - 3f64 * e4 / 64f64 - 5f64 * e6 / 256f64));
+                let e1 = (1f64 - (1f64 - e2).sqrt()) / (1f64 + (1f64 - e2).sqrt());
+                let phi1 = mu
+                    + (3f64 * e1 / 2f64 - 27f64 * e1.powi(3) / 32f64) * (2f64 * mu).sin()
+                    + (21f64 * e1 * e1 / 16f64 - 55f64 * e1.powi(4) / 32f64) * (4f64 * mu).sin()
+                    + (151f64 * e1.powi(3) / 96f64) * (6f64 * mu).sin()
+                    + (1097f64 * e1.powi(4) / 512f64) * (8f64 * mu).sin();
+                let sin1 = phi1.sin();
+                let c1 = ep2 * phi1.cos().powi(2);
+                let t1 = phi1.tan().powi(2);
+                let n1 = a / (1f64 - e2 * sin1 * sin1).sqrt();
+                let r1 = a * (1f64 - e2) / (1f64 - e2 * sin1 * sin1).powf(1.5);
+                let d = x / (n1 * k0);
+                let phi = phi1
+                    - (n1 * phi1.tan() / r1)
+                        * (d * d / 2f64
+                            - (5f64 + 3f64 * t1 + 10f64 * c1 - 4f64 * c1 * c1 - 9f64 * ep2)
+                                * d.powi(4)
+                                / 24f64
+                            + (61f64 + 90f64 * t1 + 298f64 * c1 + 45f64 * t1 * t1
+                                - 252f64 * ep2
+                                - 3f64 * c1 * c1)
+                                * d.powi(6)
+                                / 720f64);
+                let lambda = (d - (1f64 + 2f64 * t1 + c1) * d.powi(3) / 6f64
+                    + (5f64 - 2f64 * c1 + 28f64 * t1 - 3f64 * c1 * c1
+                        + 8f64 * ep2
+                        + 24f64 * t1 * t1)
+                        * d.powi(5)
+                        / 120f64)
+                    / phi1.cos();
+                (lambda, phi)
+            }
+            ProjectionMethod::LambertConformalConic => {
+                let (n, f, rho0) = self.lambert_constants();
+                let sign = n.signum();
+                let rho = sign * (x * x + (rho0 - y).powi(2)).sqrt();
+                let theta = (sign * x).atan2(sign * (rho0 - y));
+                let phi = if rho == 0f64 {
+                    sign * FRAC_PI_2
+                } else {
+                    phi_from_t(e, (rho / (a * f * self.scale_factor)).powf(1f64 / n))?
+                };
+                (theta / n, phi)
+            }
+            ProjectionMethod::Albers => {
+                let (n, c, rho0) = self.albers_constants();
+                let sign = n.signum();
+                let rho = (x * x + (rho0 - y).powi(2)).sqrt();
+                let theta = (sign * x).atan2(sign * (rho0 - y));
+                let q = (c - (rho * n / a).powi(2)) / n;
+                (theta / n, phi_from_q(e, q)?)
+            }
+            ProjectionMethod::Mercator => {
+                let k0 = self.scale_factor * m(e2, self.standard_parallel_1.to_radians());
+                (x / (a * k0), phi_from_t(e, (-y / (a * k0)).exp())?)
+            }
+            ProjectionMethod::WebMercator => (x / a, 2f64 * (y / a).exp().atan() - FRAC_PI_2),
+            ProjectionMethod::Geographic => unreachable!(),
+        };
+        if !lambda.is_finite() || !phi.is_finite() || phi.abs() > FRAC_PI_2 + 1e-10 {
+            return None;
+        }
+        let lon =
+            (self.central_meridian + lambda.to_degrees() + 540f64).rem_euclid(360f64) - 180f64;
+        Some((lon, phi.to_degrees().clamp(-90f64, 90f64)))
+    }
+
+    fn eccentricity_squared(&self) -> f64 {
+        self.flattening * (2f64 - self.flattening)
+    }
+
+    /// The cone constant n, the constant F, and the radius at the latitude of origin of the
+    /// Lambert Conformal Conic projection (Snyder (1987), eqs. 15-8 to 15-10).
+    fn lambert_constants(&self) -> (f64, f64, f64) {
+        let e2 = self.eccentricity_squared();
+        let e = e2.sqrt();
+        let phi0 = self.latitude_of_origin.to_radians();
+        let phi1 = self.standard_parallel_1.to_radians();
+        let phi2 = self.standard_parallel_2.to_radians();
+        let n = if (phi1 - phi2).abs() < 1e-10 {
+            phi1.sin()
+        } else {
+            (m(e2, phi1).ln() - m(e2, phi2).ln()) / (t(e, phi1).ln() - t(e, phi2).ln())
+        };
+        let f = m(e2, phi1) / (n * t(e, phi1).powf(n));
+        let rho0 = if (phi0.abs() - FRAC_PI_2).abs() < 1e-12 && phi0 * n > 0f64 {
+            0f64
+        } else {
+            self.semi_major_axis * f * t(e, phi0).powf(n) * self.scale_factor
+        };
+        (n, f, rho0)
+    }
+
+    /// The cone constant n, the constant C, and the radius at the latitude of origin of the
+    /// Albers Equal Area Conic projection (Snyder (1987), eqs. 14-12 to 14-14).
+    fn albers_constants(&self) -> (f64, f64, f64) {
+        let e2 = self.eccentricity_squared();
+        let e = e2.sqrt();
+        let phi0 = self.latitude_of_origin.to_radians();
+        let phi1 = self.standard_parallel_1.to_radians();
+        let phi2 = self.standard_parallel_2.to_radians();
+        let n = if (phi1 - phi2).abs() < 1e-10 {
+            phi1.sin()
+        } else {
+            (m(e2, phi1).powi(2) - m(e2, phi2).powi(2)) / (q(e, phi2) - q(e, phi1))
+        };
+        let c = m(e2, phi1).powi(2) + n * q(e, phi1);
+        let rho0 = self.semi_major_axis * (c - n * q(e, phi0)).max(0f64).sqrt() / n;
+        (n, c, rho0)
+    }
+}
+
+// The functions m, t, and q of Snyder (1987), Map Projections: A Working Manual, for an ellipsoid
+// of eccentricity e.
+fn m(e2: f64, phi: f64) -> f64 {
+    phi.cos() / (1f64 - e2 * phi.sin().powi(2)).sqrt()
+}
+
+fn t(e: f64, phi: f64) -> f64 {
+    let es = e * phi.sin();
+    (FRAC_PI_4 - phi / 2f64).tan() / ((1f64 - es) / (1f64 + es)).powf(e / 2f64)
+}
+
+fn q(e: f64, phi: f64) -> f64 {
+    let sin = phi.sin();
+    if e == 0f64 {
+        return 2f64 * sin;
+    }
+    let e2 = e * e;
+    (1f64 - e2)
+        * (sin / (1f64 - e2 * sin * sin)
+            - 1f64 / (2f64 * e) * ((1f64 - e * sin) / (1f64 + e * sin)).ln())
+}
+
+/// The distance along the meridian from the equator to latitude `phi` (Snyder (1987), eq. 3-21).
+fn meridian_arc(a: f64, e2: f64, phi: f64) -> f64 {
+    let (e4, e6) = (e2 * e2, e2 * e2 * e2);
+    a * ((1f64 - e2 / 4f64 - 3f64 * e4 / 64f64 - 5f64 * e6 / 256f64) * phi
+        - (3f64 * e2 / 8f64 + 3f64 * e4 / 32f64 + 45f64 * e6 / 1024f64) * (2f64 * phi).sin()
+        + (15f64 * e4 / 256f64 + 45f64 * e6 / 1024f64) * (4f64 * phi).sin()
+        - (35f64 * e6 / 3072f64) * (6f64 * phi).sin())
+}
+
+/// The latitude whose t is `t_value`, by iteration (Snyder (1987), eq. 7-9).
+fn phi_from_t(e: f64, t_value: f64) -> Option<f64> {
+    if !t_value.is_finite() || t_value < 0f64 {
+        return None;
+    }
+    let mut phi = FRAC_PI_2 - 2f64 * t_value.atan();
+    for _ in 0..15 {
+        let es = e * phi.sin();
+        let next = FRAC_PI_2 - 2f64 * (t_value * ((1f64 - es) / (1f64 + es)).powf(e / 2f64)).atan();
+        if (next - phi).abs() < 1e-12 {
+            return Some(next);
+        }
+        phi = next;
+    }
+    Some(phi)
+}
+
+/// The latitude whose q is `q_value`, by iteration (Snyder (1987), eq. 3-16).
+fn phi_from_q(e: f64, q_value: f64) -> Option<f64> {
+    let q_pole = q(e, FRAC_PI_2);
+    if !q_value.is_finite() || q_value.abs() > q_pole + 1e-10 {
+        return None;
+    }
+    if (q_value.abs() - q_pole).abs() < 1e-10 {
+        return Some(FRAC_PI_2.copysign(q_value));
+    }
+    let mut phi = (q_value / 2f64).asin();
+    if e == 0f64 {
+        return Some(phi);
+    }
+    let e2 = e * e;
+    for _ in 0..15 {
+        let sin = phi.sin();
+        let one_minus = 1f64 - e2 * sin * sin;
+        let next = phi
+            + one_minus * one_minus / (2f64 * phi.cos())
+                * (q_value / (1f64 - e2) - sin / one_minus
+                    + 1f64 / (2f64 * e) * ((1f64 - e * sin) / (1f64 + e * sin)).ln());
+        if (next - phi).abs() < 1e-12 {
+            return Some(next);
+        }
+        phi = next;
+    }
+    Some(phi)
+}
+
+/// A WKT element, e.g. `PARAMETER["Central_Meridian",-117.0]`.
+struct WktNode {
+    keyword: String,
+    values: Vec<WktValue>,
+}
+
+enum WktValue {
+    Text(String),
+    Number(f64),
+    Node(WktNode),
+}
+
+impl WktNode {
+    fn parse(wkt: &str) -> Option<WktNode> {
+        let (node, rest) = WktNode::parse_node(wkt.trim())?;
+        if rest.trim().is_empty() {
+            Some(node)
+        } else {
+            None
+        }
+    }
+
+    fn parse_node(s: &str) -> Option<(WktNode, &str)> {
+        let open = s.find(['[', '('])?;
+        let keyword = s[..open].trim().to_uppercase();
+        if keyword.is_empty()
+            || !keyword
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return None;
+        }
+        let mut values = vec![];
+        let mut rest = s[open + 1..].trim_start();
+        loop {
+            if let Some(text) = rest.strip_prefix('"') {
+                let close = text.find('"')?;
+                values.push(WktValue::Text(text[..close].to_string()));
+                rest = &text[close + 1..];
+            } else {
+                let end = rest.find([',', ']', ')'])?;
+                if rest[..end].contains(['[', '(']) {
+                    let (node, r) = WktNode::parse_node(rest)?;
+                    values.push(WktValue::Node(node));
+                    rest = r;
+                } else {
+                    let token = rest[..end].trim();
+                    values.push(match token.parse::<f64>() {
+                        Ok(value) => WktValue::Number(value),
+                        Err(_) => WktValue::Text(token.to_string()), // e.g. AXIS["X",EAST]
+                    });
+                    rest = &rest[end..];
+                }
+            }
+            rest = rest.trim_start();
+            match rest.chars().next()? {
+                ',' => rest = rest[1..].trim_start(),
+                ']' | ')' => return Some((WktNode { keyword, values }, &rest[1..])),
+                _ => return None,
+            }
+        }
+    }
+
+    fn children(&self) -> impl Iterator<Item = &WktNode> {
+        self.values.iter().filter_map(|value| match value {
+            WktValue::Node(node) => Some(node),
+            _ => None,
+        })
+    }
+
+    fn child(&self, keyword: &str) -> Option<&WktNode> {
+        self.children().find(|node| node.keyword == keyword)
+    }
+
+    fn text(&self, index: usize) -> Option<&str> {
+        match self.values.get(index)? {
+            WktValue::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn number(&self, index: usize) -> Option<f64> {
+        match self.values.get(index)? {
+            WktValue::Number(value) => Some(*value),
+            WktValue::Text(text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Projection, ProjectionMethod};
+    use crate::utils::{deg_to_utm, utm_to_deg};
+
+    fn assert_close(actual: (f64, f64), expected: (f64, f64), tolerance: f64) {
+        assert!(
+            (actual.0 - expected.0).abs() < tolerance && (actual.1 - expected.1).abs() < tolerance,
+            "{:?} != {:?}",
+            actual,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_transverse_mercator() {
+        // The British National Grid example of EPSG Guidance Note 7-2.
+        let bng = Projection::from_epsg(27700).unwrap();
+        assert_eq!(bng.method, ProjectionMethod::TransverseMercator);
+        assert_close(bng.forward(0.5, 50.5).unwrap(), (577274.99, 69740.49), 0.01);
+
+        // UTM zone 11N agrees with deg_to_utm, also across the zone boundary.
+        let utm = Projection::from_epsg(32611).unwrap();
+        let (x, y, _, _) = deg_to_utm(46.73, -117.01);
+        assert_close(utm.forward(-117.01, 46.73).unwrap(), (x, y), 0.05);
+        let (x, y, _, _) = deg_to_utm(-33.45, -70.66);
+        assert_close(
+            Projection::from_epsg(32719)
+                .unwrap()
+                .forward(-70.66, -33.45)
+                .unwrap(),
+            (x, y),
+            0.05,
+        );
+        let east_of_zone = utm.forward(-113.5, 45.0).unwrap();
+        assert!(east_of_zone.0 > 760000.0 && east_of_zone.0 < 780000.0);
+    }
+
+    #[test]
+    fn test_conic_projections() {
+        // The Lambert Conformal Conic (2SP) example of EPSG Guidance Note 7-2, in US survey
+        // feet: NAD27 / Texas South Central.
+        let lcc = Projection::from_epsg(32040).unwrap();
+        assert_eq!(lcc.method, ProjectionMethod::LambertConformalConic);
+        assert_close(
+            lcc.forward(-96.0, 28.5).unwrap(),
+            (2963503.91, 254759.80),
+            0.01,
+        );
+
+        // The Albers example of Snyder (1987), p. 292.
+        let albers = Projection::from_epsg(5069).unwrap();
+        assert_eq!(albers.method, ProjectionMethod::Albers);
+        assert_close(
+            albers.forward(-75.0, 35.0).unwrap(),
+            (1885472.7, 1535925.0),
+            0.1,
+        );
+        assert_close(albers.forward(-96.0, 23.0).unwrap(), (0.0, 0.0), 1e-6);
+    }
+
+    #[test]
+    fn test_inverse() {
+        // The inverse recovers the longitude/latitude of each supported method, including a
+        // southern UTM zone, a conic projection in US survey feet, and a Mercator with a
+        // standard parallel, at locations within each system's area of use.
+        let systems = [
+            (32611, (-117.0, 46.0)),
+            (32719, (-69.0, -33.0)),
+            (27700, (-2.0, 53.0)),
+            (32040, (-99.0, 29.0)),
+            (5069, (-96.0, 38.0)),
+            (3395, (0.0, 0.0)),
+            (3857, (0.0, 0.0)),
+        ];
+        for (epsg, (lon0, lat0)) in systems {
+            let projection = Projection::from_epsg(epsg).unwrap();
+            for (dlon, dlat) in [(0.0, 0.0), (-2.5, 1.5), (2.9, -2.0), (1.0, 3.0)] {
+                let (lon, lat) = (lon0 + dlon, lat0 + dlat);
+                let (x, y) = projection.forward(lon, lat).unwrap();
+                let (lon2, lat2) = projection.inverse(x, y).unwrap();
+                assert!(
+                    (lon - lon2).abs() < 1e-7 && (lat - lat2).abs() < 1e-7,
+                    "EPSG:{} ({}, {}) != ({}, {})",
+                    epsg,
+                    lon,
+                    lat,
+                    lon2,
+                    lat2
+                );
+            }
+        }
+
+        // UTM zone 11N agrees with utm_to_deg.
+        let (x, y, _, _) = deg_to_utm(46.73, -117.01);
+        let (lat, lon) = utm_to_deg(11, 'N', x, y);
+        assert_close(
+            Projection::from_epsg(32611).unwrap().inverse(x, y).unwrap(),
+            (lon, lat),
+            1e-6,
+        );
+        assert_close(
+            Projection::from_epsg(5069)
+                .unwrap()
+                .inverse(0.0, 0.0)
+                .unwrap(),
+            (-96.0, 23.0),
+            1e-9,
+        );
+        assert_eq!(
+            Projection::from_epsg(4326).unwrap().inverse(-117.0, 46.5),
+            Some((-117.0, 46.5))
+        );
+        assert_eq!(
+            Projection::from_epsg(3857).unwrap().inverse(f64::NAN, 0.0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_from_wkt() {
+        // OGC WKT with lower-case parameter names, an AXIS, and authorities.
+        let ogc =
+            "PROJCS[\"NAD83 / Conus Albers\",GEOGCS[\"NAD83\",DATUM[\"North_American_Datum_1983\",\
+            SPHEROID[\"GRS 1980\",6378137,298.257222101,AUTHORITY[\"EPSG\",\"7019\"]]],\
+            PRIMEM[\"Greenwich\",0],UNIT[\"degree\",0.0174532925199433]],\
+            PROJECTION[\"Albers_Conic_Equal_Area\"],PARAMETER[\"standard_parallel_1\",29.5],\
+            PARAMETER[\"standard_parallel_2\",45.5],PARAMETER[\"latitude_of_center\",23],\
+            PARAMETER[\"longitude_of_center\",-96],PARAMETER[\"false_easting\",0],\
+            PARAMETER[\"false_northing\",0],UNIT[\"metre\",1],AXIS[\"Easting\",EAST],\
+            AXIS[\"Northing\",NORTH],AUTHORITY[\"EPSG\",\"5070\"]]";
+        let projection = Projection::from_wkt(ogc).unwrap();
+        assert_eq!(projection, Projection::from_epsg(5070).unwrap());
+
+        let web = Projection::from_epsg(3857).unwrap();
+        assert_close(
+            web.forward(-180.0, 0.0).unwrap(),
+            (-20037508.342789244, 0.0),
+            1e-6,
+        );
+        assert_eq!(web.forward(0.0, 90.0), None);
+
+        assert_eq!(
+            Projection::from_epsg(4326).unwrap().forward(-117.0, 46.5),
+            Some((-117.0, 46.5))
+        );
+        assert_eq!(Projection::from_epsg(3035), None); // Lambert Azimuthal Equal Area
+        assert_eq!(Projection::from_epsg(1), None);
+        assert_eq!(Projection::from_wkt(""), None);
+        assert_eq!(Projection::from_wkt("PROJCS[\"unterminated\""), None);
+    }
+}
@@ -0,0 +1,129 @@
+/// Identifies the EPSG code of a coordinate reference system from its WKT description,
+/// e.g. the contents of a Shapefile's .prj file.
+///
+/// The EPSG authority of the root `PROJCS`/`GEOGCS` element is used when present (OGC WKT
+/// and the WKT produced by `esri_wkt_from_epsg`). ESRI-flavoured WKT usually lacks an
+/// authority, in which case the root element's name is matched against the common
+/// WGS84 geographic, WGS84 UTM, and NAD83 UTM systems. Returns `None` if the code cannot
+/// be determined.
+pub fn epsg_from_wkt(wkt: &str) -> Option<u16> {
+    let wkt = wkt.trim();
+    if wkt.is_empty() {
+        return None;
+    }
+    if let Some(code) = root_authority(wkt) {
+        return Some(code);
+    }
+    epsg_from_name(wkt)
+}
+
+/// Finds an `AUTHORITY["EPSG", code]` that is a direct child of the root element.
+fn root_authority(wkt: &str) -> Option<u16> {
+    let bytes = wkt.as_bytes();
+    let mut depth = 0i32;
+    let mut in_quotes = false;
+    let mut code = None;
+    let mut i = 0usize;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'"' => in_quotes = !in_quotes,
+            b'[' | b'(' if !in_quotes => depth += 1,
+            b']' | b')' if !in_quotes => depth -= 1,
+            _ if !in_quotes && depth == 1 && wkt[i..].starts_with("AUTHORITY[") => {
+                let start = i + "AUTHORITY[".len();
+                if let Some(len) = wkt[start..].find(']') {
+                    let parts: Vec<&str> = wkt[start..start + len]
+                        .split(',')
+                        .map(|s| s.trim().trim_matches('"').trim())
+                        .collect();
+                    if parts.len() == 2 && parts[0].eq_ignore_ascii_case("EPSG") {
+                        code = parts[1].parse::<u16>().ok();
+                    }
+                    i = start + len;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    code
+}
+
+fn epsg_from_name(wkt: &str) -> Option<u16> {
+    let open = wkt.find('[')?;
+    let keyword = wkt[..open].trim().to_uppercase();
+    let rest = &wkt[open + 1..];
+    let name = rest.trim_start().strip_prefix('"')?;
+    let name = &name[..name.find('"')?];
+
+    // Normalize e.g. "WGS 84 / UTM zone 11N" and "WGS_1984_UTM_Zone_11N" to
+    // "WGS_84_UTM_ZONE_11N" and "WGS_1984_UTM_ZONE_11N".
+    let mut normalized = String::new();
+    for c in name.to_uppercase().chars() {
+        if c.is_ascii_alphanumeric() {
+            normalized.push(c);
+        } else if !normalized.ends_with('_') {
+            normalized.push('_');
+        }
+    }
+    let normalized = normalized.trim_matches('_');
+
+    match keyword.as_str() {
+        "GEOGCS" => match normalized {
+            "GCS_WGS_1984" | "WGS_84" | "WGS84" | "WGS_1984" => Some(4326),
+            _ => None,
+        },
+        "PROJCS" => {
+            let (datum, zone) = normalized.split_once("_UTM_ZONE_")?;
+            let (hemisphere, digits) = match zone.chars().last()? {
+                'N' => ('N', &zone[..zone.len() - 1]),
+                'S' => ('S', &zone[..zone.len() - 1]),
+                _ => return None,
+            };
+            let zone = digits.parse::<u16>().ok()?;
+            match (datum, hemisphere) {
+                ("WGS_1984" | "WGS_84" | "WGS84", 'N') if (1..=60).contains(&zone) => {
+                    Some(32600 + zone)
+                }
+                ("WGS_1984" | "WGS_84" | "WGS84", 'S') if (1..=60).contains(&zone) => {
+                    Some(32700 + zone)
+                }
+                ("NAD_1983" | "NAD83", 'N') if (1..=23).contains(&zone) => Some(26900 + zone),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::epsg_from_wkt;
+    use crate::spatial_ref_system::esri_wkt_from_epsg;
+
+    #[test]
+    fn test_epsg_from_wkt() {
+        let ogc = "PROJCS[\"WGS 84 / UTM zone 11N\",GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",\
+            SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],\
+            AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0],UNIT[\"degree\",0.0174532925199433],\
+            AUTHORITY[\"EPSG\",\"4326\"]],PROJECTION[\"Transverse_Mercator\"],\
+            PARAMETER[\"central_meridian\",-117],UNIT[\"metre\",1],AUTHORITY[\"EPSG\",\"32611\"]]";
+        assert_eq!(epsg_from_wkt(ogc), Some(32611));
+        assert_eq!(epsg_from_wkt(&esri_wkt_from_epsg(26911)), Some(26911));
+        assert_eq!(epsg_from_wkt(&esri_wkt_from_epsg(4326)), Some(4326));
+
+        // ESRI .prj files carry no authority; the system is recognized by name.
+        let esri = "PROJCS[\"WGS_1984_UTM_Zone_19S\",GEOGCS[\"GCS_WGS_1984\",\
+            DATUM[\"D_WGS_1984\",SPHEROID[\"WGS_1984\",6378137.0,298.257223563]],\
+            PRIMEM[\"Greenwich\",0.0],UNIT[\"Degree\",0.0174532925199433]],\
+            PROJECTION[\"Transverse_Mercator\"],UNIT[\"Meter\",1.0]]";
+        assert_eq!(epsg_from_wkt(esri), Some(32719));
+
+        // The authority of a nested GEOGCS must not be mistaken for that of the projection.
+        let albers = "PROJCS[\"NAD83 / Conus Albers\",GEOGCS[\"NAD83\",AUTHORITY[\"EPSG\",\"4269\"]],\
+            PROJECTION[\"Albers_Conic_Equal_Area\"],UNIT[\"metre\",1]]";
+        assert_eq!(epsg_from_wkt(albers), None);
+        assert_eq!(epsg_from_wkt(""), None);
+    }
+}
@@ -170,6 +170,111 @@ where
         self.data = vec![value; (self.rows * self.columns) as usize];
     }
 
+    /// Sets every cell to `value` in place, without reallocating the underlying storage.
+    pub fn fill(&mut self, value: T) {
+        self.data.fill(value);
+    }
+
+    /// Sets to `value` each cell for which `predicate` holds on the corresponding cell of
+    /// `other`, returning the number of cells that were set. This is equivalent to a
+    /// `get_value`/`set_value` loop over every row and column, but works directly on the
+    /// underlying storage and so avoids the per-cell bounds checks.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use whitebox_common::structures::Array2D;
+    /// # fn main() -> Result<(), std::io::Error> {
+    /// # let (rows, columns) = (2isize, 3isize);
+    /// let mut watershed: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+    /// watershed.set_value(1, 2, 1u8);
+    /// let mut mask: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+    /// let num_cells = mask.fill_where(&watershed, |v| v == 1u8, 1u8)?;
+    /// assert_eq!(num_cells, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn fill_where<U, F>(
+        &mut self,
+        other: &Array2D<U>,
+        predicate: F,
+        value: T,
+    ) -> Result<usize, Error>
+    where
+        U: Copy + AddAssign + SubAssign,
+        F: Fn(U) -> bool,
+    {
+        if self.rows != other.rows || self.columns != other.columns {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "Arrays must have the same dimensions.",
+            ));
+        }
+        self.fill_where_values(&other.data, predicate, value)
+    }
+
+    /// Sets to `value` each cell for which `predicate` holds on the corresponding element of
+    /// `values`, which are in row-major order, returning the number of cells that were set.
+    /// This is the storage-level counterpart of `fill_where` for grids that are not held in an
+    /// Array2D, e.g. the data of a Raster.
+    pub fn fill_where_values<U, F>(
+        &mut self,
+        values: &[U],
+        predicate: F,
+        value: T,
+    ) -> Result<usize, Error>
+    where
+        U: Copy,
+        F: Fn(U) -> bool,
+    {
+        if values.len() != self.data.len() {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "The number of values must equal the number of cells in the array.",
+            ));
+        }
+        let mut num_set = 0usize;
+        for (cell, &v) in self.data.iter_mut().zip(values) {
+            if predicate(v) {
+                *cell = value;
+                num_set += 1;
+            }
+        }
+        Ok(num_set)
+    }
+
+    /// Creates a new Array2D from a grid of row-major `values`, in which each cell is `value`
+    /// where `predicate` holds on the corresponding element and `nodata` elsewhere.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use whitebox_common::structures::Array2D;
+    /// # fn main() -> Result<(), std::io::Error> {
+    /// let (rows, columns) = (2isize, 2isize);
+    /// let values = [0f64, 2.5, -1.0, 4.0];
+    /// let mask: Array2D<u8> = Array2D::from_threshold(rows, columns, &values, |v| v > 0f64, 1u8, 0u8)?;
+    /// assert_eq!(mask.get_value(0, 1), 1u8);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_threshold<U, F>(
+        rows: isize,
+        columns: isize,
+        values: &[U],
+        predicate: F,
+        value: T,
+        nodata: T,
+    ) -> Result<Array2D<T>, Error>
+    where
+        U: Copy,
+        F: Fn(U) -> bool,
+    {
+        let mut array = Array2D::new(rows, columns, nodata, nodata)?;
+        array.fill_where_values(values, predicate, value)?;
+        Ok(array)
+    }
+
     pub fn columns(&self) -> isize {
         self.columns
     }
@@ -232,3 +337,124 @@ where
         &mut self.data[idx as usize]
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::Array2D;
+    use std::time::Instant;
+
+    fn source(rows: isize, columns: isize) -> Array2D<f64> {
+        let mut values: Array2D<f64> = Array2D::new(rows, columns, -9999f64, -9999f64).unwrap();
+        for row in 0..rows {
+            for col in 0..columns {
+                if (row * 7 + col * 3) % 5 != 0 {
+                    values.set_value(row, col, ((row * 31 + col * 17) % 11) as f64 - 3f64);
+                }
+            }
+        }
+        values
+    }
+
+    fn fill_where_by_loop(out: &mut Array2D<u8>, values: &Array2D<f64>, value: u8) -> usize {
+        let mut num_set = 0;
+        for row in 0..out.rows {
+            for col in 0..out.columns {
+                let v = values.get_value(row, col);
+                if v != -9999f64 && v > 0f64 {
+                    out.set_value(row, col, value);
+                    num_set += 1;
+                }
+            }
+        }
+        num_set
+    }
+
+    #[test]
+    fn test_fill() {
+        let mut a: Array2D<i32> = Array2D::new(4, 6, 0, -1).unwrap();
+        a.set_value(2, 3, 5);
+        a.fill(-1);
+        let b: Array2D<i32> = Array2D::new(4, 6, -1, -1).unwrap();
+        for row in 0..4 {
+            assert_eq!(a.get_row_data(row), b.get_row_data(row));
+        }
+    }
+
+    #[test]
+    fn test_fill_where_matches_loop() {
+        let values = source(37, 23);
+        let mut expected: Array2D<u8> = Array2D::new(37, 23, 0u8, 0u8).unwrap();
+        let expected_set = fill_where_by_loop(&mut expected, &values, 1u8);
+        let mut actual: Array2D<u8> = Array2D::new(37, 23, 0u8, 0u8).unwrap();
+        let actual_set = actual
+            .fill_where(&values, |v| v != -9999f64 && v > 0f64, 1u8)
+            .unwrap();
+        assert_eq!(actual_set, expected_set);
+        assert!(actual_set > 0);
+        for row in 0..37 {
+            assert_eq!(actual.get_row_data(row), expected.get_row_data(row));
+        }
+
+        let threshold: Array2D<u8> = Array2D::from_threshold(
+            37,
+            23,
+            &values.data,
+            |v| v != -9999f64 && v > 0f64,
+            1u8,
+            0u8,
+        )
+        .unwrap();
+        for row in 0..37 {
+            assert_eq!(threshold.get_row_data(row), expected.get_row_data(row));
+        }
+
+        let mut wrong_size: Array2D<u8> = Array2D::new(37, 22, 0u8, 0u8).unwrap();
+        assert!(wrong_size.fill_where(&values, |v| v > 0f64, 1u8).is_err());
+        assert!(Array2D::from_threshold(2, 2, &[1f64; 3], |v| v > 0f64, 1u8, 0u8).is_err());
+    }
+
+    /// A timing comparison of building a watershed mask on a 20k x 20k grid; run with
+    /// `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_fill_where_large_grid() {
+        let (rows, columns) = (20_000isize, 20_000isize);
+        let mut watershed: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8).unwrap();
+        let mut row_data = vec![0u8; columns as usize];
+        for value in &mut row_data[(columns / 4) as usize..(3 * columns / 4) as usize] {
+            *value = 1u8;
+        }
+        for row in rows / 4..3 * rows / 4 {
+            watershed.set_row_data(row, row_data.clone());
+        }
+
+        let start = Instant::now();
+        let mut looped: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8).unwrap();
+        let mut looped_set = 0usize;
+        for row in 0..rows {
+            for col in 0..columns {
+                if watershed.get_value(row, col) == 1u8 {
+                    looped.set_value(row, col, 1u8);
+                    looped_set += 1;
+                }
+            }
+        }
+        let loop_time = start.elapsed();
+
+        let start = Instant::now();
+        let mut filled: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8).unwrap();
+        let filled_set = filled.fill_where(&watershed, |v| v == 1u8, 1u8).unwrap();
+        let fill_time = start.elapsed();
+
+        assert_eq!(filled_set, looped_set);
+        assert!(filled.data == looped.data);
+        println!(
+            "{}x{} grid: set_value loop {:?}, fill_where {:?} ({:.1}x)",
+            rows,
+            columns,
+            loop_time,
+            fill_time,
+            loop_time.as_secs_f64() / fill_time.as_secs_f64()
+        );
+    }
+}
@@ -7,6 +7,7 @@ mod line_segment;
 mod n_maximizer;
 mod n_minimizer;
 mod point2d;
+mod point_grid_index;
 mod point3d;
 mod polyline;
 mod polynomial_regression_2d;
@@ -23,6 +24,7 @@ pub use self::n_maximizer::NMaximizer;
 pub use self::n_minimizer::NMinimizer;
 pub use self::point2d::Direction;
 pub use self::point2d::Point2D;
+pub use self::point_grid_index::PointGridIndex;
 pub use self::point3d::Point3D;
 pub use self::polyline::MultiPolyline;
 pub use self::polyline::Polyline;
@@ -0,0 +1,523 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::{BoundingBox, Point2D};
+use std::f64;
+
+/// The largest number of grid cells allocated per indexed point, which bounds the memory used
+/// when a small cell size is requested for a widely spread point set.
+const MAX_CELLS_PER_POINT: usize = 4;
+
+/// A static grid-bucket spatial index over a collection of `Point2D`s, supporting
+/// nearest-neighbour, within-radius, and point-in-window queries. Results are reported as
+/// indices into the point slice the index was built from.
+///
+/// Unlike `FixedRadiusSearch2D`, the index is built once from a complete point set and its
+/// queries are not tied to a fixed search radius. Points are bucketed into a regular grid
+/// whose cells are stored contiguously, so queries visit only the cells near the query
+/// location rather than scanning every point. Points with non-finite coordinates are kept
+/// (so that indices still line up with the input) but are never returned by a query.
+///
+/// ## Example
+///     let points = vec![
+///         Point2D::new(45.3, 32.5),
+///         Point2D::new(25.3, 65.5),
+///         Point2D::new(42.3, 35.5),
+///     ];
+///     let index = PointGridIndex::new(&points);
+///
+///     let (nearest, dist) = index.nearest(&Point2D::new(41.0, 35.0)).unwrap();
+///     println!("{} {}", nearest, dist); // 2 1.392...
+///
+///     let close = index.within_radius(&Point2D::new(44.0, 34.0), 3.0);
+///     println!("{:?}", close); // [0, 2]
+pub struct PointGridIndex {
+    points: Vec<Point2D>,
+    min_x: f64,
+    min_y: f64,
+    cell_size: f64,
+    columns: isize,
+    rows: isize,
+    /// Offsets into `entries` of the first point of each cell (row-major), plus a final end offset.
+    cell_start: Vec<usize>,
+    entries: Vec<usize>,
+}
+
+impl PointGridIndex {
+    /// Builds an index over `points`, choosing a cell size that places about two points in each
+    /// cell of the points' bounding box.
+    pub fn new(points: &[Point2D]) -> PointGridIndex {
+        let (extent, count) = finite_extent(points);
+        let cell_size = if count > 0 {
+            let area = extent.get_width().max(f64::EPSILON) * extent.get_height().max(f64::EPSILON);
+            (2f64 * area / count as f64).sqrt()
+        } else {
+            1f64
+        };
+        PointGridIndex::with_cell_size(points, cell_size)
+    }
+
+    /// Builds an index over `points` using grid cells of the specified size. A cell size near the
+    /// typical query radius works well for within-radius queries. The cell size is enlarged if
+    /// necessary to limit the grid to a few cells per point.
+    pub fn with_cell_size(points: &[Point2D], cell_size: f64) -> PointGridIndex {
+        let (extent, count) = finite_extent(points);
+        let mut cell_size = if cell_size.is_finite() && cell_size > 0f64 {
+            cell_size
+        } else {
+            1f64
+        };
+        let (min_x, min_y) = if count > 0 {
+            (extent.min_x, extent.min_y)
+        } else {
+            (0f64, 0f64)
+        };
+        let max_cells = (count * MAX_CELLS_PER_POINT).max(1) as f64;
+        let dims = |cell_size: f64| {
+            if count == 0 {
+                return (1f64, 1f64);
+            }
+            (
+                (extent.get_width() / cell_size).floor() + 1f64,
+                (extent.get_height() / cell_size).floor() + 1f64,
+            )
+        };
+        let (mut columns, mut rows) = dims(cell_size);
+        while columns * rows > max_cells {
+            cell_size *= ((columns * rows) / max_cells).sqrt().max(1.1);
+            let d = dims(cell_size);
+            columns = d.0;
+            rows = d.1;
+        }
+        let (columns, rows) = (columns as isize, rows as isize);
+
+        let mut index = PointGridIndex {
+            points: points.to_vec(),
+            min_x,
+            min_y,
+            cell_size,
+            columns,
+            rows,
+            cell_start: vec![0usize; (columns * rows) as usize + 1],
+            entries: Vec::with_capacity(count),
+        };
+
+        // Counting sort of the points into their cells.
+        let cells: Vec<Option<usize>> = points
+            .iter()
+            .map(|p| {
+                if p.x.is_finite() && p.y.is_finite() {
+                    let (col, row) = index.cell_of(p);
+                    Some(index.cell_number(col, row))
+                } else {
+                    None
+                }
+            })
+            .collect();
+        for cell in cells.iter().flatten() {
+            index.cell_start[cell + 1] += 1;
+        }
+        for i in 1..index.cell_start.len() {
+            index.cell_start[i] += index.cell_start[i - 1];
+        }
+        let mut next = index.cell_start.clone();
+        index.entries = vec![0usize; count];
+        for (i, cell) in cells.iter().enumerate() {
+            if let Some(cell) = cell {
+                index.entries[next[*cell]] = i;
+                next[*cell] += 1;
+            }
+        }
+        index
+    }
+
+    /// Returns the number of points in the index, including any with non-finite coordinates.
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Returns true if the index was built from an empty point set.
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Returns the indexed point with the specified index.
+    pub fn get_point(&self, index: usize) -> Point2D {
+        self.points[index]
+    }
+
+    /// Returns the grid cell size.
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    /// Returns the index of, and distance to, the point nearest to `p`, or None if the index
+    /// holds no finite points. Ties are resolved in favour of the lowest index.
+    pub fn nearest(&self, p: &Point2D) -> Option<(usize, f64)> {
+        if self.entries.is_empty() || !p.x.is_finite() || !p.y.is_finite() {
+            return None;
+        }
+        let (col, row) = self.cell_of(p);
+
+        // Search rings of cells of increasing Chebyshev distance around the query cell, starting
+        // with the first ring that overlaps the grid. Every cell in ring r is at least (r - 1)
+        // cells from the query point, so the search stops once that bound exceeds the best match.
+        let outside = |v: isize, n: isize| {
+            if v < 0 {
+                -v
+            } else if v >= n {
+                v - n + 1
+            } else {
+                0
+            }
+        };
+        let first_ring = outside(col, self.columns).max(outside(row, self.rows));
+        let last_ring = col
+            .max(self.columns - 1 - col)
+            .max(row)
+            .max(self.rows - 1 - row);
+
+        let mut best: Option<(usize, f64)> = None;
+        for ring in first_ring..=last_ring {
+            if let Some((_, best_d2)) = best {
+                let bound = (ring - 1) as f64 * self.cell_size;
+                if ring > 0 && bound * bound > best_d2 {
+                    break;
+                }
+            }
+            let r0 = (row - ring).max(0);
+            let r1 = (row + ring).min(self.rows - 1);
+            let c0 = (col - ring).max(0);
+            let c1 = (col + ring).min(self.columns - 1);
+            for r in r0..=r1 {
+                if r == row - ring || r == row + ring {
+                    for c in c0..=c1 {
+                        self.nearest_in_cell(c, r, p, &mut best);
+                    }
+                } else {
+                    // Interior rows of a ring only contribute their two end cells.
+                    if col - ring >= 0 {
+                        self.nearest_in_cell(col - ring, r, p, &mut best);
+                    }
+                    if col + ring < self.columns {
+                        self.nearest_in_cell(col + ring, r, p, &mut best);
+                    }
+                }
+            }
+        }
+        best.map(|(i, d2)| (i, d2.sqrt()))
+    }
+
+    /// Updates `best` (index, squared distance) with the points in a cell nearer to `p`.
+    fn nearest_in_cell(
+        &self,
+        col: isize,
+        row: isize,
+        p: &Point2D,
+        best: &mut Option<(usize, f64)>,
+    ) {
+        for &i in self.cell_entries(col, row) {
+            let d2 = self.points[i].distance_squared(p);
+            let better = match *best {
+                None => true,
+                Some((bi, bd2)) => d2 < bd2 || (d2 == bd2 && i < bi),
+            };
+            if better {
+                *best = Some((i, d2));
+            }
+        }
+    }
+
+    /// Returns the indices, in ascending order, of all points within `radius` of `p` (inclusive).
+    pub fn within_radius(&self, p: &Point2D, radius: f64) -> Vec<usize> {
+        let mut ret = vec![];
+        if self.entries.is_empty()
+            || radius.is_nan()
+            || radius < 0f64
+            || !p.x.is_finite()
+            || !p.y.is_finite()
+        {
+            return ret;
+        }
+        let r2 = radius * radius;
+        let window = BoundingBox::new(p.x - radius, p.x + radius, p.y - radius, p.y + radius);
+        self.visit_window(&window, |i| {
+            if self.points[i].distance_squared(p) <= r2 {
+                ret.push(i);
+            }
+        });
+        ret.sort_unstable();
+        ret
+    }
+
+    /// Returns the indices, in ascending order, of all points within the window, including
+    /// points lying on its edges.
+    pub fn within_window(&self, window: &BoundingBox) -> Vec<usize> {
+        let mut ret = vec![];
+        self.visit_window(window, |i| {
+            let pt = &self.points[i];
+            if pt.x >= window.min_x
+                && pt.x <= window.max_x
+                && pt.y >= window.min_y
+                && pt.y <= window.max_y
+            {
+                ret.push(i);
+            }
+        });
+        ret.sort_unstable();
+        ret
+    }
+
+    /// Calls `f` with the index of each point in the grid cells overlapping the window.
+    fn visit_window<F: FnMut(usize)>(&self, window: &BoundingBox, mut f: F) {
+        if self.entries.is_empty()
+            || window.min_x.is_nan()
+            || window.min_y.is_nan()
+            || window.min_x > window.max_x
+            || window.min_y > window.max_y
+        {
+            return;
+        }
+        let (c0, r0) = self.cell_of(&Point2D::new(window.min_x, window.min_y));
+        let (c1, r1) = self.cell_of(&Point2D::new(window.max_x, window.max_y));
+        if c1 < 0 || r1 < 0 || c0 >= self.columns || r0 >= self.rows {
+            return;
+        }
+        for r in r0.max(0)..=r1.min(self.rows - 1) {
+            for c in c0.max(0)..=c1.min(self.columns - 1) {
+                for &i in self.cell_entries(c, r) {
+                    f(i);
+                }
+            }
+        }
+    }
+
+    /// Returns the (unclamped) grid column and row containing a location.
+    fn cell_of(&self, p: &Point2D) -> (isize, isize) {
+        let col = ((p.x - self.min_x) / self.cell_size).floor();
+        let row = ((p.y - self.min_y) / self.cell_size).floor();
+        // Keep far-away locations well within isize while preserving their side of the grid.
+        let limit = (self.columns.max(self.rows) * 2 + 2) as f64;
+        (
+            col.max(-limit).min(limit) as isize,
+            row.max(-limit).min(limit) as isize,
+        )
+    }
+
+    fn cell_number(&self, col: isize, row: isize) -> usize {
+        let col = col.max(0).min(self.columns - 1);
+        let row = row.max(0).min(self.rows - 1);
+        (row * self.columns + col) as usize
+    }
+
+    fn cell_entries(&self, col: isize, row: isize) -> &[usize] {
+        let cell = self.cell_number(col, row);
+        &self.entries[self.cell_start[cell]..self.cell_start[cell + 1]]
+    }
+}
+
+/// Returns the bounding box of the points with finite coordinates and the number of such points.
+fn finite_extent(points: &[Point2D]) -> (BoundingBox, usize) {
+    let mut extent = BoundingBox {
+        min_x: f64::INFINITY,
+        min_y: f64::INFINITY,
+        max_x: f64::NEG_INFINITY,
+        max_y: f64::NEG_INFINITY,
+    };
+    let mut count = 0;
+    for p in points.iter().filter(|p| p.x.is_finite() && p.y.is_finite()) {
+        extent.min_x = extent.min_x.min(p.x);
+        extent.max_x = extent.max_x.max(p.x);
+        extent.min_y = extent.min_y.min(p.y);
+        extent.max_y = extent.max_y.max(p.y);
+        count += 1;
+    }
+    (extent, count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::PointGridIndex;
+    use crate::structures::{BoundingBox, Point2D};
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use std::time::Instant;
+
+    fn random_points(rng: &mut SmallRng, n: usize, max: f64) -> Vec<Point2D> {
+        (0..n)
+            .map(|_| Point2D::new(rng.gen_range(0f64, max), rng.gen_range(0f64, max)))
+            .collect()
+    }
+
+    fn brute_nearest(points: &[Point2D], p: &Point2D) -> Option<(usize, f64)> {
+        let mut best: Option<(usize, f64)> = None;
+        for (i, pt) in points.iter().enumerate() {
+            let d2 = pt.distance_squared(p);
+            match best {
+                Some((_, bd2)) if d2 >= bd2 => {}
+                _ => best = Some((i, d2)),
+            }
+        }
+        best.map(|(i, d2)| (i, d2.sqrt()))
+    }
+
+    fn brute_within_radius(points: &[Point2D], p: &Point2D, radius: f64) -> Vec<usize> {
+        (0..points.len())
+            .filter(|&i| points[i].distance_squared(p) <= radius * radius)
+            .collect()
+    }
+
+    #[test]
+    fn test_nearest_matches_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(42);
+        for &(n, cell_size) in &[
+            (1usize, 0f64),
+            (7, 0f64),
+            (500, 0f64),
+            (2000, 0.5),
+            (2000, 250.0),
+        ] {
+            let points = random_points(&mut rng, n, 1000.0);
+            let index = if cell_size > 0.0 {
+                PointGridIndex::with_cell_size(&points, cell_size)
+            } else {
+                PointGridIndex::new(&points)
+            };
+            // Queries both inside and well outside the points' extent.
+            for _ in 0..500 {
+                let q = Point2D::new(
+                    rng.gen_range(-500f64, 1500.0),
+                    rng.gen_range(-500f64, 1500.0),
+                );
+                assert_eq!(index.nearest(&q), brute_nearest(&points, &q));
+            }
+        }
+    }
+
+    #[test]
+    fn test_within_radius_and_window_match_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(7);
+        let mut points = random_points(&mut rng, 3000, 100.0);
+        // A tight cluster and exact duplicates.
+        for _ in 0..200 {
+            points.push(Point2D::new(
+                50.0 + rng.gen_range(-0.01f64, 0.01),
+                50.0 + rng.gen_range(-0.01f64, 0.01),
+            ));
+        }
+        points.push(points[0]);
+        points.push(points[0]);
+        let index = PointGridIndex::new(&points);
+        for _ in 0..300 {
+            let q = Point2D::new(rng.gen_range(-10f64, 110.0), rng.gen_range(-10f64, 110.0));
+            let radius = rng.gen_range(0f64, 15.0);
+            assert_eq!(
+                index.within_radius(&q, radius),
+                brute_within_radius(&points, &q, radius)
+            );
+
+            let (x0, x1) = (rng.gen_range(-10f64, 110.0), rng.gen_range(-10f64, 110.0));
+            let (y0, y1) = (rng.gen_range(-10f64, 110.0), rng.gen_range(-10f64, 110.0));
+            let window = BoundingBox::new(x0.min(x1), x0.max(x1), y0.min(y1), y0.max(y1));
+            let expected: Vec<usize> = (0..points.len())
+                .filter(|&i| {
+                    let p = &points[i];
+                    p.x >= window.min_x
+                        && p.x <= window.max_x
+                        && p.y >= window.min_y
+                        && p.y <= window.max_y
+                })
+                .collect();
+            assert_eq!(index.within_window(&window), expected);
+        }
+        assert!(index.within_radius(&points[0], 0.0).len() >= 3);
+        assert_eq!(index.nearest(&points[0]).unwrap(), (0, 0.0));
+    }
+
+    #[test]
+    fn test_degenerate_inputs() {
+        let empty = PointGridIndex::new(&[]);
+        assert!(empty.is_empty());
+        assert_eq!(empty.nearest(&Point2D::new(0.0, 0.0)), None);
+        assert!(empty
+            .within_radius(&Point2D::new(0.0, 0.0), 10.0)
+            .is_empty());
+
+        // Collinear and coincident points give a zero-area extent.
+        let points = vec![
+            Point2D::new(5.0, 5.0),
+            Point2D::new(f64::NAN, 5.0),
+            Point2D::new(5.0, 5.0),
+            Point2D::new(5.0, 9.0),
+        ];
+        let index = PointGridIndex::new(&points);
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.nearest(&Point2D::new(5.0, 8.0)), Some((3, 1.0)));
+        assert_eq!(index.nearest(&Point2D::new(-1e12, 1e12)).unwrap().0, 3);
+        assert_eq!(
+            index.within_radius(&Point2D::new(5.0, 5.0), 1.0),
+            vec![0, 2]
+        );
+        assert_eq!(
+            index.within_window(&BoundingBox::new(0.0, 10.0, 0.0, 10.0)),
+            vec![0, 2, 3]
+        );
+    }
+
+    /// Compares the index with a linear scan for 100,000 points. Run with
+    /// `cargo test --release -- --ignored --nocapture benchmark`.
+    #[test]
+    #[ignore]
+    fn benchmark_100k_points() {
+        let mut rng = SmallRng::seed_from_u64(1);
+        let points = random_points(&mut rng, 100_000, 10_000.0);
+        let queries = random_points(&mut rng, 2_000, 10_000.0);
+
+        let start = Instant::now();
+        let index = PointGridIndex::new(&points);
+        let build_time = start.elapsed();
+
+        let start = Instant::now();
+        let indexed: Vec<_> = queries.iter().map(|q| index.nearest(q)).collect();
+        let index_nn_time = start.elapsed();
+        let start = Instant::now();
+        let linear: Vec<_> = queries.iter().map(|q| brute_nearest(&points, q)).collect();
+        let linear_nn_time = start.elapsed();
+        assert_eq!(indexed, linear);
+
+        let start = Instant::now();
+        let indexed: Vec<_> = queries
+            .iter()
+            .map(|q| index.within_radius(q, 50.0))
+            .collect();
+        let index_radius_time = start.elapsed();
+        let start = Instant::now();
+        let linear: Vec<_> = queries
+            .iter()
+            .map(|q| brute_within_radius(&points, q, 50.0))
+            .collect();
+        let linear_radius_time = start.elapsed();
+        assert_eq!(indexed, linear);
+
+        println!("Build: {:?}", build_time);
+        println!(
+            "Nearest neighbour, {} queries: index {:?}, linear scan {:?}",
+            queries.len(),
+            index_nn_time,
+            linear_nn_time
+        );
+        println!(
+            "Within radius, {} queries: index {:?}, linear scan {:?}",
+            queries.len(),
+            index_radius_time,
+            linear_radius_time
+        );
+        assert!(index_nn_time < linear_nn_time);
+        assert!(index_radius_time < linear_radius_time);
+    }
+}
@@ -0,0 +1,129 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::fs;
+use std::io::{Error, ErrorKind};
+
+/// Creates (if needed) the directory `name` under the system temporary directory and returns
+/// its path, for the input and output files of a test. The name is suffixed with the process
+/// ID so that concurrent test runs do not share files.
+pub fn temp_dir(name: &str) -> Result<String, Error> {
+    let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+    fs::create_dir_all(&dir)?;
+    dir.to_str().map(|d| d.to_string()).ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            "The temporary directory is not valid UTF-8.",
+        )
+    })
+}
+
+/// The geometry and values of a small grid for a test, written as a raster with
+/// `whitebox_raster::test_support::grid_fixture_raster`. Grids default to 10 m cells with
+/// their south-west corner at the origin, a NoData value of -32768, and continuous values.
+#[derive(Clone, Debug)]
+pub struct GridFixture {
+    pub rows: usize,
+    pub columns: usize,
+    pub resolution: f64,
+    pub west: f64,
+    pub south: f64,
+    pub nodata: f64,
+    pub epsg_code: u16,
+    pub categorical: bool,
+    values: Vec<f64>,
+}
+
+impl GridFixture {
+    /// A grid of `rows` by `columns` cells whose value at (row, column) is `f(row, column)`.
+    pub fn new<F: Fn(isize, isize) -> f64>(rows: usize, columns: usize, f: F) -> GridFixture {
+        let mut values = Vec::with_capacity(rows * columns);
+        for row in 0..rows as isize {
+            for col in 0..columns as isize {
+                values.push(f(row, col));
+            }
+        }
+        GridFixture {
+            rows,
+            columns,
+            resolution: 10f64,
+            west: 0f64,
+            south: 0f64,
+            nodata: -32768f64,
+            epsg_code: 0,
+            categorical: false,
+            values,
+        }
+    }
+
+    pub fn with_resolution(mut self, resolution: f64) -> GridFixture {
+        self.resolution = resolution;
+        self
+    }
+
+    pub fn with_origin(mut self, west: f64, south: f64) -> GridFixture {
+        self.west = west;
+        self.south = south;
+        self
+    }
+
+    pub fn with_nodata(mut self, nodata: f64) -> GridFixture {
+        self.nodata = nodata;
+        self
+    }
+
+    pub fn with_epsg_code(mut self, epsg_code: u16) -> GridFixture {
+        self.epsg_code = epsg_code;
+        self
+    }
+
+    /// Marks the values as class labels (e.g. stream orders or land-use codes).
+    pub fn categorical(mut self) -> GridFixture {
+        self.categorical = true;
+        self
+    }
+
+    pub fn north(&self) -> f64 {
+        self.south + self.rows as f64 * self.resolution
+    }
+
+    pub fn east(&self) -> f64 {
+        self.west + self.columns as f64 * self.resolution
+    }
+
+    /// The value at (row, column); NoData outside the grid.
+    pub fn value(&self, row: isize, col: isize) -> f64 {
+        if row < 0 || col < 0 || row as usize >= self.rows || col as usize >= self.columns {
+            return self.nodata;
+        }
+        self.values[row as usize * self.columns + col as usize]
+    }
+
+    pub fn set_value(&mut self, row: isize, col: isize, value: f64) {
+        if row >= 0 && col >= 0 && (row as usize) < self.rows && (col as usize) < self.columns {
+            self.values[row as usize * self.columns + col as usize] = value;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::GridFixture;
+
+    #[test]
+    fn test_grid_fixture() {
+        let mut grid =
+            GridFixture::new(3, 4, |r, c| (r * 10 + c) as f64).with_origin(100f64, 50f64);
+        assert_eq!((grid.north(), grid.east()), (80f64, 140f64));
+        assert_eq!(grid.value(2, 3), 23f64);
+        assert_eq!(grid.value(3, 0), -32768f64);
+        grid.set_value(1, 1, 5f64);
+        grid.set_value(-1, 1, 5f64);
+        assert_eq!(grid.value(1, 1), 5f64);
+    }
+}
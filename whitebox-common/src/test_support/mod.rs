@@ -0,0 +1,7 @@
+// Fixtures for the tests of tools in this and other crates; not used by the tools themselves.
+// Other crates enable them with the `test-support` feature in their dev-dependencies.
+mod grid_fixture;
+mod tables;
+
+pub use self::grid_fixture::{temp_dir, GridFixture};
+pub use self::tables::read_tsv;
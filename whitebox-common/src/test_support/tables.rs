@@ -0,0 +1,39 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::fs;
+use std::io::Error;
+
+/// Reads a tab-separated table written by a tool, as one vector of fields per line, the header
+/// line included.
+pub fn read_tsv(file: &str) -> Result<Vec<Vec<String>>, Error> {
+    Ok(fs::read_to_string(file)?
+        .lines()
+        .map(|line| line.split('\t').map(|field| field.to_string()).collect())
+        .collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::read_tsv;
+    use crate::test_support::temp_dir;
+    use std::fs;
+
+    #[test]
+    fn test_read_tsv() {
+        let dir = temp_dir("test_support_read_tsv").unwrap();
+        let file = format!("{}/table.tsv", dir);
+        fs::write(&file, "id\tname\n1\ta b\n2\t\n").unwrap();
+        assert_eq!(
+            read_tsv(&file).unwrap(),
+            vec![vec!["id", "name"], vec!["1", "a b"], vec!["2", ""]]
+        );
+        assert!(read_tsv(&format!("{}/missing.tsv", dir)).is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,203 @@
+/*
+This module is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::env;
+use std::path;
+
+/// Prints the standard welcome banner shown at the start of a tool run, e.g.
+///
+/// ```text
+/// ****************************
+/// * Welcome to FindOutlet    *
+/// * Powered by WhiteboxTools *
+/// * www.whiteboxgeo.com      *
+/// ****************************
+/// ```
+///
+/// Nothing is printed unless `verbose` is true.
+pub fn print_tool_banner(tool_name: &str, verbose: bool) {
+    if verbose {
+        println!("{}", tool_banner(tool_name));
+    }
+}
+
+fn tool_banner(tool_name: &str) -> String {
+    // 28 = length of the 'Powered by' by statement.
+    let welcome_len = format!("* Welcome to {} *", tool_name).len().max(28);
+    let mut s = String::new();
+    s.push_str(&format!("{}\n", "*".repeat(welcome_len)));
+    s.push_str(&format!(
+        "* Welcome to {} {}*\n",
+        tool_name,
+        " ".repeat(welcome_len - 15 - tool_name.len())
+    ));
+    s.push_str(&format!(
+        "* Powered by WhiteboxTools {}*\n",
+        " ".repeat(welcome_len - 28)
+    ));
+    s.push_str(&format!(
+        "* www.whiteboxgeo.com {}*\n",
+        " ".repeat(welcome_len - 23)
+    ));
+    s.push_str(&"*".repeat(welcome_len));
+    s
+}
+
+/// Resolves a file name given on the command line against the working directory.
+///
+/// Bare file names (containing no `/` or `\` separator) are placed in the working
+/// directory, while any other path is used as given: absolute paths, Windows drive-letter
+/// paths (`C:\data\dem.tif`, `C:/data/dem.tif`), UNC paths (`\\server\share\dem.tif`), and
+/// relative paths with a directory component. Both separator styles are recognized on all
+/// platforms. A leading `~` is expanded to the user's home directory. Empty names are
+/// returned unchanged so that optional parameters remain unset.
+pub fn resolve_path(working_directory: &str, user_path: &str) -> String {
+    let home = env::var("HOME").or_else(|_| env::var("USERPROFILE")).ok();
+    resolve_path_with_home(working_directory, user_path, home.as_deref())
+}
+
+fn resolve_path_with_home(working_directory: &str, user_path: &str, home: Option<&str>) -> String {
+    let user_path = user_path.trim();
+    if user_path.is_empty() {
+        return String::new();
+    }
+
+    if let (Some(home), Some(rest)) = (home, user_path.strip_prefix('~')) {
+        if rest.is_empty() {
+            return home.to_string();
+        }
+        if rest.starts_with(is_separator) {
+            return format!("{}{}", home.trim_end_matches(is_separator), rest);
+        }
+    }
+
+    let is_drive_path = {
+        let mut chars = user_path.chars();
+        matches!(
+            (chars.next(), chars.next()),
+            (Some(c), Some(':')) if c.is_ascii_alphabetic()
+        )
+    };
+    if is_drive_path || user_path.contains(is_separator) || working_directory.is_empty() {
+        return user_path.to_string();
+    }
+
+    if working_directory.ends_with(is_separator) {
+        format!("{}{}", working_directory, user_path)
+    } else {
+        format!("{}{}{}", working_directory, path::MAIN_SEPARATOR, user_path)
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c == '/' || c == '\\'
+}
+
+#[cfg(test)]
+mod test {
+    use super::{resolve_path_with_home, tool_banner};
+    use std::path::MAIN_SEPARATOR;
+
+    #[test]
+    fn test_resolve_bare_file_names() {
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "dem.tif", None),
+            "/data/wd/dem.tif"
+        );
+        assert_eq!(
+            resolve_path_with_home(r"C:\data\wd\", "dem.tif", None),
+            r"C:\data\wd\dem.tif"
+        );
+        assert_eq!(
+            resolve_path_with_home("/data/wd", "dem.tif", None),
+            format!("/data/wd{}dem.tif", MAIN_SEPARATOR)
+        );
+        assert_eq!(resolve_path_with_home("", "dem.tif", None), "dem.tif");
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "  dem.tif ", None),
+            "/data/wd/dem.tif"
+        );
+        assert_eq!(resolve_path_with_home("/data/wd/", "", None), "");
+    }
+
+    #[test]
+    fn test_resolve_paths_with_directories() {
+        for wd in ["/data/wd/", r"C:\data\wd\"] {
+            // Unix and Windows absolute paths, with either separator style.
+            assert_eq!(
+                resolve_path_with_home(wd, "/tmp/dem.tif", None),
+                "/tmp/dem.tif"
+            );
+            assert_eq!(
+                resolve_path_with_home(wd, r"C:\runs\dem.tif", None),
+                r"C:\runs\dem.tif"
+            );
+            assert_eq!(
+                resolve_path_with_home(wd, "C:/runs/dem.tif", None),
+                "C:/runs/dem.tif"
+            );
+            assert_eq!(resolve_path_with_home(wd, "d:dem.tif", None), "d:dem.tif");
+            // UNC paths.
+            assert_eq!(
+                resolve_path_with_home(wd, r"\\server\share\dem.tif", None),
+                r"\\server\share\dem.tif"
+            );
+            assert_eq!(
+                resolve_path_with_home(wd, "//server/share/dem.tif", None),
+                "//server/share/dem.tif"
+            );
+            // Relative paths with a directory component are used as given.
+            assert_eq!(
+                resolve_path_with_home(wd, "dem/dem.tif", None),
+                "dem/dem.tif"
+            );
+            assert_eq!(
+                resolve_path_with_home(wd, r"dem\dem.tif", None),
+                r"dem\dem.tif"
+            );
+        }
+    }
+
+    #[test]
+    fn test_resolve_home_directory() {
+        let home = Some("/home/crew/");
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "~/runs/dem.tif", home),
+            "/home/crew/runs/dem.tif"
+        );
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", r"~\runs\dem.tif", Some(r"C:\Users\crew")),
+            r"C:\Users\crew\runs\dem.tif"
+        );
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "~", home),
+            "/home/crew/"
+        );
+        // '~name' forms are not expanded, and neither is '~' without a known home directory.
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "~dem.tif", home),
+            "/data/wd/~dem.tif"
+        );
+        assert_eq!(
+            resolve_path_with_home("/data/wd/", "~/dem.tif", None),
+            "~/dem.tif"
+        );
+    }
+
+    #[test]
+    fn test_tool_banner() {
+        let banner = tool_banner("FindOutlet");
+        let lines: Vec<&str> = banner.lines().collect();
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines[1], "* Welcome to FindOutlet    *");
+        assert!(lines.iter().all(|l| l.len() == 28));
+
+        let banner = tool_banner("StreamLinkLengthAndSinuosity");
+        assert!(banner.lines().all(|l| l.len() == 43));
+    }
+}
@@ -1,11 +1,13 @@
 // private sub-module defined in other files
 mod byte_order_reader;
 mod byte_order_writer;
+mod cli;
 
 // exports identifiers from private sub-modules in the current module namespace
 pub use self::byte_order_reader::ByteOrderReader;
 pub use self::byte_order_reader::Endianness;
 pub use self::byte_order_writer::ByteOrderWriter;
+pub use self::cli::{print_tool_banner, resolve_path};
 
 use std::time::Instant;
 
@@ -0,0 +1,153 @@
+/*
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use serde_json::{json, Value};
+use std::collections::{HashMap, VecDeque};
+use std::fs::{self, File};
+use std::io::{Error, ErrorKind, Write};
+use std::path::Path;
+
+/// A saved tour, written periodically during the search so that an interrupted run (e.g. on a
+/// preempted spot instance) can be resumed, and read back as the initial tour of a new search.
+/// It is stored as a small JSON document, which can also be written by hand as a warm start:
+///
+/// `{"tool": "TravellingSalesmanProblem", "fids": [3, 0, 2, 1], "length": 1234.5}`
+///
+/// where `fids` are the zero-based record numbers of the input points in tour order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Checkpoint {
+    pub fids: Vec<usize>,
+    pub length: f64,
+}
+
+impl Checkpoint {
+    pub fn to_json(&self) -> String {
+        json!({
+            "tool": "TravellingSalesmanProblem",
+            "fids": self.fids,
+            "length": self.length,
+        })
+        .to_string()
+    }
+
+    pub fn from_json(text: &str) -> Result<Checkpoint, Error> {
+        let invalid = |msg: &str| Error::new(ErrorKind::InvalidData, msg.to_string());
+        let value: Value = serde_json::from_str(text)
+            .map_err(|e| invalid(&format!("The checkpoint is not valid JSON ({}).", e)))?;
+        let fids = value["fids"]
+            .as_array()
+            .ok_or_else(|| invalid("The checkpoint has no 'fids' array."))?
+            .iter()
+            .map(|fid| fid.as_u64().map(|fid| fid as usize))
+            .collect::<Option<Vec<usize>>>()
+            .ok_or_else(|| invalid("The checkpoint 'fids' must be non-negative integers."))?;
+        // The length is informative only, so a hand-written warm start may leave it out.
+        let length = value["length"].as_f64().unwrap_or(f64::NAN);
+        Ok(Checkpoint { fids, length })
+    }
+
+    pub fn read<P: AsRef<Path>>(file_name: P) -> Result<Checkpoint, Error> {
+        let file_name = file_name.as_ref();
+        Checkpoint::from_json(&fs::read_to_string(file_name)?)
+            .map_err(|e| Error::new(e.kind(), format!("{} ({})", e, file_name.display())))
+    }
+
+    /// Writes the checkpoint to a temporary file beside `file_name` that then replaces it, so
+    /// that an interruption leaves either the previous checkpoint or this one, never a partial
+    /// file.
+    pub fn write<P: AsRef<Path>>(&self, file_name: P) -> Result<(), Error> {
+        let file_name = file_name.as_ref();
+        let mut temp_name = file_name.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let mut file = File::create(&temp_name)?;
+        file.write_all(self.to_json().as_bytes())?;
+        file.sync_all()?;
+        drop(file);
+        fs::rename(&temp_name, file_name)
+    }
+
+    /// Orders locations, given the record number of each, as in the checkpoint. A record that
+    /// holds several locations matches its checkpoint entries in turn. Locations missing from the
+    /// checkpoint follow in input order, and entries matching no location are skipped; their
+    /// numbers are returned alongside the order of location indices.
+    pub fn tour_order(&self, fids: &[usize]) -> (Vec<usize>, usize, usize) {
+        let mut locations: HashMap<usize, VecDeque<usize>> = HashMap::new();
+        for (i, &fid) in fids.iter().enumerate() {
+            locations.entry(fid).or_default().push_back(i);
+        }
+        let mut order = Vec::with_capacity(fids.len());
+        let mut used = vec![false; fids.len()];
+        let mut num_skipped = 0;
+        for fid in &self.fids {
+            match locations.get_mut(fid).and_then(|queue| queue.pop_front()) {
+                Some(i) => {
+                    used[i] = true;
+                    order.push(i);
+                }
+                None => num_skipped += 1,
+            }
+        }
+        let num_missing = fids.len() - order.len();
+        order.extend((0..fids.len()).filter(|&i| !used[i]));
+        (order, num_missing, num_skipped)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Checkpoint;
+    use std::env;
+    use std::fs;
+
+    #[test]
+    fn test_checkpoint_round_trip() {
+        let checkpoint = Checkpoint {
+            fids: vec![3, 0, 2, 1],
+            length: 1234.5,
+        };
+        let file_name =
+            env::temp_dir().join(format!("wbt_tsp_checkpoint_{}.json", std::process::id()));
+        checkpoint.write(&file_name).unwrap();
+        assert_eq!(Checkpoint::read(&file_name).unwrap(), checkpoint);
+
+        // A second write replaces the first and leaves no temporary file behind.
+        let shorter = Checkpoint {
+            fids: vec![0, 3, 2, 1],
+            length: 1000.0,
+        };
+        shorter.write(&file_name).unwrap();
+        assert_eq!(Checkpoint::read(&file_name).unwrap(), shorter);
+        let mut temp_name = file_name.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        assert!(!std::path::Path::new(&temp_name).exists());
+        let _ = fs::remove_file(&file_name);
+
+        let warm_start = Checkpoint::from_json("{\"fids\": [1, 0]}").unwrap();
+        assert_eq!(warm_start.fids, vec![1, 0]);
+        assert!(warm_start.length.is_nan());
+        assert!(Checkpoint::from_json("{\"fids\": [1, -2]}").is_err());
+        assert!(Checkpoint::from_json("{\"order\": [1, 0]}").is_err());
+        assert!(Checkpoint::from_json("[1, 0").is_err());
+        assert!(Checkpoint::read(&file_name).is_err());
+    }
+
+    #[test]
+    fn test_tour_order() {
+        let checkpoint = Checkpoint {
+            fids: vec![4, 2, 0, 2, 9],
+            length: 0.0,
+        };
+        // Record 2 holds two locations (indices 1 and 2), and record 5 is not in the checkpoint.
+        let (order, num_missing, num_skipped) = checkpoint.tour_order(&[0, 2, 2, 4, 5]);
+        assert_eq!(order, vec![3, 1, 0, 2, 4]);
+        assert_eq!((num_missing, num_skipped), (1, 1));
+
+        let (order, num_missing, num_skipped) = checkpoint.tour_order(&[4, 0]);
+        assert_eq!(order, vec![0, 1]);
+        assert_eq!((num_missing, num_skipped), (0, 3));
+    }
+}
@@ -0,0 +1,378 @@
+/*
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::fs::File;
+use std::io::{BufWriter, Error, Write};
+use std::path::Path;
+
+/// A GPX waypoint or route point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpxPoint {
+    pub lat: f64,
+    pub lon: f64,
+    pub name: String,
+}
+
+/// Builds a GPX 1.1 document containing the optional waypoints followed by a single route.
+pub fn gpx_document(
+    route_name: &str,
+    route_desc: &str,
+    route: &[GpxPoint],
+    waypoints: &[GpxPoint],
+) -> String {
+    let mut s = String::new();
+    s.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    s.push_str("<gpx version=\"1.1\" creator=\"WhiteboxTools TravellingSalesmanProblem\" ");
+    s.push_str("xmlns=\"http://www.topografix.com/GPX/1/1\" ");
+    s.push_str("xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\" ");
+    s.push_str("xsi:schemaLocation=\"http://www.topografix.com/GPX/1/1 ");
+    s.push_str("http://www.topografix.com/GPX/1/1/gpx.xsd\">\n");
+    s.push_str(&format!(
+        "  <metadata>\n    <name>{}</name>\n  </metadata>\n",
+        escape_xml(route_name)
+    ));
+    for wpt in waypoints {
+        s.push_str(&gpx_point("wpt", wpt, "  "));
+    }
+    s.push_str("  <rte>\n");
+    s.push_str(&format!("    <name>{}</name>\n", escape_xml(route_name)));
+    if !route_desc.is_empty() {
+        s.push_str(&format!("    <desc>{}</desc>\n", escape_xml(route_desc)));
+    }
+    for pt in route {
+        s.push_str(&gpx_point("rtept", pt, "    "));
+    }
+    s.push_str("  </rte>\n");
+    s.push_str("</gpx>\n");
+    s
+}
+
+fn gpx_point(tag: &str, pt: &GpxPoint, indent: &str) -> String {
+    format!(
+        "{indent}<{tag} lat=\"{:.8}\" lon=\"{:.8}\">\n{indent}  <name>{}</name>\n{indent}</{tag}>\n",
+        pt.lat,
+        pt.lon,
+        escape_xml(&pt.name)
+    )
+}
+
+/// Writes a GPX 1.1 document (see `gpx_document`) to a file.
+pub fn write_gpx<P: AsRef<Path>>(
+    file_name: P,
+    route_name: &str,
+    route_desc: &str,
+    route: &[GpxPoint],
+    waypoints: &[GpxPoint],
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(file_name)?);
+    writer.write_all(gpx_document(route_name, route_desc, route, waypoints).as_bytes())?;
+    writer.flush()
+}
+
+/// Escapes text for use in XML content or attribute values. Control characters, which
+/// are not allowed in XML 1.0 documents, are dropped.
+pub fn escape_xml(s: &str) -> String {
+    let mut ret = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => ret.push_str("&amp;"),
+            '<' => ret.push_str("&lt;"),
+            '>' => ret.push_str("&gt;"),
+            '"' => ret.push_str("&quot;"),
+            '\'' => ret.push_str("&apos;"),
+            '\t' | '\n' | '\r' => ret.push(c),
+            c if c.is_control() => {}
+            c => ret.push(c),
+        }
+    }
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::{escape_xml, gpx_document, GpxPoint};
+
+    /// A minimal XML element tree, sufficient for checking the documents written above.
+    #[derive(Debug)]
+    struct Element {
+        name: String,
+        attrs: Vec<(String, String)>,
+        children: Vec<Element>,
+        text: String,
+    }
+
+    impl Element {
+        fn attr(&self, name: &str) -> Option<&str> {
+            self.attrs
+                .iter()
+                .find(|(n, _)| n == name)
+                .map(|(_, v)| v.as_str())
+        }
+    }
+
+    fn unescape(s: &str) -> String {
+        let mut ret = String::new();
+        let mut rest = s;
+        while let Some(amp) = rest.find('&') {
+            ret.push_str(&rest[..amp]);
+            let semi = rest[amp..].find(';').expect("unterminated entity") + amp;
+            ret.push(match &rest[amp + 1..semi] {
+                "amp" => '&',
+                "lt" => '<',
+                "gt" => '>',
+                "quot" => '"',
+                "apos" => '\'',
+                e => panic!("unknown entity &{};", e),
+            });
+            rest = &rest[semi + 1..];
+        }
+        assert!(!rest.contains('<'), "unescaped '<' in {:?}", s);
+        ret.push_str(rest);
+        ret
+    }
+
+    /// Parses a well-formed XML document, panicking on any syntax error.
+    fn parse_xml(doc: &str) -> Element {
+        let doc = doc.trim();
+        let doc = match doc.strip_prefix("<?xml") {
+            Some(rest) => rest[rest.find("?>").expect("unterminated declaration") + 2..].trim(),
+            None => doc,
+        };
+        let (root, rest) = parse_element(doc);
+        assert!(rest.trim().is_empty(), "content after the root element");
+        root
+    }
+
+    fn parse_element(s: &str) -> (Element, &str) {
+        assert!(
+            s.starts_with('<'),
+            "expected a start tag: {:?}",
+            s.chars().take(30).collect::<String>()
+        );
+        let end = s.find('>').expect("unterminated start tag");
+        let self_closing = s[..end].ends_with('/');
+        let tag = s[1..end].trim_end_matches('/');
+        let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+        let mut el = Element {
+            name: tag[..name_end].to_string(),
+            attrs: vec![],
+            children: vec![],
+            text: String::new(),
+        };
+        let mut attrs = tag[name_end..].trim();
+        while !attrs.is_empty() {
+            let eq = attrs.find("=\"").expect("attribute without a quoted value");
+            let close = attrs[eq + 2..].find('"').expect("unterminated attribute") + eq + 2;
+            let name = attrs[..eq].trim().to_string();
+            assert!(el.attr(&name).is_none(), "duplicate attribute {}", name);
+            el.attrs.push((name, unescape(&attrs[eq + 2..close])));
+            attrs = attrs[close + 1..].trim_start();
+        }
+        let mut rest = &s[end + 1..];
+        if self_closing {
+            return (el, rest);
+        }
+        loop {
+            let lt = rest.find('<').expect("missing end tag");
+            el.text.push_str(&unescape(&rest[..lt]));
+            rest = &rest[lt..];
+            if let Some(close) = rest.strip_prefix("</") {
+                let gt = close.find('>').expect("unterminated end tag");
+                assert_eq!(close[..gt].trim(), el.name, "mismatched end tag");
+                return (el, &close[gt + 1..]);
+            }
+            let (child, r) = parse_element(rest);
+            el.children.push(child);
+            rest = r;
+        }
+    }
+
+    /// Checks the order and cardinality of child elements against a schema sequence of
+    /// (element name, may repeat) pairs.
+    fn check_sequence(el: &Element, sequence: &[(&str, bool)]) {
+        let mut last: Option<usize> = None;
+        for child in &el.children {
+            let idx = sequence
+                .iter()
+                .position(|(name, _)| *name == child.name)
+                .unwrap_or_else(|| panic!("<{}> is not allowed in <{}>", child.name, el.name));
+            if let Some(last) = last {
+                assert!(
+                    idx > last || (idx == last && sequence[idx].1),
+                    "<{}> is out of order or repeated in <{}>",
+                    child.name,
+                    el.name
+                );
+            }
+            last = Some(idx);
+        }
+    }
+
+    fn check_simple(el: &Element) {
+        assert!(
+            el.children.is_empty(),
+            "<{}> must be simple content",
+            el.name
+        );
+    }
+
+    fn check_wpt_type(el: &Element) {
+        let lat: f64 = el.attr("lat").expect("lat is required").parse().unwrap();
+        let lon: f64 = el.attr("lon").expect("lon is required").parse().unwrap();
+        assert!((-90.0..=90.0).contains(&lat), "latitude out of range");
+        assert!((-180.0..180.0).contains(&lon), "longitude out of range");
+        check_sequence(
+            el,
+            &[
+                ("ele", false),
+                ("time", false),
+                ("magvar", false),
+                ("geoidheight", false),
+                ("name", false),
+                ("cmt", false),
+                ("desc", false),
+                ("src", false),
+                ("link", true),
+                ("sym", false),
+                ("type", false),
+                ("fix", false),
+                ("sat", false),
+                ("hdop", false),
+                ("vdop", false),
+                ("pdop", false),
+                ("ageofdgpsdata", false),
+                ("dgpsid", false),
+                ("extensions", false),
+            ],
+        );
+        el.children.iter().for_each(check_simple);
+    }
+
+    /// Validates a document against the parts of the GPX 1.1 schema (gpx.xsd) used by
+    /// this tool: gpxType, metadataType, wptType, and rteType.
+    fn validate_gpx(root: &Element) {
+        assert_eq!(root.name, "gpx");
+        assert_eq!(root.attr("version"), Some("1.1"));
+        assert!(root.attr("creator").is_some(), "creator is required");
+        assert_eq!(
+            root.attr("xmlns"),
+            Some("http://www.topografix.com/GPX/1/1")
+        );
+        check_sequence(
+            root,
+            &[
+                ("metadata", false),
+                ("wpt", true),
+                ("rte", true),
+                ("trk", true),
+                ("extensions", false),
+            ],
+        );
+        for child in &root.children {
+            match child.name.as_str() {
+                "metadata" => {
+                    check_sequence(
+                        child,
+                        &[
+                            ("name", false),
+                            ("desc", false),
+                            ("author", false),
+                            ("copyright", false),
+                            ("link", true),
+                            ("time", false),
+                            ("keywords", false),
+                            ("bounds", false),
+                            ("extensions", false),
+                        ],
+                    );
+                    child.children.iter().for_each(check_simple);
+                }
+                "wpt" => check_wpt_type(child),
+                "rte" => {
+                    check_sequence(
+                        child,
+                        &[
+                            ("name", false),
+                            ("cmt", false),
+                            ("desc", false),
+                            ("src", false),
+                            ("link", true),
+                            ("number", false),
+                            ("type", false),
+                            ("extensions", false),
+                            ("rtept", true),
+                        ],
+                    );
+                    for c in &child.children {
+                        if c.name == "rtept" {
+                            check_wpt_type(c);
+                        } else {
+                            check_simple(c);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn pt(lat: f64, lon: f64, name: &str) -> GpxPoint {
+        GpxPoint {
+            lat,
+            lon,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_gpx_document_is_valid() {
+        let route = vec![
+            pt(46.7, -117.0, "1 Culvert <A> & \"B\""),
+            pt(46.8, -117.1, "2 O'Brien Creek"),
+            pt(46.7, -117.0, "1 Culvert <A> & \"B\""),
+        ];
+        let waypoints = vec![pt(46.8, -117.1, "2 O'Brien Creek"), route[0].clone()];
+        let doc = gpx_document("route & stops", "Tour length: 1.0", &route, &waypoints);
+        let root = parse_xml(&doc);
+        validate_gpx(&root);
+
+        let wpts: Vec<&str> = root
+            .children
+            .iter()
+            .filter(|c| c.name == "wpt")
+            .map(|c| c.children[0].text.as_str())
+            .collect();
+        assert_eq!(wpts, vec!["2 O'Brien Creek", "1 Culvert <A> & \"B\""]);
+
+        let rte = root.children.iter().find(|c| c.name == "rte").unwrap();
+        assert_eq!(rte.children[0].text, "route & stops");
+        let names: Vec<&str> = rte
+            .children
+            .iter()
+            .filter(|c| c.name == "rtept")
+            .map(|c| c.children[0].text.as_str())
+            .collect();
+        assert_eq!(names.len(), 3);
+        assert_eq!(names[1], "2 O'Brien Creek");
+        assert_eq!(names[2], "1 Culvert <A> & \"B\"");
+
+        // Without waypoints the document only holds the metadata and route.
+        let root = parse_xml(&gpx_document("r", "", &route, &[]));
+        validate_gpx(&root);
+        assert_eq!(root.children.len(), 2);
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            escape_xml("a<b>&\"c\"'d'"),
+            "a&lt;b&gt;&amp;&quot;c&quot;&apos;d&apos;"
+        );
+        assert_eq!(escape_xml("bell\u{7}name"), "bellname");
+        assert_eq!(escape_xml("Río Grande"), "Río Grande");
+    }
+}
@@ -1,26 +1,35 @@
 /*
 Authors: Prof. John Lindsay
 Created: 23/02/2022
-Last Modified: 06/05/2022
+Last Modified: 16/10/2026
 License: MIT
 */
 extern crate tsp_rs;
 
+mod checkpoint;
+mod gpx;
+
+use checkpoint::Checkpoint;
+use gpx::GpxPoint;
 use std::env;
 use std::f64;
+use std::fs;
 use std::io::{Error, ErrorKind};
+use std::mem;
 use std::path;
 use std::str;
 use std::sync::mpsc;
-use std::sync::Arc;
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use tsp_rs::Metrizable;
 use tsp_rs::Tour;
-use whitebox_common::structures::Point2D;
+use whitebox_common::algorithms::{held_karp_tour, HELD_KARP_MAX_LOCATIONS};
+use whitebox_common::spatial_ref_system::{epsg_from_wkt, esri_wkt_from_epsg, Projection};
+use whitebox_common::structures::{Point2D, PointGridIndex};
 use whitebox_common::utils::{get_formatted_elapsed_time, haversine_distance};
 use whitebox_vector::{
     AttributeField, FieldData, FieldDataType, ShapeType, Shapefile, ShapefileGeometry,
+    VectorFormat, VectorWriter,
 };
 
 /// This tool finds approximate solutions to [travelling salesman problems](https://en.wikipedia.org/wiki/Travelling_salesman_problem),
@@ -30,6 +39,56 @@ use whitebox_vector::{
 /// takes too long. The user must specify the names of the input points vector (`--input`) and output lines
 /// vector file (`--output`), as well as the duration, in seconds, over which the algorithm is allowed to search
 /// for improved solutions (`--duration`). The tool works in parallel to find more optimal solutions.
+///
+/// When the number of locations is less than or equal to `--exact_threshold` (default 12, maximum 20),
+/// the heuristic search is skipped and the optimal tour is found directly using the Held-Karp dynamic
+/// programming algorithm. The output `OPTIMAL` attribute is 1 when the route is known to be optimal
+/// and 0 when it is the best heuristic solution found within the search duration.
+///
+/// If the output file name ends in `.gpx`, the route is written as a GPX 1.1 route (`<rte>`) for
+/// loading onto handheld GPS units instead of as a shapefile. Each route point is named by its stop
+/// order, followed by the value of the input's `NAME` (or, failing that, `ID`) attribute when one
+/// exists, and the route returns to the first stop. GPX coordinates are WGS84 latitude/longitude, so
+/// the input points must be in WGS84 geographic coordinates or a WGS84/NAD83 UTM zone, identified
+/// from the EPSG code of the input .prj file; inputs without a .prj file are accepted only if their
+/// coordinates are within geographic bounds. The `--include_waypoints` flag also writes each stop as
+/// a GPX waypoint (`<wpt>`), in the order of the input file.
+///
+/// If the output file name ends in `.geojson` or `.json`, the route is written as a GeoJSON
+/// `LineString` feature with the same attributes as the shapefile output, and the EPSG code of the
+/// input's projection, when it can be identified, is recorded in the file's `crs` member.
+///
+/// Every output format carries the coordinate system of the input points: shapefile outputs copy
+/// the input's .prj file, GeoJSON outputs record its EPSG code, and GPX outputs are converted from
+/// it. When the input has no .prj file, the coordinate system can be declared with `--assign_epsg`,
+/// which also overrides the input's own; otherwise the tool warns that the output will have no
+/// coordinate system, since most GIS software will then misplace it.
+///
+/// Input points that record the same location more than once (e.g. repeated GPS fixes at a stop)
+/// can be merged before solving with `--merge_distance`. Each location within this distance of
+/// an earlier retained location is dropped from the tour, so the retained stop is the first of
+/// each group in input order. Distances are in the units of the input coordinates, or in metres
+/// for geographic coordinates. The default of 0 merges nothing.
+///
+/// Each worker thread searches its own copy of the tour, so the memory used by the search grows with
+/// both the number of locations and the number of workers. Before solving, the tool estimates the peak
+/// memory of the search and, if it exceeds `--max_memory` (in MB, default 1024), runs fewer workers
+/// with a warning. If even a single worker would exceed the limit, the tool stops and suggests merging
+/// duplicate locations with `--merge_distance` or clustering the input points to fewer locations
+/// beforehand; specify `--force` to run with a single worker regardless. Very large inputs are also
+/// slow to solve, since each pass of the 2-opt heuristic compares every pair of locations.
+///
+/// Long searches can be checkpointed with `--checkpoint`, a JSON file to which the best tour found so
+/// far (the input FIDs, i.e. zero-based record numbers, in tour order, and the tour length) is written
+/// every `--checkpoint_interval` seconds (default 60). The file is replaced atomically, so a run that is
+/// interrupted, e.g. on a preempted spot instance, leaves the last complete checkpoint behind. With
+/// `--resume`, the checkpoint is read at startup and every worker starts from its tour rather than
+/// from the input order; a missing checkpoint file starts the search afresh, so the same command can be
+/// used for the first run and its restarts. Since the format is small, a tour from elsewhere can also
+/// be written as a checkpoint to warm-start the search. Locations not in the checkpoint are appended
+/// to its tour and FIDs matching no location are ignored, with a warning. The checkpoint is removed
+/// when the tool completes unless `--keep_checkpoint` is specified, in which case it holds the final
+/// tour.
 fn main() {
     let args: Vec<String> = env::args().collect();
 
@@ -49,6 +108,11 @@ fn main() {
         // print version information
         version();
     }
+
+    if args[1].trim() == "parameters" {
+        // print the tool descriptor, used by the Whitebox Runner to discover plugin tools
+        parameters();
+    }
 }
 
 fn help() {
@@ -67,13 +131,24 @@ fn help() {
 
     The following commands are recognized:
     help       Prints help information.
+    parameters Prints the tool name, toolbox, and parameters as JSON.
     run        Runs the tool.
     version    Prints the tool version information.
 
     The following flags can be used with the 'run' command:
     -i, --input    Name of the input lines shapefile.
-    -o, --output   Name of the output lines shapefile.
+    -o, --output   Name of the output lines shapefile, or GPX file (*.gpx).
     --duration     Maximum duration (s).
+    --exact_threshold  Maximum number of locations solved exactly (Held-Karp); default 12.
+    --include_waypoints  Also write the stops as GPX waypoints (GPX output only).
+    --merge_distance  Merge locations within this distance of an earlier location; default 0.
+    --max_memory   Estimated search memory (MB) above which fewer workers are used; default 1024.
+    --force        Run with a single worker even if its estimated memory exceeds --max_memory.
+    --assign_epsg  EPSG code of the input coordinate system, e.g. when it has no .prj file.
+    --checkpoint   JSON file to which the best tour is written periodically during the search.
+    --checkpoint_interval  Seconds between checkpoint writes; default 60.
+    --resume       Start the search from the tour in the --checkpoint file, if it exists.
+    --keep_checkpoint  Keep the checkpoint, holding the final tour, once the tool completes.
     
     Input/output file names can be fully qualified, or can rely on the
     working directory contained in the WhiteboxTools settings.json file.
@@ -94,6 +169,10 @@ fn version() {
     );
 }
 
+fn parameters() {
+    println!("{}", include_str!("travelling_salesman_problem.json"));
+}
+
 fn get_tool_name() -> String {
     String::from("TravellingSalesmanProblem") // This should be camel case and is a reference to the tool name.
 }
@@ -119,6 +198,16 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
     let mut input_file = String::new();
     let mut output_file: String = String::new();
     let mut duration = 60u64;
+    let mut exact_threshold = 12usize;
+    let mut include_waypoints = false;
+    let mut merge_distance = 0f64;
+    let mut max_memory = 1024f64;
+    let mut force = false;
+    let mut assign_epsg: Option<u16> = None;
+    let mut checkpoint_file = String::new();
+    let mut checkpoint_interval = 60u64;
+    let mut resume = false;
+    let mut keep_checkpoint = false;
     if args.len() <= 1 {
         return Err(Error::new(
             ErrorKind::InvalidInput,
@@ -159,6 +248,88 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
                     .parse::<u64>()
                     .expect(&format!("Error parsing {}", flag_val))
             };
+        } else if flag_val == "-exact_threshold" {
+            exact_threshold = if keyval {
+                vec[1]
+                    .to_string()
+                    .parse::<usize>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            } else {
+                args[i + 1]
+                    .to_string()
+                    .parse::<usize>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            };
+        } else if flag_val == "-include_waypoints" {
+            if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                include_waypoints = true;
+            }
+        } else if flag_val == "-merge_distance" {
+            merge_distance = if keyval {
+                vec[1]
+                    .to_string()
+                    .parse::<f64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            } else {
+                args[i + 1]
+                    .to_string()
+                    .parse::<f64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            };
+        } else if flag_val == "-max_memory" {
+            max_memory = if keyval {
+                vec[1]
+                    .to_string()
+                    .parse::<f64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            } else {
+                args[i + 1]
+                    .to_string()
+                    .parse::<f64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            };
+        } else if flag_val == "-force" {
+            if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                force = true;
+            }
+        } else if flag_val == "-assign_epsg" {
+            assign_epsg = Some(if keyval {
+                vec[1]
+                    .to_string()
+                    .parse::<u16>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            } else {
+                args[i + 1]
+                    .to_string()
+                    .parse::<u16>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            });
+        } else if flag_val == "-checkpoint" {
+            checkpoint_file = if keyval {
+                vec[1].to_string()
+            } else {
+                args[i + 1].to_string()
+            };
+        } else if flag_val == "-checkpoint_interval" {
+            checkpoint_interval = if keyval {
+                vec[1]
+                    .to_string()
+                    .parse::<u64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            } else {
+                args[i + 1]
+                    .to_string()
+                    .parse::<u64>()
+                    .expect(&format!("Error parsing {}", flag_val))
+            };
+        } else if flag_val == "-resume" {
+            if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                resume = true;
+            }
+        } else if flag_val == "-keep_checkpoint" {
+            if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                keep_checkpoint = true;
+            }
         }
     }
 
@@ -179,6 +350,29 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
         println!("{}", "*".repeat(welcome_len));
     }
 
+    if exact_threshold > HELD_KARP_MAX_LOCATIONS {
+        if configurations.verbose_mode {
+            println!(
+                "Warning: --exact_threshold is limited to {} locations.",
+                HELD_KARP_MAX_LOCATIONS
+            );
+        }
+        exact_threshold = HELD_KARP_MAX_LOCATIONS;
+    }
+
+    if checkpoint_file.is_empty() && (resume || keep_checkpoint) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--resume and --keep_checkpoint require a checkpoint file (--checkpoint).",
+        ));
+    }
+    if checkpoint_interval == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "--checkpoint_interval must be at least 1 second.",
+        ));
+    }
+
     let mut progress: usize;
     let mut old_progress: usize = 1;
 
@@ -192,6 +386,29 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
         output_file = format!("{}{}", working_directory, output_file);
     }
 
+    if !checkpoint_file.is_empty()
+        && !checkpoint_file.contains(&sep)
+        && !checkpoint_file.contains("/")
+    {
+        checkpoint_file = format!("{}{}", working_directory, checkpoint_file);
+    }
+
+    // Read the checkpoint before the input, so that an unreadable one is reported at once. A
+    // missing one is not an error, since the first run of a resumable search has none.
+    let resume_checkpoint = if resume {
+        if path::Path::new(&checkpoint_file).exists() {
+            Some(Checkpoint::read(&checkpoint_file)?)
+        } else {
+            println!(
+                "Warning: The checkpoint {} does not exist; the search starts from the input order.",
+                checkpoint_file
+            );
+            None
+        }
+    } else {
+        None
+    };
+
     let input = Shapefile::read(&input_file)?;
 
     // Make sure the input vector file is of point type
@@ -212,6 +429,28 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
         false
     };
 
+    let output_crs = OutputCrs::resolve(&input.projection, assign_epsg)?;
+
+    // GPX output must be in WGS84, so check that the input can be converted before solving.
+    let is_gpx = output_file.to_lowercase().ends_with(".gpx");
+    let wgs84_transform = if is_gpx {
+        Some(gpx_transform(
+            &output_crs,
+            is_geographic_proj,
+            configurations.verbose_mode,
+        )?)
+    } else {
+        // Report an unsupported output format before solving rather than after.
+        let format = VectorFormat::from_file_name(&output_file)?;
+        // These warnings are printed regardless of the verbose mode.
+        if output_crs.projection.is_empty() {
+            println!("Warning: The input has no .prj file, so the output will have no coordinate system. Specify the EPSG code of the input coordinate system with --assign_epsg.");
+        } else if output_crs.epsg.is_none() && format == VectorFormat::GeoJson {
+            println!("Warning: The EPSG code of the input coordinate system could not be identified from its .prj file, so the GeoJSON output will have no crs member. Specify it with --assign_epsg.");
+        }
+        None
+    };
+
     let mut tour: Vec<Point> = vec![];
     for record_num in 0..input.num_records {
         let record = input.get_record(record_num);
@@ -221,6 +460,7 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
                     record.points[i].x,
                     record.points[i].y,
                     is_geographic_proj,
+                    record_num,
                 ));
             }
         }
@@ -234,81 +474,275 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
         }
     }
 
-    if configurations.verbose_mode {
-        println!("The tour includes {} locations.", tour.len());
+    if merge_distance > 0f64 && tour.len() > 1 {
+        let num_locations = tour.len();
+        tour = merge_nearby_locations(tour, merge_distance);
+        if configurations.verbose_mode {
+            println!(
+                "Merged {} locations within {} of another location.",
+                num_locations - tour.len(),
+                merge_distance
+            );
+        }
     }
 
     if configurations.verbose_mode {
-        println!("Finding optimal route, please be patient...");
-    }
-
-    let tour = Arc::new(tour);
-    let (tx, rx) = mpsc::channel();
-    for _tid in 0..num_procs {
-        let tour = tour.clone();
-        let tx = tx.clone();
-        thread::spawn(move || {
-            let mut tour = Tour::from(&tour);
-            tour.optimize_kopt(std::time::Duration::from_secs(duration));
-            tx.send(tour).unwrap();
-        });
+        println!("The tour includes {} locations.", tour.len());
     }
 
-    let mut progress: i32;
-    let mut old_progress: i32 = -1;
-    let mut min_len = f64::MAX;
-    let mut min_len_tour = Tour::from(&tour);
-    for n in 0..num_procs {
-        let tour_route = rx.recv().unwrap();
-        let tour_len = tour_route.tour_len();
-        if tour_len < min_len {
-            min_len = tour_len;
-            min_len_tour = tour_route.clone();
+    let (route, min_len, is_optimal) = if tour.len() > 1 && tour.len() <= exact_threshold {
+        if configurations.verbose_mode {
+            if resume_checkpoint.is_some() {
+                println!("The tour is solved exactly, so the checkpoint is not used.");
+            }
+            println!("Solving the tour exactly (Held-Karp)...");
         }
+        let (order, tour_len) = held_karp_tour(tour.len(), |i, j| tour[i].cost(&tour[j]))
+            .expect("Error solving the tour exactly");
+        let route: Vec<Point> = order.iter().map(|&i| tour[i].clone()).collect();
+        (route, tour_len, true)
+    } else {
+        let max_memory_bytes = (max_memory.max(0f64) * 1024f64 * 1024f64) as usize;
+        let num_workers = match workers_within_memory(tour.len(), num_procs, max_memory_bytes) {
+            Some(num_workers) => {
+                if num_workers < num_procs && configurations.verbose_mode {
+                    println!(
+                        "Warning: The search with {} workers is estimated to need {:.0} MB, more than --max_memory ({} MB); using {} workers instead.",
+                        num_procs,
+                        estimate_memory(tour.len(), num_procs) as f64 / 1048576f64,
+                        max_memory,
+                        num_workers
+                    );
+                }
+                num_workers
+            }
+            None => {
+                let message = format!(
+                    "The search for a tour of {} locations is estimated to need {:.0} MB even with a single worker, more than --max_memory ({} MB). Merge duplicate locations with --merge_distance or cluster the input points to fewer locations, raise --max_memory, or specify --force to run anyway.",
+                    tour.len(),
+                    estimate_memory(tour.len(), 1) as f64 / 1048576f64,
+                    max_memory
+                );
+                if !force {
+                    return Err(Error::new(ErrorKind::InvalidInput, message));
+                }
+                if configurations.verbose_mode {
+                    println!(
+                        "Warning: {} Running with a single worker (--force).",
+                        message
+                    );
+                }
+                1
+            }
+        };
+
         if configurations.verbose_mode {
-            progress = (100.0_f64 * n as f64 / (num_procs - 1) as f64) as i32;
-            if progress != old_progress {
-                println!("Progress: {}%", progress);
-                old_progress = progress;
+            println!("Finding optimal route, please be patient...");
+        }
+
+        let initial_order: Vec<usize> = match &resume_checkpoint {
+            Some(checkpoint) => {
+                let fids: Vec<usize> = tour.iter().map(|pt| pt.record).collect();
+                let (order, num_missing, num_skipped) = checkpoint.tour_order(&fids);
+                if num_missing > 0 || num_skipped > 0 {
+                    println!(
+                        "Warning: The checkpoint does not match the input; {} locations missing from it are appended to its tour and {} of its FIDs are ignored.",
+                        num_missing, num_skipped
+                    );
+                }
+                if configurations.verbose_mode {
+                    println!("Resuming from the checkpoint {}.", checkpoint_file);
+                }
+                order
+            }
+            None => (0..tour.len()).collect(),
+        };
+
+        // The workers' tours are paths of stops referring to the shared locations, rather than
+        // copies of the locations themselves.
+        let stops: Vec<Stop> = initial_order.iter().map(|&i| Stop(&tour[i])).collect();
+        // With a checkpoint, the workers search in slices of the checkpoint interval, reporting
+        // their tours between slices so that the best of them can be saved.
+        let slice = if checkpoint_file.is_empty() {
+            None
+        } else {
+            Some(Duration::from_secs(checkpoint_interval))
+        };
+        let (min_len_path, min_len) = thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel();
+            for _tid in 0..num_workers {
+                let stops = &stops;
+                let tx = tx.clone();
+                scope.spawn(move || {
+                    let mut tour = Tour::from(stops);
+                    let deadline = Instant::now() + Duration::from_secs(duration);
+                    let mut tour_len = f64::MAX;
+                    loop {
+                        let remaining = deadline.saturating_duration_since(Instant::now());
+                        let slice = slice.map_or(remaining, |slice| slice.min(remaining));
+                        let slice_end = Instant::now() + slice;
+                        tour.optimize_kopt(slice);
+                        let previous_len = tour_len;
+                        tour_len = tour.tour_len();
+                        // A slice that ends early without an improvement has converged.
+                        let finished = slice == remaining
+                            || (Instant::now() < slice_end && tour_len >= previous_len);
+                        tx.send(WorkerTour {
+                            path: tour.path.clone(),
+                            tour_len,
+                            finished,
+                        })
+                        .unwrap();
+                        if finished {
+                            break;
+                        }
+                    }
+                });
             }
+
+            let mut progress: i32;
+            let mut old_progress: i32 = -1;
+            let mut min_len = f64::MAX;
+            let mut min_len_path = None;
+            let mut num_finished = 0;
+            let mut last_checkpoint = Instant::now();
+            let mut saved = true;
+            while num_finished < num_workers {
+                let worker_tour = rx.recv().unwrap();
+                if worker_tour.tour_len < min_len {
+                    min_len = worker_tour.tour_len;
+                    min_len_path = Some(worker_tour.path);
+                    saved = false;
+                }
+                if let (Some(slice), Some(path)) = (slice, &min_len_path) {
+                    if !saved && last_checkpoint.elapsed() >= slice {
+                        save_checkpoint(&checkpoint_file, path.iter().map(|stop| stop.0), min_len);
+                        last_checkpoint = Instant::now();
+                        saved = true;
+                    }
+                }
+                if worker_tour.finished {
+                    if configurations.verbose_mode {
+                        progress = (100.0_f64 * num_finished as f64
+                            / (num_workers - 1).max(1) as f64)
+                            as i32;
+                        if progress != old_progress {
+                            println!("Progress: {}%", progress);
+                            old_progress = progress;
+                        }
+                    }
+                    num_finished += 1;
+                }
+            }
+            (min_len_path.expect("No tour was found"), min_len)
+        });
+
+        if configurations.verbose_mode {
+            println!("Tour distance: {:.3}", min_len);
         }
-    }
+        let route: Vec<Point> = min_len_path.iter().map(|stop| stop.0.clone()).collect();
+        (route, min_len, false)
+    };
 
-    if configurations.verbose_mode {
-        println!("Tour distance: {:.3}", min_len);
+    if configurations.verbose_mode && is_optimal {
+        println!("Tour distance: {:.3} (optimal)", min_len);
     }
 
-    // create output file
-    let mut output =
-        Shapefile::new(&output_file, ShapeType::PolyLine).expect("Error creating shapefile");
-    output.projection = input.projection.clone();
-    output
-        .attributes
-        .add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
-    output.attributes.add_field(&AttributeField::new(
-        "LENGTH",
-        FieldDataType::Real,
-        9u8,
-        3u8,
-    ));
+    if let Some(transform) = wgs84_transform {
+        let label_field = ["NAME", "ID"].iter().find_map(|name| {
+            input
+                .attributes
+                .get_fields()
+                .iter()
+                .find(|f| f.name.trim().eq_ignore_ascii_case(name))
+                .map(|f| f.name.clone())
+        });
+        let width = route.len().to_string().len();
+        let stop_name = |order: usize, record: usize| -> String {
+            let mut name = format!("{:0width$}", order, width = width);
+            if let Some(field) = &label_field {
+                let label = match input.attributes.get_value(record, field) {
+                    FieldData::Null => String::new(),
+                    value => value.to_string(),
+                };
+                if !label.trim().is_empty() {
+                    name.push(' ');
+                    name.push_str(label.trim());
+                }
+            }
+            name
+        };
+
+        let mut rtepts = Vec::with_capacity(route.len() + 1);
+        for (order, pt) in route.iter().enumerate() {
+            let (lon, lat) = transform.inverse(pt.x, pt.y).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The stop at ({}, {}) could not be converted to WGS84 latitude/longitude.",
+                        pt.x, pt.y
+                    ),
+                )
+            })?;
+            rtepts.push(GpxPoint {
+                lat,
+                lon,
+                name: stop_name(order + 1, pt.record),
+            });
+        }
+        if rtepts.len() > 1 {
+            rtepts.push(rtepts[0].clone()); // close the loop
+        }
 
-    let mut vec_pts = vec![];
-    let first_pt = min_len_tour.path[0].clone();
-    for pt in min_len_tour.path {
-        vec_pts.push(Point2D::new(pt.x, pt.y));
+        let mut wpts = vec![];
+        if include_waypoints {
+            // The waypoints are listed in input order, named as in the route.
+            let mut stops: Vec<(usize, usize)> = route
+                .iter()
+                .enumerate()
+                .map(|(order, pt)| (order, pt.record))
+                .collect();
+            stops.sort_by_key(|&(order, record)| (record, order));
+            for (order, _) in stops {
+                wpts.push(rtepts[order].clone());
+            }
+        }
+
+        let route_name = path::Path::new(&output_file)
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or("route")
+            .to_string();
+        let route_desc = format!(
+            "Tour length: {:.3}{}",
+            min_len,
+            if is_optimal { " (optimal)" } else { "" }
+        );
+
+        if configurations.verbose_mode {
+            println!("Saving data...")
+        };
+        gpx::write_gpx(&output_file, &route_name, &route_desc, &rtepts, &wpts)?;
+        finish_checkpoint(&checkpoint_file, keep_checkpoint, &route, min_len);
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if configurations.verbose_mode {
+            println!(
+                "\n{}",
+                &format!("Elapsed Time (Including I/O): {}", elapsed_time)
+            );
+        }
+        return Ok(());
     }
-    vec_pts.push(Point2D::new(first_pt.x, first_pt.y)); // close the loop
-    let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
-    sfg.add_part(&vec_pts);
-    output.add_record(sfg);
-    output
-        .attributes
-        .add_record(vec![FieldData::Int(1i32), FieldData::Real(min_len)], false);
+
+    let mut vec_pts: Vec<Point2D> = route.iter().map(|pt| Point2D::new(pt.x, pt.y)).collect();
+    vec_pts.push(vec_pts[0]); // close the loop
 
     if configurations.verbose_mode {
         println!("Saving data...")
     };
-    output.write().expect("Error saving Shapefile");
+    write_route(&output_file, &output_crs, &vec_pts, min_len, is_optimal)?;
+    finish_checkpoint(&checkpoint_file, keep_checkpoint, &route, min_len);
 
     let elapsed_time = get_formatted_elapsed_time(start);
 
@@ -322,19 +756,254 @@ fn run(args: &Vec<String>) -> Result<(), std::io::Error> {
     Ok(())
 }
 
+/// The coordinate system of the outputs: the projection written to a shapefile's .prj file and
+/// the EPSG code written to GeoJSON and used to convert GPX coordinates.
+#[derive(Debug, Clone, PartialEq)]
+struct OutputCrs {
+    projection: String,
+    epsg: Option<u16>,
+}
+
+impl OutputCrs {
+    /// Resolves the coordinate system of the outputs from the input's projection, or from the
+    /// `--assign_epsg` code when one is specified.
+    fn resolve(input_projection: &str, assign_epsg: Option<u16>) -> Result<OutputCrs, Error> {
+        let input_epsg = epsg_from_wkt(input_projection);
+        match assign_epsg {
+            Some(epsg) => {
+                let projection = esri_wkt_from_epsg(epsg);
+                if projection == "Unknown EPSG Code" {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("--assign_epsg {} is not a recognized EPSG code.", epsg),
+                    ));
+                }
+                if !input_projection.trim().is_empty() && input_epsg != Some(epsg) {
+                    println!(
+                        "Warning: The coordinate system of the input .prj file is replaced by EPSG:{} (--assign_epsg).",
+                        epsg
+                    );
+                }
+                Ok(OutputCrs {
+                    projection,
+                    epsg: Some(epsg),
+                })
+            }
+            None => Ok(OutputCrs {
+                projection: input_projection.trim().to_string(),
+                epsg: input_epsg,
+            }),
+        }
+    }
+}
+
+/// Writes the closed tour as a single polyline record to a Shapefile or, if `output_file` ends in
+/// `.geojson` or `.json`, a GeoJSON file.
+fn write_route(
+    output_file: &str,
+    crs: &OutputCrs,
+    route: &[Point2D],
+    tour_len: f64,
+    is_optimal: bool,
+) -> Result<(), Error> {
+    let mut output = VectorWriter::new(output_file, ShapeType::PolyLine)?;
+    output.projection = crs.projection.clone();
+    output.epsg = crs.epsg;
+    output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+    output.add_field(&AttributeField::new(
+        "LENGTH",
+        FieldDataType::Real,
+        9u8,
+        3u8,
+    ));
+    output.add_field(&AttributeField::new(
+        "OPTIMAL",
+        FieldDataType::Int,
+        1u8,
+        0u8,
+    ));
+
+    let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
+    sfg.add_part(route);
+    output.add_record(
+        sfg,
+        vec![
+            FieldData::Int(1i32),
+            FieldData::Real(tour_len),
+            FieldData::Int(is_optimal as i32),
+        ],
+    );
+
+    output.write()
+}
+
+/// A worker's tour, reported after each slice of its search.
+struct WorkerTour<'a> {
+    path: Vec<Stop<'a>>,
+    tour_len: f64,
+    /// Whether the worker's search is over.
+    finished: bool,
+}
+
+/// Saves a tour to the checkpoint file. A failure is only a warning, printed regardless of the
+/// verbose mode, since it should not end a long search.
+fn save_checkpoint<'a>(
+    checkpoint_file: &str,
+    tour: impl Iterator<Item = &'a Point>,
+    tour_len: f64,
+) {
+    let checkpoint = Checkpoint {
+        fids: tour.map(|pt| pt.record).collect(),
+        length: tour_len,
+    };
+    if let Err(e) = checkpoint.write(checkpoint_file) {
+        println!(
+            "Warning: The checkpoint {} could not be written ({}).",
+            checkpoint_file, e
+        );
+    }
+}
+
+/// Once the outputs are written, removes the checkpoint, or with `--keep_checkpoint` replaces it
+/// with the final tour.
+fn finish_checkpoint(checkpoint_file: &str, keep_checkpoint: bool, route: &[Point], tour_len: f64) {
+    if checkpoint_file.is_empty() {
+        return;
+    }
+    if keep_checkpoint {
+        save_checkpoint(checkpoint_file, route.iter(), tour_len);
+    } else if let Err(e) = fs::remove_file(checkpoint_file) {
+        if e.kind() != ErrorKind::NotFound {
+            println!(
+                "Warning: The checkpoint {} could not be removed ({}).",
+                checkpoint_file, e
+            );
+        }
+    }
+}
+
+/// Drops each location lying within `merge_distance` (measured as the tour cost) of an earlier
+/// retained location, returning the retained locations in input order.
+fn merge_nearby_locations(tour: Vec<Point>, merge_distance: f64) -> Vec<Point> {
+    let points: Vec<Point2D> = tour.iter().map(|p| Point2D::new(p.x, p.y)).collect();
+    let index = PointGridIndex::with_cell_size(&points, merge_distance);
+    let mut merged = vec![false; tour.len()];
+    let mut retained = Vec::with_capacity(tour.len());
+    for i in 0..tour.len() {
+        if merged[i] {
+            continue;
+        }
+        // Geographic distances are in metres, so search a radius in degrees wide enough to
+        // hold every candidate at this latitude and check each with the haversine distance
+        // (which uses an earth radius of 6371 km).
+        let radius = if tour[i].is_geographic_proj {
+            let degrees = (merge_distance / 6371e3).to_degrees().min(180f64);
+            let max_lat = (tour[i].y.abs() + degrees).min(90f64);
+            let cos_lat = max_lat.to_radians().cos();
+            if cos_lat > 0.001 {
+                degrees * f64::consts::SQRT_2 / cos_lat
+            } else {
+                360f64
+            }
+        } else {
+            merge_distance
+        };
+        for j in index.within_radius(&points[i], radius) {
+            if j > i && !merged[j] && tour[i].cost(&tour[j]) <= merge_distance {
+                merged[j] = true;
+            }
+        }
+        retained.push(tour[i].clone());
+    }
+    retained
+}
+
+/// Estimated memory per location shared by all workers, in bytes.
+const SHARED_BYTES_PER_LOCATION: usize = mem::size_of::<Point>();
+
+/// Estimated memory per location for each worker, in bytes: the worker's tour, the copies of it made
+/// during the k-opt search, and the finished tour sent back to be compared with the others.
+const WORKER_BYTES_PER_LOCATION: usize = 4 * mem::size_of::<Stop<'static>>();
+
+/// Estimates the peak memory, in bytes, of searching for a tour of `num_locations` locations with
+/// `num_workers` worker threads.
+fn estimate_memory(num_locations: usize, num_workers: usize) -> usize {
+    num_locations.saturating_mul(
+        SHARED_BYTES_PER_LOCATION
+            .saturating_add(num_workers.saturating_mul(WORKER_BYTES_PER_LOCATION)),
+    )
+}
+
+/// Returns the largest number of workers, up to `num_procs`, whose estimated memory is within
+/// `max_memory` bytes, or `None` if a single worker would exceed it.
+fn workers_within_memory(
+    num_locations: usize,
+    num_procs: usize,
+    max_memory: usize,
+) -> Option<usize> {
+    (1..=num_procs.max(1))
+        .rev()
+        .find(|&num_workers| estimate_memory(num_locations, num_workers) <= max_memory)
+}
+
+/// Returns the projection of the input coordinates, whose inverse gives the WGS84
+/// latitude/longitude needed for GPX output, or an error explaining how to prepare the input if
+/// there is none. No datum transformation is made, so e.g. NAD83 inputs are treated as WGS84,
+/// which is well within the accuracy of handheld GPS units.
+fn gpx_transform(
+    crs: &OutputCrs,
+    is_geographic_proj: bool,
+    verbose: bool,
+) -> Result<Projection, Error> {
+    if crs.projection.is_empty() {
+        if is_geographic_proj {
+            if verbose {
+                println!("Warning: The input has no .prj file; assuming WGS84 geographic coordinates for the GPX output.");
+            }
+            return Projection::from_wkt(&esri_wkt_from_epsg(4326)).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::Other,
+                    "The WGS84 coordinate system could not be read.",
+                )
+            });
+        }
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "GPX output requires WGS84 coordinates, but the input points have no .prj file and their coordinates are not geographic. Add a .prj file describing the input's coordinate system, specify its EPSG code with --assign_epsg, reproject the points to WGS84 (EPSG:4326), or write a shapefile output instead.",
+        ));
+    }
+    let projection = match crs.epsg {
+        Some(epsg) => Projection::from_epsg(epsg),
+        None => Projection::from_wkt(&crs.projection),
+    };
+    projection.ok_or_else(|| {
+        let system = match crs.epsg {
+            Some(epsg) => format!("EPSG:{}", epsg),
+            None => "from its .prj file".to_string(),
+        };
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!("GPX output requires WGS84 coordinates, and the input coordinate system ({}) cannot be converted by this tool. Geographic, Transverse Mercator (including UTM), Lambert Conformal Conic, Albers, and Mercator systems are supported; specify the EPSG code with --assign_epsg, reproject the points to one of these, or write a shapefile output instead.", system),
+        )
+    })
+}
+
 #[derive(Debug, Clone, PartialEq, PartialOrd)]
 pub struct Point {
     pub x: f64,
     pub y: f64,
     pub is_geographic_proj: bool,
+    /// The index of the input record the point belongs to.
+    pub record: usize,
 }
 
 impl Point {
-    pub fn new(x: f64, y: f64, is_geographic_proj: bool) -> Point {
+    pub fn new(x: f64, y: f64, is_geographic_proj: bool, record: usize) -> Point {
         Point {
             x,
             y,
             is_geographic_proj,
+            record,
         }
     }
 }
@@ -349,8 +1018,234 @@ impl Metrizable for Point {
     }
 }
 
+/// A stop on a worker's tour, referring to one of the shared locations.
+#[derive(Debug, Clone, Copy)]
+pub struct Stop<'a>(&'a Point);
+
+impl<'a> Metrizable for Stop<'a> {
+    fn cost(&self, other: &Stop<'a>) -> f64 {
+        self.0.cost(other.0)
+    }
+}
+
 // impl Metrizable for Point2D {
 //     fn cost(&self, other: &Point2D) -> f64 {
 //         ((self.x - other.x)*(self.x - other.x) + (self.y - other.y)*(self.y - other.y)).sqrt()
 //     }
 // }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        estimate_memory, gpx_transform, merge_nearby_locations, workers_within_memory, write_route,
+        OutputCrs, Point, Stop, WORKER_BYTES_PER_LOCATION,
+    };
+    use rand::rngs::SmallRng;
+    use rand::{Rng, SeedableRng};
+    use std::env;
+    use std::fs;
+    use tsp_rs::Metrizable;
+    use whitebox_common::spatial_ref_system::{esri_wkt_from_epsg, Projection};
+    use whitebox_common::structures::Point2D;
+    use whitebox_common::utils::deg_to_utm;
+    use whitebox_vector::{FieldData, ShapeType, Shapefile};
+
+    /// Merges by comparing each location with every retained location.
+    fn brute_force_merge(tour: &[Point], merge_distance: f64) -> Vec<Point> {
+        let mut retained: Vec<Point> = vec![];
+        let mut merged = vec![false; tour.len()];
+        for i in 0..tour.len() {
+            if merged[i] {
+                continue;
+            }
+            for j in i + 1..tour.len() {
+                if tour[i].cost(&tour[j]) <= merge_distance {
+                    merged[j] = true;
+                }
+            }
+            retained.push(tour[i].clone());
+        }
+        retained
+    }
+
+    #[test]
+    fn test_merge_nearby_locations_matches_brute_force() {
+        let mut rng = SmallRng::seed_from_u64(3);
+        for &(is_geographic, max_x, max_y, distance) in &[
+            (false, 1000f64, 1000f64, 25f64),
+            (false, 1000f64, 1000f64, 0.5f64),
+            (true, 0.05f64, 0.05f64, 200f64),
+            (true, 10f64, 0.02f64, 500f64),
+        ] {
+            let y0 = if is_geographic { 64f64 } else { 0f64 };
+            let mut tour = vec![];
+            for record in 0..1500 {
+                let (x, y) = (rng.gen_range(0f64, max_x), y0 + rng.gen_range(0f64, max_y));
+                tour.push(Point::new(x, y, is_geographic, record));
+                if record % 10 == 0 {
+                    tour.push(Point::new(x, y, is_geographic, record)); // exact duplicate
+                }
+            }
+            assert_eq!(
+                merge_nearby_locations(tour.clone(), distance),
+                brute_force_merge(&tour, distance)
+            );
+        }
+    }
+
+    #[test]
+    fn test_workers_within_memory() {
+        let per_worker = 1000 * WORKER_BYTES_PER_LOCATION;
+        let limit = estimate_memory(1000, 4);
+        assert_eq!(workers_within_memory(1000, 8, limit), Some(4));
+        assert_eq!(
+            workers_within_memory(1000, 8, limit + per_worker - 1),
+            Some(4)
+        );
+        assert_eq!(workers_within_memory(1000, 8, usize::MAX), Some(8));
+        assert_eq!(
+            workers_within_memory(1000, 8, estimate_memory(1000, 1) - 1),
+            None
+        );
+        assert_eq!(workers_within_memory(0, 8, 0), Some(8));
+    }
+
+    #[test]
+    fn test_stops_share_locations() {
+        let points = [
+            Point::new(0f64, 0f64, false, 0),
+            Point::new(3f64, 4f64, false, 1),
+        ];
+        let (a, b) = (Stop(&points[0]), Stop(&points[1]));
+        assert_eq!(a.cost(&b), 5f64);
+        assert!(std::mem::size_of::<Stop>() < std::mem::size_of::<Point>());
+    }
+
+    #[test]
+    fn test_write_route_formats() {
+        let route: Vec<Point2D> = [(0.0, 0.0), (3.0, 0.0), (3.0, 4.0), (0.0, 0.0)]
+            .iter()
+            .map(|&(x, y)| Point2D::new(x, y))
+            .collect();
+        let wkt = "PROJCS[\"NAD83 / UTM zone 17N\",AUTHORITY[\"EPSG\",\"26917\"]]";
+        let crs = OutputCrs::resolve(wkt, None).unwrap();
+        let base = env::temp_dir().join(format!("wbt_tsp_route_{}", std::process::id()));
+
+        let shp = format!("{}.shp", base.to_string_lossy());
+        write_route(&shp, &crs, &route, 12.0, true).unwrap();
+        let output = Shapefile::read(&shp).unwrap();
+        assert_eq!(output.header.shape_type, ShapeType::PolyLine);
+        assert_eq!(output.num_records, 1);
+        assert_eq!(output.get_record(0).points, route);
+        assert_eq!(
+            output.attributes.get_value(0, "LENGTH"),
+            FieldData::Real(12.0)
+        );
+        assert_eq!(output.attributes.get_value(0, "OPTIMAL"), FieldData::Int(1));
+        assert_eq!(fs::read_to_string(base.with_extension("prj")).unwrap(), wkt);
+        for ext in ["shp", "shx", "dbf", "prj"] {
+            let _ = fs::remove_file(base.with_extension(ext));
+        }
+
+        let geojson = format!("{}.geojson", base.to_string_lossy());
+        write_route(&geojson, &crs, &route, 12.0, false).unwrap();
+        let text = fs::read_to_string(&geojson).unwrap();
+        assert!(text.contains("\"urn:ogc:def:crs:EPSG::26917\""));
+        assert!(text.contains(
+            "{\"type\": \"LineString\", \"coordinates\": [[0, 0], [3, 0], [3, 4], [0, 0]]}"
+        ));
+        assert!(text.contains("\"properties\": {\"FID\": 1, \"LENGTH\": 12, \"OPTIMAL\": 0}"));
+        let _ = fs::remove_file(&geojson);
+
+        assert!(write_route(
+            &format!("{}.kml", base.to_string_lossy()),
+            &crs,
+            &route,
+            12.0,
+            false
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_output_crs() {
+        let utm = "PROJCS[\"WGS_1984_UTM_Zone_11N\",AUTHORITY[\"EPSG\",32611]]";
+        assert_eq!(
+            OutputCrs::resolve(utm, None).unwrap(),
+            OutputCrs {
+                projection: utm.to_string(),
+                epsg: Some(32611)
+            }
+        );
+        let missing = OutputCrs::resolve("", None).unwrap();
+        assert_eq!(missing.epsg, None);
+        assert!(missing.projection.is_empty());
+        assert!(OutputCrs::resolve("", Some(1)).is_err());
+
+        // An assigned EPSG code declares the coordinate system of an input without a .prj
+        // file, or replaces that of the input, in every output format.
+        for input_projection in ["", utm] {
+            let crs = OutputCrs::resolve(input_projection, Some(26912)).unwrap();
+            assert_eq!(crs.epsg, Some(26912));
+            assert!(crs.projection.contains("NAD_1983_UTM_Zone_12N"));
+            assert_eq!(
+                gpx_transform(&crs, false, false).unwrap(),
+                Projection::from_epsg(26912).unwrap()
+            );
+        }
+        assert!(gpx_transform(&missing, false, false).is_err());
+        assert_eq!(
+            gpx_transform(&missing, true, false)
+                .unwrap()
+                .inverse(-117.0, 46.7),
+            Some((-117.0, 46.7))
+        );
+
+        // A .prj file whose EPSG code is not identified is converted from its WKT.
+        let lcc = esri_wkt_from_epsg(32040);
+        let crs = OutputCrs {
+            projection: lcc.clone(),
+            epsg: None,
+        };
+        assert_eq!(
+            gpx_transform(&crs, false, false).unwrap(),
+            Projection::from_wkt(&lcc).unwrap()
+        );
+        assert!(gpx_transform(
+            &OutputCrs {
+                projection: utm.to_string(),
+                epsg: None
+            },
+            false,
+            false
+        )
+        .is_err());
+        let (x, y, _, _) = deg_to_utm(46.73, -117.01);
+        let (lon, lat) = gpx_transform(&OutputCrs::resolve(utm, None).unwrap(), false, false)
+            .unwrap()
+            .inverse(x, y)
+            .unwrap();
+        assert!((lat - 46.73).abs() < 1e-6 && (lon + 117.01).abs() < 1e-6);
+
+        let crs = OutputCrs::resolve("", Some(26912)).unwrap();
+        let route: Vec<Point2D> = [(0.0, 0.0), (3.0, 4.0), (0.0, 0.0)]
+            .iter()
+            .map(|&(x, y)| Point2D::new(x, y))
+            .collect();
+        let base = env::temp_dir().join(format!("wbt_tsp_crs_{}", std::process::id()));
+        let shp = format!("{}.shp", base.to_string_lossy());
+        write_route(&shp, &crs, &route, 10.0, true).unwrap();
+        let prj = fs::read_to_string(base.with_extension("prj")).unwrap();
+        assert_eq!(prj, crs.projection);
+        assert_eq!(Shapefile::read(&shp).unwrap().projection.trim(), prj);
+        for ext in ["shp", "shx", "dbf", "prj"] {
+            let _ = fs::remove_file(base.with_extension(ext));
+        }
+
+        let geojson = format!("{}.geojson", base.to_string_lossy());
+        write_route(&geojson, &crs, &route, 10.0, true).unwrap();
+        let text = fs::read_to_string(&geojson).unwrap();
+        assert!(text.contains("\"urn:ogc:def:crs:EPSG::26912\""));
+        let _ = fs::remove_file(&geojson);
+    }
+}
@@ -14,6 +14,64 @@ pub fn read_arcbinary(
     configs: &mut RasterConfigs,
     data: &mut Vec<f64>,
 ) -> Result<(), Error> {
+    read_arcbinary_header(file_name, configs)?;
+
+    data.reserve(configs.rows * configs.columns);
+
+    // read the data file
+    // let data_file = file_name.replace(".hdr", ".flt");
+    let data_file = Path::new(&file_name)
+        .with_extension("flt")
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let mut f = File::open(data_file.clone())?;
+
+    configs.minimum = f64::INFINITY;
+    configs.maximum = f64::NEG_INFINITY;
+
+    let data_size = 4;
+    let num_cells = configs.rows * configs.columns;
+    let buf_size = 1_000_000usize;
+    let mut j = 0;
+    let mut z: f64;
+    while j < num_cells {
+        let mut buffer = vec![0; buf_size * data_size];
+
+        f.read(&mut buffer)?;
+
+        let mut offset: usize;
+        for i in 0..buf_size {
+            offset = i * 4;
+            z = if configs.endian == Endianness::LittleEndian {
+                f32::from_le_bytes(get_four_bytes(&buffer[offset..offset + 4])) as f64
+            } else {
+                f32::from_be_bytes(get_four_bytes(&buffer[offset..offset + 4])) as f64
+            };
+            data.push(z);
+
+            if z != configs.nodata {
+                if z < configs.minimum {
+                    configs.minimum = z;
+                }
+                if z > configs.maximum {
+                    configs.maximum = z;
+                }
+            }
+
+            j += 1;
+            if j == num_cells {
+                break;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Reads the ArcGIS binary header (.hdr) file into `configs` without opening
+/// the data (.flt) file.
+pub fn read_arcbinary_header(file_name: &String, configs: &mut RasterConfigs) -> Result<(), Error> {
     // read the header file
     // let header_file = file_name.replace(".flt", ".hdr");
     let header_file = Path::new(&file_name)
@@ -106,56 +164,6 @@ pub fn read_arcbinary(
             yllcenter + (0.5 * configs.resolution_y) + (configs.rows as f64) * configs.resolution_y;
     }
 
-    data.reserve(configs.rows * configs.columns);
-
-    // read the data file
-    // let data_file = file_name.replace(".hdr", ".flt");
-    let data_file = Path::new(&file_name)
-        .with_extension("flt")
-        .into_os_string()
-        .into_string()
-        .unwrap();
-    let mut f = File::open(data_file.clone())?;
-
-    configs.minimum = f64::INFINITY;
-    configs.maximum = f64::NEG_INFINITY;
-
-    let data_size = 4;
-    let num_cells = configs.rows * configs.columns;
-    let buf_size = 1_000_000usize;
-    let mut j = 0;
-    let mut z: f64;
-    while j < num_cells {
-        let mut buffer = vec![0; buf_size * data_size];
-
-        f.read(&mut buffer)?;
-
-        let mut offset: usize;
-        for i in 0..buf_size {
-            offset = i * 4;
-            z = if configs.endian == Endianness::LittleEndian {
-                f32::from_le_bytes(get_four_bytes(&buffer[offset..offset + 4])) as f64
-            } else {
-                f32::from_be_bytes(get_four_bytes(&buffer[offset..offset + 4])) as f64
-            };
-            data.push(z);
-
-            if z != configs.nodata {
-                if z < configs.minimum {
-                    configs.minimum = z;
-                }
-                if z > configs.maximum {
-                    configs.maximum = z;
-                }
-            }
-
-            j += 1;
-            if j == num_cells {
-                break;
-            }
-        }
-    }
-
     Ok(())
 }
 
@@ -1,6 +1,7 @@
 #![allow(unused_assignments, dead_code)]
 pub mod geokeys;
 pub mod ifd;
+pub mod multiband;
 pub mod tiff_consts;
 
 // use flate2::read::GzDecoder;
@@ -233,20 +234,41 @@ pub fn read_geotiff<'a>(
     configs: &'a mut RasterConfigs,
     data: &'a mut Vec<f64>,
 ) -> Result<(), Error> {
-    let f = File::open(file_name.clone())?;
+    read_geotiff_file(file_name, configs, data, false)
+}
+
+/// Reads the TIFF header and the first image file directory (IFD) of a
+/// GeoTIFF into `configs` without decoding any strips or tiles. The
+/// minimum and maximum values are not stored in the tags and are left unset.
+pub fn read_geotiff_header<'a>(
+    file_name: &'a String,
+    configs: &'a mut RasterConfigs,
+) -> Result<(), Error> {
+    let mut data = vec![];
+    read_geotiff_file(file_name, configs, &mut data, true)
+}
+
+/// Opens a TIFF file and reads its header and first image file directory (IFD) into a map of
+/// entries by tag, setting `endian` from the header. Returns the reader, for the image data, and
+/// the map.
+fn read_first_ifd(
+    file_name: &str,
+    endian: &mut Endianness,
+) -> Result<(ByteOrderReader<BufReader<File>>, HashMap<u16, Ifd>), Error> {
+    let f = File::open(file_name)?;
 
     //////////////////////////
     // Read the TIFF header //
     //////////////////////////
     let br = BufReader::new(f);
-    let mut th = ByteOrderReader::<BufReader<File>>::new(br, configs.endian);
+    let mut th = ByteOrderReader::<BufReader<File>>::new(br, *endian);
 
     let bo_indicator1 = th.read_u8()?;
     let bo_indicator2 = th.read_u8()?;
     if bo_indicator1 == 73 && bo_indicator2 == 73 {
-        configs.endian = Endianness::LittleEndian;
+        *endian = Endianness::LittleEndian;
     } else if bo_indicator1 == 77 && bo_indicator2 == 77 {
-        configs.endian = Endianness::BigEndian;
+        *endian = Endianness::BigEndian;
     } else {
         return Err(Error::new(
             ErrorKind::InvalidData,
@@ -254,8 +276,8 @@ pub fn read_geotiff<'a>(
         ));
     }
 
-    if th.get_byte_order() != configs.endian {
-        th.set_byte_order(configs.endian);
+    if th.get_byte_order() != *endian {
+        th.set_byte_order(*endian);
     }
 
     let is_big_tiff = match th.read_u16()? {
@@ -301,7 +323,6 @@ pub fn read_geotiff<'a>(
     //////////////////
 
     let mut ifd_map = HashMap::new();
-    let mut geokeys: GeoKeys = Default::default();
     let mut cur_pos: usize;
     while ifd_offset > 0 {
         th.seek(ifd_offset);
@@ -387,7 +408,7 @@ pub fn read_geotiff<'a>(
                 num_values,
                 value_offset,
                 data,
-                configs.endian,
+                *endian,
             );
 
             ifd_map.insert(tag_id, ifd.clone());
@@ -409,6 +430,79 @@ pub fn read_geotiff<'a>(
         ifd_offset = 0; // comment this out if you want to read additional images.
     }
 
+    Ok((th, ifd_map))
+}
+
+/// Reads the `n` bytes of a strip or tile at `offset` and decompresses them, decoding at most
+/// `max_uncompressed_length` bytes of LZW data.
+fn read_block(
+    th: &mut ByteOrderReader<BufReader<File>>,
+    offset: usize,
+    n: usize,
+    compression: u16,
+    max_uncompressed_length: usize,
+) -> Result<Vec<u8>, Error> {
+    let mut buf: Vec<u8> = vec![];
+    match compression {
+        COMPRESS_NONE => {
+            // no compression
+            // buf = vec![0u8; n];
+            buf.reserve_exact(n);
+            unsafe { buf.set_len(n); }
+            th.seek(offset);
+            th.read_exact(&mut buf)?;
+        }
+        COMPRESS_PACKBITS => {
+            // buf = packbits_decoder(th.buffer[offset..(offset + n)].to_vec());
+            let mut b = vec![0u8; n];
+            th.seek(offset);
+            th.read_exact(&mut b).expect("Error reading bytes from file.");
+            buf = packbits_decoder(b);
+        }
+        COMPRESS_LZW => {
+            let mut compressed = vec![0; n];
+            th.seek(offset);
+            th.read_exact(&mut compressed).expect("Error reading bytes from file.");
+            buf = Vec::with_capacity(max_uncompressed_length);
+            let mut decoder = lzw::DecoderEarlyChange::new(lzw::MsbReader::new(), 8);
+            let mut bytes_read = 0;
+            while bytes_read < n && buf.len() < max_uncompressed_length {
+                let (len, bytes) = decoder.decode_bytes(&compressed[bytes_read..]).expect("Error encountered while decoding the LZW compressed GeoTIFF file.");
+                bytes_read += len;
+                buf.extend_from_slice(bytes);
+            }
+        }
+        COMPRESS_DEFLATE => {
+            // let mut dec = GzDecoder::new(th.buffer[offset..(offset + n)].to_vec());
+            // let compressed = &th.buffer[offset..(offset + n)];
+            // let mut decoder = Decoder::new(&compressed[..]).unwrap();
+            // decoder.read_to_end(&mut buf).unwrap();
+            th.seek(offset);
+            let mut compressed = vec![0u8; n];
+            th.read_exact(&mut compressed).expect("Error reading bytes from file.");
+            // let mut decoder = Decoder::new(&compressed[..])?;
+            // decoder.read_to_end(&mut buf).unwrap();
+            buf.extend(decompress_to_vec_zlib(&compressed).expect("Error encountered while decoding the DEFLATE compressed GeoTIFF file."));
+        }
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The WhiteboxTools GeoTIFF decoder currently only supports PACKBITS and DEFLATE compression.",
+            ))
+        }
+    }
+    Ok(buf)
+}
+
+fn read_geotiff_file<'a>(
+    file_name: &'a String,
+    configs: &'a mut RasterConfigs,
+    data: &'a mut Vec<f64>,
+    header_only: bool,
+) -> Result<(), Error> {
+    let (mut th, ifd_map) = read_first_ifd(file_name, &mut configs.endian)?;
+    let mut geokeys: GeoKeys = Default::default();
+
     configs.columns = match ifd_map.get(&256) {
         Some(ifd) => {
             // The 256 tag can be either u16 or u32 type
@@ -895,6 +989,11 @@ pub fn read_geotiff<'a>(
         ));
     }
 
+    if header_only {
+        configs.data_type = geotiff_data_type(mode, &sample_format, &bits_per_sample)?;
+        return Ok(());
+    }
+
     let width = configs.columns;
     let height = configs.rows;
 
@@ -1128,59 +1227,13 @@ pub fn read_geotiff<'a>(
             }
             let offset = block_offsets[j * blocks_across + i] as usize;
             let n = block_counts[j * blocks_across + i] as usize;
-            let mut buf: Vec<u8> = vec![];
-            if n != 0 {
+            let buf = if n != 0 {
                 // it's not a sparse tile
-                match compression {
-                    COMPRESS_NONE => {
-                        // no compression
-                        // buf = vec![0u8; n];
-                        buf.reserve_exact(n);
-                        unsafe { buf.set_len(n); }
-                        th.seek(offset);
-                        th.read_exact(&mut buf)?;
-                    }
-                    COMPRESS_PACKBITS => {
-                        // buf = packbits_decoder(th.buffer[offset..(offset + n)].to_vec());
-                        let mut b = vec![0u8; n];
-                        th.seek(offset);
-                        th.read_exact(&mut b).expect("Error reading bytes from file.");
-                        buf = packbits_decoder(b);
-                    }
-                    COMPRESS_LZW => {
-                        let mut compressed = vec![0; n];
-                        th.seek(offset);
-                        th.read_exact(&mut compressed).expect("Error reading bytes from file.");
-                        let max_uncompressed_length = block_width * block_height * bits_per_sample.len() * bits_per_sample[0] as usize / 8;
-                        buf = Vec::with_capacity(max_uncompressed_length);
-                        let mut decoder = lzw::DecoderEarlyChange::new(lzw::MsbReader::new(), 8);
-                        let mut bytes_read = 0;
-                        while bytes_read < n && buf.len() < max_uncompressed_length {
-                            let (len, bytes) = decoder.decode_bytes(&compressed[bytes_read..]).expect("Error encountered while decoding the LZW compressed GeoTIFF file.");
-                            bytes_read += len;
-                            buf.extend_from_slice(bytes);
-                        }
-                    }
-                    COMPRESS_DEFLATE => {
-                        // let mut dec = GzDecoder::new(th.buffer[offset..(offset + n)].to_vec());
-                        // let compressed = &th.buffer[offset..(offset + n)];
-                        // let mut decoder = Decoder::new(&compressed[..]).unwrap();
-                        // decoder.read_to_end(&mut buf).unwrap();
-                        th.seek(offset);
-                        let mut compressed = vec![0u8; n];
-                        th.read_exact(&mut compressed).expect("Error reading bytes from file.");
-                        // let mut decoder = Decoder::new(&compressed[..])?;
-                        // decoder.read_to_end(&mut buf).unwrap();
-                        buf.extend(decompress_to_vec_zlib(&compressed).expect("Error encountered while decoding the DEFLATE compressed GeoTIFF file."));
-                    }
-                    _ => {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "The WhiteboxTools GeoTIFF decoder currently only supports PACKBITS and DEFLATE compression.",
-                        ))
-                    }
-                }
-            }
+                let max_uncompressed_length = block_width * block_height * bits_per_sample.len() * bits_per_sample[0] as usize / 8;
+                read_block(&mut th, offset, n, compression, max_uncompressed_length)?
+            } else {
+                vec![]
+            };
 
             // println!("{:?}", &buf[0..8]);
             let mut bor = ByteOrderReader::<Cursor<Vec<u8>>>::new(Cursor::new(buf), configs.endian);
@@ -1494,131 +1547,7 @@ pub fn read_geotiff<'a>(
                 }
             }
 
-            match mode {
-                IM_GRAYINVERT | IM_GRAY => {
-                    //ImageMode::GrayInvert | ImageMode::Gray => {
-                    configs.photometric_interp = PhotometricInterpretation::Continuous;
-                    match sample_format[0] {
-                        1 => {
-                            // unsigned integer
-                            match bits_per_sample[0] {
-                                8 => {
-                                    configs.data_type = DataType::U8;
-                                }
-                                16 => {
-                                    configs.data_type = DataType::U16;
-                                }
-                                32 => {
-                                    configs.data_type = DataType::U32;
-                                }
-                                64 => {
-                                    configs.data_type = DataType::U64;
-                                }
-                                _ => {
-                                    return Err(Error::new(
-                                        ErrorKind::InvalidData,
-                                        "The raster was not read correctly",
-                                    ))
-                                }
-                            }
-                        }
-                        2 => {
-                            // signed integer
-                            match bits_per_sample[0] {
-                                8 => {
-                                    configs.data_type = DataType::I8;
-                                }
-                                16 => {
-                                    configs.data_type = DataType::I16;
-                                }
-                                32 => {
-                                    configs.data_type = DataType::I32;
-                                }
-                                64 => {
-                                    configs.data_type = DataType::I64;
-                                }
-                                _ => {
-                                    return Err(Error::new(
-                                        ErrorKind::InvalidData,
-                                        "The raster was not read correctly",
-                                    ))
-                                }
-                            }
-                        }
-                        3 => {
-                            // floating point
-                            match bits_per_sample[0] {
-                                32 => {
-                                    configs.data_type = DataType::F32;
-                                }
-                                64 => {
-                                    configs.data_type = DataType::F64;
-                                }
-                                _ => {
-                                    return Err(Error::new(
-                                        ErrorKind::InvalidData,
-                                        "The raster was not read correctly",
-                                    ))
-                                }
-                            }
-                        }
-                        _ => {
-                            return Err(Error::new(
-                                ErrorKind::InvalidData,
-                                "The raster was not read correctly",
-                            ))
-                        }
-                    }
-                }
-                IM_PALETTED => {
-                    //ImageMode::Paletted => {
-                    configs.photometric_interp = PhotometricInterpretation::Categorical;
-                    configs.data_type = DataType::U8;
-                }
-                IM_RGB => {
-                    configs.photometric_interp = PhotometricInterpretation::RGB;
-                    if bits_per_sample[0] == 8 {
-                        configs.data_type = DataType::U8;
-                    } else if bits_per_sample[0] == 16 {
-                        configs.data_type = DataType::U16;
-                    } else {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "The raster was not read correctly",
-                        ));
-                    }
-                }
-                IM_NRGBA | IM_RGBA => {
-                    // if bits_per_sample[0] == 8 {
-                    //     configs.data_type = DataType::U32;
-                    // } else if bits_per_sample[0] == 16 {
-                    //     configs.data_type = DataType::U64;
-                    // } else {
-                    //     return Err(Error::new(
-                    //         ErrorKind::InvalidData,
-                    //         "The raster was not read correctly",
-                    //     ));
-                    // }
-                    if bits_per_sample[0] == 8 && bits_per_sample.len() == 4 {
-                        configs.data_type = DataType::RGBA32;
-                    } else if bits_per_sample[0] == 8 && bits_per_sample.len() == 3 {
-                        configs.data_type = DataType::RGB24;
-                    } else if bits_per_sample[0] == 16 {
-                        configs.data_type = DataType::U16;
-                    } else {
-                        return Err(Error::new(
-                            ErrorKind::InvalidData,
-                            "The raster was not read correctly",
-                        ));
-                    }
-                }
-                _ => {
-                    return Err(Error::new(
-                        ErrorKind::InvalidData,
-                        "The raster was not read correctly",
-                    ))
-                }
-            }
+            configs.data_type = geotiff_data_type(mode, &sample_format, &bits_per_sample)?;
         }
     }
 
@@ -1696,10 +1625,59 @@ pub fn read_geotiff<'a>(
     Ok(())
 }
 
+/// Maps the image mode and the SampleFormat/BitsPerSample tags onto the
+/// in-memory data type of a GeoTIFF.
+fn geotiff_data_type(
+    mode: u16,
+    sample_format: &[u16],
+    bits_per_sample: &[u16],
+) -> Result<DataType, Error> {
+    let data_type = match mode {
+        IM_GRAYINVERT | IM_GRAY => match (sample_format[0], bits_per_sample[0]) {
+            // unsigned integer
+            (1, 8) => Some(DataType::U8),
+            (1, 16) => Some(DataType::U16),
+            (1, 32) => Some(DataType::U32),
+            (1, 64) => Some(DataType::U64),
+            // signed integer
+            (2, 8) => Some(DataType::I8),
+            (2, 16) => Some(DataType::I16),
+            (2, 32) => Some(DataType::I32),
+            (2, 64) => Some(DataType::I64),
+            // floating point
+            (3, 32) => Some(DataType::F32),
+            (3, 64) => Some(DataType::F64),
+            _ => None,
+        },
+        IM_PALETTED => Some(DataType::U8),
+        IM_RGB => match bits_per_sample[0] {
+            8 => Some(DataType::U8),
+            16 => Some(DataType::U16),
+            _ => None,
+        },
+        IM_NRGBA | IM_RGBA => {
+            if bits_per_sample[0] == 8 && bits_per_sample.len() == 4 {
+                Some(DataType::RGBA32)
+            } else if bits_per_sample[0] == 8 && bits_per_sample.len() == 3 {
+                Some(DataType::RGB24)
+            } else if bits_per_sample[0] == 16 {
+                Some(DataType::U16)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    };
+    data_type.ok_or_else(|| Error::new(ErrorKind::InvalidData, "The raster was not read correctly"))
+}
+
 pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
-    // We'll need to look at the configurations to see if compression should be used
-    let configs = whitebox_common::configs::get_configs()?;
-    let use_compression = configs.compress_rasters;
+    // We'll need to look at the configurations to see if compression should be used, unless
+    // the raster overrides the setting.
+    let use_compression = match r.get_compression() {
+        Some(compress) => compress,
+        None => whitebox_common::configs::get_configs()?.compress_rasters,
+    };
 
     // get the ByteOrderWriter
     let f = File::create(r.file_name.clone())?;
@@ -2867,10 +2845,31 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         }
     }
 
+    push_georeferencing_entries(&r.configs, is_big_tiff, &mut ifd_entries, &mut larger_values_data)?;
+
+    write_ifd_entries(&mut writer, r.configs.endian, is_big_tiff, ifd_entries, ifd_start)?;
+
+    //////////////////////////////////
+    // Write the larger_values_data //
+    //////////////////////////////////
+    write_bytes(&mut writer, larger_values_data.get_inner())?;
+
+    Ok(())
+}
+
+/// Adds the tags that georeference a GeoTIFF, i.e. the model pixel scale, tie points and
+/// transformation, the GDAL nodata value, and the GeoKeys, to the IFD entries, storing their values
+/// in `larger_values_data`.
+fn push_georeferencing_entries(
+    configs: &RasterConfigs,
+    is_big_tiff: bool,
+    ifd_entries: &mut Vec<Entry>,
+    larger_values_data: &mut ByteOrderWriter<Vec<u8>>,
+) -> Result<(), Error> {
     // ModelPixelScaleTag tag (33550)
-    if r.configs.model_pixel_scale[0] == 0f64
-        && r.configs.model_tiepoint.is_empty()
-        && r.configs.model_transformation[0] == 0f64
+    if configs.model_pixel_scale[0] == 0f64
+        && configs.model_tiepoint.is_empty()
+        && configs.model_transformation[0] == 0f64
     {
         ifd_entries.push(Entry::new(
             TAG_MODELPIXELSCALETAG,
@@ -2878,22 +2877,22 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             3u64,
             larger_values_data.len() as u64,
         ));
-        larger_values_data.write_f64(r.configs.resolution_x)?;
-        larger_values_data.write_f64(r.configs.resolution_y)?;
+        larger_values_data.write_f64(configs.resolution_x)?;
+        larger_values_data.write_f64(configs.resolution_y)?;
         larger_values_data.write_f64(0f64)?;
-    } else if r.configs.model_pixel_scale[0] != 0f64 {
+    } else if configs.model_pixel_scale[0] != 0f64 {
         ifd_entries.push(Entry::new(
             TAG_MODELPIXELSCALETAG,
             DT_DOUBLE,
             3u64,
             larger_values_data.len() as u64,
         ));
-        larger_values_data.write_f64(r.configs.model_pixel_scale[0])?;
-        larger_values_data.write_f64(r.configs.model_pixel_scale[1])?;
-        larger_values_data.write_f64(r.configs.model_pixel_scale[2])?;
+        larger_values_data.write_f64(configs.model_pixel_scale[0])?;
+        larger_values_data.write_f64(configs.model_pixel_scale[1])?;
+        larger_values_data.write_f64(configs.model_pixel_scale[2])?;
     }
 
-    if r.configs.model_tiepoint.is_empty() && r.configs.model_transformation[0] == 0f64 {
+    if configs.model_tiepoint.is_empty() && configs.model_transformation[0] == 0f64 {
         // ModelTiepointTag tag (33922)
         ifd_entries.push(Entry::new(
             TAG_MODELTIEPOINTTAG,
@@ -2904,23 +2903,23 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         larger_values_data.write_f64(0f64)?; // I
         larger_values_data.write_f64(0f64)?; // J
         larger_values_data.write_f64(0f64)?; // K
-        larger_values_data.write_f64(r.configs.west)?; // X
-        larger_values_data.write_f64(r.configs.north)?; // Y
+        larger_values_data.write_f64(configs.west)?; // X
+        larger_values_data.write_f64(configs.north)?; // Y
         larger_values_data.write_f64(0f64)?; // Z
-    } else if !r.configs.model_tiepoint.is_empty() {
+    } else if !configs.model_tiepoint.is_empty() {
         // ModelTiepointTag tag (33922)
         ifd_entries.push(Entry::new(
             TAG_MODELTIEPOINTTAG,
             DT_DOUBLE,
-            r.configs.model_tiepoint.len() as u64,
+            configs.model_tiepoint.len() as u64,
             larger_values_data.len() as u64,
         ));
-        for i in 0..r.configs.model_tiepoint.len() {
-            larger_values_data.write_f64(r.configs.model_tiepoint[i])?;
+        for i in 0..configs.model_tiepoint.len() {
+            larger_values_data.write_f64(configs.model_tiepoint[i])?;
         }
     }
 
-    if r.configs.model_transformation[0] != 0f64 {
+    if configs.model_transformation[0] != 0f64 {
         // ModelTransformationTag tag (33920)
         ifd_entries.push(Entry::new(
             TAG_MODELTRANSFORMATIONTAG,
@@ -2929,12 +2928,12 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             larger_values_data.len() as u64,
         ));
         for i in 0..16 {
-            larger_values_data.write_f64(r.configs.model_transformation[i])?;
+            larger_values_data.write_f64(configs.model_transformation[i])?;
         }
     }
 
     // TAG_GDAL_NODATA tag (42113)
-    let nodata_str = format!("{}", r.configs.nodata);
+    let nodata_str = format!("{}", configs.nodata);
     let mut nodata_bytes = nodata_str.into_bytes();
     if !is_big_tiff {
         // we buffer this string with spaces to ensure that it is
@@ -3000,7 +2999,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
     let mut gk_entries: Vec<GeoKeyEntry> = vec![];
     let mut ascii_params = String::new(); //: Vec<u8> = vec![];
     let double_params: Vec<f64> = vec![];
-    if geographic_type_map.contains_key(&r.configs.epsg_code) {
+    if geographic_type_map.contains_key(&configs.epsg_code) {
         // tGTModelTypeGeoKey (1024)
         gk_entries.push(GeoKeyEntry {
             tag: TAG_GTMODELTYPEGEOKEY,
@@ -3010,7 +3009,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         });
 
         // GTRasterTypeGeoKey (1025)
-        if r.configs.pixel_is_area {
+        if configs.pixel_is_area {
             gk_entries.push(GeoKeyEntry {
                 tag: TAG_GTRASTERTYPEGEOKEY,
                 location: 0u16,
@@ -3028,7 +3027,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
         // tGTCitationGeoKey (1026)
         let mut v = String::from(
-            *geographic_type_map.get(&r.configs.epsg_code).unwrap(), // .clone(),
+            *geographic_type_map.get(&configs.epsg_code).unwrap(), // .clone(),
         );
         v.push_str("|");
         v = v.replace("_", " ");
@@ -3045,12 +3044,12 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             tag: TAG_GEOGRAPHICTYPEGEOKEY,
             location: 0u16,
             count: 1u16,
-            value_offset: r.configs.epsg_code,
+            value_offset: configs.epsg_code,
         });
 
-        if r.configs.z_units.to_lowercase() != "not specified" {
+        if configs.z_units.to_lowercase() != "not specified" {
             // VerticalUnitsGeoKey (4099)
-            let units = r.configs.z_units.to_lowercase();
+            let units = configs.z_units.to_lowercase();
             if units.contains("met") {
                 gk_entries.push(GeoKeyEntry {
                     tag: TAG_VERTICALUNITSGEOKEY,
@@ -3067,7 +3066,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
                 });
             }
         }
-    } else if projected_cs_type_map.contains_key(&r.configs.epsg_code) {
+    } else if projected_cs_type_map.contains_key(&configs.epsg_code) {
         // tGTModelTypeGeoKey (1024)
         gk_entries.push(GeoKeyEntry {
             tag: TAG_GTMODELTYPEGEOKEY,
@@ -3077,7 +3076,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         });
 
         // GTRasterTypeGeoKey (1025)
-        if r.configs.pixel_is_area {
+        if configs.pixel_is_area {
             gk_entries.push(GeoKeyEntry {
                 tag: TAG_GTRASTERTYPEGEOKEY,
                 location: 0u16,
@@ -3098,12 +3097,12 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             tag: TAG_PROJECTEDCSTYPEGEOKEY,
             location: 0u16,
             count: 1u16,
-            value_offset: r.configs.epsg_code,
+            value_offset: configs.epsg_code,
         });
 
         // PCSCitationGeoKey (3073)
         let mut v = String::from(
-            *projected_cs_type_map.get(&r.configs.epsg_code).unwrap(), // .clone(),
+            *projected_cs_type_map.get(&configs.epsg_code).unwrap(), // .clone(),
         );
         v.push_str("|");
         v = v.replace("_", " ");
@@ -3115,9 +3114,9 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         });
         ascii_params.push_str(&v);
 
-        if r.configs.xy_units.to_lowercase() != "not specified" {
+        if configs.xy_units.to_lowercase() != "not specified" {
             // ProjLinearUnitsGeoKey (3076)
-            let units = r.configs.xy_units.to_lowercase();
+            let units = configs.xy_units.to_lowercase();
             if units.contains("met") {
                 gk_entries.push(GeoKeyEntry {
                     tag: TAG_PROJLINEARUNITSGEOKEY,
@@ -3135,9 +3134,9 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             }
         }
 
-        if r.configs.z_units.to_lowercase() != "not specified" {
+        if configs.z_units.to_lowercase() != "not specified" {
             // VerticalUnitsGeoKey (4099)
-            let units = r.configs.z_units.to_lowercase();
+            let units = configs.z_units.to_lowercase();
             if units.contains("met") {
                 gk_entries.push(GeoKeyEntry {
                     tag: TAG_VERTICALUNITSGEOKEY,
@@ -3166,7 +3165,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         });
 
         // GTRasterTypeGeoKey (1025)
-        if r.configs.pixel_is_area {
+        if configs.pixel_is_area {
             gk_entries.push(GeoKeyEntry {
                 tag: TAG_GTRASTERTYPEGEOKEY,
                 location: 0u16,
@@ -3183,7 +3182,7 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         }
     }
 
-    if r.configs.geo_key_directory.is_empty() {
+    if configs.geo_key_directory.is_empty() {
         // create the GeoKeyDirectoryTag tag (34735)
         ifd_entries.push(Entry::new(
             TAG_GEOKEYDIRECTORYTAG,
@@ -3233,34 +3232,34 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
             larger_values_data.write_bytes(&ascii_params_bytes)?;
         }
     } else {
-        // let num_keys = (r.configs.geo_key_directory.len() - 4) / 4;
+        // let num_keys = (configs.geo_key_directory.len() - 4) / 4;
         // output the GeoKeyDirectoryTag tag (34735)
         ifd_entries.push(Entry::new(
             TAG_GEOKEYDIRECTORYTAG,
             DT_SHORT,
-            r.configs.geo_key_directory.len() as u64,
+            configs.geo_key_directory.len() as u64,
             larger_values_data.len() as u64,
         ));
-        for val in &r.configs.geo_key_directory {
+        for val in &configs.geo_key_directory {
             larger_values_data.write_u16(*val)?;
         }
 
-        if r.configs.geo_double_params.len() > 0 {
+        if configs.geo_double_params.len() > 0 {
             // create the GeoDoubleParamsTag tag (34736)
             ifd_entries.push(Entry::new(
                 TAG_GEODOUBLEPARAMSTAG,
                 DT_DOUBLE,
-                r.configs.geo_double_params.len() as u64,
+                configs.geo_double_params.len() as u64,
                 larger_values_data.len() as u64,
             ));
-            for double_val in &r.configs.geo_double_params {
+            for double_val in &configs.geo_double_params {
                 larger_values_data.write_f64(*double_val)?;
             }
         }
 
-        if !r.configs.geo_ascii_params.is_empty() {
+        if !configs.geo_ascii_params.is_empty() {
             // create the GeoAsciiParamsTag tag (34737)
-            let mut ascii_params_bytes = r.configs.geo_ascii_params.clone().into_bytes();
+            let mut ascii_params_bytes = configs.geo_ascii_params.clone().into_bytes();
             ascii_params_bytes.push(0);
             ifd_entries.push(Entry::new(
                 TAG_GEOASCIIPARAMSTAG,
@@ -3276,13 +3275,25 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         }
     }
 
+    Ok(())
+}
+
+/// Writes the IFD of a single-image TIFF starting at `ifd_start`, sorting the entries by tag.
+/// Entry values that do not fit in the entry are offsets into the data written after the IFD.
+fn write_ifd_entries<W: Write>(
+    writer: &mut BufWriter<W>,
+    endian: Endianness,
+    is_big_tiff: bool,
+    mut ifd_entries: Vec<Entry>,
+    ifd_start: u64,
+) -> Result<(), Error> {
     ///////////////////
     // Write the IFD //
     ///////////////////
 
     // Number of Directory Entries.
     if !is_big_tiff {
-        write_u16(&mut writer, r.configs.endian, ifd_entries.len() as u16)?;
+        write_u16(writer, endian, ifd_entries.len() as u16)?;
 
         // Sort the IFD entries
         ifd_entries.sort_by(|a, b| a.tag.cmp(&b.tag));
@@ -3291,24 +3302,24 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         let ifd_length = 2u64 + ifd_entries.len() as u64 * 12u64 + 4u64;
 
         for ifde in ifd_entries {
-            write_u16(&mut writer, r.configs.endian, ifde.tag)?; // Tag
-            write_u16(&mut writer, r.configs.endian, ifde.ifd_type)?; // Field type
-            write_u32(&mut writer, r.configs.endian, ifde.num_values as u32)?; // Num of values
+            write_u16(writer, endian, ifde.tag)?; // Tag
+            write_u16(writer, endian, ifde.ifd_type)?; // Field type
+            write_u32(writer, endian, ifde.num_values as u32)?; // Num of values
             if ifde.ifd_type == DT_SHORT && ifde.num_values == 1 {
                 // it's a value
-                write_u16(&mut writer, r.configs.endian, ifde.offset as u16)?; // Value
-                write_u16(&mut writer, r.configs.endian, 0u16)?; // Fill the remaining 2 right bytes of the u32
+                write_u16(writer, endian, ifde.offset as u16)?; // Value
+                write_u16(writer, endian, 0u16)?; // Fill the remaining 2 right bytes of the u32
             } else if ifde.ifd_type == DT_LONG && ifde.num_values == 1 {
                 // it's a value
-                write_u32(&mut writer, r.configs.endian, ifde.offset as u32)?;
+                write_u32(writer, endian, ifde.offset as u32)?;
             } else if ifde.ifd_type == DT_SHORT && ifde.num_values == 2 {
                 // I'm not really sure about this one. Two shorts will fit in the value_offset, but will they be interpreted correctly?
-                write_u32(&mut writer, r.configs.endian, ifde.offset as u32)?; // Value
+                write_u32(writer, endian, ifde.offset as u32)?; // Value
             } else {
                 // it's an offset
                 write_u32(
-                    &mut writer,
-                    r.configs.endian,
+                    writer,
+                    endian,
                     ifd_start as u32 + ifd_length as u32 + ifde.offset as u32,
                 )?;
             }
@@ -3316,9 +3327,9 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
         // 4-byte offset of the next IFD; Note, only single image TIFFs are currently supported
         // and therefore, this will always be set to '0'.
-        write_u32(&mut writer, r.configs.endian, 0u32)?;
+        write_u32(writer, endian, 0u32)?;
     } else {
-        write_u64(&mut writer, r.configs.endian, ifd_entries.len() as u64)?;
+        write_u64(writer, endian, ifd_entries.len() as u64)?;
 
         // Sort the IFD entries
         ifd_entries.sort_by(|a, b| a.tag.cmp(&b.tag));
@@ -3327,32 +3338,32 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
         let ifd_length = 8u64 + ifd_entries.len() as u64 * 20u64 + 8u64;
 
         for ifde in ifd_entries {
-            write_u16(&mut writer, r.configs.endian, ifde.tag)?; // Tag
-            write_u16(&mut writer, r.configs.endian, ifde.ifd_type)?; // Field type
-            write_u64(&mut writer, r.configs.endian, ifde.num_values)?; // Num of values
+            write_u16(writer, endian, ifde.tag)?; // Tag
+            write_u16(writer, endian, ifde.ifd_type)?; // Field type
+            write_u64(writer, endian, ifde.num_values)?; // Num of values
             if ifde.ifd_type == DT_SHORT && ifde.num_values == 1 {
                 // it's a value
-                write_u16(&mut writer, r.configs.endian, ifde.offset as u16)?; // Value
-                write_u16(&mut writer, r.configs.endian, 0u16)?; // Fill the remaining bytes of the u64
-                write_u32(&mut writer, r.configs.endian, 0u32)?; // Fill the remaining bytes of the u64
+                write_u16(writer, endian, ifde.offset as u16)?; // Value
+                write_u16(writer, endian, 0u16)?; // Fill the remaining bytes of the u64
+                write_u32(writer, endian, 0u32)?; // Fill the remaining bytes of the u64
             } else if ifde.ifd_type == DT_SHORT && ifde.num_values == 2 {
                 // I'm not really sure about this one. Two shorts will fit in the value_offset, but will they be interpreted correctly?
-                write_u32(&mut writer, r.configs.endian, ifde.offset as u32)?; // Value
-                write_u32(&mut writer, r.configs.endian, 0u32)?; // Fill the remaining bytes of the u64
+                write_u32(writer, endian, ifde.offset as u32)?; // Value
+                write_u32(writer, endian, 0u32)?; // Fill the remaining bytes of the u64
             } else if ifde.ifd_type == DT_LONG && ifde.num_values == 1 {
                 // it's a value
-                write_u32(&mut writer, r.configs.endian, ifde.offset as u32)?;
-                write_u32(&mut writer, r.configs.endian, 0u32)?; // Fill the remaining bytes of the u64
+                write_u32(writer, endian, ifde.offset as u32)?;
+                write_u32(writer, endian, 0u32)?; // Fill the remaining bytes of the u64
             } else if (ifde.ifd_type == DT_LONG && ifde.num_values == 2)
                 || (ifde.ifd_type == DT_TIFF_LONG8 && ifde.num_values == 1)
             {
                 // it's a value
-                write_u64(&mut writer, r.configs.endian, ifde.offset)?;
+                write_u64(writer, endian, ifde.offset)?;
             } else {
                 // it's an offset
                 write_u64(
-                    &mut writer,
-                    r.configs.endian,
+                    writer,
+                    endian,
                     ifd_start + ifd_length + ifde.offset,
                 )?;
             }
@@ -3360,14 +3371,9 @@ pub fn write_geotiff<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
         // 4-byte offset of the next IFD; Note, only single image TIFFs are currently supported
         // and therefore, this will always be set to '0'.
-        write_u64(&mut writer, r.configs.endian, 0u64)?;
+        write_u64(writer, endian, 0u64)?;
     }
 
-    //////////////////////////////////
-    // Write the larger_values_data //
-    //////////////////////////////////
-    write_bytes(&mut writer, larger_values_data.get_inner())?;
-
     Ok(())
 }
 
@@ -0,0 +1,651 @@
+/*
+This module is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+//! Reading and writing multi-band GeoTIFFs. `Raster` holds a single band, so these work on the
+//! bands of a file as a whole: a GeoTIFF is written with its bands stored one after the other
+//! (PlanarConfiguration 2), which any single-band reader, including `Raster::new`, reads as its
+//! first band.
+
+use super::ifd::{Entry, Ifd};
+use super::tiff_consts::*;
+use super::{
+    geotiff_data_type, push_georeferencing_entries, read_block, read_first_ifd,
+    read_geotiff_header, write_bytes, write_ifd_entries, write_u16, write_u32, write_u8,
+};
+use crate::{DataType, PhotometricInterpretation, RasterConfigs};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufWriter, Error, ErrorKind, Seek, SeekFrom};
+use whitebox_common::utils::{ByteOrderWriter, Endianness};
+
+/// One band of a multi-band GeoTIFF.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GeoTiffBand {
+    /// The cell values, in row-major order.
+    pub data: Vec<f64>,
+    /// What the band holds, e.g. `slope`; stored as the GDAL band description. May be empty.
+    pub description: String,
+}
+
+/// Writes bands sharing the grid, georeferencing, data type, and nodata value of `configs` to
+/// a GeoTIFF, one band after the other and one strip per row. Cells of a band that are NoData must
+/// hold `configs.nodata`, as a GeoTIFF has a single nodata value. The band descriptions are
+/// written to the GDAL metadata tag. Strips are DEFLATE compressed according to `compress`, or to
+/// the `compress_rasters` setting of settings.json when it is `None`. Files of 4 GB or more, which
+/// need a BigTIFF, are not supported.
+pub fn write_multiband_geotiff(
+    file_name: &str,
+    configs: &RasterConfigs,
+    bands: &[GeoTiffBand],
+    compress: Option<bool>,
+) -> Result<(), Error> {
+    if bands.is_empty() || bands.len() > u16::MAX as usize {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "A multi-band GeoTIFF needs between 1 and 65535 bands.",
+        ));
+    }
+    let (rows, columns) = (configs.rows, configs.columns);
+    if let Some(i) = bands.iter().position(|b| b.data.len() != rows * columns) {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Band {} has {} values, not {} ({} rows x {} columns).",
+                i + 1,
+                bands[i].data.len(),
+                rows * columns,
+                rows,
+                columns
+            ),
+        ));
+    }
+    let sample_format = match sample_format(configs.data_type) {
+        Some(format) => format,
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Multi-band GeoTIFFs of {:?} data are not supported.",
+                    configs.data_type
+                ),
+            ))
+        }
+    };
+    let bytes_per_sample = configs.data_type.get_data_size();
+    if 8 + (rows * columns * bytes_per_sample * bands.len()) as u64 >= 4_000_000_000 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Multi-band GeoTIFFs of 4 GB or more are not supported.",
+        ));
+    }
+    let use_compression = match compress {
+        Some(compress) => compress,
+        None => whitebox_common::configs::get_configs()?.compress_rasters,
+    };
+    let endian = configs.endian;
+
+    let mut writer = BufWriter::new(File::create(file_name)?);
+    match endian {
+        Endianness::LittleEndian => write_bytes(&mut writer, "II".as_bytes())?,
+        Endianness::BigEndian => write_bytes(&mut writer, "MM".as_bytes())?,
+    }
+    write_u16(&mut writer, endian, 42u16)?;
+    // The offset of the IFD, which follows the image data, is filled in once it is known.
+    write_u32(&mut writer, endian, 0u32)?;
+
+    // The strips of the first band come first, so that single-band readers see that band.
+    let mut strip_offsets = Vec::with_capacity(rows * bands.len());
+    let mut strip_byte_counts = Vec::with_capacity(rows * bands.len());
+    let mut current_offset = 8u64;
+    for band in bands {
+        for row in 0..rows {
+            let values = &band.data[row * columns..(row + 1) * columns];
+            let mut strip = encode_row(values, configs.data_type, endian)?;
+            if use_compression {
+                strip = compress_to_vec_zlib(&strip, 6);
+            }
+            write_bytes(&mut writer, &strip)?;
+            strip_offsets.push(current_offset);
+            strip_byte_counts.push(strip.len() as u64);
+            current_offset += strip.len() as u64;
+        }
+    }
+    if current_offset >= u32::MAX as u64 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Multi-band GeoTIFFs of 4 GB or more are not supported.",
+        ));
+    }
+    // The IFD must start on a word boundary.
+    if current_offset % 2 == 1 {
+        write_u8(&mut writer, 0u8)?;
+        current_offset += 1;
+    }
+    let ifd_start = current_offset;
+    writer.seek(SeekFrom::Start(4))?;
+    write_u32(&mut writer, endian, ifd_start as u32)?;
+    writer.seek(SeekFrom::End(0))?;
+
+    let num_bands = bands.len();
+    let mut ifd_entries: Vec<Entry> = vec![];
+    let mut larger_values_data = ByteOrderWriter::<Vec<u8>>::new(vec![], endian);
+    let data = &mut larger_values_data;
+    ifd_entries.push(Entry::new(TAG_IMAGEWIDTH, DT_LONG, 1u64, columns as u64));
+    ifd_entries.push(Entry::new(TAG_IMAGELENGTH, DT_LONG, 1u64, rows as u64));
+    let bits_per_sample = (bytes_per_sample * 8) as u16;
+    push_shorts(
+        &mut ifd_entries,
+        data,
+        TAG_BITSPERSAMPLE,
+        &vec![bits_per_sample; num_bands],
+        endian,
+    )?;
+    let compression = if use_compression {
+        COMPRESS_DEFLATE
+    } else {
+        COMPRESS_NONE
+    };
+    ifd_entries.push(Entry::new(
+        TAG_COMPRESSION,
+        DT_SHORT,
+        1u64,
+        compression as u64,
+    ));
+    ifd_entries.push(Entry::new(
+        TAG_PHOTOMETRICINTERPRETATION,
+        DT_SHORT,
+        1u64,
+        PI_BLACKISZERO as u64,
+    ));
+    push_longs(&mut ifd_entries, data, TAG_STRIPOFFSETS, &strip_offsets)?;
+    ifd_entries.push(Entry::new(
+        TAG_SAMPLESPERPIXEL,
+        DT_SHORT,
+        1u64,
+        num_bands as u64,
+    ));
+    ifd_entries.push(Entry::new(TAG_ROWSPERSTRIP, DT_SHORT, 1u64, 1u64));
+    push_longs(
+        &mut ifd_entries,
+        data,
+        TAG_STRIPBYTECOUNTS,
+        &strip_byte_counts,
+    )?;
+    ifd_entries.push(Entry::new(TAG_PLANARCONFIGURATION, DT_SHORT, 1u64, 2u64));
+    if num_bands > 1 {
+        // The bands after the first are of unspecified meaning, rather than e.g. alpha.
+        push_shorts(
+            &mut ifd_entries,
+            data,
+            TAG_EXTRASAMPLES,
+            &vec![0u16; num_bands - 1],
+            endian,
+        )?;
+    }
+    push_shorts(
+        &mut ifd_entries,
+        data,
+        TAG_SAMPLEFORMAT,
+        &vec![sample_format; num_bands],
+        endian,
+    )?;
+
+    // There is no support for storing the image resolution, so give 72x72 dpi, as write_geotiff does.
+    for tag in [TAG_XRESOLUTION, TAG_YRESOLUTION] {
+        ifd_entries.push(Entry::new(tag, DT_RATIONAL, 1u64, data.len() as u64));
+        data.write_u32(72u32)?;
+        data.write_u32(1u32)?;
+    }
+    ifd_entries.push(Entry::new(TAG_RESOLUTIONUNIT, DT_SHORT, 1u64, 2u64));
+    push_ascii(&mut ifd_entries, data, TAG_SOFTWARE, "WhiteboxTools")?;
+    if bands.iter().any(|b| !b.description.is_empty()) {
+        push_ascii(
+            &mut ifd_entries,
+            data,
+            TAG_GDAL_METADATA,
+            &gdal_metadata(bands),
+        )?;
+    }
+    push_georeferencing_entries(configs, false, &mut ifd_entries, data)?;
+
+    write_ifd_entries(&mut writer, endian, false, ifd_entries, ifd_start)?;
+    write_bytes(&mut writer, larger_values_data.get_inner())?;
+    Ok(())
+}
+
+/// Reads every band of a GeoTIFF, along with its configs, in which `bands` is the number of bands
+/// (at most 255) and `data_type` that of each sample. Descriptions are read from the GDAL metadata
+/// tag. Both pixel-interleaved and band-sequential files, in strips or tiles, are supported, but
+/// not predictors; each band's values are converted to `f64` and missing (sparse) blocks read as
+/// NoData.
+pub fn read_multiband_geotiff(file_name: &str) -> Result<(RasterConfigs, Vec<GeoTiffBand>), Error> {
+    let mut configs = RasterConfigs::default();
+    read_geotiff_header(&file_name.to_string(), &mut configs)?;
+    let mut endian = configs.endian;
+    let (mut th, ifd_map) = read_first_ifd(file_name, &mut endian)?;
+    let unsupported =
+        |what: &str| Error::new(ErrorKind::InvalidData, format!("{} {}", what, file_name));
+
+    let shorts = |tag: u16| ifd_map.get(&tag).map(|ifd| ifd.interpret_as_u16());
+    let num_bands = shorts(TAG_SAMPLESPERPIXEL).map_or(1, |v| v[0] as usize);
+    let planar = shorts(TAG_PLANARCONFIGURATION).map_or(1, |v| v[0]);
+    let bits_per_sample = shorts(TAG_BITSPERSAMPLE).unwrap_or_else(|| vec![1]);
+    // The sample format defaults to unsigned integers.
+    let sample_format = shorts(TAG_SAMPLEFORMAT).unwrap_or_else(|| vec![1]);
+    let compression = shorts(TAG_COMPRESSION).map_or(COMPRESS_NONE, |v| v[0]);
+    if shorts(TAG_PREDICTOR).map_or(1, |v| v[0]) != 1 {
+        return Err(unsupported(
+            "Multi-band reads do not support a TIFF predictor, used by",
+        ));
+    }
+    let (bits, format) = (bits_per_sample[0], sample_format[0]);
+    if bits_per_sample.iter().any(|&b| b != bits) || sample_format.iter().any(|&f| f != format) {
+        return Err(unsupported(
+            "Multi-band reads need every band to have the same data type, unlike",
+        ));
+    }
+    configs.data_type = geotiff_data_type(IM_GRAY, &[format], &[bits])?;
+    configs.bands = num_bands.min(u8::MAX as usize) as u8;
+    configs.photometric_interp = PhotometricInterpretation::Continuous;
+
+    let (rows, columns) = (configs.rows, configs.columns);
+    let (block_width, block_height, offsets, counts) = if ifd_map.contains_key(&TAG_TILEWIDTH) {
+        (
+            tag_value(&ifd_map, TAG_TILEWIDTH)? as usize,
+            tag_value(&ifd_map, TAG_TILELENGTH)? as usize,
+            tag_values(&ifd_map, TAG_TILEOFFSETS)?,
+            tag_values(&ifd_map, TAG_TILEBYTECOUNTS)?,
+        )
+    } else {
+        let rows_per_strip = match ifd_map.get(&TAG_ROWSPERSTRIP) {
+            Some(_) => (tag_value(&ifd_map, TAG_ROWSPERSTRIP)? as usize).min(rows),
+            None => rows,
+        };
+        (
+            columns,
+            rows_per_strip,
+            tag_values(&ifd_map, TAG_STRIPOFFSETS)?,
+            tag_values(&ifd_map, TAG_STRIPBYTECOUNTS)?,
+        )
+    };
+    if block_width == 0 || block_height == 0 {
+        return Err(unsupported("Invalid strip or tile size in"));
+    }
+    let blocks_across = (columns + block_width - 1) / block_width;
+    let blocks_down = (rows + block_height - 1) / block_height;
+    let blocks_per_plane = blocks_across * blocks_down;
+    let (planes, samples_per_block) = if planar == 2 {
+        (num_bands, 1)
+    } else {
+        (1, num_bands)
+    };
+    if offsets.len() < planes * blocks_per_plane || counts.len() < planes * blocks_per_plane {
+        return Err(unsupported("Missing strip or tile offsets in"));
+    }
+
+    let bytes_per_sample = bits as usize / 8;
+    let is_le = endian == Endianness::LittleEndian;
+    let mut bands = vec![
+        GeoTiffBand {
+            data: vec![configs.nodata; rows * columns],
+            description: String::new(),
+        };
+        num_bands
+    ];
+    for plane in 0..planes {
+        for block in 0..blocks_per_plane {
+            let index = plane * blocks_per_plane + block;
+            if counts[index] == 0 {
+                continue;
+            }
+            let max_length = block_width * block_height * samples_per_block * bytes_per_sample;
+            let buf = read_block(
+                &mut th,
+                offsets[index] as usize,
+                counts[index] as usize,
+                compression,
+                max_length,
+            )?;
+            let (row0, col0) = (
+                block / blocks_across * block_height,
+                block % blocks_across * block_width,
+            );
+            for y in 0..block_height.min(rows - row0) {
+                for x in 0..block_width.min(columns - col0) {
+                    for s in 0..samples_per_block {
+                        let pos =
+                            ((y * block_width + x) * samples_per_block + s) * bytes_per_sample;
+                        let bytes = match buf.get(pos..pos + bytes_per_sample) {
+                            Some(bytes) => bytes,
+                            None => return Err(unsupported("Truncated image data in")),
+                        };
+                        let band = if planar == 2 { plane } else { s };
+                        bands[band].data[(row0 + y) * columns + col0 + x] =
+                            decode_sample(bytes, is_le, format);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(ifd) = ifd_map.get(&TAG_GDAL_METADATA) {
+        for (band, description) in band_descriptions(&ifd.interpret_as_ascii()) {
+            if band < num_bands {
+                bands[band].description = description;
+            }
+        }
+    }
+    Ok((configs, bands))
+}
+
+/// The TIFF SampleFormat of a single-sample data type: 1 for unsigned integers, 2 for signed
+/// integers, and 3 for floating point.
+fn sample_format(data_type: DataType) -> Option<u16> {
+    match data_type {
+        DataType::U8 | DataType::U16 | DataType::U32 | DataType::U64 => Some(1),
+        DataType::I8 | DataType::I16 | DataType::I32 | DataType::I64 => Some(2),
+        DataType::F32 | DataType::F64 => Some(3),
+        _ => None,
+    }
+}
+
+/// Encodes a row of values as samples of the data type, which must be a single-sample type.
+fn encode_row(values: &[f64], data_type: DataType, endian: Endianness) -> Result<Vec<u8>, Error> {
+    let mut bow = ByteOrderWriter::<Vec<u8>>::new(
+        Vec::with_capacity(values.len() * data_type.get_data_size()),
+        endian,
+    );
+    for &z in values {
+        match data_type {
+            DataType::U8 => bow.write_u8(z as u8)?,
+            DataType::U16 => bow.write_u16(z as u16)?,
+            DataType::U32 => bow.write_u32(z as u32)?,
+            DataType::U64 => bow.write_u64(z as u64)?,
+            DataType::I8 => bow.write_i8(z as i8)?,
+            DataType::I16 => bow.write_i16(z as i16)?,
+            DataType::I32 => bow.write_i32(z as i32)?,
+            DataType::I64 => bow.write_i64(z as i64)?,
+            DataType::F32 => bow.write_f32(z as f32)?,
+            DataType::F64 => bow.write_f64(z)?,
+            _ => unreachable!("Only single-sample data types are encoded."),
+        }
+    }
+    Ok(bow.into_inner())
+}
+
+/// Decodes a sample of 1, 2, 4, or 8 bytes in the given SampleFormat.
+fn decode_sample(bytes: &[u8], is_le: bool, format: u16) -> f64 {
+    macro_rules! decode {
+        ($t:ty) => {{
+            let b = bytes.try_into().unwrap();
+            (if is_le {
+                <$t>::from_le_bytes(b)
+            } else {
+                <$t>::from_be_bytes(b)
+            }) as f64
+        }};
+    }
+    match (format, bytes.len()) {
+        (2, 1) => bytes[0] as i8 as f64,
+        (2, 2) => decode!(i16),
+        (2, 4) => decode!(i32),
+        (2, 8) => decode!(i64),
+        (3, 4) => decode!(f32),
+        (3, 8) => decode!(f64),
+        (_, 1) => bytes[0] as f64,
+        (_, 2) => decode!(u16),
+        (_, 4) => decode!(u32),
+        _ => decode!(u64),
+    }
+}
+
+/// The values of an integer tag, which may be stored as SHORT, LONG, or LONG8 values.
+fn tag_values(ifd_map: &HashMap<u16, Ifd>, tag: u16) -> Result<Vec<u64>, Error> {
+    match ifd_map.get(&tag) {
+        Some(ifd) if ifd.ifd_type == DT_SHORT => {
+            Ok(ifd.interpret_as_u16().iter().map(|&v| v as u64).collect())
+        }
+        Some(ifd) if ifd.ifd_type == DT_LONG => {
+            Ok(ifd.interpret_as_u32().iter().map(|&v| v as u64).collect())
+        }
+        Some(ifd) if ifd.ifd_type == DT_TIFF_LONG8 => Ok(ifd.interpret_as_u64()),
+        _ => Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("The TIFF tag {} was not read correctly", tag),
+        )),
+    }
+}
+
+/// The first value of an integer tag.
+fn tag_value(ifd_map: &HashMap<u16, Ifd>, tag: u16) -> Result<u64, Error> {
+    tag_values(ifd_map, tag)?.first().copied().ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("The TIFF tag {} was not read correctly", tag),
+        )
+    })
+}
+
+/// Adds a SHORT tag to a classic TIFF IFD; `write_ifd_entries` expects one or two values in the
+/// entry itself and more at an offset into `data`.
+fn push_shorts(
+    ifd_entries: &mut Vec<Entry>,
+    data: &mut ByteOrderWriter<Vec<u8>>,
+    tag: u16,
+    values: &[u16],
+    endian: Endianness,
+) -> Result<(), Error> {
+    match values.len() {
+        1 => ifd_entries.push(Entry::new(tag, DT_SHORT, 1u64, values[0] as u64)),
+        2 => {
+            // The entry is written as a u32 in the file's byte order, so pack the values so that
+            // the first comes first in the file.
+            let (first, second) = (values[0] as u64, values[1] as u64);
+            let packed = match endian {
+                Endianness::LittleEndian => first | second << 16,
+                Endianness::BigEndian => first << 16 | second,
+            };
+            ifd_entries.push(Entry::new(tag, DT_SHORT, 2u64, packed));
+        }
+        n => {
+            ifd_entries.push(Entry::new(tag, DT_SHORT, n as u64, data.len() as u64));
+            for &v in values {
+                data.write_u16(v)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds a LONG tag to a classic TIFF IFD, with a single value in the entry itself and more at an
+/// offset into `data`.
+fn push_longs(
+    ifd_entries: &mut Vec<Entry>,
+    data: &mut ByteOrderWriter<Vec<u8>>,
+    tag: u16,
+    values: &[u64],
+) -> Result<(), Error> {
+    if values.len() == 1 {
+        ifd_entries.push(Entry::new(tag, DT_LONG, 1u64, values[0]));
+    } else {
+        ifd_entries.push(Entry::new(
+            tag,
+            DT_LONG,
+            values.len() as u64,
+            data.len() as u64,
+        ));
+        for &v in values {
+            data.write_u32(v as u32)?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds an ASCII tag, of more than four bytes, stored at an offset into `data`.
+fn push_ascii(
+    ifd_entries: &mut Vec<Entry>,
+    data: &mut ByteOrderWriter<Vec<u8>>,
+    tag: u16,
+    text: &str,
+) -> Result<(), Error> {
+    let mut bytes = text.as_bytes().to_vec();
+    bytes.push(0);
+    ifd_entries.push(Entry::new(
+        tag,
+        DT_ASCII,
+        bytes.len() as u64,
+        data.len() as u64,
+    ));
+    if bytes.len() % 2 == 1 {
+        // it has to end on a word so that the next value starts on a word
+        bytes.push(0);
+    }
+    data.write_bytes(&bytes)
+}
+
+/// The GDAL metadata XML that holds the band descriptions.
+fn gdal_metadata(bands: &[GeoTiffBand]) -> String {
+    let mut xml = "<GDALMetadata>\n".to_string();
+    for (i, band) in bands.iter().enumerate() {
+        if !band.description.is_empty() {
+            xml.push_str(&format!(
+                "  <Item name=\"DESCRIPTION\" sample=\"{}\" role=\"description\">{}</Item>\n",
+                i,
+                escape_xml(&band.description)
+            ));
+        }
+    }
+    xml.push_str("</GDALMetadata>");
+    xml
+}
+
+/// The band descriptions in GDAL metadata XML, as (zero-based band, description) pairs.
+fn band_descriptions(xml: &str) -> Vec<(usize, String)> {
+    let mut descriptions = vec![];
+    for item in xml.split("<Item ").skip(1) {
+        let (attributes, rest) = match item.split_once('>') {
+            Some(parts) => parts,
+            None => continue,
+        };
+        if !attributes.contains("name=\"DESCRIPTION\"")
+            || !attributes.contains("role=\"description\"")
+        {
+            continue;
+        }
+        let band = attributes
+            .split("sample=\"")
+            .nth(1)
+            .and_then(|s| s.split('"').next())
+            .and_then(|s| s.parse::<usize>().ok());
+        if let (Some(band), Some((text, _))) = (band, rest.split_once("</Item>")) {
+            descriptions.push((band, unescape_xml(text)));
+        }
+    }
+    descriptions
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod test {
+    use super::{band_descriptions, read_multiband_geotiff, write_multiband_geotiff, GeoTiffBand};
+    use crate::{DataType, PhotometricInterpretation, Raster, RasterConfigs};
+    use std::env::temp_dir;
+    use std::fs;
+
+    fn configs(data_type: DataType) -> RasterConfigs {
+        RasterConfigs {
+            rows: 3,
+            columns: 4,
+            north: 4015.0,
+            south: 4000.0,
+            east: 520.0,
+            west: 500.0,
+            resolution_x: 5.0,
+            resolution_y: 5.0,
+            nodata: -9999.0,
+            data_type,
+            photometric_interp: PhotometricInterpretation::Continuous,
+            epsg_code: 32611,
+            ..Default::default()
+        }
+    }
+
+    fn band(offset: f64, description: &str, nodata: f64) -> GeoTiffBand {
+        let mut data: Vec<f64> = (0..12).map(|i| offset + i as f64).collect();
+        data[5] = nodata;
+        GeoTiffBand {
+            data,
+            description: description.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let dir = temp_dir().join(format!("wbr_multiband_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        for (data_type, num_bands) in [(DataType::F32, 3), (DataType::I16, 2), (DataType::U8, 1)] {
+            for compress in [false, true] {
+                let file = dir
+                    .join(format!("{:?}_{}_{}.tif", data_type, num_bands, compress))
+                    .to_string_lossy()
+                    .to_string();
+                let mut configs = configs(data_type);
+                if data_type == DataType::U8 {
+                    configs.nodata = 255.0;
+                }
+                let bands: Vec<GeoTiffBand> = ["slope", "", "a <b> & \"c\""]
+                    .iter()
+                    .enumerate()
+                    .take(num_bands)
+                    .map(|(i, d)| band(10.0 * i as f64, d, configs.nodata))
+                    .collect();
+                write_multiband_geotiff(&file, &configs, &bands, Some(compress)).unwrap();
+
+                let (read_configs, read_bands) = read_multiband_geotiff(&file).unwrap();
+                assert_eq!(read_configs.bands as usize, num_bands);
+                assert_eq!(read_configs.data_type, data_type);
+                assert_eq!(read_configs.nodata, configs.nodata);
+                assert_eq!((read_configs.rows, read_configs.columns), (3, 4));
+                assert_eq!(read_configs.west, 500.0);
+                assert_eq!(read_configs.north, 4015.0);
+                assert_eq!(read_configs.epsg_code, 32611);
+                assert_eq!(read_bands, bands);
+
+                // Single-band readers see the first band.
+                let first = Raster::new(&file, "r").unwrap();
+                assert_eq!(first.get_value(0, 3), 3.0);
+                assert_eq!(first.get_value(1, 1), configs.nodata);
+            }
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_band_descriptions() {
+        let xml = "<GDALMetadata>\n  <Item name=\"STATISTICS_MEAN\" sample=\"0\">1.5</Item>\n  <Item name=\"DESCRIPTION\" sample=\"1\" role=\"description\">aspect &amp; slope</Item>\n</GDALMetadata>";
+        assert_eq!(
+            band_descriptions(xml),
+            vec![(1, "aspect & slope".to_string())]
+        );
+    }
+}
@@ -23,12 +23,15 @@ mod idrisi_raster;
 mod saga_raster;
 mod surfer7_raster;
 mod surfer_ascii_raster;
+#[cfg(feature = "test-support")]
+pub mod test_support;
 mod whitebox_raster;
 
 use self::arcascii_raster::*;
 use self::arcbinary_raster::*;
 use self::esri_bil::*;
 use self::geotiff::*;
+pub use self::geotiff::multiband::{read_multiband_geotiff, write_multiband_geotiff, GeoTiffBand};
 use self::grass_raster::*;
 use self::idrisi_raster::*;
 use self::saga_raster::*;
@@ -49,6 +52,9 @@ use std::path::Path;
 use std::sync::mpsc;
 use std::sync::Arc;
 use std::thread;
+use whitebox_common::algorithms::{
+    sample_grid, GridGeometry, InterpolationMethod, NodataPolicy,
+};
 use whitebox_common::structures::{Array2D, BoundingBox};
 use whitebox_common::utils::*;
 // use rayon::prelude::*;
@@ -74,6 +80,7 @@ pub struct Raster {
     pub raster_type: RasterType,
     pub configs: RasterConfigs,
     data: Vec<f64>,
+    compression: Option<bool>,
 }
 
 impl Index<(isize, isize)> for Raster {
@@ -201,6 +208,45 @@ impl Raster {
         // Err(Error::new(ErrorKind::Other, "Error creating raster"))
     }
 
+    /// Reads only the header/metadata of an existing raster file and returns its
+    /// `RasterConfigs`. No pixel data are loaded: for GeoTIFFs only the TIFF
+    /// header and the first IFD are parsed, and for Whitebox (.dep/.tas) and
+    /// ArcGIS binary (.hdr/.flt) rasters the data file is never opened. The
+    /// configs match those of `Raster::new(file_name, "r")`, except that
+    /// statistics computed from the pixels (the GeoTIFF and ArcGIS binary
+    /// minimum/maximum, and any display range derived from them) are left at
+    /// their defaults. Formats whose header
+    /// cannot be separated from the data return an `ErrorKind::InvalidInput`
+    /// error; callers that can afford a full read should fall back to `Raster::new`.
+    pub fn open_header<'a>(file_name: &'a str) -> Result<RasterConfigs, Error> {
+        let mut configs: RasterConfigs = Default::default();
+        let file_name = file_name.to_string();
+        match get_raster_type_from_file(file_name.clone(), "r".to_string()) {
+            RasterType::ArcBinary => read_arcbinary_header(&file_name, &mut configs)?,
+            RasterType::GeoTiff => read_geotiff_header(&file_name, &mut configs)?,
+            RasterType::Whitebox => read_whitebox_header(&file_name, &mut configs)?,
+            RasterType::Unknown => {
+                return Err(Error::new(ErrorKind::Other, "Unrecognized raster type"));
+            }
+            raster_type => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Header-only reads are not supported for {:?} rasters.",
+                        raster_type
+                    ),
+                ));
+            }
+        }
+
+        // Mirror the NaN/Inf nodata substitution performed by Raster::new.
+        if configs.nodata.is_nan() || configs.nodata.is_infinite() {
+            configs.nodata = -32768.0;
+        }
+
+        Ok(configs)
+    }
+
     /// Creates a new in-memory `Raster` object with grid extent and location
     /// based on specified configurations contained within a `RasterConfigs`.
     pub fn initialize_using_config<'a>(file_name: &'a str, configs: &'a RasterConfigs) -> Raster {
@@ -508,6 +554,44 @@ impl Raster {
         self.configs.nodata
     }
 
+    /// Samples the raster at map coordinate (`x`, `y`) using bilinear interpolation
+    /// between the surrounding cell centres. Returns `None` if the point falls outside
+    /// the raster extent or if any contributing cell is nodata.
+    pub fn sample_bilinear(&self, x: f64, y: f64) -> Option<f64> {
+        self.sample_interpolated(x, y, InterpolationMethod::Bilinear, NodataPolicy::Strict)
+    }
+
+    /// Samples the raster at map coordinate (`x`, `y`) using cubic convolution over
+    /// the surrounding 4 x 4 cells. Returns `None` if the point falls outside the
+    /// raster extent or if any contributing cell is nodata.
+    pub fn sample_cubic(&self, x: f64, y: f64) -> Option<f64> {
+        self.sample_interpolated(x, y, InterpolationMethod::Cubic, NodataPolicy::Strict)
+    }
+
+    /// Samples the raster at map coordinate (`x`, `y`) with an explicit interpolation
+    /// method and nodata policy.
+    pub fn sample_interpolated(
+        &self,
+        x: f64,
+        y: f64,
+        method: InterpolationMethod,
+        policy: NodataPolicy,
+    ) -> Option<f64> {
+        let geometry = GridGeometry {
+            rows: self.configs.rows,
+            columns: self.configs.columns,
+            west: self.configs.west,
+            north: self.configs.north,
+            resolution_x: self.configs.resolution_x,
+            resolution_y: self.configs.resolution_y,
+            nodata: self.configs.nodata,
+        };
+        let columns = self.configs.columns;
+        sample_grid(&geometry, x, y, method, policy, |row, column| {
+            self.data[row as usize * columns + column as usize]
+        })
+    }
+
     pub fn set_value(&mut self, row: isize, column: isize, value: f64) {
         if column >= 0 && row >= 0 {
             let c: usize = column as usize;
@@ -659,6 +743,53 @@ impl Raster {
         data
     }
 
+    /// Returns an Array2D with the raster's dimensions, in which each cell is `value` where
+    /// `predicate` holds on the corresponding raster value and `background` elsewhere, e.g.
+    /// `raster.get_threshold_as_array2d(|z| z != nodata && z > 0f64, 1u8, 0u8)`.
+    pub fn get_threshold_as_array2d<T, F>(
+        &self,
+        predicate: F,
+        value: T,
+        background: T,
+    ) -> Array2D<T>
+    where
+        T: Copy + AddAssign + SubAssign,
+        F: Fn(f64) -> bool,
+    {
+        Array2D::from_threshold(
+            self.configs.rows as isize,
+            self.configs.columns as isize,
+            &self.data,
+            predicate,
+            value,
+            background,
+        )
+        .unwrap()
+    }
+
+    /// Sets to `value` each cell of `array` for which `predicate` holds on the corresponding
+    /// raster value, returning the number of cells that were set.
+    pub fn fill_array2d_where<T, F>(
+        &self,
+        array: &mut Array2D<T>,
+        predicate: F,
+        value: T,
+    ) -> Result<usize, Error>
+    where
+        T: Copy + AddAssign + SubAssign,
+        F: Fn(f64) -> bool,
+    {
+        if array.rows != self.configs.rows as isize
+            || array.columns != self.configs.columns as isize
+        {
+            return Err(Error::new(
+                ErrorKind::Other,
+                "Rasters must have the same dimensions and extent.",
+            ));
+        }
+        array.fill_where_values(&self.data, predicate, value)
+    }
+
     pub fn set_data_from_array2d<'a, T: Into<f64> + Copy + AddAssign + SubAssign>(
         &mut self,
         array: &'a Array2D<T>,
@@ -1216,6 +1347,17 @@ impl Raster {
         Ok(())
     }
 
+    /// Overrides the `compress_rasters` setting of settings.json when this raster is
+    /// written as a GeoTIFF. `None` (the default) uses the global setting.
+    pub fn set_compression(&mut self, compress: Option<bool>) {
+        self.compression = compress;
+    }
+
+    /// Returns the compression override set by `set_compression`, if any.
+    pub fn get_compression(&self) -> Option<bool> {
+        self.compression
+    }
+
     pub fn add_metadata_entry(&mut self, value: String) {
         self.configs.metadata.push(value);
     }
@@ -1545,3 +1687,79 @@ impl Default for PhotometricInterpretation {
         PhotometricInterpretation::Unknown
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{DataType, PhotometricInterpretation, Raster, RasterConfigs};
+    use std::env::temp_dir;
+    use std::fs;
+
+    fn write_test_raster(file_name: &str) -> RasterConfigs {
+        let mut configs = RasterConfigs {
+            rows: 4,
+            columns: 5,
+            north: 4020.0,
+            south: 4000.0,
+            east: 525.0,
+            west: 500.0,
+            resolution_x: 5.0,
+            resolution_y: 5.0,
+            nodata: -9999.0,
+            data_type: DataType::F32,
+            photometric_interp: PhotometricInterpretation::Continuous,
+            epsg_code: 32611,
+            ..Default::default()
+        };
+        configs.projection = "not specified".to_string();
+        let mut raster = Raster::initialize_using_config(file_name, &configs);
+        for row in 0..4isize {
+            for col in 0..5isize {
+                raster.set_value(row, col, (row * 10 + col) as f64);
+            }
+        }
+        raster.set_value(3, 4, -9999.0);
+        raster.write().expect("Error writing test raster");
+        configs
+    }
+
+    fn assert_same_configs(file_name: &str, pixel_statistics: bool) {
+        let full = Raster::new(file_name, "r")
+            .expect("Error reading test raster")
+            .configs;
+        let mut header = Raster::open_header(file_name).expect("Error reading test raster header");
+        assert_eq!(header.rows, 4);
+        assert_eq!(header.columns, 5);
+        if pixel_statistics {
+            // These are computed from the pixels by a full read.
+            header.minimum = full.minimum;
+            header.maximum = full.maximum;
+            header.display_min = full.display_min;
+            header.display_max = full.display_max;
+        }
+        assert_eq!(format!("{:?}", header), format!("{:?}", full));
+    }
+
+    #[test]
+    fn test_open_header_matches_full_read() {
+        let dir = temp_dir().join(format!("wbr_open_header_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let tif = dir.join("header.tif").to_string_lossy().to_string();
+        write_test_raster(&tif);
+        assert_same_configs(&tif, true);
+
+        let dep = dir.join("header.dep").to_string_lossy().to_string();
+        write_test_raster(&dep);
+        assert_same_configs(&dep, false);
+
+        let flt = dir.join("header.flt").to_string_lossy().to_string();
+        write_test_raster(&flt);
+        assert_same_configs(&flt, true);
+
+        let asc = dir.join("header.asc").to_string_lossy().to_string();
+        write_test_raster(&asc);
+        assert!(Raster::open_header(&asc).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
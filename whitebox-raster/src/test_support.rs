@@ -0,0 +1,43 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+// Raster fixtures for the tests of tools in other crates; enabled with the `test-support` feature
+// in their dev-dependencies.
+
+use super::{DataType, PhotometricInterpretation, Raster, RasterConfigs};
+use whitebox_common::test_support::GridFixture;
+
+/// Creates a new in-memory, 32-bit floating point `Raster` holding the grid of a test fixture.
+pub fn grid_fixture_raster(file_name: &str, grid: &GridFixture) -> Raster {
+    let configs = RasterConfigs {
+        rows: grid.rows,
+        columns: grid.columns,
+        north: grid.north(),
+        south: grid.south,
+        east: grid.east(),
+        west: grid.west,
+        resolution_x: grid.resolution,
+        resolution_y: grid.resolution,
+        nodata: grid.nodata,
+        data_type: DataType::F32,
+        photometric_interp: if grid.categorical {
+            PhotometricInterpretation::Categorical
+        } else {
+            PhotometricInterpretation::Continuous
+        },
+        epsg_code: grid.epsg_code,
+        ..Default::default()
+    };
+    let mut output = Raster::initialize_using_config(file_name, &configs);
+    for row in 0..grid.rows as isize {
+        for col in 0..grid.columns as isize {
+            output.set_value(row, col, grid.value(row, col));
+        }
+    }
+    output
+}
@@ -15,107 +15,7 @@ pub fn read_whitebox(
     configs: &mut RasterConfigs,
     data: &mut Vec<f64>,
 ) -> Result<(), Error> {
-    // read the header file
-    // let header_file = file_name.replace(".tas", ".dep");
-    let header_file = Path::new(&file_name)
-        .with_extension("dep")
-        .into_os_string()
-        .into_string()
-        .unwrap();
-    let f = File::open(header_file)?;
-    let f = BufReader::new(f);
-
-    for line in f.lines() {
-        let line_unwrapped = line.unwrap();
-        // println!("{}", line_unwrapped);
-        let line_split = line_unwrapped.split(":");
-        let vec = line_split.collect::<Vec<&str>>();
-        if vec[0].to_lowercase().contains("rows") {
-            configs.rows = vec[1].trim().parse::<f32>().unwrap() as usize;
-        } else if vec[0].to_lowercase().contains("col") {
-            configs.columns = vec[1].trim().parse::<f32>().unwrap() as usize;
-        } else if vec[0].to_lowercase().contains("stacks") {
-            configs.bands = vec[1].trim().to_string().parse::<u8>().unwrap();
-        } else if vec[0].to_lowercase().contains("north") {
-            configs.north = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("south") {
-            configs.south = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("east") {
-            configs.east = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("west") {
-            configs.west = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("display min") {
-            configs.display_min = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("display max") {
-            configs.display_max = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("min")
-            && !vec[0].to_lowercase().contains("display")
-        {
-            configs.minimum = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("max")
-            && !vec[0].to_lowercase().contains("display")
-        {
-            configs.maximum = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("data type") {
-            if vec[1].trim().to_lowercase().to_string().contains("double") {
-                configs.data_type = DataType::F64;
-            } else if vec[1].trim().to_lowercase().to_string().contains("float") {
-                configs.data_type = DataType::F32;
-            } else if vec[1].trim().to_lowercase().to_string().contains("integer") {
-                configs.data_type = DataType::I16;
-            } else if vec[1].trim().to_lowercase().to_string().contains("byte") {
-                configs.data_type = DataType::U8;
-            } else if vec[1].trim().to_lowercase().to_string().contains("i32") {
-                configs.data_type = DataType::I32;
-            }
-        } else if vec[0].to_lowercase().contains("data scale") {
-            if vec[1]
-                .trim()
-                .to_lowercase()
-                .to_string()
-                .contains("continuous")
-            {
-                configs.photometric_interp = PhotometricInterpretation::Continuous;
-            } else if vec[1]
-                .trim()
-                .to_lowercase()
-                .to_string()
-                .contains("categorical")
-            {
-                configs.photometric_interp = PhotometricInterpretation::Categorical;
-            } else if vec[1].trim().to_lowercase().to_string().contains("boolean") {
-                configs.photometric_interp = PhotometricInterpretation::Boolean;
-            } else if vec[1].trim().to_lowercase().to_string().contains("rgb") {
-                configs.photometric_interp = PhotometricInterpretation::RGB;
-                configs.data_type = DataType::RGBA32;
-            }
-        } else if vec[0].to_lowercase().contains("z units") {
-            configs.z_units = vec[1].trim().to_string();
-        } else if vec[0].to_lowercase().contains("xy units") {
-            configs.xy_units = vec[1].trim().to_string();
-        } else if vec[0].to_lowercase().contains("projection") {
-            configs.projection = vec[1].trim().to_string();
-        } else if vec[0].to_lowercase().contains("nodata") {
-            configs.nodata = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("preferred palette") {
-            configs.palette = vec[1].trim().to_string();
-        } else if vec[0].to_lowercase().contains("nonlinearity") {
-            configs.palette_nonlinearity = vec[1].trim().to_string().parse::<f64>().unwrap();
-        } else if vec[0].to_lowercase().contains("byte order") {
-            if vec[1].trim().to_lowercase().contains("little")
-                || vec[1].trim().to_lowercase().contains("lsb")
-            {
-                configs.endian = Endianness::LittleEndian;
-            } else {
-                configs.endian = Endianness::BigEndian;
-            }
-        } else if vec[0].to_lowercase().contains("metadata") {
-            configs.metadata.push(vec[1].trim().to_string());
-        }
-    }
-
-    configs.resolution_x = (configs.east - configs.west) / configs.columns as f64;
-    configs.resolution_y = (configs.north - configs.south) / configs.rows as f64;
+    read_whitebox_header(file_name, configs)?;
 
     // read the data file
     // let data_file = file_name.replace(".dep", ".tas");
@@ -492,3 +392,111 @@ pub fn write_whitebox<'a>(r: &'a mut Raster) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Reads the Whitebox header (.dep) file into `configs` without opening the
+/// data (.tas) file.
+pub fn read_whitebox_header(file_name: &String, configs: &mut RasterConfigs) -> Result<(), Error> {
+    // read the header file
+    // let header_file = file_name.replace(".tas", ".dep");
+    let header_file = Path::new(&file_name)
+        .with_extension("dep")
+        .into_os_string()
+        .into_string()
+        .unwrap();
+    let f = File::open(header_file)?;
+    let f = BufReader::new(f);
+
+    for line in f.lines() {
+        let line_unwrapped = line.unwrap();
+        // println!("{}", line_unwrapped);
+        let line_split = line_unwrapped.split(":");
+        let vec = line_split.collect::<Vec<&str>>();
+        if vec[0].to_lowercase().contains("rows") {
+            configs.rows = vec[1].trim().parse::<f32>().unwrap() as usize;
+        } else if vec[0].to_lowercase().contains("col") {
+            configs.columns = vec[1].trim().parse::<f32>().unwrap() as usize;
+        } else if vec[0].to_lowercase().contains("stacks") {
+            configs.bands = vec[1].trim().to_string().parse::<u8>().unwrap();
+        } else if vec[0].to_lowercase().contains("north") {
+            configs.north = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("south") {
+            configs.south = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("east") {
+            configs.east = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("west") {
+            configs.west = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("display min") {
+            configs.display_min = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("display max") {
+            configs.display_max = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("min")
+            && !vec[0].to_lowercase().contains("display")
+        {
+            configs.minimum = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("max")
+            && !vec[0].to_lowercase().contains("display")
+        {
+            configs.maximum = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("data type") {
+            if vec[1].trim().to_lowercase().to_string().contains("double") {
+                configs.data_type = DataType::F64;
+            } else if vec[1].trim().to_lowercase().to_string().contains("float") {
+                configs.data_type = DataType::F32;
+            } else if vec[1].trim().to_lowercase().to_string().contains("integer") {
+                configs.data_type = DataType::I16;
+            } else if vec[1].trim().to_lowercase().to_string().contains("byte") {
+                configs.data_type = DataType::U8;
+            } else if vec[1].trim().to_lowercase().to_string().contains("i32") {
+                configs.data_type = DataType::I32;
+            }
+        } else if vec[0].to_lowercase().contains("data scale") {
+            if vec[1]
+                .trim()
+                .to_lowercase()
+                .to_string()
+                .contains("continuous")
+            {
+                configs.photometric_interp = PhotometricInterpretation::Continuous;
+            } else if vec[1]
+                .trim()
+                .to_lowercase()
+                .to_string()
+                .contains("categorical")
+            {
+                configs.photometric_interp = PhotometricInterpretation::Categorical;
+            } else if vec[1].trim().to_lowercase().to_string().contains("boolean") {
+                configs.photometric_interp = PhotometricInterpretation::Boolean;
+            } else if vec[1].trim().to_lowercase().to_string().contains("rgb") {
+                configs.photometric_interp = PhotometricInterpretation::RGB;
+                configs.data_type = DataType::RGBA32;
+            }
+        } else if vec[0].to_lowercase().contains("z units") {
+            configs.z_units = vec[1].trim().to_string();
+        } else if vec[0].to_lowercase().contains("xy units") {
+            configs.xy_units = vec[1].trim().to_string();
+        } else if vec[0].to_lowercase().contains("projection") {
+            configs.projection = vec[1].trim().to_string();
+        } else if vec[0].to_lowercase().contains("nodata") {
+            configs.nodata = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("preferred palette") {
+            configs.palette = vec[1].trim().to_string();
+        } else if vec[0].to_lowercase().contains("nonlinearity") {
+            configs.palette_nonlinearity = vec[1].trim().to_string().parse::<f64>().unwrap();
+        } else if vec[0].to_lowercase().contains("byte order") {
+            if vec[1].trim().to_lowercase().contains("little")
+                || vec[1].trim().to_lowercase().contains("lsb")
+            {
+                configs.endian = Endianness::LittleEndian;
+            } else {
+                configs.endian = Endianness::BigEndian;
+            }
+        } else if vec[0].to_lowercase().contains("metadata") {
+            configs.metadata.push(vec[1].trim().to_string());
+        }
+    }
+
+    configs.resolution_x = (configs.east - configs.west) / configs.columns as f64;
+    configs.resolution_y = (configs.north - configs.south) / configs.rows as f64;
+
+    Ok(())
+}
@@ -0,0 +1,134 @@
+use egui::{Color32, Key, Response, RichText, Stroke, Ui, WidgetInfo, WidgetType};
+
+const FOCUS_STROKE_WIDTH: f32 = 2.0;
+const DARK_FOCUS_COLOR: Color32 = Color32::from_rgb(255, 210, 0);
+const LIGHT_FOCUS_COLOR: Color32 = Color32::from_rgb(0, 60, 200);
+
+/// Gives focused widgets a high-contrast outline in the colours of the theme. egui draws
+/// widgets with keyboard focus using the `active` visuals, so its stroke is the focus outline.
+pub fn with_focus_outline(mut visuals: egui::Visuals) -> egui::Visuals {
+    let color = if visuals.dark_mode {
+        DARK_FOCUS_COLOR
+    } else {
+        LIGHT_FOCUS_COLOR
+    };
+    visuals.widgets.active.bg_stroke = Stroke::new(FOCUS_STROKE_WIDTH, color);
+    visuals
+}
+
+/// Outlines a widget that has keyboard focus but does not paint its own frame, such as a
+/// collapsing header.
+pub fn paint_focus_outline(ui: &Ui, response: &Response) {
+    if response.has_focus() {
+        let visuals = &ui.visuals().widgets.active;
+        ui.painter().rect_stroke(
+            response.rect.expand(1.0),
+            visuals.rounding,
+            visuals.bg_stroke,
+        );
+    }
+}
+
+/// Returns true when a button was clicked, or Enter was pressed while it had keyboard focus.
+pub fn activated(ui: &Ui, response: &Response) -> bool {
+    response.clicked() || (response.has_focus() && ui.input().key_pressed(Key::Enter))
+}
+
+/// A collapsing header that can be operated from the keyboard. When the header has focus,
+/// Enter or Space toggles it and the right and left arrow keys expand and collapse it. The
+/// header starts closed and its state is kept in the egui memory, as `CollapsingHeader` does.
+pub fn collapsing_header<R>(
+    ui: &mut Ui,
+    text: RichText,
+    add_contents: impl FnOnce(&mut Ui) -> R,
+) -> Option<R> {
+    let id = ui.make_persistent_id(text.text()).with("open");
+    let open = ui.data().get_persisted::<bool>(id).unwrap_or(false);
+
+    let response = egui::CollapsingHeader::new(text)
+        .open(Some(open))
+        .show(ui, add_contents);
+    let header = &response.header_response;
+
+    // Read the keys before touching the memory; both live behind the context lock.
+    let (toggle_key, expand_key, collapse_key) = {
+        let input = ui.input();
+        (
+            input.key_pressed(Key::Enter) || input.key_pressed(Key::Space),
+            input.key_pressed(Key::ArrowRight),
+            input.key_pressed(Key::ArrowLeft),
+        )
+    };
+    let mut new_open = open;
+    // egui reports Enter and Space on a focused header as a click; only toggle once.
+    if header.clicked() || (header.has_focus() && toggle_key) {
+        new_open = !open;
+    } else if header.has_focus() && expand_key {
+        new_open = true;
+    } else if header.has_focus() && collapse_key {
+        new_open = false;
+    }
+    if new_open != open {
+        ui.data().insert_persisted(id, new_open);
+        ui.ctx().request_repaint();
+    }
+    paint_focus_outline(ui, header);
+
+    response.body_returned
+}
+
+/// An icon-only button, e.g. ✖ or 🔃. The description is shown as hover text and is the
+/// label reported to screen readers, which would otherwise only be given the icon.
+pub fn icon_button(ui: &mut Ui, icon: &str, description: &str) -> Response {
+    icon_widget(ui, icon, description, None)
+}
+
+/// An icon-only toggle button, described in the same way as `icon_button`.
+pub fn icon_toggle(ui: &mut Ui, selected: &mut bool, icon: &str, description: &str) -> Response {
+    let mut response = icon_widget(ui, icon, description, Some(*selected));
+    if response.clicked() {
+        *selected = !*selected;
+        response.mark_changed();
+    }
+    response
+}
+
+fn icon_widget(ui: &mut Ui, icon: &str, description: &str, selected: Option<bool>) -> Response {
+    let padding = ui.spacing().button_padding;
+    let text: egui::WidgetText = icon.into();
+    let galley = text.into_galley(ui, Some(false), f32::INFINITY, egui::TextStyle::Button);
+    let desired_size =
+        (galley.size() + 2.0 * padding).at_least(egui::vec2(0.0, ui.spacing().interact_size.y));
+    let (rect, response) = ui.allocate_exact_size(desired_size, egui::Sense::click());
+    response.widget_info(|| match selected {
+        Some(selected) => WidgetInfo::selected(WidgetType::SelectableLabel, selected, description),
+        None => WidgetInfo::labeled(WidgetType::Button, description),
+    });
+
+    if ui.is_rect_visible(rect) {
+        // Buttons always have a frame; toggles, like selectable labels, only when selected,
+        // hovered, or focused.
+        let (visuals, framed) = match selected {
+            Some(selected) => (
+                ui.style().interact_selectable(&response, selected),
+                selected || response.hovered() || response.has_focus(),
+            ),
+            None => (*ui.style().interact(&response), true),
+        };
+        if framed {
+            ui.painter().rect(
+                rect.expand(visuals.expansion),
+                visuals.rounding,
+                visuals.bg_fill,
+                visuals.bg_stroke,
+            );
+        }
+        let text_pos = ui
+            .layout()
+            .align_size_within_rect(galley.size(), rect.shrink2(padding))
+            .min;
+        galley.paint_with_visuals(ui.painter(), text_pos, &visuals);
+    }
+
+    response.on_hover_text(description)
+}
@@ -0,0 +1,188 @@
+use crate::tool_info::{job_log_dir, ToolInfo};
+use crate::MyApp;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// A tool run started from a tool dialog. The run is tracked separately from its dialog, which
+/// may be closed while the tool is still running.
+pub struct RunningJob {
+    tool_name: String,
+    running: Arc<Mutex<bool>>,
+    cancel: Arc<Mutex<bool>>,
+    progress: Arc<Mutex<f32>>,
+}
+
+impl RunningJob {
+    pub fn new(tool: &ToolInfo) -> Self {
+        RunningJob {
+            tool_name: tool.tool_name.clone(),
+            running: Arc::clone(&tool.continuous_mode),
+            cancel: Arc::clone(&tool.cancel),
+            progress: Arc::clone(&tool.progress),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.lock().map(|r| *r).unwrap_or(false)
+    }
+}
+
+/// What to do with running and pending tools when the Runner window is closed.
+#[derive(Clone, Copy, PartialEq)]
+pub enum CloseMode {
+    /// Keep the window open until the running tools finish and the pending run is resolved.
+    Wait,
+    /// Cancel the running tools, drop the pending run, and exit once the tools have stopped.
+    CancelJobs,
+    /// Exit at once, leaving the running tools to finish on their own and dropping the pending
+    /// run. Their output is left in the job log directory.
+    Detach,
+}
+
+impl MyApp {
+    /// Tracks a tool run so that closing the window can account for it.
+    pub fn track_job(&mut self, tool_idx: usize) {
+        self.drop_finished_jobs();
+        let tool = &self.list_of_open_tools[tool_idx];
+        self.running_jobs
+            .retain(|j| !Arc::ptr_eq(&j.running, &tool.continuous_mode));
+        let job = RunningJob::new(tool);
+        if job.is_running() {
+            self.running_jobs.push(job);
+        }
+    }
+
+    /// Stops tracking the runs that have finished. Each has been added to the run history, so
+    /// the typical runtimes are reloaded.
+    fn drop_finished_jobs(&mut self) {
+        let num_jobs = self.running_jobs.len();
+        self.running_jobs.retain(|j| j.is_running());
+        if self.running_jobs.len() < num_jobs {
+            self.run_times.invalidate();
+        }
+    }
+
+    /// Returns true if a tool is running or a run is waiting on the overwrite prompt.
+    pub fn has_active_jobs(&mut self) -> bool {
+        self.drop_finished_jobs();
+        !self.running_jobs.is_empty() || self.overwrite_prompt.is_some()
+    }
+
+    /// Shows the dialog that asks what to do with running and pending tools when the window is
+    /// closed, and carries out the choice. Every choice that exits goes through `frame.close`,
+    /// so the app state is saved as on any other exit.
+    pub fn close_dialog(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.drop_finished_jobs();
+        let cat = self.catalog.clone();
+
+        if self.close_dialog_visible {
+            let mut choice = None;
+            egui::Window::new(cat.tr("close_dialog.title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.vertical(|ui| {
+                        if !self.running_jobs.is_empty() {
+                            ui.label(cat.tr("close_dialog.running"));
+                            for job in &self.running_jobs {
+                                let progress = job.progress.lock().map(|p| *p).unwrap_or(0.0);
+                                ui.monospace(format!(
+                                    "{} ({:.0}%)",
+                                    job.tool_name,
+                                    progress * 100.0
+                                ));
+                            }
+                            ui.label("");
+                        }
+                        if let Some(prompt) = &self.overwrite_prompt {
+                            ui.label(cat.tr("close_dialog.pending"));
+                            ui.monospace(prompt.tool_name());
+                            ui.label("");
+                        }
+                        ui.horizontal(|ui| {
+                            if ui
+                                .button(cat.tr("close_dialog.wait"))
+                                .on_hover_text(cat.tr("close_dialog.wait_hover"))
+                                .clicked()
+                            {
+                                choice = Some(CloseMode::Wait);
+                            }
+                            if ui
+                                .button(cat.tr("close_dialog.cancel_jobs"))
+                                .on_hover_text(cat.tr("close_dialog.cancel_jobs_hover"))
+                                .clicked()
+                            {
+                                choice = Some(CloseMode::CancelJobs);
+                            }
+                            if ui
+                                .button(cat.tr("close_dialog.detach"))
+                                .on_hover_text(cat.trf(
+                                    "close_dialog.detach_hover",
+                                    &[("dir", &job_log_dir().display().to_string())],
+                                ))
+                                .clicked()
+                            {
+                                choice = Some(CloseMode::Detach);
+                            }
+                        });
+                    });
+                });
+
+            if let Some(mode) = choice {
+                self.close_dialog_visible = false;
+                if mode != CloseMode::Wait {
+                    self.overwrite_prompt = None;
+                }
+                if mode == CloseMode::CancelJobs {
+                    for job in &self.running_jobs {
+                        if let Ok(mut cancel) = job.cancel.lock() {
+                            *cancel = true;
+                        }
+                    }
+                }
+                self.close_mode = Some(mode);
+            }
+        }
+
+        let mode = match self.close_mode {
+            Some(mode) => mode,
+            None => return,
+        };
+        let finished = match mode {
+            CloseMode::Wait => self.running_jobs.is_empty() && self.overwrite_prompt.is_none(),
+            CloseMode::CancelJobs => self.running_jobs.is_empty(),
+            CloseMode::Detach => true,
+        };
+        if finished {
+            self.allowed_to_close = true;
+            frame.close();
+            return;
+        }
+
+        egui::Window::new(cat.tr("close_dialog.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::RIGHT_BOTTOM, [-10.0, -10.0])
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    if mode == CloseMode::CancelJobs {
+                        ui.label(cat.trf(
+                            "close_dialog.cancelling",
+                            &[("count", &self.running_jobs.len().to_string())],
+                        ));
+                    } else {
+                        ui.label(cat.trf(
+                            "close_dialog.waiting",
+                            &[("count", &self.running_jobs.len().to_string())],
+                        ));
+                        if ui.button(cat.tr("close_dialog.keep_open")).clicked() {
+                            self.close_mode = None;
+                        }
+                    }
+                });
+            });
+        // the tools report through shared state, so poll for them to finish
+        ctx.request_repaint_after(Duration::from_millis(250));
+    }
+}
@@ -1,4 +1,5 @@
 extern crate copypasta;
+use crate::accessibility::icon_button;
 use crate::MyApp;
 use anyhow::{bail, Result};
 use copypasta::{ClipboardContext, ClipboardProvider};
@@ -73,7 +74,7 @@ impl MyApp {
                     egui::TextEdit::singleline(&mut self.ei.activation_key)
                     .desired_width(self.state.textbox_width)
                 );
-                if ui.button("📋").clicked() {
+                if icon_button(ui, "📋", "Paste the activation key from the clipboard").clicked() {
                     // ui.output_mut(|o| o.copied_text = "some_text".to_string());
                     // self.ei.activation_key = "hello world".to_string(); // ui.output().copied_text.clone();
                     let mut ctx = ClipboardContext::new().unwrap();
@@ -94,7 +95,7 @@ impl MyApp {
                 ui.horizontal(|ui| {
                     ui.label("Output:");
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                        if ui.button("✖").on_hover_text("Clear tool output").clicked() {
+                        if icon_button(ui, "✖", "Clear tool output").clicked() {
                             self.ei.text_output = "".to_string();
                         }
                     });
@@ -107,7 +108,6 @@ impl MyApp {
                             .cursor_at_end(true)
                             .font(egui::TextStyle::Monospace)
                             .desired_rows(10)
-                            .lock_focus(true)
                             .desired_width(f32::INFINITY)
                     );
                 });
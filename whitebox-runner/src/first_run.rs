@@ -0,0 +1,215 @@
+use crate::MyApp;
+use std::fs;
+use std::path::{Path, PathBuf};
+use whitebox_common::configs::{exe_settings_file, platform_settings_file, Configs};
+
+/// Whether WhiteboxTools has a usable settings.json.
+#[derive(PartialEq)]
+pub enum SettingsFileState {
+    Valid,
+    Missing,
+    /// The settings.json beside the executable exists but cannot be parsed.
+    Unparsable(PathBuf),
+}
+
+/// Looks for the settings.json that `whitebox_exe` reads: beside the executable, or else in the
+/// platform's configuration directory.
+pub fn settings_file_state(whitebox_exe: &str) -> SettingsFileState {
+    let parses = |file: &Path| {
+        fs::read_to_string(file)
+            .ok()
+            .map(|contents| serde_json::from_str::<Configs>(&contents).is_ok())
+    };
+    let exe_file = exe_settings_file(Path::new(whitebox_exe));
+    match parses(&exe_file) {
+        Some(true) => SettingsFileState::Valid,
+        Some(false) => SettingsFileState::Unparsable(exe_file),
+        None => match platform_settings_file().and_then(|file| parses(&file)) {
+            Some(true) => SettingsFileState::Valid,
+            _ => SettingsFileState::Missing,
+        },
+    }
+}
+
+/// Writes `configs` to the settings.json beside `whitebox_exe` or, when that location is not
+/// writable, to the platform's configuration directory, and returns the file written. An
+/// unparsable settings.json is first kept as settings.json.bak.
+pub fn write_settings_file(whitebox_exe: &str, configs: &Configs) -> Result<PathBuf, String> {
+    let json = serde_json::to_string_pretty(configs).map_err(|e| e.to_string())?;
+    let exe_file = exe_settings_file(Path::new(whitebox_exe));
+    if exe_file.exists() {
+        _ = fs::copy(&exe_file, exe_file.with_extension("json.bak"));
+    }
+    let exe_err = match fs::write(&exe_file, &json) {
+        Ok(()) => return Ok(exe_file),
+        Err(e) => format!("{}: {}", exe_file.display(), e),
+    };
+    let platform_file = match platform_settings_file() {
+        Some(file) => file,
+        None => return Err(exe_err),
+    };
+    if let Some(dir) = platform_file.parent() {
+        _ = fs::create_dir_all(dir);
+    }
+    match fs::write(&platform_file, &json) {
+        Ok(()) => Ok(platform_file),
+        Err(e) => Err(format!("{}; {}: {}", exe_err, platform_file.display(), e)),
+    }
+}
+
+/// The choices made in the first-run dialog, shown when WhiteboxTools has no usable
+/// settings.json.
+pub struct FirstRunSettings {
+    working_dir: String,
+    max_procs: isize,
+    verbose: bool,
+    unparsable: Option<PathBuf>,
+    error: Option<String>,
+}
+
+impl MyApp {
+    /// Opens the first-run dialog if the WhiteboxTools executable has no usable settings.json.
+    /// Once the file exists the dialog is never shown again.
+    pub fn check_settings_file(&mut self) {
+        if self.state.whitebox_exe.is_empty() {
+            return; // the executable must be located first
+        }
+        let unparsable = match settings_file_state(&self.state.whitebox_exe) {
+            SettingsFileState::Valid => return,
+            SettingsFileState::Missing => None,
+            SettingsFileState::Unparsable(file) => Some(file),
+        };
+        self.first_run = Some(FirstRunSettings {
+            working_dir: self.state.working_dir.clone(),
+            max_procs: self.state.max_procs,
+            verbose: self.state.view_tool_output,
+            unparsable,
+            error: None,
+        });
+    }
+
+    /// Shows the first-run dialog, which asks for the settings that WhiteboxTools falls back to
+    /// and writes them to a new settings.json. Skipping writes the default settings.
+    pub fn first_run_dialog(&mut self, ctx: &egui::Context) {
+        let cat = self.catalog.clone();
+        let mut first_run = match self.first_run.take() {
+            Some(first_run) => first_run,
+            None => return,
+        };
+
+        let mut choice = None;
+        egui::Window::new(cat.tr("first_run.title"))
+            .collapsible(false)
+            .resizable(false)
+            .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+            .show(ctx, |ui| {
+                ui.vertical(|ui| {
+                    match &first_run.unparsable {
+                        Some(file) => ui.label(cat.trf(
+                            "first_run.unparsable",
+                            &[("file", &file.display().to_string())],
+                        )),
+                        None => ui.label(cat.tr("first_run.missing")),
+                    };
+                    ui.label("");
+                    egui::Grid::new("first_run_grid")
+                        .num_columns(2)
+                        .spacing([10.0, 6.0])
+                        .show(ui, |ui| {
+                            ui.label(cat.tr("first_run.working_dir"));
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::TextEdit::singleline(&mut first_run.working_dir)
+                                        .desired_width(self.state.textbox_width),
+                                );
+                                if ui
+                                    .button("…")
+                                    .on_hover_text(cat.tr("tool_dialog.browse_hover"))
+                                    .clicked()
+                                {
+                                    if let Some(path) = rfd::FileDialog::new()
+                                        .set_directory(Path::new(&first_run.working_dir))
+                                        .pick_folder()
+                                    {
+                                        first_run.working_dir = path.display().to_string();
+                                    }
+                                }
+                            });
+                            ui.end_row();
+
+                            ui.label(cat.tr("first_run.max_procs"));
+                            ui.horizontal(|ui| {
+                                ui.add(
+                                    egui::DragValue::new(&mut first_run.max_procs)
+                                        .speed(0)
+                                        .clamp_range(-1..=1024),
+                                );
+                                ui.label(cat.tr("first_run.max_procs_hint"));
+                            });
+                            ui.end_row();
+
+                            ui.label(cat.tr("first_run.verbose"));
+                            ui.add(crate::toggle(&mut first_run.verbose));
+                            ui.end_row();
+                        });
+                    if let Some(error) = &first_run.error {
+                        ui.label("");
+                        ui.colored_label(ui.visuals().error_fg_color, error);
+                    }
+                    ui.label("");
+                    ui.horizontal(|ui| {
+                        if ui.button(cat.tr("first_run.save")).clicked() {
+                            choice = Some(true);
+                        }
+                        if ui
+                            .button(cat.tr("first_run.skip"))
+                            .on_hover_text(cat.tr("first_run.skip_hover"))
+                            .clicked()
+                        {
+                            choice = Some(false);
+                        }
+                    });
+                });
+            });
+
+        let save = match choice {
+            Some(save) => save,
+            None => {
+                self.first_run = Some(first_run);
+                return;
+            }
+        };
+        let mut configs = Configs::new();
+        configs.compress_rasters = self.state.compress_rasters;
+        if save {
+            configs.working_directory = first_run.working_dir.trim().to_string();
+            configs.max_procs = first_run.max_procs;
+            configs.verbose_mode = first_run.verbose;
+        }
+        match write_settings_file(&self.state.whitebox_exe, &configs) {
+            Ok(file) => println!("Wrote WhiteboxTools settings to {}", file.display()),
+            Err(e) => {
+                if save {
+                    // keep the dialog open so that the choices are not lost
+                    first_run.error = Some(cat.trf("first_run.write_failed", &[("error", &e)]));
+                    self.first_run = Some(first_run);
+                    return;
+                }
+                eprintln!("Could not write a WhiteboxTools settings.json: {}", e);
+            }
+        }
+        if save {
+            if !configs.working_directory.is_empty() {
+                self.update_working_dir(&configs.working_directory);
+            }
+            self.state.max_procs = configs.max_procs;
+            self.state.view_tool_output = configs.verbose_mode;
+            for tool in self.tool_info.iter_mut() {
+                tool.update_verbose_mode(configs.verbose_mode);
+            }
+            for tool in self.list_of_open_tools.iter_mut() {
+                tool.update_verbose_mode(configs.verbose_mode);
+            }
+        }
+    }
+}
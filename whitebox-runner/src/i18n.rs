@@ -0,0 +1,145 @@
+use serde_json::Value;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::{env, fs, path::PathBuf};
+
+/// The locale code of the built-in English catalog.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// The English catalog is compiled into the binary so that the Runner always has a
+/// complete set of strings, even if the locales directory is missing.
+const ENGLISH_CATALOG: &str = include_str!("../locales/en.json");
+
+/// The string catalog used for the Runner's own interface text (panel headers, buttons,
+/// tooltips, and messages). Tool names, descriptions, and parameters are not translated.
+///
+/// Locales are JSON files named `<code>.json` in the `locales` directory next to the
+/// Runner executable, each mapping string keys to translated text. The `_language_name`
+/// key holds the name shown in the settings panel. Any key missing from a locale falls
+/// back to the built-in English text, so adding a new locale only requires dropping a
+/// JSON file into that directory.
+#[derive(Clone)]
+pub struct Catalog {
+    locale: String,
+    strings: Rc<HashMap<String, String>>,
+    fallback: Rc<HashMap<String, String>>,
+}
+
+impl Default for Catalog {
+    fn default() -> Self {
+        Catalog::load(DEFAULT_LOCALE)
+    }
+}
+
+impl Catalog {
+    /// Loads the catalog for a locale code, falling back to English if it cannot be read.
+    pub fn load(locale: &str) -> Catalog {
+        let fallback = Rc::new(parse_catalog(ENGLISH_CATALOG));
+        if locale.is_empty() || locale == DEFAULT_LOCALE {
+            return Catalog {
+                locale: DEFAULT_LOCALE.to_string(),
+                strings: fallback.clone(),
+                fallback,
+            };
+        }
+
+        let file = locales_dir().join(format!("{locale}.json"));
+        match fs::read_to_string(&file) {
+            Ok(s) => Catalog {
+                locale: locale.to_string(),
+                strings: Rc::new(parse_catalog(&s)),
+                fallback,
+            },
+            Err(_) => {
+                println!(
+                    "Could not read locale file {}; using English instead.",
+                    file.display()
+                );
+                Catalog {
+                    locale: DEFAULT_LOCALE.to_string(),
+                    strings: fallback.clone(),
+                    fallback,
+                }
+            }
+        }
+    }
+
+    pub fn locale(&self) -> &str {
+        &self.locale
+    }
+
+    /// Returns the text for a key, falling back to English and then to the key itself.
+    pub fn tr<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback.get(key))
+            .map(|s| s.as_str())
+            .unwrap_or(key)
+    }
+
+    /// Returns the text for a key with each `{name}` placeholder replaced by its value.
+    pub fn trf(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut s = self.tr(key).to_string();
+        for (name, value) in args {
+            s = s.replace(&format!("{{{name}}}"), value);
+        }
+        s
+    }
+}
+
+/// Lists the available locales as (code, language name) pairs, English first.
+pub fn available_locales() -> Vec<(String, String)> {
+    let english = parse_catalog(ENGLISH_CATALOG);
+    let mut locales = vec![(
+        DEFAULT_LOCALE.to_string(),
+        english
+            .get("_language_name")
+            .cloned()
+            .unwrap_or("English".to_string()),
+    )];
+
+    let mut others = vec![];
+    if let Ok(entries) = fs::read_dir(locales_dir()) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("json") {
+                continue;
+            }
+            let code = match path.file_stem().and_then(|s| s.to_str()) {
+                Some(code) if code != DEFAULT_LOCALE => code.to_string(),
+                _ => continue,
+            };
+            if let Ok(s) = fs::read_to_string(&path) {
+                let name = parse_catalog(&s)
+                    .get("_language_name")
+                    .cloned()
+                    .unwrap_or(code.clone());
+                others.push((code, name));
+            }
+        }
+    }
+    others.sort_by(|a, b| a.1.cmp(&b.1));
+    locales.extend(others);
+    locales
+}
+
+fn locales_dir() -> PathBuf {
+    let mut dir = env::current_exe().unwrap_or(PathBuf::new());
+    dir.pop();
+    dir.join("locales")
+}
+
+fn parse_catalog(s: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+    match serde_json::from_str::<HashMap<String, Value>>(s) {
+        Ok(map) => {
+            for (key, value) in map {
+                if let Value::String(text) = value {
+                    strings.insert(key, text);
+                }
+            }
+        }
+        Err(e) => println!("Could not parse locale file: {e}"),
+    }
+    strings
+}
@@ -1,16 +1,26 @@
 mod about;
+mod accessibility;
+mod close_dialog;
 mod custom_widgets;
 mod deactivate_extension;
 mod extension;
+mod first_run;
+mod i18n;
+mod overwrite_dialog;
+mod plugin_discovery;
+mod run_history;
 mod settings_panel;
 mod tool_dialog;
 mod tool_info;
 mod tools_panel;
 mod tree;
 mod update_extension;
+mod window_layout;
 
 use about::WbLogo;
+use accessibility::{icon_button, icon_toggle, with_focus_outline};
 use anyhow::{bail, Result};
+use close_dialog::{CloseMode, RunningJob};
 pub use custom_widgets::toggle;
 use eframe::egui;
 use egui::CentralPanel;
@@ -18,9 +28,15 @@ use egui::FontFamily::Proportional;
 use egui::FontId;
 use egui::TextStyle::*;
 use extension::ExtensionInstall;
+use first_run::FirstRunSettings;
+use i18n::Catalog;
+use overwrite_dialog::OverwritePrompt;
+use plugin_discovery::DiscoveredPlugin;
+use run_history::RunTimes;
 use serde_json::Value;
 use std::collections::{HashMap, HashSet, VecDeque};
 use std::process::Command;
+use std::sync::mpsc;
 use std::{env, path::Path, path::PathBuf};
 pub use tool_info::{
     ParameterFileType, ParameterType, ToolInfo, ToolParameter, VectorGeometryType,
@@ -96,7 +112,10 @@ fn main() {
         None
     };
     let options = eframe::NativeOptions {
-        initial_window_size: Some(egui::Vec2::new(1000.0, 700.0)),
+        initial_window_size: Some(egui::Vec2::new(
+            window_layout::DEFAULT_WINDOW_SIZE[0],
+            window_layout::DEFAULT_WINDOW_SIZE[1],
+        )),
         drag_and_drop_support: true,
         icon_data: icon_data,
         ..Default::default()
@@ -132,6 +151,12 @@ pub struct AppState {
     show_recent_tools: bool,
     most_recent: VecDeque<String>,
     check_wbt_updates: bool,
+    skip_overwrite_warning: bool, // whether to run tools without confirming that existing outputs will be overwritten
+    locale: String,               // the code of the interface string catalog, e.g. "en" or "es"
+    window_size: [f32; 2],        // in points; zero until the window has been measured
+    window_pos: Option<[f32; 2]>, // outer top-left corner, in points
+    tools_panel_width: f32,
+    plugin_cache: Vec<DiscoveredPlugin>, // plugins found by the last scan, keyed by exe modification time
 }
 
 #[derive(Default)]
@@ -141,6 +166,9 @@ struct MyApp {
     tree: Tree,
     allowed_to_close: bool,
     show_confirmation_dialog: bool,
+    close_dialog_visible: bool,
+    close_mode: Option<CloseMode>,
+    running_jobs: Vec<RunningJob>,
     list_of_open_tools: Vec<ToolInfo>,
     open_tools: Vec<bool>,
     tool_info: Vec<ToolInfo>,
@@ -166,6 +194,15 @@ struct MyApp {
     most_used: Vec<(u16, String)>,
     wb_logo: WbLogo,
     extension_tools_list: Vec<String>,
+    overwrite_prompt: Option<OverwritePrompt>,
+    catalog: Catalog,
+    available_locales: Vec<(String, String)>,
+    layout_restored: bool,
+    pending_panel_width: Option<f32>,
+    plugin_scan: Option<mpsc::Receiver<Vec<DiscoveredPlugin>>>,
+    discovered_plugins: Vec<DiscoveredPlugin>,
+    first_run: Option<FirstRunSettings>,
+    run_times: RunTimes,
 }
 
 impl MyApp {
@@ -195,7 +232,14 @@ impl MyApp {
             slf.state.show_tool_search = false;
             slf.state.show_recent_tools = false;
             slf.state.check_wbt_updates = true;
+            slf.state.skip_overwrite_warning = false;
+            slf.state.locale = i18n::DEFAULT_LOCALE.to_string();
             slf.state.most_recent = std::collections::VecDeque::new();
+            slf.state.window_size = window_layout::DEFAULT_WINDOW_SIZE;
+            slf.state.window_pos = None;
+            slf.state.tools_panel_width = window_layout::DEFAULT_TOOLS_PANEL_WIDTH;
+            // egui's own memory also holds the panel width
+            *cc.egui_ctx.memory() = Default::default();
         } else {
             #[cfg(feature = "persistence")]
             if let Some(storage) = cc.storage {
@@ -219,7 +263,12 @@ impl MyApp {
                     slf.state.show_tool_search = false;
                     slf.state.show_recent_tools = false;
                     slf.state.check_wbt_updates = true;
+                    slf.state.skip_overwrite_warning = false;
+                    slf.state.locale = i18n::DEFAULT_LOCALE.to_string();
                     slf.state.most_recent = std::collections::VecDeque::new();
+                    slf.state.window_size = window_layout::DEFAULT_WINDOW_SIZE;
+                    slf.state.window_pos = None;
+                    slf.state.tools_panel_width = window_layout::DEFAULT_TOOLS_PANEL_WIDTH;
                 }
             }
         }
@@ -234,14 +283,18 @@ impl MyApp {
             slf.ei.product_index = EXTENSION_NAME;
         }
 
+        slf.catalog = Catalog::load(&slf.state.locale);
+        slf.available_locales = i18n::available_locales();
         slf.theme_changed = true;
         slf.fonts_changed = true;
         slf.state.whitebox_exe = slf.get_executable_path().unwrap_or("".to_string());
         if slf.state.working_dir.is_empty() {
             slf.state.working_dir = "/".to_owned();
         }
+        slf.check_settings_file();
         _ = slf.get_tool_info();
         _ = slf.get_version();
+        slf.rescan_plugins();
 
         if slf.state.check_wbt_updates {
             // see if the WBT version is lower than the published version
@@ -411,6 +464,89 @@ impl MyApp {
 
         _ = self.get_tool_info();
         _ = self.get_version();
+        self.rescan_plugins();
+    }
+
+    /// Starts a background scan of the plugins directory for plugin tools that WhiteboxTools does
+    /// not list. The results are merged into the tool tree by `poll_plugin_scan`.
+    fn rescan_plugins(&mut self) {
+        let plugins_dir = plugin_discovery::plugins_directory(&self.state.whitebox_exe);
+        self.plugin_scan = Some(plugin_discovery::scan(
+            plugins_dir,
+            self.state.plugin_cache.clone(),
+        ));
+    }
+
+    fn poll_plugin_scan(&mut self, ctx: &egui::Context) {
+        let result = match &self.plugin_scan {
+            Some(rx) => rx.try_recv(),
+            None => return,
+        };
+        match result {
+            Ok(plugins) => {
+                self.plugin_scan = None;
+                self.state.plugin_cache = plugins.clone();
+                self.merge_plugins(plugins);
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(std::time::Duration::from_millis(100));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => self.plugin_scan = None,
+        }
+    }
+
+    /// Adds discovered plugins to the tool list and tree, replacing those of any earlier scan.
+    /// Plugins that failed introspection are listed in their own toolbox and flagged in their
+    /// descriptions. A plugin with the name of a tool that is already listed is skipped.
+    fn merge_plugins(&mut self, plugins: Vec<DiscoveredPlugin>) {
+        // Discovered tools always follow the WhiteboxTools tools, so removing them leaves the
+        // indices in tool_order unchanged.
+        for tool in self.tool_info.iter().filter(|t| t.plugin_exe.is_some()) {
+            self.tool_order.remove(&tool.tool_name);
+            self.tool_descriptions.remove(&tool.tool_name);
+        }
+        self.tool_info.retain(|t| t.plugin_exe.is_none());
+
+        for plugin in &plugins {
+            if self.tool_order.contains_key(&plugin.tool_name) {
+                println!(
+                    "Skipping plugin {}: a tool named {} already exists.",
+                    plugin.exe_path, plugin.tool_name
+                );
+                continue;
+            }
+            let mut tool_info = ToolInfo::new(
+                &plugin.tool_name,
+                &plugin.toolbox,
+                plugin.parameters.clone(),
+            );
+            tool_info.plugin_exe = Some(plugin.exe_path.clone());
+            tool_info.update_output_command(self.state.output_command);
+            tool_info.update_verbose_mode(self.state.view_tool_output);
+            tool_info.update_compress_rasters(self.state.compress_rasters);
+            self.tool_order
+                .insert(plugin.tool_name.clone(), self.tool_info.len());
+            self.tool_descriptions
+                .insert(plugin.tool_name.clone(), plugin.hover_text());
+            self.tool_info.push(tool_info);
+        }
+
+        self.num_tools = self.tool_info.len();
+        self.rebuild_tree();
+        self.discovered_plugins = plugins;
+    }
+
+    fn rebuild_tree(&mut self) {
+        let mut tb_hm: HashMap<&str, Vec<&str>> = HashMap::new();
+        for tool in &self.tool_info {
+            tb_hm
+                .entry(tool.toolbox.as_str())
+                .or_default()
+                .push(tool.tool_name.as_str());
+        }
+        let mut tb: Vec<&str> = tb_hm.keys().copied().collect();
+        tb.sort();
+        self.tree = Tree::from_toolboxes_and_tools(tb, tb_hm);
     }
 
     // Get the tools and toolboxes
@@ -749,16 +885,33 @@ impl eframe::App for MyApp {
     }
 
     fn on_close_event(&mut self) -> bool {
-        self.show_confirmation_dialog = true;
+        if !self.allowed_to_close {
+            // Closing would end the tools that are still running, so ask what to do with them.
+            if self.has_active_jobs() {
+                self.close_dialog_visible = true;
+            } else {
+                self.show_confirmation_dialog = true;
+            }
+        }
         self.allowed_to_close
     }
 
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.poll_plugin_scan(ctx);
+        self.poll_run_times(ctx);
+
+        if self.layout_restored {
+            self.record_window_layout(frame);
+        } else {
+            self.restore_window_layout(frame);
+            self.layout_restored = true;
+        }
+
         if self.theme_changed {
             // update the app theme
             match self.state.theme {
-                AppTheme::Light => ctx.set_visuals(egui::Visuals::light()),
-                AppTheme::Dark => ctx.set_visuals(egui::Visuals::dark()),
+                AppTheme::Light => ctx.set_visuals(with_focus_outline(egui::Visuals::light())),
+                AppTheme::Dark => ctx.set_visuals(with_focus_outline(egui::Visuals::dark())),
             };
             self.theme_changed = false;
         }
@@ -850,24 +1003,18 @@ impl eframe::App for MyApp {
                         // }
 
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            ui.toggle_value(&mut self.about_visible, "ℹ")
-                                .on_hover_text("About Whitebox Runner");
+                            icon_toggle(ui, &mut self.about_visible, "ℹ", "About Whitebox Runner");
                             // .clicked() {
                             //     // Open About window.
                             //     self.about_visible = true;
                             // }
 
-                            ui.toggle_value(&mut self.state.settings_visible, "⛭") // ⚙
-                                .on_hover_text("View settings");
+                            icon_toggle(ui, &mut self.state.settings_visible, "⛭", "View settings"); // ⚙
                             // .clicked() {
                             //     self.state.settings_visible = !self.state.settings_visible;
                             // }
 
-                            if ui
-                                .button("✖")
-                                .on_hover_text("Close all open tool dialogs")
-                                .clicked()
-                            {
+                            if icon_button(ui, "✖", "Close all open tool dialogs").clicked() {
                                 for i in (0..self.list_of_open_tools.len()).rev() {
                                     if let Ok(mut tool_output) =
                                         self.list_of_open_tools[i].tool_output.lock()
@@ -883,22 +1030,14 @@ impl eframe::App for MyApp {
 
                             if ui.visuals().dark_mode {
                                 ui.horizontal(|ui| {
-                                    if ui
-                                        .button("☀")
-                                        .on_hover_text("Switch to light mode")
-                                        .clicked()
-                                    {
+                                    if icon_button(ui, "☀", "Switch to light mode").clicked() {
                                         self.theme_changed = true;
                                         self.state.theme = AppTheme::Light;
                                     }
                                 });
                             } else {
                                 ui.horizontal(|ui| {
-                                    if ui
-                                        .button("🌙")
-                                        .on_hover_text("Switch to dark mode")
-                                        .clicked()
-                                    {
+                                    if icon_button(ui, "🌙", "Switch to dark mode").clicked() {
                                         self.theme_changed = true;
                                         self.state.theme = AppTheme::Dark;
                                     }
@@ -944,6 +1083,8 @@ impl eframe::App for MyApp {
                             }
                         }
                     }
+                    self.overwrite_dialog(ctx);
+
                     if remove_idx >= 0 {
                         self.list_of_open_tools.remove(remove_idx as usize);
                         self.open_tools.remove(remove_idx as usize);
@@ -993,6 +1134,8 @@ impl eframe::App for MyApp {
                 });
         }
 
+        self.first_run_dialog(ctx);
+
         // close the window?
         if self.show_confirmation_dialog {
             if rfd::MessageDialog::new()
@@ -1008,6 +1151,7 @@ impl eframe::App for MyApp {
                 self.show_confirmation_dialog = false;
             }
         }
+        self.close_dialog(ctx, frame);
     }
 }
 
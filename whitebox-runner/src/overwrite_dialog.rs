@@ -0,0 +1,177 @@
+use crate::tool_info::ParameterType;
+use crate::MyApp;
+use std::path::{Path, PathBuf};
+use whitebox_common::utils::resolve_path;
+
+/// A pending request to run a tool whose output files already exist.
+pub struct OverwritePrompt {
+    tool_idx: usize,
+    tool_name: String,
+    files: Vec<(usize, PathBuf)>, // (parameter index, resolved output path)
+    dont_ask_again: bool,
+}
+
+impl OverwritePrompt {
+    pub fn tool_name(&self) -> &str {
+        &self.tool_name
+    }
+}
+
+impl MyApp {
+    /// Runs the tool, first asking the user to confirm if any of its output files already exist.
+    pub fn request_tool_run(&mut self, tool_idx: usize) {
+        if self.overwrite_prompt.is_some() {
+            return; // the user must first respond to the open prompt
+        }
+        let files = self.existing_output_files(tool_idx);
+        if files.is_empty() || self.state.skip_overwrite_warning {
+            self.run_tool(tool_idx);
+        } else {
+            self.overwrite_prompt = Some(OverwritePrompt {
+                tool_idx,
+                tool_name: self.list_of_open_tools[tool_idx].tool_name.clone(),
+                files,
+                dont_ask_again: false,
+            });
+        }
+    }
+
+    pub fn run_tool(&mut self, tool_idx: usize) {
+        self.list_of_open_tools[tool_idx].update_working_dir(&self.state.working_dir);
+        self.list_of_open_tools[tool_idx].update_exe_path(&self.state.whitebox_exe);
+        self.list_of_open_tools[tool_idx].run();
+        self.track_job(tool_idx);
+    }
+
+    pub fn overwrite_dialog(&mut self, ctx: &egui::Context) {
+        // The prompt is stale if its tool dialog was closed in the meantime.
+        let is_valid = match &self.overwrite_prompt {
+            Some(prompt) => {
+                prompt.tool_idx < self.list_of_open_tools.len()
+                    && self.open_tools[prompt.tool_idx]
+                    && self.list_of_open_tools[prompt.tool_idx].tool_name == prompt.tool_name
+            }
+            None => return,
+        };
+        if !is_valid {
+            self.overwrite_prompt = None;
+            return;
+        }
+
+        let mut proceed = false;
+        let mut rename = false;
+        let mut cancel = false;
+        let cat = self.catalog.clone();
+        if let Some(prompt) = &mut self.overwrite_prompt {
+            egui::Window::new(cat.tr("overwrite_dialog.title"))
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.vertical(|ui| {
+                        ui.label(
+                            cat.trf("overwrite_dialog.message", &[("tool", &prompt.tool_name)]),
+                        );
+                        ui.label("");
+                        for (_, file) in &prompt.files {
+                            ui.monospace(file.display().to_string());
+                        }
+                        ui.label("");
+                        ui.checkbox(
+                            &mut prompt.dont_ask_again,
+                            cat.tr("overwrite_dialog.dont_ask_again"),
+                        );
+                        ui.horizontal(|ui| {
+                            if ui.button(cat.tr("overwrite_dialog.overwrite")).clicked() {
+                                proceed = true;
+                            }
+                            if ui
+                                .button(cat.tr("overwrite_dialog.auto_rename"))
+                                .on_hover_text(cat.tr("overwrite_dialog.auto_rename_hover"))
+                                .clicked()
+                            {
+                                rename = true;
+                            }
+                            if ui.button(cat.tr("tool_dialog.cancel")).clicked() {
+                                cancel = true;
+                            }
+                        });
+                    });
+                });
+        }
+
+        if proceed || rename || cancel {
+            if let Some(prompt) = self.overwrite_prompt.take() {
+                if prompt.dont_ask_again && !cancel {
+                    self.state.skip_overwrite_warning = true;
+                }
+                if rename {
+                    for (param_idx, file) in &prompt.files {
+                        let parameter =
+                            &mut self.list_of_open_tools[prompt.tool_idx].parameters[*param_idx];
+                        let new_file = next_available_path(file);
+                        let file_name = file.file_name().unwrap_or_default().to_str().unwrap_or("");
+                        let new_file_name = new_file
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_str()
+                            .unwrap_or("");
+                        let value = parameter.str_value.trim();
+                        parameter.str_value = match value.strip_suffix(file_name) {
+                            // keep the directory part as the user typed it, e.g. relative to the
+                            // working directory
+                            Some(dir) if !file_name.is_empty() => format!("{dir}{new_file_name}"),
+                            _ => new_file.to_str().unwrap_or("").to_string(),
+                        };
+                    }
+                }
+                if !cancel {
+                    self.run_tool(prompt.tool_idx);
+                }
+            }
+        }
+    }
+
+    /// Returns the NewFile parameters of a tool whose output paths already exist.
+    fn existing_output_files(&self, tool_idx: usize) -> Vec<(usize, PathBuf)> {
+        let mut ret = vec![];
+        for (i, parameter) in self.list_of_open_tools[tool_idx]
+            .parameters
+            .iter()
+            .enumerate()
+        {
+            if let ParameterType::NewFile = parameter.parameter_type {
+                let value = parameter.str_value.trim();
+                if !value.is_empty() {
+                    let file = PathBuf::from(resolve_path(&self.state.working_dir, value));
+                    if file.exists() {
+                        ret.push((i, file));
+                    }
+                }
+            }
+        }
+        ret
+    }
+}
+
+/// Appends the smallest numeric suffix (e.g. `dem_1.tif`) that does not collide with an existing file.
+fn next_available_path(file: &Path) -> PathBuf {
+    let stem = file
+        .file_stem()
+        .unwrap_or_default()
+        .to_str()
+        .unwrap_or("")
+        .to_string();
+    let extension = file
+        .extension()
+        .map(|e| format!(".{}", e.to_str().unwrap_or("")))
+        .unwrap_or_default();
+    let mut n = 1;
+    loop {
+        let candidate = file.with_file_name(format!("{stem}_{n}{extension}"));
+        if !candidate.exists() {
+            return candidate;
+        }
+        n += 1;
+    }
+}
@@ -0,0 +1,286 @@
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant, UNIX_EPOCH};
+
+/// How long a plugin may take to answer the `parameters` (or `help`) subcommand before it is killed.
+const INTROSPECTION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The toolbox of plugins that could not report their parameters. These are listed so that users
+/// can see that the plugin was found, but they cannot be configured in a tool dialog.
+const UNVERIFIED_TOOLBOX: &str = "Unverified Plugins";
+
+/// Helper executables shipped in the plugins directory that are not tools.
+const NON_TOOL_EXECUTABLES: [&str; 1] = ["register_license"];
+
+/// A tool found by running an executable in the plugins directory.
+///
+/// Plugins that are registered with a JSON descriptor file are listed by WhiteboxTools itself and
+/// are not discovered here. Any other executable is asked to describe itself with the `parameters`
+/// subcommand, which should print the same JSON descriptor (`tool_name`, `toolbox`,
+/// `short_description`, and `parameters`) to stdout. The results are cached in the app state,
+/// keyed by the executable's modification time and size, so unchanged plugins are not re-run.
+#[derive(Default, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct DiscoveredPlugin {
+    pub exe_path: String,
+    pub modified: u64, // seconds since the Unix epoch
+    pub file_size: u64,
+    pub tool_name: String,
+    pub toolbox: String,
+    pub description: String,
+    pub parameters: Value,
+    pub error: Option<String>, // why introspection failed, if it did
+}
+
+impl DiscoveredPlugin {
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// The text shown when hovering over the tool in the tools panel.
+    pub fn hover_text(&self) -> String {
+        match &self.error {
+            Some(e) => format!("⚠ Plugin introspection failed: {e}\n{}", self.description),
+            None => self.description.clone(),
+        }
+    }
+}
+
+/// Returns the plugins directory that the given WhiteboxTools executable runs plugins from.
+pub fn plugins_directory(whitebox_exe: &str) -> PathBuf {
+    let mut exe_dir = PathBuf::from(whitebox_exe);
+    exe_dir.pop();
+    exe_dir.join("plugins")
+}
+
+/// Scans the plugins directory on a background thread, introspecting each executable that is not
+/// already registered with a JSON descriptor and whose cached entry is missing or out of date.
+/// The full list of discovered plugins is sent once every executable has answered or timed out.
+pub fn scan(
+    plugins_dir: PathBuf,
+    cache: Vec<DiscoveredPlugin>,
+) -> mpsc::Receiver<Vec<DiscoveredPlugin>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut plugins = vec![];
+        let mut handles = vec![];
+        for exe in find_plugin_executables(&plugins_dir) {
+            let (modified, file_size) = file_stamp(&exe);
+            let exe_path = exe.to_string_lossy().to_string();
+            if let Some(cached) = cache.iter().find(|p| {
+                p.exe_path == exe_path && p.modified == modified && p.file_size == file_size
+            }) {
+                plugins.push(cached.clone());
+                continue;
+            }
+            handles.push(thread::spawn(move || {
+                let mut plugin = introspect(&exe);
+                plugin.modified = modified;
+                plugin.file_size = file_size;
+                plugin
+            }));
+        }
+
+        for handle in handles {
+            if let Ok(plugin) = handle.join() {
+                plugins.push(plugin);
+            }
+        }
+        plugins.sort_by(|a, b| a.tool_name.cmp(&b.tool_name));
+        _ = tx.send(plugins);
+    });
+    rx
+}
+
+/// Lists the executables in the plugins directory that are not registered with a JSON descriptor.
+fn find_plugin_executables(plugins_dir: &Path) -> Vec<PathBuf> {
+    let entries = match fs::read_dir(plugins_dir) {
+        Ok(v) => v,
+        Err(_) => return vec![],
+    };
+    let paths: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| p.is_file())
+        .collect();
+
+    // Executables named in a descriptor are already listed by WhiteboxTools.
+    let mut registered = HashSet::new();
+    for path in &paths {
+        if path.extension().map_or(false, |e| e == "json") {
+            if let Ok(s) = fs::read_to_string(path) {
+                if let Ok(v) = serde_json::from_str::<Value>(&s) {
+                    if let Some(exe) = v["exe"].as_str() {
+                        registered.insert(exe.replace("\"", "").to_lowercase());
+                    }
+                }
+            }
+        }
+    }
+
+    let mut ret = vec![];
+    for path in paths {
+        let stem = match path.file_stem().and_then(|s| s.to_str()) {
+            Some(s) => s.to_lowercase(),
+            None => continue,
+        };
+        if stem.starts_with('.')
+            || registered.contains(&stem)
+            || NON_TOOL_EXECUTABLES.contains(&stem.as_str())
+            || !is_executable(&path)
+        {
+            continue;
+        }
+        ret.push(path);
+    }
+    ret.sort();
+    ret
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map_or(false, |m| m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    let suffix = std::env::consts::EXE_SUFFIX.trim_start_matches('.');
+    path.extension()
+        .map_or(false, |e| e.to_string_lossy().eq_ignore_ascii_case(suffix))
+}
+
+/// The modification time (seconds since the Unix epoch) and size of a file, used as its cache key.
+fn file_stamp(path: &Path) -> (u64, u64) {
+    match fs::metadata(path) {
+        Ok(m) => {
+            let modified = m
+                .modified()
+                .ok()
+                .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                .map_or(0, |d| d.as_secs());
+            (modified, m.len())
+        }
+        Err(_) => (0, 0),
+    }
+}
+
+/// Runs the plugin's `parameters` subcommand and reads its descriptor. If that fails, the plugin is
+/// flagged with the error and, where possible, described by the first line of its `help` output.
+fn introspect(exe: &Path) -> DiscoveredPlugin {
+    let mut plugin = DiscoveredPlugin {
+        exe_path: exe.to_string_lossy().to_string(),
+        tool_name: default_tool_name(exe),
+        toolbox: UNVERIFIED_TOOLBOX.to_string(),
+        parameters: Value::Null,
+        ..Default::default()
+    };
+
+    let start = Instant::now();
+    let descriptor = run_with_timeout(exe, "parameters").and_then(|s| {
+        serde_json::from_str::<Value>(&s).map_err(|e| format!("invalid parameters JSON ({e})"))
+    });
+    match descriptor {
+        Ok(v) if v["parameters"].is_array() => {
+            if let Some(name) = v["tool_name"].as_str() {
+                if !name.trim().is_empty() {
+                    plugin.tool_name = name.trim().to_string();
+                }
+            }
+            plugin.toolbox = match v["toolbox"].as_str() {
+                Some(tb) if !tb.trim().is_empty() => tb.trim().to_string(),
+                _ => "Plugins".to_string(),
+            };
+            plugin.description = v["short_description"].as_str().unwrap_or("").to_string();
+            plugin.parameters = v;
+        }
+        Ok(_) => plugin.error = Some("the parameters JSON has no 'parameters' array".to_string()),
+        Err(e) => plugin.error = Some(e),
+    }
+
+    // A plugin that hung on 'parameters' is not given a second chance to hang on 'help'.
+    if plugin.failed() && start.elapsed() < INTROSPECTION_TIMEOUT {
+        if let Ok(help) = run_with_timeout(exe, "help") {
+            plugin.description = help
+                .lines()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty())
+                .unwrap_or("")
+                .to_string();
+        }
+    }
+    plugin
+}
+
+/// Runs `exe subcommand` and returns its stdout, killing the process if it does not finish within
+/// the introspection timeout.
+fn run_with_timeout(exe: &Path, subcommand: &str) -> Result<String, String> {
+    let mut child = Command::new(exe)
+        .arg(subcommand)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| format!("could not run '{subcommand}' ({e})"))?;
+
+    // Read stdout on its own thread so a large descriptor can't fill the pipe and stall the child.
+    let mut stdout = child.stdout.take().ok_or("no stdout".to_string())?;
+    let reader = thread::spawn(move || {
+        let mut s = String::new();
+        _ = stdout.read_to_string(&mut s);
+        s
+    });
+
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() < INTROSPECTION_TIMEOUT => {
+                thread::sleep(Duration::from_millis(20))
+            }
+            Ok(None) => {
+                _ = child.kill();
+                _ = child.wait();
+                return Err(format!(
+                    "'{subcommand}' did not finish within {} s",
+                    INTROSPECTION_TIMEOUT.as_secs()
+                ));
+            }
+            Err(e) => return Err(format!("could not run '{subcommand}' ({e})")),
+        }
+    };
+
+    let out = reader.join().unwrap_or_default();
+    if !status.success() {
+        return Err(format!("'{subcommand}' exited with {status}"));
+    }
+    if out.trim().is_empty() {
+        return Err(format!("'{subcommand}' printed nothing"));
+    }
+    Ok(out)
+}
+
+/// The tool name used for a plugin that can't report one: its file name in CamelCase.
+fn default_tool_name(exe: &Path) -> String {
+    let stem = exe
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    stem.split(|c: char| c == '_' || c == '-' || c == ' ')
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut chars = w.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().collect::<String>() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
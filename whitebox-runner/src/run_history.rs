@@ -0,0 +1,176 @@
+use crate::MyApp;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use whitebox_common::configs::platform_settings_file;
+
+/// The number of a tool's most recent successful runs whose median is its typical runtime.
+const RECENT_RUNS: usize = 20;
+
+/// The run history log, one JSON object per finished run, kept beside the platform's
+/// WhiteboxTools settings.json (see `platform_settings_file`) so that it outlives the Runner.
+pub fn history_file() -> Option<PathBuf> {
+    platform_settings_file()
+        .and_then(|file| file.parent().map(|dir| dir.join("runner_history.jsonl")))
+}
+
+/// Appends a finished run to the history log. It is called from the thread watching the run, so
+/// writing never holds up the interface; a log that cannot be written is skipped.
+pub fn record_run(tool_name: &str, elapsed: Duration, succeeded: bool) {
+    let file = match history_file() {
+        Some(file) => file,
+        None => return,
+    };
+    if let Some(dir) = file.parent() {
+        _ = fs::create_dir_all(dir);
+    }
+    let finished = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let record = json!({
+        "tool": tool_name,
+        "finished": finished,
+        "elapsed_seconds": elapsed.as_secs_f64(),
+        "success": succeeded,
+    });
+    if let Ok(mut log) = OpenOptions::new().create(true).append(true).open(&file) {
+        _ = writeln!(log, "{record}");
+    }
+}
+
+/// Returns the typical runtime of each tool in a history log: the median elapsed time of its
+/// most recent successful runs. Failed and cancelled runs, and unreadable lines, are ignored.
+fn typical_runtimes(log: &str) -> HashMap<String, Duration> {
+    let mut runs: HashMap<String, Vec<f64>> = HashMap::new();
+    for line in log.lines() {
+        let record: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if record["success"].as_bool() != Some(true) {
+            continue;
+        }
+        if let (Some(tool), Some(secs)) =
+            (record["tool"].as_str(), record["elapsed_seconds"].as_f64())
+        {
+            if secs.is_finite() && secs >= 0.0 {
+                runs.entry(tool.to_string()).or_default().push(secs);
+            }
+        }
+    }
+    runs.into_iter()
+        .map(|(tool, mut secs)| {
+            let recent = secs.split_off(secs.len().saturating_sub(RECENT_RUNS));
+            (tool, Duration::from_secs_f64(median(recent)))
+        })
+        .collect()
+}
+
+fn median(mut values: Vec<f64>) -> f64 {
+    values.sort_by(|a, b| a.total_cmp(b));
+    let n = values.len();
+    if n % 2 == 1 {
+        values[n / 2]
+    } else {
+        (values[n / 2 - 1] + values[n / 2]) / 2.0
+    }
+}
+
+/// Formats a duration roughly, e.g. "~4 min".
+pub fn approximate_duration(duration: Duration) -> String {
+    let secs = duration.as_secs_f64();
+    if secs < 1.0 {
+        "<1 s".to_string()
+    } else if secs < 60.0 {
+        format!("~{:.0} s", secs)
+    } else if secs < 3600.0 {
+        format!("~{:.0} min", secs / 60.0)
+    } else {
+        format!("~{:.1} h", secs / 3600.0)
+    }
+}
+
+/// The typical runtimes of the tools, read from the history log on a background thread the first
+/// time that they are needed and cached until a run finishes.
+#[derive(Default)]
+pub struct RunTimes {
+    runtimes: HashMap<String, Duration>,
+    load: Option<mpsc::Receiver<HashMap<String, Duration>>>,
+    current: bool,
+}
+
+impl RunTimes {
+    /// Marks the cache as out of date, e.g. once a run has been added to the log. A load that is
+    /// under way may have missed the run, so it is abandoned.
+    pub fn invalidate(&mut self) {
+        self.current = false;
+        self.load = None;
+    }
+}
+
+impl MyApp {
+    /// Starts loading the typical runtimes when the cache is out of date and picks up the result,
+    /// keeping the cached values in the meantime.
+    pub fn poll_run_times(&mut self, ctx: &egui::Context) {
+        let run_times = &mut self.run_times;
+        if run_times.load.is_none() {
+            if run_times.current {
+                return;
+            }
+            let (tx, rx) = mpsc::channel();
+            thread::spawn(move || {
+                let log = history_file()
+                    .and_then(|file| fs::read_to_string(file).ok())
+                    .unwrap_or_default();
+                _ = tx.send(typical_runtimes(&log));
+            });
+            run_times.load = Some(rx);
+        }
+        let result = match &run_times.load {
+            Some(rx) => rx.try_recv(),
+            None => return,
+        };
+        match result {
+            Ok(runtimes) => {
+                run_times.runtimes = runtimes;
+                run_times.load = None;
+                run_times.current = true;
+            }
+            Err(mpsc::TryRecvError::Empty) => {
+                ctx.request_repaint_after(Duration::from_millis(100));
+            }
+            Err(mpsc::TryRecvError::Disconnected) => {
+                run_times.load = None;
+                run_times.current = true;
+            }
+        }
+    }
+
+    /// The median elapsed time of the user's previous successful runs of a tool, if any.
+    pub fn typical_runtime(&self, tool_name: &str) -> Option<Duration> {
+        self.run_times.runtimes.get(tool_name).copied()
+    }
+
+    /// The hover text of a tool in the tools panel: its description and typical runtime.
+    pub fn tool_hover_text(&self, tool_name: &str) -> String {
+        let mut text = self
+            .tool_descriptions
+            .get(tool_name)
+            .cloned()
+            .unwrap_or_default();
+        if let Some(typical) = self.typical_runtime(tool_name) {
+            text.push_str("\n\n");
+            text.push_str(&self.catalog.trf(
+                "run_times.typical",
+                &[("time", &approximate_duration(typical))],
+            ));
+        }
+        text
+    }
+}
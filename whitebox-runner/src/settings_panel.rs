@@ -1,3 +1,5 @@
+use crate::accessibility::icon_button;
+use crate::i18n::{Catalog, DEFAULT_LOCALE};
 use crate::toggle;
 use crate::AppTheme;
 use crate::MyApp;
@@ -39,7 +41,7 @@ impl MyApp {
                             egui::TextEdit::singleline(&mut self.state.working_dir)
                                 .desired_width(self.state.textbox_width),
                         );
-                        if ui.button("…").clicked() {
+                        if icon_button(ui, "…", "Browse for a working directory").clicked() {
                             if let Some(path) = rfd::FileDialog::new()
                                 .set_directory(std::path::Path::new(&self.state.working_dir))
                                 .pick_folder()
@@ -161,6 +163,35 @@ impl MyApp {
                     ui.add(toggle(&mut self.state.check_wbt_updates));
                     ui.end_row();
 
+                    // Confirm before overwriting existing output files
+                    ui.label("Warn before overwriting files?");
+                    let mut warn_on_overwrite = !self.state.skip_overwrite_warning;
+                    if ui.add(toggle(&mut warn_on_overwrite)).clicked() {
+                        self.state.skip_overwrite_warning = !warn_on_overwrite;
+                    }
+                    ui.end_row();
+
+                    // Interface language
+                    ui.label(self.catalog.tr("settings.language"))
+                        .on_hover_text(self.catalog.tr("settings.language_hover"));
+                    let locales = &self.available_locales;
+                    let mut locale_idx = locales
+                        .iter()
+                        .position(|(code, _)| code == self.catalog.locale())
+                        .unwrap_or(0);
+                    let names = locales
+                        .iter()
+                        .map(|(_, name)| name.clone())
+                        .collect::<Vec<String>>();
+                    if egui::ComboBox::from_id_source("Locale combobox")
+                        .show_index(ui, &mut locale_idx, locales.len(), |i| names[i].clone())
+                        .changed()
+                    {
+                        self.state.locale = locales[locale_idx].0.clone();
+                        self.catalog = Catalog::load(&self.state.locale);
+                    }
+                    ui.end_row();
+
                     // Reset button
                     ui.label("Reset settings:");
                     if ui
@@ -185,6 +216,9 @@ impl MyApp {
                         self.state.show_tool_search = false;
                         self.state.show_recent_tools = false;
                         self.state.check_wbt_updates = true;
+                        self.state.skip_overwrite_warning = false;
+                        self.state.locale = DEFAULT_LOCALE.to_string();
+                        self.catalog = Catalog::load(&self.state.locale);
                         self.state.most_recent = std::collections::VecDeque::new();
                     }
                     ui.end_row();
@@ -206,7 +240,7 @@ impl MyApp {
                             egui::TextEdit::singleline(&mut self.state.whitebox_exe)
                                 .desired_width(self.state.textbox_width),
                         );
-                        if ui.button("…").clicked() {
+                        if icon_button(ui, "…", "Browse for the WhiteboxTools executable").clicked() {
                             if let Some(path) = rfd::FileDialog::new()
                                 .set_directory(std::path::Path::new(&self.state.whitebox_exe))
                                 .pick_file()
@@ -237,6 +271,34 @@ impl MyApp {
                     }
                     ui.end_row();
 
+                    // Plugins
+                    ui.label("Plugin tools:");
+                    ui.horizontal(|ui| {
+                        let scanning = self.plugin_scan.is_some();
+                        if ui
+                            .add_enabled(!scanning, egui::Button::new("🔌 Rescan plugins"))
+                            .on_hover_text("Look for new or updated tools in the plugins directory")
+                            .clicked()
+                        {
+                            self.rescan_plugins();
+                        }
+                        if scanning {
+                            ui.label("Scanning…");
+                        } else {
+                            let num_failed = self
+                                .discovered_plugins
+                                .iter()
+                                .filter(|p| p.failed())
+                                .count();
+                            ui.label(&format!(
+                                "{} found, {} unverified",
+                                self.discovered_plugins.len() - num_failed,
+                                num_failed
+                            ));
+                        }
+                    });
+                    ui.end_row();
+
                     // Version
                     ui.label("WhiteboxTools version:");
                     ui.label(
@@ -1,3 +1,6 @@
+use crate::accessibility::icon_button;
+use crate::i18n::Catalog;
+use crate::run_history::approximate_duration;
 use crate::toggle;
 use crate::tool_info::{ParameterFileType, ParameterType, ToolParameter, VectorGeometryType};
 use crate::MyApp;
@@ -8,21 +11,31 @@ use whitebox_vector::{ShapeType, Shapefile};
 impl MyApp {
     pub fn tool_dialog(&mut self, ctx: &egui::Context, tool_idx: usize) {
         let mut close_dialog = false;
+        let mut run_tool = false;
         let mut wk_dir = String::new();
+        let cat = self.catalog.clone();
         _ = self.get_tool_parameters(&self.list_of_open_tools[tool_idx].tool_name);
+        let typical_runtime = self.typical_runtime(&self.list_of_open_tools[tool_idx].tool_name);
         egui::Window::new(&format!("{}", &self.list_of_open_tools[tool_idx].tool_name))
         .id(egui::Id::new(format!("{}-{}", &self.list_of_open_tools[tool_idx].tool_name, tool_idx)))
         .open(&mut self.open_tools[tool_idx])
         .resizable(true)
         .vscroll(false)
         .show(ctx, |ui| {
+            // After a failed run, the parameters named in the tool's error message are highlighted,
+            // keeping their values, and 'Fix and re-run' focuses the first of them.
+            let implicated = self.list_of_open_tools[tool_idx].implicated_parameters();
+            let focus_parameter = self.list_of_open_tools[tool_idx].focus_parameter.take();
 
             ui.horizontal(|ui| {
-                ui.label(egui::RichText::new("Tool parameters:").strong());
+                ui.label(egui::RichText::new(cat.tr("tool_dialog.parameters")).strong());
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                    if ui.button("🔃").on_hover_text("Reset parameters").clicked() { // ⟲
+                    if icon_button(ui, "🔃", cat.tr("tool_dialog.reset_parameters_hover")).clicked() { // ⟲
                         self.list_of_open_tools[tool_idx].reset();
                     }
+                    if let Some(typical) = typical_runtime {
+                        ui.label(egui::RichText::new(cat.trf("run_times.typical", &[("time", &approximate_duration(typical))])).weak());
+                    }
                 });
             });
             // ui.separator();
@@ -58,703 +71,756 @@ impl MyApp {
                         }
                     }
                     let mut flagged_parameter_idx = 0;
-                    for parameter in &mut (self.list_of_open_tools[tool_idx].parameters) {
+
+                    // Parameter hints from the tool are also resolved before the loop: a parameter is
+                    // greyed out until the parameters that it requires are specified, or while one that
+                    // it conflicts with is.
+                    let enabled: Vec<bool> = (0..self.list_of_open_tools[tool_idx].parameters.len())
+                        .map(|m| self.list_of_open_tools[tool_idx].parameter_is_enabled(m))
+                        .collect();
+                    let hint_text: Vec<String> = self.list_of_open_tools[tool_idx].parameters.iter().map(|p| {
+                        let names = |flags: &Vec<String>| flags.iter().map(|f| self.list_of_open_tools[tool_idx].flag_name(f)).collect::<Vec<String>>().join(", ");
+                        let mut s = String::new();
+                        if !p.requires.is_empty() {
+                            s.push_str(&format!("\n{}", cat.trf("tool_dialog.requires", &[("names", &names(&p.requires))])));
+                        }
+                        if !p.requires_one_of.is_empty() {
+                            s.push_str(&format!("\n{}", cat.trf("tool_dialog.requires_one_of", &[("names", &names(&p.requires_one_of))])));
+                        }
+                        if !p.conflicts_with.is_empty() {
+                            s.push_str(&format!("\n{}", cat.trf("tool_dialog.conflicts_with", &[("names", &names(&p.conflicts_with))])));
+                        }
+                        s
+                    }).collect();
+
+                    let mut current_group = String::new();
+                    for (idx, parameter) in self.list_of_open_tools[tool_idx].parameters.iter_mut().enumerate() {
+                        // Parameters that the tool groups together are listed under a heading.
+                        if !parameter.group.is_empty() && parameter.group != current_group {
+                            ui.label(egui::RichText::new(&parameter.group).strong());
+                            ui.end_row();
+                        }
+                        current_group = parameter.group.clone();
+
                         let suffix = if parameter.optional { "*".to_string() } else { "".to_string() };
                         let parameter_label = if parameter.name.len() + suffix.len() < 25 {
                             format!("{}{}", &parameter.name, suffix)
                         } else {
                             format!("{}...{}", &parameter.name[0..(22-suffix.len())], suffix)
                         };
-                        let param_nm = if !parameter.optional { parameter.name.clone() } else { format!("{} [Optional]", parameter.name) };
+                        let param_nm = if !parameter.optional { parameter.name.clone() } else { cat.trf("tool_dialog.optional_parameter", &[("name", &parameter.name)]) };
                         let hover_text = match parameter.file_type {
                             ParameterFileType::Vector | ParameterFileType::RasterAndVector => {
-                                format!("{}:  {} (Geometry Type={:?})", param_nm, parameter.description, parameter.geometry_type)
+                                format!("{}:  {} ({}={:?})", param_nm, parameter.description, cat.tr("tool_dialog.geometry_type"), parameter.geometry_type)
                             },
                             _ => {
                                 format!("{}:  {}", param_nm, parameter.description)
                             }
                         };
-                        ui.label(&parameter_label)
-                        .on_hover_text(&hover_text);
+                        let hover_text = format!("{}{}", hover_text, hint_text[idx]);
+                        let indent = if parameter.group.is_empty() { "" } else { "  " };
+                        let is_implicated = implicated.contains(&idx);
+                        let (label_text, hover_text) = if is_implicated {
+                            (
+                                egui::RichText::new(format!("{}⚠ {}", indent, &parameter_label)).color(ui.visuals().error_fg_color),
+                                format!("{}\n{}", hover_text, cat.tr("tool_dialog.implicated_hover"))
+                            )
+                        } else {
+                            (egui::RichText::new(format!("{}{}", indent, &parameter_label)), hover_text)
+                        };
+                        ui.add_enabled(enabled[idx], egui::Label::new(label_text))
+                        .on_hover_text(&hover_text)
+                        .on_disabled_hover_text(&hover_text);
 
-                        match parameter.parameter_type {
-                            ParameterType::Boolean => {
-                                ui.add(toggle(&mut parameter.bool_value));
-                            },
-                            ParameterType::Directory => {
-                                ui.horizontal(|ui| {
-                                    if ui.add(
-                                        egui::TextEdit::singleline(&mut parameter.str_value)
-                                        .desired_width(self.state.textbox_width - 22.0)
-                                    ).double_clicked() {
-                                        let fdialog = get_file_dialog(&parameter.file_type); 
-                                        if let Some(path) = fdialog
-                                        .set_directory(std::path::Path::new(&self.state.working_dir))
-                                        .pick_file() {
-                                            parameter.str_value = path.display().to_string();
-                                            // update the working directory
-                                            // path.pop();
-                                            // self.state.working_dir = path.display().to_string();
-                                            wk_dir = path.display().to_string();
+                        let mut main_response: Option<egui::Response> = None;
+                        ui.add_enabled_ui(enabled[idx], |ui| {
+                            match parameter.parameter_type {
+                                ParameterType::Boolean => {
+                                    main_response = Some(ui.add(toggle(&mut parameter.bool_value)));
+                                },
+                                ParameterType::Directory => {
+                                    ui.horizontal(|ui| {
+                                        let resp = ui.add(
+                                            egui::TextEdit::singleline(&mut parameter.str_value)
+                                            .desired_width(self.state.textbox_width - 22.0)
+                                        );
+                                        main_response = Some(resp.clone());
+                                        if resp.double_clicked() {
+                                            let fdialog = get_file_dialog(&parameter.file_type); 
+                                            if let Some(path) = fdialog
+                                            .set_directory(std::path::Path::new(&self.state.working_dir))
+                                            .pick_file() {
+                                                parameter.str_value = path.display().to_string();
+                                                // update the working directory
+                                                // path.pop();
+                                                // self.state.working_dir = path.display().to_string();
+                                                wk_dir = path.display().to_string();
+                                            }
                                         }
-                                    }
     
-                                    ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
+                                        ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
     
-                                    ui.menu_button("⏷", |ui| {
-                                        ui.set_min_width(150.);
-                                        ui.set_max_width(250.);
-                                        egui::ScrollArea::both()
-                                        .max_height(400.0)
-                                        .auto_shrink([true, true])
-                                        .show(ui, |ui| {
-                                            if self.state.recent_working_dirs.len() > 0 {
-                                                for q in (0..self.state.recent_working_dirs.len()).rev() {
-                                                    if let Some(lbl) = Path::new(&self.state.recent_working_dirs[q]).file_name() {
-                                                        let lbl_str = lbl.to_str().unwrap_or(&self.state.recent_working_dirs[q]).to_string();
-                                                        if ui.button(&lbl_str).clicked() {
-                                                            parameter.str_value = self.state.recent_working_dirs[q].clone();
-                                                            ui.close_menu();
+                                        ui.menu_button("⏷", |ui| {
+                                            ui.set_min_width(150.);
+                                            ui.set_max_width(250.);
+                                            egui::ScrollArea::both()
+                                            .max_height(400.0)
+                                            .auto_shrink([true, true])
+                                            .show(ui, |ui| {
+                                                if self.state.recent_working_dirs.len() > 0 {
+                                                    for q in (0..self.state.recent_working_dirs.len()).rev() {
+                                                        if let Some(lbl) = Path::new(&self.state.recent_working_dirs[q]).file_name() {
+                                                            let lbl_str = lbl.to_str().unwrap_or(&self.state.recent_working_dirs[q]).to_string();
+                                                            if ui.button(&lbl_str).clicked() {
+                                                                parameter.str_value = self.state.recent_working_dirs[q].clone();
+                                                                ui.close_menu();
+                                                            }
                                                         }
                                                     }
+                                                } else {
+                                                    if ui.button(cat.tr("tool_dialog.no_recent_dirs")).clicked() {
+                                                        ui.close_menu();
+                                                    }
                                                 }
-                                            } else {
-                                                if ui.button("There are no recent working directories available. Please press `...` to select one.").clicked() {
-                                                    ui.close_menu();
-                                                }
-                                            }
     
-                                        });
-                                    });    
-                                });
+                                            });
+                                        });    
+                                    });
                                 
-                                if ui.button("…").clicked() {
-                                    if let Some(path) = rfd::FileDialog::new().set_directory(std::path::Path::new(&self.state.working_dir)).pick_folder() {
-                                        parameter.str_value = path.display().to_string();
+                                    if icon_button(ui, "…", cat.tr("tool_dialog.browse_hover")).clicked() {
+                                        if let Some(path) = rfd::FileDialog::new().set_directory(std::path::Path::new(&self.state.working_dir)).pick_folder() {
+                                            parameter.str_value = path.display().to_string();
+                                        }
                                     }
-                                }
-                            },
-                            ParameterType::ExistingFile => {
-                                ui.horizontal(|ui| {
-                                    let resp = ui.add(
-                                        egui::TextEdit::singleline(&mut parameter.str_value)
-                                        .desired_width(self.state.textbox_width - 22.0)
-                                        .id_source(&parameter.name)
-                                    );
-                                    if resp.lost_focus() {
-                                        if !parameter.str_value.is_empty() && !path::Path::new(&parameter.str_value).exists() {
-                                            // prepend the working directory and see if that file exists.
-                                            let f = path::Path::new(&self.state.working_dir).join(&parameter.str_value);
-                                            if f.exists() {
-                                                parameter.str_value = f.to_str().unwrap_or("").to_string();
-                                            } else {
-                                                if rfd::MessageDialog::new()
-                                                .set_level(rfd::MessageLevel::Warning)
-                                                .set_title("File does not exist")
-                                                .set_description("The specified file does not exist in the current working directory. Do you want to continue?")
-                                                .set_buttons(rfd::MessageButtons::YesNo)
-                                                .show() {
-                                                    // do nothing
+                                },
+                                ParameterType::ExistingFile => {
+                                    ui.horizontal(|ui| {
+                                        let resp = ui.add(
+                                            egui::TextEdit::singleline(&mut parameter.str_value)
+                                            .desired_width(self.state.textbox_width - 22.0)
+                                            .id_source(&parameter.name)
+                                        );
+                                        main_response = Some(resp.clone());
+                                        if resp.lost_focus() {
+                                            if !parameter.str_value.is_empty() && !path::Path::new(&parameter.str_value).exists() {
+                                                // prepend the working directory and see if that file exists.
+                                                let f = path::Path::new(&self.state.working_dir).join(&parameter.str_value);
+                                                if f.exists() {
+                                                    parameter.str_value = f.to_str().unwrap_or("").to_string();
                                                 } else {
-                                                    // Reset the parameter string value.
-                                                    parameter.str_value = "".to_string();
+                                                    if rfd::MessageDialog::new()
+                                                    .set_level(rfd::MessageLevel::Warning)
+                                                    .set_title(cat.tr("tool_dialog.file_not_found_title"))
+                                                    .set_description(cat.tr("tool_dialog.file_not_found"))
+                                                    .set_buttons(rfd::MessageButtons::YesNo)
+                                                    .show() {
+                                                        // do nothing
+                                                    } else {
+                                                        // Reset the parameter string value.
+                                                        parameter.str_value = "".to_string();
+                                                    }
                                                 }
                                             }
                                         }
-                                    }
-                                    if resp.double_clicked() {
-                                        let fdialog = get_file_dialog(&parameter.file_type); 
-                                        if let Some(path) = fdialog
-                                        .set_directory(std::path::Path::new(&self.state.working_dir))
-                                        .pick_file() {
-                                            parameter.str_value = path.display().to_string();
+                                        if resp.double_clicked() {
+                                            let fdialog = get_file_dialog(&parameter.file_type); 
+                                            if let Some(path) = fdialog
+                                            .set_directory(std::path::Path::new(&self.state.working_dir))
+                                            .pick_file() {
+                                                parameter.str_value = path.display().to_string();
                                             
-                                            if parameter.file_type == ParameterFileType::Vector && 
-                                            parameter.geometry_type != VectorGeometryType::Any {
-                                                check_geometry_type(parameter, &self.state.working_dir);
-                                            }
+                                                if parameter.file_type == ParameterFileType::Vector && 
+                                                parameter.geometry_type != VectorGeometryType::Any {
+                                                    check_geometry_type(parameter, &self.state.working_dir, &cat);
+                                                }
 
-                                            // update the working directory
-                                            // path.pop();
-                                            // self.state.working_dir = path.display().to_string();
-                                            // self.update_working_dir(&path.display().to_string());
-                                            wk_dir = path.display().to_string();
+                                                // update the working directory
+                                                // path.pop();
+                                                // self.state.working_dir = path.display().to_string();
+                                                // self.update_working_dir(&path.display().to_string());
+                                                wk_dir = path.display().to_string();
+                                            }
                                         }
-                                    }
                                     
-                                    ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
-
-                                    ui.menu_button("⏷", |ui| {
-                                        ui.set_min_width(150.);
-                                        ui.set_max_width(250.);
-                                        if self.state.recent_working_dirs.len() == 0 {
-                                            if ui.button("The current working directory is not set. Press `...` to choose a new directory instead.").clicked() {
-                                                ui.close_menu();
-                                            }
-                                        } else {
-                                            egui::ScrollArea::both()
-                                            .max_height(400.0)
-                                            .auto_shrink([true, true])
-                                            .show(ui, |ui| {
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    ui.label(egui::RichText::new("Recent directories:")
-                                                    .italics()
-                                                    .strong()
-                                                    .color(ui.visuals().hyperlink_color));
+                                        ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
+
+                                        ui.menu_button("⏷", |ui| {
+                                            ui.set_min_width(150.);
+                                            ui.set_max_width(250.);
+                                            if self.state.recent_working_dirs.len() == 0 {
+                                                if ui.button(cat.tr("tool_dialog.working_dir_not_set")).clicked() {
+                                                    ui.close_menu();
                                                 }
-                                                // first find all the files in each of the recent directories, except the most recent.
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    for q in (0..self.state.recent_working_dirs.len()-1).rev() { // The '-1' excludes the most recent dir.
-                                                        let extensions = get_file_extensions(&parameter.file_type);
-                                                        let dir = &self.state.recent_working_dirs[q];
+                                            } else {
+                                                egui::ScrollArea::both()
+                                                .max_height(400.0)
+                                                .auto_shrink([true, true])
+                                                .show(ui, |ui| {
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        ui.label(egui::RichText::new(cat.tr("tool_dialog.recent_directories"))
+                                                        .italics()
+                                                        .strong()
+                                                        .color(ui.visuals().hyperlink_color));
+                                                    }
+                                                    // first find all the files in each of the recent directories, except the most recent.
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        for q in (0..self.state.recent_working_dirs.len()-1).rev() { // The '-1' excludes the most recent dir.
+                                                            let extensions = get_file_extensions(&parameter.file_type);
+                                                            let dir = &self.state.recent_working_dirs[q];
             
-                                                        let mut files: Vec<String> = vec![];
-                                                        if let Ok(paths) = fs::read_dir(dir) {
-                                                            for path in paths {
-                                                                if let Ok(dir_entry) = path {
-                                                                    let p = dir_entry.path();
-                                                                    if p.is_file() {
-                                                                        if !extensions.is_empty() {
-                                                                            if let Some(exe) = p.extension() {
-                                                                                let ext_str = exe.to_str().unwrap_or("").to_lowercase();
-                                                                                for e in &extensions {
-                                                                                    if e.to_lowercase() == ext_str {
-                                                                                        if let Some(short_fn) = p.file_name() {
-                                                                                            files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                                            break;
+                                                            let mut files: Vec<String> = vec![];
+                                                            if let Ok(paths) = fs::read_dir(dir) {
+                                                                for path in paths {
+                                                                    if let Ok(dir_entry) = path {
+                                                                        let p = dir_entry.path();
+                                                                        if p.is_file() {
+                                                                            if !extensions.is_empty() {
+                                                                                if let Some(exe) = p.extension() {
+                                                                                    let ext_str = exe.to_str().unwrap_or("").to_lowercase();
+                                                                                    for e in &extensions {
+                                                                                        if e.to_lowercase() == ext_str {
+                                                                                            if let Some(short_fn) = p.file_name() {
+                                                                                                files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                                break;
+                                                                                            }
                                                                                         }
                                                                                     }
                                                                                 }
-                                                                            }
-                                                                        } else {
-                                                                            if let Some(short_fn) = p.file_name() {
-                                                                                files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                            }
-                                                                        }                                        
+                                                                            } else {
+                                                                                if let Some(short_fn) = p.file_name() {
+                                                                                    files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                }
+                                                                            }                                        
+                                                                        }
                                                                     }
                                                                 }
                                                             }
-                                                        }
             
-                                                        if files.len() > 0 {
-                                                            files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                                                            if let Some(lbl) = Path::new(dir).file_name() {
-                                                                let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                                ui.menu_button(&lbl_str, |ui| {
-                                                                    ui.set_min_width(150.);
-                                                                    ui.set_max_width(250.);
-
-                                                                    egui::ScrollArea::both()
-                                                                    .max_height(400.0)
-                                                                    .auto_shrink([true; 2])
-                                                                    .show(ui, |ui| {
-                                                                        if let Some(lbl) = Path::new(dir).file_name() {
-                                                                            let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                                            ui.label(egui::RichText::new(&format!("Files in {lbl_str}:"))
-                                                                            .italics()
-                                                                            .strong()
-                                                                            .color(ui.visuals().hyperlink_color));
-                                                                        }
+                                                            if files.len() > 0 {
+                                                                files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+                                                                if let Some(lbl) = Path::new(dir).file_name() {
+                                                                    let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                                    ui.menu_button(&lbl_str, |ui| {
+                                                                        ui.set_min_width(150.);
+                                                                        ui.set_max_width(250.);
 
-                                                                        for file in &files {
-                                                                            if ui.add(egui::Button::new(file)).clicked() {
-                                                                                parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
+                                                                        egui::ScrollArea::both()
+                                                                        .max_height(400.0)
+                                                                        .auto_shrink([true; 2])
+                                                                        .show(ui, |ui| {
+                                                                            if let Some(lbl) = Path::new(dir).file_name() {
+                                                                                let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                                                ui.label(egui::RichText::new(cat.trf("tool_dialog.files_in", &[("dir", &lbl_str)]))
+                                                                                .italics()
+                                                                                .strong()
+                                                                                .color(ui.visuals().hyperlink_color));
+                                                                            }
+
+                                                                            for file in &files {
+                                                                                if ui.add(egui::Button::new(file)).clicked() {
+                                                                                    parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
             
-                                                                                if parameter.file_type == ParameterFileType::Vector && 
-                                                                                parameter.geometry_type != VectorGeometryType::Any {
-                                                                                    check_geometry_type(parameter, &dir);
-                                                                                }
+                                                                                    if parameter.file_type == ParameterFileType::Vector && 
+                                                                                    parameter.geometry_type != VectorGeometryType::Any {
+                                                                                        check_geometry_type(parameter, &dir, &cat);
+                                                                                    }
             
-                                                                                wk_dir = parameter.str_value.clone();
-                                                                                ui.close_menu();
+                                                                                    wk_dir = parameter.str_value.clone();
+                                                                                    ui.close_menu();
+                                                                                }
                                                                             }
-                                                                        }
+                                                                        });
                                                                     });
-                                                                });
-                                                                ui.add_space(1.);
+                                                                    ui.add_space(1.);
+                                                                }
                                                             }
                                                         }
                                                     }
-                                                }
 
-                                                // now do the current working directory
-                                                let extensions = get_file_extensions(&parameter.file_type);
-                                                let dir = &self.state.recent_working_dirs[self.state.recent_working_dirs.len()-1];
+                                                    // now do the current working directory
+                                                    let extensions = get_file_extensions(&parameter.file_type);
+                                                    let dir = &self.state.recent_working_dirs[self.state.recent_working_dirs.len()-1];
     
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    ui.separator();
-                                                }
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        ui.separator();
+                                                    }
 
-                                                if let Some(lbl) = Path::new(dir).file_name() {
-                                                    let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                    ui.label(egui::RichText::new(&format!("Files in {lbl_str}:"))
-                                                    .italics()
-                                                    .strong()
-                                                    .color(ui.visuals().hyperlink_color));
-                                                }
+                                                    if let Some(lbl) = Path::new(dir).file_name() {
+                                                        let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                        ui.label(egui::RichText::new(cat.trf("tool_dialog.files_in", &[("dir", &lbl_str)]))
+                                                        .italics()
+                                                        .strong()
+                                                        .color(ui.visuals().hyperlink_color));
+                                                    }
 
-                                                let mut files: Vec<String> = vec![];
-                                                if let Ok(paths) = fs::read_dir(dir) {
-                                                    for path in paths {
-                                                        if let Ok(dir_entry) = path {
-                                                            let p = dir_entry.path();
-                                                            if p.is_file() {
-                                                                if !extensions.is_empty() {
-                                                                    if let Some(exe) = p.extension() {
-                                                                        let ext_str = exe.to_str().unwrap_or("").to_lowercase();
-                                                                        for e in &extensions {
-                                                                            if e.to_lowercase() == ext_str {
-                                                                                if let Some(short_fn) = p.file_name() {
-                                                                                    files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                                    break;
+                                                    let mut files: Vec<String> = vec![];
+                                                    if let Ok(paths) = fs::read_dir(dir) {
+                                                        for path in paths {
+                                                            if let Ok(dir_entry) = path {
+                                                                let p = dir_entry.path();
+                                                                if p.is_file() {
+                                                                    if !extensions.is_empty() {
+                                                                        if let Some(exe) = p.extension() {
+                                                                            let ext_str = exe.to_str().unwrap_or("").to_lowercase();
+                                                                            for e in &extensions {
+                                                                                if e.to_lowercase() == ext_str {
+                                                                                    if let Some(short_fn) = p.file_name() {
+                                                                                        files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                        break;
+                                                                                    }
                                                                                 }
                                                                             }
                                                                         }
-                                                                    }
-                                                                } else {
-                                                                    if let Some(short_fn) = p.file_name() {
-                                                                        files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                    }
-                                                                }                                        
+                                                                    } else {
+                                                                        if let Some(short_fn) = p.file_name() {
+                                                                            files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                        }
+                                                                    }                                        
+                                                                }
                                                             }
                                                         }
                                                     }
-                                                }
     
-                                                if files.len() > 0 {
-                                                    files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                                                    for file in &files {
-                                                        if ui.add(egui::Button::new(file)).clicked() {
-                                                            parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
-
-                                                            if parameter.file_type == ParameterFileType::Vector && 
-                                                            parameter.geometry_type != VectorGeometryType::Any {
-                                                                check_geometry_type(parameter, &dir);
-                                                            }
+                                                    if files.len() > 0 {
+                                                        files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+                                                        for file in &files {
+                                                            if ui.add(egui::Button::new(file)).clicked() {
+                                                                parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
 
-                                                            wk_dir = parameter.str_value.clone();
+                                                                if parameter.file_type == ParameterFileType::Vector && 
+                                                                parameter.geometry_type != VectorGeometryType::Any {
+                                                                    check_geometry_type(parameter, &dir, &cat);
+                                                                }
+
+                                                                wk_dir = parameter.str_value.clone();
+                                                                ui.close_menu();
+                                                            }
+                                                        }
+                                                    } else {
+                                                        if ui.button(cat.tr("tool_dialog.no_matching_files")).clicked() {
                                                             ui.close_menu();
                                                         }
                                                     }
-                                                } else {
-                                                    if ui.button("No file of the required type are within the current working directory. Press `...` to choose a new directory instead.").clicked() {
-                                                        ui.close_menu();
-                                                    }
-                                                }
-                                            });
+                                                });
 
-                                        }
+                                            }
                                         
+                                        });
                                     });
-                                });
 
-                                if ui.button("…").clicked() {
-                                    let fdialog = get_file_dialog(&parameter.file_type); 
-                                    if let Some(path) = fdialog
-                                    .set_directory(std::path::Path::new(&self.state.working_dir))
-                                    .pick_file() {
-                                        parameter.str_value = path.display().to_string();
-
-                                        if parameter.file_type == ParameterFileType::Vector && 
-                                        parameter.geometry_type != VectorGeometryType::Any {
-                                            check_geometry_type(parameter, &self.state.working_dir);
-                                        }
-
-                                        // update the working directory
-                                        // path.pop();
-                                        // self.state.working_dir = path.display().to_string();
-                                        // self.update_working_dir(&path.display().to_string());
-                                        wk_dir = path.display().to_string();
-                                    }
-                                }
-                            },
-                            ParameterType::ExistingFileOrFloat => {
-                                ui.horizontal(|ui| {
-                                    if ui.add(
-                                        egui::TextEdit::singleline(&mut parameter.str_value)
-                                        .desired_width(self.state.textbox_width - 22.0)
-                                    ).double_clicked() {
+                                    if icon_button(ui, "…", cat.tr("tool_dialog.browse_hover")).clicked() {
                                         let fdialog = get_file_dialog(&parameter.file_type); 
                                         if let Some(path) = fdialog
                                         .set_directory(std::path::Path::new(&self.state.working_dir))
                                         .pick_file() {
                                             parameter.str_value = path.display().to_string();
+
+                                            if parameter.file_type == ParameterFileType::Vector && 
+                                            parameter.geometry_type != VectorGeometryType::Any {
+                                                check_geometry_type(parameter, &self.state.working_dir, &cat);
+                                            }
+
+                                            // update the working directory
+                                            // path.pop();
+                                            // self.state.working_dir = path.display().to_string();
+                                            // self.update_working_dir(&path.display().to_string());
                                             wk_dir = path.display().to_string();
                                         }
                                     }
+                                },
+                                ParameterType::ExistingFileOrFloat => {
+                                    ui.horizontal(|ui| {
+                                        let resp = ui.add(
+                                            egui::TextEdit::singleline(&mut parameter.str_value)
+                                            .desired_width(self.state.textbox_width - 22.0)
+                                        );
+                                        main_response = Some(resp.clone());
+                                        if resp.double_clicked() {
+                                            let fdialog = get_file_dialog(&parameter.file_type); 
+                                            if let Some(path) = fdialog
+                                            .set_directory(std::path::Path::new(&self.state.working_dir))
+                                            .pick_file() {
+                                                parameter.str_value = path.display().to_string();
+                                                wk_dir = path.display().to_string();
+                                            }
+                                        }
 
-                                    ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
+                                        ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
 
-                                    ui.menu_button("⏷", |ui| {
-                                        ui.set_min_width(150.);
-                                        ui.set_max_width(250.);
-                                        if self.state.recent_working_dirs.len() == 0 {
-                                            if ui.button("The current working directory is not set. Press `...` to choose a new directory instead.").clicked() {
-                                                ui.close_menu();
-                                            }
-                                        } else {
-                                            egui::ScrollArea::both()
-                                            .max_height(400.0)
-                                            .auto_shrink([true, true])
-                                            .show(ui, |ui| {
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    ui.label(egui::RichText::new("Recent directories:")
-                                                    .italics()
-                                                    .strong()
-                                                    .color(ui.visuals().hyperlink_color));
+                                        ui.menu_button("⏷", |ui| {
+                                            ui.set_min_width(150.);
+                                            ui.set_max_width(250.);
+                                            if self.state.recent_working_dirs.len() == 0 {
+                                                if ui.button(cat.tr("tool_dialog.working_dir_not_set")).clicked() {
+                                                    ui.close_menu();
                                                 }
-                                                // first find all the files in each of the recent directories, except the most recent.
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    for q in (0..self.state.recent_working_dirs.len()-1).rev() { // The '-1' excludes the most recent dir.
-                                                        let extensions = get_file_extensions(&parameter.file_type);
-                                                        let dir = &self.state.recent_working_dirs[q];
+                                            } else {
+                                                egui::ScrollArea::both()
+                                                .max_height(400.0)
+                                                .auto_shrink([true, true])
+                                                .show(ui, |ui| {
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        ui.label(egui::RichText::new(cat.tr("tool_dialog.recent_directories"))
+                                                        .italics()
+                                                        .strong()
+                                                        .color(ui.visuals().hyperlink_color));
+                                                    }
+                                                    // first find all the files in each of the recent directories, except the most recent.
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        for q in (0..self.state.recent_working_dirs.len()-1).rev() { // The '-1' excludes the most recent dir.
+                                                            let extensions = get_file_extensions(&parameter.file_type);
+                                                            let dir = &self.state.recent_working_dirs[q];
             
-                                                        let mut files: Vec<String> = vec![];
-                                                        if let Ok(paths) = fs::read_dir(dir) {
-                                                            for path in paths {
-                                                                if let Ok(dir_entry) = path {
-                                                                    let p = dir_entry.path();
-                                                                    if p.is_file() {
-                                                                        if !extensions.is_empty() {
-                                                                            if let Some(exe) = p.extension() {
-                                                                                let ext_str = exe.to_str().unwrap_or("").to_lowercase();
-                                                                                for e in &extensions {
-                                                                                    if e.to_lowercase() == ext_str {
-                                                                                        if let Some(short_fn) = p.file_name() {
-                                                                                            files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                                            break;
+                                                            let mut files: Vec<String> = vec![];
+                                                            if let Ok(paths) = fs::read_dir(dir) {
+                                                                for path in paths {
+                                                                    if let Ok(dir_entry) = path {
+                                                                        let p = dir_entry.path();
+                                                                        if p.is_file() {
+                                                                            if !extensions.is_empty() {
+                                                                                if let Some(exe) = p.extension() {
+                                                                                    let ext_str = exe.to_str().unwrap_or("").to_lowercase();
+                                                                                    for e in &extensions {
+                                                                                        if e.to_lowercase() == ext_str {
+                                                                                            if let Some(short_fn) = p.file_name() {
+                                                                                                files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                                break;
+                                                                                            }
                                                                                         }
                                                                                     }
                                                                                 }
-                                                                            }
-                                                                        } else {
-                                                                            if let Some(short_fn) = p.file_name() {
-                                                                                files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                            }
-                                                                        }                                        
+                                                                            } else {
+                                                                                if let Some(short_fn) = p.file_name() {
+                                                                                    files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                }
+                                                                            }                                        
+                                                                        }
                                                                     }
                                                                 }
                                                             }
-                                                        }
             
-                                                        if files.len() > 0 {
-                                                            files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                                                            if let Some(lbl) = Path::new(dir).file_name() {
-                                                                let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                                ui.menu_button(&lbl_str, |ui| {
-                                                                    ui.set_min_width(150.);
-                                                                    ui.set_max_width(250.);
-
-                                                                    egui::ScrollArea::both()
-                                                                    .max_height(400.0)
-                                                                    .auto_shrink([true; 2])
-                                                                    .show(ui, |ui| {
-                                                                        if let Some(lbl) = Path::new(dir).file_name() {
-                                                                            let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                                            ui.label(egui::RichText::new(&format!("Files in {lbl_str}:"))
-                                                                            .italics()
-                                                                            .strong()
-                                                                            .color(ui.visuals().hyperlink_color));
-                                                                        }
+                                                            if files.len() > 0 {
+                                                                files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+                                                                if let Some(lbl) = Path::new(dir).file_name() {
+                                                                    let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                                    ui.menu_button(&lbl_str, |ui| {
+                                                                        ui.set_min_width(150.);
+                                                                        ui.set_max_width(250.);
+
+                                                                        egui::ScrollArea::both()
+                                                                        .max_height(400.0)
+                                                                        .auto_shrink([true; 2])
+                                                                        .show(ui, |ui| {
+                                                                            if let Some(lbl) = Path::new(dir).file_name() {
+                                                                                let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                                                ui.label(egui::RichText::new(cat.trf("tool_dialog.files_in", &[("dir", &lbl_str)]))
+                                                                                .italics()
+                                                                                .strong()
+                                                                                .color(ui.visuals().hyperlink_color));
+                                                                            }
                                                                         
-                                                                        for file in &files {
-                                                                            if ui.add(egui::Button::new(file)).clicked() {
-                                                                                parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
+                                                                            for file in &files {
+                                                                                if ui.add(egui::Button::new(file)).clicked() {
+                                                                                    parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
             
-                                                                                if parameter.file_type == ParameterFileType::Vector && 
-                                                                                parameter.geometry_type != VectorGeometryType::Any {
-                                                                                    check_geometry_type(parameter, &dir);
-                                                                                }
+                                                                                    if parameter.file_type == ParameterFileType::Vector && 
+                                                                                    parameter.geometry_type != VectorGeometryType::Any {
+                                                                                        check_geometry_type(parameter, &dir, &cat);
+                                                                                    }
             
-                                                                                wk_dir = parameter.str_value.clone();
-                                                                                ui.close_menu();
+                                                                                    wk_dir = parameter.str_value.clone();
+                                                                                    ui.close_menu();
+                                                                                }
                                                                             }
-                                                                        }
+                                                                        });
                                                                     });
-                                                                });
-                                                                ui.add_space(1.);
+                                                                    ui.add_space(1.);
+                                                                }
                                                             }
                                                         }
                                                     }
-                                                }
 
-                                                // now do the current working directory
-                                                let extensions = get_file_extensions(&parameter.file_type);
-                                                let dir = &self.state.recent_working_dirs[self.state.recent_working_dirs.len()-1];
+                                                    // now do the current working directory
+                                                    let extensions = get_file_extensions(&parameter.file_type);
+                                                    let dir = &self.state.recent_working_dirs[self.state.recent_working_dirs.len()-1];
     
-                                                if self.state.recent_working_dirs.len() > 1 {
-                                                    ui.separator();
-                                                }
+                                                    if self.state.recent_working_dirs.len() > 1 {
+                                                        ui.separator();
+                                                    }
 
-                                                if let Some(lbl) = Path::new(dir).file_name() {
-                                                    let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
-                                                    ui.label(egui::RichText::new(&format!("Files in {lbl_str}:"))
-                                                    .italics()
-                                                    .strong()
-                                                    .color(ui.visuals().hyperlink_color));
-                                                }
+                                                    if let Some(lbl) = Path::new(dir).file_name() {
+                                                        let lbl_str = lbl.to_str().unwrap_or(dir).to_string();
+                                                        ui.label(egui::RichText::new(cat.trf("tool_dialog.files_in", &[("dir", &lbl_str)]))
+                                                        .italics()
+                                                        .strong()
+                                                        .color(ui.visuals().hyperlink_color));
+                                                    }
 
-                                                let mut files: Vec<String> = vec![];
-                                                if let Ok(paths) = fs::read_dir(dir) {
-                                                    for path in paths {
-                                                        if let Ok(dir_entry) = path {
-                                                            let p = dir_entry.path();
-                                                            if p.is_file() {
-                                                                if !extensions.is_empty() {
-                                                                    if let Some(exe) = p.extension() {
-                                                                        let ext_str = exe.to_str().unwrap_or("").to_lowercase();
-                                                                        for e in &extensions {
-                                                                            if e.to_lowercase() == ext_str {
-                                                                                if let Some(short_fn) = p.file_name() {
-                                                                                    files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                                    break;
+                                                    let mut files: Vec<String> = vec![];
+                                                    if let Ok(paths) = fs::read_dir(dir) {
+                                                        for path in paths {
+                                                            if let Ok(dir_entry) = path {
+                                                                let p = dir_entry.path();
+                                                                if p.is_file() {
+                                                                    if !extensions.is_empty() {
+                                                                        if let Some(exe) = p.extension() {
+                                                                            let ext_str = exe.to_str().unwrap_or("").to_lowercase();
+                                                                            for e in &extensions {
+                                                                                if e.to_lowercase() == ext_str {
+                                                                                    if let Some(short_fn) = p.file_name() {
+                                                                                        files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                                        break;
+                                                                                    }
                                                                                 }
                                                                             }
                                                                         }
-                                                                    }
-                                                                } else {
-                                                                    if let Some(short_fn) = p.file_name() {
-                                                                        files.push(short_fn.to_str().unwrap_or("").to_string());
-                                                                    }
-                                                                }                                        
+                                                                    } else {
+                                                                        if let Some(short_fn) = p.file_name() {
+                                                                            files.push(short_fn.to_str().unwrap_or("").to_string());
+                                                                        }
+                                                                    }                                        
+                                                                }
                                                             }
                                                         }
                                                     }
-                                                }
     
-                                                if files.len() > 0 {
-                                                    files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
-                                                    for file in &files {
-                                                        if ui.add(egui::Button::new(file)).clicked() {
-                                                            parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
-
-                                                            if parameter.file_type == ParameterFileType::Vector && 
-                                                            parameter.geometry_type != VectorGeometryType::Any {
-                                                                check_geometry_type(parameter, &dir);
-                                                            }
+                                                    if files.len() > 0 {
+                                                        files.sort_by(|a, b| a.to_lowercase().cmp(&b.to_lowercase()));
+                                                        for file in &files {
+                                                            if ui.add(egui::Button::new(file)).clicked() {
+                                                                parameter.str_value = format!("{}{}{}", dir, std::path::MAIN_SEPARATOR, file.clone());
+
+                                                                if parameter.file_type == ParameterFileType::Vector && 
+                                                                parameter.geometry_type != VectorGeometryType::Any {
+                                                                    check_geometry_type(parameter, &dir, &cat);
+                                                                }
 
-                                                            wk_dir = parameter.str_value.clone();
+                                                                wk_dir = parameter.str_value.clone();
+                                                                ui.close_menu();
+                                                            }
+                                                        }
+                                                    } else {
+                                                        if ui.button(cat.tr("tool_dialog.no_matching_files")).clicked() {
                                                             ui.close_menu();
                                                         }
                                                     }
-                                                } else {
-                                                    if ui.button("No file of the required type are within the current working directory. Press `...` to choose a new directory instead.").clicked() {
-                                                        ui.close_menu();
-                                                    }
-                                                }
-                                            });
+                                                });
 
-                                        }
+                                            }
                                         
-                                    });
+                                        });
 
-                                    if ui.button("…").clicked() {
-                                        let fdialog = get_file_dialog(&parameter.file_type); 
-                                        if let Some(path) = fdialog
-                                        .set_directory(std::path::Path::new(&self.state.working_dir))
-                                        .pick_file() {
-                                            parameter.str_value = path.display().to_string();
-                                            // update the working directory
-                                            // path.pop();
-                                            // self.state.working_dir = path.display().to_string();
-                                            // self.update_working_dir(&path.display().to_string());
-                                            wk_dir = path.display().to_string();
+                                        if icon_button(ui, "…", cat.tr("tool_dialog.browse_hover")).clicked() {
+                                            let fdialog = get_file_dialog(&parameter.file_type); 
+                                            if let Some(path) = fdialog
+                                            .set_directory(std::path::Path::new(&self.state.working_dir))
+                                            .pick_file() {
+                                                parameter.str_value = path.display().to_string();
+                                                // update the working directory
+                                                // path.pop();
+                                                // self.state.working_dir = path.display().to_string();
+                                                // self.update_working_dir(&path.display().to_string());
+                                                wk_dir = path.display().to_string();
+                                            }
                                         }
-                                    }
 
-                                    ui.label("OR");
+                                        ui.label(cat.tr("tool_dialog.or"));
                                     
-                                    ui.add(
-                                        egui::TextEdit::singleline(&mut parameter.str_vec_value[0])
-                                        .desired_width(50.0)
-                                    );
-                                });
-                            },
-                            ParameterType::FileList => {
-                                egui::ScrollArea::vertical().id_source(&parameter.name).show(ui, |ui| {
-                                    if ui.add(
-                                        egui::TextEdit::multiline(&mut parameter.str_value)
-                                        .desired_width(self.state.textbox_width)
-                                        .desired_rows(4)
-                                        .id_source(&parameter.name)
-                                    ).double_clicked() {
-                                        let fdialog = get_file_dialog(&parameter.file_type); 
-                                        if let Some(path) = fdialog
+                                        ui.add(
+                                            egui::TextEdit::singleline(&mut parameter.str_vec_value[0])
+                                            .desired_width(50.0)
+                                        );
+                                    });
+                                },
+                                ParameterType::FileList => {
+                                    egui::ScrollArea::vertical().id_source(&parameter.name).show(ui, |ui| {
+                                        let resp = ui.add(
+                                            egui::TextEdit::multiline(&mut parameter.str_value)
+                                            .desired_width(self.state.textbox_width)
+                                            .desired_rows(4)
+                                            .id_source(&parameter.name)
+                                        );
+                                        main_response = Some(resp.clone());
+                                        if resp.double_clicked() {
+                                            let fdialog = get_file_dialog(&parameter.file_type); 
+                                            if let Some(path) = fdialog
+                                            .set_directory(std::path::Path::new(&self.state.working_dir))
+                                            .pick_file() {
+                                                parameter.str_value = path.display().to_string();
+                                                // update the working directory
+                                                // path.pop();
+                                                // self.state.working_dir = path.display().to_string();
+                                                // self.update_working_dir(&path.display().to_string());
+                                                wk_dir = path.display().to_string();
+                                            }
+                                        }
+                                    });
+                                    if icon_button(ui, "…", cat.tr("tool_dialog.browse_hover")).clicked() {
+                                        let fdialog = get_file_dialog(&parameter.file_type);
+
+                                        if let Some(paths) = fdialog
                                         .set_directory(std::path::Path::new(&self.state.working_dir))
-                                        .pick_file() {
-                                            parameter.str_value = path.display().to_string();
+                                        .pick_files() {
+                                            // let s = String::new();
+                                            for path in &paths {
+                                                parameter.str_value.push_str(&format!("{}\n", path.display().to_string()));
+                                            }
+                                        
                                             // update the working directory
-                                            // path.pop();
-                                            // self.state.working_dir = path.display().to_string();
-                                            // self.update_working_dir(&path.display().to_string());
-                                            wk_dir = path.display().to_string();
+                                            // paths[0].pop();
+                                            // self.state.working_dir = paths[0].display().to_string();
+                                            // self.update_working_dir(&paths[0].display().to_string());
+                                            wk_dir = paths[0].display().to_string();
                                         }
                                     }
-                                });
-                                if ui.button("…").clicked() {
-                                    let fdialog = get_file_dialog(&parameter.file_type);
-
-                                    if let Some(paths) = fdialog
-                                    .set_directory(std::path::Path::new(&self.state.working_dir))
-                                    .pick_files() {
-                                        // let s = String::new();
-                                        for path in &paths {
-                                            parameter.str_value.push_str(&format!("{}\n", path.display().to_string()));
-                                        }
-                                        
-                                        // update the working directory
-                                        // paths[0].pop();
-                                        // self.state.working_dir = paths[0].display().to_string();
-                                        // self.update_working_dir(&paths[0].display().to_string());
-                                        wk_dir = paths[0].display().to_string();
-                                    }
                                 }
-                            }
-                            ParameterType::Float | ParameterType::Integer => {
-                                // ui.add(egui::DragValue::new(&mut parameter.float_value).speed(0).max_decimals(5));
-                                ui.add(
-                                    egui::TextEdit::singleline(&mut parameter.str_value)
-                                    .desired_width(50.0) //self.state.textbox_width)
-                                );
+                                ParameterType::Float | ParameterType::Integer => {
+                                    // ui.add(egui::DragValue::new(&mut parameter.float_value).speed(0).max_decimals(5));
+                                    main_response = Some(ui.add(
+                                        egui::TextEdit::singleline(&mut parameter.str_value)
+                                        .desired_width(50.0) //self.state.textbox_width)
+                                    ));
 
-                                // let text_edit = egui::TextEdit::singleline(&mut parameter.str_value)
-                                // .desired_width(50.0);
-                                // let output = text_edit.show(ui);
-                                // if output.response.double_clicked() {
-                                //     // What to do here?
-                                // }
+                                    // let text_edit = egui::TextEdit::singleline(&mut parameter.str_value)
+                                    // .desired_width(50.0);
+                                    // let output = text_edit.show(ui);
+                                    // if output.response.double_clicked() {
+                                    //     // What to do here?
+                                    // }
 
-                            },
-                            ParameterType::NewFile => {
-                                // ui.text_edit_singleline(&mut parameter.str_value);
+                                },
+                                ParameterType::NewFile => {
+                                    // ui.text_edit_singleline(&mut parameter.str_value);
 
-                                let resp = ui.add(
-                                    egui::TextEdit::singleline(&mut parameter.str_value)
-                                    .desired_width(self.state.textbox_width)
-                                    .id_source(&parameter.name)
-                                );
+                                    let resp = ui.add(
+                                        egui::TextEdit::singleline(&mut parameter.str_value)
+                                        .desired_width(self.state.textbox_width)
+                                        .id_source(&parameter.name)
+                                    );
+                                    main_response = Some(resp.clone());
 
-                                if resp.lost_focus() {
-                                    if !parameter.str_value.is_empty() && !path::Path::new(&parameter.str_value).exists() {
-                                        // prepend the working directory and see if that file exists.
-                                        let f = path::Path::new(&self.state.working_dir).join(&parameter.str_value);
-                                        if !f.exists() {
+                                    if resp.lost_focus() {
+                                        if !parameter.str_value.is_empty() && !path::Path::new(&parameter.str_value).exists() {
+                                            // prepend the working directory; existing files are confirmed for overwriting when the tool is run.
+                                            let f = path::Path::new(&self.state.working_dir).join(&parameter.str_value);
                                             parameter.str_value = f.to_str().unwrap_or("").to_string();
-                                        } else {
-                                            if rfd::MessageDialog::new()
-                                            .set_level(rfd::MessageLevel::Warning)
-                                            .set_title("File exists")
-                                            .set_description("The specified file already exists in the current working directory. Do you want to overwrite the file?")
-                                            .set_buttons(rfd::MessageButtons::YesNo)
-                                            .show() {
-                                                parameter.str_value = f.to_str().unwrap_or("").to_string();
-                                            } else {
-                                                // Reset the parameter string value.
-                                                parameter.str_value = "".to_string();
-                                            }
                                         }
                                     }
-                                }
 
-                                if resp.double_clicked() {
-                                    let fdialog = get_file_dialog(&parameter.file_type); 
-                                    if let Some(path) = fdialog
-                                    .set_directory(std::path::Path::new(&self.state.working_dir))
-                                    .save_file() {
-                                        parameter.str_value = path.display().to_string();
-                                        // self.update_working_dir(&path.display().to_string());
-                                        wk_dir = path.display().to_string();
+                                    if resp.double_clicked() {
+                                        let fdialog = get_file_dialog(&parameter.file_type); 
+                                        if let Some(path) = fdialog
+                                        .set_directory(std::path::Path::new(&self.state.working_dir))
+                                        .save_file() {
+                                            parameter.str_value = path.display().to_string();
+                                            // self.update_working_dir(&path.display().to_string());
+                                            wk_dir = path.display().to_string();
+                                        }
                                     }
-                                }
-                                if ui.button("…").clicked() {
-                                    let fdialog = get_file_dialog(&parameter.file_type); 
-                                    if let Some(path) = fdialog.set_directory(std::path::Path::new(&self.state.working_dir)).save_file() {
-                                        parameter.str_value = path.display().to_string();
-                                        // self.update_working_dir(&path.display().to_string());
-                                        wk_dir = path.display().to_string();
+                                    if icon_button(ui, "…", cat.tr("tool_dialog.browse_hover")).clicked() {
+                                        let fdialog = get_file_dialog(&parameter.file_type); 
+                                        if let Some(path) = fdialog.set_directory(std::path::Path::new(&self.state.working_dir)).save_file() {
+                                            parameter.str_value = path.display().to_string();
+                                            // self.update_working_dir(&path.display().to_string());
+                                            wk_dir = path.display().to_string();
+                                        }
                                     }
+                                },
+                                ParameterType::OptionList => {
+                                    let alternatives = parameter.str_vec_value.clone();
+                                    main_response = Some(egui::ComboBox::from_id_source(&parameter.name).show_index(
+                                        ui,
+                                        &mut parameter.int_value,
+                                        alternatives.len(),
+                                        |i| alternatives[i].to_owned()
+                                    ));
                                 }
-                            },
-                            ParameterType::OptionList => {
-                                let alternatives = parameter.str_vec_value.clone();
-                                egui::ComboBox::from_id_source(&parameter.name).show_index(
-                                    ui,
-                                    &mut parameter.int_value,
-                                    alternatives.len(),
-                                    |i| alternatives[i].to_owned()
-                                );
-                            }
-                            ParameterType::String => {
-                                ui.add(
-                                    egui::TextEdit::singleline(&mut parameter.str_value)
-                                    .desired_width(self.state.textbox_width)
-                                );
-                            },
-                            ParameterType::StringOrNumber => {
-                                ui.add(
-                                    egui::TextEdit::singleline(&mut parameter.str_value)
-                                    .desired_width(self.state.textbox_width)
-                                );
-                            },
-                            ParameterType::VectorAttributeField => {
-                                ui.horizontal(|ui| {
-                                    ui.add(
+                                ParameterType::String => {
+                                    main_response = Some(ui.add(
                                         egui::TextEdit::singleline(&mut parameter.str_value)
-                                        .desired_width(self.state.textbox_width - 22.0)
-                                    );
-                                    ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
+                                        .desired_width(self.state.textbox_width)
+                                    ));
+                                },
+                                ParameterType::StringOrNumber => {
+                                    main_response = Some(ui.add(
+                                        egui::TextEdit::singleline(&mut parameter.str_value)
+                                        .desired_width(self.state.textbox_width)
+                                    ));
+                                },
+                                ParameterType::VectorAttributeField => {
+                                    ui.horizontal(|ui| {
+                                        main_response = Some(ui.add(
+                                            egui::TextEdit::singleline(&mut parameter.str_value)
+                                            .desired_width(self.state.textbox_width - 22.0)
+                                        ));
+                                        ui.add_space(-(ui.style().spacing.item_spacing[0])+2.);
 
-                                    if ui.menu_button("⏷", |ui| {
-                                        egui::ScrollArea::both()
-                                        .max_height(200.0)
-                                        .auto_shrink([true; 2])
-                                        .show(ui, |ui| {
-                                            if parameter.str_vec_value.len() > 2 {
-                                                for k in 2..parameter.str_vec_value.len() {
-                                                    let att = &parameter.str_vec_value[k];
-                                                    if ui.button(att).clicked() {
-                                                        parameter.str_value = att.clone();
+                                        if ui.menu_button("⏷", |ui| {
+                                            egui::ScrollArea::both()
+                                            .max_height(200.0)
+                                            .auto_shrink([true; 2])
+                                            .show(ui, |ui| {
+                                                if parameter.str_vec_value.len() > 2 {
+                                                    for k in 2..parameter.str_vec_value.len() {
+                                                        let att = &parameter.str_vec_value[k];
+                                                        if ui.button(att).clicked() {
+                                                            parameter.str_value = att.clone();
+                                                            ui.close_menu();
+                                                        }
+                                                    }
+                                                } else {
+                                                    if ui.button(cat.tr("tool_dialog.no_attribute_hints")).clicked() {
                                                         ui.close_menu();
                                                     }
                                                 }
-                                            } else {
-                                                if ui.button("No attribute hints are available: The parent vector file must first be specified").clicked() {
-                                                    ui.close_menu();
+                                            });
+                                        }).response.clicked() {
+                                            if parameter.str_vec_value.len() > 2 {
+                                                while parameter.str_vec_value.len() > 2 {
+                                                    parameter.str_vec_value.pop();
                                                 }
                                             }
-                                        });
-                                    }).response.clicked() {
-                                        if parameter.str_vec_value.len() > 2 {
-                                            while parameter.str_vec_value.len() > 2 {
-                                                parameter.str_vec_value.pop();
-                                            }
-                                        }
-                                        let mut file_name = flagged_parameter[flagged_parameter_idx].clone();
-                                        let mut file_path = path::PathBuf::new();
-                                        file_path.push(&file_name);
-                                        if !file_path.exists() {
-                                            // prepend the working directory and see if that file exists.
+                                            let mut file_name = flagged_parameter[flagged_parameter_idx].clone();
                                             let mut file_path = path::PathBuf::new();
-                                            file_path.push(&self.state.working_dir);
-                                            file_path = file_path.join(&file_name);
-                                            // file_path = path::PathBuf::new(&self.state.working_dir).join(&file_name);
-                                            if file_path.exists() {
-                                                file_name = file_path.to_str().unwrap_or("").to_string();
+                                            file_path.push(&file_name);
+                                            if !file_path.exists() {
+                                                // prepend the working directory and see if that file exists.
+                                                let mut file_path = path::PathBuf::new();
+                                                file_path.push(&self.state.working_dir);
+                                                file_path = file_path.join(&file_name);
+                                                // file_path = path::PathBuf::new(&self.state.working_dir).join(&file_name);
+                                                if file_path.exists() {
+                                                    file_name = file_path.to_str().unwrap_or("").to_string();
+                                                }
                                             }
-                                        }
-                                        if file_path.exists() {
-                                            if let Ok(shape) = Shapefile::read(&file_name) {
-                                                for att in &shape.attributes.fields {
-                                                    parameter.str_vec_value.push(att.name.clone());
+                                            if file_path.exists() {
+                                                if let Ok(shape) = Shapefile::read(&file_name) {
+                                                    for att in &shape.attributes.fields {
+                                                        parameter.str_vec_value.push(att.name.clone());
+                                                    }
                                                 }
                                             }
                                         }
-                                    }
 
-                                });
+                                    });
 
-                                flagged_parameter_idx += 1;
-                            },
+                                    flagged_parameter_idx += 1;
+                                },
+                            }
+                        });
+
+                        if let Some(resp) = &main_response {
+                            if is_implicated {
+                                ui.painter().rect_stroke(
+                                    resp.rect.expand(2.0),
+                                    2.0,
+                                    egui::Stroke::new(1.5, ui.visuals().error_fg_color)
+                                );
+                            }
+                            if focus_parameter == Some(idx) {
+                                resp.request_focus();
+                                resp.scroll_to_me(Some(egui::Align::Center));
+                            }
                         }
                         
                         ui.end_row();
@@ -767,9 +833,9 @@ impl MyApp {
                 ui.vertical(|ui| {
                     ui.set_height(145.);
                     ui.horizontal(|ui| {
-                        ui.label(egui::RichText::new("Tool output:").strong());
+                        ui.label(egui::RichText::new(cat.tr("tool_dialog.tool_output")).strong());
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("✖").on_hover_text("Clear tool output").clicked() {
+                            if icon_button(ui, "✖", cat.tr("tool_dialog.clear_output_hover")).clicked() {
                                 if let Ok(mut tool_output) = self.list_of_open_tools[tool_idx].tool_output.lock() {
                                     *tool_output = "".to_string();
                                 }
@@ -785,7 +851,6 @@ impl MyApp {
                                     .cursor_at_end(true)
                                     .font(egui::TextStyle::Monospace)
                                     .desired_rows(8)
-                                    .lock_focus(true)
                                     .desired_width(f32::INFINITY)
                             );
 
@@ -809,6 +874,18 @@ impl MyApp {
                                     .show_percentage());
 
                                     ui.label(&*progress_label);
+
+                                    // While the tool runs, its typical runtime gives an estimate of the time left.
+                                    let running = self.list_of_open_tools[tool_idx].continuous_mode.lock().map(|cm| *cm).unwrap_or(false);
+                                    if let (true, Some(typical), Some(started)) = (running, typical_runtime, self.list_of_open_tools[tool_idx].run_started) {
+                                        let elapsed = started.elapsed();
+                                        let hint = if elapsed < typical {
+                                            cat.trf("run_times.remaining", &[("time", &approximate_duration(typical - elapsed))])
+                                        } else {
+                                            cat.trf("run_times.overdue", &[("time", &approximate_duration(typical))])
+                                        };
+                                        ui.label(egui::RichText::new(hint).weak());
+                                    }
                                 });
                             }
                         }
@@ -819,17 +896,23 @@ impl MyApp {
             ui.separator();
 
             ui.horizontal(|ui| {
-                if ui.button("Run").clicked() {
-                    self.list_of_open_tools[tool_idx].update_working_dir(&self.state.working_dir);
-                    self.list_of_open_tools[tool_idx].update_exe_path(&self.state.whitebox_exe);
-                    self.list_of_open_tools[tool_idx].run();
+                if ui.add_enabled(self.overwrite_prompt.is_none(), egui::Button::new(cat.tr("tool_dialog.run"))).clicked() {
+                    run_tool = true;
+                }
+                if !implicated.is_empty() {
+                    if ui.button(cat.tr("tool_dialog.fix_and_rerun"))
+                    .on_hover_text(cat.tr("tool_dialog.fix_and_rerun_hover"))
+                    .clicked() {
+                        self.list_of_open_tools[tool_idx].focus_parameter = Some(implicated[0]);
+                        ctx.request_repaint();
+                    }
                 }
                 if !self.extension_tools_list.contains(&self.list_of_open_tools[tool_idx].tool_name) {
-                    if ui.button("Cancel").clicked() {
+                    if ui.button(cat.tr("tool_dialog.cancel")).clicked() {
                         self.list_of_open_tools[tool_idx].cancel();
                     }
                 }
-                if ui.button("Help").clicked() {
+                if ui.button(cat.tr("tool_dialog.help")).clicked() {
                     let toolbox = self.list_of_open_tools[tool_idx]
                     .toolbox
                     .replace("GIS", "Gis")
@@ -850,7 +933,7 @@ impl MyApp {
                     println!("URL: {url}");
                     if !webbrowser::open(&url).is_ok() {
                         if let Ok(mut tool_output) = self.list_of_open_tools[tool_idx].tool_output.lock() {
-                            tool_output.push_str("Could not navigate to help link in browser.\n");
+                            tool_output.push_str(&format!("{}\n", cat.tr("tool_dialog.help_link_failed")));
 
                             let help_str = self.list_of_open_tools[tool_idx].get_tool_help();
                             if help_str.is_some() {
@@ -862,7 +945,7 @@ impl MyApp {
                 }
 
                 if !self.extension_tools_list.contains(&self.list_of_open_tools[tool_idx].tool_name) {
-                    if ui.button("View Code").clicked() {
+                    if ui.button(cat.tr("tool_dialog.view_code")).clicked() {
                         // let url = self.view_code(&(self.list_of_open_tools[tool_idx].tool_name));
                         let output = std::process::Command::new(&self.state.whitebox_exe)
                                 .args([&format!("--viewcode={}", self.list_of_open_tools[tool_idx].tool_name)])
@@ -876,7 +959,7 @@ impl MyApp {
                             };
                             if !webbrowser::open(&url).is_ok() {
                                 if let Ok(mut tool_output) = self.list_of_open_tools[tool_idx].tool_output.lock() {
-                                    tool_output.push_str("Could not navigate to code link in browser.\n");
+                                    tool_output.push_str(&format!("{}\n", cat.tr("tool_dialog.code_link_failed")));
                                 }
                             }
                         } else {
@@ -886,7 +969,7 @@ impl MyApp {
                     }
                 }
                 
-                if ui.button("Close").clicked() {
+                if ui.button(cat.tr("tool_dialog.close")).clicked() {
                     close_dialog = true;
                 }
 
@@ -912,16 +995,21 @@ impl MyApp {
             self.update_working_dir(&wk_dir);
         }
 
+        if run_tool {
+            self.request_tool_run(tool_idx);
+        }
+
         if close_dialog {
             if let Ok(mut tool_output) = self.list_of_open_tools[tool_idx].tool_output.lock() {
                 *tool_output = "".to_string();
             }
+            self.list_of_open_tools[tool_idx].clear_failure();
             self.open_tools[tool_idx] = false;
         }
     }
 }
 
-fn check_geometry_type(parameter: &mut ToolParameter, working_dir: &str) {
+fn check_geometry_type(parameter: &mut ToolParameter, working_dir: &str, cat: &Catalog) {
     if !path::Path::new(&parameter.str_value).exists() {
         // prepend the working directory and see if that file exists.
         let f = path::Path::new(working_dir).join(&parameter.str_value);
@@ -970,8 +1058,8 @@ fn check_geometry_type(parameter: &mut ToolParameter, working_dir: &str) {
             if err_found {
                 if rfd::MessageDialog::new()
                 .set_level(rfd::MessageLevel::Warning)
-                .set_title("Wrong Vector Geometry Type")
-                .set_description("The specified file does not have the correct vector geometry type for this parameter. Do you want to continue?")
+                .set_title(cat.tr("tool_dialog.wrong_geometry_title"))
+                .set_description(cat.tr("tool_dialog.wrong_geometry"))
                 .set_buttons(rfd::MessageButtons::YesNo)
                 .show() {
                     // do nothing
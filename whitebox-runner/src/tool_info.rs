@@ -1,10 +1,14 @@
+use crate::run_history::record_run;
 use serde_json::Value;
 // use duct;
 use std::f32;
+use std::fs::{self, File};
 use std::io::{Read, Write};
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 #[derive(Default, Clone)]
 pub struct ToolInfo {
@@ -22,6 +26,10 @@ pub struct ToolInfo {
     pub progress: Arc<Mutex<f32>>,
     pub progress_label: Arc<Mutex<String>>,
     pub continuous_mode: Arc<Mutex<bool>>,
+    pub plugin_exe: Option<String>, // set for plugins discovered in the plugins directory, which are run directly
+    pub failed_flags: Arc<Mutex<Vec<String>>>, // flags named in the error message of a failed run
+    pub focus_parameter: Option<usize>, // parameter the tool dialog should focus on its next frame
+    pub run_started: Option<Instant>, // when the last run was started, for its progress estimate
 }
 
 impl ToolInfo {
@@ -42,6 +50,10 @@ impl ToolInfo {
             progress: Arc::new(Mutex::new(0.0)),
             progress_label: Arc::new(Mutex::new("Progress".to_string())),
             continuous_mode: Arc::new(Mutex::new(false)),
+            plugin_exe: None,
+            failed_flags: Arc::new(Mutex::new(vec![])),
+            focus_parameter: None,
+            run_started: None,
         }
     }
 
@@ -61,6 +73,18 @@ impl ToolInfo {
                 .show();
             return;
         }
+        let violations = self.hint_violations();
+        if !violations.is_empty() {
+            rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title("Error Parsing Parameter")
+                .set_description(&violations.join("\n"))
+                .set_buttons(rfd::MessageButtons::Ok)
+                .show();
+            return;
+        }
+        self.clear_failure();
+
         // Collect the parameter values
         let mut param_str = String::new(); // String::from(&format!("{} -r={} --wd={}", self.exe_path, self.tool_name, self.working_dir));
         let mut args: Vec<String> = vec![
@@ -396,31 +420,65 @@ impl ToolInfo {
         let tool_output = Arc::clone(&self.tool_output);
         if let Ok(mut to) = tool_output.lock() {
             if self.output_command {
+                let command = match &self.plugin_exe {
+                    Some(plugin_exe) => format!("{plugin_exe} run"),
+                    None => self.exe_path.clone(),
+                };
                 to.push_str(&format!(
                     "{} -r={} --wd=\"{}\" {}\n",
-                    &self.exe_path, self.tool_name, self.working_dir, param_str
+                    command, self.tool_name, self.working_dir, param_str
                 ));
             }
         }
 
-        let exe_path = Arc::new(self.exe_path.clone());
-        let exe = Arc::clone(&exe_path);
+        // Discovered plugins have no descriptor in the plugins directory, so WhiteboxTools can't
+        // run them; the plugin executable is run directly with its 'run' subcommand instead.
+        let exe_path = match &self.plugin_exe {
+            Some(plugin_exe) => {
+                args.insert(0, "run".to_string());
+                plugin_exe.clone()
+            }
+            None => self.exe_path.clone(),
+        };
+
+        // The tool writes its output to log files rather than to pipes, so that a run left
+        // behind when the Runner exits (see close_dialog.rs) can still write to them.
+        let (out_file, err_file) = job_log_files(&self.tool_name);
+        let spawned = File::create(&out_file).and_then(|out| {
+            let err = File::create(&err_file)?;
+            let reader = File::open(&out_file)?;
+            let child = Command::new(&exe_path)
+                .args(&args)
+                .stdout(Stdio::from(out))
+                .stderr(Stdio::from(err))
+                .spawn()?;
+            Ok((child, reader))
+        });
+        let (mut child, mut stdout) = match spawned {
+            Ok(v) => v,
+            Err(e) => {
+                if let Ok(mut to) = self.tool_output.lock() {
+                    to.push_str(&format!("Error running {}: {e}\n", self.tool_name));
+                }
+                if let Ok(mut cm) = self.continuous_mode.lock() {
+                    *cm = false;
+                }
+                let _ = fs::remove_file(&out_file);
+                let _ = fs::remove_file(&err_file);
+                return;
+            }
+        };
+
+        let started = Instant::now();
+        self.run_started = Some(started);
+        let tool_name = self.tool_name.clone();
         let pcnt = Arc::clone(&self.progress);
         let progress_label = Arc::clone(&self.progress_label);
         let continuous_mode = Arc::clone(&self.continuous_mode);
         let tool_output = Arc::clone(&self.tool_output);
         let cancel = Arc::clone(&self.cancel);
+        let failed_flags = Arc::clone(&self.failed_flags);
         thread::spawn(move || {
-            let mut child = Command::new(&*exe)
-                .args(&args)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .spawn()
-                .unwrap();
-
-            let mut stdout = child.stdout.take().unwrap();
-            let mut stderr = child.stderr.take().unwrap();
-
             let mut buf = [0u8; 200];
             let mut out_str = String::new();
             let mut do_read = || -> usize {
@@ -505,7 +563,10 @@ impl ToolInfo {
                     }
                 }
 
-                let _last = do_read();
+                if do_read() == 0 {
+                    // the log file has been read to its end; wait for the tool to write more
+                    thread::sleep(Duration::from_millis(50));
+                }
             }
 
             // make sure we try at least one more read in case there's new data in the pipe after the child exited
@@ -516,8 +577,8 @@ impl ToolInfo {
             }
 
             // Was anything written to stderr?
-            let mut s = String::new();
-            let read = stderr.read_to_string(&mut s).unwrap_or(0);
+            let s = fs::read_to_string(&err_file).unwrap_or_default();
+            let read = s.len();
             if read > 0 {
                 println!("Error: {s}");
                 if let Ok(mut to) = tool_output.lock() {
@@ -525,6 +586,19 @@ impl ToolInfo {
                 }
             }
 
+            // The run is logged before it is marked as finished, so that the typical runtimes
+            // reloaded once it finishes include it.
+            let succeeded = matches!(child.wait(), Ok(status) if status.success());
+            record_run(&tool_name, started.elapsed(), succeeded);
+
+            // The error message of a failed run names the parameters at fault by their flags,
+            // which the tool dialog highlights so that they can be corrected and the tool re-run.
+            if !succeeded {
+                if let Ok(mut flags) = failed_flags.lock() {
+                    *flags = flags_in_message(&s);
+                }
+            }
+
             if let Ok(mut val) = pcnt.lock() {
                 *val = 0.0;
             }
@@ -533,6 +607,9 @@ impl ToolInfo {
                 *val2 = "Progress".to_string();
             }
 
+            let _ = fs::remove_file(&out_file);
+            let _ = fs::remove_file(&err_file);
+
             if let Ok(mut cm) = continuous_mode.lock() {
                 *cm = false;
             }
@@ -541,6 +618,117 @@ impl ToolInfo {
         // self.animate_progress = false;
     }
 
+    /// Returns true if the parameter with the given flag has been given a value.
+    pub fn flag_is_specified(&self, flag: &str) -> bool {
+        self.parameters
+            .iter()
+            .any(|p| p.flags.iter().any(|f| f == flag) && p.is_specified())
+    }
+
+    /// Returns the name of the parameter with the given flag, for use in messages.
+    pub fn flag_name(&self, flag: &str) -> String {
+        match self
+            .parameters
+            .iter()
+            .find(|p| p.flags.iter().any(|f| f == flag))
+        {
+            Some(p) => p.name.clone(),
+            None => flag.to_string(),
+        }
+    }
+
+    /// Returns false if a parameter cannot be used yet, because a parameter that it requires
+    /// is missing or one that it conflicts with has been specified. A parameter that already
+    /// has a value stays enabled so that it can be cleared.
+    pub fn parameter_is_enabled(&self, idx: usize) -> bool {
+        let parameter = &self.parameters[idx];
+        if parameter.is_specified() {
+            return true;
+        }
+        parameter.requires.iter().all(|f| self.flag_is_specified(f))
+            && !parameter
+                .conflicts_with
+                .iter()
+                .any(|f| self.flag_is_specified(f))
+    }
+
+    /// Checks the parameter values against the tool's parameter hints, returning a message
+    /// for each combination that the tool will not accept.
+    pub fn hint_violations(&self) -> Vec<String> {
+        let mut messages: Vec<String> = vec![];
+        let mut push = |message: String| {
+            if !messages.contains(&message) {
+                messages.push(message);
+            }
+        };
+        for parameter in &self.parameters {
+            if !parameter.requires_one_of.is_empty()
+                && !parameter.is_specified()
+                && !parameter
+                    .requires_one_of
+                    .iter()
+                    .any(|f| self.flag_is_specified(f))
+            {
+                let mut names = vec![parameter.name.clone()];
+                names.extend(parameter.requires_one_of.iter().map(|f| self.flag_name(f)));
+                push(format!("Specify one of: {}.", names.join(", ")));
+            }
+            if !parameter.is_specified() {
+                continue;
+            }
+            for flag in &parameter.requires {
+                if !self.flag_is_specified(flag) {
+                    push(format!(
+                        "{} requires {} to be specified.",
+                        parameter.name,
+                        self.flag_name(flag)
+                    ));
+                }
+            }
+            for flag in &parameter.conflicts_with {
+                if self.flag_is_specified(flag) {
+                    let mut names = vec![parameter.name.clone(), self.flag_name(flag)];
+                    names.sort();
+                    push(format!(
+                        "{} and {} cannot be specified together.",
+                        names[0], names[1]
+                    ));
+                }
+            }
+        }
+        messages
+    }
+
+    /// Returns the indices of the parameters whose flags were named in the error message of
+    /// the last run, if it failed, in the order that they were first mentioned.
+    pub fn implicated_parameters(&self) -> Vec<usize> {
+        let flags = match self.failed_flags.lock() {
+            Ok(flags) => flags.clone(),
+            Err(_) => return vec![],
+        };
+        let mut indices: Vec<usize> = vec![];
+        for flag in &flags {
+            if let Some(idx) = self
+                .parameters
+                .iter()
+                .position(|p| p.flags.iter().any(|f| normalize_flag(f) == *flag))
+            {
+                if !indices.contains(&idx) {
+                    indices.push(idx);
+                }
+            }
+        }
+        indices
+    }
+
+    /// Forgets the parameters implicated by a failed run.
+    pub fn clear_failure(&mut self) {
+        if let Ok(mut flags) = self.failed_flags.lock() {
+            flags.clear();
+        }
+        self.focus_parameter = None;
+    }
+
     pub fn cancel(&mut self) {
         if let Ok(mut cancel) = self.cancel.lock() {
             *cancel = true;
@@ -549,6 +737,7 @@ impl ToolInfo {
 
     pub fn reset(&mut self) {
         self.parameters = parse_parameters(&self.json_parameters);
+        self.clear_failure();
         if let Ok(mut cancel) = self.cancel.lock() {
             *cancel = false;
         }
@@ -587,10 +776,16 @@ impl ToolInfo {
     }
 
     pub fn get_tool_help(&self) -> Option<String> {
-        let output = Command::new(&self.exe_path)
-            .args([format!("--toolhelp={}", self.tool_name)])
-            .output()
-            .expect("Could not execute the WhiteboxTools binary");
+        let output = match &self.plugin_exe {
+            Some(plugin_exe) => Command::new(plugin_exe)
+                .args(["help"])
+                .output()
+                .expect("Could not execute the plugin binary"),
+            None => Command::new(&self.exe_path)
+                .args([format!("--toolhelp={}", self.tool_name)])
+                .output()
+                .expect("Could not execute the WhiteboxTools binary"),
+        };
 
         if output.status.success() {
             let s = match std::str::from_utf8(&(output.stdout)) {
@@ -607,6 +802,71 @@ impl ToolInfo {
     }
 }
 
+/// Returns the directory holding the output logs of running tools. A run's logs are removed
+/// when it finishes, so any that remain belong to runs that outlived the Runner.
+pub fn job_log_dir() -> PathBuf {
+    std::env::temp_dir().join("whitebox_runner_jobs")
+}
+
+/// Creates the log directory and returns unique stdout and stderr log file paths for a run.
+fn job_log_files(tool_name: &str) -> (PathBuf, PathBuf) {
+    let dir = job_log_dir();
+    let _ = fs::create_dir_all(&dir);
+    let stamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    let mut n = 0;
+    loop {
+        let name = format!("{tool_name}_{}_{stamp}_{n}", std::process::id());
+        let out_file = dir.join(format!("{name}.log"));
+        if !out_file.exists() {
+            return (out_file, dir.join(format!("{name}.err.log")));
+        }
+        n += 1;
+    }
+}
+
+/// Normalizes a parameter flag for comparison, e.g. '-i' and '--I' both become '--i'.
+fn normalize_flag(flag: &str) -> String {
+    format!("--{}", flag.trim_start_matches('-').to_lowercase())
+}
+
+/// Returns the normalized parameter flags (e.g. '--pour_pts') mentioned in a tool's error
+/// message, in the order that they first appear. A flag is a run of one or two dashes, not
+/// attached to a preceding word, followed by a letter and any letters, digits or underscores,
+/// so that hyphenated words and negative numbers are not mistaken for flags.
+pub fn flags_in_message(message: &str) -> Vec<String> {
+    let chars: Vec<char> = message.chars().collect();
+    let mut flags: Vec<String> = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let attached =
+            i > 0 && (chars[i - 1].is_alphanumeric() || chars[i - 1] == '-' || chars[i - 1] == '_');
+        if chars[i] != '-' || attached {
+            i += 1;
+            continue;
+        }
+        let mut j = i + 1;
+        if j < chars.len() && chars[j] == '-' {
+            j += 1;
+        }
+        let name_start = j;
+        if j < chars.len() && chars[j].is_ascii_alphabetic() {
+            while j < chars.len() && (chars[j].is_ascii_alphanumeric() || chars[j] == '_') {
+                j += 1;
+            }
+            let name: String = chars[name_start..j].iter().collect();
+            let flag = normalize_flag(&name);
+            if !flags.contains(&flag) {
+                flags.push(flag);
+            }
+        }
+        i = j;
+    }
+    flags
+}
+
 #[derive(Default, Debug, PartialEq, Clone)]
 pub enum ParameterType {
     Boolean,
@@ -664,6 +924,29 @@ pub struct ToolParameter {
     pub str_vec_value: Vec<String>,
     pub file_type: ParameterFileType,
     pub geometry_type: VectorGeometryType,
+    // Optional hints from the tool about how this parameter relates to the others; the
+    // flags refer to other parameters of the same tool.
+    pub group: String,
+    pub requires: Vec<String>,
+    pub requires_one_of: Vec<String>,
+    pub conflicts_with: Vec<String>,
+}
+
+impl ToolParameter {
+    /// Returns true if the user has given the parameter a value.
+    pub fn is_specified(&self) -> bool {
+        match self.parameter_type {
+            ParameterType::Boolean => self.bool_value,
+            ParameterType::ExistingFileOrFloat => {
+                !self.str_value.trim().is_empty()
+                    || self
+                        .str_vec_value
+                        .first()
+                        .map_or(false, |v| !v.trim().is_empty())
+            }
+            _ => !self.str_value.trim().is_empty(),
+        }
+    }
 }
 
 fn parse_parameters(parameters: &Value) -> Vec<ToolParameter> {
@@ -697,6 +980,21 @@ fn parse_parameters(parameters: &Value) -> Vec<ToolParameter> {
             None
         };
         let optional = tool_parameters[j]["optional"].as_bool().unwrap_or(false);
+        let group = tool_parameters[j]["group"]
+            .as_str()
+            .unwrap_or("")
+            .to_string();
+        let flag_list = |key: &str| -> Vec<String> {
+            tool_parameters[j][key]
+                .as_array()
+                .unwrap_or(&empty_arr)
+                .iter()
+                .map(|v| v.as_str().unwrap_or("").to_owned())
+                .collect()
+        };
+        let requires = flag_list("requires");
+        let requires_one_of = flag_list("requires_one_of");
+        let conflicts_with = flag_list("conflicts_with");
 
         let mut str_vec_value: Vec<String> = vec![];
         let mut str_value = "".to_string();
@@ -1054,6 +1352,10 @@ fn parse_parameters(parameters: &Value) -> Vec<ToolParameter> {
             file_type: file_type,
             geometry_type: geometry_type,
             // attribute_type: AttributeType::Any,
+            group: group,
+            requires: requires,
+            requires_one_of: requires_one_of,
+            conflicts_with: conflicts_with,
         };
         ret.push(tp);
     }
@@ -1,33 +1,42 @@
+use crate::accessibility::{activated, collapsing_header, icon_button};
 use crate::MyApp;
-use egui::{CollapsingHeader, ScrollArea};
+use egui::ScrollArea;
 
 impl MyApp {
     pub fn tools_panel(&mut self, ctx: &egui::Context) {
-        // Tool tree side panel
-        egui::SidePanel::left("tool_panel").show(ctx, |ui| {
+        let cat = self.catalog.clone();
+        // Tool tree side panel; the saved width is applied once at startup, after which the
+        // panel is freely resizable and its width is recorded in the app state.
+        let panel = match self.pending_panel_width.take() {
+            Some(width) => egui::SidePanel::left("tool_panel").exact_width(width),
+            None => egui::SidePanel::left("tool_panel").default_width(self.state.tools_panel_width),
+        };
+        let panel_response = panel.show(ctx, |ui| {
             ui.vertical_centered(|ui| {
                 ui.small(" "); // just to put some vertical space between the header and the top.
-                ui.heading(&format!("🛠 {} Available Tools", self.num_tools));
+                ui.heading(&cat.trf("tools_panel.heading", &[("n", &self.num_tools.to_string())]));
             });
             ui.separator();
 
+            let mut focus_search = false;
             ui.horizontal(|ui| {
-                if ui.toggle_value(&mut self.state.show_toolboxes, "Toolboxes")
-                .on_hover_text("Search for tools in their toolboxes")
+                if ui.toggle_value(&mut self.state.show_toolboxes, cat.tr("tools_panel.toolboxes"))
+                .on_hover_text(cat.tr("tools_panel.toolboxes_hover"))
                 .clicked() {
                     self.state.show_toolboxes = true;
                     self.state.show_tool_search = false;
                     self.state.show_recent_tools = false;
                 }
-                if ui.toggle_value(&mut self.state.show_tool_search, "Tool Search")
-                .on_hover_text("Search for tools by keywords")
+                if ui.toggle_value(&mut self.state.show_tool_search, cat.tr("tools_panel.tool_search"))
+                .on_hover_text(cat.tr("tools_panel.tool_search_hover"))
                 .clicked() {
                     self.state.show_toolboxes = false;
                     self.state.show_tool_search = true;
                     self.state.show_recent_tools = false;
+                    focus_search = true;
                 }
-                if ui.toggle_value(&mut self.state.show_recent_tools, "Recent Tools")
-                .on_hover_text("List recently used and most used tools.")
+                if ui.toggle_value(&mut self.state.show_recent_tools, cat.tr("tools_panel.recent_tools"))
+                .on_hover_text(cat.tr("tools_panel.recent_tools_hover"))
                 .clicked() {
                     self.state.show_toolboxes = false;
                     self.state.show_tool_search = false;
@@ -61,40 +70,20 @@ impl MyApp {
                             for i in 0..self.tree.children.len() {
 
                                 let tree = &self.tree.children[i];
-                                CollapsingHeader::new(
-                                    egui::RichText::new(&tree.label)
-                                    .strong()
-                                    // .italics()
-                                    // .color(ui.visuals().hyperlink_color)
-                                    // .background_color(ui.visuals().selection.bg_fill)
-                                    // .color(ui.visuals().selection.stroke.color)
-                                )
-                                .default_open(false)
-                                // .icon(circle_icon)
-                                .show(ui, |ui| {
+                                collapsing_header(ui, egui::RichText::new(&tree.label).strong(), |ui| {
                                     for j in 0..tree.children.len() {
                                         let tree2 = &tree.children[j];
                                         if tree2.is_toolbox() {
-                                            CollapsingHeader::new(
-                                                egui::RichText::new(&tree2.label)
-                                                .strong()
-                                                // .italics()
-                                                // .color(ui.visuals().hyperlink_color)
-                                                // .background_color(ui.visuals().selection.bg_fill)
-                                                // .color(ui.visuals().selection.stroke.color)
-                                            )
-                                            .default_open(false)
-                                            // .icon(circle_icon)
-                                            .show(ui, |ui| {
+                                            collapsing_header(ui, egui::RichText::new(&tree2.label).strong(), |ui| {
                                                 for k in 0..tree2.children.len() {
                                                     let tree3 = &tree2.children[k];
                                                     let tool_index = *self.tool_order.get(&tree3.label.clone()).unwrap();
 
                                                     // if ui.toggle_value(&mut self.open_tools[tool_index], &format!("🔧 {}", tree3.label))
                                                     
-                                                    if ui.button(&format!("🔧 {}", tree3.label))
-                                                    .on_hover_text(self.tool_descriptions.get(&tree3.label).unwrap_or(&String::new()))
-                                                    .clicked() {
+                                                    let response = ui.button(&format!("🔧 {}", tree3.label))
+                                                    .on_hover_text(self.tool_hover_text(&tree3.label));
+                                                    if activated(ui, &response) {
                                                         clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                                     }
 
@@ -107,9 +96,9 @@ impl MyApp {
                                         } else { // it's a tool
                                             let tool_index = *self.tool_order.get(&tree2.label.clone()).unwrap();
                                             // if ui.toggle_value(&mut self.open_tools[tool_index], &format!("🔧 {}", tree2.label))
-                                            if ui.button(&format!("🔧 {}", tree2.label))
-                                            .on_hover_text(self.tool_descriptions.get(&tree2.label).unwrap_or(&String::new()))
-                                            .clicked() {
+                                            let response = ui.button(&format!("🔧 {}", tree2.label))
+                                            .on_hover_text(self.tool_hover_text(&tree2.label));
+                                            if activated(ui, &response) {
                                                 // self.tool_info[tool_index].update_exe_path(&self.state.whitebox_exe);
                                                 clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                             }
@@ -138,12 +127,12 @@ impl MyApp {
                             // ui.label("Keywords:")
                             // .on_hover_text("Search for keywords (separated by commas) in tool names or descriptions");
                             ui.label(
-                                egui::RichText::new("Keywords:")
+                                egui::RichText::new(cat.tr("tools_panel.keywords"))
                                 // .italics()
                                 .strong()
                                 // .color(ui.visuals().hyperlink_color)
                             )
-                            .on_hover_text("Search for keywords in tool names or descriptions. Keywords should be separated by spaces (AND) or commas (OR). AND (&) and OR (|) operators are also valid to combine search words.");
+                            .on_hover_text(cat.tr("tools_panel.keywords_hover"));
 
                             // ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             //     if ui.button("Clear").on_hover_text("Clear search keywords").clicked() {
@@ -153,14 +142,17 @@ impl MyApp {
                         });
                         
                         ui.horizontal(|ui| {
-                            ui.add(
+                            let search_box = ui.add(
                                 egui::TextEdit::singleline(&mut self.search_words_str)
                                 .desired_width(self.state.textbox_width)
                                 
                                 // .on_hover_text("Search for keywords (separated by commas) in tool names or descriptions");
                             );
+                            if focus_search {
+                                search_box.request_focus();
+                            }
 
-                            if ui.button("✖").on_hover_text("Clear search keywords").clicked() {
+                            if icon_button(ui, "✖", cat.tr("tools_panel.clear_search_hover")).clicked() {
                                 self.search_words_str = "".to_string();
                             }
                         });
@@ -169,12 +161,12 @@ impl MyApp {
 
                         ui.horizontal(|ui| {
                             if self.num_search_hits != 1 {
-                                ui.label(&format!("Found {} tools", self.num_search_hits));
+                                ui.label(&cat.trf("tools_panel.found_tools", &[("n", &self.num_search_hits.to_string())]));
                             } else {
-                                ui.label(&format!("Found {} tool", self.num_search_hits));
+                                ui.label(&cat.trf("tools_panel.found_one_tool", &[("n", &self.num_search_hits.to_string())]));
                             }
                             ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                ui.checkbox(&mut self.case_sensitive_search, "Case sensitive");
+                                ui.checkbox(&mut self.case_sensitive_search, cat.tr("tools_panel.case_sensitive"));
                             });
                         });
 
@@ -244,9 +236,9 @@ impl MyApp {
                                             //     // self.update_recent_tools(&tn);
                                             //     clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                             // }
-                                            if ui.button(&tool)
-                                            .on_hover_text(self.tool_descriptions.get(&tool).unwrap_or(&String::new()))
-                                            .clicked() {
+                                            let response = ui.button(&tool)
+                                            .on_hover_text(self.tool_hover_text(&tool));
+                                            if activated(ui, &response) {
                                                 // self.tool_info[tool_index].update_exe_path(&self.state.whitebox_exe);
                                                 clicked_tool = self.tool_info[*tool_index].tool_name.clone();
                                             }
@@ -276,14 +268,14 @@ impl MyApp {
                             ui.horizontal(|ui| {
                                 // ui.label("Recently used tools:");
                                 ui.label(
-                                    egui::RichText::new("Recently used tools:")
+                                    egui::RichText::new(cat.tr("tools_panel.recently_used"))
                                     // .italics()
                                     .strong()
                                     // .color(ui.visuals().hyperlink_color)
                                 );
 
                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                    if ui.button("🔃").on_hover_text("Reset recent tools").clicked() {
+                                    if icon_button(ui, "🔃", cat.tr("tools_panel.reset_recent_hover")).clicked() {
                                         self.state.most_recent.clear();
                                     }
                                 });
@@ -291,7 +283,11 @@ impl MyApp {
 
                             for tool in &self.state.most_recent {
                                 // ui.label(format!("{}", tool));
-                                let tool_index = *self.tool_order.get(tool).unwrap();
+                                // Plugin tools may not be listed until the plugin scan finishes.
+                                let tool_index = match self.tool_order.get(tool) {
+                                    Some(idx) => *idx,
+                                    None => continue,
+                                };
                                 // if ui.toggle_value(&mut self.open_tools[tool_index], tool)
                                 // .on_hover_text(self.tool_descriptions.get(tool).unwrap_or(&String::new()))
                                 // .clicked() {
@@ -300,9 +296,9 @@ impl MyApp {
                                 //     // self.update_recent_tools(&tn);
                                 //     // clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                 // }
-                                if ui.button(tool)
-                                .on_hover_text(self.tool_descriptions.get(tool).unwrap_or(&String::new()))
-                                .clicked() {
+                                let response = ui.button(tool)
+                                .on_hover_text(self.tool_hover_text(tool));
+                                if activated(ui, &response) {
                                     // self.tool_info[tool_index].update_exe_path(&self.state.whitebox_exe);
                                     clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                 }
@@ -312,14 +308,14 @@ impl MyApp {
                             ui.horizontal(|ui| {
                                 // ui.label("Most-used tools:");
                                 ui.label(
-                                    egui::RichText::new("Most-used tools:")
+                                    egui::RichText::new(cat.tr("tools_panel.most_used"))
                                     // .italics()
                                     .strong()
                                     // .color(ui.visuals().hyperlink_color)
                                 );
 
                                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                    if ui.button("🔃").on_hover_text("Reset most-used tools").clicked() {
+                                    if icon_button(ui, "🔃", cat.tr("tools_panel.reset_most_used_hover")).clicked() {
                                         self.most_used.clear();
                                         self.most_used_hm.clear();
                                     }
@@ -327,15 +323,18 @@ impl MyApp {
                             });
 
                             for val in &self.most_used {
-                                let tool_index = *self.tool_order.get(&val.1).unwrap();
+                                let tool_index = match self.tool_order.get(&val.1) {
+                                    Some(idx) => *idx,
+                                    None => continue,
+                                };
                                 // if ui.toggle_value(&mut self.open_tools[tool_index], &format!("{} ({})", val.1, val.0))
                                 // .on_hover_text(self.tool_descriptions.get(&val.1).unwrap_or(&String::new()))
                                 // .clicked() {
                                 //     self.tool_info[tool_index].update_exe_path(&self.state.whitebox_exe);
                                 // }
-                                if ui.button(&format!("{} ({})", val.1, val.0))
-                                .on_hover_text(self.tool_descriptions.get(&val.1).unwrap_or(&String::new()))
-                                .clicked() {
+                                let response = ui.button(&format!("{} ({})", val.1, val.0))
+                                .on_hover_text(self.tool_hover_text(&val.1));
+                                if activated(ui, &response) {
                                     // self.tool_info[tool_index].update_exe_path(&self.state.whitebox_exe);
                                     clicked_tool = self.tool_info[tool_index].tool_name.clone();
                                 }
@@ -406,5 +405,6 @@ impl MyApp {
             }
             
         });
+        self.state.tools_panel_width = panel_response.response.rect.width();
     }
 }
@@ -1,3 +1,4 @@
+use crate::accessibility::icon_button;
 use crate::MyApp;
 use anyhow::{bail, Result};
 use reqwest;
@@ -43,7 +44,7 @@ impl MyApp {
                     ui.horizontal(|ui| {
                         ui.label("Output:");
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                            if ui.button("✖").on_hover_text("Clear tool output").clicked() {
+                            if icon_button(ui, "✖", "Clear tool output").clicked() {
                                 self.ei.text_output = "".to_string();
                             }
                         });
@@ -56,7 +57,6 @@ impl MyApp {
                                 .cursor_at_end(true)
                                 .font(egui::TextStyle::Monospace)
                                 .desired_rows(10)
-                                .lock_focus(true)
                                 .desired_width(f32::INFINITY),
                         );
                     });
@@ -0,0 +1,100 @@
+use crate::MyApp;
+
+pub const DEFAULT_WINDOW_SIZE: [f32; 2] = [1000.0, 700.0];
+pub const DEFAULT_TOOLS_PANEL_WIDTH: f32 = 280.0;
+const MIN_WINDOW_SIZE: [f32; 2] = [480.0, 320.0];
+const MIN_TOOLS_PANEL_WIDTH: f32 = 150.0;
+
+impl MyApp {
+    /// Applies the saved window size and position and the tools panel width. Called on the first
+    /// frame, once the monitor size is known.
+    pub fn restore_window_layout(&mut self, frame: &mut eframe::Frame) {
+        let info = frame.info().window_info;
+        let monitor = info.monitor_size.map(|m| [m.x, m.y]);
+
+        let saved_size = if self.state.window_size[0] > 0.0 && self.state.window_size[1] > 0.0 {
+            self.state.window_size
+        } else {
+            DEFAULT_WINDOW_SIZE
+        };
+        let (size, pos) = clamp_window_geometry(saved_size, self.state.window_pos, monitor);
+        frame.set_window_size(egui::vec2(size[0], size[1]));
+        if let Some(pos) = pos {
+            frame.set_window_pos(egui::pos2(pos[0], pos[1]));
+        }
+        self.state.window_size = size;
+        self.state.window_pos = pos;
+
+        self.state.tools_panel_width = clamp_panel_width(self.state.tools_panel_width, size[0]);
+        self.pending_panel_width = Some(self.state.tools_panel_width);
+
+        // Exactly one of the tools panel views is shown at a time.
+        let num_views = [
+            self.state.show_toolboxes,
+            self.state.show_tool_search,
+            self.state.show_recent_tools,
+        ]
+        .iter()
+        .filter(|&&v| v)
+        .count();
+        if num_views != 1 {
+            self.state.show_toolboxes = true;
+            self.state.show_tool_search = false;
+            self.state.show_recent_tools = false;
+        }
+    }
+
+    /// Records the current window size and position so that they are persisted with the app state.
+    pub fn record_window_layout(&mut self, frame: &eframe::Frame) {
+        let info = frame.info().window_info;
+        if info.fullscreen || info.size.x <= 0.0 || info.size.y <= 0.0 {
+            return; // keep the windowed geometry
+        }
+        self.state.window_size = [info.size.x, info.size.y];
+        if let Some(pos) = info.position {
+            self.state.window_pos = Some([pos.x, pos.y]);
+        }
+    }
+}
+
+/// Fits a saved window size within the monitor and returns the position to use. A saved position
+/// that would leave part of the window off the monitor (e.g. one saved on a monitor that is no
+/// longer attached) is replaced by a centred position. Without a known monitor size the size is
+/// used as saved and only non-negative positions are kept.
+fn clamp_window_geometry(
+    size: [f32; 2],
+    pos: Option<[f32; 2]>,
+    monitor: Option<[f32; 2]>,
+) -> ([f32; 2], Option<[f32; 2]>) {
+    let mut size = [
+        size[0].max(MIN_WINDOW_SIZE[0]),
+        size[1].max(MIN_WINDOW_SIZE[1]),
+    ];
+    let monitor = match monitor {
+        Some(m) if m[0] > 0.0 && m[1] > 0.0 => m,
+        _ => {
+            let pos = pos.filter(|p| p[0] >= 0.0 && p[1] >= 0.0);
+            return (size, pos);
+        }
+    };
+    size[0] = size[0].min(monitor[0]);
+    size[1] = size[1].min(monitor[1]);
+
+    let on_screen = |p: &[f32; 2]| {
+        p[0] >= 0.0 && p[1] >= 0.0 && p[0] + size[0] <= monitor[0] && p[1] + size[1] <= monitor[1]
+    };
+    let pos = match pos {
+        Some(p) if on_screen(&p) => p,
+        _ => [(monitor[0] - size[0]) / 2.0, (monitor[1] - size[1]) / 2.0],
+    };
+    (size, Some(pos))
+}
+
+/// Keeps the tools panel wide enough to use while leaving room for the tool dialogs.
+fn clamp_panel_width(width: f32, window_width: f32) -> f32 {
+    if width <= 0.0 {
+        return DEFAULT_TOOLS_PANEL_WIDTH.min(window_width * 0.5);
+    }
+    let max_width = (window_width * 0.6).max(MIN_TOOLS_PANEL_WIDTH);
+    width.max(MIN_TOOLS_PANEL_WIDTH).min(max_width)
+}
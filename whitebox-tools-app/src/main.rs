@@ -68,7 +68,15 @@ extern crate serde_derive;
 /// ```
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    run()?; // propogate error to provide ability to backtrace
+    if let Err(err) = run() {
+        // Classified tool failures exit with their own codes (see tools::FailureClass), after
+        // printing the error as returning it from main would.
+        if let Some(class) = tools::failure_class(&err) {
+            eprintln!("Error: {:?}", err);
+            std::process::exit(class.exit_code());
+        }
+        return Err(err.into()); // propogate error to provide ability to backtrace
+    }
     Ok(())
 }
 
@@ -0,0 +1,485 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+use whitebox_common::algorithms::{erode_mask, StructuringElement};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool removes the fringe of interpolated values that resampled or reprojected rasters often
+/// carry along the boundary between their valid data and NoData, before the fringe contaminates
+/// slope or flow pointer calculations. The valid-data footprint of the input raster (`--input`) is
+/// eroded by `--n_cells` grid cells: every valid cell within `--n_cells` cells (including diagonal
+/// neighbours) of a NoData cell is set to NoData in the output (`--output`), and all other cells
+/// keep their input value. The edges of the raster are not treated as a NoData boundary. The
+/// erosion thresholds a distance transform of the NoData cells in a single pass, so its run time
+/// does not depend on `--n_cells`.
+///
+/// With `--adaptive`, only fringe cells whose values look like artifacts are removed: a fringe cell
+/// is set to NoData if its value differs by more than the `--adaptive` threshold (in the units of the
+/// raster) from the mean of the interior (non-fringe) cells within `--n_cells` + 1 cells of it, or if
+/// there are no such interior cells. Fringe cells that agree with the interior are kept.
+///
+/// The number of cells trimmed is printed in verbose mode and recorded in the output's metadata.
+///
+/// # See Also
+/// `MaskShrink`, `SetNodataValue`, `ClipRasterToRaster`
+pub struct EdgeBleedTrim {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl EdgeBleedTrim {
+    pub fn new() -> EdgeBleedTrim {
+        // public constructor
+        let name = "EdgeBleedTrim".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Sets the fringe of valid cells along a raster's NoData boundary to NoData."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Fringe Width (cells)".to_owned(),
+            flags: vec!["--n_cells".to_owned()],
+            description: "Width of the fringe along the NoData boundary to trim, in grid cells."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Adaptive Threshold".to_owned(),
+            flags: vec!["--adaptive".to_owned()],
+            description: "Optional threshold; when given, only fringe cells differing from the mean of nearby interior cells by more than it are trimmed.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem_utm.tif -o=dem_trimmed.tif --n_cells=2
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem_utm.tif -o=dem_trimmed.tif --n_cells=2 --adaptive=5.0", short_exe, name).replace("*", &sep);
+
+        EdgeBleedTrim {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for EdgeBleedTrim {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut output_file = String::new();
+        let mut n_cells = 1usize;
+        let mut adaptive: Option<f64> = None;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-n_cells" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                n_cells = value.trim().parse::<usize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Unable to parse --n_cells value '{}'; expected a non-negative number of cells.",
+                            value
+                        ),
+                    )
+                })?;
+            } else if flag_val == "-adaptive" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let threshold = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --adaptive value '{}'.", value),
+                    )
+                })?;
+                if threshold.is_nan() || threshold < 0f64 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "--adaptive must be a non-negative threshold; got {}.",
+                            value
+                        ),
+                    ));
+                }
+                adaptive = Some(threshold);
+            }
+        }
+
+        if input_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Input raster file (--input) not specified.",
+            ));
+        }
+        if output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output raster file (--output) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        input_file = resolve_path(working_directory, &input_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Raster::new(&input_file, "r")?;
+
+        let start = Instant::now();
+
+        let rows = input.configs.rows;
+        let columns = input.configs.columns;
+        let nodata = input.configs.nodata;
+
+        // The valid-data footprint, padded by n_cells of valid cells on every side so that
+        // erode_mask, which erodes from the grid edges, only erodes from NoData cells.
+        let (prows, pcolumns) = (rows + 2 * n_cells, columns + 2 * n_cells);
+        let mut footprint = vec![true; prows * pcolumns];
+        let mut num_valid = 0usize;
+        for row in 0..rows {
+            for col in 0..columns {
+                let valid = input.get_value(row as isize, col as isize) != nodata;
+                footprint[(row + n_cells) * pcolumns + col + n_cells] = valid;
+                if valid {
+                    num_valid += 1;
+                }
+            }
+        }
+        let eroded = erode_mask(
+            &footprint,
+            prows,
+            pcolumns,
+            n_cells,
+            StructuringElement::Square,
+        );
+        let interior: Vec<bool> = (0..rows * columns)
+            .map(|i| eroded[(i / columns + n_cells) * pcolumns + i % columns + n_cells])
+            .collect();
+
+        // With --adaptive, the sum and count of the interior values in every window are read
+        // from summed-area tables, with one extra leading row and column of zeros.
+        let window_mean = match adaptive {
+            Some(_) => {
+                let mut sums = vec![0f64; (rows + 1) * (columns + 1)];
+                let mut counts = vec![0usize; (rows + 1) * (columns + 1)];
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let i = (row + 1) * (columns + 1) + col + 1;
+                        let (z, n) = if interior[row * columns + col] {
+                            (input.get_value(row as isize, col as isize), 1)
+                        } else {
+                            (0f64, 0)
+                        };
+                        sums[i] = z + sums[i - 1] + sums[i - columns - 1] - sums[i - columns - 2];
+                        counts[i] =
+                            n + counts[i - 1] + counts[i - columns - 1] - counts[i - columns - 2];
+                    }
+                }
+                Some((sums, counts))
+            }
+            None => None,
+        };
+        let radius = n_cells + 1;
+        let interior_mean = |row: usize, col: usize| -> Option<f64> {
+            let (sums, counts) = window_mean.as_ref()?;
+            let (r0, c0) = (row.saturating_sub(radius), col.saturating_sub(radius));
+            let (r1, c1) = (
+                (row + radius + 1).min(rows),
+                (col + radius + 1).min(columns),
+            );
+            let at = |r: usize, c: usize| r * (columns + 1) + c;
+            let n =
+                counts[at(r1, c1)] + counts[at(r0, c0)] - counts[at(r0, c1)] - counts[at(r1, c0)];
+            if n == 0 {
+                return None;
+            }
+            let sum = sums[at(r1, c1)] + sums[at(r0, c0)] - sums[at(r0, c1)] - sums[at(r1, c0)];
+            Some(sum / n as f64)
+        };
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        let mut num_fringe = 0usize;
+        let mut num_trimmed = 0usize;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = input.get_value(row as isize, col as isize);
+                let mut value = z;
+                if z != nodata && !interior[row * columns + col] {
+                    num_fringe += 1;
+                    let trim = match adaptive {
+                        Some(threshold) => match interior_mean(row, col) {
+                            Some(mean) => (z - mean).abs() > threshold,
+                            None => true,
+                        },
+                        None => true,
+                    };
+                    if trim {
+                        value = nodata;
+                        num_trimmed += 1;
+                    }
+                }
+                output.set_value(row as isize, col as isize, value);
+            }
+            if verbose && rows > 1 {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Trimmed {} of {} fringe cells ({} valid cells in the input).",
+                num_trimmed, num_fringe, num_valid
+            );
+        }
+
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Fringe width (cells): {}", n_cells));
+        if let Some(threshold) = adaptive {
+            output.add_metadata_entry(format!("Adaptive threshold: {}", threshold));
+        }
+        output.add_metadata_entry(format!("Cells trimmed: {}", num_trimmed));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::EdgeBleedTrim;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    const NODATA: f64 = -32768f64;
+
+    // A 12 x 12 plane whose valid footprint is a 10 x 10 block at rows and columns 1 to 10,
+    // except for column 0, which is valid and lies against the western raster edge.
+    fn interior_value(r: isize, c: isize) -> f64 {
+        100f64 + r as f64 + 0.5 * c as f64
+    }
+
+    fn write_input(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(12, 12, f).with_nodata(NODATA);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    #[test]
+    fn test_trim_corrupted_fringe() {
+        let dir = temp_dir("edge_bleed_trim").unwrap();
+        let input = format!("{}/input.tif", dir);
+        // Valid data at rows 1 to 10 and columns 0 to 10; the cells along the NoData boundary
+        // (rows 1 and 10, column 10) are corrupted, except on the row 10 stretch from columns
+        // 3 to 5, which holds good values.
+        let valid = |r: isize, c: isize| (1..=10).contains(&r) && c <= 10;
+        let corrupted = |r: isize, c: isize| {
+            (r == 1 || r == 10 || c == 10) && !(r == 10 && (3..=5).contains(&c))
+        };
+        write_input(&input, &|r, c| {
+            if !valid(r, c) {
+                NODATA
+            } else if corrupted(r, c) {
+                -50f64
+            } else {
+                interior_value(r, c)
+            }
+        });
+
+        let run = |name: &str, extra: &[&str]| -> Raster {
+            let output = format!("{}/{}.tif", dir, name);
+            let mut args = vec![format!("-i={}", input), format!("-o={}", output)];
+            args.extend(extra.iter().map(|s| s.to_string()));
+            EdgeBleedTrim::new().run(args, "", false).unwrap();
+            Raster::new(&output, "r").unwrap()
+        };
+
+        // Every cell within one cell of NoData is trimmed; column 0 borders only the raster edge.
+        let trimmed = run("trimmed", &[]);
+        for r in 0..12isize {
+            for c in 0..12isize {
+                let fringe = r == 1 || r == 10 || c == 10;
+                let expected = if !valid(r, c) || fringe {
+                    NODATA
+                } else {
+                    interior_value(r, c)
+                };
+                assert_eq!(trimmed.get_value(r, c), expected, "({}, {})", r, c);
+            }
+        }
+
+        // Two cells deep, the adaptive trim keeps the good fringe cells and the column 9 cells,
+        // which agree with the interior, and removes the corrupted ones.
+        let adaptive = run("adaptive", &["--n_cells=2", "--adaptive=5"]);
+        for r in 0..12isize {
+            for c in 0..12isize {
+                let expected = if !valid(r, c) || corrupted(r, c) {
+                    NODATA
+                } else {
+                    interior_value(r, c)
+                };
+                assert_eq!(adaptive.get_value(r, c), expected, "({}, {})", r, c);
+            }
+        }
+
+        // Zero cells leaves the raster unchanged.
+        let unchanged = run("unchanged", &["--n_cells=0"]);
+        let original = Raster::new(&input, "r").unwrap();
+        for r in 0..12isize {
+            for c in 0..12isize {
+                assert_eq!(unchanged.get_value(r, c), original.get_value(r, c));
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+}
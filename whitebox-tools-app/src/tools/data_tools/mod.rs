@@ -4,16 +4,20 @@ mod clean_vector;
 mod convert_nodata_to_zero;
 mod convert_raster_format;
 mod csv_points_to_vector;
+mod edge_bleed_trim;
 mod export_table_to_csv;
 mod join_tables;
 mod lines_to_polygons;
 mod merge_table_with_csv;
 mod merge_vectors;
 mod modify_nodata_value;
+mod multi_band_composite_from_singles;
 mod multipart_to_singlepart;
 mod new_raster;
 mod polygons_to_lines;
 mod print_geotiff_tags;
+mod raster_footprint_vector;
+mod rasterize_vector;
 mod raster_to_vector_lines;
 mod raster_to_vector_points;
 mod raster_to_vector_polygons;
@@ -21,6 +25,7 @@ mod reinitialize_attribute_table;
 mod remove_polygon_holes;
 mod set_nodata_value;
 mod singlepart_to_multipart;
+mod synthetic_test_data_generator;
 mod vector_lines_to_raster;
 mod vector_points_to_raster;
 mod vector_polygons_to_raster;
@@ -31,16 +36,20 @@ pub use self::clean_vector::CleanVector;
 pub use self::convert_nodata_to_zero::ConvertNodataToZero;
 pub use self::convert_raster_format::ConvertRasterFormat;
 pub use self::csv_points_to_vector::CsvPointsToVector;
+pub use self::edge_bleed_trim::EdgeBleedTrim;
 pub use self::export_table_to_csv::ExportTableToCsv;
 pub use self::join_tables::JoinTables;
 pub use self::lines_to_polygons::LinesToPolygons;
 pub use self::merge_table_with_csv::MergeTableWithCsv;
 pub use self::merge_vectors::MergeVectors;
 pub use self::modify_nodata_value::ModifyNoDataValue;
+pub use self::multi_band_composite_from_singles::MultiBandCompositeFromSingles;
 pub use self::multipart_to_singlepart::MultiPartToSinglePart;
 pub use self::new_raster::NewRasterFromBase;
 pub use self::polygons_to_lines::PolygonsToLines;
 pub use self::print_geotiff_tags::PrintGeoTiffTags;
+pub use self::raster_footprint_vector::RasterFootprintVector;
+pub use self::rasterize_vector::RasterizeVector;
 pub use self::raster_to_vector_lines::RasterToVectorLines;
 pub use self::raster_to_vector_points::RasterToVectorPoints;
 pub use self::raster_to_vector_polygons::RasterToVectorPolygons;
@@ -48,6 +57,7 @@ pub use self::reinitialize_attribute_table::ReinitializeAttributeTable;
 pub use self::remove_polygon_holes::RemovePolygonHoles;
 pub use self::set_nodata_value::SetNodataValue;
 pub use self::singlepart_to_multipart::SinglePartToMultiPart;
+pub use self::synthetic_test_data_generator::SyntheticTestDataGenerator;
 pub use self::vector_lines_to_raster::VectorLinesToRaster;
 pub use self::vector_points_to_raster::VectorPointsToRaster;
 pub use self::vector_polygons_to_raster::VectorPolygonsToRaster;
@@ -0,0 +1,557 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::{check_geometry, header_configs};
+use crate::tools::*;
+use std::collections::HashSet;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::path::Path;
+use whitebox_common::error::{ErrorContext, WbtError};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool stacks single-band rasters (`--inputs`) into one multi-band GeoTIFF (`--output`),
+/// e.g. slope, aspect, and flow accumulation for visualization or as the features of a machine
+/// learning model. The inputs are given as an ordered, semicolon-separated list and become the
+/// bands of the output in that order. Each band's description, which GDAL and QGIS show as the
+/// band name, is set from the file name of its input without the extension, e.g. `slope` for
+/// `slope.tif`.
+///
+/// The inputs must share the same rows, columns, resolution, extent, and EPSG code; otherwise the
+/// tool fails with an error naming the inputs that differ. The output takes the georeferencing of
+/// the first input. Only the first band of an input is read. A GeoTIFF has one NoData value for all
+/// of its bands, so the output uses that of the first input, and the NoData cells of each other
+/// input are written with it; the tool fails if a valid cell of any input holds that value. The
+/// output's data type is that of the inputs when they all share one, and otherwise 32-bit floating
+/// point, or 64-bit floating point if any input holds 32- or 64-bit integers or 64-bit floats. The
+/// bands are stored one after another, so software that reads only single-band rasters, including
+/// the other tools, reads the first band.
+///
+/// With `--split`, the tool does the reverse: it explodes a single multi-band GeoTIFF (`--inputs`)
+/// into one single-band raster per band. The outputs are named after `--output` with the band's
+/// description appended, e.g. `terrain_slope.tif` for the band `slope` and `--output=terrain.tif`,
+/// with characters other than letters, digits, `-`, and `_` replaced by `_`. A band without a
+/// description, or whose description repeats that of an earlier band, is named by its number
+/// instead, e.g. `terrain_band2.tif`. The outputs may be in any raster format, based on the
+/// extension of `--output`, and keep the data type and NoData value of the input.
+///
+/// # See Also
+/// `ConvertRasterFormat`, `NewRasterFromBase`
+pub struct MultiBandCompositeFromSingles {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl MultiBandCompositeFromSingles {
+    pub fn new() -> MultiBandCompositeFromSingles {
+        // public constructor
+        let name = "MultiBandCompositeFromSingles".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Stacks single-band rasters into a multi-band GeoTIFF, or splits one into single bands."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input single-band raster files, in band order; with --split, the one multi-band GeoTIFF.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description:
+                "Output multi-band GeoTIFF; with --split, the base name of the single-band outputs."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Split a multi-band raster into single bands?".to_owned(),
+            flags: vec!["--split".to_owned()],
+            description: "Split the input multi-band GeoTIFF into one raster per band.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='slope.tif;aspect.tif;flow_accum.tif' -o=terrain.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=terrain.tif -o=terrain.tif --split",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        MultiBandCompositeFromSingles {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for MultiBandCompositeFromSingles {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut output_file = String::new();
+        let mut split = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-inputs" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-split" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    split = true;
+                }
+            }
+        }
+
+        let separator = if input_files.contains(';') { ';' } else { ',' };
+        let inputs: Vec<String> = input_files
+            .split(separator)
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .map(|f| resolve_path(working_directory, &f))
+            .collect();
+        if inputs.is_empty() {
+            return Err(
+                WbtError::invalid_argument("Input raster files (--inputs) not specified.").into(),
+            );
+        }
+        if output_file.is_empty() {
+            return Err(
+                WbtError::invalid_argument("Output raster file (--output) not specified.").into(),
+            );
+        }
+        let output_file = resolve_path(working_directory, &output_file);
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let start = Instant::now();
+        if split {
+            if inputs.len() != 1 {
+                return Err(WbtError::invalid_argument(format!(
+                    "Splitting (--split) takes one multi-band GeoTIFF (--inputs), not {}.",
+                    inputs.len()
+                ))
+                .into());
+            }
+            split_bands(&inputs[0], &output_file, verbose, start)
+        } else {
+            composite_bands(&inputs, &output_file, verbose, start)
+        }
+    }
+}
+
+/// Stacks the first bands of the inputs into a multi-band GeoTIFF.
+fn composite_bands(
+    inputs: &[String],
+    output_file: &str,
+    verbose: bool,
+    start: Instant,
+) -> Result<(), Error> {
+    if !is_geotiff(output_file) {
+        return Err(WbtError::invalid_argument(format!(
+            "The output (--output) must be a GeoTIFF (.tif or .tiff) to hold several bands: {}",
+            output_file
+        ))
+        .into());
+    }
+    let labels: Vec<String> = (1..=inputs.len())
+        .map(|band| format!("--inputs band {}", band))
+        .collect();
+    let mut headers = Vec::with_capacity(inputs.len());
+    for (label, file_name) in labels.iter().zip(inputs) {
+        let configs = header_configs(file_name).context(format!("{} {}", label, file_name))?;
+        headers.push((label.as_str(), file_name.as_str(), configs));
+    }
+    check_geometry(&headers, true).map_err(|e| WbtError::geometry_mismatch(e.to_string()))?;
+
+    let mut data_types = Vec::with_capacity(inputs.len());
+    for (label, file_name, configs) in &headers {
+        if !is_single_sample(configs.data_type) {
+            return Err(WbtError::invalid_argument(format!(
+                "{} {} holds {:?} values; only numeric rasters can be stacked.",
+                label, file_name, configs.data_type
+            ))
+            .into());
+        }
+        data_types.push(configs.data_type);
+    }
+    let data_type = common_data_type(&data_types);
+
+    let mut configs = headers[0].2.clone();
+    configs.data_type = data_type;
+    configs.bands = inputs.len().min(u8::MAX as usize) as u8;
+    configs.photometric_interp = PhotometricInterpretation::Continuous;
+    let nodata = configs.nodata;
+
+    let mut bands = Vec::with_capacity(inputs.len());
+    for (band, file_name) in inputs.iter().enumerate() {
+        if verbose {
+            println!("Reading band {} of {}...", band + 1, inputs.len());
+        }
+        let input =
+            Raster::new(file_name, "r").context(format!("{} {}", labels[band], file_name))?;
+        let input_nodata = input.configs.nodata;
+        let mut data = Vec::with_capacity(configs.rows * configs.columns);
+        for row in 0..configs.rows as isize {
+            for col in 0..configs.columns as isize {
+                let z = input.get_value(row, col);
+                if z == input_nodata {
+                    data.push(nodata);
+                } else if z == nodata {
+                    return Err(WbtError::data_error(format!(
+                        "{} {} has a valid cell (row {}, column {}) holding {}, the NoData value of the output, which it takes from the first input.",
+                        labels[band], file_name, row, col, nodata
+                    ))
+                    .into());
+                } else {
+                    data.push(z);
+                }
+            }
+        }
+        bands.push(GeoTiffBand {
+            data,
+            description: file_stem(file_name),
+        });
+    }
+
+    let elapsed_time = get_formatted_elapsed_time(start);
+    if verbose {
+        println!("Saving data...")
+    };
+    write_multiband_geotiff(output_file, &configs, &bands, None).context("--output")?;
+    if verbose {
+        println!(
+            "Output file written with {} bands of {:?} data",
+            bands.len(),
+            data_type
+        );
+        println!(
+            "{}",
+            &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+        );
+    }
+    Ok(())
+}
+
+/// Writes each band of a multi-band GeoTIFF to a single-band raster named after the output.
+fn split_bands(
+    input_file: &str,
+    output_file: &str,
+    verbose: bool,
+    start: Instant,
+) -> Result<(), Error> {
+    if !is_geotiff(input_file) {
+        return Err(WbtError::invalid_argument(format!(
+            "Splitting (--split) needs a GeoTIFF (.tif or .tiff) input (--inputs): {}",
+            input_file
+        ))
+        .into());
+    }
+    if verbose {
+        println!("Reading data...")
+    };
+    let (configs, bands) = read_multiband_geotiff(input_file).context("--inputs")?;
+    if !is_single_sample(configs.data_type) {
+        return Err(WbtError::invalid_argument(format!(
+            "--inputs {} holds {:?} values; only numeric rasters can be split.",
+            input_file, configs.data_type
+        ))
+        .into());
+    }
+
+    let output_path = Path::new(output_file);
+    let extension = output_path
+        .extension()
+        .map_or("tif".to_string(), |e| e.to_string_lossy().to_string());
+    let output_stem = output_path.with_extension("").to_string_lossy().to_string();
+    let mut configs = configs;
+    configs.bands = 1;
+    let mut used_names = HashSet::new();
+    let elapsed_time = get_formatted_elapsed_time(start);
+    for (band, GeoTiffBand { data, description }) in bands.into_iter().enumerate() {
+        let mut name = sanitize(&description);
+        if name.is_empty() || !used_names.insert(name.clone()) {
+            name = format!("band{}", band + 1);
+            used_names.insert(name.clone());
+        }
+        let file_name = format!("{}_{}.{}", output_stem, name, extension);
+        let mut output = Raster::initialize_using_config(&file_name, &configs);
+        for row in 0..configs.rows {
+            let values = data[row * configs.columns..(row + 1) * configs.columns].to_vec();
+            output.set_row_data(row as isize, values);
+        }
+        output.add_metadata_entry(
+            "Created by whitebox_tools\' MultiBandCompositeFromSingles tool".to_string(),
+        );
+        output.add_metadata_entry(format!("Band {} of {}", band + 1, input_file));
+        output.write().context(format!("--output {}", file_name))?;
+        if verbose {
+            println!("Band {} written to {}", band + 1, file_name);
+        }
+    }
+    if verbose {
+        println!(
+            "{}",
+            &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+        );
+    }
+    Ok(())
+}
+
+fn is_geotiff(file_name: &str) -> bool {
+    let file_name = file_name.to_lowercase();
+    file_name.ends_with(".tif") || file_name.ends_with(".tiff")
+}
+
+fn is_single_sample(data_type: DataType) -> bool {
+    !matches!(
+        data_type,
+        DataType::RGB24 | DataType::RGB48 | DataType::RGBA32 | DataType::Unknown
+    )
+}
+
+/// The data type that holds the values of every input: theirs if they share one, 32-bit floats if
+/// that holds them all exactly, and 64-bit floats otherwise.
+fn common_data_type(data_types: &[DataType]) -> DataType {
+    if data_types.iter().all(|&dt| dt == data_types[0]) {
+        return data_types[0];
+    }
+    let fits_f32 = |dt: &DataType| {
+        matches!(
+            dt,
+            DataType::U8 | DataType::I8 | DataType::U16 | DataType::I16 | DataType::F32
+        )
+    };
+    if data_types.iter().all(fits_f32) {
+        DataType::F32
+    } else {
+        DataType::F64
+    }
+}
+
+/// The file name without its directory or extension, the description of the band it becomes.
+fn file_stem(file_name: &str) -> String {
+    Path::new(file_name)
+        .file_stem()
+        .map_or(String::new(), |s| s.to_string_lossy().to_string())
+}
+
+/// A band description made safe for a file name.
+fn sanitize(description: &str) -> String {
+    description
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::MultiBandCompositeFromSingles;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::error::WbtError;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::*;
+
+    // A 2 x 3 raster with 10 m cells and its north-west corner at (west, 2000).
+    fn write_input(file: &str, west: f64, data_type: DataType, nodata: f64, values: &[f64]) {
+        let grid = GridFixture::new(2, 3, |r, c| values[(r * 3 + c) as usize])
+            .with_origin(west, 1980f64)
+            .with_nodata(nodata);
+        let mut raster = grid_fixture_raster(file, &grid);
+        raster.configs.data_type = data_type;
+        raster.write().unwrap();
+    }
+
+    #[test]
+    fn test_composite_and_split() {
+        let dir = temp_dir("multi_band_composite_from_singles").unwrap();
+        let slope = format!("{}/slope.tif", dir);
+        write_input(
+            &slope,
+            1000.0,
+            DataType::F32,
+            -9999.0,
+            &[0.5, 1.5, -9999.0, 2.0, 2.5, 3.0],
+        );
+        let aspect = format!("{}/aspect.tif", dir);
+        write_input(
+            &aspect,
+            1000.0,
+            DataType::I16,
+            -32768.0,
+            &[90.0, 180.0, 270.0, -32768.0, 0.0, 45.0],
+        );
+        let output = format!("{}/terrain.tif", dir);
+        let args = vec![format!("-i={};{}", slope, aspect), format!("-o={}", output)];
+        MultiBandCompositeFromSingles::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let (configs, bands) = read_multiband_geotiff(&output).unwrap();
+        assert_eq!(configs.bands, 2);
+        assert_eq!(configs.data_type, DataType::F32);
+        assert_eq!(configs.nodata, -9999.0);
+        assert_eq!((configs.west, configs.north), (1000.0, 2000.0));
+        assert_eq!(bands[0].description, "slope");
+        assert_eq!(bands[0].data, vec![0.5, 1.5, -9999.0, 2.0, 2.5, 3.0]);
+        // The NoData cells of the second band take the output's NoData value.
+        assert_eq!(bands[1].description, "aspect");
+        assert_eq!(bands[1].data, vec![90.0, 180.0, 270.0, -9999.0, 0.0, 45.0]);
+
+        // Splitting gives the bands back, named by their descriptions.
+        let args = vec![
+            format!("-i={}", output),
+            format!("-o={}/split.tif", dir),
+            "--split".to_string(),
+        ];
+        MultiBandCompositeFromSingles::new()
+            .run(args, "", false)
+            .unwrap();
+        let split_aspect = Raster::new(&format!("{}/split_aspect.tif", dir), "r").unwrap();
+        assert_eq!(split_aspect.get_value(0, 2), 270.0);
+        assert_eq!(split_aspect.get_value(1, 0), -9999.0);
+        let split_slope = Raster::new(&format!("{}/split_slope.tif", dir), "r").unwrap();
+        assert_eq!(split_slope.get_value(1, 2), 3.0);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_composite_errors() {
+        let dir = temp_dir("multi_band_composite_from_singles_errors").unwrap();
+        let first = format!("{}/first.tif", dir);
+        write_input(&first, 1000.0, DataType::F32, -9999.0, &[1.0; 6]);
+        let output = format!("{}/stack.tif", dir);
+
+        // A valid cell of a later band that holds the output's NoData value is an error.
+        let clash = format!("{}/clash.tif", dir);
+        write_input(
+            &clash,
+            1000.0,
+            DataType::F32,
+            0.0,
+            &[1.0, -9999.0, 1.0, 1.0, 1.0, 0.0],
+        );
+        let args = vec![format!("-i={};{}", first, clash), format!("-o={}", output)];
+        let err = MultiBandCompositeFromSingles::new()
+            .run(args, "", false)
+            .unwrap_err();
+        assert!(matches!(
+            WbtError::from_io_ref(&err),
+            Some(WbtError::DataError(_))
+        ));
+        assert!(err.to_string().contains("(row 0, column 1)"), "{}", err);
+
+        // So are inputs that do not share the first one's grid.
+        let offset = format!("{}/offset.tif", dir);
+        write_input(&offset, 1010.0, DataType::F32, -9999.0, &[1.0; 6]);
+        let args = vec![format!("-i={};{}", first, offset), format!("-o={}", output)];
+        let err = MultiBandCompositeFromSingles::new()
+            .run(args, "", false)
+            .unwrap_err();
+        assert!(matches!(
+            WbtError::from_io_ref(&err),
+            Some(WbtError::GeometryMismatch(_))
+        ));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,579 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::io::{Error, ErrorKind};
+use std::panic;
+use std::path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use whitebox_common::algorithms::trace_zone_boundaries;
+use whitebox_common::error::{ErrorContext, WbtError};
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool outlines the valid-data footprint of one or more rasters (`--inputs`), i.e. the area
+/// covered by cells that are not NoData, and writes the outlines to a polygon vector
+/// (`--output`), either a Shapefile (.shp) or a GeoJSON file (.geojson). It is useful for building
+/// an index of the coverage of a set of DEM tiles or checking where a clipped or reprojected
+/// raster actually holds data.
+///
+/// The output has one feature per input raster, in the order of `--inputs`. The footprint
+/// follows the cell edges: each group of valid cells connected through their edges becomes one
+/// polygon, with a hole for every enclosed area of NoData cells, so a raster with several
+/// separate areas of data becomes a multipart feature. Valid cells that touch only at a corner
+/// are outlined as separate polygons meeting at that corner. The attributes of each feature are:
+///
+/// | Field     | Description                                                         |
+/// |-----------|---------------------------------------------------------------------|
+/// | FILENAME  | The input raster, as listed in `--inputs`                           |
+/// | STATUS    | `success`, or `failure` if the raster could not be read             |
+/// | NUM_CELLS | The number of valid cells                                           |
+/// | DATA_FRAC | The fraction of the raster's cells that are valid                   |
+/// | ERROR     | Why the raster could not be read; empty on success                  |
+///
+/// The inputs are read and outlined in parallel. An input that cannot be read, e.g. a missing or
+/// corrupt file, does not stop the others: its feature has a null geometry, a `failure` status,
+/// and the error message, and a warning is printed in verbose mode. The tool fails only if none of
+/// the inputs can be read. A raster without any valid cells is outlined successfully with a null
+/// geometry. The output takes its projection from the first raster that is read successfully;
+/// the inputs are assumed to share it.
+///
+/// # See Also
+/// `RasterToVectorPolygons`, `LayerFootprint`, `MinimumBoundingEnvelope`
+pub struct RasterFootprintVector {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RasterFootprintVector {
+    pub fn new() -> RasterFootprintVector {
+        // public constructor
+        let name = "RasterFootprintVector".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Outlines the valid-data footprint of rasters as polygons, one feature per raster."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Files".to_owned(),
+            flags: vec!["-i".to_owned(), "--inputs".to_owned()],
+            description: "Input raster files.".to_owned(),
+            parameter_type: ParameterType::FileList(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output polygon vector file (.shp or .geojson).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif -o=footprint.shp
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i='tile1.tif;tile2.tif;tile3.tif' -o=footprints.geojson",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        RasterFootprintVector {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RasterFootprintVector {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_files = String::new();
+        let mut output_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-inputs" {
+                input_files = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        let separator = if input_files.contains(';') { ';' } else { ',' };
+        let inputs: Vec<String> = input_files
+            .split(separator)
+            .map(|f| f.trim().to_string())
+            .filter(|f| !f.is_empty())
+            .collect();
+        if inputs.is_empty() {
+            return Err(
+                WbtError::invalid_argument("Input raster files (--inputs) not specified.").into(),
+            );
+        }
+        if output_file.is_empty() {
+            return Err(
+                WbtError::invalid_argument("Output vector file (--output) not specified.").into(),
+            );
+        }
+        let mut output = VectorWriter::new(
+            &resolve_path(working_directory, &output_file),
+            ShapeType::Polygon,
+        )
+        .context("--output")?;
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let start = Instant::now();
+
+        let mut num_procs = num_cpus::get();
+        let configs = whitebox_common::configs::get_configs()?;
+        if configs.max_procs > 0 && (configs.max_procs as usize) < num_procs {
+            num_procs = configs.max_procs as usize;
+        }
+        let num_procs = num_procs.clamp(1, inputs.len());
+
+        // The inputs are handed out to the workers one at a time, so that a few large rasters do
+        // not hold up a worker's share of the rest.
+        let next_input = AtomicUsize::new(0);
+        let mut footprints: Vec<Option<Result<Footprint, WbtError>>> = vec![None; inputs.len()];
+        let (tx, rx) = mpsc::channel();
+        std::thread::scope(|scope| {
+            for _ in 0..num_procs {
+                let tx = tx.clone();
+                let (inputs, next_input) = (&inputs, &next_input);
+                scope.spawn(move || loop {
+                    let i = next_input.fetch_add(1, Ordering::SeqCst);
+                    if i >= inputs.len() {
+                        break;
+                    }
+                    let file = resolve_path(working_directory, &inputs[i]);
+                    tx.send((i, trace_footprint(&file).context(&inputs[i])))
+                        .unwrap();
+                });
+            }
+            drop(tx);
+
+            let mut progress: usize;
+            let mut old_progress: usize = 1;
+            for (received, (i, footprint)) in rx.iter().enumerate() {
+                if verbose {
+                    if let Err(e) = &footprint {
+                        println!("Warning: {}", e);
+                    }
+                    progress = (100.0_f64 * (received + 1) as f64 / inputs.len() as f64) as usize;
+                    if progress != old_progress {
+                        println!("Progress: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+                footprints[i] = Some(footprint);
+            }
+        });
+        let footprints: Vec<Result<Footprint, WbtError>> = footprints
+            .into_iter()
+            .map(|f| f.expect("Every input is outlined by a worker."))
+            .collect();
+
+        let num_failed = footprints.iter().filter(|f| f.is_err()).count();
+        if num_failed == inputs.len() {
+            let first_error = footprints.into_iter().find_map(|f| f.err()).unwrap();
+            return Err(first_error
+                .context(format!(
+                    "None of the {} input rasters could be read",
+                    inputs.len()
+                ))
+                .into());
+        }
+
+        let error_messages: Vec<String> = footprints
+            .iter()
+            .map(|f| match f {
+                Ok(_) => String::new(),
+                Err(e) => truncate_text(&e.to_string()),
+            })
+            .collect();
+        let text_width = |values: &mut dyn Iterator<Item = usize>| -> u8 {
+            values.max().unwrap_or(1).clamp(1, MAX_TEXT_WIDTH) as u8
+        };
+        output.add_field(&AttributeField::new(
+            "FILENAME",
+            FieldDataType::Text,
+            text_width(&mut inputs.iter().map(|f| f.len())),
+            0u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "STATUS",
+            FieldDataType::Text,
+            7u8,
+            0u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "NUM_CELLS",
+            FieldDataType::Int,
+            11u8,
+            0u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "DATA_FRAC",
+            FieldDataType::Real,
+            12u8,
+            6u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "ERROR",
+            FieldDataType::Text,
+            text_width(&mut error_messages.iter().map(|m| m.len())),
+            0u8,
+        ));
+
+        for ((file, footprint), error_message) in inputs.iter().zip(footprints).zip(error_messages)
+        {
+            let filename = FieldData::Text(truncate_text(file));
+            match footprint {
+                Ok(footprint) => {
+                    if output.projection.is_empty() {
+                        output.projection = footprint.projection;
+                    }
+                    output.add_record(
+                        footprint.geometry,
+                        vec![
+                            filename,
+                            FieldData::Text("success".to_string()),
+                            FieldData::Int(i32::try_from(footprint.num_cells).unwrap_or(i32::MAX)),
+                            FieldData::Real(footprint.data_fraction),
+                            FieldData::Text(error_message),
+                        ],
+                    );
+                }
+                Err(_) => {
+                    output.add_record(
+                        ShapefileGeometry::default(),
+                        vec![
+                            filename,
+                            FieldData::Text("failure".to_string()),
+                            FieldData::Null,
+                            FieldData::Null,
+                            FieldData::Text(error_message),
+                        ],
+                    );
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Outlined {} of {} input rasters.",
+                inputs.len() - num_failed,
+                inputs.len()
+            );
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The widest text field a Shapefile's attribute table can hold.
+const MAX_TEXT_WIDTH: usize = 254;
+
+/// Shortens a text attribute to `MAX_TEXT_WIDTH` bytes, on a character boundary.
+fn truncate_text(text: &str) -> String {
+    let mut end = text.len().min(MAX_TEXT_WIDTH);
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text[..end].to_string()
+}
+
+/// The outline of a raster's valid cells.
+#[derive(Clone)]
+struct Footprint {
+    geometry: ShapefileGeometry,
+    num_cells: usize,
+    data_fraction: f64,
+    projection: String,
+}
+
+/// Reads a raster and outlines its valid cells, with one part per ring. The geometry is null if
+/// there are no valid cells.
+fn trace_footprint(file: &str) -> Result<Footprint, WbtError> {
+    // Some readers panic on malformed files rather than returning an error; that is still a
+    // failure of this input only.
+    let input = match panic::catch_unwind(|| Raster::new(file, "r")) {
+        Ok(Ok(input)) => input,
+        Ok(Err(e)) => {
+            return Err(match WbtError::from(e) {
+                WbtError::Internal(message) => WbtError::data_error(message),
+                e => e,
+            })
+        }
+        Err(_) => return Err(WbtError::data_error("The raster could not be read.")),
+    };
+    let rows = input.configs.rows;
+    let columns = input.configs.columns;
+    let nodata = input.configs.nodata;
+    let (north, west) = (input.configs.north, input.configs.west);
+    let (res_x, res_y) = (input.configs.resolution_x, input.configs.resolution_y);
+
+    let mut num_cells = 0usize;
+    for row in 0..rows {
+        for col in 0..columns {
+            if input.get_value(row as isize, col as isize) != nodata {
+                num_cells += 1;
+            }
+        }
+    }
+    let mut geometry = ShapefileGeometry::default();
+    if num_cells > 0 {
+        geometry = ShapefileGeometry::new(ShapeType::Polygon);
+        let rings = trace_zone_boundaries(rows, columns, |row, col| {
+            (input.get_value(row as isize, col as isize) != nodata).then_some(())
+        });
+        for ring in rings {
+            let points: Vec<Point2D> = ring
+                .vertices
+                .iter()
+                .map(|&(r, c)| Point2D::new(west + c as f64 * res_x, north - r as f64 * res_y))
+                .collect();
+            geometry.add_part(&points);
+        }
+    }
+    Ok(Footprint {
+        geometry,
+        num_cells,
+        data_fraction: num_cells as f64 / (rows * columns).max(1) as f64,
+        projection: input.configs.coordinate_ref_system_wkt.clone(),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::RasterFootprintVector;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::error::WbtError;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_vector::{FieldData, ShapeType, Shapefile};
+
+    const NODATA: f64 = -32768f64;
+
+    // A 4 x 5 raster with 10 m cells and its north-west corner at (1000, 2000).
+    fn write_input(file: &str, rows: &[&str]) {
+        let grid = GridFixture::new(4, 5, |r, c| {
+            if rows[r as usize].as_bytes()[c as usize] == b'.' {
+                NODATA
+            } else {
+                1f64
+            }
+        })
+        .with_origin(1000f64, 1960f64)
+        .with_nodata(NODATA);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    #[test]
+    fn test_footprints() {
+        let dir = temp_dir("raster_footprint_vector").unwrap();
+        // A block of data with a NoData hole, and a separate cell touching it at a corner.
+        let block = format!("{}/block.tif", dir);
+        write_input(&block, &["xxx..", "x.x..", "xxx..", "...x."]);
+        let empty = format!("{}/empty.tif", dir);
+        write_input(&empty, &[".....", ".....", ".....", "....."]);
+        let missing = format!("{}/missing.tif", dir);
+        let output = format!("{}/footprints.shp", dir);
+
+        let args = vec![
+            format!("-i={};{};{}", block, missing, empty),
+            format!("-o={}", output),
+        ];
+        RasterFootprintVector::new().run(args, "", false).unwrap();
+
+        let footprints = Shapefile::read(&output).unwrap();
+        assert_eq!(footprints.header.shape_type, ShapeType::Polygon);
+        assert_eq!(footprints.num_records, 3);
+
+        let geometry = &footprints.records[0];
+        let parts: Vec<Vec<(f64, f64)>> = (0..geometry.num_parts as usize)
+            .map(|part| {
+                let start = geometry.parts[part] as usize;
+                let end = geometry
+                    .parts
+                    .get(part + 1)
+                    .map_or(geometry.points.len(), |&p| p as usize);
+                geometry.points[start..end]
+                    .iter()
+                    .map(|p| (p.x, p.y))
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                vec![
+                    (1000.0, 2000.0),
+                    (1030.0, 2000.0),
+                    (1030.0, 1970.0),
+                    (1000.0, 1970.0),
+                    (1000.0, 2000.0)
+                ],
+                vec![
+                    (1020.0, 1990.0),
+                    (1010.0, 1990.0),
+                    (1010.0, 1980.0),
+                    (1020.0, 1980.0),
+                    (1020.0, 1990.0)
+                ],
+                vec![
+                    (1030.0, 1970.0),
+                    (1040.0, 1970.0),
+                    (1040.0, 1960.0),
+                    (1030.0, 1960.0),
+                    (1030.0, 1970.0)
+                ],
+            ]
+        );
+        let attributes = |i: usize| -> Vec<FieldData> {
+            (0..5)
+                .map(|f| {
+                    let name = &footprints.attributes.get_field(f).name;
+                    footprints.attributes.get_value(i, name)
+                })
+                .collect()
+        };
+        let block_atts = attributes(0);
+        assert_eq!(block_atts[0], FieldData::Text(block.clone()));
+        assert_eq!(block_atts[1], FieldData::Text("success".to_string()));
+        assert_eq!(block_atts[2], FieldData::Int(9));
+        assert_eq!(block_atts[3], FieldData::Real(0.45));
+
+        // The missing raster is reported without stopping the others.
+        assert_eq!(footprints.records[1].shape_type, ShapeType::Null);
+        let missing_atts = attributes(1);
+        assert_eq!(missing_atts[1], FieldData::Text("failure".to_string()));
+        match &missing_atts[4] {
+            FieldData::Text(message) => assert!(message.starts_with(&missing), "{}", message),
+            value => panic!("Unexpected error value {:?}", value),
+        }
+
+        // A raster without data is outlined, with a null geometry.
+        assert_eq!(footprints.records[2].shape_type, ShapeType::Null);
+        let empty_atts = attributes(2);
+        assert_eq!(empty_atts[1], FieldData::Text("success".to_string()));
+        assert_eq!(empty_atts[2], FieldData::Int(0));
+
+        // The tool fails only when no input can be read.
+        let args = vec![format!("-i={}", missing), format!("-o={}", output)];
+        let err = RasterFootprintVector::new()
+            .run(args, "", false)
+            .unwrap_err();
+        assert!(matches!(
+            WbtError::from_io_ref(&err),
+            Some(WbtError::FileNotFound(_))
+        ));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
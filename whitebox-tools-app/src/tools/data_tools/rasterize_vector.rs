@@ -0,0 +1,598 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use geojson::{GeoJson, Value as GeoValue};
+use std::env;
+use std::f64;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use whitebox_common::algorithms::{
+    rasterize_point, rasterize_polygon, rasterize_polyline, GridGeometry,
+};
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::{FieldData, ShapeType, Shapefile};
+
+/// This tool burns the features of a vector layer (points, lines, or polygons) onto the grid
+/// of a template raster (`--base`). The output raster has the same rows, columns, extent, and
+/// projection as the template. Each feature is assigned either the constant `--value` (default 1.0)
+/// or the value of a numeric attribute `--field`. Features are burned in file order, so later
+/// features overwrite earlier ones where they overlap. Cells not covered by any feature are set
+/// to NoData, or to zero when `--zero_background` is specified.
+///
+/// The input may be a shapefile or a GeoJSON FeatureCollection (`.geojson` / `.json`). GeoJSON
+/// coordinates are assumed to be in the same coordinate system as the template raster.
+///
+/// By default, a polygon covers a cell when the cell centre falls inside the polygon; holes are
+/// respected. With `--all_touched`, every cell touched by the polygon, including its boundary, is
+/// burned. Lines burn every cell they cross; `--line_thickness` (in map units) widens lines to a
+/// band of cells around the traversed path. Points burn the cell that contains them.
+///
+/// The scanline and line-traversal routines are provided by `whitebox_common::algorithms` so that
+/// other tools can rasterize geometries onto a grid without writing intermediate files.
+///
+/// # See Also
+/// `VectorPolygonsToRaster`, `VectorLinesToRaster`, `VectorPointsToRaster`
+pub struct RasterizeVector {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RasterizeVector {
+    /// public constructor
+    pub fn new() -> RasterizeVector {
+        let name = "RasterizeVector".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description =
+            "Burns vector points, lines, or polygons onto the grid of a template raster."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Vector File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input vector file (shapefile or GeoJSON).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Any,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Template Raster File".to_owned(),
+            flags: vec!["--base".to_owned()],
+            description: "Input raster defining the output grid.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Field Name".to_owned(),
+            flags: vec!["--field".to_owned()],
+            description: "Optional numeric attribute field supplying burn values.".to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Number,
+                "--input".to_string(),
+            ),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Burn Value".to_owned(),
+            flags: vec!["--value".to_owned()],
+            description: "Constant burn value, used when no field is specified.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Burn All Touched Cells?".to_owned(),
+            flags: vec!["--all_touched".to_owned()],
+            description:
+                "Burn every cell touched by a polygon rather than only cells whose centres are inside."
+                    .to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Line Thickness".to_owned(),
+            flags: vec!["--line_thickness".to_owned()],
+            description: "Width of burned lines, in map units. Zero burns single-cell lines."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Zero Background?".to_owned(),
+            flags: vec!["--zero_background".to_owned()],
+            description: "Set unburned cells to zero instead of NoData.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=lakes.shp --base=dem.tif -o=lakes.tif --field=ELEV --all_touched
+        >>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=streams.geojson --base=dem.tif -o=streams.tif --line_thickness=20.0 --zero_background", short_exe, name).replace("*", &sep);
+
+        RasterizeVector {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+/// A vector feature reduced to the parts needed for rasterization.
+enum BurnGeometry {
+    Points(Vec<Point2D>),
+    Lines(Vec<Vec<Point2D>>),
+    /// Each entry is one polygon with its outer ring and holes.
+    Polygons(Vec<Vec<Vec<Point2D>>>),
+}
+
+impl WhiteboxTool for RasterizeVector {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut base_file = String::new();
+        let mut output_file = String::new();
+        let mut field_name = String::new();
+        let mut burn_value = 1f64;
+        let mut all_touched = false;
+        let mut line_thickness = 0f64;
+        let mut zero_background = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-base" {
+                base_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-field" {
+                field_name = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-value" {
+                burn_value = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-all_touched" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    all_touched = true;
+                }
+            } else if flag_val == "-line_thickness" {
+                line_thickness = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-zero_background" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    zero_background = true;
+                }
+            }
+        }
+
+        if input_file.is_empty() || base_file.is_empty() || output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --input, --base, and --output parameters are required.",
+            ));
+        }
+        if line_thickness < 0f64 || !line_thickness.is_finite() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--line_thickness must be a non-negative number.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(working_directory, &input_file);
+        base_file = resolve_path(working_directory, &base_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let base = Raster::new(&base_file, "r")?;
+        let features = self.read_features(&input_file, &field_name, burn_value)?;
+
+        let start = Instant::now();
+
+        let mut output = Raster::initialize_using_file(&output_file, &base);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        let nodata = output.configs.nodata;
+        if zero_background {
+            output.reinitialize_values(0f64);
+        } else {
+            output.reinitialize_values(nodata);
+        }
+
+        let geometry = GridGeometry {
+            rows: output.configs.rows,
+            columns: output.configs.columns,
+            west: output.configs.west,
+            north: output.configs.north,
+            resolution_x: output.configs.resolution_x,
+            resolution_y: output.configs.resolution_y,
+            nodata: nodata,
+        };
+        let min_res = geometry.resolution_x.min(geometry.resolution_y);
+        let line_radius = (line_thickness / (2f64 * min_res)).round() as usize;
+
+        let mut num_burned = 0usize;
+        let num_features = features.len();
+        for (feature_num, (burn_geometry, value)) in features.iter().enumerate() {
+            let value = *value;
+            let mut burn = |row: isize, column: isize| {
+                output.set_value(row, column, value);
+                num_burned += 1;
+            };
+            match burn_geometry {
+                BurnGeometry::Points(points) => {
+                    for p in points {
+                        if let Some((row, column)) = rasterize_point(&geometry, p.x, p.y) {
+                            burn(row, column);
+                        }
+                    }
+                }
+                BurnGeometry::Lines(lines) => {
+                    for line in lines {
+                        rasterize_polyline(&geometry, line, line_radius, &mut burn);
+                    }
+                }
+                BurnGeometry::Polygons(polygons) => {
+                    for rings in polygons {
+                        let ring_refs: Vec<&[Point2D]> =
+                            rings.iter().map(|ring| ring.as_slice()).collect();
+                        rasterize_polygon(&geometry, &ring_refs, all_touched, &mut burn);
+                    }
+                }
+            }
+
+            if verbose {
+                progress = (100.0_f64 * (feature_num + 1) as f64 / num_features as f64) as usize;
+                if progress != old_progress {
+                    println!("Rasterizing: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Template raster: {}", base_file));
+        if field_name.is_empty() {
+            output.add_metadata_entry(format!("Burn value: {}", burn_value));
+        } else {
+            output.add_metadata_entry(format!("Burn field: {}", field_name));
+        }
+        output.add_metadata_entry(format!("All touched: {}", all_touched));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if num_burned == 0 && verbose {
+            println!("Warning: No features overlapped the template raster.");
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl RasterizeVector {
+    /// Reads the input features and their burn values from a shapefile or GeoJSON file.
+    fn read_features(
+        &self,
+        input_file: &str,
+        field_name: &str,
+        burn_value: f64,
+    ) -> Result<Vec<(BurnGeometry, f64)>, Error> {
+        let lower = input_file.to_lowercase();
+        if lower.ends_with(".geojson") || lower.ends_with(".json") {
+            return read_geojson_features(input_file, field_name, burn_value);
+        }
+
+        let vector_data = Shapefile::read(input_file)?;
+        let field_index = if field_name.is_empty() {
+            None
+        } else {
+            match vector_data.attributes.get_field_num(field_name) {
+                Some(i) if vector_data.attributes.is_field_numeric(i) => Some(i),
+                Some(_) => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The attribute field '{}' is not numeric.", field_name),
+                    ))
+                }
+                None => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("The attribute field '{}' was not found.", field_name),
+                    ))
+                }
+            }
+        };
+
+        let base_shape_type = vector_data.header.shape_type.base_shape_type();
+        let mut features = Vec::with_capacity(vector_data.num_records);
+        for record_num in 0..vector_data.num_records {
+            let record = vector_data.get_record(record_num);
+            if record.shape_type == ShapeType::Null || record.num_points == 0 {
+                continue;
+            }
+            let value = if field_index.is_some() {
+                match vector_data.attributes.get_value(record_num, field_name) {
+                    FieldData::Int(val) => val as f64,
+                    FieldData::Real(val) => val,
+                    _ => continue, // null value for record
+                }
+            } else {
+                burn_value
+            };
+
+            let mut parts: Vec<Vec<Point2D>> = vec![];
+            for part in 0..record.num_parts as usize {
+                let start_point_in_part = record.parts[part] as usize;
+                let end_point_in_part = if part < record.num_parts as usize - 1 {
+                    record.parts[part + 1] as usize
+                } else {
+                    record.num_points as usize
+                };
+                parts.push(record.points[start_point_in_part..end_point_in_part].to_vec());
+            }
+
+            let burn_geometry = match base_shape_type {
+                ShapeType::Point | ShapeType::MultiPoint => {
+                    BurnGeometry::Points(record.points.clone())
+                }
+                ShapeType::PolyLine => BurnGeometry::Lines(parts),
+                // all rings of a record are combined with the even-odd rule
+                ShapeType::Polygon => BurnGeometry::Polygons(vec![parts]),
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        "Unsupported vector shape type.",
+                    ))
+                }
+            };
+            features.push((burn_geometry, value));
+        }
+        Ok(features)
+    }
+}
+
+fn positions_to_points(positions: &[Vec<f64>]) -> Vec<Point2D> {
+    positions
+        .iter()
+        .filter(|p| p.len() >= 2)
+        .map(|p| Point2D::new(p[0], p[1]))
+        .collect()
+}
+
+fn geojson_value_to_burn_geometry(value: &GeoValue) -> Option<BurnGeometry> {
+    match value {
+        GeoValue::Point(p) => Some(BurnGeometry::Points(positions_to_points(&[p.clone()]))),
+        GeoValue::MultiPoint(pts) => Some(BurnGeometry::Points(positions_to_points(pts))),
+        GeoValue::LineString(line) => Some(BurnGeometry::Lines(vec![positions_to_points(line)])),
+        GeoValue::MultiLineString(lines) => Some(BurnGeometry::Lines(
+            lines.iter().map(|l| positions_to_points(l)).collect(),
+        )),
+        GeoValue::Polygon(rings) => Some(BurnGeometry::Polygons(vec![rings
+            .iter()
+            .map(|r| positions_to_points(r))
+            .collect()])),
+        GeoValue::MultiPolygon(polygons) => Some(BurnGeometry::Polygons(
+            polygons
+                .iter()
+                .map(|rings| rings.iter().map(|r| positions_to_points(r)).collect())
+                .collect(),
+        )),
+        GeoValue::GeometryCollection(_) => None,
+    }
+}
+
+fn read_geojson_features(
+    input_file: &str,
+    field_name: &str,
+    burn_value: f64,
+) -> Result<Vec<(BurnGeometry, f64)>, Error> {
+    let geojson_str = fs::read_to_string(input_file)?;
+    let gj: GeoJson = geojson_str
+        .parse()
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+    let fc = match gj {
+        GeoJson::FeatureCollection(fc) => fc,
+        _ => {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "GeoJSON input must be a FeatureCollection.",
+            ))
+        }
+    };
+
+    let mut features = vec![];
+    let mut field_found = field_name.is_empty();
+    for feature in &fc.features {
+        let geometry = match &feature.geometry {
+            Some(g) => g,
+            None => continue,
+        };
+        let value = if field_name.is_empty() {
+            burn_value
+        } else {
+            match feature.property(field_name) {
+                Some(v) => {
+                    field_found = true;
+                    match v.as_f64() {
+                        Some(val) => val,
+                        None => continue, // null or non-numeric value for feature
+                    }
+                }
+                None => continue,
+            }
+        };
+        match geojson_value_to_burn_geometry(&geometry.value) {
+            Some(burn_geometry) => features.push((burn_geometry, value)),
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "GeoJSON GeometryCollection features are not supported.",
+                ))
+            }
+        }
+    }
+
+    if !field_found {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!("The attribute field '{}' was not found.", field_name),
+        ));
+    }
+    Ok(features)
+}
@@ -0,0 +1,559 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::path::Path;
+use whitebox_common::algorithms::{
+    synthetic_hydro_dataset, SyntheticHydroDataset, SyntheticHydroParams, SyntheticTerrain,
+    SYNTHETIC_NODATA,
+};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This developer-facing tool writes small synthetic hydrological datasets for testing hydrological
+/// tools: a DEM with its D8 pointer, streams, channel junction, stream order, watershed, and outlet
+/// rasters, all consistent with one another. The `--terrain` is one of:
+///
+/// | Terrain | Description |
+/// |---------|-------------|
+/// | `inclined_plane` | A plane dipping south onto a single channel along the bottom row, draining east to an outlet in the bottom-right corner. |
+/// | `v_valley` | Two planar flanks dipping towards a single channel down the centre column, starting a quarter of the way down the grid and draining south to an outlet on the bottom row. |
+/// | `dendritic` | A main channel down the centre column joined by three straight tributaries, alternately from the west and the east, at three junctions. |
+///
+/// The grid size (`--rows`, `--columns`) and cell size (`--resolution`) are set by the user, as are
+/// the gradient of the main channel (`--channel_slope`) and of the hillslopes (`--hillslope_slope`),
+/// in metres per metre. The tributaries of the `dendritic` terrain are three times as steep as its
+/// main channel. The hillslopes must be at least 2.5 times as steep as the channels (9 times for
+/// `dendritic`) so that the D8 flow stays in the channels.
+///
+/// The following files are written to the output directory (`--out_dir`), which is created if needed:
+///
+/// | File | Contents |
+/// |------|----------|
+/// | dem.tif | Elevations, with the outlet at 100 m. |
+/// | d8_pntr.tif | The Whitebox-style D8 pointer, i.e. the steepest descent direction. The outlet, on the grid edge, points off the grid. |
+/// | streams.tif | 1 on channel cells and 0 elsewhere. |
+/// | chnjnt.tif | The number of stream cells flowing into each stream cell (0 at channel heads), and NoData off the streams. |
+/// | order.tif | The Strahler order of stream cells, and 0 elsewhere. |
+/// | watershed.tif | 1 on the cells draining to the outlet, and NoData elsewhere. |
+/// | outlet.tif | 1 at the outlet and 0 elsewhere, for use as a pour point. |
+/// | manifest.json | The terrain and parameters, the outlet's row, column, and coordinates, the numbers of channel heads, junctions, and stream cells, the highest stream order, and the file names. |
+///
+/// The datasets are checked for internal consistency before they are written: every pointer
+/// descends, every stream cell drains to another stream cell or is the outlet, the junction, order,
+/// and watershed rasters agree with the pointer and streams, and the expected junctions exist. The
+/// rasters can be passed directly to `HillslopesTopaz`, and the resulting subwta raster passes its
+/// `--validate_only` check. Tests within WhiteboxTools can generate the same datasets in memory
+/// with `whitebox_common::algorithms::synthetic_hydro_dataset`.
+///
+/// # See Also
+/// `HillslopesTopaz`, `D8Pointer`, `StrahlerStreamOrder`, `Watershed`
+pub struct SyntheticTestDataGenerator {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SyntheticTestDataGenerator {
+    pub fn new() -> SyntheticTestDataGenerator {
+        // public constructor
+        let name = "SyntheticTestDataGenerator".to_string();
+        let toolbox = "Data Tools".to_string();
+        let description = "Writes a synthetic DEM with consistent D8 pointer, streams, junction, order, and watershed rasters for testing hydrological tools.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Terrain".to_owned(),
+            flags: vec!["--terrain".to_owned()],
+            description: "Synthetic terrain: 'inclined_plane', 'v_valley', or 'dendritic'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "inclined_plane".to_owned(),
+                "v_valley".to_owned(),
+                "dendritic".to_owned(),
+            ]),
+            default_value: Some("dendritic".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory".to_owned(),
+            flags: vec!["--out_dir".to_owned()],
+            description: "Output directory for the rasters and manifest.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Rows".to_owned(),
+            flags: vec!["--rows".to_owned()],
+            description: "Number of rows.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("21".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Columns".to_owned(),
+            flags: vec!["--columns".to_owned()],
+            description: "Number of columns.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("21".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Resolution".to_owned(),
+            flags: vec!["--resolution".to_owned()],
+            description: "Cell size, in metres.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Channel Slope".to_owned(),
+            flags: vec!["--channel_slope".to_owned()],
+            description: "Gradient of the main channel (m/m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.01".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Hillslope Slope".to_owned(),
+            flags: vec!["--hillslope_slope".to_owned()],
+            description: "Gradient of the hillslopes (m/m).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.1".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --terrain=dendritic --out_dir=fixtures
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --terrain=v_valley --out_dir=valley --rows=40 --columns=25 --resolution=30 --channel_slope=0.02 --hillslope_slope=0.2", short_exe, name).replace("*", &sep);
+
+        SyntheticTestDataGenerator {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SyntheticTestDataGenerator {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut terrain = SyntheticTerrain::Dendritic;
+        let mut out_dir = String::new();
+        let mut params = SyntheticHydroParams::default();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-terrain" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                terrain = SyntheticTerrain::from_name(value).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Unrecognized --terrain '{}'; expected 'inclined_plane', 'v_valley', or 'dendritic'.",
+                            value
+                        ),
+                    )
+                })?;
+            } else if flag_val == "-out_dir" {
+                out_dir = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-rows" || flag_val == "-columns" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                let n = value.trim().parse::<usize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse {} value '{}'.", vec[0], value),
+                    )
+                })?;
+                if flag_val == "-rows" {
+                    params.rows = n;
+                } else {
+                    params.columns = n;
+                }
+            } else if flag_val == "-resolution"
+                || flag_val == "-channel_slope"
+                || flag_val == "-hillslope_slope"
+            {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                let x = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse {} value '{}'.", vec[0], value),
+                    )
+                })?;
+                match flag_val.as_str() {
+                    "-resolution" => params.resolution = x,
+                    "-channel_slope" => params.channel_slope = x,
+                    _ => params.hillslope_slope = x,
+                }
+            }
+        }
+
+        if out_dir.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output directory (--out_dir) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        out_dir = resolve_path(working_directory, &out_dir);
+
+        let start = Instant::now();
+        let dataset = synthetic_hydro_dataset(terrain, &params)?;
+        let files = write_synthetic_dataset(&dataset, &out_dir)?;
+
+        if verbose {
+            println!(
+                "Wrote a {} x {} {} dataset with {} channel heads and {} junctions to {}.",
+                params.rows,
+                params.columns,
+                terrain.name(),
+                dataset.num_channel_heads(),
+                dataset.num_junctions(),
+                out_dir
+            );
+            for file in [
+                &files.dem,
+                &files.d8_pntr,
+                &files.streams,
+                &files.chnjnt,
+                &files.order,
+                &files.watershed,
+                &files.outlet,
+                &files.manifest,
+            ] {
+                println!("Output file written: {}", file);
+            }
+            println!(
+                "{}",
+                &format!(
+                    "Elapsed Time (including I/O): {}",
+                    get_formatted_elapsed_time(start)
+                )
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The paths of the files written by `write_synthetic_dataset`.
+pub(crate) struct SyntheticDatasetFiles {
+    pub dem: String,
+    pub d8_pntr: String,
+    pub streams: String,
+    pub chnjnt: String,
+    pub order: String,
+    pub watershed: String,
+    pub outlet: String,
+    pub manifest: String,
+}
+
+/// Writes the rasters and manifest of a synthetic dataset to `out_dir`, creating it if needed.
+/// Tests can pair this with `synthetic_hydro_dataset` to build input files for a tool.
+pub(crate) fn write_synthetic_dataset(
+    dataset: &SyntheticHydroDataset,
+    out_dir: &str,
+) -> Result<SyntheticDatasetFiles, Error> {
+    fs::create_dir_all(out_dir)?;
+    let params = &dataset.params;
+    let (rows, columns) = (params.rows, params.columns);
+    let file = |name: &str| Path::new(out_dir).join(name).to_string_lossy().to_string();
+    let mut outlet = vec![0f64; rows * columns];
+    outlet[dataset.outlet.0 * columns + dataset.outlet.1] = 1f64;
+
+    let grids: [(&str, &[f64], DataType); 7] = [
+        ("dem.tif", &dataset.dem, DataType::F32),
+        ("d8_pntr.tif", &dataset.d8_pntr, DataType::I16),
+        ("streams.tif", &dataset.streams, DataType::I16),
+        ("chnjnt.tif", &dataset.chnjnt, DataType::I16),
+        ("order.tif", &dataset.order, DataType::I16),
+        ("watershed.tif", &dataset.watershed, DataType::I16),
+        ("outlet.tif", &outlet, DataType::I16),
+    ];
+    for (name, values, data_type) in grids.iter() {
+        let mut configs = RasterConfigs {
+            ..Default::default()
+        };
+        configs.rows = rows;
+        configs.columns = columns;
+        configs.north = params.north;
+        configs.south = params.north - rows as f64 * params.resolution;
+        configs.west = params.west;
+        configs.east = params.west + columns as f64 * params.resolution;
+        configs.resolution_x = params.resolution;
+        configs.resolution_y = params.resolution;
+        configs.nodata = SYNTHETIC_NODATA;
+        configs.data_type = *data_type;
+        configs.photometric_interp = if *name == "dem.tif" {
+            PhotometricInterpretation::Continuous
+        } else {
+            PhotometricInterpretation::Categorical
+        };
+        let mut raster = Raster::initialize_using_config(&file(name), &configs);
+        for row in 0..rows {
+            for col in 0..columns {
+                raster.set_value(row as isize, col as isize, values[row * columns + col]);
+            }
+        }
+        raster.add_metadata_entry(format!(
+            "Created by whitebox_tools' SyntheticTestDataGenerator tool ({} terrain)",
+            dataset.terrain.name()
+        ));
+        raster.write()?;
+    }
+
+    let (outlet_row, outlet_col) = dataset.outlet;
+    let manifest = json!({
+        "terrain": dataset.terrain.name(),
+        "rows": rows,
+        "columns": columns,
+        "resolution": params.resolution,
+        "west": params.west,
+        "north": params.north,
+        "base_elevation": params.base_elevation,
+        "channel_slope": params.channel_slope,
+        "hillslope_slope": params.hillslope_slope,
+        "nodata": SYNTHETIC_NODATA,
+        "outlet": {
+            "row": outlet_row,
+            "column": outlet_col,
+            "x": dataset.x(outlet_col),
+            "y": dataset.y(outlet_row),
+        },
+        "num_channel_heads": dataset.num_channel_heads(),
+        "num_junctions": dataset.num_junctions(),
+        "num_stream_cells": dataset.streams.iter().filter(|&&v| v == 1f64).count(),
+        "max_order": dataset.order.iter().cloned().fold(0f64, f64::max),
+        "files": {
+            "dem": "dem.tif",
+            "d8_pntr": "d8_pntr.tif",
+            "streams": "streams.tif",
+            "chnjnt": "chnjnt.tif",
+            "order": "order.tif",
+            "watershed": "watershed.tif",
+            "outlet": "outlet.tif",
+        },
+    });
+    let manifest_file = file("manifest.json");
+    let text = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| Error::new(ErrorKind::Other, e.to_string()))?;
+    fs::write(&manifest_file, text + "\n")?;
+
+    Ok(SyntheticDatasetFiles {
+        dem: file("dem.tif"),
+        d8_pntr: file("d8_pntr.tif"),
+        streams: file("streams.tif"),
+        chnjnt: file("chnjnt.tif"),
+        order: file("order.tif"),
+        watershed: file("watershed.tif"),
+        outlet: file("outlet.tif"),
+        manifest: manifest_file,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{write_synthetic_dataset, SyntheticTestDataGenerator};
+    use crate::tools::hydro_analysis::HillslopesTopaz;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::algorithms::{
+        synthetic_hydro_dataset, SyntheticHydroParams, SyntheticTerrain,
+    };
+    use whitebox_common::test_support::temp_dir;
+    use whitebox_raster::Raster;
+
+    #[test]
+    fn test_datasets_pass_hillslopes_topaz_validation() {
+        let root = temp_dir("synthetic_test_data_validation").unwrap();
+        for terrain in [
+            SyntheticTerrain::InclinedPlane,
+            SyntheticTerrain::VValley,
+            SyntheticTerrain::Dendritic,
+        ] {
+            let dir = format!("{}/{}", root, terrain.name());
+            let params = SyntheticHydroParams {
+                rows: 15,
+                columns: 11,
+                ..Default::default()
+            };
+            let dataset = synthetic_hydro_dataset(terrain, &params).unwrap();
+            let files = write_synthetic_dataset(&dataset, &dir).unwrap();
+            let subwta = format!("{}/subwta.tif", dir);
+            let netw = format!("{}/netw.tsv", dir);
+            HillslopesTopaz::new()
+                .run(
+                    vec![
+                        format!("--dem={}", files.dem),
+                        format!("--d8_pntr={}", files.d8_pntr),
+                        format!("--streams={}", files.streams),
+                        format!("--pour_pts={}", files.outlet),
+                        format!("--watershed={}", files.watershed),
+                        format!("--chnjnt={}", files.chnjnt),
+                        format!("--order={}", files.order),
+                        format!("--subwta={}", subwta),
+                        format!("--netw={}", netw),
+                    ],
+                    "",
+                    false,
+                )
+                .unwrap();
+            HillslopesTopaz::new()
+                .run(
+                    vec![
+                        format!("--d8_pntr={}", files.d8_pntr),
+                        format!("--streams={}", files.streams),
+                        format!("--watershed={}", files.watershed),
+                        format!("--subwta={}", subwta),
+                        format!("--netw={}", netw),
+                        "--validate_only".to_string(),
+                        format!("--violations={}/violations.tsv", dir),
+                    ],
+                    "",
+                    false,
+                )
+                .unwrap();
+            // one link per channel head and junction
+            let num_links = fs::read_to_string(&netw).unwrap().lines().count() - 1;
+            assert_eq!(
+                num_links,
+                dataset.num_channel_heads() + dataset.num_junctions(),
+                "{}",
+                terrain.name()
+            );
+        }
+        fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_tool_writes_rasters_and_manifest() {
+        let dir = temp_dir("synthetic_test_data_tool").unwrap();
+        SyntheticTestDataGenerator::new()
+            .run(
+                vec![
+                    "--terrain=v_valley".to_string(),
+                    "--out_dir=valley".to_string(),
+                    "--rows=12".to_string(),
+                    "--columns=9".to_string(),
+                    "--resolution=30".to_string(),
+                ],
+                &dir,
+                false,
+            )
+            .unwrap();
+        let manifest: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(format!("{}/valley/manifest.json", dir)).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(manifest["terrain"], "v_valley");
+        assert_eq!(manifest["outlet"]["row"], 11);
+        assert_eq!(manifest["outlet"]["column"], 4);
+        assert_eq!(manifest["outlet"]["x"], 135.0);
+        assert_eq!(manifest["num_junctions"], 0);
+
+        let d8 = Raster::new(&format!("{}/valley/d8_pntr.tif", dir), "r").unwrap();
+        assert_eq!(d8.configs.rows, 12);
+        assert_eq!(d8.configs.resolution_x, 30f64);
+        assert_eq!(d8.get_value(11, 4), 8f64);
+        assert_eq!(d8.get_value(5, 0), 2f64);
+
+        let err = SyntheticTestDataGenerator::new()
+            .run(
+                vec![
+                    "--terrain=dendritic".to_string(),
+                    "--out_dir=small".to_string(),
+                    "--rows=8".to_string(),
+                ],
+                &dir,
+                false,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("at least 11 rows"));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -5,14 +5,30 @@ Created: 08/06/2025
 License: MIT
 */
 
+use crate::tools::hydro_analysis::raster_geometry::header_configs;
 use crate::tools::*;
 use std::env;
 use std::io::{Error, ErrorKind};
 use std::path;
 use std::sync::Arc;
-use whitebox_common::utils::get_formatted_elapsed_time;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
 use whitebox_raster::*;
 
+/// This tool clips an input raster (`--input`) to a mask raster (`--mask`) with the same rows,
+/// columns, and resolution. Cells where the mask is NoData or zero are set to NoData in the output,
+/// and all other cells keep their input values.
+///
+/// The output carries over the data type, photometric interpretation (e.g. categorical), palette,
+/// and metadata entries of the input, so that clipped categorical rasters, such as sub-catchment or
+/// land use maps, keep their qualitative palette. The clip details are appended to the input's
+/// metadata. The `--as_categorical` flag forces categorical settings on the output when the input
+/// lacks them, giving it the categorical photometric interpretation and, unless the input is already
+/// categorical with a palette of its own, the `qual.plt` palette. Note that the GeoTIFF writer stores
+/// neither the categorical interpretation nor the metadata entries; use a format that does, such as
+/// the Whitebox raster format (`.tas`/`.dep`), to preserve them.
+///
+/// # See Also
+/// `Clip`, `ClipRasterToPolygon`
 pub struct ClipRasterToRaster {
     name: String,
     description: String,
@@ -59,6 +75,15 @@ impl ClipRasterToRaster {
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Force Categorical Output?".to_owned(),
+            flags: vec!["--as_categorical".to_owned()],
+            description: "Give the output categorical settings (photometric interpretation and a qualitative palette).".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
         // --- example usage ---
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let exe = format!("{}", env::current_exe().unwrap().display());
@@ -69,7 +94,8 @@ impl ClipRasterToRaster {
             .replace(".exe", "")
             .replace(&sep, "");
         let usage = format!(
-            ">>{} -r={} -v --wd=\"*path*to*wd*\" -i=input.tif -m=mask.tif -o=clipped.tif",
+            ">>{0} -r={1} -v --wd=\"*path*to*wd*\" -i=input.tif -m=mask.tif -o=clipped.tif
+>>{0} -r={1} -v --wd=\"*path*to*wd*\" -i=subwta.tas -m=mask.tas -o=subwta_clip.tas --as_categorical",
             exe_short, name
         )
         .replace("*", &sep);
@@ -121,10 +147,10 @@ impl WhiteboxTool for ClipRasterToRaster {
                 "No parameters supplied.",
             ));
         }
-        let sep: String = path::MAIN_SEPARATOR.to_string();
         let mut input_file = String::new();
         let mut mask_file = String::new();
         let mut output_file = String::new();
+        let mut as_categorical = false;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -160,6 +186,10 @@ impl WhiteboxTool for ClipRasterToRaster {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-as_categorical" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    as_categorical = true;
+                }
             }
         }
 
@@ -169,31 +199,31 @@ impl WhiteboxTool for ClipRasterToRaster {
                 "Missing required arguments.",
             ));
         }
-        if !input_file.contains(&sep) && !input_file.contains('/') {
-            input_file = format!("{}{}", working_directory, input_file);
-        }
-        if !mask_file.contains(&sep) && !mask_file.contains('/') {
-            mask_file = format!("{}{}", working_directory, mask_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains('/') {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        input_file = resolve_path(working_directory, &input_file);
+        mask_file = resolve_path(working_directory, &mask_file);
+        output_file = resolve_path(working_directory, &output_file);
 
         // --------------------------------------------------
         //          Open rasters and sanity checks
         // --------------------------------------------------
-        let input = Arc::new(Raster::new(&input_file, "r")?);
-        let mask = Arc::new(Raster::new(&mask_file, "r")?);
-        if input.configs.rows != mask.configs.rows
-            || input.configs.columns != mask.configs.columns
-            || (input.configs.resolution_x - mask.configs.resolution_x).abs() > f64::EPSILON
-            || (input.configs.resolution_y - mask.configs.resolution_y).abs() > f64::EPSILON
+        // Compare the headers first so that mismatched grids are rejected before any pixels are read.
+        let input_configs = header_configs(&input_file)?;
+        let mask_configs = header_configs(&mask_file)?;
+        if input_configs.rows != mask_configs.rows
+            || input_configs.columns != mask_configs.columns
+            || (input_configs.resolution_x - mask_configs.resolution_x).abs() > f64::EPSILON
+            || (input_configs.resolution_y - mask_configs.resolution_y).abs() > f64::EPSILON
         {
             return Err(Error::new(
                 ErrorKind::InvalidInput,
                 "Input and mask rasters must have identical extent, rows, columns, and resolution.",
             ));
         }
+        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let mask = Arc::new(Raster::new(&mask_file, "r")?);
 
         //--------------------------------------------------
         //              Core clipping loop
@@ -205,6 +235,25 @@ impl WhiteboxTool for ClipRasterToRaster {
 
         let start = std::time::Instant::now();
         let mut output = Raster::initialize_using_file(&output_file, &input);
+        // Carry over the display settings and metadata of the input explicitly, so that a clipped
+        // categorical raster keeps its palette; the clip entries are appended below.
+        output.configs.data_type = input.configs.data_type;
+        output.configs.photometric_interp = input.configs.photometric_interp;
+        output.configs.palette = input.configs.palette.clone();
+        output.configs.palette_nonlinearity = input.configs.palette_nonlinearity;
+        output.configs.metadata = input.configs.metadata.clone();
+        if as_categorical {
+            let has_palette = match input.configs.photometric_interp {
+                PhotometricInterpretation::Categorical | PhotometricInterpretation::Paletted => {
+                    !input.configs.palette.is_empty() && input.configs.palette != "not specified"
+                }
+                _ => false,
+            };
+            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            if !has_palette {
+                output.configs.palette = "qual.plt".to_string();
+            }
+        }
 
         let mut old_progress = 0usize;
         for row in 0..rows {
@@ -235,6 +284,9 @@ impl WhiteboxTool for ClipRasterToRaster {
         ));
         output.add_metadata_entry(format!("Input:  {}", input_file));
         output.add_metadata_entry(format!("Mask:   {}", mask_file));
+        if as_categorical {
+            output.add_metadata_entry("As categorical: true".to_string());
+        }
         output.add_metadata_entry(format!(
             "Elapsed Time (excluding I/O): {}",
             get_formatted_elapsed_time(start)
@@ -250,3 +302,124 @@ impl WhiteboxTool for ClipRasterToRaster {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::ClipRasterToRaster;
+    use crate::tools::WhiteboxTool;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::{DataType, PhotometricInterpretation, Raster};
+
+    // A 3 x 3 raster in the Whitebox format, which stores the display settings and metadata.
+    fn write_raster(
+        file: &str,
+        photometric_interp: PhotometricInterpretation,
+        palette: &str,
+        metadata: &[&str],
+    ) {
+        let mut raster =
+            grid_fixture_raster(file, &GridFixture::new(3, 3, |r, c| (r * 3 + c + 1) as f64));
+        raster.configs.data_type = DataType::I16;
+        raster.configs.photometric_interp = photometric_interp;
+        raster.configs.palette = palette.to_string();
+        for md in metadata {
+            raster.add_metadata_entry(md.to_string());
+        }
+        raster.write().unwrap();
+    }
+
+    fn clip(dir: &str, input: &str, extra: &[&str]) -> Raster {
+        let mut args = vec![
+            format!("--input={}/{}", dir, input),
+            format!("--mask={}/mask.tas", dir),
+            format!("--output={}/clipped.tas", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.to_string()));
+        ClipRasterToRaster::new().run(args, "", false).unwrap();
+        Raster::new(&format!("{}/clipped.tas", dir), "r").unwrap()
+    }
+
+    #[test]
+    fn test_preserves_categorical_settings_and_metadata() {
+        let dir = temp_dir("clip_raster_to_raster_categorical").unwrap();
+        write_raster(
+            &format!("{}/subwta.tas", dir),
+            PhotometricInterpretation::Categorical,
+            "qual.plt",
+            &["Created by SubwtaBuilder"],
+        );
+        write_raster(
+            &format!("{}/mask.tas", dir),
+            PhotometricInterpretation::Continuous,
+            "grey.plt",
+            &[],
+        );
+        let output = clip(&dir, "subwta.tas", &[]);
+        assert_eq!(output.configs.data_type, DataType::I16);
+        assert_eq!(
+            output.configs.photometric_interp,
+            PhotometricInterpretation::Categorical
+        );
+        assert_eq!(output.configs.palette, "qual.plt");
+        assert_eq!(output.configs.metadata[0], "Created by SubwtaBuilder");
+        assert!(output.configs.metadata[1].contains("ClipRasterToRaster"));
+        assert_eq!(output.get_value(1, 1), 5f64);
+    }
+
+    #[test]
+    fn test_as_categorical_forces_categorical_settings() {
+        let dir = temp_dir("clip_raster_to_raster_as_categorical").unwrap();
+        write_raster(
+            &format!("{}/landuse.tas", dir),
+            PhotometricInterpretation::Continuous,
+            "spectrum.plt",
+            &[],
+        );
+        write_raster(
+            &format!("{}/mask.tas", dir),
+            PhotometricInterpretation::Continuous,
+            "grey.plt",
+            &[],
+        );
+        let output = clip(&dir, "landuse.tas", &[]);
+        assert_eq!(
+            output.configs.photometric_interp,
+            PhotometricInterpretation::Continuous
+        );
+        assert_eq!(output.configs.palette, "spectrum.plt");
+
+        let output = clip(&dir, "landuse.tas", &["--as_categorical"]);
+        assert_eq!(
+            output.configs.photometric_interp,
+            PhotometricInterpretation::Categorical
+        );
+        assert_eq!(output.configs.palette, "qual.plt");
+        assert_eq!(output.configs.data_type, DataType::I16);
+    }
+
+    #[test]
+    fn test_rejects_mismatched_grids() {
+        let dir = temp_dir("clip_raster_to_raster_mismatch").unwrap();
+        write_raster(
+            &format!("{}/landuse.tas", dir),
+            PhotometricInterpretation::Continuous,
+            "spectrum.plt",
+            &[],
+        );
+        grid_fixture_raster(
+            &format!("{}/mask.tif", dir),
+            &GridFixture::new(2, 3, |_, _| 1f64),
+        )
+        .write()
+        .unwrap();
+
+        let args = vec![
+            format!("--input={}/landuse.tas", dir),
+            format!("--mask={}/mask.tif", dir),
+            format!("--output={}/clipped.tas", dir),
+        ];
+        let err = ClipRasterToRaster::new().run(args, "", false).unwrap_err();
+        assert!(err.to_string().contains("identical extent"));
+    }
+}
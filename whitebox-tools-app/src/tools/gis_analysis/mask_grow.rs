@@ -0,0 +1,206 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::gis_analysis::mask_morphology::{
+    mask_morphology_parameters, run_mask_morphology, MaskOperation,
+};
+use crate::tools::*;
+use std::env;
+use std::io::Error;
+use std::path;
+
+/// This tool dilates (grows) a binary mask raster, such as a watershed mask, by a given number of grid
+/// cells. Clipping climate, soils, or other rasters exactly to a watershed boundary leaves later
+/// interpolation without data beyond the edge; clipping to a grown mask avoids these edge artifacts.
+/// The input mask (`--input`) contains the non-zero, non-NoData cells; zero and NoData cells are
+/// background.
+///
+/// A cell is added to the mask if any mask cell lies within its structuring element (`--element`), which
+/// is either `circular` (cells whose centres are within `--radius` cells, the default) or `square` (cells
+/// within `--radius` rows and columns). The operation is repeated `--iterations` times; with a square
+/// element, growing by a radius of *r* with *n* iterations is the same as growing once by *r* × *n*.
+/// Rather than visiting every cell of the structuring element, the tool thresholds a distance transform
+/// of the mask, so its run time does not depend on the radius.
+///
+/// Mask cells are written as 1 in the output, which has the extent and cell size of the input; the
+/// mask does not grow beyond the edges of the raster. Cells outside of the grown mask keep their input
+/// value, so NoData cells remain NoData.
+///
+/// # See Also
+/// `MaskShrink`, `BufferRaster`, `EuclideanDistance`
+pub struct MaskGrow {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl MaskGrow {
+    pub fn new() -> MaskGrow {
+        // public constructor
+        let name = "MaskGrow".to_string();
+        let toolbox = "GIS Analysis/Distance Tools".to_string();
+        let description =
+            "Dilates a binary mask raster by a radius in grid cells, using a circular or square structuring element."
+                .to_string();
+
+        let parameters = mask_morphology_parameters();
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=watershed.tif -o=watershed_grown.tif --radius=3
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=watershed.tif -o=watershed_grown.tif --radius=2 --element=square --iterations=2", short_exe, name).replace("*", &sep);
+
+        MaskGrow {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for MaskGrow {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        run_mask_morphology(
+            &self.get_tool_name(),
+            MaskOperation::Grow,
+            args,
+            working_directory,
+            verbose,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MaskGrow;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::{DataType, Raster};
+
+    const NODATA: f64 = -32768f64;
+
+    // Writes a 9 x 9 mask raster with 10 m cells.
+    fn write_mask(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(9, 9, f).with_nodata(NODATA).categorical();
+        let mut raster = grid_fixture_raster(file, &grid);
+        raster.configs.data_type = DataType::I16;
+        raster.write().unwrap();
+    }
+
+    #[test]
+    fn test_grow_single_cell() {
+        let dir = temp_dir("mask_grow_single_cell").unwrap();
+        let input = format!("{}/mask.tif", dir);
+        write_mask(&input, &|r, c| if (r, c) == (4, 4) { 1f64 } else { NODATA });
+
+        // A circle of radius 2 holds the 13 cells within 2 cells of the centre.
+        let output = format!("{}/circle.tif", dir);
+        MaskGrow::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("-o={}", output),
+                    "--radius=2".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let output = Raster::new(&output, "r").unwrap();
+        for r in 0..9isize {
+            for c in 0..9isize {
+                let (dr, dc) = (r - 4, c - 4);
+                let expected = if dr * dr + dc * dc <= 4 { 1f64 } else { NODATA };
+                assert_eq!(output.get_value(r, c), expected, "row {} col {}", r, c);
+            }
+        }
+
+        // Two iterations of a square of radius 1 give a 5 x 5 square, clipped at the raster edge
+        // for a mask cell in the corner.
+        let input2 = format!("{}/mask2.tif", dir);
+        write_mask(&input2, &|r, c| if (r, c) == (0, 8) { 1f64 } else { 0f64 });
+        let output = format!("{}/square.tif", dir);
+        MaskGrow::new()
+            .run(
+                vec![
+                    format!("-i={}", input2),
+                    format!("-o={}", output),
+                    "--radius=1".to_string(),
+                    "--element=square".to_string(),
+                    "--iterations=2".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let output = Raster::new(&output, "r").unwrap();
+        for r in 0..9isize {
+            for c in 0..9isize {
+                let expected = if r <= 2 && c >= 6 { 1f64 } else { 0f64 };
+                assert_eq!(output.get_value(r, c), expected, "row {} col {}", r, c);
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+}
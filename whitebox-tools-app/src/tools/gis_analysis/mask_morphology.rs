@@ -0,0 +1,266 @@
+/*
+This module is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::io::{Error, ErrorKind};
+use whitebox_common::algorithms::{dilate_mask, erode_mask, StructuringElement};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// Whether a mask is dilated (`MaskGrow`) or eroded (`MaskShrink`).
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum MaskOperation {
+    Grow,
+    Shrink,
+}
+
+/// The parameters shared by `MaskGrow` and `MaskShrink`.
+pub(crate) fn mask_morphology_parameters() -> Vec<ToolParameter> {
+    let mut parameters = vec![];
+    parameters.push(ToolParameter {
+        name: "Input Mask File".to_owned(),
+        flags: vec!["-i".to_owned(), "--input".to_owned()],
+        description: "Input mask raster; non-zero, non-NoData cells are in the mask.".to_owned(),
+        parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+        default_value: None,
+        optional: false,
+    });
+
+    parameters.push(ToolParameter {
+        name: "Output File".to_owned(),
+        flags: vec!["-o".to_owned(), "--output".to_owned()],
+        description: "Output mask raster file.".to_owned(),
+        parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+        default_value: None,
+        optional: false,
+    });
+
+    parameters.push(ToolParameter {
+        name: "Radius (cells)".to_owned(),
+        flags: vec!["--radius".to_owned()],
+        description: "Radius of the structuring element, in grid cells.".to_owned(),
+        parameter_type: ParameterType::Integer,
+        default_value: Some("1".to_owned()),
+        optional: true,
+    });
+
+    parameters.push(ToolParameter {
+        name: "Structuring Element".to_owned(),
+        flags: vec!["--element".to_owned()],
+        description: "Shape of the structuring element: 'circular' or 'square'.".to_owned(),
+        parameter_type: ParameterType::OptionList(vec!["circular".to_owned(), "square".to_owned()]),
+        default_value: Some("circular".to_owned()),
+        optional: true,
+    });
+
+    parameters.push(ToolParameter {
+        name: "Iterations".to_owned(),
+        flags: vec!["--iterations".to_owned()],
+        description: "Number of times the operation is applied.".to_owned(),
+        parameter_type: ParameterType::Integer,
+        default_value: Some("1".to_owned()),
+        optional: true,
+    });
+    parameters
+}
+
+/// Parses the arguments of `MaskGrow` or `MaskShrink` and writes the dilated or eroded mask.
+pub(crate) fn run_mask_morphology(
+    tool_name: &str,
+    operation: MaskOperation,
+    args: Vec<String>,
+    working_directory: &str,
+    verbose: bool,
+) -> Result<(), Error> {
+    let mut input_file = String::new();
+    let mut output_file = String::new();
+    let mut radius = 1usize;
+    let mut element = StructuringElement::Circle;
+    let mut iterations = 1usize;
+
+    if args.len() == 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Tool run with no parameters.",
+        ));
+    }
+    for i in 0..args.len() {
+        let mut arg = args[i].replace("\"", "");
+        arg = arg.replace("\'", "");
+        let cmd = arg.split("="); // in case an equals sign was used
+        let vec = cmd.collect::<Vec<&str>>();
+        let mut keyval = false;
+        if vec.len() > 1 {
+            keyval = true;
+        }
+        let flag_val = vec[0].to_lowercase().replace("--", "-");
+        if flag_val == "-i" || flag_val == "-input" {
+            input_file = if keyval {
+                vec[1].to_string()
+            } else {
+                args[i + 1].to_string()
+            };
+        } else if flag_val == "-o" || flag_val == "-output" {
+            output_file = if keyval {
+                vec[1].to_string()
+            } else {
+                args[i + 1].to_string()
+            };
+        } else if flag_val == "-radius" || flag_val == "-iterations" {
+            let value = if keyval {
+                vec[1].to_string()
+            } else {
+                args[i + 1].to_string()
+            };
+            let n = value.trim().parse::<usize>().map_err(|_| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("Error parsing {} value '{}'.", vec[0], value),
+                )
+            })?;
+            if flag_val == "-radius" {
+                radius = n;
+            } else {
+                iterations = n;
+            }
+        } else if flag_val == "-element" {
+            let value = if keyval {
+                vec[1].to_lowercase()
+            } else {
+                args[i + 1].to_lowercase()
+            };
+            element = match value.trim() {
+                "circular" | "circle" => StructuringElement::Circle,
+                "square" => StructuringElement::Square,
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Unrecognized --element '{}'; expected 'circular' or 'square'.",
+                            value
+                        ),
+                    ))
+                }
+            };
+        }
+    }
+
+    if input_file.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Input mask file (--input) not specified.",
+        ));
+    }
+    if output_file.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Output raster file (--output) not specified.",
+        ));
+    }
+
+    print_tool_banner(tool_name, verbose);
+
+    input_file = resolve_path(working_directory, &input_file);
+    output_file = resolve_path(working_directory, &output_file);
+
+    if verbose {
+        println!("Reading data...")
+    };
+    let input = Raster::new(&input_file, "r")?;
+
+    let start = Instant::now();
+
+    let rows = input.configs.rows;
+    let columns = input.configs.columns;
+    let nodata = input.configs.nodata;
+
+    let mut mask = vec![false; rows * columns];
+    let mut has_nodata = false;
+    for row in 0..rows {
+        for col in 0..columns {
+            let z = input.get_value(row as isize, col as isize);
+            if z == nodata {
+                has_nodata = true;
+            } else if z != 0f64 {
+                mask[row * columns + col] = true;
+            }
+        }
+    }
+    let num_input_cells = mask.iter().filter(|&&v| v).count();
+
+    for _ in 0..iterations {
+        mask = match operation {
+            MaskOperation::Grow => dilate_mask(&mask, rows, columns, radius, element),
+            MaskOperation::Shrink => erode_mask(&mask, rows, columns, radius, element),
+        };
+    }
+    let num_output_cells = mask.iter().filter(|&&v| v).count();
+
+    // Cells outside of the output mask keep their input value (0 or NoData). Cells removed from
+    // the mask take NoData when the input marks its background with NoData, and 0 otherwise.
+    let background = if has_nodata { nodata } else { 0f64 };
+    let mut output = Raster::initialize_using_file(&output_file, &input);
+    for row in 0..rows {
+        for col in 0..columns {
+            let z = input.get_value(row as isize, col as isize);
+            let value = if mask[row * columns + col] {
+                1f64
+            } else if z == nodata || z == 0f64 {
+                z
+            } else {
+                background
+            };
+            output.set_value(row as isize, col as isize, value);
+        }
+    }
+
+    let elapsed_time = get_formatted_elapsed_time(start);
+
+    if verbose {
+        println!(
+            "Mask cells: {} in the input, {} in the output.",
+            num_input_cells, num_output_cells
+        );
+    }
+
+    output.configs.display_min = 0f64;
+    output.configs.display_max = 1f64;
+    output.add_metadata_entry(format!("Created by whitebox_tools\' {} tool", tool_name));
+    output.add_metadata_entry(format!("Input file: {}", input_file));
+    output.add_metadata_entry(format!("Radius (cells): {}", radius));
+    output.add_metadata_entry(format!(
+        "Structuring element: {}",
+        match element {
+            StructuringElement::Circle => "circular",
+            StructuringElement::Square => "square",
+        }
+    ));
+    output.add_metadata_entry(format!("Iterations: {}", iterations));
+    output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+    if verbose {
+        println!("Saving data...")
+    };
+    let _ = match output.write() {
+        Ok(_) => {
+            if verbose {
+                println!("Output file written")
+            }
+        }
+        Err(e) => return Err(e),
+    };
+
+    if verbose {
+        println!(
+            "{}",
+            &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+        );
+    }
+
+    Ok(())
+}
@@ -0,0 +1,224 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::gis_analysis::mask_morphology::{
+    mask_morphology_parameters, run_mask_morphology, MaskOperation,
+};
+use crate::tools::*;
+use std::env;
+use std::io::Error;
+use std::path;
+
+/// This tool erodes (shrinks) a binary mask raster, such as a watershed mask, by a given number of grid
+/// cells, e.g. to exclude the cells along a watershed boundary from statistics. The input mask
+/// (`--input`) contains the non-zero, non-NoData cells; zero and NoData cells are background.
+///
+/// A cell remains in the mask only if every cell of its structuring element (`--element`) is in the
+/// mask; the element is either `circular` (cells whose centres are within `--radius` cells, the default)
+/// or `square` (cells within `--radius` rows and columns). Cells beyond the edges of the raster count as
+/// background, so a mask that touches the raster edge is also eroded from that edge. The operation is
+/// repeated `--iterations` times. Rather than visiting every cell of the structuring element, the tool
+/// thresholds a distance transform of the background, so its run time does not depend on the radius.
+///
+/// Mask cells are written as 1 in the output, which has the extent and cell size of the input. Cells
+/// removed from the mask are set to NoData if the input contains NoData cells and to 0 otherwise, while
+/// the other background cells keep their input value.
+///
+/// # See Also
+/// `MaskGrow`, `BufferRaster`, `EuclideanDistance`
+pub struct MaskShrink {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl MaskShrink {
+    pub fn new() -> MaskShrink {
+        // public constructor
+        let name = "MaskShrink".to_string();
+        let toolbox = "GIS Analysis/Distance Tools".to_string();
+        let description =
+            "Erodes a binary mask raster by a radius in grid cells, using a circular or square structuring element."
+                .to_string();
+
+        let parameters = mask_morphology_parameters();
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=watershed.tif -o=watershed_interior.tif --radius=3
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=watershed.tif -o=watershed_interior.tif --radius=1 --element=square --iterations=2", short_exe, name).replace("*", &sep);
+
+        MaskShrink {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for MaskShrink {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        run_mask_morphology(
+            &self.get_tool_name(),
+            MaskOperation::Shrink,
+            args,
+            working_directory,
+            verbose,
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::MaskShrink;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::{DataType, Raster};
+
+    const NODATA: f64 = -32768f64;
+
+    // Writes a 9 x 9 mask raster with 10 m cells.
+    fn write_mask(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(9, 9, f).with_nodata(NODATA).categorical();
+        let mut raster = grid_fixture_raster(file, &grid);
+        raster.configs.data_type = DataType::I16;
+        raster.write().unwrap();
+    }
+
+    #[test]
+    fn test_shrink_block_at_raster_edge() {
+        let dir = temp_dir("mask_shrink_block").unwrap();
+        let input = format!("{}/mask.tif", dir);
+        // A 6 x 7 block of mask cells against the western raster edge, with NoData background.
+        write_mask(&input, &|r, c| {
+            if (1..=6).contains(&r) && c <= 6 {
+                1f64
+            } else {
+                NODATA
+            }
+        });
+        let output = format!("{}/shrunk.tif", dir);
+        MaskShrink::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("-o={}", output),
+                    "--radius=1".to_string(),
+                    "--element=square".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        // The raster edge counts as background, so column 0 is removed along with the
+        // block's other boundary cells; removed cells become NoData.
+        let output = Raster::new(&output, "r").unwrap();
+        for r in 0..9isize {
+            for c in 0..9isize {
+                let expected = if (2..=5).contains(&r) && (1..=5).contains(&c) {
+                    1f64
+                } else {
+                    NODATA
+                };
+                assert_eq!(output.get_value(r, c), expected, "row {} col {}", r, c);
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_shrink_keeps_zero_background() {
+        let dir = temp_dir("mask_shrink_zero_background").unwrap();
+        let input = format!("{}/mask.tif", dir);
+        // A 5 x 5 block in the middle of a zero background.
+        write_mask(&input, &|r, c| {
+            if (2..=6).contains(&r) && (2..=6).contains(&c) {
+                1f64
+            } else {
+                0f64
+            }
+        });
+        let output = format!("{}/shrunk.tif", dir);
+        MaskShrink::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("-o={}", output),
+                    "--radius=2".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        // Only the centre cell has the whole circle of radius 2 inside the block.
+        let output = Raster::new(&output, "r").unwrap();
+        for r in 0..9isize {
+            for c in 0..9isize {
+                let expected = if (r, c) == (4, 4) { 1f64 } else { 0f64 };
+                assert_eq!(output.get_value(r, c), expected, "row {} col {}", r, c);
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+}
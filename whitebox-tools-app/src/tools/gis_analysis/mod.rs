@@ -45,6 +45,9 @@ mod layer_footprint;
 mod line_intersections;
 mod linearity_index;
 mod lowest_pos;
+mod mask_grow;
+mod mask_morphology;
+mod mask_shrink;
 mod max_abs_overlay;
 mod max_overlay;
 mod medoid;
@@ -140,6 +143,8 @@ pub use self::layer_footprint::LayerFootprint;
 pub use self::line_intersections::LineIntersections;
 pub use self::linearity_index::LinearityIndex;
 pub use self::lowest_pos::LowestPosition;
+pub use self::mask_grow::MaskGrow;
+pub use self::mask_shrink::MaskShrink;
 pub use self::max_abs_overlay::MaxAbsoluteOverlay;
 pub use self::max_overlay::MaxOverlay;
 pub use self::medoid::Medoid;
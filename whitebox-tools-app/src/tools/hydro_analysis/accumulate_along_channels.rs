@@ -0,0 +1,735 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::hillslopes_topaz::{read_netw, NetwLink};
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool routes a per-cell value raster (`--value`), e.g. a sediment or nutrient load, down a
+/// D8 flow pointer (`--d8_pntr`) and reports the cumulative upstream total at each cell of a stream
+/// network (`--streams`). Each stream cell carries its own value, the values of the hillslope cells
+/// that drain into it without passing through another stream cell (its lateral inputs), and the
+/// accumulated values of the stream cells flowing into it. The output raster (`--output`) holds the
+/// accumulated values on the stream cells and NoData elsewhere, so that the value of an outlet cell
+/// equals the sum of the value raster over the cells draining to it.
+///
+/// Cells are accumulated in topological order, i.e. a cell is visited once all of the cells flowing
+/// into it have been, as in `D8FlowAccumulation`. Cells with NoData pointers are excluded, and flow
+/// leaving the grid or entering a NoData pointer cell is lost. NoData cells in the value raster count
+/// as zero, and the number of such cells is reported as a warning.
+///
+/// When a channel network table (`--netw`) from `HillslopesTopaz` is supplied, a per-link summary is
+/// written to a tab-separated table (`--summary`). The cells of each link are found by following the
+/// pointer from its upstream end (`us_x`, `us_y`, the row and column written by `HillslopesTopaz`) to
+/// the cell above its downstream end (`ds_x`, `ds_y`), which is the junction that begins the next link;
+/// the outlet link (`is_outlet`) also includes its downstream end. The table has one row per link and
+/// the following columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `topaz_id` | The link's TOPAZ channel ID. |
+/// | `out_row`, `out_col` | The last cell of the link, from which it drains. |
+/// | `num_cells` | The number of stream cells in the link. |
+/// | `channel_value` | The sum of the value raster over the link's cells. |
+/// | `lateral_input` | The sum of the lateral inputs from hillslope cells into the link's cells. |
+/// | `link_load` | The value added along the link, i.e. `channel_value` plus `lateral_input`. |
+/// | `accumulated` | The accumulated value at `out_row`, `out_col`, i.e. the load leaving the link. |
+///
+/// The `link_load` of the links sum to the `accumulated` value of the outlet link when every stream
+/// cell of the network belongs to a link. By default, the pointer raster is assumed to use the
+/// clockwise indexing method used by WhiteboxTools. If the pointer file contains ESRI flow direction
+/// values instead, the `--esri_pntr` parameter must be specified.
+///
+/// # See Also
+/// `D8FlowAccumulation`, `D8MassFlux`, `HillslopesTopaz`
+pub struct AccumulateAlongChannels {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl AccumulateAlongChannels {
+    pub fn new() -> AccumulateAlongChannels {
+        // public constructor
+        let name = "AccumulateAlongChannels".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Routes a per-cell value raster down the D8 pointer, reporting the cumulative upstream total on each stream cell."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file (positive values are stream cells).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Value File".to_owned(),
+            flags: vec!["--value".to_owned()],
+            description: "Input raster of the per-cell values to accumulate, e.g. a load; NoData counts as zero.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster of the accumulated values on the stream cells.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Channel Network Table".to_owned(),
+            flags: vec!["--netw".to_owned()],
+            description: "Optional input channel network TSV file from HillslopesTopaz, used for the per-link summary.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Link Summary File".to_owned(),
+            flags: vec!["--summary".to_owned()],
+            description: "Optional output tab-separated per-link summary table; requires --netw."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --value='load.tif' -o='load_accum.tif'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --value='load.tif' -o='load_accum.tif' --netw='netw.tsv' --summary='link_loads.tsv'", short_exe, name).replace("*", &sep);
+
+        AccumulateAlongChannels {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for AccumulateAlongChannels {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut value_file = String::new();
+        let mut output_file = String::new();
+        let mut netw_file = String::new();
+        let mut summary_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-value" {
+                value_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-netw" {
+                netw_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-summary" {
+                summary_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        if netw_file.trim().is_empty() != summary_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The per-link summary requires both a channel network table (--netw) and an output summary file (--summary).",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        value_file = resolve_path(working_directory, &value_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        check_rasters_share_geometry(
+            &[
+                ("--d8_pntr", &d8_file),
+                ("--streams", &streams_file),
+                ("--value", &value_file),
+            ],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+        let values = Raster::new(&value_file, "r")?;
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+
+        let start = Instant::now();
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [i8; 129] = [-1i8; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0i8;
+            pntr_matches[2] = 1i8;
+            pntr_matches[4] = 2i8;
+            pntr_matches[8] = 3i8;
+            pntr_matches[16] = 4i8;
+            pntr_matches[32] = 5i8;
+            pntr_matches[64] = 6i8;
+            pntr_matches[128] = 7i8;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1i8;
+            pntr_matches[2] = 2i8;
+            pntr_matches[4] = 3i8;
+            pntr_matches[8] = 4i8;
+            pntr_matches[16] = 5i8;
+            pntr_matches[32] = 6i8;
+            pntr_matches[64] = 7i8;
+            pntr_matches[128] = 0i8;
+        }
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+        // The flow direction of each cell: -1 for cells without one (e.g. outlets), and -2 for
+        // NoData pointer cells, which are excluded. Values that are NoData count as zero.
+        let pntr_nodata = pntr.configs.nodata;
+        let streams_nodata = streams.configs.nodata;
+        let values_nodata = values.configs.nodata;
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -2, -2)?;
+        let mut is_stream: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+        let mut accum: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let mut num_nodata_values = 0usize;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = pntr.get_value(row, col);
+                if z == pntr_nodata {
+                    continue;
+                }
+                let dir = if z > 0f64 && z <= 128f64 {
+                    pntr_matches[z as usize]
+                } else {
+                    -1i8
+                };
+                flow_dir.set_value(row, col, dir);
+                let s = streams.get_value(row, col);
+                if s != streams_nodata && s > 0f64 {
+                    is_stream.set_value(row, col, 1u8);
+                }
+                let v = values.get_value(row, col);
+                if v == values_nodata || v.is_nan() {
+                    num_nodata_values += 1;
+                } else {
+                    accum.set_value(row, col, v);
+                }
+            }
+        }
+        let value = accum.duplicate();
+
+        // The downstream neighbour of a cell, if it flows into a cell that is not excluded.
+        let receiver = |row: isize, col: isize| -> Option<(isize, isize)> {
+            let dir = flow_dir.get_value(row, col);
+            if dir < 0 {
+                return None;
+            }
+            let (rn, cn) = (row + dy[dir as usize], col + dx[dir as usize]);
+            if flow_dir.get_value(rn, cn) == -2 {
+                return None;
+            }
+            Some((rn, cn))
+        };
+
+        let mut num_inflowing: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+        let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                if let Some((rn, cn)) = receiver(row, col) {
+                    num_inflowing.increment(rn, cn, 1);
+                }
+            }
+        }
+        for row in 0..rows {
+            for col in 0..columns {
+                if flow_dir.get_value(row, col) != -2 && num_inflowing.get_value(row, col) == 0 {
+                    queue.push_back((row, col));
+                }
+            }
+        }
+
+        // Accumulate in topological order, tallying the lateral inputs that stream cells receive
+        // directly from hillslope cells.
+        let mut lateral: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let num_cells = (rows * columns).max(1);
+        let mut num_solved = 0usize;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        while let Some((row, col)) = queue.pop_front() {
+            if let Some((rn, cn)) = receiver(row, col) {
+                let a = accum.get_value(row, col);
+                accum.increment(rn, cn, a);
+                if is_stream.get_value(rn, cn) == 1u8 && is_stream.get_value(row, col) == 0u8 {
+                    lateral.increment(rn, cn, a);
+                }
+                num_inflowing.decrement(rn, cn, 1);
+                if num_inflowing.get_value(rn, cn) == 0 {
+                    queue.push_back((rn, cn));
+                }
+            }
+            num_solved += 1;
+            if verbose {
+                progress = (100.0_f64 * num_solved as f64 / num_cells as f64) as usize;
+                if progress != old_progress {
+                    println!("Accumulating values: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &pntr);
+        output.configs.data_type = DataType::F64;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "blueyellow.plt".to_string();
+        let out_nodata = -32768f64;
+        output.configs.nodata = out_nodata;
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_stream.get_value(row, col) == 1u8 {
+                    output.set_value(row, col, accum.get_value(row, col));
+                } else {
+                    output.set_value(row, col, out_nodata);
+                }
+            }
+        }
+
+        if !netw_file.trim().is_empty() {
+            netw_file = resolve_path(working_directory, &netw_file);
+            summary_file = resolve_path(working_directory, &summary_file);
+            let links = read_netw(&netw_file)?;
+            let mut summaries = Vec::with_capacity(links.len());
+            for link in &links {
+                let cells = link_cells(link, rows, columns, &receiver)?;
+                let mut summary = LinkSummary {
+                    topaz_id: link.topaz_id,
+                    out_cell: cells[cells.len() - 1],
+                    num_cells: cells.len(),
+                    channel_value: 0f64,
+                    lateral_input: 0f64,
+                    accumulated: accum
+                        .get_value(cells[cells.len() - 1].0, cells[cells.len() - 1].1),
+                };
+                for &(row, col) in &cells {
+                    summary.channel_value += value.get_value(row, col);
+                    summary.lateral_input += lateral.get_value(row, col);
+                }
+                summaries.push(summary);
+            }
+            write_summary(&summaries, &summary_file)?;
+            if verbose {
+                println!(
+                    "Wrote the summaries of {} links to {}.",
+                    summaries.len(),
+                    summary_file
+                );
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+        output.add_metadata_entry(format!("Streams file: {}", streams_file));
+        output.add_metadata_entry(format!("Value file: {}", value_file));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if num_nodata_values > 0 {
+            println!(
+                "Warning: {} cells with NoData in the value raster (--value) were counted as zero.",
+                num_nodata_values
+            );
+        }
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The per-link totals written to the summary table.
+struct LinkSummary {
+    topaz_id: i64,
+    out_cell: (isize, isize),
+    num_cells: usize,
+    channel_value: f64,
+    lateral_input: f64,
+    accumulated: f64,
+}
+
+/// Returns the cells of a link, in flow order, by following the pointer from its upstream end
+/// to the cell above its downstream end, or to the downstream end itself for the outlet link.
+fn link_cells(
+    link: &NetwLink,
+    rows: isize,
+    columns: isize,
+    receiver: &dyn Fn(isize, isize) -> Option<(isize, isize)>,
+) -> Result<Vec<(isize, isize)>, Error> {
+    let not_followed = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Link {} of the channel network table (--netw) does not follow the D8 pointer (--d8_pntr) from ({}, {}) to ({}, {}).",
+                link.topaz_id, link.us.0, link.us.1, link.ds.0, link.ds.1
+            ),
+        )
+    };
+    let in_grid = |(row, col): (isize, isize)| row >= 0 && row < rows && col >= 0 && col < columns;
+    if !in_grid(link.us) || !in_grid(link.ds) {
+        return Err(not_followed());
+    }
+    let mut cells = vec![link.us];
+    let mut current = link.us;
+    if link.us == link.ds {
+        return Ok(cells);
+    }
+    loop {
+        let next = receiver(current.0, current.1).ok_or_else(not_followed)?;
+        if next == link.ds {
+            if link.is_outlet {
+                cells.push(next);
+            }
+            return Ok(cells);
+        }
+        cells.push(next);
+        current = next;
+        if cells.len() as isize > rows * columns {
+            return Err(not_followed());
+        }
+    }
+}
+
+fn write_summary(summaries: &[LinkSummary], file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "topaz_id\tout_row\tout_col\tnum_cells\tchannel_value\tlateral_input\tlink_load\taccumulated"
+    )?;
+    for s in summaries {
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            s.topaz_id,
+            s.out_cell.0,
+            s.out_cell.1,
+            s.num_cells,
+            s.channel_value,
+            s.lateral_input,
+            s.channel_value + s.lateral_input,
+            s.accumulated
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::AccumulateAlongChannels;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 5 x 6 grid with 10 m cells. Rows 0-1 drain south onto a channel along row 2 that flows
+    // east off the grid. A tributary in column 3 flows north into it from row 4; the cells east of
+    // the tributary in rows 3-4 drain west into it, and the rest of rows 3-4 drain north.
+    fn pointer(r: isize, c: isize) -> f64 {
+        match (r, c) {
+            (0..=1, _) => 8f64,
+            (2, _) => 2f64,
+            (_, 4..=5) => 32f64,
+            _ => 128f64,
+        }
+    }
+
+    fn is_stream(r: isize, c: isize) -> bool {
+        r == 2 || (c == 3 && r > 2)
+    }
+
+    fn write_raster(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        grid_fixture_raster(file, &GridFixture::new(5, 6, f))
+            .write()
+            .unwrap();
+    }
+
+    // The value of a cell; (0, 0) is NoData, and counts as zero.
+    fn value(r: isize, c: isize) -> f64 {
+        if (r, c) == (0, 0) {
+            -32768f64
+        } else {
+            (r * 6 + c) as f64
+        }
+    }
+
+    fn sum_of_values(f: &dyn Fn(isize, isize) -> bool) -> f64 {
+        let mut sum = 0f64;
+        for r in 0..5 {
+            for c in 0..6 {
+                if f(r, c) && (r, c) != (0, 0) {
+                    sum += value(r, c);
+                }
+            }
+        }
+        sum
+    }
+
+    #[test]
+    fn test_mass_is_conserved() {
+        let dir = temp_dir("accumulate_along_channels").unwrap();
+        let pntr = format!("{}/pntr.tif", dir);
+        let streams = format!("{}/streams.tif", dir);
+        let values = format!("{}/values.tif", dir);
+        let output = format!("{}/accum.tif", dir);
+        let netw = format!("{}/netw.tsv", dir);
+        let summary = format!("{}/summary.tsv", dir);
+        write_raster(&pntr, &pointer);
+        write_raster(&streams, &|r, c| if is_stream(r, c) { 1f64 } else { 0f64 });
+        write_raster(&values, &value);
+        // The ds_x/us_x columns hold rows, as HillslopesTopaz writes them.
+        fs::write(
+            &netw,
+            "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet\tchn_order\n\
+             0\t24\t2\t3\t2\t0\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t1\n\
+             1\t34\t2\t3\t4\t3\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t2\n\
+             2\t44\t2\t5\t2\t3\t0\t1\t-1\t0.000\t0.000\t0.000\t0.000\t2\t0.000\tfalse\ttrue\t3\n",
+        )
+        .unwrap();
+
+        AccumulateAlongChannels::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr),
+                    format!("--streams={}", streams),
+                    format!("--value={}", values),
+                    format!("--output={}", output),
+                    format!("--netw={}", netw),
+                    format!("--summary={}", summary),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+
+        // The outlet carries the sum of the value raster over the whole catchment.
+        let accum = Raster::new(&output, "r").unwrap();
+        assert_eq!(accum.get_value(2, 5), sum_of_values(&|_, _| true));
+        assert_eq!(accum.get_value(0, 1), accum.configs.nodata);
+        assert_eq!(accum.get_value(4, 5), accum.configs.nodata);
+        // The tributary collects its own cells and those draining west into it.
+        assert_eq!(
+            accum.get_value(3, 3),
+            sum_of_values(&|r, c| r > 2 && c >= 3)
+        );
+        // Column 0 of the channel receives the hillslope cells above and below it.
+        assert_eq!(accum.get_value(2, 0), sum_of_values(&|_, c| c == 0));
+
+        let table = fs::read_to_string(&summary).unwrap();
+        let rows: Vec<Vec<&str>> = table.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(
+            rows[0],
+            vec![
+                "topaz_id",
+                "out_row",
+                "out_col",
+                "num_cells",
+                "channel_value",
+                "lateral_input",
+                "link_load",
+                "accumulated"
+            ]
+        );
+        assert_eq!(rows[1][..4], ["24", "2", "2", "3"]);
+        assert_eq!(rows[2][..4], ["34", "3", "3", "2"]);
+        assert_eq!(rows[3][..4], ["44", "2", "5", "3"]);
+        let field = |row: usize, col: usize| rows[row][col].parse::<f64>().unwrap();
+        assert_eq!(field(1, 7), sum_of_values(&|_, c| c < 3));
+        assert_eq!(field(2, 5), sum_of_values(&|r, c| r > 2 && c > 3));
+        // The link loads sum to the load leaving the outlet link.
+        assert_eq!(
+            field(1, 6) + field(2, 6) + field(3, 6),
+            sum_of_values(&|_, _| true)
+        );
+        assert_eq!(field(3, 7), sum_of_values(&|_, _| true));
+    }
+
+    #[test]
+    fn test_summary_requires_netw() {
+        let err = AccumulateAlongChannels::new()
+            .run(
+                vec![
+                    "--d8_pntr=pntr.tif".to_string(),
+                    "--streams=streams.tif".to_string(),
+                    "--value=values.tif".to_string(),
+                    "--output=accum.tif".to_string(),
+                    "--summary=summary.tsv".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("--netw"));
+    }
+}
@@ -0,0 +1,800 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::pour_point_locator::locate_pour_point;
+use crate::tools::*;
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value as GeoValue};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool summarizes the basin draining to a gauged outlet with the attributes commonly used by
+/// regional regression equations, in a single pass and without writing intermediate rasters. The
+/// outlet is given by `--pour_pts` (a point Shapefile or GeoJSON file, or a raster in which the outlet
+/// cell has a positive value), which must contain exactly one point lying on a stream cell of the
+/// `--streams` raster. The basin is delineated in memory by following the D8 pointer (`--d8_pntr`)
+/// upslope from the outlet, and the following attributes are calculated from the DEM (`--dem`):
+///
+/// | Attribute | Description |
+/// |-----------|-------------|
+/// | `num_cells`, `drainage_area` | The number of cells in the basin and its area, in squared map units. |
+/// | `mean_elevation`, `min_elevation`, `max_elevation`, `relief` | Basin elevation statistics; the relief is the maximum less the minimum elevation. |
+/// | `mean_slope` | The mean slope gradient (rise over run) of the basin cells, calculated with Horn's (1981) method. |
+/// | `main_channel_length`, `main_channel_slope` | The longest flow path along stream cells to the outlet, and its elevation drop divided by its length. |
+/// | `longest_flow_path_length`, `longest_flow_path_slope` | The same for the longest flow path from any basin cell, which usually starts on the basin divide. |
+///
+/// Lengths are measured along the D8 flow paths, between cell centres. The slopes are `null` for
+/// paths of zero length, e.g. when the outlet is a channel head. DEM NoData cells are counted in the
+/// drainage area but are excluded from the elevation and slope statistics; NoData neighbours in the
+/// slope calculation are replaced by the elevation of the centre cell. The rasters must be in a
+/// projected coordinate system with elevations in the same units as the map units.
+///
+/// The attributes are written as the properties of a single point feature at the outlet cell centre,
+/// in a GeoJSON file (`--output`), and/or to a JSON report (`--report`) that also lists the input
+/// files; at least one of the two must be specified. The basin can optionally be saved as a raster
+/// (`--save_watershed`), with a value of 1 in the basin and NoData elsewhere.
+///
+/// By default, the pointer raster is assumed to use the clockwise indexing method used by
+/// WhiteboxTools. If the pointer file contains ESRI flow direction values instead, the `--esri_pntr`
+/// parameter must be specified.
+///
+/// # Reference
+/// Horn B.K.P., 1981. Hill shading and the reflectance map. Proceedings of the IEEE 69(1), 14-47.
+///
+/// # See Also
+/// `Watershed`, `LongestFlowpath`, `Slope`, `FindOutlet`, `WatershedAreaReport`
+pub struct BasinOutletHydraulicGeometry {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl BasinOutletHydraulicGeometry {
+    pub fn new() -> BasinOutletHydraulicGeometry {
+        // public constructor
+        let name = "BasinOutletHydraulicGeometry".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Calculates the drainage area, relief, slope, and main channel attributes of the basin draining to an outlet."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input DEM raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file; stream cells have positive values.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Pour Point (Outlet) File".to_owned(),
+            flags: vec!["--pour_pts".to_owned()],
+            description: "Input pour point (outlet) file, containing a single point.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output GeoJSON File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output GeoJSON file of the outlet point with the basin attributes."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["--report".to_owned()],
+            description: "Output JSON report of the basin attributes.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Watershed File".to_owned(),
+            flags: vec!["--save_watershed".to_owned()],
+            description: "Optional output raster of the delineated basin.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --dem='dem.tif' --streams='streams.tif' --pour_pts='gauge.geojson' -o='basin_outlet.geojson' --report='basin_outlet.json'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --dem='dem.tif' --streams='streams.tif' --pour_pts='gauge.shp' --report='basin_outlet.json' --save_watershed='basin.tif'", short_exe, name).replace("*", &sep);
+
+        BasinOutletHydraulicGeometry {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for BasinOutletHydraulicGeometry {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut pourpts_file = String::new();
+        let mut output_file = String::new();
+        let mut report_file = String::new();
+        let mut watershed_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-pour_pts" {
+                pourpts_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-report" {
+                report_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-save_watershed" {
+                watershed_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if output_file.trim().is_empty() && report_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either an output GeoJSON file (--output) or a JSON report (--report) must be specified.",
+            ));
+        }
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        dem_file = resolve_path(working_directory, &dem_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        pourpts_file = resolve_path(working_directory, &pourpts_file);
+        output_file = resolve_path(working_directory, &output_file);
+        report_file = resolve_path(working_directory, &report_file);
+        watershed_file = resolve_path(working_directory, &watershed_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let pntr_nodata = pntr.configs.nodata;
+
+        let open_matching = |file: &str, flag: &str| -> Result<Raster, Error> {
+            let raster = Raster::new(file, "r")?;
+            if raster.configs.rows != pntr.configs.rows
+                || raster.configs.columns != pntr.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The {} raster must have the same rows and columns as the D8 pointer.",
+                        flag
+                    ),
+                ));
+            }
+            Ok(raster)
+        };
+        let dem = open_matching(&dem_file, "--dem")?;
+        let streams = open_matching(&streams_file, "--streams")?;
+        let (outlet_row, outlet_col) = locate_pour_point(&pourpts_file, &pntr)?;
+
+        let start = Instant::now();
+
+        if outlet_row < 0 || outlet_row >= rows || outlet_col < 0 || outlet_col >= columns {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The pour point lies outside of the D8 pointer raster.",
+            ));
+        }
+        let streams_nodata = streams.configs.nodata;
+        let is_stream = |row: isize, col: isize| {
+            let s = streams.get_value(row, col);
+            s != streams_nodata && s > 0f64
+        };
+        if !is_stream(outlet_row, outlet_col) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The pour point (row {}, column {}) is not on a stream cell.",
+                    outlet_row, outlet_col
+                ),
+            ));
+        }
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [usize; 129] = [999usize; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0usize;
+            pntr_matches[2] = 1usize;
+            pntr_matches[4] = 2usize;
+            pntr_matches[8] = 3usize;
+            pntr_matches[16] = 4usize;
+            pntr_matches[32] = 5usize;
+            pntr_matches[64] = 6usize;
+            pntr_matches[128] = 7usize;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1usize;
+            pntr_matches[2] = 2usize;
+            pntr_matches[4] = 3usize;
+            pntr_matches[8] = 4usize;
+            pntr_matches[16] = 5usize;
+            pntr_matches[32] = 6usize;
+            pntr_matches[64] = 7usize;
+            pntr_matches[128] = 0usize;
+        }
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let res_x = pntr.configs.resolution_x;
+        let res_y = pntr.configs.resolution_y;
+        let diag = (res_x * res_x + res_y * res_y).sqrt();
+        let step_length = [diag, res_x, diag, res_y, diag, res_x, diag, res_y];
+        let flow_dir = |row: isize, col: isize| -> Option<usize> {
+            let z = pntr.get_value(row, col);
+            if z == pntr_nodata || z <= 0f64 || z > 128f64 {
+                return None;
+            }
+            let dir = pntr_matches[z as usize];
+            if dir > 7 {
+                None
+            } else {
+                Some(dir)
+            }
+        };
+
+        // Walk upslope from the outlet. The flow distance of each basin cell is that of the cell it
+        // drains into plus the step between them; the channel distance is only defined for stream
+        // cells whose flow path stays on the stream network all the way to the outlet.
+        let mut basin: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+        let mut flow_dist: Array2D<f64> = Array2D::new(rows, columns, -1f64, -1f64)?;
+        let mut channel_dist: Array2D<f64> = Array2D::new(rows, columns, -1f64, -1f64)?;
+        basin.set_value(outlet_row, outlet_col, 1u8);
+        flow_dist.set_value(outlet_row, outlet_col, 0f64);
+        channel_dist.set_value(outlet_row, outlet_col, 0f64);
+        let mut queue = VecDeque::new();
+        queue.push_back((outlet_row, outlet_col));
+        let mut num_cells = 0usize;
+        while let Some((row, col)) = queue.pop_front() {
+            num_cells += 1;
+            for n in 0..8 {
+                let (row_n, col_n) = (row + dy[n], col + dx[n]);
+                if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
+                    continue;
+                }
+                if basin.get_value(row_n, col_n) == 1u8 {
+                    continue;
+                }
+                if let Some(dir) = flow_dir(row_n, col_n) {
+                    if row_n + dy[dir] == row && col_n + dx[dir] == col {
+                        basin.set_value(row_n, col_n, 1u8);
+                        let dist = flow_dist.get_value(row, col) + step_length[dir];
+                        flow_dist.set_value(row_n, col_n, dist);
+                        if channel_dist.get_value(row, col) >= 0f64 && is_stream(row_n, col_n) {
+                            let dist = channel_dist.get_value(row, col) + step_length[dir];
+                            channel_dist.set_value(row_n, col_n, dist);
+                        }
+                        queue.push_back((row_n, col_n));
+                    }
+                }
+            }
+        }
+
+        if verbose {
+            println!("Delineated a basin of {} cells.", num_cells);
+        }
+
+        let dem_nodata = dem.configs.nodata;
+        let elev = |row: isize, col: isize| -> Option<f64> {
+            if row < 0 || row >= rows || col < 0 || col >= columns {
+                return None;
+            }
+            let z = dem.get_value(row, col);
+            if z == dem_nodata {
+                None
+            } else {
+                Some(z)
+            }
+        };
+
+        let mut num_valid = 0usize;
+        let mut sum_elev = 0f64;
+        let mut min_elev = f64::INFINITY;
+        let mut max_elev = f64::NEG_INFINITY;
+        let mut sum_slope = 0f64;
+        let mut longest_flow_path = (-1f64, outlet_row, outlet_col);
+        let mut main_channel = (-1f64, outlet_row, outlet_col);
+        // Horn's neighbours, in the order NE, E, SE, S, SW, W, NW, N
+        let mut n = [0f64; 8];
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                if basin.get_value(row, col) != 1u8 {
+                    continue;
+                }
+                let dist = flow_dist.get_value(row, col);
+                if dist > longest_flow_path.0 {
+                    longest_flow_path = (dist, row, col);
+                }
+                let dist = channel_dist.get_value(row, col);
+                if dist > main_channel.0 {
+                    main_channel = (dist, row, col);
+                }
+                if let Some(z) = elev(row, col) {
+                    num_valid += 1;
+                    sum_elev += z;
+                    min_elev = min_elev.min(z);
+                    max_elev = max_elev.max(z);
+                    for i in 0..8 {
+                        n[i] = elev(row + dy[i], col + dx[i]).unwrap_or(z);
+                    }
+                    let dz_dx =
+                        (n[0] + 2f64 * n[1] + n[2] - n[6] - 2f64 * n[5] - n[4]) / (8f64 * res_x);
+                    let dz_dy =
+                        (n[6] + 2f64 * n[7] + n[0] - n[4] - 2f64 * n[3] - n[2]) / (8f64 * res_y);
+                    sum_slope += (dz_dx * dz_dx + dz_dy * dz_dy).sqrt();
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Calculating basin attributes: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_valid == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The DEM has no valid elevations within the basin.",
+            ));
+        }
+
+        // The slope of a flow path from its start to the outlet, or None for a zero-length path.
+        let path_slope = |(length, row, col): (f64, isize, isize)| -> Option<f64> {
+            match (elev(row, col), elev(outlet_row, outlet_col)) {
+                (Some(z_start), Some(z_outlet)) if length > 0f64 => {
+                    Some((z_start - z_outlet) / length)
+                }
+                _ => None,
+            }
+        };
+
+        let epsg_code = pntr.configs.epsg_code;
+        let easting = pntr.get_x_from_column(outlet_col);
+        let northing = pntr.get_y_from_row(outlet_row);
+        let mut attributes: JsonMap<String, JsonValue> = JsonMap::new();
+        attributes.insert("num_cells".to_string(), json!(num_cells));
+        attributes.insert(
+            "drainage_area".to_string(),
+            json!(num_cells as f64 * res_x * res_y),
+        );
+        attributes.insert(
+            "mean_elevation".to_string(),
+            json!(sum_elev / num_valid as f64),
+        );
+        attributes.insert("min_elevation".to_string(), json!(min_elev));
+        attributes.insert("max_elevation".to_string(), json!(max_elev));
+        attributes.insert("relief".to_string(), json!(max_elev - min_elev));
+        attributes.insert(
+            "mean_slope".to_string(),
+            json!(sum_slope / num_valid as f64),
+        );
+        attributes.insert("main_channel_length".to_string(), json!(main_channel.0));
+        attributes.insert(
+            "main_channel_slope".to_string(),
+            json!(path_slope(main_channel)),
+        );
+        attributes.insert(
+            "longest_flow_path_length".to_string(),
+            json!(longest_flow_path.0),
+        );
+        attributes.insert(
+            "longest_flow_path_slope".to_string(),
+            json!(path_slope(longest_flow_path)),
+        );
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if !output_file.is_empty() {
+            let mut properties: JsonMap<String, JsonValue> = JsonMap::new();
+            properties.insert("Id".to_string(), json!(1));
+            properties.insert("row".to_string(), json!(outlet_row));
+            properties.insert("column".to_string(), json!(outlet_col));
+            properties.insert("easting".to_string(), json!(easting));
+            properties.insert("northing".to_string(), json!(northing));
+            properties.insert("epsg".to_string(), json!(epsg_code));
+            properties.extend(attributes.clone());
+            let feature = Feature {
+                bbox: None,
+                geometry: Some(Geometry::new(GeoValue::Point(vec![easting, northing]))),
+                id: None,
+                properties: Some(properties),
+                foreign_members: None,
+            };
+
+            let mut foreign_members: Option<JsonMap<String, JsonValue>> = None;
+            if epsg_code != 0 {
+                let mut crs_map = JsonMap::new();
+                crs_map.insert("type".to_string(), json!("name"));
+                crs_map.insert(
+                    "properties".to_string(),
+                    json!({"name": format!("urn:ogc:def:crs:EPSG::{}", epsg_code)}),
+                );
+                let mut members = JsonMap::new();
+                members.insert("crs".to_string(), JsonValue::Object(crs_map));
+                foreign_members = Some(members);
+            }
+
+            let feature_collection = FeatureCollection {
+                bbox: None,
+                features: vec![feature],
+                foreign_members,
+            };
+            if verbose {
+                println!("Writing {}.", output_file);
+            }
+            let geojson = GeoJson::FeatureCollection(feature_collection).to_string();
+            let mut file = File::create(&output_file)?;
+            file.write_all(geojson.as_bytes())?;
+            file.sync_all()?;
+        }
+
+        if !report_file.is_empty() {
+            let report = json!({
+                "tool": self.get_tool_name(),
+                "inputs": {
+                    "d8_pntr": d8_file,
+                    "dem": dem_file,
+                    "streams": streams_file,
+                    "pour_pts": pourpts_file,
+                },
+                "outlet": {
+                    "row": outlet_row,
+                    "column": outlet_col,
+                    "easting": easting,
+                    "northing": northing,
+                    "epsg": epsg_code,
+                },
+                "attributes": attributes,
+            });
+            if verbose {
+                println!("Writing {}.", report_file);
+            }
+            let text = serde_json::to_string_pretty(&report).map_err(Error::other)?;
+            let mut file = File::create(&report_file)?;
+            file.write_all(text.as_bytes())?;
+            file.sync_all()?;
+        }
+
+        if !watershed_file.is_empty() {
+            let mut output = Raster::initialize_using_file(&watershed_file, &pntr);
+            output.configs.data_type = DataType::I16;
+            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            output.configs.palette = "qual.plt".to_string();
+            output.configs.nodata = -32768f64;
+            output.reinitialize_values(-32768f64);
+            for row in 0..rows {
+                for col in 0..columns {
+                    if basin.get_value(row, col) == 1u8 {
+                        output.set_value(row, col, 1f64);
+                    }
+                }
+            }
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+            output.add_metadata_entry(format!("Pour point file: {}", pourpts_file));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            if verbose {
+                println!("Writing {}.", watershed_file);
+            }
+            output.write()?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::BasinOutletHydraulicGeometry;
+    use crate::tools::WhiteboxTool;
+    use serde_json::Value;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 5 x 5 basin whose outer columns drain sideways into a channel down the centre column,
+    // which drains south to an outlet at (4, 2). Elevations fall by 1 per row and rise by 1 per
+    // column away from the channel.
+    fn write_inputs(dir: &str) {
+        let grids: [(&str, &dyn Fn(isize, isize) -> f64); 4] = [
+            ("dem", &|r, c| 100f64 - r as f64 + (c - 2).abs() as f64),
+            ("d8", &|_, c| match c {
+                0 | 1 => 2f64,
+                2 => 8f64,
+                _ => 32f64,
+            }),
+            ("streams", &|_, c| if c == 2 { 1f64 } else { 0f64 }),
+            ("outlet", &|r, c| if (r, c) == (4, 2) { 1f64 } else { 0f64 }),
+        ];
+        for (name, f) in grids.iter() {
+            let grid = GridFixture::new(5, 5, f).with_origin(1000f64, 450f64);
+            grid_fixture_raster(&format!("{}/{}.tif", dir, name), &grid)
+                .write()
+                .unwrap();
+        }
+    }
+
+    fn tool_args(dir: &str, pour_pts: &str) -> Vec<String> {
+        vec![
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--dem={}/dem.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+            format!("--pour_pts={}/{}", dir, pour_pts),
+        ]
+    }
+
+    fn read_json(file: &str) -> Value {
+        serde_json::from_str(&fs::read_to_string(file).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_basin_attributes() {
+        let dir = temp_dir("basin_outlet_hydraulic_geometry").unwrap();
+        write_inputs(&dir);
+
+        let mut args = tool_args(&dir, "outlet.tif");
+        args.push(format!("--output={}/outlet.geojson", dir));
+        args.push(format!("--report={}/report.json", dir));
+        BasinOutletHydraulicGeometry::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let geojson = read_json(&format!("{}/outlet.geojson", dir));
+        let features = geojson["features"].as_array().unwrap();
+        assert_eq!(features.len(), 1);
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([1025.0, 455.0])
+        );
+        let props = &features[0]["properties"];
+        assert_eq!(
+            (&props["row"], &props["column"]),
+            (&Value::from(4), &Value::from(2))
+        );
+        assert_eq!(props["num_cells"], 25);
+        assert_eq!(props["drainage_area"], 2500.0);
+        assert!((props["mean_elevation"].as_f64().unwrap() - 99.2).abs() < 1e-9);
+        assert_eq!(props["min_elevation"], 96.0);
+        assert_eq!(props["max_elevation"], 102.0);
+        assert_eq!(props["relief"], 6.0);
+        assert!(props["mean_slope"].as_f64().unwrap() > 0.0);
+        // from the channel head at (0, 2), and from the divide corner at (0, 0)
+        assert_eq!(props["main_channel_length"], 40.0);
+        assert_eq!(props["main_channel_slope"], 0.1);
+        assert_eq!(props["longest_flow_path_length"], 60.0);
+        assert_eq!(props["longest_flow_path_slope"], 0.1);
+
+        let report = read_json(&format!("{}/report.json", dir));
+        assert_eq!(report["tool"], "BasinOutletHydraulicGeometry");
+        assert_eq!(report["outlet"]["row"], 4);
+        for (key, value) in report["attributes"].as_object().unwrap() {
+            assert_eq!(&props[key], value);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // A GeoJSON pour point at the centre of (2, 2) drains the top three rows.
+    #[test]
+    fn test_interior_outlet_and_saved_watershed() {
+        let dir = temp_dir("basin_outlet_hydraulic_geometry_interior").unwrap();
+        write_inputs(&dir);
+        fs::write(
+            format!("{}/gauge.geojson", dir),
+            r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{},"geometry":{"type":"Point","coordinates":[1025.0,475.0]}}]}"#,
+        )
+        .unwrap();
+
+        let mut args = tool_args(&dir, "gauge.geojson");
+        args.push(format!("--report={}/report.json", dir));
+        args.push(format!("--save_watershed={}/basin.tif", dir));
+        BasinOutletHydraulicGeometry::new()
+            .run(args, "", false)
+            .unwrap();
+        let attributes = &read_json(&format!("{}/report.json", dir))["attributes"];
+        assert_eq!(attributes["num_cells"], 15);
+        assert_eq!(attributes["main_channel_length"], 20.0);
+        assert_eq!(attributes["longest_flow_path_length"], 40.0);
+
+        let basin = Raster::new(&format!("{}/basin.tif", dir), "r").unwrap();
+        for r in 0..5isize {
+            for c in 0..5isize {
+                let expected = if r <= 2 { 1f64 } else { basin.configs.nodata };
+                assert_eq!(basin.get_value(r, c), expected);
+            }
+        }
+
+        // A pour point off the stream network, and a run without any output, are errors.
+        fs::write(
+            format!("{}/gauge.geojson", dir),
+            r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{},"geometry":{"type":"Point","coordinates":[1005.0,475.0]}}]}"#,
+        )
+        .unwrap();
+        let mut args = tool_args(&dir, "gauge.geojson");
+        args.push(format!("--report={}/report.json", dir));
+        let err = BasinOutletHydraulicGeometry::new()
+            .run(args, "", false)
+            .unwrap_err();
+        assert!(err.to_string().contains("not on a stream cell"));
+        assert!(BasinOutletHydraulicGeometry::new()
+            .run(tool_args(&dir, "outlet.tif"), "", false)
+            .is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,1093 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use geojson::{GeoJson, Geometry, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool delineates the catchments draining to many gauges in a single pass over a D8 flow pointer
+/// raster (`--d8_pntr`). It is intended for batches of hundreds of gauges, where running the `Watershed`
+/// tool once per gauge would re-read the pointer raster and write a full-extent raster for every gauge.
+/// The gauges (`--pour_pts`) are read from a Shapefile or a GeoJSON (`.geojson`/`.json`) file of points,
+/// and should already be snapped onto the stream network, e.g. using `JensonSnapPourPoints`. Each gauge is
+/// identified by the value of its `--id_field` attribute (a Shapefile field or GeoJSON feature property);
+/// when no field is given, gauges are identified by their 1-based record number. Gauge ids must be unique.
+///
+/// The upslope area of each gauge is found by a breadth-first search along the inverse of the flow
+/// pointer, starting from the gauge's cell. Outputs may be written in either or both of two forms:
+///
+/// * a single labeled raster (`--output`), covering the full extent of the pointer raster; and
+/// * one raster per gauge in an output directory (`--output_dir`), named after the gauge id and cropped
+///   to the bounding box of the gauge's catchment plus a one-cell border. Catchment cells have a value of 1
+///   and all other cells are NoData.
+///
+/// **Nested gauges.** When one gauge lies upslope of another, the catchment of the upstream gauge is
+/// nested within that of the downstream gauge. The per-gauge rasters and the reported cell counts and
+/// areas always contain each gauge's complete catchment, nested catchments included. Because a cell of
+/// the labeled raster can hold only one label, `--nested` sets how cells shared by nested catchments are
+/// labeled:
+///
+/// * `innermost` (the default) gives each cell to the nearest gauge downslope of it, so that a nested
+///   catchment keeps its own label and the outer catchment is labeled with the remaining area only. This
+///   is the labeling produced by the `Watershed` tool.
+/// * `last_wins` labels a cell with the gauge that occurs last in the points file among all of the gauges
+///   whose catchments contain the cell, as if the catchments were drawn in file order. Ordering the points
+///   file therefore sets the priority of the gauges; e.g. sorting by increasing area reproduces
+///   `innermost`, while placing an outlet gauge last labels its entire catchment with the outlet's id.
+///
+/// Labels are the gauge ids when every id is an integer, and the 1-based record numbers otherwise.
+/// Two gauges in the same cell share the same catchment; the gauge occurring later in the file is treated
+/// as nested within the earlier one.
+///
+/// The optional report (`--report`) is a tab-separated table with one row per gauge, in file order, and
+/// the following columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `gauge_id` | The gauge id. |
+/// | `label` | The gauge's value in the labeled raster. |
+/// | `row`, `column` | The grid cell containing the gauge. |
+/// | `num_cells` | Number of grid cells in the gauge's complete catchment. |
+/// | `area` | Catchment area, in squared map units. |
+/// | `labeled_cells` | Number of cells carrying the gauge's label in the labeled raster. |
+/// | `nested_in` | Id of the nearest downslope gauge whose catchment contains this one, or `NA`. |
+/// | `contains` | Comma-separated ids of the gauges nested directly within this one, or `NA`. |
+/// | `status` | `ok`, `off_grid` for gauges outside of the raster, or `nodata` for gauges on NoData pointer cells. |
+///
+/// Gauges that are not `ok` have empty catchments. By default, the pointer raster is assumed to use the
+/// clockwise indexing method used by WhiteboxTools. If the pointer file contains ESRI flow direction values
+/// instead, the `--esri_pntr` parameter must be specified.
+///
+/// # See Also
+/// `Watershed`, `JensonSnapPourPoints`, `WatershedAreaReport`, `D8Pointer`
+pub struct CatchmentDelineationBatch {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl CatchmentDelineationBatch {
+    pub fn new() -> CatchmentDelineationBatch {
+        // public constructor
+        let name = "CatchmentDelineationBatch".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Delineates the catchments of a batch of gauge points against a single D8 pointer raster, handling nested gauges."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Gauge Points File".to_owned(),
+            flags: vec!["--pour_pts".to_owned()],
+            description: "Input snapped gauge points file (Shapefile or GeoJSON).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Gauge ID Field".to_owned(),
+            flags: vec!["--id_field".to_owned()],
+            description:
+                "Attribute field holding the gauge ids; record numbers are used if unspecified."
+                    .to_owned(),
+            parameter_type: ParameterType::VectorAttributeField(
+                AttributeType::Any,
+                "--pour_pts".to_string(),
+            ),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Labeled Raster File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output labeled catchments raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory".to_owned(),
+            flags: vec!["--output_dir".to_owned()],
+            description: "Output directory for per-gauge cropped catchment rasters.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Nested Catchment Labeling".to_owned(),
+            flags: vec!["--nested".to_owned()],
+            description: "Labeling of cells shared by nested catchments; options are 'innermost' and 'last_wins'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "innermost".to_owned(),
+                "last_wins".to_owned(),
+            ]),
+            default_value: Some("innermost".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["--report".to_owned()],
+            description: "Output tab-separated report of gauge catchment sizes and nesting."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --pour_pts='gauges.shp' --id_field=SITE_ID -o='catchments.tif' --report='catchments.tsv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --pour_pts='gauges.geojson' --id_field=SITE_ID --output_dir='catchments' --nested=last_wins", short_exe, name).replace("*", &sep);
+
+        CatchmentDelineationBatch {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for CatchmentDelineationBatch {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut pourpts_file = String::new();
+        let mut id_field = String::new();
+        let mut output_file = String::new();
+        let mut output_dir = String::new();
+        let mut nested = String::from("innermost");
+        let mut report_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-pour_pts" {
+                pourpts_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-id_field" {
+                id_field = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_dir" {
+                output_dir = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-nested" {
+                nested = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+                .to_lowercase();
+            } else if flag_val == "-report" {
+                report_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let last_wins = match nested.trim() {
+            "innermost" => false,
+            "last_wins" => true,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The --nested parameter must be either 'innermost' or 'last_wins'.",
+                ))
+            }
+        };
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        pourpts_file = resolve_path(working_directory, &pourpts_file);
+        output_file = resolve_path(working_directory, &output_file);
+        output_dir = resolve_path(working_directory, &output_dir);
+        report_file = resolve_path(working_directory, &report_file);
+
+        if output_file.is_empty() && output_dir.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either an output raster (--output) or an output directory (--output_dir) must be specified.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let mut gauges = read_gauges(&pourpts_file, &id_field)?;
+        if gauges.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The gauge points file does not contain any points.",
+            ));
+        }
+        let mut unique_ids = HashSet::new();
+        for g in &gauges {
+            if !unique_ids.insert(g.id.clone()) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Gauge ids must be unique; '{}' occurs more than once.",
+                        g.id
+                    ),
+                ));
+            }
+        }
+
+        let start = Instant::now();
+
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let nodata = -32768f64;
+        let pntr_nodata = pntr.configs.nodata;
+        let num_gauges = gauges.len();
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [i8; 129] = [0i8; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0i8;
+            pntr_matches[2] = 1i8;
+            pntr_matches[4] = 2i8;
+            pntr_matches[8] = 3i8;
+            pntr_matches[16] = 4i8;
+            pntr_matches[32] = 5i8;
+            pntr_matches[64] = 6i8;
+            pntr_matches[128] = 7i8;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1i8;
+            pntr_matches[2] = 2i8;
+            pntr_matches[4] = 3i8;
+            pntr_matches[8] = 4i8;
+            pntr_matches[16] = 5i8;
+            pntr_matches[32] = 6i8;
+            pntr_matches[64] = 7i8;
+            pntr_matches[128] = 0i8;
+        }
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+        // -2 marks NoData, -1 a cell without a downslope neighbour.
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -2, -2)?;
+        let mut z: f64;
+        for row in 0..rows {
+            for col in 0..columns {
+                z = pntr.get_value(row, col);
+                if z != pntr_nodata {
+                    if z > 0.0 && z <= 128.0 {
+                        flow_dir.set_value(row, col, pntr_matches[z as usize]);
+                    } else {
+                        flow_dir.set_value(row, col, -1i8);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Initializing: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Locate the gauges. The gauges in each cell are listed in file order.
+        let mut cell_gauges: HashMap<(isize, isize), Vec<usize>> = HashMap::new();
+        for (i, g) in gauges.iter_mut().enumerate() {
+            g.row = pntr.get_row_from_y(g.y);
+            g.col = pntr.get_column_from_x(g.x);
+            if g.row < 0 || g.row >= rows || g.col < 0 || g.col >= columns {
+                g.status = GaugeStatus::OffGrid;
+            } else if flow_dir.get_value(g.row, g.col) == -2 {
+                g.status = GaugeStatus::NoData;
+            } else {
+                cell_gauges.entry((g.row, g.col)).or_insert(vec![]).push(i);
+            }
+        }
+
+        // Find the gauge each gauge is nested directly within: an earlier gauge in the same cell, or
+        // else the last-listed gauge in the first gauged cell downslope.
+        let max_steps = (rows * columns) as usize;
+        for i in 0..num_gauges {
+            if gauges[i].status != GaugeStatus::Ok {
+                continue;
+            }
+            let (row, col) = (gauges[i].row, gauges[i].col);
+            let in_cell = &cell_gauges[&(row, col)];
+            let pos = in_cell.iter().position(|&j| j == i).unwrap();
+            if pos > 0 {
+                gauges[i].parent = Some(in_cell[pos - 1]);
+                continue;
+            }
+            let (mut x, mut y) = (col, row);
+            for _ in 0..max_steps {
+                let dir = flow_dir.get_value(y, x);
+                if dir < 0 {
+                    break;
+                }
+                x += dx[dir as usize];
+                y += dy[dir as usize];
+                if flow_dir.get_value(y, x) == -2 {
+                    break; // off the grid or onto NoData
+                }
+                if let Some(downslope) = cell_gauges.get(&(y, x)) {
+                    gauges[i].parent = Some(*downslope.last().unwrap());
+                    break;
+                }
+            }
+        }
+        for i in 0..num_gauges {
+            if let Some(p) = gauges[i].parent {
+                gauges[p].children.push(i);
+            }
+        }
+
+        // Assign each upslope cell to its innermost gauge, i.e. the nearest gauge downslope of it.
+        let mut owner: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+        let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+        let mut num_processed = 0usize;
+        for (&(row, col), in_cell) in &cell_gauges {
+            let innermost = *in_cell.last().unwrap();
+            owner.set_value(row, col, innermost as i32);
+            gauges[innermost].own_cells(row, col);
+            queue.push_back((row, col));
+            while let Some((r, c)) = queue.pop_front() {
+                for n in 0..8 {
+                    let (rn, cn) = (r + dy[n], c + dx[n]);
+                    // does the neighbour flow into this cell?
+                    if flow_dir.get_value(rn, cn) != ((n + 4) % 8) as i8 {
+                        continue;
+                    }
+                    if owner.get_value(rn, cn) != -1 || cell_gauges.contains_key(&(rn, cn)) {
+                        continue; // a gauged cell seeds its own search
+                    }
+                    owner.set_value(rn, cn, innermost as i32);
+                    gauges[innermost].own_cells(rn, cn);
+                    queue.push_back((rn, cn));
+                }
+            }
+            if verbose {
+                num_processed += 1;
+                progress = (100.0_f64 * num_processed as f64 / cell_gauges.len() as f64) as usize;
+                if progress != old_progress {
+                    println!("Delineating catchments: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Accumulate the exclusive cells of nested gauges into the complete catchments, innermost first.
+        let depth = |i: usize| {
+            let mut d = 0usize;
+            let mut g = gauges[i].parent;
+            while let Some(p) = g {
+                d += 1;
+                g = gauges[p].parent;
+            }
+            d
+        };
+        let mut order: Vec<usize> = (0..num_gauges).collect();
+        let depths: Vec<usize> = (0..num_gauges).map(depth).collect();
+        order.sort_by(|a, b| depths[*b].cmp(&depths[*a]));
+        for g in gauges.iter_mut() {
+            g.num_cells = g.num_own_cells;
+            g.extent = g.own_extent;
+        }
+        for &i in &order {
+            if let Some(p) = gauges[i].parent {
+                let (n, extent) = (gauges[i].num_cells, gauges[i].extent);
+                gauges[p].num_cells += n;
+                gauges[p].extent = union_extent(gauges[p].extent, extent);
+            }
+        }
+
+        // The gauge whose label is given to the cells owned by each gauge.
+        let label_gauge: Vec<usize> = (0..num_gauges)
+            .map(|i| {
+                let mut selected = i;
+                if last_wins {
+                    let mut g = gauges[i].parent;
+                    while let Some(p) = g {
+                        selected = selected.max(p);
+                        g = gauges[p].parent;
+                    }
+                }
+                selected
+            })
+            .collect();
+        for i in 0..num_gauges {
+            let n = gauges[i].num_own_cells;
+            gauges[label_gauge[i]].labeled_cells += n;
+        }
+        let numeric_ids: Option<Vec<f64>> = gauges
+            .iter()
+            .map(|g| g.id.trim().parse::<i32>().ok().map(|v| v as f64))
+            .collect();
+        let labels: Vec<f64> = match numeric_ids {
+            Some(ids) if ids.iter().all(|&v| v != nodata) => ids,
+            _ => (1..=num_gauges).map(|v| v as f64).collect(),
+        };
+
+        let num_nested = gauges.iter().filter(|g| g.parent.is_some()).count();
+        let num_not_located = gauges
+            .iter()
+            .filter(|g| g.status != GaugeStatus::Ok)
+            .count();
+        if verbose {
+            println!(
+                "{} of {} gauges are nested within the catchment of another gauge.",
+                num_nested, num_gauges
+            );
+            if num_not_located > 0 {
+                println!(
+                    "WARNING: {} gauges lie outside of the pointer raster or on NoData cells.",
+                    num_not_located
+                );
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if !output_file.is_empty() {
+            let mut output = Raster::initialize_using_file(&output_file, &pntr);
+            output.configs.nodata = nodata;
+            output.configs.data_type = DataType::I32;
+            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            output.configs.palette = "qual.pal".to_string();
+            output.reinitialize_values(nodata);
+            for row in 0..rows {
+                for col in 0..columns {
+                    let i = owner.get_value(row, col);
+                    if i >= 0 {
+                        output.set_value(row, col, labels[label_gauge[i as usize]]);
+                    }
+                }
+            }
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+            output.add_metadata_entry(format!("Gauge points file: {}", pourpts_file));
+            output.add_metadata_entry(format!("Nested catchment labeling: {}", nested.trim()));
+            output.add_metadata_entry(format!("Nested gauges: {}", num_nested));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose {
+                println!("Saving data...")
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if !output_dir.is_empty() {
+            fs::create_dir_all(&output_dir)?;
+            let res_x = pntr.configs.resolution_x;
+            let res_y = pntr.configs.resolution_y;
+            for i in 0..num_gauges {
+                if gauges[i].num_cells == 0 {
+                    continue;
+                }
+                // Crop to the catchment plus a one-cell NoData border, within the grid.
+                let (min_row, min_col, max_row, max_col) = gauges[i].extent;
+                let (min_row, min_col) = ((min_row - 1).max(0), (min_col - 1).max(0));
+                let (max_row, max_col) =
+                    ((max_row + 1).min(rows - 1), (max_col + 1).min(columns - 1));
+                let mut configs = pntr.configs.clone();
+                configs.rows = (max_row - min_row + 1) as usize;
+                configs.columns = (max_col - min_col + 1) as usize;
+                configs.north = pntr.configs.north - min_row as f64 * res_y;
+                configs.south = pntr.configs.north - (max_row + 1) as f64 * res_y;
+                configs.west = pntr.configs.west + min_col as f64 * res_x;
+                configs.east = pntr.configs.west + (max_col + 1) as f64 * res_x;
+                // The georeferencing is given by the new extent rather than the pointer's tie point.
+                configs.model_tiepoint = vec![];
+                configs.model_transformation = [0f64; 16];
+                configs.nodata = nodata;
+                configs.data_type = DataType::I32;
+                configs.photometric_interp = PhotometricInterpretation::Categorical;
+                configs.palette = "qual.pal".to_string();
+                let file_name = format!(
+                    "{}{}{}.tif",
+                    output_dir.trim_end_matches(['/', '\\']),
+                    path::MAIN_SEPARATOR,
+                    sanitize_file_name(&gauges[i].id)
+                );
+                let mut output = Raster::initialize_using_config(&file_name, &configs);
+                for row in min_row..=max_row {
+                    for col in min_col..=max_col {
+                        let mut g = owner.get_value(row, col);
+                        // is the owning gauge nested within (or equal to) gauge i?
+                        while g >= 0 && g as usize != i {
+                            g = gauges[g as usize].parent.map_or(-1, |p| p as i32);
+                        }
+                        if g >= 0 {
+                            output.set_value(row - min_row, col - min_col, 1f64);
+                        }
+                    }
+                }
+                output.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+                output.add_metadata_entry(format!("Gauge id: {}", gauges[i].id));
+                output.write()?;
+
+                if verbose {
+                    progress = (100.0_f64 * (i + 1) as f64 / num_gauges as f64) as usize;
+                    if progress != old_progress {
+                        println!("Writing gauge catchments: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+        }
+
+        if !report_file.is_empty() {
+            let cell_area = pntr.configs.resolution_x * pntr.configs.resolution_y;
+            write_report(&gauges, &labels, cell_area, &report_file)?;
+            if verbose {
+                println!("Report written to {}", report_file);
+            }
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum GaugeStatus {
+    Ok,
+    OffGrid,
+    NoData,
+}
+
+impl GaugeStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            GaugeStatus::Ok => "ok",
+            GaugeStatus::OffGrid => "off_grid",
+            GaugeStatus::NoData => "nodata",
+        }
+    }
+}
+
+/// (min_row, min_col, max_row, max_col); empty extents have min > max.
+type Extent = (isize, isize, isize, isize);
+
+const EMPTY_EXTENT: Extent = (isize::MAX, isize::MAX, isize::MIN, isize::MIN);
+
+fn union_extent(a: Extent, b: Extent) -> Extent {
+    (a.0.min(b.0), a.1.min(b.1), a.2.max(b.2), a.3.max(b.3))
+}
+
+struct Gauge {
+    id: String,
+    x: f64,
+    y: f64,
+    row: isize,
+    col: isize,
+    status: GaugeStatus,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    num_own_cells: usize,
+    own_extent: Extent,
+    num_cells: usize,
+    extent: Extent,
+    labeled_cells: usize,
+}
+
+impl Gauge {
+    fn new(id: String, x: f64, y: f64) -> Gauge {
+        Gauge {
+            id,
+            x,
+            y,
+            row: -1,
+            col: -1,
+            status: GaugeStatus::Ok,
+            parent: None,
+            children: vec![],
+            num_own_cells: 0,
+            own_extent: EMPTY_EXTENT,
+            num_cells: 0,
+            extent: EMPTY_EXTENT,
+            labeled_cells: 0,
+        }
+    }
+
+    fn own_cells(&mut self, row: isize, col: isize) {
+        self.num_own_cells += 1;
+        self.own_extent = union_extent(self.own_extent, (row, col, row, col));
+    }
+}
+
+/// Reads the gauge locations and ids from a Shapefile or GeoJSON points file.
+fn read_gauges(file_name: &str, id_field: &str) -> Result<Vec<Gauge>, Error> {
+    let id_field = id_field.trim();
+    let mut gauges = vec![];
+    let lc = file_name.to_lowercase();
+    if lc.ends_with(".geojson") || lc.ends_with(".json") {
+        let geojson_str = fs::read_to_string(file_name)?;
+        let gj: GeoJson = geojson_str
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let fc = match gj {
+            GeoJson::FeatureCollection(fc) => fc,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "GeoJSON must be a FeatureCollection of Points.",
+                ))
+            }
+        };
+        for feature in fc.features {
+            let pt = match &feature.geometry {
+                Some(Geometry {
+                    value: Value::Point(pt),
+                    ..
+                }) => pt.clone(),
+                Some(Geometry {
+                    value: Value::MultiPoint(pts),
+                    ..
+                }) if !pts.is_empty() => pts[0].clone(),
+                _ => continue, // ignore non-point features
+            };
+            let id = if id_field.is_empty() {
+                (gauges.len() + 1).to_string()
+            } else {
+                match feature.property(id_field) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(v) if !v.is_null() => v.to_string(),
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "Gauge {} does not have a value for the '{}' property.",
+                                gauges.len() + 1,
+                                id_field
+                            ),
+                        ))
+                    }
+                }
+            };
+            gauges.push(Gauge::new(id, pt[0], pt[1]));
+        }
+    } else {
+        let pourpts = Shapefile::read(file_name)?;
+        if pourpts.header.shape_type.base_shape_type() != ShapeType::Point
+            && pourpts.header.shape_type.base_shape_type() != ShapeType::MultiPoint
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of point base shape type.",
+            ));
+        }
+        if !id_field.is_empty() && pourpts.attributes.get_field_num(id_field).is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The gauge points file does not contain a '{}' field.",
+                    id_field
+                ),
+            ));
+        }
+        for record_num in 0..pourpts.num_records {
+            let record = pourpts.get_record(record_num);
+            if record.points.is_empty() {
+                continue;
+            }
+            let id = if id_field.is_empty() {
+                (record_num + 1).to_string()
+            } else {
+                match pourpts.attributes.get_value(record_num, id_field) {
+                    FieldData::Null => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "Gauge {} does not have a value for the '{}' field.",
+                                record_num + 1,
+                                id_field
+                            ),
+                        ))
+                    }
+                    v => v.to_string().trim().to_string(),
+                }
+            };
+            gauges.push(Gauge::new(id, record.points[0].x, record.points[0].y));
+        }
+    }
+    Ok(gauges)
+}
+
+/// Replaces characters that are not safe in file names.
+fn sanitize_file_name(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn write_report(
+    gauges: &[Gauge],
+    labels: &[f64],
+    cell_area: f64,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "gauge_id\tlabel\trow\tcolumn\tnum_cells\tarea\tlabeled_cells\tnested_in\tcontains\tstatus"
+    )?;
+    for (i, g) in gauges.iter().enumerate() {
+        let (row, col) = if g.status == GaugeStatus::OffGrid {
+            ("NA".to_string(), "NA".to_string())
+        } else {
+            (g.row.to_string(), g.col.to_string())
+        };
+        let nested_in = match g.parent {
+            Some(p) => gauges[p].id.clone(),
+            None => "NA".to_string(),
+        };
+        let contains = if g.children.is_empty() {
+            "NA".to_string()
+        } else {
+            g.children
+                .iter()
+                .map(|&c| gauges[c].id.clone())
+                .collect::<Vec<String>>()
+                .join(",")
+        };
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{}\t{}\t{:.3}\t{}\t{}\t{}\t{}",
+            g.id,
+            labels[i],
+            row,
+            col,
+            g.num_cells,
+            g.num_cells as f64 * cell_area,
+            g.labeled_cells,
+            nested_in,
+            contains,
+            g.status.as_str()
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::CatchmentDelineationBatch;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 5 x 5 pointer with 10 m cells. Columns 0-3 drain east, column 4 drains south, and the
+    // bottom-right cell is the outlet.
+    fn write_pointer(file: &str) {
+        let pntr = GridFixture::new(5, 5, |r, c| {
+            if (r, c) == (4, 4) {
+                0f64
+            } else if c == 4 {
+                8f64
+            } else {
+                2f64
+            }
+        });
+        grid_fixture_raster(file, &pntr).write().unwrap();
+    }
+
+    // Gauges, in file order, at cell (row, col) centres.
+    fn write_gauges(file: &str, gauges: &[(&str, isize, isize)]) {
+        let features: Vec<String> = gauges
+            .iter()
+            .map(|(id, r, c)| {
+                format!(
+                    r#"{{"type":"Feature","properties":{{"site":"{}"}},"geometry":{{"type":"Point","coordinates":[{},{}]}}}}"#,
+                    id,
+                    *c as f64 * 10f64 + 5f64,
+                    50f64 - *r as f64 * 10f64 - 5f64
+                )
+            })
+            .collect();
+        fs::write(
+            file,
+            format!(
+                r#"{{"type":"FeatureCollection","features":[{}]}}"#,
+                features.join(",")
+            ),
+        )
+        .unwrap();
+    }
+
+    fn run(dir: &str, nested: &str) {
+        CatchmentDelineationBatch::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}/pntr.tif", dir),
+                    format!("--pour_pts={}/gauges.geojson", dir),
+                    "--id_field=site".to_string(),
+                    format!("--output={}/catchments.tif", dir),
+                    format!("--output_dir={}/gauges", dir),
+                    format!("--nested={}", nested),
+                    format!("--report={}/report.tsv", dir),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+    }
+
+    fn count_labels(file: &str) -> Vec<usize> {
+        let raster = Raster::new(file, "r").unwrap();
+        let mut counts = vec![0usize; 4];
+        for r in 0..5isize {
+            for c in 0..5isize {
+                let v = raster.get_value(r, c);
+                if v != raster.configs.nodata {
+                    counts[v as usize] += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    #[test]
+    fn test_nested_gauges() {
+        let dir = temp_dir("catchment_delineation_batch_nested").unwrap();
+        write_pointer(&format!("{}/pntr.tif", dir));
+        // 'mid' drains rows 0-2, 'side' drains cells (3, 0) and (3, 1), and both are nested within
+        // the outlet gauge, which is listed last.
+        write_gauges(
+            &format!("{}/gauges.geojson", dir),
+            &[("mid", 2, 4), ("side", 3, 1), ("outlet", 4, 4)],
+        );
+        run(&dir, "innermost");
+
+        // Labels are record numbers, since the ids are not integers.
+        assert_eq!(
+            count_labels(&format!("{}/catchments.tif", dir)),
+            vec![0, 15, 2, 8]
+        );
+        let report = fs::read_to_string(format!("{}/report.tsv", dir)).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(
+            lines[0],
+            "gauge_id\tlabel\trow\tcolumn\tnum_cells\tarea\tlabeled_cells\tnested_in\tcontains\tstatus"
+        );
+        assert_eq!(lines[1], "mid\t1\t2\t4\t15\t1500.000\t15\toutlet\tNA\tok");
+        assert_eq!(lines[2], "side\t2\t3\t1\t2\t200.000\t2\toutlet\tNA\tok");
+        assert_eq!(
+            lines[3],
+            "outlet\t3\t4\t4\t25\t2500.000\t8\tNA\tmid,side\tok"
+        );
+
+        // The per-gauge rasters hold complete catchments, cropped to their extents.
+        let side = Raster::new(&format!("{}/gauges/side.tif", dir), "r").unwrap();
+        assert_eq!((side.configs.rows, side.configs.columns), (3, 3));
+        assert_eq!(side.configs.west, 0f64);
+        assert_eq!(side.configs.north, 30f64);
+        assert_eq!(side.get_value(1, 0), 1f64);
+        assert_eq!(side.get_value(1, 1), 1f64);
+        assert_eq!(side.get_value(1, 2), side.configs.nodata);
+        assert_eq!(side.get_value(0, 0), side.configs.nodata);
+        let outlet = Raster::new(&format!("{}/gauges/outlet.tif", dir), "r").unwrap();
+        assert_eq!((outlet.configs.rows, outlet.configs.columns), (5, 5));
+        for r in 0..5isize {
+            for c in 0..5isize {
+                assert_eq!(outlet.get_value(r, c), 1f64);
+            }
+        }
+
+        // With the outlet listed last, it claims its whole catchment.
+        run(&dir, "last_wins");
+        assert_eq!(
+            count_labels(&format!("{}/catchments.tif", dir)),
+            vec![0, 0, 0, 25]
+        );
+        let report = fs::read_to_string(format!("{}/report.tsv", dir)).unwrap();
+        assert!(report
+            .lines()
+            .nth(1)
+            .unwrap()
+            .starts_with("mid\t1\t2\t4\t15\t1500.000\t0\toutlet"));
+    }
+
+    #[test]
+    fn test_gauges_sharing_a_cell_and_off_grid() {
+        let dir = temp_dir("catchment_delineation_batch_shared").unwrap();
+        write_pointer(&format!("{}/pntr.tif", dir));
+        write_gauges(
+            &format!("{}/gauges.geojson", dir),
+            &[("1", 2, 4), ("2", 2, 4), ("3", 9, 9)],
+        );
+        run(&dir, "innermost");
+
+        // The integer ids are used as labels, and the later gauge in the shared cell is nested.
+        assert_eq!(
+            count_labels(&format!("{}/catchments.tif", dir)),
+            vec![0, 0, 15, 0]
+        );
+        let report = fs::read_to_string(format!("{}/report.tsv", dir)).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[1], "1\t1\t2\t4\t15\t1500.000\t0\tNA\t2\tok");
+        assert_eq!(lines[2], "2\t2\t2\t4\t15\t1500.000\t15\t1\tNA\tok");
+        assert_eq!(lines[3], "3\t3\tNA\tNA\t0\t0.000\t0\tNA\tNA\toff_grid");
+    }
+}
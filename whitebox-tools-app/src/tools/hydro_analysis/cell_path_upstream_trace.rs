@@ -0,0 +1,657 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::pour_point_locator::locate_pour_points;
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::HashSet;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use std::path::Path;
+use whitebox_common::algorithms::D8Walker;
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool traces the main stem upstream of one or more sites, e.g. water-quality sampling
+/// locations, and is the upstream counterpart of `TraceDownslopeFlowpaths`. Starting at the cell of
+/// each site (`--pour_pts`, a point Shapefile, a GeoJSON file of points, or a raster in which the
+/// site cells have positive values), the trace repeatedly steps to the neighbour that drains into
+/// the current cell with the largest flow accumulation (`--flow_accum`), so that at each junction
+/// it follows the tributary with the largest contributing area. The trace ends at a headwater cell,
+/// i.e. a cell with no inflowing neighbours. Neighbours with NoData accumulation are ignored, and
+/// ties in accumulation are broken by taking the first tied neighbour clockwise from the
+/// north-east, so the trace is deterministic. The D8 pointer (`--d8_pntr`) uses the Whitebox
+/// scheme unless `--esri_pntr` is specified.
+///
+/// The output (`--output`) is a Shapefile, or GeoJSON if its name ends in `.geojson` or `.json`,
+/// with one PolyLineM feature per site running through the cell centres from the site to the
+/// headwater. Vertex measures are the cumulative distance upstream of the site, in map units, with
+/// diagonal steps counted at their diagonal length. Each feature has the `SITE` (the site number,
+/// from 1, in file order), `LENGTH`, `NUM_CELLS`, `SITE_ACCUM` and `HEAD_ACCUM` attributes. A site
+/// that is itself a headwater is written as a line of two coincident vertices.
+///
+/// When a DEM (`--dem`) is supplied, a tab-separated profile of every traced cell is also written
+/// alongside the output, named after it with a `_profile.tsv` suffix (e.g. `mainstem_profile.tsv`
+/// for `mainstem.shp`), with the `site_id`, `step` (from 0 at the site), `row`, `col`, `x`, `y`,
+/// `distance`, `accumulation` and `elevation` columns. All input rasters must share the rows and
+/// columns of the pointer, and a site off the grid or on a NoData accumulation cell is an error.
+///
+/// # See Also
+/// `TraceDownslopeFlowpaths`, `D8FlowAccumulation`, `LongestFlowpath`, `LongProfile`
+pub struct CellPathUpstreamTrace {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl CellPathUpstreamTrace {
+    pub fn new() -> CellPathUpstreamTrace {
+        // public constructor
+        let name = "CellPathUpstreamTrace".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Traces the path of maximum contributing area upstream from sites to their headwaters."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Flow Accumulation File".to_owned(),
+            flags: vec!["--flow_accum".to_owned()],
+            description: "Input raster D8 flow accumulation file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Sites File".to_owned(),
+            flags: vec!["--pour_pts".to_owned()],
+            description: "Input sites file (point Shapefile, GeoJSON, or raster).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output lines vector file (*.shp, *.geojson) of traced paths.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File (optional)".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description:
+                "Optional input raster DEM file; writes a TSV profile of the traced paths."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr=D8.tif --flow_accum=accum.tif --pour_pts=sites.shp -o=mainstem.shp --dem=dem.tif", short_exe, name).replace("*", &sep);
+
+        CellPathUpstreamTrace {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for CellPathUpstreamTrace {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut accum_file = String::new();
+        let mut sites_file = String::new();
+        let mut output_file = String::new();
+        let mut dem_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-flow_accum" {
+                accum_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-pour_pts" {
+                sites_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        // check the vector format before doing any work
+        VectorFormat::from_file_name(&output_file)?;
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        accum_file = resolve_path(working_directory, &accum_file);
+        sites_file = resolve_path(working_directory, &sites_file);
+        output_file = resolve_path(working_directory, &output_file);
+        let profile_file = if dem_file.trim().is_empty() {
+            None
+        } else {
+            dem_file = resolve_path(working_directory, &dem_file);
+            Some(profile_file_name(&output_file))
+        };
+
+        let mut inputs = vec![("--d8_pntr", &d8_file), ("--flow_accum", &accum_file)];
+        if profile_file.is_some() {
+            inputs.push(("--dem", &dem_file));
+        }
+        check_rasters_share_geometry(&inputs, false)?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let pntr = Raster::new(&d8_file, "r")?;
+        let accum = Raster::new(&accum_file, "r")?;
+        let dem = match profile_file {
+            Some(_) => Some(Raster::new(&dem_file, "r")?),
+            None => None,
+        };
+        let sites = locate_pour_points(&sites_file, &pntr)?;
+        if sites.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No sites found in --pour_pts",
+            ));
+        }
+
+        let start = Instant::now();
+
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            esri_style,
+            |row, col| pntr.get_value(row, col),
+        );
+        let mut paths = Vec::with_capacity(sites.len());
+        for (i, &(row, col)) in sites.iter().enumerate() {
+            if !walker.in_grid(row, col) || accum.get_value(row, col) == accum.configs.nodata {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Site {} (row {}, column {}) is not on a valid flow accumulation cell.",
+                        i + 1,
+                        row,
+                        col
+                    ),
+                ));
+            }
+            paths.push(trace_upstream(&walker, &accum, row, col));
+        }
+
+        if verbose {
+            let max_len = paths.iter().map(|p| p.len()).max().unwrap_or(0);
+            println!(
+                "Traced {} sites; the longest path has {} cells.",
+                paths.len(),
+                max_len
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let mut output = VectorWriter::new(&output_file, ShapeType::PolyLineM)?;
+        output.projection = pntr.configs.coordinate_ref_system_wkt.clone();
+        if pntr.configs.epsg_code != 0 {
+            output.epsg = Some(pntr.configs.epsg_code);
+        }
+        output.add_field(&AttributeField::new("SITE", FieldDataType::Int, 7u8, 0u8));
+        output.add_field(&AttributeField::new(
+            "LENGTH",
+            FieldDataType::Real,
+            12u8,
+            3u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "NUM_CELLS",
+            FieldDataType::Int,
+            9u8,
+            0u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "SITE_ACCUM",
+            FieldDataType::Real,
+            14u8,
+            3u8,
+        ));
+        output.add_field(&AttributeField::new(
+            "HEAD_ACCUM",
+            FieldDataType::Real,
+            14u8,
+            3u8,
+        ));
+        for (i, path) in paths.iter().enumerate() {
+            let mut points: Vec<Point2D> = path
+                .iter()
+                .map(|c| Point2D::new(pntr.get_x_from_column(c.col), pntr.get_y_from_row(c.row)))
+                .collect();
+            let mut measures: Vec<f64> = path.iter().map(|c| c.distance).collect();
+            if points.len() == 1 {
+                points.push(points[0]);
+                measures.push(measures[0]);
+            }
+            let site = &path[0];
+            let head = &path[path.len() - 1];
+            let mut sfg = ShapefileGeometry::new(ShapeType::PolyLineM);
+            sfg.add_partm(&points, &measures);
+            output.add_record(
+                sfg,
+                vec![
+                    FieldData::Int((i + 1) as i32),
+                    FieldData::Real(head.distance),
+                    FieldData::Int(path.len() as i32),
+                    FieldData::Real(site.accumulation),
+                    FieldData::Real(head.accumulation),
+                ],
+            );
+        }
+        output.write()?;
+
+        if let (Some(profile_file), Some(dem)) = (profile_file, dem) {
+            write_profile_to_tsv(&paths, &pntr, &dem, &profile_file)?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A cell on a traced path, with its distance upstream of the site.
+struct PathCell {
+    row: isize,
+    col: isize,
+    distance: f64,
+    accumulation: f64,
+}
+
+/// Traces upstream from (`row`, `col`) along the inflowing neighbours with the largest
+/// accumulation, ending at a headwater. Ties go to the first neighbour clockwise from the
+/// north-east. The path starts at the site; a cell already on the path is never revisited, which
+/// ends the trace early on a pointer loop.
+fn trace_upstream(walker: &D8Walker, accum: &Raster, row: isize, col: isize) -> Vec<PathCell> {
+    let nodata = accum.configs.nodata;
+    let (res_x, res_y) = (accum.configs.resolution_x, accum.configs.resolution_y);
+    let mut path = vec![PathCell {
+        row,
+        col,
+        distance: 0f64,
+        accumulation: accum.get_value(row, col),
+    }];
+    let mut visited = HashSet::new();
+    visited.insert((row, col));
+    let (mut row, mut col) = (row, col);
+    loop {
+        let mut next: Option<(isize, isize, f64)> = None;
+        for (r, c) in walker.inflows(row, col) {
+            let a = accum.get_value(r, c);
+            if a == nodata || visited.contains(&(r, c)) {
+                continue;
+            }
+            if next.map_or(true, |(_, _, best)| a > best) {
+                next = Some((r, c, a));
+            }
+        }
+        let (r, c, a) = match next {
+            Some(n) => n,
+            None => break,
+        };
+        let step = (((c - col) as f64 * res_x).powi(2) + ((r - row) as f64 * res_y).powi(2)).sqrt();
+        let distance = path[path.len() - 1].distance + step;
+        path.push(PathCell {
+            row: r,
+            col: c,
+            distance,
+            accumulation: a,
+        });
+        visited.insert((r, c));
+        row = r;
+        col = c;
+    }
+    path
+}
+
+/// The profile table written beside `output_file`: its name with the extension replaced by
+/// `_profile.tsv`.
+fn profile_file_name(output_file: &str) -> String {
+    let path = Path::new(output_file);
+    let stem = path
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}_profile.tsv", stem))
+        .to_string_lossy()
+        .to_string()
+}
+
+fn write_profile_to_tsv(
+    paths: &[Vec<PathCell>],
+    pntr: &Raster,
+    dem: &Raster,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "site_id\tstep\trow\tcol\tx\ty\tdistance\taccumulation\televation"
+    )?;
+    for (i, path) in paths.iter().enumerate() {
+        for (step, cell) in path.iter().enumerate() {
+            let z = dem.get_value(cell.row, cell.col);
+            let elevation = if z == dem.configs.nodata {
+                String::from("nan")
+            } else {
+                format!("{:.3}", z)
+            };
+            writeln!(
+                &mut file,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{}\t{}",
+                i + 1,
+                step,
+                cell.row,
+                cell.col,
+                pntr.get_x_from_column(cell.col),
+                pntr.get_y_from_row(cell.row),
+                cell.distance,
+                cell.accumulation,
+                elevation
+            )?;
+        }
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{profile_file_name, trace_upstream, CellPathUpstreamTrace};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::algorithms::D8Walker;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    /// A 5 by 5 grid of 10 m cells draining to the outlet at the bottom of column 2, the main
+    /// channel. The west side drains east into it and the east side drains west into it, except
+    /// for the north-east cell, which drains south-west into (1, 3).
+    fn write_inputs(dir: &str) -> (Raster, Raster) {
+        let mut pntr = GridFixture::new(5, 5, |_, c| {
+            if c < 2 {
+                2f64 // east
+            } else if c > 2 {
+                32f64 // west
+            } else {
+                8f64 // south
+            }
+        });
+        pntr.set_value(0, 4, 16f64); // south-west into (1, 3)
+        pntr.set_value(4, 2, 0f64);
+        let mut accum = GridFixture::new(5, 5, |_, c| match c {
+            0 | 4 => 1f64,
+            1 | 3 => 2f64,
+            _ => 0f64,
+        });
+        accum.set_value(0, 3, 1f64);
+        accum.set_value(1, 3, 3f64);
+        let mut total = 0f64;
+        for r in 0..5isize {
+            total += 1f64 + accum.value(r, 1) + accum.value(r, 3);
+            accum.set_value(r, 2, total);
+        }
+        let mut pntr = grid_fixture_raster(&format!("{}/d8.tif", dir), &pntr);
+        let mut accum = grid_fixture_raster(&format!("{}/accum.tif", dir), &accum);
+        pntr.write().unwrap();
+        accum.write().unwrap();
+        (pntr, accum)
+    }
+
+    #[test]
+    fn test_trace_follows_largest_inflow() {
+        let dir = temp_dir("cell_path_upstream_trace_walk").unwrap();
+        let (pntr, mut accum) = write_inputs(&dir);
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            false,
+            |row, col| pntr.get_value(row, col),
+        );
+
+        // From the outlet the trace climbs the channel, whose cells beat the side inflows, and then
+        // turns west at the top row, where the west inflow (2) beats the east inflow (1).
+        let path = trace_upstream(&walker, &accum, 4, 2);
+        let cells: Vec<(isize, isize)> = path.iter().map(|c| (c.row, c.col)).collect();
+        assert_eq!(
+            cells,
+            vec![(4, 2), (3, 2), (2, 2), (1, 2), (0, 2), (0, 1), (0, 0)]
+        );
+        assert_eq!(path[6].distance, 60f64);
+        assert_eq!(path[0].accumulation, 25f64);
+
+        // With the channel cell above (1, 2) lowered, the tributary at (1, 3) wins, and its two
+        // equal inflows go to the first clockwise from the north-east, the diagonal at (0, 4).
+        accum.set_value(0, 2, 2f64);
+        let path = trace_upstream(&walker, &accum, 1, 2);
+        let cells: Vec<(isize, isize)> = path.iter().map(|c| (c.row, c.col)).collect();
+        assert_eq!(cells, vec![(1, 2), (1, 3), (0, 4)]);
+        assert_eq!(path[2].distance, 10f64 + 200f64.sqrt());
+
+        // Equal inflows from the west and east go to the east neighbour.
+        accum.set_value(1, 1, 3f64);
+        let path = trace_upstream(&walker, &accum, 1, 2);
+        assert_eq!((path[1].row, path[1].col), (1, 3));
+
+        // A headwater is its own path.
+        let path = trace_upstream(&walker, &accum, 2, 0);
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0].distance, 0f64);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_trace_outputs() {
+        let dir = temp_dir("cell_path_upstream_trace_run").unwrap();
+        write_inputs(&dir);
+        let dem = GridFixture::new(5, 5, |r, _| 100f64 - 10f64 * r as f64);
+        grid_fixture_raster(&format!("{}/dem.tif", dir), &dem)
+            .write()
+            .unwrap();
+        let sites = GridFixture::new(5, 5, |r, c| match (r, c) {
+            (2, 2) | (3, 0) => 1f64,
+            _ => 0f64,
+        });
+        grid_fixture_raster(&format!("{}/sites.tif", dir), &sites)
+            .write()
+            .unwrap();
+
+        let args = vec![
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--flow_accum={}/accum.tif", dir),
+            format!("--pour_pts={}/sites.tif", dir),
+            format!("--dem={}/dem.tif", dir),
+            format!("--output={}/mainstem.geojson", dir),
+        ];
+        CellPathUpstreamTrace::new().run(args, "", false).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(format!("{}/mainstem.geojson", dir)).unwrap())
+                .unwrap();
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        let props = &features[0]["properties"];
+        assert_eq!(props["SITE"], 1);
+        assert_eq!(props["NUM_CELLS"], 5);
+        assert_eq!(props["LENGTH"].as_f64(), Some(40f64));
+        assert_eq!(props["SITE_ACCUM"].as_f64(), Some(15f64));
+        assert_eq!(props["HEAD_ACCUM"].as_f64(), Some(1f64));
+        let coords = features[0]["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(coords[0][0].as_f64(), Some(25f64));
+        assert_eq!(coords[0][1].as_f64(), Some(25f64));
+        assert_eq!(coords[2][1].as_f64(), Some(45f64));
+        // the headwater site is a line of two coincident vertices
+        assert_eq!(features[1]["properties"]["NUM_CELLS"], 1);
+        let coords = features[1]["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(coords.len(), 2);
+        assert_eq!(coords[0], coords[1]);
+
+        let profile = fs::read_to_string(format!("{}/mainstem_profile.tsv", dir)).unwrap();
+        let rows: Vec<Vec<&str>> = profile.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(rows.len(), 7);
+        assert_eq!(rows[0][8], "elevation");
+        assert_eq!(
+            rows[3],
+            vec!["1", "2", "0", "2", "25", "45", "20.000", "4", "100.000"]
+        );
+        assert_eq!(rows[6][..3], ["2", "0", "3"]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_profile_file_name() {
+        assert_eq!(
+            profile_file_name("/data/mainstem.shp"),
+            "/data/mainstem_profile.tsv"
+        );
+        assert_eq!(profile_file_name("trace.geojson"), "trace_profile.tsv");
+    }
+}
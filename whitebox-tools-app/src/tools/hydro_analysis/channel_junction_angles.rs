@@ -0,0 +1,819 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::hillslopes_topaz::{read_netw, NetwLink};
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::algorithms::{calculate_rotation_degrees, D8Walker};
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool measures the planform entrance angle of each tributary at the junctions of a stream
+/// network, i.e. the angle between the direction in which a tributary arrives at a junction and
+/// the direction in which the receiving channel leaves it. A tributary flowing in parallel with
+/// the receiving channel enters at 0 degrees, one joining it at right angles at 90 degrees, and
+/// one flowing against it at 180 degrees.
+///
+/// The links of the network are either read from a channel network table (`--netw`) written by
+/// `HillslopesTopaz`, in which case each link is followed down the D8 pointer (`--d8_pntr`) from
+/// its upstream end (`us_x`, `us_y`) to its downstream end (`ds_x`, `ds_y`), the row and column
+/// written by `HillslopesTopaz`, and identified by its `topaz_id`; or, without a table, derived
+/// from the streams raster (`--streams`), in which case a link starts at every channel head and
+/// every junction (a stream cell with two or more inflowing stream cells) and runs down to the next
+/// junction or the end of the network. Derived links are numbered from 1 in row-major order of
+/// their first cells. Either way, a junction cell is the last cell of its inflowing links and the
+/// first cell of its downstream link, and every cell of a link read from the table must be a
+/// stream cell.
+///
+/// The direction of an inflowing link is the vector from the cell `--k_cells` steps above the
+/// junction (default 3) to the junction, and that of the downstream link is the vector from the
+/// junction to the cell `--k_cells` steps below it, both in map coordinates. A link with fewer
+/// steps than `--k_cells` uses all of its cells, and the steps actually used are reported. A
+/// downstream link consisting of the junction alone, e.g. at the outlet, takes its direction from
+/// the junction's pointer; a junction without one is skipped with a warning. The rotation between
+/// the two vectors is computed with `calculate_rotation_degrees`.
+///
+/// The output (`--output`) is a tab-separated table with one row per inflowing link and the
+/// following columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `junction` | The junction number, from 1, in row-major order of the junction cells. |
+/// | `row`, `col`, `x`, `y` | The junction cell and its centre. |
+/// | `ds_link` | The ID of the downstream link. |
+/// | `inflow_link` | The ID of the inflowing link. |
+/// | `inflow_steps`, `ds_steps` | The steps used for the inflowing and downstream directions. |
+/// | `entrance_angle` | The entrance angle, in degrees from 0 to 180. |
+/// | `bank` | The bank the tributary enters from, looking downstream: `left`, `right`, or `none` when the directions are collinear. |
+///
+/// The inflowing links of a junction are listed in order of their IDs. When `--output_points` is
+/// given, a point Shapefile (or GeoJSON, by extension) is also written with one feature per
+/// junction and the `JUNCTION`, `DS_LINK` and `INFLOWS` attributes, followed by `LINK_n`,
+/// `ANGLE_n` and `BANK_n` for each inflowing link, up to the largest number of inflows at any
+/// junction. The streams raster must share the rows and columns of the pointer, which uses the
+/// Whitebox scheme unless `--esri_pntr` is specified.
+///
+/// # See Also
+/// `HillslopesTopaz`, `StreamLinkIdentifier`, `StreamLinkLengthAndSinuosity`
+pub struct ChannelJunctionAngles {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ChannelJunctionAngles {
+    pub fn new() -> ChannelJunctionAngles {
+        // public constructor
+        let name = "ChannelJunctionAngles".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Measures the planform entrance angle of each tributary at the junctions of a stream network."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file (positive values are stream cells).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Channel Network Table".to_owned(),
+            flags: vec!["--netw".to_owned()],
+            description: "Optional input channel network TSV file from HillslopesTopaz; links are derived from the streams otherwise.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Direction Steps".to_owned(),
+            flags: vec!["--k_cells".to_owned()],
+            description: "Number of steps along each link used for its direction at a junction."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated table of the junction angles.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Junction Points File (optional)".to_owned(),
+            flags: vec!["--output_points".to_owned()],
+            description: "Optional output points vector file (*.shp, *.geojson) of the junctions."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr=D8.tif --streams=streams.tif --netw=netw.tsv --k_cells=5 -o=junction_angles.tsv --output_points=junctions.shp", short_exe, name).replace("*", &sep);
+
+        ChannelJunctionAngles {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ChannelJunctionAngles {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut netw_file = String::new();
+        let mut k_cells = 3isize;
+        let mut output_file = String::new();
+        let mut points_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-netw" {
+                netw_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-k_cells" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                k_cells = value.trim().parse::<isize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid --k_cells value: {}", value),
+                    )
+                })?;
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_points" {
+                points_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if k_cells < 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--k_cells must be at least 1.",
+            ));
+        }
+        let points_file = if points_file.trim().is_empty() {
+            None
+        } else {
+            // check the vector format before doing any work
+            VectorFormat::from_file_name(&points_file)?;
+            Some(resolve_path(working_directory, &points_file))
+        };
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        check_rasters_share_geometry(
+            &[("--d8_pntr", &d8_file), ("--streams", &streams_file)],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let pntr = Raster::new(&d8_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+
+        let start = Instant::now();
+
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            esri_style,
+            |row, col| pntr.get_value(row, col),
+        );
+        let links = if netw_file.trim().is_empty() {
+            derive_links(&walker, &streams)
+        } else {
+            netw_file = resolve_path(working_directory, &netw_file);
+            let mut links = vec![];
+            for link in read_netw(&netw_file)? {
+                links.push(trace_link(&walker, &streams, &link)?);
+            }
+            links
+        };
+        let (junctions, skipped) = junction_angles(&links, &walker, &pntr, k_cells as usize);
+
+        if verbose {
+            println!(
+                "Measured {} tributary entrance angles at {} junctions of {} links.",
+                junctions.iter().map(|j| j.inflows.len()).sum::<usize>(),
+                junctions.len(),
+                links.len()
+            );
+        }
+        if skipped > 0 {
+            println!(
+                "Warning: {} junctions were skipped because their downstream direction is undefined.",
+                skipped
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        write_angles_to_tsv(&junctions, &pntr, &output_file)?;
+
+        if let Some(points_file) = points_file {
+            let max_inflows = junctions
+                .iter()
+                .map(|j| j.inflows.len())
+                .max()
+                .unwrap_or(0)
+                .max(2);
+            let mut output = VectorWriter::new(&points_file, ShapeType::Point)?;
+            output.projection = pntr.configs.coordinate_ref_system_wkt.clone();
+            if pntr.configs.epsg_code != 0 {
+                output.epsg = Some(pntr.configs.epsg_code);
+            }
+            output.add_field(&AttributeField::new(
+                "JUNCTION",
+                FieldDataType::Int,
+                7u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "DS_LINK",
+                FieldDataType::Int,
+                9u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "INFLOWS",
+                FieldDataType::Int,
+                2u8,
+                0u8,
+            ));
+            for n in 1..=max_inflows {
+                output.add_field(&AttributeField::new(
+                    &format!("LINK_{}", n),
+                    FieldDataType::Int,
+                    9u8,
+                    0u8,
+                ));
+                output.add_field(&AttributeField::new(
+                    &format!("ANGLE_{}", n),
+                    FieldDataType::Real,
+                    8u8,
+                    3u8,
+                ));
+                output.add_field(&AttributeField::new(
+                    &format!("BANK_{}", n),
+                    FieldDataType::Text,
+                    5u8,
+                    0u8,
+                ));
+            }
+            for (i, junction) in junctions.iter().enumerate() {
+                let mut sfg = ShapefileGeometry::new(ShapeType::Point);
+                sfg.add_point(Point2D::new(
+                    pntr.get_x_from_column(junction.cell.1),
+                    pntr.get_y_from_row(junction.cell.0),
+                ));
+                let mut attributes = vec![
+                    FieldData::Int((i + 1) as i32),
+                    FieldData::Int(junction.ds_link as i32),
+                    FieldData::Int(junction.inflows.len() as i32),
+                ];
+                for n in 0..max_inflows {
+                    match junction.inflows.get(n) {
+                        Some(inflow) => {
+                            attributes.push(FieldData::Int(inflow.link as i32));
+                            attributes.push(FieldData::Real(inflow.entrance_angle));
+                            attributes.push(FieldData::Text(inflow.bank.to_string()));
+                        }
+                        None => {
+                            attributes.push(FieldData::Null);
+                            attributes.push(FieldData::Null);
+                            attributes.push(FieldData::Null);
+                        }
+                    }
+                }
+                output.add_record(sfg, attributes);
+            }
+            output.write()?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A link of the stream network, with its cells in flow order. The last cell of a link that
+/// ends at a junction is the junction, which is also the first cell of the downstream link.
+struct ChannelLink {
+    id: i64,
+    cells: Vec<(isize, isize)>,
+}
+
+/// The angle at which an inflowing link enters a junction.
+struct InflowAngle {
+    link: i64,
+    inflow_steps: usize,
+    ds_steps: usize,
+    entrance_angle: f64,
+    bank: &'static str,
+}
+
+/// A junction and the entrance angles of its inflowing links, in order of their IDs.
+struct Junction {
+    cell: (isize, isize),
+    ds_link: i64,
+    inflows: Vec<InflowAngle>,
+}
+
+fn is_stream(streams: &Raster, row: isize, col: isize) -> bool {
+    let value = streams.get_value(row, col);
+    value > 0f64 && value != streams.configs.nodata
+}
+
+/// Splits the stream network into links, each starting at a channel head or a junction (a stream
+/// cell with two or more inflowing stream cells) and running down the pointer to the next
+/// junction, which ends the link as well as beginning the next one, or to the last stream cell.
+/// Links are numbered from 1 in row-major order of their first cells.
+fn derive_links(walker: &D8Walker, streams: &Raster) -> Vec<ChannelLink> {
+    walker
+        .stream_links(|row, col| is_stream(streams, row, col))
+        .into_iter()
+        .enumerate()
+        .map(|(i, mut cells)| {
+            let (row, col) = cells[cells.len() - 1];
+            match walker.downstream(row, col) {
+                Some((r, c)) if is_stream(streams, r, c) => cells.push((r, c)),
+                _ => {}
+            }
+            ChannelLink {
+                id: i as i64 + 1,
+                cells,
+            }
+        })
+        .collect()
+}
+
+/// Follows the pointer from a network table link's upstream end to its downstream end, returning
+/// the link, identified by its TOPAZ ID, with the cells passed through. Every cell must be a stream
+/// cell.
+fn trace_link(walker: &D8Walker, streams: &Raster, link: &NetwLink) -> Result<ChannelLink, Error> {
+    let (id, us, ds) = (link.topaz_id, link.us, link.ds);
+    let not_followed = || {
+        Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Link {} of the channel network table (--netw) does not follow the D8 pointer (--d8_pntr) along the streams (--streams) from ({}, {}) to ({}, {}).",
+                id, us.0, us.1, ds.0, ds.1
+            ),
+        )
+    };
+    let max_cells = streams.configs.rows * streams.configs.columns;
+    let mut cells = vec![us];
+    let mut current = us;
+    loop {
+        if !walker.in_grid(current.0, current.1) || !is_stream(streams, current.0, current.1) {
+            return Err(not_followed());
+        }
+        if current == ds {
+            return Ok(ChannelLink { id, cells });
+        }
+        current = match walker.downstream(current.0, current.1) {
+            Some(next) if cells.len() < max_cells => next,
+            _ => return Err(not_followed()),
+        };
+        cells.push(current);
+    }
+}
+
+/// Finds the junctions of the network, i.e. the cells that end one or more links and begin
+/// another, and measures the entrance angle of each inflowing link, using up to `k_cells` steps
+/// of each link for its direction. Junctions are returned in row-major order, along with the
+/// number skipped because their downstream direction is undefined.
+fn junction_angles(
+    links: &[ChannelLink],
+    walker: &D8Walker,
+    pntr: &Raster,
+    k_cells: usize,
+) -> (Vec<Junction>, usize) {
+    let mut heads = HashMap::new();
+    for (i, link) in links.iter().enumerate() {
+        heads.insert(link.cells[0], i);
+    }
+    let mut inflows: BTreeMap<(isize, isize), Vec<usize>> = BTreeMap::new();
+    for (i, link) in links.iter().enumerate() {
+        let last = link.cells[link.cells.len() - 1];
+        if link.cells.len() > 1 && heads.contains_key(&last) {
+            inflows.entry(last).or_default().push(i);
+        }
+    }
+
+    let mut junctions = vec![];
+    let mut skipped = 0;
+    for (cell, mut inflow_links) in inflows {
+        let ds_link = &links[heads[&cell]];
+        // the cell that ends the downstream direction
+        let (ds_steps, ds_cell) = if ds_link.cells.len() > 1 {
+            let steps = k_cells.min(ds_link.cells.len() - 1);
+            (steps, ds_link.cells[steps])
+        } else {
+            match walker.downstream(cell.0, cell.1) {
+                Some(next) => (1, next),
+                None => {
+                    skipped += 1;
+                    continue;
+                }
+            }
+        };
+        inflow_links.sort_by_key(|&i| links[i].id);
+        let mut angles = vec![];
+        for i in inflow_links {
+            let cells = &links[i].cells;
+            let inflow_steps = k_cells.min(cells.len() - 1);
+            let inflow_cell = cells[cells.len() - 1 - inflow_steps];
+            // Both directions are placed at the junction. The rotation from the inflow direction
+            // to the downstream one is below 180 degrees for a tributary entering from the left bank.
+            let (x, y) = (pntr.get_x_from_column(cell.1), pntr.get_y_from_row(cell.0));
+            let rotation = calculate_rotation_degrees(
+                2f64 * x - pntr.get_x_from_column(inflow_cell.1),
+                2f64 * y - pntr.get_y_from_row(inflow_cell.0), // a
+                x,
+                y, // o
+                pntr.get_x_from_column(ds_cell.1),
+                pntr.get_y_from_row(ds_cell.0), // b
+            );
+            let (entrance_angle, bank) = if rotation.abs() < 1e-9 {
+                (0f64, "none")
+            } else if (rotation - 180f64).abs() < 1e-9 {
+                (180f64, "none")
+            } else if rotation < 180f64 {
+                (rotation, "left")
+            } else {
+                (360f64 - rotation, "right")
+            };
+            angles.push(InflowAngle {
+                link: links[i].id,
+                inflow_steps,
+                ds_steps,
+                entrance_angle,
+                bank,
+            });
+        }
+        junctions.push(Junction {
+            cell,
+            ds_link: ds_link.id,
+            inflows: angles,
+        });
+    }
+    (junctions, skipped)
+}
+
+fn write_angles_to_tsv(junctions: &[Junction], pntr: &Raster, file_path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "junction\trow\tcol\tx\ty\tds_link\tinflow_link\tinflow_steps\tds_steps\tentrance_angle\tbank"
+    )?;
+    for (i, junction) in junctions.iter().enumerate() {
+        for inflow in &junction.inflows {
+            writeln!(
+                &mut file,
+                "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{}",
+                i + 1,
+                junction.cell.0,
+                junction.cell.1,
+                pntr.get_x_from_column(junction.cell.1),
+                pntr.get_y_from_row(junction.cell.0),
+                junction.ds_link,
+                inflow.link,
+                inflow.inflow_steps,
+                inflow.ds_steps,
+                inflow.entrance_angle,
+                inflow.bank
+            )?;
+        }
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{derive_links, junction_angles, ChannelJunctionAngles};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::algorithms::D8Walker;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    /// A 7 by 7 grid of 10 m cells whose main channel runs south down column 3 to the outlet at
+    /// (6, 3). At the junction (3, 3) it is joined by a tributary flowing west along row 3 from the
+    /// east edge and by a diagonal tributary flowing south-east from the north-west corner.
+    fn write_inputs(dir: &str) -> (Raster, Raster) {
+        let mut pntr = GridFixture::new(7, 7, |_, _| 8f64); // south
+        let mut streams = GridFixture::new(7, 7, |_, c| if c == 3 { 1f64 } else { 0f64 });
+        for c in 4..7isize {
+            pntr.set_value(3, c, 32f64); // west
+            streams.set_value(3, c, 1f64);
+        }
+        for i in 0..3isize {
+            pntr.set_value(i, i, 4f64); // south-east
+            streams.set_value(i, i, 1f64);
+        }
+        pntr.set_value(6, 3, 0f64);
+        let mut pntr = grid_fixture_raster(&format!("{}/d8.tif", dir), &pntr);
+        let mut streams = grid_fixture_raster(&format!("{}/streams.tif", dir), &streams);
+        pntr.write().unwrap();
+        streams.write().unwrap();
+        (pntr, streams)
+    }
+
+    #[test]
+    fn test_junction_angles() {
+        let dir = temp_dir("channel_junction_angles_walk").unwrap();
+        let (pntr, streams) = write_inputs(&dir);
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            false,
+            |row, col| pntr.get_value(row, col),
+        );
+
+        let links = derive_links(&walker, &streams);
+        let cells: Vec<Vec<(isize, isize)>> = links.iter().map(|l| l.cells.clone()).collect();
+        assert_eq!(
+            cells,
+            vec![
+                vec![(0, 0), (1, 1), (2, 2), (3, 3)],
+                vec![(0, 3), (1, 3), (2, 3), (3, 3)],
+                vec![(3, 3), (4, 3), (5, 3), (6, 3)],
+                vec![(3, 6), (3, 5), (3, 4), (3, 3)],
+            ]
+        );
+
+        let (junctions, skipped) = junction_angles(&links, &walker, &pntr, 2);
+        assert_eq!(skipped, 0);
+        assert_eq!(junctions.len(), 1);
+        assert_eq!(junctions[0].cell, (3, 3));
+        assert_eq!(junctions[0].ds_link, 3);
+        let angles: Vec<(i64, f64, &str)> = junctions[0]
+            .inflows
+            .iter()
+            .map(|a| (a.link, (a.entrance_angle * 1e6).round() / 1e6, a.bank))
+            .collect();
+        // Looking south down the main channel, the east tributary is on the left bank and the
+        // north-west one on the right.
+        assert_eq!(
+            angles,
+            vec![(1, 45f64, "right"), (2, 0f64, "none"), (4, 90f64, "left")]
+        );
+        assert!(junctions[0].inflows.iter().all(|a| a.inflow_steps == 2));
+
+        // Links shorter than k_cells use all of their steps.
+        let (junctions, _) = junction_angles(&links, &walker, &pntr, 10);
+        assert!(junctions[0]
+            .inflows
+            .iter()
+            .all(|a| a.inflow_steps == 3 && a.ds_steps == 3));
+
+        // A junction at the end of the network takes its direction from its pointer, or is
+        // skipped without one.
+        let short: Vec<_> = derive_links(&walker, &streams)
+            .into_iter()
+            .map(|mut l| {
+                if l.id == 3 {
+                    l.cells.truncate(1);
+                }
+                l
+            })
+            .collect();
+        let (junctions, skipped) = junction_angles(&short, &walker, &pntr, 2);
+        assert_eq!((junctions[0].inflows[0].ds_steps, skipped), (1, 0));
+        assert_eq!(junctions[0].inflows[2].entrance_angle.round(), 90f64);
+        let flat = D8Walker::new(7, 7, pntr.configs.nodata, false, |row, col| {
+            if (row, col) == (3, 3) {
+                0f64
+            } else {
+                pntr.get_value(row, col)
+            }
+        });
+        let (junctions, skipped) = junction_angles(&short, &flat, &pntr, 2);
+        assert_eq!((junctions.len(), skipped), (0, 1));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_junction_angle_outputs() {
+        let dir = temp_dir("channel_junction_angles_run").unwrap();
+        write_inputs(&dir);
+        fs::write(
+            format!("{}/netw.tsv", dir),
+            "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet\tchn_order\n\
+             0\t24\t6\t3\t3\t3\t1\t2\t3\t0.000\t0.000\t0.000\t0.000\t2\t0.000\tfalse\ttrue\t4\n\
+             1\t34\t3\t3\t3\t6\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t1\n\
+             2\t44\t3\t3\t0\t3\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t2\n\
+             3\t54\t3\t3\t0\t0\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t3\n",
+        )
+        .unwrap();
+
+        let args = vec![
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+            format!("--netw={}/netw.tsv", dir),
+            "--k_cells=2".to_string(),
+            format!("--output={}/angles.tsv", dir),
+            format!("--output_points={}/junctions.geojson", dir),
+        ];
+        ChannelJunctionAngles::new()
+            .run(args.clone(), "", false)
+            .unwrap();
+
+        let table = fs::read_to_string(format!("{}/angles.tsv", dir)).unwrap();
+        let rows: Vec<Vec<&str>> = table.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0][9], "entrance_angle");
+        assert_eq!(
+            rows[1],
+            vec!["1", "3", "3", "35", "35", "24", "34", "2", "2", "90.000", "left"]
+        );
+        assert_eq!(rows[2][6..], ["44", "2", "2", "0.000", "none"]);
+        assert_eq!(rows[3][6..], ["54", "2", "2", "45.000", "right"]);
+
+        let json: serde_json::Value = serde_json::from_str(
+            &fs::read_to_string(format!("{}/junctions.geojson", dir)).unwrap(),
+        )
+        .unwrap();
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features.len(), 1);
+        let props = &features[0]["properties"];
+        assert_eq!(props["DS_LINK"], 24);
+        assert_eq!(props["INFLOWS"], 3);
+        assert_eq!(props["LINK_3"], 54);
+        assert_eq!(props["ANGLE_3"].as_f64(), Some(45f64));
+        assert_eq!(props["BANK_1"], "left");
+
+        // A link that leaves the streams is rejected.
+        fs::write(
+            format!("{}/netw.tsv", dir),
+            "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet\tchn_order\n\
+             0\t24\t6\t3\t3\t3\t1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t2\t0.000\tfalse\ttrue\t2\n\
+             1\t34\t3\t3\t4\t6\t-1\t-1\t-1\t0.000\t0.000\t0.000\t0.000\t1\t0.000\ttrue\tfalse\t1\n",
+        )
+        .unwrap();
+        let err = ChannelJunctionAngles::new()
+            .run(args, "", false)
+            .unwrap_err();
+        assert!(err.to_string().contains("Link 34"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,1003 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use serde_json::json;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::algorithms::{d8_path_length, D8Walker};
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool is a diagnostic for DEM conditioning. Aggressive depression breaching across flat
+/// areas can carve unnaturally straight stream links that run side by side, a short distance
+/// apart, which later break hillslope delineation. The tool flags the links of a stream network
+/// that look like such artifacts, so that a workflow can decide whether the conditioning step needs
+/// different parameters. It reads the network and writes reports; it does not modify it.
+///
+/// The network is defined by the positive, non-nodata cells of the streams raster (`--streams`)
+/// and the D8 pointer (`--d8_pntr`). Links are traced from each channel head and junction down to
+/// the next junction, as in `StreamLinkLengthAndSinuosity`, and are numbered from 1 in row-major
+/// order of their upstream-most cells. The length and sinuosity of a link are also measured as in
+/// that tool, and its direction is the vector from its upstream-most to its downstream-most cell.
+/// A link is flagged when it is:
+///
+/// 1. straight, i.e. its sinuosity is at most `--max_sinuosity` (default 1.05);
+/// 2. long, i.e. its length is at least `--min_length` (default 100, in map units); and
+/// 3. parallel to another straight, long link: their directions differ by at most `--max_angle`
+///    degrees (default 10), they lie alongside each other (their extents along the shared direction
+///    overlap), and the closest cell centres of the two are no more than `--max_distance` apart
+///    (default 60, in map units).
+///
+/// A link and its downstream continuation meet end to end, so they never overlap alongside each
+/// other and are not flagged, however straight the channel. Note that a D8 path cannot follow an
+/// arbitrary straight line exactly; a straight channel at 22.5 degrees to the grid has a sinuosity
+/// of about 1.08, so `--max_sinuosity` may need raising to catch artifacts that are not aligned
+/// with the grid.
+///
+/// The output (`--output`) is a tab-separated table with one row per flagged link and the columns
+/// `link_id`, `length`, `chord`, `sinuosity`, `azimuth` (the direction of flow, in degrees
+/// clockwise from north), `partners` (the comma-separated IDs of the links it parallels),
+/// `nearest_partner`, `distance` and `angle` (the partner with the closest cells, and their
+/// separation and difference in direction), the coordinates of the upstream and downstream end
+/// cells, and `num_cells`. When `--output_vector` is given, the flagged links are also written as a
+/// line Shapefile (or GeoJSON, by extension) with the `LINK_ID`, `LENGTH`, `SINUOSITY`, `AZIMUTH`,
+/// `PARTNERS`, `PARTNER`, `DISTANCE` and `ANGLE` attributes, where `PARTNERS` is the number of
+/// partners. The optional summary (`--summary`) counts the links, the straight and long links, the
+/// flagged links, the parallel pairs, and the total length of the flagged links; it is a
+/// tab-separated table, or a JSON file that also records the inputs and thresholds when the file
+/// name ends in `.json`. The streams raster must share the geometry of the pointer, which uses the
+/// Whitebox scheme unless `--esri_pntr` is specified.
+///
+/// # See Also
+/// `StreamLinkLengthAndSinuosity`, `ChannelJunctionAngles`, `BreachDepressionsLeastCost`,
+/// `IdentifyFlatAreasAndPits`
+pub struct DetectParallelStreamArtifacts {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl DetectParallelStreamArtifacts {
+    pub fn new() -> DetectParallelStreamArtifacts {
+        // public constructor
+        let name = "DetectParallelStreamArtifacts".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Flags straight, parallel stream links that suggest artifacts of DEM conditioning."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file (positive values are stream cells).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Sinuosity".to_owned(),
+            flags: vec!["--max_sinuosity".to_owned()],
+            description: "Largest sinuosity (length / chord) of a link considered straight."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("1.05".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Link Length".to_owned(),
+            flags: vec!["--min_length".to_owned()],
+            description: "Shortest link considered, in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("100.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Distance".to_owned(),
+            flags: vec!["--max_distance".to_owned()],
+            description: "Largest separation of two parallel links, in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("60.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Angle".to_owned(),
+            flags: vec!["--max_angle".to_owned()],
+            description: "Largest difference in direction of two parallel links, in degrees."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("10.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated table of the flagged links.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Flagged Links File (optional)".to_owned(),
+            flags: vec!["--output_vector".to_owned()],
+            description:
+                "Optional output lines vector file (*.shp, *.geojson) of the flagged links."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary File (optional)".to_owned(),
+            flags: vec!["--summary".to_owned()],
+            description:
+                "Optional output tab-separated table, or JSON file (*.json), of the link counts."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --streams=streams.tif --d8_pntr=D8.tif -o=parallel_links.tsv
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --streams=streams.tif --d8_pntr=D8.tif --max_sinuosity=1.08 --min_length=150 --max_distance=90 --max_angle=15 -o=parallel_links.tsv --output_vector=parallel_links.shp --summary=parallel_links.json", short_exe, name).replace("*", &sep);
+
+        DetectParallelStreamArtifacts {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for DetectParallelStreamArtifacts {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut streams_file = String::new();
+        let mut d8_file = String::new();
+        let mut thresholds = Thresholds::default();
+        let mut output_file = String::new();
+        let mut vector_file = String::new();
+        let mut summary_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = || {
+                if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                }
+            };
+            let number = |flag: &str| {
+                let value = value();
+                value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Invalid --{} value: {}", flag, value),
+                    )
+                })
+            };
+            if flag_val == "-streams" {
+                streams_file = value();
+            } else if flag_val == "-d8_pntr" {
+                d8_file = value();
+            } else if flag_val == "-max_sinuosity" {
+                thresholds.max_sinuosity = number("max_sinuosity")?;
+            } else if flag_val == "-min_length" {
+                thresholds.min_length = number("min_length")?;
+            } else if flag_val == "-max_distance" {
+                thresholds.max_distance = number("max_distance")?;
+            } else if flag_val == "-max_angle" {
+                thresholds.max_angle = number("max_angle")?;
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = value();
+            } else if flag_val == "-output_vector" {
+                vector_file = value();
+            } else if flag_val == "-summary" {
+                summary_file = value();
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if !(1f64..).contains(&thresholds.max_sinuosity) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--max_sinuosity must be at least 1.",
+            ));
+        }
+        if !(0f64..).contains(&thresholds.min_length)
+            || !(0f64..).contains(&thresholds.max_distance)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--min_length and --max_distance must not be negative.",
+            ));
+        }
+        if !(0f64..90f64).contains(&thresholds.max_angle) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--max_angle must be at least 0 and less than 90 degrees.",
+            ));
+        }
+        let vector_file = if vector_file.trim().is_empty() {
+            None
+        } else {
+            // check the vector format before doing any work
+            VectorFormat::from_file_name(&vector_file)?;
+            Some(resolve_path(working_directory, &vector_file))
+        };
+
+        streams_file = resolve_path(working_directory, &streams_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        output_file = resolve_path(working_directory, &output_file);
+        if !summary_file.is_empty() {
+            summary_file = resolve_path(working_directory, &summary_file);
+        }
+        check_rasters_share_geometry(
+            &[("--d8_pntr", &d8_file), ("--streams", &streams_file)],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let streams = Raster::new(&streams_file, "r")?;
+        let pntr = Raster::new(&d8_file, "r")?;
+
+        let start = Instant::now();
+
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            esri_style,
+            |row, col| pntr.get_value(row, col),
+        );
+        let streams_nodata = streams.configs.nodata;
+        let is_stream = |row: isize, col: isize| {
+            let z = streams.get_value(row, col);
+            z != streams_nodata && z > 0f64
+        };
+        if verbose {
+            println!("Tracing stream links...")
+        };
+        let links = measure_links(
+            walker.stream_links(is_stream),
+            streams.configs.resolution_x,
+            streams.configs.resolution_y,
+        );
+        let pairs = find_parallel_pairs(&links, &thresholds);
+        let flagged = flagged_links(&links, &pairs);
+        let summary = Summary {
+            num_links: links.len(),
+            num_candidates: links.iter().filter(|l| thresholds.is_candidate(l)).count(),
+            num_flagged: flagged.len(),
+            num_pairs: pairs.len(),
+            flagged_length: flagged.iter().map(|f| links[f.link].length).sum(),
+        };
+
+        if verbose {
+            println!(
+                "Flagged {} of {} stream links ({} straight and long links, {} parallel pairs).",
+                summary.num_flagged, summary.num_links, summary.num_candidates, summary.num_pairs
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        write_flagged_to_tsv(&flagged, &links, &streams, &output_file)?;
+
+        if let Some(vector_file) = vector_file {
+            let mut output = VectorWriter::new(&vector_file, ShapeType::PolyLine)?;
+            output.projection = streams.configs.coordinate_ref_system_wkt.clone();
+            if streams.configs.epsg_code != 0 {
+                output.epsg = Some(streams.configs.epsg_code);
+            }
+            output.add_field(&AttributeField::new(
+                "LINK_ID",
+                FieldDataType::Int,
+                9u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "LENGTH",
+                FieldDataType::Real,
+                12u8,
+                3u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "SINUOSITY",
+                FieldDataType::Real,
+                8u8,
+                4u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "AZIMUTH",
+                FieldDataType::Real,
+                8u8,
+                3u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "PARTNERS",
+                FieldDataType::Int,
+                4u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "PARTNER",
+                FieldDataType::Int,
+                9u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "DISTANCE",
+                FieldDataType::Real,
+                12u8,
+                3u8,
+            ));
+            output.add_field(&AttributeField::new("ANGLE", FieldDataType::Real, 8u8, 3u8));
+            for f in &flagged {
+                let link = &links[f.link];
+                let points: Vec<Point2D> = link
+                    .cells
+                    .iter()
+                    .map(|&(row, col)| {
+                        Point2D::new(streams.get_x_from_column(col), streams.get_y_from_row(row))
+                    })
+                    .collect();
+                let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
+                sfg.add_part(&points);
+                output.add_record(
+                    sfg,
+                    vec![
+                        FieldData::Int(link.id as i32),
+                        FieldData::Real(link.length),
+                        FieldData::Real(link.sinuosity),
+                        FieldData::Real(link.azimuth()),
+                        FieldData::Int(f.partners.len() as i32),
+                        FieldData::Int(links[f.nearest.partner].id as i32),
+                        FieldData::Real(f.nearest.distance),
+                        FieldData::Real(f.nearest.angle),
+                    ],
+                );
+            }
+            output.write()?;
+        }
+
+        if !summary_file.is_empty() {
+            if verbose {
+                println!("Writing summary to {}.", summary_file);
+            }
+            if summary_file.to_lowercase().ends_with(".json") {
+                let report = json!({
+                    "streams": streams_file,
+                    "d8_pntr": d8_file,
+                    "max_sinuosity": thresholds.max_sinuosity,
+                    "min_length": thresholds.min_length,
+                    "max_distance": thresholds.max_distance,
+                    "max_angle": thresholds.max_angle,
+                    "num_links": summary.num_links,
+                    "num_candidates": summary.num_candidates,
+                    "num_flagged": summary.num_flagged,
+                    "num_pairs": summary.num_pairs,
+                    "flagged_length": summary.flagged_length,
+                });
+                let mut file = File::create(&summary_file)?;
+                file.write_all(serde_json::to_string_pretty(&report)?.as_bytes())?;
+            } else {
+                write_summary_to_tsv(&summary, &summary_file)?;
+            }
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The thresholds a link must meet to be flagged.
+struct Thresholds {
+    max_sinuosity: f64,
+    min_length: f64,
+    max_distance: f64,
+    max_angle: f64,
+}
+
+impl Default for Thresholds {
+    fn default() -> Thresholds {
+        Thresholds {
+            max_sinuosity: 1.05,
+            min_length: 100f64,
+            max_distance: 60f64,
+            max_angle: 10f64,
+        }
+    }
+}
+
+impl Thresholds {
+    /// Returns true if the link is straight and long enough to be a candidate artifact.
+    fn is_candidate(&self, link: &LinkGeometry) -> bool {
+        link.chord > 0f64 && link.sinuosity <= self.max_sinuosity && link.length >= self.min_length
+    }
+}
+
+/// A link of the stream network, with its cells in flow order, and its shape. `direction` is the
+/// unit vector from the first cell to the last, in map coordinates (x east, y north).
+struct LinkGeometry {
+    id: usize,
+    cells: Vec<(isize, isize)>,
+    length: f64,
+    chord: f64,
+    sinuosity: f64,
+    direction: (f64, f64),
+    resolution: (f64, f64),
+}
+
+impl LinkGeometry {
+    /// The position of a cell centre relative to the grid origin, in map units.
+    fn position(&self, cell: (isize, isize)) -> (f64, f64) {
+        (
+            cell.1 as f64 * self.resolution.0,
+            -(cell.0 as f64) * self.resolution.1,
+        )
+    }
+
+    /// The direction of flow, in degrees clockwise from north.
+    fn azimuth(&self) -> f64 {
+        let azimuth = self.direction.0.atan2(self.direction.1).to_degrees();
+        if azimuth < 0f64 {
+            azimuth + 360f64
+        } else {
+            azimuth
+        }
+    }
+}
+
+/// Measures the traced links, numbering them from 1. A link's sinuosity is its length divided by
+/// its chord, or 1 for a single-cell link.
+fn measure_links(
+    links: Vec<Vec<(isize, isize)>>,
+    resolution_x: f64,
+    resolution_y: f64,
+) -> Vec<LinkGeometry> {
+    links
+        .into_iter()
+        .enumerate()
+        .map(|(i, cells)| {
+            let length = d8_path_length(&cells, resolution_x, resolution_y);
+            let (us_row, us_col) = cells[0];
+            let (ds_row, ds_col) = cells[cells.len() - 1];
+            let dx = (ds_col - us_col) as f64 * resolution_x;
+            let dy = (us_row - ds_row) as f64 * resolution_y;
+            let chord = (dx * dx + dy * dy).sqrt();
+            let (sinuosity, direction) = if chord > 0f64 {
+                (length / chord, (dx / chord, dy / chord))
+            } else {
+                (1f64, (0f64, 0f64))
+            };
+            LinkGeometry {
+                id: i + 1,
+                cells,
+                length,
+                chord,
+                sinuosity,
+                direction,
+                resolution: (resolution_x, resolution_y),
+            }
+        })
+        .collect()
+}
+
+/// Two candidate links, given by their indices, that run parallel to each other, with the
+/// distance between their closest cell centres and the angle between their directions.
+#[derive(Clone, Copy, Debug)]
+struct ParallelPair {
+    a: usize,
+    b: usize,
+    distance: f64,
+    angle: f64,
+}
+
+/// Finds the pairs of candidate links that are parallel: their directions differ by at most
+/// `max_angle`, their extents along the mean of the two directions overlap, and their closest cells
+/// are at most `max_distance` apart. Pairs are returned in order of their links.
+fn find_parallel_pairs(links: &[LinkGeometry], thresholds: &Thresholds) -> Vec<ParallelPair> {
+    let candidates: Vec<usize> = (0..links.len())
+        .filter(|&i| thresholds.is_candidate(&links[i]))
+        .collect();
+    // the bounding box of each candidate's cell centres, for a quick distance check
+    let bounds: Vec<(f64, f64, f64, f64)> = candidates
+        .iter()
+        .map(|&i| {
+            let link = &links[i];
+            link.cells.iter().fold(
+                (
+                    f64::INFINITY,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NEG_INFINITY,
+                ),
+                |b, &cell| {
+                    let (x, y) = link.position(cell);
+                    (b.0.min(x), b.1.min(y), b.2.max(x), b.3.max(y))
+                },
+            )
+        })
+        .collect();
+    let mut pairs = vec![];
+    for (m, &a) in candidates.iter().enumerate() {
+        for (n, &b) in candidates.iter().enumerate().skip(m + 1) {
+            let (ba, bb) = (bounds[m], bounds[n]);
+            let gap_x = (bb.0 - ba.2).max(ba.0 - bb.2).max(0f64);
+            let gap_y = (bb.1 - ba.3).max(ba.1 - bb.3).max(0f64);
+            if (gap_x * gap_x + gap_y * gap_y).sqrt() > thresholds.max_distance {
+                continue;
+            }
+            let (la, lb) = (&links[a], &links[b]);
+            let dot = la.direction.0 * lb.direction.0 + la.direction.1 * lb.direction.1;
+            let angle = dot.clamp(-1f64, 1f64).acos().to_degrees();
+            if angle > thresholds.max_angle {
+                continue;
+            }
+            // the extents of the two links along the mean direction
+            let mean = (
+                la.direction.0 + lb.direction.0,
+                la.direction.1 + lb.direction.1,
+            );
+            let extent = |link: &LinkGeometry| {
+                let project = |cell| {
+                    let (x, y) = link.position(cell);
+                    x * mean.0 + y * mean.1
+                };
+                let (us, ds) = (
+                    project(link.cells[0]),
+                    project(link.cells[link.cells.len() - 1]),
+                );
+                (us.min(ds), us.max(ds))
+            };
+            let (ea, eb) = (extent(la), extent(lb));
+            if ea.1.min(eb.1) - ea.0.max(eb.0) <= 0f64 {
+                continue;
+            }
+            let mut distance = f64::INFINITY;
+            for &ca in &la.cells {
+                let (xa, ya) = la.position(ca);
+                for &cb in &lb.cells {
+                    let (xb, yb) = lb.position(cb);
+                    distance = distance.min(((xa - xb).powi(2) + (ya - yb).powi(2)).sqrt());
+                }
+            }
+            if distance <= thresholds.max_distance {
+                pairs.push(ParallelPair {
+                    a,
+                    b,
+                    distance,
+                    angle,
+                });
+            }
+        }
+    }
+    pairs
+}
+
+/// The nearest of a flagged link's partners, by index.
+#[derive(Clone, Copy, Debug)]
+struct Partner {
+    partner: usize,
+    distance: f64,
+    angle: f64,
+}
+
+/// A flagged link, by index, with the indices of the links it parallels, in order, and the nearest
+/// of them.
+struct FlaggedLink {
+    link: usize,
+    partners: Vec<usize>,
+    nearest: Partner,
+}
+
+/// Collects the links that belong to one or more parallel pairs, in order of their IDs. The
+/// nearest partner is the one with the smallest distance, the lower ID winning ties.
+fn flagged_links(links: &[LinkGeometry], pairs: &[ParallelPair]) -> Vec<FlaggedLink> {
+    let mut flagged: Vec<Option<FlaggedLink>> = (0..links.len()).map(|_| None).collect();
+    for pair in pairs {
+        for (link, partner) in [(pair.a, pair.b), (pair.b, pair.a)] {
+            let candidate = Partner {
+                partner,
+                distance: pair.distance,
+                angle: pair.angle,
+            };
+            match flagged[link] {
+                Some(ref mut f) => {
+                    f.partners.push(partner);
+                    if candidate.distance < f.nearest.distance
+                        || (candidate.distance == f.nearest.distance && partner < f.nearest.partner)
+                    {
+                        f.nearest = candidate;
+                    }
+                }
+                None => {
+                    flagged[link] = Some(FlaggedLink {
+                        link,
+                        partners: vec![partner],
+                        nearest: candidate,
+                    })
+                }
+            }
+        }
+    }
+    flagged
+        .into_iter()
+        .flatten()
+        .map(|mut f| {
+            f.partners.sort();
+            f
+        })
+        .collect()
+}
+
+/// The counts reported in the summary.
+struct Summary {
+    num_links: usize,
+    num_candidates: usize,
+    num_flagged: usize,
+    num_pairs: usize,
+    flagged_length: f64,
+}
+
+fn write_flagged_to_tsv(
+    flagged: &[FlaggedLink],
+    links: &[LinkGeometry],
+    streams: &Raster,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "link_id\tlength\tchord\tsinuosity\tazimuth\tpartners\tnearest_partner\tdistance\tangle\tus_x\tus_y\tds_x\tds_y\tnum_cells"
+    )?;
+    for f in flagged {
+        let link = &links[f.link];
+        let (us_row, us_col) = link.cells[0];
+        let (ds_row, ds_col) = link.cells[link.cells.len() - 1];
+        let partners: Vec<String> = f
+            .partners
+            .iter()
+            .map(|&p| links[p].id.to_string())
+            .collect();
+        writeln!(
+            &mut file,
+            "{}\t{:.3}\t{:.3}\t{:.4}\t{:.3}\t{}\t{}\t{:.3}\t{:.3}\t{}\t{}\t{}\t{}\t{}",
+            link.id,
+            link.length,
+            link.chord,
+            link.sinuosity,
+            link.azimuth(),
+            partners.join(","),
+            links[f.nearest.partner].id,
+            f.nearest.distance,
+            f.nearest.angle,
+            streams.get_x_from_column(us_col),
+            streams.get_y_from_row(us_row),
+            streams.get_x_from_column(ds_col),
+            streams.get_y_from_row(ds_row),
+            link.cells.len()
+        )?;
+    }
+    file.flush()
+}
+
+fn write_summary_to_tsv(summary: &Summary, file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "num_links\tnum_candidates\tnum_flagged\tnum_pairs\tflagged_length"
+    )?;
+    writeln!(
+        &mut file,
+        "{}\t{}\t{}\t{}\t{:.3}",
+        summary.num_links,
+        summary.num_candidates,
+        summary.num_flagged,
+        summary.num_pairs,
+        summary.flagged_length
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::DetectParallelStreamArtifacts;
+    use super::{find_parallel_pairs, flagged_links, measure_links, Thresholds};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    fn column(col: isize, rows: std::ops::Range<isize>) -> Vec<(isize, isize)> {
+        rows.map(|r| (r, col)).collect()
+    }
+
+    #[test]
+    fn test_parallel_pairs() {
+        // Three straight links flowing south, in columns 0, 2 and 9 of a grid of 10 m cells; a
+        // meandering link beside the first; and a short link in column 4.
+        let links = measure_links(
+            vec![
+                column(0, 0..15),
+                column(2, 3..20),
+                column(9, 0..15),
+                vec![
+                    (0, 5),
+                    (1, 6),
+                    (2, 5),
+                    (3, 6),
+                    (4, 5),
+                    (5, 6),
+                    (6, 5),
+                    (7, 6),
+                ],
+                column(4, 0..5),
+            ],
+            10f64,
+            10f64,
+        );
+        assert_eq!(links[0].length, 140f64);
+        assert_eq!((links[0].sinuosity, links[0].azimuth()), (1f64, 180f64));
+        assert!(links[3].sinuosity > 1.3);
+
+        let thresholds = Thresholds {
+            max_sinuosity: 1.05,
+            min_length: 100f64,
+            max_distance: 30f64,
+            max_angle: 10f64,
+        };
+        let pairs = find_parallel_pairs(&links, &thresholds);
+        assert_eq!(pairs.len(), 1);
+        assert_eq!((pairs[0].a, pairs[0].b), (0, 1));
+        assert_eq!((pairs[0].distance, pairs[0].angle), (20f64, 0f64));
+
+        // Column 9 is 70 m from column 2.
+        let wide = Thresholds {
+            max_distance: 70f64,
+            ..thresholds
+        };
+        let pairs = find_parallel_pairs(&links, &wide);
+        assert_eq!(pairs.len(), 2);
+        let flagged = flagged_links(&links, &pairs);
+        let summary: Vec<(usize, Vec<usize>, usize)> = flagged
+            .iter()
+            .map(|f| (f.link, f.partners.clone(), f.nearest.partner))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(0, vec![1], 1), (1, vec![0, 2], 0), (2, vec![1], 1)]
+        );
+    }
+
+    #[test]
+    fn test_end_to_end_and_converging_links() {
+        // A straight channel broken by a junction is not parallel to itself, but two straight
+        // tributaries converging on a junction from the same direction are.
+        let links = measure_links(
+            vec![
+                column(0, 0..12),
+                column(0, 11..24),
+                vec![
+                    (0, 3),
+                    (1, 3),
+                    (2, 3),
+                    (3, 3),
+                    (4, 3),
+                    (5, 3),
+                    (6, 3),
+                    (7, 3),
+                    (8, 3),
+                    (9, 3),
+                    (10, 3),
+                    (11, 2),
+                    (11, 1),
+                    (11, 0),
+                ],
+                (0..12).map(|r| (r, 13 - r)).collect(),
+            ],
+            10f64,
+            10f64,
+        );
+        let thresholds = Thresholds {
+            max_sinuosity: 1.2,
+            max_angle: 20f64,
+            ..Thresholds::default()
+        };
+        let pairs = find_parallel_pairs(&links, &thresholds);
+        let found: Vec<(usize, usize)> = pairs.iter().map(|p| (p.a, p.b)).collect();
+        assert_eq!(found, vec![(0, 2)]);
+    }
+
+    #[test]
+    fn test_detect_parallel_stream_artifacts() {
+        let dir = temp_dir("detect_parallel_stream_artifacts").unwrap();
+
+        // Two straight channels flow south down columns 1 and 3 of a 20 by 8 grid of 10 m cells,
+        // and a third down column 7 is 40 m from the nearer of them.
+        let pntr = GridFixture::new(20, 8, |_, _| 8f64); // south
+        let streams = GridFixture::new(20, 8, |r, c| {
+            if (c == 1 || c == 3 || c == 7) && r >= 2 {
+                1f64
+            } else {
+                0f64
+            }
+        });
+        grid_fixture_raster(&format!("{}/d8.tif", dir), &pntr)
+            .write()
+            .unwrap();
+        grid_fixture_raster(&format!("{}/streams.tif", dir), &streams)
+            .write()
+            .unwrap();
+
+        let args = vec![
+            format!("--streams={}/streams.tif", dir),
+            format!("--d8_pntr={}/d8.tif", dir),
+            "--max_distance=30".to_string(),
+            format!("--output={}/flagged.tsv", dir),
+            format!("--output_vector={}/flagged.geojson", dir),
+            format!("--summary={}/summary.json", dir),
+        ];
+        DetectParallelStreamArtifacts::new()
+            .run(args.clone(), "", false)
+            .unwrap();
+
+        let rows = read_tsv(&format!("{}/flagged.tsv", dir)).unwrap();
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0][5], "partners");
+        assert_eq!(
+            rows[1],
+            vec![
+                "1", "170.000", "170.000", "1.0000", "180.000", "2", "2", "20.000", "0.000", "15",
+                "175", "15", "5", "18"
+            ]
+        );
+        assert_eq!(rows[2][..2], ["2", "170.000"]);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(format!("{}/flagged.geojson", dir)).unwrap())
+                .unwrap();
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[1]["properties"]["PARTNER"], 1);
+        assert_eq!(features[1]["properties"]["DISTANCE"].as_f64(), Some(20f64));
+
+        let summary: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(format!("{}/summary.json", dir)).unwrap())
+                .unwrap();
+        assert_eq!(summary["num_links"], 3);
+        assert_eq!(summary["num_candidates"], 3);
+        assert_eq!(summary["num_flagged"], 2);
+        assert_eq!(summary["num_pairs"], 1);
+        assert_eq!(summary["flagged_length"].as_f64(), Some(340f64));
+
+        // Invalid thresholds are rejected.
+        let mut bad = args.clone();
+        bad.push("--max_angle=90".to_string());
+        assert!(DetectParallelStreamArtifacts::new()
+            .run(bad, "", false)
+            .is_err());
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,643 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use crate::tools::*;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::f64;
+use std::io::Error;
+use whitebox_common::error::WbtError;
+use whitebox_common::structures::BoundingBox;
+use whitebox_common::utils::resolve_path;
+
+/// The arguments of a `FindOutlet` run, as parsed from the command line.
+pub(super) struct FindOutletArgs {
+    pub(super) d8_file: String,
+    pub(super) streams_file: String,
+    pub(super) flow_accum_file: String,
+    pub(super) accum_threshold: Option<f64>,
+    pub(super) stream_threshold: f64,
+    pub(super) min_stream_order: usize,
+    pub(super) watershed_file: String,
+    pub(super) output_file: String,
+    pub(super) esri_style: bool,
+    pub(super) auto_pntr: bool,
+    pub(super) skip_crs_check: bool,
+    pub(super) requested_lng_lat: Vec<(f64, f64)>,
+    pub(super) requested_row_col: Vec<(isize, isize)>,
+    pub(super) requested_xy: Vec<(f64, f64)>,
+    pub(super) pour_pts_file: String,
+    pub(super) snap_radius: Option<usize>,
+    pub(super) snap_to_stream: bool,
+    pub(super) sca: bool,
+    pub(super) area_ratio_tolerance: f64,
+    pub(super) snap_max_radius: Option<usize>,
+    pub(super) max_candidates_limit: usize,
+    pub(super) max_steps_limit: Option<usize>,
+    pub(super) all_outlets: bool,
+    pub(super) outlet_within_mask: bool,
+    pub(super) snap_to_junction: bool,
+    pub(super) junction_window: usize,
+    pub(super) mask_erosion: usize,
+    pub(super) bbox: Option<BoundingBox>,
+    pub(super) score_candidates: Option<usize>,
+    pub(super) dem_file: String,
+    pub(super) slope_steps: Option<usize>,
+    pub(super) downstream_preview: Option<usize>,
+    pub(super) trace_path_file: String,
+    pub(super) diagnostics_file: String,
+    pub(super) summary_csv_file: String,
+    pub(super) junctions_file: String,
+    pub(super) output_raster_file: String,
+    pub(super) compress: Option<bool>,
+}
+
+impl FindOutletArgs {
+    /// Parses the arguments of a run, failing with an `argument_error` on a missing or
+    /// unparsable argument or a combination of options that cannot be used together.
+    pub(super) fn parse(args: &[String]) -> Result<FindOutletArgs, Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut flow_accum_file = String::new();
+        let mut accum_threshold: Option<f64> = None;
+        let mut stream_threshold = 0f64;
+        let mut min_stream_order = 0usize;
+        let mut watershed_file = String::new();
+        let mut output_file = String::new();
+        let mut esri_style = false;
+        let mut auto_pntr = false;
+        let mut skip_crs_check = false;
+        let mut requested_lng_lat: Vec<(f64, f64)> = vec![];
+        let mut requested_row_col: Vec<(isize, isize)> = vec![];
+        let mut requested_xy: Vec<(f64, f64)> = vec![];
+        let mut pour_pts_file = String::new();
+        let mut snap_radius: Option<usize> = None;
+        let mut snap_to_stream = false;
+        let mut sca = false;
+        let mut area_ratio_tolerance = 0.25f64;
+        let mut snap_max_radius: Option<usize> = None;
+        let mut max_candidates_limit = 512usize;
+        let mut max_steps_limit: Option<usize> = None;
+        let mut all_outlets = false;
+        let mut outlet_within_mask = false;
+        let mut snap_to_junction = false;
+        let mut junction_window = 3usize;
+        let mut mask_erosion = 0usize;
+        let mut bbox: Option<BoundingBox> = None;
+        let mut score_candidates: Option<usize> = None;
+        let mut dem_file = String::new();
+        let mut slope_steps: Option<usize> = None;
+        let mut downstream_preview: Option<usize> = None;
+        let mut trace_path_file = String::new();
+        let mut diagnostics_file = String::new();
+        let mut summary_csv_file = String::new();
+        let mut junctions_file = String::new();
+        let mut output_raster_file = String::new();
+        let mut compress: Option<bool> = None;
+
+        if args.is_empty() {
+            return Err(WbtError::invalid_argument("Tool run with no parameters.").into());
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace('\"', "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split('=');
+            let vec = cmd.collect::<Vec<&str>>();
+            let keyval = vec.len() > 1;
+            let flag = vec[0].to_lowercase();
+            if flag == "-d8_pntr" || flag == "--d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-streams" || flag == "--streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-flow_accum" || flag == "--flow_accum" {
+                flow_accum_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-accum_threshold" || flag == "--accum_threshold" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                accum_threshold = Some(value.trim().parse::<f64>().map_err(|_| {
+                    WbtError::invalid_argument(format!(
+                        "Unable to parse --accum_threshold value '{}'.",
+                        value
+                    ))
+                })?);
+            } else if flag == "-sca" || flag == "--sca" {
+                sca = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-area_ratio_tolerance" || flag == "--area_ratio_tolerance" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                area_ratio_tolerance = value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|v| *v >= 0f64)
+                .ok_or_else(|| {
+                    WbtError::invalid_argument(format!(
+                        "Unable to parse --area_ratio_tolerance value '{}'; expected a non-negative number.",
+                        value
+                    ))
+                })?;
+            } else if flag == "-stream_threshold" || flag == "--stream_threshold" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                stream_threshold = value
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|v| v.is_finite())
+                    .ok_or_else(|| {
+                        WbtError::invalid_argument(format!(
+                            "Unable to parse --stream_threshold value '{}'.",
+                            value
+                        ))
+                    })?;
+            } else if flag == "-min_stream_order" || flag == "--min_stream_order" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                min_stream_order = value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --min_stream_order value '{}'; expected a non-negative stream order.",
+                        value
+                    ))
+            })?;
+            } else if flag == "-watershed" || flag == "--watershed" {
+                watershed_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-o" || flag == "--output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "--esri_pntr" || flag == "-esri_pntr" || flag == "--esri_style" {
+                esri_style = true;
+            } else if flag == "--requested_outlet_lng_lat" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                requested_lng_lat = parse_pair_list(
+                    &value,
+                    "--requested_outlet_lng_lat",
+                    "lon,lat",
+                    ["longitude", "latitude"],
+                )?;
+            } else if flag == "--requested_outlet_row_col" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                requested_row_col = parse_pair_list(
+                    &value,
+                    "--requested_outlet_row_col",
+                    "row,col",
+                    ["row", "column"],
+                )?;
+            } else if flag == "--requested_outlet_xy" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                requested_xy = parse_pair_list(
+                    &value,
+                    "--requested_outlet_xy",
+                    "easting,northing",
+                    ["easting", "northing"],
+                )?;
+            } else if flag == "-pour_pts" || flag == "--pour_pts" {
+                pour_pts_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-snap_radius" || flag == "--snap_radius" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                snap_radius = Some(value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --snap_radius value '{}'; expected a non-negative number of cells.",
+                        value
+                    ))
+            })?);
+            } else if flag == "-snap_to_stream" || flag == "--snap_to_stream" {
+                snap_to_stream = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-snap_max_radius" || flag == "--snap_max_radius" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                snap_max_radius = Some(value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --snap_max_radius value '{}'; expected a non-negative number of cells.",
+                        value
+                    ))
+            })?);
+            } else if flag == "-max_candidates"
+                || flag == "--max_candidates"
+                || flag == "-max_steps"
+                || flag == "--max_steps"
+                || flag == "-score_candidates"
+                || flag == "--score_candidates"
+            {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                let limit = value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&v| v > 0)
+                    .ok_or_else(|| {
+                        WbtError::invalid_argument(format!(
+                            "Unable to parse --{} value '{}'; expected a positive integer.",
+                            flag.trim_start_matches('-'),
+                            value
+                        ))
+                    })?;
+                if flag.ends_with("max_candidates") {
+                    max_candidates_limit = limit;
+                } else if flag.ends_with("score_candidates") {
+                    score_candidates = Some(limit);
+                } else {
+                    max_steps_limit = Some(limit);
+                }
+            } else if flag == "-auto_pntr" || flag == "--auto_pntr" {
+                auto_pntr = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-skip_crs_check" || flag == "--skip_crs_check" {
+                skip_crs_check = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-all_outlets" || flag == "--all_outlets" {
+                all_outlets = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-outlet_within_mask" || flag == "--outlet_within_mask" {
+                outlet_within_mask = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-snap_to_junction" || flag == "--snap_to_junction" {
+                snap_to_junction = vec.len() == 1 || !vec[1].to_lowercase().contains("false");
+            } else if flag == "-junction_window" || flag == "--junction_window" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                junction_window = value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --junction_window value '{}'; expected a non-negative number of cells.",
+                        value
+                    ))
+            })?;
+            } else if flag == "-mask_erosion" || flag == "--mask_erosion" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                mask_erosion = value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --mask_erosion value '{}'; expected a non-negative number of cells.",
+                        value
+                    ))
+            })?;
+            } else if flag == "-bbox" || flag == "--bbox" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                bbox = Some(parse_bbox(&value)?);
+            } else if flag == "-dem" || flag == "--dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-slope_steps" || flag == "--slope_steps" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                slope_steps = Some(
+                value
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|&v| v > 0)
+                    .ok_or_else(|| {
+                        WbtError::invalid_argument(format!(
+                                "Unable to parse --slope_steps value '{}'; expected a positive number of steps.",
+                                value
+                            ))
+                    })?,
+            );
+            } else if flag == "-downstream_preview" || flag == "--downstream_preview" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                downstream_preview = Some(value.trim().parse::<usize>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                        "Unable to parse --downstream_preview value '{}'; expected a non-negative number of steps.",
+                        value
+                    ))
+            })?);
+            } else if flag == "-trace_path" || flag == "--trace_path" {
+                trace_path_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-diagnostics" || flag == "--diagnostics" {
+                diagnostics_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-summary_csv" || flag == "--summary_csv" {
+                summary_csv_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-junctions" || flag == "--junctions" {
+                junctions_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-output_raster" || flag == "--output_raster" {
+                output_raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag == "-compress" || flag == "--compress" {
+                compress = Some(vec.len() == 1 || !vec[1].to_lowercase().contains("false"));
+            }
+        }
+
+        if d8_file.is_empty() {
+            return Err(WbtError::invalid_argument(
+                "Input D8 pointer raster (--d8_pntr) not specified.",
+            )
+            .into());
+        }
+        if streams_file.is_empty() && flow_accum_file.is_empty() {
+            return Err(WbtError::invalid_argument(
+                "Input streams raster (--streams) not specified.",
+            )
+            .into());
+        }
+        if streams_file.is_empty() && accum_threshold.is_none() {
+            return Err(WbtError::invalid_argument("An accumulation threshold (--accum_threshold) is required to derive streams from --flow_accum.").into());
+        }
+        let location_flags: Vec<&str> = [
+            ("--requested_outlet_lng_lat", !requested_lng_lat.is_empty()),
+            ("--requested_outlet_row_col", !requested_row_col.is_empty()),
+            ("--requested_outlet_xy", !requested_xy.is_empty()),
+            ("--pour_pts", !pour_pts_file.is_empty()),
+        ]
+        .iter()
+        .filter(|(_, given)| *given)
+        .map(|(flag, _)| *flag)
+        .collect();
+        if location_flags.len() > 1 {
+            return Err(WbtError::invalid_argument(format!(
+            "The requested outlet was given with both {}; supply it with only one of --requested_outlet_lng_lat, --requested_outlet_row_col, --requested_outlet_xy, and --pour_pts.",
+            location_flags.join(" and ")
+        ))
+        .into());
+        }
+        let location_requested = !location_flags.is_empty();
+        if watershed_file.is_empty() && !location_requested {
+            return Err(WbtError::invalid_argument("Either --watershed must be supplied or a requested outlet location (--requested_outlet_lng_lat / --requested_outlet_row_col / --requested_outlet_xy / --pour_pts) must be provided.").into());
+        }
+        if all_outlets && (watershed_file.is_empty() || location_requested) {
+            return Err(WbtError::invalid_argument("Writing all outlets (--all_outlets) requires a watershed mask (--watershed) and cannot be combined with a requested outlet location.").into());
+        }
+        if score_candidates.is_some()
+            && (watershed_file.is_empty() || all_outlets || location_requested)
+        {
+            return Err(WbtError::invalid_argument("Scoring candidates (--score_candidates) requires a watershed mask (--watershed) and cannot be combined with --all_outlets or a requested outlet location.").into());
+        }
+        if mask_erosion > 0 && watershed_file.is_empty() {
+            return Err(WbtError::invalid_argument("Eroding the watershed mask (--mask_erosion) requires a watershed mask (--watershed).").into());
+        }
+        if outlet_within_mask && watershed_file.is_empty() {
+            return Err(WbtError::invalid_argument("Keeping the outlet within the watershed mask (--outlet_within_mask) requires a watershed mask (--watershed).").into());
+        }
+        if snap_radius.is_some() && flow_accum_file.is_empty() {
+            return Err(WbtError::invalid_argument("Snapping requested outlets (--snap_radius) requires a flow accumulation raster (--flow_accum).").into());
+        }
+        if sca && flow_accum_file.is_empty() {
+            return Err(WbtError::invalid_argument(
+            "Reading contributing areas (--sca) requires a flow accumulation raster (--flow_accum).",
+        )
+        .into());
+        }
+        if snap_to_stream && !location_requested {
+            return Err(WbtError::invalid_argument("Snapping to a stream cell (--snap_to_stream) requires a requested outlet location.").into());
+        }
+        if snap_max_radius.is_some() && !snap_to_stream {
+            return Err(WbtError::invalid_argument(
+                "The stream snap radius (--snap_max_radius) requires --snap_to_stream.",
+            )
+            .into());
+        }
+        if slope_steps.is_some() && dem_file.is_empty() {
+            return Err(WbtError::invalid_argument(
+                "The channel slope (--slope_steps) requires a DEM raster (--dem).",
+            )
+            .into());
+        }
+        if output_file.is_empty() {
+            return Err(WbtError::invalid_argument(
+                "Output GeoJSON file (--output) not specified.",
+            )
+            .into());
+        }
+
+        Ok(FindOutletArgs {
+            d8_file,
+            streams_file,
+            flow_accum_file,
+            accum_threshold,
+            stream_threshold,
+            min_stream_order,
+            watershed_file,
+            output_file,
+            esri_style,
+            auto_pntr,
+            skip_crs_check,
+            requested_lng_lat,
+            requested_row_col,
+            requested_xy,
+            pour_pts_file,
+            snap_radius,
+            snap_to_stream,
+            sca,
+            area_ratio_tolerance,
+            snap_max_radius,
+            max_candidates_limit,
+            max_steps_limit,
+            all_outlets,
+            outlet_within_mask,
+            snap_to_junction,
+            junction_window,
+            mask_erosion,
+            bbox,
+            score_candidates,
+            dem_file,
+            slope_steps,
+            downstream_preview,
+            trace_path_file,
+            diagnostics_file,
+            summary_csv_file,
+            junctions_file,
+            output_raster_file,
+            compress,
+        })
+    }
+
+    /// Resolves the input and output files against the working directory.
+    pub(super) fn resolve_paths(&mut self, working_directory: &str) {
+        self.d8_file = resolve_path(working_directory, &self.d8_file);
+        self.streams_file = resolve_path(working_directory, &self.streams_file);
+        self.flow_accum_file = resolve_path(working_directory, &self.flow_accum_file);
+        self.watershed_file = resolve_path(working_directory, &self.watershed_file);
+        self.output_file = resolve_path(working_directory, &self.output_file);
+        self.pour_pts_file = resolve_path(working_directory, &self.pour_pts_file);
+        self.dem_file = resolve_path(working_directory, &self.dem_file);
+        self.trace_path_file = resolve_path(working_directory, &self.trace_path_file);
+        self.diagnostics_file = resolve_path(working_directory, &self.diagnostics_file);
+        self.summary_csv_file = resolve_path(working_directory, &self.summary_csv_file);
+        self.junctions_file = resolve_path(working_directory, &self.junctions_file);
+        self.output_raster_file = resolve_path(working_directory, &self.output_raster_file);
+    }
+}
+
+/// The arguments of a run as a JSON object keyed by flag, without its leading dashes; flags
+/// given without a value are `true`.
+pub(super) fn argument_values(args: &[String]) -> JsonValue {
+    let mut values = JsonMap::new();
+    let mut i = 0;
+    while i < args.len() {
+        let arg = args[i].replace(['"', '\''], "");
+        i += 1;
+        let (flag, value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), json!(value)),
+            // A value follows its flag unless it is the next flag; negative numbers are values.
+            None => match args.get(i) {
+                Some(next)
+                    if !next.starts_with('-')
+                        || next[1..].starts_with(|c: char| c.is_ascii_digit() || c == '.') =>
+                {
+                    i += 1;
+                    (arg, json!(next.replace(['"', '\''], "")))
+                }
+                _ => (arg, json!(true)),
+            },
+        };
+        values.insert(flag.trim_start_matches('-').to_lowercase(), value);
+    }
+    JsonValue::Object(values)
+}
+
+/// Parses a semicolon-separated list of `a,b` pairs, e.g. `'-120.5,42.1;-120.6,42.0'`.
+pub(crate) fn parse_pair_list<T: std::str::FromStr>(
+    value: &str,
+    flag: &str,
+    format: &str,
+    names: [&str; 2],
+) -> Result<Vec<(T, T)>, Error> {
+    let mut pairs = vec![];
+    for item in value.split(';').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let parts: Vec<&str> = item.split(',').map(|s| s.trim()).collect();
+        if parts.len() != 2 {
+            return Err(WbtError::invalid_argument(format!(
+                "{} expects '{}'; received '{}'.",
+                flag, format, item
+            ))
+            .into());
+        }
+        let mut parsed = vec![];
+        for (part, name) in parts.iter().zip(names.iter()) {
+            parsed.push(part.parse::<T>().map_err(|_| {
+                WbtError::invalid_argument(format!(
+                    "Unable to parse {} '{}' for {}.",
+                    name, part, flag
+                ))
+            })?);
+        }
+        let b = parsed.pop().unwrap();
+        let a = parsed.pop().unwrap();
+        pairs.push((a, b));
+    }
+    if pairs.is_empty() {
+        return Err(WbtError::invalid_argument(format!(
+            "{} expects '{}'; received '{}'.",
+            flag, format, value
+        ))
+        .into());
+    }
+    Ok(pairs)
+}
+
+/// Parses a `--bbox` value, `minx,miny,maxx,maxy` in map coordinates.
+pub(super) fn parse_bbox(value: &str) -> Result<BoundingBox, Error> {
+    let coords: Vec<f64> = value
+        .split(',')
+        .map(|s| s.trim().parse::<f64>().ok().filter(|v| v.is_finite()))
+        .collect::<Option<Vec<f64>>>()
+        .filter(|coords| coords.len() == 4)
+        .ok_or_else(|| {
+            WbtError::invalid_argument(format!(
+                "Unable to parse --bbox value '{}'; expected 'minx,miny,maxx,maxy'.",
+                value
+            ))
+        })?;
+    if coords[0] >= coords[2] || coords[1] >= coords[3] {
+        return Err(WbtError::invalid_argument(format!(
+            "The --bbox value '{}' must have minx < maxx and miny < maxy.",
+            value
+        ))
+        .into());
+    }
+    Ok(BoundingBox::new(coords[0], coords[2], coords[1], coords[3]))
+}
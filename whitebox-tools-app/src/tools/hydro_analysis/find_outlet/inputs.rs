@@ -0,0 +1,474 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use super::args::FindOutletArgs;
+use super::trace::{is_stream_value, pointer_tables, StreamFilter};
+use crate::tools::hydro_analysis::raster_geometry::{check_geometry, header_configs};
+use geojson::{GeoJson, Geometry, Value as GeoValue};
+use std::f64;
+use std::io::Error;
+use std::ops::Range;
+use whitebox_common::algorithms::{point_in_poly, D8_DX, D8_DY};
+use whitebox_common::error::{ErrorContext, WbtError};
+use whitebox_common::structures::{Array2D, BoundingBox, Point2D};
+use whitebox_raster::*;
+use whitebox_vector::{ShapeType, Shapefile};
+
+/// The input rasters of a run, read once their grids have been checked against the D8 pointer.
+pub(super) struct Inputs {
+    pub(super) pntr: Raster,
+    pub(super) streams: Raster,
+    /// Whether the streams were derived from `--flow_accum` rather than read from `--streams`.
+    pub(super) streams_derived: bool,
+    pub(super) stream_filter: StreamFilter,
+    pub(super) accum: Option<Raster>,
+    pub(super) dem: Option<Raster>,
+    /// The cells inside the watershed, read from a raster (positive cells) or rasterized from
+    /// polygons onto the D8 pointer grid.
+    pub(super) watershed: Option<Array2D<u8>>,
+    pub(super) watershed_is_vector: bool,
+    /// The pointer scheme, as given or detected with `--auto_pntr`.
+    pub(super) esri_style: bool,
+    /// The rows and columns to which the junction scan and the outlet search are limited.
+    pub(super) search_window: (Range<isize>, Range<isize>),
+}
+
+impl Inputs {
+    /// Compares the grids of the input rasters from their headers, before any pixels are read.
+    pub(super) fn check_grids(args: &FindOutletArgs) -> Result<(), Error> {
+        let mut inputs = vec![("--d8_pntr", &args.d8_file)];
+        if !args.streams_file.is_empty() {
+            inputs.push(("--streams", &args.streams_file));
+        }
+        if !args.flow_accum_file.is_empty() {
+            inputs.push(("--flow_accum", &args.flow_accum_file));
+        }
+        if !args.dem_file.is_empty() {
+            inputs.push(("--dem", &args.dem_file));
+        }
+        if !args.watershed_file.is_empty() && !is_polygon_file(&args.watershed_file) {
+            inputs.push(("--watershed", &args.watershed_file));
+        }
+        let mut headers = Vec::with_capacity(inputs.len());
+        for &(flag, file_name) in &inputs {
+            let configs = header_configs(file_name).context(format!("{} {}", flag, file_name))?;
+            headers.push((flag, file_name.as_str(), configs));
+        }
+        check_geometry(&headers, !args.skip_crs_check)
+            .map_err(|e| WbtError::geometry_mismatch(e.to_string()))?;
+        Ok(())
+    }
+
+    pub(super) fn read(args: &FindOutletArgs, verbose: bool) -> Result<Inputs, Error> {
+        if verbose {
+            println!("Reading input rasters...");
+        }
+        let pntr = Raster::new(&args.d8_file, "r")?;
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let search_window = match &args.bbox {
+            Some(bbox) => bbox_window(&pntr, bbox).ok_or_else(|| {
+                WbtError::geometry_mismatch(format!(
+                    "The bounding box (--bbox) {},{},{},{} does not overlap the D8 pointer raster ({},{},{},{}).",
+                    bbox.min_x,
+                    bbox.min_y,
+                    bbox.max_x,
+                    bbox.max_y,
+                    pntr.configs.west,
+                    pntr.configs.south,
+                    pntr.configs.east,
+                    pntr.configs.north
+                ))
+            })?,
+            None => (0..rows, 0..columns),
+        };
+
+        // An explicit streams raster is always preferred; the accumulation raster is only
+        // used to derive a stream mask when --streams is omitted, to snap requests, and for the
+        // drainage area at the outlet.
+        let streams_derived = args.streams_file.is_empty();
+        let accum = if args.flow_accum_file.is_empty() {
+            None
+        } else {
+            Some(Raster::new(&args.flow_accum_file, "r")?)
+        };
+        let streams = if let (true, Some(accum)) = (streams_derived, &accum) {
+            let threshold = args.accum_threshold.unwrap_or_default();
+            if verbose {
+                println!(
+                    "Deriving streams from flow accumulation (threshold = {})...",
+                    threshold
+                );
+            }
+            streams_from_flow_accum(accum, threshold)
+        } else {
+            if verbose && !args.flow_accum_file.is_empty() {
+                println!("Using the streams raster; --flow_accum is not used for the streams.");
+            }
+            Raster::new(&args.streams_file, "r")?
+        };
+        let dem = if args.dem_file.is_empty() {
+            None
+        } else {
+            Some(Raster::new(&args.dem_file, "r")?)
+        };
+        let watershed_is_vector = is_polygon_file(&args.watershed_file);
+        let mut watershed: Option<Array2D<u8>> = None;
+        if watershed_is_vector {
+            let polygons = read_watershed_polygons(&args.watershed_file)
+                .context(format!("--watershed {}", args.watershed_file))?;
+            watershed = Some(rasterize_polygons(&polygons, &pntr)?);
+        } else if !args.watershed_file.is_empty() {
+            let ws = Raster::new(&args.watershed_file, "r")?;
+            let ws_nodata = ws.configs.nodata;
+            watershed =
+                Some(ws.get_threshold_as_array2d(|val| val != ws_nodata && val > 0f64, 1u8, 0u8));
+        }
+
+        // A derived stream mask holds 1 for channels, so the thresholds only apply to --streams.
+        let stream_filter = if streams_derived {
+            StreamFilter::default()
+        } else {
+            StreamFilter {
+                threshold: args.stream_threshold,
+                min_order: args.min_stream_order as f64,
+            }
+        };
+
+        let mut esri_style = args.esri_style;
+        if args.auto_pntr {
+            let votes = count_pointer_contradictions(&pntr, &streams, stream_filter);
+            match votes.detected_esri_style() {
+                Some(detected) => {
+                    esri_style = detected;
+                    if verbose {
+                        println!(
+                            "Detected the {} pointer scheme ({} of {} sampled stream cells contradict the Whitebox scheme, {} the ESRI scheme).",
+                            pointer_scheme_name(esri_style),
+                            votes.whitebox,
+                            votes.sampled,
+                            votes.esri
+                        );
+                    }
+                }
+                None => {
+                    if verbose {
+                        println!(
+                            "Warning: Unable to detect the pointer scheme ({} of {} sampled stream cells contradict the Whitebox scheme, {} the ESRI scheme); using the {} scheme.",
+                            votes.whitebox,
+                            votes.sampled,
+                            votes.esri,
+                            pointer_scheme_name(esri_style)
+                        );
+                    }
+                }
+            }
+        }
+
+        Ok(Inputs {
+            pntr,
+            streams,
+            streams_derived,
+            stream_filter,
+            accum,
+            dem,
+            watershed,
+            watershed_is_vector,
+            esri_style,
+            search_window,
+        })
+    }
+
+    pub(super) fn in_search_window(&self, row: isize, col: isize) -> bool {
+        self.search_window.0.contains(&row) && self.search_window.1.contains(&col)
+    }
+}
+
+/// Builds an in-memory stream mask from a flow accumulation raster: cells with accumulation
+/// greater than `threshold` are streams (1), other valid cells are 0, and nodata is preserved.
+fn streams_from_flow_accum(accum: &Raster, threshold: f64) -> Raster {
+    let nodata = accum.configs.nodata;
+    let mut streams = Raster::initialize_using_file("streams_from_flow_accum.tif", accum);
+    for row in 0..accum.configs.rows as isize {
+        for col in 0..accum.configs.columns as isize {
+            let value = accum.get_value(row, col);
+            if value == nodata {
+                streams.set_value(row, col, nodata);
+            } else if value > threshold {
+                streams.set_value(row, col, 1f64);
+            } else {
+                streams.set_value(row, col, 0f64);
+            }
+        }
+    }
+    streams
+}
+
+/// The number of stream cells sampled by `--auto_pntr`.
+const AUTO_PNTR_SAMPLE_SIZE: usize = 4096;
+
+/// The name of a D8 pointer scheme, as written to the `pntr_scheme` property.
+pub(super) fn pointer_scheme_name(esri_style: bool) -> &'static str {
+    if esri_style {
+        "esri"
+    } else {
+        "whitebox"
+    }
+}
+
+/// The number of sampled stream cells whose flow direction contradicts the stream network under
+/// each pointer scheme.
+pub(super) struct PointerSchemeVotes {
+    pub(super) sampled: usize,
+    pub(super) whitebox: usize,
+    pub(super) esri: usize,
+}
+
+impl PointerSchemeVotes {
+    /// Whether the pointer uses the ESRI scheme, or `None` when the samples cannot tell. A scheme
+    /// is chosen when it has at most half the contradictions of the other, and the other
+    /// contradicts at least a tenth of the samples.
+    pub(super) fn detected_esri_style(&self) -> Option<bool> {
+        let (fewer, more) = (self.whitebox.min(self.esri), self.whitebox.max(self.esri));
+        if self.sampled == 0 || fewer * 2 > more || more * 10 < self.sampled {
+            None
+        } else {
+            Some(self.esri < self.whitebox)
+        }
+    }
+}
+
+/// Reads the pointer of up to `AUTO_PNTR_SAMPLE_SIZE` stream cells, spread evenly over the stream
+/// network, under both pointer schemes. A stream cell contradicts a scheme when the cell it drains
+/// to is on the grid and has a valid pointer but is not a stream cell, or drains straight back
+/// into it. Reading the pointer under the wrong scheme turns every flow direction by 45 degrees,
+/// which sends most channel cells off the network.
+fn count_pointer_contradictions(
+    pntr: &Raster,
+    streams: &Raster,
+    stream_filter: StreamFilter,
+) -> PointerSchemeVotes {
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let pntr_nodata = pntr.configs.nodata;
+    let streams_nodata = streams.configs.nodata;
+    let has_direction = |p: f64| {
+        p != pntr_nodata
+            && p > 0f64
+            && p <= 128f64
+            && p.fract() == 0f64
+            && (p as u8).is_power_of_two()
+    };
+    let mut cells = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            if is_stream_value(streams[(row, col)], streams_nodata, stream_filter)
+                && has_direction(pntr[(row, col)])
+            {
+                cells.push((row, col));
+            }
+        }
+    }
+    let stride = cells.len().div_ceil(AUTO_PNTR_SAMPLE_SIZE).max(1);
+    let mut votes = PointerSchemeVotes {
+        sampled: 0,
+        whitebox: 0,
+        esri: 0,
+    };
+    let schemes = [
+        (false, pointer_tables(false).1),
+        (true, pointer_tables(true).1),
+    ];
+    for &(row, col) in cells.iter().step_by(stride) {
+        votes.sampled += 1;
+        for (esri_style, pntr_matches) in &schemes {
+            let n = pntr_matches[pntr[(row, col)] as usize] as usize;
+            let (nr, nc) = (row + D8_DY[n], col + D8_DX[n]);
+            if nr < 0 || nr >= rows || nc < 0 || nc >= columns {
+                continue;
+            }
+            let p = pntr[(nr, nc)];
+            if !has_direction(p) {
+                continue;
+            }
+            let back = pntr_matches[p as usize] as usize;
+            let contradicts = !is_stream_value(streams[(nr, nc)], streams_nodata, stream_filter)
+                || (nr + D8_DY[back] == row && nc + D8_DX[back] == col);
+            if contradicts {
+                if *esri_style {
+                    votes.esri += 1;
+                } else {
+                    votes.whitebox += 1;
+                }
+            }
+        }
+    }
+    votes
+}
+
+/// The window of rows and columns of the cells that overlap a bounding box in map coordinates,
+/// or `None` if the box misses the raster.
+pub(super) fn bbox_window(
+    pntr: &Raster,
+    bbox: &BoundingBox,
+) -> Option<(Range<isize>, Range<isize>)> {
+    let configs = &pntr.configs;
+    if bbox.max_x <= configs.west
+        || bbox.min_x >= configs.east
+        || bbox.max_y <= configs.south
+        || bbox.min_y >= configs.north
+    {
+        return None;
+    }
+    // A box edge on a cell edge does not take in the cell beyond it.
+    let end_row = ((configs.north - bbox.min_y) / configs.resolution_y).ceil() as isize;
+    let end_col = ((bbox.max_x - configs.west) / configs.resolution_x).ceil() as isize;
+    Some((
+        pntr.get_row_from_y(bbox.max_y).max(0)..end_row.min(configs.rows as isize),
+        pntr.get_column_from_x(bbox.min_x).max(0)..end_col.min(configs.columns as isize),
+    ))
+}
+
+/// A watershed polygon, as closed rings of map coordinates.
+struct MaskPolygon {
+    exterior: Vec<Point2D>,
+    holes: Vec<Vec<Point2D>>,
+}
+
+/// Returns true if a watershed mask file is a Shapefile or GeoJSON file rather than a raster.
+fn is_polygon_file(file: &str) -> bool {
+    let file = file.to_lowercase();
+    file.ends_with(".shp") || file.ends_with(".geojson") || file.ends_with(".json")
+}
+
+/// Closes a ring whose last vertex does not repeat its first.
+fn closed_ring(mut ring: Vec<Point2D>) -> Vec<Point2D> {
+    if let (Some(&first), Some(last)) = (ring.first(), ring.last()) {
+        if !first.nearly_equals(last) {
+            ring.push(first);
+        }
+    }
+    ring
+}
+
+/// Reads the polygons of a watershed Shapefile or GeoJSON file. Each exterior ring of a Shapefile
+/// record is paired with all of the record's holes; GeoJSON Polygons and the parts of
+/// MultiPolygons keep their own holes. Features of other geometry types are ignored.
+fn read_watershed_polygons(file: &str) -> Result<Vec<MaskPolygon>, Error> {
+    let not_polygons =
+        || WbtError::invalid_argument("The watershed file (--watershed) must contain polygons.");
+    let mut polygons = vec![];
+    if file.to_lowercase().ends_with(".shp") {
+        let shapefile = Shapefile::read(file)?;
+        if shapefile.header.shape_type.base_shape_type() != ShapeType::Polygon {
+            return Err(not_polygons().into());
+        }
+        for i in 0..shapefile.num_records {
+            let record = shapefile.get_record(i);
+            let mut exteriors = vec![];
+            let mut holes = vec![];
+            for part in 0..record.num_parts as usize {
+                let start = record.parts[part] as usize;
+                let end = if part + 1 < record.num_parts as usize {
+                    record.parts[part + 1] as usize
+                } else {
+                    record.num_points as usize
+                };
+                let ring = closed_ring(record.points[start..end].to_vec());
+                if ring.len() < 4 {
+                    continue;
+                }
+                if record.is_hole(part as i32) {
+                    holes.push(ring);
+                } else {
+                    exteriors.push(ring);
+                }
+            }
+            for exterior in exteriors {
+                polygons.push(MaskPolygon {
+                    exterior,
+                    holes: holes.clone(),
+                });
+            }
+        }
+    } else {
+        let geojson: GeoJson = std::fs::read_to_string(file)?.parse().map_err(|e| {
+            WbtError::data_error(format!(
+                "Unable to parse the watershed GeoJSON (--watershed): {}",
+                e
+            ))
+        })?;
+        let geometries: Vec<Geometry> = match geojson {
+            GeoJson::FeatureCollection(fc) => {
+                fc.features.into_iter().filter_map(|f| f.geometry).collect()
+            }
+            GeoJson::Feature(feature) => feature.geometry.into_iter().collect(),
+            GeoJson::Geometry(geometry) => vec![geometry],
+        };
+        let to_polygon = |rings: &[Vec<Vec<f64>>]| -> Option<MaskPolygon> {
+            let mut rings = rings
+                .iter()
+                .map(|ring| closed_ring(ring.iter().map(|p| Point2D::new(p[0], p[1])).collect()));
+            let exterior = rings.next().filter(|ring| ring.len() >= 4)?;
+            Some(MaskPolygon {
+                exterior,
+                holes: rings.filter(|ring| ring.len() >= 4).collect(),
+            })
+        };
+        for geometry in geometries {
+            match geometry.value {
+                GeoValue::Polygon(rings) => polygons.extend(to_polygon(&rings)),
+                GeoValue::MultiPolygon(parts) => {
+                    polygons.extend(parts.iter().filter_map(|rings| to_polygon(rings)))
+                }
+                _ => continue,
+            }
+        }
+    }
+    if polygons.is_empty() {
+        return Err(not_polygons().into());
+    }
+    Ok(polygons)
+}
+
+/// Rasterizes watershed polygons onto the grid of the D8 pointer raster, marking with 1 each cell
+/// whose centre is inside a polygon and outside its holes. Only the cells within the bounding box
+/// of each polygon are tested.
+fn rasterize_polygons(polygons: &[MaskPolygon], pntr: &Raster) -> Result<Array2D<u8>, Error> {
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let mut mask: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+    for polygon in polygons {
+        let (mut min_x, mut max_x) = (f64::INFINITY, f64::NEG_INFINITY);
+        let (mut min_y, mut max_y) = (f64::INFINITY, f64::NEG_INFINITY);
+        for p in &polygon.exterior {
+            min_x = min_x.min(p.x);
+            max_x = max_x.max(p.x);
+            min_y = min_y.min(p.y);
+            max_y = max_y.max(p.y);
+        }
+        let first_row = pntr.get_row_from_y(max_y).max(0);
+        let last_row = pntr.get_row_from_y(min_y).min(rows - 1);
+        let first_col = pntr.get_column_from_x(min_x).max(0);
+        let last_col = pntr.get_column_from_x(max_x).min(columns - 1);
+        for row in first_row..=last_row {
+            let y = pntr.get_y_from_row(row);
+            for col in first_col..=last_col {
+                if mask.get_value(row, col) == 1u8 {
+                    continue;
+                }
+                let p = Point2D::new(pntr.get_x_from_column(col), y);
+                if point_in_poly(&p, &polygon.exterior)
+                    && !polygon.holes.iter().any(|hole| point_in_poly(&p, hole))
+                {
+                    mask.set_value(row, col, 1u8);
+                }
+            }
+        }
+    }
+    Ok(mask)
+}
@@ -0,0 +1,908 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+mod args;
+mod inputs;
+mod output;
+mod start;
+mod trace;
+
+use self::args::{argument_values, FindOutletArgs};
+use self::inputs::Inputs;
+use self::output::{write_junctions, OutletOutputs};
+use self::start::{raster_projection, requested_locations, WatershedCells};
+use self::trace::{
+    count_junctions, junction_scan_window, pointer_tables, resolve_num_procs, select_traces,
+    TraceContext,
+};
+use crate::tools::*;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::env;
+use std::io::Error;
+use std::path;
+use std::time::Instant;
+use whitebox_common::spatial_ref_system::ProjectionMethod;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+
+pub(crate) use self::args::parse_pair_list;
+pub(crate) use self::trace::OutletTracer;
+
+/// This tool identifies the outlet of a watershed, i.e. the stream cell through which the
+/// watershed mask (`--watershed`) drains, using a D8 pointer raster (`--d8_pntr`) and a stream
+/// network (`--streams`, or `--flow_accum` with `--accum_threshold`). Alternatively, the outlet
+/// may be traced downslope from one or more requested locations (`--requested_outlet_lng_lat`,
+/// `--requested_outlet_row_col`, `--requested_outlet_xy`, or the points of `--pour_pts`). The
+/// outlet is written as a GeoJSON point (`--output`), or as a point Shapefile when the output
+/// file has a `.shp` extension.
+///
+/// The watershed mask may be a raster, whose positive cells are inside the watershed, or
+/// polygons in a Shapefile or GeoJSON file, which are rasterized onto the D8 pointer grid. The
+/// mask cells are ranked by decreasing distance to the mask boundary, and up to
+/// `--max_candidates` of them are traced downstream, for at most `--max_steps` D8 steps each,
+/// until a trace reaches a stream cell with a single inflowing stream cell. The highest-ranked
+/// successful candidate gives the outlet, whatever the number of threads used. With
+/// `--all_outlets`, every distinct outlet of the mask is written instead, and
+/// `--score_candidates` reports how many other candidates reach the selected outlet.
+///
+/// A stream cell is one whose value is greater than `--stream_threshold` and at least
+/// `--min_stream_order`, and is not nodata. The D8 pointer uses the Whitebox scheme unless
+/// `--esri_pntr` is given, or the scheme is detected from the stream network (`--auto_pntr`).
+/// The streams, flow accumulation, DEM, and watershed rasters must share the grid and EPSG code
+/// of the D8 pointer (see `--skip_crs_check`); the search may be limited to a bounding box
+/// (`--bbox`) on large grids.
+///
+/// A requested location may be snapped to the cell of highest flow accumulation within
+/// `--snap_radius` cells, and is traced from the nearest cell with a valid D8 pointer (the
+/// nearest stream cell with `--snap_to_stream`). The outlet may be kept within the watershed
+/// mask (`--outlet_within_mask`), moved upstream of a nearby junction (`--snap_to_junction`),
+/// and, with a DEM (`--dem`), reported with its elevation and the local channel slope. The
+/// properties of each outlet feature record how it was found, e.g. the start cell, the steps
+/// taken, the path length, and the drainage area when `--flow_accum` is supplied.
+///
+/// Optional outputs help with QA and batch runs: the traced flow paths (`--trace_path`),
+/// downstream previews (`--downstream_preview`), every trace attempt (`--diagnostics`), the
+/// junction counts (`--junctions`), the outlet as a raster aligned to the D8 pointer
+/// (`--output_raster`), a CSV summary (`--summary_csv`), and a JSON report of the run
+/// (`--report`, and `--error_json` on failure).
+///
+/// When the tool fails, the error is assigned a failure class, which determines the exit code:
+///
+/// | Failure class | Exit code | Cause |
+/// |---------------|-----------|-------|
+/// | `argument_error` | 2 | Missing or unparsable arguments |
+/// | `geometry_mismatch` | 3 | Input rasters with differing dimensions, extents, or EPSG codes, or an empty watershed mask |
+/// | `conversion_failure` | 4 | The requested lon/lat could not be converted to a grid cell |
+/// | `trace_failure` | 5 | No outlet could be traced from the candidate cells |
+/// | `file_not_found` | 6 | An input file, e.g. the D8 pointer or the pour points, does not exist |
+/// | `data_error` | 7 | An input that could be read but not used, e.g. a watershed GeoJSON that does not parse |
+/// | `internal_error` | 8 | Any other failure to read an input, e.g. an I/O error |
+///
+/// Exit code 1 is still used for errors that are not classified, e.g. a failure to write one of
+/// the outputs.
+///
+/// # See Also
+/// `SnapPourPoints`, `JensonSnapPourPoints`, `Watershed`, `HillslopesTopaz`
+pub struct FindOutlet {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    parameter_hints: Vec<ParameterHints>,
+    example_usage: String,
+}
+
+impl FindOutlet {
+    pub fn new() -> FindOutlet {
+        let name = "FindOutlet".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Identifies the primary outlet for a watershed mask using D8 flow directions and stream network.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file (1=stream, 0=non-stream). Optional when --flow_accum is supplied.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Stream Threshold".to_owned(),
+            flags: vec!["--stream_threshold".to_owned()],
+            description: "Optional value above which a --streams cell is a channel; nodata cells never are (default 0).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Stream Order".to_owned(),
+            flags: vec!["--min_stream_order".to_owned()],
+            description: "Optional minimum --streams value, e.g. a Strahler order, of channel cells; lower-valued cells are not streams (default 0).".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Flow Accumulation File".to_owned(),
+            flags: vec!["--flow_accum".to_owned()],
+            description: "Optional flow accumulation raster used to derive the stream network when --streams is not supplied, to snap requested outlets (--snap_radius), and to report the drainage area at the outlet.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Channel Initiation Accumulation Threshold".to_owned(),
+            flags: vec!["--accum_threshold".to_owned()],
+            description: "Accumulation value above which a cell is treated as a stream when deriving streams from --flow_accum.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Flow Accumulation Is an Area".to_owned(),
+            flags: vec!["--sca".to_owned()],
+            description: "The --flow_accum values are contributing areas, e.g. from D8FlowAccumulation --out_type=sca, rather than cell counts, and are reported as the drainage area directly.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Drainage Area Ratio Tolerance".to_owned(),
+            flags: vec!["--area_ratio_tolerance".to_owned()],
+            description: "Warn, in verbose mode, when the drainage area at the outlet differs from the watershed mask area by more than this fraction of it (default 0.25).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.25".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Watershed Mask File".to_owned(),
+            flags: vec!["--watershed".to_owned()],
+            description:
+                "Optional watershed mask raster file (1=inside, 0=outside), or polygon Shapefile or GeoJSON file. Required unless a requested outlet location is supplied.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Requested Outlet Longitude/Latitude".to_owned(),
+            flags: vec!["--requested_outlet_lng_lat".to_owned()],
+            description: "Optional requested outlet location specified as 'lon,lat' (WGS84), or a semicolon-separated list of locations.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Requested Outlet Row/Column".to_owned(),
+            flags: vec!["--requested_outlet_row_col".to_owned()],
+            description: "Optional requested outlet specified as 'row,col' in raster coordinates, or a semicolon-separated list of cells.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Requested Outlet Easting/Northing".to_owned(),
+            flags: vec!["--requested_outlet_xy".to_owned()],
+            description: "Optional requested outlet specified as 'easting,northing' in the coordinate system of the D8 pointer raster, or a semicolon-separated list of locations.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Pour Points File".to_owned(),
+            flags: vec!["--pour_pts".to_owned()],
+            description: "Optional pour points (Shapefile, GeoJSON, or raster) in the D8 pointer's coordinate system, traced as requested outlets.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap Radius (cells)".to_owned(),
+            flags: vec!["--snap_radius".to_owned()],
+            description: "Optional radius, in cells, within which requested outlets are snapped to the cell of highest --flow_accum before tracing.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap to Stream".to_owned(),
+            flags: vec!["--snap_to_stream".to_owned()],
+            description: "When a requested outlet has no valid D8 pointer, prefer the nearest valid cell that is also a stream cell as the start of the trace.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Stream Snap Radius (cells)".to_owned(),
+            flags: vec!["--snap_max_radius".to_owned()],
+            description: "Optional radius, in cells, within which --snap_to_stream looks for a stream cell before falling back on the nearest valid cell; unlimited by default.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Candidates".to_owned(),
+            flags: vec!["--max_candidates".to_owned()],
+            description: "Optional maximum number of watershed cells, deepest first, from which an outlet is traced.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("512".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Mask Erosion (cells)".to_owned(),
+            flags: vec!["--mask_erosion".to_owned()],
+            description: "Optional number of cells by which the watershed mask is eroded before its candidate cells are ranked, dropping a fringe of noise along its boundary.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Search Bounding Box".to_owned(),
+            flags: vec!["--bbox".to_owned()],
+            description: "Optional bounding box, 'minx,miny,maxx,maxy' in the coordinate system of the D8 pointer raster, to which the junction scan and the outlet search are limited.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Trace Steps".to_owned(),
+            flags: vec!["--max_steps".to_owned()],
+            description: "Optional maximum number of D8 steps in a single trace; defaults to four times the number of grid cells.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Score Candidates".to_owned(),
+            flags: vec!["--score_candidates".to_owned()],
+            description: "Optional number of further successful watershed candidates traced after the selected one to measure its support.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Write All Outlets".to_owned(),
+            flags: vec!["--all_outlets".to_owned()],
+            description: "Trace every watershed candidate and write each distinct outlet, ranked by the mask cells draining to it, rather than only the first.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Pour Point File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description:
+                "Output GeoJSON file, or Shapefile (.shp), containing the identified outlet point."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Pointer Uses ESRI Style".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "Specify if the input D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Detect Pointer Scheme".to_owned(),
+            flags: vec!["--auto_pntr".to_owned()],
+            description: "Detect whether the D8 pointer uses the Whitebox or ESRI scheme from its agreement with the stream network; --esri_pntr is the fallback when detection is ambiguous.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Skip CRS Check".to_owned(),
+            flags: vec!["--skip_crs_check".to_owned()],
+            description: "Do not require the input rasters to share the EPSG code of the D8 pointer, e.g. when their codes are missing; their grids must still match.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Outlet Within Mask".to_owned(),
+            flags: vec!["--outlet_within_mask".to_owned()],
+            description: "Only accept outlets inside the watershed mask, falling back on the last stream cell inside it with a junction count of 1 where the trace leaves the mask.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Snap Outlet Upstream of Junction".to_owned(),
+            flags: vec!["--snap_to_junction".to_owned()],
+            description: "Move an outlet traced past a nearby stream junction back to the cell immediately upstream of the junction.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_string()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Junction Window (cells)".to_owned(),
+            flags: vec!["--junction_window".to_owned()],
+            description: "Optional radius, in cells, around the outlet within which --snap_to_junction looks for a junction.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("3".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Optional input DEM raster used to report the outlet elevation and the local channel slope upstream of the outlet.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Channel Slope Steps".to_owned(),
+            flags: vec!["--slope_steps".to_owned()],
+            description: "Optional number of steps up the stream from the outlet over which the channel slope is measured; requires --dem.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Downstream Preview Steps".to_owned(),
+            flags: vec!["--downstream_preview".to_owned()],
+            description: "Optional number of D8 steps to continue the trace downstream of the outlet, ignoring the watershed mask; the continuation is written as a LineString feature.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Diagnostics File".to_owned(),
+            flags: vec!["--diagnostics".to_owned()],
+            description: "Optional output JSON file listing every start cell traced and its result, written on success and on failure.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Trace Path File".to_owned(),
+            flags: vec!["--trace_path".to_owned()],
+            description: "Optional output GeoJSON file of the flow path traced from the start cell to the outlet, as a LineString.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary CSV File".to_owned(),
+            flags: vec!["--summary_csv".to_owned()],
+            description:
+                "Optional output CSV file with one row of outlet attributes per identified outlet."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Junction Count File".to_owned(),
+            flags: vec!["--junctions".to_owned()],
+            description: "Optional output raster of the number of inflowing stream cells at each stream cell.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Outlet Raster File".to_owned(),
+            flags: vec!["--output_raster".to_owned()],
+            description: "Optional output raster aligned to the D8 pointer with a value of 1 at each identified outlet cell and NoData elsewhere.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Compress Raster Outputs".to_owned(),
+            flags: vec!["--compress".to_owned()],
+            description: "Optional override of the compress_rasters setting for raster outputs written as GeoTIFFs.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["--report".to_owned()],
+            description:
+                "Optional output JSON report of the run, written on success and on failure."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Error File".to_owned(),
+            flags: vec!["--error_json".to_owned()],
+            description:
+                "Optional output JSON document describing the failure, written only when the run fails."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        // The stream network comes from --streams or is derived from --flow_accum, and the
+        // outlet is searched for within --watershed or traced from one requested location.
+        let stream_network = "Stream Network";
+        let outlet_location = "Outlet Location";
+        let parameter_hints = vec![
+            ParameterHints {
+                flag: "--streams".to_owned(),
+                group: Some(stream_network.to_owned()),
+                requires_one_of: vec!["--flow_accum".to_owned()],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--flow_accum".to_owned(),
+                group: Some(stream_network.to_owned()),
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--accum_threshold".to_owned(),
+                group: Some(stream_network.to_owned()),
+                requires: vec!["--flow_accum".to_owned()],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--watershed".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires_one_of: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--requested_outlet_lng_lat".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                conflicts_with: vec![
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--requested_outlet_row_col".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                conflicts_with: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--requested_outlet_xy".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                conflicts_with: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--pour_pts".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                conflicts_with: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--snap_radius".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires: vec!["--flow_accum".to_owned()],
+                requires_one_of: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--snap_to_stream".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires_one_of: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--snap_max_radius".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires: vec!["--snap_to_stream".to_owned()],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--all_outlets".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires: vec!["--watershed".to_owned()],
+                conflicts_with: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--score_candidates".to_owned(),
+                group: Some(outlet_location.to_owned()),
+                requires: vec!["--watershed".to_owned()],
+                conflicts_with: vec![
+                    "--requested_outlet_lng_lat".to_owned(),
+                    "--requested_outlet_row_col".to_owned(),
+                    "--requested_outlet_xy".to_owned(),
+                    "--pour_pts".to_owned(),
+                    "--all_outlets".to_owned(),
+                ],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--outlet_within_mask".to_owned(),
+                requires: vec!["--watershed".to_owned()],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--slope_steps".to_owned(),
+                requires: vec!["--dem".to_owned()],
+                ..Default::default()
+            },
+            ParameterHints {
+                flag: "--sca".to_owned(),
+                requires: vec!["--flow_accum".to_owned()],
+                ..Default::default()
+            },
+        ];
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(
+            ">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --watershed='ws.tif' --requested_outlet_lng_lat='-120.5,42.1' --output='outlet.geojson'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --flow_accum='flow_accum.tif' --accum_threshold=1000.0 --watershed='ws.tif' --output='outlet.geojson'",
+            short_exe, name
+        )
+        .replace("*", &sep);
+
+        FindOutlet {
+            name,
+            description,
+            toolbox,
+            parameters,
+            parameter_hints,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for FindOutlet {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        parameters_to_json(&self.parameters, &self.parameter_hints)
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        // The report and error document are written when the run fails, so their paths are read
+        // first.
+        let mut report_file = String::new();
+        let mut error_json_file = String::new();
+        for i in 0..args.len() {
+            let arg = args[i].replace(['"', '\''], "");
+            let vec = arg.split('=').collect::<Vec<&str>>();
+            let flag = vec[0].to_lowercase();
+            let value = || {
+                if vec.len() > 1 {
+                    vec[1].to_string()
+                } else {
+                    args.get(i + 1).cloned().unwrap_or_default()
+                }
+            };
+            if flag == "-report" || flag == "--report" {
+                report_file = value();
+            } else if flag == "-error_json" || flag == "--error_json" {
+                error_json_file = value();
+            }
+        }
+
+        let parameters = argument_values(&args);
+        let mut failure_details = JsonMap::new();
+        let result = self.find_outlet(args, working_directory, verbose, &mut failure_details);
+        if let (Err(err), false) = (&result, error_json_file.is_empty()) {
+            let error_json_file = resolve_path(working_directory, &error_json_file);
+            let class = failure_class(err);
+            let mut document = json!({
+                "tool": self.get_tool_name(),
+                "failure_class": class.map(|c| c.as_str()),
+                "exit_code": class.map_or(1, |c| c.exit_code()),
+                "error_kind": format!("{:?}", err.kind()),
+                "message": err.to_string(),
+                "parameters": parameters,
+                "attempts": [],
+            });
+            for (key, value) in failure_details {
+                document[key.as_str()] = value;
+            }
+            let written = serde_json::to_string_pretty(&document)
+                .map_err(Error::other)
+                .and_then(|text| std::fs::write(&error_json_file, text));
+            if let Err(e) = written {
+                println!(
+                    "Warning: unable to write error file {}: {}",
+                    error_json_file, e
+                );
+            }
+        }
+        if report_file.is_empty() {
+            return result.map(|_| ());
+        }
+        let report_file = resolve_path(working_directory, &report_file);
+        let report = match &result {
+            Ok(outlet) => json!({
+                "tool": self.get_tool_name(),
+                "status": "success",
+                "failure_class": JsonValue::Null,
+                "exit_code": 0,
+                "message": JsonValue::Null,
+                "outlet": outlet,
+            }),
+            Err(err) => {
+                let class = failure_class(err);
+                json!({
+                    "tool": self.get_tool_name(),
+                    "status": "failure",
+                    "failure_class": class.map(|c| c.as_str()),
+                    "exit_code": class.map_or(1, |c| c.exit_code()),
+                    "message": err.to_string(),
+                    "outlet": JsonValue::Null,
+                })
+            }
+        };
+        let written = serde_json::to_string_pretty(&report)
+            .map_err(Error::other)
+            .and_then(|text| std::fs::write(&report_file, text));
+        match (result, written) {
+            (Ok(_), Ok(_)) => Ok(()),
+            (Ok(_), Err(e)) => Err(Error::new(
+                e.kind(),
+                format!("Unable to write report file {}: {}", report_file, e),
+            )),
+            (Err(err), written) => {
+                if let Err(e) = written {
+                    println!(
+                        "Warning: unable to write report file {}: {}",
+                        report_file, e
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl FindOutlet {
+    /// Runs the tool, returning the outlet written to the report. When no outlet can be traced,
+    /// the trace attempts and the limits they ran under are added to `failure_details` for the
+    /// `--error_json` document.
+    fn find_outlet<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+        failure_details: &mut JsonMap<String, JsonValue>,
+    ) -> Result<JsonValue, Error> {
+        let mut args = FindOutletArgs::parse(&args)?;
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        // Processor settings follow settings.json, as in the plugin tools. Verbosity is already
+        // resolved from the same file by the tool manager. Raster outputs are compressed
+        // according to compress_rasters unless --compress overrides it.
+        let configs = whitebox_common::configs::get_configs()?;
+        let num_procs = resolve_num_procs(configs.max_procs, num_cpus::get());
+        let compress = args.compress.unwrap_or(configs.compress_rasters);
+        args.resolve_paths(working_directory);
+
+        Inputs::check_grids(&args)?;
+        let start = Instant::now();
+        let inputs = Inputs::read(&args, verbose)?;
+        let pntr = &inputs.pntr;
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+
+        let (inflowing_vals, pntr_matches) = pointer_tables(inputs.esri_style);
+        let scan_window = junction_scan_window(&inputs, !args.junctions_file.is_empty());
+        if verbose {
+            println!(
+                "Computing stream junction counts ({} rows x {} columns)...",
+                scan_window.0.len(),
+                scan_window.1.len()
+            );
+        }
+        let junction_counts = count_junctions(
+            pntr,
+            &inputs.streams,
+            inputs.stream_filter,
+            &inflowing_vals,
+            scan_window,
+            num_procs,
+            verbose,
+        )?;
+        // The junction counts are written before tracing so that they are available when
+        // diagnosing a failed trace.
+        if !args.junctions_file.is_empty() {
+            write_junctions(
+                &args,
+                pntr,
+                &junction_counts,
+                compress,
+                &self.get_tool_name(),
+                verbose,
+            )?;
+        }
+
+        let cells = WatershedCells::scan(&args, &inputs, verbose)?;
+
+        // Distances in the outputs are measured in meters where the coordinate system is known.
+        let pntr_projection = raster_projection(pntr);
+        let trace_ctx = TraceContext {
+            pntr,
+            streams: &inputs.streams,
+            mask: if cells.mask_has_data {
+                Some(&cells.mask)
+            } else {
+                None
+            },
+            junction_counts: &junction_counts,
+            inflowing_vals: &inflowing_vals,
+            pntr_nodata: pntr.configs.nodata,
+            streams_nodata: inputs.streams.configs.nodata,
+            stream_filter: inputs.stream_filter,
+            pntr_matches: &pntr_matches,
+            rows,
+            columns,
+            max_steps: args
+                .max_steps_limit
+                .unwrap_or((rows * columns * 4).max(1) as usize),
+            outlet_within_mask: args.outlet_within_mask,
+            geographic: pntr_projection
+                .as_ref()
+                .is_some_and(|p| p.method == ProjectionMethod::Geographic),
+            linear_unit: pntr_projection.as_ref().map_or(1f64, |p| p.linear_unit),
+        };
+
+        let requests = requested_locations(&args, &inputs, pntr_projection.as_ref(), verbose)?;
+        let mut selection = select_traces(
+            &args,
+            &cells,
+            &requests,
+            &trace_ctx,
+            num_procs,
+            verbose,
+            failure_details,
+        )?;
+        if args.snap_to_junction {
+            selection.snap_to_junctions(&trace_ctx, args.junction_window, verbose);
+        }
+        if args.all_outlets {
+            selection.rank_by_upstream_cells(&trace_ctx, verbose);
+        }
+
+        OutletOutputs {
+            tool_name: self.get_tool_name(),
+            args: &args,
+            inputs: &inputs,
+            cells: &cells,
+            ctx: &trace_ctx,
+            requests: &requests,
+            num_procs,
+            compress,
+        }
+        .write(&selection, start, verbose)
+    }
+}
+
+#[cfg(test)]
+mod tests;
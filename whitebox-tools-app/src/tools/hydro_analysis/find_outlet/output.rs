@@ -0,0 +1,1168 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use super::args::FindOutletArgs;
+use super::inputs::{pointer_scheme_name, Inputs};
+use super::start::{RequestedLocation, WatershedCells};
+use super::trace::{
+    channel_slope_upstream, is_stream_value, trace_downstream_preview, SelectedTrace, Selection,
+    TraceContext, TraceSuccessData,
+};
+use crate::tools::*;
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value as GeoValue};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::f64;
+use std::fs::File;
+use std::io::{Error, Write};
+use std::time::Instant;
+use whitebox_common::structures::{Array2D, Point2D};
+use whitebox_common::utils::get_formatted_elapsed_time;
+use whitebox_raster::*;
+use whitebox_vector::{
+    AttributeField, FieldData, FieldDataType, ShapeType, ShapefileGeometry, VectorWriter,
+};
+
+/// The state of a run that its outputs are written from: its arguments, inputs, and watershed
+/// cells, the trace context, and the requested locations.
+pub(super) struct OutletOutputs<'a> {
+    pub(super) tool_name: String,
+    pub(super) args: &'a FindOutletArgs,
+    pub(super) inputs: &'a Inputs,
+    pub(super) cells: &'a WatershedCells,
+    pub(super) ctx: &'a TraceContext<'a>,
+    pub(super) requests: &'a [RequestedLocation],
+    pub(super) num_procs: usize,
+    pub(super) compress: bool,
+}
+
+impl<'a> OutletOutputs<'a> {
+    /// Writes the outlets (`--output`), the trace paths, outlet raster, and summary CSV where
+    /// requested, and returns the outlets for the report.
+    pub(super) fn write(
+        &self,
+        selection: &Selection,
+        start: Instant,
+        verbose: bool,
+    ) -> Result<JsonValue, Error> {
+        let pntr = &self.inputs.pntr;
+        let epsg_code = pntr.configs.epsg_code;
+        let mut features = vec![];
+        let mut outlets: Vec<JsonValue> = vec![];
+        let mut previews: Vec<(usize, isize, isize, f64, f64)> = vec![];
+        let mut trace_paths = vec![];
+        for (id, outcome) in selection.outcomes.iter().enumerate() {
+            let request = self.requests.get(id);
+            let selected = match outcome {
+                Ok(selected) => selected,
+                Err(reason) => {
+                    if verbose {
+                        println!("Requested outlet {} failed: {}", id, reason);
+                    }
+                    features.push(self.failure_feature(id, reason, request));
+                    outlets.push(json!({
+                        "id": id,
+                        "status": "failure",
+                        "message": reason,
+                    }));
+                    continue;
+                }
+            };
+
+            features.push(self.outlet_feature(id, selected, selection, request, verbose));
+            let (outlet_row, outlet_col) =
+                (selected.success.outlet_row, selected.success.outlet_col);
+            let easting = pntr.get_x_from_column(outlet_col);
+            let northing = pntr.get_y_from_row(outlet_row);
+            if verbose {
+                println!(
+                    "Outlet {}: row {}, col {}, distance {}, steps {}.",
+                    id,
+                    outlet_row,
+                    outlet_col,
+                    selected.distance_to_boundary,
+                    selected.success.steps_taken
+                );
+            }
+            previews.push((id, outlet_row, outlet_col, easting, northing));
+            if !self.args.trace_path_file.is_empty() {
+                trace_paths.push(self.trace_path_feature(trace_paths.len(), id, selected));
+            }
+            let mut outlet = json!({
+                "id": id,
+                "status": "success",
+                "row": outlet_row,
+                "column": outlet_col,
+                "easting": easting,
+                "northing": northing,
+                "epsg": epsg_code,
+                "start_mode": selected.start_mode.as_str(),
+            });
+            if let (Some(count), JsonValue::Object(map)) =
+                (selection.upstream_cells[id], &mut outlet)
+            {
+                map.insert("upstream_mask_cells".to_string(), json!(count));
+            }
+            outlets.push(outlet);
+        }
+
+        let mut foreign_members: Option<JsonMap<String, JsonValue>> = None;
+        if epsg_code != 0 {
+            let mut crs_map = JsonMap::new();
+            crs_map.insert("type".to_string(), json!("name"));
+            crs_map.insert(
+                "properties".to_string(),
+                json!({"name": format!("urn:ogc:def:crs:EPSG::{}", epsg_code)}),
+            );
+            let mut members = JsonMap::new();
+            members.insert("crs".to_string(), JsonValue::Object(crs_map));
+            foreign_members = Some(members);
+        }
+
+        // The downstream previews follow the outlet features, so that the outlet Ids match
+        // the indices of the requested locations.
+        if let Some(num_steps) = self.args.downstream_preview {
+            for (outlet_id, outlet_row, outlet_col, easting, northing) in previews {
+                let preview = trace_downstream_preview(outlet_row, outlet_col, num_steps, self.ctx);
+                if verbose {
+                    println!(
+                        "Downstream preview: {} of {} steps ({}){}.",
+                        preview.cells.len(),
+                        num_steps,
+                        if preview.on_stream {
+                            "on stream"
+                        } else {
+                            "leaves the stream network"
+                        },
+                        match preview.reason {
+                            Some(reason) => format!(", stopped: {}", reason),
+                            None => String::new(),
+                        }
+                    );
+                }
+                // A LineString needs two positions, so a continuation that could not take a
+                // step is written as a zero-length line at the outlet.
+                let mut coordinates = vec![vec![easting, northing]];
+                for &(r, c) in &preview.cells {
+                    coordinates.push(vec![pntr.get_x_from_column(c), pntr.get_y_from_row(r)]);
+                }
+                if coordinates.len() == 1 {
+                    coordinates.push(vec![easting, northing]);
+                }
+                let mut preview_properties: JsonMap<String, JsonValue> = JsonMap::new();
+                preview_properties.insert("Id".to_string(), json!(features.len()));
+                preview_properties.insert("feature".to_string(), json!("downstream_preview"));
+                preview_properties.insert("outlet_id".to_string(), json!(outlet_id));
+                preview_properties.insert("steps_requested".to_string(), json!(num_steps));
+                preview_properties.insert("steps".to_string(), json!(preview.cells.len()));
+                preview_properties.insert("length".to_string(), json!(preview.length));
+                preview_properties.insert("on_stream".to_string(), json!(preview.on_stream));
+                preview_properties.insert(
+                    "reason".to_string(),
+                    match preview.reason {
+                        Some(reason) => json!(reason),
+                        None => JsonValue::Null,
+                    },
+                );
+                features.push(Feature {
+                    bbox: None,
+                    geometry: Some(Geometry::new(GeoValue::LineString(coordinates))),
+                    id: None,
+                    properties: Some(preview_properties),
+                    foreign_members: None,
+                });
+            }
+        }
+
+        if !self.args.trace_path_file.is_empty() {
+            if verbose {
+                println!(
+                    "Writing trace path GeoJSON to {}.",
+                    self.args.trace_path_file
+                );
+            }
+            let trace_path_collection = FeatureCollection {
+                bbox: None,
+                features: trace_paths,
+                foreign_members: foreign_members.clone(),
+            };
+            let geojson = GeoJson::FeatureCollection(trace_path_collection).to_string();
+            let mut file = File::create(&self.args.trace_path_file)?;
+            file.write_all(geojson.as_bytes())?;
+            file.sync_all()?;
+        }
+
+        let feature_collection = FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members,
+        };
+
+        // The GeoJSON output takes the features, so the summary keeps its own copy.
+        let summary_features = if self.args.summary_csv_file.is_empty() {
+            vec![]
+        } else {
+            feature_collection.features[..outlets.len()].to_vec()
+        };
+        if self.args.output_file.to_lowercase().ends_with(".shp") {
+            if verbose {
+                println!("Writing outlet Shapefile to {}.", self.args.output_file);
+                if feature_collection.features.len() > outlets.len() {
+                    println!("Downstream previews are not written to Shapefile output.");
+                }
+            }
+            write_outlet_shapefile(
+                &self.args.output_file,
+                &feature_collection.features[..outlets.len()],
+                &pntr.configs.coordinate_ref_system_wkt,
+            )?;
+        } else {
+            if verbose {
+                println!("Writing outlet GeoJSON to {}.", self.args.output_file);
+            }
+
+            let geojson = GeoJson::FeatureCollection(feature_collection).to_string();
+            let mut file = File::create(&self.args.output_file)?;
+            file.write_all(geojson.as_bytes())?;
+            file.sync_all()?;
+        }
+
+        if !self.args.output_raster_file.is_empty() {
+            let mut output = Raster::initialize_using_file(&self.args.output_raster_file, pntr);
+            output.configs.data_type = DataType::I16;
+            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            output.configs.nodata = -32768f64;
+            output.reinitialize_values(-32768f64);
+            output.set_compression(Some(self.compress));
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.tool_name
+            ));
+            output.add_metadata_entry(format!("Outlet file: {}", self.args.output_file));
+            for selected in selection
+                .outcomes
+                .iter()
+                .filter_map(|outcome| outcome.as_ref().ok())
+            {
+                let (row, col) = (selected.success.outlet_row, selected.success.outlet_col);
+                output.set_value(row, col, 1f64);
+                output.add_metadata_entry(format!("Outlet row/col: {}, {}", row, col));
+            }
+            if verbose {
+                println!("Writing outlet raster to {}.", self.args.output_raster_file);
+            }
+            output.write()?;
+        }
+
+        if !self.args.summary_csv_file.is_empty() {
+            if verbose {
+                println!(
+                    "Writing outlet summary CSV to {}.",
+                    self.args.summary_csv_file
+                );
+            }
+            write_summary_csv(
+                &self.args.summary_csv_file,
+                &summary_features,
+                start.elapsed().as_secs_f64(),
+            )?;
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!("Elapsed Time (excluding I/O): {}", elapsed_time);
+        }
+
+        Ok(self.report(selection, outlets))
+    }
+
+    /// The outlets of the run as written to `--report`, with the outputs written.
+    fn report(&self, selection: &Selection, mut outlets: Vec<JsonValue>) -> JsonValue {
+        let batch = self.requests.len() > 1;
+        let junctions = if self.args.junctions_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(self.args.junctions_file)
+        };
+        let output_raster = if self.args.output_raster_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(self.args.output_raster_file)
+        };
+        let trace_path = if self.args.trace_path_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(self.args.trace_path_file)
+        };
+        let diagnostics = if self.args.diagnostics_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(self.args.diagnostics_file)
+        };
+        let summary_csv = if self.args.summary_csv_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(self.args.summary_csv_file)
+        };
+        if batch {
+            let succeeded = selection
+                .outcomes
+                .iter()
+                .filter(|outcome| outcome.is_ok())
+                .count();
+            return json!({
+                "requests": self.requests.len(),
+                "succeeded": succeeded,
+                "outlets": outlets,
+                "output": self.args.output_file,
+                "output_raster": output_raster,
+                "junctions": junctions,
+                "trace_path": trace_path,
+                "diagnostics": diagnostics,
+                "summary_csv": summary_csv,
+            });
+        }
+        if self.args.all_outlets {
+            return json!({
+                "outlets": outlets,
+                "output": self.args.output_file,
+                "output_raster": output_raster,
+                "junctions": junctions,
+                "trace_path": trace_path,
+                "diagnostics": diagnostics,
+                "summary_csv": summary_csv,
+                "candidate_stats": selection.candidate_stats.as_ref().map_or(JsonValue::Null, |stats| stats.to_json()),
+            });
+        }
+        let mut outlet = outlets.remove(0);
+        if let JsonValue::Object(ref mut map) = outlet {
+            map.remove("id");
+            map.remove("status");
+            map.insert("output".to_string(), json!(self.args.output_file));
+            map.insert("output_raster".to_string(), output_raster);
+            map.insert("junctions".to_string(), junctions);
+            map.insert("trace_path".to_string(), trace_path);
+            map.insert("diagnostics".to_string(), diagnostics);
+            map.insert("summary_csv".to_string(), summary_csv);
+            map.insert(
+                "candidate_stats".to_string(),
+                selection
+                    .candidate_stats
+                    .as_ref()
+                    .map_or(JsonValue::Null, |stats| stats.to_json()),
+            );
+        }
+        outlet
+    }
+
+    /// Inserts the properties describing the requested location, which are also written for a
+    /// request that could not be traced.
+    fn requested_properties(
+        &self,
+        properties: &mut JsonMap<String, JsonValue>,
+        request: Option<&RequestedLocation>,
+    ) {
+        let pntr = &self.inputs.pntr;
+        let lng_lat = request.and_then(|r| r.lng_lat);
+        let cell = request.and_then(|r| r.cell);
+        // The given easting/northing, or else the centre of the requested cell.
+        let map_xy = request
+            .and_then(|r| r.xy)
+            .or_else(|| cell.map(|(r, c)| (pntr.get_x_from_column(c), pntr.get_y_from_row(r))));
+        properties.insert(
+            "requested_lon".to_string(),
+            match lng_lat {
+                Some((lon, _)) => json!(lon),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "requested_lat".to_string(),
+            match lng_lat {
+                Some((_, lat)) => json!(lat),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "requested_row".to_string(),
+            match cell {
+                Some((r, _)) => json!(r),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "requested_col".to_string(),
+            match cell {
+                Some((_, c)) => json!(c),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "requested_easting".to_string(),
+            match map_xy {
+                Some((x, _)) => json!(x),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "requested_northing".to_string(),
+            match map_xy {
+                Some((_, y)) => json!(y),
+                None => JsonValue::Null,
+            },
+        );
+        if self.args.snap_radius.is_some() {
+            let snapped = request.and_then(|r| r.snapped);
+            properties.insert(
+                "snapped_row".to_string(),
+                match snapped {
+                    Some(((r, _), _)) => json!(r),
+                    None => JsonValue::Null,
+                },
+            );
+            properties.insert(
+                "snapped_col".to_string(),
+                match snapped {
+                    Some(((_, c), _)) => json!(c),
+                    None => JsonValue::Null,
+                },
+            );
+            properties.insert(
+                "snapped_accum".to_string(),
+                match snapped {
+                    Some((_, value)) => json!(value),
+                    None => JsonValue::Null,
+                },
+            );
+        }
+    }
+
+    /// The feature of a requested location that could not be traced, at the requested cell.
+    fn failure_feature(
+        &self,
+        id: usize,
+        reason: &str,
+        request: Option<&RequestedLocation>,
+    ) -> Feature {
+        let pntr = &self.inputs.pntr;
+        let epsg_code = pntr.configs.epsg_code;
+        let mut properties: JsonMap<String, JsonValue> = JsonMap::new();
+        properties.insert("Id".to_string(), json!(id));
+        properties.insert("status".to_string(), json!("failure"));
+        properties.insert("failure_reason".to_string(), json!(reason));
+        properties.insert("epsg".to_string(), json!(epsg_code));
+        properties.insert(
+            "pntr_scheme".to_string(),
+            json!(pointer_scheme_name(self.inputs.esri_style)),
+        );
+        self.requested_properties(&mut properties, request);
+        let geometry = request.and_then(|r| r.cell).map(|(r, c)| {
+            Geometry::new(GeoValue::Point(vec![
+                pntr.get_x_from_column(c),
+                pntr.get_y_from_row(r),
+            ]))
+        });
+        Feature {
+            bbox: None,
+            geometry,
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        }
+    }
+
+    /// The point feature of a traced outlet and its properties.
+    fn outlet_feature(
+        &self,
+        id: usize,
+        selected: &SelectedTrace,
+        selection: &Selection,
+        request: Option<&RequestedLocation>,
+        verbose: bool,
+    ) -> Feature {
+        let pntr = &self.inputs.pntr;
+        let epsg_code = pntr.configs.epsg_code;
+        let batch = self.requests.len() > 1;
+        let TraceSuccessData {
+            outlet_row,
+            outlet_col,
+            steps_taken,
+            steps_beyond_mask,
+            path_length,
+            length_beyond_mask,
+            outlet_downstream,
+            outlet_junction_count,
+            mask_fallback,
+            ..
+        } = selected.success;
+        let easting = pntr.get_x_from_column(outlet_col);
+        let northing = pntr.get_y_from_row(outlet_row);
+        let start_row = selected.start_row;
+        let start_col = selected.start_col;
+        let start_mode_str = selected.start_mode.as_str();
+        let distance_to_boundary = selected.distance_to_boundary;
+        // The straight-line distance from the start cell to the nearest boundary cell.
+        let distance_to_boundary_m = if distance_to_boundary >= 0 {
+            let distance = self
+                .cells
+                .boundary_cells
+                .iter()
+                .map(|&cell| self.ctx.cell_distance((start_row, start_col), cell))
+                .fold(f64::INFINITY, f64::min);
+            json!(distance)
+        } else {
+            JsonValue::Null
+        };
+        let candidate_rank = selected.candidate_rank;
+        let start_offset_cells = selected.start_offset_cells;
+        let start_in_mask =
+            self.cells.mask_has_data && self.cells.mask.get_value(start_row, start_col) == 1u8;
+        let outlet_in_mask =
+            self.cells.mask_has_data && self.cells.mask.get_value(outlet_row, outlet_col) == 1u8;
+
+        let mut properties: JsonMap<String, JsonValue> = JsonMap::new();
+        properties.insert("Id".to_string(), json!(id));
+        if batch {
+            properties.insert("status".to_string(), json!("success"));
+            properties.insert("failure_reason".to_string(), JsonValue::Null);
+        }
+        properties.insert("row".to_string(), json!(outlet_row));
+        properties.insert("column".to_string(), json!(outlet_col));
+        properties.insert("easting".to_string(), json!(easting));
+        properties.insert("northing".to_string(), json!(northing));
+        properties.insert("epsg".to_string(), json!(epsg_code));
+        properties.insert(
+            "centroid_row".to_string(),
+            if self.cells.mask_has_data {
+                json!(self.cells.centroid_row)
+            } else {
+                JsonValue::Null
+            },
+        );
+        properties.insert(
+            "centroid_col".to_string(),
+            if self.cells.mask_has_data {
+                json!(self.cells.centroid_col)
+            } else {
+                JsonValue::Null
+            },
+        );
+        properties.insert(
+            "distance_to_boundary".to_string(),
+            if distance_to_boundary >= 0 {
+                json!(distance_to_boundary)
+            } else {
+                JsonValue::Null
+            },
+        );
+        properties.insert("distance_to_boundary_m".to_string(), distance_to_boundary_m);
+        properties.insert("start_mode".to_string(), json!(start_mode_str));
+        properties.insert("start_row".to_string(), json!(start_row));
+        properties.insert("start_col".to_string(), json!(start_col));
+        properties.insert("start_in_mask".to_string(), json!(start_in_mask));
+        properties.insert(
+            "start_distance_to_boundary".to_string(),
+            if distance_to_boundary >= 0 {
+                json!(distance_to_boundary)
+            } else {
+                JsonValue::Null
+            },
+        );
+        properties.insert("start_offset_cells".to_string(), json!(start_offset_cells));
+        properties.insert(
+            "start_cell_type".to_string(),
+            json!(if is_stream_value(
+                self.inputs.streams[(start_row, start_col)],
+                self.inputs.streams.configs.nodata,
+                self.inputs.stream_filter
+            ) {
+                "stream"
+            } else {
+                "non_stream"
+            }),
+        );
+        properties.insert("steps_from_start".to_string(), json!(steps_taken));
+        properties.insert("steps_from_center".to_string(), json!(steps_taken));
+        properties.insert("steps_beyond_mask".to_string(), json!(steps_beyond_mask));
+        properties.insert("path_length_m".to_string(), json!(path_length));
+        properties.insert(
+            "length_beyond_mask_m".to_string(),
+            json!(length_beyond_mask),
+        );
+        properties.insert(
+            "candidate_rank".to_string(),
+            match candidate_rank {
+                Some(val) => json!(val),
+                None => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "candidates_considered".to_string(),
+            json!(self.cells.max_candidates),
+        );
+        properties.insert("max_steps_used".to_string(), json!(self.ctx.max_steps));
+        properties.insert("num_procs".to_string(), json!(self.num_procs));
+        properties.insert(
+            "pntr_scheme".to_string(),
+            json!(pointer_scheme_name(self.inputs.esri_style)),
+        );
+        properties.insert(
+            "streams_source".to_string(),
+            json!(if self.inputs.streams_derived {
+                "flow_accum"
+            } else {
+                "streams"
+            }),
+        );
+        properties.insert(
+            "accum_threshold".to_string(),
+            match self.args.accum_threshold {
+                Some(val) if self.inputs.streams_derived => json!(val),
+                _ => JsonValue::Null,
+            },
+        );
+        properties.insert(
+            "watershed_cell_count".to_string(),
+            json!(self.cells.total_cells),
+        );
+        properties.insert("mask_erosion".to_string(), json!(self.args.mask_erosion));
+        properties.insert(
+            "outlet_mask_value".to_string(),
+            if self.cells.mask_has_data {
+                json!(self.cells.mask.get_value(outlet_row, outlet_col))
+            } else {
+                JsonValue::Null
+            },
+        );
+        properties.insert("outlet_in_mask".to_string(), json!(outlet_in_mask));
+        properties.insert(
+            "outlet_downstream_of_mask".to_string(),
+            json!(outlet_downstream),
+        );
+        properties.insert(
+            "outlet_junction_count".to_string(),
+            json!(outlet_junction_count),
+        );
+        properties.insert(
+            "outlet_stream_value".to_string(),
+            json!(self.inputs.streams[(outlet_row, outlet_col)]),
+        );
+        if let Some(accum) = &self.inputs.accum {
+            let value = accum[(outlet_row, outlet_col)];
+            let cell_area = self.ctx.cell_area(outlet_row, outlet_col);
+            let drainage_area = if value == accum.configs.nodata || value.is_nan() {
+                None
+            } else if self.args.sca {
+                Some(value)
+            } else {
+                Some(value * cell_area)
+            };
+            // The mask area is also measured with the outlet's cell area, so that the ratio
+            // of a cell-count accumulation does not depend on it.
+            let ratio = drainage_area
+                .filter(|_| self.cells.mask_has_data && self.cells.total_cells > 0)
+                .map(|area| area / (self.cells.total_cells as f64 * cell_area));
+            properties.insert(
+                "outlet_flow_accum".to_string(),
+                drainage_area.map_or(JsonValue::Null, |_| json!(value)),
+            );
+            properties.insert(
+                "outlet_drainage_area_m2".to_string(),
+                drainage_area.map_or(JsonValue::Null, |area| json!(area)),
+            );
+            properties.insert(
+                "drainage_area_ratio".to_string(),
+                ratio.map_or(JsonValue::Null, |ratio| json!(ratio)),
+            );
+            if let Some(ratio) = ratio {
+                if verbose && (ratio - 1f64).abs() > self.args.area_ratio_tolerance {
+                    println!(
+                        "Warning: The drainage area at outlet {} (row {}, col {}) is {:.3} times the watershed mask area; the trace may have reached the wrong stream.",
+                        id, outlet_row, outlet_col, ratio
+                    );
+                }
+            }
+        }
+        if let Some(count) = selection.upstream_cells[id] {
+            properties.insert("upstream_mask_cells".to_string(), json!(count));
+        }
+        if let Some(support) = &selection.candidate_support {
+            properties.insert(
+                "n_alternative_outlets".to_string(),
+                json!(support.alternatives.len()),
+            );
+            properties.insert(
+                "selected_outlet_support".to_string(),
+                json!(support.selected_traces as f64 / support.traces as f64),
+            );
+            let alternatives: Vec<JsonValue> = support
+                .alternatives
+                .iter()
+                .take(5)
+                .map(|&((row, col), traces)| {
+                    json!({
+                        "row": row,
+                        "col": col,
+                        "easting": pntr.get_x_from_column(col),
+                        "northing": pntr.get_y_from_row(row),
+                        "traces": traces,
+                    })
+                })
+                .collect();
+            properties.insert(
+                "alternative_outlets".to_string(),
+                JsonValue::Array(alternatives),
+            );
+        }
+        if self.args.outlet_within_mask {
+            if verbose && mask_fallback {
+                println!(
+                    "Outlet {}: the trace left the watershed without an outlet inside it; using the last stream cell inside the mask with a junction count of 1.",
+                    id
+                );
+            }
+            properties.insert("mask_fallback".to_string(), json!(mask_fallback));
+        }
+        if let Some(((row, col), snapped)) = selection.junction_snaps[id] {
+            properties.insert("junction_snapped".to_string(), json!(snapped));
+            properties.insert("original_outlet_row".to_string(), json!(row));
+            properties.insert("original_outlet_col".to_string(), json!(col));
+        }
+        if let Some(dem) = &self.inputs.dem {
+            let channel = channel_slope_upstream(
+                &selected.success.path,
+                self.args.slope_steps.unwrap_or(10),
+                dem,
+                self.ctx,
+            );
+            properties.insert(
+                "outlet_elevation".to_string(),
+                match channel.outlet_elevation {
+                    Some(z) => json!(z),
+                    None => JsonValue::Null,
+                },
+            );
+            properties.insert(
+                "channel_slope".to_string(),
+                match channel.slope {
+                    Some(slope) => json!(slope),
+                    None => JsonValue::Null,
+                },
+            );
+            properties.insert("channel_slope_steps".to_string(), json!(channel.steps));
+            properties.insert("channel_slope_length".to_string(), json!(channel.length));
+        }
+        properties.insert(
+            "perimeter_stream_count".to_string(),
+            json!(self.cells.perimeter_stream_cells.len()),
+        );
+        self.requested_properties(&mut properties, request);
+        properties.insert(
+            "requested_cell_offset".to_string(),
+            if request.and_then(|r| r.cell).is_some() {
+                json!(start_offset_cells)
+            } else {
+                JsonValue::Null
+            },
+        );
+        if !self.cells.perimeter_stream_cells.is_empty() {
+            let preview: Vec<JsonValue> = self
+                .cells
+                .perimeter_stream_cells
+                .iter()
+                .take(5)
+                .map(|(r, c)| json!({"row": r, "col": c}))
+                .collect();
+            properties.insert(
+                "perimeter_stream_samples".to_string(),
+                JsonValue::Array(preview),
+            );
+        }
+        let geometry = Geometry::new(GeoValue::Point(vec![easting, northing]));
+        Feature {
+            bbox: None,
+            geometry: Some(geometry),
+            id: None,
+            properties: Some(properties),
+            foreign_members: None,
+        }
+    }
+
+    /// The flow path walked from the start cell to the outlet (`--trace_path`), as a line through
+    /// the cell centres.
+    fn trace_path_feature(
+        &self,
+        path_id: usize,
+        outlet_id: usize,
+        selected: &SelectedTrace,
+    ) -> Feature {
+        let pntr = &self.inputs.pntr;
+        let success = &selected.success;
+        // As with the downstream previews, a trace that starts on its outlet is written
+        // as a zero-length line.
+        let mut coordinates: Vec<Vec<f64>> = success
+            .path
+            .iter()
+            .map(|&(r, c)| vec![pntr.get_x_from_column(c), pntr.get_y_from_row(r)])
+            .collect();
+        if coordinates.len() == 1 {
+            coordinates.push(coordinates[0].clone());
+        }
+        let mut path_properties: JsonMap<String, JsonValue> = JsonMap::new();
+        path_properties.insert("Id".to_string(), json!(path_id));
+        path_properties.insert("outlet_id".to_string(), json!(outlet_id));
+        path_properties.insert("steps_taken".to_string(), json!(success.steps_taken));
+        path_properties.insert(
+            "steps_beyond_mask".to_string(),
+            json!(success.steps_beyond_mask),
+        );
+        path_properties.insert("path_length_m".to_string(), json!(success.path_length));
+        path_properties.insert(
+            "length_beyond_mask_m".to_string(),
+            json!(success.length_beyond_mask),
+        );
+        path_properties.insert(
+            "start_mode".to_string(),
+            json!(selected.start_mode.as_str()),
+        );
+        Feature {
+            bbox: None,
+            geometry: Some(Geometry::new(GeoValue::LineString(coordinates))),
+            id: None,
+            properties: Some(path_properties),
+            foreign_members: None,
+        }
+    }
+}
+
+/// Writes the stream junction counts used by the trace (`--junctions`).
+pub(super) fn write_junctions(
+    args: &FindOutletArgs,
+    pntr: &Raster,
+    junction_counts: &Array2D<i16>,
+    compress: bool,
+    tool_name: &str,
+    verbose: bool,
+) -> Result<(), Error> {
+    let mut output = Raster::initialize_using_file(&args.junctions_file, pntr);
+    output.configs.data_type = DataType::I16;
+    output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+    output.configs.nodata = -32768f64;
+    output.reinitialize_values(-32768f64);
+    for row in 0..pntr.configs.rows as isize {
+        for col in 0..pntr.configs.columns as isize {
+            let cnt = junction_counts.get_value(row, col);
+            if cnt >= 0 {
+                output.set_value(row, col, cnt as f64);
+            }
+        }
+    }
+    output.configs.display_min = 0f64;
+    output.configs.display_max = 8f64;
+    output.set_compression(Some(compress));
+    output.add_metadata_entry(format!("Created by whitebox_tools\' {} tool", tool_name));
+    output.add_metadata_entry(format!("D8 pointer file: {}", args.d8_file));
+    if verbose {
+        println!("Writing junction counts to {}...", args.junctions_file);
+    }
+    output.write()
+}
+
+/// The attribute fields of a Shapefile output: the GeoJSON property, its DBF field name (which
+/// is limited to 10 characters), and the field type, width, and precision.
+const SHAPEFILE_FIELDS: [(&str, &str, FieldDataType, u8, u8); 63] = [
+    ("Id", "Id", FieldDataType::Int, 8, 0),
+    ("status", "status", FieldDataType::Text, 8, 0),
+    ("failure_reason", "fail_msg", FieldDataType::Text, 254, 0),
+    ("row", "row", FieldDataType::Int, 10, 0),
+    ("column", "column", FieldDataType::Int, 10, 0),
+    ("easting", "easting", FieldDataType::Real, 18, 6),
+    ("northing", "northing", FieldDataType::Real, 18, 6),
+    ("epsg", "epsg", FieldDataType::Int, 6, 0),
+    ("centroid_row", "cent_row", FieldDataType::Real, 14, 3),
+    ("centroid_col", "cent_col", FieldDataType::Real, 14, 3),
+    (
+        "distance_to_boundary",
+        "bnd_dist",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    (
+        "distance_to_boundary_m",
+        "bnd_dist_m",
+        FieldDataType::Real,
+        18,
+        3,
+    ),
+    ("start_mode", "start_mode", FieldDataType::Text, 16, 0),
+    ("start_row", "start_row", FieldDataType::Int, 10, 0),
+    ("start_col", "start_col", FieldDataType::Int, 10, 0),
+    ("start_in_mask", "start_in_m", FieldDataType::Bool, 1, 0),
+    (
+        "start_distance_to_boundary",
+        "start_bnd",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    ("start_offset_cells", "start_off", FieldDataType::Int, 10, 0),
+    ("start_cell_type", "start_type", FieldDataType::Text, 10, 0),
+    ("steps_from_start", "steps", FieldDataType::Int, 10, 0),
+    ("steps_from_center", "steps_ctr", FieldDataType::Int, 10, 0),
+    ("steps_beyond_mask", "steps_bynd", FieldDataType::Int, 10, 0),
+    ("path_length_m", "path_len_m", FieldDataType::Real, 18, 3),
+    (
+        "length_beyond_mask_m",
+        "len_bynd_m",
+        FieldDataType::Real,
+        18,
+        3,
+    ),
+    ("candidate_rank", "cand_rank", FieldDataType::Int, 10, 0),
+    (
+        "candidates_considered",
+        "cand_count",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    ("max_steps_used", "max_steps", FieldDataType::Int, 12, 0),
+    ("num_procs", "num_procs", FieldDataType::Int, 6, 0),
+    ("pntr_scheme", "pntr_schm", FieldDataType::Text, 8, 0),
+    ("streams_source", "strm_src", FieldDataType::Text, 10, 0),
+    ("accum_threshold", "accum_thr", FieldDataType::Real, 18, 6),
+    (
+        "watershed_cell_count",
+        "ws_cells",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    ("mask_erosion", "mask_eros", FieldDataType::Int, 6, 0),
+    ("outlet_mask_value", "mask_val", FieldDataType::Int, 6, 0),
+    ("outlet_in_mask", "in_mask", FieldDataType::Bool, 1, 0),
+    (
+        "outlet_downstream_of_mask",
+        "downstream",
+        FieldDataType::Bool,
+        1,
+        0,
+    ),
+    (
+        "outlet_junction_count",
+        "junctions",
+        FieldDataType::Int,
+        6,
+        0,
+    ),
+    (
+        "outlet_stream_value",
+        "strm_val",
+        FieldDataType::Real,
+        18,
+        6,
+    ),
+    ("outlet_flow_accum", "out_accum", FieldDataType::Real, 18, 6),
+    (
+        "outlet_drainage_area_m2",
+        "drain_m2",
+        FieldDataType::Real,
+        18,
+        3,
+    ),
+    (
+        "drainage_area_ratio",
+        "area_ratio",
+        FieldDataType::Real,
+        18,
+        6,
+    ),
+    ("mask_fallback", "mask_fallb", FieldDataType::Bool, 1, 0),
+    ("junction_snapped", "jct_snap", FieldDataType::Bool, 1, 0),
+    ("original_outlet_row", "orig_row", FieldDataType::Int, 10, 0),
+    ("original_outlet_col", "orig_col", FieldDataType::Int, 10, 0),
+    ("outlet_elevation", "out_elev", FieldDataType::Real, 18, 6),
+    ("channel_slope", "chn_slope", FieldDataType::Real, 18, 8),
+    (
+        "channel_slope_steps",
+        "slope_stps",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    (
+        "channel_slope_length",
+        "slope_len",
+        FieldDataType::Real,
+        18,
+        6,
+    ),
+    (
+        "perimeter_stream_count",
+        "perim_strm",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    ("requested_lon", "req_lon", FieldDataType::Real, 18, 8),
+    ("requested_lat", "req_lat", FieldDataType::Real, 18, 8),
+    ("requested_row", "req_row", FieldDataType::Int, 10, 0),
+    ("requested_col", "req_col", FieldDataType::Int, 10, 0),
+    ("requested_easting", "req_east", FieldDataType::Real, 18, 6),
+    (
+        "requested_northing",
+        "req_north",
+        FieldDataType::Real,
+        18,
+        6,
+    ),
+    (
+        "requested_cell_offset",
+        "req_offset",
+        FieldDataType::Int,
+        10,
+        0,
+    ),
+    ("snapped_row", "snap_row", FieldDataType::Int, 10, 0),
+    ("snapped_col", "snap_col", FieldDataType::Int, 10, 0),
+    ("snapped_accum", "snap_accum", FieldDataType::Real, 18, 6),
+    ("upstream_mask_cells", "up_cells", FieldDataType::Int, 10, 0),
+    (
+        "n_alternative_outlets",
+        "n_alt_out",
+        FieldDataType::Int,
+        6,
+        0,
+    ),
+    (
+        "selected_outlet_support",
+        "support",
+        FieldDataType::Real,
+        18,
+        6,
+    ),
+];
+
+/// Writes the point features of the outlets to a Shapefile, with a field for each of the
+/// `SHAPEFILE_FIELDS` found among their properties. A feature without a geometry, i.e. a
+/// requested location that could not be converted to a grid cell, is written as a null shape.
+/// The columns of the `--summary_csv` output and the outlet properties that they are read from.
+const SUMMARY_CSV_COLUMNS: [(&str, &str); 13] = [
+    ("id", "Id"),
+    ("row", "row"),
+    ("col", "column"),
+    ("easting", "easting"),
+    ("northing", "northing"),
+    ("epsg", "epsg"),
+    ("start_mode", "start_mode"),
+    ("steps_taken", "steps_from_start"),
+    ("steps_beyond_mask", "steps_beyond_mask"),
+    ("path_length_m", "path_length_m"),
+    ("outlet_junction_count", "outlet_junction_count"),
+    ("distance_to_boundary", "distance_to_boundary"),
+    ("distance_to_boundary_m", "distance_to_boundary_m"),
+];
+
+/// Writes one CSV row per successful outlet feature. The rows are written to a temporary file
+/// beside the output, which is renamed over it once complete.
+fn write_summary_csv(
+    file_name: &str,
+    features: &[Feature],
+    elapsed_seconds: f64,
+) -> Result<(), Error> {
+    let mut text = SUMMARY_CSV_COLUMNS
+        .iter()
+        .map(|(column, _)| *column)
+        .collect::<Vec<&str>>()
+        .join(",");
+    text.push_str(",elapsed_seconds\n");
+    for properties in features.iter().filter_map(|f| f.properties.as_ref()) {
+        if properties.get("status").and_then(|v| v.as_str()) == Some("failure") {
+            continue;
+        }
+        for (_, key) in SUMMARY_CSV_COLUMNS.iter() {
+            match properties.get(*key) {
+                Some(JsonValue::String(s)) => text.push_str(s),
+                Some(JsonValue::Null) | None => {}
+                Some(value) => text.push_str(&value.to_string()),
+            }
+            text.push(',');
+        }
+        text.push_str(&format!("{}\n", elapsed_seconds));
+    }
+
+    let temp_file = format!("{}.tmp", file_name);
+    let written = File::create(&temp_file).and_then(|mut file| {
+        file.write_all(text.as_bytes())?;
+        file.sync_all()
+    });
+    match written.and_then(|_| std::fs::rename(&temp_file, file_name)) {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            let _ = std::fs::remove_file(&temp_file);
+            Err(Error::new(
+                e.kind(),
+                format!("Unable to write summary CSV file {}: {}", file_name, e),
+            ))
+        }
+    }
+}
+
+fn write_outlet_shapefile(
+    file_name: &str,
+    features: &[Feature],
+    projection: &str,
+) -> Result<(), Error> {
+    let fields: Vec<&(&str, &str, FieldDataType, u8, u8)> = SHAPEFILE_FIELDS
+        .iter()
+        .filter(|(key, ..)| {
+            features.iter().any(|feature| {
+                feature
+                    .properties
+                    .as_ref()
+                    .is_some_and(|p| p.contains_key(*key))
+            })
+        })
+        .collect();
+
+    let mut output = VectorWriter::new(file_name, ShapeType::Point)?;
+    output.projection = projection.to_string();
+    for (_, name, field_type, width, precision) in &fields {
+        output.add_field(&AttributeField::new(
+            name,
+            field_type.clone(),
+            *width,
+            *precision,
+        ));
+    }
+
+    for feature in features {
+        let mut geometry = ShapefileGeometry::default();
+        if let Some(GeoValue::Point(coords)) = feature.geometry.as_ref().map(|g| &g.value) {
+            geometry = ShapefileGeometry::new(ShapeType::Point);
+            geometry.add_point(Point2D::new(coords[0], coords[1]));
+        }
+        let record = fields
+            .iter()
+            .map(|(key, _, field_type, ..)| {
+                let value = feature.properties.as_ref().and_then(|p| p.get(*key));
+                match (field_type, value) {
+                    (FieldDataType::Int, Some(v)) => v.as_i64().map(|i| FieldData::Int(i as i32)),
+                    (FieldDataType::Real, Some(v)) => v.as_f64().map(FieldData::Real),
+                    (FieldDataType::Bool, Some(v)) => v.as_bool().map(FieldData::Bool),
+                    (FieldDataType::Text, Some(v)) => {
+                        v.as_str().map(|s| FieldData::Text(s.to_string()))
+                    }
+                    _ => None,
+                }
+                .unwrap_or(FieldData::Null)
+            })
+            .collect();
+        output.add_record(geometry, record);
+    }
+    output.write()
+}
@@ -0,0 +1,560 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use super::args::FindOutletArgs;
+use super::inputs::Inputs;
+use super::trace::{is_stream_value, StreamFilter};
+use crate::tools::hydro_analysis::pour_point_locator::locate_pour_points;
+use std::collections::{HashSet, VecDeque};
+use std::f64;
+use std::io::Error;
+use whitebox_common::algorithms::{erode_mask, StructuringElement, D8_DX, D8_DY};
+use whitebox_common::error::WbtError;
+use whitebox_common::spatial_ref_system::Projection;
+use whitebox_common::structures::Array2D;
+use whitebox_raster::*;
+
+/// The cells of the watershed mask from which outlets are searched for: the mask itself, its
+/// boundary, the distance of each cell to the boundary, and the candidate start cells ranked by
+/// that distance.
+pub(super) struct WatershedCells {
+    /// The mask cells hold 1. Without a watershed, no cell does.
+    pub(super) mask: Array2D<u8>,
+    pub(super) mask_has_data: bool,
+    pub(super) total_cells: usize,
+    pub(super) centroid_row: f64,
+    pub(super) centroid_col: f64,
+    pub(super) boundary_cells: Vec<(isize, isize)>,
+    pub(super) perimeter_stream_cells: Vec<(isize, isize)>,
+    /// The distance of each cell of the (eroded) mask to its boundary, in cells; only allocated
+    /// when there is a watershed to measure them in.
+    distances: Array2D<i32>,
+    /// The candidates, as (distance, row, column), by decreasing distance to the boundary.
+    pub(super) candidates: Vec<(i32, isize, isize)>,
+    /// The number of candidates that may be traced (`--max_candidates`).
+    pub(super) max_candidates: usize,
+}
+
+impl WatershedCells {
+    /// Scans the watershed mask, if any, for its boundary cells, measures the distance of every
+    /// mask cell to the boundary, and ranks the candidates by it.
+    pub(super) fn scan(
+        args: &FindOutletArgs,
+        inputs: &Inputs,
+        verbose: bool,
+    ) -> Result<WatershedCells, Error> {
+        let rows = inputs.pntr.configs.rows as isize;
+        let columns = inputs.pntr.configs.columns as isize;
+        let streams = &inputs.streams;
+        let streams_nodata = streams.configs.nodata;
+        let stream_filter = inputs.stream_filter;
+        let mask_erosion = args.mask_erosion;
+        let mut progress: usize;
+        let mut old_progress: usize;
+
+        let mut mask: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut eroded_mask: Option<Array2D<u8>> = None;
+        let mut distances: Array2D<i32> = Array2D::new(0, 0, -1i32, -1i32)?;
+        let mut mask_has_data = false;
+        let mut total_cells: usize = 0;
+        let mut sum_row = 0f64;
+        let mut sum_col = 0f64;
+        let mut centroid_row = f64::NAN;
+        let mut centroid_col = f64::NAN;
+        let mut boundary_cells: Vec<(isize, isize)> = Vec::new();
+        let mut perimeter_stream_cells: Vec<(isize, isize)> = Vec::new();
+
+        if let Some(ref ws) = inputs.watershed {
+            if verbose {
+                println!("Building watershed mask...");
+            }
+            total_cells = mask.fill_where(ws, |v| v == 1u8, 1u8)?;
+            if total_cells == 0 {
+                return Err(WbtError::geometry_mismatch(if inputs.watershed_is_vector {
+                        "Watershed polygons (--watershed) do not contain the centre of any D8 pointer cell; they must be in the coordinate system of the D8 pointer raster."
+                    } else {
+                        "Watershed raster does not contain any positive-valued cells."
+                    }).into());
+            }
+
+            mask_has_data = true;
+
+            // Candidates are ranked within the mask eroded by --mask_erosion cells, which drops a
+            // fringe of noise along its boundary; traces still leave, and are reported against,
+            // the original mask.
+            if mask_erosion > 0 {
+                let mut cells = Vec::with_capacity((rows * columns) as usize);
+                for row in 0..rows {
+                    for col in 0..columns {
+                        cells.push(mask.get_value(row, col) == 1u8);
+                    }
+                }
+                let cells = erode_mask(
+                    &cells,
+                    rows as usize,
+                    columns as usize,
+                    mask_erosion,
+                    StructuringElement::Square,
+                );
+                let kept = cells.iter().filter(|&&set| set).count();
+                if kept == 0 {
+                    return Err(WbtError::geometry_mismatch(format!(
+                            "Eroding the watershed mask by {} cells (--mask_erosion) leaves no cells; use a smaller erosion.",
+                            mask_erosion
+                        )).into());
+                }
+                if verbose {
+                    println!(
+                        "Eroded the watershed mask by {} cells, leaving {} of {} cells.",
+                        mask_erosion, kept, total_cells
+                    );
+                }
+                let mut eroded = mask.duplicate();
+                for (i, _) in cells.iter().enumerate().filter(|&(_, &set)| !set) {
+                    eroded.set_value(i as isize / columns, i as isize % columns, 0u8);
+                }
+                eroded_mask = Some(eroded);
+            }
+            let scan_mask = eroded_mask.as_ref().unwrap_or(&mask);
+            // The boundary scan, the distances, and the candidates see the mask clipped to --bbox.
+            let in_scan_mask = |row: isize, col: isize| {
+                inputs.in_search_window(row, col) && scan_mask.get_value(row, col) == 1u8
+            };
+            // The centroid is accumulated during the boundary scan, which visits every mask cell.
+            old_progress = 1;
+            for row in 0..rows {
+                for col in 0..columns {
+                    if mask.get_value(row, col) == 1u8 {
+                        sum_row += row as f64;
+                        sum_col += col as f64;
+                    }
+                    if in_scan_mask(row, col) {
+                        let mut is_boundary = false;
+                        for n in 0..8 {
+                            let nr = row + D8_DY[n];
+                            let nc = col + D8_DX[n];
+                            if nr < 0 || nr >= rows || nc < 0 || nc >= columns {
+                                is_boundary = true;
+                                break;
+                            } else if !in_scan_mask(nr, nc) {
+                                is_boundary = true;
+                                break;
+                            }
+                        }
+                        if is_boundary {
+                            boundary_cells.push((row, col));
+                            if is_stream_value(streams[(row, col)], streams_nodata, stream_filter) {
+                                perimeter_stream_cells.push((row, col));
+                            }
+                        }
+                    }
+                }
+                if verbose && rows > 1 {
+                    progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                    if progress != old_progress {
+                        println!("Scanning watershed boundary: {}%", progress);
+                        old_progress = progress;
+                    }
+                }
+            }
+
+            centroid_row = sum_row / total_cells as f64;
+            centroid_col = sum_col / total_cells as f64;
+
+            if boundary_cells.is_empty() && args.bbox.is_some() {
+                return Err(WbtError::geometry_mismatch(
+                    "The bounding box (--bbox) does not contain any cells of the watershed mask.",
+                )
+                .into());
+            }
+            if boundary_cells.is_empty() {
+                return Err(WbtError::geometry_mismatch(
+                    "Unable to locate watershed boundary cells. Check the watershed raster values.",
+                )
+                .into());
+            }
+
+            if verbose {
+                println!(
+                    "Identified {} watershed cells ({} boundary cells).",
+                    total_cells,
+                    boundary_cells.len()
+                );
+                if !perimeter_stream_cells.is_empty() {
+                    println!(
+                        "Warning: Watershed perimeter intersects {} stream cells.",
+                        perimeter_stream_cells.len()
+                    );
+                }
+            }
+
+            distances = Array2D::new(rows, columns, -1i32, -1i32)?;
+            let mut queue: VecDeque<(isize, isize)> = VecDeque::with_capacity(boundary_cells.len());
+            for &(row, col) in &boundary_cells {
+                distances.set_value(row, col, 0);
+                queue.push_back((row, col));
+            }
+
+            while let Some((row, col)) = queue.pop_front() {
+                let base_distance = distances.get_value(row, col);
+                for n in 0..8 {
+                    let nr = row + D8_DY[n];
+                    let nc = col + D8_DX[n];
+                    if nr >= 0 && nr < rows && nc >= 0 && nc < columns {
+                        if in_scan_mask(nr, nc) && distances.get_value(nr, nc) == -1 {
+                            distances.set_value(nr, nc, base_distance + 1);
+                            queue.push_back((nr, nc));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut candidates: Vec<(i32, isize, isize)> = Vec::new();
+        if mask_has_data {
+            let scan_mask = eroded_mask.as_ref().unwrap_or(&mask);
+            for row in inputs.search_window.0.clone() {
+                for col in inputs.search_window.1.clone() {
+                    if scan_mask.get_value(row, col) == 1u8 {
+                        let dist = distances.get_value(row, col);
+                        candidates.push((dist, row, col));
+                    }
+                }
+            }
+            // Ties in distance are broken by row, then column, so runs are reproducible.
+            candidates.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+        }
+        let max_candidates = if mask_has_data {
+            candidates.len().min(args.max_candidates_limit)
+        } else {
+            0usize
+        };
+
+        Ok(WatershedCells {
+            mask,
+            mask_has_data,
+            total_cells,
+            centroid_row,
+            centroid_col,
+            boundary_cells,
+            perimeter_stream_cells,
+            distances,
+            candidates,
+            max_candidates,
+        })
+    }
+
+    /// The distance of a cell to the mask boundary, in cells, or -1 when it was not measured.
+    pub(super) fn distance_to_boundary(&self, row: isize, col: isize) -> i32 {
+        if self.mask_has_data {
+            self.distances.get_value(row, col)
+        } else {
+            -1
+        }
+    }
+}
+
+/// A requested outlet location and the grid cell it falls in, if it could be converted.
+pub(super) struct RequestedLocation {
+    pub(super) lng_lat: Option<(f64, f64)>,
+    /// The easting and northing given with `--requested_outlet_xy`.
+    pub(super) xy: Option<(f64, f64)>,
+    pub(super) cell: Option<(isize, isize)>,
+    /// The cell of highest flow accumulation near `cell`, and its accumulation, when snapping.
+    pub(super) snapped: Option<((isize, isize), f64)>,
+}
+
+impl RequestedLocation {
+    /// The cell from which the trace starts: the snapped cell, if any, or the requested one.
+    pub(super) fn start_cell(&self) -> Option<(isize, isize)> {
+        self.snapped.map(|(cell, _)| cell).or(self.cell)
+    }
+}
+
+/// Reads the requested outlet locations (`--requested_outlet_lng_lat`,
+/// `--requested_outlet_row_col`, `--requested_outlet_xy`, or `--pour_pts`) and converts them to
+/// grid cells, moved inside `--bbox` and snapped with `--snap_radius` where given. A single
+/// lon/lat that cannot be converted is a `conversion_failure`.
+pub(super) fn requested_locations(
+    args: &FindOutletArgs,
+    inputs: &Inputs,
+    pntr_projection: Option<&Projection>,
+    verbose: bool,
+) -> Result<Vec<RequestedLocation>, Error> {
+    let pntr = &inputs.pntr;
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let search_window = &inputs.search_window;
+    let mut requested_row_col = args.requested_row_col.clone();
+    // Every pour point is a requested outlet cell; several are traced as a batch.
+    if !args.pour_pts_file.is_empty() {
+        let pour_pts_file = &args.pour_pts_file;
+        requested_row_col = locate_pour_points(pour_pts_file, pntr).map_err(WbtError::from)?;
+        if requested_row_col.is_empty() {
+            return Err(WbtError::invalid_argument(format!(
+                "No pour points found in --pour_pts ({}).",
+                pour_pts_file
+            ))
+            .into());
+        }
+        if verbose {
+            println!(
+                "Read {} pour point(s) from {}.",
+                requested_row_col.len(),
+                pour_pts_file
+            );
+        }
+    }
+
+    // Each requested location is traced on its own from the shared junction counts and
+    // mask. With at most one location, a failed trace aborts the run (after falling back on
+    // the watershed candidates); with several, a failure is recorded in that request's
+    // feature and the other requests are still written.
+    let mut requests: Vec<RequestedLocation> = vec![];
+    if !requested_row_col.is_empty() {
+        for &(row, col) in &requested_row_col {
+            requests.push(RequestedLocation {
+                lng_lat: None,
+                xy: None,
+                cell: Some((clamp_index(row, rows - 1), clamp_index(col, columns - 1))),
+                snapped: None,
+            });
+        }
+    } else if !args.requested_xy.is_empty() {
+        for &(x, y) in &args.requested_xy {
+            requests.push(RequestedLocation {
+                lng_lat: None,
+                xy: Some((x, y)),
+                cell: Some((
+                    clamp_index(pntr.get_row_from_y(y), rows - 1),
+                    clamp_index(pntr.get_column_from_x(x), columns - 1),
+                )),
+                snapped: None,
+            });
+        }
+    } else if !args.requested_lng_lat.is_empty() {
+        if verbose && pntr_projection.is_none() {
+            println!("The coordinate system of the D8 pointer could not be identified from its EPSG code ({}) or WKT.", pntr.configs.epsg_code);
+        }
+        for &(lon, lat) in &args.requested_lng_lat {
+            requests.push(RequestedLocation {
+                lng_lat: Some((lon, lat)),
+                xy: None,
+                cell: lon_lat_to_row_col(pntr, pntr_projection, lon, lat),
+                snapped: None,
+            });
+        }
+    }
+    // A request outside --bbox is moved to the nearest cell inside it, as a request off the
+    // grid is moved to its edge.
+    if args.bbox.is_some() {
+        for (id, request) in requests.iter_mut().enumerate() {
+            if let Some((row, col)) = request.cell {
+                let clamped = (
+                    row.clamp(search_window.0.start, search_window.0.end - 1),
+                    col.clamp(search_window.1.start, search_window.1.end - 1),
+                );
+                if clamped != (row, col) {
+                    if verbose {
+                        println!(
+                                "Warning: Requested outlet {} at row {}, col {} is outside the bounding box (--bbox); using row {}, col {} instead.",
+                                id, row, col, clamped.0, clamped.1
+                            );
+                    }
+                    request.cell = Some(clamped);
+                }
+            }
+        }
+    }
+    if let (Some(radius), Some(accum)) = (args.snap_radius, &inputs.accum) {
+        for (id, request) in requests.iter_mut().enumerate() {
+            if let Some((row, col)) = request.cell {
+                request.snapped = snap_to_max_accum(accum, row, col, radius as isize);
+                if verbose {
+                    match request.snapped {
+                            Some(((r, c), value)) => println!(
+                                "Requested outlet {} snapped from row {}, col {} to row {}, col {} (accumulation {}).",
+                                id, row, col, r, c, value
+                            ),
+                            None => println!(
+                                "Warning: Requested outlet {} was not snapped; there is no flow accumulation data within {} cells of row {}, col {}.",
+                                id, radius, row, col
+                            ),
+                        }
+                }
+            }
+        }
+    }
+    if let (1, Some(request)) = (requests.len(), requests.first()) {
+        if let (None, Some(lng_lat)) = (request.cell, request.lng_lat) {
+            return Err(WbtError::conversion_failure(conversion_message(
+                lng_lat,
+                pntr.configs.epsg_code,
+            ))
+            .into());
+        }
+    }
+    Ok(requests)
+}
+
+/// The reason a requested lon/lat could not be traced from.
+pub(super) fn conversion_message((lon, lat): (f64, f64), epsg_code: u16) -> String {
+    format!(
+        "Unable to convert requested outlet lon/lat ({}, {}) to raster coordinates for EPSG {}. Provide --requested_outlet_row_col instead.",
+        lon, lat, epsg_code
+    )
+}
+
+/// Returns the cell with the highest flow accumulation within `radius` cells of (`row`, `col`),
+/// and its accumulation, preferring the cell nearest (`row`, `col`) on ties. Returns `None` when
+/// every cell of the window is NoData.
+fn snap_to_max_accum(
+    accum: &Raster,
+    row: isize,
+    col: isize,
+    radius: isize,
+) -> Option<((isize, isize), f64)> {
+    let nodata = accum.configs.nodata;
+    let rows = accum.configs.rows as isize;
+    let columns = accum.configs.columns as isize;
+    let mut best: Option<((isize, isize), f64, isize)> = None;
+    for r in (row - radius).max(0)..=(row + radius).min(rows - 1) {
+        for c in (col - radius).max(0)..=(col + radius).min(columns - 1) {
+            let value = accum.get_value(r, c);
+            if value == nodata || value.is_nan() {
+                continue;
+            }
+            let dist2 = (r - row) * (r - row) + (c - col) * (c - col);
+            let better = match best {
+                None => true,
+                Some((_, best_value, best_dist2)) => {
+                    value > best_value || (value == best_value && dist2 < best_dist2)
+                }
+            };
+            if better {
+                best = Some(((r, c), value, dist2));
+            }
+        }
+    }
+    best.map(|(cell, value, _)| (cell, value))
+}
+
+/// With `--snap_to_stream`, the stream cells that `find_nearest_valid_cell` prefers as a start
+/// cell, and the number of BFS steps (`--snap_max_radius`) within which it looks for one.
+#[derive(Clone, Copy)]
+pub(super) struct StreamSnap<'a> {
+    pub(super) streams: &'a Raster,
+    pub(super) filter: StreamFilter,
+    pub(super) max_radius: Option<usize>,
+}
+
+/// Searches outwards from (`row`, `col`), clamped to the grid, for the nearest cell with a valid
+/// D8 pointer, returning it and its distance in BFS steps. With `stream_snap`, a valid cell that is
+/// also a stream cell is preferred: the nearest one within the radius is returned if there is one,
+/// and the nearest valid cell of any kind otherwise.
+pub(super) fn find_nearest_valid_cell(
+    row: isize,
+    col: isize,
+    rows: isize,
+    columns: isize,
+    pntr: &Raster,
+    pntr_nodata: f64,
+    pntr_matches: &[i8; 129],
+    stream_snap: Option<StreamSnap>,
+) -> Option<((isize, isize), usize)> {
+    let mut start_row = clamp_index(row, rows - 1);
+    let mut start_col = clamp_index(col, columns - 1);
+    if start_row < 0 {
+        start_row = 0;
+    }
+    if start_col < 0 {
+        start_col = 0;
+    }
+
+    let mut queue: VecDeque<(isize, isize, usize)> = VecDeque::new();
+    let mut visited: HashSet<(isize, isize)> = HashSet::new();
+    queue.push_back((start_row, start_col, 0));
+    visited.insert((start_row, start_col));
+    // the nearest valid cell that is not a stream cell, while looking for a stream cell
+    let mut fallback: Option<((isize, isize), usize)> = None;
+
+    while let Some((r, c, dist)) = queue.pop_front() {
+        let within_radius = |snap: &StreamSnap| snap.max_radius.is_none_or(|max| dist <= max);
+        if let (Some(snap), Some(cell)) = (&stream_snap, fallback) {
+            if !within_radius(snap) {
+                return Some(cell);
+            }
+        }
+        let pointer = pntr[(r, c)];
+        if pointer != pntr_nodata && pointer > 0f64 {
+            let idx = pointer.round() as usize;
+            if idx < pntr_matches.len() && pntr_matches[idx] >= 0 {
+                match &stream_snap {
+                    Some(snap) if within_radius(snap) => {
+                        let value = snap.streams[(r, c)];
+                        if is_stream_value(value, snap.streams.configs.nodata, snap.filter) {
+                            return Some(((r, c), dist));
+                        }
+                        if fallback.is_none() {
+                            fallback = Some(((r, c), dist));
+                        }
+                    }
+                    _ => return Some(((r, c), dist)),
+                }
+            }
+        }
+
+        for n in 0..8 {
+            let nr = r + D8_DY[n];
+            let nc = c + D8_DX[n];
+            if nr >= 0 && nr < rows && nc >= 0 && nc < columns {
+                if visited.insert((nr, nc)) {
+                    queue.push_back((nr, nc, dist + 1));
+                }
+            }
+        }
+
+        if visited.len() > (rows * columns) as usize {
+            break;
+        }
+    }
+
+    fallback
+}
+
+fn clamp_index(value: isize, max: isize) -> isize {
+    if value < 0 {
+        0
+    } else if value > max {
+        max
+    } else {
+        value
+    }
+}
+
+/// Identifies the coordinate system of the D8 pointer raster from its EPSG code or, when the
+/// code is unknown or unsupported, from its WKT.
+pub(super) fn raster_projection(pntr: &Raster) -> Option<Projection> {
+    Projection::from_epsg(pntr.configs.epsg_code)
+        .or_else(|| Projection::from_wkt(&pntr.configs.coordinate_ref_system_wkt))
+        .or_else(|| Projection::from_wkt(&pntr.configs.projection))
+}
+
+/// Converts a WGS84 lon/lat to the cell of the D8 pointer raster containing it, clamped to the
+/// raster's extent. Returns `None` when the raster's coordinate system is not known.
+pub(super) fn lon_lat_to_row_col(
+    pntr: &Raster,
+    projection: Option<&Projection>,
+    lon: f64,
+    lat: f64,
+) -> Option<(isize, isize)> {
+    let (x, y) = projection?.forward(lon, lat)?;
+    Some((
+        clamp_index(pntr.get_row_from_y(y), pntr.configs.rows as isize - 1),
+        clamp_index(pntr.get_column_from_x(x), pntr.configs.columns as isize - 1),
+    ))
+}
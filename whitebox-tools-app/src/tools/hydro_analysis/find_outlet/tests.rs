@@ -0,0 +1,3116 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use super::args::parse_bbox;
+use super::inputs::{bbox_window, PointerSchemeVotes};
+use super::start::{find_nearest_valid_cell, lon_lat_to_row_col, raster_projection, StreamSnap};
+use super::trace::{
+    candidate_threads, count_junctions, is_stream_value, mask_scan_window, pointer_tables,
+    resolve_num_procs, snap_outlet_to_junction, trace_candidates, CandidateStats, StreamFilter,
+    TraceAttempt, TraceContext, TraceFailureKind, TraceStartMode, TraceSuccessData,
+    JUNCTIONS_UNSCANNED,
+};
+use super::FindOutlet;
+use crate::tools::{failure_class, FailureClass, WhiteboxTool};
+use serde_json::{json, Map, Value};
+use std::fs;
+use whitebox_common::error::WbtError;
+use whitebox_common::spatial_ref_system::{esri_wkt_from_epsg, Projection};
+use whitebox_common::structures::{Array2D, Point2D};
+use whitebox_common::test_support::{temp_dir, GridFixture};
+use whitebox_common::utils::utm_to_deg;
+use whitebox_raster::test_support::grid_fixture_raster;
+use whitebox_raster::Raster;
+use whitebox_vector::{
+    AttributeField, FieldData, FieldDataType, ShapeType, Shapefile, ShapefileGeometry,
+};
+
+#[test]
+fn test_resolve_num_procs() {
+    assert_eq!(resolve_num_procs(1, 16), 1);
+    assert_eq!(resolve_num_procs(4, 16), 4);
+    assert_eq!(resolve_num_procs(32, 16), 16);
+    assert_eq!(resolve_num_procs(-1, 16), 16);
+    assert_eq!(resolve_num_procs(0, 16), 16);
+}
+
+// Every cell of a 4 x 5 grid flows east into a stream in the last column, which lies
+// outside the watershed mask. Only the stream cell in row 3 has a single inflow.
+#[test]
+fn test_max_procs_of_one_forces_serial_candidate_evaluation() {
+    let (rows, columns) = (4isize, 5isize);
+    let pntr = grid_fixture_raster("pntr.tif", &GridFixture::new(4, 5, |_, _| 2f64));
+    let streams = grid_fixture_raster(
+        "streams.tif",
+        &GridFixture::new(4, 5, |_, c| if c == columns - 1 { 1f64 } else { 0f64 }),
+    );
+    let mut mask: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8).unwrap();
+    let mut junction_counts: Array2D<i16> = Array2D::new(rows, columns, -1i16, -1i16).unwrap();
+    for row in 0..rows {
+        for col in 0..columns {
+            if col == columns - 1 {
+                junction_counts.set_value(row, col, if row == 3 { 1 } else { 2 });
+            } else {
+                mask.set_value(row, col, 1u8);
+            }
+        }
+    }
+    let tables = pointer_tables(false);
+    let ctx = trace_context(&pntr, &streams, &mask, &junction_counts, &tables);
+    let candidates = vec![(0, 0isize, 0isize), (0, 3, 0), (0, 1, 0), (0, 2, 0)];
+
+    let num_threads = candidate_threads(resolve_num_procs(1, 16), candidates.len());
+    assert_eq!(num_threads, 1);
+    let (serial, serial_failures, serial_traced) = trace_candidates(&candidates, &ctx, num_threads);
+    // the serial evaluation stops at the first successful candidate
+    assert_eq!(serial_traced, 2);
+    assert_eq!(serial_failures.len(), 1);
+    let serial = serial.unwrap();
+    assert_eq!(serial.candidate_rank, Some(1));
+    assert_eq!(
+        (serial.success.outlet_row, serial.success.outlet_col),
+        (3, 4)
+    );
+
+    let num_threads = candidate_threads(resolve_num_procs(-1, 16), candidates.len());
+    assert_eq!(num_threads, 4);
+    let (parallel, parallel_failures, parallel_traced) =
+        trace_candidates(&candidates, &ctx, num_threads);
+    // the threads may trace candidates ranked behind the winner before they learn of it
+    assert!(parallel_traced >= 2);
+    assert_eq!(parallel_failures, serial_failures);
+    let parallel = parallel.unwrap();
+    assert_eq!(parallel.candidate_rank, Some(1));
+    assert_eq!(
+        (parallel.success.outlet_row, parallel.success.outlet_col),
+        (3, 4)
+    );
+}
+
+// A benchmark-style check on a 150 x 200 grid flowing east into a stream outside the mask,
+// whose only single-inflow cell is in row 97: 512 candidates in scrambled order, of which
+// ranks 400 and 450 succeed, are traced on 1 to 16 threads. Each run must select rank 400
+// and report the same failures; the timings are printed for comparison.
+#[test]
+fn test_parallel_candidate_selection_is_deterministic() {
+    let (rows, columns) = (150isize, 200isize);
+    let outlet_row = 97isize;
+    let pntr = grid_fixture_raster("pntr.tif", &GridFixture::new(150, 200, |_, _| 2f64));
+    let streams = grid_fixture_raster(
+        "streams.tif",
+        &GridFixture::new(150, 200, |_, c| if c == columns - 1 { 1f64 } else { 0f64 }),
+    );
+    let mut mask: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8).unwrap();
+    let mut junction_counts: Array2D<i16> = Array2D::new(rows, columns, -1i16, -1i16).unwrap();
+    for row in 0..rows {
+        for col in 0..columns {
+            if col == columns - 1 {
+                junction_counts.set_value(row, col, if row == outlet_row { 1 } else { 2 });
+            } else {
+                mask.set_value(row, col, 1u8);
+            }
+        }
+    }
+    let tables = pointer_tables(false);
+    let ctx = trace_context(&pntr, &streams, &mask, &junction_counts, &tables);
+
+    // failing candidates from the other rows, scrambled with a linear congruential generator
+    let mut state = 12345u64;
+    let mut candidates: Vec<(i32, isize, isize)> = (0..512)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let row = ((state >> 33) % (rows as u64 - 1)) as isize;
+            let row = if row >= outlet_row { row + 1 } else { row };
+            let col = ((state >> 17) % (columns as u64 - 1)) as isize;
+            (0, row, col)
+        })
+        .collect();
+    candidates[400] = (0, outlet_row, 3);
+    candidates[450] = (0, outlet_row, 150);
+
+    let mut expected = None;
+    for num_threads in [1usize, 2, 3, 4, 8, 16] {
+        let start = std::time::Instant::now();
+        let (selected, failures, traced) = trace_candidates(&candidates, &ctx, num_threads);
+        println!(
+            "{} thread(s): {} candidates traced in {:?}",
+            num_threads,
+            traced,
+            start.elapsed()
+        );
+        let selected = selected.unwrap();
+        assert_eq!(selected.candidate_rank, Some(400));
+        assert_eq!(
+            (selected.success.outlet_row, selected.success.outlet_col),
+            (outlet_row, columns - 1)
+        );
+        assert_eq!(failures.len(), 400);
+        assert!(failures
+            .iter()
+            .enumerate()
+            .all(|(i, f)| f.candidate_rank == Some(i)));
+        assert!(traced >= 401);
+        match &expected {
+            None => expected = Some(failures),
+            Some(expected) => assert_eq!(&failures, expected),
+        }
+    }
+
+    // without a successful candidate, every failure is reported in rank order
+    candidates.truncate(400);
+    for num_threads in [1usize, 5] {
+        let (selected, failures, traced) = trace_candidates(&candidates, &ctx, num_threads);
+        assert!(selected.is_none());
+        assert_eq!(traced, 400);
+        assert_eq!(failures[..], expected.as_ref().unwrap()[..]);
+    }
+}
+
+// Distances are in meters for projected and geographic pointers, and in map units when the
+// coordinate system is unknown.
+#[test]
+fn test_cell_distances_in_meters() {
+    let pntr = grid_fixture_raster("pntr.tif", &GridFixture::new(3, 3, |_, _| -32768f64));
+    let geographic_pntr = grid_fixture_raster(
+        "pntr.tif",
+        &GridFixture::new(3, 3, |_, _| -32768f64)
+            .with_resolution(0.01)
+            .with_origin(-116.03, 45.0),
+    );
+    let mut mask: Array2D<u8> = Array2D::new(3, 3, 1u8, 0u8).unwrap();
+    mask.set_value(2, 2, 0u8);
+    let junction_counts: Array2D<i16> = Array2D::new(3, 3, -1i16, -1i16).unwrap();
+    let tables = pointer_tables(false);
+    let context = |pntr, geographic: bool, linear_unit: f64| TraceContext {
+        geographic,
+        linear_unit,
+        ..trace_context(pntr, pntr, &mask, &junction_counts, &tables)
+    };
+    let path = [(0, 0), (1, 1), (2, 2)];
+
+    let ctx = context(&pntr, false, 1f64);
+    assert_eq!(ctx.cell_distance((0, 0), (0, 2)), 20.0);
+    let (length, beyond_mask) = ctx.path_lengths(&path);
+    assert!((length - 20f64 * 2f64.sqrt()).abs() < 1e-9);
+    assert!((beyond_mask - 10f64 * 2f64.sqrt()).abs() < 1e-9);
+
+    // 10 US survey feet
+    let ctx = context(&pntr, false, 0.3048006096012192);
+    assert!((ctx.cell_distance((0, 0), (1, 0)) - 3.048006096012192).abs() < 1e-9);
+
+    // The cell centres of the geographic pointer are 0.01 degrees of latitude apart (about
+    // 1112 m), and 0.01 degrees of longitude apart (about 786 m at 45 degrees north).
+    let ctx = context(&geographic_pntr, true, 1f64);
+    assert!((ctx.cell_distance((0, 0), (1, 0)) - 1111.95).abs() < 0.01);
+    assert!((ctx.cell_distance((1, 0), (1, 1)) - 786.06).abs() < 0.01);
+    assert_eq!(
+        ctx.path_lengths(&[(1, 1)]),
+        (0f64, 0f64),
+        "a path of one cell has no length"
+    );
+}
+
+// The outlet moves to the cell upstream of a junction on its flow path, unless the junction
+// is outside the window or the upstream cell is outside the mask or off the stream network.
+#[test]
+fn test_snap_outlet_to_junction() {
+    let pntr = grid_fixture_raster("pntr.tif", &GridFixture::new(3, 4, |_, _| -32768f64));
+    let mut streams = grid_fixture_raster(
+        "streams.tif",
+        &GridFixture::new(3, 4, |r, _| if r == 1 { 1f64 } else { -32768f64 }),
+    );
+    let mut mask: Array2D<u8> = Array2D::new(3, 4, 1u8, 0u8).unwrap();
+    let mut junction_counts: Array2D<i16> = Array2D::new(3, 4, -1i16, -1i16).unwrap();
+    for col in 0..4 {
+        junction_counts.set_value(1, col, if col == 2 { 2 } else { 1 });
+    }
+    let tables = pointer_tables(false);
+    let snap = |streams: &Raster, mask: &Array2D<u8>, window: isize| -> bool {
+        let ctx = trace_context(&pntr, streams, mask, &junction_counts, &tables);
+        let mut success = TraceSuccessData {
+            outlet_row: 1,
+            outlet_col: 3,
+            steps_taken: 3,
+            steps_beyond_mask: 0,
+            path_length: 30.0,
+            length_beyond_mask: 0.0,
+            outlet_downstream: false,
+            outlet_junction_count: 1,
+            mask_fallback: false,
+            path: vec![(1, 0), (1, 1), (1, 2), (1, 3)],
+        };
+        let snapped = snap_outlet_to_junction(&mut success, &ctx, window);
+        if snapped {
+            assert_eq!((success.outlet_row, success.outlet_col), (1, 1));
+            assert_eq!(success.steps_taken, 1);
+            assert_eq!(success.path_length, 10.0);
+            assert_eq!(success.path, vec![(1, 0), (1, 1)]);
+        }
+        snapped
+    };
+    assert!(snap(&streams, &mask, 1));
+    assert!(!snap(&streams, &mask, 0));
+    mask.set_value(1, 1, 0u8);
+    assert!(!snap(&streams, &mask, 1));
+
+    mask.set_value(1, 1, 1u8);
+    streams.set_value(1, 1, 0f64);
+    assert!(!snap(&streams, &mask, 1));
+}
+
+// A TraceContext over the grids of a test with Whitebox pointers (`pointer_tables(false)`), the
+// default stream filter, and a limit of four steps per cell.
+fn trace_context<'a>(
+    pntr: &'a Raster,
+    streams: &'a Raster,
+    mask: &'a Array2D<u8>,
+    junction_counts: &'a Array2D<i16>,
+    tables: &'a ([f64; 8], [i8; 129]),
+) -> TraceContext<'a> {
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    TraceContext {
+        pntr,
+        streams,
+        mask: Some(mask),
+        junction_counts,
+        inflowing_vals: &tables.0,
+        pntr_nodata: pntr.configs.nodata,
+        streams_nodata: streams.configs.nodata,
+        stream_filter: StreamFilter::default(),
+        pntr_matches: &tables.1,
+        rows,
+        columns,
+        max_steps: 4 * (rows * columns) as usize,
+        outlet_within_mask: false,
+        geographic: false,
+        linear_unit: 1f64,
+    }
+}
+
+// Writes a grid fixture as a raster, e.g. GridFixture::new(5, 6, f) for a 5 x 6 grid of 10 m
+// cells whose south-west corner is at (0, 0).
+fn write_grid(file: &str, grid: GridFixture) {
+    grid_fixture_raster(file, &grid).write().unwrap();
+}
+
+// Rows 0-1 drain south and rows 3-4 drain north into a channel along row 2, which
+// drains east. The watershed mask covers the first four columns.
+#[test]
+fn test_streams_derived_from_flow_accum() {
+    let dir = temp_dir("find_outlet_flow_accum").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let accum = format!("{}/accum.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &accum,
+        GridFixture::new(5, 6, |r, c| match r {
+            0 | 4 => 0f64,
+            1 | 3 => 1f64,
+            _ => (5 * c + 4) as f64,
+        }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+
+    let output = format!("{}/outlet.geojson", dir);
+    let mut args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--flow_accum={}", accum),
+        format!("--watershed={}", watershed),
+        format!("--output={}", output),
+    ];
+    let err = FindOutlet::new().run(args.clone(), "", false).unwrap_err();
+    assert!(err.to_string().contains("--accum_threshold"));
+
+    args.push("--accum_threshold=3.5".to_string());
+    FindOutlet::new().run(args.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&props["row"], &props["column"]),
+        (&Value::from(2), &Value::from(3))
+    );
+    assert_eq!(props["outlet_junction_count"], 1);
+    assert_eq!(props["streams_source"], "flow_accum");
+    assert_eq!(props["accum_threshold"], 3.5);
+    // 19 cells of 100 m2 drain to the outlet, against the 20 cells of the mask.
+    assert_eq!(props["outlet_flow_accum"], 19f64);
+    assert_eq!(props["outlet_drainage_area_m2"], 1900f64);
+    assert_eq!(props["drainage_area_ratio"], 0.95);
+
+    // With --sca the accumulation is already an area.
+    let mut sca_args = args.clone();
+    sca_args.push("--sca".to_string());
+    FindOutlet::new().run(sca_args, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(props["outlet_drainage_area_m2"], 19f64);
+    assert_eq!(props["drainage_area_ratio"], 0.0095);
+
+    let mut bad_args = args.clone();
+    bad_args.push("--area_ratio_tolerance=-1".to_string());
+    let err = FindOutlet::new().run(bad_args, "", false).unwrap_err();
+    assert!(err.to_string().contains("--area_ratio_tolerance"));
+
+    // Supplying neither a streams nor a flow accumulation raster remains an error.
+    let err = FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--watershed={}", watershed),
+                format!("--output={}", output),
+            ],
+            "",
+            false,
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("(--streams) not specified"));
+}
+
+// Rows 0-1 drain south and rows 3-4 drain north into a channel along row 2, which drains
+// east, so a request traced without snapping reaches the channel in its own column.
+#[test]
+fn test_snap_requested_outlet_to_flow_accum() {
+    let dir = temp_dir("find_outlet_snap_radius").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let accum = format!("{}/accum.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    let run = |extra: &[&str]| -> Result<Map<String, Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+            "--requested_outlet_row_col=0,4".to_string(),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{accum}", &accum)));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone())
+    };
+
+    // Without --snap_radius the accumulation raster does not move the request, but gives
+    // the drainage area at the outlet; without a watershed mask there is no area ratio.
+    write_grid(
+        &accum,
+        GridFixture::new(5, 6, |r, c| if r == 2 { (5 * c + 4) as f64 } else { 1f64 }),
+    );
+    let props = run(&["--flow_accum={accum}"]).unwrap();
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(4)));
+    assert!(props.get("snapped_row").is_none());
+    assert_eq!(props["outlet_flow_accum"], 24f64);
+    assert_eq!(props["outlet_drainage_area_m2"], 2400f64);
+    assert_eq!(props["drainage_area_ratio"], Value::Null);
+    assert!(run(&[]).unwrap().get("outlet_flow_accum").is_none());
+    let err = run(&["--sca"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+
+    let props = run(&["--flow_accum={accum}", "--snap_radius=2"]).unwrap();
+    assert_eq!(
+        (&props["requested_row"], &props["requested_col"]),
+        (&json!(0), &json!(4))
+    );
+    assert_eq!(
+        (&props["snapped_row"], &props["snapped_col"]),
+        (&json!(2), &json!(5))
+    );
+    assert_eq!(props["snapped_accum"], 29f64);
+    assert_eq!(
+        (&props["start_row"], &props["start_col"]),
+        (&json!(2), &json!(5))
+    );
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(5)));
+
+    // Ties go to the cell nearest the request.
+    write_grid(
+        &accum,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 7f64 } else { 1f64 }),
+    );
+    let props = run(&["--flow_accum={accum}", "--snap_radius=2"]).unwrap();
+    assert_eq!(
+        (&props["snapped_row"], &props["snapped_col"]),
+        (&json!(2), &json!(4))
+    );
+
+    // A window without accumulation data leaves the request where it is.
+    write_grid(
+        &accum,
+        GridFixture::new(5, 6, |r, c| if r < 2 && c > 2 { -32768f64 } else { 50f64 }),
+    );
+    let props = run(&["--flow_accum={accum}", "--snap_radius=1"]).unwrap();
+    assert_eq!(props["snapped_row"], Value::Null);
+    assert_eq!(props["snapped_accum"], Value::Null);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(4)));
+
+    let err = run(&["--snap_radius=2"]).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("requires a flow accumulation raster"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// A 5 x 6 grid drains east into a channel down column 5, except for a lake of NoData
+// pointers over rows 1-3 and columns 1-3. The request at its centre is two search steps from
+// the nearest valid hillslope cells and three from the channel.
+#[test]
+fn test_snap_to_stream() {
+    let dir = temp_dir("find_outlet_snap_to_stream").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let lake = |r: isize, c: isize| (1..=3).contains(&r) && (1..=3).contains(&c);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, c| {
+            if lake(r, c) {
+                -32768f64
+            } else if c == 5 {
+                8f64
+            } else {
+                2f64
+            }
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |_, c| if c == 5 { 1f64 } else { 0f64 }),
+    );
+
+    let pntr_raster = Raster::new(&pntr, "r").unwrap();
+    let streams_raster = Raster::new(&streams, "r").unwrap();
+    let (_, pntr_matches) = pointer_tables(false);
+    let find = |snap: Option<StreamSnap>| {
+        find_nearest_valid_cell(2, 2, 5, 6, &pntr_raster, -32768f64, &pntr_matches, snap).unwrap()
+    };
+    let snap = |max_radius: Option<usize>| StreamSnap {
+        streams: &streams_raster,
+        filter: StreamFilter::default(),
+        max_radius,
+    };
+    let (cell, dist) = find(None);
+    assert_eq!(dist, 2);
+    assert!(cell.1 < 5);
+    let (cell, dist) = find(Some(snap(None)));
+    assert_eq!((cell.1, dist), (5, 3));
+    assert_eq!(find(Some(snap(Some(3)))), (cell, 3));
+    // Without a stream cell within the radius, the nearest valid cell is used.
+    assert_eq!(find(Some(snap(Some(2)))), find(None));
+
+    let run = |extra: &[&str]| -> Result<Map<String, Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+            "--requested_outlet_row_col=2,2".to_string(),
+        ];
+        args.extend(extra.iter().map(|a| a.to_string()));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone())
+    };
+    let props = run(&[]).unwrap();
+    assert_eq!(props["start_cell_type"], "non_stream");
+    assert_eq!(props["start_offset_cells"], 2);
+    let props = run(&["--snap_to_stream"]).unwrap();
+    assert_eq!(props["start_cell_type"], "stream");
+    assert_eq!(
+        (&props["start_col"], &props["start_offset_cells"]),
+        (&json!(5), &json!(3))
+    );
+    let props = run(&["--snap_to_stream", "--snap_max_radius=2"]).unwrap();
+    assert_eq!(props["start_cell_type"], "non_stream");
+    assert_eq!(props["start_offset_cells"], 2);
+
+    let err = run(&["--snap_max_radius=2"]).unwrap_err();
+    assert!(err.to_string().contains("requires --snap_to_stream"));
+    let err = FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--watershed={}", streams),
+                format!("--output={}", output),
+                "--snap_to_stream".to_string(),
+            ],
+            "",
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The channel along row 2 drains east off the raster from the outlet at (2, 3).
+#[test]
+fn test_downstream_preview() {
+    let dir = temp_dir("find_outlet_downstream_preview").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let write_pntr = |nodata_below_outlet: bool| {
+        write_grid(
+            &pntr,
+            GridFixture::new(5, 6, |r, c| match r {
+                0 | 1 => 8f64,
+                2 if nodata_below_outlet && c == 4 => -32768f64,
+                2 => 2f64,
+                _ => 128f64,
+            }),
+        )
+    };
+    write_pntr(false);
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--watershed={}", watershed),
+        format!("--output={}", output),
+        "--downstream_preview=5".to_string(),
+    ];
+
+    FindOutlet::new().run(args.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2);
+    assert_eq!(features[0]["properties"]["column"], 3);
+    let preview = &features[1];
+    assert_eq!(preview["geometry"]["type"], "LineString");
+    assert_eq!(
+        preview["geometry"]["coordinates"],
+        serde_json::json!([[35.0, 25.0], [45.0, 25.0], [55.0, 25.0]])
+    );
+    let props = &preview["properties"];
+    assert_eq!(props["feature"], "downstream_preview");
+    assert_eq!(props["steps_requested"], 5);
+    assert_eq!(props["steps"], 2);
+    assert_eq!(props["length"], 20.0);
+    assert_eq!(props["on_stream"], true);
+    assert_eq!(props["reason"], "left_raster");
+
+    // A continuation that immediately reaches nodata is a zero-length line, not an error.
+    write_pntr(true);
+    FindOutlet::new().run(args, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let preview = &geojson["features"][1];
+    assert_eq!(
+        preview["geometry"]["coordinates"],
+        serde_json::json!([[35.0, 25.0], [35.0, 25.0]])
+    );
+    assert_eq!(preview["properties"]["steps"], 0);
+    assert_eq!(preview["properties"]["length"], 0.0);
+    assert_eq!(preview["properties"]["on_stream"], false);
+    assert_eq!(preview["properties"]["reason"], "nodata");
+}
+
+#[test]
+fn test_trace_path() {
+    let dir = temp_dir("find_outlet_trace_path").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let trace_path = format!("{}/trace.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+
+    // A requested start two cells north of the channel walks south onto it.
+    FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                "--requested_outlet_row_col=0,1".to_string(),
+                format!("--output={}", output),
+                format!("--trace_path={}", trace_path),
+            ],
+            "",
+            false,
+        )
+        .unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&trace_path).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0]["geometry"]["type"], "LineString");
+    assert_eq!(
+        features[0]["geometry"]["coordinates"],
+        json!([[15.0, 45.0], [15.0, 35.0], [15.0, 25.0]])
+    );
+    let props = &features[0]["properties"];
+    assert_eq!(props["outlet_id"], 0);
+    assert_eq!(props["steps_taken"], 2);
+    assert_eq!(props["steps_beyond_mask"], 0);
+    assert_eq!(props["start_mode"], "requested");
+
+    // With several candidates, only the selected trace is written, from its start cell to
+    // the outlet.
+    FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--watershed={}", watershed),
+                format!("--output={}", output),
+                format!("--trace_path={}", trace_path),
+            ],
+            "",
+            false,
+        )
+        .unwrap();
+    let outlet: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let outlet_props = &outlet["features"][0]["properties"];
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&trace_path).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    let coordinates = features[0]["geometry"]["coordinates"].as_array().unwrap();
+    let start_col = outlet_props["start_col"].as_f64().unwrap();
+    let start_row = outlet_props["start_row"].as_f64().unwrap();
+    assert_eq!(
+        coordinates[0],
+        json!([10.0 * start_col + 5.0, 45.0 - 10.0 * start_row])
+    );
+    assert_eq!(coordinates[coordinates.len() - 1], json!([35.0, 25.0]));
+    assert_eq!(
+        features[0]["properties"]["steps_taken"],
+        outlet_props["steps_from_start"]
+    );
+    assert_eq!(features[0]["properties"]["start_mode"], "watershed");
+}
+
+// The outlet at the mask boundary on the channel along row 2 is written to both outputs.
+#[test]
+fn test_output_raster() {
+    let dir = temp_dir("find_outlet_output_raster").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    // The Whitebox format keeps the metadata entries, which the GeoTIFF writer does not.
+    let output_raster = format!("{}/outlet.tas", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+
+    FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--watershed={}", watershed),
+                format!("--output={}", output),
+                format!("--output_raster={}", output_raster),
+            ],
+            "",
+            false,
+        )
+        .unwrap();
+    let outlet: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &outlet["features"][0]["properties"];
+    assert_eq!(
+        (props["row"].clone(), props["column"].clone()),
+        (json!(2), json!(3))
+    );
+
+    let raster = Raster::new(&output_raster, "r").unwrap();
+    assert_eq!((raster.configs.rows, raster.configs.columns), (5, 6));
+    let nodata = raster.configs.nodata;
+    for r in 0..5 {
+        for c in 0..6 {
+            let expected = if (r, c) == (2, 3) { 1f64 } else { nodata };
+            assert_eq!(raster.get_value(r, c), expected);
+        }
+    }
+    // The Whitebox header stores the colons of metadata entries as semicolons.
+    let metadata = &raster.configs.metadata;
+    assert!(metadata.contains(&format!("Outlet file; {}", output)));
+    assert!(metadata.contains(&"Outlet row/col; 2, 3".to_string()));
+}
+
+#[test]
+fn test_auto_pntr() {
+    let dir = temp_dir("find_outlet_auto_pntr").unwrap();
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+
+    // The same flow field in both schemes: south onto the channel on row 2, which drains
+    // east, and north onto it from below.
+    for (name, south, east, north) in [
+        ("whitebox", 8f64, 2f64, 128f64),
+        ("esri", 4f64, 1f64, 64f64),
+    ] {
+        let pntr = format!("{}/pntr_{}.tif", dir, name);
+        let output = format!("{}/outlet_{}.geojson", dir, name);
+        write_grid(
+            &pntr,
+            GridFixture::new(5, 6, |r, _| match r {
+                0 | 1 => south,
+                2 => east,
+                _ => north,
+            }),
+        );
+        FindOutlet::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr),
+                    format!("--streams={}", streams),
+                    format!("--watershed={}", watershed),
+                    format!("--output={}", output),
+                    "--auto_pntr".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let outlet: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let props = &outlet["features"][0]["properties"];
+        assert_eq!(props["pntr_scheme"], name);
+        assert_eq!(
+            (props["row"].clone(), props["column"].clone()),
+            (json!(2), json!(3))
+        );
+    }
+
+    // Without stream cells to sample, detection falls back to the explicit flag.
+    let votes = PointerSchemeVotes {
+        sampled: 0,
+        whitebox: 0,
+        esri: 0,
+    };
+    assert_eq!(votes.detected_esri_style(), None);
+    let votes = PointerSchemeVotes {
+        sampled: 20,
+        whitebox: 12,
+        esri: 1,
+    };
+    assert_eq!(votes.detected_esri_style(), Some(true));
+    let votes = PointerSchemeVotes {
+        sampled: 20,
+        whitebox: 3,
+        esri: 2,
+    };
+    assert_eq!(votes.detected_esri_style(), None);
+    let votes = PointerSchemeVotes {
+        sampled: 100,
+        whitebox: 0,
+        esri: 5,
+    };
+    assert_eq!(votes.detected_esri_style(), None);
+}
+
+// A sloppy mask over the first four columns of two parallel channels, along rows 2 and 5,
+// which both drain east. Rows 0-1 and 4 drain south and rows 3 and 6-8 north onto them, so
+// 16 mask cells drain to the northern channel and 20 to the southern one.
+#[test]
+fn test_all_outlets() {
+    let dir = temp_dir("find_outlet_all_outlets").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlets.geojson", dir);
+    let report = format!("{}/report.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(9, 6, |r, _| match r {
+            0 | 1 | 4 => 8f64,
+            2 | 5 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(9, 6, |r, _| if r == 2 || r == 5 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(9, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--watershed={}", watershed),
+        format!("--output={}", output),
+        format!("--report={}", report),
+    ];
+
+    // By default only the outlet of the highest-ranked candidate, (1, 1), is found.
+    FindOutlet::new().run(args.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 1);
+    assert_eq!(features[0]["properties"]["row"], 2);
+    assert!(features[0]["properties"]
+        .get("upstream_mask_cells")
+        .is_none());
+
+    let mut all_args = args.clone();
+    all_args.push("--all_outlets".to_string());
+    FindOutlet::new().run(all_args, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 2);
+    // The southern outlet drains more of the mask, so it ranks first although it was
+    // reached from a lower-ranked candidate.
+    let first = &features[0]["properties"];
+    let second = &features[1]["properties"];
+    assert_eq!(
+        (&first["Id"], &first["row"], &first["column"]),
+        (&json!(0), &json!(5), &json!(3))
+    );
+    assert_eq!(first["upstream_mask_cells"], 20);
+    assert_eq!(
+        (&second["Id"], &second["row"], &second["column"]),
+        (&json!(1), &json!(2), &json!(3))
+    );
+    assert_eq!(second["upstream_mask_cells"], 16);
+    assert_eq!(second["candidate_rank"], 0);
+    assert!(first["candidate_rank"].as_u64().unwrap() > 0);
+    let json: Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    let outlets = json["outlet"]["outlets"].as_array().unwrap();
+    assert_eq!(outlets.len(), 2);
+    assert_eq!(outlets[0]["upstream_mask_cells"], 20);
+    // every mask cell is traced
+    assert_eq!(json["outlet"]["candidate_stats"]["attempted"], 36);
+    assert_eq!(json["outlet"]["candidate_stats"]["succeeded"], 36);
+
+    let mut requested_args = args.clone();
+    requested_args.push("--all_outlets".to_string());
+    requested_args.push("--requested_outlet_row_col=0,1".to_string());
+    let err = FindOutlet::new()
+        .run(requested_args, "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The fixture of `test_all_outlets`: the mask drains to two stream outlets, (2, 3) and (5, 3).
+#[test]
+fn test_score_candidates() {
+    let dir = temp_dir("find_outlet_score_candidates").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(9, 6, |r, _| match r {
+            0 | 1 | 4 => 8f64,
+            2 | 5 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(9, 6, |r, _| if r == 2 || r == 5 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(9, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--watershed={}", watershed),
+        format!("--output={}", output),
+    ];
+
+    let mut score_args = args.clone();
+    score_args.push("--score_candidates=10".to_string());
+    FindOutlet::new().run(score_args, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let properties = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&properties["row"], &properties["column"]),
+        (&json!(2), &json!(3))
+    );
+    assert_eq!(properties["n_alternative_outlets"], 1);
+    let support = properties["selected_outlet_support"].as_f64().unwrap();
+    assert!(support > 0f64 && support < 1f64);
+    let alternatives = properties["alternative_outlets"].as_array().unwrap();
+    assert_eq!(alternatives.len(), 1);
+    assert_eq!(
+        (&alternatives[0]["row"], &alternatives[0]["col"]),
+        (&json!(5), &json!(3))
+    );
+    let alternative_traces = alternatives[0]["traces"].as_f64().unwrap();
+    assert!((support - (1f64 - alternative_traces / 11f64)).abs() < 1e-9);
+
+    // Without the option, the properties are left out.
+    FindOutlet::new().run(args.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    assert!(geojson["features"][0]["properties"]
+        .get("selected_outlet_support")
+        .is_none());
+
+    for extra in [
+        vec!["--score_candidates=0"],
+        vec!["--score_candidates=3", "--all_outlets"],
+        vec!["--score_candidates=3", "--requested_outlet_row_col=0,1"],
+    ] {
+        let mut bad_args = args.clone();
+        bad_args.extend(extra.iter().map(|a| a.to_string()));
+        let err = FindOutlet::new().run(bad_args, "", false).unwrap_err();
+        assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    }
+    let err = FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                "--requested_outlet_row_col=0,1".to_string(),
+                format!("--output={}", output),
+                "--score_candidates=3".to_string(),
+            ],
+            "",
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// An 8-bit streams grid with a nodata value of 255 and a background of 0. Row 0 drains east
+// to column 3 and then south, with weak channel marks (1) along it; row 1 is nodata; and
+// row 2 is the channel (2), which drains east.
+#[test]
+fn test_stream_threshold_and_nodata() {
+    let above = |threshold| StreamFilter {
+        threshold,
+        min_order: 0f64,
+    };
+    assert!(!is_stream_value(255f64, 255f64, above(0f64)));
+    assert!(!is_stream_value(0f64, 255f64, above(0f64)));
+    assert!(!is_stream_value(f64::NAN, 255f64, above(0f64)));
+    assert!(is_stream_value(1f64, 255f64, above(0f64)));
+    assert!(!is_stream_value(1f64, -32768f64, above(1f64)));
+    assert!(is_stream_value(-1f64, -32768f64, above(-2f64)));
+
+    let dir = temp_dir("find_outlet_stream_threshold").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, c| match (r, c) {
+            (0, c) if c < 3 => 2f64,
+            (0, _) | (1, _) => 8f64,
+            (2, _) => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 => 1f64,
+            1 => 255f64,
+            2 => 2f64,
+            _ => 0f64,
+        })
+        .with_nodata(255f64),
+    );
+    let run = |threshold: &str| {
+        FindOutlet::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr),
+                    format!("--streams={}", streams),
+                    "--requested_outlet_row_col=0,1".to_string(),
+                    format!("--stream_threshold={}", threshold),
+                    format!("--output={}", output),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let props = &geojson["features"][0]["properties"];
+        (
+            props["row"].as_i64().unwrap(),
+            props["column"].as_i64().unwrap(),
+        )
+    };
+
+    // By default the weak marks are channels, and the start cell, fed by (0, 0), is the
+    // outlet.
+    assert_eq!(run("0"), (0, 1));
+    // Above a threshold of 1, the trace runs past them and across the nodata row to the
+    // channel, where the nodata cell draining into (2, 3) does not count as a tributary.
+    assert_eq!(run("1"), (2, 3));
+
+    let err = FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                "--requested_outlet_row_col=0,1".to_string(),
+                "--stream_threshold=high".to_string(),
+                format!("--output={}", output),
+            ],
+            "",
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// Streams holding Strahler orders: a first-order channel along row 2 becomes second order
+// below the first-order tributary from (1, 3).
+#[test]
+fn test_min_stream_order() {
+    let order = StreamFilter {
+        threshold: 0f64,
+        min_order: 2f64,
+    };
+    assert!(!is_stream_value(1f64, -32768f64, order));
+    assert!(is_stream_value(2f64, -32768f64, order));
+    assert!(!is_stream_value(f64::NAN, -32768f64, order));
+
+    let dir = temp_dir("find_outlet_min_stream_order").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, c| match (r, c) {
+            (1, 3) => 1f64,
+            (2, c) if c < 3 => 1f64,
+            (2, _) => 2f64,
+            _ => 0f64,
+        }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        "--requested_outlet_row_col=0,1".to_string(),
+        format!("--output={}", output),
+    ];
+    let run = |min_order: Option<&str>| {
+        let mut args = args.clone();
+        if let Some(min_order) = min_order {
+            args.push(format!("--min_stream_order={}", min_order));
+        }
+        FindOutlet::new().run(args, "", false).unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let props = &geojson["features"][0]["properties"];
+        (
+            props["column"].as_i64().unwrap(),
+            props["outlet_stream_value"].as_f64().unwrap(),
+        )
+    };
+
+    assert_eq!(run(None), (1, 1f64));
+    assert_eq!(run(Some("0")), run(None));
+    // The first-order cells are not streams, so the trace runs on to the second-order
+    // channel.
+    assert_eq!(run(Some("2")), (4, 2f64));
+
+    let mut malformed = args.clone();
+    malformed.push("--min_stream_order=-1".to_string());
+    let err = FindOutlet::new().run(malformed, "", false).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The watershed of test_downstream_preview (the first four columns) as polygons: a GeoJSON
+// MultiPolygon split at y = 20, with a hole around cell (0, 0), and a Shapefile rectangle.
+#[test]
+fn test_vector_watershed() {
+    let dir = temp_dir("find_outlet_vector_watershed").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let run = |watershed: &str| -> Map<String, Value> {
+        FindOutlet::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr),
+                    format!("--streams={}", streams),
+                    format!("--watershed={}", watershed),
+                    format!("--output={}", output),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone()
+    };
+    let from_raster = run(&watershed);
+
+    let multipolygon = format!("{}/ws.geojson", dir);
+    fs::write(
+        &multipolygon,
+        json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "properties": {},
+                "geometry": {
+                    "type": "MultiPolygon",
+                    "coordinates": [
+                        [
+                            [[0.0, 20.0], [40.0, 20.0], [40.0, 50.0], [0.0, 50.0], [0.0, 20.0]],
+                            [[1.0, 41.0], [1.0, 49.0], [9.0, 49.0], [9.0, 41.0], [1.0, 41.0]]
+                        ],
+                        [[[0.0, 0.0], [40.0, 0.0], [40.0, 20.0], [0.0, 20.0], [0.0, 0.0]]]
+                    ]
+                }
+            }]
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let from_geojson = run(&multipolygon);
+    for key in ["row", "column", "outlet_junction_count", "outlet_in_mask"] {
+        assert_eq!(from_geojson[key], from_raster[key], "{}", key);
+    }
+    assert_eq!(from_raster["watershed_cell_count"], 20);
+    assert_eq!(from_geojson["watershed_cell_count"], 19);
+
+    let shapefile = format!("{}/ws.shp", dir);
+    let mut polygons = Shapefile::new(&shapefile, ShapeType::Polygon).unwrap();
+    polygons
+        .attributes
+        .add_field(&AttributeField::new("FID", FieldDataType::Int, 10u8, 0u8));
+    let mut geometry = ShapefileGeometry::new(ShapeType::Polygon);
+    geometry.add_part(&[
+        Point2D::new(0.0, 0.0),
+        Point2D::new(0.0, 50.0),
+        Point2D::new(40.0, 50.0),
+        Point2D::new(40.0, 0.0),
+        Point2D::new(0.0, 0.0),
+    ]);
+    polygons.add_record(geometry);
+    polygons
+        .attributes
+        .add_record(vec![FieldData::Int(1)], false);
+    polygons.write().unwrap();
+    let from_shapefile = run(&shapefile);
+    for key in [
+        "row",
+        "column",
+        "watershed_cell_count",
+        "distance_to_boundary",
+    ] {
+        assert_eq!(from_shapefile[key], from_raster[key], "{}", key);
+    }
+
+    // Polygons that miss the grid are a geometry mismatch.
+    let elsewhere = format!("{}/elsewhere.geojson", dir);
+    fs::write(
+        &elsewhere,
+        r#"{"type": "Polygon", "coordinates": [[[500, 500], [600, 500], [600, 600], [500, 500]]]}"#,
+    )
+    .unwrap();
+    let err = FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--watershed={}", elsewhere),
+                format!("--output={}", output),
+            ],
+            "",
+            false,
+        )
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+}
+
+// A 40 x 40 grid drains to a channel along row 20 that flows east; the junction
+// counts are 0 at the channel head, 1 along the channel, and NoData elsewhere.
+// A mask in the south-east corner of the grid, whose halo is clipped by the grid edges. The
+// windowed scan, in bands on several threads, matches the serial full-extent scan within
+// the window.
+#[test]
+fn test_count_junctions_window() {
+    let dir = temp_dir("find_outlet_junction_window").unwrap();
+    let pntr_file = format!("{}/pntr.tif", dir);
+    let streams_file = format!("{}/streams.tif", dir);
+    write_grid(
+        &pntr_file,
+        GridFixture::new(8, 9, |r, c| {
+            [1f64, 2f64, 4f64, 8f64, 16f64, 32f64, 64f64, 128f64][((r * 7 + c * 3) % 8) as usize]
+        }),
+    );
+    write_grid(
+        &streams_file,
+        GridFixture::new(8, 9, |r, c| if (r + c) % 3 != 0 { 1f64 } else { 0f64 }),
+    );
+    let pntr = Raster::new(&pntr_file, "r").unwrap();
+    let streams = Raster::new(&streams_file, "r").unwrap();
+    let (inflowing_vals, _) = pointer_tables(false);
+
+    let mut mask: Array2D<u8> = Array2D::new(8, 9, 0u8, 0u8).unwrap();
+    for r in 5..8isize {
+        for c in 6..9isize {
+            mask.set_value(r, c, 1u8);
+        }
+    }
+    let window = mask_scan_window(&mask);
+    assert_eq!(window, (4..8, 5..9));
+    let empty: Array2D<u8> = Array2D::new(8, 9, 0u8, 0u8).unwrap();
+    assert_eq!(mask_scan_window(&empty), (0..0, 0..0));
+
+    let full = count_junctions(
+        &pntr,
+        &streams,
+        StreamFilter::default(),
+        &inflowing_vals,
+        (0..8, 0..9),
+        1,
+        false,
+    )
+    .unwrap();
+    for num_threads in [1, 3, 16] {
+        let windowed = count_junctions(
+            &pntr,
+            &streams,
+            StreamFilter::default(),
+            &inflowing_vals,
+            window.clone(),
+            num_threads,
+            false,
+        )
+        .unwrap();
+        for r in 0..8isize {
+            for c in 0..9isize {
+                let expected = if window.0.contains(&r) && window.1.contains(&c) {
+                    full.get_value(r, c)
+                } else {
+                    JUNCTIONS_UNSCANNED
+                };
+                assert_eq!(windowed.get_value(r, c), expected, "({}, {})", r, c);
+            }
+        }
+    }
+    let parallel = count_junctions(
+        &pntr,
+        &streams,
+        StreamFilter::default(),
+        &inflowing_vals,
+        (0..8, 0..9),
+        4,
+        false,
+    )
+    .unwrap();
+    for r in 0..8isize {
+        assert_eq!(parallel.get_row_data(r), full.get_row_data(r));
+    }
+    assert!((0..8isize).any(|r| (0..9isize).any(|c| full.get_value(r, c) > 0)));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The channel along row 6 leaves a mask at the west edge of the grid and is joined by
+// tributaries in columns 3-6, at the mask boundary and beyond the scanned window of columns
+// 0-4, so the trace counts the junctions it reaches outside the window to find the outlet at
+// column 7.
+#[test]
+fn test_trace_beyond_junction_scan_window() {
+    let dir = temp_dir("find_outlet_beyond_window").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(12, 12, |r, _| match r {
+            r if r < 6 => 8f64,
+            6 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(12, 12, |r, c| {
+            if r == 6 || (r == 5 && (3..7).contains(&c)) {
+                1f64
+            } else {
+                0f64
+            }
+        }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(12, 12, |r, c| {
+            if (3..10).contains(&r) && c < 4 {
+                1f64
+            } else {
+                0f64
+            }
+        }),
+    );
+    let mut outlets = vec![];
+    for junctions in [false, true] {
+        let output = format!("{}/outlet_{}.geojson", dir, junctions);
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+        ];
+        // The --junctions raster needs the full-extent scan.
+        if junctions {
+            args.push(format!("--junctions={}/junctions.tif", dir));
+        }
+        FindOutlet::new().run(args, "", false).unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        let properties = &geojson["features"][0]["properties"];
+        outlets.push((
+            properties["row"].clone(),
+            properties["column"].clone(),
+            properties["outlet_junction_count"].clone(),
+            properties["steps_beyond_mask"].clone(),
+        ));
+    }
+    assert_eq!(outlets[0], (json!(6), json!(7), json!(1), json!(4)));
+    assert_eq!(outlets[0], outlets[1]);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_junction_counts_honour_compress() {
+    let dir = temp_dir("find_outlet_compress").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(40, 40, |r, _| match r {
+            r if r < 20 => 8f64,
+            20 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(40, 40, |r, _| if r == 20 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(40, 40, |_, c| if c < 30 { 1f64 } else { 0f64 }),
+    );
+
+    let mut sizes = vec![];
+    let mut values = vec![];
+    for compress in ["true", "false"] {
+        let output = format!("{}/outlet_{}.geojson", dir, compress);
+        let junctions = format!("{}/junctions_{}.tif", dir, compress);
+        FindOutlet::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr),
+                    format!("--streams={}", streams),
+                    format!("--watershed={}", watershed),
+                    format!("--output={}", output),
+                    format!("--junctions={}", junctions),
+                    format!("--compress={}", compress),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        // The GeoJSON output is unaffected by the raster side output.
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(geojson["features"][0]["properties"]["column"], 29);
+        sizes.push(fs::metadata(&junctions).unwrap().len());
+        let raster = Raster::new(&junctions, "r").unwrap();
+        let mut grid = vec![];
+        for r in 0..40isize {
+            for c in 0..40isize {
+                grid.push(raster.get_value(r, c));
+            }
+        }
+        values.push(grid);
+    }
+    assert!(
+        sizes[0] < sizes[1],
+        "compressed {} bytes, uncompressed {} bytes",
+        sizes[0],
+        sizes[1]
+    );
+    assert_eq!(values[0], values[1]);
+    for r in 0..40usize {
+        for c in 0..40usize {
+            let expected = match (r, c) {
+                (20, 0) => 0f64,
+                (20, _) => 1f64,
+                _ => -32768f64,
+            };
+            assert_eq!(values[0][r * 40 + c], expected, "row {} col {}", r, c);
+        }
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+// Three requested starts on the grid of test_downstream_preview, without a watershed
+// mask. The pointers at (0, 4) and (0, 5) point at each other, so the second request loops.
+#[test]
+fn test_multiple_requested_outlets() {
+    let dir = temp_dir("find_outlet_multiple_requests").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlets.geojson", dir);
+    let report = format!("{}/report.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, c| match (r, c) {
+            (0, 4) => 2f64,
+            (0, 5) => 32f64,
+            (0, _) | (1, _) => 8f64,
+            (2, _) => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    let args = |requests: &str| {
+        vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+            format!("--report={}", report),
+            format!("--requested_outlet_row_col={}", requests),
+            "--downstream_preview=1".to_string(),
+        ]
+    };
+
+    FindOutlet::new()
+        .run(args("0,1; 0,5; 4,2"), "", false)
+        .unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let features = geojson["features"].as_array().unwrap();
+    assert_eq!(features.len(), 5);
+    for (id, feature) in features.iter().take(3).enumerate() {
+        assert_eq!(feature["properties"]["Id"], id);
+    }
+    assert_eq!(features[0]["properties"]["status"], "success");
+    assert_eq!(features[0]["properties"]["row"], 2);
+    assert_eq!(features[0]["properties"]["requested_col"], 1);
+    let failed = &features[1]["properties"];
+    assert_eq!(failed["status"], "failure");
+    assert!(failed["failure_reason"]
+        .as_str()
+        .unwrap()
+        .starts_with("Failed to trace a valid outlet from the requested location."));
+    assert!(failed.get("row").is_none());
+    // a request that could not be traced is located at the requested cell
+    assert_eq!(
+        features[1]["geometry"]["coordinates"],
+        serde_json::json!([55.0, 45.0])
+    );
+    assert_eq!(features[2]["properties"]["status"], "success");
+    assert_eq!(features[2]["properties"]["requested_row"], 4);
+    // the previews follow the outlets and name the outlet they continue
+    assert_eq!(features[3]["properties"]["Id"], 3);
+    assert_eq!(features[3]["properties"]["outlet_id"], 0);
+    assert_eq!(features[4]["properties"]["outlet_id"], 2);
+    let json: Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(json["outlet"]["requests"], 3);
+    assert_eq!(json["outlet"]["succeeded"], 2);
+    assert_eq!(json["outlet"]["outlets"][1]["status"], "failure");
+
+    // The run fails only when every request fails.
+    let err = FindOutlet::new().run(args("0,1;0"), "", false).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--requested_outlet_row_col expects 'row,col'; received '0'."
+    );
+    let err = FindOutlet::new()
+        .run(args("0,5;0,4"), "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    assert!(err
+        .to_string()
+        .starts_with("None of the 2 requested outlet locations could be traced."));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// Pour points read from GeoJSON or a raster are traced as requested outlet cells.
+#[test]
+fn test_pour_points() {
+    let dir = temp_dir("find_outlet_pour_points").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlets.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    let write_points = |name: &str, points: &[(f64, f64)]| -> String {
+        let file = format!("{}/{}", dir, name);
+        let features: Vec<Value> = points
+            .iter()
+            .map(|&(x, y)| {
+                json!({
+                    "type": "Feature",
+                    "geometry": {"type": "Point", "coordinates": [x, y]},
+                    "properties": {}
+                })
+            })
+            .collect();
+        let collection = json!({"type": "FeatureCollection", "features": features});
+        fs::write(&file, collection.to_string()).unwrap();
+        file
+    };
+    let run = |pour_pts: &str, extra: &[&str]| -> Result<Value, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+            format!("--pour_pts={}", pour_pts),
+        ];
+        args.extend(extra.iter().map(|s| s.to_string()));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"].clone())
+    };
+
+    // The centre of cell (0, 1)
+    let features = run(&write_points("one.geojson", &[(15f64, 45f64)]), &[]).unwrap();
+    let outlet = &features[0]["properties"];
+    assert_eq!(outlet["start_mode"], "requested");
+    assert_eq!((&outlet["row"], &outlet["column"]), (&json!(2), &json!(1)));
+    assert_eq!(
+        (&outlet["requested_row"], &outlet["requested_col"]),
+        (&json!(0), &json!(1))
+    );
+
+    // Several points are traced as a batch.
+    let two = write_points("two.geojson", &[(15f64, 45f64), (25f64, 5f64)]);
+    let features = run(&two, &[]).unwrap();
+    assert_eq!(features.as_array().unwrap().len(), 2);
+    assert_eq!(features[1]["properties"]["Id"], 1);
+    assert_eq!(features[1]["properties"]["status"], "success");
+    assert_eq!(features[1]["properties"]["requested_row"], 4);
+
+    let pour_pts = format!("{}/pour_pts.tif", dir);
+    write_grid(
+        &pour_pts,
+        GridFixture::new(5, 6, |r, c| if (r, c) == (4, 2) { 1f64 } else { 0f64 }),
+    );
+    let features = run(&pour_pts, &[]).unwrap();
+    let outlet = &features[0]["properties"];
+    assert_eq!(
+        (&outlet["requested_row"], &outlet["requested_col"]),
+        (&json!(4), &json!(2))
+    );
+
+    let err = run(&two, &["--requested_outlet_lng_lat=-120.5,42.1"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    assert!(err
+        .to_string()
+        .contains("given with both --requested_outlet_lng_lat and --pour_pts"));
+    let err = run(&write_points("none.geojson", &[]), &[]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    assert!(err
+        .to_string()
+        .starts_with("No pour points found in --pour_pts"));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_parameter_hints_are_serialized() {
+    let json: Value = serde_json::from_str(&FindOutlet::new().get_tool_parameters()).unwrap();
+    let parameters = json["parameters"].as_array().unwrap();
+    let find = |flag: &str| {
+        parameters
+            .iter()
+            .find(|p| p["flags"].as_array().unwrap().iter().any(|f| f == flag))
+            .unwrap()
+    };
+    let streams = find("--streams");
+    assert_eq!(streams["group"], "Stream Network");
+    assert_eq!(
+        streams["requires_one_of"],
+        serde_json::json!(["--flow_accum"])
+    );
+    assert_eq!(
+        find("--accum_threshold")["requires"],
+        serde_json::json!(["--flow_accum"])
+    );
+    assert_eq!(
+        find("--requested_outlet_lng_lat")["conflicts_with"],
+        serde_json::json!([
+            "--requested_outlet_row_col",
+            "--requested_outlet_xy",
+            "--pour_pts"
+        ])
+    );
+    assert_eq!(find("--snap_radius")["requires"], json!(["--flow_accum"]));
+    // Parameters without hints serialize exactly as before.
+    let d8_pntr = find("--d8_pntr").as_object().unwrap();
+    assert_eq!(d8_pntr.len(), 6);
+    assert!(!d8_pntr.contains_key("group"));
+    assert!(find("--output").get("requires").is_none());
+}
+
+// Runs FindOutlet with a report and returns the error and the report's contents.
+fn run_with_report(args: &[String], report: &str) -> (std::io::Error, Value) {
+    let mut args = args.to_vec();
+    args.push(format!("--report={}", report));
+    let err = FindOutlet::new().run(args, "", false).unwrap_err();
+    let report: Value = serde_json::from_str(&fs::read_to_string(report).unwrap()).unwrap();
+    (err, report)
+}
+
+// A run that cannot start keeps the InvalidInput kind, while a run that traces every
+// candidate without finding an outlet is NotFound; both describe themselves in the error
+// document, which only a failed trace fills with its attempts.
+#[test]
+fn test_error_json() {
+    let dir = temp_dir("find_outlet_error_json").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let no_streams = format!("{}/no_streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let small_watershed = format!("{}/small_ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let error_json = format!("{}/error.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(&no_streams, GridFixture::new(5, 6, |_, _| 0f64));
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    write_grid(&small_watershed, GridFixture::new(4, 6, |_, _| 1f64));
+    let run = |streams: &str, watershed: &str| {
+        let _ = fs::remove_file(&error_json);
+        FindOutlet::new().run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                "--watershed".to_string(),
+                watershed.to_string(),
+                format!("--output={}", output),
+                "--max_candidates=3".to_string(),
+                "--skip_crs_check".to_string(),
+                format!("--error_json={}", error_json),
+            ],
+            "",
+            false,
+        )
+    };
+    let document =
+        || -> Value { serde_json::from_str(&fs::read_to_string(&error_json).unwrap()).unwrap() };
+
+    let err = run(&streams, &small_watershed).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    let json = document();
+    assert_eq!(json["tool"], "FindOutlet");
+    assert_eq!(json["failure_class"], "geometry_mismatch");
+    assert_eq!(json["exit_code"], 3);
+    assert_eq!(json["error_kind"], "InvalidInput");
+    assert_eq!(json["message"], err.to_string());
+    assert_eq!(json["parameters"]["watershed"], json!(small_watershed));
+    assert_eq!(json["parameters"]["max_candidates"], "3");
+    assert_eq!(json["parameters"]["skip_crs_check"], true);
+    assert_eq!(json["attempts"], json!([]));
+    assert!(json.get("candidates_considered").is_none());
+
+    // No stream cell drains the watershed.
+    let err = run(&no_streams, &watershed).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::NotFound);
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    let json = document();
+    assert_eq!(json["failure_class"], "trace_failure");
+    assert_eq!(json["exit_code"], 5);
+    assert_eq!(json["error_kind"], "NotFound");
+    assert_eq!(json["candidates_considered"], 3);
+    assert_eq!(json["max_steps_used"], 120);
+    let attempts = json["attempts"].as_array().unwrap();
+    assert_eq!(attempts.len(), 3);
+    assert!(attempts
+        .iter()
+        .all(|attempt| attempt["result"] == "failure"));
+    assert_eq!(attempts[0]["candidate_rank"], 0);
+
+    // A successful run writes no error document.
+    run(&streams, &watershed).unwrap();
+    assert!(!std::path::Path::new(&error_json).exists());
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_failure_classes() {
+    let dir = temp_dir("find_outlet_failure_classes").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let no_streams = format!("{}/no_streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let report = format!("{}/report.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(&no_streams, GridFixture::new(5, 6, |_, _| 0f64));
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--watershed={}", watershed),
+    ];
+
+    // The human-readable message is unchanged by the classification.
+    let (err, json) = run_with_report(&args, &report);
+    assert_eq!(
+        err.to_string(),
+        "Output GeoJSON file (--output) not specified."
+    );
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    assert!(matches!(
+        WbtError::from_io_ref(&err),
+        Some(WbtError::InvalidArgument(_))
+    ));
+    assert_eq!(json["status"], "failure");
+    assert_eq!(json["failure_class"], "argument_error");
+    assert_eq!(json["exit_code"], 2);
+    assert_eq!(json["message"], err.to_string());
+
+    // A watershed mask with a different number of rows
+    let small_watershed = format!("{}/small_ws.tif", dir);
+    write_grid(
+        &small_watershed,
+        GridFixture::new(4, 6, |_, _| 1f64).with_origin(0f64, 10f64),
+    );
+    let mut mismatched = args.clone();
+    mismatched[2] = format!("--watershed={}", small_watershed);
+    mismatched.push(format!("--output={}", output));
+    let (err, json) = run_with_report(&mismatched, &report);
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    assert!(matches!(
+        WbtError::from_io_ref(&err),
+        Some(WbtError::GeometryMismatch(_))
+    ));
+    assert_eq!(json["failure_class"], "geometry_mismatch");
+    assert_eq!(json["exit_code"], 3);
+
+    // A missing input names the flag it was given with.
+    let mut missing = args.clone();
+    missing[2] = format!("--watershed={}/missing.tif", dir);
+    missing.push(format!("--output={}", output));
+    let (err, json) = run_with_report(&missing, &report);
+    assert_eq!(failure_class(&err), Some(FailureClass::FileNotFound));
+    match WbtError::from_io_ref(&err) {
+        Some(WbtError::FileNotFound(message)) => {
+            assert!(message.starts_with(&format!("--watershed {}/missing.tif: ", dir)))
+        }
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert_eq!(json["failure_class"], "file_not_found");
+    assert_eq!(json["exit_code"], 6);
+
+    // No stream cell drains the watershed.
+    let mut untraceable = args.clone();
+    untraceable[1] = format!("--streams={}", no_streams);
+    untraceable.push(format!("--output={}", output));
+    let (err, json) = run_with_report(&untraceable, &report);
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    assert_eq!(json["failure_class"], "trace_failure");
+    assert_eq!(json["exit_code"], 5);
+    assert!(json["outlet"].is_null());
+
+    let mut valid = args.clone();
+    valid.push(format!("--output={}", output));
+    valid.push(format!("--report={}", report));
+    FindOutlet::new().run(valid, "", false).unwrap();
+    let json: Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(json["status"], "success");
+    assert!(json["failure_class"].is_null());
+    assert_eq!(json["exit_code"], 0);
+    assert_eq!(
+        (&json["outlet"]["row"], &json["outlet"]["column"]),
+        (&Value::from(2), &Value::from(3))
+    );
+}
+
+#[test]
+fn test_diagnostics() {
+    let dir = temp_dir("find_outlet_diagnostics").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let no_streams = format!("{}/no_streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let diagnostics = format!("{}/diagnostics.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(&no_streams, GridFixture::new(5, 6, |_, _| 0f64));
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = |streams: &str, extra: &[String]| {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+            format!("--diagnostics={}", diagnostics),
+        ];
+        args.extend_from_slice(extra);
+        args
+    };
+    let read_diagnostics =
+        || -> Value { serde_json::from_str(&fs::read_to_string(&diagnostics).unwrap()).unwrap() };
+
+    // Every one of the 20 candidates is listed, although the error quotes only five.
+    let err = FindOutlet::new()
+        .run(args(&no_streams, &[]), "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    assert_eq!(err.to_string().matches("Candidate").count(), 5);
+    let json = read_diagnostics();
+    assert_eq!(json["status"], "failure");
+    assert_eq!(json["selected"], json!([]));
+    let attempts = json["attempts"].as_array().unwrap();
+    assert_eq!(attempts.len(), 20);
+    for (rank, attempt) in attempts.iter().enumerate() {
+        assert_eq!(attempt["mode"], "watershed");
+        assert_eq!(attempt["candidate_rank"], rank);
+        assert_eq!(attempt["result"], "failure");
+        assert!(attempt["failure_reason"].is_string());
+        assert_eq!(attempt["failure_kind"], "left_raster");
+    }
+
+    // The highest-ranked candidate is traced to the outlet at the mask boundary.
+    let report = format!("{}/report.json", dir);
+    FindOutlet::new()
+        .run(args(&streams, &[format!("--report={}", report)]), "", false)
+        .unwrap();
+    let report: Value = serde_json::from_str(&fs::read_to_string(&report).unwrap()).unwrap();
+    assert_eq!(
+        report["outlet"]["candidate_stats"],
+        json!({
+            "attempted": 1,
+            "succeeded": 1,
+            "failed": 0,
+            "failures": {
+                "loop": 0,
+                "invalid_pointer": 0,
+                "max_steps": 0,
+                "junction_mismatch": 0,
+                "left_raster": 0,
+                "left_mask": 0
+            },
+            "winner_rank": 0,
+            "winner_distance_to_boundary": read_diagnostics()["attempts"][0]["distance_to_boundary"]
+        })
+    );
+    let json = read_diagnostics();
+    assert_eq!(json["status"], "success");
+    assert_eq!(json["perimeter_junction_counts"], json!({"0": 1, "1": 1}));
+    let attempt = &json["attempts"][0];
+    assert_eq!(json["attempts"].as_array().unwrap().len(), 1);
+    assert_eq!(attempt["mode"], "watershed");
+    assert_eq!(attempt["candidate_rank"], 0);
+    assert_eq!(attempt["result"], "success");
+    assert_eq!(
+        (&attempt["outlet_row"], &attempt["outlet_col"]),
+        (&json!(2), &json!(3))
+    );
+    let selected = &json["selected"][0];
+    assert_eq!(selected["attempt"], 0);
+    assert!(selected["request_id"].is_null());
+    assert!(selected["reason"]
+        .as_str()
+        .unwrap()
+        .starts_with("Watershed candidate 0"));
+
+    // A requested start that is traced is the only attempt.
+    FindOutlet::new()
+        .run(
+            args(&streams, &["--requested_outlet_row_col=4,1".to_string()]),
+            "",
+            false,
+        )
+        .unwrap();
+    let json = read_diagnostics();
+    assert_eq!(json["attempts"].as_array().unwrap().len(), 1);
+    let attempt = &json["attempts"][0];
+    assert_eq!(attempt["mode"], "requested");
+    assert_eq!(attempt["request_id"], 0);
+    assert_eq!(
+        (&attempt["start_row"], &attempt["start_col"]),
+        (&json!(4), &json!(1))
+    );
+    assert_eq!(json["selected"][0]["attempt"], 0);
+    assert_eq!(json["selected"][0]["request_id"], 0);
+}
+
+// The candidate limit and step budget override the defaults, and equally deep candidates
+// are ranked by row, then column.
+#[test]
+fn test_candidate_and_step_limits() {
+    let dir = temp_dir("find_outlet_limits").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let no_streams = format!("{}/no_streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let diagnostics = format!("{}/diagnostics.json", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(&no_streams, GridFixture::new(5, 6, |_, _| 0f64));
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let run = |streams: &str, extra: &[&str]| -> Result<Map<String, Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+            format!("--diagnostics={}", diagnostics),
+        ];
+        args.extend(extra.iter().map(|s| s.to_string()));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone())
+    };
+    let attempts = || -> Vec<Value> {
+        let json: Value = serde_json::from_str(&fs::read_to_string(&diagnostics).unwrap()).unwrap();
+        json["attempts"].as_array().unwrap().clone()
+    };
+
+    let props = run(&streams, &[]).unwrap();
+    assert_eq!(props["candidates_considered"], 20);
+    assert_eq!(props["max_steps_used"], 5 * 6 * 4);
+
+    run(&no_streams, &["--max_candidates=3"]).unwrap_err();
+    let cells: Vec<(Value, Value)> = attempts()
+        .iter()
+        .map(|a| (a["start_row"].clone(), a["start_col"].clone()))
+        .collect();
+    assert_eq!(
+        cells,
+        vec![
+            (json!(1), json!(1)),
+            (json!(1), json!(2)),
+            (json!(2), json!(1))
+        ]
+    );
+
+    // Within one step only the candidate on the outlet stream cell reaches it.
+    let props = run(&streams, &["--max_steps=1"]).unwrap();
+    assert_eq!(props["candidate_rank"], 13);
+    assert_eq!(props["max_steps_used"], 1);
+    assert_eq!(attempts()[0]["failure_kind"], "max_steps");
+    let err = run(&streams, &["--max_steps=1", "--max_candidates=13"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    assert_eq!(attempts().len(), 13);
+
+    for arg in ["--max_candidates=0", "--max_steps=-1", "--max_steps=x"] {
+        let err = run(&streams, &[arg]).unwrap_err();
+        assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    }
+}
+
+// The mask of the 5 x 6 fixture covers the first four columns, so that its cells in rows 1-3
+// of columns 1 and 2 are one cell from its edge.
+#[test]
+fn test_mask_erosion() {
+    let dir = temp_dir("find_outlet_mask_erosion").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let run = |extra: &[&str], with_mask: bool| -> Result<Map<String, Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+        ];
+        if with_mask {
+            args.push(format!("--watershed={}", watershed));
+        } else {
+            args.push("--requested_outlet_row_col=1,1".to_string());
+        }
+        args.extend(extra.iter().map(|s| s.to_string()));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone())
+    };
+
+    let plain = run(&[], true).unwrap();
+    assert_eq!(plain["candidates_considered"], 20);
+    assert_eq!(plain["mask_erosion"], 0);
+
+    // Only the six cells one from the edge remain as candidates, ranked by their distance to
+    // the edge of the eroded mask, while the outlet is still found against the original mask.
+    let eroded = run(&["--mask_erosion=1"], true).unwrap();
+    assert_eq!(eroded["candidates_considered"], 6);
+    assert_eq!(eroded["mask_erosion"], 1);
+    assert_eq!(eroded["distance_to_boundary"], 0);
+    assert_eq!(eroded["watershed_cell_count"], 20);
+    for key in ["row", "column", "outlet_in_mask", "steps_beyond_mask"] {
+        assert_eq!(eroded[key], plain[key], "{}", key);
+    }
+
+    let err = run(&["--mask_erosion=2"], true).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    assert!(err.to_string().contains("leaves no cells"));
+    for (arg, with_mask) in [("--mask_erosion=1", false), ("--mask_erosion=-1", true)] {
+        let err = run(&[arg], with_mask).unwrap_err();
+        assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    }
+    fs::remove_dir_all(&dir).ok();
+}
+
+// A tributary along column 2 joins the channel along row 2 at (2, 2), which the trace from
+// the deepest candidate passes before reaching the outlet at the mask boundary.
+#[test]
+fn test_snap_to_junction() {
+    let dir = temp_dir("find_outlet_snap_to_junction").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, c| {
+            if r == 2 || (r < 2 && c == 2) {
+                1f64
+            } else {
+                0f64
+            }
+        }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let run = |extra: &[&str]| -> Map<String, Value> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+        ];
+        args.extend(extra.iter().map(|s| s.to_string()));
+        FindOutlet::new().run(args, "", false).unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone()
+    };
+
+    let props = run(&[]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert!(props.get("junction_snapped").is_none());
+
+    let props = run(&["--snap_to_junction"]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(1)));
+    assert_eq!(props["junction_snapped"], true);
+    assert_eq!(
+        (&props["original_outlet_row"], &props["original_outlet_col"]),
+        (&json!(2), &json!(3))
+    );
+    assert_eq!(props["steps_from_start"], 1);
+    assert_eq!(props["outlet_junction_count"], 1);
+
+    // The junction is one cell from the outlet, outside a window of zero cells.
+    let props = run(&["--snap_to_junction", "--junction_window=0"]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert_eq!(props["junction_snapped"], false);
+    assert_eq!(props["original_outlet_col"], 3);
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The channel along row 2 drains east and is joined by a tributary down column 2 at the
+// junction (2, 2), the last mask cell on the channel; the channel cell below the junction,
+// (2, 3), lies outside the mask.
+#[test]
+fn test_outlet_within_mask() {
+    let dir = temp_dir("find_outlet_within_mask").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let headwater = format!("{}/headwater.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, c| {
+            if r == 2 || (r < 2 && c == 2) {
+                1f64
+            } else {
+                0f64
+            }
+        }),
+    );
+    // A channel heading at the last mask cell has no stream cell inside the mask to fall
+    // back on.
+    write_grid(
+        &headwater,
+        GridFixture::new(5, 6, |r, c| if r == 2 && c >= 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 3 { 1f64 } else { 0f64 }),
+    );
+    let wide = format!("{}/wide.tif", dir);
+    write_grid(
+        &wide,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = |streams: &str, extra: &[&str]| -> Vec<String> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+        ];
+        args.extend(extra.iter().map(|s| s.to_string()));
+        args
+    };
+    let run = |streams: &str, extra: &[&str]| -> Map<String, Value> {
+        FindOutlet::new()
+            .run(args(streams, extra), "", false)
+            .unwrap();
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone()
+    };
+
+    // By default, the outlet is the first cell with a junction count of 1 below the mask.
+    let props = run(&streams, &[]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert_eq!(props["outlet_in_mask"], false);
+    assert_eq!(props["outlet_downstream_of_mask"], true);
+    assert!(props.get("mask_fallback").is_none());
+
+    // The trace leaves the mask at the junction and falls back on the channel cell above it.
+    let props = run(&streams, &["--outlet_within_mask"]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(1)));
+    assert_eq!(props["outlet_in_mask"], true);
+    assert_eq!(props["outlet_downstream_of_mask"], false);
+    assert_eq!(props["outlet_junction_count"], 1);
+    assert_eq!(props["mask_fallback"], true);
+    assert_eq!(props["steps_beyond_mask"], 0);
+
+    // An outlet accepted where the trace leaves the mask is not a fallback.
+    let props = run(
+        &streams,
+        &["--outlet_within_mask", &format!("--watershed={}", wide)],
+    );
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert_eq!(props["mask_fallback"], false);
+
+    let props = run(&headwater, &[]);
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    let err = FindOutlet::new()
+        .run(args(&headwater, &["--outlet_within_mask"]), "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::TraceFailure));
+    assert!(err.to_string().contains("leaves the watershed"), "{}", err);
+
+    let mut no_mask = args(
+        &streams,
+        &["--outlet_within_mask", "--requested_outlet_row_col=0,0"],
+    );
+    no_mask.retain(|a| !a.starts_with("--watershed"));
+    let err = FindOutlet::new().run(no_mask, "", false).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// Failed candidates are counted by kind; requested attempts are not candidates.
+// The channel along row 2 drains east, with elevations of 100 - col² along it, and the
+// watershed covers the first four columns, so that the outlet is (2, 3) at 91 m.
+#[test]
+fn test_outlet_elevation_and_channel_slope() {
+    let dir = temp_dir("find_outlet_channel_slope").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let tributary = format!("{}/tributary.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let dem = format!("{}/dem.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &tributary,
+        GridFixture::new(5, 6, |r, c| {
+            if r == 2 || (r < 2 && c == 2) {
+                1f64
+            } else {
+                0f64
+            }
+        }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let write_dem = |nodata_cell: (isize, isize)| {
+        write_grid(
+            &dem,
+            GridFixture::new(5, 6, |r, c| {
+                if (r, c) == nodata_cell {
+                    -32768f64
+                } else {
+                    100f64 - (c * c) as f64
+                }
+            }),
+        )
+    };
+    let run = |streams: &str, extra: &[&str]| -> Result<Map<String, Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--watershed={}", watershed),
+            format!("--output={}", output),
+        ];
+        args.extend(extra.iter().map(|s| s.replace("{dem}", &dem)));
+        FindOutlet::new().run(args, "", false)?;
+        let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        Ok(geojson["features"][0]["properties"]
+            .as_object()
+            .unwrap()
+            .clone())
+    };
+
+    write_dem((-1, -1));
+    let props = run(&streams, &[]).unwrap();
+    assert!(props.get("outlet_elevation").is_none());
+
+    // The walk up the stream stops at the channel head in column 0, three steps up.
+    let props = run(&streams, &["--dem={dem}"]).unwrap();
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert_eq!(props["outlet_elevation"], 91.0);
+    assert_eq!(props["channel_slope_steps"], 3);
+    assert_eq!(props["channel_slope_length"], 30.0);
+    assert_eq!(props["channel_slope"], 0.3);
+
+    let props = run(&streams, &["--dem={dem}", "--slope_steps=2"]).unwrap();
+    assert_eq!(props["channel_slope_steps"], 2);
+    assert_eq!(props["channel_slope"], 0.4);
+
+    // A walk from the outlet alone stops at the junction with the tributary in column 2,
+    // which the traced path did not come through.
+    let props = run(
+        &tributary,
+        &["--dem={dem}", "--requested_outlet_row_col=2,3"],
+    )
+    .unwrap();
+    assert_eq!((&props["row"], &props["column"]), (&json!(2), &json!(3)));
+    assert_eq!(props["channel_slope_steps"], 1);
+    assert_eq!(props["channel_slope"], 0.5);
+
+    // The walk stops before a nodata elevation.
+    write_dem((2, 1));
+    let props = run(&streams, &["--dem={dem}"]).unwrap();
+    assert_eq!(props["channel_slope_steps"], 1);
+    assert_eq!(props["channel_slope"], 0.5);
+
+    // A nodata elevation at the outlet is reported as null rather than NaN.
+    write_dem((2, 3));
+    let props = run(&streams, &["--dem={dem}"]).unwrap();
+    assert_eq!(props["outlet_elevation"], Value::Null);
+    assert_eq!(props["channel_slope"], Value::Null);
+
+    let err = run(&streams, &["--slope_steps=5"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    assert!(err.to_string().contains("--dem"));
+    let err = run(&streams, &["--dem={dem}", "--slope_steps=0"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+
+    write_grid(&dem, GridFixture::new(4, 6, |_, _| 100f64));
+    let err = run(&streams, &["--dem={dem}"]).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The fixture of test_downstream_preview on a 0.01 degree grid in EPSG:4326: the preview and the
+// channel slope reach run east along row 2, at 45.025 degrees north, where a column is about
+// 785.92 m wide.
+#[test]
+fn test_preview_and_channel_slope_lengths_in_meters_for_geographic_pointer() {
+    let dir = temp_dir("find_outlet_geographic_lengths").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let dem = format!("{}/dem.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let geographic = |grid: GridFixture| {
+        grid.with_resolution(0.01)
+            .with_origin(-116.03, 45.0)
+            .with_epsg_code(4326)
+    };
+    write_grid(
+        &pntr,
+        geographic(GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        })),
+    );
+    write_grid(
+        &streams,
+        geographic(GridFixture::new(
+            5,
+            6,
+            |r, _| {
+                if r == 2 {
+                    1f64
+                } else {
+                    0f64
+                }
+            },
+        )),
+    );
+    write_grid(
+        &watershed,
+        geographic(GridFixture::new(
+            5,
+            6,
+            |_, c| {
+                if c < 4 {
+                    1f64
+                } else {
+                    0f64
+                }
+            },
+        )),
+    );
+    write_grid(
+        &dem,
+        geographic(GridFixture::new(5, 6, |_, c| 100f64 - (c * c) as f64)),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--watershed={}", watershed),
+        format!("--output={}", output),
+        format!("--dem={}", dem),
+        "--downstream_preview=5".to_string(),
+    ];
+
+    FindOutlet::new().run(args, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let column_width = 785.92;
+    let outlet = &geojson["features"][0]["properties"];
+    assert_eq!(outlet["column"], 3);
+    assert_eq!(outlet["channel_slope_steps"], 3);
+    let slope_length = outlet["channel_slope_length"].as_f64().unwrap();
+    assert!((slope_length - 3f64 * column_width).abs() < 0.05);
+    let slope = outlet["channel_slope"].as_f64().unwrap();
+    assert!((slope - 9f64 / slope_length).abs() < 1e-12);
+    let preview = &geojson["features"][1]["properties"];
+    assert_eq!(preview["steps"], 2);
+    let preview_length = preview["length"].as_f64().unwrap();
+    assert!((preview_length - 2f64 * column_width).abs() < 0.05);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_summary_csv() {
+    let dir = temp_dir("find_outlet_summary_csv").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let output = format!("{}/outlets.geojson", dir);
+    let summary = format!("{}/summary.csv", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, c| match (r, c) {
+            (0, 4) => 2f64,
+            (0, 5) => 32f64,
+            (0, _) | (1, _) => 8f64,
+            (2, _) => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let run = |extra: &str| {
+        FindOutlet::new().run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--output={}", output),
+                format!("--summary_csv={}", summary),
+                extra.to_string(),
+            ],
+            "",
+            false,
+        )
+    };
+    let read_rows = || -> Vec<Vec<String>> {
+        fs::read_to_string(&summary)
+            .unwrap()
+            .lines()
+            .map(|line| line.split(',').map(|v| v.to_string()).collect())
+            .collect()
+    };
+    let header = "id,row,col,easting,northing,epsg,start_mode,steps_taken,steps_beyond_mask,path_length_m,outlet_junction_count,distance_to_boundary,distance_to_boundary_m,elapsed_seconds";
+
+    run(&format!("--watershed={}", watershed)).unwrap();
+    let rows = read_rows();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].join(","), header);
+    assert_eq!(rows[1][..3], ["0", "2", "3"]);
+    assert_eq!(rows[1][3..7], ["35.0", "25.0", "0", "watershed"]);
+    // the trace from (1, 1) runs three cells east; the count includes the step out of the mask
+    assert_eq!(rows[1][7..13], ["4", "0", "30.0", "1", "1", "10.0"]);
+    assert!(rows[1][13].parse::<f64>().unwrap() >= 0.0);
+
+    // Failed requests have no row, and unknown distances to the boundary are left empty.
+    run("--requested_outlet_row_col=0,1; 0,5; 4,2").unwrap();
+    let rows = read_rows();
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[1][..3], ["0", "2", "1"]);
+    assert_eq!(rows[2][..3], ["2", "2", "2"]);
+    assert_eq!(rows[2][6], "requested");
+    assert_eq!(rows[2][11..13], ["", ""]);
+    assert!(!std::path::Path::new(&format!("{}.tmp", summary)).exists());
+
+    // A failed run leaves the previous summary in place.
+    assert!(run("--requested_outlet_row_col=0,5").is_err());
+    assert_eq!(read_rows().len(), 3);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_candidate_stats() {
+    let failed = |rank: usize, kind: TraceFailureKind| {
+        let mut attempt = TraceAttempt::new(TraceStartMode::WatershedCandidate, Some((0, 0)));
+        attempt.candidate_rank = Some(rank);
+        attempt.failure_kind = Some(kind);
+        attempt
+    };
+    let mut requested = TraceAttempt::new(TraceStartMode::Requested, None);
+    requested.failure_kind = Some(TraceFailureKind::NoValidStart);
+    let mut winner = TraceAttempt::new(TraceStartMode::WatershedCandidate, Some((3, 4)));
+    winner.candidate_rank = Some(3);
+    winner.distance_to_boundary = Some(7);
+    winner.result = "success";
+    let attempts = vec![
+        requested,
+        failed(0, TraceFailureKind::Loop),
+        failed(1, TraceFailureKind::MaxSteps),
+        failed(2, TraceFailureKind::Loop),
+        winner,
+    ];
+    let stats = CandidateStats::from_attempts(&attempts);
+    assert_eq!(
+        stats.to_json(),
+        json!({
+            "attempted": 4,
+            "succeeded": 1,
+            "failed": 3,
+            "failures": {
+                "loop": 2,
+                "invalid_pointer": 0,
+                "max_steps": 1,
+                "junction_mismatch": 0,
+                "left_raster": 0,
+                "left_mask": 0
+            },
+            "winner_rank": 3,
+            "winner_distance_to_boundary": 7
+        })
+    );
+    let stats = CandidateStats::from_attempts(&attempts[..3]);
+    assert!(stats.to_json()["winner_rank"].is_null());
+}
+
+#[test]
+fn test_failure_class_exit_codes() {
+    for class in FailureClass::ALL.iter() {
+        assert_eq!(
+            FailureClass::from_exit_code(class.exit_code()),
+            Some(*class)
+        );
+    }
+    assert_eq!(FailureClass::from_exit_code(0), None);
+    assert_eq!(FailureClass::from_exit_code(1), None);
+}
+
+// The outlet of the channel along row 2 is written as a point Shapefile with truncated
+// field names, and the projection of the D8 pointer raster is copied into the .prj file.
+#[test]
+fn test_shapefile_output() {
+    let dir = temp_dir("find_outlet_shapefile").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        })
+        .with_epsg_code(32611),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }).with_epsg_code(32611),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }).with_epsg_code(32611),
+    );
+
+    let output = format!("{}/outlet.shp", dir);
+    FindOutlet::new()
+        .run(
+            vec![
+                format!("--d8_pntr={}", pntr),
+                format!("--streams={}", streams),
+                format!("--watershed={}", watershed),
+                format!("--output={}", output),
+                "--downstream_preview=2".to_string(),
+            ],
+            "",
+            false,
+        )
+        .unwrap();
+
+    let shapefile = Shapefile::read(&output).unwrap();
+    assert_eq!(shapefile.num_records, 1);
+    let point = shapefile.get_record(0).points[0];
+    assert_eq!((point.x, point.y), (35f64, 25f64));
+    assert_eq!(shapefile.attributes.get_value(0, "row"), FieldData::Int(2));
+    assert_eq!(
+        shapefile.attributes.get_value(0, "column"),
+        FieldData::Int(3)
+    );
+    assert_eq!(
+        shapefile.attributes.get_value(0, "junctions"),
+        FieldData::Int(1)
+    );
+    assert_eq!(
+        shapefile.attributes.get_value(0, "start_mode"),
+        FieldData::Text("watershed".to_string())
+    );
+    assert_eq!(
+        shapefile.attributes.get_value(0, "req_row"),
+        FieldData::Null
+    );
+    assert!(shapefile
+        .attributes
+        .fields
+        .iter()
+        .all(|field| field.name.len() <= 10));
+    let prj = fs::read_to_string(format!("{}/outlet.prj", dir)).unwrap();
+    assert!(prj.contains("UTM"));
+}
+
+// Every cell centre of a UTM zone 11N raster converts to lon/lat and back to its own cell,
+// whether the coordinate system is identified from the EPSG code or from the WKT.
+#[test]
+fn test_lon_lat_to_row_col_round_trip() {
+    let mut pntr = grid_fixture_raster(
+        "utm_pntr.tif",
+        &GridFixture::new(40, 30, |_, _| -32768f64)
+            .with_origin(499850f64, 5174600f64)
+            .with_epsg_code(32611),
+    );
+    let projection = raster_projection(&pntr).unwrap();
+    let round_trip = |pntr: &Raster, projection: &Projection| {
+        for row in 0..40 {
+            for col in 0..30 {
+                let (lat, lon) = utm_to_deg(
+                    11,
+                    'N',
+                    pntr.get_x_from_column(col),
+                    pntr.get_y_from_row(row),
+                );
+                assert_eq!(
+                    lon_lat_to_row_col(pntr, Some(projection), lon, lat),
+                    Some((row, col))
+                );
+            }
+        }
+    };
+    round_trip(&pntr, &projection);
+
+    pntr.configs.epsg_code = 0;
+    pntr.configs.coordinate_ref_system_wkt = esri_wkt_from_epsg(32611);
+    round_trip(&pntr, &raster_projection(&pntr).unwrap());
+
+    pntr.configs.coordinate_ref_system_wkt = String::new();
+    assert!(raster_projection(&pntr).is_none());
+    assert_eq!(lon_lat_to_row_col(&pntr, None, -117.0, 46.7), None);
+}
+
+// A lon/lat request on a UTM raster starts the trace at the cell containing it.
+#[test]
+fn test_requested_lon_lat_on_projected_raster() {
+    let dir = temp_dir("find_outlet_projected_lon_lat").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let unknown_pntr = format!("{}/unknown_pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let origin = (500000f64, 5175000f64);
+    let pointers = |r: isize, _: isize| match r {
+        0 | 1 => 8f64,
+        2 => 2f64,
+        _ => 128f64,
+    };
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, pointers)
+            .with_origin(origin.0, origin.1)
+            .with_epsg_code(32611),
+    );
+    write_grid(
+        &unknown_pntr,
+        GridFixture::new(5, 6, pointers).with_origin(origin.0, origin.1),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 })
+            .with_origin(origin.0, origin.1)
+            .with_epsg_code(32611),
+    );
+
+    // The centre of cell (1, 1)
+    let (lat, lon) = utm_to_deg(11, 'N', 500015f64, 5175035f64);
+    let args = |pntr: &str| {
+        vec![
+            format!("--d8_pntr={}", pntr),
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+            format!("--requested_outlet_lng_lat={},{}", lon, lat),
+        ]
+    };
+    FindOutlet::new().run(args(&pntr), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&props["requested_row"], &props["requested_col"]),
+        (&Value::from(1), &Value::from(1))
+    );
+    assert_eq!(props["start_mode"], "requested");
+    assert_eq!(props["row"], 2);
+    assert_eq!(props["epsg"], 32611);
+
+    // The pointer without an EPSG code does not match the streams unless the check is skipped.
+    let err = FindOutlet::new()
+        .run(args(&unknown_pntr), "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    let lines: Vec<String> = err.to_string().lines().map(String::from).collect();
+    assert_eq!(
+        lines[0],
+        "Input rasters must share geometry; differs from --d8_pntr: --streams (epsg_code)."
+    );
+    assert!(lines[1].contains("EPSG 0, north 5175050"));
+    assert!(lines[2].contains("EPSG 32611"));
+    let mut skipped = args(&unknown_pntr);
+    skipped.push("--skip_crs_check".to_string());
+    let err = FindOutlet::new().run(skipped, "", false).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ConversionFailure));
+    fs::remove_dir_all(&dir).ok();
+}
+
+// A request in map coordinates starts the trace at the cell containing it; a request off the
+// raster is clamped to its edge.
+#[test]
+fn test_requested_outlet_xy() {
+    let dir = temp_dir("find_outlet_requested_xy").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    let origin = (500000f64, 5175000f64);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        })
+        .with_origin(origin.0, origin.1)
+        .with_epsg_code(32611),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 })
+            .with_origin(origin.0, origin.1)
+            .with_epsg_code(32611),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--output={}", output),
+        "--requested_outlet_xy=500012.5,5175031; 499900,5175100".to_string(),
+    ];
+    FindOutlet::new().run(args.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&props["requested_row"], &props["requested_col"]),
+        (&json!(1), &json!(1))
+    );
+    assert_eq!(
+        (&props["requested_easting"], &props["requested_northing"]),
+        (&json!(500012.5), &json!(5175031.0))
+    );
+    assert!(props["requested_lon"].is_null());
+    assert_eq!(props["start_mode"], "requested");
+    assert_eq!(props["row"], 2);
+    let props = &geojson["features"][1]["properties"];
+    assert_eq!(
+        (&props["requested_row"], &props["requested_col"]),
+        (&json!(0), &json!(0))
+    );
+    assert_eq!(props["requested_easting"], 499900.0);
+
+    for other in [
+        "--requested_outlet_row_col=1,1",
+        "--requested_outlet_lng_lat=-117,46.7",
+    ] {
+        let mut both = args.clone();
+        both.push(other.to_string());
+        let err = FindOutlet::new().run(both, "", false).unwrap_err();
+        assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+        assert!(err
+            .to_string()
+            .starts_with("The requested outlet was given with both --requested_outlet_"));
+        assert!(err.to_string().contains("and --requested_outlet_xy;"));
+    }
+    let mut malformed = args.clone();
+    malformed[3] = "--requested_outlet_xy=500012.5".to_string();
+    let err = FindOutlet::new().run(malformed, "", false).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_parse_bbox_and_window() {
+    let bbox = parse_bbox("10, 10,30,40").unwrap();
+    assert_eq!(
+        (bbox.min_x, bbox.min_y, bbox.max_x, bbox.max_y),
+        (10.0, 10.0, 30.0, 40.0)
+    );
+    for value in [
+        "10,10,30",
+        "10,10,30,x",
+        "30,10,10,40",
+        "10,40,30,10",
+        "10,10,inf,40",
+    ] {
+        let err = parse_bbox(value).unwrap_err();
+        assert_eq!(
+            failure_class(&err),
+            Some(FailureClass::ArgumentError),
+            "{}",
+            value
+        );
+    }
+
+    let dir = temp_dir("find_outlet_bbox_window").unwrap();
+    let pntr_file = format!("{}/pntr.tif", dir);
+    write_grid(&pntr_file, GridFixture::new(5, 6, |_, _| 1f64));
+    let pntr = Raster::new(&pntr_file, "r").unwrap();
+    // Box edges on cell edges take in only the cells inside them.
+    assert_eq!(bbox_window(&pntr, &bbox), Some((1..4, 1..3)));
+    let bbox = parse_bbox("5,5,35,45").unwrap();
+    assert_eq!(bbox_window(&pntr, &bbox), Some((0..5, 0..4)));
+    let bbox = parse_bbox("-100,-100,15,15").unwrap();
+    assert_eq!(bbox_window(&pntr, &bbox), Some((3..5, 0..2)));
+    let bbox = parse_bbox("60,0,70,10").unwrap();
+    assert_eq!(bbox_window(&pntr, &bbox), None);
+    fs::remove_dir_all(&dir).ok();
+}
+
+// The raster of test_streams_derived_from_flow_accum, searched within the first two
+// columns.
+#[test]
+fn test_bbox() {
+    let dir = temp_dir("find_outlet_bbox").unwrap();
+    let pntr = format!("{}/pntr.tif", dir);
+    let streams = format!("{}/streams.tif", dir);
+    let watershed = format!("{}/ws.tif", dir);
+    let junctions = format!("{}/junctions.tif", dir);
+    let output = format!("{}/outlet.geojson", dir);
+    write_grid(
+        &pntr,
+        GridFixture::new(5, 6, |r, _| match r {
+            0 | 1 => 8f64,
+            2 => 2f64,
+            _ => 128f64,
+        }),
+    );
+    write_grid(
+        &streams,
+        GridFixture::new(5, 6, |r, _| if r == 2 { 1f64 } else { 0f64 }),
+    );
+    write_grid(
+        &watershed,
+        GridFixture::new(5, 6, |_, c| if c < 4 { 1f64 } else { 0f64 }),
+    );
+    let args = vec![
+        format!("--d8_pntr={}", pntr),
+        format!("--streams={}", streams),
+        format!("--output={}", output),
+        "--bbox=0,0,20,50".to_string(),
+    ];
+
+    // The mask is clipped to the box, whose edge cells are boundary cells, while traces
+    // still walk out of it to the edge of the whole mask.
+    let mut masked = args.clone();
+    masked.push(format!("--watershed={}", watershed));
+    masked.push(format!("--junctions={}", junctions));
+    FindOutlet::new().run(masked.clone(), "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&props["row"], &props["column"]),
+        (&Value::from(2), &Value::from(3))
+    );
+    let raster = Raster::new(&junctions, "r").unwrap();
+    let nodata = raster.configs.nodata;
+    for row in 0..5 {
+        for col in 0..6 {
+            assert_eq!(raster.get_value(row, col) == nodata, col >= 2 || row != 2);
+        }
+    }
+
+    // A request outside the box is moved to its nearest cell.
+    let mut requested = args.clone();
+    requested.push("--requested_outlet_row_col=0,5".to_string());
+    FindOutlet::new().run(requested, "", false).unwrap();
+    let geojson: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+    let props = &geojson["features"][0]["properties"];
+    assert_eq!(
+        (&props["requested_row"], &props["requested_col"]),
+        (&json!(0), &json!(1))
+    );
+    assert_eq!(props["row"], 2);
+
+    // A box without mask cells, or off the raster, is a geometry mismatch.
+    masked[3] = "--bbox=45,0,60,50".to_string();
+    let err = FindOutlet::new()
+        .run(masked.clone(), "", false)
+        .unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    assert!(err
+        .to_string()
+        .contains("does not contain any cells of the watershed mask"));
+    masked[3] = "--bbox=100,0,120,50".to_string();
+    let err = FindOutlet::new().run(masked, "", false).unwrap_err();
+    assert_eq!(failure_class(&err), Some(FailureClass::GeometryMismatch));
+    assert!(err
+        .to_string()
+        .contains("does not overlap the D8 pointer raster"));
+    fs::remove_dir_all(&dir).ok();
+}
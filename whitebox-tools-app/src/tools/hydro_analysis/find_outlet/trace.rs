@@ -0,0 +1,2084 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Author: Dr. Roger Lew
+Created: 09/09/2025
+License: MIT
+*/
+
+use super::args::FindOutletArgs;
+use super::inputs::Inputs;
+use super::start::{
+    conversion_message, find_nearest_valid_cell, lon_lat_to_row_col, raster_projection,
+    RequestedLocation, StreamSnap, WatershedCells,
+};
+use crate::tools::*;
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::collections::{BTreeMap, HashSet};
+use std::f64;
+use std::io::Error;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use whitebox_common::algorithms::{D8_DX, D8_DY};
+use whitebox_common::error::WbtError;
+use whitebox_common::spatial_ref_system::{Projection, ProjectionMethod};
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::haversine_distance;
+use whitebox_raster::*;
+
+#[derive(Copy, Clone)]
+pub(super) enum TraceStartMode {
+    Requested,
+    WatershedCandidate,
+}
+
+impl TraceStartMode {
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            TraceStartMode::Requested => "requested",
+            TraceStartMode::WatershedCandidate => "watershed",
+        }
+    }
+}
+
+pub(super) struct TraceContext<'a> {
+    pub(super) pntr: &'a Raster,
+    pub(super) streams: &'a Raster,
+    pub(super) mask: Option<&'a Array2D<u8>>,
+    pub(super) junction_counts: &'a Array2D<i16>,
+    /// The pointer values that drain into a cell from each neighbour, with which cells outside
+    /// the window scanned by `count_junctions` are counted.
+    pub(super) inflowing_vals: &'a [f64; 8],
+    pub(super) pntr_nodata: f64,
+    pub(super) streams_nodata: f64,
+    /// The streams values that are channel cells (`--stream_threshold` and
+    /// `--min_stream_order`).
+    pub(super) stream_filter: StreamFilter,
+    pub(super) pntr_matches: &'a [i8; 129],
+    pub(super) rows: isize,
+    pub(super) columns: isize,
+    pub(super) max_steps: usize,
+    /// With `--outlet_within_mask`, only cells inside the watershed mask are accepted as the
+    /// outlet.
+    pub(super) outlet_within_mask: bool,
+    /// True if the pointer is in geographic coordinates, whose distances are measured along the
+    /// great circle.
+    pub(super) geographic: bool,
+    /// The length of the pointer's map unit in meters; 1 when its coordinate system is unknown.
+    pub(super) linear_unit: f64,
+}
+
+impl<'a> TraceContext<'a> {
+    /// The junction count of (`row`, `col`), counted now if it lies outside the window scanned
+    /// by `count_junctions`.
+    fn junction_count(&self, row: isize, col: isize) -> i16 {
+        match self.junction_counts.get_value(row, col) {
+            JUNCTIONS_UNSCANNED => cell_junction_count(
+                self.pntr,
+                self.streams,
+                self.stream_filter,
+                self.inflowing_vals,
+                row,
+                col,
+            ),
+            count => count,
+        }
+    }
+
+    /// True if (`row`, `col`) is a channel cell of the streams raster.
+    fn is_stream(&self, row: isize, col: isize) -> bool {
+        is_stream_value(
+            self.streams[(row, col)],
+            self.streams_nodata,
+            self.stream_filter,
+        )
+    }
+
+    /// The distance in meters between the centres of two cells, or in map units when the
+    /// coordinate system of the pointer is unknown.
+    pub(super) fn cell_distance(&self, from: (isize, isize), to: (isize, isize)) -> f64 {
+        if self.geographic {
+            haversine_distance(
+                (
+                    self.pntr.get_y_from_row(from.0),
+                    self.pntr.get_x_from_column(from.1),
+                ),
+                (
+                    self.pntr.get_y_from_row(to.0),
+                    self.pntr.get_x_from_column(to.1),
+                ),
+            )
+        } else {
+            (((to.1 - from.1) as f64 * self.pntr.configs.resolution_x).powi(2)
+                + ((to.0 - from.0) as f64 * self.pntr.configs.resolution_y).powi(2))
+            .sqrt()
+                * self.linear_unit
+        }
+    }
+
+    /// The cell that (`row`, `col`) drains to, read as by the trace; `None` for a nodata, zero
+    /// or out-of-range pointer, or one that leads off the raster.
+    pub(super) fn downstream(&self, row: isize, col: isize) -> Option<(isize, isize)> {
+        let pointer = self.pntr[(row, col)];
+        if pointer == self.pntr_nodata || pointer <= 0f64 {
+            return None;
+        }
+        let dir = *self.pntr_matches.get(pointer.round() as usize)?;
+        if dir < 0 {
+            return None;
+        }
+        let (nr, nc) = (row + D8_DY[dir as usize], col + D8_DX[dir as usize]);
+        if nr < 0 || nr >= self.rows || nc < 0 || nc >= self.columns {
+            None
+        } else {
+            Some((nr, nc))
+        }
+    }
+
+    /// The area of a cell, as the product of the distances, measured as by `cell_distance`,
+    /// from its centre to those of the next cells east and south. A geographic cell's area thus
+    /// depends on its latitude.
+    pub(super) fn cell_area(&self, row: isize, col: isize) -> f64 {
+        self.cell_distance((row, col), (row, col + 1))
+            * self.cell_distance((row, col), (row + 1, col))
+    }
+
+    /// The length of a flow path, and the part of it outside the watershed mask, measured
+    /// between cell centres as by `cell_distance`. A step counts as outside the mask when the
+    /// cell it enters is.
+    pub(super) fn path_lengths(&self, path: &[(isize, isize)]) -> (f64, f64) {
+        let (mut length, mut beyond_mask) = (0f64, 0f64);
+        for step in path.windows(2) {
+            let step_length = self.cell_distance(step[0], step[1]);
+            length += step_length;
+            if self
+                .mask
+                .map(|mask| mask.get_value(step[1].0, step[1].1) == 0u8)
+                .unwrap_or(false)
+            {
+                beyond_mask += step_length;
+            }
+        }
+        (length, beyond_mask)
+    }
+}
+
+struct TraceParams<'a> {
+    label: &'a str,
+    mode: TraceStartMode,
+}
+
+pub(super) struct TraceSuccessData {
+    pub(super) outlet_row: isize,
+    pub(super) outlet_col: isize,
+    pub(super) steps_taken: usize,
+    pub(super) steps_beyond_mask: usize,
+    /// Length of the path from the start cell to the outlet, as measured by
+    /// `TraceContext::cell_distance`.
+    pub(super) path_length: f64,
+    /// Length of the steps of the path into cells outside the watershed mask.
+    pub(super) length_beyond_mask: f64,
+    pub(super) outlet_downstream: bool,
+    pub(super) outlet_junction_count: i16,
+    /// True if, with `--outlet_within_mask`, the trace left the mask without an acceptable
+    /// outlet and fell back on the last stream cell inside it with a junction count of 1.
+    pub(super) mask_fallback: bool,
+    /// Cells visited from the start cell to the outlet, inclusive, in flow order.
+    pub(super) path: Vec<(isize, isize)>,
+}
+
+/// The continuation of the flow path downstream of the selected outlet.
+pub(super) struct DownstreamPreview {
+    /// Cells visited after the outlet, in flow order.
+    pub(super) cells: Vec<(isize, isize)>,
+    /// Length of the continuation in meters (map units when the coordinate system is unknown).
+    pub(super) length: f64,
+    /// True if every visited cell is a stream cell (false when no step could be taken).
+    pub(super) on_stream: bool,
+    /// Why the continuation stopped short of the requested number of steps.
+    pub(super) reason: Option<&'static str>,
+}
+
+/// The category of a failed trace.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(super) enum TraceFailureKind {
+    /// The start cell lies outside the raster.
+    OutOfBounds,
+    /// No valid D8 cell was found near a requested location.
+    NoValidStart,
+    /// A requested lon/lat could not be converted to a grid cell.
+    ConversionFailure,
+    /// The flow path revisits a cell.
+    Loop,
+    /// The flow path reaches a missing, out-of-range or unsupported D8 pointer.
+    InvalidPointer,
+    /// The trace exceeds the maximum step count.
+    MaxSteps,
+    /// The flow path leaves the raster on a stream cell whose junction count is not 1.
+    JunctionMismatch,
+    /// The flow path leaves the raster without reaching a stream.
+    LeftRaster,
+    /// With `--outlet_within_mask`, the flow path leaves the watershed mask without passing a
+    /// stream cell inside it whose junction count is 1.
+    LeftMask,
+}
+
+impl TraceFailureKind {
+    /// The ways a trace from a watershed candidate can fail, in the order they are reported.
+    const CANDIDATE_KINDS: [TraceFailureKind; 6] = [
+        TraceFailureKind::Loop,
+        TraceFailureKind::InvalidPointer,
+        TraceFailureKind::MaxSteps,
+        TraceFailureKind::JunctionMismatch,
+        TraceFailureKind::LeftRaster,
+        TraceFailureKind::LeftMask,
+    ];
+
+    pub(super) fn as_str(&self) -> &'static str {
+        match self {
+            TraceFailureKind::OutOfBounds => "out_of_bounds",
+            TraceFailureKind::NoValidStart => "no_valid_start",
+            TraceFailureKind::ConversionFailure => "conversion_failure",
+            TraceFailureKind::Loop => "loop",
+            TraceFailureKind::InvalidPointer => "invalid_pointer",
+            TraceFailureKind::MaxSteps => "max_steps",
+            TraceFailureKind::JunctionMismatch => "junction_mismatch",
+            TraceFailureKind::LeftRaster => "left_raster",
+            TraceFailureKind::LeftMask => "left_mask",
+        }
+    }
+}
+
+pub(super) struct TraceFailureData {
+    pub(super) kind: TraceFailureKind,
+    pub(super) reason: String,
+    last_junction: Option<(isize, isize, i16)>,
+}
+
+/// The latest stream cell a failed trace passed whose junction count disqualified it.
+#[derive(Serialize, Clone, Copy, Debug, PartialEq)]
+struct LastJunction {
+    row: isize,
+    col: isize,
+    junction_count: i16,
+}
+
+/// A start cell from which a trace was attempted, as written to the `--diagnostics` file.
+#[derive(Serialize, Debug, PartialEq)]
+pub(super) struct TraceAttempt {
+    /// The index of the requested location, for requested starts.
+    pub(super) request_id: Option<usize>,
+    pub(super) mode: &'static str,
+    /// The rank of a watershed candidate, by decreasing distance to the mask boundary.
+    pub(super) candidate_rank: Option<usize>,
+    /// The start cell, or `None` when no valid D8 cell could be found for a request.
+    pub(super) start_row: Option<isize>,
+    pub(super) start_col: Option<isize>,
+    /// Cells between the requested (or snapped) cell and the valid D8 cell traced from.
+    offset_cells: usize,
+    pub(super) distance_to_boundary: Option<i32>,
+    pub(super) result: &'static str,
+    pub(super) failure_kind: Option<TraceFailureKind>,
+    pub(super) failure_reason: Option<String>,
+    last_junction: Option<LastJunction>,
+    pub(super) outlet_row: Option<isize>,
+    pub(super) outlet_col: Option<isize>,
+    pub(super) steps_taken: Option<usize>,
+}
+
+impl TraceAttempt {
+    pub(super) fn new(mode: TraceStartMode, start: Option<(isize, isize)>) -> TraceAttempt {
+        TraceAttempt {
+            request_id: None,
+            mode: mode.as_str(),
+            candidate_rank: None,
+            start_row: start.map(|(row, _)| row),
+            start_col: start.map(|(_, col)| col),
+            offset_cells: 0,
+            distance_to_boundary: None,
+            result: "failure",
+            failure_kind: None,
+            failure_reason: None,
+            last_junction: None,
+            outlet_row: None,
+            outlet_col: None,
+            steps_taken: None,
+        }
+    }
+
+    pub(super) fn failed(mut self, failure: TraceFailureData) -> TraceAttempt {
+        self.failure_kind = Some(failure.kind);
+        self.failure_reason = Some(failure.reason);
+        self.last_junction = failure
+            .last_junction
+            .map(|(row, col, junction_count)| LastJunction {
+                row,
+                col,
+                junction_count,
+            });
+        self
+    }
+
+    pub(super) fn succeeded(trace: &SelectedTrace) -> TraceAttempt {
+        let mut attempt =
+            TraceAttempt::new(trace.start_mode, Some((trace.start_row, trace.start_col)));
+        attempt.candidate_rank = trace.candidate_rank;
+        attempt.offset_cells = trace.start_offset_cells;
+        attempt.distance_to_boundary = Some(trace.distance_to_boundary).filter(|&d| d >= 0);
+        attempt.result = "success";
+        attempt.outlet_row = Some(trace.success.outlet_row);
+        attempt.outlet_col = Some(trace.success.outlet_col);
+        attempt.steps_taken = Some(trace.success.steps_taken);
+        attempt
+    }
+
+    /// The failure reason as reported in the error message, with the latest junction mismatch.
+    fn failure_message(&self) -> String {
+        let mut message = self.failure_reason.clone().unwrap_or_default();
+        if let Some(junction) = self.last_junction {
+            message.push_str(&format!(
+                " Latest stream encountered at row {}, col {} had junction count {}.",
+                junction.row, junction.col, junction.junction_count
+            ));
+        }
+        message
+    }
+}
+
+/// The attempt that produced an outlet, and why it was chosen.
+#[derive(Serialize)]
+struct TraceSelection {
+    request_id: Option<usize>,
+    /// The index of the attempt in `attempts`.
+    attempt: usize,
+    reason: String,
+}
+
+/// The contents of the `--diagnostics` file.
+#[derive(Serialize)]
+struct TraceDiagnostics {
+    status: &'static str,
+    attempts: Vec<TraceAttempt>,
+    selected: Vec<TraceSelection>,
+    /// The number of stream cells on the mask perimeter with each junction count.
+    perimeter_junction_counts: BTreeMap<i16, usize>,
+}
+
+/// A summary of the watershed candidates traced before an outlet was selected.
+pub(super) struct CandidateStats {
+    pub(super) attempted: usize,
+    pub(super) succeeded: usize,
+    /// The number of failed traces of each of `TraceFailureKind::CANDIDATE_KINDS`.
+    pub(super) failures: Vec<(TraceFailureKind, usize)>,
+    /// The rank and distance to the mask boundary of the selected candidate.
+    pub(super) winner: Option<(usize, i32)>,
+}
+
+impl CandidateStats {
+    pub(super) fn from_attempts(attempts: &[TraceAttempt]) -> CandidateStats {
+        let candidates: Vec<&TraceAttempt> = attempts
+            .iter()
+            .filter(|attempt| attempt.mode == TraceStartMode::WatershedCandidate.as_str())
+            .collect();
+        let failures = TraceFailureKind::CANDIDATE_KINDS
+            .iter()
+            .map(|&kind| {
+                let count = candidates
+                    .iter()
+                    .filter(|attempt| attempt.failure_kind == Some(kind))
+                    .count();
+                (kind, count)
+            })
+            .collect();
+        let winner = candidates
+            .iter()
+            .find(|attempt| attempt.result == "success")
+            .map(|attempt| {
+                (
+                    attempt.candidate_rank.unwrap_or(0),
+                    attempt.distance_to_boundary.unwrap_or(-1),
+                )
+            });
+        CandidateStats {
+            attempted: candidates.len(),
+            succeeded: candidates
+                .iter()
+                .filter(|attempt| attempt.result == "success")
+                .count(),
+            failures,
+            winner,
+        }
+    }
+
+    pub(super) fn to_json(&self) -> JsonValue {
+        let failures: JsonMap<String, JsonValue> = self
+            .failures
+            .iter()
+            .map(|(kind, count)| (kind.as_str().to_string(), json!(count)))
+            .collect();
+        json!({
+            "attempted": self.attempted,
+            "succeeded": self.succeeded,
+            "failed": self.attempted - self.succeeded,
+            "failures": failures,
+            "winner_rank": self.winner.map(|(rank, _)| rank),
+            "winner_distance_to_boundary": self.winner.map(|(_, distance)| distance),
+        })
+    }
+
+    fn print(&self) {
+        println!("Candidate evaluation:");
+        println!("  {:<24}{}", "candidates attempted", self.attempted);
+        println!("  {:<24}{}", "successes", self.succeeded);
+        println!("  {:<24}{}", "failures", self.attempted - self.succeeded);
+        for (kind, count) in &self.failures {
+            println!("    {:<22}{}", kind.as_str().replace('_', " "), count);
+        }
+        match self.winner {
+            Some((rank, distance)) => {
+                println!("  {:<24}{}", "winner rank", rank);
+                println!("  {:<24}{}", "winner distance", distance);
+            }
+            None => println!("  {:<24}none", "winner rank"),
+        }
+    }
+}
+
+pub(super) struct SelectedTrace {
+    pub(super) success: TraceSuccessData,
+    pub(super) start_row: isize,
+    pub(super) start_col: isize,
+    pub(super) start_mode: TraceStartMode,
+    pub(super) distance_to_boundary: i32,
+    pub(super) candidate_rank: Option<usize>,
+    pub(super) start_offset_cells: usize,
+}
+
+fn trace_flow_path(
+    mut row: isize,
+    mut col: isize,
+    ctx: &TraceContext,
+    params: &TraceParams,
+) -> Result<TraceSuccessData, TraceFailureData> {
+    if row < 0 || row >= ctx.rows || col < 0 || col >= ctx.columns {
+        return Err(TraceFailureData {
+            kind: TraceFailureKind::OutOfBounds,
+            reason: format!(
+                "{}: start cell ({}, {}) lies outside raster bounds.",
+                params.label, row, col
+            ),
+            last_junction: None,
+        });
+    }
+
+    let mut visited: HashSet<(isize, isize)> = HashSet::new();
+    let mut path: Vec<(isize, isize)> = Vec::new();
+    let mut steps: usize = 0;
+    let mut has_left_mask = false;
+    let mut steps_beyond_mask: usize = 0;
+    let mut path_length = 0f64;
+    let mut length_beyond_mask = 0f64;
+    let mut last_junction_mismatch: Option<(isize, isize, i16)> = None;
+    // With --outlet_within_mask, the trace ends where it leaves the mask, and the latest stream
+    // cell inside the mask with a junction count of 1 is kept to fall back on.
+    let within_mask = ctx.outlet_within_mask && ctx.mask.is_some();
+    let mut last_in_mask: Option<MaskFallback> = None;
+
+    loop {
+        if !visited.insert((row, col)) {
+            return Err(TraceFailureData {
+                kind: TraceFailureKind::Loop,
+                reason: format!(
+                    "{}: flow path loops near row {}, col {}.",
+                    params.label, row, col
+                ),
+                last_junction: last_junction_mismatch,
+            });
+        }
+        path.push((row, col));
+
+        if let Some(mask) = ctx.mask {
+            if mask.get_value(row, col) == 0u8 {
+                has_left_mask = true;
+            }
+        }
+
+        let (is_stream, junction_count) = if ctx.is_stream(row, col) {
+            let junction = ctx.junction_count(row, col);
+            (true, junction)
+        } else {
+            (false, -1i16)
+        };
+
+        if within_mask {
+            if has_left_mask {
+                return mask_fallback_outcome(last_in_mask, path, params, last_junction_mismatch);
+            }
+            if is_stream && junction_count == 1 {
+                last_in_mask = Some(MaskFallback {
+                    path_cells: path.len(),
+                    steps,
+                    path_length,
+                });
+            }
+        }
+
+        let outlet_downstream_now = ctx
+            .mask
+            .map(|mask| mask.get_value(row, col) == 0u8)
+            .unwrap_or(false);
+
+        let accept_current = match params.mode {
+            TraceStartMode::Requested => is_stream && junction_count == 1,
+            TraceStartMode::WatershedCandidate => has_left_mask && is_stream && junction_count == 1,
+        };
+
+        if accept_current {
+            return Ok(TraceSuccessData {
+                outlet_row: row,
+                outlet_col: col,
+                steps_taken: steps,
+                steps_beyond_mask,
+                path_length,
+                length_beyond_mask,
+                outlet_downstream: outlet_downstream_now,
+                outlet_junction_count: junction_count,
+                mask_fallback: false,
+                path,
+            });
+        } else if is_stream && junction_count != 1 {
+            last_junction_mismatch = Some((row, col, junction_count));
+        }
+
+        let pointer = ctx.pntr[(row, col)];
+        if pointer == ctx.pntr_nodata || pointer <= 0f64 {
+            let reason = if ctx.mask.is_some() && has_left_mask {
+                format!(
+                    "{}: downstream pointer becomes invalid ({}) near row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            } else {
+                format!(
+                    "{}: encountered invalid D8 pointer ({}) at row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            };
+            return Err(TraceFailureData {
+                kind: TraceFailureKind::InvalidPointer,
+                reason,
+                last_junction: last_junction_mismatch,
+            });
+        }
+
+        let pointer_index = pointer.round() as usize;
+        if pointer_index >= ctx.pntr_matches.len() {
+            let reason = if ctx.mask.is_some() && has_left_mask {
+                format!(
+                    "{}: downstream pointer value {} out of range near row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            } else {
+                format!(
+                    "{}: pointer value {} out of range at row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            };
+            return Err(TraceFailureData {
+                kind: TraceFailureKind::InvalidPointer,
+                reason,
+                last_junction: last_junction_mismatch,
+            });
+        }
+
+        let dir = ctx.pntr_matches[pointer_index];
+        if dir < 0 {
+            let reason = if ctx.mask.is_some() && has_left_mask {
+                format!(
+                    "{}: downstream pointer value {} unsupported near row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            } else {
+                format!(
+                    "{}: unsupported pointer value {} at row {}, col {}.",
+                    params.label, pointer, row, col
+                )
+            };
+            return Err(TraceFailureData {
+                kind: TraceFailureKind::InvalidPointer,
+                reason,
+                last_junction: last_junction_mismatch,
+            });
+        }
+
+        let nr = row + D8_DY[dir as usize];
+        let nc = col + D8_DX[dir as usize];
+
+        steps += 1;
+
+        if nr < 0 || nr >= ctx.rows || nc < 0 || nc >= ctx.columns {
+            if is_stream && junction_count == 1 {
+                return Ok(TraceSuccessData {
+                    outlet_row: row,
+                    outlet_col: col,
+                    steps_taken: steps,
+                    steps_beyond_mask,
+                    path_length,
+                    length_beyond_mask,
+                    outlet_downstream: outlet_downstream_now || has_left_mask,
+                    outlet_junction_count: junction_count,
+                    mask_fallback: false,
+                    path,
+                });
+            }
+            if within_mask {
+                return mask_fallback_outcome(last_in_mask, path, params, last_junction_mismatch);
+            }
+            let reason = if is_stream {
+                format!(
+                    "{}: reached raster edge at row {}, col {} with junction count {} (expected 1).",
+                    params.label, row, col, junction_count
+                )
+            } else {
+                format!(
+                    "{}: exited raster at row {}, col {} without hitting a stream.",
+                    params.label, row, col
+                )
+            };
+            return Err(TraceFailureData {
+                kind: if is_stream {
+                    TraceFailureKind::JunctionMismatch
+                } else {
+                    TraceFailureKind::LeftRaster
+                },
+                reason,
+                last_junction: last_junction_mismatch,
+            });
+        }
+
+        if let Some(mask) = ctx.mask {
+            if mask.get_value(row, col) == 1u8 && mask.get_value(nr, nc) == 0u8 {
+                if is_stream && junction_count == 1 {
+                    return Ok(TraceSuccessData {
+                        outlet_row: row,
+                        outlet_col: col,
+                        steps_taken: steps,
+                        steps_beyond_mask,
+                        path_length,
+                        length_beyond_mask,
+                        outlet_downstream: false,
+                        outlet_junction_count: junction_count,
+                        mask_fallback: false,
+                        path,
+                    });
+                }
+                if is_stream && junction_count != 1 {
+                    last_junction_mismatch = Some((row, col, junction_count));
+                }
+                if within_mask {
+                    return mask_fallback_outcome(
+                        last_in_mask,
+                        path,
+                        params,
+                        last_junction_mismatch,
+                    );
+                }
+                has_left_mask = true;
+            }
+        }
+
+        let step_length = ctx.cell_distance((row, col), (nr, nc));
+        path_length += step_length;
+        row = nr;
+        col = nc;
+
+        if ctx
+            .mask
+            .map(|mask| mask.get_value(row, col) == 0u8)
+            .unwrap_or(false)
+        {
+            has_left_mask = true;
+            steps_beyond_mask += 1;
+            length_beyond_mask += step_length;
+        }
+
+        if ctx.is_stream(row, col) {
+            let junction = ctx.junction_count(row, col);
+            if junction == 1 && (matches!(params.mode, TraceStartMode::Requested) || has_left_mask)
+            {
+                let downstream = ctx
+                    .mask
+                    .map(|mask| mask.get_value(row, col) == 0u8)
+                    .unwrap_or(has_left_mask);
+                path.push((row, col));
+                return Ok(TraceSuccessData {
+                    outlet_row: row,
+                    outlet_col: col,
+                    steps_taken: steps,
+                    steps_beyond_mask,
+                    path_length,
+                    length_beyond_mask,
+                    outlet_downstream: downstream,
+                    outlet_junction_count: junction,
+                    mask_fallback: false,
+                    path,
+                });
+            } else if junction != 1 {
+                last_junction_mismatch = Some((row, col, junction));
+            }
+        }
+
+        if steps >= ctx.max_steps {
+            let reason = if ctx.mask.is_some() {
+                if has_left_mask {
+                    format!(
+                        "{}: exceeded maximum step count ({}) while searching downstream of the watershed.",
+                        params.label, ctx.max_steps
+                    )
+                } else {
+                    format!(
+                        "{}: exceeded maximum step count ({}) before exiting watershed.",
+                        params.label, ctx.max_steps
+                    )
+                }
+            } else {
+                format!(
+                    "{}: exceeded maximum step count ({}) while traversing flow path.",
+                    params.label, ctx.max_steps
+                )
+            };
+            return Err(TraceFailureData {
+                kind: TraceFailureKind::MaxSteps,
+                reason,
+                last_junction: last_junction_mismatch,
+            });
+        }
+    }
+}
+
+/// The latest stream cell inside the watershed mask with a junction count of 1 on a trace with
+/// `--outlet_within_mask`: the number of path cells up to and including it, and the steps taken
+/// and path length at it.
+#[derive(Clone, Copy)]
+struct MaskFallback {
+    path_cells: usize,
+    steps: usize,
+    path_length: f64,
+}
+
+/// Ends a trace with `--outlet_within_mask` where its flow path leaves the watershed mask (or the
+/// raster) without an acceptable outlet: the outlet falls back on the latest stream cell inside
+/// the mask with a junction count of 1, and the trace fails if it passed none. `path` ends at the
+/// last cell visited.
+fn mask_fallback_outcome(
+    fallback: Option<MaskFallback>,
+    mut path: Vec<(isize, isize)>,
+    params: &TraceParams,
+    last_junction: Option<(isize, isize, i16)>,
+) -> Result<TraceSuccessData, TraceFailureData> {
+    let (row, col) = *path.last().expect("A trace visits its start cell.");
+    let fallback = fallback.ok_or_else(|| TraceFailureData {
+        kind: TraceFailureKind::LeftMask,
+        reason: format!(
+            "{}: flow path leaves the watershed near row {}, col {} without passing a stream cell inside it with junction count 1.",
+            params.label, row, col
+        ),
+        last_junction,
+    })?;
+    path.truncate(fallback.path_cells);
+    let (outlet_row, outlet_col) = path[fallback.path_cells - 1];
+    Ok(TraceSuccessData {
+        outlet_row,
+        outlet_col,
+        steps_taken: fallback.steps,
+        steps_beyond_mask: 0,
+        path_length: fallback.path_length,
+        length_beyond_mask: 0f64,
+        outlet_downstream: false,
+        outlet_junction_count: 1,
+        mask_fallback: true,
+        path,
+    })
+}
+
+/// Moves the outlet of a successful trace back up its flow path to the cell immediately upstream
+/// of the nearest junction (a stream cell with a junction count of 2 or more) that the path
+/// passed through within `window` cells of the outlet. The outlet is kept when there is no such
+/// junction, or when the cell upstream of it is not a stream cell inside the watershed mask.
+/// Returns true if the outlet moved.
+pub(super) fn snap_outlet_to_junction(
+    success: &mut TraceSuccessData,
+    ctx: &TraceContext,
+    window: isize,
+) -> bool {
+    let outlet = (success.outlet_row, success.outlet_col);
+    if success.path.last() != Some(&outlet) {
+        return false;
+    }
+    let is_stream = |(row, col): (isize, isize)| ctx.is_stream(row, col);
+    for idx in (1..success.path.len() - 1).rev() {
+        let (row, col) = success.path[idx];
+        if (row - outlet.0).abs() > window || (col - outlet.1).abs() > window {
+            continue;
+        }
+        if !is_stream((row, col)) || ctx.junction_count(row, col) < 2 {
+            continue;
+        }
+        let upstream = success.path[idx - 1];
+        let in_mask = ctx
+            .mask
+            .map(|mask| mask.get_value(upstream.0, upstream.1) == 1u8)
+            .unwrap_or(true);
+        if !is_stream(upstream) || !in_mask {
+            return false;
+        }
+        success.path.truncate(idx);
+        success.outlet_row = upstream.0;
+        success.outlet_col = upstream.1;
+        success.steps_taken = idx - 1;
+        success.steps_beyond_mask = match ctx.mask {
+            Some(mask) => success.path[1..]
+                .iter()
+                .filter(|&&(r, c)| mask.get_value(r, c) == 0u8)
+                .count(),
+            None => 0,
+        };
+        (success.path_length, success.length_beyond_mask) = ctx.path_lengths(&success.path);
+        success.outlet_downstream = false;
+        success.outlet_junction_count = ctx.junction_count(upstream.0, upstream.1);
+        return true;
+    }
+    false
+}
+
+/// Follows the D8 pointer downstream of the outlet cell for up to `num_steps` steps, ignoring
+/// the watershed mask. The continuation stops early, with a reason, at a cell without a valid
+/// flow direction, at the raster edge, on reaching a nodata cell, or on revisiting a cell.
+pub(super) fn trace_downstream_preview(
+    outlet_row: isize,
+    outlet_col: isize,
+    num_steps: usize,
+    ctx: &TraceContext,
+) -> DownstreamPreview {
+    let mut cells: Vec<(isize, isize)> = Vec::new();
+    let mut visited: HashSet<(isize, isize)> = HashSet::new();
+    visited.insert((outlet_row, outlet_col));
+    let mut length = 0f64;
+    let mut on_stream = true;
+    let mut reason: Option<&'static str> = None;
+    let (mut row, mut col) = (outlet_row, outlet_col);
+    while cells.len() < num_steps {
+        let pointer = ctx.pntr[(row, col)];
+        if pointer == ctx.pntr_nodata {
+            reason = Some("nodata");
+            break;
+        }
+        let pointer_index = pointer.round() as usize;
+        if pointer <= 0f64 {
+            reason = Some("no_flow_direction");
+            break;
+        }
+        if pointer_index >= ctx.pntr_matches.len() || ctx.pntr_matches[pointer_index] < 0 {
+            reason = Some("invalid_pointer");
+            break;
+        }
+        let dir = ctx.pntr_matches[pointer_index] as usize;
+        let nr = row + D8_DY[dir];
+        let nc = col + D8_DX[dir];
+        if nr < 0 || nr >= ctx.rows || nc < 0 || nc >= ctx.columns {
+            reason = Some("left_raster");
+            break;
+        }
+        if ctx.pntr[(nr, nc)] == ctx.pntr_nodata {
+            reason = Some("nodata");
+            break;
+        }
+        if !visited.insert((nr, nc)) {
+            reason = Some("loop");
+            break;
+        }
+        if !ctx.is_stream(nr, nc) {
+            on_stream = false;
+        }
+        length += ctx.cell_distance((row, col), (nr, nc));
+        cells.push((nr, nc));
+        row = nr;
+        col = nc;
+    }
+    DownstreamPreview {
+        on_stream: on_stream && !cells.is_empty(),
+        cells,
+        length,
+        reason,
+    }
+}
+
+/// The DEM elevation at the outlet and the local channel slope upstream of it.
+pub(super) struct ChannelSlope {
+    /// None when the DEM is nodata at the outlet.
+    pub(super) outlet_elevation: Option<f64>,
+    /// The elevation drop from the last cell reached to the outlet, divided by `length`; None
+    /// when the outlet elevation is nodata or no step could be taken.
+    pub(super) slope: Option<f64>,
+    pub(super) steps: usize,
+    /// Length of the walk in meters (map units when the coordinate system is unknown).
+    pub(super) length: f64,
+}
+
+/// Walks up the stream network from the outlet, the last cell of the traced `path`, for up to
+/// `num_steps` steps and measures the channel slope between the outlet and the last cell
+/// reached. Where several stream cells flow into a cell the walk follows the traced path; it
+/// stops early at a channel head, at a junction off the path, and before a cell whose elevation
+/// is nodata.
+pub(super) fn channel_slope_upstream(
+    path: &[(isize, isize)],
+    num_steps: usize,
+    dem: &Raster,
+    ctx: &TraceContext,
+) -> ChannelSlope {
+    let dem_nodata = dem.configs.nodata;
+    let elevation = |(row, col): (isize, isize)| {
+        let z = dem.get_value(row, col);
+        if z == dem_nodata || z.is_nan() {
+            None
+        } else {
+            Some(z)
+        }
+    };
+    let outlet = path[path.len() - 1];
+    let mut path_idx = Some(path.len() - 1);
+    let mut visited: HashSet<(isize, isize)> = HashSet::new();
+    visited.insert(outlet);
+    let (mut current, mut steps, mut length) = (outlet, 0usize, 0f64);
+    while steps < num_steps {
+        // The stream cells whose pointer leads into the current cell.
+        let mut inflows: Vec<(isize, isize)> = Vec::with_capacity(8);
+        for n in 0..8 {
+            let (nr, nc) = (current.0 + D8_DY[n], current.1 + D8_DX[n]);
+            if nr < 0 || nr >= ctx.rows || nc < 0 || nc >= ctx.columns {
+                continue;
+            }
+            if !ctx.is_stream(nr, nc) {
+                continue;
+            }
+            let pointer = ctx.pntr[(nr, nc)];
+            if pointer == ctx.pntr_nodata || pointer <= 0f64 {
+                continue;
+            }
+            let pointer_index = pointer.round() as usize;
+            if pointer_index < ctx.pntr_matches.len()
+                && pointer_index.is_power_of_two()
+                && ctx.pntr_matches[pointer_index] == ((n + 4) % 8) as i8
+            {
+                inflows.push((nr, nc));
+            }
+        }
+        let on_path = path_idx
+            .filter(|&i| i > 0)
+            .map(|i| path[i - 1])
+            .filter(|cell| inflows.contains(cell));
+        let next = match on_path {
+            Some(cell) => cell,
+            None if inflows.len() == 1 => inflows[0],
+            None => break,
+        };
+        if !visited.insert(next) || elevation(next).is_none() {
+            break;
+        }
+        length += ctx.cell_distance(current, next);
+        path_idx = if on_path.is_some() {
+            path_idx.map(|i| i - 1)
+        } else {
+            None
+        };
+        current = next;
+        steps += 1;
+    }
+    let outlet_elevation = elevation(outlet);
+    let slope = match (outlet_elevation, elevation(current)) {
+        (Some(z_outlet), Some(z_upstream)) if steps > 0 => Some((z_upstream - z_outlet) / length),
+        _ => None,
+    };
+    ChannelSlope {
+        outlet_elevation,
+        slope,
+        steps,
+        length,
+    }
+}
+
+/// Returns the number of processors to use given the max_procs setting; as in the plugin
+/// tools, a positive value caps the available processors and anything else uses them all.
+pub(super) fn resolve_num_procs(max_procs: isize, available: usize) -> usize {
+    let available = available.max(1);
+    if max_procs > 0 && (max_procs as usize) < available {
+        max_procs as usize
+    } else {
+        available
+    }
+}
+
+/// The values of a streams raster that mark channel cells (see `is_stream_value`). The default
+/// accepts every positive value.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub(super) struct StreamFilter {
+    /// Channel values are greater than this (`--stream_threshold`).
+    pub(super) threshold: f64,
+    /// Unless 0, channel values are at least this, e.g. a Strahler order (`--min_stream_order`).
+    pub(super) min_order: f64,
+}
+
+/// True if a streams raster value marks a channel cell: greater than the filter's threshold, at
+/// least its minimum order (if any), and not nodata. Nodata is excluded whatever its sign, e.g.
+/// the 255 of an 8-bit grid whose background is 0, and NaN never compares greater than the
+/// threshold.
+pub(super) fn is_stream_value(value: f64, nodata: f64, filter: StreamFilter) -> bool {
+    value != nodata
+        && value > filter.threshold
+        && (filter.min_order == 0f64 || value >= filter.min_order)
+}
+
+/// The D8 lookup tables of a pointer scheme: the pointer value with which each neighbour,
+/// clockwise from the north-east, drains into the centre cell, and the index into `D8_DX`/`D8_DY` of
+/// the direction of each pointer value.
+pub(super) fn pointer_tables(esri_style: bool) -> ([f64; 8], [i8; 129]) {
+    let mut inflowing_vals = [16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64, 8f64];
+    if esri_style {
+        inflowing_vals = [8f64, 16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64];
+    }
+    let mut pntr_matches: [i8; 129] = [0i8; 129];
+    if !esri_style {
+        pntr_matches[1] = 0i8;
+        pntr_matches[2] = 1i8;
+        pntr_matches[4] = 2i8;
+        pntr_matches[8] = 3i8;
+        pntr_matches[16] = 4i8;
+        pntr_matches[32] = 5i8;
+        pntr_matches[64] = 6i8;
+        pntr_matches[128] = 7i8;
+    } else {
+        pntr_matches[1] = 1i8;
+        pntr_matches[2] = 2i8;
+        pntr_matches[4] = 3i8;
+        pntr_matches[8] = 4i8;
+        pntr_matches[16] = 5i8;
+        pntr_matches[32] = 6i8;
+        pntr_matches[64] = 7i8;
+        pntr_matches[128] = 0i8;
+    }
+    (inflowing_vals, pntr_matches)
+}
+
+/// The junction count of the cells outside the window scanned by `count_junctions`.
+pub(super) const JUNCTIONS_UNSCANNED: i16 = -2;
+
+/// The number of inflowing stream cells of (`row`, `col`), i.e. its junction count, or -1 if it
+/// is off the stream network.
+fn cell_junction_count(
+    pntr: &Raster,
+    streams: &Raster,
+    stream_filter: StreamFilter,
+    inflowing_vals: &[f64; 8],
+    row: isize,
+    col: isize,
+) -> i16 {
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let streams_nodata = streams.configs.nodata;
+    if !is_stream_value(streams[(row, col)], streams_nodata, stream_filter) {
+        return -1;
+    }
+    let mut cnt = 0i16;
+    for n in 0..8 {
+        let nr = row + D8_DY[n];
+        let nc = col + D8_DX[n];
+        if nr >= 0
+            && nr < rows
+            && nc >= 0
+            && nc < columns
+            && is_stream_value(streams[(nr, nc)], streams_nodata, stream_filter)
+        {
+            let neighbour_pointer = pntr[(nr, nc)];
+            if neighbour_pointer != pntr.configs.nodata && neighbour_pointer == inflowing_vals[n] {
+                cnt += 1;
+            }
+        }
+    }
+    cnt
+}
+
+/// The window of rows and columns scanned for junctions when tracing within a watershed mask:
+/// the bounding box of the mask grown by a one-cell halo and clipped to the grid. It is empty
+/// for an empty mask.
+pub(super) fn mask_scan_window(mask: &Array2D<u8>) -> (Range<isize>, Range<isize>) {
+    let (mut min_row, mut max_row) = (isize::MAX, -1isize);
+    let (mut min_col, mut max_col) = (isize::MAX, -1isize);
+    for row in 0..mask.rows() {
+        for col in 0..mask.columns() {
+            if mask.get_value(row, col) == 1u8 {
+                min_row = min_row.min(row);
+                max_row = max_row.max(row);
+                min_col = min_col.min(col);
+                max_col = max_col.max(col);
+            }
+        }
+    }
+    if max_row < 0 {
+        return (0..0, 0..0);
+    }
+    (
+        (min_row - 1).max(0)..(max_row + 2).min(mask.rows()),
+        (min_col - 1).max(0)..(max_col + 2).min(mask.columns()),
+    )
+}
+
+/// The window of rows and columns whose junctions are counted before tracing. Within a
+/// watershed, only its bounding box (and a one-cell halo) is scanned, within the `--bbox`
+/// window; a trace that leaves the box counts the cells it reaches. The `--junctions` raster
+/// covers the whole grid (or the `--bbox` window), so writing it (`full_scan`) still needs a
+/// full scan of it.
+pub(super) fn junction_scan_window(
+    inputs: &Inputs,
+    full_scan: bool,
+) -> (Range<isize>, Range<isize>) {
+    let search_window = &inputs.search_window;
+    match &inputs.watershed {
+        Some(ws) if !full_scan => {
+            let (mask_rows, mask_cols) = mask_scan_window(ws);
+            (
+                mask_rows.start.max(search_window.0.start)..mask_rows.end.min(search_window.0.end),
+                mask_cols.start.max(search_window.1.start)..mask_cols.end.min(search_window.1.end),
+            )
+        }
+        _ => search_window.clone(),
+    }
+}
+
+/// Counts the junctions of the stream cells within a window of rows and columns (see
+/// `cell_junction_count`). The rows of the window are split into contiguous bands scanned on up
+/// to `num_threads` threads. Cells off the stream network are -1, and cells outside the window
+/// are `JUNCTIONS_UNSCANNED`.
+pub(super) fn count_junctions(
+    pntr: &Raster,
+    streams: &Raster,
+    stream_filter: StreamFilter,
+    inflowing_vals: &[f64; 8],
+    (scan_rows, scan_cols): (Range<isize>, Range<isize>),
+    num_threads: usize,
+    verbose: bool,
+) -> Result<Array2D<i16>, Error> {
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let num_scan_rows = (scan_rows.end - scan_rows.start).max(0) as usize;
+    let full_extent = num_scan_rows == rows as usize && scan_cols == (0..columns);
+    let mut junction_counts: Array2D<i16> = Array2D::new(
+        rows,
+        columns,
+        if full_extent {
+            -1i16
+        } else {
+            JUNCTIONS_UNSCANNED
+        },
+        -1i16,
+    )?;
+    if num_scan_rows == 0 || scan_cols.is_empty() {
+        return Ok(junction_counts);
+    }
+    let num_threads = num_threads.clamp(1, num_scan_rows);
+    let band = num_scan_rows.div_ceil(num_threads) as isize;
+    let (tx, rx) = mpsc::channel();
+    std::thread::scope(|scope| {
+        let mut start = scan_rows.start;
+        while start < scan_rows.end {
+            let end = (start + band).min(scan_rows.end);
+            let tx = tx.clone();
+            let scan_cols = scan_cols.clone();
+            scope.spawn(move || {
+                for row in start..end {
+                    let counts: Vec<i16> = scan_cols
+                        .clone()
+                        .map(|col| {
+                            cell_junction_count(
+                                pntr,
+                                streams,
+                                stream_filter,
+                                inflowing_vals,
+                                row,
+                                col,
+                            )
+                        })
+                        .collect();
+                    tx.send((row, counts)).unwrap();
+                }
+            });
+            start = end;
+        }
+        drop(tx);
+
+        // Progress is reported over the rows received from every band.
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for (received, (row, counts)) in rx.iter().enumerate() {
+            for (col, cnt) in scan_cols.clone().zip(counts) {
+                junction_counts.set_value(row, col, cnt);
+            }
+            if verbose && num_scan_rows > 1 {
+                progress = (100.0_f64 * received as f64 / (num_scan_rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Junction scan: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+    });
+    Ok(junction_counts)
+}
+
+/// Resolves requested locations to outlets as `FindOutlet` does without a watershed mask: the
+/// nearest cell with a valid D8 pointer is traced downstream to the first stream cell with a
+/// junction count of 1. Used by tools that trace many requests against one pointer and stream
+/// network, e.g. `OutletSensitivityAnalysis`.
+pub(crate) struct OutletTracer<'a> {
+    pntr: &'a Raster,
+    streams: &'a Raster,
+    stream_filter: StreamFilter,
+    junction_counts: Array2D<i16>,
+    inflowing_vals: [f64; 8],
+    pntr_matches: [i8; 129],
+    max_steps: usize,
+    projection: Option<Projection>,
+}
+
+impl<'a> OutletTracer<'a> {
+    /// Counts the junctions of the stream network, whose channels are the `streams` cells
+    /// greater than `stream_threshold` (see `--stream_threshold`), on up to `max_procs` threads
+    /// (see `settings.json`).
+    pub(crate) fn new(
+        pntr: &'a Raster,
+        streams: &'a Raster,
+        stream_threshold: f64,
+        esri_style: bool,
+        verbose: bool,
+    ) -> Result<OutletTracer<'a>, Error> {
+        let (inflowing_vals, pntr_matches) = pointer_tables(esri_style);
+        let max_procs = whitebox_common::configs::get_configs()?.max_procs;
+        let stream_filter = StreamFilter {
+            threshold: stream_threshold,
+            min_order: 0f64,
+        };
+        let junction_counts = count_junctions(
+            pntr,
+            streams,
+            stream_filter,
+            &inflowing_vals,
+            (
+                0..pntr.configs.rows as isize,
+                0..pntr.configs.columns as isize,
+            ),
+            resolve_num_procs(max_procs, num_cpus::get()),
+            verbose,
+        )?;
+        Ok(OutletTracer {
+            pntr,
+            streams,
+            stream_filter,
+            junction_counts,
+            inflowing_vals,
+            pntr_matches,
+            max_steps: pntr.configs.rows * pntr.configs.columns * 4,
+            projection: raster_projection(pntr),
+        })
+    }
+
+    /// The grid cell of a WGS84 lon/lat, if it can be projected into the pointer's coordinate
+    /// system.
+    pub(crate) fn lon_lat_cell(&self, lon: f64, lat: f64) -> Option<(isize, isize)> {
+        lon_lat_to_row_col(self.pntr, self.projection.as_ref(), lon, lat)
+    }
+
+    /// Traces the requested cell to its outlet cell, or returns the reason the trace failed.
+    pub(crate) fn trace(&self, row: isize, col: isize) -> Result<(isize, isize), String> {
+        let rows = self.pntr.configs.rows as isize;
+        let columns = self.pntr.configs.columns as isize;
+        let ((start_row, start_col), _) = find_nearest_valid_cell(
+            row,
+            col,
+            rows,
+            columns,
+            self.pntr,
+            self.pntr.configs.nodata,
+            &self.pntr_matches,
+            None,
+        )
+        .ok_or_else(|| {
+            format!(
+                "Requested start: unable to locate a valid D8 cell near row {}, col {}.",
+                row, col
+            )
+        })?;
+        let ctx = TraceContext {
+            pntr: self.pntr,
+            streams: self.streams,
+            mask: None,
+            junction_counts: &self.junction_counts,
+            inflowing_vals: &self.inflowing_vals,
+            pntr_nodata: self.pntr.configs.nodata,
+            streams_nodata: self.streams.configs.nodata,
+            stream_filter: self.stream_filter,
+            pntr_matches: &self.pntr_matches,
+            rows,
+            columns,
+            max_steps: self.max_steps,
+            outlet_within_mask: false,
+            geographic: self
+                .projection
+                .as_ref()
+                .is_some_and(|p| p.method == ProjectionMethod::Geographic),
+            linear_unit: self.projection.as_ref().map_or(1f64, |p| p.linear_unit),
+        };
+        let params = TraceParams {
+            label: "Requested start",
+            mode: TraceStartMode::Requested,
+        };
+        trace_flow_path(start_row, start_col, &ctx, &params)
+            .map(|success| (success.outlet_row, success.outlet_col))
+            .map_err(|failure| failure.reason)
+    }
+}
+
+/// Returns the number of threads used to trace watershed candidates. A single
+/// processor, or a single candidate, is evaluated serially.
+pub(super) fn candidate_threads(num_procs: usize, num_candidates: usize) -> usize {
+    num_procs.min(num_candidates).max(1)
+}
+
+/// Traces watershed candidates in rank order and returns the result of each candidate traced,
+/// sorted by rank. With more than one thread, each thread repeatedly takes the next untraced
+/// candidate. With `stop_at_success`, a thread stops once a candidate ranked ahead of its next
+/// one has succeeded, so every candidate ranked ahead of the best success is traced and only the
+/// number traced beyond it varies with the number of threads.
+fn trace_candidate_results(
+    candidates: &[(i32, isize, isize)],
+    ctx: &TraceContext,
+    num_threads: usize,
+    stop_at_success: bool,
+) -> Vec<(usize, Result<TraceSuccessData, TraceFailureData>)> {
+    let next_candidate = AtomicUsize::new(0);
+    let best_success = AtomicUsize::new(usize::MAX);
+    let trace_worker = || {
+        let mut results: Vec<(usize, Result<TraceSuccessData, TraceFailureData>)> = Vec::new();
+        loop {
+            let idx = next_candidate.fetch_add(1, Ordering::SeqCst);
+            if idx >= candidates.len() || idx > best_success.load(Ordering::SeqCst) {
+                break;
+            }
+            let (_, row, col) = candidates[idx];
+            let label = format!("Candidate {}", idx);
+            let params = TraceParams {
+                label: &label,
+                mode: TraceStartMode::WatershedCandidate,
+            };
+            let result = trace_flow_path(row, col, ctx, &params);
+            if result.is_ok() && stop_at_success {
+                best_success.fetch_min(idx, Ordering::SeqCst);
+            }
+            results.push((idx, result));
+        }
+        results
+    };
+
+    let mut results = if num_threads <= 1 {
+        trace_worker()
+    } else {
+        std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..num_threads)
+                .map(|_| scope.spawn(trace_worker))
+                .collect();
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().expect("Candidate tracing thread panicked."))
+                .collect::<Vec<_>>()
+        })
+    };
+    results.sort_by_key(|(idx, _)| *idx);
+    results
+}
+
+/// The selected trace of the watershed candidate ranked `idx`, or its failed attempt.
+fn candidate_outcome(
+    candidates: &[(i32, isize, isize)],
+    idx: usize,
+    result: Result<TraceSuccessData, TraceFailureData>,
+) -> Result<SelectedTrace, Box<TraceAttempt>> {
+    let (distance_to_boundary, row, col) = candidates[idx];
+    match result {
+        Ok(success) => Ok(SelectedTrace {
+            success,
+            start_row: row,
+            start_col: col,
+            start_mode: TraceStartMode::WatershedCandidate,
+            distance_to_boundary,
+            candidate_rank: Some(idx),
+            start_offset_cells: 0,
+        }),
+        Err(failure) => {
+            let mut attempt =
+                TraceAttempt::new(TraceStartMode::WatershedCandidate, Some((row, col)));
+            attempt.candidate_rank = Some(idx);
+            attempt.distance_to_boundary = Some(distance_to_boundary);
+            Err(Box::new(attempt.failed(failure)))
+        }
+    }
+}
+
+/// Traces watershed candidates in rank order and returns the first successful trace, the
+/// failed attempts of the candidates ranked ahead of it, and the number of candidates traced.
+/// The selection is identical to the serial evaluation whatever the number of threads.
+pub(super) fn trace_candidates(
+    candidates: &[(i32, isize, isize)],
+    ctx: &TraceContext,
+    num_threads: usize,
+) -> (Option<SelectedTrace>, Vec<TraceAttempt>, usize) {
+    let results = trace_candidate_results(candidates, ctx, num_threads, true);
+    let num_traced = results.len();
+    let mut failures: Vec<TraceAttempt> = Vec::new();
+    for (idx, result) in results {
+        match candidate_outcome(candidates, idx, result) {
+            Ok(selected) => return (Some(selected), failures, num_traced),
+            Err(attempt) => failures.push(*attempt),
+        }
+    }
+    (None, failures, num_traced)
+}
+
+/// Traces watershed candidates in rank order, in batches of at least `num_procs`, until `count`
+/// of them succeed or the candidates run out, and returns the outlet cells of the first `count`
+/// successful traces in rank order.
+fn trace_next_outlets(
+    candidates: &[(i32, isize, isize)],
+    ctx: &TraceContext,
+    num_procs: usize,
+    count: usize,
+) -> Vec<(isize, isize)> {
+    let batch = count.max(num_procs);
+    let mut outlets: Vec<(isize, isize)> = Vec::with_capacity(count);
+    let mut start = 0;
+    while outlets.len() < count && start < candidates.len() {
+        let end = (start + batch).min(candidates.len());
+        let results = trace_candidate_results(
+            &candidates[start..end],
+            ctx,
+            candidate_threads(num_procs, end - start),
+            false,
+        );
+        for (_, result) in results {
+            if let Ok(success) = result {
+                if outlets.len() < count {
+                    outlets.push((success.outlet_row, success.outlet_col));
+                }
+            }
+        }
+        start = end;
+    }
+    outlets
+}
+
+/// How many of the successful candidate traces scored by `--score_candidates`, including the
+/// selected one, reach the selected outlet cell, and the other outlet cells they reach.
+pub(super) struct CandidateSupport {
+    pub(super) traces: usize,
+    pub(super) selected_traces: usize,
+    /// The other outlet cells and their number of traces, by decreasing number of traces and
+    /// then in the order first reached.
+    pub(super) alternatives: Vec<((isize, isize), usize)>,
+}
+
+impl CandidateSupport {
+    pub(super) fn new(
+        selected: (isize, isize),
+        alternatives: &[(isize, isize)],
+    ) -> CandidateSupport {
+        let mut counts: Vec<((isize, isize), usize)> = vec![];
+        for &cell in alternatives.iter().filter(|&&cell| cell != selected) {
+            match counts.iter_mut().find(|(c, _)| *c == cell) {
+                Some((_, n)) => *n += 1,
+                None => counts.push((cell, 1)),
+            }
+        }
+        // The sort is stable, so ties keep the order in which the outlets were first reached.
+        counts.sort_by_key(|&(_, n)| std::cmp::Reverse(n));
+        CandidateSupport {
+            traces: alternatives.len() + 1,
+            selected_traces: alternatives.len() + 1 - counts.iter().map(|(_, n)| n).sum::<usize>(),
+            alternatives: counts,
+        }
+    }
+}
+
+/// Traces every watershed candidate, for `--all_outlets`, and returns the highest-ranked
+/// successful trace of each distinct outlet cell in rank order, with the attempt of every
+/// candidate, successful or not, in rank order.
+fn trace_all_candidates(
+    candidates: &[(i32, isize, isize)],
+    ctx: &TraceContext,
+    num_threads: usize,
+) -> (Vec<SelectedTrace>, Vec<TraceAttempt>) {
+    let mut outlets: HashSet<(isize, isize)> = HashSet::new();
+    let mut traces: Vec<SelectedTrace> = Vec::new();
+    let mut attempts: Vec<TraceAttempt> = Vec::new();
+    for (idx, result) in trace_candidate_results(candidates, ctx, num_threads, false) {
+        match candidate_outcome(candidates, idx, result) {
+            Ok(selected) => {
+                attempts.push(TraceAttempt::succeeded(&selected));
+                let outlet = (selected.success.outlet_row, selected.success.outlet_col);
+                if outlets.insert(outlet) {
+                    traces.push(selected);
+                }
+            }
+            Err(attempt) => attempts.push(*attempt),
+        }
+    }
+    (traces, attempts)
+}
+
+/// The number of watershed mask cells draining to each of `outlets` along the D8 pointer. A
+/// cell is counted for the first of the outlets its flow path reaches, so the cells upstream of
+/// an outlet that drains to another are only counted for the upstream one; cells whose paths
+/// reach none of them, e.g. by leaving the raster or looping, are not counted.
+pub(super) fn upstream_mask_cells(outlets: &[(isize, isize)], ctx: &TraceContext) -> Vec<usize> {
+    let mut counts = vec![0usize; outlets.len()];
+    let mask = match ctx.mask {
+        Some(mask) => mask,
+        None => return counts,
+    };
+    // The outlet each cell drains to, or NO_OUTLET; cells not yet visited are UNKNOWN.
+    const UNKNOWN: i32 = -2;
+    const NO_OUTLET: i32 = -1;
+    let index = |row: isize, col: isize| (row * ctx.columns + col) as usize;
+    let mut labels = vec![UNKNOWN; (ctx.rows * ctx.columns) as usize];
+    for (i, &(row, col)) in outlets.iter().enumerate() {
+        labels[index(row, col)] = i as i32;
+    }
+    let mut path: Vec<(isize, isize)> = Vec::new();
+    for row in 0..ctx.rows {
+        for col in 0..ctx.columns {
+            if mask.get_value(row, col) != 1u8 {
+                continue;
+            }
+            let (mut r, mut c) = (row, col);
+            let label = loop {
+                let label = labels[index(r, c)];
+                if label != UNKNOWN {
+                    break label;
+                }
+                // Cells on the current path read as draining nowhere, which ends a loop.
+                labels[index(r, c)] = NO_OUTLET;
+                path.push((r, c));
+                match ctx.downstream(r, c) {
+                    Some(next) => (r, c) = next,
+                    None => break NO_OUTLET,
+                }
+            };
+            for (r, c) in path.drain(..) {
+                labels[index(r, c)] = label;
+            }
+            if label >= 0 {
+                counts[label as usize] += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// The traces selected in a run, one per output feature: the outlet of each requested location
+/// (or the reason it failed), the outlet selected from the watershed candidates, or, with
+/// `--all_outlets`, every distinct outlet.
+pub(super) struct Selection {
+    pub(super) outcomes: Vec<Result<SelectedTrace, String>>,
+    /// The outlet cell of each trace before `--snap_to_junction`, and whether it was moved.
+    pub(super) junction_snaps: Vec<Option<((isize, isize), bool)>>,
+    /// The number of mask cells draining to each outlet, with `--all_outlets`.
+    pub(super) upstream_cells: Vec<Option<usize>>,
+    pub(super) candidate_stats: Option<CandidateStats>,
+    pub(super) candidate_support: Option<CandidateSupport>,
+}
+
+/// The attempts traced in a run and the outcomes they produced, before the diagnostics are
+/// written.
+struct Traced {
+    attempts: Vec<TraceAttempt>,
+    selections: Vec<TraceSelection>,
+    outcomes: Vec<Result<SelectedTrace, String>>,
+    candidate_stats: Option<CandidateStats>,
+    candidate_support: Option<CandidateSupport>,
+}
+
+/// The attempts of a run that traced no outlet, and the message of its `trace_failure`.
+type Untraced = (Vec<TraceAttempt>, String);
+
+/// Traces the requested locations, or the watershed candidates, and selects the outlets of the
+/// run. The diagnostics are written whether or not an outlet is found. When none is, the
+/// attempts and the limits they ran under are added to `failure_details` for the
+/// `--error_json` document, and the error is a `trace_failure`.
+pub(super) fn select_traces(
+    args: &FindOutletArgs,
+    cells: &WatershedCells,
+    requests: &[RequestedLocation],
+    ctx: &TraceContext,
+    num_procs: usize,
+    verbose: bool,
+    failure_details: &mut JsonMap<String, JsonValue>,
+) -> Result<Selection, Error> {
+    let stream_snap = if args.snap_to_stream {
+        Some(StreamSnap {
+            streams: ctx.streams,
+            filter: ctx.stream_filter,
+            max_radius: args.snap_max_radius,
+        })
+    } else {
+        None
+    };
+    // Each requested location is traced on its own from the shared junction counts and mask.
+    // With at most one location, a failed trace aborts the run (after falling back on the
+    // watershed candidates); with several, a failure is recorded in that request's feature and
+    // the other requests are still written.
+    let traced = if requests.len() > 1 {
+        trace_requests(requests, cells, ctx, stream_snap)
+    } else if args.all_outlets {
+        trace_every_outlet(cells, ctx, num_procs, verbose)
+    } else {
+        trace_single_outlet(
+            requests.first(),
+            cells,
+            ctx,
+            stream_snap,
+            args.score_candidates,
+            num_procs,
+            verbose,
+        )
+    };
+
+    let mut perimeter_junction_counts: BTreeMap<i16, usize> = BTreeMap::new();
+    for &(row, col) in &cells.perimeter_stream_cells {
+        *perimeter_junction_counts
+            .entry(ctx.junction_counts.get_value(row, col))
+            .or_insert(0) += 1;
+    }
+    match traced {
+        Ok(traced) => {
+            write_diagnostics(
+                &args.diagnostics_file,
+                TraceDiagnostics {
+                    status: "success",
+                    attempts: traced.attempts,
+                    selected: traced.selections,
+                    perimeter_junction_counts,
+                },
+                verbose,
+            )?;
+            let num_outcomes = traced.outcomes.len();
+            Ok(Selection {
+                outcomes: traced.outcomes,
+                junction_snaps: vec![None; num_outcomes],
+                upstream_cells: vec![None; num_outcomes],
+                candidate_stats: traced.candidate_stats,
+                candidate_support: traced.candidate_support,
+            })
+        }
+        // A failed run still writes its diagnostics; an error writing them is only a warning.
+        // Running out of candidates is reported as NotFound, unlike the InvalidInput of a run
+        // that could not start, so that callers may retry with relaxed options.
+        Err((attempts, message)) => {
+            failure_details.insert(
+                "attempts".to_string(),
+                serde_json::to_value(&attempts).unwrap_or_default(),
+            );
+            failure_details.insert(
+                "candidates_considered".to_string(),
+                json!(cells.max_candidates),
+            );
+            failure_details.insert("max_steps_used".to_string(), json!(ctx.max_steps));
+            let written = write_diagnostics(
+                &args.diagnostics_file,
+                TraceDiagnostics {
+                    status: "failure",
+                    attempts,
+                    selected: vec![],
+                    perimeter_junction_counts,
+                },
+                verbose,
+            );
+            if let Err(e) = written {
+                println!(
+                    "Warning: unable to write diagnostics file {}: {}",
+                    args.diagnostics_file, e
+                );
+            }
+            Err(Error::from(WbtError::trace_failure(message)))
+        }
+    }
+}
+
+/// Traces each of several requested locations, recording the failure of any that cannot be
+/// traced; the run fails only when none can.
+fn trace_requests(
+    requests: &[RequestedLocation],
+    cells: &WatershedCells,
+    ctx: &TraceContext,
+    stream_snap: Option<StreamSnap>,
+) -> Result<Traced, Untraced> {
+    let mut attempts: Vec<TraceAttempt> = vec![];
+    let mut selections: Vec<TraceSelection> = vec![];
+    let mut outcomes: Vec<Result<SelectedTrace, String>> = vec![];
+    for (id, request) in requests.iter().enumerate() {
+        let mut attempt = match (request.start_cell(), request.lng_lat) {
+            (Some(cell), _) => match trace_requested(cell, cells, ctx, stream_snap) {
+                Ok(trace) => {
+                    selections.push(TraceSelection {
+                        request_id: Some(id),
+                        attempt: attempts.len(),
+                        reason: String::from(
+                            "The trace from the requested location reached an outlet stream cell.",
+                        ),
+                    });
+                    let attempt = TraceAttempt::succeeded(&trace);
+                    outcomes.push(Ok(trace));
+                    attempt
+                }
+                Err(attempt) => {
+                    outcomes.push(Err(failure_message(true, &[attempt.failure_message()])));
+                    *attempt
+                }
+            },
+            (None, Some(lng_lat)) => {
+                let message = conversion_message(lng_lat, ctx.pntr.configs.epsg_code);
+                let mut attempt = TraceAttempt::new(TraceStartMode::Requested, None);
+                attempt.failure_kind = Some(TraceFailureKind::ConversionFailure);
+                attempt.failure_reason = Some(message.clone());
+                outcomes.push(Err(message));
+                attempt
+            }
+            (None, None) => unreachable!(),
+        };
+        attempt.request_id = Some(id);
+        attempts.push(attempt);
+    }
+    if outcomes.iter().all(|outcome| outcome.is_err()) {
+        let reasons: Vec<String> = outcomes
+            .iter()
+            .enumerate()
+            .filter_map(|(id, outcome)| outcome.as_ref().err().map(|e| format!("{}: {}", id, e)))
+            .collect();
+        let message = format!(
+            "None of the {} requested outlet locations could be traced. {}",
+            requests.len(),
+            reasons.join(" | ")
+        );
+        return Err((attempts, message));
+    }
+    Ok(Traced {
+        attempts,
+        selections,
+        outcomes,
+        candidate_stats: None,
+        candidate_support: None,
+    })
+}
+
+/// Traces every watershed candidate (`--all_outlets`), keeping the highest-ranked trace to each
+/// distinct outlet.
+fn trace_every_outlet(
+    cells: &WatershedCells,
+    ctx: &TraceContext,
+    num_procs: usize,
+    verbose: bool,
+) -> Result<Traced, Untraced> {
+    let max_candidates = cells.max_candidates;
+    let (traces, attempts) = trace_all_candidates(
+        &cells.candidates[..max_candidates],
+        ctx,
+        candidate_threads(num_procs, max_candidates),
+    );
+    let mut selections: Vec<TraceSelection> = vec![];
+    for trace in &traces {
+        selections.push(TraceSelection {
+            request_id: None,
+            attempt: attempts
+                .iter()
+                .position(|attempt| attempt.candidate_rank == trace.candidate_rank)
+                .unwrap_or(0),
+            reason: format!(
+                "Watershed candidate {} ({} cells from the mask boundary) is the highest-ranked candidate whose trace reached the outlet at row {}, col {}.",
+                trace.candidate_rank.unwrap_or(0),
+                trace.distance_to_boundary,
+                trace.success.outlet_row,
+                trace.success.outlet_col
+            ),
+        });
+    }
+    let stats = CandidateStats::from_attempts(&attempts);
+    if verbose {
+        stats.print();
+    }
+    if traces.is_empty() {
+        let attempt_summaries: Vec<String> = attempts
+            .iter()
+            .take(5)
+            .map(|attempt| attempt.failure_message())
+            .collect();
+        let message = failure_message(false, &attempt_summaries);
+        return Err((attempts, message));
+    }
+    Ok(Traced {
+        attempts,
+        selections,
+        outcomes: traces.into_iter().map(Ok).collect(),
+        candidate_stats: Some(stats),
+        candidate_support: None,
+    })
+}
+
+/// Traces the requested location, if any, and falls back on the watershed candidates when it
+/// cannot be traced, scoring the selected candidate with `--score_candidates`.
+fn trace_single_outlet(
+    request: Option<&RequestedLocation>,
+    cells: &WatershedCells,
+    ctx: &TraceContext,
+    stream_snap: Option<StreamSnap>,
+    score_candidates: Option<usize>,
+    num_procs: usize,
+    verbose: bool,
+) -> Result<Traced, Untraced> {
+    let max_candidates = cells.max_candidates;
+    let mut attempts: Vec<TraceAttempt> = vec![];
+    let mut selections: Vec<TraceSelection> = vec![];
+    let mut candidate_stats: Option<CandidateStats> = None;
+    let mut candidate_support: Option<CandidateSupport> = None;
+    let mut attempt_summaries: Vec<String> = Vec::new();
+    let mut selected: Option<SelectedTrace> = None;
+    if let Some(cell) = request.and_then(|request| request.start_cell()) {
+        match trace_requested(cell, cells, ctx, stream_snap) {
+            Ok(trace) => {
+                selections.push(TraceSelection {
+                    request_id: Some(0),
+                    attempt: attempts.len(),
+                    reason: String::from(
+                        "The trace from the requested location reached an outlet stream cell.",
+                    ),
+                });
+                attempts.push(TraceAttempt::succeeded(&trace));
+                selected = Some(trace);
+            }
+            Err(attempt) => {
+                attempt_summaries.push(attempt.failure_message());
+                attempts.push(*attempt);
+            }
+        }
+        attempts[0].request_id = Some(0);
+    }
+    if selected.is_none() && cells.mask_has_data {
+        let (candidate_selected, candidate_failures, _) = trace_candidates(
+            &cells.candidates[..max_candidates],
+            ctx,
+            candidate_threads(num_procs, max_candidates),
+        );
+        for attempt in candidate_failures {
+            if attempt_summaries.len() < 5 {
+                attempt_summaries.push(attempt.failure_message());
+            }
+            attempts.push(attempt);
+        }
+        if let Some(trace) = &candidate_selected {
+            selections.push(TraceSelection {
+                request_id: None,
+                attempt: attempts.len(),
+                reason: format!(
+                    "Watershed candidate {} ({} cells from the mask boundary) is the highest-ranked candidate whose trace reached an outlet stream cell{}.",
+                    trace.candidate_rank.unwrap_or(0),
+                    trace.distance_to_boundary,
+                    if request.is_none() {
+                        ""
+                    } else {
+                        "; the requested location could not be traced"
+                    }
+                ),
+            });
+            attempts.push(TraceAttempt::succeeded(trace));
+            if let (Some(count), Some(rank)) = (score_candidates, trace.candidate_rank) {
+                let alternatives = trace_next_outlets(
+                    &cells.candidates[(rank + 1).min(max_candidates)..max_candidates],
+                    ctx,
+                    num_procs,
+                    count,
+                );
+                let support = CandidateSupport::new(
+                    (trace.success.outlet_row, trace.success.outlet_col),
+                    &alternatives,
+                );
+                if verbose {
+                    println!(
+                        "Selected outlet support: {} of {} successful candidate traces ({} alternative outlet(s)).",
+                        support.selected_traces,
+                        support.traces,
+                        support.alternatives.len()
+                    );
+                }
+                candidate_support = Some(support);
+            }
+        }
+        selected = candidate_selected;
+        let stats = CandidateStats::from_attempts(&attempts);
+        if verbose {
+            stats.print();
+        }
+        candidate_stats = Some(stats);
+    }
+    match selected {
+        Some(trace) => Ok(Traced {
+            attempts,
+            selections,
+            outcomes: vec![Ok(trace)],
+            candidate_stats,
+            candidate_support,
+        }),
+        None => {
+            let message = failure_message(request.is_some(), &attempt_summaries);
+            Err((attempts, message))
+        }
+    }
+}
+
+/// Traces a requested location from the nearest cell with a valid D8 pointer (or, with
+/// `--snap_to_stream`, the nearest valid stream cell).
+fn trace_requested(
+    (req_row, req_col): (isize, isize),
+    cells: &WatershedCells,
+    ctx: &TraceContext,
+    stream_snap: Option<StreamSnap>,
+) -> Result<SelectedTrace, Box<TraceAttempt>> {
+    let ((start_row, start_col), offset) = find_nearest_valid_cell(
+        req_row,
+        req_col,
+        ctx.rows,
+        ctx.columns,
+        ctx.pntr,
+        ctx.pntr_nodata,
+        ctx.pntr_matches,
+        stream_snap,
+    )
+    .ok_or_else(|| {
+        let mut attempt = TraceAttempt::new(TraceStartMode::Requested, None);
+        attempt.failure_kind = Some(TraceFailureKind::NoValidStart);
+        attempt.failure_reason = Some(format!(
+            "Requested start: unable to locate a valid D8 cell near row {}, col {}.",
+            req_row, req_col
+        ));
+        Box::new(attempt)
+    })?;
+    let label = String::from("Requested start");
+    let params = TraceParams {
+        label: &label,
+        mode: TraceStartMode::Requested,
+    };
+    let distance_to_boundary = cells.distance_to_boundary(start_row, start_col);
+    match trace_flow_path(start_row, start_col, ctx, &params) {
+        Ok(success) => Ok(SelectedTrace {
+            success,
+            start_row,
+            start_col,
+            start_mode: TraceStartMode::Requested,
+            distance_to_boundary,
+            candidate_rank: None,
+            start_offset_cells: offset,
+        }),
+        Err(failure) => {
+            let mut attempt =
+                TraceAttempt::new(TraceStartMode::Requested, Some((start_row, start_col)));
+            attempt.offset_cells = offset;
+            attempt.distance_to_boundary = Some(distance_to_boundary).filter(|&d| d >= 0);
+            Err(Box::new(attempt.failed(failure)))
+        }
+    }
+}
+
+/// The message of a run that traced no outlet, quoting the reasons of the attempts.
+fn failure_message(requested: bool, attempt_summaries: &[String]) -> String {
+    let mut message = if requested {
+        String::from("Failed to trace a valid outlet from the requested location.")
+    } else {
+        String::from("Failed to identify an outlet stream cell for the provided watershed mask.")
+    };
+    if !attempt_summaries.is_empty() {
+        message.push_str(" Reasons considered: ");
+        message.push_str(&attempt_summaries.join(" | "));
+    }
+    message
+}
+
+/// Writes the trace diagnostics (`--diagnostics`), if requested.
+fn write_diagnostics(
+    diagnostics_file: &str,
+    diagnostics: TraceDiagnostics,
+    verbose: bool,
+) -> Result<(), Error> {
+    if diagnostics_file.is_empty() {
+        return Ok(());
+    }
+    if verbose {
+        println!("Writing trace diagnostics to {}.", diagnostics_file);
+    }
+    let text = serde_json::to_string_pretty(&diagnostics).map_err(Error::other)?;
+    std::fs::write(diagnostics_file, text)
+}
+
+impl Selection {
+    /// Moves each outlet back up its traced flow path to the cell just upstream of a junction
+    /// within `window` cells of it (`--snap_to_junction`).
+    pub(super) fn snap_to_junctions(&mut self, ctx: &TraceContext, window: usize, verbose: bool) {
+        for (id, outcome) in self.outcomes.iter_mut().enumerate() {
+            if let Ok(trace) = outcome {
+                let original = (trace.success.outlet_row, trace.success.outlet_col);
+                let snapped = snap_outlet_to_junction(&mut trace.success, ctx, window as isize);
+                if verbose && snapped {
+                    println!(
+                        "Outlet moved from row {}, col {} to row {}, col {}, upstream of a junction.",
+                        original.0, original.1, trace.success.outlet_row, trace.success.outlet_col
+                    );
+                }
+                self.junction_snaps[id] = Some((original, snapped));
+            }
+        }
+    }
+
+    /// Ranks the outlets by the number of mask cells draining to each, ties keeping the
+    /// candidate order (`--all_outlets`).
+    pub(super) fn rank_by_upstream_cells(&mut self, ctx: &TraceContext, verbose: bool) {
+        let outlet_cells: Vec<(isize, isize)> = self
+            .outcomes
+            .iter()
+            .filter_map(|outcome| outcome.as_ref().ok())
+            .map(|trace| (trace.success.outlet_row, trace.success.outlet_col))
+            .collect();
+        let counts = upstream_mask_cells(&outlet_cells, ctx);
+        let mut order: Vec<usize> = (0..self.outcomes.len()).collect();
+        order.sort_by(|&a, &b| counts[b].cmp(&counts[a]));
+        let mut ranked: Vec<Option<Result<SelectedTrace, String>>> =
+            self.outcomes.drain(..).map(Some).collect();
+        self.outcomes = order.iter().filter_map(|&i| ranked[i].take()).collect();
+        self.junction_snaps = order.iter().map(|&i| self.junction_snaps[i]).collect();
+        self.upstream_cells = order.iter().map(|&i| Some(counts[i])).collect();
+        if verbose {
+            println!(
+                "Identified {} distinct outlet(s); mask cells draining to each: {:?}.",
+                self.outcomes.len(),
+                order.iter().map(|&i| counts[i]).collect::<Vec<usize>>()
+            );
+            if self.outcomes.len() > 1 {
+                println!(
+                    "Warning: The watershed mask drains through {} distinct outlets; check the mask.",
+                    self.outcomes.len()
+                );
+            }
+        }
+    }
+}
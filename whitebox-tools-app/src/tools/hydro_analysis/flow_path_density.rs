@@ -0,0 +1,585 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use num_cpus;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool measures the local convergence of flow paths, e.g. to identify cells at risk of ephemeral
+/// gully erosion. The value of each cell in the output raster is the number of cells whose D8 flow path
+/// passes through it within a flow path length of `--max_distance` (in map units), counting the cell
+/// itself. Unlike flow accumulation, which counts every upslope cell no matter how distant, the count is
+/// bounded by the distance budget, so that it highlights where flow concentrates over short distances
+/// rather than simply increasing downstream. As `--max_distance` grows, the output approaches the
+/// flow accumulation in cells, as given by `D8FlowAccumulation` with `--out_type=cells`.
+///
+/// The density is found by walking downslope along the flow pointer (`--d8_pntr`) from every cell,
+/// adding one to each cell visited, until the length of the path walked would exceed `--max_distance`.
+/// Steps between cells have the length of the cell size in the direction of the step, or the cell
+/// diagonal for diagonal steps. A walk also stops at a cell without a downslope neighbour, or where the
+/// path leaves the grid or reaches NoData. The walks are independent, so they are divided into bands of
+/// rows that are walked in parallel.
+///
+/// When a `--mask` raster is given, only cells with positive mask values are walked from and counted,
+/// walks stop where they leave the mask, and cells outside of the mask are NoData in the output. By
+/// default, the pointer raster is assumed to use the clockwise indexing method used by WhiteboxTools.
+/// If the pointer file contains ESRI flow direction values instead, the `--esri_pntr` parameter must be
+/// specified.
+///
+/// # See Also
+/// `D8FlowAccumulation`, `D8Pointer`, `TraceDownslopeFlowpaths`
+pub struct FlowPathDensity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl FlowPathDensity {
+    pub fn new() -> FlowPathDensity {
+        // public constructor
+        let name = "FlowPathDensity".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Counts the cells whose D8 flow paths pass through each cell within a maximum flow path length."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Flow Path Length".to_owned(),
+            flags: vec!["--max_distance".to_owned()],
+            description: "Maximum length of the flow path walked from each cell, in map units."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description: "Optional input mask raster; cells with positive values are processed."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output flow path density raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --max_distance=100.0 -o='density.tif'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --max_distance=250.0 --mask='watershed.tif' -o='density.tif'", short_exe, name).replace("*", &sep);
+
+        FlowPathDensity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for FlowPathDensity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut max_distance = f64::NAN;
+        let mut mask_file = String::new();
+        let mut output_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-max_distance" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                max_distance = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --max_distance value '{}'.", value),
+                    )
+                })?;
+            } else if flag_val == "-mask" {
+                mask_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if max_distance.is_nan() || max_distance < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--max_distance must be specified as a non-negative length in map units.",
+            ));
+        }
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let mask = if mask_file.trim().is_empty() {
+            None
+        } else {
+            let mask = Raster::new(&resolve_path(working_directory, &mask_file), "r")?;
+            if mask.configs.rows != pntr.configs.rows
+                || mask.configs.columns != pntr.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The mask raster must have the same rows and columns as the D8 pointer.",
+                ));
+            }
+            Some(mask)
+        };
+
+        let start = Instant::now();
+
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let pntr_nodata = pntr.configs.nodata;
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [i8; 129] = [-1i8; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0i8;
+            pntr_matches[2] = 1i8;
+            pntr_matches[4] = 2i8;
+            pntr_matches[8] = 3i8;
+            pntr_matches[16] = 4i8;
+            pntr_matches[32] = 5i8;
+            pntr_matches[64] = 6i8;
+            pntr_matches[128] = 7i8;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1i8;
+            pntr_matches[2] = 2i8;
+            pntr_matches[4] = 3i8;
+            pntr_matches[8] = 4i8;
+            pntr_matches[16] = 5i8;
+            pntr_matches[32] = 6i8;
+            pntr_matches[64] = 7i8;
+            pntr_matches[128] = 0i8;
+        }
+
+        // -2 marks NoData and cells outside of the mask, -1 a cell without a downslope neighbour.
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -2, -2)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = pntr.get_value(row, col);
+                if z == pntr_nodata {
+                    continue;
+                }
+                if let Some(mask) = &mask {
+                    let m = mask.get_value(row, col);
+                    if m == mask.configs.nodata || m <= 0f64 {
+                        continue;
+                    }
+                }
+                if z > 0f64 && z <= 128f64 {
+                    flow_dir.set_value(row, col, pntr_matches[z as usize]);
+                } else {
+                    flow_dir.set_value(row, col, -1i8);
+                }
+            }
+        }
+        let flow_dir = Arc::new(flow_dir);
+
+        let cell_size_x = pntr.configs.resolution_x;
+        let cell_size_y = pntr.configs.resolution_y;
+        let diag_cell_size = (cell_size_x * cell_size_x + cell_size_y * cell_size_y).sqrt();
+        let step_lengths = [
+            diag_cell_size,
+            cell_size_x,
+            diag_cell_size,
+            cell_size_y,
+            diag_cell_size,
+            cell_size_x,
+            diag_cell_size,
+            cell_size_y,
+        ];
+
+        // A walk moves at most `reach` rows from where it starts, so each band of rows only needs
+        // to count the cells within `reach` rows of it.
+        let reach = if cell_size_y > 0f64 {
+            (max_distance / cell_size_y).floor().min(rows as f64) as isize
+        } else {
+            rows
+        };
+
+        let mut num_procs = num_cpus::get() as isize;
+        let configs = whitebox_common::configs::get_configs()?;
+        let max_procs = configs.max_procs;
+        if max_procs > 0 && max_procs < num_procs {
+            num_procs = max_procs;
+        }
+        let num_bands = num_procs.min(rows).max(1);
+        let band_rows = (rows + num_bands - 1) / num_bands;
+
+        let (tx, rx) = mpsc::channel();
+        for band in 0..num_bands {
+            let flow_dir = flow_dir.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+                let start_row = band * band_rows;
+                let end_row = (start_row + band_rows).min(rows);
+                let first_row = (start_row - reach).max(0);
+                let last_row = (end_row + reach).min(rows);
+                let mut counts: Array2D<u32> =
+                    Array2D::new(last_row - first_row, columns, 0, 0).unwrap();
+                let max_steps = rows * columns;
+                for row in start_row..end_row {
+                    for col in 0..columns {
+                        if flow_dir.get_value(row, col) == -2 {
+                            continue;
+                        }
+                        let (mut r, mut c) = (row, col);
+                        let mut distance = 0f64;
+                        counts.increment(r - first_row, c, 1);
+                        for _ in 0..max_steps {
+                            let dir = flow_dir.get_value(r, c);
+                            if dir < 0 {
+                                break;
+                            }
+                            distance += step_lengths[dir as usize];
+                            if distance > max_distance {
+                                break;
+                            }
+                            r += dy[dir as usize];
+                            c += dx[dir as usize];
+                            if flow_dir.get_value(r, c) == -2 {
+                                break; // off the grid, NoData, or outside of the mask
+                            }
+                            counts.increment(r - first_row, c, 1);
+                        }
+                    }
+                }
+                tx.send((first_row, counts)).unwrap();
+            });
+        }
+
+        let mut density: Array2D<f64> = Array2D::new(rows, columns, 0f64, 0f64)?;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for n in 0..num_bands {
+            let (first_row, counts) = rx.recv().expect("Error receiving data from thread.");
+            for row in 0..counts.rows {
+                for col in 0..columns {
+                    let count = counts.get_value(row, col);
+                    if count > 0 {
+                        density.increment(first_row + row, col, count as f64);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (n + 1) as f64 / num_bands as f64) as usize;
+                if progress != old_progress {
+                    println!("Walking flow paths: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &pntr);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "blueyellow.plt".to_string();
+        let out_nodata = -32768f64;
+        output.configs.nodata = out_nodata;
+        for row in 0..rows {
+            for col in 0..columns {
+                if flow_dir.get_value(row, col) == -2 {
+                    output.set_value(row, col, out_nodata);
+                } else {
+                    output.set_value(row, col, density.get_value(row, col));
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+        output.add_metadata_entry(format!("Maximum distance: {}", max_distance));
+        if !mask_file.trim().is_empty() {
+            output.add_metadata_entry(format!("Mask file: {}", mask_file));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::FlowPathDensity;
+    use crate::tools::hydro_analysis::D8FlowAccumulation;
+    use crate::tools::WhiteboxTool;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    fn write_raster(file: &str, rows: usize, columns: usize, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(rows, columns, f).with_origin(5000f64, 1000f64);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    fn density(dir: &str, extra: &[&str]) -> Raster {
+        let mut args = vec![
+            format!("--d8_pntr={}/pntr.tif", dir),
+            format!("--output={}/density.tif", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        FlowPathDensity::new().run(args, "", false).unwrap();
+        Raster::new(&format!("{}/density.tif", dir), "r").unwrap()
+    }
+
+    // A 4 x 5 grid in which every cell flows east, except the last column, which flows south
+    // to an outlet in the bottom-right cell.
+    fn write_pointer(dir: &str) {
+        write_raster(&format!("{}/pntr.tif", dir), 4, 5, &|r, c| {
+            if c < 4 {
+                2f64
+            } else if r < 3 {
+                8f64
+            } else {
+                0f64
+            }
+        });
+    }
+
+    #[test]
+    fn test_counts_are_bounded_by_the_distance() {
+        let dir = temp_dir("flow_path_density_bounded").unwrap();
+        write_pointer(&dir);
+        // Within one step, each cell counts itself and its upslope neighbours.
+        let output = density(&dir, &["--max_distance=10"]);
+        assert_eq!(output.get_value(0, 0), 1f64);
+        assert_eq!(output.get_value(0, 3), 2f64);
+        assert_eq!(output.get_value(0, 4), 2f64);
+        assert_eq!(output.get_value(1, 4), 3f64);
+        // Two steps reach (1, 4) from (0, 3), (0, 4), (1, 2), and (1, 3).
+        let output = density(&dir, &["--max_distance=20"]);
+        assert_eq!(output.get_value(1, 4), 5f64);
+        // The diagonal step from (0, 3) to (1, 4) is not used, so 14.2 m is the same as 10 m.
+        let output = density(&dir, &["--max_distance=14.2"]);
+        assert_eq!(output.get_value(1, 4), 3f64);
+    }
+
+    #[test]
+    fn test_matches_flow_accumulation_for_large_distances() {
+        let dir = temp_dir("flow_path_density_accumulation").unwrap();
+        // A 12 x 9 grid draining towards a valley in column 4 that flows south, with diagonal
+        // flow in the upper-left corner.
+        write_raster(&format!("{}/pntr.tif", dir), 12, 9, &|r, c| {
+            if c == 4 {
+                if r == 11 {
+                    0f64
+                } else {
+                    8f64
+                }
+            } else if c < 4 {
+                if r < 3 && c < 3 {
+                    4f64
+                } else {
+                    2f64
+                }
+            } else {
+                32f64
+            }
+        });
+        D8FlowAccumulation::new()
+            .run(
+                vec![
+                    format!("--input={}/pntr.tif", dir),
+                    format!("--output={}/accum.tif", dir),
+                    "--out_type=cells".to_string(),
+                    "--pntr".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let accum = Raster::new(&format!("{}/accum.tif", dir), "r").unwrap();
+        let output = density(&dir, &["--max_distance=1e9"]);
+        for r in 0..12 {
+            for c in 0..9 {
+                assert_eq!(
+                    output.get_value(r, c),
+                    accum.get_value(r, c),
+                    "({}, {})",
+                    r,
+                    c
+                );
+            }
+        }
+        assert_eq!(output.get_value(11, 4), 108f64);
+    }
+
+    #[test]
+    fn test_mask_limits_sources_and_walks() {
+        let dir = temp_dir("flow_path_density_mask").unwrap();
+        write_pointer(&dir);
+        // Mask out column 2, which breaks the rows' paths to the east.
+        write_raster(&format!("{}/mask.tif", dir), 4, 5, &|_, c| {
+            if c == 2 {
+                0f64
+            } else {
+                1f64
+            }
+        });
+        let output = density(&dir, &["--max_distance=100", "--mask={dir}/mask.tif"]);
+        assert_eq!(output.get_value(0, 2), -32768f64);
+        assert_eq!(output.get_value(0, 1), 2f64);
+        assert_eq!(output.get_value(0, 3), 1f64);
+        // The outlet collects column 3 and column 4.
+        assert_eq!(output.get_value(3, 4), 8f64);
+    }
+}
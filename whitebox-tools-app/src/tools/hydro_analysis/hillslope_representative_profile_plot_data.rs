@@ -0,0 +1,832 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::f64;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path::{self, Path};
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool exports plot-ready representative profiles of the hillslopes in a TOPAZ-style
+/// sub-catchment raster (`--subwta`), e.g. the output of `HillslopesTopaz`, for report generation.
+/// Each hillslope's cells are aggregated into a single profile running from the top of the
+/// hillslope to its channel, which is resampled to a fixed number of equally spaced stations
+/// (`--stations`, default 21, the number of points WEPP slope files conventionally use).
+///
+/// Hillslopes are the cells whose IDs end in 1, 2, or 3, and channels are the cells whose IDs end
+/// in 4. The flow distance of each hillslope cell to the channel is measured along the D8 flow
+/// pointer (`--d8_pntr`) from the cell centre to the centre of the first channel cell downslope,
+/// and its slope is the drop in elevation (`--dem`) to the next cell downslope divided by the
+/// distance between them. Cells that do not drain to a channel are ignored, as are the slopes of
+/// cells with NoData elevations.
+///
+/// The representative length of a hillslope, L, is its maximum flow distance, and its
+/// representative width is its area divided by L. A cell at distance d lies at the normalized
+/// distance x = 1 - d / L, from 0 at the top of the hillslope to 1 at the channel. The x range is
+/// divided into one segment between each pair of adjacent stations, and the slope of a segment is
+/// the mean slope of its cells (that of the nearest segment with cells when it has none). The
+/// slope at a station is the mean slope of the segments on either side of it, and the elevation
+/// profile descends from the mean elevation of the hillslope's top cells along the segment slopes.
+/// The cumulative area fraction at a station is the fraction of the hillslope's cells upslope of
+/// it, rising from 0 at the top to 1 at the channel. A hillslope of a single cell, or with no
+/// length, is written as a two-station profile whose length is its flow distance to the channel
+/// (or the cell size, when that is zero) rather than being dropped.
+///
+/// One CSV file per hillslope, named `hillslope_<topaz_id>.csv`, is written to the output
+/// directory (`--out_dir`), which is created if needed. Its header comment lines give the
+/// hillslope's TOPAZ ID, area, representative length and width, and number of stations, and its
+/// columns are:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `station` | The 0-based station number, from the top of the hillslope. |
+/// | `x` | The normalized distance of the station. |
+/// | `distance` | The distance of the station from the top of the hillslope, in map units. |
+/// | `elevation` | The elevation of the representative profile. |
+/// | `slope` | The slope at the station (rise over run, positive downslope). |
+/// | `area_fraction` | The cumulative fraction of the hillslope's area upslope of the station. |
+///
+/// The profiles of all hillslopes are also written to a combined long-format CSV file
+/// (`--output`) with a leading `topaz_id` column, ordered by TOPAZ ID and station.
+///
+/// Distances are in the units of the grid, which should therefore be projected. By default, the
+/// pointer raster is assumed to use the clockwise indexing method used by WhiteboxTools. If the
+/// pointer file contains ESRI flow direction values instead, the `--esri_pntr` parameter must be
+/// specified.
+///
+/// # See Also
+/// `HillslopesTopaz`, `HillslopeWidthFunction`, `D8Pointer`
+pub struct HillslopeRepresentativeProfilePlotData {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl HillslopeRepresentativeProfilePlotData {
+    pub fn new() -> HillslopeRepresentativeProfilePlotData {
+        // public constructor
+        let name = "HillslopeRepresentativeProfilePlotData".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Exports each TOPAZ hillslope's representative profile, resampled to fixed stations, as plot-ready CSV files."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Subwta File".to_owned(),
+            flags: vec!["--subwta".to_owned()],
+            description: "Input TOPAZ-style hillslope and channel ID raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Stations".to_owned(),
+            flags: vec!["--stations".to_owned()],
+            description: "Number of equally spaced stations in each profile (at least 2)."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("21".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Directory".to_owned(),
+            flags: vec!["--out_dir".to_owned()],
+            description: "Output directory for the per-hillslope CSV files.".to_owned(),
+            parameter_type: ParameterType::Directory,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output combined long-format CSV file of all profiles.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Csv),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --dem='dem.tif' --d8_pntr='d8pntr.tif' --out_dir='profiles' -o='profiles.csv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --dem='dem.tif' --d8_pntr='d8pntr.tif' --stations=11 --out_dir='profiles' -o='profiles.csv'", short_exe, name).replace("*", &sep);
+
+        HillslopeRepresentativeProfilePlotData {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for HillslopeRepresentativeProfilePlotData {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut subwta_file = String::new();
+        let mut dem_file = String::new();
+        let mut d8_file = String::new();
+        let mut num_stations = 21usize;
+        let mut out_dir = String::new();
+        let mut output_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-subwta" {
+                subwta_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-stations" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                num_stations = match value.trim().parse::<usize>() {
+                    Ok(n) if n >= 2 => n,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "--stations must be an integer of at least 2; got '{}'.",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            } else if flag_val == "-out_dir" {
+                out_dir = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        if out_dir.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output directory (--out_dir) not specified.",
+            ));
+        }
+        if output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output file (--output) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        subwta_file = resolve_path(working_directory, &subwta_file);
+        dem_file = resolve_path(working_directory, &dem_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        out_dir = resolve_path(working_directory, &out_dir);
+        output_file = resolve_path(working_directory, &output_file);
+
+        check_rasters_share_geometry(
+            &[
+                ("--subwta", &subwta_file),
+                ("--dem", &dem_file),
+                ("--d8_pntr", &d8_file),
+            ],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let subwta = Raster::new(&subwta_file, "r")?;
+        let dem = Raster::new(&dem_file, "r")?;
+        let pntr = Raster::new(&d8_file, "r")?;
+        let rows = subwta.configs.rows as isize;
+        let columns = subwta.configs.columns as isize;
+
+        let start = Instant::now();
+
+        // The TOPAZ ID of each cell, or 0 for NoData and non-positive values.
+        let subwta_nodata = subwta.configs.nodata;
+        let mut ids: Array2D<i64> = Array2D::new(rows, columns, 0, 0)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = subwta.get_value(row, col);
+                if z != subwta_nodata && z > 0f64 {
+                    ids.set_value(row, col, z.round() as i64);
+                }
+            }
+        }
+        let is_hillslope = |id: i64| id > 0 && matches!(id % 10, 1..=3);
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [i8; 129] = [-1i8; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0i8;
+            pntr_matches[2] = 1i8;
+            pntr_matches[4] = 2i8;
+            pntr_matches[8] = 3i8;
+            pntr_matches[16] = 4i8;
+            pntr_matches[32] = 5i8;
+            pntr_matches[64] = 6i8;
+            pntr_matches[128] = 7i8;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1i8;
+            pntr_matches[2] = 2i8;
+            pntr_matches[4] = 3i8;
+            pntr_matches[8] = 4i8;
+            pntr_matches[16] = 5i8;
+            pntr_matches[32] = 6i8;
+            pntr_matches[64] = 7i8;
+            pntr_matches[128] = 0i8;
+        }
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let res_x = subwta.configs.resolution_x;
+        let res_y = subwta.configs.resolution_y;
+        let diag = (res_x * res_x + res_y * res_y).sqrt();
+        let step_length = [diag, res_x, diag, res_y, diag, res_x, diag, res_y];
+
+        let pntr_nodata = pntr.configs.nodata;
+        let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
+        let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = pntr.get_value(row, col);
+                if z != pntr_nodata && z > 0f64 && z <= 128f64 {
+                    flow_dir.set_value(row, col, pntr_matches[z as usize]);
+                }
+                let id = ids.get_value(row, col);
+                if id > 0 && id % 10 == 4 {
+                    queue.push_back((row, col));
+                }
+            }
+        }
+
+        // Search upslope from the channel cells, accumulating the distance downslope.
+        let dist_nodata = -1f64;
+        let mut dist: Array2D<f64> = Array2D::new(rows, columns, dist_nodata, dist_nodata)?;
+        while let Some((row, col)) = queue.pop_front() {
+            let d = dist.get_value(row, col).max(0f64);
+            for n in 0..8 {
+                let (rn, cn) = (row + dy[n], col + dx[n]);
+                // does the neighbour flow into this cell?
+                if flow_dir.get_value(rn, cn) != ((n + 4) % 8) as i8 {
+                    continue;
+                }
+                if !is_hillslope(ids.get_value(rn, cn)) || dist.get_value(rn, cn) >= 0f64 {
+                    continue;
+                }
+                dist.set_value(rn, cn, d + step_length[(n + 4) % 8]);
+                queue.push_back((rn, cn));
+            }
+        }
+
+        // Gather the distance, elevation, and slope of each hillslope's cells.
+        let dem_nodata = dem.configs.nodata;
+        let mut hillslopes: BTreeMap<i64, Vec<ProfileCell>> = BTreeMap::new();
+        let mut num_ignored = 0usize;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                let id = ids.get_value(row, col);
+                if !is_hillslope(id) {
+                    continue;
+                }
+                let d = dist.get_value(row, col);
+                if d < 0f64 {
+                    num_ignored += 1;
+                    continue;
+                }
+                let z = dem.get_value(row, col);
+                let elevation = if z != dem_nodata { Some(z) } else { None };
+                // dist is only set for cells draining to a channel, so the direction is valid.
+                let dir = flow_dir.get_value(row, col) as usize;
+                let z_down = dem.get_value(row + dy[dir], col + dx[dir]);
+                let slope = match elevation {
+                    Some(z) if z_down != dem_nodata => Some((z - z_down) / step_length[dir]),
+                    _ => None,
+                };
+                hillslopes.entry(id).or_insert(vec![]).push(ProfileCell {
+                    distance: d,
+                    elevation,
+                    slope,
+                });
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Gathering hillslope cells: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if hillslopes.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No hillslope cells (IDs ending in 1, 2, or 3) draining to a channel were found.",
+            ));
+        }
+
+        let cell_area = res_x * res_y;
+        let cell_size = (res_x + res_y) / 2f64;
+        let profiles: Vec<(i64, RepresentativeProfile)> = hillslopes
+            .iter()
+            .map(|(&id, cells)| {
+                (
+                    id,
+                    representative_profile(cells, num_stations, cell_area, cell_size),
+                )
+            })
+            .collect();
+        let num_degenerate = profiles
+            .iter()
+            .filter(|(_, profile)| profile.x.len() == 2 && num_stations > 2)
+            .count();
+
+        if verbose {
+            println!("Writing profiles...")
+        };
+        fs::create_dir_all(&out_dir)?;
+        for (id, profile) in &profiles {
+            let file = Path::new(&out_dir).join(format!("hillslope_{}.csv", id));
+            write_hillslope_csv(*id, profile, &file.to_string_lossy())?;
+        }
+        write_combined_csv(&profiles, &output_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!(
+                "Wrote the profiles of {} hillslopes to {} and {}.",
+                profiles.len(),
+                out_dir,
+                output_file
+            );
+            if num_degenerate > 0 {
+                println!(
+                    "{} hillslopes of a single cell, or without length, were written as two-station profiles.",
+                    num_degenerate
+                );
+            }
+            if num_ignored > 0 {
+                println!(
+                    "Warning: {} hillslope cells that do not drain to a channel were ignored.",
+                    num_ignored
+                );
+            }
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A hillslope cell's flow distance to the channel, elevation, and slope to the next cell
+/// downslope; the latter two are `None` where the DEM has NoData.
+struct ProfileCell {
+    distance: f64,
+    elevation: Option<f64>,
+    slope: Option<f64>,
+}
+
+/// A hillslope's representative profile, sampled at equally spaced stations from its top.
+#[derive(Debug)]
+struct RepresentativeProfile {
+    area: f64,
+    length: f64,
+    width: f64,
+    x: Vec<f64>,
+    elevation: Vec<f64>,
+    slope: Vec<f64>,
+    area_fraction: Vec<f64>,
+}
+
+/// Aggregates the cells of a hillslope into a representative profile of `num_stations` stations,
+/// or of two stations for a hillslope of a single cell or without length.
+fn representative_profile(
+    cells: &[ProfileCell],
+    num_stations: usize,
+    cell_area: f64,
+    cell_size: f64,
+) -> RepresentativeProfile {
+    let area = cells.len() as f64 * cell_area;
+    let max_distance = cells.iter().map(|c| c.distance).fold(0f64, f64::max);
+    let degenerate = cells.len() < 2 || max_distance <= 0f64;
+    let (num_stations, length) = if degenerate {
+        (
+            2,
+            if max_distance > 0f64 {
+                max_distance
+            } else {
+                cell_size
+            },
+        )
+    } else {
+        (num_stations, max_distance)
+    };
+    let num_segments = num_stations - 1;
+    let x: Vec<f64> = (0..num_stations)
+        .map(|i| i as f64 / num_segments as f64)
+        .collect();
+    let cell_x = |c: &ProfileCell| {
+        if degenerate {
+            0f64
+        } else {
+            1f64 - c.distance / length
+        }
+    };
+
+    // The mean slope of each segment, filled from the nearest segment with slopes.
+    let mut sums = vec![0f64; num_segments];
+    let mut counts = vec![0usize; num_segments];
+    for c in cells {
+        if let Some(s) = c.slope {
+            // The small offset keeps cells lying on station positions out of the segment
+            // above after rounding.
+            let seg = (cell_x(c) * num_segments as f64 + 1e-9).floor() as usize;
+            sums[seg.min(num_segments - 1)] += s;
+            counts[seg.min(num_segments - 1)] += 1;
+        }
+    }
+    let sampled: Vec<usize> = (0..num_segments).filter(|&k| counts[k] > 0).collect();
+    let segment_slopes: Vec<f64> = (0..num_segments)
+        .map(|k| {
+            match sampled
+                .iter()
+                .min_by_key(|&&j| (j as isize - k as isize).abs())
+            {
+                Some(&j) => sums[j] / counts[j] as f64,
+                None => 0f64,
+            }
+        })
+        .collect();
+    let slope: Vec<f64> = (0..num_stations)
+        .map(|i| {
+            if i == 0 {
+                segment_slopes[0]
+            } else if i == num_segments {
+                segment_slopes[num_segments - 1]
+            } else {
+                (segment_slopes[i - 1] + segment_slopes[i]) / 2f64
+            }
+        })
+        .collect();
+
+    // The profile descends from the mean elevation of the top cells along the segment slopes.
+    let top: Vec<f64> = cells
+        .iter()
+        .filter(|c| c.distance >= max_distance - 1e-9)
+        .filter_map(|c| c.elevation)
+        .collect();
+    let all: Vec<f64> = cells.iter().filter_map(|c| c.elevation).collect();
+    let top_elevation = if !top.is_empty() {
+        top.iter().sum::<f64>() / top.len() as f64
+    } else if !all.is_empty() {
+        all.iter().cloned().fold(f64::MIN, f64::max)
+    } else {
+        0f64
+    };
+    let segment_length = length / num_segments as f64;
+    let mut elevation = vec![top_elevation; num_stations];
+    for i in 1..num_stations {
+        elevation[i] = elevation[i - 1] - segment_slopes[i - 1] * segment_length;
+    }
+
+    let area_fraction: Vec<f64> = x
+        .iter()
+        .enumerate()
+        .map(|(i, &xi)| {
+            if i == num_segments {
+                1f64
+            } else {
+                let upslope = cells.iter().filter(|c| cell_x(c) < xi - 1e-9).count();
+                upslope as f64 / cells.len() as f64
+            }
+        })
+        .collect();
+
+    RepresentativeProfile {
+        area,
+        length,
+        width: area / length,
+        x,
+        elevation,
+        slope,
+        area_fraction,
+    }
+}
+
+fn write_profile_rows<W: Write>(
+    writer: &mut W,
+    id: Option<i64>,
+    profile: &RepresentativeProfile,
+) -> io::Result<()> {
+    for i in 0..profile.x.len() {
+        if let Some(id) = id {
+            write!(writer, "{},", id)?;
+        }
+        writeln!(
+            writer,
+            "{},{:.4},{:.3},{:.3},{:.5},{:.4}",
+            i,
+            profile.x[i],
+            profile.x[i] * profile.length,
+            profile.elevation[i],
+            profile.slope[i],
+            profile.area_fraction[i]
+        )?;
+    }
+    Ok(())
+}
+
+fn write_hillslope_csv(
+    id: i64,
+    profile: &RepresentativeProfile,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(file_path)?);
+    writeln!(&mut writer, "# topaz_id: {}", id)?;
+    writeln!(&mut writer, "# area: {:.3}", profile.area)?;
+    writeln!(&mut writer, "# length: {:.3}", profile.length)?;
+    writeln!(&mut writer, "# width: {:.3}", profile.width)?;
+    writeln!(&mut writer, "# stations: {}", profile.x.len())?;
+    writeln!(
+        &mut writer,
+        "station,x,distance,elevation,slope,area_fraction"
+    )?;
+    write_profile_rows(&mut writer, None, profile)?;
+    writer.flush()
+}
+
+fn write_combined_csv(
+    profiles: &[(i64, RepresentativeProfile)],
+    file_path: &str,
+) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut writer,
+        "topaz_id,station,x,distance,elevation,slope,area_fraction"
+    )?;
+    for (id, profile) in profiles {
+        write_profile_rows(&mut writer, Some(*id), profile)?;
+    }
+    writer.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{representative_profile, HillslopeRepresentativeProfilePlotData, ProfileCell};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    // A 4 x 7 grid with 10 m cells. Column 4 is channel 24, flowing south. Hillslope 22 (columns
+    // 0-3) drains east down a 10% slope, hillslope 23 (columns 5-6) drains west, and the single
+    // cell (0, 6) is hillslope 21.
+    fn write_rasters(dir: &str) {
+        let write = |name: &str, f: &dyn Fn(isize, isize) -> f64| {
+            grid_fixture_raster(&format!("{}/{}", dir, name), &GridFixture::new(4, 7, f))
+                .write()
+                .unwrap();
+        };
+        write("subwta.tif", &|r, c| match (r, c) {
+            (_, 0..=3) => 22f64,
+            (_, 4) => 24f64,
+            (0, 6) => 21f64,
+            _ => 23f64,
+        });
+        write("pntr.tif", &|r, c| match c {
+            0..=3 => 2f64,
+            4 if r == 3 => 0f64,
+            4 => 8f64,
+            _ => 32f64,
+        });
+        write("dem.tif", &|r, c| {
+            100f64 - r as f64 * 0.1 + (c - 4).abs() as f64 * if c < 4 { 1f64 } else { 2f64 }
+        });
+    }
+
+    fn read_csv(file: &str) -> (Vec<String>, Vec<Vec<String>>) {
+        let text = fs::read_to_string(file).unwrap();
+        let comments = text
+            .lines()
+            .filter(|l| l.starts_with('#'))
+            .map(|l| l.to_string())
+            .collect();
+        let rows = text
+            .lines()
+            .filter(|l| !l.starts_with('#'))
+            .skip(1)
+            .map(|l| l.split(',').map(|s| s.to_string()).collect())
+            .collect();
+        (comments, rows)
+    }
+
+    #[test]
+    fn test_profile_plot_data() {
+        let dir = temp_dir("hillslope_representative_profile_plot_data").unwrap();
+        write_rasters(&dir);
+        let args = vec![
+            format!("--subwta={}/subwta.tif", dir),
+            format!("--dem={}/dem.tif", dir),
+            format!("--d8_pntr={}/pntr.tif", dir),
+            format!("--out_dir={}/profiles", dir),
+            format!("--output={}/profiles.csv", dir),
+            "--stations=5".to_string(),
+        ];
+        HillslopeRepresentativeProfilePlotData::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let (comments, rows) = read_csv(&format!("{}/profiles/hillslope_22.csv", dir));
+        assert_eq!(
+            comments,
+            vec![
+                "# topaz_id: 22",
+                "# area: 1600.000",
+                "# length: 40.000",
+                "# width: 40.000",
+                "# stations: 5"
+            ]
+        );
+        assert_eq!(rows.len(), 5);
+        let column = |k: usize| rows.iter().map(|r| r[k].clone()).collect::<Vec<String>>();
+        assert_eq!(
+            column(1),
+            ["0.0000", "0.2500", "0.5000", "0.7500", "1.0000"]
+        );
+        assert_eq!(column(2), ["0.000", "10.000", "20.000", "30.000", "40.000"]);
+        // The top cells average 99.85 m and the profile falls 1 m per 10 m segment.
+        assert_eq!(
+            column(3),
+            ["103.850", "102.850", "101.850", "100.850", "99.850"]
+        );
+        assert!(column(4).iter().all(|s| s == "0.10000"));
+        assert_eq!(
+            column(5),
+            ["0.0000", "0.2500", "0.5000", "0.7500", "1.0000"]
+        );
+
+        // The single-cell hillslope is a two-station profile one cell long.
+        let (comments, rows) = read_csv(&format!("{}/profiles/hillslope_21.csv", dir));
+        assert_eq!(comments[2], "# length: 20.000");
+        assert_eq!(comments[4], "# stations: 2");
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0][5], "0.0000");
+        assert_eq!(rows[1][5], "1.0000");
+
+        // The combined file holds every station of every hillslope, ordered by TOPAZ ID.
+        let (_, rows) = read_csv(&format!("{}/profiles.csv", dir));
+        assert_eq!(rows.len(), 2 + 5 + 5);
+        assert_eq!(rows[0][0], "21");
+        assert_eq!(rows[2][0], "22");
+        assert_eq!(rows[7][0], "23");
+        assert_eq!(rows[7][1], "0");
+    }
+
+    #[test]
+    fn test_degenerate_profile() {
+        let cells = [ProfileCell {
+            distance: 0f64,
+            elevation: Some(50f64),
+            slope: Some(0.2),
+        }];
+        let profile = representative_profile(&cells, 21, 100f64, 10f64);
+        assert_eq!(profile.x, vec![0f64, 1f64]);
+        assert_eq!(profile.length, 10f64);
+        assert_eq!(profile.width, 10f64);
+        assert_eq!(profile.elevation, vec![50f64, 48f64]);
+        assert_eq!(profile.slope, vec![0.2, 0.2]);
+        assert_eq!(profile.area_fraction, vec![0f64, 1f64]);
+    }
+}
@@ -0,0 +1,613 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool computes the width function of each hillslope in a TOPAZ-style sub-catchment raster
+/// (`--subwta`), e.g. the output of `HillslopesTopaz`. The width function w(x) is the contributing width
+/// of a hillslope as a function of normalized distance downslope, x, which runs from 0 at the top of the
+/// hillslope to 1 at its channel. It is a richer description of hillslope shape than the single average
+/// width (area divided by length) used by simple WEPP parameterizations, distinguishing e.g. convergent
+/// hillslopes that narrow towards the channel from divergent ones that widen.
+///
+/// Hillslopes are the cells whose IDs end in 1, 2, or 3, and channels are the cells whose IDs end in 4.
+/// The flow distance of each hillslope cell to the channel is read from the `--dist_to_chn` raster, e.g.
+/// the output of `DownslopeDistanceToStream`, or, when it is not specified, is measured along the D8 flow
+/// pointer (`--d8_pntr`) from the cell centre to the centre of the first channel cell downslope. Cells
+/// that do not drain to a channel, or that have NoData distances, are ignored.
+///
+/// For a hillslope with maximum flow distance L, a cell at distance d lies at x = 1 - d / L. The x range
+/// is divided into `--bins` equal bins (default 10) and the cells of each bin are counted. The width of a
+/// bin is its area (cells × cell size) divided by its length along the hillslope (L / bins), so that a
+/// rectangular hillslope has a constant width function equal to its width. The output (`--output`) is a
+/// tab-separated table with one row per hillslope and bin, ordered by TOPAZ ID and bin, and the following
+/// columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `topaz_id` | The hillslope's TOPAZ ID. |
+/// | `bin` | The 1-based bin number, from the top of the hillslope. |
+/// | `x_start`, `x_end` | The normalized distance range of the bin. |
+/// | `num_cells` | The number of cells in the bin. |
+/// | `width` | The contributing width of the bin, in map units. |
+/// | `length` | The hillslope's maximum flow distance, L, in map units. |
+/// | `max_width` | The largest width of any of the hillslope's bins. |
+/// | `distance_of_max` | The normalized distance of the centre of the bin with the largest width; the first such bin on ties. |
+///
+/// Distances are in the units of the grid, which should therefore be projected. By default, the pointer
+/// raster is assumed to use the clockwise indexing method used by WhiteboxTools. If the pointer file
+/// contains ESRI flow direction values instead, the `--esri_pntr` parameter must be specified.
+///
+/// # See Also
+/// `HillslopesTopaz`, `DownslopeDistanceToStream`, `D8Pointer`
+pub struct HillslopeWidthFunction {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl HillslopeWidthFunction {
+    pub fn new() -> HillslopeWidthFunction {
+        // public constructor
+        let name = "HillslopeWidthFunction".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Computes the width function of each TOPAZ hillslope, i.e. contributing width by normalized distance downslope."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Subwta File".to_owned(),
+            flags: vec!["--subwta".to_owned()],
+            description: "Input TOPAZ-style hillslope and channel ID raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Distance to Channel File".to_owned(),
+            flags: vec!["--dist_to_chn".to_owned()],
+            description: "Optional input raster of flow distances to the channel; measured along the pointer if unspecified.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Bins".to_owned(),
+            flags: vec!["--bins".to_owned()],
+            description: "Number of normalized distance bins per hillslope.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated width function table.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --d8_pntr='d8pntr.tif' --bins=20 -o='width_function.tsv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --d8_pntr='d8pntr.tif' --dist_to_chn='dist.tif' -o='width_function.tsv'", short_exe, name).replace("*", &sep);
+
+        HillslopeWidthFunction {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for HillslopeWidthFunction {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut subwta_file = String::new();
+        let mut d8_file = String::new();
+        let mut dist_file = String::new();
+        let mut num_bins = 10usize;
+        let mut output_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-subwta" {
+                subwta_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dist_to_chn" {
+                dist_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-bins" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                num_bins = match value.trim().parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("--bins must be a positive integer; got '{}'.", value),
+                        ))
+                    }
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        subwta_file = resolve_path(working_directory, &subwta_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let subwta = Raster::new(&subwta_file, "r")?;
+        let pntr = Raster::new(&d8_file, "r")?;
+        let rows = subwta.configs.rows as isize;
+        let columns = subwta.configs.columns as isize;
+        if pntr.configs.rows != subwta.configs.rows
+            || pntr.configs.columns != subwta.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The subwta and D8 pointer rasters must have the same rows and columns.",
+            ));
+        }
+        let dist_raster = if dist_file.trim().is_empty() {
+            None
+        } else {
+            let raster = Raster::new(&resolve_path(working_directory, &dist_file), "r")?;
+            if raster.configs.rows != subwta.configs.rows
+                || raster.configs.columns != subwta.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The subwta and distance to channel rasters must have the same rows and columns.",
+                ));
+            }
+            Some(raster)
+        };
+
+        let start = Instant::now();
+
+        // The TOPAZ ID of each cell, or 0 for NoData and non-positive values.
+        let subwta_nodata = subwta.configs.nodata;
+        let mut ids: Array2D<i64> = Array2D::new(rows, columns, 0, 0)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = subwta.get_value(row, col);
+                if z != subwta_nodata && z > 0f64 {
+                    ids.set_value(row, col, z.round() as i64);
+                }
+            }
+        }
+        let is_hillslope = |id: i64| id > 0 && matches!(id % 10, 1..=3);
+
+        let res_x = subwta.configs.resolution_x;
+        let res_y = subwta.configs.resolution_y;
+        let dist_nodata = -1f64;
+        let mut dist: Array2D<f64> = Array2D::new(rows, columns, dist_nodata, dist_nodata)?;
+        match &dist_raster {
+            Some(raster) => {
+                let nodata = raster.configs.nodata;
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let z = raster.get_value(row, col);
+                        if is_hillslope(ids.get_value(row, col)) && z != nodata && z >= 0f64 {
+                            dist.set_value(row, col, z);
+                        }
+                    }
+                }
+            }
+            None => {
+                // Create a mapping from the pointer values to cells offsets.
+                let mut pntr_matches: [i8; 129] = [-1i8; 129];
+                if !esri_style {
+                    // This maps Whitebox-style D8 pointer values
+                    // onto the cell offsets in dx and dy.
+                    pntr_matches[1] = 0i8;
+                    pntr_matches[2] = 1i8;
+                    pntr_matches[4] = 2i8;
+                    pntr_matches[8] = 3i8;
+                    pntr_matches[16] = 4i8;
+                    pntr_matches[32] = 5i8;
+                    pntr_matches[64] = 6i8;
+                    pntr_matches[128] = 7i8;
+                } else {
+                    // This maps Esri-style D8 pointer values
+                    // onto the cell offsets in dx and dy.
+                    pntr_matches[1] = 1i8;
+                    pntr_matches[2] = 2i8;
+                    pntr_matches[4] = 3i8;
+                    pntr_matches[8] = 4i8;
+                    pntr_matches[16] = 5i8;
+                    pntr_matches[32] = 6i8;
+                    pntr_matches[64] = 7i8;
+                    pntr_matches[128] = 0i8;
+                }
+                let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+                let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+                let diag = (res_x * res_x + res_y * res_y).sqrt();
+                let step_length = [diag, res_x, diag, res_y, diag, res_x, diag, res_y];
+
+                let pntr_nodata = pntr.configs.nodata;
+                let mut flow_dir: Array2D<i8> = Array2D::new(rows, columns, -1, -1)?;
+                let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+                for row in 0..rows {
+                    for col in 0..columns {
+                        let z = pntr.get_value(row, col);
+                        if z != pntr_nodata && z > 0f64 && z <= 128f64 {
+                            flow_dir.set_value(row, col, pntr_matches[z as usize]);
+                        }
+                        let id = ids.get_value(row, col);
+                        if id > 0 && id % 10 == 4 {
+                            queue.push_back((row, col));
+                        }
+                    }
+                }
+
+                // Search upslope from the channel cells, accumulating the distance downslope.
+                while let Some((row, col)) = queue.pop_front() {
+                    let d = dist.get_value(row, col).max(0f64);
+                    for n in 0..8 {
+                        let (rn, cn) = (row + dy[n], col + dx[n]);
+                        // does the neighbour flow into this cell?
+                        if flow_dir.get_value(rn, cn) != ((n + 4) % 8) as i8 {
+                            continue;
+                        }
+                        if !is_hillslope(ids.get_value(rn, cn)) || dist.get_value(rn, cn) >= 0f64 {
+                            continue;
+                        }
+                        dist.set_value(rn, cn, d + step_length[(n + 4) % 8]);
+                        queue.push_back((rn, cn));
+                    }
+                }
+            }
+        }
+
+        // Gather the distances of each hillslope's cells.
+        let mut hillslopes: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+        let mut num_ignored = 0usize;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                let id = ids.get_value(row, col);
+                if !is_hillslope(id) {
+                    continue;
+                }
+                let d = dist.get_value(row, col);
+                if d < 0f64 {
+                    num_ignored += 1;
+                    continue;
+                }
+                hillslopes.entry(id).or_insert(vec![]).push(d);
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Binning cells: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+        if hillslopes.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No hillslope cells (IDs ending in 1, 2, or 3) with a distance to the channel were found.",
+            ));
+        }
+
+        let cell_area = res_x * res_y;
+        let mut width_functions = Vec::with_capacity(hillslopes.len());
+        for (&id, distances) in &hillslopes {
+            match width_function(distances, num_bins, cell_area) {
+                Some(wf) => width_functions.push((id, wf)),
+                None => {
+                    if verbose {
+                        println!(
+                            "Warning: Hillslope {} has a maximum distance of zero and was skipped.",
+                            id
+                        );
+                    }
+                }
+            }
+        }
+
+        write_table(&width_functions, &output_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!(
+                "Wrote the width functions of {} hillslopes to {}.",
+                width_functions.len(),
+                output_file
+            );
+            if num_ignored > 0 {
+                println!(
+                    "Warning: {} hillslope cells without a distance to the channel were ignored.",
+                    num_ignored
+                );
+            }
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The binned width function of a hillslope.
+struct WidthFunction {
+    length: f64,
+    counts: Vec<usize>,
+    widths: Vec<f64>,
+}
+
+/// Bins the flow distances of a hillslope's cells by normalized distance downslope and converts the
+/// counts to widths. Returns `None` when the hillslope has no length.
+fn width_function(distances: &[f64], num_bins: usize, cell_area: f64) -> Option<WidthFunction> {
+    let length = distances.iter().cloned().fold(0f64, f64::max);
+    if length <= 0f64 {
+        return None;
+    }
+    let mut counts = vec![0usize; num_bins];
+    for &d in distances {
+        // The small offset keeps cells lying on bin edges, e.g. x = 1/3 with 3 bins, out of the
+        // bin below after rounding.
+        let bin = ((1f64 - d / length) * num_bins as f64 + 1e-9).floor() as usize;
+        counts[bin.min(num_bins - 1)] += 1;
+    }
+    let bin_length = length / num_bins as f64;
+    let widths = counts
+        .iter()
+        .map(|&n| n as f64 * cell_area / bin_length)
+        .collect();
+    Some(WidthFunction {
+        length,
+        counts,
+        widths,
+    })
+}
+
+fn write_table(width_functions: &[(i64, WidthFunction)], file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "topaz_id\tbin\tx_start\tx_end\tnum_cells\twidth\tlength\tmax_width\tdistance_of_max"
+    )?;
+    for (id, wf) in width_functions {
+        let num_bins = wf.counts.len();
+        let mut max_bin = 0;
+        for (bin, &w) in wf.widths.iter().enumerate() {
+            if w > wf.widths[max_bin] {
+                max_bin = bin;
+            }
+        }
+        let distance_of_max = (max_bin as f64 + 0.5) / num_bins as f64;
+        for bin in 0..num_bins {
+            writeln!(
+                &mut file,
+                "{}\t{}\t{:.4}\t{:.4}\t{}\t{:.3}\t{:.3}\t{:.3}\t{:.4}",
+                id,
+                bin + 1,
+                bin as f64 / num_bins as f64,
+                (bin + 1) as f64 / num_bins as f64,
+                wf.counts[bin],
+                wf.widths[bin],
+                wf.length,
+                wf.widths[max_bin],
+                distance_of_max
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{width_function, HillslopeWidthFunction};
+    use crate::tools::WhiteboxTool;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    // A 4 x 7 grid with 10 m cells. Column 4 is channel 24, flowing south. Hillslope 22 (columns
+    // 0-3) drains east and hillslope 23 (columns 5-6) drains west, so both are rectangles 40 m wide.
+    fn write_rasters(dir: &str) {
+        let write = |name: &str, f: &dyn Fn(isize, isize) -> f64| {
+            grid_fixture_raster(&format!("{}/{}", dir, name), &GridFixture::new(4, 7, f))
+                .write()
+                .unwrap();
+        };
+        write("subwta.tif", &|_, c| match c {
+            0..=3 => 22f64,
+            4 => 24f64,
+            _ => 23f64,
+        });
+        write("pntr.tif", &|r, c| match c {
+            0..=3 => 2f64,
+            4 if r == 3 => 0f64,
+            4 => 8f64,
+            _ => 32f64,
+        });
+        write("dist.tif", &|_, c| (c - 4).abs() as f64 * 10f64);
+    }
+
+    fn run(dir: &str, extra: &[&str]) -> Vec<Vec<String>> {
+        let mut args = vec![
+            format!("--subwta={}/subwta.tif", dir),
+            format!("--d8_pntr={}/pntr.tif", dir),
+            format!("--output={}/width.tsv", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        HillslopeWidthFunction::new().run(args, "", false).unwrap();
+        // without the header
+        read_tsv(&format!("{}/width.tsv", dir))
+            .unwrap()
+            .split_off(1)
+    }
+
+    #[test]
+    fn test_rectangular_hillslopes_have_constant_width() {
+        let dir = temp_dir("hillslope_width_function_rectangular").unwrap();
+        write_rasters(&dir);
+        let rows = run(&dir, &["--bins=4"]);
+        // Hillslope 23 is only 2 cells long, so with 4 bins every other one of its bins is empty.
+        assert_eq!(rows.len(), 8);
+        for row in &rows[0..4] {
+            assert_eq!(row[0], "22");
+            assert_eq!(row[4], "4");
+            assert_eq!(row[5], "40.000");
+            assert_eq!(row[6], "40.000");
+            assert_eq!(row[7], "40.000");
+            assert_eq!(row[8], "0.1250");
+        }
+        assert_eq!(rows[3][2], "0.7500");
+        assert_eq!(rows[3][3], "1.0000");
+
+        let rows = run(&dir, &["--bins=2"]);
+        assert_eq!(rows.len(), 4);
+        for row in &rows {
+            assert_eq!(row[5], "40.000");
+        }
+        assert_eq!(rows[2][0], "23");
+        assert_eq!(rows[2][6], "20.000");
+
+        // Distances read from a raster give the same table.
+        let from_raster = run(&dir, &["--bins=2", "--dist_to_chn={dir}/dist.tif"]);
+        assert_eq!(from_raster, rows);
+    }
+
+    #[test]
+    fn test_width_function_of_divergent_hillslope() {
+        // A triangle widening from 1 cell at the top to 3 cells at the channel, with 10 m cells.
+        let distances = [30f64, 20f64, 20f64, 10f64, 10f64, 10f64];
+        let wf = width_function(&distances, 3, 100f64).unwrap();
+        assert_eq!(wf.counts, vec![1, 2, 3]);
+        assert_eq!(wf.widths, vec![10f64, 20f64, 30f64]);
+        assert!(width_function(&[0f64], 3, 100f64).is_none());
+    }
+}
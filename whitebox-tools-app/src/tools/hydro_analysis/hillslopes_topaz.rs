@@ -4,19 +4,23 @@ Authors: Dr. Roger Lew
 Created: 09/06/2025
 */
 
+use crate::tools::hydro_analysis::pour_point_locator::locate_pour_point;
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
 use crate::tools::*;
-use geojson::{GeoJson, Geometry, Value};
-use std::collections::{HashMap, VecDeque};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap, VecDeque};
 use std::env;
 use std::f64;
-use std::fs::File;
-use std::io::{self, Error, ErrorKind, Write};
-use std::path;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path::{self, Path, PathBuf};
+use std::process;
 use std::time::Instant;
 use whitebox_common::algorithms::calculate_rotation_degrees;
 use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
 use whitebox_raster::*;
-use whitebox_vector::*;
 
 /// This tool will identify the hillslopes associated with a user-specified stream network for a single catchment. Hillslopes
 /// include the catchment areas draining to the left and right sides of each stream link in the network as well
@@ -29,6 +33,11 @@ use whitebox_vector::*;
 /// The tool implements the TOPAZ-style channel and hillslope IDs with channels ending with "4" starting with 24, and hillslopes
 /// ending with 1 ("top"), 2 ("left"), or 3 ("right").
 ///
+/// The channel numbering can be changed with `--topaz_start` (the outlet channel's ID, default 24) and
+/// `--topaz_stride` (the increment between successive channel IDs, default 10), e.g. to keep the IDs of
+/// several catchments from overlapping. The start must end in 4 and the stride must be a multiple of 10, so
+/// that channels still end in 4 and each channel's hillslopes are the channel ID minus 1, 2, or 3.
+///
 /// The user must specify the name of a flow pointer
 /// (flow direction) raster (`--d8_pntr`), a streams raster (`--streams`), and the output raster (`--output`).
 /// The flow pointer and streams rasters should be generated using the `D8Pointer` algorithm. This will require
@@ -39,6 +48,97 @@ use whitebox_vector::*;
 ///
 /// NoData values in the input flow pointer raster are assigned NoData values in the output image.
 ///
+/// All input rasters, including the optional stream order (`--order`) and `--flow_accum` rasters, must have
+/// the same rows, columns, resolution, and extent as the DEM. Otherwise the tool fails before processing,
+/// naming each raster that differs from the DEM and the fields that differ, and listing the dimensions,
+/// resolution, EPSG code, and corner coordinates of every input.
+///
+/// The `--output_mode` parameter controls which cells are written to the TOPAZ ID raster: `all` (default)
+/// writes channels and hillslopes, `channels` writes only channel cells (IDs ending in 4), and `hillslopes`
+/// writes only hillslope cells with channel cells set to NoData. The `channels` mode skips the hillslope
+/// flood fill entirely, so `areaup` is not computed and is reported as 0 in the network table.
+///
+/// Pour points digitized against a slightly different grid than the streams raster often land one cell
+/// off the channel. By default, if the cell containing the pour point is not a stream cell within the
+/// watershed, its 8 neighbours are searched instead: a single neighbouring stream cell in the watershed
+/// is used directly, and when several exist, the one that the located cell's D8 pointer drains into is
+/// preferred (otherwise the nearest, favouring orthogonal neighbours). The adjustment is reported in
+/// verbose mode. The tool only fails when no adjacent stream cell exists. Specify `--strict_pour_pt` to
+/// require the pour point to fall exactly on a stream cell.
+///
+/// The pour point may be the last stream cell inside the watershed or the raster: the outlet link ends
+/// at the pour point and never steps out of the basin. A channel that leaves the watershed or the raster
+/// anywhere else is an error naming the cell, as it means that the mask and the D8 pointer disagree.
+///
+/// The `order` column of the network table holds each link's stream order, read from the `--order`
+/// raster at the link's upstream end. When `--order` is not specified, the Strahler order is computed
+/// from the link network instead: headwater links are order 1, and a link below a confluence takes the
+/// highest order of its inflows, plus one if two or more inflows share it.
+///
+/// Each link is also assigned a WEPP channel routing sequence number, reported in the `chn_order` column
+/// of the network table: 1 is the most upstream link, every link is numbered after all of its inflows,
+/// and the outlet link is numbered last. Links that become available at the same time (e.g. parallel
+/// headwaters) are numbered in ascending TOPAZ ID order. The optional `--chn_order` raster stamps each
+/// channel cell with the sequence number of its link.
+///
+/// As a check of the hillslope and channel area bookkeeping, a flow accumulation raster (`--flow_accum`),
+/// measured in cells and counting each cell itself (e.g. `D8FlowAccumulation` with `--out_type=cells`),
+/// may be supplied. The cumulative area drained by each link, i.e. its hillslopes (including the top
+/// hillslope of a headwater link), its channel cells, and all upstream links, is then compared with the
+/// accumulation at the link's most downstream channel cell times the cell area. The relative discrepancy,
+/// (cumulative area - accumulated area) / accumulated area, is written to the `area_error` column of the
+/// network table, and a warning is printed when any link's discrepancy exceeds `--max_area_error` (default
+/// 0.05, i.e. 5%). Discrepancies usually point to a watershed mask that is inconsistent with the D8 pointer,
+/// e.g. one that cuts off part of the contributing area. The check is unavailable in the `channels` output
+/// mode, which does not label the hillslopes.
+///
+/// With `--validate_only`, the tool checks an existing `--subwta` raster, e.g. one that has been edited by
+/// hand, against the current inputs instead of regenerating it. Only the `--d8_pntr`, `--streams`,
+/// `--watershed`, `--subwta`, and `--netw` files are read, and nothing is overwritten; the subwta raster is
+/// expected to contain channels and hillslopes, as written by the default `all` output mode. Each violation
+/// of the following invariants is written to the `--violations` TSV file, with its grid cell and coordinates:
+///
+/// | Violation | Description |
+/// |-----------|-------------|
+/// | `invalid_id` | A value that is not a positive integer ending in 1, 2, 3, or 4. |
+/// | `outside_watershed` | An ID outside of the watershed mask. |
+/// | `missing_id` | A watershed cell without an ID. |
+/// | `channel_off_stream` | A channel ID (ending in 4) on a cell that is not a stream cell. |
+/// | `stream_not_channel` | A stream cell within the watershed whose ID does not end in 4. |
+/// | `wrong_channel` | A hillslope cell whose flow path reaches a channel other than its own (the hillslope ID plus 1 to 3) first. |
+/// | `no_channel` | A hillslope cell whose flow path leaves the watershed without reaching a channel. |
+/// | `channel_not_in_netw` | A channel ID that is missing from the network table, reported at its first cell. |
+/// | `netw_not_in_subwta` | A network table `topaz_id` with no channel cells in the subwta raster. |
+///
+/// The number of violations of each type is printed in verbose mode, and the tool exits with an error
+/// reporting the total when any are found.
+///
+/// Every output file is first written under a temporary name beside its final path (the name with
+/// `.tmp-<process id>` before the extension) and synced to disk. The outputs are renamed into place
+/// together once all of them are complete, so a run that fails or is killed part way never leaves a
+/// truncated `netw.tsv` or subwta raster, nor a `netw.tsv` that does not match the raster; the
+/// outputs of an earlier run stay as they were, and the temporaries are removed on failure.
+///
+/// The optional `--summary` JSON file is written at the end of the run, and also when the run fails, in
+/// which case `error` holds the message and the values that had not yet been computed are `null`. Its keys
+/// are:
+///
+/// | Key | Description |
+/// |-----|-------------|
+/// | `tool` | `HillslopesTopaz`. |
+/// | `status` | `success` or `failure`. |
+/// | `error` | The error message of a failed run, otherwise `null`. |
+/// | `num_links` | The number of channel links. |
+/// | `num_hillslopes` | The number of hillslopes with at least one cell; `null` in the `channels` output mode. |
+/// | `channel_length_m` | The total length of the channel links, in metres. |
+/// | `watershed_area_m2` | The area of the watershed mask, in square metres. |
+/// | `outlet` | The `row`, `column`, `x`, and `y` of the outlet cell, after any pour point adjustment. |
+/// | `min_topaz_id`, `max_topaz_id` | The smallest and largest TOPAZ IDs written to the subwta raster. |
+/// | `links_per_order` | The number of links of each stream order, keyed by the order. |
+/// | `phase_seconds` | The time taken by each completed phase, in seconds: `initialization` (including reading the inputs), `build_links`, `link_relationships`, `topaz_ids`, `stamp_channels`, `hillslope_fill`, `link_areas`, `area_check` (with `--flow_accum`), and `write_outputs`; or `validation` with `--validate_only`. |
+/// | `total_seconds` | The total run time, in seconds. |
+/// | `outputs` | The paths of the files written, keyed by `subwta`, `netw`, and `chn_order` (or `violations` with `--validate_only`). |
+///
 /// # See Also
 /// `Hillslopes`, `StreamLinkIdentifier`, `Watershed`, `Subbasins`, `D8Pointer`, `BreachDepressions`, `FillDepressions`
 
@@ -59,6 +159,8 @@ struct Link {
     areaup: f64,               // Area upstream of the link in square meters
     is_headwater: bool,        // True for headwater links
     is_outlet: bool,           // True for outlet link
+    chn_order: i32,            // WEPP channel routing sequence (1 = most upstream)
+    area_error: f64,           // Relative discrepancy from the flow accumulation
     path: Vec<(isize, isize)>, // Cells in the channel path from top to bottom
 }
 
@@ -80,25 +182,130 @@ impl Link {
             areaup: 0.0,
             is_headwater: false,
             is_outlet: false,
+            chn_order: 0,
+            area_error: f64::NAN,
             path: Vec::new(),
         }
     }
 }
 
-fn write_links_to_tsv(links: &[Link], file_path: &str) -> io::Result<()> {
-    let mut file = File::create(file_path)?;
+/// Output files that are written under temporary sibling names and moved into place together once
+/// all of them are complete, so that a failed or interrupted run never leaves a truncated output
+/// under its final name. The temporaries of outputs that were not committed are removed when the
+/// staging is dropped.
+struct StagedOutputs {
+    /// The directory, temporary stem, and final path of each output.
+    files: Vec<(PathBuf, String, PathBuf)>,
+}
+
+impl StagedOutputs {
+    fn new() -> StagedOutputs {
+        StagedOutputs { files: vec![] }
+    }
+
+    /// Returns the temporary path to write `file` to: its name with `.tmp-<process id>` inserted
+    /// before the extension, so that rasters are written in the same format and any sidecar files
+    /// (e.g. the .tas data of a .dep raster) are staged with it.
+    fn stage(&mut self, file: &str) -> String {
+        let path = Path::new(file);
+        let stem = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let tmp_stem = format!("{}.tmp-{}", stem, process::id());
+        let tmp_name = match path.extension() {
+            Some(ext) => format!("{}.{}", tmp_stem, ext.to_string_lossy()),
+            None => tmp_stem.clone(),
+        };
+        let dir = match path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        self.files.push((dir.clone(), tmp_stem, path.to_path_buf()));
+        dir.join(tmp_name).to_string_lossy().to_string()
+    }
+
+    /// The files written for a staged output, i.e. those named by its temporary stem, each paired
+    /// with its final path.
+    fn written_files(dir: &Path, tmp_stem: &str, file: &Path) -> Vec<(PathBuf, PathBuf)> {
+        let stem = file
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let entries = match fs::read_dir(dir) {
+            Ok(entries) => entries,
+            Err(_) => return vec![],
+        };
+        entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| {
+                let name = entry.file_name().to_string_lossy().to_string();
+                let suffix = name.strip_prefix(tmp_stem)?;
+                if !suffix.is_empty() && !suffix.starts_with('.') {
+                    return None;
+                }
+                Some((
+                    entry.path(),
+                    file.with_file_name(format!("{}{}", stem, suffix)),
+                ))
+            })
+            .collect()
+    }
+
+    /// Syncs every staged file to disk and then renames each into place.
+    fn commit(&mut self) -> io::Result<()> {
+        let mut moves: Vec<(PathBuf, PathBuf)> = vec![];
+        for (dir, tmp_stem, file) in &self.files {
+            let written = StagedOutputs::written_files(dir, tmp_stem, file);
+            if written.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::NotFound,
+                    format!("Output file {} was not written.", file.display()),
+                ));
+            }
+            moves.extend(written);
+        }
+        for (tmp, _) in &moves {
+            OpenOptions::new().write(true).open(tmp)?.sync_all()?;
+        }
+        for (tmp, file) in &moves {
+            fs::rename(tmp, file)?;
+        }
+        self.files.clear();
+        Ok(())
+    }
+}
+
+impl Drop for StagedOutputs {
+    fn drop(&mut self) {
+        for (dir, tmp_stem, file) in &self.files {
+            for (tmp, _) in StagedOutputs::written_files(dir, tmp_stem, file) {
+                let _ = fs::remove_file(tmp);
+            }
+        }
+    }
+}
+
+/// Writes the network table; the `area_error` column is only included when `with_area_error` is set.
+/// The rows are streamed through a buffer, which is flushed and synced to disk before returning.
+fn write_links_to_tsv(links: &[Link], file_path: &str, with_area_error: bool) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
 
     // Write header
-    writeln!(
+    write!(
         &mut file,
-        "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet"
+        "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet\tchn_order"
     )?;
+    if with_area_error {
+        write!(&mut file, "\tarea_error")?;
+    }
+    writeln!(&mut file)?;
 
     // Write each link
     for link in links {
-        writeln!(
+        write!(
             &mut file,
-            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{}\t{:.3}\t{}\t{}",
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{}\t{:.3}\t{}\t{}\t{}",
             link.id,
             link.topaz_id,
             link.ds.0,
@@ -115,13 +322,386 @@ fn write_links_to_tsv(links: &[Link], file_path: &str) -> io::Result<()> {
             link.order,
             link.areaup,
             link.is_headwater,
-            link.is_outlet
+            link.is_outlet,
+            link.chn_order
         )?;
+        if with_area_error {
+            write!(&mut file, "\t{:.4}", link.area_error)?;
+        }
+        writeln!(&mut file)?;
     }
 
+    file.flush()?;
+    file.get_ref().sync_all()
+}
+
+/// A link of a network table written by `write_links_to_tsv`, as read back by the tools that take
+/// the table as input (`--netw`). As in the table, `ds` and `us` hold the (row, column) of the
+/// link's end cells, i.e. the `ds_x` and `us_x` columns hold rows.
+pub(crate) struct NetwLink {
+    pub(crate) id: i64,
+    pub(crate) topaz_id: i64,
+    pub(crate) ds: (isize, isize),
+    pub(crate) us: (isize, isize),
+    pub(crate) inflows: [i64; 3],
+    pub(crate) length_m: f64,
+    pub(crate) drop_m: f64,
+    pub(crate) order: i64,
+    pub(crate) areaup: f64,
+    pub(crate) is_outlet: bool,
+    pub(crate) chn_order: i64,
+    /// The line of the table the link was read from, counting the header as line 1.
+    pub(crate) line: usize,
+}
+
+/// Reads the links of a network table written by this tool. Only the columns held by `NetwLink`
+/// are required, so e.g. a table without `area_error` is read alike.
+pub(crate) fn read_netw(file_path: &str) -> Result<Vec<NetwLink>, Error> {
+    let contents = fs::read_to_string(file_path)?;
+    let mut lines = contents.lines();
+    let header: Vec<&str> = lines
+        .next()
+        .unwrap_or("")
+        .split('\t')
+        .map(|h| h.trim())
+        .collect();
+    let names = [
+        "id",
+        "topaz_id",
+        "ds_x",
+        "ds_y",
+        "us_x",
+        "us_y",
+        "inflow0_id",
+        "inflow1_id",
+        "inflow2_id",
+        "length_m",
+        "drop_m",
+        "order",
+        "areaup",
+        "is_outlet",
+        "chn_order",
+    ];
+    let mut columns = [0usize; 15];
+    for (i, name) in names.iter().enumerate() {
+        columns[i] = match header.iter().position(|h| h == name) {
+            Some(col) => col,
+            None => {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "The channel network table (--netw) {} has no {} column.",
+                        file_path, name
+                    ),
+                ))
+            }
+        };
+    }
+    let mut links = vec![];
+    for (i, line) in lines.enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+        let invalid = || {
+            Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "Invalid link on line {} of the channel network table (--netw) {}.",
+                    i + 2,
+                    file_path
+                ),
+            )
+        };
+        let int = |k: usize| -> Result<i64, Error> {
+            fields
+                .get(columns[k])
+                .and_then(|f| f.parse::<i64>().ok())
+                .ok_or_else(invalid)
+        };
+        let float = |k: usize| -> Result<f64, Error> {
+            fields
+                .get(columns[k])
+                .and_then(|f| f.parse::<f64>().ok())
+                .ok_or_else(invalid)
+        };
+        let is_outlet = match fields.get(columns[13]).map(|f| f.to_lowercase()) {
+            Some(f) if f == "true" || f == "1" => true,
+            Some(f) if f == "false" || f == "0" => false,
+            _ => return Err(invalid()),
+        };
+        links.push(NetwLink {
+            id: int(0)?,
+            topaz_id: int(1)?,
+            ds: (int(2)? as isize, int(3)? as isize),
+            us: (int(4)? as isize, int(5)? as isize),
+            inflows: [int(6)?, int(7)?, int(8)?],
+            length_m: float(9)?,
+            drop_m: float(10)?,
+            order: int(11)?,
+            areaup: float(12)?,
+            is_outlet,
+            chn_order: int(14)?,
+            line: i + 2,
+        });
+    }
+    Ok(links)
+}
+
+/// Returns the number of cells drained by each link, i.e. its own cells (`local_cells`) plus those
+/// of all upstream links. Links are visited in channel routing order, so that every link's inflows
+/// are totalled before the link itself.
+fn cumulative_link_cells(links: &[Link], local_cells: &[usize]) -> Vec<usize> {
+    let mut routing: Vec<usize> = (0..links.len()).collect();
+    routing.sort_by_key(|&i| links[i].chn_order);
+    let mut cumulative = local_cells.to_vec();
+    for i in routing {
+        for inflow in [
+            links[i].inflow0_id,
+            links[i].inflow1_id,
+            links[i].inflow2_id,
+        ] {
+            if inflow >= 0 {
+                cumulative[i] += cumulative[inflow as usize];
+            }
+        }
+    }
+    cumulative
+}
+
+/// Assigns each link its Strahler stream order from the link graph: headwaters are order 1, and a
+/// link takes the highest order of its inflows, plus one when two or more inflows share it. Links are
+/// visited once all of their inflows have been, so those on a cycle, which `assign_chn_order`
+/// rejects, are left as they were.
+fn assign_strahler_order(links: &mut [Link]) {
+    let mut downstream: Vec<Option<usize>> = vec![None; links.len()];
+    let mut pending_inflows = vec![0usize; links.len()];
+    for (i, link) in links.iter().enumerate() {
+        for inflow in [link.inflow0_id, link.inflow1_id, link.inflow2_id] {
+            if inflow >= 0 {
+                downstream[inflow as usize] = Some(i);
+                pending_inflows[i] += 1;
+            }
+        }
+    }
+
+    let mut ready: Vec<usize> = (0..links.len())
+        .filter(|&i| pending_inflows[i] == 0)
+        .collect();
+    while let Some(i) = ready.pop() {
+        let inflow_orders: Vec<u8> = [
+            links[i].inflow0_id,
+            links[i].inflow1_id,
+            links[i].inflow2_id,
+        ]
+        .iter()
+        .filter(|&&inflow| inflow >= 0)
+        .map(|&inflow| links[inflow as usize].order)
+        .collect();
+        links[i].order = match inflow_orders.iter().max() {
+            None => 1,
+            Some(&max) if inflow_orders.iter().filter(|&&o| o == max).count() > 1 => {
+                max.saturating_add(1)
+            }
+            Some(&max) => max,
+        };
+        if let Some(ds) = downstream[i] {
+            pending_inflows[ds] -= 1;
+            if pending_inflows[ds] == 0 {
+                ready.push(ds);
+            }
+        }
+    }
+}
+
+/// Assigns each link its WEPP channel routing sequence number with a topological sort of the
+/// link graph, so that every link is numbered after all of its inflows (1 = most upstream, the
+/// outlet last). Among the links ready to be numbered, the lowest TOPAZ ID goes first.
+fn assign_chn_order(links: &mut [Link]) -> Result<(), Error> {
+    let mut downstream: Vec<Option<usize>> = vec![None; links.len()];
+    let mut pending_inflows = vec![0usize; links.len()];
+    for (i, link) in links.iter().enumerate() {
+        for inflow in [link.inflow0_id, link.inflow1_id, link.inflow2_id] {
+            if inflow >= 0 {
+                downstream[inflow as usize] = Some(i);
+                pending_inflows[i] += 1;
+            }
+        }
+    }
+
+    let mut ready = BinaryHeap::new();
+    for (i, link) in links.iter().enumerate() {
+        if pending_inflows[i] == 0 {
+            ready.push(Reverse((link.topaz_id, i)));
+        }
+    }
+
+    let mut next_order = 1;
+    while let Some(Reverse((_, i))) = ready.pop() {
+        links[i].chn_order = next_order;
+        next_order += 1;
+        if let Some(ds) = downstream[i] {
+            pending_inflows[ds] -= 1;
+            if pending_inflows[ds] == 0 {
+                ready.push(Reverse((links[ds].topaz_id, ds)));
+            }
+        }
+    }
+
+    if next_order as usize <= links.len() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "Unable to determine the channel routing order; the link network contains a cycle",
+        ));
+    }
     Ok(())
 }
 
+/// The contents of the `--summary` JSON file, filled in as the run progresses so that a failed run
+/// reports as much as was known when it stopped.
+#[derive(Default)]
+struct RunSummary {
+    num_links: Option<usize>,
+    num_hillslopes: Option<usize>,
+    channel_length_m: Option<f64>,
+    watershed_area_m2: Option<f64>,
+    outlet: Option<JsonValue>,
+    min_topaz_id: Option<i32>,
+    max_topaz_id: Option<i32>,
+    links_per_order: Option<BTreeMap<u8, usize>>,
+    phase_seconds: JsonMap<String, JsonValue>,
+    outputs: JsonMap<String, JsonValue>,
+}
+
+impl RunSummary {
+    /// Records the time taken by a phase that began at `start`.
+    fn phase(&mut self, name: &str, start: Instant) {
+        self.phase_seconds
+            .insert(name.to_string(), json!(start.elapsed().as_secs_f64()));
+    }
+
+    /// Records an output file once it has been written.
+    fn output(&mut self, name: &str, file: &str) {
+        self.outputs.insert(name.to_string(), json!(file));
+    }
+
+    fn to_json(&self, tool: &str, error: Option<&Error>, start: Instant) -> JsonValue {
+        json!({
+            "tool": tool,
+            "status": if error.is_none() { "success" } else { "failure" },
+            "error": error.map(|e| e.to_string()),
+            "num_links": self.num_links,
+            "num_hillslopes": self.num_hillslopes,
+            "channel_length_m": self.channel_length_m,
+            "watershed_area_m2": self.watershed_area_m2,
+            "outlet": self.outlet,
+            "min_topaz_id": self.min_topaz_id,
+            "max_topaz_id": self.max_topaz_id,
+            "links_per_order": self.links_per_order.as_ref().map(|counts| {
+                counts
+                    .iter()
+                    .map(|(order, n)| (order.to_string(), json!(n)))
+                    .collect::<JsonMap<String, JsonValue>>()
+            }),
+            "phase_seconds": self.phase_seconds,
+            "total_seconds": start.elapsed().as_secs_f64(),
+            "outputs": self.outputs,
+        })
+    }
+}
+
+/// The parsed arguments of a delineation run, with file paths resolved against the working
+/// directory.
+struct DelineationOptions {
+    dem_file: String,
+    d8_file: String,
+    streams_file: String,
+    pourpts_file: String,
+    watershed_file: String,
+    chnjnt_file: String,
+    order_file: String,
+    flow_accum_file: String,
+    subwta_file: String,
+    netw_file: String,
+    chn_order_file: String,
+    esri_style: bool,
+    output_mode: String,
+    strict_pour_pt: bool,
+    topaz_start: i32,
+    topaz_stride: i32,
+    max_area_error: f64,
+}
+
+/// The outputs of a delineation, computed in memory before any of them is written.
+struct Delineation {
+    links: Vec<Link>,
+    subwta: Raster,
+    chn_order: Option<Raster>,
+    with_area_error: bool,
+    elapsed_time: String,
+}
+
+impl Delineation {
+    /// Writes the network table and the rasters. Every output is staged and only moved into place
+    /// once all have been written, so a failure part way leaves the earlier outputs as they were.
+    fn write(
+        mut self,
+        options: &DelineationOptions,
+        verbose: bool,
+        summary: &mut RunSummary,
+    ) -> Result<(), Error> {
+        let start = Instant::now();
+        if verbose {
+            println!("Writing network links to {}.", options.netw_file);
+        }
+        let mut staged = StagedOutputs::new();
+        write_links_to_tsv(
+            &self.links,
+            &staged.stage(&options.netw_file),
+            self.with_area_error,
+        )?;
+
+        if verbose {
+            println!("Saving data...")
+        };
+        self.subwta.file_name = staged.stage(&options.subwta_file);
+        self.subwta.write()?;
+        if verbose {
+            println!("Output file written")
+        }
+
+        if let Some(chn_order) = &mut self.chn_order {
+            if verbose {
+                println!(
+                    "Writing channel routing order to {}.",
+                    options.chn_order_file
+                );
+            }
+            chn_order.file_name = staged.stage(&options.chn_order_file);
+            chn_order.write()?;
+        }
+
+        staged.commit()?;
+        summary.output("netw", &options.netw_file);
+        summary.output("subwta", &options.subwta_file);
+        if self.chn_order.is_some() {
+            summary.output("chn_order", &options.chn_order_file);
+        }
+
+        summary.phase("write_outputs", start);
+        if verbose {
+            let elapsed = start.elapsed();
+            println!("Phase 6: Write files {:.2?}.", elapsed);
+
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", self.elapsed_time)
+            );
+        }
+        Ok(())
+    }
+}
+
 pub struct HillslopesTopaz {
     name: String,
     description: String,
@@ -199,10 +779,10 @@ impl HillslopesTopaz {
         parameters.push(ToolParameter {
             name: "Input Stream Order File".to_owned(),
             flags: vec!["--order".to_owned()],
-            description: "Input stream order raster file.".to_owned(),
+            description: "Optional input stream order raster file; the Strahler order is computed from the link network when it is not specified.".to_owned(),
             parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
             default_value: None,
-            optional: false,
+            optional: true,
         });
 
         parameters.push(ToolParameter {
@@ -223,6 +803,106 @@ impl HillslopesTopaz {
             optional: false,
         });
 
+        parameters.push(ToolParameter {
+            name: "Output Channel Routing Order File".to_owned(),
+            flags: vec!["--chn_order".to_owned()],
+            description: "Optional output raster of each channel cell's WEPP routing sequence number (1 = most upstream, outlet last).".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Mode".to_owned(),
+            flags: vec!["--output_mode".to_owned()],
+            description:
+                "Cells written to the TOPAZ IDs raster: 'all', 'channels', or 'hillslopes'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "all".to_owned(),
+                "channels".to_owned(),
+                "hillslopes".to_owned(),
+            ]),
+            default_value: Some("all".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Require pour point exactly on a stream cell?".to_owned(),
+            flags: vec!["--strict_pour_pt".to_owned()],
+            description: "Fail unless the pour point falls exactly on a stream cell within the watershed, rather than using an adjacent stream cell.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Outlet Channel TOPAZ ID".to_owned(),
+            flags: vec!["--topaz_start".to_owned()],
+            description: "TOPAZ ID of the outlet channel; must end in 4.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("24".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Channel TOPAZ ID Stride".to_owned(),
+            flags: vec!["--topaz_stride".to_owned()],
+            description:
+                "Increment between successive channel TOPAZ IDs; must be a multiple of 10."
+                    .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Validate an existing TOPAZ IDs file?".to_owned(),
+            flags: vec!["--validate_only".to_owned()],
+            description: "Check an existing --subwta raster and --netw table against the inputs instead of regenerating them; no outputs are overwritten.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Violations File".to_owned(),
+            flags: vec!["--violations".to_owned()],
+            description: "Output TSV file of the violations found with --validate_only.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Flow Accumulation File".to_owned(),
+            flags: vec!["--flow_accum".to_owned()],
+            description: "Optional flow accumulation raster (in cells) used to check each link's cumulative drainage area.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Relative Area Error".to_owned(),
+            flags: vec!["--max_area_error".to_owned()],
+            description: "Relative discrepancy from the flow accumulation above which a warning is printed, as a fraction.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.05".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary File".to_owned(),
+            flags: vec!["--summary".to_owned()],
+            description:
+                "Optional output JSON summary of the run, written on success and on failure."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let e = format!("{}", env::current_exe().unwrap().display());
         let mut parent = env::current_exe().unwrap();
@@ -236,7 +916,7 @@ impl HillslopesTopaz {
         if e.contains(".exe") {
             short_exe += ".exe";
         }
-        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=dem.tif --d8_pntr=d8.tif --streams=streams.tif --pour_pts=outlet.shp --watershed=basin.tif --chnjnt=junctions.tif --order=order.tif --subwta=subwta.tif --netw=netw.tsv", short_exe, name).replace("*", &sep);
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=dem.tif --d8_pntr=d8.tif --streams=streams.tif --pour_pts=outlet.shp --watershed=basin.tif --chnjnt=junctions.tif --order=order.tif --subwta=subwta.tif --netw=netw.tsv --chn_order=chn_order.tif", short_exe, name).replace("*", &sep);
 
         HillslopesTopaz {
             name: name,
@@ -246,100 +926,6 @@ impl HillslopesTopaz {
             example_usage: usage,
         }
     }
-
-    /// Locate pour point from vector or raster input
-    fn locate_pour_point(
-        &self,
-        pourpts_file: &str,
-        pntr: &Raster,
-    ) -> Result<(isize, isize), Error> {
-        let mut pour_point = (-1, -1);
-        let mut count = 0;
-
-        if pourpts_file.to_lowercase().ends_with(".shp") {
-            let pourpts = Shapefile::read(pourpts_file)?;
-            if pourpts.header.shape_type.base_shape_type() != ShapeType::Point {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "Pour points must be point type",
-                ));
-            }
-
-            for i in 0..pourpts.num_records {
-                let record = pourpts.get_record(i);
-                let row = pntr.get_row_from_y(record.points[0].y);
-                let col = pntr.get_column_from_x(record.points[0].x);
-                pour_point = (row, col);
-                count += 1;
-            }
-        } else if pourpts_file.to_lowercase().ends_with(".geojson")
-            || pourpts_file.to_lowercase().ends_with(".json")
-        {
-            let geojson_str = std::fs::read_to_string(pourpts_file)?;
-            let gj: GeoJson = geojson_str
-                .parse()
-                .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
-
-            if let GeoJson::FeatureCollection(fc) = gj {
-                for feature in fc.features {
-                    if let Some(Geometry { value, .. }) = feature.geometry {
-                        match value {
-                            Value::Point(pt) => {
-                                let (x, y) = (pt[0], pt[1]);
-                                let row = pntr.get_row_from_y(y);
-                                let col = pntr.get_column_from_x(x);
-                                pour_point = (row, col);
-                                count += 1;
-                            }
-                            Value::MultiPoint(pts) => {
-                                for pt in pts {
-                                    let (x, y) = (pt[0], pt[1]);
-                                    let row = pntr.get_row_from_y(y);
-                                    let col = pntr.get_column_from_x(x);
-                                    pour_point = (row, col);
-                                    count += 1;
-                                }
-                            }
-                            _ => continue,
-                        }
-                    }
-                }
-            }
-        } else {
-            // Raster
-            let pourpts = Raster::new(pourpts_file, "r")?;
-            if pourpts.configs.rows != pntr.configs.rows
-                || pourpts.configs.columns != pntr.configs.columns
-            {
-                return Err(Error::new(
-                    ErrorKind::InvalidInput,
-                    "Pour points raster must match DEM dimensions",
-                ));
-            }
-
-            for row in 0..pntr.configs.rows as isize {
-                for col in 0..pntr.configs.columns as isize {
-                    if pourpts.get_value(row, col) > 0.0
-                        && pourpts.get_value(row, col) != pourpts.configs.nodata
-                    {
-                        pour_point = (row, col);
-                        count += 1;
-                    }
-                }
-            }
-        }
-
-        if count == 0 {
-            Err(Error::new(ErrorKind::InvalidInput, "No pour points found"))
-        } else if count > 1 {
-            Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Exactly one pour point required",
-            ))
-        } else {
-            Ok(pour_point)
-        }
-    }
 }
 
 impl WhiteboxTool for HillslopesTopaz {
@@ -373,8 +959,68 @@ impl WhiteboxTool for HillslopesTopaz {
     fn run<'a>(
         &self,
         args: Vec<String>,
-        _working_directory: &'a str,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        // The summary is also written when the run fails, so its path is read first.
+        let mut summary_file = String::new();
+        for i in 0..args.len() {
+            let arg = args[i].replace(['"', '\''], "");
+            let vec = arg.split('=').collect::<Vec<&str>>();
+            let flag = vec[0].to_lowercase();
+            if flag == "-summary" || flag == "--summary" {
+                summary_file = if vec.len() > 1 {
+                    vec[1].to_string()
+                } else {
+                    args.get(i + 1).cloned().unwrap_or_default()
+                };
+            }
+        }
+
+        let start = Instant::now();
+        let mut summary = RunSummary::default();
+        let result = self.delineate(args, working_directory, verbose, &mut summary);
+        if summary_file.is_empty() {
+            return result;
+        }
+        let summary_file = resolve_path(working_directory, &summary_file);
+        let summary = summary.to_json(&self.get_tool_name(), result.as_ref().err(), start);
+        let mut staged = StagedOutputs::new();
+        let written = serde_json::to_string_pretty(&summary)
+            .map_err(Error::other)
+            .and_then(|text| fs::write(staged.stage(&summary_file), text))
+            .and_then(|_| staged.commit());
+        match (result, written) {
+            (Ok(_), Ok(_)) => {
+                if verbose {
+                    println!("Summary written to {}.", summary_file);
+                }
+                Ok(())
+            }
+            (Ok(_), Err(e)) => Err(Error::new(
+                e.kind(),
+                format!("Unable to write summary file {}: {}", summary_file, e),
+            )),
+            (Err(err), written) => {
+                if let Err(e) = written {
+                    println!(
+                        "Warning: unable to write summary file {}: {}",
+                        summary_file, e
+                    );
+                }
+                Err(err)
+            }
+        }
+    }
+}
+
+impl HillslopesTopaz {
+    fn delineate<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
         verbose: bool,
+        summary: &mut RunSummary,
     ) -> Result<(), Error> {
         let start0 = Instant::now();
 
@@ -388,7 +1034,16 @@ impl WhiteboxTool for HillslopesTopaz {
         let mut order_file = String::new();
         let mut subwta_file = String::new();
         let mut netw_file = String::new();
+        let mut chn_order_file = String::new();
         let mut esri_style = false;
+        let mut output_mode = String::from("all");
+        let mut strict_pour_pt = false;
+        let mut validate_only = false;
+        let mut violations_file = String::new();
+        let mut topaz_start = 24i32;
+        let mut topaz_stride = 10i32;
+        let mut flow_accum_file = String::new();
+        let mut max_area_error = 0.05f64;
 
         if args.len() == 0 {
             return Err(Error::new(
@@ -460,83 +1115,328 @@ impl WhiteboxTool for HillslopesTopaz {
                 } else {
                     args[i + 1].to_string()
                 };
+            } else if flag_val == "-chn_order" {
+                chn_order_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
             } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
                 if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
                     esri_style = true;
                 }
+            } else if flag_val == "-output_mode" {
+                output_mode = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-strict_pour_pt" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    strict_pour_pt = true;
+                }
+            } else if flag_val == "-validate_only" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    validate_only = true;
+                }
+            } else if flag_val == "-violations" {
+                violations_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-topaz_start" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                topaz_start = value.trim().parse::<i32>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --topaz_start value '{}'.", value),
+                    )
+                })?;
+            } else if flag_val == "-topaz_stride" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                topaz_stride = value.trim().parse::<i32>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --topaz_stride value '{}'.", value),
+                    )
+                })?;
+            } else if flag_val == "-flow_accum" {
+                flow_accum_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-max_area_error" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                max_area_error = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --max_area_error value '{}'.", value),
+                    )
+                })?;
             }
         }
 
-        if verbose {
-            let tool_name = self.get_tool_name();
-            let welcome_len = format!("* Welcome to {} *", tool_name).len().max(28);
-            // 28 = length of the 'Powered by' by statement.
-            println!("{}", "*".repeat(welcome_len));
-            println!(
-                "* Welcome to {} {}*",
-                tool_name,
-                " ".repeat(welcome_len - 15 - tool_name.len())
-            );
-            println!(
-                "* Powered by WhiteboxTools {}*",
-                " ".repeat(welcome_len - 28)
-            );
-            println!("* www.whiteboxgeo.com {}*", " ".repeat(welcome_len - 23));
-            println!("{}", "*".repeat(welcome_len));
+        let output_mode = output_mode.trim().to_string();
+        if output_mode != "all" && output_mode != "channels" && output_mode != "hillslopes" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Unrecognized --output_mode '{}'; expected 'all', 'channels', or 'hillslopes'.",
+                    output_mode
+                ),
+            ));
+        }
+
+        // Channels must keep ending in 4 for the hillslope IDs (channel - 1, 2, or 3) to hold.
+        if topaz_start <= 0 || topaz_start % 10 != 4 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "--topaz_start must be a positive integer ending in 4; got {}.",
+                    topaz_start
+                ),
+            ));
+        }
+        if topaz_stride <= 0 || topaz_stride % 10 != 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "--topaz_stride must be a positive multiple of 10; got {}.",
+                    topaz_stride
+                ),
+            ));
+        }
+
+        if max_area_error.is_nan() || max_area_error < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "--max_area_error must be a non-negative fraction; got {}.",
+                    max_area_error
+                ),
+            ));
+        }
+        if !flow_accum_file.is_empty() && output_mode == "channels" {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "--flow_accum cannot be checked in the 'channels' output mode, which does not label hillslopes.",
+            ));
         }
 
+        print_tool_banner(&self.get_tool_name(), verbose);
+
         if verbose {
             println!("Reading data...")
         };
 
         // Add working directory to file paths
+        dem_file = resolve_path(working_directory, &dem_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        pourpts_file = resolve_path(working_directory, &pourpts_file);
+        watershed_file = resolve_path(working_directory, &watershed_file);
+        chnjnt_file = resolve_path(working_directory, &chnjnt_file);
+        order_file = resolve_path(working_directory, &order_file);
+        subwta_file = resolve_path(working_directory, &subwta_file);
+        netw_file = resolve_path(working_directory, &netw_file);
+        chn_order_file = resolve_path(working_directory, &chn_order_file);
+        violations_file = resolve_path(working_directory, &violations_file);
+        flow_accum_file = resolve_path(working_directory, &flow_accum_file);
+
+        // Check an existing subwta raster and network table rather than regenerating them.
+        if validate_only {
+            if violations_file.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "An output violations file (--violations) must be specified with --validate_only.",
+                ));
+            }
+
+            if verbose {
+                println!("Validating {} and {}.", subwta_file, netw_file);
+            }
+            check_rasters_share_geometry(
+                &[
+                    ("--d8_pntr", &d8_file),
+                    ("--streams", &streams_file),
+                    ("--watershed", &watershed_file),
+                    ("--subwta", &subwta_file),
+                ],
+                false,
+            )?;
+            let d8_pntr = Raster::new(&d8_file, "r")?;
+            let streams = Raster::new(&streams_file, "r")?;
+            let watershed = Raster::new(&watershed_file, "r")?;
+            let subwta = Raster::new(&subwta_file, "r")?;
+            let netw_ids: Vec<i32> = read_netw(&netw_file)?
+                .iter()
+                .map(|link| link.topaz_id as i32)
+                .collect();
+
+            let start = Instant::now();
+
+            let violations = validate_subwta(
+                &subwta,
+                &d8_pntr,
+                &streams,
+                &watershed,
+                &netw_ids,
+                &d8_pntr_matches(esri_style),
+            )?;
+            let mut staged = StagedOutputs::new();
+            write_violations_to_tsv(&violations, &subwta, &staged.stage(&violations_file))?;
+            staged.commit()?;
+            summary.phase("validation", start);
+            summary.output("violations", &violations_file);
+
+            if verbose {
+                for kind in VIOLATION_KINDS {
+                    let n = violations.iter().filter(|v| v.kind == kind).count();
+                    if n > 0 {
+                        println!("{}: {}", kind, n);
+                    }
+                }
+                println!("Violations written to {}.", violations_file);
+                println!(
+                    "{}",
+                    &format!(
+                        "Elapsed Time (excluding I/O): {}",
+                        get_formatted_elapsed_time(start)
+                    )
+                );
+            }
+
+            if !violations.is_empty() {
+                return Err(Error::new(
+                    ErrorKind::InvalidData,
+                    format!(
+                        "Validation found {} violations; see {}",
+                        violations.len(),
+                        violations_file
+                    ),
+                ));
+            }
+            if verbose {
+                println!("No violations found.");
+            }
+            return Ok(());
+        }
+
+        let options = DelineationOptions {
+            dem_file,
+            d8_file,
+            streams_file,
+            pourpts_file,
+            watershed_file,
+            chnjnt_file,
+            order_file,
+            flow_accum_file,
+            subwta_file,
+            netw_file,
+            chn_order_file,
+            esri_style,
+            output_mode,
+            strict_pour_pt,
+            topaz_start,
+            topaz_stride,
+            max_area_error,
+        };
+        let delineation = self.compute(&options, start0, verbose, summary)?;
+        delineation.write(&options, verbose, summary)
+    }
+
+    /// Reads the inputs and labels the channels and hillslopes, without writing any output.
+    fn compute(
+        &self,
+        options: &DelineationOptions,
+        start0: Instant,
+        verbose: bool,
+        summary: &mut RunSummary,
+    ) -> Result<Delineation, Error> {
+        let &DelineationOptions {
+            ref dem_file,
+            ref d8_file,
+            ref streams_file,
+            ref pourpts_file,
+            ref watershed_file,
+            ref chnjnt_file,
+            ref order_file,
+            ref flow_accum_file,
+            ref subwta_file,
+            ref chn_order_file,
+            esri_style,
+            ref output_mode,
+            strict_pour_pt,
+            topaz_start,
+            topaz_stride,
+            max_area_error,
+            ..
+        } = options;
+
+        if verbose {
+            println!("Checking grid alignment.");
+        }
+
+        // Validate grid alignment from the raster headers before any pixels are read, including the
+        // stream order raster, which is read at link heads using DEM coordinates.
+        let mut inputs = vec![
+            ("--dem", dem_file),
+            ("--d8_pntr", d8_file),
+            ("--streams", streams_file),
+            ("--watershed", watershed_file),
+            ("--chnjnt", chnjnt_file),
+        ];
+        if !order_file.is_empty() {
+            inputs.push(("--order", order_file));
+        }
+        if !flow_accum_file.is_empty() {
+            inputs.push(("--flow_accum", flow_accum_file));
+        }
+        check_rasters_share_geometry(&inputs, false)?;
+
         if verbose {
             println!("Reading {} file.", dem_file);
         }
-        let dem = Raster::new(&dem_file, "r")?;
+        let dem = Raster::new(dem_file, "r")?;
 
         if verbose {
             println!("Reading {} file.", d8_file);
         }
-        let d8_pntr = Raster::new(&d8_file, "r")?;
+        let d8_pntr = Raster::new(d8_file, "r")?;
 
         if verbose {
             println!("Reading {} file.", streams_file);
         }
-        let streams = Raster::new(&streams_file, "r")?;
+        let streams = Raster::new(streams_file, "r")?;
         if verbose {
             println!("Reading {} file.", watershed_file);
         }
-        let watershed = Raster::new(&watershed_file, "r")?;
+        let watershed = Raster::new(watershed_file, "r")?;
         if verbose {
             println!("Reading {} file.", chnjnt_file);
         }
-        let chnjnt = Raster::new(&chnjnt_file, "r")?;
-        if order_file.is_empty() {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Stream order raster must be specified using the --order flag.",
-            ));
-        }
-        if verbose {
-            println!("Reading {} file.", order_file);
-        }
-        let order = Raster::new(&order_file, "r")?;
+        let chnjnt = Raster::new(chnjnt_file, "r")?;
+        let order = if order_file.is_empty() {
+            None
+        } else {
+            if verbose {
+                println!("Reading {} file.", order_file);
+            }
+            Some(Raster::new(order_file, "r")?)
+        };
+        let flow_accum = if flow_accum_file.is_empty() {
+            None
+        } else {
+            if verbose {
+                println!("Reading {} file.", flow_accum_file);
+            }
+            Some(Raster::new(flow_accum_file, "r")?)
+        };
 
         let start = Instant::now();
 
-        if verbose {
-            println!("Checking grid alignment.");
-        }
-
-        // Validate grid alignment
-        if !rasters_share_geometry(&[&dem, &d8_pntr, &streams, &watershed, &chnjnt]) {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Input rasters must share geometry",
-            ));
-        }
-
         // Validate chnjnt values
         if verbose {
             println!("Checking channel junction map for 3 or more inflows.");
@@ -550,7 +1450,7 @@ impl WhiteboxTool for HillslopesTopaz {
                 if val != chnjnt.configs.nodata && val > 3.0 {
                     return Err(Error::new(
                         ErrorKind::InvalidInput,
-                        "chnjnt values must be 0, 1, 2, or 3",
+                        "--chnjnt values must be 0, 1, 2, or 3",
                     ));
                 }
             }
@@ -567,34 +1467,7 @@ impl WhiteboxTool for HillslopesTopaz {
         let dx = [1, 1, 1, 0, -1, -1, -1, 0];
         let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
 
-        // Create a mapping from the pointer values to cells offsets.
-        // This may seem wasteful, using only 8 of 129 values in the array,
-        // but the mapping method is far faster than calculating z.ln() / ln(2.0).
-        // It's also a good way of allowing for different point styles.
-        let mut pntr_matches: [usize; 129] = [8usize; 129];
-        if !esri_style {
-            // This maps Whitebox-style D8 pointer values
-            // onto the cell offsets in dx and dy.
-            pntr_matches[1] = 0;
-            pntr_matches[2] = 1;
-            pntr_matches[4] = 2;
-            pntr_matches[8] = 3;
-            pntr_matches[16] = 4;
-            pntr_matches[32] = 5;
-            pntr_matches[64] = 6;
-            pntr_matches[128] = 7;
-        } else {
-            // This maps Esri-style D8 pointer values
-            // onto the cell offsets in dx and dy.
-            pntr_matches[1] = 1;
-            pntr_matches[2] = 2;
-            pntr_matches[4] = 3;
-            pntr_matches[8] = 4;
-            pntr_matches[16] = 5;
-            pntr_matches[32] = 6;
-            pntr_matches[64] = 7;
-            pntr_matches[128] = 0;
-        }
+        let pntr_matches = d8_pntr_matches(esri_style);
 
         // validate d8_pntr values
         // this avoids having to check after every direction check and reduces cyclomatic complexity
@@ -606,43 +1479,83 @@ impl WhiteboxTool for HillslopesTopaz {
                 if watershed.get_value(row, col) == watershed.configs.nodata {
                     continue; // Skip cells outside watershed
                 }
-                let val = d8_pntr.get_value(row, col) as usize;
-                if pntr_matches[val] >= 8 {
+                let val = d8_pntr.get_value(row, col) as usize;
+                if pntr_matches[val] >= 8 {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Invalid D8 pointer (--d8_pntr) value {} at ({}, {})",
+                            val, row, col
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // Locate pour point
+        if verbose {
+            println!("Locating pour point.");
+        }
+        let mut pour_point = locate_pour_point(pourpts_file, &dem)?;
+        let is_stream = |row: isize, col: isize| {
+            streams.get_value(row, col) > 0.0 && streams.get_value(row, col) != streams_nodata
+        };
+        let in_watershed = |row: isize, col: isize| {
+            watershed.get_value(row, col) > 0.0
+                && watershed.get_value(row, col) != watershed.configs.nodata
+        };
+        if strict_pour_pt {
+            if !is_stream(pour_point.0, pour_point.1) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The pour point (--pour_pts) must be on a stream cell (--streams).",
+                ));
+            }
+            if !in_watershed(pour_point.0, pour_point.1) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The pour point (--pour_pts) must be within the watershed (--watershed).",
+                ));
+            }
+        } else if !is_stream(pour_point.0, pour_point.1)
+            || !in_watershed(pour_point.0, pour_point.1)
+        {
+            // The located cell drains into this cell, if its pointer is valid.
+            let z = d8_pntr.get_value(pour_point.0, pour_point.1);
+            let drains_to = if z > 0.0 && z <= 128.0 && pntr_matches[z as usize] < 8 {
+                let dir = pntr_matches[z as usize];
+                Some((pour_point.0 + dy[dir], pour_point.1 + dx[dir]))
+            } else {
+                None
+            };
+            match adjacent_stream_cell(pour_point, drains_to, |row, col| {
+                is_stream(row, col) && in_watershed(row, col)
+            }) {
+                Some((cell, rule)) => {
+                    if verbose {
+                        println!(
+                            "Pour point at (row {}, column {}) is not a stream cell within the watershed; using {} (row {}, column {}).",
+                            pour_point.0, pour_point.1, rule, cell.0, cell.1
+                        );
+                    }
+                    pour_point = cell;
+                }
+                None => {
                     return Err(Error::new(
                         ErrorKind::InvalidInput,
-                        format!("Invalid D8 pointer value {} at ({}, {})", val, row, col),
+                        "The pour point (--pour_pts) must be on or adjacent to a stream cell (--streams) within the watershed (--watershed).",
                     ));
                 }
             }
         }
 
-        // Locate pour point
-        if verbose {
-            println!("Locating pour point.");
-        }
-        let pour_point = self.locate_pour_point(&pourpts_file, &dem)?;
-        if streams.get_value(pour_point.0, pour_point.1) <= 0.0
-            || streams.get_value(pour_point.0, pour_point.1) == streams_nodata
-        {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Pour point must be on a stream cell",
-            ));
-        }
-        if watershed.get_value(pour_point.0, pour_point.1) <= 0.0 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Pour point must be within watershed",
-            ));
-        }
-
         // Initialize output raster
         // whitebox_raster::Raster treats data as f64 then writes to the config.data_type
         if verbose {
             println!("Initializing output raster.");
         }
 
-        let mut subwta = Raster::initialize_using_file(&subwta_file, &d8_pntr);
+        let mut subwta = Raster::initialize_using_file(subwta_file, &d8_pntr);
         subwta.configs.data_type = DataType::F32;
         subwta.configs.palette = "qual.plt".to_string();
         subwta.configs.photometric_interp = PhotometricInterpretation::Categorical;
@@ -650,6 +1563,13 @@ impl WhiteboxTool for HillslopesTopaz {
         subwta.configs.nodata = low_value;
         subwta.reinitialize_values(low_value);
 
+        summary.outlet = Some(json!({
+            "row": pour_point.0,
+            "column": pour_point.1,
+            "x": dem.get_x_from_column(pour_point.1),
+            "y": dem.get_y_from_row(pour_point.0),
+        }));
+        summary.phase("initialization", start0);
         if verbose {
             let elapsed = start0.elapsed();
             println!(
@@ -668,13 +1588,19 @@ impl WhiteboxTool for HillslopesTopaz {
             println!("Finding headwaters.");
         }
         let mut headwaters = Vec::new();
+        let mut watershed_cells = 0usize;
         for row in 0..rows {
             for col in 0..columns {
-                if chnjnt[(row, col)] == 0.0 && watershed[(row, col)] == 1.0 {
+                if watershed[(row, col)] != 1.0 {
+                    continue;
+                }
+                watershed_cells += 1;
+                if chnjnt[(row, col)] == 0.0 {
                     headwaters.push((row, col));
                 }
             }
         }
+        summary.watershed_area_m2 = Some(watershed_cells as f64 * cellsize_x * cellsize_y);
 
         if verbose {
             println!("Found {} headwaters.", headwaters.len());
@@ -756,19 +1682,23 @@ impl WhiteboxTool for HillslopesTopaz {
                 let row_n = current.0 + dy[c];
                 let col_n = current.1 + dx[c];
 
-                // Check bounds
-                if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
-                    return Err(Error::new(
-                        ErrorKind::InvalidInput,
-                        "Pointer direction leads outside raster bounds",
-                    ));
-                }
-
-                // Check if next cell is in watershed
-                if watershed[(row_n, col_n)] != 1.0 {
+                // The outlet link ends at the pour point above, so it never takes the step out of
+                // the basin at its mouth, even when the pour point is on the edge of the watershed
+                // or the raster. Any other link leaving them means the mask and pointer disagree.
+                let leaves = if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
+                    Some("raster bounds")
+                } else if watershed[(row_n, col_n)] != 1.0 {
+                    Some("watershed")
+                } else {
+                    None
+                };
+                if let Some(leaves) = leaves {
                     return Err(Error::new(
                         ErrorKind::InvalidInput,
-                        "Pointer direction leads outside watershed",
+                        format!(
+                            "Pointer direction leads outside {} at (row {}, column {}) on a channel that does not pass the pour point (row {}, column {}).",
+                            leaves, current.0, current.1, pour_point.0, pour_point.1
+                        ),
                     ));
                 }
 
@@ -776,6 +1706,8 @@ impl WhiteboxTool for HillslopesTopaz {
             }
         }
 
+        summary.num_links = Some(links.len());
+        summary.phase("build_links", start1);
         if verbose {
             let elapsed = start1.elapsed();
             println!(
@@ -846,9 +1778,24 @@ impl WhiteboxTool for HillslopesTopaz {
             link.drop_m = link.us_z - link.ds_z;
 
             // Set stream order if provided
-            link.order = order.get_value(link.us.0, link.us.1) as u8;
+            if let Some(order) = &order {
+                link.order = order.get_value(link.us.0, link.us.1) as u8;
+            }
+        }
+        if order.is_none() {
+            if verbose {
+                println!("Computing the Strahler order of each link (no --order raster).");
+            }
+            assign_strahler_order(&mut links);
         }
 
+        summary.channel_length_m = Some(links.iter().map(|link| link.length_m).sum());
+        let mut links_per_order = BTreeMap::new();
+        for link in &links {
+            *links_per_order.entry(link.order).or_insert(0usize) += 1;
+        }
+        summary.links_per_order = Some(links_per_order);
+        summary.phase("link_relationships", start2);
         if verbose {
             let elapsed = start2.elapsed();
             println!(
@@ -862,15 +1809,15 @@ impl WhiteboxTool for HillslopesTopaz {
         if verbose {
             println!("Assigning TOPAZ IDs to links.");
         }
-        let mut next_id = 24; // Starting TOPAZ ID
-                              // channel ids always end with 4 staring with 24
+        // channel ids always end with 4, starting with --topaz_start (24 by default)
+        let mut next_id = topaz_start;
 
         let mut outlet_idx: i32 = -1; // Index of the outlet link
         for i in 0..links.len() {
             if links[i].is_outlet {
                 outlet_idx = i as i32;
                 links[i].topaz_id = next_id;
-                next_id += 10;
+                next_id += topaz_stride;
                 break;
             }
         }
@@ -928,17 +1875,17 @@ impl WhiteboxTool for HillslopesTopaz {
                 if inflow0_angle < inflow1_angle {
                     links[inflow0_id].topaz_id = next_id;
                     queue.push_back(inflow0_id as usize);
-                    next_id += 10; // channels are enumerated by 10s
+                    next_id += topaz_stride; // channels are enumerated by the stride
                     links[inflow1_id].topaz_id = next_id;
                     queue.push_back(inflow1_id as usize);
-                    next_id += 10;
+                    next_id += topaz_stride;
                 } else {
                     links[inflow1_id].topaz_id = next_id;
                     queue.push_back(inflow1_id as usize);
-                    next_id += 10;
+                    next_id += topaz_stride;
                     links[inflow0_id].topaz_id = next_id;
                     queue.push_back(inflow0_id as usize);
-                    next_id += 10;
+                    next_id += topaz_stride;
                 }
             } else {
                 // handle thrid inflow
@@ -966,21 +1913,24 @@ impl WhiteboxTool for HillslopesTopaz {
                 // Assign TOPAZ IDs in clockwise order
                 links[inflows[0].0].topaz_id = next_id;
                 queue.push_back(inflows[0].0);
-                next_id += 10; // channels are enumerated by 10s
+                next_id += topaz_stride; // channels are enumerated by the stride
                 links[inflows[1].0].topaz_id = next_id;
                 queue.push_back(inflows[1].0);
-                next_id += 10;
+                next_id += topaz_stride;
                 links[inflows[2].0].topaz_id = next_id;
                 queue.push_back(inflows[2].0);
-                next_id += 10;
+                next_id += topaz_stride;
             }
         }
 
+        summary.phase("topaz_ids", start3);
         if verbose {
             let elapsed = start3.elapsed();
             println!("Phase 3: Assigned TOPAZ IDs in {:.2?}.", elapsed);
         }
 
+        assign_chn_order(&mut links)?;
+
         // Phase 4: Stamp channel topaz_ids in output raster
         let start4 = Instant::now();
         if verbose {
@@ -1005,6 +1955,7 @@ impl WhiteboxTool for HillslopesTopaz {
             }
         }
 
+        summary.phase("stamp_channels", start4);
         if verbose {
             let elapsed = start4.elapsed();
             println!(
@@ -1016,162 +1967,171 @@ impl WhiteboxTool for HillslopesTopaz {
         // Phase 5: flood fill hillslope values
         let start5 = Instant::now();
 
-        let mut subwta_counts = HashMap::new();
-        if verbose {
+        let mut subwta_counts: HashMap<i32, usize> = HashMap::new();
+        if verbose && output_mode != "channels" {
             println!("Flood filling hillslope values.");
         }
-        for row in 0..rows {
-            for col in 0..columns {
-                // check if not in watershed
-                if watershed[(row, col)] != 1.0 {
-                    continue;
-                }
-
-                // check if already labeled
-                if subwta[(row, col)] != low_value {
-                    continue;
-                }
-
-                // flood fill from this cell
-                let mut current = (row, col);
-                let mut found_topaz_id = 0.0;
-                while found_topaz_id == 0.0 {
-                    let dir_val = d8_pntr.get_value(current.0, current.1);
-                    let dir = dir_val as usize;
-                    let c = pntr_matches[dir];
-                    let row_n = current.0 + dy[c];
-                    let col_n = current.1 + dx[c];
-
-                    // Check bounds
-                    if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
-                        break; // Out of bounds
+        // channels mode only needs the stamped channel cells
+        if output_mode != "channels" {
+            for row in 0..rows {
+                for col in 0..columns {
+                    // check if not in watershed
+                    if watershed[(row, col)] != 1.0 {
+                        continue;
                     }
 
-                    // Check if next cell is in watershed
-                    if watershed[(row_n, col_n)] != 1.0 {
-                        break; // left the watershed
+                    // check if already labeled
+                    if subwta[(row, col)] != low_value {
+                        continue;
                     }
 
-                    // Check for hillslope cell (ID-3)
-                    if subwta[(row_n, col_n)] != low_value {
-                        if subwta[(row_n, col_n)] <= 0.0 {
-                            return Err(Error::new(
-                                ErrorKind::InvalidInput,
-                                format!(
-                                    "Invalid hillslope ID {} at ({}, {})",
-                                    subwta[(row_n, col_n)],
-                                    row_n,
-                                    col_n
-                                ),
-                            ));
+                    // flood fill from this cell
+                    let mut current = (row, col);
+                    let mut found_topaz_id = 0.0;
+                    while found_topaz_id == 0.0 {
+                        let dir_val = d8_pntr.get_value(current.0, current.1);
+                        let dir = dir_val as usize;
+                        let c = pntr_matches[dir];
+                        let row_n = current.0 + dy[c];
+                        let col_n = current.1 + dx[c];
+
+                        // Check bounds
+                        if row_n < 0 || row_n >= rows || col_n < 0 || col_n >= columns {
+                            break; // Out of bounds
                         }
 
-                        // found a hillslope cell
-                        if subwta[(row_n, col_n)] % 10.0 <= 3.0 {
-                            found_topaz_id = subwta[(row_n, col_n)];
+                        // Check if next cell is in watershed
+                        if watershed[(row_n, col_n)] != 1.0 {
+                            break; // left the watershed
+                        }
+
+                        // Check for hillslope cell (ID-3)
+                        if subwta[(row_n, col_n)] != low_value {
+                            if subwta[(row_n, col_n)] <= 0.0 {
+                                return Err(Error::new(
+                                    ErrorKind::InvalidInput,
+                                    format!(
+                                        "Invalid hillslope ID {} at ({}, {})",
+                                        subwta[(row_n, col_n)],
+                                        row_n,
+                                        col_n
+                                    ),
+                                ));
+                            }
 
-                        // we know this is a channel cell is it a headwater pour point
-                        } else if chnjnt[(row_n, col_n)] == 0.0 {
-                            found_topaz_id = subwta[(row_n, col_n)] - 3.0;
+                            // found a hillslope cell; the stride is a multiple of 10,
+                            // so hillslopes always end in 1 to 3 and channels in 4
+                            if subwta[(row_n, col_n)] % 10.0 <= 3.0 {
+                                found_topaz_id = subwta[(row_n, col_n)];
 
-                        // we know this is a channel cell that isn't a headwater pour point
-                        } else {
-                            let topaz_id = subwta[(row_n, col_n)];
+                            // we know this is a channel cell is it a headwater pour point
+                            } else if chnjnt[(row_n, col_n)] == 0.0 {
+                                found_topaz_id = subwta[(row_n, col_n)] - 3.0;
 
-                            let dir_val = d8_pntr.get_value(row_n, col_n);
-                            let dir = dir_val as usize;
-                            let cn = pntr_matches[dir];
-
-                            // direction of flow into channel
-                            let vx = dx[c] as f64;
-                            let vy = dy[c] as f64;
-
-                            // direction of flow down channel
-                            let ux = dx[cn] as f64;
-                            let uy = dy[cn] as f64;
-
-                            // Calculate cross product to determine side of flow
-                            let cross = ux * vy - uy * vx;
-
-                            if cross > 0.0 {
-                                // test cell is on the “left” side of the flow
-                                // ends with 2
-                                found_topaz_id = topaz_id - 2.0;
-                            } else if cross < 0.0 {
-                                // test cell is on the “right” side of the flow
-                                // ends with 3
-                                found_topaz_id = topaz_id - 1.0;
+                            // we know this is a channel cell that isn't a headwater pour point
                             } else {
-                                // the hillslope drains in the same direction as the channel cell.
-                                // The cross product is ambiguous and can't be used to determine the side of the flow.
-                                // So we need to look at the flow direciton of the upstream channel to determine the side of the hillslope
-                                for i in 0..8 {
-                                    let row_nn = row_n + dy[i];
-                                    let col_nn = col_n + dx[i];
-                                    if row_nn < 0
-                                        || row_nn >= rows
-                                        || col_nn < 0
-                                        || col_nn >= columns
-                                    {
-                                        continue; // out of bounds
-                                    }
-                                    let dir_val = d8_pntr.get_value(row_nn, col_nn);
-                                    let dir = dir_val as usize;
-                                    let c_up = pntr_matches[dir];
-
-                                    let up_chn_candidate_row = row_nn + dy[c_up];
-                                    let up_chn_candidate_col = col_nn + dx[c_up];
-
-                                    if up_chn_candidate_row == row_n
-                                        && up_chn_candidate_col == col_n
-                                        && chnjnt.get_value(row_nn, col_nn) > 0.0
-                                    {
-                                        // direction of the flow down channel from the upstream channel cell
-                                        let ux = dx[c_up] as f64;
-                                        let uy = dy[c_up] as f64;
-
-                                        // Calculate cross product to determine side of flow
-                                        let cross = ux * vy - uy * vx;
-
-                                        if cross > 0.0 {
-                                            // test cell is on the “left” side of the flow
-                                            found_topaz_id = topaz_id - 2.0;
-                                        } else if cross < 0.0 {
-                                            // test cell is on the “right” side of the flow
-                                            found_topaz_id = topaz_id - 1.0;
+                                let topaz_id = subwta[(row_n, col_n)];
+
+                                let dir_val = d8_pntr.get_value(row_n, col_n);
+                                let dir = dir_val as usize;
+                                let cn = pntr_matches[dir];
+
+                                // direction of flow into channel
+                                let vx = dx[c] as f64;
+                                let vy = dy[c] as f64;
+
+                                // direction of flow down channel
+                                let ux = dx[cn] as f64;
+                                let uy = dy[cn] as f64;
+
+                                // Calculate cross product to determine side of flow
+                                let cross = ux * vy - uy * vx;
+
+                                if cross > 0.0 {
+                                    // test cell is on the “left” side of the flow
+                                    // ends with 2
+                                    found_topaz_id = topaz_id - 2.0;
+                                } else if cross < 0.0 {
+                                    // test cell is on the “right” side of the flow
+                                    // ends with 3
+                                    found_topaz_id = topaz_id - 1.0;
+                                } else {
+                                    // the hillslope drains in the same direction as the channel cell.
+                                    // The cross product is ambiguous and can't be used to determine the side of the flow.
+                                    // So we need to look at the flow direciton of the upstream channel to determine the side of the hillslope
+                                    for i in 0..8 {
+                                        let row_nn = row_n + dy[i];
+                                        let col_nn = col_n + dx[i];
+                                        if row_nn < 0
+                                            || row_nn >= rows
+                                            || col_nn < 0
+                                            || col_nn >= columns
+                                        {
+                                            continue; // out of bounds
+                                        }
+                                        let dir_val = d8_pntr.get_value(row_nn, col_nn);
+                                        let dir = dir_val as usize;
+                                        let c_up = pntr_matches[dir];
+
+                                        let up_chn_candidate_row = row_nn + dy[c_up];
+                                        let up_chn_candidate_col = col_nn + dx[c_up];
+
+                                        if up_chn_candidate_row == row_n
+                                            && up_chn_candidate_col == col_n
+                                            && chnjnt.get_value(row_nn, col_nn) > 0.0
+                                        {
+                                            // direction of the flow down channel from the upstream channel cell
+                                            let ux = dx[c_up] as f64;
+                                            let uy = dy[c_up] as f64;
+
+                                            // Calculate cross product to determine side of flow
+                                            let cross = ux * vy - uy * vx;
+
+                                            if cross > 0.0 {
+                                                // test cell is on the “left” side of the flow
+                                                found_topaz_id = topaz_id - 2.0;
+                                            } else if cross < 0.0 {
+                                                // test cell is on the “right” side of the flow
+                                                found_topaz_id = topaz_id - 1.0;
+                                            }
+                                            break;
                                         }
-                                        break;
                                     }
                                 }
                             }
                         }
+                        current = (row_n, col_n);
                     }
-                    current = (row_n, col_n);
-                }
 
-                // If we reached a hillslope cell, walk back down and assign found_topaz_id value
-                if found_topaz_id != 0.0 {
-                    let mut backtrack = (row, col);
-                    while backtrack != current {
-                        if subwta[(backtrack.0, backtrack.1)] == low_value {
-                            subwta[(backtrack.0, backtrack.1)] = found_topaz_id;
-                            subwta_counts
-                                .entry(found_topaz_id as i32)
-                                .and_modify(|e| *e += 1)
-                                .or_insert(1);
+                    // If we reached a hillslope cell, walk back down and assign found_topaz_id value
+                    if found_topaz_id != 0.0 {
+                        let mut backtrack = (row, col);
+                        while backtrack != current {
+                            if subwta[(backtrack.0, backtrack.1)] == low_value {
+                                subwta[(backtrack.0, backtrack.1)] = found_topaz_id;
+                                subwta_counts
+                                    .entry(found_topaz_id as i32)
+                                    .and_modify(|e| *e += 1)
+                                    .or_insert(1);
+                            }
+                            let dir_val = d8_pntr.get_value(backtrack.0, backtrack.1);
+                            let dir = dir_val as usize;
+                            let c = pntr_matches[dir];
+                            backtrack = (backtrack.0 + dy[c], backtrack.1 + dx[c]);
                         }
-                        let dir_val = d8_pntr.get_value(backtrack.0, backtrack.1);
-                        let dir = dir_val as usize;
-                        let c = pntr_matches[dir];
-                        backtrack = (backtrack.0 + dy[c], backtrack.1 + dx[c]);
                     }
                 }
             }
         }
 
+        summary.phase("hillslope_fill", start5);
         if verbose {
             let elapsed = start5.elapsed();
-            println!("Phase 5: Flood filled hillslope values in {:.2?}.", elapsed);
+            if output_mode == "channels" {
+                println!("Phase 5: Skipped hillslope flood fill (channels output mode).");
+            } else {
+                println!("Phase 5: Flood filled hillslope values in {:.2?}.", elapsed);
+            }
         }
 
         // Phase 6: Calculate up area for each link
@@ -1193,17 +2153,104 @@ impl WhiteboxTool for HillslopesTopaz {
             link.areaup = count as f64 * cellsize_x * cellsize_y; // area in m2
         }
 
+        summary.phase("link_areas", start6);
         if verbose {
             let elapsed = start6.elapsed();
             println!("Phase 6: Calculated area for each link in {:.2?}.", elapsed);
         }
 
-        // Write netw.tsv
-        let start6 = Instant::now();
-        if verbose {
-            println!("Writing network links to {}.", netw_file);
+        // The IDs written to the subwta raster, which depend on the output mode
+        let mut topaz_ids: Vec<i32> = vec![];
+        if output_mode != "hillslopes" {
+            topaz_ids.extend(links.iter().map(|link| link.topaz_id));
+        }
+        if output_mode != "channels" {
+            topaz_ids.extend(subwta_counts.keys().copied());
+            summary.num_hillslopes = Some(subwta_counts.len());
+        }
+        summary.min_topaz_id = topaz_ids.iter().min().copied();
+        summary.max_topaz_id = topaz_ids.iter().max().copied();
+
+        // Cross-check the cumulative link areas against the flow accumulation
+        if let Some(flow_accum) = &flow_accum {
+            let start = Instant::now();
+            if verbose {
+                println!("Checking link areas against {}.", flow_accum_file);
+            }
+            // A link's own cells are its hillslopes and its channel cells; the downstream
+            // junction cell belongs to the next link, unless this is the outlet link.
+            let local_cells: Vec<usize> = links
+                .iter()
+                .map(|link| {
+                    let hillslope_cells: usize = (1..=3)
+                        .filter_map(|i| subwta_counts.get(&(link.topaz_id - i)))
+                        .sum();
+                    let channel_cells = link.path.len() - if link.is_outlet { 0 } else { 1 };
+                    hillslope_cells + channel_cells
+                })
+                .collect();
+            let cumulative = cumulative_link_cells(&links, &local_cells);
+            let accum_nodata = flow_accum.configs.nodata;
+            let mut num_exceeding = 0;
+            let mut worst = (-1f64, 0i32); // (largest discrepancy, its channel)
+            for (link, &cells) in links.iter_mut().zip(cumulative.iter()) {
+                let (row, col) = if link.is_outlet || link.path.len() < 2 {
+                    link.ds
+                } else {
+                    link.path[link.path.len() - 2]
+                };
+                let accum = flow_accum.get_value(row, col);
+                link.area_error = if accum != accum_nodata && accum > 0f64 {
+                    (cells as f64 - accum) / accum
+                } else {
+                    f64::NAN
+                };
+                // a link without a valid accumulation value can't be checked and is reported
+                if link.area_error.is_nan() || link.area_error.abs() > max_area_error {
+                    num_exceeding += 1;
+                }
+                let magnitude = if link.area_error.is_nan() {
+                    f64::INFINITY
+                } else {
+                    link.area_error.abs()
+                };
+                if magnitude > worst.0 {
+                    worst = (magnitude, link.topaz_id);
+                }
+            }
+            if num_exceeding > 0 {
+                let (magnitude, topaz_id) = worst;
+                println!(
+                    "Warning: The cumulative area of {} of {} links differs from the flow accumulation by more than {}% (largest: {} for channel {}). Check that the watershed mask is consistent with the D8 pointer.",
+                    num_exceeding,
+                    links.len(),
+                    max_area_error * 100f64,
+                    if magnitude.is_finite() {
+                        format!("{:.1}%", magnitude * 100f64)
+                    } else {
+                        String::from("no valid accumulation")
+                    },
+                    topaz_id
+                );
+            } else if verbose {
+                println!(
+                    "All link areas are within {}% of the flow accumulation.",
+                    max_area_error * 100f64
+                );
+            }
+            summary.phase("area_check", start);
+        }
+
+        if output_mode == "hillslopes" {
+            // channel cells were needed to label the hillslopes; remove them now
+            for link in &links {
+                for &(row, col) in &link.path {
+                    if subwta.get_value(row, col) == link.topaz_id as f64 {
+                        subwta.set_value(row, col, low_value);
+                    }
+                }
+            }
         }
-        write_links_to_tsv(&links, &netw_file)?;
 
         let elapsed_time = get_formatted_elapsed_time(start);
         subwta.add_metadata_entry(format!(
@@ -1212,53 +2259,1112 @@ impl WhiteboxTool for HillslopesTopaz {
         ));
         subwta.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
         subwta.add_metadata_entry(format!("Pour-points file: {}", pourpts_file));
+        subwta.add_metadata_entry(format!("Output mode: {}", output_mode));
+        if flow_accum.is_some() {
+            subwta.add_metadata_entry(format!("Flow accumulation file: {}", flow_accum_file));
+        }
         subwta.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
 
-        if verbose {
-            println!("Saving data...")
+        let chn_order = if chn_order_file.is_empty() {
+            None
+        } else {
+            let mut chn_order = Raster::initialize_using_file(chn_order_file, &d8_pntr);
+            chn_order.configs.data_type = DataType::I32;
+            chn_order.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            chn_order.configs.nodata = -32768f64;
+            chn_order.reinitialize_values(-32768f64);
+            for link in &links {
+                for &(row, col) in &link.path {
+                    if (row, col) == link.ds && !link.is_outlet {
+                        // junction cells belong to the downstream link
+                        continue;
+                    }
+                    chn_order.set_value(row, col, link.chn_order as f64);
+                }
+            }
+            chn_order.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            chn_order.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+            chn_order.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            Some(chn_order)
         };
-        let _ = match subwta.write() {
-            Ok(_) => {
-                if verbose {
-                    println!("Output file written")
+
+        Ok(Delineation {
+            links,
+            subwta,
+            chn_order,
+            with_area_error: flow_accum.is_some(),
+            elapsed_time,
+        })
+    }
+}
+
+/// Finds the stream cell to use for a pour point that is not itself a valid outlet cell. Returns the
+/// chosen neighbour of `located` and a description of the rule used to choose it, or `None` if no
+/// neighbour satisfies `is_outlet_cell`. When several neighbours qualify, the one that `located`
+/// drains into is preferred, then the nearest (orthogonal before diagonal, in clockwise order from north).
+fn adjacent_stream_cell<F>(
+    located: (isize, isize),
+    drains_to: Option<(isize, isize)>,
+    is_outlet_cell: F,
+) -> Option<((isize, isize), &'static str)>
+where
+    F: Fn(isize, isize) -> bool,
+{
+    let offsets = [
+        (-1, 0),
+        (0, 1),
+        (1, 0),
+        (0, -1),
+        (-1, 1),
+        (1, 1),
+        (1, -1),
+        (-1, -1),
+    ];
+    let candidates = offsets
+        .iter()
+        .map(|(dr, dc)| (located.0 + dr, located.1 + dc))
+        .filter(|&(row, col)| is_outlet_cell(row, col))
+        .collect::<Vec<(isize, isize)>>();
+    match candidates.len() {
+        0 => None,
+        1 => Some((candidates[0], "the only adjacent stream cell")),
+        _ => match drains_to {
+            Some(cell) if candidates.contains(&cell) => {
+                Some((cell, "the adjacent stream cell it drains into"))
+            }
+            _ => Some((candidates[0], "the nearest adjacent stream cell")),
+        },
+    }
+}
+
+/// The violation types reported by `--validate_only`, in the order they are checked.
+const VIOLATION_KINDS: [&str; 9] = [
+    "invalid_id",
+    "outside_watershed",
+    "missing_id",
+    "channel_off_stream",
+    "stream_not_channel",
+    "wrong_channel",
+    "no_channel",
+    "channel_not_in_netw",
+    "netw_not_in_subwta",
+];
+
+/// A subwta invariant violation found by `--validate_only`.
+struct Violation {
+    kind: &'static str,
+    cell: Option<(isize, isize)>, // None for network table entries
+    value: Option<f64>,           // None for missing IDs
+    detail: String,
+}
+
+/// The TOPAZ ID held by a subwta value, or None if it is not a positive integer ending in 1 to 4.
+fn topaz_id_of(value: f64) -> Option<i32> {
+    if value > 0.0 && value < i32::MAX as f64 && value.fract() == 0.0 {
+        let id = value as i32;
+        if (1..=4).contains(&(id % 10)) {
+            return Some(id);
+        }
+    }
+    None
+}
+
+/// Checks the invariants of a subwta raster against the inputs and the network table's TOPAZ IDs.
+/// Cell violations are listed in row-major order, followed by the network table mismatches.
+fn validate_subwta(
+    subwta: &Raster,
+    d8_pntr: &Raster,
+    streams: &Raster,
+    watershed: &Raster,
+    netw_ids: &[i32],
+    pntr_matches: &[usize; 129],
+) -> Result<Vec<Violation>, Error> {
+    let rows = subwta.configs.rows as isize;
+    let columns = subwta.configs.columns as isize;
+    let nodata = subwta.configs.nodata;
+    let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+    let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+    let is_stream = |row: isize, col: isize| {
+        streams.get_value(row, col) > 0.0 && streams.get_value(row, col) != streams.configs.nodata
+    };
+    let in_watershed = |row: isize, col: isize| {
+        watershed.get_value(row, col) > 0.0
+            && watershed.get_value(row, col) != watershed.configs.nodata
+    };
+    let channel_id = |row: isize, col: isize| match topaz_id_of(subwta.get_value(row, col)) {
+        Some(id) if id % 10 == 4 && in_watershed(row, col) => Some(id),
+        _ => None,
+    };
+    let downslope = |row: isize, col: isize| {
+        let z = d8_pntr.get_value(row, col);
+        if z == d8_pntr.configs.nodata || z <= 0.0 || z > 128.0 {
+            return None;
+        }
+        let c = pntr_matches[z as usize];
+        if c >= 8 {
+            return None;
+        }
+        Some((row + dy[c], col + dx[c]))
+    };
+
+    let mut violations = vec![];
+    let mut violation =
+        |kind: &'static str, cell: Option<(isize, isize)>, value: Option<f64>, detail: String| {
+            violations.push(Violation {
+                kind,
+                cell,
+                value,
+                detail,
+            })
+        };
+
+    // Cell values; channel IDs are recorded with their first cell.
+    let mut channel_cells: HashMap<i32, (isize, isize)> = HashMap::new();
+    let mut hillslope_cells = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            let value = subwta.get_value(row, col);
+            let inside = in_watershed(row, col);
+            if value == nodata {
+                if inside {
+                    violation("missing_id", Some((row, col)), None, String::new());
+                }
+                continue;
+            }
+            let id = match topaz_id_of(value) {
+                Some(id) => id,
+                None => {
+                    violation("invalid_id", Some((row, col)), Some(value), String::new());
+                    continue;
+                }
+            };
+            if !inside {
+                violation(
+                    "outside_watershed",
+                    Some((row, col)),
+                    Some(value),
+                    String::new(),
+                );
+                continue;
+            }
+            if id % 10 == 4 {
+                if !is_stream(row, col) {
+                    violation(
+                        "channel_off_stream",
+                        Some((row, col)),
+                        Some(value),
+                        String::new(),
+                    );
+                }
+                channel_cells.entry(id).or_insert((row, col));
+            } else {
+                if is_stream(row, col) {
+                    violation(
+                        "stream_not_channel",
+                        Some((row, col)),
+                        Some(value),
+                        String::new(),
+                    );
+                }
+                hillslope_cells.push((row, col, id));
+            }
+        }
+    }
+
+    // Hillslope flow paths. The channel first reached from each cell is memoized along each path
+    // (0 = none, -1 = not yet known, -2 = on the path being traced).
+    let mut reached: Array2D<i32> = Array2D::new(rows, columns, -1, -1)?;
+    let mut path = vec![];
+    for &(row, col, id) in &hillslope_cells {
+        path.clear();
+        let mut current = (row, col);
+        let channel = loop {
+            match reached.get_value(current.0, current.1) {
+                -1 => {}
+                -2 => break 0, // a pointer cycle
+                known => break known,
+            }
+            reached.set_value(current.0, current.1, -2);
+            path.push(current);
+            match downslope(current.0, current.1) {
+                Some((r, c)) if in_watershed(r, c) => {
+                    if let Some(channel) = channel_id(r, c) {
+                        break channel;
+                    }
+                    current = (r, c);
                 }
+                _ => break 0,
             }
-            Err(e) => return Err(e),
         };
+        for &(r, c) in &path {
+            reached.set_value(r, c, channel);
+        }
 
-        if verbose {
-            let elapsed = start6.elapsed();
-            println!("Phase 6: Write files {:.2?}.", elapsed);
+        let expected = id - id % 10 + 4;
+        if channel == 0 {
+            violation(
+                "no_channel",
+                Some((row, col)),
+                Some(id as f64),
+                format!("expected channel {}", expected),
+            );
+        } else if channel != expected {
+            violation(
+                "wrong_channel",
+                Some((row, col)),
+                Some(id as f64),
+                format!("drains to channel {} first; expected {}", channel, expected),
+            );
+        }
+    }
 
-            println!(
-                "{}",
-                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+    // The channel IDs must match the network table.
+    let mut subwta_ids: Vec<&i32> = channel_cells.keys().collect();
+    subwta_ids.sort();
+    for id in subwta_ids {
+        if !netw_ids.contains(id) {
+            violation(
+                "channel_not_in_netw",
+                Some(channel_cells[id]),
+                Some(*id as f64),
+                String::new(),
             );
         }
+    }
+    for id in netw_ids {
+        if !channel_cells.contains_key(id) {
+            violation("netw_not_in_subwta", None, Some(*id as f64), String::new());
+        }
+    }
+
+    Ok(violations)
+}
+
+fn write_violations_to_tsv(
+    violations: &[Violation],
+    subwta: &Raster,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(&mut file, "violation\trow\tcolumn\tx\ty\ttopaz_id\tdetail")?;
+    for v in violations {
+        let value = v.value.map_or("NA".to_string(), |z| z.to_string());
+        match v.cell {
+            Some((row, col)) => writeln!(
+                &mut file,
+                "{}\t{}\t{}\t{:.3}\t{:.3}\t{}\t{}",
+                v.kind,
+                row,
+                col,
+                subwta.get_x_from_column(col),
+                subwta.get_y_from_row(row),
+                value,
+                v.detail
+            )?,
+            None => writeln!(
+                &mut file,
+                "{}\tNA\tNA\tNA\tNA\t{}\t{}",
+                v.kind, value, v.detail
+            )?,
+        }
+    }
+    file.flush()?;
+    file.get_ref().sync_all()
+}
 
-        Ok(())
+/// Creates a mapping from the pointer values to cells offsets; invalid pointer values map to 8.
+/// This may seem wasteful, using only 8 of 129 values in the array,
+/// but the mapping method is far faster than calculating z.ln() / ln(2.0).
+/// It's also a good way of allowing for different point styles.
+fn d8_pntr_matches(esri_style: bool) -> [usize; 129] {
+    let mut pntr_matches: [usize; 129] = [8usize; 129];
+    if !esri_style {
+        // This maps Whitebox-style D8 pointer values
+        // onto the cell offsets in dx and dy.
+        pntr_matches[1] = 0;
+        pntr_matches[2] = 1;
+        pntr_matches[4] = 2;
+        pntr_matches[8] = 3;
+        pntr_matches[16] = 4;
+        pntr_matches[32] = 5;
+        pntr_matches[64] = 6;
+        pntr_matches[128] = 7;
+    } else {
+        // This maps Esri-style D8 pointer values
+        // onto the cell offsets in dx and dy.
+        pntr_matches[1] = 1;
+        pntr_matches[2] = 2;
+        pntr_matches[4] = 3;
+        pntr_matches[8] = 4;
+        pntr_matches[16] = 5;
+        pntr_matches[32] = 6;
+        pntr_matches[64] = 7;
+        pntr_matches[128] = 0;
     }
+    pntr_matches
 }
 
-/// Check if all rasters share the same geometry
-fn rasters_share_geometry(rasters: &[&Raster]) -> bool {
-    if rasters.is_empty() {
-        return true;
+#[cfg(test)]
+mod test {
+    use super::{
+        assign_chn_order, assign_strahler_order, cumulative_link_cells, read_netw, HillslopesTopaz,
+        Link,
+    };
+    use crate::tools::hydro_analysis::D8FlowAccumulation;
+    use crate::tools::WhiteboxTool;
+    use serde_json::{json, Value};
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 5 x 5 catchment with a single channel running down the centre column
+    // to an outlet on the bottom row; side cells drain straight into the channel.
+    fn write_inputs(dir: &str) {
+        let nodata = -32768f64;
+        let grids: [(&str, &dyn Fn(isize, isize) -> f64); 7] = [
+            ("dem", &|r, c| 100f64 - r as f64 + (c - 2).abs() as f64),
+            ("d8", &|_, c| match c {
+                0 | 1 => 2f64,
+                2 => 8f64,
+                _ => 32f64,
+            }),
+            ("streams", &|_, c| if c == 2 { 1f64 } else { 0f64 }),
+            ("watershed", &|_, _| 1f64),
+            ("chnjnt", &|r, c| match (r, c) {
+                (0, 2) => 0f64,
+                (_, 2) => 1f64,
+                _ => nodata,
+            }),
+            ("order", &|_, c| if c == 2 { 1f64 } else { 0f64 }),
+            ("outlet", &|r, c| if (r, c) == (4, 2) { 1f64 } else { 0f64 }),
+        ];
+        for (name, f) in grids.iter() {
+            let grid = GridFixture::new(5, 5, f).with_origin(1000f64, 450f64);
+            grid_fixture_raster(&format!("{}/{}.tif", dir, name), &grid)
+                .write()
+                .unwrap();
+        }
+    }
+
+    fn tool_args(dir: &str, mode: &str) -> Vec<String> {
+        let subwta = format!("{}/subwta_{}.tif", dir, mode);
+        vec![
+            format!("--dem={}/dem.tif", dir),
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+            format!("--pour_pts={}/outlet.tif", dir),
+            format!("--watershed={}/watershed.tif", dir),
+            format!("--chnjnt={}/chnjnt.tif", dir),
+            format!("--order={}/order.tif", dir),
+            format!("--subwta={}", subwta),
+            format!("--netw={}/netw_{}.tsv", dir, mode),
+            format!("--output_mode={}", mode),
+        ]
     }
 
-    let base = &rasters[0].configs;
-    for raster in rasters.iter().skip(1) {
-        if raster.configs.rows != base.rows
-            || raster.configs.columns != base.columns
-            || raster.configs.north != base.north
-            || raster.configs.south != base.south
-            || raster.configs.east != base.east
-            || raster.configs.west != base.west
-            || raster.configs.resolution_x != base.resolution_x
-            || raster.configs.resolution_y != base.resolution_y
-        {
-            return false;
+    fn run_mode(dir: &str, mode: &str) -> Raster {
+        HillslopesTopaz::new()
+            .run(tool_args(dir, mode), "", false)
+            .unwrap();
+        Raster::new(&format!("{}/subwta_{}.tif", dir, mode), "r").unwrap()
+    }
+
+    #[test]
+    fn test_output_modes_partition_combined_output() {
+        let dir = temp_dir("hillslopes_topaz_output_mode").unwrap();
+        write_inputs(&dir);
+
+        let all = run_mode(&dir, "all");
+        let channels = run_mode(&dir, "channels");
+        let hillslopes = run_mode(&dir, "hillslopes");
+        let nodata = all.configs.nodata;
+
+        let mut num_channel_cells = 0;
+        for r in 0..5isize {
+            for c in 0..5isize {
+                let z = all.get_value(r, c);
+                assert!(z != nodata);
+                if z % 10f64 == 4f64 {
+                    num_channel_cells += 1;
+                    assert_eq!(channels.get_value(r, c), z);
+                    assert_eq!(hillslopes.get_value(r, c), nodata);
+                } else {
+                    assert_eq!(channels.get_value(r, c), nodata);
+                    assert_eq!(hillslopes.get_value(r, c), z);
+                }
+            }
+        }
+        assert_eq!(num_channel_cells, 5);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // A run whose last output can't be written, after the table and subwta raster have been,
+    // leaves none of them under its final name and no temporaries behind, and the outputs of an
+    // earlier run are left as they were.
+    #[test]
+    fn test_failed_run_leaves_no_partial_outputs() {
+        let dir = temp_dir("hillslopes_topaz_staged_outputs").unwrap();
+        write_inputs(&dir);
+        let args = tool_args(&dir, "all");
+        let outputs = [
+            format!("{}/netw_all.tsv", dir),
+            format!("{}/subwta_all.tif", dir),
+        ];
+        let fail = || {
+            let mut unwritable = args.clone();
+            unwritable.push(format!("--chn_order={}/missing/chn_order.tif", dir));
+            HillslopesTopaz::new()
+                .run(unwritable, "", false)
+                .unwrap_err();
+            let temporaries: Vec<String> = fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+                .filter(|name| name.contains(".tmp-"))
+                .collect();
+            assert!(temporaries.is_empty(), "{:?}", temporaries);
+        };
+
+        fail();
+        for file in &outputs {
+            assert!(!std::path::Path::new(file).exists(), "{}", file);
+        }
+
+        HillslopesTopaz::new().run(args.clone(), "", false).unwrap();
+        let written: Vec<Vec<u8>> = outputs.iter().map(|f| fs::read(f).unwrap()).collect();
+        fail();
+        for (file, contents) in outputs.iter().zip(&written) {
+            assert_eq!(&fs::read(file).unwrap(), contents, "{}", file);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_summary() {
+        let dir = temp_dir("hillslopes_topaz_summary").unwrap();
+        write_inputs(&dir);
+        let summary_file = format!("{}/summary.json", dir);
+        let read_summary = || -> Value {
+            serde_json::from_str(&fs::read_to_string(&summary_file).unwrap()).unwrap()
+        };
+
+        let mut args = tool_args(&dir, "all");
+        args.push(format!("--summary={}", summary_file));
+        HillslopesTopaz::new().run(args.clone(), "", false).unwrap();
+        let summary = read_summary();
+        assert_eq!(summary["status"], "success");
+        assert_eq!(summary["error"], Value::Null);
+        assert_eq!(summary["num_links"], 1);
+        assert_eq!(summary["num_hillslopes"], 3);
+        assert_eq!(summary["channel_length_m"], 40.0);
+        assert_eq!(summary["watershed_area_m2"], 2500.0);
+        assert_eq!(
+            summary["outlet"],
+            json!({"row": 4, "column": 2, "x": 1025.0, "y": 455.0})
+        );
+        assert_eq!(summary["min_topaz_id"], 21);
+        assert_eq!(summary["max_topaz_id"], 24);
+        assert_eq!(summary["links_per_order"], json!({"1": 1}));
+        for phase in ["initialization", "hillslope_fill", "write_outputs"] {
+            assert!(summary["phase_seconds"][phase].is_f64(), "{}", phase);
+        }
+        assert_eq!(
+            summary["outputs"]["subwta"],
+            format!("{}/subwta_all.tif", dir)
+        );
+        assert_eq!(summary["outputs"]["netw"], format!("{}/netw_all.tsv", dir));
+
+        // A failed run reports its error and only what was computed before it.
+        let outlet_file = format!("{}/outlet.tif", dir);
+        let template = Raster::new(&outlet_file, "r").unwrap();
+        let mut outlet = Raster::initialize_using_file(&outlet_file, &template);
+        outlet.reinitialize_values(0f64);
+        outlet.set_value(4, 0, 1f64);
+        outlet.write().unwrap();
+        args.push("--strict_pour_pt".to_string());
+        let err = HillslopesTopaz::new().run(args, "", false).unwrap_err();
+        let summary = read_summary();
+        assert_eq!(summary["status"], "failure");
+        assert_eq!(summary["error"], err.to_string());
+        assert_eq!(summary["num_links"], Value::Null);
+        assert_eq!(summary["outlet"], Value::Null);
+        assert_eq!(summary["outputs"], json!({}));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_pour_point_adjacent_to_stream() {
+        let dir = temp_dir("hillslopes_topaz_pour_pt").unwrap();
+        write_inputs(&dir);
+
+        // Move the outlet one cell west of the channel; that cell drains east into
+        // the channel outlet, which is preferred over the diagonal channel neighbour.
+        let outlet_file = format!("{}/outlet.tif", dir);
+        let template = Raster::new(&outlet_file, "r").unwrap();
+        let mut outlet = Raster::initialize_using_file(&outlet_file, &template);
+        outlet.reinitialize_values(0f64);
+        outlet.set_value(4, 1, 1f64);
+        outlet.write().unwrap();
+
+        let all = run_mode(&dir, "all");
+        assert_eq!(all.get_value(4, 2), 24f64);
+        assert_eq!(all.get_value(0, 2), 24f64);
+
+        let mut args = tool_args(&dir, "all");
+        args.push("--strict_pour_pt".to_string());
+        let err = HillslopesTopaz::new().run(args, "", false).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "The pour point (--pour_pts) must be on a stream cell (--streams)."
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // Rewrites an input raster of `write_inputs` cell by cell from its current values.
+    fn edit_input(dir: &str, name: &str, f: &dyn Fn(isize, isize, f64) -> f64) {
+        let file = format!("{}/{}.tif", dir, name);
+        let template = Raster::new(&file, "r").unwrap();
+        let mut raster = Raster::initialize_using_file(&file, &template);
+        for r in 0..5isize {
+            for c in 0..5isize {
+                raster.set_value(r, c, f(r, c, template.get_value(r, c)));
+            }
+        }
+        drop(template);
+        raster.write().unwrap();
+    }
+
+    #[test]
+    fn test_pour_point_at_watershed_boundary() {
+        let dir = temp_dir("hillslopes_topaz_mouth").unwrap();
+        write_inputs(&dir);
+
+        // The mask excludes the bottom row, so the channel leaves the watershed one step below
+        // the pour point, which is adjusted from the first cell outside onto the last one inside.
+        edit_input(&dir, "watershed", &|r, _, _| {
+            if r == 4 {
+                0f64
+            } else {
+                1f64
+            }
+        });
+        let all = run_mode(&dir, "all");
+        assert_eq!(all.get_value(3, 2), 24f64);
+        assert_eq!(all.get_value(0, 2), 24f64);
+        assert_eq!(all.get_value(4, 2), all.configs.nodata);
+        let netw = fs::read_to_string(format!("{}/netw_all.tsv", dir)).unwrap();
+        assert_eq!(netw.lines().count(), 2);
+
+        // A channel that leaves the watershed without passing the pour point is still an error.
+        edit_input(&dir, "streams", &|r, c, z| {
+            if (r, c) == (2, 3) {
+                1f64
+            } else {
+                z
+            }
+        });
+        edit_input(&dir, "chnjnt", &|r, c, z| {
+            if (r, c) == (2, 3) {
+                0f64
+            } else {
+                z
+            }
+        });
+        edit_input(&dir, "d8", &|r, c, z| {
+            if (r, c) == (2, 3) {
+                2f64
+            } else {
+                z
+            }
+        });
+        edit_input(&dir, "watershed", &|r, c, z| {
+            if (r, c) == (2, 4) {
+                0f64
+            } else {
+                z
+            }
+        });
+        let err = HillslopesTopaz::new()
+            .run(tool_args(&dir, "all"), "", false)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Pointer direction leads outside watershed at (row 2, column 3) on a channel that does not pass the pour point (row 3, column 2)."
+        );
+
+        // As is one that leaves the raster.
+        edit_input(&dir, "watershed", &|r, _, _| {
+            if r == 4 {
+                0f64
+            } else {
+                1f64
+            }
+        });
+        edit_input(&dir, "streams", &|r, c, z| {
+            if (r, c) == (2, 4) {
+                1f64
+            } else {
+                z
+            }
+        });
+        edit_input(&dir, "chnjnt", &|r, c, z| {
+            if (r, c) == (2, 4) {
+                1f64
+            } else {
+                z
+            }
+        });
+        edit_input(&dir, "d8", &|r, c, z| {
+            if (r, c) == (2, 4) {
+                2f64
+            } else {
+                z
+            }
+        });
+        let err = HillslopesTopaz::new()
+            .run(tool_args(&dir, "all"), "", false)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Pointer direction leads outside raster bounds at (row 2, column 4) on a channel that does not pass the pour point (row 3, column 2)."
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_bare_file_names_use_working_directory() {
+        let dir = temp_dir("hillslopes_topaz_working_dir").unwrap();
+        write_inputs(&dir);
+
+        // The working directory is given without a trailing separator.
+        let args: Vec<String> = tool_args(&dir, "all")
+            .iter()
+            .map(|arg| arg.replace(&format!("{}/", dir), ""))
+            .collect();
+        assert_eq!(args[0], "--dem=dem.tif");
+        HillslopesTopaz::new().run(args, &dir, false).unwrap();
+        let subwta = Raster::new(&format!("{}/subwta_all.tif", dir), "r").unwrap();
+        assert_eq!(subwta.get_value(4, 2), 24f64);
+        assert!(std::path::Path::new(&format!("{}/netw_all.tsv", dir)).exists());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_geometry_mismatch_names_raster_and_fields() {
+        let dir = temp_dir("hillslopes_topaz_geometry").unwrap();
+        write_inputs(&dir);
+
+        // An order raster missing the bottom row of the DEM.
+        let file = format!("{}/order.tif", dir);
+        let order = GridFixture::new(4, 5, |_, c| if c == 2 { 1f64 } else { 0f64 })
+            .with_origin(1000f64, 460f64);
+        grid_fixture_raster(&file, &order).write().unwrap();
+
+        let err = HillslopesTopaz::new()
+            .run(tool_args(&dir, "all"), "", false)
+            .unwrap_err()
+            .to_string();
+        let mut lines = err.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "Input rasters must share geometry; differs from --dem: --order (rows, south)."
+        );
+        let listed: Vec<&str> = lines.collect();
+        assert_eq!(listed.len(), 6);
+        assert!(listed[0].starts_with("  --dem "));
+        assert!(listed[5].starts_with(&format!("  --order {}: 4 rows x 5 columns", file)));
+        let order = Raster::new(&file, "r").unwrap();
+        assert!(listed[5].ends_with(&format!(
+            "north {}, south {}, east {}, west {}",
+            order.configs.north, order.configs.south, order.configs.east, order.configs.west
+        )));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn assert_inflows_routed_first(links: &[Link]) {
+        for link in links {
+            for inflow in [link.inflow0_id, link.inflow1_id, link.inflow2_id] {
+                if inflow >= 0 {
+                    assert!(link.chn_order > links[inflow as usize].chn_order);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_chn_order_follows_link_graph() {
+        // (index, topaz_id, inflows); the link indices are deliberately out of TOPAZ ID order.
+        let network = [
+            (0, 54, [-1, -1, -1]),
+            (1, 24, [3, 4, -1]),
+            (2, 44, [-1, -1, -1]),
+            (3, 34, [2, 0, 5]),
+            (4, 64, [-1, -1, -1]),
+            (5, 74, [-1, -1, -1]),
+        ];
+        let mut links: Vec<Link> = network
+            .iter()
+            .map(|&(id, topaz_id, inflows)| {
+                let mut link = Link::new();
+                link.id = id;
+                link.topaz_id = topaz_id;
+                link.inflow0_id = inflows[0];
+                link.inflow1_id = inflows[1];
+                link.inflow2_id = inflows[2];
+                link.is_outlet = topaz_id == 24;
+                link
+            })
+            .collect();
+        assign_chn_order(&mut links).unwrap();
+        assert_inflows_routed_first(&links);
+
+        let orders: Vec<i32> = links.iter().map(|link| link.chn_order).collect();
+        // parallel headwaters are numbered by TOPAZ ID: 44, 54, 64, 74, then 34 and the outlet
+        assert_eq!(orders, vec![2, 6, 1, 5, 3, 4]);
+
+        // each link drains its own cells and those of every link upstream of it
+        let local_cells = [1, 10, 100, 1000, 10000, 100000];
+        assert_eq!(
+            cumulative_link_cells(&links, &local_cells),
+            vec![1, 111111, 100, 101101, 10000, 100000]
+        );
+
+        // three order-1 inflows make an order-2 link, which an order-1 inflow does not raise
+        assign_strahler_order(&mut links);
+        let strahler: Vec<u8> = links.iter().map(|link| link.order).collect();
+        assert_eq!(strahler, vec![1, 2, 1, 2, 1, 1]);
+
+        // a cycle cannot be ordered
+        links[2].inflow0_id = 1;
+        assert!(assign_chn_order(&mut links).is_err());
+    }
+
+    // Replaces the single channel written by `write_inputs` with two tributaries from the
+    // top corners that join at (2, 2) and drain down the centre column to the outlet.
+    fn write_tributaries(dir: &str) {
+        let nodata = -32768f64;
+        let grids: [(&str, &dyn Fn(isize, isize) -> f64); 3] = [
+            ("d8", &|r, c| match (r, c) {
+                (0, 0) | (1, 1) | (0, 1) => 4f64,
+                (0, 4) | (1, 3) | (0, 3) => 16f64,
+                (_, 2) => 8f64,
+                (_, 0) | (_, 1) => 2f64,
+                _ => 32f64,
+            }),
+            ("streams", &|r, c| match (r, c) {
+                (0, 0) | (1, 1) | (0, 4) | (1, 3) => 1f64,
+                (r, 2) if r >= 2 => 1f64,
+                _ => 0f64,
+            }),
+            ("chnjnt", &|r, c| match (r, c) {
+                (0, 0) | (0, 4) => 0f64,
+                (1, 1) | (1, 3) | (3, 2) | (4, 2) => 1f64,
+                (2, 2) => 2f64,
+                _ => nodata,
+            }),
+        ];
+        for (name, f) in grids.iter() {
+            let file = format!("{}/{}.tif", dir, name);
+            let template = Raster::new(&file, "r").unwrap();
+            let mut raster = Raster::initialize_using_file(&file, &template);
+            for r in 0..5isize {
+                for c in 0..5isize {
+                    raster.set_value(r, c, f(r, c));
+                }
+            }
+            raster.write().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_chn_order_outputs() {
+        let dir = temp_dir("hillslopes_topaz_chn_order").unwrap();
+        write_inputs(&dir);
+        write_tributaries(&dir);
+
+        let mut args = tool_args(&dir, "all");
+        args.push(format!("--chn_order={}/chn_order.tif", dir));
+        HillslopesTopaz::new().run(args, "", false).unwrap();
+
+        let links = read_netw(&format!("{}/netw_all.tsv", dir)).unwrap();
+        let mut orders: Vec<(i32, i32)> = links
+            .iter()
+            .map(|link| (link.topaz_id as i32, link.chn_order as i32))
+            .collect();
+        orders.sort();
+        assert_eq!(orders, vec![(24, 3), (34, 1), (44, 2)]);
+        // The outlet link is fed by the other two.
+        let outlet = links.iter().find(|link| link.is_outlet).unwrap();
+        assert_eq!(outlet.topaz_id, 24);
+        let mut inflows: Vec<i64> = outlet
+            .inflows
+            .iter()
+            .copied()
+            .filter(|&id| id >= 0)
+            .collect();
+        inflows.sort();
+        let mut others: Vec<i64> = links
+            .iter()
+            .filter(|l| !l.is_outlet)
+            .map(|l| l.id)
+            .collect();
+        others.sort();
+        assert_eq!(inflows, others);
+
+        let chn_order = Raster::new(&format!("{}/chn_order.tif", dir), "r").unwrap();
+        let subwta = Raster::new(&format!("{}/subwta_all.tif", dir), "r").unwrap();
+        for r in 0..5isize {
+            for c in 0..5isize {
+                let topaz_id = subwta.get_value(r, c) as i32;
+                let expected = orders
+                    .iter()
+                    .find(|&&(id, _)| id == topaz_id)
+                    .map_or(chn_order.configs.nodata, |&(_, order)| order as f64);
+                assert_eq!(chn_order.get_value(r, c), expected);
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // Without --order, the Strahler order computed from the links matches an order raster holding
+    // it, and the TOPAZ IDs are unchanged.
+    #[test]
+    fn test_order_computed_without_order_raster() {
+        let dir = temp_dir("hillslopes_topaz_no_order").unwrap();
+        write_inputs(&dir);
+        write_tributaries(&dir);
+        edit_input(&dir, "order", &|r, c, _| match (r, c) {
+            (0, 0) | (1, 1) | (0, 4) | (1, 3) => 1f64,
+            (r, 2) if r >= 2 => 2f64,
+            _ => 0f64,
+        });
+
+        let with_order = run_mode(&dir, "all");
+        let netw_with_order = fs::read_to_string(format!("{}/netw_all.tsv", dir)).unwrap();
+
+        let args: Vec<String> = tool_args(&dir, "all")
+            .into_iter()
+            .filter(|arg| !arg.starts_with("--order="))
+            .collect();
+        HillslopesTopaz::new().run(args, "", false).unwrap();
+        let without_order = Raster::new(&format!("{}/subwta_all.tif", dir), "r").unwrap();
+        let netw_without_order = fs::read_to_string(format!("{}/netw_all.tsv", dir)).unwrap();
+
+        for r in 0..5isize {
+            for c in 0..5isize {
+                assert_eq!(without_order.get_value(r, c), with_order.get_value(r, c));
+            }
+        }
+        assert_eq!(netw_without_order, netw_with_order);
+
+        let orders: Vec<(i32, u8)> = netw_column(&format!("{}/netw_all.tsv", dir), "order")
+            .into_iter()
+            .map(|(id, order)| (id, order.parse().unwrap()))
+            .collect();
+        assert_eq!(orders, vec![(24, 2), (34, 1), (44, 1)]);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn netw_topaz_ids(file: &str) -> Vec<i32> {
+        netw_column(file, "topaz_id")
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect()
+    }
+
+    #[test]
+    fn test_topaz_start_and_stride() {
+        let dir = temp_dir("hillslopes_topaz_start_stride").unwrap();
+        write_inputs(&dir);
+        write_tributaries(&dir);
+
+        let default = run_mode(&dir, "all");
+        assert_eq!(
+            netw_topaz_ids(&format!("{}/netw_all.tsv", dir)),
+            vec![24, 34, 44]
+        );
+
+        let mut args = tool_args(&dir, "all");
+        args.push("--topaz_start=104".to_string());
+        args.push("--topaz_stride=10".to_string());
+        HillslopesTopaz::new().run(args, "", false).unwrap();
+        assert_eq!(
+            netw_topaz_ids(&format!("{}/netw_all.tsv", dir)),
+            vec![104, 114, 124]
+        );
+
+        // Every channel and hillslope keeps its place in the sequence, shifted by 80.
+        let shifted = Raster::new(&format!("{}/subwta_all.tif", dir), "r").unwrap();
+        assert_eq!(shifted.get_value(4, 2), 104f64);
+        let mut hillslope_ids = vec![];
+        for r in 0..5isize {
+            for c in 0..5isize {
+                let z = shifted.get_value(r, c);
+                assert_eq!(z, default.get_value(r, c) + 80f64);
+                if z % 10f64 != 4f64 {
+                    hillslope_ids.push(z as i32);
+                }
+            }
+        }
+        hillslope_ids.sort();
+        hillslope_ids.dedup();
+        assert_eq!(hillslope_ids, vec![102, 103, 113, 122]);
+
+        for (flag, message) in [
+            (
+                "--topaz_start=100",
+                "--topaz_start must be a positive integer ending in 4; got 100.",
+            ),
+            (
+                "--topaz_stride=15",
+                "--topaz_stride must be a positive multiple of 10; got 15.",
+            ),
+        ] {
+            let mut args = tool_args(&dir, "all");
+            args.push(flag.to_string());
+            let err = HillslopesTopaz::new().run(args, "", false).unwrap_err();
+            assert_eq!(err.to_string(), message);
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    fn validate(dir: &str) -> (Result<(), std::io::Error>, Vec<String>) {
+        let mut args = tool_args(dir, "all");
+        args.push("--validate_only".to_string());
+        args.push(format!("--violations={}/violations.tsv", dir));
+        let result = HillslopesTopaz::new().run(args, "", false);
+        let violations = fs::read_to_string(format!("{}/violations.tsv", dir)).unwrap();
+        let lines = violations.lines().map(|l| l.to_string()).collect();
+        (result, lines)
+    }
+
+    #[test]
+    fn test_validate_only() {
+        let dir = temp_dir("hillslopes_topaz_validate_only").unwrap();
+        write_inputs(&dir);
+
+        // A freshly generated subwta is valid.
+        let generated = run_mode(&dir, "all");
+        let (result, lines) = validate(&dir);
+        assert!(result.is_ok());
+        assert_eq!(
+            lines,
+            vec!["violation\trow\tcolumn\tx\ty\ttopaz_id\tdetail".to_string()]
+        );
+
+        // Hand edits: a channel ID off the stream, a stream cell relabeled as a hillslope, and a
+        // hillslope given a non-existent channel's ID; the network table gains a stray channel.
+        let subwta_file = format!("{}/subwta_all.tif", dir);
+        let mut subwta = Raster::initialize_using_file(&subwta_file, &generated);
+        for r in 0..5isize {
+            for c in 0..5isize {
+                subwta.set_value(r, c, generated.get_value(r, c));
+            }
+        }
+        subwta.set_value(2, 0, 34f64);
+        subwta.set_value(3, 2, 23f64);
+        subwta.set_value(2, 4, 32f64);
+        subwta.write().unwrap();
+        let netw_file = format!("{}/netw_all.tsv", dir);
+        let netw = fs::read_to_string(&netw_file).unwrap();
+        let stray = netw.lines().nth(1).unwrap().replacen("\t24\t", "\t44\t", 1);
+        fs::write(&netw_file, format!("{}{}\n", netw, stray)).unwrap();
+
+        let (result, lines) = validate(&dir);
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            format!("Validation found 5 violations; see {}/violations.tsv", dir)
+        );
+        assert_eq!(
+            &lines[1..],
+            &[
+                "channel_off_stream\t2\t0\t1005.000\t475.000\t34\t",
+                "stream_not_channel\t3\t2\t1025.000\t465.000\t23\t",
+                "wrong_channel\t2\t4\t1045.000\t475.000\t32\tdrains to channel 24 first; expected 34",
+                "channel_not_in_netw\t2\t0\t1005.000\t475.000\t34\t",
+                "netw_not_in_subwta\tNA\tNA\tNA\tNA\t44\t",
+            ]
+        );
+
+        // Nothing is overwritten in validation mode.
+        let subwta = Raster::new(&subwta_file, "r").unwrap();
+        assert_eq!(subwta.get_value(2, 0), 34f64);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // Reads a column of a network table as (topaz_id, value) pairs, sorted by TOPAZ ID.
+    fn netw_column(file: &str, column: &str) -> Vec<(i32, String)> {
+        let mut rows = read_tsv(file).unwrap();
+        let header = rows.remove(0);
+        let topaz_col = header.iter().position(|h| h == "topaz_id").unwrap();
+        let value_col = header.iter().position(|h| h == column).unwrap();
+        let mut values: Vec<(i32, String)> = rows
+            .iter()
+            .map(|row| (row[topaz_col].parse().unwrap(), row[value_col].clone()))
+            .collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_area_cross_check_against_flow_accum() {
+        let dir = temp_dir("hillslopes_topaz_flow_accum").unwrap();
+        write_inputs(&dir);
+        write_tributaries(&dir);
+        D8FlowAccumulation::new()
+            .run(
+                vec![
+                    format!("--input={}/d8.tif", dir),
+                    format!("--output={}/accum.tif", dir),
+                    "--out_type=cells".to_string(),
+                    "--pntr".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let netw_file = format!("{}/netw_all.tsv", dir);
+
+        // Without --flow_accum the network table is unchanged.
+        HillslopesTopaz::new()
+            .run(tool_args(&dir, "all"), "", false)
+            .unwrap();
+        let netw = fs::read_to_string(&netw_file).unwrap();
+        assert!(netw.lines().next().unwrap().ends_with("\tchn_order"));
+
+        let mut args = tool_args(&dir, "all");
+        args.push(format!("--flow_accum={}/accum.tif", dir));
+        HillslopesTopaz::new().run(args.clone(), "", false).unwrap();
+        let errors: Vec<(i32, String)> = [24, 34, 44]
+            .iter()
+            .map(|&id| (id, "0.0000".to_string()))
+            .collect();
+        assert_eq!(netw_column(&netw_file, "area_error"), errors);
+
+        // A mask that cuts off a cell draining to the outlet channel only affects the outlet
+        // link; a discrepancy above --max_area_error is a warning, not an error.
+        let file = format!("{}/watershed.tif", dir);
+        let template = Raster::new(&file, "r").unwrap();
+        let mut watershed = Raster::initialize_using_file(&file, &template);
+        for r in 0..5isize {
+            for c in 0..5isize {
+                watershed.set_value(r, c, if (r, c) == (4, 0) { 0f64 } else { 1f64 });
+            }
         }
+        watershed.write().unwrap();
+        args.push("--max_area_error=0.01".to_string());
+        HillslopesTopaz::new().run(args, "", false).unwrap();
+        let errors = netw_column(&netw_file, "area_error");
+        assert_eq!(errors[0], (24, "-0.0400".to_string()));
+        assert_eq!(errors[1].1, "0.0000");
+        assert_eq!(errors[2].1, "0.0000");
+
+        let mut args = tool_args(&dir, "channels");
+        args.push(format!("--flow_accum={}/accum.tif", dir));
+        assert!(HillslopesTopaz::new().run(args, "", false).is_err());
+        fs::remove_dir_all(&dir).ok();
     }
-    true
 }
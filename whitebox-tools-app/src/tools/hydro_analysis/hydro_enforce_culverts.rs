@@ -0,0 +1,807 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use geojson::{GeoJson, Geometry, Value};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::{ShapeType, Shapefile};
+
+/// This tool enforces drainage through road embankments at mapped culvert or stream-crossing locations
+/// (`--culverts`) in a digital elevation model (DEM; `--dem`). Road fills in lidar DEMs act as dams that
+/// impound flow paths, and this tool replaces manual pixel editing by carving a constrained breach channel
+/// through the embankment at each crossing point.
+///
+/// For each crossing point, the tool examines the DEM cells within half the maximum breach length
+/// (`--max_length`, in map units) of the point. The lowest cell within this search area is taken as the
+/// downstream low point. The upstream low point is the lowest cell in the search area that is impounded
+/// relative to the downstream low point, i.e. whose lowest-spill route to the downstream low point must rise
+/// above its own elevation. The tool then finds the least-cost path from the upstream low point, through the
+/// crossing cell, to the downstream low point, where the cost of entering a cell is proportional to the depth
+/// that it must be lowered below the upstream low point. Cells along this path are lowered so that
+/// elevations decrease monotonically from the upstream to the downstream low point. The crossing points are
+/// processed in input order and each breach is carved into the DEM modified by earlier crossings.
+///
+/// The crossing points may be supplied as a point shapefile or a GeoJSON FeatureCollection of Point or
+/// MultiPoint features. In addition to the modified DEM (`--output`), the tool writes a tab-separated report
+/// (`--report`) with one row per crossing point, containing the carve depth (the maximum lowering), the carve
+/// length (the length of the breach path from the upstream to the downstream low point), and a status. The
+/// status is `breached` for carved crossings, `no_breach_found` if no impounded upstream low point exists
+/// within the search length or the breach path would exceed `--max_length`, and `outside_dem` for points that
+/// fall outside of the DEM or on nodata cells. Crossings without a valid breach are left unmodified. If the
+/// report file is not specified, it is written beside the output DEM with a `_culverts.tsv` suffix.
+///
+/// # See Also
+/// `BurnStreamsAtRoads`, `BreachDepressionsLeastCost`
+pub struct HydroEnforceCulverts {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl HydroEnforceCulverts {
+    pub fn new() -> HydroEnforceCulverts {
+        // public constructor
+        let name = "HydroEnforceCulverts".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Breaches road embankments in a DEM at mapped culvert or crossing points.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster digital elevation model (DEM) file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Culvert Points File".to_owned(),
+            flags: vec!["--culverts".to_owned()],
+            description: "Input culvert/crossing points file (shapefile or GeoJSON).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["--report".to_owned()],
+            description: "Output tab-separated report of carve depth and length per culvert."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Breach Length".to_owned(),
+            flags: vec!["--max_length".to_owned()],
+            description: "Maximum length of a breach path through an embankment, in map units."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=dem.tif --culverts=culverts.shp -o=output.tif --report=culverts.tsv --max_length=30.0", short_exe, name).replace("*", &sep);
+
+        HydroEnforceCulverts {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for HydroEnforceCulverts {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut culverts_file = String::new();
+        let mut output_file = String::new();
+        let mut report_file = String::new();
+        let mut max_length = f64::NAN;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-culverts" {
+                culverts_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-report" {
+                report_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-max_length" {
+                max_length = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if !(max_length > 0f64) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --max_length parameter must be a positive number of map units.",
+            ));
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(working_directory, &dem_file);
+        culverts_file = resolve_path(working_directory, &culverts_file);
+        output_file = resolve_path(working_directory, &output_file);
+        if report_file.is_empty() {
+            let stem = match output_file.rfind('.') {
+                Some(idx) if idx > output_file.rfind(|c| c == '/' || c == '\\').unwrap_or(0) => {
+                    &output_file[..idx]
+                }
+                _ => &output_file[..],
+            };
+            report_file = format!("{}_culverts.tsv", stem);
+        } else {
+            report_file = resolve_path(working_directory, &report_file);
+        }
+
+        if verbose {
+            println!("Reading culvert points...")
+        };
+        let culverts = read_culvert_points(&culverts_file)?;
+
+        if verbose {
+            println!("Reading DEM raster...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+
+        let start = Instant::now();
+
+        let small_num = {
+            let diagres = (dem.configs.resolution_x * dem.configs.resolution_x
+                + dem.configs.resolution_y * dem.configs.resolution_y)
+                .sqrt();
+            let elev_digits = (dem.configs.maximum as i32).to_string().len();
+            let elev_multiplier = 10.0_f64.powi((9 - elev_digits) as i32);
+            1.0_f64 / elev_multiplier as f64 * diagres.ceil()
+        };
+
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        // Even if the input is f32, the output will need to be 64-bit to represent the small elevation differences
+        output.configs.data_type = DataType::F64;
+        output.set_data_from_raster(&dem)?;
+        drop(dem);
+
+        let mut results = Vec::with_capacity(culverts.len());
+        let num_culverts = culverts.len();
+        for (i, &(x, y)) in culverts.iter().enumerate() {
+            let row = output.get_row_from_y(y);
+            let col = output.get_column_from_x(x);
+            let result = breach_culvert(&output, row, col, max_length, small_num);
+            for &(r, c, z) in &result.carved_cells {
+                output.set_value(r, c, z);
+            }
+            if verbose && result.status != CulvertStatus::Breached {
+                println!(
+                    "Warning: No breach was carved for culvert {} ({}, {}): {}.",
+                    i + 1,
+                    x,
+                    y,
+                    result.status.description()
+                );
+            }
+            results.push((i + 1, x, y, row, col, result));
+
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_culverts as f64) as usize;
+                if progress != old_progress {
+                    println!("Breaching embankments: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let num_breached = results
+            .iter()
+            .filter(|r| r.5.status == CulvertStatus::Breached)
+            .count();
+        if verbose {
+            println!(
+                "{} of {} culverts were breached; see {} for details.",
+                num_breached, num_culverts, report_file
+            );
+        }
+
+        write_report(&results, &report_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        output.add_metadata_entry(format!("Input culverts file: {}", culverts_file));
+        output.add_metadata_entry(format!("Maximum breach length: {}", max_length));
+        output.add_metadata_entry(format!(
+            "Culverts breached: {} of {}",
+            num_breached, num_culverts
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum CulvertStatus {
+    Breached,
+    NoBreachFound,
+    OutsideDem,
+}
+
+impl CulvertStatus {
+    fn as_str(&self) -> &'static str {
+        match self {
+            CulvertStatus::Breached => "breached",
+            CulvertStatus::NoBreachFound => "no_breach_found",
+            CulvertStatus::OutsideDem => "outside_dem",
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            CulvertStatus::Breached => "breached",
+            CulvertStatus::NoBreachFound => {
+                "no valid breach was found within the maximum breach length"
+            }
+            CulvertStatus::OutsideDem => "the point lies outside of the DEM or on a nodata cell",
+        }
+    }
+}
+
+struct CulvertResult {
+    status: CulvertStatus,
+    upstream_z: f64,
+    downstream_z: f64,
+    carve_depth: f64,
+    carve_length: f64,
+    carved_cells: Vec<(isize, isize, f64)>, // (row, column, new elevation)
+}
+
+impl CulvertResult {
+    fn failed(status: CulvertStatus) -> CulvertResult {
+        CulvertResult {
+            status,
+            upstream_z: f64::NAN,
+            downstream_z: f64::NAN,
+            carve_depth: 0f64,
+            carve_length: 0f64,
+            carved_cells: vec![],
+        }
+    }
+}
+
+/// Carves a breach through the embankment at (`row`, `col`), searching the cells within
+/// `max_length / 2` of the crossing for the upstream and downstream low points.
+fn breach_culvert(
+    dem: &Raster,
+    row: isize,
+    col: isize,
+    max_length: f64,
+    small_num: f64,
+) -> CulvertResult {
+    let rows = dem.configs.rows as isize;
+    let columns = dem.configs.columns as isize;
+    let nodata = dem.configs.nodata;
+    if row < 0 || col < 0 || row >= rows || col >= columns || dem.get_value(row, col) == nodata {
+        return CulvertResult::failed(CulvertStatus::OutsideDem);
+    }
+
+    let res_x = dem.configs.resolution_x;
+    let res_y = dem.configs.resolution_y;
+    let half_length = max_length / 2f64;
+    let radius = (half_length / res_x.min(res_y)).ceil() as isize;
+    let width = 2 * radius + 1;
+    let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+    let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+    // The search window is stored locally; cells outside the search disc are nodata.
+    let mut z = vec![nodata; (width * width) as usize];
+    let idx = |r: isize, c: isize| ((r + radius) * width + c + radius) as usize;
+    let centre = idx(0, 0);
+    let dist_from_crossing = |r: isize, c: isize| {
+        ((r as f64 * res_y) * (r as f64 * res_y) + (c as f64 * res_x) * (c as f64 * res_x)).sqrt()
+    };
+    let mut low_idx = centre;
+    for r in -radius..=radius {
+        for c in -radius..=radius {
+            if dist_from_crossing(r, c) > half_length {
+                continue;
+            }
+            let (rn, cn) = (row + r, col + c);
+            if rn < 0 || cn < 0 || rn >= rows || cn >= columns {
+                continue;
+            }
+            let zn = dem.get_value(rn, cn);
+            if zn == nodata {
+                continue;
+            }
+            z[idx(r, c)] = zn;
+            if zn < z[low_idx] {
+                low_idx = idx(r, c);
+            }
+        }
+    }
+    let to_local = |i: usize| (i as isize / width - radius, i as isize % width - radius);
+    let step_length = |n: usize| {
+        if dx[n] != 0 && dy[n] != 0 {
+            (res_x * res_x + res_y * res_y).sqrt()
+        } else if dx[n] != 0 {
+            res_x
+        } else {
+            res_y
+        }
+    };
+
+    // Priority-flood from the downstream low point; cells whose spill elevation is
+    // above their own elevation are impounded.
+    let mut spill = vec![f64::INFINITY; z.len()];
+    let mut minheap = BinaryHeap::new();
+    spill[low_idx] = z[low_idx];
+    let (r, c) = to_local(low_idx);
+    minheap.push(GridCell {
+        row: r,
+        column: c,
+        priority: z[low_idx],
+    });
+    while let Some(cell) = minheap.pop() {
+        let i = idx(cell.row, cell.column);
+        if cell.priority > spill[i] {
+            continue;
+        }
+        for n in 0..8 {
+            let (rn, cn) = (cell.row + dy[n], cell.column + dx[n]);
+            if rn < -radius || cn < -radius || rn > radius || cn > radius {
+                continue;
+            }
+            let j = idx(rn, cn);
+            if z[j] == nodata {
+                continue;
+            }
+            let s = spill[i].max(z[j]);
+            if s < spill[j] {
+                spill[j] = s;
+                minheap.push(GridCell {
+                    row: rn,
+                    column: cn,
+                    priority: s,
+                });
+            }
+        }
+    }
+
+    let mut up_idx = usize::MAX;
+    for i in 0..z.len() {
+        if z[i] == nodata || spill[i] <= z[i] {
+            continue;
+        }
+        let (r, c) = to_local(i);
+        if up_idx == usize::MAX
+            || z[i] < z[up_idx]
+            || (z[i] == z[up_idx] && {
+                let (ru, cu) = to_local(up_idx);
+                dist_from_crossing(r, c) < dist_from_crossing(ru, cu)
+            })
+        {
+            up_idx = i;
+        }
+    }
+    if up_idx == usize::MAX {
+        return CulvertResult::failed(CulvertStatus::NoBreachFound);
+    }
+    let upstream_z = z[up_idx];
+
+    // Least-cost paths from the crossing, where entering a cell costs the depth it
+    // must be lowered below the upstream low point (plus a small length penalty).
+    let mut cost = vec![f64::INFINITY; z.len()];
+    let mut back_link = vec![usize::MAX; z.len()];
+    cost[centre] = 0f64;
+    minheap.push(GridCell {
+        row: 0,
+        column: 0,
+        priority: 0f64,
+    });
+    while let Some(cell) = minheap.pop() {
+        let i = idx(cell.row, cell.column);
+        if cell.priority > cost[i] {
+            continue;
+        }
+        for n in 0..8 {
+            let (rn, cn) = (cell.row + dy[n], cell.column + dx[n]);
+            if rn < -radius || cn < -radius || rn > radius || cn > radius {
+                continue;
+            }
+            let j = idx(rn, cn);
+            if z[j] == nodata {
+                continue;
+            }
+            let length = step_length(n);
+            let c = cost[i] + length * ((z[j] - upstream_z).max(0f64) + 0.0001);
+            if c < cost[j] {
+                cost[j] = c;
+                back_link[j] = i;
+                minheap.push(GridCell {
+                    row: rn,
+                    column: cn,
+                    priority: c,
+                });
+            }
+        }
+    }
+    if cost[up_idx].is_infinite() || cost[low_idx].is_infinite() {
+        return CulvertResult::failed(CulvertStatus::NoBreachFound);
+    }
+
+    // The breach path runs from the upstream low point, through the crossing, to the downstream low point.
+    let mut breach_path = vec![];
+    let mut i = up_idx;
+    while i != centre {
+        breach_path.push(i);
+        i = back_link[i];
+    }
+    breach_path.push(centre);
+    let mut downstream = vec![];
+    let mut i = low_idx;
+    while i != centre {
+        downstream.push(i);
+        i = back_link[i];
+    }
+    breach_path.extend(downstream.iter().rev());
+
+    let mut carve_length = 0f64;
+    for w in breach_path.windows(2) {
+        let (r1, c1) = to_local(w[0]);
+        let (r2, c2) = to_local(w[1]);
+        carve_length +=
+            (((r2 - r1) as f64 * res_y).powi(2) + ((c2 - c1) as f64 * res_x).powi(2)).sqrt();
+    }
+    if carve_length > max_length {
+        return CulvertResult::failed(CulvertStatus::NoBreachFound);
+    }
+
+    // Lower the path so that it descends monotonically from the upstream low point.
+    let mut carved_cells = vec![];
+    let mut carve_depth = 0f64;
+    let mut prev_z = upstream_z;
+    for &i in breach_path.iter().skip(1) {
+        let target = prev_z - small_num;
+        if z[i] > target {
+            let (r, c) = to_local(i);
+            carved_cells.push((row + r, col + c, target));
+            carve_depth = carve_depth.max(z[i] - target);
+            prev_z = target;
+        } else {
+            prev_z = z[i];
+        }
+    }
+
+    CulvertResult {
+        status: CulvertStatus::Breached,
+        upstream_z,
+        downstream_z: z[low_idx],
+        carve_depth,
+        carve_length,
+        carved_cells,
+    }
+}
+
+fn read_culvert_points(file_name: &str) -> Result<Vec<(f64, f64)>, Error> {
+    let mut points = vec![];
+    if file_name.to_lowercase().ends_with(".geojson") || file_name.to_lowercase().ends_with(".json")
+    {
+        let geojson_str = fs::read_to_string(file_name)?;
+        let gj: GeoJson = geojson_str
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        match gj {
+            GeoJson::FeatureCollection(fc) => {
+                for feature in fc.features {
+                    if let Some(Geometry { value, .. }) = feature.geometry {
+                        match value {
+                            Value::Point(pt) => points.push((pt[0], pt[1])),
+                            Value::MultiPoint(pts) => {
+                                for pt in pts {
+                                    points.push((pt[0], pt[1]));
+                                }
+                            }
+                            _ => continue, // ignore Line/Polygon features
+                        }
+                    }
+                }
+            }
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "GeoJSON must be a FeatureCollection of Points or MultiPoints.",
+                ))
+            }
+        }
+    } else {
+        let culverts = Shapefile::read(file_name)?;
+        if culverts.header.shape_type.base_shape_type() != ShapeType::Point
+            && culverts.header.shape_type.base_shape_type() != ShapeType::MultiPoint
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector culverts data must be of point base shape type.",
+            ));
+        }
+        for record_num in 0..culverts.num_records {
+            let record = culverts.get_record(record_num);
+            for p in &record.points {
+                points.push((p.x, p.y));
+            }
+        }
+    }
+    Ok(points)
+}
+
+fn write_report(
+    results: &[(usize, f64, f64, isize, isize, CulvertResult)],
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "id\tx\ty\trow\tcolumn\tstatus\tupstream_z\tdownstream_z\tcarve_depth\tcarve_length\tcells_lowered"
+    )?;
+    for (id, x, y, row, col, result) in results {
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{:.3}\t{:.3}\t{:.3}\t{:.3}\t{}",
+            id,
+            x,
+            y,
+            row,
+            col,
+            result.status.as_str(),
+            result.upstream_z,
+            result.downstream_z,
+            result.carve_depth,
+            result.carve_length,
+            result.carved_cells.len()
+        )?;
+    }
+    Ok(())
+}
+
+#[derive(PartialEq, Debug)]
+struct GridCell {
+    row: isize,
+    column: isize,
+    priority: f64,
+}
+
+impl Eq for GridCell {}
+
+impl PartialOrd for GridCell {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        other.priority.partial_cmp(&self.priority)
+    }
+}
+
+impl Ord for GridCell {
+    fn cmp(&self, other: &GridCell) -> Ordering {
+        self.partial_cmp(other).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::HydroEnforceCulverts;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 21 x 21 DEM with 1 m cells sloping gently to the south, crossed by a 5 m high
+    // single-cell road embankment along row 10, with a culvert at the centre of the road.
+    fn write_inputs(dir: &str) {
+        let road = |r: isize| if r == 10 { 5f64 } else { 0f64 };
+        let dem = GridFixture::new(21, 21, |r, _| 100f64 - 0.1 * r as f64 + road(r))
+            .with_resolution(1f64);
+        grid_fixture_raster(&format!("{}/dem.tif", dir), &dem)
+            .write()
+            .unwrap();
+
+        // the centre of cell (10, 10)
+        fs::write(
+            format!("{}/culverts.geojson", dir),
+            r#"{"type": "FeatureCollection", "features": [{"type": "Feature", "properties": {}, "geometry": {"type": "Point", "coordinates": [10.5, 10.5]}}]}"#,
+        )
+        .unwrap();
+    }
+
+    fn run(dir: &str, max_length: f64) -> (Raster, Vec<Vec<String>>) {
+        let args = vec![
+            format!("--dem={}/dem.tif", dir),
+            format!("--culverts={}/culverts.geojson", dir),
+            format!("--output={}/enforced.tif", dir),
+            format!("--report={}/culverts.tsv", dir),
+            format!("--max_length={}", max_length),
+        ];
+        HydroEnforceCulverts::new().run(args, "", false).unwrap();
+        let report = read_tsv(&format!("{}/culverts.tsv", dir)).unwrap();
+        (
+            Raster::new(&format!("{}/enforced.tif", dir), "r").unwrap(),
+            report,
+        )
+    }
+
+    #[test]
+    fn test_breach_single_crossing() {
+        let dir = temp_dir("hydro_enforce_culverts").unwrap();
+        write_inputs(&dir);
+
+        let (dem, report) = run(&dir, 6f64);
+        assert_eq!(report.len(), 2);
+        let fields = &report[1];
+        assert_eq!(fields[5], "breached");
+        assert!((fields[8].parse::<f64>().unwrap() - 4.9).abs() < 0.01);
+
+        // the road cell now sits between its upstream and downstream neighbours
+        let z_up = dem.get_value(9, 10);
+        let z_road = dem.get_value(10, 10);
+        let z_down = dem.get_value(11, 10);
+        assert!(z_road < z_up && z_road > z_down);
+        // the rest of the embankment is untouched
+        assert!((dem.get_value(10, 9) - 104f64).abs() < 1e-4);
+
+        // a search length shorter than the embankment width cannot breach the road
+        let (dem, report) = run(&dir, 1f64);
+        let fields = &report[1];
+        assert_eq!(fields[5], "no_breach_found");
+        assert!((dem.get_value(10, 10) - 104f64).abs() < 1e-4);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,671 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use serde_json::{json, Value as JsonValue};
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool is a pre-flight check of a digital elevation model (`--dem`) before depression
+/// removal. It inventories the cells that have no downslope neighbour, which `FillDepressions`,
+/// `BreachDepressions`, or `BreachDepressionsLeastCost` would need to modify, so that a workflow can
+/// decide how to condition the DEM, or whether it needs conditioning at all. Each cell is compared
+/// with its eight neighbours and classified in the output raster (`--output`) as:
+///
+/// | Value | Class | Cell |
+/// |-------|-------|------|
+/// | 0 | `ok` | Has a lower neighbour, or lies on the edge of the data |
+/// | 1 | `pit` | A single-cell pit: every neighbour is higher |
+/// | 2 | `flat` | Every neighbour has the same elevation |
+/// | 3 | `depression_candidate` | No lower neighbour, but some equal and some higher neighbours |
+///
+/// Depression candidates approximate the floors of multi-cell depressions, and the rims of flat
+/// areas that do not drain. Cells on the edge of the grid, or next to NoData cells, are assumed to
+/// drain off the edge of the data and are classed as `ok`.
+///
+/// The analysis may be restricted to a watershed (`--watershed`); cells outside of the watershed,
+/// i.e. zero or NoData cells, are NoData in the output, although their elevations are still used as
+/// neighbours of the cells inside. The watershed raster must share the grid (rows, columns,
+/// resolution, and extent) of the DEM.
+///
+/// The optional summary (`--summary`) reports, for each class, the number of cells, their area,
+/// the percentage of the analysed cells, and the number of regions, i.e. groups of cells of the class
+/// that are connected through any of their eight neighbours (found by flood fill; every pit is its
+/// own region). The summary is a tab-separated table, or a JSON file with a `classes` array when the
+/// file name ends in `.json`.
+///
+/// # See Also
+/// `FindNoFlowCells`, `FillDepressions`, `BreachDepressions`, `DepthInSink`
+pub struct IdentifyFlatAreasAndPits {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl IdentifyFlatAreasAndPits {
+    pub fn new() -> IdentifyFlatAreasAndPits {
+        // public constructor
+        let name = "IdentifyFlatAreasAndPits".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Classifies the pits, flat areas, and depression candidates of a DEM and summarizes their number and area.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Watershed File (optional)".to_owned(),
+            flags: vec!["--watershed".to_owned()],
+            description:
+                "Optional watershed raster; only its non-zero, non-NoData cells are analysed."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file (0=ok, 1=pit, 2=flat, 3=depression candidate)."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary File (optional)".to_owned(),
+            flags: vec!["--summary".to_owned()],
+            description: "Optional output tab-separated table, or JSON file (*.json), summarizing each class.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif -o=dem_diagnostics.tif --summary=dem_diagnostics.tsv
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --watershed=watershed.tif -o=dem_diagnostics.tif --summary=dem_diagnostics.json", short_exe, name).replace("*", &sep);
+
+        IdentifyFlatAreasAndPits {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for IdentifyFlatAreasAndPits {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut watershed_file = String::new();
+        let mut output_file = String::new();
+        let mut summary_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-dem" || flag_val == "-input" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-watershed" {
+                watershed_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-summary" {
+                summary_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if dem_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Input DEM file (--dem) not specified.",
+            ));
+        }
+        if output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output raster file (--output) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(working_directory, &dem_file);
+        output_file = resolve_path(working_directory, &output_file);
+        if !watershed_file.is_empty() {
+            watershed_file = resolve_path(working_directory, &watershed_file);
+        }
+        if !summary_file.is_empty() {
+            summary_file = resolve_path(working_directory, &summary_file);
+        }
+
+        if !watershed_file.is_empty() {
+            check_rasters_share_geometry(
+                &[("--dem", &dem_file), ("--watershed", &watershed_file)],
+                false,
+            )?;
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+        let watershed = if !watershed_file.is_empty() {
+            Some(Raster::new(&watershed_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = dem.configs.rows as isize;
+        let columns = dem.configs.columns as isize;
+        let nodata = dem.configs.nodata;
+
+        let in_watershed = |row: isize, col: isize| -> bool {
+            match watershed {
+                Some(ref ws) => {
+                    let z = ws.get_value(row, col);
+                    z != ws.configs.nodata && z != 0f64
+                }
+                None => true,
+            }
+        };
+
+        // A single pass over each cell's neighbourhood classifies it; -1 marks unanalysed cells.
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+        let mut class: Array2D<i8> = Array2D::new(rows, columns, -1i8, -1i8)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = dem.get_value(row, col);
+                if z == nodata || !in_watershed(row, col) {
+                    continue;
+                }
+                let (mut num_lower, mut num_equal, mut num_higher) = (0, 0, 0);
+                for n in 0..8 {
+                    let (r, c) = (row + dy[n], col + dx[n]);
+                    let zn = dem.get_value(r, c);
+                    if r < 0 || r >= rows || c < 0 || c >= columns || zn == nodata {
+                        // the cell can drain off the edge of the data
+                        num_lower += 1;
+                    } else if zn < z {
+                        num_lower += 1;
+                    } else if zn == z {
+                        num_equal += 1;
+                    } else {
+                        num_higher += 1;
+                    }
+                    if num_lower > 0 {
+                        break;
+                    }
+                }
+                let value = if num_lower > 0 {
+                    OK
+                } else if num_equal == 0 {
+                    PIT
+                } else if num_higher == 0 {
+                    FLAT
+                } else {
+                    DEPRESSION_CANDIDATE
+                };
+                class.set_value(row, col, value);
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Classifying cells: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // Count the cells of each class, and flood fill the 8-connected regions of each class.
+        let mut summaries: Vec<ClassSummary> = CLASS_NAMES
+            .iter()
+            .map(|_| ClassSummary::default())
+            .collect();
+        let mut visited: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut stack: Vec<(isize, isize)> = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                let value = class.get_value(row, col);
+                if value < 0 {
+                    continue;
+                }
+                summaries[value as usize].num_cells += 1;
+                if visited.get_value(row, col) == 1u8 {
+                    continue;
+                }
+                summaries[value as usize].num_regions += 1;
+                visited.set_value(row, col, 1u8);
+                stack.push((row, col));
+                while let Some((r, c)) = stack.pop() {
+                    for n in 0..8 {
+                        let (rn, cn) = (r + dy[n], c + dx[n]);
+                        if class.get_value(rn, cn) == value && visited.get_value(rn, cn) == 0u8 {
+                            visited.set_value(rn, cn, 1u8);
+                            stack.push((rn, cn));
+                        }
+                    }
+                }
+            }
+        }
+        let num_cells: usize = summaries.iter().map(|s| s.num_cells).sum();
+        if num_cells == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "There are no valid DEM cells to analyse (within the watershed).",
+            ));
+        }
+        let cell_area = dem.configs.resolution_x * dem.configs.resolution_y;
+        for s in summaries.iter_mut() {
+            s.area = s.num_cells as f64 * cell_area;
+            s.percent = 100f64 * s.num_cells as f64 / num_cells as f64;
+        }
+
+        let out_nodata = -32768f64;
+        let mut output = Raster::initialize_using_file(&output_file, &dem);
+        output.configs.data_type = DataType::I16;
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.configs.nodata = out_nodata;
+        output.reinitialize_values(out_nodata);
+        for row in 0..rows {
+            for col in 0..columns {
+                let value = class.get_value(row, col);
+                if value >= 0 {
+                    output.set_value(row, col, value as f64);
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            for (name, s) in CLASS_NAMES.iter().zip(summaries.iter()).skip(1) {
+                println!(
+                    "{}: {} cells ({:.2}%) in {} regions",
+                    name, s.num_cells, s.percent, s.num_regions
+                );
+            }
+        }
+
+        output.configs.display_min = 0f64;
+        output.configs.display_max = 3f64;
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input DEM file: {}", dem_file));
+        if !watershed_file.is_empty() {
+            output.add_metadata_entry(format!("Input watershed file: {}", watershed_file));
+        }
+        output
+            .add_metadata_entry("Classes: 0=ok, 1=pit, 2=flat, 3=depression candidate".to_string());
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !summary_file.is_empty() {
+            if verbose {
+                println!("Writing summary to {}.", summary_file);
+            }
+            if summary_file.to_lowercase().ends_with(".json") {
+                write_summary_to_json(
+                    &summaries,
+                    num_cells,
+                    cell_area,
+                    &dem_file,
+                    &watershed_file,
+                    &summary_file,
+                )?;
+            } else {
+                write_summary_to_tsv(&summaries, &summary_file)?;
+            }
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+const OK: i8 = 0;
+const PIT: i8 = 1;
+const FLAT: i8 = 2;
+const DEPRESSION_CANDIDATE: i8 = 3;
+const CLASS_NAMES: [&str; 4] = ["ok", "pit", "flat", "depression_candidate"];
+
+#[derive(Default)]
+struct ClassSummary {
+    num_cells: usize,
+    area: f64,
+    percent: f64,
+    num_regions: usize,
+}
+
+fn write_summary_to_tsv(summaries: &[ClassSummary], file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "value\tclass\tnum_cells\tarea\tpercent\tnum_regions"
+    )?;
+    for (value, (name, s)) in CLASS_NAMES.iter().zip(summaries.iter()).enumerate() {
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{:.3}\t{:.3}\t{}",
+            value, name, s.num_cells, s.area, s.percent, s.num_regions
+        )?;
+    }
+    Ok(())
+}
+
+fn write_summary_to_json(
+    summaries: &[ClassSummary],
+    num_cells: usize,
+    cell_area: f64,
+    dem_file: &str,
+    watershed_file: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let records: Vec<JsonValue> = CLASS_NAMES
+        .iter()
+        .zip(summaries.iter())
+        .enumerate()
+        .map(|(value, (name, s))| {
+            json!({
+                "value": value,
+                "class": name,
+                "num_cells": s.num_cells,
+                "area": s.area,
+                "percent": s.percent,
+                "num_regions": s.num_regions,
+            })
+        })
+        .collect();
+    let report = json!({
+        "dem": dem_file,
+        "watershed": if watershed_file.is_empty() {
+            JsonValue::Null
+        } else {
+            json!(watershed_file)
+        },
+        "num_cells": num_cells,
+        "cell_area": cell_area,
+        "classes": records,
+    });
+    let mut file = File::create(file_path)?;
+    file.write_all(serde_json::to_string_pretty(&report)?.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::IdentifyFlatAreasAndPits;
+    use crate::tools::WhiteboxTool;
+    use serde_json::Value;
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // Writes a 10 x 10 raster with 10 m cells whose north-west corner is at (0, 100).
+    fn write_raster(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        grid_fixture_raster(file, &GridFixture::new(10, 10, f))
+            .write()
+            .unwrap();
+    }
+
+    // A plane sloping east (z = 100 - col) with two pits, at (2, 2) and (7, 7), and a 3 x 3
+    // basin at z = 50 in rows 2-4, columns 5-7: its centre is flat and the other eight cells
+    // are depression candidates, since their neighbours are either in the basin or higher.
+    fn plane_dem(r: isize, c: isize) -> f64 {
+        match (r, c) {
+            (2, 2) | (7, 7) => 10f64,
+            (2..=4, 5..=7) => 50f64,
+            _ => 100f64 - c as f64,
+        }
+    }
+
+    #[test]
+    fn test_pits_flats_and_depressions() {
+        let dir = temp_dir("identify_flat_areas_and_pits").unwrap();
+        let dem = format!("{}/dem.tif", dir);
+        write_raster(&dem, &plane_dem);
+
+        let output = format!("{}/diagnostics.tif", dir);
+        let summary = format!("{}/summary.tsv", dir);
+        IdentifyFlatAreasAndPits::new()
+            .run(
+                vec![
+                    format!("--dem={}", dem),
+                    format!("-o={}", output),
+                    format!("--summary={}", summary),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let raster = Raster::new(&output, "r").unwrap();
+        for r in 0..10isize {
+            for c in 0..10isize {
+                let expected = match (r, c) {
+                    (2, 2) | (7, 7) => 1f64,
+                    (3, 6) => 2f64,
+                    (2..=4, 5..=7) => 3f64,
+                    _ => 0f64,
+                };
+                assert_eq!(raster.get_value(r, c), expected, "row {} col {}", r, c);
+            }
+        }
+        let rows = read_tsv(&summary).unwrap();
+        assert_eq!(
+            rows[0],
+            [
+                "value",
+                "class",
+                "num_cells",
+                "area",
+                "percent",
+                "num_regions"
+            ]
+        );
+        assert_eq!(
+            rows[2],
+            ["1", "pit", "2", "200.000", "2.000", "2"].map(|s| s.to_string())
+        );
+        assert_eq!(
+            rows[3],
+            ["2", "flat", "1", "100.000", "1.000", "1"].map(|s| s.to_string())
+        );
+        assert_eq!(
+            rows[4],
+            ["3", "depression_candidate", "8", "800.000", "8.000", "1"].map(|s| s.to_string())
+        );
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // A DEM that is flat except for a higher ridge along column 4 has two flat regions, on
+    // either side of the ridge; cells on the edge of the grid or next to NoData are ok.
+    #[test]
+    fn test_flat_regions_and_watershed() {
+        let dir = temp_dir("identify_flat_areas_and_pits_ws").unwrap();
+        let dem = format!("{}/dem.tif", dir);
+        let watershed = format!("{}/ws.tif", dir);
+        write_raster(&dem, &|r, c| match (r, c) {
+            (5, 8) => -32768f64,
+            (_, 4) => 20f64,
+            _ => 10f64,
+        });
+        write_raster(&watershed, &|r, _| if r < 8 { 1f64 } else { 0f64 });
+
+        let output = format!("{}/diagnostics.tif", dir);
+        let summary = format!("{}/summary.json", dir);
+        IdentifyFlatAreasAndPits::new()
+            .run(
+                vec![
+                    "--dem=dem.tif".to_string(),
+                    "--watershed=ws.tif".to_string(),
+                    "-o=diagnostics.tif".to_string(),
+                    "--summary=summary.json".to_string(),
+                ],
+                &dir,
+                false,
+            )
+            .unwrap();
+        let raster = Raster::new(&output, "r").unwrap();
+        let mut flat_cells = 0;
+        for r in 0..10isize {
+            for c in 0..10isize {
+                let value = raster.get_value(r, c);
+                if r >= 8 || (r, c) == (5, 8) {
+                    assert_eq!(value, -32768f64, "row {} col {}", r, c);
+                } else if r == 0 || c == 0 || c == 9 || (4..=6).contains(&r) && (7..=9).contains(&c)
+                {
+                    assert_eq!(value, 0f64, "row {} col {}", r, c);
+                } else if (3..=5).contains(&c) {
+                    // the ridge, and the cells beside it with higher and equal neighbours
+                    assert_eq!(
+                        value,
+                        if c == 4 { 0f64 } else { 3f64 },
+                        "row {} col {}",
+                        r,
+                        c
+                    );
+                } else {
+                    assert_eq!(value, 2f64, "row {} col {}", r, c);
+                    flat_cells += 1;
+                }
+            }
+        }
+        let json: Value = serde_json::from_str(&fs::read_to_string(&summary).unwrap()).unwrap();
+        assert_eq!(json["num_cells"], 79);
+        let flat = &json["classes"][2];
+        assert_eq!(flat["class"], "flat");
+        assert_eq!(flat["num_cells"], flat_cells);
+        assert_eq!(flat["num_regions"], 2);
+        assert_eq!(json["classes"][1]["num_cells"], 0);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
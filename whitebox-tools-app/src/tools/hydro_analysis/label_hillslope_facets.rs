@@ -0,0 +1,812 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap};
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool splits each hillslope of a TOPAZ-style sub-catchment raster (`--subwta`), e.g. the
+/// output of `HillslopesTopaz`, into facets of similar aspect, so that attributes such as burn
+/// severity can be summarized per facet rather than per hillslope. Hillslopes are the cells whose
+/// IDs end in 1, 2, or 3; channels (IDs ending in 4) and other cells are NoData in the outputs.
+///
+/// The aspect of each cell, in degrees clockwise from north, is read from an `--aspect` raster,
+/// e.g. the output of `Aspect`, in which negative values mark flat cells. Alternatively, it is
+/// computed from a `--dem` using Horn's (1981) 3 x 3 finite differences, with NoData neighbours
+/// taking the value of the centre cell. The circle of aspects is divided into `--n_classes` equal
+/// classes (4 by default, at most 9), numbered clockwise from 1 for the class centred on north;
+/// with four classes these are N (315-45°), E, S, and W. Flat cells, and cells without an aspect,
+/// are class 0.
+///
+/// A facet is a 4-connected patch of cells of one aspect class within one hillslope. Facets
+/// smaller than `--min_area` (in squared map units, 0 by default) are merged, smallest first,
+/// into the largest facet of the same hillslope that shares an edge with them, taking its class,
+/// so that noisy aspects do not break hillslopes into slivers. Facets never merge across
+/// hillslope boundaries, and a small facet without a neighbouring facet in its hillslope is kept.
+///
+/// The output raster (`--output`) codes each hillslope cell as `hillslope_id * 10 + facet_class`,
+/// e.g. 221 to 224 for the N to W facets of hillslope 22. The output table (`--output_table`) is
+/// a tab-separated file with one row per hillslope and facet class, ordered by TOPAZ ID and class,
+/// and the following columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `topaz_id` | The hillslope's TOPAZ ID. |
+/// | `facet_id` | The facet code in the output raster. |
+/// | `facet_class` | The aspect class, 0 for flat cells. |
+/// | `direction` | The compass direction of the class with 4 or 8 classes, otherwise its central aspect in degrees; `flat` for class 0. |
+/// | `num_cells` | The number of cells of the facet class, which may span several patches. |
+/// | `area` | The area of the facet class, in squared map units. |
+/// | `fraction` | The facet class's share of the hillslope's area. |
+///
+/// # See Also
+/// `HillslopesTopaz`, `Aspect`, `HillslopeWidthFunction`
+pub struct LabelHillslopeFacets {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl LabelHillslopeFacets {
+    pub fn new() -> LabelHillslopeFacets {
+        // public constructor
+        let name = "LabelHillslopeFacets".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Splits each TOPAZ hillslope into facets of similar aspect, merging facets below a minimum area."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Subwta File".to_owned(),
+            flags: vec!["--subwta".to_owned()],
+            description: "Input TOPAZ-style hillslope and channel ID raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input DEM raster file from which aspects are computed; required unless --aspect is specified.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Aspect File".to_owned(),
+            flags: vec!["--aspect".to_owned()],
+            description: "Input aspect raster file, in degrees clockwise from north with negative values for flat cells; used instead of --dem.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Aspect Classes".to_owned(),
+            flags: vec!["--n_classes".to_owned()],
+            description: "Number of equal aspect classes, from 1 to 9, the first centred on north."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("4".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Facet Area".to_owned(),
+            flags: vec!["--min_area".to_owned()],
+            description: "Facets smaller than this area, in squared map units, are merged into the largest neighbouring facet of the same hillslope.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output facet raster file, coded hillslope_id * 10 + facet_class."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Table File".to_owned(),
+            flags: vec!["--output_table".to_owned()],
+            description: "Output tab-separated table of facet areas per hillslope.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --dem='dem.tif' --min_area=900 -o='facets.tif' --output_table='facets.tsv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --aspect='aspect.tif' --n_classes=8 -o='facets.tif' --output_table='facets.tsv'", short_exe, name).replace("*", &sep);
+
+        LabelHillslopeFacets {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for LabelHillslopeFacets {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut subwta_file = String::new();
+        let mut dem_file = String::new();
+        let mut aspect_file = String::new();
+        let mut n_classes = 4usize;
+        let mut min_area = 0f64;
+        let mut output_file = String::new();
+        let mut table_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-subwta" {
+                subwta_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-aspect" {
+                aspect_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-n_classes" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                n_classes = match value.trim().parse::<usize>() {
+                    Ok(n) if (1..=9).contains(&n) => n,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "--n_classes must be an integer from 1 to 9; got '{}'.",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            } else if flag_val == "-min_area" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                min_area = match value.trim().parse::<f64>() {
+                    Ok(a) if a >= 0f64 => a,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("--min_area must be a non-negative number; got '{}'.", value),
+                        ))
+                    }
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_table" {
+                table_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if dem_file.trim().is_empty() == aspect_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Specify exactly one of a DEM (--dem) or an aspect raster (--aspect).",
+            ));
+        }
+        if output_file.trim().is_empty() || table_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Both the output raster (--output) and the output table (--output_table) must be specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        subwta_file = resolve_path(working_directory, &subwta_file);
+        output_file = resolve_path(working_directory, &output_file);
+        table_file = resolve_path(working_directory, &table_file);
+        let (surface_flag, surface_file) = if aspect_file.trim().is_empty() {
+            ("--dem", resolve_path(working_directory, &dem_file))
+        } else {
+            ("--aspect", resolve_path(working_directory, &aspect_file))
+        };
+        check_rasters_share_geometry(
+            &[("--subwta", &subwta_file), (surface_flag, &surface_file)],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let subwta = Raster::new(&subwta_file, "r")?;
+        let surface = Raster::new(&surface_file, "r")?;
+        let rows = subwta.configs.rows as isize;
+        let columns = subwta.configs.columns as isize;
+
+        let start = Instant::now();
+
+        // The TOPAZ ID of each hillslope cell, or 0 for channels, NoData, and other cells.
+        let subwta_nodata = subwta.configs.nodata;
+        let mut ids: Array2D<i64> = Array2D::new(rows, columns, 0, 0)?;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = subwta.get_value(row, col);
+                if z != subwta_nodata && z > 0f64 {
+                    let id = z.round() as i64;
+                    if matches!(id % 10, 1..=3) {
+                        ids.set_value(row, col, id);
+                    }
+                }
+            }
+        }
+
+        // The aspect class of each hillslope cell.
+        let mut classes: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                if ids.get_value(row, col) == 0 {
+                    continue;
+                }
+                let aspect = if surface_flag == "--dem" {
+                    horn_aspect(&surface, row, col)
+                } else {
+                    let z = surface.get_value(row, col);
+                    if z != surface.configs.nodata && z >= 0f64 {
+                        Some(z)
+                    } else {
+                        None
+                    }
+                };
+                classes.set_value(row, col, aspect_class(aspect, n_classes));
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Classifying aspects: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let cell_area = subwta.configs.resolution_x * subwta.configs.resolution_y;
+        let (patch_ids, patches) = label_patches(&ids, &classes)?;
+        if patches.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No hillslope cells (IDs ending in 1, 2, or 3) were found.",
+            ));
+        }
+        let min_cells = min_area / cell_area;
+        let (facet_classes, num_merged) = merge_small_patches(&patches, min_cells);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let out_nodata = -32768f64;
+        let mut output = Raster::initialize_using_file(&output_file, &subwta);
+        output.configs.data_type = DataType::I32;
+        output.configs.nodata = out_nodata;
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        output.reinitialize_values(out_nodata);
+        // The number of cells of each hillslope's facet classes.
+        let mut facets: BTreeMap<i64, BTreeMap<u8, usize>> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let patch = patch_ids.get_value(row, col);
+                if patch < 0 {
+                    continue;
+                }
+                let id = ids.get_value(row, col);
+                let class = facet_classes[patch as usize];
+                output.set_value(row, col, (id * 10 + class as i64) as f64);
+                *facets.entry(id).or_default().entry(class).or_insert(0) += 1;
+            }
+        }
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Subwta file: {}", subwta_file));
+        output.add_metadata_entry(format!(
+            "Aspect source ({}): {}",
+            surface_flag, surface_file
+        ));
+        output.add_metadata_entry(format!("Number of aspect classes: {}", n_classes));
+        output.add_metadata_entry(format!("Minimum facet area: {}", min_area));
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        write_table(&facets, n_classes, cell_area, &table_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!(
+                "Labelled {} hillslopes with {} facet patches ({} merged below the minimum area).",
+                facets.len(),
+                patches.len() - num_merged,
+                num_merged
+            );
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The aspect of a DEM cell in degrees clockwise from north, from Horn's (1981) 3 x 3 finite
+/// differences; NoData neighbours take the centre's elevation. `None` for NoData and flat cells.
+fn horn_aspect(dem: &Raster, row: isize, col: isize) -> Option<f64> {
+    let nodata = dem.configs.nodata;
+    let z0 = dem.get_value(row, col);
+    if z0 == nodata {
+        return None;
+    }
+    let z = |dr: isize, dc: isize| {
+        let v = dem.get_value(row + dr, col + dc);
+        if v == nodata {
+            z0
+        } else {
+            v
+        }
+    };
+    // The gradient towards the east and the north.
+    let gx = ((z(-1, 1) + 2f64 * z(0, 1) + z(1, 1)) - (z(-1, -1) + 2f64 * z(0, -1) + z(1, -1)))
+        / (8f64 * dem.configs.resolution_x);
+    let gy = ((z(-1, -1) + 2f64 * z(-1, 0) + z(-1, 1)) - (z(1, -1) + 2f64 * z(1, 0) + z(1, 1)))
+        / (8f64 * dem.configs.resolution_y);
+    if gx == 0f64 && gy == 0f64 {
+        return None;
+    }
+    // The cell faces down the gradient.
+    Some((-gx).atan2(-gy).to_degrees().rem_euclid(360f64))
+}
+
+/// The aspect class of an aspect in degrees: 1 to `n_classes` clockwise from the class centred on
+/// north, or 0 for a cell without an aspect.
+fn aspect_class(aspect: Option<f64>, n_classes: usize) -> u8 {
+    match aspect {
+        Some(a) => {
+            let width = 360f64 / n_classes as f64;
+            let class = ((a + width / 2f64).rem_euclid(360f64) / width).floor() as usize;
+            (class.min(n_classes - 1) + 1) as u8
+        }
+        None => 0,
+    }
+}
+
+/// The label of an aspect class in the output table.
+fn class_direction(class: u8, n_classes: usize) -> String {
+    const COMPASS_4: [&str; 4] = ["N", "E", "S", "W"];
+    const COMPASS_8: [&str; 8] = ["N", "NE", "E", "SE", "S", "SW", "W", "NW"];
+    match (class, n_classes) {
+        (0, _) => "flat".to_string(),
+        (c, 4) => COMPASS_4[c as usize - 1].to_string(),
+        (c, 8) => COMPASS_8[c as usize - 1].to_string(),
+        (c, n) => format!("{}", (c as f64 - 1f64) * 360f64 / n as f64),
+    }
+}
+
+/// A 4-connected patch of hillslope cells of one aspect class.
+struct Patch {
+    class: u8,
+    num_cells: usize,
+    /// The patches of the same hillslope that share an edge with this one.
+    neighbours: BTreeSet<usize>,
+}
+
+/// Labels the 4-connected patches of cells sharing a hillslope ID and aspect class, returning
+/// each cell's patch index (-1 outside hillslopes) and the patches in raster order.
+fn label_patches(
+    ids: &Array2D<i64>,
+    classes: &Array2D<u8>,
+) -> Result<(Array2D<i64>, Vec<Patch>), Error> {
+    let rows = ids.rows();
+    let columns = ids.columns();
+    let dx = [1, 0, -1, 0];
+    let dy = [0, 1, 0, -1];
+    let mut patch_ids: Array2D<i64> = Array2D::new(rows, columns, -1, -1)?;
+    let mut patches: Vec<Patch> = vec![];
+    let mut stack = vec![];
+    for row in 0..rows {
+        for col in 0..columns {
+            let id = ids.get_value(row, col);
+            if id == 0 || patch_ids.get_value(row, col) >= 0 {
+                continue;
+            }
+            let class = classes.get_value(row, col);
+            let patch = patches.len() as i64;
+            let mut num_cells = 0usize;
+            patch_ids.set_value(row, col, patch);
+            stack.push((row, col));
+            while let Some((r, c)) = stack.pop() {
+                num_cells += 1;
+                for n in 0..4 {
+                    let (rn, cn) = (r + dy[n], c + dx[n]);
+                    if ids.get_value(rn, cn) == id
+                        && classes.get_value(rn, cn) == class
+                        && patch_ids.get_value(rn, cn) < 0
+                    {
+                        patch_ids.set_value(rn, cn, patch);
+                        stack.push((rn, cn));
+                    }
+                }
+            }
+            patches.push(Patch {
+                class,
+                num_cells,
+                neighbours: BTreeSet::new(),
+            });
+        }
+    }
+
+    // Only the east and south neighbours are visited, so each shared edge is seen once.
+    for row in 0..rows {
+        for col in 0..columns {
+            let patch = patch_ids.get_value(row, col);
+            if patch < 0 {
+                continue;
+            }
+            for n in 0..2 {
+                let (rn, cn) = (row + dy[n], col + dx[n]);
+                let other = patch_ids.get_value(rn, cn);
+                if other >= 0 && other != patch && ids.get_value(rn, cn) == ids.get_value(row, col)
+                {
+                    patches[patch as usize].neighbours.insert(other as usize);
+                    patches[other as usize].neighbours.insert(patch as usize);
+                }
+            }
+        }
+    }
+    Ok((patch_ids, patches))
+}
+
+/// Merges patches of fewer than `min_cells` cells, smallest first, into their largest
+/// neighbouring patch (the first in raster order on ties), which may in turn be merged if it is
+/// still too small. Returns the final aspect class of each patch and the number of patches
+/// merged.
+fn merge_small_patches(patches: &[Patch], min_cells: f64) -> (Vec<u8>, usize) {
+    let mut parent: Vec<usize> = (0..patches.len()).collect();
+    let mut num_cells: Vec<usize> = patches.iter().map(|p| p.num_cells).collect();
+    let mut neighbours: Vec<BTreeSet<usize>> =
+        patches.iter().map(|p| p.neighbours.clone()).collect();
+    fn find(parent: &mut [usize], mut p: usize) -> usize {
+        while parent[p] != p {
+            parent[p] = parent[parent[p]];
+            p = parent[p];
+        }
+        p
+    }
+
+    let mut heap: BinaryHeap<Reverse<(usize, usize)>> = num_cells
+        .iter()
+        .enumerate()
+        .map(|(p, &n)| Reverse((n, p)))
+        .collect();
+    let mut num_merged = 0usize;
+    while let Some(Reverse((cells, p))) = heap.pop() {
+        if (cells as f64) >= min_cells {
+            break;
+        }
+        // Skip entries for merged patches and stale sizes.
+        if find(&mut parent, p) != p || num_cells[p] != cells {
+            continue;
+        }
+        let candidates: BTreeSet<usize> = std::mem::take(&mut neighbours[p])
+            .into_iter()
+            .map(|q| find(&mut parent, q))
+            .filter(|&q| q != p)
+            .collect();
+        // A patch without neighbours, e.g. a whole hillslope of one class, is kept.
+        if let Some(t) = candidates
+            .iter()
+            .copied()
+            .max_by_key(|&q| (num_cells[q], Reverse(q)))
+        {
+            parent[p] = t;
+            num_cells[t] += cells;
+            neighbours[t].extend(candidates.into_iter().filter(|&q| q != t));
+            heap.push(Reverse((num_cells[t], t)));
+            num_merged += 1;
+        }
+    }
+
+    let classes = (0..patches.len())
+        .map(|p| patches[find(&mut parent, p)].class)
+        .collect();
+    (classes, num_merged)
+}
+
+fn write_table(
+    facets: &BTreeMap<i64, BTreeMap<u8, usize>>,
+    n_classes: usize,
+    cell_area: f64,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "topaz_id\tfacet_id\tfacet_class\tdirection\tnum_cells\tarea\tfraction"
+    )?;
+    for (id, classes) in facets {
+        let total: usize = classes.values().sum();
+        for (&class, &num_cells) in classes {
+            writeln!(
+                &mut file,
+                "{}\t{}\t{}\t{}\t{}\t{:.3}\t{:.4}",
+                id,
+                id * 10 + class as i64,
+                class,
+                class_direction(class, n_classes),
+                num_cells,
+                num_cells as f64 * cell_area,
+                num_cells as f64 / total as f64
+            )?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{aspect_class, class_direction, LabelHillslopeFacets};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    fn write_grid(file: &str, rows: usize, columns: usize, f: &dyn Fn(isize, isize) -> f64) {
+        grid_fixture_raster(file, &GridFixture::new(rows, columns, f))
+            .write()
+            .unwrap();
+    }
+
+    fn run(dir: &str, extra: &[&str]) -> Vec<Vec<String>> {
+        let mut args = vec![
+            format!("--subwta={}/subwta.tif", dir),
+            format!("--output={}/facets.tif", dir),
+            format!("--output_table={}/facets.tsv", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        LabelHillslopeFacets::new().run(args, "", false).unwrap();
+        fs::read_to_string(format!("{}/facets.tsv", dir))
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|l| l.split('\t').map(|s| s.to_string()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_aspect_classes() {
+        assert_eq!(aspect_class(Some(0f64), 4), 1);
+        assert_eq!(aspect_class(Some(350f64), 4), 1);
+        assert_eq!(aspect_class(Some(45f64), 4), 2);
+        assert_eq!(aspect_class(Some(180f64), 4), 3);
+        assert_eq!(aspect_class(Some(300f64), 4), 4);
+        assert_eq!(aspect_class(Some(360f64), 4), 1);
+        assert_eq!(aspect_class(Some(100f64), 8), 3);
+        assert_eq!(aspect_class(Some(200f64), 1), 1);
+        assert_eq!(aspect_class(None, 4), 0);
+        assert_eq!(class_direction(2, 4), "E");
+        assert_eq!(class_direction(8, 8), "NW");
+        assert_eq!(class_direction(2, 3), "120");
+        assert_eq!(class_direction(0, 4), "flat");
+    }
+
+    // A cone peaking at the centre of a 21 x 21 hillslope. Its flat peak is merged into a
+    // neighbouring facet, leaving four facets of about a quarter of the hillslope each.
+    #[test]
+    fn test_conical_hill_has_four_equal_facets() {
+        let dir = temp_dir("label_hillslope_facets_cone").unwrap();
+        write_grid(&format!("{}/subwta.tif", dir), 21, 21, &|_, _| 22f64);
+        write_grid(&format!("{}/dem.tif", dir), 21, 21, &|r, c| {
+            let (dr, dc) = ((r - 10) as f64, (c - 10) as f64);
+            100f64 - (dr * dr + dc * dc).sqrt()
+        });
+        let rows = run(&dir, &["--dem={dir}/dem.tif", "--min_area=200"]);
+        assert_eq!(rows.len(), 4);
+        let directions: Vec<&str> = rows.iter().map(|r| r[3].as_str()).collect();
+        assert_eq!(directions, vec!["N", "E", "S", "W"]);
+        for row in &rows {
+            assert_eq!(row[0], "22");
+            let cells: usize = row[4].parse().unwrap();
+            assert!((110..=111).contains(&cells), "{:?}", row);
+            let fraction: f64 = row[6].parse().unwrap();
+            assert!((fraction - 0.25).abs() < 0.01, "{:?}", row);
+        }
+        let facets = Raster::new(&format!("{}/facets.tif", dir), "r").unwrap();
+        assert_eq!(facets.get_value(0, 10), 221f64);
+        assert_eq!(facets.get_value(10, 20), 222f64);
+        assert_eq!(facets.get_value(20, 10), 223f64);
+        assert_eq!(facets.get_value(10, 0), 224f64);
+
+        // Without merging, the peak is a flat facet of its own.
+        let rows = run(&dir, &["--dem={dir}/dem.tif"]);
+        assert_eq!(rows.len(), 5);
+        assert_eq!((rows[0][2].as_str(), rows[0][4].as_str()), ("0", "1"));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    // Hillslope 22 (columns 0-3) faces east and hillslope 23 (columns 4-5) faces south, each with
+    // a one-cell north-facing patch on their shared boundary; cell (3, 5) is a one-cell hillslope
+    // 21 and (3, 0) is a channel.
+    #[test]
+    fn test_small_facets_merge_within_hillslopes() {
+        let dir = temp_dir("label_hillslope_facets_merge").unwrap();
+        write_grid(&format!("{}/subwta.tif", dir), 4, 6, &|r, c| match (r, c) {
+            (3, 0) => 24f64,
+            (3, 5) => 21f64,
+            (_, 0..=3) => 22f64,
+            _ => 23f64,
+        });
+        write_grid(&format!("{}/aspect.tif", dir), 4, 6, &|r, c| match (r, c) {
+            (1, 3) | (1, 4) => 0f64,
+            (3, 5) => 270f64,
+            (_, 0..=3) => 90f64,
+            _ => 180f64,
+        });
+        let rows = run(&dir, &["--aspect={dir}/aspect.tif"]);
+        let summary: Vec<(&str, &str, &str)> = rows
+            .iter()
+            .map(|r| (r[0].as_str(), r[1].as_str(), r[4].as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("21", "214", "1"),
+                ("22", "221", "1"),
+                ("22", "222", "14"),
+                ("23", "231", "1"),
+                ("23", "233", "6"),
+            ]
+        );
+
+        // The north-facing cells join the facet of their own hillslope, and the one-cell
+        // hillslope has no neighbouring facet to join.
+        let rows = run(&dir, &["--aspect={dir}/aspect.tif", "--min_area=150"]);
+        let summary: Vec<(&str, &str, &str)> = rows
+            .iter()
+            .map(|r| (r[0].as_str(), r[1].as_str(), r[4].as_str()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![("21", "214", "1"), ("22", "222", "15"), ("23", "233", "7")]
+        );
+        let facets = Raster::new(&format!("{}/facets.tif", dir), "r").unwrap();
+        assert_eq!(facets.get_value(1, 3), 222f64);
+        assert_eq!(facets.get_value(1, 4), 233f64);
+        assert_eq!(facets.get_value(3, 0), facets.configs.nodata);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_requires_one_aspect_source() {
+        let dir = temp_dir("label_hillslope_facets_args").unwrap();
+        write_grid(&format!("{}/subwta.tif", dir), 2, 2, &|_, _| 22f64);
+        let args = |extra: &[&str]| {
+            let mut args = vec![
+                format!("--subwta={}/subwta.tif", dir),
+                format!("--output={}/facets.tif", dir),
+                format!("--output_table={}/facets.tsv", dir),
+            ];
+            args.extend(extra.iter().map(|a| a.replace("{dir}", &dir)));
+            args
+        };
+        assert!(LabelHillslopeFacets::new()
+            .run(args(&[]), "", false)
+            .is_err());
+        let both = args(&["--dem={dir}/subwta.tif", "--aspect={dir}/subwta.tif"]);
+        assert!(LabelHillslopeFacets::new().run(both, "", false).is_err());
+        let too_many = args(&["--aspect={dir}/subwta.tif", "--n_classes=10"]);
+        assert!(LabelHillslopeFacets::new()
+            .run(too_many, "", false)
+            .is_err());
+        fs::remove_dir_all(&dir).ok();
+    }
+}
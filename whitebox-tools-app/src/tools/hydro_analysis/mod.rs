@@ -1,15 +1,21 @@
 // private sub-module defined in other files
+mod accumulate_along_channels;
 mod average_flowpath_slope;
 mod average_upslope_flowpath_length;
+mod basin_outlet_hydraulic_geometry;
 mod basins;
 mod breach_depressions;
 mod breach_depressions_least_cost;
 mod breach_pits;
 mod burn_streams_at_roads;
+mod catchment_delineation_batch;
+mod cell_path_upstream_trace;
+mod channel_junction_angles;
 mod d8_flow_accum;
 mod d8_mass_flux;
 mod d8_pointer;
 mod depth_in_sink;
+mod detect_parallel_stream_artifacts;
 mod dinf_flow_accum;
 mod dinf_mass_flux;
 mod dinf_pointer;
@@ -31,40 +37,59 @@ mod flatten_lakes;
 mod flood_order;
 mod flow_accum_full_workflow;
 mod flow_length_diff;
+mod flow_path_density;
 mod hillslopes;
+mod hillslope_representative_profile_plot_data;
+mod hillslope_width_function;
 mod hillslopes_topaz;
+mod hydro_enforce_culverts;
+mod identify_flat_areas_and_pits;
 mod impoundment_index;
 mod insert_dams;
 mod isobasins;
 mod jenson_snap_pour_points;
+mod label_hillslope_facets;
 mod longest_flowpath;
 mod max_upslope_flowpath;
 mod mdinf_flow_accum;
 mod num_inflowing_neighbours;
+mod outlet_sensitivity_analysis;
+mod pour_point_from_largest_stream;
+mod pour_point_locator;
 mod raise_walls;
+pub(crate) mod raster_geometry;
 mod rho8_pointer;
 mod sink;
 mod snap_pour_points;
 mod stochastic_depression_analysis;
 mod strahler_basins;
 mod subbasins;
+mod subwta_to_wepp_management_grid;
 mod trace_downslope_flowpaths;
 mod unnest_basins;
 mod upslope_depression_storage;
 mod watershed;
+mod watershed_area_report;
+mod wepp_channel_parameter_table;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::accumulate_along_channels::AccumulateAlongChannels;
 pub use self::average_flowpath_slope::AverageFlowpathSlope;
 pub use self::average_upslope_flowpath_length::AverageUpslopeFlowpathLength;
+pub use self::basin_outlet_hydraulic_geometry::BasinOutletHydraulicGeometry;
 pub use self::basins::Basins;
 pub use self::breach_depressions::BreachDepressions;
 pub use self::breach_depressions_least_cost::BreachDepressionsLeastCost;
 pub use self::breach_pits::BreachSingleCellPits;
 pub use self::burn_streams_at_roads::BurnStreamsAtRoads;
+pub use self::catchment_delineation_batch::CatchmentDelineationBatch;
+pub use self::cell_path_upstream_trace::CellPathUpstreamTrace;
+pub use self::channel_junction_angles::ChannelJunctionAngles;
 pub use self::d8_flow_accum::D8FlowAccumulation;
 pub use self::d8_mass_flux::D8MassFlux;
 pub use self::d8_pointer::D8Pointer;
 pub use self::depth_in_sink::DepthInSink;
+pub use self::detect_parallel_stream_artifacts::DetectParallelStreamArtifacts;
 pub use self::dinf_flow_accum::DInfFlowAccumulation;
 pub use self::dinf_mass_flux::DInfMassFlux;
 pub use self::dinf_pointer::DInfPointer;
@@ -86,16 +111,24 @@ pub use self::flatten_lakes::FlattenLakes;
 pub use self::flood_order::FloodOrder;
 pub use self::flow_accum_full_workflow::FlowAccumulationFullWorkflow;
 pub use self::flow_length_diff::FlowLengthDiff;
+pub use self::flow_path_density::FlowPathDensity;
 pub use self::hillslopes::Hillslopes;
+pub use self::hillslope_representative_profile_plot_data::HillslopeRepresentativeProfilePlotData;
+pub use self::hillslope_width_function::HillslopeWidthFunction;
 pub use self::hillslopes_topaz::HillslopesTopaz;
+pub use self::hydro_enforce_culverts::HydroEnforceCulverts;
+pub use self::identify_flat_areas_and_pits::IdentifyFlatAreasAndPits;
 pub use self::impoundment_index::ImpoundmentSizeIndex;
 pub use self::insert_dams::InsertDams;
 pub use self::isobasins::Isobasins;
 pub use self::jenson_snap_pour_points::JensonSnapPourPoints;
+pub use self::label_hillslope_facets::LabelHillslopeFacets;
 pub use self::longest_flowpath::LongestFlowpath;
 pub use self::max_upslope_flowpath::MaxUpslopeFlowpathLength;
 pub use self::mdinf_flow_accum::MDInfFlowAccumulation;
 pub use self::num_inflowing_neighbours::NumInflowingNeighbours;
+pub use self::outlet_sensitivity_analysis::OutletSensitivityAnalysis;
+pub use self::pour_point_from_largest_stream::PourPointFromLargestStream;
 pub use self::raise_walls::RaiseWalls;
 pub use self::rho8_pointer::Rho8Pointer;
 pub use self::sink::Sink;
@@ -103,7 +136,10 @@ pub use self::snap_pour_points::SnapPourPoints;
 pub use self::stochastic_depression_analysis::StochasticDepressionAnalysis;
 pub use self::strahler_basins::StrahlerOrderBasins;
 pub use self::subbasins::Subbasins;
+pub use self::subwta_to_wepp_management_grid::SubwtaToWeppManagementGrid;
 pub use self::trace_downslope_flowpaths::TraceDownslopeFlowpaths;
 pub use self::unnest_basins::UnnestBasins;
 pub use self::upslope_depression_storage::UpslopeDepressionStorage;
 pub use self::watershed::Watershed;
+pub use self::watershed_area_report::WatershedAreaReport;
+pub use self::wepp_channel_parameter_table::WeppChannelParameterTable;
@@ -0,0 +1,941 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use super::find_outlet::{parse_pair_list, OutletTracer};
+use super::pour_point_locator::locate_pour_point;
+use super::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use rand::prelude::*;
+use rand::rngs::SmallRng;
+use serde_json::{json, Value as JsonValue};
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::algorithms::D8Walker;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool measures how sensitive a delineated watershed is to the placement of its outlet. A
+/// requested outlet is given, as with `FindOutlet`, by `--requested_outlet_row_col`,
+/// `--requested_outlet_lng_lat`, or a single point in `--pour_pts`. The requested cell is moved
+/// `--n_samples` times (default 25) to a cell chosen at random, with equal probability, from the
+/// cells within `--jitter_cells` cells (default 2) of it, i.e. within a disk of that radius that is
+/// clipped to the grid. Each moved request is resolved to an outlet exactly as `FindOutlet` resolves
+/// a requested outlet: it is traced downslope along the D8 pointer (`--d8_pntr`) to the first stream
+/// cell with a single inflowing stream cell. A cell of the streams raster (`--streams`) is a channel
+/// when its value is greater than `--stream_threshold` (default 0). The upslope area of each
+/// distinct outlet is then delineated in memory. Samples are drawn from a generator seeded with
+/// `--seed` (default 0), so a run is repeatable.
+///
+/// The unperturbed request is also resolved, to the reference outlet, and it is an error if that
+/// trace fails. A sample converges when its outlet is the reference outlet. Samples whose trace
+/// fails are reported with the reason, count against the convergence fractions, and are left out
+/// of the area statistics.
+///
+/// The report (`--output`) is written as JSON if its name ends in `.json`, with the reference
+/// outlet, the fraction of samples converging to it (`same_outlet_fraction`), the most common
+/// outlet and its fraction (`modal_outlet`), every distinct outlet with its sample count and area,
+/// the minimum, quartiles, maximum, mean, standard deviation, and coefficient of variation of the
+/// sampled basin areas, and a `samples` array. Otherwise it is a tab-separated table with one row
+/// per sample and the `sample`, `start_row`, `start_col`, `status`, `outlet_row`, `outlet_col`,
+/// `num_cells`, `area`, `same_as_reference`, and `failure_reason` columns. Areas are in squared map
+/// units.
+///
+/// The optional agreement raster (`--agreement`) counts, for each cell, the samples whose basin
+/// contains it. Cells in no sampled basin are 0 and NoData pointer cells are NoData.
+///
+/// # See Also
+/// `FindOutlet`, `Watershed`, `StochasticDepressionAnalysis`
+pub struct OutletSensitivityAnalysis {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl OutletSensitivityAnalysis {
+    pub fn new() -> OutletSensitivityAnalysis {
+        // public constructor
+        let name = "OutletSensitivityAnalysis".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Perturbs a requested outlet and reports how the resolved outlet and its basin area vary.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file (1=stream, 0=non-stream).".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Stream Threshold".to_owned(),
+            flags: vec!["--stream_threshold".to_owned()],
+            description: "Optional value above which a --streams cell is a channel; nodata cells never are (default 0).".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Requested Outlet Longitude/Latitude".to_owned(),
+            flags: vec!["--requested_outlet_lng_lat".to_owned()],
+            description: "Requested outlet location specified as 'lon,lat' (WGS84).".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Requested Outlet Row/Column".to_owned(),
+            flags: vec!["--requested_outlet_row_col".to_owned()],
+            description: "Requested outlet specified as 'row,col' in raster coordinates."
+                .to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Pour Point File".to_owned(),
+            flags: vec!["--pour_pts".to_owned()],
+            description: "Requested outlet as a single pour point (Shapefile, GeoJSON, or raster) in the D8 pointer's coordinate system.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Point,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Jitter Radius (cells)".to_owned(),
+            flags: vec!["--jitter_cells".to_owned()],
+            description: "Radius, in cells, within which the requested outlet is moved.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("2".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Samples".to_owned(),
+            flags: vec!["--n_samples".to_owned()],
+            description: "Number of perturbed outlets to resolve.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("25".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Random Seed".to_owned(),
+            flags: vec!["--seed".to_owned()],
+            description: "Seed of the random generator used to perturb the outlet.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output report file; JSON if the name ends in .json, otherwise a tab-separated table of samples.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Agreement Raster File (optional)".to_owned(),
+            flags: vec!["--agreement".to_owned()],
+            description:
+                "Optional output raster of the number of samples whose basin contains each cell."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr=D8.tif --streams=streams.tif --requested_outlet_row_col='120,85' --jitter_cells=3 --n_samples=50 -o=sensitivity.json --agreement=agreement.tif", short_exe, name).replace("*", &sep);
+
+        OutletSensitivityAnalysis {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for OutletSensitivityAnalysis {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut stream_threshold = 0f64;
+        let mut requested_lng_lat: Option<(f64, f64)> = None;
+        let mut requested_row_col: Option<(isize, isize)> = None;
+        let mut pour_pts_file = String::new();
+        let mut jitter_cells = 2isize;
+        let mut n_samples = 25usize;
+        let mut seed = 0u64;
+        let mut output_file = String::new();
+        let mut agreement_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = || {
+                if keyval {
+                    vec[1].to_string()
+                } else {
+                    args.get(i + 1).cloned().unwrap_or_default()
+                }
+            };
+            if flag_val == "-d8_pntr" {
+                d8_file = value();
+            } else if flag_val == "-streams" {
+                streams_file = value();
+            } else if flag_val == "-stream_threshold" {
+                let v = value();
+                stream_threshold = v
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|t| t.is_finite())
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("Unable to parse --stream_threshold value '{}'.", v),
+                        )
+                    })?;
+            } else if flag_val == "-requested_outlet_lng_lat" {
+                requested_lng_lat = Some(single_pair(parse_pair_list(
+                    &value(),
+                    "--requested_outlet_lng_lat",
+                    "lon,lat",
+                    ["longitude", "latitude"],
+                )?)?);
+            } else if flag_val == "-requested_outlet_row_col" {
+                requested_row_col = Some(single_pair(parse_pair_list(
+                    &value(),
+                    "--requested_outlet_row_col",
+                    "row,col",
+                    ["row", "column"],
+                )?)?);
+            } else if flag_val == "-pour_pts" {
+                pour_pts_file = value();
+            } else if flag_val == "-jitter_cells" {
+                let v = value();
+                jitter_cells = v.trim().parse::<isize>().ok().filter(|r| *r >= 0).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Unable to parse --jitter_cells value '{}'; expected a non-negative number of cells.",
+                            v
+                        ),
+                    )
+                })?;
+            } else if flag_val == "-n_samples" {
+                let v = value();
+                n_samples = v
+                    .trim()
+                    .parse::<usize>()
+                    .ok()
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                            "Unable to parse --n_samples value '{}'; expected a positive integer.",
+                            v
+                        ),
+                        )
+                    })?;
+            } else if flag_val == "-seed" {
+                let v = value();
+                seed = v.trim().parse::<u64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse --seed value '{}'.", v),
+                    )
+                })?;
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = value();
+            } else if flag_val == "-agreement" {
+                agreement_file = value();
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let num_requests = requested_lng_lat.is_some() as usize
+            + requested_row_col.is_some() as usize
+            + !pour_pts_file.trim().is_empty() as usize;
+        if num_requests != 1 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Exactly one of --requested_outlet_row_col, --requested_outlet_lng_lat, or --pour_pts is required.",
+            ));
+        }
+        if output_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "An output report file (--output) is required.",
+            ));
+        }
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        output_file = resolve_path(working_directory, &output_file);
+        let agreement_file = if agreement_file.trim().is_empty() {
+            None
+        } else {
+            Some(resolve_path(working_directory, &agreement_file))
+        };
+
+        check_rasters_share_geometry(
+            &[("--d8_pntr", &d8_file), ("--streams", &streams_file)],
+            false,
+        )?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let pntr = Raster::new(&d8_file, "r")?;
+        let streams = Raster::new(&streams_file, "r")?;
+
+        let start = Instant::now();
+
+        let tracer = OutletTracer::new(&pntr, &streams, stream_threshold, esri_style, verbose)?;
+        let (req_row, req_col) = if let Some(cell) = requested_row_col {
+            cell
+        } else if let Some((lon, lat)) = requested_lng_lat {
+            tracer.lon_lat_cell(lon, lat).ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidInput,
+                    "Unable to project --requested_outlet_lng_lat; the D8 pointer's coordinate system is unknown.",
+                )
+            })?
+        } else {
+            locate_pour_point(&resolve_path(working_directory, &pour_pts_file), &pntr)?
+        };
+        let walker = D8Walker::new(
+            pntr.configs.rows as isize,
+            pntr.configs.columns as isize,
+            pntr.configs.nodata,
+            esri_style,
+            |row, col| pntr.get_value(row, col),
+        );
+        if !walker.in_grid(req_row, req_col) {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "The requested outlet (row {}, column {}) is off the grid.",
+                    req_row, req_col
+                ),
+            ));
+        }
+
+        let reference = tracer.trace(req_row, req_col).map_err(|reason| {
+            Error::new(
+                ErrorKind::InvalidInput,
+                format!("The requested outlet could not be resolved: {}", reason),
+            )
+        })?;
+
+        if verbose {
+            println!("Resolving {} perturbed outlets...", n_samples);
+        }
+        let starts = jitter_cells_within(&walker, req_row, req_col, jitter_cells);
+        let mut rng = SmallRng::seed_from_u64(seed);
+        let mut samples = Vec::with_capacity(n_samples);
+        for _ in 0..n_samples {
+            let (row, col) = starts[rng.gen_range(0..starts.len())];
+            samples.push(Sample {
+                start_row: row,
+                start_col: col,
+                outlet: tracer.trace(row, col),
+            });
+        }
+
+        // delineate each distinct outlet once, counting its samples into the agreement grid
+        let mut outlet_counts: BTreeMap<(isize, isize), usize> = BTreeMap::new();
+        outlet_counts.insert(reference, 0);
+        for sample in &samples {
+            if let Ok(outlet) = sample.outlet {
+                *outlet_counts.entry(outlet).or_insert(0) += 1;
+            }
+        }
+        let mut agreement = match &agreement_file {
+            Some(file) => {
+                let mut output = Raster::initialize_using_file(file, &pntr);
+                output.configs.data_type = DataType::I32;
+                output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+                output.configs.nodata = -32768f64;
+                for row in 0..pntr.configs.rows as isize {
+                    for col in 0..pntr.configs.columns as isize {
+                        if pntr.get_value(row, col) == pntr.configs.nodata {
+                            output.set_value(row, col, -32768f64);
+                        } else {
+                            output.set_value(row, col, 0f64);
+                        }
+                    }
+                }
+                Some(output)
+            }
+            None => None,
+        };
+        let mut basin_cells: BTreeMap<(isize, isize), usize> = BTreeMap::new();
+        for (&outlet, &count) in &outlet_counts {
+            let cells = upslope_cells(&walker, outlet);
+            if let Some(agreement) = agreement.as_mut() {
+                if count > 0 {
+                    for &(row, col) in &cells {
+                        agreement.increment(row, col, count as f64);
+                    }
+                }
+            }
+            basin_cells.insert(outlet, cells.len());
+        }
+        let cell_area = pntr.configs.resolution_x * pntr.configs.resolution_y;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            let converged = samples.iter().filter(|s| s.outlet == Ok(reference)).count();
+            println!(
+                "{} of {} samples converged to the reference outlet (row {}, column {}).",
+                converged, n_samples, reference.0, reference.1
+            );
+            println!("Saving data...")
+        };
+        if output_file.to_lowercase().ends_with(".json") {
+            let report = sensitivity_report(
+                &samples,
+                reference,
+                &outlet_counts,
+                &basin_cells,
+                cell_area,
+                json!({
+                    "d8_pntr": d8_file,
+                    "streams": streams_file,
+                    "requested_row": req_row,
+                    "requested_col": req_col,
+                    "jitter_cells": jitter_cells,
+                    "n_samples": n_samples,
+                    "seed": seed,
+                    "agreement": agreement_file,
+                }),
+            );
+            let mut file = File::create(&output_file)?;
+            file.write_all(serde_json::to_string_pretty(&report)?.as_bytes())?;
+        } else {
+            write_samples_to_tsv(&samples, reference, &basin_cells, cell_area, &output_file)?;
+        }
+
+        if let Some(mut agreement) = agreement {
+            agreement.configs.palette = "spectrum.plt".to_string();
+            agreement.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            agreement.add_metadata_entry(format!("D8 pointer file: {}", d8_file));
+            agreement.add_metadata_entry(format!("Samples: {}", n_samples));
+            agreement.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            let _ = match agreement.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A perturbed request and the outlet it resolved to, or the reason its trace failed.
+struct Sample {
+    start_row: isize,
+    start_col: isize,
+    outlet: Result<(isize, isize), String>,
+}
+
+/// The only pair of `pairs`, or an error unless it holds exactly one.
+fn single_pair<T>(mut pairs: Vec<(T, T)>) -> Result<(T, T), Error> {
+    if pairs.len() != 1 {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Exactly one requested outlet is required; found {}.",
+                pairs.len()
+            ),
+        ));
+    }
+    Ok(pairs.remove(0))
+}
+
+/// The grid cells within `radius` cells of (`row`, `col`), in row-major order.
+fn jitter_cells_within(
+    walker: &D8Walker,
+    row: isize,
+    col: isize,
+    radius: isize,
+) -> Vec<(isize, isize)> {
+    let mut cells = vec![];
+    for dr in -radius..=radius {
+        for dc in -radius..=radius {
+            if dr * dr + dc * dc <= radius * radius && walker.in_grid(row + dr, col + dc) {
+                cells.push((row + dr, col + dc));
+            }
+        }
+    }
+    cells
+}
+
+/// The cells draining to `outlet`, including the outlet itself, found by a breadth-first search
+/// through the inflowing neighbours. A cell is visited once, so pointer loops end the search.
+fn upslope_cells(walker: &D8Walker, outlet: (isize, isize)) -> Vec<(isize, isize)> {
+    let mut cells = vec![outlet];
+    let mut visited = HashSet::new();
+    visited.insert(outlet);
+    let mut queue = VecDeque::new();
+    queue.push_back(outlet);
+    while let Some((row, col)) = queue.pop_front() {
+        for cell in walker.inflows(row, col) {
+            if visited.insert(cell) {
+                cells.push(cell);
+                queue.push_back(cell);
+            }
+        }
+    }
+    cells
+}
+
+/// The `p` quantile (0 to 1) of sorted values, interpolating linearly between ranks.
+fn quantile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let lower = rank.floor() as usize;
+    let upper = rank.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+fn sensitivity_report(
+    samples: &[Sample],
+    reference: (isize, isize),
+    outlet_counts: &BTreeMap<(isize, isize), usize>,
+    basin_cells: &BTreeMap<(isize, isize), usize>,
+    cell_area: f64,
+    inputs: JsonValue,
+) -> JsonValue {
+    let n = samples.len() as f64;
+    let mut areas: Vec<f64> = samples
+        .iter()
+        .filter_map(|s| s.outlet.as_ref().ok())
+        .map(|outlet| basin_cells[outlet] as f64 * cell_area)
+        .collect();
+    areas.sort_by(|a, b| a.total_cmp(b));
+    let area_stats = if areas.is_empty() {
+        JsonValue::Null
+    } else {
+        let mean = areas.iter().sum::<f64>() / areas.len() as f64;
+        let std_dev = (areas.iter().map(|a| (a - mean) * (a - mean)).sum::<f64>()
+            / areas.len() as f64)
+            .sqrt();
+        json!({
+            "min": areas[0],
+            "p25": quantile(&areas, 0.25),
+            "median": quantile(&areas, 0.5),
+            "p75": quantile(&areas, 0.75),
+            "max": areas[areas.len() - 1],
+            "mean": mean,
+            "std_dev": std_dev,
+            "cv": if mean > 0f64 { json!(std_dev / mean) } else { JsonValue::Null },
+        })
+    };
+
+    // ties go to the first outlet in row-major order
+    let mut modal: Option<((isize, isize), usize)> = None;
+    for (&outlet, &count) in outlet_counts {
+        if count > 0 && modal.map_or(true, |(_, best)| count > best) {
+            modal = Some((outlet, count));
+        }
+    }
+    let outlets: Vec<JsonValue> = outlet_counts
+        .iter()
+        .filter(|(_, &count)| count > 0)
+        .map(|(&(row, col), &count)| {
+            json!({
+                "row": row,
+                "col": col,
+                "count": count,
+                "fraction": count as f64 / n,
+                "num_cells": basin_cells[&(row, col)],
+                "area": basin_cells[&(row, col)] as f64 * cell_area,
+            })
+        })
+        .collect();
+    let records: Vec<JsonValue> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| match &s.outlet {
+            Ok(outlet) => json!({
+                "sample": i + 1,
+                "start_row": s.start_row,
+                "start_col": s.start_col,
+                "status": "ok",
+                "outlet_row": outlet.0,
+                "outlet_col": outlet.1,
+                "num_cells": basin_cells[outlet],
+                "area": basin_cells[outlet] as f64 * cell_area,
+                "same_as_reference": *outlet == reference,
+            }),
+            Err(reason) => json!({
+                "sample": i + 1,
+                "start_row": s.start_row,
+                "start_col": s.start_col,
+                "status": "failed",
+                "same_as_reference": false,
+                "failure_reason": reason,
+            }),
+        })
+        .collect();
+    let successful = samples.iter().filter(|s| s.outlet.is_ok()).count();
+    json!({
+        "inputs": inputs,
+        "reference_outlet": {
+            "row": reference.0,
+            "col": reference.1,
+            "num_cells": basin_cells[&reference],
+            "area": basin_cells[&reference] as f64 * cell_area,
+        },
+        "successful_samples": successful,
+        "failed_samples": samples.len() - successful,
+        "same_outlet_fraction": outlet_counts[&reference] as f64 / n,
+        "modal_outlet": modal.map(|((row, col), count)| json!({
+            "row": row,
+            "col": col,
+            "count": count,
+            "fraction": count as f64 / n,
+        })),
+        "area": area_stats,
+        "outlets": outlets,
+        "samples": records,
+    })
+}
+
+fn write_samples_to_tsv(
+    samples: &[Sample],
+    reference: (isize, isize),
+    basin_cells: &BTreeMap<(isize, isize), usize>,
+    cell_area: f64,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "sample\tstart_row\tstart_col\tstatus\toutlet_row\toutlet_col\tnum_cells\tarea\tsame_as_reference\tfailure_reason"
+    )?;
+    for (i, s) in samples.iter().enumerate() {
+        match &s.outlet {
+            Ok(outlet) => writeln!(
+                &mut file,
+                "{}\t{}\t{}\tok\t{}\t{}\t{}\t{:.3}\t{}\t",
+                i + 1,
+                s.start_row,
+                s.start_col,
+                outlet.0,
+                outlet.1,
+                basin_cells[outlet],
+                basin_cells[outlet] as f64 * cell_area,
+                *outlet == reference
+            )?,
+            Err(reason) => writeln!(
+                &mut file,
+                "{}\t{}\t{}\tfailed\tNA\tNA\tNA\tNA\tfalse\t{}",
+                i + 1,
+                s.start_row,
+                s.start_col,
+                reason.replace('\t', " ")
+            )?,
+        }
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{quantile, OutletSensitivityAnalysis};
+    use crate::tools::WhiteboxTool;
+    use serde_json::Value;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    /// A 7 by 7 grid of 10 m cells whose channel, column 3, drains south to the outlet at its
+    /// bottom. The side cells drain straight across into the channel, so the basin of the channel
+    /// cell in row r holds the 7 * (r + 1) cells of rows 0 to r.
+    fn write_inputs(dir: &str) {
+        let mut pntr = GridFixture::new(7, 7, |_, c| {
+            if c < 3 {
+                2f64 // east
+            } else if c > 3 {
+                32f64 // west
+            } else {
+                8f64 // south
+            }
+        });
+        pntr.set_value(6, 3, 0f64);
+        grid_fixture_raster(&format!("{}/d8.tif", dir), &pntr)
+            .write()
+            .unwrap();
+        let streams = GridFixture::new(7, 7, |_, c| if c == 3 { 1f64 } else { 0f64 });
+        grid_fixture_raster(&format!("{}/streams.tif", dir), &streams)
+            .write()
+            .unwrap();
+    }
+
+    fn run(dir: &str, extra: &[&str]) -> Result<(), std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.to_string()));
+        OutletSensitivityAnalysis::new().run(args, dir, false)
+    }
+
+    #[test]
+    fn test_jitter_report_and_agreement() {
+        let dir = temp_dir("outlet_sensitivity_analysis_jitter").unwrap();
+        write_inputs(&dir);
+
+        let args = [
+            "--requested_outlet_row_col=3,1",
+            "--jitter_cells=1",
+            "--n_samples=40",
+            "--seed=7",
+            "-o=report.json",
+            "--agreement=agreement.tif",
+        ];
+        run(&dir, &args).unwrap();
+        let report: Value =
+            serde_json::from_str(&fs::read_to_string(format!("{}/report.json", dir)).unwrap())
+                .unwrap();
+
+        // the request traces east onto the channel at (3, 3), whose basin is rows 0 to 3
+        assert_eq!(report["reference_outlet"]["row"], 3);
+        assert_eq!(report["reference_outlet"]["col"], 3);
+        assert_eq!(report["reference_outlet"]["area"].as_f64(), Some(2800f64));
+        assert_eq!(report["failed_samples"], 0);
+
+        // moves north or south land on a different channel cell
+        let samples = report["samples"].as_array().unwrap();
+        assert_eq!(samples.len(), 40);
+        let mut converged = 0;
+        let mut rows = [0usize; 7];
+        for s in samples {
+            let row = s["outlet_row"].as_i64().unwrap();
+            assert_eq!(s["start_row"], row);
+            assert_eq!(s["outlet_col"], 3);
+            assert_eq!(s["area"].as_f64(), Some(700f64 * (row + 1) as f64));
+            rows[row as usize] += 1;
+            if s["same_as_reference"] == true {
+                converged += 1;
+            }
+        }
+        assert!(converged > 0 && converged < 40);
+        assert_eq!(rows[3], converged);
+        assert_eq!(
+            report["same_outlet_fraction"].as_f64(),
+            Some(converged as f64 / 40f64)
+        );
+        assert_eq!(report["modal_outlet"]["row"], 3);
+        assert_eq!(report["outlets"].as_array().unwrap().len(), 3);
+        assert_eq!(report["area"]["min"].as_f64(), Some(2100f64));
+        assert_eq!(report["area"]["max"].as_f64(), Some(3500f64));
+        assert_eq!(report["area"]["median"].as_f64(), Some(2800f64));
+
+        // every sampled basin holds row 0, and only the row 4 outlets hold row 4
+        let agreement = Raster::new(&format!("{}/agreement.tif", dir), "r").unwrap();
+        assert_eq!(agreement.get_value(0, 0), 40f64);
+        assert_eq!(agreement.get_value(3, 6), (40 - rows[2]) as f64);
+        assert_eq!(agreement.get_value(4, 0), rows[4] as f64);
+        assert_eq!(agreement.get_value(6, 6), 0f64);
+
+        // the same seed gives the same samples
+        run(
+            &dir,
+            &[
+                "--requested_outlet_row_col=3,1",
+                "--jitter_cells=1",
+                "--n_samples=40",
+                "--seed=7",
+                "-o=samples.tsv",
+            ],
+        )
+        .unwrap();
+        let table = fs::read_to_string(format!("{}/samples.tsv", dir)).unwrap();
+        let lines: Vec<Vec<&str>> = table.lines().map(|l| l.split('\t').collect()).collect();
+        assert_eq!(lines.len(), 41);
+        assert_eq!(lines[0][9], "failure_reason");
+        for (line, s) in lines[1..].iter().zip(samples) {
+            assert_eq!(line[4], s["outlet_row"].to_string());
+            assert_eq!(line[3], "ok");
+        }
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_requires_one_request() {
+        let dir = temp_dir("outlet_sensitivity_analysis_args").unwrap();
+        write_inputs(&dir);
+
+        assert!(run(&dir, &["-o=report.json"]).is_err());
+        assert!(run(
+            &dir,
+            &[
+                "--requested_outlet_row_col=3,1",
+                "--requested_outlet_lng_lat=-120.5,42.1",
+                "-o=report.json",
+            ],
+        )
+        .is_err());
+        assert!(run(
+            &dir,
+            &["--requested_outlet_row_col=3,1;4,1", "-o=report.json"]
+        )
+        .is_err());
+        assert!(run(
+            &dir,
+            &[
+                "--requested_outlet_row_col=3,1",
+                "--n_samples=0",
+                "-o=r.json"
+            ]
+        )
+        .is_err());
+
+        // streams with the same dimensions but a shifted extent do not line up with the pointer
+        let shifted = GridFixture::new(7, 7, |_, c| if c == 3 { 1f64 } else { -32768f64 })
+            .with_origin(10f64, 0f64);
+        grid_fixture_raster(&format!("{}/streams.tif", dir), &shifted)
+            .write()
+            .unwrap();
+        let err = run(&dir, &["--requested_outlet_row_col=3,1", "-o=report.json"]).unwrap_err();
+        assert!(err.to_string().contains("--streams (east, west)"));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_quantile() {
+        let values = [1f64, 2f64, 4f64, 8f64, 16f64];
+        assert_eq!(quantile(&values, 0f64), 1f64);
+        assert_eq!(quantile(&values, 0.5), 4f64);
+        assert_eq!(quantile(&values, 0.25), 2f64);
+        assert_eq!(quantile(&values, 0.875), 12f64);
+        assert_eq!(quantile(&[5f64], 0.75), 5f64);
+    }
+}
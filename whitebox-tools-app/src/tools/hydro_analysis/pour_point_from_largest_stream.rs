@@ -0,0 +1,805 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use geojson::{Feature, FeatureCollection, GeoJson, Geometry, Value as GeoValue};
+use serde_json::{json, Map as JsonMap, Value as JsonValue};
+use std::cmp::Ordering;
+use std::env;
+use std::f64;
+use std::fs;
+use std::fs::File;
+use std::io::{Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::algorithms::{rasterize_polygon, GridGeometry};
+use whitebox_common::structures::{Array2D, Point2D};
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::{ShapeType, Shapefile};
+
+/// This tool locates the pour point of the largest stream leaving an area of interest. The area is
+/// given by `--boundary`, which may be either a polygon vector (a Shapefile, or a GeoJSON
+/// FeatureCollection with a `.geojson`/`.json` extension) or a mask raster in which cells with
+/// positive values are inside the boundary. Polygons are rasterized onto the grid of the D8 pointer
+/// raster (`--d8_pntr`) and cover the cells whose centres fall inside them; a mask raster must have
+/// the same rows and columns as the pointer.
+///
+/// An exit cell is a stream cell (`--streams`, any positive value) inside the boundary whose
+/// downstream neighbour, following the flow pointer, lies outside of the boundary or off the edge of
+/// the grid. Exit cells are ranked by their stream order (`--order`), with ties broken by their flow
+/// accumulation (`--flow_accum`); when only one of the two rasters is given, it alone sets the rank.
+/// Any remaining ties are broken by row and then column, so the ranking is deterministic. At least one
+/// of `--order` and `--flow_accum` must be specified.
+///
+/// The output (`--output`) is a GeoJSON FeatureCollection of points at the centres of the top `--n`
+/// exit cells (default 1), in rank order, with the following properties:
+///
+/// | Property | Description |
+/// |----------|-------------|
+/// | `Id`, `rank` | The 1-based rank of the exit cell. |
+/// | `row`, `column` | The grid cell of the pour point. |
+/// | `easting`, `northing` | The coordinates of the cell centre. |
+/// | `order` | The stream order of the cell, or `null` when `--order` is not given or is NoData. |
+/// | `accumulation` | The flow accumulation of the cell, or `null` when `--flow_accum` is not given or is NoData. |
+/// | `exit_row`, `exit_column` | The downstream cell outside of the boundary, which may be off the grid. |
+/// | `epsg` | The EPSG code of the pointer raster. |
+///
+/// Unlike `FindOutlet`, which traces downstream from the interior of a watershed mask to the outlet
+/// of a single watershed, this tool considers every stream leaving the boundary and chooses among them
+/// by size, which suits boundaries that do not follow a drainage divide. A river that leaves and
+/// re-enters the boundary has more than one exit cell, each of which is ranked separately. By default,
+/// the pointer raster is assumed to use the clockwise indexing method used by WhiteboxTools. If the
+/// pointer file contains ESRI flow direction values instead, the `--esri_pntr` parameter must be
+/// specified.
+///
+/// # See Also
+/// `FindOutlet`, `StrahlerStreamOrder`, `D8FlowAccumulation`, `JensonSnapPourPoints`
+pub struct PourPointFromLargestStream {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl PourPointFromLargestStream {
+    pub fn new() -> PourPointFromLargestStream {
+        // public constructor
+        let name = "PourPointFromLargestStream".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Picks the pour point of the highest-order or largest stream leaving a boundary polygon or mask."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input D8 pointer raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file; stream cells have positive values.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Stream Order File".to_owned(),
+            flags: vec!["--order".to_owned()],
+            description: "Input stream order raster file, e.g. Strahler order.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Flow Accumulation File".to_owned(),
+            flags: vec!["--flow_accum".to_owned()],
+            description: "Input flow accumulation raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Boundary File".to_owned(),
+            flags: vec!["--boundary".to_owned()],
+            description:
+                "Input boundary polygon vector (Shapefile or GeoJSON) or mask raster file."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::RasterAndVector(
+                VectorGeometryType::Polygon,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output GeoJSON File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output pour point GeoJSON file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Pour Points".to_owned(),
+            flags: vec!["--n".to_owned()],
+            description: "Number of top-ranked pour points to output.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("1".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --order='strahler.tif' --boundary='aoi.geojson' -o='pour_point.geojson'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --d8_pntr='d8pntr.tif' --streams='streams.tif' --flow_accum='flow_accum.tif' --boundary='mask.tif' -o='pour_points.geojson' --n=3", short_exe, name).replace("*", &sep);
+
+        PourPointFromLargestStream {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for PourPointFromLargestStream {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut d8_file = String::new();
+        let mut streams_file = String::new();
+        let mut order_file = String::new();
+        let mut accum_file = String::new();
+        let mut boundary_file = String::new();
+        let mut output_file = String::new();
+        let mut num_points = 1usize;
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-order" {
+                order_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-flow_accum" {
+                accum_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-boundary" {
+                boundary_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-n" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                num_points = match value.trim().parse::<usize>() {
+                    Ok(n) if n > 0 => n,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!("--n must be a positive integer; got '{}'.", value),
+                        ))
+                    }
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if order_file.trim().is_empty() && accum_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Either a stream order (--order) or a flow accumulation (--flow_accum) raster must be specified.",
+            ));
+        }
+        if boundary_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A boundary polygon or mask raster (--boundary) must be specified.",
+            ));
+        }
+
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        boundary_file = resolve_path(working_directory, &boundary_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let pntr = Raster::new(&d8_file, "r")?;
+        let rows = pntr.configs.rows as isize;
+        let columns = pntr.configs.columns as isize;
+        let pntr_nodata = pntr.configs.nodata;
+
+        let open_matching = |file: &str, flag: &str| -> Result<Raster, Error> {
+            let raster = Raster::new(file, "r")?;
+            if raster.configs.rows != pntr.configs.rows
+                || raster.configs.columns != pntr.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "The {} raster must have the same rows and columns as the D8 pointer.",
+                        flag
+                    ),
+                ));
+            }
+            Ok(raster)
+        };
+        let streams = open_matching(&streams_file, "--streams")?;
+        let order = if order_file.trim().is_empty() {
+            None
+        } else {
+            Some(open_matching(
+                &resolve_path(working_directory, &order_file),
+                "--order",
+            )?)
+        };
+        let accum = if accum_file.trim().is_empty() {
+            None
+        } else {
+            Some(open_matching(
+                &resolve_path(working_directory, &accum_file),
+                "--flow_accum",
+            )?)
+        };
+
+        let start = Instant::now();
+
+        // 1 marks cells inside the boundary.
+        let mut inside: Array2D<u8> = Array2D::new(rows, columns, 0, 0)?;
+        let lc = boundary_file.to_lowercase();
+        if lc.ends_with(".shp") || lc.ends_with(".geojson") || lc.ends_with(".json") {
+            let geometry = GridGeometry {
+                rows: pntr.configs.rows,
+                columns: pntr.configs.columns,
+                west: pntr.configs.west,
+                north: pntr.configs.north,
+                resolution_x: pntr.configs.resolution_x,
+                resolution_y: pntr.configs.resolution_y,
+                nodata: pntr_nodata,
+            };
+            for rings in read_boundary_polygons(&boundary_file)? {
+                let ring_refs: Vec<&[Point2D]> = rings.iter().map(|ring| ring.as_slice()).collect();
+                rasterize_polygon(&geometry, &ring_refs, false, |row, col| {
+                    inside.set_value(row, col, 1u8);
+                });
+            }
+        } else {
+            let mask = open_matching(&boundary_file, "--boundary")?;
+            let mask_nodata = mask.configs.nodata;
+            for row in 0..rows {
+                for col in 0..columns {
+                    let z = mask.get_value(row, col);
+                    if z != mask_nodata && z > 0f64 {
+                        inside.set_value(row, col, 1u8);
+                    }
+                }
+            }
+        }
+
+        // Create a mapping from the pointer values to cells offsets.
+        let mut pntr_matches: [usize; 129] = [999usize; 129];
+        if !esri_style {
+            // This maps Whitebox-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 0usize;
+            pntr_matches[2] = 1usize;
+            pntr_matches[4] = 2usize;
+            pntr_matches[8] = 3usize;
+            pntr_matches[16] = 4usize;
+            pntr_matches[32] = 5usize;
+            pntr_matches[64] = 6usize;
+            pntr_matches[128] = 7usize;
+        } else {
+            // This maps Esri-style D8 pointer values
+            // onto the cell offsets in dx and dy.
+            pntr_matches[1] = 1usize;
+            pntr_matches[2] = 2usize;
+            pntr_matches[4] = 3usize;
+            pntr_matches[8] = 4usize;
+            pntr_matches[16] = 5usize;
+            pntr_matches[32] = 6usize;
+            pntr_matches[64] = 7usize;
+            pntr_matches[128] = 0usize;
+        }
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+        let value_at = |raster: &Option<Raster>, row: isize, col: isize| -> Option<f64> {
+            match raster {
+                Some(r) => {
+                    let z = r.get_value(row, col);
+                    if z != r.configs.nodata {
+                        Some(z)
+                    } else {
+                        None
+                    }
+                }
+                None => None,
+            }
+        };
+
+        let streams_nodata = streams.configs.nodata;
+        let mut exits: Vec<ExitCell> = vec![];
+        let mut num_inside = 0usize;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                if inside.get_value(row, col) != 1u8 {
+                    continue;
+                }
+                num_inside += 1;
+                let s = streams.get_value(row, col);
+                if s == streams_nodata || s <= 0f64 {
+                    continue;
+                }
+                let z = pntr.get_value(row, col);
+                if z == pntr_nodata || z <= 0f64 || z > 128f64 {
+                    continue;
+                }
+                let dir = pntr_matches[z as usize];
+                if dir > 7 {
+                    continue;
+                }
+                let (exit_row, exit_col) = (row + dy[dir], col + dx[dir]);
+                // Off-grid cells read as the background value of 0.
+                if inside.get_value(exit_row, exit_col) == 1u8 {
+                    continue;
+                }
+                exits.push(ExitCell {
+                    row,
+                    col,
+                    exit_row,
+                    exit_col,
+                    order: value_at(&order, row, col),
+                    accumulation: value_at(&accum, row, col),
+                });
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Finding exit cells: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if num_inside == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The boundary does not cover any cells of the D8 pointer raster.",
+            ));
+        }
+        if exits.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No stream cells inside the boundary flow out of it.",
+            ));
+        }
+
+        exits.sort_by(rank_exit_cells);
+        if verbose {
+            println!(
+                "Found {} exit cell(s); writing the top {}.",
+                exits.len(),
+                num_points.min(exits.len())
+            );
+        }
+        exits.truncate(num_points);
+
+        let epsg_code = pntr.configs.epsg_code;
+        let mut features = Vec::with_capacity(exits.len());
+        for (i, exit) in exits.iter().enumerate() {
+            let easting = pntr.get_x_from_column(exit.col);
+            let northing = pntr.get_y_from_row(exit.row);
+            let mut properties: JsonMap<String, JsonValue> = JsonMap::new();
+            properties.insert("Id".to_string(), json!(i + 1));
+            properties.insert("rank".to_string(), json!(i + 1));
+            properties.insert("row".to_string(), json!(exit.row));
+            properties.insert("column".to_string(), json!(exit.col));
+            properties.insert("easting".to_string(), json!(easting));
+            properties.insert("northing".to_string(), json!(northing));
+            properties.insert("order".to_string(), json!(exit.order));
+            properties.insert("accumulation".to_string(), json!(exit.accumulation));
+            properties.insert("exit_row".to_string(), json!(exit.exit_row));
+            properties.insert("exit_column".to_string(), json!(exit.exit_col));
+            properties.insert("epsg".to_string(), json!(epsg_code));
+            features.push(Feature {
+                bbox: None,
+                geometry: Some(Geometry::new(GeoValue::Point(vec![easting, northing]))),
+                id: None,
+                properties: Some(properties),
+                foreign_members: None,
+            });
+        }
+
+        let mut foreign_members: Option<JsonMap<String, JsonValue>> = None;
+        if epsg_code != 0 {
+            let mut crs_map = JsonMap::new();
+            crs_map.insert("type".to_string(), json!("name"));
+            crs_map.insert(
+                "properties".to_string(),
+                json!({"name": format!("urn:ogc:def:crs:EPSG::{}", epsg_code)}),
+            );
+            let mut members = JsonMap::new();
+            members.insert("crs".to_string(), JsonValue::Object(crs_map));
+            foreign_members = Some(members);
+        }
+
+        let feature_collection = FeatureCollection {
+            bbox: None,
+            features,
+            foreign_members,
+        };
+
+        let geojson = GeoJson::FeatureCollection(feature_collection).to_string();
+        let mut file = File::create(&output_file)?;
+        file.write_all(geojson.as_bytes())?;
+        file.sync_all()?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if verbose {
+            println!("Elapsed Time (excluding I/O): {}", elapsed_time);
+        }
+
+        Ok(())
+    }
+}
+
+/// A stream cell inside the boundary whose downstream neighbour is outside of it.
+struct ExitCell {
+    row: isize,
+    col: isize,
+    exit_row: isize,
+    exit_col: isize,
+    order: Option<f64>,
+    accumulation: Option<f64>,
+}
+
+/// Orders exit cells from largest to smallest stream: by order, then accumulation, with missing
+/// values ranked last and remaining ties broken by row and column.
+fn rank_exit_cells(a: &ExitCell, b: &ExitCell) -> Ordering {
+    let key = |v: Option<f64>| v.unwrap_or(f64::NEG_INFINITY);
+    key(b.order)
+        .partial_cmp(&key(a.order))
+        .unwrap_or(Ordering::Equal)
+        .then(
+            key(b.accumulation)
+                .partial_cmp(&key(a.accumulation))
+                .unwrap_or(Ordering::Equal),
+        )
+        .then(a.row.cmp(&b.row))
+        .then(a.col.cmp(&b.col))
+}
+
+/// Reads the polygons of a Shapefile or GeoJSON file. Each polygon is a list of rings, which are
+/// combined with the even-odd rule when rasterized.
+fn read_boundary_polygons(file_name: &str) -> Result<Vec<Vec<Vec<Point2D>>>, Error> {
+    let to_points = |positions: &[Vec<f64>]| -> Vec<Point2D> {
+        positions
+            .iter()
+            .filter(|p| p.len() >= 2)
+            .map(|p| Point2D::new(p[0], p[1]))
+            .collect()
+    };
+    let mut polygons = vec![];
+    let lc = file_name.to_lowercase();
+    if lc.ends_with(".geojson") || lc.ends_with(".json") {
+        let geojson_str = fs::read_to_string(file_name)?;
+        let gj: GeoJson = geojson_str
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+        let fc = match gj {
+            GeoJson::FeatureCollection(fc) => fc,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "GeoJSON must be a FeatureCollection of Polygons.",
+                ))
+            }
+        };
+        for feature in fc.features {
+            match feature.geometry.map(|g| g.value) {
+                Some(GeoValue::Polygon(rings)) => {
+                    polygons.push(rings.iter().map(|r| to_points(r)).collect());
+                }
+                Some(GeoValue::MultiPolygon(parts)) => {
+                    for rings in parts {
+                        polygons.push(rings.iter().map(|r| to_points(r)).collect());
+                    }
+                }
+                _ => continue, // ignore non-polygon features
+            }
+        }
+    } else {
+        let vector_data = Shapefile::read(file_name)?;
+        if vector_data.header.shape_type.base_shape_type() != ShapeType::Polygon {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input vector data must be of polygon base shape type.",
+            ));
+        }
+        for record_num in 0..vector_data.num_records {
+            let record = vector_data.get_record(record_num);
+            let mut rings: Vec<Vec<Point2D>> = vec![];
+            for part in 0..record.num_parts as usize {
+                let start_point_in_part = record.parts[part] as usize;
+                let end_point_in_part = if part < record.num_parts as usize - 1 {
+                    record.parts[part + 1] as usize
+                } else {
+                    record.num_points as usize
+                };
+                rings.push(record.points[start_point_in_part..end_point_in_part].to_vec());
+            }
+            if !rings.is_empty() {
+                polygons.push(rings);
+            }
+        }
+    }
+    if polygons.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidInput,
+            "The boundary file does not contain any polygons.",
+        ));
+    }
+    Ok(polygons)
+}
+
+#[cfg(test)]
+mod test {
+    use super::PourPointFromLargestStream;
+    use crate::tools::WhiteboxTool;
+    use serde_json::Value;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    // A 6 x 6 grid with 10 m cells. Streams in rows 1 and 4 flow east across the whole grid;
+    // the row 4 stream is of order 2 with the larger accumulation. Row 2 flows west off the grid
+    // with order 2 but a small accumulation. All other cells are off-stream and flow east.
+    fn write_rasters(dir: &str) {
+        let write = |name: &str, f: &dyn Fn(isize, isize) -> f64| {
+            grid_fixture_raster(&format!("{}/{}", dir, name), &GridFixture::new(6, 6, f))
+                .write()
+                .unwrap();
+        };
+        write("pntr.tif", &|r, _| if r == 2 { 32f64 } else { 2f64 });
+        write("streams.tif", &|r, _| match r {
+            1 | 2 | 4 => 1f64,
+            _ => 0f64,
+        });
+        write("order.tif", &|r, _| match r {
+            1 => 1f64,
+            2 | 4 => 2f64,
+            _ => -32768f64,
+        });
+        write("accum.tif", &|r, c| match r {
+            1 => 10f64 + c as f64,
+            2 => 6f64 - c as f64,
+            4 => 50f64 + c as f64,
+            _ => 1f64,
+        });
+        // The boundary covers columns 0-2 of rows 0-4.
+        write("mask.tif", &|r, c| {
+            if r <= 4 && c <= 2 {
+                1f64
+            } else {
+                0f64
+            }
+        });
+        fs::write(
+            format!("{}/boundary.geojson", dir),
+            r#"{"type":"FeatureCollection","features":[{"type":"Feature","properties":{},"geometry":{"type":"Polygon","coordinates":[[[0,10],[30,10],[30,60],[0,60],[0,10]]]}}]}"#,
+        )
+        .unwrap();
+    }
+
+    fn run(dir: &str, extra: &[&str]) -> Result<Vec<Value>, std::io::Error> {
+        let mut args = vec![
+            format!("--d8_pntr={}/pntr.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+            format!("--output={}/pour_points.geojson", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        PourPointFromLargestStream::new().run(args, "", false)?;
+        let text = fs::read_to_string(format!("{}/pour_points.geojson", dir)).unwrap();
+        let json: Value = serde_json::from_str(&text).unwrap();
+        Ok(json["features"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|f| f["properties"].clone())
+            .collect())
+    }
+
+    fn cells(features: &[Value]) -> Vec<(i64, i64)> {
+        features
+            .iter()
+            .map(|p| (p["row"].as_i64().unwrap(), p["column"].as_i64().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn test_ranks_exit_cells_by_order_then_accumulation() {
+        let dir = temp_dir("pour_point_from_largest_stream_rank").unwrap();
+        write_rasters(&dir);
+        for boundary in &["boundary.geojson", "mask.tif"] {
+            let boundary_arg = format!("--boundary={{dir}}/{}", boundary);
+            let features = run(
+                &dir,
+                &[
+                    "--order={dir}/order.tif",
+                    "--flow_accum={dir}/accum.tif",
+                    &boundary_arg,
+                    "--n=5",
+                ],
+            )
+            .unwrap();
+            // Row 4 leaves to the east, row 2 off the west edge, and row 1 to the east.
+            assert_eq!(cells(&features), vec![(4, 2), (2, 0), (1, 2)]);
+            assert_eq!(features[0]["rank"], 1);
+            assert_eq!(features[0]["order"], 2.0);
+            assert_eq!(features[0]["accumulation"], 52.0);
+            assert_eq!(features[0]["exit_column"], 3);
+            assert_eq!(features[1]["exit_column"], -1);
+            assert_eq!(features[0]["easting"], 25.0);
+            assert_eq!(features[0]["northing"], 15.0);
+        }
+    }
+
+    #[test]
+    fn test_accumulation_only_and_top_n() {
+        let dir = temp_dir("pour_point_from_largest_stream_accum").unwrap();
+        write_rasters(&dir);
+        let features = run(
+            &dir,
+            &["--flow_accum={dir}/accum.tif", "--boundary={dir}/mask.tif"],
+        )
+        .unwrap();
+        assert_eq!(cells(&features), vec![(4, 2)]);
+        assert!(features[0]["order"].is_null());
+
+        // Without accumulation, rows 2 and 4 tie on order and are broken by row.
+        let features = run(
+            &dir,
+            &[
+                "--order={dir}/order.tif",
+                "--boundary={dir}/mask.tif",
+                "--n=2",
+            ],
+        )
+        .unwrap();
+        assert_eq!(cells(&features), vec![(2, 0), (4, 2)]);
+    }
+
+    #[test]
+    fn test_rejects_missing_ranking_raster_and_bad_n() {
+        let dir = temp_dir("pour_point_from_largest_stream_errors").unwrap();
+        write_rasters(&dir);
+        let err = run(&dir, &["--boundary={dir}/mask.tif"]).unwrap_err();
+        assert!(err.to_string().contains("--order"));
+        let err = run(
+            &dir,
+            &[
+                "--order={dir}/order.tif",
+                "--boundary={dir}/mask.tif",
+                "--n=0",
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(err.to_string(), "--n must be a positive integer; got '0'.");
+    }
+}
@@ -0,0 +1,120 @@
+/*
+This module is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use geojson::{GeoJson, Geometry, Value};
+use std::io::{Error, ErrorKind};
+use whitebox_raster::Raster;
+use whitebox_vector::{ShapeType, Shapefile};
+
+/// Locates the grid cell of a single pour point on the grid of `pntr`. The pour point may be given
+/// as a point Shapefile, a GeoJSON file (`.geojson` or `.json`) of Point or MultiPoint features, or
+/// a raster with the same rows and columns as `pntr` in which the pour point cell has a positive
+/// value. Returns an error unless exactly one pour point is found.
+pub(crate) fn locate_pour_point(
+    pourpts_file: &str,
+    pntr: &Raster,
+) -> Result<(isize, isize), Error> {
+    let pour_points = locate_pour_points(pourpts_file, pntr)?;
+    if pour_points.is_empty() {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            "No pour points found in --pour_pts",
+        ))
+    } else if pour_points.len() > 1 {
+        Err(Error::new(
+            ErrorKind::InvalidInput,
+            format!(
+                "Exactly one pour point required in --pour_pts; found {}",
+                pour_points.len()
+            ),
+        ))
+    } else {
+        Ok(pour_points[0])
+    }
+}
+
+/// Locates the grid cells of every pour point in `pourpts_file`, which is read as for
+/// `locate_pour_point`. Vector points are returned in file order, and raster pour points in
+/// row-major order. The cells of points outside the grid of `pntr` are not clipped.
+pub(crate) fn locate_pour_points(
+    pourpts_file: &str,
+    pntr: &Raster,
+) -> Result<Vec<(isize, isize)>, Error> {
+    let mut pour_points = vec![];
+
+    if pourpts_file.to_lowercase().ends_with(".shp") {
+        let pourpts = Shapefile::read(pourpts_file)?;
+        if pourpts.header.shape_type.base_shape_type() != ShapeType::Point {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Pour points (--pour_pts) must be point type",
+            ));
+        }
+
+        for i in 0..pourpts.num_records {
+            let record = pourpts.get_record(i);
+            let row = pntr.get_row_from_y(record.points[0].y);
+            let col = pntr.get_column_from_x(record.points[0].x);
+            pour_points.push((row, col));
+        }
+    } else if pourpts_file.to_lowercase().ends_with(".geojson")
+        || pourpts_file.to_lowercase().ends_with(".json")
+    {
+        let geojson_str = std::fs::read_to_string(pourpts_file)?;
+        let gj: GeoJson = geojson_str
+            .parse()
+            .map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+
+        if let GeoJson::FeatureCollection(fc) = gj {
+            for feature in fc.features {
+                if let Some(Geometry { value, .. }) = feature.geometry {
+                    match value {
+                        Value::Point(pt) => {
+                            let (x, y) = (pt[0], pt[1]);
+                            let row = pntr.get_row_from_y(y);
+                            let col = pntr.get_column_from_x(x);
+                            pour_points.push((row, col));
+                        }
+                        Value::MultiPoint(pts) => {
+                            for pt in pts {
+                                let (x, y) = (pt[0], pt[1]);
+                                let row = pntr.get_row_from_y(y);
+                                let col = pntr.get_column_from_x(x);
+                                pour_points.push((row, col));
+                            }
+                        }
+                        _ => continue,
+                    }
+                }
+            }
+        }
+    } else {
+        // Raster
+        let pourpts = Raster::new(pourpts_file, "r")?;
+        if pourpts.configs.rows != pntr.configs.rows
+            || pourpts.configs.columns != pntr.configs.columns
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Pour points raster (--pour_pts) must match DEM dimensions",
+            ));
+        }
+
+        for row in 0..pntr.configs.rows as isize {
+            for col in 0..pntr.configs.columns as isize {
+                if pourpts.get_value(row, col) > 0.0
+                    && pourpts.get_value(row, col) != pourpts.configs.nodata
+                {
+                    pour_points.push((row, col));
+                }
+            }
+        }
+    }
+
+    Ok(pour_points)
+}
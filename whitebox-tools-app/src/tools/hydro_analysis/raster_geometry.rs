@@ -0,0 +1,180 @@
+/*
+This module is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use std::io::{Error, ErrorKind};
+use whitebox_raster::{Raster, RasterConfigs};
+
+/// The geometry fields of `other` that differ from those of `base`, including the EPSG code when
+/// `check_crs` is set.
+pub(crate) fn geometry_mismatches(
+    base: &RasterConfigs,
+    other: &RasterConfigs,
+    check_crs: bool,
+) -> Vec<&'static str> {
+    let mut fields = vec![];
+    if other.rows != base.rows {
+        fields.push("rows");
+    }
+    if other.columns != base.columns {
+        fields.push("columns");
+    }
+    if other.resolution_x != base.resolution_x {
+        fields.push("resolution_x");
+    }
+    if other.resolution_y != base.resolution_y {
+        fields.push("resolution_y");
+    }
+    if check_crs && other.epsg_code != base.epsg_code {
+        fields.push("epsg_code");
+    }
+    if other.north != base.north {
+        fields.push("north");
+    }
+    if other.south != base.south {
+        fields.push("south");
+    }
+    if other.east != base.east {
+        fields.push("east");
+    }
+    if other.west != base.west {
+        fields.push("west");
+    }
+    fields
+}
+
+/// Checks that all rasters, given with the flag and file they were read from, share the geometry
+/// of the first, and their EPSG code when `check_crs` is set. Otherwise the error names each raster
+/// that differs and the fields that differ, and lists the dimensions, resolution, EPSG code, and
+/// extent of every input so the odd one out is easy to spot.
+pub(crate) fn check_geometry(
+    rasters: &[(&str, &str, RasterConfigs)],
+    check_crs: bool,
+) -> Result<(), Error> {
+    let (base_flag, base) = match rasters.first() {
+        Some((flag, _, configs)) => (*flag, configs),
+        None => return Ok(()),
+    };
+    let differences: Vec<String> = rasters
+        .iter()
+        .skip(1)
+        .filter_map(|(flag, _, configs)| {
+            let fields = geometry_mismatches(base, configs, check_crs);
+            if fields.is_empty() {
+                None
+            } else {
+                Some(format!("{} ({})", flag, fields.join(", ")))
+            }
+        })
+        .collect();
+    if differences.is_empty() {
+        return Ok(());
+    }
+
+    let mut msg = format!(
+        "Input rasters must share geometry; differs from {}: {}.",
+        base_flag,
+        differences.join("; ")
+    );
+    for (flag, file_name, c) in rasters {
+        msg.push_str(&format!(
+            "\n  {} {}: {} rows x {} columns, resolution {} x {}, EPSG {}, north {}, south {}, east {}, west {}",
+            flag,
+            file_name,
+            c.rows,
+            c.columns,
+            c.resolution_x,
+            c.resolution_y,
+            c.epsg_code,
+            c.north,
+            c.south,
+            c.east,
+            c.west
+        ));
+    }
+    Err(Error::new(ErrorKind::InvalidInput, msg))
+}
+
+/// Checks that the raster files share the geometry of the first, as `check_geometry` does, reading
+/// only their headers.
+pub(crate) fn check_rasters_share_geometry(
+    files: &[(&str, &String)],
+    check_crs: bool,
+) -> Result<(), Error> {
+    check_geometry(&read_headers(files)?, check_crs)
+}
+
+/// Reads the headers of the raster files, given with the flag they were read from.
+pub(crate) fn read_headers<'a>(
+    files: &[(&'a str, &'a String)],
+) -> Result<Vec<(&'a str, &'a str, RasterConfigs)>, Error> {
+    let mut rasters = Vec::with_capacity(files.len());
+    for &(flag, file_name) in files {
+        rasters.push((flag, file_name.as_str(), header_configs(file_name)?));
+    }
+    Ok(rasters)
+}
+
+/// Reads the raster's configs without loading its pixels, falling back to a full read for
+/// formats that do not support header-only reads.
+pub(crate) fn header_configs(file_name: &str) -> Result<RasterConfigs, Error> {
+    match Raster::open_header(file_name) {
+        Ok(configs) => Ok(configs),
+        Err(e) if e.kind() == ErrorKind::InvalidInput => Ok(Raster::new(file_name, "r")?.configs),
+        Err(e) => Err(e),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_geometry, geometry_mismatches};
+    use whitebox_common::test_support::GridFixture;
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::RasterConfigs;
+
+    // The configs of a 3 x 4 grid of 10 m cells.
+    fn configs(epsg_code: u16, west: f64) -> RasterConfigs {
+        let grid = GridFixture::new(3, 4, |_, _| 0f64)
+            .with_origin(west, 0f64)
+            .with_epsg_code(epsg_code);
+        grid_fixture_raster("grid.tif", &grid).configs
+    }
+
+    #[test]
+    fn test_geometry_mismatches() {
+        let base = configs(32611, 0f64);
+        assert!(geometry_mismatches(&base, &configs(32611, 0f64), true).is_empty());
+        assert_eq!(
+            geometry_mismatches(&base, &configs(32610, 0f64), true),
+            vec!["epsg_code"]
+        );
+        assert!(geometry_mismatches(&base, &configs(32610, 0f64), false).is_empty());
+        assert_eq!(
+            geometry_mismatches(&base, &configs(32611, 5f64), false),
+            vec!["east", "west"]
+        );
+    }
+
+    #[test]
+    fn test_check_geometry() {
+        let rasters = [
+            ("--d8_pntr", "d8.tif", configs(32611, 0f64)),
+            ("--streams", "streams.tif", configs(32610, 0f64)),
+        ];
+        assert!(check_geometry(&rasters, false).is_ok());
+        let err = check_geometry(&rasters, true).unwrap_err().to_string();
+        let lines: Vec<&str> = err.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Input rasters must share geometry; differs from --d8_pntr: --streams (epsg_code)."
+        );
+        assert_eq!(
+            lines[2],
+            "  --streams streams.tif: 3 rows x 4 columns, resolution 10 x 10, EPSG 32610, north 30, south 0, east 40, west 0"
+        );
+    }
+}
@@ -0,0 +1,1252 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool assigns a WEPP management scenario to each hillslope of a TOPAZ-style sub-catchment raster
+/// (`--subwta`), e.g. the output of `HillslopesTopaz`, using an ordered list of rules (`--rules`). Each
+/// rule maps a set of conditions on the hillslope's attributes to an integer management code and a
+/// management name, e.g. the file name of a WEPP management (.man) file. Hillslopes are the cells whose
+/// IDs end in 1, 2, or 3; channels (IDs ending in 4) are not assigned a management.
+///
+/// The attributes of each hillslope come from three sources:
+///
+/// - The built-in attributes `topaz_id`, `num_cells`, and `area` (in squared map units).
+/// - An optional per-hillslope statistics table (`--stats`), e.g. the dominant land cover and soil of
+///   each hillslope. This is a tab-separated file with a header row, a `topaz_id` column, and one row per
+///   hillslope; every other column becomes an attribute named after its (lower-case) header.
+/// - Optional categorical rasters (`--rasters`), given as a semicolon-separated list of `name=file`
+///   pairs, e.g. `--rasters='landuse=nlcd.tif;soil=mukey.tif'`. The rasters must share the grid (rows,
+///   columns, resolution, and extent) of the subwta raster. Each raster adds two attributes: `<name>`, the most common (integer)
+///   value of the hillslope's cells, with ties going to the smaller value, and `<name>_mean`, the mean
+///   value of its cells. NoData cells are ignored.
+///
+/// The rules file is either a tab-separated table or, when its extension is `.yaml` or `.yml`, a YAML
+/// list. A table has a header row with `code`, `management`, and `conditions` columns, in any order,
+/// followed by one rule per row; blank lines and lines starting with `#` are ignored, e.g.:
+///
+/// | code | management | conditions |
+/// |------|------------|------------|
+/// | 1 | forest.man | landuse in {41, 42, 43}; slope_mean < 30 |
+/// | 2 | grass.man | landuse in {71, 81} |
+/// | 3 | agriculture.man | landuse = 82 |
+/// | 0 | default.man | default |
+///
+/// The equivalent YAML file is a list of mappings with the same keys, one `key: value` pair per line and
+/// optionally quoted values:
+///
+/// ```text
+/// - code: 1
+///   management: forest.man
+///   conditions: "landuse in {41, 42, 43}; slope_mean < 30"
+/// - code: 0
+///   management: default.man
+///   conditions: default
+/// ```
+///
+/// Codes are non-negative integers. The conditions of a rule are separated by semicolons and must all
+/// hold for the rule to match. A condition is either a comparison, `attribute op value` with `op` one of
+/// `<`, `<=`, `>`, `>=`, `=` (or `==`), and `!=`, or a category membership test, `attribute in {a, b, ...}`
+/// or `attribute not in {a, b, ...}`. The ordering comparisons require numeric values; equality and
+/// membership compare numerically when both values are numbers and as text otherwise. A condition on an
+/// attribute that a hillslope has no value for, e.g. a hillslope missing from the statistics table,
+/// does not hold. The conditions `*`, which matches every hillslope, and `default`, which marks the rule
+/// used for unmatched hillslopes, stand alone. Referring to an attribute that none of the inputs provide
+/// is an error, as is any malformed rule, and errors report the line number of the offending rule.
+///
+/// Rules are tried in the order of the file, and each hillslope is assigned the first rule whose
+/// conditions hold. When no rule matches a hillslope, the tool fails by default; with
+/// `--on_unmatched=default` the hillslope is assigned the `default` rule instead, which the rules file
+/// must then contain.
+///
+/// The output (`--output`) is a tab-separated table with one row per hillslope, ordered by TOPAZ ID, and
+/// the columns `topaz_id`, `num_cells`, `code`, `management`, and `rule_line`, the line number of the
+/// assigned rule in the rules file. When `--out_raster` is specified, a raster of the management codes is
+/// also written, with each hillslope's cells stamped with its code and all other cells set to NoData.
+///
+/// # See Also
+/// `HillslopesTopaz`, `HillslopeWidthFunction`
+pub struct SubwtaToWeppManagementGrid {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SubwtaToWeppManagementGrid {
+    pub fn new() -> SubwtaToWeppManagementGrid {
+        // public constructor
+        let name = "SubwtaToWeppManagementGrid".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description =
+            "Assigns WEPP management scenarios to TOPAZ hillslopes using an ordered rules file."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Subwta File".to_owned(),
+            flags: vec!["--subwta".to_owned()],
+            description: "Input TOPAZ-style hillslope and channel ID raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Hillslope Statistics File".to_owned(),
+            flags: vec!["--stats".to_owned()],
+            description:
+                "Optional tab-separated per-hillslope statistics table with a topaz_id column."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Categorical Rasters".to_owned(),
+            flags: vec!["--rasters".to_owned()],
+            description: "Optional semicolon-separated list of name=file categorical rasters, e.g. 'landuse=nlcd.tif;soil=mukey.tif'.".to_owned(),
+            parameter_type: ParameterType::String,
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Rules File".to_owned(),
+            flags: vec!["--rules".to_owned()],
+            description:
+                "Input ordered management rules file, tab-separated or YAML (.yaml, .yml)."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Any),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated per-hillslope management assignment table."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Management Code Raster File".to_owned(),
+            flags: vec!["--out_raster".to_owned()],
+            description: "Optional output raster of the management code of each hillslope."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Unmatched Hillslopes".to_owned(),
+            flags: vec!["--on_unmatched".to_owned()],
+            description: "What to do with hillslopes that match no rule: fail ('error') or assign the 'default' rule.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "error".to_owned(),
+                "default".to_owned(),
+            ]),
+            default_value: Some("error".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --stats='hillslope_stats.tsv' --rules='rules.tsv' -o='management.tsv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --subwta='subwta.tif' --rasters='landuse=nlcd.tif;soil=mukey.tif' --rules='rules.yaml' -o='management.tsv' --out_raster='management.tif' --on_unmatched=default", short_exe, name).replace("*", &sep);
+
+        SubwtaToWeppManagementGrid {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for SubwtaToWeppManagementGrid {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut subwta_file = String::new();
+        let mut stats_file = String::new();
+        let mut rasters_list = String::new();
+        let mut rules_file = String::new();
+        let mut output_file = String::new();
+        let mut out_raster_file = String::new();
+        let mut use_default = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-subwta" {
+                subwta_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-stats" {
+                stats_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-rasters" {
+                // the list itself contains equals signs
+                rasters_list = if keyval {
+                    vec[1..].join("=")
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-rules" {
+                rules_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-out_raster" {
+                out_raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-on_unmatched" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                use_default = match value.trim().to_lowercase().as_str() {
+                    "error" => false,
+                    "default" => true,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "--on_unmatched must be 'error' or 'default'; got '{}'.",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        subwta_file = resolve_path(working_directory, &subwta_file);
+        rules_file = resolve_path(working_directory, &rules_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        // Parse the rules first so that a malformed rules file fails before any rasters are read.
+        let rules_text = fs::read_to_string(&rules_file)?;
+        let lower_rules_file = rules_file.to_lowercase();
+        let rules = if lower_rules_file.ends_with(".yaml") || lower_rules_file.ends_with(".yml") {
+            parse_yaml_rules(&rules_file, &rules_text)?
+        } else {
+            parse_tsv_rules(&rules_file, &rules_text)?
+        };
+        let default_rule = rules.iter().position(|r| r.is_default);
+        if use_default && default_rule.is_none() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "--on_unmatched=default requires a rule with the 'default' condition in the rules file '{}'.",
+                    rules_file
+                ),
+            ));
+        }
+
+        let mut rasters: Vec<(String, String)> = vec![];
+        for entry in rasters_list.split(';') {
+            let entry = entry.trim();
+            if entry.is_empty() {
+                continue;
+            }
+            let (name, file) = match entry.split_once('=') {
+                Some((name, file)) if !name.trim().is_empty() && !file.trim().is_empty() => {
+                    (name.trim().to_lowercase(), file.trim())
+                }
+                _ => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "--rasters entries must be 'name=file' pairs; got '{}'.",
+                            entry
+                        ),
+                    ))
+                }
+            };
+            if rasters.iter().any(|(n, _)| *n == name) {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!("--rasters lists the name '{}' more than once.", name),
+                ));
+            }
+            rasters.push((name, resolve_path(working_directory, file)));
+        }
+
+        let flags: Vec<String> = rasters
+            .iter()
+            .map(|(name, _)| format!("--rasters {}", name))
+            .collect();
+        let mut inputs = vec![("--subwta", &subwta_file)];
+        for (flag, (_, file)) in flags.iter().zip(rasters.iter()) {
+            inputs.push((flag.as_str(), file));
+        }
+        check_rasters_share_geometry(&inputs, false)?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let subwta = Raster::new(&subwta_file, "r")?;
+        let rows = subwta.configs.rows as isize;
+        let columns = subwta.configs.columns as isize;
+
+        let start = Instant::now();
+
+        // The TOPAZ ID of each cell, or 0 for NoData and non-positive values.
+        let subwta_nodata = subwta.configs.nodata;
+        let mut ids: Array2D<i64> = Array2D::new(rows, columns, 0, 0)?;
+        let mut num_cells: BTreeMap<i64, usize> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = subwta.get_value(row, col);
+                if z != subwta_nodata && z > 0f64 {
+                    let id = z.round() as i64;
+                    ids.set_value(row, col, id);
+                    if matches!(id % 10, 1..=3) {
+                        *num_cells.entry(id).or_default() += 1;
+                    }
+                }
+            }
+        }
+        if num_cells.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No hillslope cells (IDs ending in 1, 2, or 3) were found in the subwta raster.",
+            ));
+        }
+
+        // Gather the attributes of each hillslope.
+        let cell_area = subwta.configs.resolution_x * subwta.configs.resolution_y;
+        let mut attribute_names: BTreeSet<String> = ["topaz_id", "num_cells", "area"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let mut attributes: BTreeMap<i64, HashMap<String, String>> = BTreeMap::new();
+
+        if !stats_file.trim().is_empty() {
+            let stats_file = resolve_path(working_directory, &stats_file);
+            let (stat_columns, table) = read_stats_table(&stats_file)?;
+            let mut num_missing = 0usize;
+            for &id in num_cells.keys() {
+                match table.get(&id) {
+                    Some(values) => {
+                        let entry = attributes.entry(id).or_default();
+                        for (name, value) in stat_columns.iter().zip(values.iter()) {
+                            entry.insert(name.clone(), value.clone());
+                        }
+                    }
+                    None => num_missing += 1,
+                }
+            }
+            if verbose && num_missing > 0 {
+                println!(
+                    "Warning: {} hillslopes are missing from the statistics table {}.",
+                    num_missing, stats_file
+                );
+            }
+            attribute_names.extend(stat_columns);
+        }
+
+        for (name, file) in &rasters {
+            let raster = Raster::new(file, "r")?;
+            let nodata = raster.configs.nodata;
+            let mut counts: HashMap<i64, BTreeMap<i64, usize>> = HashMap::new();
+            let mut sums: HashMap<i64, (f64, usize)> = HashMap::new();
+            for row in 0..rows {
+                for col in 0..columns {
+                    let id = ids.get_value(row, col);
+                    let z = raster.get_value(row, col);
+                    if !num_cells.contains_key(&id) || z == nodata {
+                        continue;
+                    }
+                    *counts
+                        .entry(id)
+                        .or_default()
+                        .entry(z.round() as i64)
+                        .or_default() += 1;
+                    let sum = sums.entry(id).or_insert((0f64, 0));
+                    sum.0 += z;
+                    sum.1 += 1;
+                }
+            }
+            for (id, values) in &counts {
+                let mut dominant = (0i64, 0usize);
+                // The values are in ascending order, so ties go to the smaller value.
+                for (&value, &n) in values {
+                    if n > dominant.1 {
+                        dominant = (value, n);
+                    }
+                }
+                let (sum, n) = sums[id];
+                let entry = attributes.entry(*id).or_default();
+                entry.insert(name.clone(), dominant.0.to_string());
+                entry.insert(format!("{}_mean", name), (sum / n as f64).to_string());
+            }
+            attribute_names.insert(name.clone());
+            attribute_names.insert(format!("{}_mean", name));
+        }
+
+        for (&id, &n) in &num_cells {
+            let entry = attributes.entry(id).or_default();
+            entry.insert("topaz_id".to_string(), id.to_string());
+            entry.insert("num_cells".to_string(), n.to_string());
+            entry.insert("area".to_string(), (n as f64 * cell_area).to_string());
+        }
+
+        for rule in &rules {
+            for condition in &rule.conditions {
+                if !attribute_names.contains(&condition.attribute) {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Rules file '{}', line {}: unknown attribute '{}'; the available attributes are {}.",
+                            rules_file,
+                            rule.line,
+                            condition.attribute,
+                            attribute_names.iter().cloned().collect::<Vec<String>>().join(", ")
+                        ),
+                    ));
+                }
+            }
+        }
+
+        // Assign each hillslope the first rule that it matches.
+        let mut assignments: Vec<(i64, usize, &Rule)> = Vec::with_capacity(num_cells.len());
+        let mut unmatched = vec![];
+        let mut num_defaulted = 0usize;
+        for (&id, &n) in &num_cells {
+            let values = &attributes[&id];
+            match rules.iter().find(|r| !r.is_default && r.matches(values)) {
+                Some(rule) => assignments.push((id, n, rule)),
+                None => match default_rule {
+                    Some(i) if use_default => {
+                        assignments.push((id, n, &rules[i]));
+                        num_defaulted += 1;
+                    }
+                    _ => unmatched.push(id),
+                },
+            }
+        }
+        if !unmatched.is_empty() {
+            let examples: Vec<String> =
+                unmatched.iter().take(10).map(|id| id.to_string()).collect();
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "{} hillslopes match no rule in '{}' (e.g. {}); add a catch-all '*' rule or use --on_unmatched=default.",
+                    unmatched.len(),
+                    rules_file,
+                    examples.join(", ")
+                ),
+            ));
+        }
+
+        write_table(&assignments, &output_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if !out_raster_file.trim().is_empty() {
+            out_raster_file = resolve_path(working_directory, &out_raster_file);
+            let codes: HashMap<i64, i32> =
+                assignments.iter().map(|(id, _, r)| (*id, r.code)).collect();
+            let out_nodata = -32768f64;
+            let mut output = Raster::initialize_using_file(&out_raster_file, &subwta);
+            output.configs.data_type = DataType::I32;
+            output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            output.configs.nodata = out_nodata;
+            output.reinitialize_values(out_nodata);
+            for row in 0..rows {
+                for col in 0..columns {
+                    if let Some(&code) = codes.get(&ids.get_value(row, col)) {
+                        output.set_value(row, col, code as f64);
+                    }
+                }
+            }
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("Input subwta file: {}", subwta_file));
+            output.add_metadata_entry(format!("Input rules file: {}", rules_file));
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+            output.write()?;
+        }
+
+        if verbose {
+            println!(
+                "Assigned managements to {} hillslopes in {}.",
+                assignments.len(),
+                output_file
+            );
+            if num_defaulted > 0 {
+                println!(
+                    "{} hillslopes matched no rule and were assigned the default rule.",
+                    num_defaulted
+                );
+            }
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operator {
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual,
+    In,
+    NotIn,
+}
+
+/// A single test on one of a hillslope's attributes.
+#[derive(Debug, PartialEq)]
+struct Condition {
+    attribute: String,
+    operator: Operator,
+    values: Vec<String>,
+}
+
+impl Condition {
+    fn holds(&self, value: &str) -> bool {
+        let equals = |other: &String| match (value.parse::<f64>(), other.parse::<f64>()) {
+            (Ok(a), Ok(b)) => a == b,
+            _ => value == other,
+        };
+        match self.operator {
+            Operator::Equal => equals(&self.values[0]),
+            Operator::NotEqual => !equals(&self.values[0]),
+            Operator::In => self.values.iter().any(equals),
+            Operator::NotIn => !self.values.iter().any(equals),
+            _ => {
+                let (a, b) = match (value.parse::<f64>(), self.values[0].parse::<f64>()) {
+                    (Ok(a), Ok(b)) => (a, b),
+                    _ => return false,
+                };
+                match self.operator {
+                    Operator::Less => a < b,
+                    Operator::LessEqual => a <= b,
+                    Operator::Greater => a > b,
+                    _ => a >= b,
+                }
+            }
+        }
+    }
+}
+
+/// A management rule and the line of the rules file that it was read from.
+#[derive(Debug)]
+struct Rule {
+    line: usize,
+    code: i32,
+    management: String,
+    conditions: Vec<Condition>,
+    is_default: bool,
+}
+
+impl Rule {
+    fn matches(&self, attributes: &HashMap<String, String>) -> bool {
+        self.conditions
+            .iter()
+            .all(|c| match attributes.get(&c.attribute) {
+                Some(value) if !value.trim().is_empty() => c.holds(value.trim()),
+                _ => false,
+            })
+    }
+}
+
+fn rule_error(file_name: &str, line: usize, message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("Rules file '{}', line {}: {}", file_name, line, message),
+    )
+}
+
+fn unquote(s: &str) -> &str {
+    let s = s.trim();
+    if s.len() >= 2
+        && ((s.starts_with('"') && s.ends_with('"')) || (s.starts_with('\'') && s.ends_with('\'')))
+    {
+        &s[1..s.len() - 1]
+    } else {
+        s
+    }
+}
+
+fn parse_condition(text: &str) -> Result<Condition, String> {
+    let lower = text.to_lowercase();
+    for (keyword, operator) in [(" not in ", Operator::NotIn), (" in ", Operator::In)] {
+        if let Some(pos) = lower.find(keyword) {
+            let attribute = text[..pos].trim().to_lowercase();
+            let set = text[pos + keyword.len()..].trim();
+            let inner = if (set.starts_with('{') && set.ends_with('}'))
+                || (set.starts_with('[') && set.ends_with(']'))
+            {
+                &set[1..set.len() - 1]
+            } else {
+                return Err(format!(
+                    "the categories of '{}' must be enclosed in braces, e.g. {{1, 2}}",
+                    text
+                ));
+            };
+            let values: Vec<String> = inner
+                .split(',')
+                .map(|v| unquote(v).to_string())
+                .filter(|v| !v.is_empty())
+                .collect();
+            if attribute.is_empty() || values.is_empty() {
+                return Err(format!(
+                    "'{}' needs an attribute and at least one category",
+                    text
+                ));
+            }
+            return Ok(Condition {
+                attribute,
+                operator,
+                values,
+            });
+        }
+    }
+
+    let pos = match text.find(['<', '>', '=', '!']) {
+        Some(pos) => pos,
+        None => {
+            return Err(format!(
+                "'{}' is not a comparison (<, <=, >, >=, =, !=) or category test (in, not in)",
+                text
+            ))
+        }
+    };
+    let rest = &text[pos..];
+    let (operator, len) = if rest.starts_with("<=") {
+        (Operator::LessEqual, 2)
+    } else if rest.starts_with(">=") {
+        (Operator::GreaterEqual, 2)
+    } else if rest.starts_with("==") {
+        (Operator::Equal, 2)
+    } else if rest.starts_with("!=") {
+        (Operator::NotEqual, 2)
+    } else if rest.starts_with('<') {
+        (Operator::Less, 1)
+    } else if rest.starts_with('>') {
+        (Operator::Greater, 1)
+    } else if rest.starts_with('=') {
+        (Operator::Equal, 1)
+    } else {
+        return Err(format!("'{}' has an unknown operator", text));
+    };
+    let attribute = text[..pos].trim().to_lowercase();
+    let value = unquote(&rest[len..]).to_string();
+    if attribute.is_empty() || value.is_empty() {
+        return Err(format!("'{}' needs an attribute and a value", text));
+    }
+    let is_ordering = matches!(
+        operator,
+        Operator::Less | Operator::LessEqual | Operator::Greater | Operator::GreaterEqual
+    );
+    if is_ordering && value.parse::<f64>().is_err() {
+        return Err(format!("'{}' compares with a non-numeric value", text));
+    }
+    Ok(Condition {
+        attribute,
+        operator,
+        values: vec![value],
+    })
+}
+
+fn build_rule(
+    file_name: &str,
+    line: usize,
+    code: &str,
+    management: &str,
+    conditions: &str,
+) -> Result<Rule, Error> {
+    let code = match code.trim().parse::<i32>() {
+        Ok(c) if c >= 0 => c,
+        _ => {
+            return Err(rule_error(
+                file_name,
+                line,
+                &format!(
+                    "the code must be a non-negative integer; got '{}'",
+                    code.trim()
+                ),
+            ))
+        }
+    };
+    let management = management.trim().to_string();
+    if management.is_empty() {
+        return Err(rule_error(file_name, line, "the management is empty"));
+    }
+    let conditions = conditions.trim();
+    let mut rule = Rule {
+        line,
+        code,
+        management,
+        conditions: vec![],
+        is_default: false,
+    };
+    if conditions.eq_ignore_ascii_case("default") {
+        rule.is_default = true;
+    } else if conditions != "*" {
+        for text in conditions
+            .split(';')
+            .map(|c| c.trim())
+            .filter(|c| !c.is_empty())
+        {
+            let condition =
+                parse_condition(text).map_err(|msg| rule_error(file_name, line, &msg))?;
+            rule.conditions.push(condition);
+        }
+        if rule.conditions.is_empty() {
+            return Err(rule_error(
+                file_name,
+                line,
+                "the rule has no conditions; use '*' to match every hillslope",
+            ));
+        }
+    }
+    Ok(rule)
+}
+
+fn check_rules(file_name: &str, rules: Vec<Rule>) -> Result<Vec<Rule>, Error> {
+    if rules.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("Rules file '{}' contains no rules.", file_name),
+        ));
+    }
+    if let Some(second) = rules.iter().filter(|r| r.is_default).nth(1) {
+        return Err(rule_error(
+            file_name,
+            second.line,
+            "only one rule may use the 'default' condition",
+        ));
+    }
+    Ok(rules)
+}
+
+/// Parses a tab-separated rules table with `code`, `management`, and `conditions` columns.
+fn parse_tsv_rules(file_name: &str, text: &str) -> Result<Vec<Rule>, Error> {
+    let mut header: Option<[usize; 3]> = None;
+    let mut rules = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line_num = i + 1;
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        match header {
+            None => {
+                let mut columns = [usize::MAX; 3];
+                for (j, field) in fields.iter().enumerate() {
+                    match field.trim().to_lowercase().as_str() {
+                        "code" => columns[0] = j,
+                        "management" => columns[1] = j,
+                        "conditions" => columns[2] = j,
+                        _ => {}
+                    }
+                }
+                if columns.contains(&usize::MAX) {
+                    return Err(rule_error(
+                        file_name,
+                        line_num,
+                        "the header must have tab-separated 'code', 'management', and 'conditions' columns",
+                    ));
+                }
+                header = Some(columns);
+            }
+            Some([code, management, conditions]) => {
+                let needed = code.max(management).max(conditions) + 1;
+                if fields.len() < needed {
+                    return Err(rule_error(
+                        file_name,
+                        line_num,
+                        &format!(
+                            "expected at least {} tab-separated fields, found {}",
+                            needed,
+                            fields.len()
+                        ),
+                    ));
+                }
+                rules.push(build_rule(
+                    file_name,
+                    line_num,
+                    fields[code],
+                    fields[management],
+                    fields[conditions],
+                )?);
+            }
+        }
+    }
+    check_rules(file_name, rules)
+}
+
+/// Parses a YAML list of rules, each a mapping with `code`, `management`, and `conditions` keys. Only
+/// the block style used in the tool documentation is supported.
+fn parse_yaml_rules(file_name: &str, text: &str) -> Result<Vec<Rule>, Error> {
+    let mut items: Vec<(usize, HashMap<String, String>)> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line_num = i + 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed == "---" {
+            continue;
+        }
+        let pair = if trimmed == "-" || trimmed.starts_with("- ") {
+            items.push((line_num, HashMap::new()));
+            trimmed[1..].trim()
+        } else if line.starts_with([' ', '\t']) && !items.is_empty() {
+            trimmed
+        } else {
+            return Err(rule_error(
+                file_name,
+                line_num,
+                "expected a list item starting with '- '",
+            ));
+        };
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = match pair.split_once(':') {
+            Some((key, value)) => (key.trim().to_lowercase(), unquote(value).to_string()),
+            None => return Err(rule_error(file_name, line_num, "expected 'key: value'")),
+        };
+        if !["code", "management", "conditions"].contains(&key.as_str()) {
+            return Err(rule_error(
+                file_name,
+                line_num,
+                &format!(
+                    "unknown key '{}'; expected 'code', 'management', or 'conditions'",
+                    key
+                ),
+            ));
+        }
+        let item = &mut items.last_mut().unwrap().1;
+        if item.insert(key.clone(), value).is_some() {
+            return Err(rule_error(
+                file_name,
+                line_num,
+                &format!("the key '{}' is repeated", key),
+            ));
+        }
+    }
+
+    let mut rules = vec![];
+    for (line_num, item) in &items {
+        for key in ["code", "management", "conditions"] {
+            if !item.contains_key(key) {
+                return Err(rule_error(
+                    file_name,
+                    *line_num,
+                    &format!("the rule is missing the '{}' key", key),
+                ));
+            }
+        }
+        rules.push(build_rule(
+            file_name,
+            *line_num,
+            &item["code"],
+            &item["management"],
+            &item["conditions"],
+        )?);
+    }
+    check_rules(file_name, rules)
+}
+
+/// The attribute values of each hillslope in a statistics table, keyed by TOPAZ ID.
+type StatsTable = HashMap<i64, Vec<String>>;
+
+/// Reads a tab-separated per-hillslope statistics table, returning the lower-case names of its
+/// attribute columns and the values of each hillslope, keyed by TOPAZ ID.
+fn read_stats_table(file_name: &str) -> io::Result<(Vec<String>, StatsTable)> {
+    let text = fs::read_to_string(file_name)?;
+    let table_error = |line: usize, message: String| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Statistics file '{}', line {}: {}",
+                file_name, line, message
+            ),
+        )
+    };
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let (header_line, header) = match lines.next() {
+        Some((i, l)) => (i + 1, l),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Statistics file '{}' is empty.", file_name),
+            ))
+        }
+    };
+    let names: Vec<String> = header
+        .split('\t')
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+    let id_column = match names.iter().position(|n| n == "topaz_id") {
+        Some(j) => j,
+        None => {
+            return Err(table_error(
+                header_line,
+                "the header has no 'topaz_id' column".to_string(),
+            ))
+        }
+    };
+
+    let mut table: StatsTable = HashMap::new();
+    for (i, line) in lines {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != names.len() {
+            return Err(table_error(
+                i + 1,
+                format!(
+                    "expected {} tab-separated fields, found {}",
+                    names.len(),
+                    fields.len()
+                ),
+            ));
+        }
+        let id = match fields[id_column].trim().parse::<f64>() {
+            Ok(id) => id.round() as i64,
+            Err(_) => {
+                return Err(table_error(
+                    i + 1,
+                    format!("invalid topaz_id '{}'", fields[id_column].trim()),
+                ))
+            }
+        };
+        let values = fields
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != id_column)
+            .map(|(_, v)| unquote(v).to_string())
+            .collect();
+        if table.insert(id, values).is_some() {
+            return Err(table_error(i + 1, format!("topaz_id {} is repeated", id)));
+        }
+    }
+    let columns = names
+        .into_iter()
+        .enumerate()
+        .filter(|(j, _)| *j != id_column)
+        .map(|(_, n)| n)
+        .collect();
+    Ok((columns, table))
+}
+
+fn write_table(assignments: &[(i64, usize, &Rule)], file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "topaz_id\tnum_cells\tcode\tmanagement\trule_line"
+    )?;
+    for (id, num_cells, rule) in assignments {
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{}\t{}",
+            id, num_cells, rule.code, rule.management, rule.line
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        parse_condition, parse_tsv_rules, parse_yaml_rules, Operator, SubwtaToWeppManagementGrid,
+    };
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 2 x 6 grid with 10 m cells. Columns 0-1 are hillslope 21, column 2 is hillslope 22,
+    // column 3 is channel 24, and columns 4-5 are hillslope 23. The land use raster is 41 on the
+    // left half, 82 on the right half, and NoData in the bottom-right cell.
+    fn write_rasters(dir: &str) {
+        let write = |name: &str, f: &dyn Fn(isize, isize) -> f64| {
+            let grid = GridFixture::new(2, 6, f).categorical();
+            grid_fixture_raster(&format!("{}/{}", dir, name), &grid)
+                .write()
+                .unwrap();
+        };
+        write("subwta.tif", &|_, c| match c {
+            0..=1 => 21f64,
+            2 => 22f64,
+            3 => 24f64,
+            _ => 23f64,
+        });
+        write("landuse.tif", &|r, c| match c {
+            0..=2 => 41f64,
+            5 if r == 1 => -32768f64,
+            _ => 82f64,
+        });
+        fs::write(
+            format!("{}/stats.tsv", dir),
+            "topaz_id\tslope\tsoil\n21\t0.35\tloam\n22\t0.05\tclay\n",
+        )
+        .unwrap();
+    }
+
+    fn run(
+        dir: &str,
+        rules_file: &str,
+        rules: &str,
+        extra: &[&str],
+    ) -> Result<Vec<Vec<String>>, String> {
+        fs::write(format!("{}/{}", dir, rules_file), rules).unwrap();
+        let mut args = vec![
+            format!("--subwta={}/subwta.tif", dir),
+            format!("--stats={}/stats.tsv", dir),
+            format!("--rasters=landuse={}/landuse.tif", dir),
+            format!("--rules={}/{}", dir, rules_file),
+            format!("--output={}/management.tsv", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        SubwtaToWeppManagementGrid::new()
+            .run(args, "", false)
+            .map_err(|e| e.to_string())?;
+        Ok(fs::read_to_string(format!("{}/management.tsv", dir))
+            .unwrap()
+            .lines()
+            .skip(1)
+            .map(|l| l.split('\t').map(|s| s.to_string()).collect())
+            .collect())
+    }
+
+    #[test]
+    fn test_parse_conditions() {
+        let c = parse_condition("Landuse in {41, '42', 43}").unwrap();
+        assert_eq!(c.attribute, "landuse");
+        assert_eq!(c.operator, Operator::In);
+        assert_eq!(c.values, vec!["41", "42", "43"]);
+        assert!(c.holds("42.0"));
+        assert!(!c.holds("44"));
+        let c = parse_condition("soil not in [clay, silt]").unwrap();
+        assert_eq!(c.operator, Operator::NotIn);
+        assert!(c.holds("loam"));
+        let c = parse_condition("slope >= 0.3").unwrap();
+        assert_eq!(c.operator, Operator::GreaterEqual);
+        assert!(c.holds("0.3") && !c.holds("0.2") && !c.holds("steep"));
+        assert!(parse_condition("soil != \"clay\"").unwrap().holds("loam"));
+        assert!(parse_condition("slope > steep").is_err());
+        assert!(parse_condition("landuse in 41, 42").is_err());
+        assert!(parse_condition("landuse").is_err());
+    }
+
+    #[test]
+    fn test_rule_errors_report_line_numbers() {
+        let err = parse_tsv_rules("r.tsv", "# rules\ncode\tmanagement\tconditions\n1\tforest.man\tlanduse = 41\n2\tgrass.man\tslope >> 2\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.starts_with("Rules file 'r.tsv', line 4:"), "{}", err);
+        let err = parse_tsv_rules("r.tsv", "code\tmanagement\n")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("line 1:"), "{}", err);
+        let err = parse_tsv_rules(
+            "r.tsv",
+            "code\tmanagement\tconditions\n\n-1\tforest.man\t*\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("line 3: the code"), "{}", err);
+        let err = parse_tsv_rules(
+            "r.tsv",
+            "code\tmanagement\tconditions\n0\ta.man\tdefault\n1\tb.man\tDEFAULT\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("line 3:"), "{}", err);
+
+        let rules = parse_yaml_rules(
+            "r.yaml",
+            "# rules\n- code: 1\n  management: \"forest.man\"\n  conditions: 'landuse in {41, 42}; slope < 0.3'\n-\n  code: 0\n  management: default.man\n  conditions: default\n",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!((rules[0].line, rules[0].code), (2, 1));
+        assert_eq!(rules[0].management, "forest.man");
+        assert_eq!(rules[0].conditions.len(), 2);
+        assert_eq!(rules[1].line, 5);
+        assert!(rules[1].is_default);
+        let err = parse_yaml_rules(
+            "r.yaml",
+            "- code: 1\n  managment: a.man\n  conditions: '*'\n",
+        )
+        .unwrap_err()
+        .to_string();
+        assert!(err.contains("line 2: unknown key 'managment'"), "{}", err);
+        let err = parse_yaml_rules("r.yaml", "- code: 1\n  conditions: '*'\n")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("line 1: the rule is missing the 'management' key"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_assign_managements() {
+        let dir = temp_dir("subwta_to_wepp_management_grid").unwrap();
+        write_rasters(&dir);
+        let rules = "code\tmanagement\tconditions\n\
+                     1\tforest.man\tlanduse = 41; slope > 0.2\n\
+                     2\tgrass.man\tlanduse_mean >= 60\n\
+                     0\tdefault.man\tdefault\n";
+        // Hillslope 22 is forested but gentle, so it matches no rule.
+        let err = run(&dir, "rules.tsv", rules, &[]).unwrap_err();
+        assert!(err.starts_with("1 hillslopes match no rule"), "{}", err);
+        assert!(err.contains("(e.g. 22)"), "{}", err);
+
+        let rows = run(
+            &dir,
+            "rules.tsv",
+            rules,
+            &[
+                "--on_unmatched=default",
+                "--out_raster={dir}/management.tif",
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            rows,
+            vec![
+                vec!["21", "4", "1", "forest.man", "2"],
+                vec!["22", "2", "0", "default.man", "4"],
+                vec!["23", "4", "2", "grass.man", "3"],
+            ]
+        );
+        let raster = Raster::new(&format!("{}/management.tif", dir), "r").unwrap();
+        let codes: Vec<f64> = (0..6).map(|c| raster.get_value(1, c)).collect();
+        assert_eq!(codes, vec![1f64, 1f64, 0f64, -32768f64, 2f64, 2f64]);
+
+        // Hillslope 23 is missing from the statistics table, so its conditions on soil do not hold.
+        let rows = run(
+            &dir,
+            "rules.yaml",
+            "- code: 5\n  management: clay.man\n  conditions: soil in {clay}\n- code: 6\n  management: other.man\n  conditions: soil != clay\n- code: 7\n  management: rest.man\n  conditions: '*'\n",
+            &[],
+        )
+        .unwrap();
+        let codes: Vec<&str> = rows.iter().map(|r| r[2].as_str()).collect();
+        assert_eq!(codes, vec!["6", "5", "7"]);
+
+        let err = run(
+            &dir,
+            "rules.tsv",
+            "code\tmanagement\tconditions\n1\ta.man\tslope > 0.1\n2\tb.man\taspect < 90\n",
+            &[],
+        )
+        .unwrap_err();
+        assert!(
+            err.contains("line 3: unknown attribute 'aspect'"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,607 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use serde_json::{json, Value as JsonValue};
+use std::collections::BTreeMap;
+use std::env;
+use std::f64;
+use std::f64::consts::PI;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool reports basic morphometrics for each basin in a watershed raster (`--watershed`), such as
+/// the output of the `Watershed`, `Basins`, or `Subbasins` tools. The watershed raster may be either a
+/// binary mask or contain a label for each basin; every non-zero, non-nodata value is treated as a basin
+/// label. The tool writes one row per label to a tab-separated table (`--output`) with the following
+/// columns:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `label` | The basin label. |
+/// | `num_cells` | Number of grid cells in the basin. |
+/// | `area` | Basin area, in squared map units. |
+/// | `perimeter` | Length of the basin boundary, in map units. |
+/// | `compactness` | Gravelius compactness coefficient, `perimeter / (2 * sqrt(PI * area))`. |
+/// | `centroid_x`, `centroid_y` | Mean coordinates of the basin's cell centres. |
+/// | `min_x`, `min_y`, `max_x`, `max_y` | Bounding box of the basin's cell edges. |
+/// | `min_elev`, `max_elev`, `relief` | Elevation range, when a DEM (`--dem`) is supplied. |
+///
+/// The perimeter is measured along the cell edges separating the basin from other labels, background,
+/// and the grid edge, including the edges of any interior holes. Counting exterior edges rather than
+/// boundary cells means that diagonal, staircase-shaped boundaries are measured correctly. The compactness
+/// coefficient is 1.0 for a circle, and is about 1.128 for a square basin.
+///
+/// If the output file name ends in `.json`, the same metrics are instead written as a JSON object with a
+/// `basins` array, which is convenient for pipelines that delineate a single basin at a time. Elevation
+/// statistics ignore DEM nodata cells, and the DEM must have the same number of rows and columns as the
+/// watershed raster. Measurements are in the raster's map units, so a projected coordinate system
+/// should be used.
+///
+/// # See Also
+/// `Watershed`, `Basins`, `Subbasins`, `ZonalStatistics`
+pub struct WatershedAreaReport {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl WatershedAreaReport {
+    pub fn new() -> WatershedAreaReport {
+        // public constructor
+        let name = "WatershedAreaReport".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Reports the area, perimeter, compactness, centroid, bounding box, and elevation range of each basin in a watershed raster.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Watershed File".to_owned(),
+            flags: vec!["--watershed".to_owned()],
+            description: "Input watershed raster, either a binary mask or labelled basins."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input DEM File (optional)".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description:
+                "Optional input DEM raster used to report the elevation range of each basin."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Report File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated table, or JSON file (*.json), of basin metrics."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --watershed=basins.tif -o=basins.tsv
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --watershed=watershed.tif --dem=dem.tif -o=watershed.json", short_exe, name).replace("*", &sep);
+
+        WatershedAreaReport {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for WatershedAreaReport {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut watershed_file = String::new();
+        let mut dem_file = String::new();
+        let mut output_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-watershed" {
+                watershed_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        if watershed_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Input watershed file (--watershed) not specified.",
+            ));
+        }
+        if output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output report file (--output) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        watershed_file = resolve_path(working_directory, &watershed_file);
+        dem_file = resolve_path(working_directory, &dem_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let watershed = Raster::new(&watershed_file, "r")?;
+        let dem = if !dem_file.is_empty() {
+            Some(Raster::new(&dem_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = watershed.configs.rows as isize;
+        let columns = watershed.configs.columns as isize;
+        let nodata = watershed.configs.nodata;
+        let res_x = watershed.configs.resolution_x;
+        let res_y = watershed.configs.resolution_y;
+
+        if let Some(ref dem) = dem {
+            if dem.configs.rows != watershed.configs.rows
+                || dem.configs.columns != watershed.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        let label_at = |row: isize, col: isize| -> Option<i64> {
+            let z = watershed.get_value(row, col);
+            if z == nodata || z == 0f64 {
+                None
+            } else {
+                Some(z.round() as i64)
+            }
+        };
+
+        let mut basins: BTreeMap<i64, BasinMetrics> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let label = match label_at(row, col) {
+                    Some(label) => label,
+                    None => continue,
+                };
+                let basin = basins
+                    .entry(label)
+                    .or_insert_with(|| BasinMetrics::new(label));
+                basin.num_cells += 1;
+
+                let x = watershed.get_x_from_column(col);
+                let y = watershed.get_y_from_row(row);
+                basin.sum_x += x;
+                basin.sum_y += y;
+                basin.min_x = basin.min_x.min(x - res_x / 2f64);
+                basin.max_x = basin.max_x.max(x + res_x / 2f64);
+                basin.min_y = basin.min_y.min(y - res_y / 2f64);
+                basin.max_y = basin.max_y.max(y + res_y / 2f64);
+
+                // exterior edges: north and south edges are res_x long, east and west edges res_y
+                if label_at(row - 1, col) != Some(label) {
+                    basin.perimeter += res_x;
+                }
+                if label_at(row + 1, col) != Some(label) {
+                    basin.perimeter += res_x;
+                }
+                if label_at(row, col - 1) != Some(label) {
+                    basin.perimeter += res_y;
+                }
+                if label_at(row, col + 1) != Some(label) {
+                    basin.perimeter += res_y;
+                }
+
+                if let Some(ref dem) = dem {
+                    let z = dem.get_value(row, col);
+                    if z != dem.configs.nodata {
+                        basin.min_elev = basin.min_elev.min(z);
+                        basin.max_elev = basin.max_elev.max(z);
+                    }
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Measuring basins: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if basins.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The watershed raster does not contain any basin (non-zero, non-nodata) cells.",
+            ));
+        }
+
+        let cell_area = res_x * res_y;
+        for basin in basins.values_mut() {
+            basin.area = basin.num_cells as f64 * cell_area;
+            basin.compactness = basin.perimeter / (2f64 * (PI * basin.area).sqrt());
+        }
+        let basins: Vec<BasinMetrics> = basins.into_values().collect();
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Found {} basins.", basins.len());
+            println!("Writing basin report to {}.", output_file);
+        }
+        let has_dem = dem.is_some();
+        if output_file.to_lowercase().ends_with(".json") {
+            write_metrics_to_json(&basins, has_dem, &watershed_file, &dem_file, &output_file)?;
+        } else {
+            write_metrics_to_tsv(&basins, has_dem, &output_file)?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+struct BasinMetrics {
+    label: i64,
+    num_cells: usize,
+    area: f64,
+    perimeter: f64,
+    compactness: f64,
+    sum_x: f64,
+    sum_y: f64,
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+    min_elev: f64,
+    max_elev: f64,
+}
+
+impl BasinMetrics {
+    fn new(label: i64) -> BasinMetrics {
+        BasinMetrics {
+            label,
+            num_cells: 0,
+            area: 0f64,
+            perimeter: 0f64,
+            compactness: 0f64,
+            sum_x: 0f64,
+            sum_y: 0f64,
+            min_x: f64::INFINITY,
+            min_y: f64::INFINITY,
+            max_x: f64::NEG_INFINITY,
+            max_y: f64::NEG_INFINITY,
+            min_elev: f64::INFINITY,
+            max_elev: f64::NEG_INFINITY,
+        }
+    }
+
+    fn centroid(&self) -> (f64, f64) {
+        let n = self.num_cells as f64;
+        (self.sum_x / n, self.sum_y / n)
+    }
+
+    /// The elevation range, or None if the basin has no valid DEM cells.
+    fn elevation_range(&self) -> Option<(f64, f64)> {
+        if self.min_elev <= self.max_elev {
+            Some((self.min_elev, self.max_elev))
+        } else {
+            None
+        }
+    }
+}
+
+fn write_metrics_to_tsv(basins: &[BasinMetrics], has_dem: bool, file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    write!(
+        &mut file,
+        "label\tnum_cells\tarea\tperimeter\tcompactness\tcentroid_x\tcentroid_y\tmin_x\tmin_y\tmax_x\tmax_y"
+    )?;
+    if has_dem {
+        write!(&mut file, "\tmin_elev\tmax_elev\trelief")?;
+    }
+    writeln!(&mut file)?;
+    for b in basins {
+        let (cx, cy) = b.centroid();
+        write!(
+            &mut file,
+            "{}\t{}\t{:.3}\t{:.3}\t{:.4}\t{:.3}\t{:.3}\t{}\t{}\t{}\t{}",
+            b.label,
+            b.num_cells,
+            b.area,
+            b.perimeter,
+            b.compactness,
+            cx,
+            cy,
+            b.min_x,
+            b.min_y,
+            b.max_x,
+            b.max_y
+        )?;
+        if has_dem {
+            match b.elevation_range() {
+                Some((min, max)) => {
+                    write!(&mut file, "\t{:.3}\t{:.3}\t{:.3}", min, max, max - min)?
+                }
+                None => write!(&mut file, "\tNA\tNA\tNA")?,
+            }
+        }
+        writeln!(&mut file)?;
+    }
+    Ok(())
+}
+
+fn write_metrics_to_json(
+    basins: &[BasinMetrics],
+    has_dem: bool,
+    watershed_file: &str,
+    dem_file: &str,
+    file_path: &str,
+) -> io::Result<()> {
+    let records: Vec<JsonValue> = basins
+        .iter()
+        .map(|b| {
+            let (cx, cy) = b.centroid();
+            let mut record = json!({
+                "label": b.label,
+                "num_cells": b.num_cells,
+                "area": b.area,
+                "perimeter": b.perimeter,
+                "compactness": b.compactness,
+                "centroid_x": cx,
+                "centroid_y": cy,
+                "min_x": b.min_x,
+                "min_y": b.min_y,
+                "max_x": b.max_x,
+                "max_y": b.max_y,
+            });
+            if has_dem {
+                let range = b.elevation_range();
+                record["min_elev"] = json!(range.map(|r| r.0));
+                record["max_elev"] = json!(range.map(|r| r.1));
+                record["relief"] = json!(range.map(|r| r.1 - r.0));
+            }
+            record
+        })
+        .collect();
+    let report = json!({
+        "watershed": watershed_file,
+        "dem": if has_dem { json!(dem_file) } else { JsonValue::Null },
+        "basins": records,
+    });
+    let mut file = File::create(file_path)?;
+    file.write_all(serde_json::to_string_pretty(&report)?.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::WatershedAreaReport;
+    use crate::tools::WhiteboxTool;
+    use serde_json::Value;
+    use std::f64::consts::PI;
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    // Writes a 6 x 6 raster with 10 m cells whose south-west corner is at (0, 0).
+    fn write_raster(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        grid_fixture_raster(file, &GridFixture::new(6, 6, f))
+            .write()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_square_binary_mask() {
+        let dir = temp_dir("watershed_area_report_square").unwrap();
+        let mask = format!("{}/mask.tif", dir);
+        write_raster(&mask, &|r, c| {
+            if (1..=3).contains(&r) && (1..=3).contains(&c) {
+                1f64
+            } else {
+                0f64
+            }
+        });
+
+        let output = format!("{}/report.json", dir);
+        WatershedAreaReport::new()
+            .run(
+                vec![format!("--watershed={}", mask), format!("-o={}", output)],
+                "",
+                false,
+            )
+            .unwrap();
+        let report: Value = serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(report["dem"], Value::Null);
+        let basins = report["basins"].as_array().unwrap();
+        assert_eq!(basins.len(), 1);
+        let b = &basins[0];
+        assert_eq!(b["label"], 1);
+        assert_eq!(b["num_cells"], 9);
+        assert_eq!(b["area"], 900f64);
+        assert_eq!(b["perimeter"], 120f64);
+        let compactness = 120f64 / (2f64 * (PI * 900f64).sqrt());
+        assert!((b["compactness"].as_f64().unwrap() - compactness).abs() < 1e-9);
+        assert!((compactness - 1.1284).abs() < 1e-4);
+        assert_eq!(
+            (b["centroid_x"].as_f64(), b["centroid_y"].as_f64()),
+            (Some(25f64), Some(35f64))
+        );
+        assert_eq!(
+            (&b["min_x"], &b["min_y"], &b["max_x"], &b["max_y"]),
+            (
+                &Value::from(10f64),
+                &Value::from(20f64),
+                &Value::from(40f64),
+                &Value::from(50f64)
+            )
+        );
+        assert!(b.get("min_elev").is_none());
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_labelled_l_shape_and_staircase() {
+        let dir = temp_dir("watershed_area_report_labels").unwrap();
+        let labels = format!("{}/labels.tif", dir);
+        let dem = format!("{}/dem.tif", dir);
+        // label 2: an L of five cells; label 3: two cells nested in the L's corner;
+        // label 4: a four-cell diagonal staircase.
+        write_raster(&labels, &|r, c| match (r, c) {
+            (0, 0) | (1, 0) | (2, 0) | (2, 1) | (2, 2) => 2f64,
+            (0, 1) | (1, 1) => 3f64,
+            (3, 3) | (3, 4) | (4, 4) | (4, 5) => 4f64,
+            _ => 0f64,
+        });
+        write_raster(&dem, &|r, c| (r * 10 + c) as f64);
+
+        let output = format!("{}/report.tsv", dir);
+        WatershedAreaReport::new()
+            .run(
+                vec![
+                    "--watershed=labels.tif".to_string(),
+                    "--dem=dem.tif".to_string(),
+                    "--output=report.tsv".to_string(),
+                ],
+                &dir,
+                false,
+            )
+            .unwrap();
+        let rows = read_tsv(&output).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0][13], "relief");
+
+        // The L has 12 exterior cell edges; the edges shared with label 3 still count.
+        assert_eq!(
+            rows[1][..4],
+            ["2", "5", "500.000", "120.000"].map(|s| s.to_string())
+        );
+        assert_eq!(
+            rows[1][7..],
+            ["0", "30", "30", "60", "0.000", "22.000", "22.000"].map(|s| s.to_string())
+        );
+        assert_eq!(
+            rows[2][..4],
+            ["3", "2", "200.000", "60.000"].map(|s| s.to_string())
+        );
+        // Each diagonal step of the staircase adds two cell edges: 10 edges in all.
+        assert_eq!(
+            rows[3][..4],
+            ["4", "4", "400.000", "100.000"].map(|s| s.to_string())
+        );
+        assert_eq!(rows[3][5..7], ["45.000", "20.000"].map(|s| s.to_string()));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
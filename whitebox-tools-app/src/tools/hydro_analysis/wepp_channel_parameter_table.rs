@@ -0,0 +1,906 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::hillslopes_topaz::read_netw;
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::{BTreeMap, HashMap};
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool derives the WEPP channel input parameters of each link of a channel network table
+/// (`--netw`) written by `HillslopesTopaz`, replacing the spreadsheet step that usually converts
+/// `netw.tsv` into a channel parameter table. It needs no rasters of its own: the slope and length
+/// of each channel come from the DEM-derived `length_m` and `drop_m` columns of the network table.
+///
+/// For each channel, the tool reports:
+///
+/// - The representative slope, `drop_m / length_m` (0 for links of zero length).
+/// - The drainage area, the `areaup` of the link plus that of every link upstream of it, in km².
+/// - The channel width, from a drainage-area regression, `width = width_coeff × area^width_exp`,
+///   with the area in km² and the width in metres.
+/// - Manning's n, looked up by the channel's land use (see below), then by its stream order (the
+///   `order` column), and finally falling back to a default value.
+///
+/// The coefficients file (`--coefficients`) is a tab-separated table with a header row with the
+/// columns `parameter`, `key`, and `value`, followed by one coefficient per row; blank lines and lines
+/// starting with `#` are ignored, e.g.:
+///
+/// | parameter | key | value |
+/// |-----------|-----|-------|
+/// | width_coeff | | 1.5 |
+/// | width_exp | | 0.4 |
+/// | manning_order | 1 | 0.05 |
+/// | manning_order | 2 | 0.045 |
+/// | manning_landuse | 42 | 0.08 |
+/// | manning_default | | 0.04 |
+///
+/// The `width_coeff` and `width_exp` rows are required, and `manning_default` is optional; these have
+/// an empty key. The `manning_order` and `manning_landuse` rows are keyed by an integer stream order
+/// and land use code respectively. The `width_coeff` and Manning's n values must be positive. Errors
+/// in the coefficients file, e.g. an unknown parameter, a malformed value, or a repeated row, report
+/// the line number of the offending row. It is an error for a channel to have no Manning's n.
+///
+/// When a land use raster (`--landuse`) is given along with the subwta raster (`--subwta`) written
+/// with the network table, the land use of a channel is the most common value of the land use raster
+/// over the channel's cells in the subwta raster, with ties going to the smaller value; NoData cells
+/// are ignored. Channels whose land use has no `manning_landuse` row use their order instead.
+///
+/// Channels with a slope below `--min_slope` (default 0.001) are flagged in the `low_slope` column,
+/// and a warning reports how many there are. With `--clamp_slope`, the `slope` of these channels is
+/// set to `--min_slope`; the `derived_slope` column always holds the slope from the network table.
+///
+/// The output (`--output`) is a tab-separated table with one row per channel in WEPP channel routing
+/// order, i.e. ordered by the `chn_order` column of the network table, and the columns `chn_order`,
+/// `topaz_id`, `order`, `length_m`, `area_km2`, `derived_slope`, `slope`, `width_m`, `manning_n`,
+/// `n_source` (`landuse`, `order`, or `default`), and `low_slope`. With `--landuse`, a `landuse`
+/// column follows `order`, empty for channels with no land use cells.
+///
+/// # See Also
+/// `HillslopesTopaz`, `SubwtaToWeppManagementGrid`, `AccumulateAlongChannels`
+pub struct WeppChannelParameterTable {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl WeppChannelParameterTable {
+    pub fn new() -> WeppChannelParameterTable {
+        // public constructor
+        let name = "WeppChannelParameterTable".to_string();
+        let toolbox = "Hydrological Analysis".to_string();
+        let description = "Derives the WEPP channel slope, length, width, and Manning's n of each link of a HillslopesTopaz channel network table.".to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Channel Network Table".to_owned(),
+            flags: vec!["--netw".to_owned()],
+            description: "Input channel network TSV file from HillslopesTopaz.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Coefficients File".to_owned(),
+            flags: vec!["--coefficients".to_owned()],
+            description: "Input tab-separated width regression coefficients and Manning's n lookup, with parameter, key, and value columns.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated WEPP channel parameter table.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Subwta File".to_owned(),
+            flags: vec!["--subwta".to_owned()],
+            description: "Optional input TOPAZ-style hillslope and channel ID raster file; required with --landuse.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Land Use File".to_owned(),
+            flags: vec!["--landuse".to_owned()],
+            description: "Optional input land use raster file, used for the Manning's n lookup."
+                .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Minimum Slope".to_owned(),
+            flags: vec!["--min_slope".to_owned()],
+            description: "Channels with a slope below this value (m/m) are flagged.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.001".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Clamp slopes to the minimum?".to_owned(),
+            flags: vec!["--clamp_slope".to_owned()],
+            description: "Set the slope of flagged channels to the minimum slope.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --netw='netw.tsv' --coefficients='channel_coeffs.tsv' -o='chn_params.tsv'
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --netw='netw.tsv' --coefficients='channel_coeffs.tsv' -o='chn_params.tsv' --subwta='subwta.tif' --landuse='nlcd.tif' --min_slope=0.0005 --clamp_slope", short_exe, name).replace("*", &sep);
+
+        WeppChannelParameterTable {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for WeppChannelParameterTable {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut netw_file = String::new();
+        let mut coefficients_file = String::new();
+        let mut output_file = String::new();
+        let mut subwta_file = String::new();
+        let mut landuse_file = String::new();
+        let mut min_slope = 0.001f64;
+        let mut clamp_slope = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-netw" {
+                netw_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-coefficients" {
+                coefficients_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-subwta" {
+                subwta_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-landuse" {
+                landuse_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-min_slope" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                min_slope = match value.trim().parse::<f64>() {
+                    Ok(s) if s.is_finite() && s >= 0f64 => s,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "--min_slope must be a non-negative number; got '{}'.",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            } else if flag_val == "-clamp_slope" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    clamp_slope = true;
+                }
+            }
+        }
+
+        if !landuse_file.trim().is_empty() && subwta_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The land use raster (--landuse) requires the subwta raster (--subwta) to locate the channel cells.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        netw_file = resolve_path(working_directory, &netw_file);
+        coefficients_file = resolve_path(working_directory, &coefficients_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        // Read the coefficients first so that a malformed file fails before anything else is read.
+        let coefficients = read_coefficients(&coefficients_file)?;
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let links = read_netw(&netw_file)?;
+        if links.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The channel network table (--netw) {} has no links.",
+                    netw_file
+                ),
+            ));
+        }
+
+        let start = Instant::now();
+
+        // Accumulate the drainage areas in routing order, so that every link's inflows are totalled
+        // before the link itself.
+        let mut routing: Vec<usize> = (0..links.len()).collect();
+        routing.sort_by_key(|&i| (links[i].chn_order, links[i].topaz_id));
+        let index_of: HashMap<i64, usize> = links
+            .iter()
+            .enumerate()
+            .map(|(i, link)| (link.id, i))
+            .collect();
+        let mut area = vec![0f64; links.len()];
+        for &i in &routing {
+            area[i] += links[i].areaup;
+            for inflow in links[i].inflows {
+                if inflow < 0 {
+                    continue;
+                }
+                match index_of.get(&inflow) {
+                    Some(&j) => area[i] += area[j],
+                    None => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidData,
+                            format!(
+                                "Link {} on line {} of the channel network table (--netw) {} has an inflow ({}) that is not in the table.",
+                                links[i].topaz_id, links[i].line, netw_file, inflow
+                            ),
+                        ))
+                    }
+                }
+            }
+        }
+        if area.iter().all(|&a| a <= 0f64) {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!(
+                    "The channel network table (--netw) {} has no drainage area (areaup); it must be written by HillslopesTopaz with hillslopes.",
+                    netw_file
+                ),
+            ));
+        }
+
+        let mut landuse: Option<HashMap<i64, i64>> = None;
+        if !landuse_file.trim().is_empty() {
+            subwta_file = resolve_path(working_directory, &subwta_file);
+            landuse_file = resolve_path(working_directory, &landuse_file);
+            check_rasters_share_geometry(
+                &[("--subwta", &subwta_file), ("--landuse", &landuse_file)],
+                false,
+            )?;
+            landuse = Some(channel_landuse(&subwta_file, &landuse_file)?);
+        }
+
+        let mut channels = Vec::with_capacity(links.len());
+        let mut num_low_slope = 0usize;
+        for &i in &routing {
+            let link = &links[i];
+            let derived_slope = if link.length_m > 0f64 {
+                link.drop_m / link.length_m
+            } else {
+                0f64
+            };
+            // NaN slopes, e.g. from NoData elevations, are flagged as well.
+            let low_slope = derived_slope.is_nan() || derived_slope < min_slope;
+            if low_slope {
+                num_low_slope += 1;
+            }
+            let channel_landuse = landuse
+                .as_ref()
+                .and_then(|l| l.get(&link.topaz_id).copied());
+            let (manning_n, n_source) = match channel_landuse
+                .and_then(|code| coefficients.manning_landuse.get(&code))
+            {
+                Some(&n) => (n, "landuse"),
+                None => match coefficients.manning_order.get(&link.order) {
+                    Some(&n) => (n, "order"),
+                    None => match coefficients.manning_default {
+                        Some(n) => (n, "default"),
+                        None => {
+                            return Err(Error::new(
+                                ErrorKind::InvalidData,
+                                format!(
+                                    "No Manning's n for channel {} (order {}); add a manning_order or manning_default row to the coefficients file {}.",
+                                    link.topaz_id, link.order, coefficients_file
+                                ),
+                            ))
+                        }
+                    },
+                },
+            };
+            let area_km2 = area[i] / 1_000_000f64;
+            channels.push(ChannelParameters {
+                chn_order: link.chn_order,
+                topaz_id: link.topaz_id,
+                order: link.order,
+                landuse: channel_landuse,
+                length_m: link.length_m,
+                area_km2,
+                derived_slope,
+                slope: if low_slope && clamp_slope {
+                    min_slope
+                } else {
+                    derived_slope
+                },
+                width_m: coefficients.width_coeff * area_km2.powf(coefficients.width_exp),
+                manning_n,
+                n_source,
+                low_slope,
+            });
+        }
+
+        write_table(&channels, landuse.is_some(), &output_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        if num_low_slope > 0 {
+            println!(
+                "Warning: {} of {} channels have a slope below the minimum slope ({}){}.",
+                num_low_slope,
+                channels.len(),
+                min_slope,
+                if clamp_slope {
+                    " and were clamped to it"
+                } else {
+                    ""
+                }
+            );
+        }
+        if verbose {
+            println!(
+                "Wrote the parameters of {} channels to {}.",
+                channels.len(),
+                output_file
+            );
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The width regression coefficients and Manning's n lookups of a coefficients file.
+#[derive(Debug)]
+struct Coefficients {
+    width_coeff: f64,
+    width_exp: f64,
+    manning_order: BTreeMap<i64, f64>,
+    manning_landuse: BTreeMap<i64, f64>,
+    manning_default: Option<f64>,
+}
+
+fn coefficient_error(file_name: &str, line: usize, message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Coefficients file '{}', line {}: {}",
+            file_name, line, message
+        ),
+    )
+}
+
+/// Reads a coefficients file, reporting the line number of any malformed row.
+fn read_coefficients(file_name: &str) -> Result<Coefficients, Error> {
+    let text = fs::read_to_string(file_name)?;
+    parse_coefficients(file_name, &text)
+}
+
+fn parse_coefficients(file_name: &str, text: &str) -> Result<Coefficients, Error> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let (header_line, header) = match lines.next() {
+        Some((i, l)) => (i + 1, l),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Coefficients file '{}' is empty.", file_name),
+            ))
+        }
+    };
+    let names: Vec<String> = header
+        .split('\t')
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+    let mut columns = [0usize; 3];
+    for (k, name) in ["parameter", "key", "value"].iter().enumerate() {
+        columns[k] = match names.iter().position(|n| n == name) {
+            Some(j) => j,
+            None => {
+                return Err(coefficient_error(
+                    file_name,
+                    header_line,
+                    &format!("the header has no '{}' column", name),
+                ))
+            }
+        };
+    }
+
+    let mut width_coeff: Option<f64> = None;
+    let mut width_exp: Option<f64> = None;
+    let mut coefficients = Coefficients {
+        width_coeff: 0f64,
+        width_exp: 0f64,
+        manning_order: BTreeMap::new(),
+        manning_landuse: BTreeMap::new(),
+        manning_default: None,
+    };
+    for (i, line) in lines {
+        let line_no = i + 1;
+        let error = |message: String| coefficient_error(file_name, line_no, &message);
+        let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+        if fields.len() != names.len() {
+            return Err(error(format!(
+                "expected {} tab-separated fields, found {}",
+                names.len(),
+                fields.len()
+            )));
+        }
+        let parameter = fields[columns[0]].to_lowercase();
+        let key = fields[columns[1]];
+        let value = match fields[columns[2]].parse::<f64>() {
+            Ok(v) if v.is_finite() => v,
+            _ => {
+                return Err(error(format!(
+                    "invalid value '{}' for {}",
+                    fields[columns[2]], parameter
+                )))
+            }
+        };
+        if ![
+            "width_coeff",
+            "width_exp",
+            "manning_order",
+            "manning_landuse",
+            "manning_default",
+        ]
+        .contains(&parameter.as_str())
+        {
+            return Err(error(format!(
+                "unknown parameter '{}'; expected width_coeff, width_exp, manning_order, manning_landuse, or manning_default",
+                fields[columns[0]]
+            )));
+        }
+        let keyed = parameter == "manning_order" || parameter == "manning_landuse";
+        if keyed && key.is_empty() {
+            return Err(error(format!("{} requires an integer key", parameter)));
+        } else if !keyed && !key.is_empty() {
+            return Err(error(format!("{} takes no key; got '{}'", parameter, key)));
+        }
+        if parameter != "width_exp" && value <= 0f64 {
+            return Err(error(format!(
+                "{} must be positive; got {}",
+                parameter, value
+            )));
+        }
+        let repeated = match parameter.as_str() {
+            "width_coeff" => width_coeff.replace(value).is_some(),
+            "width_exp" => width_exp.replace(value).is_some(),
+            "manning_default" => coefficients.manning_default.replace(value).is_some(),
+            "manning_order" | "manning_landuse" => {
+                let code = match key.parse::<i64>() {
+                    Ok(code) => code,
+                    Err(_) => {
+                        return Err(error(format!(
+                            "invalid {} key '{}'; expected an integer",
+                            parameter, key
+                        )))
+                    }
+                };
+                let lookup = if parameter == "manning_order" {
+                    &mut coefficients.manning_order
+                } else {
+                    &mut coefficients.manning_landuse
+                };
+                lookup.insert(code, value).is_some()
+            }
+            _ => unreachable!(),
+        };
+        if repeated {
+            return Err(error(if keyed {
+                format!("{} {} is repeated", parameter, key)
+            } else {
+                format!("{} is repeated", parameter)
+            }));
+        }
+    }
+    coefficients.width_coeff = width_coeff.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Coefficients file '{}' has no width_coeff row.", file_name),
+        )
+    })?;
+    coefficients.width_exp = width_exp.ok_or_else(|| {
+        Error::new(
+            ErrorKind::InvalidData,
+            format!("Coefficients file '{}' has no width_exp row.", file_name),
+        )
+    })?;
+    Ok(coefficients)
+}
+
+/// Returns the most common land use of the channel cells (IDs ending in 4) of each channel in
+/// the subwta raster, with ties going to the smaller value. NoData land use cells are ignored.
+fn channel_landuse(subwta_file: &str, landuse_file: &str) -> Result<HashMap<i64, i64>, Error> {
+    let subwta = Raster::new(subwta_file, "r")?;
+    let landuse = Raster::new(landuse_file, "r")?;
+    let subwta_nodata = subwta.configs.nodata;
+    let landuse_nodata = landuse.configs.nodata;
+    let mut counts: HashMap<i64, BTreeMap<i64, usize>> = HashMap::new();
+    for row in 0..subwta.configs.rows as isize {
+        for col in 0..subwta.configs.columns as isize {
+            let z = subwta.get_value(row, col);
+            if z == subwta_nodata || z <= 0f64 {
+                continue;
+            }
+            let id = z.round() as i64;
+            let code = landuse.get_value(row, col);
+            if id % 10 != 4 || code == landuse_nodata {
+                continue;
+            }
+            *counts
+                .entry(id)
+                .or_default()
+                .entry(code.round() as i64)
+                .or_default() += 1;
+        }
+    }
+    Ok(counts
+        .into_iter()
+        .map(|(id, codes)| {
+            // max_by_key returns the last maximum, so reverse to prefer the smaller code.
+            let (&code, _) = codes.iter().rev().max_by_key(|(_, &n)| n).unwrap();
+            (id, code)
+        })
+        .collect())
+}
+
+/// The WEPP parameters of a channel, written as one row of the output table.
+struct ChannelParameters {
+    chn_order: i64,
+    topaz_id: i64,
+    order: i64,
+    landuse: Option<i64>,
+    length_m: f64,
+    area_km2: f64,
+    derived_slope: f64,
+    slope: f64,
+    width_m: f64,
+    manning_n: f64,
+    n_source: &'static str,
+    low_slope: bool,
+}
+
+fn write_table(
+    channels: &[ChannelParameters],
+    with_landuse: bool,
+    file_path: &str,
+) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    write!(&mut file, "chn_order\ttopaz_id\torder")?;
+    if with_landuse {
+        write!(&mut file, "\tlanduse")?;
+    }
+    writeln!(
+        &mut file,
+        "\tlength_m\tarea_km2\tderived_slope\tslope\twidth_m\tmanning_n\tn_source\tlow_slope"
+    )?;
+    for c in channels {
+        write!(&mut file, "{}\t{}\t{}", c.chn_order, c.topaz_id, c.order)?;
+        if with_landuse {
+            match c.landuse {
+                Some(code) => write!(&mut file, "\t{}", code)?,
+                None => write!(&mut file, "\t")?,
+            }
+        }
+        writeln!(
+            &mut file,
+            "\t{:.3}\t{:.6}\t{:.6}\t{:.6}\t{:.3}\t{:.4}\t{}\t{}",
+            c.length_m,
+            c.area_km2,
+            c.derived_slope,
+            c.slope,
+            c.width_m,
+            c.manning_n,
+            c.n_source,
+            c.low_slope
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_coefficients, WeppChannelParameterTable};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+
+    // A worked example of three links, listed in walk order as HillslopesTopaz writes them: the
+    // outlet link 44 (order 2) is fed by the headwater links 24 and 34 (order 1). Link 34 has a
+    // slope of 0.0002, below the default minimum.
+    const NETW: &str = "id\ttopaz_id\tds_x\tds_y\tus_x\tus_y\tinflow0_id\tinflow1_id\tinflow2_id\tlength_m\tds_z\tus_z\tdrop_m\torder\tareaup\tis_headwater\tis_outlet\tchn_order
+0\t44\t0\t3\t0\t2\t1\t2\t-1\t200.000\t100.000\t101.000\t1.000\t2\t500000.000\tfalse\ttrue\t3
+1\t24\t0\t1\t0\t0\t-1\t-1\t-1\t100.000\t101.000\t103.000\t2.000\t1\t200000.000\ttrue\tfalse\t1
+2\t34\t0\t2\t0\t2\t-1\t-1\t-1\t50.000\t101.000\t101.010\t0.010\t1\t300000.000\ttrue\tfalse\t2
+";
+
+    const COEFFICIENTS: &str = "parameter\tkey\tvalue
+# width = 2 * area^0.5
+width_coeff\t\t2
+width_exp\t\t0.5
+manning_order\t1\t0.05
+manning_landuse\t82\t0.1
+manning_default\t\t0.04
+";
+
+    // A 2 x 2 subwta raster with one cell each for channels 24 and 34 and two for channel 44, and
+    // a land use raster of 41, 82 / 82, 41, so that channel 44 ties between 41 and 82.
+    fn write_rasters(dir: &str) {
+        let write = |name: &str, values: [f64; 4]| {
+            let grid = GridFixture::new(2, 2, |r, c| values[(r * 2 + c) as usize]).categorical();
+            grid_fixture_raster(&format!("{}/{}", dir, name), &grid)
+                .write()
+                .unwrap();
+        };
+        write("subwta.tif", [24f64, 34f64, 44f64, 44f64]);
+        write("landuse.tif", [41f64, 82f64, 82f64, 41f64]);
+    }
+
+    fn run(dir: &str, extra: &[&str]) -> Result<Vec<Vec<String>>, String> {
+        let mut args = vec![
+            format!("--netw={}/netw.tsv", dir),
+            format!("--coefficients={}/coefficients.tsv", dir),
+            format!("--output={}/chn_params.tsv", dir),
+        ];
+        args.extend(extra.iter().map(|a| a.replace("{dir}", dir)));
+        WeppChannelParameterTable::new()
+            .run(args, "", false)
+            .map_err(|e| e.to_string())?;
+        Ok(fs::read_to_string(format!("{}/chn_params.tsv", dir))
+            .unwrap()
+            .lines()
+            .map(|l| l.split('\t').map(|s| s.to_string()).collect())
+            .collect())
+    }
+
+    #[test]
+    fn test_worked_example() {
+        let dir = temp_dir("wepp_channel_parameter_table").unwrap();
+        fs::write(format!("{}/netw.tsv", dir), NETW).unwrap();
+        fs::write(format!("{}/coefficients.tsv", dir), COEFFICIENTS).unwrap();
+        write_rasters(&dir);
+
+        let table = run(&dir, &[]).unwrap();
+        assert_eq!(
+            table[0].join("\t"),
+            "chn_order\ttopaz_id\torder\tlength_m\tarea_km2\tderived_slope\tslope\twidth_m\tmanning_n\tn_source\tlow_slope"
+        );
+        // Rows are in routing order; the outlet drains 0.2 + 0.3 + 0.5 km², so its width is
+        // 2 * 1^0.5 = 2 m, and its order has no Manning's n row.
+        assert_eq!(
+            table[1].join("\t"),
+            "1\t24\t1\t100.000\t0.200000\t0.020000\t0.020000\t0.894\t0.0500\torder\tfalse"
+        );
+        assert_eq!(
+            table[2].join("\t"),
+            "2\t34\t1\t50.000\t0.300000\t0.000200\t0.000200\t1.095\t0.0500\torder\ttrue"
+        );
+        assert_eq!(
+            table[3].join("\t"),
+            "3\t44\t2\t200.000\t1.000000\t0.005000\t0.005000\t2.000\t0.0400\tdefault\tfalse"
+        );
+
+        // Clamping only changes the slope of the flagged channel.
+        let table = run(&dir, &["--min_slope=0.001", "--clamp_slope"]).unwrap();
+        assert_eq!(table[2][5..8], ["0.000200", "0.001000", "1.095"]);
+        assert_eq!(table[3][5..7], ["0.005000", "0.005000"]);
+
+        // Channel 34 is on land use 82; channel 44 ties between 41 and 82 and takes 41, which has
+        // no Manning's n row.
+        let table = run(
+            &dir,
+            &["--subwta={dir}/subwta.tif", "--landuse={dir}/landuse.tif"],
+        )
+        .unwrap();
+        assert_eq!(table[0][3], "landuse");
+        assert_eq!(table[1][3..4], ["41"]);
+        assert_eq!(table[1][9..11], ["0.0500", "order"]);
+        assert_eq!(table[2][3], "82");
+        assert_eq!(table[2][9..11], ["0.1000", "landuse"]);
+        assert_eq!(table[3][3], "41");
+        assert_eq!(table[3][9..11], ["0.0400", "default"]);
+
+        let err = run(&dir, &["--landuse={dir}/landuse.tif"]).unwrap_err();
+        assert!(err.contains("--subwta"), "{}", err);
+
+        // Without a default, the outlet has no Manning's n.
+        fs::write(
+            format!("{}/coefficients.tsv", dir),
+            COEFFICIENTS.replace("manning_default\t\t0.04\n", ""),
+        )
+        .unwrap();
+        let err = run(&dir, &[]).unwrap_err();
+        assert!(
+            err.contains("No Manning's n for channel 44 (order 2)"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_coefficient_errors_report_line_numbers() {
+        let parse = |text: &str| parse_coefficients("c.tsv", text).unwrap_err().to_string();
+        let base = "parameter\tkey\tvalue\nwidth_coeff\t\t2\nwidth_exp\t\t0.5\n";
+
+        let err = parse(&format!("{}manning_order\t1\tabc\n", base));
+        assert!(
+            err.starts_with("Coefficients file 'c.tsv', line 4: invalid value 'abc'"),
+            "{}",
+            err
+        );
+        let err = parse(&format!("{}manning_ordr\t1\t0.05\n", base));
+        assert!(
+            err.contains("line 4: unknown parameter 'manning_ordr'"),
+            "{}",
+            err
+        );
+        let err = parse(&format!(
+            "{}\n# comment\nmanning_order\t1\t0.05\nmanning_order\t1\t0.06\n",
+            base
+        ));
+        assert!(
+            err.contains("line 7: manning_order 1 is repeated"),
+            "{}",
+            err
+        );
+        let err = parse(&format!("{}manning_order\t\t0.05\n", base));
+        assert!(
+            err.contains("line 4: manning_order requires an integer key"),
+            "{}",
+            err
+        );
+        let err = parse(&format!("{}manning_landuse\tforest\t0.05\n", base));
+        assert!(
+            err.contains("line 4: invalid manning_landuse key 'forest'"),
+            "{}",
+            err
+        );
+        let err = parse(&format!("{}manning_default\t\t-0.04\n", base));
+        assert!(
+            err.contains("line 4: manning_default must be positive"),
+            "{}",
+            err
+        );
+        let err = parse(&format!("{}manning_default\t0.04\n", base));
+        assert!(
+            err.contains("line 4: expected 3 tab-separated fields, found 2"),
+            "{}",
+            err
+        );
+        let err = parse("parameter\tvalue\n");
+        assert!(
+            err.contains("line 1: the header has no 'key' column"),
+            "{}",
+            err
+        );
+        let err = parse("parameter\tkey\tvalue\nwidth_coeff\t\t2\n");
+        assert!(err.contains("has no width_exp row"), "{}", err);
+
+        let coefficients = parse_coefficients(
+            "c.tsv",
+            &format!(
+                "{}manning_order\t2\t0.045\nmanning_landuse\t42\t0.08\n",
+                base
+            ),
+        )
+        .unwrap();
+        assert_eq!(
+            (coefficients.width_coeff, coefficients.width_exp),
+            (2f64, 0.5f64)
+        );
+        assert_eq!(coefficients.manning_order.get(&2), Some(&0.045));
+        assert_eq!(coefficients.manning_landuse.get(&42), Some(&0.08));
+        assert_eq!(coefficients.manning_default, None);
+    }
+}
@@ -51,6 +51,7 @@ mod percentage_contrast_stretch;
 mod percentile_filter;
 mod prewitt_filter;
 mod range_filter;
+mod raster_histogram_equalize;
 mod remove_spurs;
 mod resample;
 mod rgb_to_ihs;
@@ -121,6 +122,7 @@ pub use self::percentage_contrast_stretch::PercentageContrastStretch;
 pub use self::percentile_filter::PercentileFilter;
 pub use self::prewitt_filter::PrewittFilter;
 pub use self::range_filter::RangeFilter;
+pub use self::raster_histogram_equalize::RasterHistogramEqualize;
 pub use self::remove_spurs::RemoveSpurs;
 pub use self::resample::Resample;
 pub use self::rgb_to_ihs::RgbToIhs;
@@ -0,0 +1,664 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use std::time::Instant;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool converts a raster, such as a DEM or flow accumulation grid, into an 8-bit raster for
+/// use in report figures and other visualizations. Two modes are available (`--mode`):
+///
+/// | Mode | Mapping |
+/// |------|---------|
+/// | `percentile_stretch` | Linear stretch between the `--low_percentile` and `--high_percentile` values of the input, which are saturated at the ends of the output range |
+/// | `hist_equalize` | Histogram equalization, which maps each value by its cumulative frequency so that the output tones are used about equally often |
+///
+/// Percentiles are found by linear interpolation between the closest ranks of the sorted input values.
+/// When a `--mask` raster is supplied, the percentiles and the frequency distribution are calculated
+/// only from the cells with positive mask values, e.g. those within a basin, so that the surrounding
+/// landscape does not dilute the contrast. Cells outside of the mask are still mapped, using the
+/// statistics of the masked cells.
+///
+/// Valid cells are mapped onto the values 1 to 255 of the output raster, while NoData cells in the
+/// input remain NoData, which is written as 0. The mapping is also written to a TSV file
+/// (`--breakpoints`, by default the output file name with a `.tsv` extension), which lists, for each
+/// output value, the range of input values mapped onto it, the number of cells used in the statistics
+/// with those values, and the cumulative percentage of those cells.
+///
+/// # See Also
+/// `PercentageContrastStretch`, `HistogramEqualization`, `MinMaxContrastStretch`
+pub struct RasterHistogramEqualize {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl RasterHistogramEqualize {
+    pub fn new() -> RasterHistogramEqualize {
+        // public constructor
+        let name = "RasterHistogramEqualize".to_string();
+        let toolbox = "Image Processing Tools/Image Enhancement".to_string();
+        let description =
+            "Creates an 8-bit percentile-stretched or histogram-equalized raster for visualization."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Mode".to_owned(),
+            flags: vec!["--mode".to_owned()],
+            description: "Mapping mode; one of 'percentile_stretch' and 'hist_equalize'."
+                .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "percentile_stretch".to_owned(),
+                "hist_equalize".to_owned(),
+            ]),
+            default_value: Some("percentile_stretch".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Low Percentile".to_owned(),
+            flags: vec!["--low_percentile".to_owned()],
+            description: "Percentile mapped onto the lowest output value (percentile_stretch)."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("2.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "High Percentile".to_owned(),
+            flags: vec!["--high_percentile".to_owned()],
+            description: "Percentile mapped onto the highest output value (percentile_stretch)."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("98.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description:
+                "Optional mask raster; statistics are calculated from cells with positive values."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output 8-bit raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Breakpoints File".to_owned(),
+            flags: vec!["--breakpoints".to_owned()],
+            description: "Optional output TSV file of the mapping; defaults to the output file with a .tsv extension."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif --mode=percentile_stretch --low_percentile=2.0 --high_percentile=98.0 -o=dem_8bit.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=flow_accum.tif --mode=hist_equalize --mask=watershed.tif -o=accum_8bit.tif --breakpoints=accum_8bit.tsv", short_exe, name).replace("*", &sep);
+
+        RasterHistogramEqualize {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for RasterHistogramEqualize {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut mode = String::from("percentile_stretch");
+        let mut low_percentile = 2f64;
+        let mut high_percentile = 98f64;
+        let mut mask_file = String::new();
+        let mut output_file = String::new();
+        let mut breakpoints_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-mode" {
+                mode = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-low_percentile" || flag_val == "-high_percentile" {
+                let value = if keyval { vec[1] } else { args[i + 1].as_str() };
+                let p = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Unable to parse {} value '{}'.", vec[0], value),
+                    )
+                })?;
+                if flag_val == "-low_percentile" {
+                    low_percentile = p;
+                } else {
+                    high_percentile = p;
+                }
+            } else if flag_val == "-mask" {
+                mask_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-breakpoints" {
+                breakpoints_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let equalize = match mode.trim() {
+            "percentile_stretch" => false,
+            "hist_equalize" => true,
+            _ => {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Unrecognized --mode '{}'; use 'percentile_stretch' or 'hist_equalize'.",
+                        mode
+                    ),
+                ))
+            }
+        };
+        if !equalize
+            && (!(0f64..=100f64).contains(&low_percentile)
+                || !(0f64..=100f64).contains(&high_percentile)
+                || low_percentile >= high_percentile)
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The percentiles must satisfy 0 <= --low_percentile < --high_percentile <= 100.",
+            ));
+        }
+
+        input_file = resolve_path(working_directory, &input_file);
+        output_file = resolve_path(working_directory, &output_file);
+        breakpoints_file = if breakpoints_file.trim().is_empty() {
+            path::Path::new(&output_file)
+                .with_extension("tsv")
+                .to_string_lossy()
+                .to_string()
+        } else {
+            resolve_path(working_directory, &breakpoints_file)
+        };
+
+        if verbose {
+            println!("Reading data...")
+        };
+
+        let input = Raster::new(&input_file, "r")?;
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+
+        let mask = if mask_file.trim().is_empty() {
+            None
+        } else {
+            let mask = Raster::new(&resolve_path(working_directory, &mask_file), "r")?;
+            if mask.configs.rows != input.configs.rows
+                || mask.configs.columns != input.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The mask raster must have the same rows and columns as the input raster.",
+                ));
+            }
+            Some(mask)
+        };
+
+        let start = Instant::now();
+
+        let mut values: Vec<f64> = vec![];
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = input.get_value(row, col);
+                if z == nodata || z.is_nan() {
+                    continue;
+                }
+                if let Some(mask) = &mask {
+                    let m = mask.get_value(row, col);
+                    if m == mask.configs.nodata || m <= 0f64 {
+                        continue;
+                    }
+                }
+                values.push(z);
+            }
+        }
+        if values.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The input raster has no valid cells from which to calculate statistics.",
+            ));
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+
+        let mapping = if equalize {
+            ToneMapping::Equalize {
+                sorted: &values,
+                min_frequency: cumulative_frequency(&values, values[0]),
+            }
+        } else {
+            ToneMapping::Stretch {
+                low: percentile(&values, low_percentile),
+                high: percentile(&values, high_percentile),
+            }
+        };
+        if verbose {
+            if let ToneMapping::Stretch { low, high } = mapping {
+                println!("Low cut value: {}", low);
+                println!("High cut value: {}", high);
+            }
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        output.configs.data_type = DataType::U8;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "grey.plt".to_string();
+        output.configs.nodata = 0f64;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = input.get_value(row, col);
+                if z == nodata || z.is_nan() {
+                    output.set_value(row, col, 0f64);
+                } else {
+                    output.set_value(row, col, mapping.map(z) as f64);
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if let Err(e) = write_breakpoints(&values, &mapping, &breakpoints_file) {
+            return Err(Error::new(
+                e.kind(),
+                format!(
+                    "Unable to write breakpoints file {}: {}",
+                    breakpoints_file, e
+                ),
+            ));
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        output.add_metadata_entry(format!("Mode: {}", mode.trim()));
+        if let ToneMapping::Stretch { low, high } = mapping {
+            output.add_metadata_entry(format!(
+                "Low cut value ({} percentile): {}",
+                low_percentile, low
+            ));
+            output.add_metadata_entry(format!(
+                "High cut value ({} percentile): {}",
+                high_percentile, high
+            ));
+        }
+        if !mask_file.trim().is_empty() {
+            output.add_metadata_entry(format!("Mask file: {}", mask_file));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// The mapping of input values onto the output tones, 1 to 255.
+enum ToneMapping<'a> {
+    Stretch {
+        low: f64,
+        high: f64,
+    },
+    Equalize {
+        sorted: &'a [f64],
+        min_frequency: f64,
+    },
+}
+
+impl ToneMapping<'_> {
+    fn map(&self, z: f64) -> u8 {
+        let t = match *self {
+            ToneMapping::Stretch { low, high } => {
+                if high > low {
+                    (z - low) / (high - low)
+                } else if z > low {
+                    1f64
+                } else {
+                    0f64
+                }
+            }
+            ToneMapping::Equalize {
+                sorted,
+                min_frequency,
+            } => {
+                if min_frequency < 1f64 {
+                    (cumulative_frequency(sorted, z) - min_frequency) / (1f64 - min_frequency)
+                } else {
+                    0f64 // a single distinct value
+                }
+            }
+        };
+        1 + (t.clamp(0f64, 1f64) * 254f64).round() as u8
+    }
+}
+
+/// Returns the `p`th percentile of sorted values, interpolating linearly between the closest ranks.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let position = (p / 100f64) * (sorted.len() - 1) as f64;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (position - lower as f64)
+}
+
+/// Returns the fraction of the sorted values that are less than or equal to `z`.
+fn cumulative_frequency(sorted: &[f64], z: f64) -> f64 {
+    sorted.partition_point(|&v| v <= z) as f64 / sorted.len() as f64
+}
+
+fn write_breakpoints(sorted: &[f64], mapping: &ToneMapping, file_path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "output_value\tinput_min\tinput_max\tnum_cells\tcumulative_percent"
+    )?;
+    let n = sorted.len();
+    let mut i = 0;
+    while i < n {
+        // The mapping is monotonic, so the values of each output tone are contiguous.
+        let tone = mapping.map(sorted[i]);
+        let mut j = i + 1;
+        while j < n && mapping.map(sorted[j]) == tone {
+            j += 1;
+        }
+        writeln!(
+            &mut file,
+            "{}\t{}\t{}\t{}\t{:.4}",
+            tone,
+            sorted[i],
+            sorted[j - 1],
+            j - i,
+            100f64 * j as f64 / n as f64
+        )?;
+        i = j;
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{percentile, RasterHistogramEqualize};
+    use crate::tools::WhiteboxTool;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    fn write_raster(file: &str, rows: usize, columns: usize, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(rows, columns, f).with_resolution(1f64);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    #[test]
+    fn test_percentile_of_known_distribution() {
+        let values: Vec<f64> = (0..=1000).map(|v| v as f64).collect();
+        assert_eq!(percentile(&values, 2f64), 20f64);
+        assert_eq!(percentile(&values, 98f64), 980f64);
+        assert_eq!(percentile(&values, 0f64), 0f64);
+        assert_eq!(percentile(&values, 100f64), 1000f64);
+        // Values between ranks are interpolated.
+        let values = [1f64, 2f64, 3f64, 4f64];
+        assert!((percentile(&values, 50f64) - 2.5).abs() < 1e-12);
+        assert!((percentile(&values, 10f64) - 1.3).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_percentile_stretch() {
+        let dir = temp_dir("raster_histogram_equalize_stretch").unwrap();
+        // The values 0 to 1000, with a NoData cell in place of 1000.
+        write_raster(&format!("{}/input.tif", dir), 11, 91, &|r, c| {
+            let v = (r * 91 + c) as f64;
+            if v == 1000f64 {
+                -32768f64
+            } else {
+                v
+            }
+        });
+        let args = vec![
+            format!("--input={}/input.tif", dir),
+            format!("--output={}/output.tif", dir),
+            "--low_percentile=2".to_string(),
+            "--high_percentile=98".to_string(),
+        ];
+        RasterHistogramEqualize::new().run(args, "", false).unwrap();
+        let output = Raster::new(&format!("{}/output.tif", dir), "r").unwrap();
+        // The cut values of 0..=999 are 19.98 and 979.02.
+        let expected = |v: f64| {
+            1f64 + ((v - 19.98) / (979.02 - 19.98) * 254f64)
+                .clamp(0f64, 254f64)
+                .round()
+        };
+        for v in [0, 19, 20, 21, 500, 978, 979, 999] {
+            let (r, c) = (v / 91, v % 91);
+            assert_eq!(output.get_value(r, c), expected(v as f64), "value {}", v);
+        }
+        assert_eq!(output.get_value(0, 0), 1f64);
+        assert_eq!(output.get_value(10, 90), 0f64);
+        assert_eq!(output.configs.nodata, 0f64);
+
+        let table = read_tsv(&format!("{}/output.tsv", dir))
+            .unwrap()
+            .split_off(1);
+        assert_eq!(table[0][0], "1");
+        assert_eq!(table[0][1], "0");
+        assert_eq!(table[0][2], "21");
+        assert_eq!(table.last().unwrap()[0], "255");
+        assert_eq!(table.last().unwrap()[4], "100.0000");
+        let total: usize = table.iter().map(|r| r[3].parse::<usize>().unwrap()).sum();
+        assert_eq!(total, 1000);
+    }
+
+    #[test]
+    fn test_hist_equalize_of_uniform_values_is_identity() {
+        let dir = temp_dir("raster_histogram_equalize_uniform").unwrap();
+        write_raster(&format!("{}/input.tif", dir), 15, 17, &|r, c| {
+            (r * 17 + c + 1) as f64
+        });
+        let args = vec![
+            format!("--input={}/input.tif", dir),
+            "--mode=hist_equalize".to_string(),
+            format!("--output={}/output.tif", dir),
+            format!("--breakpoints={}/mapping.tsv", dir),
+        ];
+        RasterHistogramEqualize::new().run(args, "", false).unwrap();
+        let output = Raster::new(&format!("{}/output.tif", dir), "r").unwrap();
+        for r in 0..15 {
+            for c in 0..17 {
+                assert_eq!(output.get_value(r, c), (r * 17 + c + 1) as f64);
+            }
+        }
+        // a header and 255 rows
+        assert_eq!(
+            read_tsv(&format!("{}/mapping.tsv", dir)).unwrap().len(),
+            256
+        );
+    }
+
+    #[test]
+    fn test_masked_statistics_ignore_outside_cells() {
+        let dir = temp_dir("raster_histogram_equalize_mask").unwrap();
+        // The masked left half holds the values 0 to 100, and the right half very large values.
+        write_raster(&format!("{}/input.tif", dir), 101, 2, &|r, c| {
+            if c == 0 {
+                r as f64
+            } else {
+                1e6
+            }
+        });
+        write_raster(&format!("{}/mask.tif", dir), 101, 2, &|_, c| {
+            if c == 0 {
+                1f64
+            } else {
+                0f64
+            }
+        });
+        let args = vec![
+            format!("--input={}/input.tif", dir),
+            format!("--mask={}/mask.tif", dir),
+            format!("--output={}/output.tif", dir),
+            "--low_percentile=10".to_string(),
+            "--high_percentile=90".to_string(),
+        ];
+        RasterHistogramEqualize::new().run(args, "", false).unwrap();
+        let output = Raster::new(&format!("{}/output.tif", dir), "r").unwrap();
+        // The cut values are 10 and 90, from the masked cells alone.
+        assert_eq!(output.get_value(10, 0), 1f64);
+        assert_eq!(output.get_value(50, 0), 128f64);
+        assert_eq!(output.get_value(90, 0), 255f64);
+        // Cells outside of the mask are still mapped, using the masked statistics.
+        assert_eq!(output.get_value(0, 1), 255f64);
+        let table = read_tsv(&format!("{}/output.tsv", dir))
+            .unwrap()
+            .split_off(1);
+        let total: usize = table.iter().map(|r| r[3].parse::<usize>().unwrap()).sum();
+        assert_eq!(total, 101);
+        assert_eq!(table.last().unwrap()[2], "100");
+    }
+}
@@ -73,6 +73,7 @@ mod square;
 mod subtract;
 mod tan;
 mod tanh;
+mod temporal_raster_difference;
 mod to_degrees;
 mod to_radians;
 mod trend_surface;
@@ -160,6 +161,7 @@ pub use self::square::Square;
 pub use self::subtract::Subtract;
 pub use self::tan::Tan;
 pub use self::tanh::Tanh;
+pub use self::temporal_raster_difference::TemporalRasterDifference;
 pub use self::to_degrees::ToDegrees;
 pub use self::to_radians::ToRadians;
 pub use self::trend_surface::TrendSurface;
@@ -0,0 +1,773 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool measures the change between two co-registered rasters of the same variable captured at
+/// different times, e.g. pre- and post-fire canopy cover. The earlier raster is `--input1` and the later
+/// raster is `--input2`; both (and the optional `--mask`) must have the same number of rows and columns
+/// and the same spatial extent. The output raster (`--output`) contains, depending on `--mode`:
+///
+/// - `difference` (default): `input2 - input1`
+/// - `ratio`: `input2 / input1`
+/// - `percent_change`: `100 * (input2 - input1) / input1`
+///
+/// A cell is assigned NoData in the output if it is NoData in either input or if it lies outside the
+/// watershed mask (mask cells that are NoData or less than or equal to zero). In the `ratio` and
+/// `percent_change` modes, cells where `input1` is zero have no defined change; they are also assigned
+/// NoData and are counted separately as undefined cells in the statistics.
+///
+/// Summary statistics of the output values are always printed: the number of valid, NoData, and undefined
+/// cells, the mean, minimum, maximum, and standard deviation of the change, and the percentage of valid
+/// cells that increased, decreased, or were unchanged. A cell is unchanged when its value is within
+/// `--tolerance` of no change (0 for `difference` and `percent_change`, 1 for `ratio`), in the units of
+/// the output. The statistics may also be written to a tab-separated file (`--summary`), and a histogram
+/// of the output values with `--num_bins` equal-width bins may be written to another (`--histogram`).
+///
+/// # See Also
+/// `Subtract`, `Divide`, `RasterSummaryStats`, `RasterHistogram`
+pub struct TemporalRasterDifference {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl TemporalRasterDifference {
+    /// public constructor
+    pub fn new() -> TemporalRasterDifference {
+        let name = "TemporalRasterDifference".to_string();
+        let toolbox = "Math and Stats Tools".to_string();
+        let description =
+            "Calculates the change between two rasters from different times, with nodata-aware change statistics."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Earlier Input File".to_owned(),
+            flags: vec!["--input1".to_owned()],
+            description: "Input raster file from the earlier time.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Later Input File".to_owned(),
+            flags: vec!["--input2".to_owned()],
+            description: "Input raster file from the later time.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Change Mode".to_owned(),
+            flags: vec!["--mode".to_owned()],
+            description: "Change measure: 'difference' (input2 - input1), 'ratio' (input2 / input1), or 'percent_change'.".to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "difference".to_owned(),
+                "ratio".to_owned(),
+                "percent_change".to_owned(),
+            ]),
+            default_value: Some("difference".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File (optional)".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description: "Optional mask raster (e.g. a watershed); only cells with positive mask values are compared.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Unchanged Tolerance".to_owned(),
+            flags: vec!["--tolerance".to_owned()],
+            description: "Maximum departure from no change, in output units, for a cell to count as unchanged.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: Some("0.0".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Summary File (optional)".to_owned(),
+            flags: vec!["--summary".to_owned()],
+            description: "Optional output tab-separated file of change statistics.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Histogram File (optional)".to_owned(),
+            flags: vec!["--histogram".to_owned()],
+            description: "Optional output tab-separated histogram of the output values.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Number of Histogram Bins".to_owned(),
+            flags: vec!["--num_bins".to_owned()],
+            description: "Number of equal-width histogram bins.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("50".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --input1=canopy_pre.tif --input2=canopy_post.tif -o=change.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --input1=canopy_pre.tif --input2=canopy_post.tif -o=change.tif --mode=percent_change --mask=watershed.tif --tolerance=1.0 --summary=change.tsv --histogram=change_hist.tsv --num_bins=20", short_exe, name).replace("*", &sep);
+
+        TemporalRasterDifference {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for TemporalRasterDifference {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input1_file = String::new();
+        let mut input2_file = String::new();
+        let mut output_file = String::new();
+        let mut mode_str = String::from("difference");
+        let mut mask_file = String::new();
+        let mut tolerance = 0f64;
+        let mut summary_file = String::new();
+        let mut histogram_file = String::new();
+        let mut num_bins = 50usize;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-input1" {
+                input1_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-input2" {
+                input2_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-mode" {
+                mode_str = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+            } else if flag_val == "-mask" {
+                mask_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-tolerance" {
+                tolerance = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-summary" {
+                summary_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-histogram" {
+                histogram_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-num_bins" {
+                num_bins = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val)) as usize
+                };
+            }
+        }
+
+        let mode = ChangeMode::from_str(&mode_str)?;
+        if tolerance < 0f64 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --tolerance value must not be negative.",
+            ));
+        }
+        if num_bins == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The --num_bins value must be at least 1.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        for file in [
+            &mut input1_file,
+            &mut input2_file,
+            &mut output_file,
+            &mut mask_file,
+            &mut summary_file,
+            &mut histogram_file,
+        ] {
+            *file = resolve_path(working_directory, file);
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input1 = Raster::new(&input1_file, "r")?;
+        let input2 = Raster::new(&input2_file, "r")?;
+        let mask = if !mask_file.is_empty() {
+            Some(Raster::new(&mask_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        if !same_geometry(&input1, &input2)
+            || mask.as_ref().map_or(false, |m| !same_geometry(&input1, m))
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let rows = input1.configs.rows as isize;
+        let columns = input1.configs.columns as isize;
+        let nodata1 = input1.configs.nodata;
+        let nodata2 = input2.configs.nodata;
+        let out_nodata = -32768f64;
+
+        let mut output = Raster::initialize_using_file(&output_file, &input1);
+        output.configs.data_type = DataType::F32;
+        output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+        output.configs.palette = "blue_white_red.plt".to_string();
+        output.configs.nodata = out_nodata;
+        output.reinitialize_values(out_nodata);
+
+        let mut stats = ChangeStats::new(mode, tolerance);
+        for row in 0..rows {
+            for col in 0..columns {
+                if let Some(ref m) = mask {
+                    let zm = m.get_value(row, col);
+                    if zm == m.configs.nodata || zm <= 0f64 {
+                        stats.num_masked += 1;
+                        continue;
+                    }
+                }
+                let z1 = input1.get_value(row, col);
+                let z2 = input2.get_value(row, col);
+                if z1 == nodata1 || z2 == nodata2 {
+                    stats.num_nodata += 1;
+                    continue;
+                }
+                match mode.change(z1, z2) {
+                    Some(z) => {
+                        output.set_value(row, col, z);
+                        stats.add(z);
+                    }
+                    None => stats.num_undefined += 1,
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * row as f64 / (rows - 1).max(1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        // The histogram needs the value range, so it takes a second pass over the output.
+        let mut histogram = Histogram::new(stats.min, stats.max, num_bins);
+        if !histogram_file.is_empty() && stats.num_valid > 0 {
+            for row in 0..rows {
+                for col in 0..columns {
+                    let z = output.get_value(row, col);
+                    if z != out_nodata {
+                        histogram.add(z);
+                    }
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        for (statistic, value) in stats.summary() {
+            println!("{}: {}", statistic, value);
+        }
+        if stats.num_valid == 0 {
+            println!("Warning: no cells had valid values in both inputs; the output contains only NoData.");
+        }
+
+        if !summary_file.is_empty() {
+            let mut file = File::create(&summary_file)?;
+            writeln!(&mut file, "statistic\tvalue")?;
+            for (statistic, value) in stats.summary() {
+                writeln!(&mut file, "{}\t{}", statistic, value)?;
+            }
+            if verbose {
+                println!("Summary written to {}", summary_file);
+            }
+        }
+
+        if !histogram_file.is_empty() {
+            histogram.write(&histogram_file)?;
+            if verbose {
+                println!("Histogram written to {}", histogram_file);
+            }
+        }
+
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input1 file: {}", input1_file));
+        output.add_metadata_entry(format!("Input2 file: {}", input2_file));
+        output.add_metadata_entry(format!("Mode: {}", mode.name()));
+        if !mask_file.is_empty() {
+            output.add_metadata_entry(format!("Mask file: {}", mask_file));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum ChangeMode {
+    Difference,
+    Ratio,
+    PercentChange,
+}
+
+impl ChangeMode {
+    fn from_str(s: &str) -> Result<ChangeMode, Error> {
+        match s.trim() {
+            "difference" | "diff" => Ok(ChangeMode::Difference),
+            "ratio" => Ok(ChangeMode::Ratio),
+            "percent_change" | "percent" => Ok(ChangeMode::PercentChange),
+            _ => Err(Error::new(
+                ErrorKind::InvalidInput,
+                format!(
+                    "Unrecognized --mode '{}'; expected 'difference', 'ratio', or 'percent_change'.",
+                    s
+                ),
+            )),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ChangeMode::Difference => "difference",
+            ChangeMode::Ratio => "ratio",
+            ChangeMode::PercentChange => "percent_change",
+        }
+    }
+
+    /// The output value representing no change.
+    fn no_change(&self) -> f64 {
+        match self {
+            ChangeMode::Ratio => 1f64,
+            _ => 0f64,
+        }
+    }
+
+    /// Returns the change from `z1` to `z2`, or `None` if it is undefined (division by zero).
+    fn change(&self, z1: f64, z2: f64) -> Option<f64> {
+        match self {
+            ChangeMode::Difference => Some(z2 - z1),
+            ChangeMode::Ratio if z1 != 0f64 => Some(z2 / z1),
+            ChangeMode::PercentChange if z1 != 0f64 => Some(100f64 * (z2 - z1) / z1),
+            _ => None,
+        }
+    }
+}
+
+struct ChangeStats {
+    mode: ChangeMode,
+    tolerance: f64,
+    num_valid: usize,
+    num_nodata: usize,
+    num_masked: usize,
+    num_undefined: usize,
+    num_increased: usize,
+    num_decreased: usize,
+    sum: f64,
+    sq_sum: f64,
+    min: f64,
+    max: f64,
+}
+
+impl ChangeStats {
+    fn new(mode: ChangeMode, tolerance: f64) -> ChangeStats {
+        ChangeStats {
+            mode,
+            tolerance,
+            num_valid: 0,
+            num_nodata: 0,
+            num_masked: 0,
+            num_undefined: 0,
+            num_increased: 0,
+            num_decreased: 0,
+            sum: 0f64,
+            sq_sum: 0f64,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+
+    fn add(&mut self, z: f64) {
+        self.num_valid += 1;
+        self.sum += z;
+        self.sq_sum += z * z;
+        self.min = self.min.min(z);
+        self.max = self.max.max(z);
+        let departure = z - self.mode.no_change();
+        if departure > self.tolerance {
+            self.num_increased += 1;
+        } else if departure < -self.tolerance {
+            self.num_decreased += 1;
+        }
+    }
+
+    fn summary(&self) -> Vec<(&'static str, String)> {
+        let n = self.num_valid as f64;
+        let (mean, std_dev, min, max) = if self.num_valid > 0 {
+            let mean = self.sum / n;
+            let variance = (self.sq_sum / n - mean * mean).max(0f64);
+            (mean, variance.sqrt(), self.min, self.max)
+        } else {
+            (f64::NAN, f64::NAN, f64::NAN, f64::NAN)
+        };
+        let percent = |count: usize| {
+            if self.num_valid > 0 {
+                100f64 * count as f64 / n
+            } else {
+                f64::NAN
+            }
+        };
+        let num_unchanged = self.num_valid - self.num_increased - self.num_decreased;
+        vec![
+            ("mode", self.mode.name().to_string()),
+            ("num_valid_cells", self.num_valid.to_string()),
+            ("num_nodata_cells", self.num_nodata.to_string()),
+            ("num_masked_cells", self.num_masked.to_string()),
+            ("num_undefined_cells", self.num_undefined.to_string()),
+            ("mean_change", format!("{:.6}", mean)),
+            ("min_change", format!("{:.6}", min)),
+            ("max_change", format!("{:.6}", max)),
+            ("std_dev_change", format!("{:.6}", std_dev)),
+            ("tolerance", self.tolerance.to_string()),
+            (
+                "pct_increased",
+                format!("{:.3}", percent(self.num_increased)),
+            ),
+            (
+                "pct_decreased",
+                format!("{:.3}", percent(self.num_decreased)),
+            ),
+            ("pct_unchanged", format!("{:.3}", percent(num_unchanged))),
+        ]
+    }
+}
+
+struct Histogram {
+    min: f64,
+    bin_width: f64,
+    counts: Vec<usize>,
+}
+
+impl Histogram {
+    fn new(min: f64, max: f64, num_bins: usize) -> Histogram {
+        let range = if max > min { max - min } else { 0f64 };
+        Histogram {
+            min,
+            bin_width: range / num_bins as f64,
+            counts: vec![0; num_bins],
+        }
+    }
+
+    fn add(&mut self, z: f64) {
+        let bin = if self.bin_width > 0f64 {
+            (((z - self.min) / self.bin_width) as usize).min(self.counts.len() - 1)
+        } else {
+            0
+        };
+        self.counts[bin] += 1;
+    }
+
+    fn write(&self, file_path: &str) -> io::Result<()> {
+        let mut file = File::create(file_path)?;
+        writeln!(&mut file, "bin_min\tbin_max\tcount")?;
+        if !self.min.is_finite() {
+            return Ok(()); // no valid cells
+        }
+        for (i, count) in self.counts.iter().enumerate() {
+            writeln!(
+                &mut file,
+                "{}\t{}\t{}",
+                self.min + i as f64 * self.bin_width,
+                self.min + (i + 1) as f64 * self.bin_width,
+                count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn same_geometry(a: &Raster, b: &Raster) -> bool {
+    a.configs.rows == b.configs.rows
+        && a.configs.columns == b.configs.columns
+        && a.configs.north == b.configs.north
+        && a.configs.south == b.configs.south
+        && a.configs.east == b.configs.east
+        && a.configs.west == b.configs.west
+}
+
+#[cfg(test)]
+mod test {
+    use super::TemporalRasterDifference;
+    use crate::tools::WhiteboxTool;
+    use std::collections::HashMap;
+    use std::fs;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    const NODATA: f64 = -9999f64;
+
+    // Writes a 2 x 3 raster of 10 m cells holding `values` in row-major order.
+    fn write_raster(file: &str, values: &[f64]) {
+        let grid = GridFixture::new(2, 3, |r, c| values[(r * 3 + c) as usize]).with_nodata(NODATA);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    fn run(dir: &str, mode: &str, extra: &[String]) -> (Raster, HashMap<String, String>) {
+        let mut args = vec![
+            format!("--input1={}/pre.tif", dir),
+            format!("--input2={}/post.tif", dir),
+            format!("--output={}/change_{}.tif", dir, mode),
+            format!("--mode={}", mode),
+            format!("--summary={}/summary_{}.tsv", dir, mode),
+            format!("--histogram={}/hist_{}.tsv", dir, mode),
+            "--num_bins=4".to_string(),
+        ];
+        args.extend_from_slice(extra);
+        TemporalRasterDifference::new()
+            .run(args, "", false)
+            .unwrap();
+        let summary = read_tsv(&format!("{}/summary_{}.tsv", dir, mode))
+            .unwrap()
+            .into_iter()
+            .skip(1)
+            .map(|row| (row[0].clone(), row[1].clone()))
+            .collect();
+        let raster = Raster::new(&format!("{}/change_{}.tif", dir, mode), "r").unwrap();
+        (raster, summary)
+    }
+
+    #[test]
+    fn test_difference_with_mixed_nodata_and_mask() {
+        let dir = temp_dir("temporal_raster_difference_diff").unwrap();
+        write_raster(&format!("{}/pre.tif", dir), &[10., NODATA, 5., 4., 8., 2.]);
+        write_raster(&format!("{}/post.tif", dir), &[12., 3., NODATA, 4., 6., 9.]);
+        write_raster(&format!("{}/mask.tif", dir), &[1., 1., 1., 1., 1., 0.]);
+
+        let (out, summary) = run(&dir, "difference", &[]);
+        let nodata = out.configs.nodata;
+        assert_eq!(out.get_value(0, 0), 2f64);
+        assert_eq!(out.get_value(0, 1), nodata); // nodata in input1
+        assert_eq!(out.get_value(0, 2), nodata); // nodata in input2
+        assert_eq!(out.get_value(1, 1), -2f64);
+        assert_eq!(summary["num_valid_cells"], "4");
+        assert_eq!(summary["num_nodata_cells"], "2");
+        assert_eq!(summary["mean_change"], "1.750000");
+        assert_eq!(summary["pct_increased"], "50.000");
+        assert_eq!(summary["pct_decreased"], "25.000");
+        assert_eq!(summary["pct_unchanged"], "25.000");
+        let counts = read_tsv(&format!("{}/hist_difference.tsv", dir))
+            .unwrap()
+            .iter()
+            .skip(1)
+            .map(|row| row.last().unwrap().parse::<usize>().unwrap())
+            .collect::<Vec<usize>>();
+        assert_eq!(counts, vec![2, 1, 0, 1]);
+
+        let mask = [
+            format!("--mask={}/mask.tif", dir),
+            "--tolerance=2.0".to_string(),
+        ];
+        let (out, summary) = run(&dir, "difference", &mask);
+        assert_eq!(out.get_value(1, 2), out.configs.nodata);
+        assert_eq!(summary["num_valid_cells"], "3");
+        assert_eq!(summary["num_masked_cells"], "1");
+        assert_eq!(summary["pct_unchanged"], "100.000");
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_ratio_divide_by_zero() {
+        let dir = temp_dir("temporal_raster_difference_ratio").unwrap();
+        write_raster(&format!("{}/pre.tif", dir), &[0., 0., 4., 5., 2., NODATA]);
+        write_raster(&format!("{}/post.tif", dir), &[3., 0., 2., 5., 3., 1.]);
+
+        let (out, summary) = run(&dir, "ratio", &[]);
+        let nodata = out.configs.nodata;
+        assert_eq!(out.get_value(0, 0), nodata);
+        assert_eq!(out.get_value(0, 1), nodata);
+        assert_eq!(out.get_value(0, 2), 0.5f64);
+        assert_eq!(out.get_value(1, 0), 1f64);
+        assert_eq!(summary["num_undefined_cells"], "2");
+        assert_eq!(summary["num_nodata_cells"], "1");
+        assert_eq!(summary["num_valid_cells"], "3");
+        assert_eq!(summary["pct_unchanged"], "33.333");
+
+        let (out, summary) = run(&dir, "percent_change", &[]);
+        assert_eq!(out.get_value(0, 0), out.configs.nodata);
+        assert_eq!(out.get_value(1, 1), 50f64);
+        assert_eq!(summary["num_undefined_cells"], "2");
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -15,6 +15,7 @@ use std::io::{Error, ErrorKind};
 use std::path;
 use std::process::Command;
 use std::time::Instant;
+use whitebox_common::error::WbtError;
 use whitebox_common::utils::get_formatted_elapsed_time;
 // use std::io;
 // use std::path::PathBuf;
@@ -38,16 +39,20 @@ impl ToolManager {
         tool_names.push("ConvertNodataToZero".to_string());
         tool_names.push("ConvertRasterFormat".to_string());
         tool_names.push("CsvPointsToVector".to_string());
+        tool_names.push("EdgeBleedTrim".to_string());
         tool_names.push("ExportTableToCsv".to_string());
         tool_names.push("JoinTables".to_string());
         tool_names.push("LinesToPolygons".to_string());
         tool_names.push("MergeTableWithCsv".to_string());
         tool_names.push("MergeVectors".to_string());
         tool_names.push("ModifyNoDataValue".to_string());
+        tool_names.push("MultiBandCompositeFromSingles".to_string());
         tool_names.push("MultiPartToSinglePart".to_string());
         tool_names.push("NewRasterFromBase".to_string());
         tool_names.push("PolygonsToLines".to_string());
         tool_names.push("PrintGeoTiffTags".to_string());
+        tool_names.push("RasterFootprintVector".to_string());
+        tool_names.push("RasterizeVector".to_string());
         tool_names.push("RasterToVectorLines".to_string());
         tool_names.push("RasterToVectorPoints".to_string());
         tool_names.push("RasterToVectorPolygons".to_string());
@@ -55,6 +60,7 @@ impl ToolManager {
         tool_names.push("RemovePolygonHoles".to_string());
         tool_names.push("SetNodataValue".to_string());
         tool_names.push("SinglePartToMultiPart".to_string());
+        tool_names.push("SyntheticTestDataGenerator".to_string());
         tool_names.push("VectorLinesToRaster".to_string());
         tool_names.push("VectorPointsToRaster".to_string());
         tool_names.push("VectorPolygonsToRaster".to_string());
@@ -106,6 +112,8 @@ impl ToolManager {
         tool_names.push("LinearityIndex".to_string());
         tool_names.push("LineIntersections".to_string());
         tool_names.push("LowestPosition".to_string());
+        tool_names.push("MaskGrow".to_string());
+        tool_names.push("MaskShrink".to_string());
         tool_names.push("MaxAbsoluteOverlay".to_string());
         tool_names.push("MaxOverlay".to_string());
         tool_names.push("Medoid".to_string());
@@ -155,17 +163,23 @@ impl ToolManager {
         tool_names.push("WeightedSum".to_string());
 
         // hydro_analysis
+        tool_names.push("AccumulateAlongChannels".to_string());
         tool_names.push("AverageFlowpathSlope".to_string());
         tool_names.push("AverageUpslopeFlowpathLength".to_string());
+        tool_names.push("BasinOutletHydraulicGeometry".to_string());
         tool_names.push("Basins".to_string());
         tool_names.push("BreachDepressions".to_string());
         tool_names.push("BreachDepressionsLeastCost".to_string());
         tool_names.push("BreachSingleCellPits".to_string());
         tool_names.push("BurnStreamsAtRoads".to_string());
+        tool_names.push("CatchmentDelineationBatch".to_string());
+        tool_names.push("CellPathUpstreamTrace".to_string());
+        tool_names.push("ChannelJunctionAngles".to_string());
         tool_names.push("D8FlowAccumulation".to_string());
         tool_names.push("D8MassFlux".to_string());
         tool_names.push("D8Pointer".to_string());
         tool_names.push("DepthInSink".to_string());
+        tool_names.push("DetectParallelStreamArtifacts".to_string());
         tool_names.push("DInfFlowAccumulation".to_string());
         tool_names.push("DInfMassFlux".to_string());
         tool_names.push("DInfPointer".to_string());
@@ -187,16 +201,24 @@ impl ToolManager {
         tool_names.push("FloodOrder".to_string());
         tool_names.push("FlowAccumulationFullWorkflow".to_string());
         tool_names.push("FlowLengthDiff".to_string());
+        tool_names.push("FlowPathDensity".to_string());
         tool_names.push("Hillslopes".to_string());
+        tool_names.push("HillslopeRepresentativeProfilePlotData".to_string());
+        tool_names.push("HillslopeWidthFunction".to_string());
         tool_names.push("HillslopesTopaz".to_string());
+        tool_names.push("HydroEnforceCulverts".to_string());
+        tool_names.push("IdentifyFlatAreasAndPits".to_string());
         tool_names.push("ImpoundmentSizeIndex".to_string());
         tool_names.push("InsertDams".to_string());
         tool_names.push("Isobasins".to_string());
         tool_names.push("JensonSnapPourPoints".to_string());
+        tool_names.push("LabelHillslopeFacets".to_string());
         tool_names.push("LongestFlowpath".to_string());
         tool_names.push("MaxUpslopeFlowpathLength".to_string());
         tool_names.push("MDInfFlowAccumulation".to_string());
         tool_names.push("NumInflowingNeighbours".to_string());
+        tool_names.push("OutletSensitivityAnalysis".to_string());
+        tool_names.push("PourPointFromLargestStream".to_string());
         tool_names.push("RaiseWalls".to_string());
         tool_names.push("Rho8Pointer".to_string());
         tool_names.push("Sink".to_string());
@@ -204,10 +226,13 @@ impl ToolManager {
         tool_names.push("StochasticDepressionAnalysis".to_string());
         tool_names.push("StrahlerOrderBasins".to_string());
         tool_names.push("Subbasins".to_string());
+        tool_names.push("SubwtaToWeppManagementGrid".to_string());
         tool_names.push("TraceDownslopeFlowpaths".to_string());
         tool_names.push("UnnestBasins".to_string());
         tool_names.push("UpslopeDepressionStorage".to_string());
         tool_names.push("Watershed".to_string());
+        tool_names.push("WatershedAreaReport".to_string());
+        tool_names.push("WeppChannelParameterTable".to_string());
 
         // image_analysis
         tool_names.push("AdaptiveFilter".to_string());
@@ -262,6 +287,7 @@ impl ToolManager {
         tool_names.push("PercentileFilter".to_string());
         tool_names.push("PrewittFilter".to_string());
         tool_names.push("RangeFilter".to_string());
+        tool_names.push("RasterHistogramEqualize".to_string());
         tool_names.push("RemoveSpurs".to_string());
         tool_names.push("Resample".to_string());
         tool_names.push("RgbToIhs".to_string());
@@ -406,6 +432,7 @@ impl ToolManager {
         tool_names.push("Subtract".to_string());
         tool_names.push("Tan".to_string());
         tool_names.push("Tanh".to_string());
+        tool_names.push("TemporalRasterDifference".to_string());
         tool_names.push("ToDegrees".to_string());
         tool_names.push("ToRadians".to_string());
         tool_names.push("TrendSurface".to_string());
@@ -418,6 +445,7 @@ impl ToolManager {
         tool_names.push("ZScores".to_string());
 
         // stream_network_analysis
+        tool_names.push("ChannelCrossSectionExtractor".to_string());
         tool_names.push("DistanceToOutlet".to_string());
         tool_names.push("ExtractStreams".to_string());
         tool_names.push("ExtractValleys".to_string());
@@ -434,9 +462,11 @@ impl ToolManager {
         tool_names.push("ShreveStreamMagnitude".to_string());
         tool_names.push("StrahlerStreamOrder".to_string());
         tool_names.push("PruneStrahlerStreamOrder".to_string());
+        tool_names.push("StreamBufferZones".to_string());
         tool_names.push("StreamLinkClass".to_string());
         tool_names.push("StreamLinkIdentifier".to_string());
         tool_names.push("StreamLinkLength".to_string());
+        tool_names.push("StreamLinkLengthAndSinuosity".to_string());
         tool_names.push("StreamLinkSlope".to_string());
         tool_names.push("StreamSlopeContinuous".to_string());
         tool_names.push("TopologicalStreamOrder".to_string());
@@ -489,6 +519,7 @@ impl ToolManager {
         tool_names.push("MultiscaleStdDevNormalsSignature".to_string());
         tool_names.push("MultiscaleRoughnessSignature".to_string());
         tool_names.push("MultiscaleTopographicPositionImage".to_string());
+        tool_names.push("NoDataFill".to_string());
         tool_names.push("NumDownslopeNeighbours".to_string());
         tool_names.push("NumUpslopeNeighbours".to_string());
         tool_names.push("PennockLandformClass".to_string());
@@ -505,6 +536,7 @@ impl ToolManager {
         tool_names.push("TopographicHachures".to_string());
         tool_names.push("SedimentTransportIndex".to_string());
         tool_names.push("Slope".to_string());
+        tool_names.push("SlopeAspectCurvatureBundle".to_string());
         tool_names.push("SlopeVsElevationPlot".to_string());
         tool_names.push("SphericalStdDevOfNormals".to_string());
         tool_names.push("StandardDeviationOfSlope".to_string());
@@ -535,16 +567,22 @@ impl ToolManager {
             "convertnodatatozero" => Some(Box::new(data_tools::ConvertNodataToZero::new())),
             "convertrasterformat" => Some(Box::new(data_tools::ConvertRasterFormat::new())),
             "csvpointstovector" => Some(Box::new(data_tools::CsvPointsToVector::new())),
+            "edgebleedtrim" => Some(Box::new(data_tools::EdgeBleedTrim::new())),
             "exporttabletocsv" => Some(Box::new(data_tools::ExportTableToCsv::new())),
             "jointables" => Some(Box::new(data_tools::JoinTables::new())),
             "linestopolygons" => Some(Box::new(data_tools::LinesToPolygons::new())),
             "mergetablewithcsv" => Some(Box::new(data_tools::MergeTableWithCsv::new())),
             "mergevectors" => Some(Box::new(data_tools::MergeVectors::new())),
             "modifynodatavalue" => Some(Box::new(data_tools::ModifyNoDataValue::new())),
+            "multibandcompositefromsingles" => {
+                Some(Box::new(data_tools::MultiBandCompositeFromSingles::new()))
+            }
             "multiparttosinglepart" => Some(Box::new(data_tools::MultiPartToSinglePart::new())),
             "newrasterfrombase" => Some(Box::new(data_tools::NewRasterFromBase::new())),
             "polygonstolines" => Some(Box::new(data_tools::PolygonsToLines::new())),
             "printgeotifftags" => Some(Box::new(data_tools::PrintGeoTiffTags::new())),
+            "rasterfootprintvector" => Some(Box::new(data_tools::RasterFootprintVector::new())),
+            "rasterizevector" => Some(Box::new(data_tools::RasterizeVector::new())),
             "rastertovectorlines" => Some(Box::new(data_tools::RasterToVectorLines::new())),
             "rastertovectorpoints" => Some(Box::new(data_tools::RasterToVectorPoints::new())),
             "rastertovectorpolygons" => Some(Box::new(data_tools::RasterToVectorPolygons::new())),
@@ -554,6 +592,9 @@ impl ToolManager {
             "removepolygonholes" => Some(Box::new(data_tools::RemovePolygonHoles::new())),
             "setnodatavalue" => Some(Box::new(data_tools::SetNodataValue::new())),
             "singleparttomultipart" => Some(Box::new(data_tools::SinglePartToMultiPart::new())),
+            "synthetictestdatagenerator" => {
+                Some(Box::new(data_tools::SyntheticTestDataGenerator::new()))
+            }
             "vectorlinestoraster" => Some(Box::new(data_tools::VectorLinesToRaster::new())),
             "vectorpointstoraster" => Some(Box::new(data_tools::VectorPointsToRaster::new())),
             "vectorpolygonstoraster" => Some(Box::new(data_tools::VectorPolygonsToRaster::new())),
@@ -623,6 +664,8 @@ impl ToolManager {
             "lineintersections" => Some(Box::new(gis_analysis::LineIntersections::new())),
             "linearityindex" => Some(Box::new(gis_analysis::LinearityIndex::new())),
             "lowestposition" => Some(Box::new(gis_analysis::LowestPosition::new())),
+            "maskgrow" => Some(Box::new(gis_analysis::MaskGrow::new())),
+            "maskshrink" => Some(Box::new(gis_analysis::MaskShrink::new())),
             "maxabsoluteoverlay" => Some(Box::new(gis_analysis::MaxAbsoluteOverlay::new())),
             "maxoverlay" => Some(Box::new(gis_analysis::MaxOverlay::new())),
             "medoid" => Some(Box::new(gis_analysis::Medoid::new())),
@@ -684,10 +727,16 @@ impl ToolManager {
             "weightedsum" => Some(Box::new(gis_analysis::WeightedSum::new())),
 
             // hydro_analysis
+            "accumulatealongchannels" => {
+                Some(Box::new(hydro_analysis::AccumulateAlongChannels::new()))
+            }
             "averageflowpathslope" => Some(Box::new(hydro_analysis::AverageFlowpathSlope::new())),
             "averageupslopeflowpathlength" => {
                 Some(Box::new(hydro_analysis::AverageUpslopeFlowpathLength::new()))
             }
+            "basinoutlethydraulicgeometry" => {
+                Some(Box::new(hydro_analysis::BasinOutletHydraulicGeometry::new()))
+            }
             "basins" => Some(Box::new(hydro_analysis::Basins::new())),
             "breachdepressions" => Some(Box::new(hydro_analysis::BreachDepressions::new())),
             "breachdepressionsleastcost" => {
@@ -695,10 +744,18 @@ impl ToolManager {
             }
             "breachsinglecellpits" => Some(Box::new(hydro_analysis::BreachSingleCellPits::new())),
             "burnstreamsatroads" => Some(Box::new(hydro_analysis::BurnStreamsAtRoads::new())),
+            "catchmentdelineationbatch" => {
+                Some(Box::new(hydro_analysis::CatchmentDelineationBatch::new()))
+            }
+            "cellpathupstreamtrace" => Some(Box::new(hydro_analysis::CellPathUpstreamTrace::new())),
+            "channeljunctionangles" => Some(Box::new(hydro_analysis::ChannelJunctionAngles::new())),
             "d8flowaccumulation" => Some(Box::new(hydro_analysis::D8FlowAccumulation::new())),
             "d8massflux" => Some(Box::new(hydro_analysis::D8MassFlux::new())),
             "d8pointer" => Some(Box::new(hydro_analysis::D8Pointer::new())),
             "depthinsink" => Some(Box::new(hydro_analysis::DepthInSink::new())),
+            "detectparallelstreamartifacts" => Some(Box::new(
+                hydro_analysis::DetectParallelStreamArtifacts::new(),
+            )),
             "dinfflowaccumulation" => Some(Box::new(hydro_analysis::DInfFlowAccumulation::new())),
             "dinfmassflux" => Some(Box::new(hydro_analysis::DInfMassFlux::new())),
             "dinfpointer" => Some(Box::new(hydro_analysis::DInfPointer::new())),
@@ -732,12 +789,24 @@ impl ToolManager {
                 Some(Box::new(hydro_analysis::FlowAccumulationFullWorkflow::new()))
             }
             "flowlengthdiff" => Some(Box::new(hydro_analysis::FlowLengthDiff::new())),
+            "flowpathdensity" => Some(Box::new(hydro_analysis::FlowPathDensity::new())),
             "hillslopes" => Some(Box::new(hydro_analysis::Hillslopes::new())),
+            "hillsloperepresentativeprofileplotdata" => Some(Box::new(
+                hydro_analysis::HillslopeRepresentativeProfilePlotData::new(),
+            )),
+            "hillslopewidthfunction" => {
+                Some(Box::new(hydro_analysis::HillslopeWidthFunction::new()))
+            }
             "hillslopestopaz" => Some(Box::new(hydro_analysis::HillslopesTopaz::new())),
+            "hydroenforceculverts" => Some(Box::new(hydro_analysis::HydroEnforceCulverts::new())),
+            "identifyflatareasandpits" => {
+                Some(Box::new(hydro_analysis::IdentifyFlatAreasAndPits::new()))
+            }
             "impoundmentsizeindex" => Some(Box::new(hydro_analysis::ImpoundmentSizeIndex::new())),
             "insertdams" => Some(Box::new(hydro_analysis::InsertDams::new())),
             "isobasins" => Some(Box::new(hydro_analysis::Isobasins::new())),
             "jensonsnappourpoints" => Some(Box::new(hydro_analysis::JensonSnapPourPoints::new())),
+            "labelhillslopefacets" => Some(Box::new(hydro_analysis::LabelHillslopeFacets::new())),
             "longestflowpath" => Some(Box::new(hydro_analysis::LongestFlowpath::new())),
             "maxupslopeflowpathlength" => {
                 Some(Box::new(hydro_analysis::MaxUpslopeFlowpathLength::new()))
@@ -746,6 +815,12 @@ impl ToolManager {
             "numinflowingneighbours" => {
                 Some(Box::new(hydro_analysis::NumInflowingNeighbours::new()))
             }
+            "outletsensitivityanalysis" => {
+                Some(Box::new(hydro_analysis::OutletSensitivityAnalysis::new()))
+            }
+            "pourpointfromlargeststream" => {
+                Some(Box::new(hydro_analysis::PourPointFromLargestStream::new()))
+            }
             "raisewalls" => Some(Box::new(hydro_analysis::RaiseWalls::new())),
             "rho8pointer" => Some(Box::new(hydro_analysis::Rho8Pointer::new())),
             "sink" => Some(Box::new(hydro_analysis::Sink::new())),
@@ -755,6 +830,9 @@ impl ToolManager {
             }
             "strahlerorderbasins" => Some(Box::new(hydro_analysis::StrahlerOrderBasins::new())),
             "subbasins" => Some(Box::new(hydro_analysis::Subbasins::new())),
+            "subwtatoweppmanagementgrid" => {
+                Some(Box::new(hydro_analysis::SubwtaToWeppManagementGrid::new()))
+            }
             "tracedownslopeflowpaths" => {
                 Some(Box::new(hydro_analysis::TraceDownslopeFlowpaths::new()))
             }
@@ -763,6 +841,10 @@ impl ToolManager {
                 Some(Box::new(hydro_analysis::UpslopeDepressionStorage::new()))
             }
             "watershed" => Some(Box::new(hydro_analysis::Watershed::new())),
+            "watershedareareport" => Some(Box::new(hydro_analysis::WatershedAreaReport::new())),
+            "weppchannelparametertable" => {
+                Some(Box::new(hydro_analysis::WeppChannelParameterTable::new()))
+            }
 
             // image_analysis
             "adaptivefilter" => Some(Box::new(image_analysis::AdaptiveFilter::new())),
@@ -843,6 +925,9 @@ impl ToolManager {
             "percentilefilter" => Some(Box::new(image_analysis::PercentileFilter::new())),
             "prewittfilter" => Some(Box::new(image_analysis::PrewittFilter::new())),
             "rangefilter" => Some(Box::new(image_analysis::RangeFilter::new())),
+            "rasterhistogramequalize" => {
+                Some(Box::new(image_analysis::RasterHistogramEqualize::new()))
+            }
             "removespurs" => Some(Box::new(image_analysis::RemoveSpurs::new())),
             "resample" => Some(Box::new(image_analysis::Resample::new())),
             "rgbtoihs" => Some(Box::new(image_analysis::RgbToIhs::new())),
@@ -1029,6 +1114,9 @@ impl ToolManager {
             "subtract" => Some(Box::new(math_stat_analysis::Subtract::new())),
             "tan" => Some(Box::new(math_stat_analysis::Tan::new())),
             "tanh" => Some(Box::new(math_stat_analysis::Tanh::new())),
+            "temporalrasterdifference" => {
+                Some(Box::new(math_stat_analysis::TemporalRasterDifference::new()))
+            }
             "todegrees" => Some(Box::new(math_stat_analysis::ToDegrees::new())),
             "toradians" => Some(Box::new(math_stat_analysis::ToRadians::new())),
             "trendsurface" => Some(Box::new(math_stat_analysis::TrendSurface::new())),
@@ -1047,6 +1135,9 @@ impl ToolManager {
             "zscores" => Some(Box::new(math_stat_analysis::ZScores::new())),
 
             // stream_network_analysis
+            "channelcrosssectionextractor" => Some(Box::new(
+                stream_network_analysis::ChannelCrossSectionExtractor::new(),
+            )),
             "distancetooutlet" => Some(Box::new(stream_network_analysis::DistanceToOutlet::new())),
             "extractstreams" => Some(Box::new(stream_network_analysis::ExtractStreams::new())),
             "extractvalleys" => Some(Box::new(stream_network_analysis::ExtractValleys::new())),
@@ -1081,11 +1172,17 @@ impl ToolManager {
             "strahlerstreamorder" => {
                 Some(Box::new(stream_network_analysis::StrahlerStreamOrder::new()))
             }
+            "streambufferzones" => {
+                Some(Box::new(stream_network_analysis::StreamBufferZones::new()))
+            }
             "streamlinkclass" => Some(Box::new(stream_network_analysis::StreamLinkClass::new())),
             "streamlinkidentifier" => Some(Box::new(
                 stream_network_analysis::StreamLinkIdentifier::new(),
             )),
             "streamlinklength" => Some(Box::new(stream_network_analysis::StreamLinkLength::new())),
+            "streamlinklengthandsinuosity" => Some(Box::new(
+                stream_network_analysis::StreamLinkLengthAndSinuosity::new(),
+            )),
             "streamlinkslope" => Some(Box::new(stream_network_analysis::StreamLinkSlope::new())),
             "streamslopecontinuous" => Some(Box::new(
                 stream_network_analysis::StreamSlopeContinuous::new(),
@@ -1178,6 +1275,7 @@ impl ToolManager {
             "multiscaletopographicpositionimage" => Some(Box::new(
                 terrain_analysis::MultiscaleTopographicPositionImage::new(),
             )),
+            "nodatafill" => Some(Box::new(terrain_analysis::NoDataFill::new())),
             "numdownslopeneighbours" => {
                 Some(Box::new(terrain_analysis::NumDownslopeNeighbours::new()))
             }
@@ -1202,6 +1300,9 @@ impl ToolManager {
                 Some(Box::new(terrain_analysis::SedimentTransportIndex::new()))
             }
             "slope" => Some(Box::new(terrain_analysis::Slope::new())),
+            "slopeaspectcurvaturebundle" => Some(Box::new(
+                terrain_analysis::SlopeAspectCurvatureBundle::new(),
+            )),
             "slopevselevationplot" => Some(Box::new(terrain_analysis::SlopeVsElevationPlot::new())),
             "sphericalstddevofnormals" => {
                 Some(Box::new(terrain_analysis::SphericalStdDevOfNormals::new()))
@@ -1310,6 +1411,16 @@ impl ToolManager {
 
                     if !ecode.success() {
                         println!("Failure to run plugin subprocess.");
+                        // Pass on the exit codes of classified failures (see FailureClass).
+                        if let Some(class) = ecode.code().and_then(FailureClass::from_exit_code) {
+                            return Err(class
+                                .error(format!(
+                                    "The {} plugin failed ({}).",
+                                    tool_name,
+                                    class.as_str()
+                                ))
+                                .into());
+                        }
                     }
                 } else {
                     // We couldn't find an executable file for the tool, but still check to see if it's
@@ -1749,6 +1860,106 @@ pub trait WhiteboxTool {
     ) -> Result<(), Error>;
 }
 
+/// Classes of tool failure that scripts may need to tell apart, e.g. to retry a failed trace
+/// with relaxed options but not a run with misaligned inputs. A tool classifies an error by
+/// returning a `WbtError` (see `whitebox_common::error`), and the whitebox_tools executable then
+/// exits with the class's exit code rather than 1, which is still used for unclassified errors.
+///
+/// | Class | Exit code | Meaning |
+/// |-------|-----------|---------|
+/// | `argument_error` | 2 | Missing, malformed, or contradictory arguments |
+/// | `geometry_mismatch` | 3 | Input rasters that do not align, or that do not describe a usable area |
+/// | `conversion_failure` | 4 | A coordinate could not be converted into the grid |
+/// | `trace_failure` | 5 | The analysis ran but found no result, e.g. no outlet could be traced |
+/// | `file_not_found` | 6 | An input file does not exist |
+/// | `data_error` | 7 | Input data that could be read but not used |
+/// | `internal_error` | 8 | A failure that is not the user's or the data's fault |
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FailureClass {
+    ArgumentError,
+    GeometryMismatch,
+    ConversionFailure,
+    TraceFailure,
+    FileNotFound,
+    DataError,
+    InternalError,
+}
+
+impl FailureClass {
+    const ALL: [FailureClass; 7] = [
+        FailureClass::ArgumentError,
+        FailureClass::GeometryMismatch,
+        FailureClass::ConversionFailure,
+        FailureClass::TraceFailure,
+        FailureClass::FileNotFound,
+        FailureClass::DataError,
+        FailureClass::InternalError,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureClass::ArgumentError => "argument_error",
+            FailureClass::GeometryMismatch => "geometry_mismatch",
+            FailureClass::ConversionFailure => "conversion_failure",
+            FailureClass::TraceFailure => "trace_failure",
+            FailureClass::FileNotFound => "file_not_found",
+            FailureClass::DataError => "data_error",
+            FailureClass::InternalError => "internal_error",
+        }
+    }
+
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            FailureClass::ArgumentError => 2,
+            FailureClass::GeometryMismatch => 3,
+            FailureClass::ConversionFailure => 4,
+            FailureClass::TraceFailure => 5,
+            FailureClass::FileNotFound => 6,
+            FailureClass::DataError => 7,
+            FailureClass::InternalError => 8,
+        }
+    }
+
+    pub fn from_exit_code(code: i32) -> Option<FailureClass> {
+        FailureClass::ALL
+            .iter()
+            .copied()
+            .find(|c| c.exit_code() == code)
+    }
+
+    /// A `WbtError` of this class, e.g. to pass on the failure of a plugin subprocess.
+    pub fn error<S: Into<String>>(&self, message: S) -> WbtError {
+        match self {
+            FailureClass::ArgumentError => WbtError::invalid_argument(message),
+            FailureClass::GeometryMismatch => WbtError::geometry_mismatch(message),
+            FailureClass::ConversionFailure => WbtError::conversion_failure(message),
+            FailureClass::TraceFailure => WbtError::trace_failure(message),
+            FailureClass::FileNotFound => WbtError::file_not_found(message),
+            FailureClass::DataError => WbtError::data_error(message),
+            FailureClass::InternalError => WbtError::internal(message),
+        }
+    }
+}
+
+impl From<&WbtError> for FailureClass {
+    fn from(err: &WbtError) -> FailureClass {
+        match err {
+            WbtError::InvalidArgument(_) => FailureClass::ArgumentError,
+            WbtError::FileNotFound(_) => FailureClass::FileNotFound,
+            WbtError::GeometryMismatch(_) => FailureClass::GeometryMismatch,
+            WbtError::DataError(_) => FailureClass::DataError,
+            WbtError::ConversionFailure(_) => FailureClass::ConversionFailure,
+            WbtError::TraceFailure(_) => FailureClass::TraceFailure,
+            WbtError::Internal(_) => FailureClass::InternalError,
+        }
+    }
+}
+
+/// Returns the class of an error returned by a tool, if the tool classified it.
+pub fn failure_class(err: &Error) -> Option<FailureClass> {
+    WbtError::from_io_ref(err).map(FailureClass::from)
+}
+
 fn get_help<'a>(wt: Box<dyn WhiteboxTool + 'a>) -> String {
     let tool_name = wt.get_tool_name();
     let description = wt.get_tool_description();
@@ -1825,6 +2036,50 @@ impl ToolParameter {
     }
 }
 
+/// Optional hints describing how one of a tool's parameters relates to the others, keyed by one
+/// of the parameter's flags. They are only read by front-ends such as the Whitebox Runner, which
+/// uses them to group parameters, grey out those that cannot be used yet, and check the parameter
+/// combination before running the tool; the tool itself must still validate its arguments.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ParameterHints {
+    /// The flag of the parameter that the hints apply to.
+    #[serde(skip)]
+    flag: String,
+    /// The heading of the group that the parameter is displayed under.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group: Option<String>,
+    /// Flags that must all be specified before the parameter can be used.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    requires: Vec<String>,
+    /// Either this parameter or at least one of these flags must be specified.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    requires_one_of: Vec<String>,
+    /// Flags that cannot be specified along with this parameter.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    conflicts_with: Vec<String>,
+}
+
+/// Serializes tool parameters in the form returned by `get_tool_parameters`, adding the keys
+/// of any hints to the matching parameters. Parameters without hints serialize as usual.
+fn parameters_to_json(parameters: &[ToolParameter], hints: &[ParameterHints]) -> String {
+    let mut values = Vec::with_capacity(parameters.len());
+    for parameter in parameters {
+        let mut value = match serde_json::to_value(parameter) {
+            Ok(v) => v,
+            Err(err) => return format!("{:?}", err),
+        };
+        for h in hints.iter().filter(|h| parameter.flags.contains(&h.flag)) {
+            if let (Some(obj), Ok(serde_json::Value::Object(extra))) =
+                (value.as_object_mut(), serde_json::to_value(h))
+            {
+                obj.extend(extra);
+            }
+        }
+        values.push(value);
+    }
+    format!("{{\"parameters\":{}}}", serde_json::Value::Array(values))
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 enum ParameterType {
     Boolean,
@@ -0,0 +1,928 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, BufWriter, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::algorithms::{sample_bilinear, D8Walker, GridGeometry, NodataPolicy};
+use whitebox_common::structures::Point2D;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+use whitebox_vector::*;
+
+/// This tool samples channel cross-sections from a DEM (`--dem`) for hydraulic modelling. Sections are
+/// placed along each channel every `--spacing` map units, starting half a spacing from the upstream end
+/// of the channel, and run perpendicular to the local flow direction. Each section is `--width` map units
+/// long, centred on the channel, and is sampled at `--n_points` evenly spaced points (at least 2) using
+/// bilinear interpolation of the DEM. The local flow direction at a station is the direction of the chord
+/// joining the points half a spacing upstream and downstream of it along the channel, which smooths out
+/// the zig-zag of channels traced on the grid.
+///
+/// The channels are either the links of a raster stream network (`--streams`, where stream cells are the
+/// positive, non-nodata cells, with the D8 pointer raster `--d8_pntr`), or the lines of a channel vector
+/// (`--channels`, a PolyLine Shapefile). Raster links are identified by walking downstream from each channel
+/// head and junction to the next junction, as in `StreamLinkIdentifier`, are numbered from 1 in row-major
+/// order of their upstream-most cells, and run through the centres of their cells to the centre of the
+/// cell they drain to. Vector channels are identified by their record number (from 1) and are assumed to
+/// be digitized in the downstream direction; each part of a multipart line is sampled separately.
+///
+/// Points are ordered from the left bank to the right bank, looking downstream, and their offsets from the
+/// channel are negative on the left and positive on the right. A section that extends past the edge of the
+/// DEM or into NoData cells is truncated at the last valid point on each side of the channel and flagged
+/// in the `truncated` column. Sections with fewer than two valid points, e.g. where the channel itself is
+/// on NoData cells, are skipped with a warning.
+///
+/// The output (`--output`) is a tab-separated table if its name ends in `.tsv` or `.txt`, with one row
+/// per section point, grouped in blocks by section:
+///
+/// | Column | Description |
+/// |--------|-------------|
+/// | `section_id` | The section number, from 1, in channel order and then downstream order. |
+/// | `channel_id` | The link or vector record identifier of the channel. |
+/// | `station` | The distance of the section along the channel from its upstream end. |
+/// | `point`, `offset` | The point number across the section (from 0) and its offset from the channel. |
+/// | `x`, `y`, `elevation` | The point location and its interpolated DEM elevation. |
+/// | `truncated` | 1 if the section was truncated at the DEM edge or NoData cells, otherwise 0. |
+///
+/// Otherwise the output is a vector file (a Shapefile, or GeoJSON if its name ends in `.geojson` or
+/// `.json`) with one PolyLineZ (`LineString`) feature per section, whose vertex elevations are the sampled
+/// DEM values, and with the `SECTION`, `CHANNEL`, `STATION`, `N_POINTS` and `TRUNCATED` attributes.
+/// Shapefile vertices also carry their offsets as measures.
+///
+/// # See Also
+/// `LongProfile`, `StreamLinkIdentifier`, `RasterStreamsToVector`
+pub struct ChannelCrossSectionExtractor {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl ChannelCrossSectionExtractor {
+    pub fn new() -> ChannelCrossSectionExtractor {
+        // public constructor
+        let name = "ChannelCrossSectionExtractor".to_string();
+        let toolbox = "Stream Network Analysis".to_string();
+        let description =
+            "Samples DEM cross-sections perpendicular to channels at regular intervals."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file; requires --d8_pntr.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file, used with --streams.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Channel Lines File".to_owned(),
+            flags: vec!["--channels".to_owned()],
+            description:
+                "Input channel lines vector file, digitized downstream; used instead of --streams."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description:
+                "Output table (*.tsv, *.txt) or lines vector (*.shp, *.geojson) of cross-sections."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Vector(
+                VectorGeometryType::Line,
+            )),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Section Spacing".to_owned(),
+            flags: vec!["--spacing".to_owned()],
+            description: "Distance between sections along the channel, in map units.".to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Section Width".to_owned(),
+            flags: vec!["--width".to_owned()],
+            description: "Total length of each section, centred on the channel, in map units."
+                .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Points per Section".to_owned(),
+            flags: vec!["--n_points".to_owned()],
+            description: "Number of evenly spaced points sampled across each section (at least 2)."
+                .to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("21".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=dem.tif --streams=streams.tif --d8_pntr=D8.tif -o=sections.tsv --spacing=50.0 --width=100.0 --n_points=41
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=dem.tif --channels=channels.shp -o=sections.geojson --spacing=50.0 --width=100.0", short_exe, name).replace("*", &sep);
+
+        ChannelCrossSectionExtractor {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for ChannelCrossSectionExtractor {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        match serde_json::to_string(&self.parameters) {
+            Ok(json_str) => return format!("{{\"parameters\":{}}}", json_str),
+            Err(err) => return format!("{:?}", err),
+        }
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut dem_file = String::new();
+        let mut streams_file = String::new();
+        let mut d8_file = String::new();
+        let mut channels_file = String::new();
+        let mut output_file = String::new();
+        let mut spacing = f64::NAN;
+        let mut width = f64::NAN;
+        let mut n_points = 21usize;
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-dem" {
+                dem_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-channels" {
+                channels_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-spacing" {
+                spacing = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-width" {
+                width = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<f64>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-n_points" {
+                n_points = if keyval {
+                    vec[1]
+                        .to_string()
+                        .parse::<usize>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                } else {
+                    args[i + 1]
+                        .to_string()
+                        .parse::<usize>()
+                        .expect(&format!("Error parsing {}", flag_val))
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        if streams_file.trim().is_empty() == channels_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Specify the channels with exactly one of a raster streams file (--streams) or a channel lines vector (--channels).",
+            ));
+        }
+        if !streams_file.trim().is_empty() && d8_file.trim().is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "A D8 pointer file (--d8_pntr) is required with a raster streams file (--streams).",
+            ));
+        }
+        if spacing.is_nan() || spacing <= 0f64 || spacing.is_infinite() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The section spacing (--spacing) must be a positive number.",
+            ));
+        }
+        if width.is_nan() || width <= 0f64 || width.is_infinite() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The section width (--width) must be a positive number.",
+            ));
+        }
+        if n_points < 2 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Each section must have at least two points (--n_points).",
+            ));
+        }
+        let lc = output_file.to_lowercase();
+        let is_table = lc.ends_with(".tsv") || lc.ends_with(".txt");
+        if !is_table {
+            // check the vector format before doing any work
+            VectorFormat::from_file_name(&output_file)?;
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        dem_file = resolve_path(working_directory, &dem_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        channels_file = resolve_path(working_directory, &channels_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let dem = Raster::new(&dem_file, "r")?;
+
+        let start = Instant::now();
+
+        // Each channel is an identifier and a line running downstream.
+        let mut channels: Vec<(i32, Vec<Point2D>)> = vec![];
+        if !streams_file.is_empty() {
+            let streams = Raster::new(&streams_file, "r")?;
+            let pntr = Raster::new(&d8_file, "r")?;
+            if streams.configs.rows != dem.configs.rows
+                || streams.configs.columns != dem.configs.columns
+                || pntr.configs.rows != dem.configs.rows
+                || pntr.configs.columns != dem.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+
+            let rows = streams.configs.rows as isize;
+            let columns = streams.configs.columns as isize;
+            let streams_nodata = streams.configs.nodata;
+
+            let walker = D8Walker::new(
+                rows,
+                columns,
+                pntr.configs.nodata,
+                esri_style,
+                |row, col| pntr.get_value(row, col),
+            );
+
+            let is_stream = |row: isize, col: isize| {
+                let z = streams.get_value(row, col);
+                z != streams_nodata && z > 0f64
+            };
+            for row in 0..rows {
+                for col in 0..columns {
+                    if is_stream(row, col) && walker.has_invalid_pointer(row, col) {
+                        return Err(Error::new(ErrorKind::InvalidInput,
+                                "An unexpected value has been identified in the pointer image. This tool requires a pointer grid that has been created using either the D8 or Rho8 tools."));
+                    }
+                }
+            }
+
+            if verbose {
+                println!("Tracing stream links...")
+            };
+            for (i, mut link) in walker.stream_links(is_stream).into_iter().enumerate() {
+                // continue each link to the junction it drains to, so that the links meet
+                let (row, col) = link[link.len() - 1];
+                match walker.downstream(row, col) {
+                    Some((r, c)) if is_stream(r, c) => link.push((r, c)),
+                    _ => {}
+                }
+                let line = link
+                    .iter()
+                    .map(|&(r, c)| Point2D::new(dem.get_x_from_column(c), dem.get_y_from_row(r)))
+                    .collect();
+                channels.push(((i + 1) as i32, line));
+            }
+        } else {
+            let input = Shapefile::read(&channels_file)?;
+            if input.header.shape_type.base_shape_type() != ShapeType::PolyLine {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The channels vector (--channels) must be of PolyLine base shape type.",
+                ));
+            }
+            for record_num in 0..input.num_records {
+                let record = input.get_record(record_num);
+                for part in 0..record.num_parts as usize {
+                    let part_start = record.parts[part] as usize;
+                    let part_end = if part < record.num_parts as usize - 1 {
+                        record.parts[part + 1] as usize
+                    } else {
+                        record.num_points as usize
+                    };
+                    channels.push((
+                        (record_num + 1) as i32,
+                        record.points[part_start..part_end].to_vec(),
+                    ));
+                }
+            }
+        }
+
+        let geometry = GridGeometry {
+            rows: dem.configs.rows,
+            columns: dem.configs.columns,
+            west: dem.configs.west,
+            north: dem.configs.north,
+            resolution_x: dem.configs.resolution_x,
+            resolution_y: dem.configs.resolution_y,
+            nodata: dem.configs.nodata,
+        };
+        let sample = |x: f64, y: f64| {
+            sample_bilinear(&geometry, x, y, NodataPolicy::Strict, |row, col| {
+                dem.get_value(row, col)
+            })
+        };
+
+        let mut sections: Vec<CrossSection> = vec![];
+        let mut num_skipped = 0usize;
+        let num_channels = channels.len();
+        for (i, (channel_id, line)) in channels.iter().enumerate() {
+            for (station, centre, direction) in section_stations(line, spacing) {
+                match sample_section(centre, direction, width, n_points, sample) {
+                    Some((points, truncated)) => sections.push(CrossSection {
+                        channel_id: *channel_id,
+                        station,
+                        points,
+                        truncated,
+                    }),
+                    None => num_skipped += 1,
+                }
+            }
+
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_channels as f64) as usize;
+                if progress != old_progress {
+                    println!("Sampling sections: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let num_truncated = sections.iter().filter(|s| s.truncated).count();
+        if verbose {
+            println!(
+                "Extracted {} sections from {} channels ({} truncated).",
+                sections.len(),
+                num_channels,
+                num_truncated
+            );
+        }
+        if num_skipped > 0 {
+            println!(
+                "Warning: {} sections were skipped because fewer than two of their points fell on valid DEM cells.",
+                num_skipped
+            );
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        if is_table {
+            write_sections_to_tsv(&sections, &output_file)?;
+        } else {
+            let mut output = VectorWriter::new(&output_file, ShapeType::PolyLineZ)?;
+            output.projection = dem.configs.coordinate_ref_system_wkt.clone();
+            if dem.configs.epsg_code != 0 {
+                output.epsg = Some(dem.configs.epsg_code);
+            }
+            output.add_field(&AttributeField::new(
+                "SECTION",
+                FieldDataType::Int,
+                7u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "CHANNEL",
+                FieldDataType::Int,
+                7u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "STATION",
+                FieldDataType::Real,
+                12u8,
+                3u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "N_POINTS",
+                FieldDataType::Int,
+                5u8,
+                0u8,
+            ));
+            output.add_field(&AttributeField::new(
+                "TRUNCATED",
+                FieldDataType::Int,
+                1u8,
+                0u8,
+            ));
+            for (i, section) in sections.iter().enumerate() {
+                let points: Vec<Point2D> = section.points.iter().map(|p| p.location).collect();
+                let offsets: Vec<f64> = section.points.iter().map(|p| p.offset).collect();
+                let elevations: Vec<f64> = section.points.iter().map(|p| p.elevation).collect();
+                let mut sfg = ShapefileGeometry::new(ShapeType::PolyLineZ);
+                sfg.add_partz(&points, &offsets, &elevations);
+                output.add_record(
+                    sfg,
+                    vec![
+                        FieldData::Int((i + 1) as i32),
+                        FieldData::Int(section.channel_id),
+                        FieldData::Real(section.station),
+                        FieldData::Int(section.points.len() as i32),
+                        FieldData::Int(section.truncated as i32),
+                    ],
+                );
+            }
+            output.write()?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+struct SectionPoint {
+    index: usize,
+    offset: f64,
+    location: Point2D,
+    elevation: f64,
+}
+
+struct CrossSection {
+    channel_id: i32,
+    station: f64,
+    points: Vec<SectionPoint>,
+    truncated: bool,
+}
+
+/// Returns the point at distance `s` along `line`, whose cumulative vertex distances are `distances`.
+fn point_along(line: &[Point2D], distances: &[f64], s: f64) -> Point2D {
+    let s = s.max(0f64).min(distances[distances.len() - 1]);
+    let i = match distances.iter().position(|&d| d >= s) {
+        Some(i) if i > 0 => i,
+        _ => return line[0],
+    };
+    let segment = distances[i] - distances[i - 1];
+    if segment <= 0f64 {
+        return line[i];
+    }
+    let t = (s - distances[i - 1]) / segment;
+    Point2D::new(
+        line[i - 1].x + t * (line[i].x - line[i - 1].x),
+        line[i - 1].y + t * (line[i].y - line[i - 1].y),
+    )
+}
+
+/// Places sections along a line every `spacing`, starting half a spacing from its start, returning
+/// the station, the centre point, and the unit flow direction of each. The flow direction is that of the
+/// chord between the points half a spacing up- and downstream of the station. Stations where the chord has
+/// no length are omitted.
+fn section_stations(line: &[Point2D], spacing: f64) -> Vec<(f64, Point2D, (f64, f64))> {
+    let mut stations = vec![];
+    if line.len() < 2 {
+        return stations;
+    }
+    let mut distances = Vec::with_capacity(line.len());
+    distances.push(0f64);
+    for i in 1..line.len() {
+        distances.push(distances[i - 1] + line[i].distance(&line[i - 1]));
+    }
+    let length = distances[distances.len() - 1];
+
+    let mut k = 0usize;
+    loop {
+        let station = (k as f64 + 0.5) * spacing;
+        if station >= length {
+            break;
+        }
+        let up = point_along(line, &distances, station - spacing / 2f64);
+        let down = point_along(line, &distances, station + spacing / 2f64);
+        let (ux, uy) = (down.x - up.x, down.y - up.y);
+        let chord = (ux * ux + uy * uy).sqrt();
+        if chord > 0f64 {
+            stations.push((
+                station,
+                point_along(line, &distances, station),
+                (ux / chord, uy / chord),
+            ));
+        }
+        k += 1;
+    }
+    stations
+}
+
+/// Samples a section of `width` centred on `centre` and perpendicular to the unit flow `direction`, with
+/// points ordered from the left bank to the right bank looking downstream. The section is truncated on
+/// each side at the last point, moving out from the channel, for which `sample` returns a value. Returns
+/// the valid points and whether the section was truncated, or `None` if fewer than two points are valid.
+fn sample_section<F>(
+    centre: Point2D,
+    direction: (f64, f64),
+    width: f64,
+    n_points: usize,
+    sample: F,
+) -> Option<(Vec<SectionPoint>, bool)>
+where
+    F: Fn(f64, f64) -> Option<f64>,
+{
+    // The right-hand normal to the flow direction.
+    let (nx, ny) = (direction.1, -direction.0);
+    let step = width / (n_points - 1) as f64;
+    let samples: Vec<(f64, Point2D, Option<f64>)> = (0..n_points)
+        .map(|j| {
+            let offset = -width / 2f64 + j as f64 * step;
+            let p = Point2D::new(centre.x + offset * nx, centre.y + offset * ny);
+            (offset, p, sample(p.x, p.y))
+        })
+        .collect();
+
+    // Grow the valid run outwards from the point(s) closest to the channel.
+    let (mut lo, mut hi) = ((n_points - 1) / 2, n_points / 2);
+    if samples[lo].2.is_none() || samples[hi].2.is_none() {
+        return None;
+    }
+    while lo > 0 && samples[lo - 1].2.is_some() {
+        lo -= 1;
+    }
+    while hi < n_points - 1 && samples[hi + 1].2.is_some() {
+        hi += 1;
+    }
+    if hi == lo {
+        return None;
+    }
+
+    let points = (lo..=hi)
+        .map(|j| SectionPoint {
+            index: j,
+            offset: samples[j].0,
+            location: samples[j].1,
+            elevation: samples[j].2.unwrap(),
+        })
+        .collect();
+    Some((points, lo > 0 || hi < n_points - 1))
+}
+
+fn write_sections_to_tsv(sections: &[CrossSection], file_path: &str) -> io::Result<()> {
+    let mut file = BufWriter::new(File::create(file_path)?);
+    writeln!(
+        &mut file,
+        "section_id\tchannel_id\tstation\tpoint\toffset\tx\ty\televation\ttruncated"
+    )?;
+    for (i, section) in sections.iter().enumerate() {
+        for p in &section.points {
+            writeln!(
+                &mut file,
+                "{}\t{}\t{:.3}\t{}\t{:.3}\t{}\t{}\t{:.3}\t{}",
+                i + 1,
+                section.channel_id,
+                section.station,
+                p.index,
+                p.offset,
+                p.location.x,
+                p.location.y,
+                p.elevation,
+                section.truncated as i32
+            )?;
+        }
+    }
+    file.flush()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{sample_section, section_stations, ChannelCrossSectionExtractor};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::structures::Point2D;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_vector::{
+        AttributeField, FieldData, FieldDataType, ShapeType, Shapefile, ShapefileGeometry,
+    };
+
+    /// A 20-row by 12-column grid of 1 m cells holding an asymmetric V-shaped valley whose channel runs
+    /// south down column 8. The east valley side rises twice as steeply as the west side, and the valley
+    /// floor falls 0.1 m per row.
+    fn write_inputs(dir: &str) {
+        let across = |c: isize| {
+            if c > 8 {
+                2f64 * (c - 8) as f64
+            } else {
+                (8 - c) as f64
+            }
+        };
+        let mut dem = GridFixture::new(20, 12, |r, c| across(c) + 10f64 - 0.1 * r as f64)
+            .with_resolution(1f64);
+        // a NoData cell on the west side of the valley
+        dem.set_value(14, 6, -32768f64);
+        let streams =
+            GridFixture::new(20, 12, |_, c| if c == 8 { 1f64 } else { 0f64 }).with_resolution(1f64);
+        // south
+        let pntr =
+            GridFixture::new(20, 12, |r, _| if r < 19 { 8f64 } else { 0f64 }).with_resolution(1f64);
+        for (name, grid) in [("dem", dem), ("streams", streams), ("d8", pntr)] {
+            grid_fixture_raster(&format!("{}/{}.tif", dir, name), &grid)
+                .write()
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn test_stations_and_orientation() {
+        let line = vec![Point2D::new(0f64, 10f64), Point2D::new(0f64, 0f64)];
+        let stations = section_stations(&line, 4f64);
+        let values: Vec<f64> = stations.iter().map(|s| s.0).collect();
+        assert_eq!(values, vec![2f64, 6f64]);
+        assert_eq!(stations[0].1, Point2D::new(0f64, 8f64));
+        assert_eq!(stations[0].2, (0f64, -1f64));
+
+        // Flowing south, the left bank is to the east.
+        let (points, truncated) =
+            sample_section(stations[0].1, stations[0].2, 4f64, 3, |x, _| Some(x)).unwrap();
+        assert!(!truncated);
+        let xs: Vec<f64> = points.iter().map(|p| p.location.x).collect();
+        assert_eq!(xs, vec![2f64, 0f64, -2f64]);
+        assert_eq!(points[0].offset, -2f64);
+
+        // The east bank is cut off beyond x = 1.5.
+        let (points, truncated) = sample_section(stations[0].1, stations[0].2, 4f64, 5, |x, _| {
+            if x < 1.5 {
+                Some(x)
+            } else {
+                None
+            }
+        })
+        .unwrap();
+        assert!(truncated);
+        assert_eq!(
+            points.iter().map(|p| p.index).collect::<Vec<usize>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert!(sample_section(stations[0].1, stations[0].2, 4f64, 5, |_, _| None).is_none());
+    }
+
+    #[test]
+    fn test_raster_channel_sections() {
+        let dir = temp_dir("channel_cross_section_extractor").unwrap();
+        write_inputs(&dir);
+
+        let args = vec![
+            format!("--dem={}/dem.tif", dir),
+            format!("--streams={}/streams.tif", dir),
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--output={}/sections.tsv", dir),
+            "--spacing=4.0".to_string(),
+            "--width=6.0".to_string(),
+            "--n_points=7".to_string(),
+        ];
+        ChannelCrossSectionExtractor::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let rows: Vec<Vec<f64>> = read_tsv(&format!("{}/sections.tsv", dir))
+            .unwrap()
+            .iter()
+            .skip(1)
+            .map(|row| row.iter().map(|v| v.parse::<f64>().unwrap()).collect())
+            .collect();
+
+        // The link runs 19 m from row 0 to row 19, giving stations at 2, 6, 10, 14 and 18 m.
+        let stations: Vec<f64> = rows.iter().filter(|r| r[3] == 0f64).map(|r| r[2]).collect();
+        assert_eq!(stations, vec![2f64, 6f64, 10f64, 14f64, 18f64]);
+
+        // The first section is centred on row 2 (y = 17.5), where the valley floor is at 9.8 m.
+        let first: Vec<&Vec<f64>> = rows.iter().filter(|r| r[0] == 1f64).collect();
+        assert_eq!(first.len(), 7);
+        for r in &first {
+            let offset = r[4];
+            // left bank (negative offsets) to the east, which rises 2 m per m
+            let expected_x = 8.5 - offset;
+            let across = if offset < 0f64 {
+                -2f64 * offset
+            } else {
+                offset
+            };
+            assert!((r[5] - expected_x).abs() < 1e-9);
+            assert!((r[6] - 17.5).abs() < 1e-9);
+            assert!((r[7] - (across + 9.8)).abs() < 1e-3);
+            assert_eq!(r[1], 1f64);
+            assert_eq!(r[8], 0f64);
+        }
+
+        // The section at station 14 (row 14) is truncated on the right bank by the NoData cell in
+        // column 6, so only the offsets up to 1 m west of the channel remain. The east side is cut at
+        // the raster edge for offsets beyond 3.5 m, which the 6 m section does not reach.
+        let fourth: Vec<&Vec<f64>> = rows.iter().filter(|r| r[0] == 4f64).collect();
+        assert_eq!(fourth[0][2], 14f64);
+        assert_eq!(
+            fourth.iter().map(|r| r[4]).collect::<Vec<f64>>(),
+            vec![-3f64, -2f64, -1f64, 0f64, 1f64]
+        );
+        assert!(fourth.iter().all(|r| r[8] == 1f64));
+
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_vector_channel_sections() {
+        let dir = temp_dir("channel_cross_section_extractor_vector").unwrap();
+        write_inputs(&dir);
+
+        let mut channels =
+            Shapefile::new(&format!("{}/channels.shp", dir), ShapeType::PolyLine).unwrap();
+        channels
+            .attributes
+            .add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
+        sfg.add_part(&[Point2D::new(8.5, 19.5), Point2D::new(8.5, 11.5)]);
+        channels.add_record(sfg);
+        channels
+            .attributes
+            .add_record(vec![FieldData::Int(1)], false);
+        channels.write().unwrap();
+
+        let args = vec![
+            format!("--dem={}/dem.tif", dir),
+            format!("--channels={}/channels.shp", dir),
+            format!("--output={}/sections.geojson", dir),
+            "--spacing=4.0".to_string(),
+            "--width=2.0".to_string(),
+            "--n_points=3".to_string(),
+        ];
+        ChannelCrossSectionExtractor::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(format!("{}/sections.geojson", dir)).unwrap())
+                .unwrap();
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+        assert_eq!(features[0]["geometry"]["type"], "LineString");
+        assert_eq!(features[0]["properties"]["CHANNEL"], 1);
+        assert_eq!(features[0]["properties"]["STATION"], 2);
+        assert_eq!(features[0]["properties"]["N_POINTS"], 3);
+        assert_eq!(features[1]["properties"]["SECTION"], 2);
+        // row 2 of the grid: east bank, channel, west bank
+        let coords = features[0]["geometry"]["coordinates"].as_array().unwrap();
+        let z: Vec<f64> = coords.iter().map(|c| c[2].as_f64().unwrap()).collect();
+        assert!((z[0] - 11.8).abs() < 1e-3);
+        assert!((z[1] - 9.8).abs() < 1e-3);
+        assert!((z[2] - 10.8).abs() < 1e-3);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
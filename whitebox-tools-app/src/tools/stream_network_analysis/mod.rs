@@ -1,4 +1,5 @@
 // private sub-module defined in other files
+mod channel_cross_section_extractor;
 mod dist_to_outlet;
 mod extract_streams;
 mod extract_valleys;
@@ -14,10 +15,12 @@ mod rasterize_streams;
 mod remove_short_streams;
 mod shreve_magnitude;
 mod strahler_order;
+mod stream_buffer_zones;
 mod stream_junctions;
 mod stream_link_class;
 mod stream_link_id;
 mod stream_link_length;
+mod stream_link_length_and_sinuosity;
 mod stream_link_slope;
 mod stream_slope_continuous;
 mod topological_stream_order;
@@ -25,6 +28,7 @@ mod total_length_channels;
 mod tributary_id;
 
 // exports identifiers from private sub-modules in the current module namespace
+pub use self::channel_cross_section_extractor::ChannelCrossSectionExtractor;
 pub use self::dist_to_outlet::DistanceToOutlet;
 pub use self::extract_streams::ExtractStreams;
 pub use self::extract_valleys::ExtractValleys;
@@ -40,10 +44,12 @@ pub use self::rasterize_streams::RasterizeStreams;
 pub use self::remove_short_streams::RemoveShortStreams;
 pub use self::shreve_magnitude::ShreveStreamMagnitude;
 pub use self::strahler_order::StrahlerStreamOrder;
+pub use self::stream_buffer_zones::StreamBufferZones;
 pub use self::stream_junctions::StreamJunctionIdentifier;
 pub use self::stream_link_class::StreamLinkClass;
 pub use self::stream_link_id::StreamLinkIdentifier;
 pub use self::stream_link_length::StreamLinkLength;
+pub use self::stream_link_length_and_sinuosity::StreamLinkLengthAndSinuosity;
 pub use self::stream_link_slope::StreamLinkSlope;
 pub use self::stream_slope_continuous::StreamSlopeContinuous;
 pub use self::topological_stream_order::TopologicalStreamOrder;
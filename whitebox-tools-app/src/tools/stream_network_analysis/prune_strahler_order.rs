@@ -2,14 +2,16 @@
 This tool is part of the WhiteboxTools geospatial analysis library.
 Authors: Dr. Roger Lew and CODEX (gpt-5-codex high)
 Created: 29/09/2025
-Last Modified: 29/09/2025
+Last Modified: 16/10/2026
 License: MIT
 */
 
 use crate::tools::*;
 use std::env;
-use std::io::{Error, ErrorKind};
+use std::io::Error;
 use std::path;
+use whitebox_common::error::{ErrorContext, WbtError};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
 use whitebox_raster::*;
 
 /// This tool removes first-order (order value of one) links from an existing Strahler
@@ -139,10 +141,7 @@ impl WhiteboxTool for PruneStrahlerStreamOrder {
         let mut zero_background = false;
 
         if args.len() == 0 {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Tool run with no parameters.",
-            ));
+            return Err(WbtError::invalid_argument("Tool run with no parameters.").into());
         }
         for i in 0..args.len() {
             let mut arg = args[i].replace("\"", "");
@@ -174,52 +173,30 @@ impl WhiteboxTool for PruneStrahlerStreamOrder {
         }
 
         if streams_file.is_empty() {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
+            return Err(WbtError::invalid_argument(
                 "Input Strahler-order raster (--streams) not specified.",
-            ));
+            )
+            .into());
         }
         if output_file.is_empty() {
-            return Err(Error::new(
-                ErrorKind::InvalidInput,
-                "Output raster (--output) not specified.",
-            ));
-        }
-
-        if verbose {
-            let tool_name = self.get_tool_name();
-            let welcome_len = format!("* Welcome to {} *", tool_name).len().max(28);
-            // 28 = length of the 'Powered by' by statement.
-            println!("{}", "*".repeat(welcome_len));
-            println!(
-                "* Welcome to {} {}*",
-                tool_name,
-                " ".repeat(welcome_len - 15 - tool_name.len())
-            );
-            println!(
-                "* Powered by WhiteboxTools {}*",
-                " ".repeat(welcome_len - 28)
+            return Err(
+                WbtError::invalid_argument("Output raster (--output) not specified.").into(),
             );
-            println!("* www.whiteboxgeo.com {}*", " ".repeat(welcome_len - 23));
-            println!("{}", "*".repeat(welcome_len));
         }
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
+        print_tool_banner(&self.get_tool_name(), verbose);
 
         let mut progress: usize;
         let mut old_progress: usize = 1;
 
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        streams_file = resolve_path(working_directory, &streams_file);
+        output_file = resolve_path(working_directory, &output_file);
 
         if verbose {
             println!("Reading streams data...")
         };
-        let streams = Raster::new(&streams_file, "r")?;
+        let streams =
+            Raster::new(&streams_file, "r").context(format!("--streams {}", streams_file))?;
 
         let start = Instant::now();
 
@@ -282,3 +259,80 @@ impl WhiteboxTool for PruneStrahlerStreamOrder {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::PruneStrahlerStreamOrder;
+    use crate::tools::{failure_class, FailureClass, WhiteboxTool};
+    use std::fs;
+    use whitebox_common::error::WbtError;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    #[test]
+    fn test_prune_orders() {
+        let dir = temp_dir("prune_strahler_order").unwrap();
+        let streams = format!("{}/order.tif", dir);
+        let output = format!("{}/pruned.tif", dir);
+        let orders = GridFixture::new(3, 4, |_, c| [1f64, 2f64, 3f64, -32768f64][c as usize]);
+        grid_fixture_raster(&streams, &orders.categorical())
+            .write()
+            .unwrap();
+        let args = vec![
+            format!("--streams={}", streams),
+            format!("--output={}", output),
+        ];
+        PruneStrahlerStreamOrder::new()
+            .run(args.clone(), "", false)
+            .unwrap();
+        let raster = Raster::new(&output, "r").unwrap();
+        let values: Vec<f64> = (0..4).map(|c| raster.get_value(0, c)).collect();
+        assert_eq!(values, vec![-32768f64, 1f64, 2f64, -32768f64]);
+
+        let mut zero_args = args.clone();
+        zero_args.push("--zero_background".to_string());
+        PruneStrahlerStreamOrder::new()
+            .run(zero_args, "", false)
+            .unwrap();
+        let raster = Raster::new(&output, "r").unwrap();
+        assert_eq!(raster.get_value(0, 0), 0f64);
+        assert_eq!(raster.get_value(0, 3), -32768f64);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_classified_errors() {
+        let err = PruneStrahlerStreamOrder::new()
+            .run(vec!["--streams=order.tif".to_string()], "", false)
+            .unwrap_err();
+        assert_eq!(
+            WbtError::from_io_ref(&err),
+            Some(&WbtError::invalid_argument(
+                "Output raster (--output) not specified."
+            ))
+        );
+        assert_eq!(failure_class(&err), Some(FailureClass::ArgumentError));
+
+        let dir = temp_dir("prune_strahler_order_missing").unwrap();
+        let missing = format!("{}/missing.tif", dir);
+        let err = PruneStrahlerStreamOrder::new()
+            .run(
+                vec![
+                    format!("--streams={}", missing),
+                    format!("--output={}/pruned.tif", dir),
+                ],
+                "",
+                false,
+            )
+            .unwrap_err();
+        match WbtError::from_io_ref(&err) {
+            Some(WbtError::FileNotFound(message)) => {
+                assert!(message.starts_with(&format!("--streams {}: ", missing)))
+            }
+            other => panic!("unexpected error {:?}", other),
+        }
+        assert_eq!(failure_class(&err), Some(FailureClass::FileNotFound));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
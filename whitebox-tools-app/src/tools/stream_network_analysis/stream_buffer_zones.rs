@@ -0,0 +1,565 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::hydro_analysis::raster_geometry::check_rasters_share_geometry;
+use crate::tools::*;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::time::Instant;
+use whitebox_common::algorithms::distance_transform;
+use whitebox_common::utils::{get_formatted_elapsed_time, print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool maps riparian buffer zones whose width depends on stream order. Given a stream order
+/// raster (`--order`), such as the output of `StrahlerStreamOrder`, and a table of buffer distances
+/// by order (`--distances`), every cell within the buffer distance of a stream cell of some order is
+/// coded with that order in the output raster (`--output`). Where the buffers of several orders
+/// overlap, the highest order wins. Cells outside every buffer are 0.
+///
+/// Stream cells are the positive, non-nodata cells of the order raster, with orders rounded to the
+/// nearest integer. Distances are measured in map units between cell centres, so a distance of 0
+/// codes only the stream cells themselves, and a cell is inside a buffer when its distance equals
+/// the buffer distance. Each order's buffer is found with one exact Euclidean distance transform
+/// from all of its stream cells, so the run time does not grow with the buffer widths. Streams of
+/// an order without a row in the table are not buffered, and are reported in a warning.
+///
+/// The distances table is a tab-separated file with a header row naming an `order` and a
+/// `distance` column; blank lines and lines starting with `#` are ignored. For example:
+///
+/// ```text
+/// order    distance
+/// 1        10
+/// 2        20
+/// 3        45
+/// ```
+///
+/// Orders must be positive integers and distances non-negative numbers, and an order may only be
+/// listed once. Errors in the table report its line number.
+///
+/// Optionally, the output may be restricted to a mask raster (`--mask`): cells that are nodata or
+/// 0 in the mask are nodata in the output. Stream cells outside the mask still buffer the cells
+/// inside it.
+///
+/// # See Also
+/// `StrahlerStreamOrder`, `BufferRaster`, `EuclideanDistance`
+pub struct StreamBufferZones {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl StreamBufferZones {
+    pub fn new() -> StreamBufferZones {
+        // public constructor
+        let name = "StreamBufferZones".to_string();
+        let toolbox = "Stream Network Analysis".to_string();
+        let description =
+            "Maps stream buffer zones whose width depends on stream order, coding each cell with the highest order whose buffer contains it."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Stream Order File".to_owned(),
+            flags: vec!["--order".to_owned()],
+            description: "Input raster stream order file, e.g. Strahler order.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Buffer Distances File".to_owned(),
+            flags: vec!["--distances".to_owned()],
+            description:
+                "Input tab-separated table of the buffer distance, in map units, of each stream order."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File (optional)".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description:
+                "Optional input raster mask; cells that are nodata or 0 are nodata in the output."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file of buffer zones coded by stream order.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --order=strahler.tif --distances=buffers.tsv -o=buffers.tif
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --order=strahler.tif --distances=buffers.tsv --mask=watershed.tif -o=buffers.tif", short_exe, name).replace("*", &sep);
+
+        StreamBufferZones {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for StreamBufferZones {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut order_file = String::new();
+        let mut distances_file = String::new();
+        let mut mask_file = String::new();
+        let mut output_file = String::new();
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-order" {
+                order_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-distances" {
+                distances_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-mask" {
+                mask_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        order_file = resolve_path(working_directory, &order_file);
+        distances_file = resolve_path(working_directory, &distances_file);
+        output_file = resolve_path(working_directory, &output_file);
+
+        // Read the table first so that a malformed file fails before the rasters are read.
+        let distances = read_distances(&distances_file)?;
+
+        let has_mask = !mask_file.trim().is_empty();
+        if has_mask {
+            mask_file = resolve_path(working_directory, &mask_file);
+            check_rasters_share_geometry(
+                &[("--order", &order_file), ("--mask", &mask_file)],
+                false,
+            )?;
+        }
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let order = Raster::new(&order_file, "r")?;
+        let mask = if has_mask {
+            Some(Raster::new(&mask_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = order.configs.rows;
+        let columns = order.configs.columns;
+        let order_nodata = order.configs.nodata;
+
+        // The stream cells of each order, in row-major order.
+        let mut stream_orders = vec![0i64; rows * columns];
+        let mut cells_by_order: BTreeMap<i64, usize> = BTreeMap::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                let z = order.get_value(row as isize, col as isize);
+                if z != order_nodata && z > 0f64 {
+                    let k = z.round() as i64;
+                    if k > 0 {
+                        stream_orders[row * columns + col] = k;
+                        *cells_by_order.entry(k).or_insert(0) += 1;
+                    }
+                }
+            }
+        }
+
+        let unbuffered: Vec<String> = cells_by_order
+            .keys()
+            .filter(|k| !distances.contains_key(k))
+            .map(|k| k.to_string())
+            .collect();
+        if !unbuffered.is_empty() {
+            println!(
+                "Warning: the buffer distances file has no distance for stream order(s) {}; these streams are not buffered.",
+                unbuffered.join(", ")
+            );
+        }
+
+        // Orders are buffered from lowest to highest, so that higher orders overwrite lower ones
+        // where their buffers overlap.
+        let cell_size = [order.configs.resolution_x, order.configs.resolution_y];
+        let mut zones = vec![0i64; rows * columns];
+        let num_orders = cells_by_order.len();
+        for (n, (&k, &num_cells)) in cells_by_order.iter().enumerate() {
+            let distance = match distances.get(&k) {
+                Some(&d) => d,
+                None => continue,
+            };
+            let sources: Vec<bool> = stream_orders.iter().map(|&v| v == k).collect();
+            let dist = distance_transform(&sources, rows, columns, cell_size);
+            let mut num_coded = 0usize;
+            for (zone, &d) in zones.iter_mut().zip(dist.iter()) {
+                if d <= distance {
+                    *zone = k;
+                    num_coded += 1;
+                }
+            }
+            if verbose {
+                println!(
+                    "Order {}: {} stream cells buffered by {} to {} cells ({} of {} orders).",
+                    k,
+                    num_cells,
+                    distance,
+                    num_coded,
+                    n + 1,
+                    num_orders
+                );
+            }
+        }
+
+        let mut output = Raster::initialize_using_file(&output_file, &order);
+        output.configs.data_type = DataType::I16;
+        output.configs.nodata = -32768f64;
+        output.configs.photometric_interp = PhotometricInterpretation::Categorical;
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+        for row in 0..rows {
+            for col in 0..columns {
+                let inside = match &mask {
+                    Some(mask) => {
+                        let m = mask.get_value(row as isize, col as isize);
+                        m != mask.configs.nodata && m != 0f64
+                    }
+                    None => true,
+                };
+                let value = if inside {
+                    zones[row * columns + col] as f64
+                } else {
+                    -32768f64
+                };
+                output.set_value(row as isize, col as isize, value);
+            }
+            if verbose && rows > 1 {
+                progress = (100.0_f64 * row as f64 / (rows - 1) as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Stream order file: {}", order_file));
+        output.add_metadata_entry(format!("Buffer distances file: {}", distances_file));
+        if mask.is_some() {
+            output.add_metadata_entry(format!("Mask file: {}", mask_file));
+        }
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn read_distances(file_name: &str) -> Result<BTreeMap<i64, f64>, Error> {
+    let text = fs::read_to_string(file_name)?;
+    parse_distances(file_name, &text)
+}
+
+fn distances_error(file_name: &str, line: usize, message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!(
+            "Buffer distances file '{}', line {}: {}",
+            file_name, line, message
+        ),
+    )
+}
+
+/// Parses the `order` and `distance` columns of a buffer distances table.
+fn parse_distances(file_name: &str, text: &str) -> Result<BTreeMap<i64, f64>, Error> {
+    let mut lines = text
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.trim().is_empty() && !l.trim_start().starts_with('#'));
+    let (header_line, header) = match lines.next() {
+        Some((i, l)) => (i + 1, l),
+        None => {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                format!("Buffer distances file '{}' is empty.", file_name),
+            ))
+        }
+    };
+    let names: Vec<String> = header
+        .split('\t')
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+    let mut columns = [0usize; 2];
+    for (k, name) in ["order", "distance"].iter().enumerate() {
+        columns[k] = match names.iter().position(|n| n == name) {
+            Some(j) => j,
+            None => {
+                return Err(distances_error(
+                    file_name,
+                    header_line,
+                    &format!("the header has no '{}' column", name),
+                ))
+            }
+        };
+    }
+
+    let mut distances = BTreeMap::new();
+    for (i, line) in lines {
+        let line_no = i + 1;
+        let error = |message: String| distances_error(file_name, line_no, &message);
+        let fields: Vec<&str> = line.split('\t').map(|f| f.trim()).collect();
+        if fields.len() != names.len() {
+            return Err(error(format!(
+                "expected {} tab-separated fields, found {}",
+                names.len(),
+                fields.len()
+            )));
+        }
+        let order = match fields[columns[0]].parse::<i64>() {
+            Ok(k) if k > 0 => k,
+            _ => {
+                return Err(error(format!(
+                    "invalid order '{}'; expected a positive integer",
+                    fields[columns[0]]
+                )))
+            }
+        };
+        let distance = match fields[columns[1]].parse::<f64>() {
+            Ok(d) if d.is_finite() && d >= 0f64 => d,
+            _ => {
+                return Err(error(format!(
+                    "invalid distance '{}' for order {}; expected a non-negative number",
+                    fields[columns[1]], order
+                )))
+            }
+        };
+        if distances.insert(order, distance).is_some() {
+            return Err(error(format!("order {} is repeated", order)));
+        }
+    }
+    if distances.is_empty() {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!(
+                "Buffer distances file '{}' has no buffer distances.",
+                file_name
+            ),
+        ));
+    }
+    Ok(distances)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_distances, StreamBufferZones};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    fn write_grid(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        let grid = GridFixture::new(5, 9, f).categorical();
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    fn read_grid(file: &str) -> Vec<Vec<f64>> {
+        let raster = Raster::new(file, "r").unwrap();
+        (0..raster.configs.rows as isize)
+            .map(|r| {
+                (0..raster.configs.columns as isize)
+                    .map(|c| raster.get_value(r, c))
+                    .collect()
+            })
+            .collect()
+    }
+
+    // A first-order stream runs down column 1 and a second-order stream down column 6 of a 5 x 9
+    // grid of 10 m cells. The 30 m first-order buffer and the 20 m second-order buffer both reach
+    // column 4, where the second order wins.
+    #[test]
+    fn test_overlapping_buffers() {
+        let dir = temp_dir("stream_buffer_zones").unwrap();
+        let order = format!("{}/order.tif", dir);
+        let mask = format!("{}/mask.tif", dir);
+        let distances = format!("{}/distances.tsv", dir);
+        let output = format!("{}/buffers.tif", dir);
+        write_grid(&order, &|_, c| match c {
+            1 => 1f64,
+            6 => 2f64,
+            _ => -32768f64,
+        });
+        write_grid(&mask, &|r, _| if r < 4 { 1f64 } else { 0f64 });
+        let run = |table: &str, extra: &[String]| {
+            fs::write(&distances, table).unwrap();
+            let mut args = vec![
+                format!("--order={}", order),
+                format!("--distances={}", distances),
+                format!("--output={}", output),
+            ];
+            args.extend(extra.iter().cloned());
+            StreamBufferZones::new().run(args, "", false)
+        };
+
+        run("order\tdistance\n1\t30\n2\t20\n", &[]).unwrap();
+        let zones = read_grid(&output);
+        assert_eq!(zones[0], [1.0, 1.0, 1.0, 1.0, 2.0, 2.0, 2.0, 2.0, 2.0]);
+        assert!(zones.iter().all(|row| row == &zones[0]));
+
+        // A wider second-order buffer covers the first-order stream itself, leaving the first
+        // order only the cells the second order does not reach.
+        run("# riparian rules\norder\tdistance\n1\t30\n\n2\t50\n", &[]).unwrap();
+        let zones = read_grid(&output);
+        assert_eq!(zones[0], [1.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0, 2.0]);
+
+        // Unbuffered orders are left out, and the mask sets the cells outside it to nodata.
+        run("order\tdistance\n2\t10\n", &[format!("--mask={}", mask)]).unwrap();
+        let zones = read_grid(&output);
+        assert_eq!(zones[0], [0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 2.0, 2.0, 0.0]);
+        assert!(zones[4].iter().all(|&z| z == -32768.0));
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_distances_errors_report_line_numbers() {
+        let error = |text: &str| parse_distances("d.tsv", text).unwrap_err().to_string();
+        assert!(error("").contains("is empty"));
+        assert!(error("order\twidth\n").contains("line 1: the header has no 'distance' column"));
+        assert!(error("order\tdistance\n1\t10\n0\t5\n").contains("line 3: invalid order '0'"));
+        assert!(error("# rules\norder\tdistance\n1\t-5\n")
+            .contains("line 3: invalid distance '-5' for order 1"));
+        assert!(error("order\tdistance\n1\t10\n1\t20\n").contains("line 3: order 1 is repeated"));
+        assert!(error("order\tdistance\n1\n").contains("line 2: expected 2 tab-separated fields"));
+        assert!(error("order\tdistance\n").contains("has no buffer distances"));
+        let distances = parse_distances("d.tsv", "distance\torder\n15.5\t3\n").unwrap();
+        assert_eq!(distances.get(&3), Some(&15.5));
+    }
+}
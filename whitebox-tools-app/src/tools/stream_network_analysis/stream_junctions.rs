@@ -3,6 +3,7 @@ use std::env;
 use std::f64;
 use std::io::{Error, ErrorKind};
 use std::path;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
 use whitebox_raster::*;
 
 pub struct StreamJunctionIdentifier {
@@ -53,6 +54,14 @@ impl StreamJunctionIdentifier {
             default_value: Some("false".to_owned()),
             optional: true,
         });
+        parameters.push(ToolParameter {
+            name: "Fail if the pointer scheme appears to be wrong?".to_owned(),
+            flags: vec!["--strict_pntr".to_owned()],
+            description: "Raise an error, rather than a warning, when the stream network fits the other D8 pointer scheme much better than the selected one.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
 
         let sep: String = path::MAIN_SEPARATOR.to_string();
         let e = format!("{}", env::current_exe().unwrap().display());
@@ -128,6 +137,7 @@ impl WhiteboxTool for StreamJunctionIdentifier {
         let mut streams_file = String::new();
         let mut output_file = String::new();
         let mut esri_style = false;
+        let mut strict_pntr = false;
         let mut background_val = f64::NEG_INFINITY;
 
         if args.len() == 0 {
@@ -163,36 +173,18 @@ impl WhiteboxTool for StreamJunctionIdentifier {
                 if vec.len() == 1 || !vec[1].to_lowercase().contains("false") {
                     esri_style = true;
                 }
+            } else if vec[0].to_lowercase() == "--strict_pntr" {
+                if vec.len() == 1 || !vec[1].to_lowercase().contains("false") {
+                    strict_pntr = true;
+                }
             }
         }
 
-        if verbose {
-            let tool_name = self.get_tool_name();
-            let welcome_len = format!("* Welcome to {} *", tool_name).len().max(28);
-            println!("{}", "*".repeat(welcome_len));
-            println!(
-                "* Welcome to {} {}*",
-                tool_name,
-                " ".repeat(welcome_len - 15 - tool_name.len())
-            );
-            println!(
-                "* Powered by WhiteboxTools {}*",
-                " ".repeat(welcome_len - 28)
-            );
-            println!("* www.whiteboxgeo.com {}*", " ".repeat(welcome_len - 23));
-            println!("{}", "*".repeat(welcome_len));
-        }
+        print_tool_banner(&self.get_tool_name(), verbose);
 
-        let sep: String = path::MAIN_SEPARATOR.to_string();
-        if !d8_file.contains(&sep) && !d8_file.contains("/") {
-            d8_file = format!("{}{}", working_directory, d8_file);
-        }
-        if !streams_file.contains(&sep) && !streams_file.contains("/") {
-            streams_file = format!("{}{}", working_directory, streams_file);
-        }
-        if !output_file.contains(&sep) && !output_file.contains("/") {
-            output_file = format!("{}{}", working_directory, output_file);
-        }
+        d8_file = resolve_path(working_directory, &d8_file);
+        streams_file = resolve_path(working_directory, &streams_file);
+        output_file = resolve_path(working_directory, &output_file);
 
         if verbose {
             println!("Reading pointer data...");
@@ -226,9 +218,31 @@ impl WhiteboxTool for StreamJunctionIdentifier {
         let dx = [1, 1, 1, 0, -1, -1, -1, 0];
         let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
 
-        let mut inflowing_vals = [16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64, 8f64];
-        if esri_style {
-            inflowing_vals = [8f64, 16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64];
+        let inflowing_vals = inflowing_values(esri_style);
+
+        // A wrongly toggled --esri_pntr yields plausible-looking but wrong junction counts,
+        // because the inflow comparisons below then rarely match.
+        let check = check_pointer_scheme(&pntr, &streams);
+        if check.selected_scheme_is_unlikely(esri_style) {
+            let (selected_fit, other_fit) = if esri_style {
+                (check.esri_fit, check.whitebox_fit)
+            } else {
+                (check.whitebox_fit, check.esri_fit)
+            };
+            let msg = format!(
+                "The D8 pointer appears to use the {} scheme rather than the {} scheme: {:.0}% of {} sampled stream cells have an inflowing stream neighbour under the {} scheme, but only {:.0}% under the selected scheme. {} the --esri_pntr flag.",
+                if esri_style { "Whitebox" } else { "ESRI" },
+                if esri_style { "ESRI" } else { "Whitebox" },
+                100.0 * other_fit,
+                check.num_sampled,
+                if esri_style { "Whitebox" } else { "ESRI" },
+                100.0 * selected_fit,
+                if esri_style { "Try removing" } else { "Try adding" },
+            );
+            if strict_pntr {
+                return Err(Error::new(ErrorKind::InvalidInput, msg));
+            }
+            println!("WARNING: {}", msg);
         }
 
         let num_cells = (rows * columns) as usize;
@@ -284,3 +298,203 @@ impl WhiteboxTool for StreamJunctionIdentifier {
         Ok(())
     }
 }
+
+/// The pointer values of the eight neighbours (in dx/dy order) that flow into the centre cell.
+fn inflowing_values(esri_style: bool) -> [f64; 8] {
+    if esri_style {
+        [8f64, 16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64]
+    } else {
+        [16f64, 32f64, 64f64, 128f64, 1f64, 2f64, 4f64, 8f64]
+    }
+}
+
+/// The fraction of sampled stream cells with at least one inflowing stream neighbour under each
+/// pointer scheme. Only headwater cells lack an inflow under the correct scheme.
+struct SchemeCheck {
+    num_sampled: usize,
+    whitebox_fit: f64,
+    esri_fit: f64,
+}
+
+impl SchemeCheck {
+    /// True when the other scheme fits the stream network dramatically better.
+    fn selected_scheme_is_unlikely(&self, esri_style: bool) -> bool {
+        let (selected, other) = if esri_style {
+            (self.esri_fit, self.whitebox_fit)
+        } else {
+            (self.whitebox_fit, self.esri_fit)
+        };
+        self.num_sampled >= 20 && other >= 0.5 && other > 2.0 * selected
+    }
+}
+
+/// Compares the pointer schemes on a bounded sample of stream cells taken from at most 256
+/// evenly spaced rows.
+fn check_pointer_scheme(pntr: &Raster, streams: &Raster) -> SchemeCheck {
+    const MAX_SAMPLES: usize = 10_000;
+    let rows = pntr.configs.rows as isize;
+    let columns = pntr.configs.columns as isize;
+    let row_step = (rows / 256).max(1) as usize;
+    let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+    let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+    let whitebox_vals = inflowing_values(false);
+    let esri_vals = inflowing_values(true);
+    let (mut num_sampled, mut whitebox_hits, mut esri_hits) = (0usize, 0usize, 0usize);
+    'rows: for row in (0..rows).step_by(row_step) {
+        for col in 0..columns {
+            if streams[(row, col)] <= 0.0 {
+                continue;
+            }
+            let (mut whitebox_inflow, mut esri_inflow) = (false, false);
+            for k in 0..8 {
+                let rn = row + dy[k];
+                let cn = col + dx[k];
+                if streams[(rn, cn)] > 0.0 {
+                    let z = pntr[(rn, cn)];
+                    whitebox_inflow |= z == whitebox_vals[k];
+                    esri_inflow |= z == esri_vals[k];
+                }
+            }
+            num_sampled += 1;
+            if whitebox_inflow {
+                whitebox_hits += 1;
+            }
+            if esri_inflow {
+                esri_hits += 1;
+            }
+            if num_sampled >= MAX_SAMPLES {
+                break 'rows;
+            }
+        }
+    }
+    let n = num_sampled.max(1) as f64;
+    SchemeCheck {
+        num_sampled,
+        whitebox_fit: whitebox_hits as f64 / n,
+        esri_fit: esri_hits as f64 / n,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{check_pointer_scheme, StreamJunctionIdentifier};
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 30 x 5 grid with a stream flowing south down column 2, joined at row 10 by a
+    // tributary flowing south-east from (8, 0). Pointers are written in the given scheme.
+    fn write_inputs(dir: &str, esri_style: bool) -> (String, String) {
+        let (south, south_east) = if esri_style {
+            (4f64, 2f64)
+        } else {
+            (8f64, 4f64)
+        };
+        let pntr_file = format!("{}/pntr.tif", dir);
+        let streams_file = format!("{}/streams.tif", dir);
+        let tributary = |r: isize, c: isize| (r == 8 && c == 0) || (r == 9 && c == 1);
+        let pntr = GridFixture::new(
+            30,
+            5,
+            |r, c| {
+                if tributary(r, c) {
+                    south_east
+                } else {
+                    south
+                }
+            },
+        );
+        let streams = GridFixture::new(30, 5, |r, c| {
+            if c == 2 || tributary(r, c) {
+                1f64
+            } else {
+                0f64
+            }
+        });
+        grid_fixture_raster(&pntr_file, &pntr).write().unwrap();
+        grid_fixture_raster(&streams_file, &streams)
+            .write()
+            .unwrap();
+        (pntr_file, streams_file)
+    }
+
+    #[test]
+    fn test_whitebox_pointer_flagged_as_esri() {
+        let dir = temp_dir("stream_junctions_whitebox_pntr").unwrap();
+        let (pntr_file, streams_file) = write_inputs(&dir, false);
+        let pntr = Raster::new(&pntr_file, "r").unwrap();
+        let streams = Raster::new(&streams_file, "r").unwrap();
+        let check = check_pointer_scheme(&pntr, &streams);
+        assert_eq!(check.num_sampled, 32);
+        // only the two headwaters lack an inflow under the correct scheme
+        assert_eq!(check.whitebox_fit, 30.0 / 32.0);
+        assert!(check.esri_fit < 0.1);
+        assert!(!check.selected_scheme_is_unlikely(false));
+        assert!(check.selected_scheme_is_unlikely(true));
+
+        let output = format!("{}/junctions.tif", dir);
+        let args = vec![
+            format!("--d8_pntr={}", pntr_file),
+            format!("--streams={}", streams_file),
+            format!("-o={}", output),
+            "--esri_pntr".to_string(),
+        ];
+        // a warning only, unless --strict_pntr is given
+        StreamJunctionIdentifier::new()
+            .run(args.clone(), "", false)
+            .unwrap();
+        let mut strict_args = args.clone();
+        strict_args.push("--strict_pntr".to_string());
+        let err = StreamJunctionIdentifier::new()
+            .run(strict_args, "", false)
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Try removing the --esri_pntr flag"));
+
+        // the correct scheme passes the strict check and finds the junction
+        let args = vec![
+            format!("--d8_pntr={}", pntr_file),
+            format!("--streams={}", streams_file),
+            format!("-o={}", output),
+            "--strict_pntr".to_string(),
+        ];
+        StreamJunctionIdentifier::new()
+            .run(args, "", false)
+            .unwrap();
+        let junctions = Raster::new(&output, "r").unwrap();
+        assert_eq!(junctions.get_value(10, 2), 2f64);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_esri_pointer_flagged_as_whitebox() {
+        let dir = temp_dir("stream_junctions_esri_pntr").unwrap();
+        let (pntr_file, streams_file) = write_inputs(&dir, true);
+        let pntr = Raster::new(&pntr_file, "r").unwrap();
+        let streams = Raster::new(&streams_file, "r").unwrap();
+        let check = check_pointer_scheme(&pntr, &streams);
+        assert_eq!(check.esri_fit, 30.0 / 32.0);
+        assert!(check.whitebox_fit < 0.1);
+        assert!(check.selected_scheme_is_unlikely(false));
+        assert!(!check.selected_scheme_is_unlikely(true));
+
+        let output = format!("{}/junctions.tif", dir);
+        let err = StreamJunctionIdentifier::new()
+            .run(
+                vec![
+                    format!("--d8_pntr={}", pntr_file),
+                    format!("--streams={}", streams_file),
+                    format!("-o={}", output),
+                    "--strict_pntr".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Try adding the --esri_pntr flag"));
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,518 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::fs::File;
+use std::io::{self, Error, ErrorKind, Write};
+use std::path;
+use whitebox_common::algorithms::{d8_path_length, D8Walker};
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool measures the geometry of each link in a stream network, writing a tab-separated table
+/// (`--output`) with the link identifier, the along-channel length (`length_m`), the straight-line
+/// distance between the link's end cells (`chord_m`), the sinuosity ratio (`length_m / chord_m`), and the
+/// map coordinates of the upstream and downstream end cells. Lengths are measured through the centres of
+/// the link's cells using the D8 flow path, with diagonal steps counted as the cell diagonal. Because a
+/// single-cell link has neither a length nor a chord, its sinuosity is defined as 1.0.
+///
+/// The stream network is defined by the positive, non-nodata cells of the streams raster (`--streams`)
+/// and the D8 pointer raster (`--d8_pntr`). Links are identified by walking downstream from each channel
+/// head and junction to the next junction, as in `StreamLinkIdentifier`, and are numbered from 1 in
+/// row-major order of their upstream-most cells. If a link identifier raster (`--link_ids`) is supplied,
+/// its values are used as the link identifiers instead, and links are also split wherever the identifier
+/// changes. Optionally, a raster (`--output_raster`) may be written in which each link's cells carry its
+/// sinuosity; all other cells are assigned the nodata value.
+///
+/// # See Also
+/// `StreamLinkIdentifier`, `StreamLinkLength`, `D8Pointer`
+pub struct StreamLinkLengthAndSinuosity {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl StreamLinkLengthAndSinuosity {
+    pub fn new() -> StreamLinkLengthAndSinuosity {
+        // public constructor
+        let name = "StreamLinkLengthAndSinuosity".to_string();
+        let toolbox = "Stream Network Analysis".to_string();
+        let description =
+            "Measures the length, chord distance, and sinuosity of each link in a stream network."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input Streams File".to_owned(),
+            flags: vec!["--streams".to_owned()],
+            description: "Input raster streams file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input D8 Pointer File".to_owned(),
+            flags: vec!["--d8_pntr".to_owned()],
+            description: "Input raster D8 pointer file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Stream Link ID File (optional)".to_owned(),
+            flags: vec!["--link_ids".to_owned()],
+            description: "Optional input raster stream link ID file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Table File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output tab-separated table of link geometry metrics.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Text),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Sinuosity Raster File (optional)".to_owned(),
+            flags: vec!["--output_raster".to_owned()],
+            description: "Optional output raster in which each link's cells carry its sinuosity."
+                .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Does the pointer file use the ESRI pointer scheme?".to_owned(),
+            flags: vec!["--esri_pntr".to_owned()],
+            description: "D8 pointer uses the ESRI style scheme.".to_owned(),
+            parameter_type: ParameterType::Boolean,
+            default_value: Some("false".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --streams=streams.tif --d8_pntr=D8.tif -o=links.tsv
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --streams=streams.tif --d8_pntr=D8.tif --link_ids=link_ids.tif -o=links.tsv --output_raster=sinuosity.tif --esri_pntr", short_exe, name).replace("*", &sep);
+
+        StreamLinkLengthAndSinuosity {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for StreamLinkLengthAndSinuosity {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut streams_file = String::new();
+        let mut d8_file = String::new();
+        let mut link_ids_file = String::new();
+        let mut output_file = String::new();
+        let mut raster_file = String::new();
+        let mut esri_style = false;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-streams" {
+                streams_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-d8_pntr" {
+                d8_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-link_ids" {
+                link_ids_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-output_raster" {
+                raster_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-esri_pntr" || flag_val == "-esri_style" {
+                if vec.len() == 1 || !vec[1].to_string().to_lowercase().contains("false") {
+                    esri_style = true;
+                }
+            }
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        streams_file = resolve_path(working_directory, &streams_file);
+        d8_file = resolve_path(working_directory, &d8_file);
+        link_ids_file = resolve_path(working_directory, &link_ids_file);
+        output_file = resolve_path(working_directory, &output_file);
+        raster_file = resolve_path(working_directory, &raster_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let streams = Raster::new(&streams_file, "r")?;
+        let pntr = Raster::new(&d8_file, "r")?;
+        let link_ids = if !link_ids_file.is_empty() {
+            Some(Raster::new(&link_ids_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = streams.configs.rows as isize;
+        let columns = streams.configs.columns as isize;
+        let streams_nodata = streams.configs.nodata;
+        let pntr_nodata = pntr.configs.nodata;
+        let res_x = streams.configs.resolution_x;
+        let res_y = streams.configs.resolution_y;
+
+        // make sure the input files have the same size
+        if pntr.configs.rows != streams.configs.rows
+            || pntr.configs.columns != streams.configs.columns
+            || link_ids.as_ref().map_or(false, |ids| {
+                ids.configs.rows != streams.configs.rows
+                    || ids.configs.columns != streams.configs.columns
+            })
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "The input files must have the same number of rows and columns and spatial extent.",
+            ));
+        }
+
+        let walker = D8Walker::new(rows, columns, pntr_nodata, esri_style, |row, col| {
+            pntr.get_value(row, col)
+        });
+
+        let is_stream = |row: isize, col: isize| {
+            let z = streams.get_value(row, col);
+            z != streams_nodata && z > 0f64
+        };
+        let mut bad_pointer = false;
+        for row in 0..rows {
+            for col in 0..columns {
+                if is_stream(row, col) && walker.has_invalid_pointer(row, col) {
+                    bad_pointer = true;
+                }
+            }
+        }
+        if bad_pointer {
+            return Err(Error::new(ErrorKind::InvalidInput,
+                "An unexpected value has been identified in the pointer image. This tool requires a pointer grid that has been created using either the D8 or Rho8 tools."));
+        }
+
+        if verbose {
+            println!("Tracing stream links...")
+        };
+        let mut links: Vec<(f64, Vec<(isize, isize)>)> = vec![];
+        for (i, link) in walker.stream_links(is_stream).into_iter().enumerate() {
+            match link_ids {
+                Some(ref ids) => {
+                    // split the traced link wherever the supplied identifier changes
+                    let mut current_id = ids.get_value(link[0].0, link[0].1);
+                    let mut piece = vec![];
+                    for cell in link {
+                        let id = ids.get_value(cell.0, cell.1);
+                        if id != current_id && !piece.is_empty() {
+                            links.push((current_id, piece));
+                            piece = vec![];
+                        }
+                        current_id = id;
+                        piece.push(cell);
+                    }
+                    links.push((current_id, piece));
+                }
+                None => links.push(((i + 1) as f64, link)),
+            }
+        }
+
+        let num_links = links.len();
+        let mut metrics = Vec::with_capacity(num_links);
+        for (i, (id, cells)) in links.iter().enumerate() {
+            let length = d8_path_length(cells, res_x, res_y);
+            let (us_row, us_col) = cells[0];
+            let (ds_row, ds_col) = cells[cells.len() - 1];
+            let chord = ((((ds_row - us_row) as f64) * res_y).powi(2)
+                + (((ds_col - us_col) as f64) * res_x).powi(2))
+            .sqrt();
+            let sinuosity = if cells.len() == 1 || chord <= 0f64 {
+                1f64
+            } else {
+                length / chord
+            };
+            metrics.push(LinkMetrics {
+                id: *id,
+                length,
+                chord,
+                sinuosity,
+                us_x: streams.get_x_from_column(us_col),
+                us_y: streams.get_y_from_row(us_row),
+                ds_x: streams.get_x_from_column(ds_col),
+                ds_y: streams.get_y_from_row(ds_row),
+                num_cells: cells.len(),
+            });
+
+            if verbose {
+                progress = (100.0_f64 * (i + 1) as f64 / num_links as f64) as usize;
+                if progress != old_progress {
+                    println!("Measuring links: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        if verbose {
+            println!("Found {} stream links.", num_links);
+            println!("Writing link table to {}.", output_file);
+        }
+        write_metrics_to_tsv(&metrics, &output_file)?;
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if !raster_file.is_empty() {
+            let mut output = Raster::initialize_using_file(&raster_file, &streams);
+            output.configs.data_type = DataType::F32;
+            output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+            output.configs.palette = "spectrum.plt".to_string();
+            output.reinitialize_values(output.configs.nodata);
+            for ((_, cells), m) in links.iter().zip(metrics.iter()) {
+                for &(row, col) in cells {
+                    output.set_value(row, col, m.sinuosity);
+                }
+            }
+            output.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            output.add_metadata_entry(format!("Input streams file: {}", streams_file));
+            output.add_metadata_entry(format!("Input d8 pointer file: {}", d8_file));
+            if !link_ids_file.is_empty() {
+                output.add_metadata_entry(format!("Input link ID file: {}", link_ids_file));
+            }
+            output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+            if verbose {
+                println!("Saving data...")
+            };
+            let _ = match output.write() {
+                Ok(_) => {
+                    if verbose {
+                        println!("Output file written")
+                    }
+                }
+                Err(e) => return Err(e),
+            };
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+struct LinkMetrics {
+    id: f64,
+    length: f64,
+    chord: f64,
+    sinuosity: f64,
+    us_x: f64,
+    us_y: f64,
+    ds_x: f64,
+    ds_y: f64,
+    num_cells: usize,
+}
+
+fn write_metrics_to_tsv(metrics: &[LinkMetrics], file_path: &str) -> io::Result<()> {
+    let mut file = File::create(file_path)?;
+    writeln!(
+        &mut file,
+        "link_id\tlength_m\tchord_m\tsinuosity\tus_x\tus_y\tds_x\tds_y\tnum_cells"
+    )?;
+    for m in metrics {
+        writeln!(
+            &mut file,
+            "{}\t{:.3}\t{:.3}\t{:.4}\t{}\t{}\t{}\t{}\t{}",
+            m.id, m.length, m.chord, m.sinuosity, m.us_x, m.us_y, m.ds_x, m.ds_y, m.num_cells
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::StreamLinkLengthAndSinuosity;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::algorithms::d8_path_length;
+    use whitebox_common::test_support::{read_tsv, temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    // A 6 x 6 grid of 10 m cells with a meandering link running from the top-left
+    // corner to the bottom edge, and an isolated single-cell link in the corner.
+    const LINK: [(isize, isize); 6] = [(0, 0), (1, 1), (2, 1), (3, 2), (4, 2), (5, 2)];
+
+    fn write_inputs(dir: &str) {
+        let mut streams = GridFixture::new(6, 6, |_, _| 0f64);
+        let mut pntr = GridFixture::new(6, 6, |_, _| 8f64); // south
+        for (i, &(r, c)) in LINK.iter().enumerate() {
+            streams.set_value(r, c, 1f64);
+            if i + 1 < LINK.len() && LINK[i + 1].1 != c {
+                pntr.set_value(r, c, 4f64); // south-east
+            }
+        }
+        streams.set_value(5, 5, 1f64);
+        grid_fixture_raster(&format!("{}/streams.tif", dir), &streams)
+            .write()
+            .unwrap();
+        grid_fixture_raster(&format!("{}/d8.tif", dir), &pntr)
+            .write()
+            .unwrap();
+    }
+
+    #[test]
+    fn test_link_lengths_and_sinuosity() {
+        let dir = temp_dir("stream_link_length_and_sinuosity").unwrap();
+        write_inputs(&dir);
+
+        let args = vec![
+            format!("--streams={}/streams.tif", dir),
+            format!("--d8_pntr={}/d8.tif", dir),
+            format!("--output={}/links.tsv", dir),
+            format!("--output_raster={}/sinuosity.tif", dir),
+        ];
+        StreamLinkLengthAndSinuosity::new()
+            .run(args, "", false)
+            .unwrap();
+
+        let rows: Vec<Vec<f64>> = read_tsv(&format!("{}/links.tsv", dir))
+            .unwrap()
+            .iter()
+            .skip(1)
+            .map(|row| row.iter().map(|v| v.parse::<f64>().unwrap()).collect())
+            .collect();
+        assert_eq!(rows.len(), 2);
+
+        let expected_length = d8_path_length(&LINK, 10f64, 10f64);
+        let expected_chord = (50f64 * 50f64 + 20f64 * 20f64).sqrt();
+        assert_eq!(rows[0][0], 1f64);
+        assert!((rows[0][1] - expected_length).abs() < 1e-3);
+        assert!((rows[0][2] - expected_chord).abs() < 1e-3);
+        assert!((rows[0][3] - expected_length / expected_chord).abs() < 1e-4);
+        assert_eq!((rows[0][4], rows[0][5]), (5f64, 55f64));
+        assert_eq!((rows[0][6], rows[0][7]), (25f64, 5f64));
+        assert_eq!(rows[0][8], 6f64);
+
+        // the isolated single-cell link
+        assert_eq!((rows[1][1], rows[1][2], rows[1][3]), (0f64, 0f64, 1f64));
+
+        let raster = Raster::new(&format!("{}/sinuosity.tif", dir), "r").unwrap();
+        assert!((raster.get_value(3, 2) - expected_length / expected_chord).abs() < 1e-4);
+        assert_eq!(raster.get_value(5, 5), 1f64);
+        assert_eq!(raster.get_value(0, 5), raster.configs.nodata);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
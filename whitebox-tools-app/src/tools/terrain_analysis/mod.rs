@@ -45,6 +45,7 @@ mod multiscale_roughness_signature;
 mod multiscale_std_dev_normals;
 mod multiscale_std_dev_normals_signature;
 mod multiscale_topographic_position_image;
+mod no_data_fill;
 mod num_downslope_neighbours;
 mod num_upslope_neighbours;
 mod pennock_landform_class;
@@ -59,6 +60,7 @@ mod remove_off_terrain_objects;
 mod ruggedness_index;
 mod sediment_transport_index;
 mod slope;
+mod slope_aspect_curvature_bundle;
 mod slope_vs_elev_plot;
 mod spherical_std_dev_of_normals;
 mod standard_deviation_of_slope;
@@ -118,6 +120,7 @@ pub use self::multiscale_roughness_signature::MultiscaleRoughnessSignature;
 pub use self::multiscale_std_dev_normals::MultiscaleStdDevNormals;
 pub use self::multiscale_std_dev_normals_signature::MultiscaleStdDevNormalsSignature;
 pub use self::multiscale_topographic_position_image::MultiscaleTopographicPositionImage;
+pub use self::no_data_fill::NoDataFill;
 pub use self::num_downslope_neighbours::NumDownslopeNeighbours;
 pub use self::num_upslope_neighbours::NumUpslopeNeighbours;
 pub use self::pennock_landform_class::PennockLandformClass;
@@ -132,6 +135,7 @@ pub use self::remove_off_terrain_objects::RemoveOffTerrainObjects;
 pub use self::ruggedness_index::RuggednessIndex;
 pub use self::sediment_transport_index::SedimentTransportIndex;
 pub use self::slope::Slope;
+pub use self::slope_aspect_curvature_bundle::SlopeAspectCurvatureBundle;
 pub use self::slope_vs_elev_plot::SlopeVsElevationPlot;
 pub use self::spherical_std_dev_of_normals::SphericalStdDevOfNormals;
 pub use self::standard_deviation_of_slope::StandardDeviationOfSlope;
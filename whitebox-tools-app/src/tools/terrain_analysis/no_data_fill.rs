@@ -0,0 +1,682 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::collections::VecDeque;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use whitebox_common::structures::Array2D;
+use whitebox_common::utils::{print_tool_banner, resolve_path};
+use whitebox_raster::*;
+
+/// This tool fills small NoData gaps, such as the isolated pinholes that occur in DEM mosaics, which
+/// would otherwise cause slope, flow pointer, and other neighbourhood-based tools to fail within a basin.
+/// A gap is a group of NoData cells in the input raster (`--input`) connected through any of their eight
+/// neighbours. Gaps containing no more than `--max_gap_cells` cells are filled by interpolating from the
+/// ring of valid cells that surround them, while larger gaps are left untouched; the number and locations
+/// of the larger gaps are reported when the tool is run in verbose mode, and their count is recorded in
+/// the output metadata.
+///
+/// Two interpolation methods are available (`--method`). With `idw` (the default), each gap cell is assigned
+/// the inverse-distance weighted (power 2) average of the ring cells, so that filled values vary smoothly
+/// across the gap. With `mean`, every cell of a gap is assigned the mean of the ring cells.
+///
+/// When a watershed mask (`--mask`) is supplied, only NoData cells inside the mask (non-zero, non-NoData
+/// mask cells) are considered for filling, and the size of a gap is the number of its cells inside the
+/// mask. A gap that straddles the mask boundary is therefore filled inside the mask and left as NoData
+/// outside of it. Valid cells outside of the mask are still used for interpolation. Without a mask, gaps
+/// that touch the edge of the raster are treated as the background surrounding the data and are neither
+/// filled nor reported. The mask must have the same number of rows and columns as the input raster.
+///
+/// The optional `--filled` raster flags the cells that were filled (1) and all other cells (0).
+///
+/// # See Also
+/// `FillMissingData`, `SetNodataValue`, `IsNoData`
+pub struct NoDataFill {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl NoDataFill {
+    pub fn new() -> NoDataFill {
+        // public constructor
+        let name = "NoDataFill".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description =
+            "Fills small NoData gaps in a raster, optionally within a watershed mask, by interpolating from the surrounding valid cells."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input File".to_owned(),
+            flags: vec!["-i".to_owned(), "--input".to_owned()],
+            description: "Input raster file, e.g. a DEM.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File (optional)".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description: "Optional watershed mask raster; only NoData cells inside the mask (non-zero) are filled.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output File".to_owned(),
+            flags: vec!["-o".to_owned(), "--output".to_owned()],
+            description: "Output raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Filled Cells File (optional)".to_owned(),
+            flags: vec!["--filled".to_owned()],
+            description:
+                "Optional output raster flagging filled cells (1) and all other cells (0)."
+                    .to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Maximum Gap Size (cells)".to_owned(),
+            flags: vec!["--max_gap_cells".to_owned()],
+            description: "Largest gap, in grid cells, that will be filled.".to_owned(),
+            parameter_type: ParameterType::Integer,
+            default_value: Some("10".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Interpolation Method".to_owned(),
+            flags: vec!["--method".to_owned()],
+            description: "Interpolation method: 'idw' (inverse-distance weighted) or 'mean' (mean of the surrounding ring of valid cells).".to_owned(),
+            parameter_type: ParameterType::OptionList(vec!["idw".to_owned(), "mean".to_owned()]),
+            default_value: Some("idw".to_owned()),
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif -o=dem_filled.tif --max_gap_cells=10
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" -i=dem.tif --mask=watershed.tif -o=dem_filled.tif --filled=filled.tif --max_gap_cells=25 --method=mean", short_exe, name).replace("*", &sep);
+
+        NoDataFill {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+impl WhiteboxTool for NoDataFill {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut mask_file = String::new();
+        let mut output_file = String::new();
+        let mut filled_file = String::new();
+        let mut max_gap_cells = 10usize;
+        let mut use_idw = true;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            if flag_val == "-i" || flag_val == "-input" {
+                input_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-mask" {
+                mask_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-o" || flag_val == "-output" {
+                output_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-filled" {
+                filled_file = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+            } else if flag_val == "-max_gap_cells" {
+                let value = if keyval {
+                    vec[1].to_string()
+                } else {
+                    args[i + 1].to_string()
+                };
+                max_gap_cells = value.trim().parse::<usize>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Error parsing --max_gap_cells value '{}'.", value),
+                    )
+                })?;
+            } else if flag_val == "-method" {
+                let value = if keyval {
+                    vec[1].to_lowercase()
+                } else {
+                    args[i + 1].to_lowercase()
+                };
+                use_idw = match value.trim() {
+                    "idw" => true,
+                    "mean" => false,
+                    _ => {
+                        return Err(Error::new(
+                            ErrorKind::InvalidInput,
+                            format!(
+                                "Unrecognized --method '{}'; expected 'idw' or 'mean'.",
+                                value
+                            ),
+                        ))
+                    }
+                };
+            }
+        }
+
+        if input_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Input raster file (--input) not specified.",
+            ));
+        }
+        if output_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Output raster file (--output) not specified.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(working_directory, &input_file);
+        mask_file = resolve_path(working_directory, &mask_file);
+        output_file = resolve_path(working_directory, &output_file);
+        filled_file = resolve_path(working_directory, &filled_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Raster::new(&input_file, "r")?;
+        let mask = if !mask_file.is_empty() {
+            Some(Raster::new(&mask_file, "r")?)
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let res_x = input.configs.resolution_x;
+        let res_y = input.configs.resolution_y;
+
+        if let Some(ref mask) = mask {
+            if mask.configs.rows != input.configs.rows
+                || mask.configs.columns != input.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+        }
+
+        // A NoData cell that may be filled.
+        let is_candidate = |row: isize, col: isize| -> bool {
+            if input.get_value(row, col) != nodata {
+                return false;
+            }
+            match mask {
+                Some(ref mask) => {
+                    let m = mask.get_value(row, col);
+                    m != mask.configs.nodata && m != 0f64
+                }
+                None => true,
+            }
+        };
+
+        let dx = [1, 1, 1, 0, -1, -1, -1, 0];
+        let dy = [-1, 0, 1, 1, 1, 0, -1, -1];
+
+        let mut output = Raster::initialize_using_file(&output_file, &input);
+        let mut filled: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut visited: Array2D<u8> = Array2D::new(rows, columns, 0u8, 0u8)?;
+        let mut num_filled_gaps = 0usize;
+        let mut num_filled_cells = 0usize;
+        let mut large_gaps: Vec<LargeGap> = vec![];
+        let mut unfillable_gaps = 0usize;
+        let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+        for row in 0..rows {
+            for col in 0..columns {
+                if visited.get_value(row, col) == 1u8 || !is_candidate(row, col) {
+                    continue;
+                }
+
+                // Gather the gap and the ring of valid cells surrounding it.
+                let mut cells: Vec<(isize, isize)> = vec![];
+                let mut ring: Vec<(isize, isize, f64)> = vec![];
+                let mut touches_edge = false;
+                let (mut sum_row, mut sum_col) = (0f64, 0f64);
+                visited.set_value(row, col, 1u8);
+                queue.push_back((row, col));
+                while let Some((r, c)) = queue.pop_front() {
+                    cells.push((r, c));
+                    sum_row += r as f64;
+                    sum_col += c as f64;
+                    for n in 0..8 {
+                        let (rn, cn) = (r + dy[n], c + dx[n]);
+                        if rn < 0 || rn >= rows || cn < 0 || cn >= columns {
+                            touches_edge = true;
+                            continue;
+                        }
+                        if visited.get_value(rn, cn) == 1u8 {
+                            continue;
+                        }
+                        let z = input.get_value(rn, cn);
+                        if z != nodata {
+                            visited.set_value(rn, cn, 1u8);
+                            ring.push((rn, cn, z));
+                        } else if is_candidate(rn, cn) {
+                            visited.set_value(rn, cn, 1u8);
+                            queue.push_back((rn, cn));
+                        }
+                    }
+                }
+                // Valid cells are shared between the rings of neighbouring gaps.
+                for &(r, c, _) in &ring {
+                    visited.set_value(r, c, 0u8);
+                }
+
+                if touches_edge && mask.is_none() {
+                    continue; // the background surrounding the data
+                }
+                if cells.len() > max_gap_cells {
+                    let n = cells.len() as f64;
+                    large_gaps.push(LargeGap {
+                        num_cells: cells.len(),
+                        row: (sum_row / n).round() as isize,
+                        col: (sum_col / n).round() as isize,
+                    });
+                    continue;
+                }
+                if ring.is_empty() {
+                    unfillable_gaps += 1;
+                    continue;
+                }
+
+                let mean = ring.iter().map(|&(_, _, z)| z).sum::<f64>() / ring.len() as f64;
+                for &(r, c) in &cells {
+                    let z = if use_idw {
+                        let (mut sum_w, mut sum_wz) = (0f64, 0f64);
+                        for &(rn, cn, zn) in &ring {
+                            let dist_x = (cn - c) as f64 * res_x;
+                            let dist_y = (rn - r) as f64 * res_y;
+                            let w = 1f64 / (dist_x * dist_x + dist_y * dist_y);
+                            sum_w += w;
+                            sum_wz += w * zn;
+                        }
+                        sum_wz / sum_w
+                    } else {
+                        mean
+                    };
+                    output.set_value(r, c, z);
+                    filled.set_value(r, c, 1u8);
+                }
+                num_filled_gaps += 1;
+                num_filled_cells += cells.len();
+            }
+            if verbose {
+                progress = (100.0_f64 * (row + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Filling gaps: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        for row in 0..rows {
+            for col in 0..columns {
+                if filled.get_value(row, col) == 0u8 {
+                    output.set_value(row, col, input.get_value(row, col));
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!(
+                "Filled {} gaps ({} cells).",
+                num_filled_gaps, num_filled_cells
+            );
+            if unfillable_gaps > 0 {
+                println!(
+                    "{} gaps have no valid neighbouring cells and were left unfilled.",
+                    unfillable_gaps
+                );
+            }
+            if !large_gaps.is_empty() {
+                println!(
+                    "{} gaps larger than {} cells were left unfilled:",
+                    large_gaps.len(),
+                    max_gap_cells
+                );
+                for gap in &large_gaps {
+                    println!(
+                        "  {} cells centred near row {}, column {} (x = {}, y = {})",
+                        gap.num_cells,
+                        gap.row,
+                        gap.col,
+                        input.get_x_from_column(gap.col),
+                        input.get_y_from_row(gap.row)
+                    );
+                }
+            }
+        }
+
+        output.configs.display_min = input.configs.display_min;
+        output.configs.display_max = input.configs.display_max;
+        output.add_metadata_entry(format!(
+            "Created by whitebox_tools\' {} tool",
+            self.get_tool_name()
+        ));
+        output.add_metadata_entry(format!("Input file: {}", input_file));
+        if !mask_file.is_empty() {
+            output.add_metadata_entry(format!("Mask file: {}", mask_file));
+        }
+        output.add_metadata_entry(format!("Maximum gap size (cells): {}", max_gap_cells));
+        output.add_metadata_entry(format!("Method: {}", if use_idw { "idw" } else { "mean" }));
+        output.add_metadata_entry(format!(
+            "Filled gaps: {} ({} cells)",
+            num_filled_gaps, num_filled_cells
+        ));
+        output.add_metadata_entry(format!(
+            "Gaps left unfilled: {}",
+            large_gaps.len() + unfillable_gaps
+        ));
+        output.add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let _ = match output.write() {
+            Ok(_) => {
+                if verbose {
+                    println!("Output file written")
+                }
+            }
+            Err(e) => return Err(e),
+        };
+
+        if !filled_file.is_empty() {
+            let mut flags = Raster::initialize_using_file(&filled_file, &input);
+            flags.configs.data_type = DataType::U8;
+            flags.configs.photometric_interp = PhotometricInterpretation::Categorical;
+            flags.configs.nodata = 255f64;
+            for row in 0..rows {
+                for col in 0..columns {
+                    flags.set_value(row, col, filled.get_value(row, col) as f64);
+                }
+            }
+            flags.configs.display_min = 0f64;
+            flags.configs.display_max = 1f64;
+            flags.add_metadata_entry(format!(
+                "Created by whitebox_tools\' {} tool",
+                self.get_tool_name()
+            ));
+            flags.add_metadata_entry(format!("Input file: {}", input_file));
+            flags.write()?;
+        }
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// A gap too large to fill, located by its centre cell.
+struct LargeGap {
+    num_cells: usize,
+    row: isize,
+    col: isize,
+}
+
+#[cfg(test)]
+mod test {
+    use super::NoDataFill;
+    use crate::tools::WhiteboxTool;
+    use std::fs;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    const NODATA: f64 = -32768f64;
+
+    // Writes a 7 x 7 raster with 10 m cells.
+    fn write_raster(file: &str, f: &dyn Fn(isize, isize) -> f64) {
+        grid_fixture_raster(file, &GridFixture::new(7, 7, f).with_nodata(NODATA))
+            .write()
+            .unwrap();
+    }
+
+    // A plane rising to the east, which IDW reproduces exactly at the centre of a symmetric ring.
+    fn plane(c: isize) -> f64 {
+        100f64 + c as f64
+    }
+
+    #[test]
+    fn test_single_cell_hole() {
+        let dir = temp_dir("no_data_fill_single_cell").unwrap();
+        let input = format!("{}/dem.tif", dir);
+        write_raster(&input, &|r, c| {
+            if (r, c) == (3, 3) {
+                NODATA
+            } else {
+                plane(c)
+            }
+        });
+        let output = format!("{}/filled_dem.tif", dir);
+        let filled = format!("{}/filled.tif", dir);
+        NoDataFill::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("-o={}", output),
+                    format!("--filled={}", filled),
+                    "--max_gap_cells=1".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let output = Raster::new(&output, "r").unwrap();
+        assert!((output.get_value(3, 3) - plane(3)).abs() < 1e-4);
+        assert_eq!(output.get_value(3, 4), plane(4));
+        let filled = Raster::new(&filled, "r").unwrap();
+        for r in 0..7isize {
+            for c in 0..7isize {
+                let expected = if (r, c) == (3, 3) { 1f64 } else { 0f64 };
+                assert_eq!(filled.get_value(r, c), expected);
+            }
+        }
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_hole_larger_than_threshold_is_left_unfilled() {
+        let dir = temp_dir("no_data_fill_large_hole").unwrap();
+        let input = format!("{}/dem.tif", dir);
+        // a 3 x 3 hole, plus a single-cell hole using the mean-of-ring method
+        write_raster(&input, &|r, c| {
+            if ((2..=4).contains(&r) && (1..=3).contains(&c)) || (r, c) == (1, 5) {
+                NODATA
+            } else {
+                plane(c)
+            }
+        });
+        let output = format!("{}/filled_dem.tif", dir);
+        NoDataFill::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("-o={}", output),
+                    "--max_gap_cells=8".to_string(),
+                    "--method=mean".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let output = Raster::new(&output, "r").unwrap();
+        for r in 2..=4isize {
+            for c in 1..=3isize {
+                assert_eq!(output.get_value(r, c), NODATA);
+            }
+        }
+        // The ring of (1, 5) holds three cells in each of columns 4, 5, and 6, less the
+        // hole's own cell; its mean is the plane's value at column 5.
+        assert!((output.get_value(1, 5) - plane(5)).abs() < 1e-4);
+        fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_hole_at_mask_boundary() {
+        let dir = temp_dir("no_data_fill_mask_boundary").unwrap();
+        let input = format!("{}/dem.tif", dir);
+        let mask = format!("{}/mask.tif", dir);
+        // The mask covers columns 0-3; the hole spans columns 3 and 4 of row 3, and a
+        // second hole lies entirely outside the mask.
+        write_raster(&input, &|r, c| {
+            if (r == 3 && (3..=4).contains(&c)) || (r, c) == (1, 5) {
+                NODATA
+            } else {
+                plane(c)
+            }
+        });
+        write_raster(&mask, &|_, c| if c <= 3 { 1f64 } else { 0f64 });
+        let output = format!("{}/filled_dem.tif", dir);
+        let filled = format!("{}/filled.tif", dir);
+        NoDataFill::new()
+            .run(
+                vec![
+                    format!("-i={}", input),
+                    format!("--mask={}", mask),
+                    format!("-o={}", output),
+                    format!("--filled={}", filled),
+                    "--max_gap_cells=1".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let output = Raster::new(&output, "r").unwrap();
+        // Only the cell inside the mask is filled (and counted towards the gap size), using
+        // the valid cells on both sides of the mask boundary.
+        let z = output.get_value(3, 3);
+        assert!(z != NODATA);
+        assert!(z > plane(2) && z < plane(5));
+        assert_eq!(output.get_value(3, 4), NODATA);
+        assert_eq!(output.get_value(1, 5), NODATA);
+        let filled = Raster::new(&filled, "r").unwrap();
+        assert_eq!(filled.get_value(3, 3), 1f64);
+        assert_eq!(filled.get_value(3, 4), 0f64);
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,681 @@
+/*
+This tool is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::tools::*;
+use std::env;
+use std::f64;
+use std::io::{Error, ErrorKind};
+use std::path;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+use whitebox_common::utils::{
+    get_formatted_elapsed_time, haversine_distance, print_tool_banner, resolve_path,
+};
+use whitebox_raster::*;
+
+/// This tool calculates slope, aspect, plan curvature, and profile curvature from a digital elevation
+/// model (DEM) in a single pass, reading the DEM once rather than once per derivative as when running
+/// `Slope`, `Aspect`, `PlanCurvature`, and `ProfileCurvature` separately. Each derivative is written only
+/// if its output file is specified (`--slope`, `--aspect`, `--plan_curv`, `--prof_curv`); at least one
+/// output is required.
+///
+/// The partial derivatives are estimated from the 3x3 neighbourhood of each cell. The first derivatives
+/// use the third-order finite difference of Horn (1981) and the second derivatives use the kernels of
+/// Zevenbergen and Thorne (1987). Both are exact for planes and quadratic surfaces. Slope units
+/// (`--units`) are degrees (default), radians, percent, or ratio (rise over run). Aspect is the azimuth of
+/// the downslope direction in degrees clockwise from north, and is -1 on flat cells. Plan and profile
+/// curvature follow the definitions used by `PlanCurvature` and `ProfileCurvature` (Florinsky, 2016) and
+/// are measured in units of m<sup>-1</sup>; both are 0 on flat cells.
+///
+/// A neighbour that is NoData, or lies beyond the edge of the raster, is replaced by extrapolating
+/// linearly from the cell's other neighbours, which leaves the derivatives of a planar surface unchanged;
+/// the window is treated as flat in a direction in which both of the cell's neighbours are missing. Cells
+/// along the edges of the DEM and around NoData gaps therefore receive values, and only NoData DEM cells
+/// are NoData in the outputs. When a mask raster (`--mask`) is supplied, outputs are calculated only for
+/// cells with non-zero, non-NoData mask values, although elevations outside the mask are still used in the
+/// neighbourhoods of cells along the mask edge. The mask must have the same number of rows and columns as
+/// the DEM.
+///
+/// The *Z conversion factor* (`--zfactor`) multiplies the elevations, for when the vertical and horizontal
+/// units differ. For DEMs in geographic coordinates, the grid spacing of each row is converted to metres.
+///
+/// # References
+/// Florinsky, I. (2016). Digital terrain analysis in soil science and geology. Academic Press.
+///
+/// Horn, B. K. (1981). Hill shading and the reflectance map. Proceedings of the IEEE, 69(1), 14-47.
+///
+/// Zevenbergen, L. W., and Thorne, C. R. (1987). Quantitative analysis of land surface topography. Earth
+/// Surface Processes and Landforms, 12(1), 47-56.
+///
+/// # See Also
+/// `Slope`, `Aspect`, `PlanCurvature`, `ProfileCurvature`
+pub struct SlopeAspectCurvatureBundle {
+    name: String,
+    description: String,
+    toolbox: String,
+    parameters: Vec<ToolParameter>,
+    example_usage: String,
+}
+
+impl SlopeAspectCurvatureBundle {
+    pub fn new() -> SlopeAspectCurvatureBundle {
+        // public constructor
+        let name = "SlopeAspectCurvatureBundle".to_string();
+        let toolbox = "Geomorphometric Analysis".to_string();
+        let description =
+            "Calculates slope, aspect, plan curvature, and profile curvature rasters from a DEM in a single pass."
+                .to_string();
+
+        let mut parameters = vec![];
+        parameters.push(ToolParameter {
+            name: "Input DEM File".to_owned(),
+            flags: vec!["-i".to_owned(), "--dem".to_owned()],
+            description: "Input raster DEM file.".to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: false,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Input Mask File (optional)".to_owned(),
+            flags: vec!["--mask".to_owned()],
+            description:
+                "Optional mask raster; outputs are calculated only for non-zero mask cells."
+                    .to_owned(),
+            parameter_type: ParameterType::ExistingFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Slope File".to_owned(),
+            flags: vec!["--slope".to_owned()],
+            description: "Optional output slope raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Aspect File".to_owned(),
+            flags: vec!["--aspect".to_owned()],
+            description: "Optional output aspect raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Plan Curvature File".to_owned(),
+            flags: vec!["--plan_curv".to_owned()],
+            description: "Optional output plan curvature raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Output Profile Curvature File".to_owned(),
+            flags: vec!["--prof_curv".to_owned()],
+            description: "Optional output profile curvature raster file.".to_owned(),
+            parameter_type: ParameterType::NewFile(ParameterFileType::Raster),
+            default_value: None,
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Slope Units".to_owned(),
+            flags: vec!["--units".to_owned()],
+            description:
+                "Units of the slope raster; options include 'degrees', 'radians', 'percent', 'ratio'."
+                    .to_owned(),
+            parameter_type: ParameterType::OptionList(vec![
+                "degrees".to_owned(),
+                "radians".to_owned(),
+                "percent".to_owned(),
+                "ratio".to_owned(),
+            ]),
+            default_value: Some("degrees".to_owned()),
+            optional: true,
+        });
+
+        parameters.push(ToolParameter {
+            name: "Z Conversion Factor".to_owned(),
+            flags: vec!["--zfactor".to_owned()],
+            description:
+                "Optional multiplier for when the vertical and horizontal units are not the same."
+                    .to_owned(),
+            parameter_type: ParameterType::Float,
+            default_value: None,
+            optional: true,
+        });
+
+        let sep: String = path::MAIN_SEPARATOR.to_string();
+        let e = format!("{}", env::current_exe().unwrap().display());
+        let mut parent = env::current_exe().unwrap();
+        parent.pop();
+        let p = format!("{}", parent.display());
+        let mut short_exe = e
+            .replace(&p, "")
+            .replace(".exe", "")
+            .replace(".", "")
+            .replace(&sep, "");
+        if e.contains(".exe") {
+            short_exe += ".exe";
+        }
+        let usage = format!(">>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --slope=slope.tif --aspect=aspect.tif --units=percent
+>>.*{0} -r={1} -v --wd=\"*path*to*data*\" --dem=DEM.tif --mask=watershed.tif --slope=slope.tif --aspect=aspect.tif --plan_curv=plan.tif --prof_curv=prof.tif", short_exe, name).replace("*", &sep);
+
+        SlopeAspectCurvatureBundle {
+            name: name,
+            description: description,
+            toolbox: toolbox,
+            parameters: parameters,
+            example_usage: usage,
+        }
+    }
+}
+
+/// The derivatives calculated for a cell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Derivatives {
+    /// Slope gradient (rise over run).
+    gradient: f64,
+    /// Downslope azimuth in degrees clockwise from north; -1 on flat cells.
+    aspect: f64,
+    plan_curv: f64,
+    prof_curv: f64,
+}
+
+/// Calculates the derivatives at the centre of a 3x3 window of elevations, stored in row-major
+/// order from the north-west corner, for grid spacings `res_x` and `res_y`. NoData neighbours
+/// must already have been replaced.
+fn derivatives(z: &[f64; 9], res_x: f64, res_y: f64) -> Derivatives {
+    // First derivatives (Horn, 1981), with y increasing to the north.
+    let p = ((z[2] + 2. * z[5] + z[8]) - (z[0] + 2. * z[3] + z[6])) / (8. * res_x);
+    let q = ((z[0] + 2. * z[1] + z[2]) - (z[6] + 2. * z[7] + z[8])) / (8. * res_y);
+    // Second derivatives (Zevenbergen and Thorne, 1987).
+    let r = (z[3] - 2. * z[4] + z[5]) / (res_x * res_x);
+    let t = (z[1] - 2. * z[4] + z[7]) / (res_y * res_y);
+    let s = (z[2] - z[0] + z[6] - z[8]) / (4. * res_x * res_y);
+
+    let grad2 = p * p + q * q;
+    if grad2 > 0f64 {
+        let mut aspect = (-p).atan2(-q).to_degrees();
+        if aspect < 0f64 {
+            aspect += 360f64;
+        }
+        Derivatives {
+            gradient: grad2.sqrt(),
+            aspect,
+            // Florinsky (2016) Principles and Methods of Digital Terrain Modelling, Chapter 2, pg. 19.
+            plan_curv: -(q * q * r - 2. * p * q * s + p * p * t) / grad2.powi(3).sqrt(),
+            prof_curv: -(p * p * r + 2. * p * q * s + q * q * t)
+                / (grad2 * (1. + grad2).powi(3).sqrt()),
+        }
+    } else {
+        Derivatives {
+            gradient: 0f64,
+            aspect: -1f64,
+            plan_curv: 0f64,
+            prof_curv: 0f64,
+        }
+    }
+}
+
+/// Replaces missing (NoData) neighbours in a 3x3 window so that a planar surface is preserved. A
+/// missing edge neighbour is extrapolated linearly from the centre cell through the opposite
+/// neighbour, or takes the centre elevation when both are missing. A missing corner neighbour is
+/// then completed from the two edge neighbours adjacent to it.
+fn fill_missing_neighbours(z: &mut [f64; 9], missing: &[bool; 9]) {
+    for &n in &[1usize, 3, 5, 7] {
+        if missing[n] {
+            let opposite = 8 - n;
+            z[n] = if missing[opposite] {
+                z[4]
+            } else {
+                2. * z[4] - z[opposite]
+            };
+        }
+    }
+    for &n in &[0usize, 2, 6, 8] {
+        if missing[n] {
+            let vertical = if n < 3 { 1 } else { 7 };
+            let horizontal = if n % 3 == 0 { 3 } else { 5 };
+            z[n] = z[vertical] + z[horizontal] - z[4];
+        }
+    }
+}
+
+impl WhiteboxTool for SlopeAspectCurvatureBundle {
+    fn get_source_file(&self) -> String {
+        String::from(file!())
+    }
+
+    fn get_tool_name(&self) -> String {
+        self.name.clone()
+    }
+
+    fn get_tool_description(&self) -> String {
+        self.description.clone()
+    }
+
+    fn get_tool_parameters(&self) -> String {
+        let mut s = String::from("{\"parameters\": [");
+        for i in 0..self.parameters.len() {
+            if i < self.parameters.len() - 1 {
+                s.push_str(&(self.parameters[i].to_string()));
+                s.push_str(",");
+            } else {
+                s.push_str(&(self.parameters[i].to_string()));
+            }
+        }
+        s.push_str("]}");
+        s
+    }
+
+    fn get_example_usage(&self) -> String {
+        self.example_usage.clone()
+    }
+
+    fn get_toolbox(&self) -> String {
+        self.toolbox.clone()
+    }
+
+    fn run<'a>(
+        &self,
+        args: Vec<String>,
+        working_directory: &'a str,
+        verbose: bool,
+    ) -> Result<(), Error> {
+        let mut input_file = String::new();
+        let mut mask_file = String::new();
+        let mut slope_file = String::new();
+        let mut aspect_file = String::new();
+        let mut plan_file = String::new();
+        let mut prof_file = String::new();
+        let mut units = "degrees".to_string();
+        let mut z_factor = 1f64;
+
+        if args.len() == 0 {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Tool run with no parameters.",
+            ));
+        }
+        for i in 0..args.len() {
+            let mut arg = args[i].replace("\"", "");
+            arg = arg.replace("\'", "");
+            let cmd = arg.split("="); // in case an equals sign was used
+            let vec = cmd.collect::<Vec<&str>>();
+            let mut keyval = false;
+            if vec.len() > 1 {
+                keyval = true;
+            }
+            let flag_val = vec[0].to_lowercase().replace("--", "-");
+            let value = if keyval {
+                vec[1].to_string()
+            } else if i + 1 < args.len() {
+                args[i + 1].to_string()
+            } else {
+                String::new()
+            };
+            if flag_val == "-i" || flag_val == "-input" || flag_val == "-dem" {
+                input_file = value;
+            } else if flag_val == "-mask" {
+                mask_file = value;
+            } else if flag_val == "-slope" {
+                slope_file = value;
+            } else if flag_val == "-aspect" {
+                aspect_file = value;
+            } else if flag_val == "-plan_curv" {
+                plan_file = value;
+            } else if flag_val == "-prof_curv" {
+                prof_file = value;
+            } else if flag_val == "-units" {
+                let units_lc = value.trim().to_lowercase();
+                units = if units_lc.starts_with("deg") {
+                    "degrees".to_string()
+                } else if units_lc.starts_with("rad") {
+                    "radians".to_string()
+                } else if units_lc.starts_with("per") {
+                    "percent".to_string()
+                } else if units_lc.starts_with("ratio") {
+                    "ratio".to_string()
+                } else {
+                    return Err(Error::new(
+                        ErrorKind::InvalidInput,
+                        format!(
+                            "Unrecognized --units '{}'; expected 'degrees', 'radians', 'percent', or 'ratio'.",
+                            value
+                        ),
+                    ));
+                };
+            } else if flag_val == "-zfactor" {
+                z_factor = value.trim().parse::<f64>().map_err(|_| {
+                    Error::new(
+                        ErrorKind::InvalidInput,
+                        format!("Error parsing --zfactor value '{}'.", value),
+                    )
+                })?;
+            }
+        }
+
+        if input_file.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "Input DEM file (--dem) not specified.",
+            ));
+        }
+        if slope_file.is_empty()
+            && aspect_file.is_empty()
+            && plan_file.is_empty()
+            && prof_file.is_empty()
+        {
+            return Err(Error::new(
+                ErrorKind::InvalidInput,
+                "No outputs requested; specify at least one of --slope, --aspect, --plan_curv, or --prof_curv.",
+            ));
+        }
+
+        print_tool_banner(&self.get_tool_name(), verbose);
+
+        let configs = whitebox_common::configs::get_configs()?;
+        let mut num_procs = num_cpus::get() as isize;
+        if configs.max_procs > 0 && configs.max_procs < num_procs {
+            num_procs = configs.max_procs;
+        }
+
+        let mut progress: usize;
+        let mut old_progress: usize = 1;
+
+        input_file = resolve_path(working_directory, &input_file);
+        mask_file = resolve_path(working_directory, &mask_file);
+        slope_file = resolve_path(working_directory, &slope_file);
+        aspect_file = resolve_path(working_directory, &aspect_file);
+        plan_file = resolve_path(working_directory, &plan_file);
+        prof_file = resolve_path(working_directory, &prof_file);
+
+        if verbose {
+            println!("Reading data...")
+        };
+        let input = Arc::new(Raster::new(&input_file, "r")?);
+        let mask = if !mask_file.is_empty() {
+            let mask = Raster::new(&mask_file, "r")?;
+            if mask.configs.rows != input.configs.rows
+                || mask.configs.columns != input.configs.columns
+            {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    "The input files must have the same number of rows and columns and spatial extent.",
+                ));
+            }
+            Some(Arc::new(mask))
+        } else {
+            None
+        };
+
+        let start = Instant::now();
+
+        let rows = input.configs.rows as isize;
+        let columns = input.configs.columns as isize;
+        let nodata = input.configs.nodata;
+        let is_geographic = input.is_in_geographic_coordinates();
+
+        let (tx, rx) = mpsc::channel();
+        for tid in 0..num_procs {
+            let input = input.clone();
+            let mask = mask.clone();
+            let units = units.clone();
+            let tx = tx.clone();
+            thread::spawn(move || {
+                let offsets = [
+                    (-1, -1),
+                    (-1, 0),
+                    (-1, 1),
+                    (0, -1),
+                    (0, 0),
+                    (0, 1),
+                    (1, -1),
+                    (1, 0),
+                    (1, 1),
+                ];
+                let mut z = [0f64; 9];
+                let mut missing = [false; 9];
+                for row in (0..rows).filter(|r| r % num_procs == tid) {
+                    let (res_x, res_y) = if is_geographic {
+                        let lat = input.get_y_from_row(row);
+                        let lon = input.get_x_from_column(0);
+                        (
+                            haversine_distance((lat, lon), (lat, input.get_x_from_column(1))),
+                            haversine_distance((input.get_y_from_row(row - 1), lon), (lat, lon)),
+                        )
+                    } else {
+                        (input.configs.resolution_x, input.configs.resolution_y)
+                    };
+                    let mut data = vec![[nodata; 4]; columns as usize];
+                    for col in 0..columns {
+                        if input.get_value(row, col) == nodata {
+                            continue;
+                        }
+                        if let Some(ref mask) = mask {
+                            let m = mask.get_value(row, col);
+                            if m == mask.configs.nodata || m == 0f64 {
+                                continue;
+                            }
+                        }
+                        for n in 0..9 {
+                            let value = input.get_value(row + offsets[n].0, col + offsets[n].1);
+                            missing[n] = value == nodata;
+                            z[n] = value * z_factor;
+                        }
+                        fill_missing_neighbours(&mut z, &missing);
+                        let d = derivatives(&z, res_x, res_y);
+                        let slope = match units.as_str() {
+                            "degrees" => d.gradient.atan().to_degrees(),
+                            "radians" => d.gradient.atan(),
+                            "percent" => d.gradient * 100f64,
+                            _ => d.gradient,
+                        };
+                        data[col as usize] = [slope, d.aspect, d.plan_curv, d.prof_curv];
+                    }
+                    tx.send((row, data)).unwrap();
+                }
+            });
+        }
+
+        let output_files = [&slope_file, &aspect_file, &plan_file, &prof_file];
+        let mut outputs: Vec<Option<Raster>> = output_files
+            .iter()
+            .map(|file| {
+                if file.is_empty() {
+                    None
+                } else {
+                    let mut output = Raster::initialize_using_file(file, &input);
+                    output.configs.data_type = DataType::F32;
+                    output.configs.photometric_interp = PhotometricInterpretation::Continuous;
+                    Some(output)
+                }
+            })
+            .collect();
+        for r in 0..rows {
+            let (row, data) = rx.recv().expect("Error receiving data from thread.");
+            for (i, output) in outputs.iter_mut().enumerate() {
+                if let Some(output) = output {
+                    output.set_row_data(row, data.iter().map(|d| d[i]).collect());
+                }
+            }
+            if verbose {
+                progress = (100.0_f64 * (r + 1) as f64 / rows as f64) as usize;
+                if progress != old_progress {
+                    println!("Progress: {}%", progress);
+                    old_progress = progress;
+                }
+            }
+        }
+
+        let elapsed_time = get_formatted_elapsed_time(start);
+
+        if verbose {
+            println!("Saving data...")
+        };
+        let derivative_names = ["Slope", "Aspect", "Plan curvature", "Profile curvature"];
+        for (i, output) in outputs.iter_mut().enumerate() {
+            if let Some(output) = output {
+                output.add_metadata_entry(format!(
+                    "Created by whitebox_tools\' {} tool",
+                    self.get_tool_name()
+                ));
+                output.add_metadata_entry(format!("Input file: {}", input_file));
+                if !mask_file.is_empty() {
+                    output.add_metadata_entry(format!("Mask file: {}", mask_file));
+                }
+                output.add_metadata_entry(format!("Derivative: {}", derivative_names[i]));
+                if i == 0 {
+                    output.add_metadata_entry(format!("Slope units: {}", units));
+                }
+                output.add_metadata_entry(format!("Z-factor: {}", z_factor));
+                output
+                    .add_metadata_entry(format!("Elapsed Time (excluding I/O): {}", elapsed_time));
+                output.write()?;
+            }
+        }
+        if verbose {
+            println!("Output files written")
+        };
+
+        if verbose {
+            println!(
+                "{}",
+                &format!("Elapsed Time (excluding I/O): {}", elapsed_time)
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{derivatives, SlopeAspectCurvatureBundle};
+    use crate::tools::WhiteboxTool;
+    use whitebox_common::test_support::{temp_dir, GridFixture};
+    use whitebox_raster::test_support::grid_fixture_raster;
+    use whitebox_raster::Raster;
+
+    const NODATA: f64 = -32768f64;
+
+    // Writes a 7 x 7 raster with 10 m cells in projected coordinates. The function is given
+    // the coordinates of the cell centres relative to the south-west corner.
+    fn write_raster(file: &str, f: &dyn Fn(f64, f64) -> f64) {
+        let grid = GridFixture::new(7, 7, |r, c| {
+            f(10f64 * c as f64 + 5f64, 65f64 - 10f64 * r as f64)
+        })
+        .with_origin(500_000f64, 4_000_000f64)
+        .with_nodata(NODATA);
+        grid_fixture_raster(file, &grid).write().unwrap();
+    }
+
+    // A plane rising 0.1 to the east and 0.2 to the north, with a NoData cell at (3, 3) and a
+    // mask that excludes the first column.
+    #[test]
+    fn test_tilted_plane() {
+        let dir = temp_dir("slope_aspect_curvature_bundle_plane").unwrap();
+        let dem = format!("{}/dem.tif", dir);
+        let mask = format!("{}/mask.tif", dir);
+        write_raster(&dem, &|x, y| {
+            if (x, y) == (35f64, 35f64) {
+                NODATA
+            } else {
+                100f64 + 0.1 * x + 0.2 * y
+            }
+        });
+        write_raster(&mask, &|x, _| if x < 10f64 { 0f64 } else { 1f64 });
+        let outputs = ["slope", "aspect", "plan_curv", "prof_curv"];
+        let mut args = vec![format!("--dem={}", dem), format!("--mask={}", mask)];
+        for name in &outputs {
+            args.push(format!("--{}={}/{}.tif", name, dir, name));
+        }
+        SlopeAspectCurvatureBundle::new()
+            .run(args.clone(), "", false)
+            .unwrap();
+
+        let gradient = (0.1f64 * 0.1 + 0.2 * 0.2).sqrt();
+        let expected_slope = gradient.atan().to_degrees(); // 12.604 degrees
+        let expected_aspect = 180f64 + (0.1f64 / 0.2).atan().to_degrees(); // faces SSW
+        let rasters: Vec<Raster> = outputs
+            .iter()
+            .map(|name| Raster::new(&format!("{}/{}.tif", dir, name), "r").unwrap())
+            .collect();
+        for r in 0..7isize {
+            for c in 0..7isize {
+                if (r, c) == (3, 3) || c == 0 {
+                    for raster in &rasters {
+                        assert_eq!(raster.get_value(r, c), NODATA);
+                    }
+                    continue;
+                }
+                // Edge cells and the neighbours of the NoData cell are exact.
+                assert!((rasters[0].get_value(r, c) - expected_slope).abs() < 1e-4);
+                assert!((rasters[1].get_value(r, c) - expected_aspect).abs() < 1e-4);
+                assert!(rasters[2].get_value(r, c).abs() < 1e-6);
+                assert!(rasters[3].get_value(r, c).abs() < 1e-6);
+            }
+        }
+
+        // Percent units.
+        let slope = format!("{}/slope_percent.tif", dir);
+        SlopeAspectCurvatureBundle::new()
+            .run(
+                vec![
+                    format!("--dem={}", dem),
+                    format!("--slope={}", slope),
+                    "--units=percent".to_string(),
+                ],
+                "",
+                false,
+            )
+            .unwrap();
+        let raster = Raster::new(&slope, "r").unwrap();
+        assert!((raster.get_value(0, 0) - gradient * 100f64).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_aspect_directions_and_quadratic_curvature() {
+        let window = |f: &dyn Fn(f64, f64) -> f64| {
+            let mut z = [0f64; 9];
+            for n in 0..9 {
+                let (x, y) = ((n % 3) as f64 - 1., 1. - (n / 3) as f64);
+                z[n] = f(x, y);
+            }
+            z
+        };
+        // Surfaces falling towards the north, east, south, and west.
+        assert_eq!(derivatives(&window(&|_, y| -y), 1., 1.).aspect, 0.);
+        assert_eq!(derivatives(&window(&|x, _| -x), 1., 1.).aspect, 90.);
+        assert_eq!(derivatives(&window(&|_, y| y), 1., 1.).aspect, 180.);
+        assert_eq!(derivatives(&window(&|x, _| x), 1., 1.).aspect, 270.);
+        assert_eq!(derivatives(&window(&|_, _| 5.), 1., 1.).aspect, -1.);
+
+        // z = 0.3x + 0.1y + 0.05x^2 - 0.02xy + 0.04y^2 has p = 0.3, q = 0.1, r = 0.1,
+        // s = -0.02, and t = 0.08 at the origin, which the kernels recover exactly.
+        let d = derivatives(
+            &window(&|x, y| 0.3 * x + 0.1 * y + 0.05 * x * x - 0.02 * x * y + 0.04 * y * y),
+            1.,
+            1.,
+        );
+        let (p, q, r, s, t) = (0.3f64, 0.1f64, 0.1f64, -0.02f64, 0.08f64);
+        let g = p * p + q * q;
+        let plan = -(q * q * r - 2. * p * q * s + p * p * t) / g.powi(3).sqrt();
+        let prof = -(p * p * r + 2. * p * q * s + q * q * t) / (g * (1. + g).powi(3).sqrt());
+        assert!((d.gradient - g.sqrt()).abs() < 1e-12);
+        assert!((d.plan_curv - plan).abs() < 1e-12);
+        assert!((d.prof_curv - prof).abs() < 1e-12);
+    }
+}
@@ -8,6 +8,7 @@ License: MIT
 
 // private sub-module defined in other files
 pub mod shapefile;
+pub mod vector_writer;
 
 // exports identifiers from private sub-modules in the current module namespace
 // pub use self::shapefile::attributes::{
@@ -18,4 +19,5 @@ pub use crate::shapefile::attributes::*;
 pub use crate::shapefile::geometry::ShapeType;
 pub use crate::shapefile::geometry::*;
 pub use crate::shapefile::Shapefile;
+pub use crate::vector_writer::{VectorFormat, VectorWriter};
 // pub use whitebox_common::structures::Point2D;
@@ -194,12 +194,12 @@ impl Shapefile {
         &self.records[index]
     }
 
-    /// Adds a new ShapefileGeometry.
+    /// Adds a new ShapefileGeometry, which must be of the file's ShapeType or a null record.
     pub fn add_record(&mut self, geometry: ShapefileGeometry) {
         if self.file_mode == "r" {
             panic!("The file was opened in read-only mode.");
         }
-        if geometry.shape_type == self.header.shape_type {
+        if geometry.shape_type == self.header.shape_type || geometry.shape_type == ShapeType::Null {
             self.records.push(geometry);
             self.num_records += 1;
         } else {
@@ -719,14 +719,21 @@ impl Shapefile {
             ));
         }
 
-        self.num_records = self.records.len(); // make sure they are the same.
-        if self.num_records == 0 {
+        if self.records.is_empty() {
             return Err(Error::new(
                 ErrorKind::Other,
                 "The file does not currently contain any record data.",
             ));
         }
 
+        self.write_file()
+    }
+
+    /// Writes the .shp, .shx, .dbf and (if there is a projection) .prj files. Unlike `write`, this
+    /// accepts a file without records, which is written as a valid, empty layer.
+    pub(crate) fn write_file(&mut self) -> Result<(), Error> {
+        self.num_records = self.records.len(); // make sure they are the same.
+
         /////////////////////////////////////////
         // Write the geometry data (.shp file) //
         /////////////////////////////////////////
@@ -1308,7 +1315,11 @@ impl Shapefile {
                 self.header.m_max = 0f64;
                 self.header.z_min = 0f64;
                 self.header.z_max = 0f64;
-                for sg in &self.records {
+                for sg in self
+                    .records
+                    .iter()
+                    .filter(|sg| sg.shape_type != ShapeType::Null)
+                {
                     if sg.points[0].x < self.header.x_min {
                         self.header.x_min = sg.points[0].x;
                     }
@@ -1358,7 +1369,11 @@ impl Shapefile {
                 self.header.m_max = f64::NEG_INFINITY;
                 self.header.z_min = 0f64;
                 self.header.z_max = 0f64;
-                for sg in &self.records {
+                for sg in self
+                    .records
+                    .iter()
+                    .filter(|sg| sg.shape_type != ShapeType::Null)
+                {
                     if sg.points[0].x < self.header.x_min {
                         self.header.x_min = sg.points[0].x;
                     }
@@ -1421,7 +1436,11 @@ impl Shapefile {
                 self.header.m_max = f64::NEG_INFINITY;
                 self.header.z_min = f64::INFINITY;
                 self.header.z_max = f64::NEG_INFINITY;
-                for sg in &self.records {
+                for sg in self
+                    .records
+                    .iter()
+                    .filter(|sg| sg.shape_type != ShapeType::Null)
+                {
                     if sg.points[0].x < self.header.x_min {
                         self.header.x_min = sg.points[0].x;
                     }
@@ -1488,5 +1507,17 @@ impl Shapefile {
                 }
             }
         }
+
+        if self.records.is_empty() {
+            // an empty layer has no extent; avoid writing infinite bounds
+            self.header.x_min = 0f64;
+            self.header.x_max = 0f64;
+            self.header.y_min = 0f64;
+            self.header.y_max = 0f64;
+            self.header.m_min = 0f64;
+            self.header.m_max = 0f64;
+            self.header.z_min = 0f64;
+            self.header.z_max = 0f64;
+        }
     }
 }
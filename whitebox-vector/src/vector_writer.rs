@@ -0,0 +1,699 @@
+/*
+This code is part of the WhiteboxTools geospatial analysis library.
+Authors: Dr. Roger Lew
+Created: 16/10/2026
+Last Modified: 16/10/2026
+License: MIT
+*/
+
+use crate::shapefile::attributes::*;
+use crate::shapefile::geometry::*;
+use crate::shapefile::Shapefile;
+use std::fs::File;
+use std::io::prelude::*;
+use std::io::{BufWriter, Error, ErrorKind};
+use std::path::Path;
+use whitebox_common::spatial_ref_system::epsg_from_wkt;
+use whitebox_common::structures::Point2D;
+
+/// The output formats supported by `VectorWriter`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VectorFormat {
+    Shapefile,
+    GeoJson,
+}
+
+impl VectorFormat {
+    /// Determines the format from a file name's extension: `.shp` for a Shapefile and `.geojson`
+    /// or `.json` for GeoJSON. A file name without an extension is treated as a Shapefile, as in
+    /// `Shapefile::new`.
+    pub fn from_file_name(file_name: &str) -> Result<VectorFormat, Error> {
+        match Path::new(file_name).extension().and_then(|e| e.to_str()) {
+            None => Ok(VectorFormat::Shapefile),
+            Some(ext) => match ext.to_lowercase().as_str() {
+                "shp" => Ok(VectorFormat::Shapefile),
+                "geojson" | "json" => Ok(VectorFormat::GeoJson),
+                _ => Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Unsupported vector output format '.{}'; use a Shapefile (.shp) or GeoJSON (.geojson, .json) file.",
+                        ext
+                    ),
+                )),
+            },
+        }
+    }
+}
+
+/// `VectorWriter` writes a layer of `ShapefileGeometry` records and their attributes to either
+/// an ESRI Shapefile or a GeoJSON file, chosen by the output file extension (see
+/// `VectorFormat::from_file_name`). Tools that can write either format build their output once
+/// with this writer rather than with a `Shapefile` directly.
+///
+/// Shapefile output is written by `Shapefile::write`. GeoJSON output is a `FeatureCollection`
+/// with one feature per record:
+///
+/// - Point and MultiPoint records become `Point` and `MultiPoint` geometries.
+/// - PolyLine records become a `LineString`, or a `MultiLineString` if they have several parts.
+/// - Polygon records become a `Polygon`, or a `MultiPolygon` if they have several outer rings.
+///   Each hole is assigned to the outer ring preceding it. Ring orientation is reversed from the
+///   Shapefile (clockwise outer rings) to the GeoJSON (counter-clockwise outer rings) convention.
+/// - Null records have a `null` geometry.
+///
+/// Z values are written as a third coordinate; measures have no GeoJSON equivalent and are
+/// dropped. Integer and real fields become JSON numbers (non-finite reals become `null`), text
+/// fields strings, boolean fields booleans, and date fields `YYYY-MM-DD` strings. If an EPSG
+/// code is set, or can be identified from the projection WKT, it is written as a `crs` foreign
+/// member (`urn:ogc:def:crs:EPSG::<code>`). Layers without records are written as valid, empty
+/// files in either format.
+///
+/// Examples:
+///
+/// ```ignore
+/// let mut output = VectorWriter::new(&output_file, ShapeType::PolyLine)?;
+/// output.projection = input.projection.clone();
+/// output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+///
+/// let mut sfg = ShapefileGeometry::new(ShapeType::PolyLine);
+/// sfg.add_part(&points);
+/// output.add_record(sfg, vec![FieldData::Int(1i32)]);
+///
+/// output.write()?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct VectorWriter {
+    pub file_name: String,
+    pub format: VectorFormat,
+    pub shape_type: ShapeType,
+    pub projection: String,
+    pub epsg: Option<u16>,
+    pub fields: Vec<AttributeField>,
+    pub geometries: Vec<ShapefileGeometry>,
+    pub records: Vec<Vec<FieldData>>,
+}
+
+impl VectorWriter {
+    pub fn new(file_name: &str, shape_type: ShapeType) -> Result<VectorWriter, Error> {
+        let format = VectorFormat::from_file_name(file_name)?;
+        let file_name = if Path::new(file_name).extension().is_none() {
+            // likely no extension provided; default to .shp
+            format!("{}.shp", file_name)
+        } else {
+            file_name.to_string()
+        };
+        Ok(VectorWriter {
+            file_name,
+            format,
+            shape_type,
+            projection: String::new(),
+            epsg: None,
+            fields: vec![],
+            geometries: vec![],
+            records: vec![],
+        })
+    }
+
+    pub fn add_field(&mut self, field: &AttributeField) {
+        self.fields.push(field.clone());
+    }
+
+    /// Adds a record, made up of its geometry and one value for each field.
+    pub fn add_record(&mut self, geometry: ShapefileGeometry, attributes: Vec<FieldData>) {
+        self.geometries.push(geometry);
+        self.records.push(attributes);
+    }
+
+    pub fn num_records(&self) -> usize {
+        self.geometries.len()
+    }
+
+    /// The EPSG code written to GeoJSON output: `epsg` if it is set, otherwise the code
+    /// identified from `projection`, if any.
+    pub fn get_epsg(&self) -> Option<u16> {
+        self.epsg.or_else(|| epsg_from_wkt(&self.projection))
+    }
+
+    pub fn write(&self) -> Result<(), Error> {
+        self.validate()?;
+        match self.format {
+            VectorFormat::Shapefile => self.write_shapefile(),
+            VectorFormat::GeoJson => self.write_geojson(),
+        }
+    }
+
+    fn validate(&self) -> Result<(), Error> {
+        let layer_type = self.shape_type.base_shape_type();
+        for (i, (geometry, record)) in self.geometries.iter().zip(&self.records).enumerate() {
+            if record.len() != self.fields.len() {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Record {} has {} attribute values but the layer has {} fields.",
+                        i + 1,
+                        record.len(),
+                        self.fields.len()
+                    ),
+                ));
+            }
+            let record_type = geometry.shape_type.base_shape_type();
+            if record_type != ShapeType::Null && record_type != layer_type {
+                return Err(Error::new(
+                    ErrorKind::InvalidInput,
+                    format!(
+                        "Record {} is a {} geometry but the layer is of {} type.",
+                        i + 1,
+                        geometry.shape_type,
+                        self.shape_type
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    fn write_shapefile(&self) -> Result<(), Error> {
+        let mut output = Shapefile::new(&self.file_name, self.shape_type)?;
+        output.projection = self.projection.clone();
+        for field in &self.fields {
+            output.attributes.add_field(field);
+        }
+        for (geometry, record) in self.geometries.iter().zip(&self.records) {
+            output.add_record(geometry.clone());
+            output.attributes.add_record(record.clone(), false);
+        }
+        output.write_file()
+    }
+
+    fn write_geojson(&self) -> Result<(), Error> {
+        let f = File::create(&self.file_name)?;
+        let mut writer = BufWriter::new(f);
+
+        writer.write_all(b"{\n\"type\": \"FeatureCollection\",\n")?;
+        if let Some(epsg) = self.get_epsg() {
+            writeln!(
+                writer,
+                "\"crs\": {{\"type\": \"name\", \"properties\": {{\"name\": \"urn:ogc:def:crs:EPSG::{}\"}}}},",
+                epsg
+            )?;
+        }
+        writer.write_all(b"\"features\": [")?;
+        for (i, (geometry, record)) in self.geometries.iter().zip(&self.records).enumerate() {
+            let properties = self
+                .fields
+                .iter()
+                .zip(record)
+                .map(|(field, value)| {
+                    format!("{}: {}", json_string(field.name.trim()), json_value(value))
+                })
+                .collect::<Vec<String>>()
+                .join(", ");
+            write!(
+                writer,
+                "{}\n{{\"type\": \"Feature\", \"id\": {}, \"geometry\": {}, \"properties\": {{{}}}}}",
+                if i > 0 { "," } else { "" },
+                i + 1,
+                geojson_geometry(geometry),
+                properties
+            )?;
+        }
+        if self.geometries.is_empty() {
+            writer.write_all(b"]\n}\n")?;
+        } else {
+            writer.write_all(b"\n]\n}\n")?;
+        }
+        writer.flush()
+    }
+}
+
+/// Serializes a geometry as a GeoJSON geometry object.
+fn geojson_geometry(geometry: &ShapefileGeometry) -> String {
+    if geometry.shape_type == ShapeType::Null || geometry.points.is_empty() {
+        return "null".to_string();
+    }
+    let has_z = geometry.has_z_data() && geometry.z_array.len() == geometry.points.len();
+    let coords = |start: usize, end: usize, reverse: bool| -> String {
+        let mut indices: Vec<usize> = (start..end).collect();
+        if reverse {
+            indices.reverse();
+        }
+        let positions = indices
+            .iter()
+            .map(|&i| {
+                position(
+                    &geometry.points[i],
+                    if has_z {
+                        Some(geometry.z_array[i])
+                    } else {
+                        None
+                    },
+                )
+            })
+            .collect::<Vec<String>>();
+        format!("[{}]", positions.join(", "))
+    };
+    let part_range = |part: usize| -> (usize, usize) {
+        let start = geometry.parts.get(part).map_or(0, |&p| p as usize);
+        let end = geometry
+            .parts
+            .get(part + 1)
+            .map_or(geometry.points.len(), |&p| p as usize);
+        (start, end)
+    };
+    let num_parts = geometry.parts.len().max(1);
+
+    match geometry.shape_type.base_shape_type() {
+        ShapeType::Point => format!(
+            "{{\"type\": \"Point\", \"coordinates\": {}}}",
+            position(
+                &geometry.points[0],
+                if has_z {
+                    Some(geometry.z_array[0])
+                } else {
+                    None
+                }
+            )
+        ),
+        ShapeType::MultiPoint => format!(
+            "{{\"type\": \"MultiPoint\", \"coordinates\": {}}}",
+            coords(0, geometry.points.len(), false)
+        ),
+        ShapeType::PolyLine => {
+            let lines: Vec<String> = (0..num_parts)
+                .map(|part| {
+                    let (start, end) = part_range(part);
+                    coords(start, end, false)
+                })
+                .collect();
+            if lines.len() == 1 {
+                format!(
+                    "{{\"type\": \"LineString\", \"coordinates\": {}}}",
+                    lines[0]
+                )
+            } else {
+                format!(
+                    "{{\"type\": \"MultiLineString\", \"coordinates\": [{}]}}",
+                    lines.join(", ")
+                )
+            }
+        }
+        ShapeType::Polygon => {
+            // Each hole belongs to the outer ring preceding it; a leading hole starts its own polygon.
+            let mut polygons: Vec<Vec<String>> = vec![];
+            for part in 0..num_parts {
+                let (start, end) = part_range(part);
+                let ring = coords(start, end, true);
+                let is_hole = !is_clockwise(&geometry.points[start..end]);
+                if polygons.is_empty() || !is_hole {
+                    polygons.push(vec![ring]);
+                } else {
+                    polygons.last_mut().unwrap().push(ring);
+                }
+            }
+            let polygons: Vec<String> = polygons
+                .iter()
+                .map(|rings| format!("[{}]", rings.join(", ")))
+                .collect();
+            if polygons.len() == 1 {
+                format!(
+                    "{{\"type\": \"Polygon\", \"coordinates\": {}}}",
+                    polygons[0]
+                )
+            } else {
+                format!(
+                    "{{\"type\": \"MultiPolygon\", \"coordinates\": [{}]}}",
+                    polygons.join(", ")
+                )
+            }
+        }
+        _ => "null".to_string(),
+    }
+}
+
+/// Whether a ring's vertices are in clockwise order, which in a Shapefile marks an outer ring
+/// (holes are counter-clockwise).
+fn is_clockwise(ring: &[Point2D]) -> bool {
+    let mut sum = 0f64;
+    for i in 1..ring.len() {
+        sum += (ring[i].x - ring[i - 1].x) * (ring[i].y + ring[i - 1].y);
+    }
+    sum > 0f64
+}
+
+fn position(p: &Point2D, z: Option<f64>) -> String {
+    match z {
+        Some(z) => format!(
+            "[{}, {}, {}]",
+            json_number(p.x),
+            json_number(p.y),
+            json_number(z)
+        ),
+        None => format!("[{}, {}]", json_number(p.x), json_number(p.y)),
+    }
+}
+
+fn json_value(value: &FieldData) -> String {
+    match value {
+        FieldData::Int(v) => v.to_string(),
+        FieldData::Real(v) => json_number(*v),
+        FieldData::Text(v) => json_string(v),
+        FieldData::Date(v) => format!("\"{:04}-{:02}-{:02}\"", v.year, v.month, v.day),
+        FieldData::Bool(v) => v.to_string(),
+        FieldData::Null => "null".to_string(),
+    }
+}
+
+/// Formats a number for JSON, which has no representation for NaN or infinity.
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        v.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::{VectorFormat, VectorWriter};
+    use crate::shapefile::attributes::*;
+    use crate::shapefile::geometry::*;
+    use crate::shapefile::Shapefile;
+    use serde_json::Value;
+    use std::env;
+    use std::fs;
+    use whitebox_common::structures::Point2D;
+
+    const UTM_17N: &str = "PROJCS[\"WGS 84 / UTM zone 17N\",GEOGCS[\"WGS 84\",DATUM[\"WGS_1984\",SPHEROID[\"WGS 84\",6378137,298.257223563,AUTHORITY[\"EPSG\",\"7030\"]],AUTHORITY[\"EPSG\",\"6326\"]],PRIMEM[\"Greenwich\",0,AUTHORITY[\"EPSG\",\"8901\"]],UNIT[\"degree\",0.0174532925199433,AUTHORITY[\"EPSG\",\"9122\"]],AUTHORITY[\"EPSG\",\"4326\"]],PROJECTION[\"Transverse_Mercator\"],PARAMETER[\"latitude_of_origin\",0],PARAMETER[\"central_meridian\",-81],PARAMETER[\"scale_factor\",0.9996],PARAMETER[\"false_easting\",500000],PARAMETER[\"false_northing\",0],UNIT[\"metre\",1,AUTHORITY[\"EPSG\",\"9001\"]],AXIS[\"Easting\",EAST],AXIS[\"Northing\",NORTH],AUTHORITY[\"EPSG\",\"32617\"]]";
+
+    fn pts(coords: &[(f64, f64)]) -> Vec<Point2D> {
+        coords.iter().map(|&(x, y)| Point2D::new(x, y)).collect()
+    }
+
+    fn temp_file(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("wbt_vector_writer_{}_{}", std::process::id(), name))
+            .to_string_lossy()
+            .to_string()
+    }
+
+    /// A polyline layer with a single-part and a two-part line and one value of each field type.
+    fn polyline_layer(file_name: &str) -> VectorWriter {
+        let mut output = VectorWriter::new(file_name, ShapeType::PolyLine).unwrap();
+        output.projection = UTM_17N.to_string();
+        output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        output.add_field(&AttributeField::new(
+            "LENGTH",
+            FieldDataType::Real,
+            9u8,
+            3u8,
+        ));
+        output.add_field(&AttributeField::new("NAME", FieldDataType::Text, 20u8, 0u8));
+        output.add_field(&AttributeField::new(
+            "VISITED",
+            FieldDataType::Bool,
+            1u8,
+            0u8,
+        ));
+        output.add_field(&AttributeField::new("DATE", FieldDataType::Date, 8u8, 0u8));
+
+        let mut line = ShapefileGeometry::new(ShapeType::PolyLine);
+        line.add_part(&pts(&[(0.0, 0.0), (10.0, 0.0), (10.0, 5.5)]));
+        output.add_record(
+            line,
+            vec![
+                FieldData::Int(1),
+                FieldData::Real(15.5),
+                FieldData::Text("A \"quoted\" name".to_string()),
+                FieldData::Bool(true),
+                FieldData::Date(DateData {
+                    year: 2026,
+                    month: 10,
+                    day: 6,
+                }),
+            ],
+        );
+        let mut multi_line = ShapefileGeometry::new(ShapeType::PolyLine);
+        multi_line.add_part(&pts(&[(20.0, 20.0), (30.0, 20.0)]));
+        multi_line.add_part(&pts(&[(40.0, 20.0), (40.0, 30.0)]));
+        output.add_record(
+            multi_line,
+            vec![
+                FieldData::Int(2),
+                FieldData::Real(20.0),
+                FieldData::Text("B".to_string()),
+                FieldData::Bool(false),
+                FieldData::Null,
+            ],
+        );
+        output
+    }
+
+    #[test]
+    fn test_format_from_file_name() {
+        assert_eq!(
+            VectorFormat::from_file_name("out.shp").unwrap(),
+            VectorFormat::Shapefile
+        );
+        assert_eq!(
+            VectorFormat::from_file_name("out").unwrap(),
+            VectorFormat::Shapefile
+        );
+        assert_eq!(
+            VectorFormat::from_file_name("out.GeoJSON").unwrap(),
+            VectorFormat::GeoJson
+        );
+        assert_eq!(
+            VectorFormat::from_file_name("out.json").unwrap(),
+            VectorFormat::GeoJson
+        );
+        assert!(VectorFormat::from_file_name("out.gpkg").is_err());
+    }
+
+    #[test]
+    fn test_shapefile_round_trip() {
+        let file_name = temp_file("lines.shp");
+        polyline_layer(&file_name).write().unwrap();
+
+        let input = Shapefile::read(&file_name).unwrap();
+        assert_eq!(input.header.shape_type, ShapeType::PolyLine);
+        assert_eq!(input.num_records, 2);
+        assert_eq!(input.projection.trim(), UTM_17N);
+        assert_eq!(
+            input.get_record(0).points,
+            pts(&[(0.0, 0.0), (10.0, 0.0), (10.0, 5.5)])
+        );
+        assert_eq!(input.get_record(1).num_parts, 2);
+        assert_eq!(input.get_record(1).parts, vec![0, 2]);
+        assert_eq!(input.attributes.get_num_fields(), 5);
+        assert_eq!(input.attributes.get_value(0, "FID"), FieldData::Int(1));
+        assert_eq!(
+            input.attributes.get_value(0, "LENGTH"),
+            FieldData::Real(15.5)
+        );
+        assert_eq!(
+            input.attributes.get_value(0, "NAME"),
+            FieldData::Text("A \"quoted\" name".to_string())
+        );
+        assert_eq!(
+            input.attributes.get_value(1, "VISITED"),
+            FieldData::Bool(false)
+        );
+
+        for ext in ["shp", "shx", "dbf", "prj"] {
+            let _ = fs::remove_file(temp_file(&format!("lines.{}", ext)));
+        }
+    }
+
+    #[test]
+    fn test_geojson_round_trip() {
+        let file_name = temp_file("lines.geojson");
+        polyline_layer(&file_name).write().unwrap();
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(&file_name).unwrap()).unwrap();
+        assert_eq!(json["type"], "FeatureCollection");
+        assert_eq!(
+            json["crs"]["properties"]["name"],
+            "urn:ogc:def:crs:EPSG::32617"
+        );
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features.len(), 2);
+
+        assert_eq!(features[0]["geometry"]["type"], "LineString");
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([[0, 0], [10, 0], [10, 5.5]])
+        );
+        let properties = &features[0]["properties"];
+        assert_eq!(properties["FID"], 1);
+        assert_eq!(properties["LENGTH"], 15.5);
+        assert_eq!(properties["NAME"], "A \"quoted\" name");
+        assert_eq!(properties["VISITED"], true);
+        assert_eq!(properties["DATE"], "2026-10-06");
+
+        assert_eq!(features[1]["geometry"]["type"], "MultiLineString");
+        assert_eq!(
+            features[1]["geometry"]["coordinates"],
+            serde_json::json!([[[20, 20], [30, 20]], [[40, 20], [40, 30]]])
+        );
+        assert_eq!(features[1]["properties"]["DATE"], Value::Null);
+
+        let _ = fs::remove_file(&file_name);
+    }
+
+    #[test]
+    fn test_geojson_polygons_and_points() {
+        // An outer ring (clockwise, as in a Shapefile) with a hole, followed by a second outer ring.
+        let mut polygon = ShapefileGeometry::new(ShapeType::Polygon);
+        polygon.add_part(&pts(&[
+            (0.0, 0.0),
+            (0.0, 10.0),
+            (10.0, 10.0),
+            (10.0, 0.0),
+            (0.0, 0.0),
+        ]));
+        polygon.add_part(&pts(&[
+            (2.0, 2.0),
+            (4.0, 2.0),
+            (4.0, 4.0),
+            (2.0, 4.0),
+            (2.0, 2.0),
+        ]));
+        polygon.add_part(&pts(&[
+            (20.0, 0.0),
+            (20.0, 5.0),
+            (25.0, 5.0),
+            (25.0, 0.0),
+            (20.0, 0.0),
+        ]));
+        let mut single = ShapefileGeometry::new(ShapeType::Polygon);
+        single.add_part(&pts(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (0.0, 0.0)]));
+
+        let file_name = temp_file("polygons.geojson");
+        let mut output = VectorWriter::new(&file_name, ShapeType::Polygon).unwrap();
+        output.epsg = Some(26917);
+        output.add_record(polygon, vec![]);
+        output.add_record(single, vec![]);
+        output.add_record(ShapefileGeometry::new(ShapeType::Null), vec![]);
+        output.write().unwrap();
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(&file_name).unwrap()).unwrap();
+        assert_eq!(
+            json["crs"]["properties"]["name"],
+            "urn:ogc:def:crs:EPSG::26917"
+        );
+        let features = json["features"].as_array().unwrap();
+        assert_eq!(features[0]["geometry"]["type"], "MultiPolygon");
+        assert_eq!(
+            features[0]["geometry"]["coordinates"],
+            serde_json::json!([
+                [
+                    [[0, 0], [10, 0], [10, 10], [0, 10], [0, 0]],
+                    [[2, 2], [2, 4], [4, 4], [4, 2], [2, 2]]
+                ],
+                [[[20, 0], [25, 0], [25, 5], [20, 5], [20, 0]]]
+            ])
+        );
+        assert_eq!(features[1]["geometry"]["type"], "Polygon");
+        assert_eq!(features[2]["geometry"], Value::Null);
+        assert_eq!(features[2]["properties"], serde_json::json!({}));
+        let _ = fs::remove_file(&file_name);
+
+        let file_name = temp_file("points.geojson");
+        let mut output = VectorWriter::new(&file_name, ShapeType::PointZ).unwrap();
+        output.add_field(&AttributeField::new("VALUE", FieldDataType::Real, 9u8, 3u8));
+        let mut point = ShapefileGeometry::new(ShapeType::PointZ);
+        point.add_pointz(Point2D::new(1.5, 2.5), 0.0, 100.0);
+        output.add_record(point, vec![FieldData::Real(f64::NAN)]);
+        output.write().unwrap();
+
+        let json: Value = serde_json::from_str(&fs::read_to_string(&file_name).unwrap()).unwrap();
+        assert!(json.get("crs").is_none());
+        let feature = &json["features"][0];
+        assert_eq!(feature["geometry"]["type"], "Point");
+        assert_eq!(
+            feature["geometry"]["coordinates"],
+            serde_json::json!([1.5, 2.5, 100])
+        );
+        assert_eq!(feature["properties"]["VALUE"], Value::Null);
+        let _ = fs::remove_file(&file_name);
+    }
+
+    #[test]
+    fn test_empty_layers() {
+        let file_name = temp_file("empty.geojson");
+        let mut output = VectorWriter::new(&file_name, ShapeType::Point).unwrap();
+        output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        output.write().unwrap();
+        let json: Value = serde_json::from_str(&fs::read_to_string(&file_name).unwrap()).unwrap();
+        assert_eq!(json["features"], serde_json::json!([]));
+        let _ = fs::remove_file(&file_name);
+
+        let file_name = temp_file("empty.shp");
+        let mut output = VectorWriter::new(&file_name, ShapeType::Point).unwrap();
+        output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        output.write().unwrap();
+        let input = Shapefile::read(&file_name).unwrap();
+        assert_eq!(input.header.shape_type, ShapeType::Point);
+        assert_eq!(input.num_records, 0);
+        assert_eq!(input.header.x_min, 0.0);
+        assert_eq!(input.attributes.get_num_fields(), 1);
+        for ext in ["shp", "shx", "dbf"] {
+            let _ = fs::remove_file(temp_file(&format!("empty.{}", ext)));
+        }
+    }
+
+    #[test]
+    fn test_shapefile_null_records() {
+        let file_name = temp_file("nulls.shp");
+        let mut output = VectorWriter::new(&file_name, ShapeType::Point).unwrap();
+        output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        let mut point = ShapefileGeometry::new(ShapeType::Point);
+        point.add_point(Point2D::new(5.0, 7.0));
+        output.add_record(point, vec![FieldData::Int(1)]);
+        output.add_record(ShapefileGeometry::default(), vec![FieldData::Int(2)]);
+        output.write().unwrap();
+        let input = Shapefile::read(&file_name).unwrap();
+        assert_eq!(input.num_records, 2);
+        assert_eq!(input.records[1].shape_type, ShapeType::Null);
+        assert_eq!(input.header.x_min, 5.0);
+        assert_eq!(input.header.y_max, 7.0);
+        assert_eq!(input.attributes.get_value(1, "FID"), FieldData::Int(2));
+        for ext in ["shp", "shx", "dbf"] {
+            let _ = fs::remove_file(temp_file(&format!("nulls.{}", ext)));
+        }
+    }
+
+    #[test]
+    fn test_mismatched_records_are_rejected() {
+        let file_name = temp_file("mismatch.geojson");
+        let mut output = VectorWriter::new(&file_name, ShapeType::PolyLine).unwrap();
+        output.add_field(&AttributeField::new("FID", FieldDataType::Int, 3u8, 0u8));
+        let mut line = ShapefileGeometry::new(ShapeType::PolyLine);
+        line.add_part(&pts(&[(0.0, 0.0), (1.0, 1.0)]));
+        output.add_record(line.clone(), vec![]);
+        assert!(output.write().is_err());
+
+        output.records[0] = vec![FieldData::Int(1)];
+        let mut point = ShapefileGeometry::new(ShapeType::Point);
+        point.add_point(Point2D::new(0.0, 0.0));
+        output.add_record(point, vec![FieldData::Int(2)]);
+        assert!(output.write().is_err());
+    }
+}
@@ -861,6 +861,24 @@ Okay, that's it for now.
         if epsg is not None: args.append("--epsg='{}'".format(epsg))
         return self.run_tool('csv_points_to_vector', args, callback)  # returns 1 if error
 
+    def edge_bleed_trim(self, i, output, n_cells=1, adaptive=None, callback=None):
+        """Sets the fringe of valid cells along a raster's NoData boundary to NoData.
+
+        Keyword arguments:
+
+        i -- Input raster file. 
+        output -- Output raster file. 
+        n_cells -- Width of the fringe along the NoData boundary to trim, in grid cells. 
+        adaptive -- Optional threshold; when given, only fringe cells differing from the mean of nearby interior cells by more than it are trimmed. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--output='{}'".format(output))
+        args.append("--n_cells={}".format(n_cells))
+        if adaptive is not None: args.append("--adaptive={}".format(adaptive))
+        return self.run_tool('edge_bleed_trim', args, callback)  # returns 1 if error
+
     def export_table_to_csv(self, i, output, headers=True, callback=None):
         """Exports an attribute table to a CSV text file.
 
@@ -975,6 +993,22 @@ Okay, that's it for now.
         args.append("--new_value={}".format(new_value))
         return self.run_tool('modify_no_data_value', args, callback)  # returns 1 if error
 
+    def multi_band_composite_from_singles(self, inputs, output, split=False, callback=None):
+        """Stacks single-band rasters into a multi-band GeoTIFF, or splits one into single bands.
+
+        Keyword arguments:
+
+        inputs -- Input single-band raster files, in band order; with --split, the one multi-band GeoTIFF. 
+        output -- Output multi-band GeoTIFF; with --split, the base name of the single-band outputs. 
+        split -- Split the input multi-band GeoTIFF into one raster per band. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--inputs='{}'".format(inputs))
+        args.append("--output='{}'".format(output))
+        if split: args.append("--split")
+        return self.run_tool('multi_band_composite_from_singles', args, callback)  # returns 1 if error
+
     def multi_part_to_single_part(self, i, output, exclude_holes=True, callback=None):
         """Converts a vector file containing multi-part features into a vector containing only single-part features.
 
@@ -1037,6 +1071,46 @@ Okay, that's it for now.
         args.append("--input='{}'".format(i))
         return self.run_tool('print_geo_tiff_tags', args, callback)  # returns 1 if error
 
+    def raster_footprint_vector(self, inputs, output, callback=None):
+        """Outlines the valid-data footprint of rasters as polygons, one feature per raster.
+
+        Keyword arguments:
+
+        inputs -- Input raster files. 
+        output -- Output polygon vector file (.shp or .geojson). 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--inputs='{}'".format(inputs))
+        args.append("--output='{}'".format(output))
+        return self.run_tool('raster_footprint_vector', args, callback)  # returns 1 if error
+
+    def rasterize_vector(self, i, base, output, field=None, value=1.0, all_touched=False, line_thickness=0.0, zero_background=False, callback=None):
+        """Burns vector points, lines, or polygons onto the grid of a template raster.
+
+        Keyword arguments:
+
+        i -- Input vector file (shapefile or GeoJSON). 
+        base -- Input raster defining the output grid. 
+        output -- Output raster file. 
+        field -- Optional numeric attribute field supplying burn values. 
+        value -- Constant burn value, used when no field is specified. 
+        all_touched -- Burn every cell touched by a polygon rather than only cells whose centres are inside. 
+        line_thickness -- Width of burned lines, in map units. Zero burns single-cell lines. 
+        zero_background -- Set unburned cells to zero instead of NoData. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--base='{}'".format(base))
+        args.append("--output='{}'".format(output))
+        if field is not None: args.append("--field='{}'".format(field))
+        args.append("--value={}".format(value))
+        if all_touched: args.append("--all_touched")
+        args.append("--line_thickness={}".format(line_thickness))
+        if zero_background: args.append("--zero_background")
+        return self.run_tool('rasterize_vector', args, callback)  # returns 1 if error
+
     def raster_to_vector_lines(self, i, output, callback=None):
         """Converts a raster lines features into a vector of the POLYLINE shapetype.
 
@@ -1155,6 +1229,30 @@ Okay, that's it for now.
         args.append("--output='{}'".format(output))
         return self.run_tool('single_part_to_multi_part', args, callback)  # returns 1 if error
 
+    def synthetic_test_data_generator(self, out_dir, terrain="dendritic", rows=21, columns=21, resolution=10.0, channel_slope=0.01, hillslope_slope=0.1, callback=None):
+        """Writes a synthetic DEM with consistent D8 pointer, streams, junction, order, and watershed rasters for testing hydrological tools.
+
+        Keyword arguments:
+
+        terrain -- Synthetic terrain: 'inclined_plane', 'v_valley', or 'dendritic'. 
+        out_dir -- Output directory for the rasters and manifest. 
+        rows -- Number of rows. 
+        columns -- Number of columns. 
+        resolution -- Cell size, in metres. 
+        channel_slope -- Gradient of the main channel (m/m). 
+        hillslope_slope -- Gradient of the hillslopes (m/m). 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--terrain={}".format(terrain))
+        args.append("--out_dir='{}'".format(out_dir))
+        args.append("--rows={}".format(rows))
+        args.append("--columns={}".format(columns))
+        args.append("--resolution={}".format(resolution))
+        args.append("--channel_slope={}".format(channel_slope))
+        args.append("--hillslope_slope={}".format(hillslope_slope))
+        return self.run_tool('synthetic_test_data_generator', args, callback)  # returns 1 if error
+
     def vector_lines_to_raster(self, i, output, field="FID", nodata=True, cell_size=None, base=None, callback=None):
         """Converts a vector containing polylines into a raster.
 
@@ -1977,20 +2075,40 @@ Okay, that's it for now.
         if max_triangle_edge_length is not None: args.append("--max_triangle_edge_length='{}'".format(max_triangle_edge_length))
         return self.run_tool('tin_gridding', args, callback)  # returns 1 if error
 
-    def travelling_salesman_problem(self, i, output, duration=60, callback=None):
+    def travelling_salesman_problem(self, i, output, duration=60, exact_threshold=12, include_waypoints=False, merge_distance=0.0, max_memory=1024.0, force=False, assign_epsg=None, checkpoint=None, checkpoint_interval=60, resume=False, keep_checkpoint=False, callback=None):
         """Finds approximate solutions to travelling salesman problems, the goal of which is to identify the shortest route connecting a set of locations.
 
         Keyword arguments:
 
         i -- Name of the input points shapefile. 
-        output -- Name of the output lines shapefile. 
+        output -- Name of the output lines shapefile, a GeoJSON file (*.geojson), or a GPX file (*.gpx) containing the route in WGS84. 
         duration -- Maximum duration, in seconds. 
+        exact_threshold -- Maximum number of locations for which the optimal tour is found exactly (Held-Karp), skipping the heuristic search; limited to 20. 
+        include_waypoints -- Also write the stops as GPX waypoints (GPX output only). 
+        merge_distance -- Locations within this distance of an earlier location are merged into it before solving, in input units (metres for geographic coordinates); 0 merges nothing. 
+        max_memory -- Estimated memory of the search, in MB, above which fewer worker threads are used. 
+        force -- Run with a single worker even if its estimated memory exceeds the maximum. 
+        assign_epsg -- EPSG code of the input coordinate system, declaring it when the input has no .prj file or overriding the input's own; it is carried to every output format. 
+        checkpoint -- JSON file to which the best tour found so far is written periodically, for resuming an interrupted search. 
+        checkpoint_interval -- Seconds between checkpoint writes. 
+        resume -- Start the search from the tour in the checkpoint file, if it exists. 
+        keep_checkpoint -- Keep the checkpoint, holding the final tour, once the tool completes. 
         callback -- Custom function for handling tool text outputs.
         """
         args = []
         args.append("--input='{}'".format(i))
         args.append("--output='{}'".format(output))
         args.append("--duration={}".format(duration))
+        args.append("--exact_threshold={}".format(exact_threshold))
+        if include_waypoints: args.append("--include_waypoints")
+        args.append("--merge_distance={}".format(merge_distance))
+        args.append("--max_memory={}".format(max_memory))
+        if force: args.append("--force")
+        if assign_epsg is not None: args.append("--assign_epsg={}".format(assign_epsg))
+        if checkpoint is not None: args.append("--checkpoint='{}'".format(checkpoint))
+        args.append("--checkpoint_interval={}".format(checkpoint_interval))
+        if resume: args.append("--resume")
+        if keep_checkpoint: args.append("--keep_checkpoint")
         return self.run_tool('travelling_salesman_problem', args, callback)  # returns 1 if error
 
     def vector_hex_binning(self, i, output, width, orientation="horizontal", callback=None):
@@ -2127,6 +2245,46 @@ Okay, that's it for now.
         args.append("--output='{}'".format(output))
         return self.run_tool('euclidean_distance', args, callback)  # returns 1 if error
 
+    def mask_grow(self, i, output, radius=1, element="circular", iterations=1, callback=None):
+        """Dilates a binary mask raster by a radius in grid cells, using a circular or square structuring element.
+
+        Keyword arguments:
+
+        i -- Input mask raster; non-zero, non-NoData cells are in the mask. 
+        output -- Output mask raster file. 
+        radius -- Radius of the structuring element, in grid cells. 
+        element -- Shape of the structuring element: 'circular' or 'square'. 
+        iterations -- Number of times the operation is applied. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--output='{}'".format(output))
+        args.append("--radius={}".format(radius))
+        args.append("--element={}".format(element))
+        args.append("--iterations={}".format(iterations))
+        return self.run_tool('mask_grow', args, callback)  # returns 1 if error
+
+    def mask_shrink(self, i, output, radius=1, element="circular", iterations=1, callback=None):
+        """Erodes a binary mask raster by a radius in grid cells, using a circular or square structuring element.
+
+        Keyword arguments:
+
+        i -- Input mask raster; non-zero, non-NoData cells are in the mask. 
+        output -- Output mask raster file. 
+        radius -- Radius of the structuring element, in grid cells. 
+        element -- Shape of the structuring element: 'circular' or 'square'. 
+        iterations -- Number of times the operation is applied. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--output='{}'".format(output))
+        args.append("--radius={}".format(radius))
+        args.append("--element={}".format(element))
+        args.append("--iterations={}".format(iterations))
+        return self.run_tool('mask_shrink', args, callback)  # returns 1 if error
+
     ##############################
     # GIS Analysis/Overlay Tools #
     ##############################
@@ -2179,7 +2337,7 @@ Okay, that's it for now.
         if maintain_dimensions: args.append("--maintain_dimensions")
         return self.run_tool('clip_raster_to_polygon', args, callback)  # returns 1 if error
 
-    def clip_raster_to_raster(self, i, mask, output, callback=None):
+    def clip_raster_to_raster(self, i, mask, output, as_categorical=False, callback=None):
         """
         Clips a raster (`i`) using another raster (`mask`).
 
@@ -2188,12 +2346,14 @@ Okay, that's it for now.
         i -- Input raster file. 
         mask -- Mask raster (cells with NODATA or value 0 are treated as background). 
         output -- Output raster file. 
+        as_categorical -- Give the output categorical settings (photometric interpretation and a qualitative palette). 
         callback -- Custom function for handling tool text outputs.
         """
         args = []
         args.append(f"--input='{i}'")
         args.append(f"--mask='{mask}'")
         args.append(f"--output='{output}'")
+        if as_categorical: args.append("--as_categorical")
         return self.run_tool('clip_raster_to_raster', args, callback)  # returns 1 if error
     
     def count_if(self, inputs, output, value, callback=None):
@@ -3958,6 +4118,28 @@ Okay, that's it for now.
         args.append("--lightness={}".format(lightness))
         return self.run_tool('multiscale_topographic_position_image', args, callback)  # returns 1 if error
 
+    def no_data_fill(self, i, output, mask=None, filled=None, max_gap_cells=10, method="idw", callback=None):
+        """Fills small NoData gaps in a raster, optionally within a watershed mask, by interpolating from the surrounding valid cells.
+
+        Keyword arguments:
+
+        i -- Input raster file, e.g. a DEM. 
+        output -- Output raster file. 
+        mask -- Optional watershed mask raster; only NoData cells inside the mask (non-zero) are filled. 
+        filled -- Optional output raster flagging filled cells (1) and all other cells (0). 
+        max_gap_cells -- Largest gap, in grid cells, that will be filled. 
+        method -- Interpolation method: 'idw' (inverse-distance weighted) or 'mean' (mean of the surrounding ring of valid cells). 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--output='{}'".format(output))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        if filled is not None: args.append("--filled='{}'".format(filled))
+        args.append("--max_gap_cells={}".format(max_gap_cells))
+        args.append("--method={}".format(method))
+        return self.run_tool('no_data_fill', args, callback)  # returns 1 if error
+
     def num_downslope_neighbours(self, dem, output, callback=None):
         """Calculates the number of downslope neighbours to each grid cell in a DEM.
 
@@ -4308,6 +4490,32 @@ Okay, that's it for now.
         args.append("--units={}".format(units))
         return self.run_tool('slope', args, callback)  # returns 1 if error
 
+    def slope_aspect_curvature_bundle(self, dem, slope=None, aspect=None, plan_curv=None, prof_curv=None, mask=None, units="degrees", zfactor=None, callback=None):
+        """Calculates slope, aspect, plan curvature, and profile curvature rasters from a DEM in a single pass.
+
+        Keyword arguments:
+
+        dem -- Input raster DEM file. 
+        slope -- Optional output slope raster file. 
+        aspect -- Optional output aspect raster file. 
+        plan_curv -- Optional output plan curvature raster file. 
+        prof_curv -- Optional output profile curvature raster file. 
+        mask -- Optional mask raster; outputs are calculated only for non-zero mask cells. 
+        units -- Units of the slope raster; options include 'degrees', 'radians', 'percent', 'ratio'. 
+        zfactor -- Optional multiplier for when the vertical and horizontal units are not the same. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--dem='{}'".format(dem))
+        if slope is not None: args.append("--slope='{}'".format(slope))
+        if aspect is not None: args.append("--aspect='{}'".format(aspect))
+        if plan_curv is not None: args.append("--plan_curv='{}'".format(plan_curv))
+        if prof_curv is not None: args.append("--prof_curv='{}'".format(prof_curv))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        args.append("--units={}".format(units))
+        if zfactor is not None: args.append("--zfactor='{}'".format(zfactor))
+        return self.run_tool('slope_aspect_curvature_bundle', args, callback)  # returns 1 if error
+
     def slope_vs_aspect_plot(self, i, output, bin_size=2.0, min_slope=0.1, zfactor=1.0, callback=None):
         """This tool creates a slope-aspect relation plot from an input DEM.
 
@@ -4656,6 +4864,30 @@ Okay, that's it for now.
     # Hydrological Analysis #
     #########################
 
+    def accumulate_along_channels(self, d8_pntr, streams, value, output, netw=None, summary=None, esri_pntr=False, callback=None):
+        """Routes a per-cell value raster down the D8 pointer, reporting the cumulative upstream total on each stream cell.
+
+        Keyword arguments:
+
+        d8_pntr -- Input D8 pointer raster file. 
+        streams -- Input raster streams file (positive values are stream cells). 
+        value -- Input raster of the per-cell values to accumulate, e.g. a load; NoData counts as zero. 
+        output -- Output raster of the accumulated values on the stream cells. 
+        netw -- Optional input channel network TSV file from HillslopesTopaz, used for the per-link summary. 
+        summary -- Optional output tab-separated per-link summary table; requires netw. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--streams='{}'".format(streams))
+        args.append("--value='{}'".format(value))
+        args.append("--output='{}'".format(output))
+        if netw is not None: args.append("--netw='{}'".format(netw))
+        if summary is not None: args.append("--summary='{}'".format(summary))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('accumulate_along_channels', args, callback)  # returns 1 if error
+
     def average_flowpath_slope(self, dem, output, callback=None):
         """Measures the average slope gradient from each grid cell to all upslope divide cells.
 
@@ -4684,6 +4916,32 @@ Okay, that's it for now.
         args.append("--output='{}'".format(output))
         return self.run_tool('average_upslope_flowpath_length', args, callback)  # returns 1 if error
 
+    def basin_outlet_hydraulic_geometry(self, d8_pntr, dem, streams, pour_pts, output=None, report=None, save_watershed=None, esri_pntr=False, callback=None):
+        """Calculates the drainage area, relief, slope, and main channel attributes of the basin draining to an outlet.
+
+        Keyword arguments:
+
+        d8_pntr -- Input D8 pointer raster file. 
+        dem -- Input DEM raster file. 
+        streams -- Input raster streams file; stream cells have positive values. 
+        pour_pts -- Input pour point (outlet) file, containing a single point. 
+        output -- Output GeoJSON file of the outlet point with the basin attributes. 
+        report -- Output JSON report of the basin attributes. 
+        save_watershed -- Optional output raster of the delineated basin. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--dem='{}'".format(dem))
+        args.append("--streams='{}'".format(streams))
+        args.append("--pour_pts='{}'".format(pour_pts))
+        if output is not None: args.append("--output='{}'".format(output))
+        if report is not None: args.append("--report='{}'".format(report))
+        if save_watershed is not None: args.append("--save_watershed='{}'".format(save_watershed))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('basin_outlet_hydraulic_geometry', args, callback)  # returns 1 if error
+
     def basins(self, d8_pntr, output, esri_pntr=False, callback=None):
         """Identifies drainage basins that drain to the DEM edge.
 
@@ -4780,6 +5038,78 @@ Okay, that's it for now.
         if width is not None: args.append("--width='{}'".format(width))
         return self.run_tool('burn_streams_at_roads', args, callback)  # returns 1 if error
 
+    def catchment_delineation_batch(self, d8_pntr, pour_pts, id_field=None, output=None, output_dir=None, nested="innermost", report=None, esri_pntr=False, callback=None):
+        """Delineates the catchments of a batch of gauge points against a single D8 pointer raster, handling nested gauges.
+
+        Keyword arguments:
+
+        d8_pntr -- Input D8 pointer raster file. 
+        pour_pts -- Input snapped gauge points file (Shapefile or GeoJSON). 
+        id_field -- Attribute field holding the gauge ids; record numbers are used if unspecified. 
+        output -- Output labeled catchments raster file. 
+        output_dir -- Output directory for per-gauge cropped catchment rasters. 
+        nested -- Labeling of cells shared by nested catchments; options are 'innermost' and 'last_wins'. 
+        report -- Output tab-separated report of gauge catchment sizes and nesting. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--pour_pts='{}'".format(pour_pts))
+        if id_field is not None: args.append("--id_field='{}'".format(id_field))
+        if output is not None: args.append("--output='{}'".format(output))
+        if output_dir is not None: args.append("--output_dir='{}'".format(output_dir))
+        args.append("--nested={}".format(nested))
+        if report is not None: args.append("--report='{}'".format(report))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('catchment_delineation_batch', args, callback)  # returns 1 if error
+
+    def cell_path_upstream_trace(self, d8_pntr, flow_accum, pour_pts, output, dem=None, esri_pntr=False, callback=None):
+        """Traces the path of maximum contributing area upstream from sites to their headwaters.
+
+        Keyword arguments:
+
+        d8_pntr -- Input raster D8 pointer file. 
+        flow_accum -- Input raster D8 flow accumulation file. 
+        pour_pts -- Input sites file (point Shapefile, GeoJSON, or raster). 
+        output -- Output lines vector file (*.shp, *.geojson) of traced paths. 
+        dem -- Optional input raster DEM file; writes a TSV profile of the traced paths. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--flow_accum='{}'".format(flow_accum))
+        args.append("--pour_pts='{}'".format(pour_pts))
+        args.append("--output='{}'".format(output))
+        if dem is not None: args.append("--dem='{}'".format(dem))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('cell_path_upstream_trace', args, callback)  # returns 1 if error
+
+    def channel_junction_angles(self, d8_pntr, streams, output, netw=None, k_cells=3, output_points=None, esri_pntr=False, callback=None):
+        """Measures the planform entrance angle of each tributary at the junctions of a stream network.
+
+        Keyword arguments:
+
+        d8_pntr -- Input raster D8 pointer file. 
+        streams -- Input raster streams file (positive values are stream cells). 
+        netw -- Optional input channel network TSV file from HillslopesTopaz; links are derived from the streams otherwise. 
+        k_cells -- Number of steps along each link used for its direction at a junction. 
+        output -- Output tab-separated table of the junction angles. 
+        output_points -- Optional output points vector file (*.shp, *.geojson) of the junctions. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--streams='{}'".format(streams))
+        if netw is not None: args.append("--netw='{}'".format(netw))
+        args.append("--k_cells={}".format(k_cells))
+        args.append("--output='{}'".format(output))
+        if output_points is not None: args.append("--output_points='{}'".format(output_points))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('channel_junction_angles', args, callback)  # returns 1 if error
+
     def d8_flow_accumulation(self, i, output, out_type="cells", log=False, clip=False, pntr=False, esri_pntr=False, callback=None):
         """Calculates a D8 flow accumulation raster from an input DEM or flow pointer.
 
@@ -4914,6 +5244,36 @@ Okay, that's it for now.
         if zero_background: args.append("--zero_background")
         return self.run_tool('depth_in_sink', args, callback)  # returns 1 if error
 
+    def detect_parallel_stream_artifacts(self, streams, d8_pntr, output, max_sinuosity=1.05, min_length=100.0, max_distance=60.0, max_angle=10.0, output_vector=None, summary=None, esri_pntr=False, callback=None):
+        """Flags straight, parallel stream links that suggest artifacts of DEM conditioning.
+
+        Keyword arguments:
+
+        streams -- Input raster streams file (positive values are stream cells). 
+        d8_pntr -- Input raster D8 pointer file. 
+        max_sinuosity -- Largest sinuosity (length / chord) of a link considered straight. 
+        min_length -- Shortest link considered, in map units. 
+        max_distance -- Largest separation of two parallel links, in map units. 
+        max_angle -- Largest difference in direction of two parallel links, in degrees. 
+        output -- Output tab-separated table of the flagged links. 
+        output_vector -- Optional output lines vector file (*.shp, *.geojson) of the flagged links. 
+        summary -- Optional output tab-separated table, or JSON file (*.json), of the link counts. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--streams='{}'".format(streams))
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--max_sinuosity={}".format(max_sinuosity))
+        args.append("--min_length={}".format(min_length))
+        args.append("--max_distance={}".format(max_distance))
+        args.append("--max_angle={}".format(max_angle))
+        args.append("--output='{}'".format(output))
+        if output_vector is not None: args.append("--output_vector='{}'".format(output_vector))
+        if summary is not None: args.append("--summary='{}'".format(summary))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('detect_parallel_stream_artifacts', args, callback)  # returns 1 if error
+
     def depth_to_water(self, dem, output, streams=None, lakes=None, callback=None):
         """This tool calculates cartographic depth-to-water (DTW) index.
 
@@ -5177,12 +5537,45 @@ Okay, that's it for now.
     def find_outlet(
         self,
         d8_pntr,
-        streams,
+        streams=None,
         watershed=None,
         output=None,
         esri_pntr=False,
+        auto_pntr=False,
+        skip_crs_check=False,
         requested_outlet_lng_lat=None,
         requested_outlet_row_col=None,
+        requested_outlet_xy=None,
+        pour_pts=None,
+        flow_accum=None,
+        sca=False,
+        area_ratio_tolerance=None,
+        accum_threshold=None,
+        stream_threshold=None,
+        min_stream_order=None,
+        snap_radius=None,
+        snap_to_stream=False,
+        snap_max_radius=None,
+        max_candidates=None,
+        mask_erosion=None,
+        bbox=None,
+        max_steps=None,
+        all_outlets=False,
+        score_candidates=None,
+        outlet_within_mask=False,
+        snap_to_junction=False,
+        junction_window=None,
+        dem=None,
+        slope_steps=None,
+        downstream_preview=None,
+        trace_path=None,
+        diagnostics=None,
+        summary_csv=None,
+        report=None,
+        error_json=None,
+        junctions=None,
+        output_raster=None,
+        compress=None,
         callback=None,
     ):
         """Identifies an outlet stream cell and writes a pour point GeoJSON.
@@ -5190,12 +5583,45 @@ Okay, that's it for now.
         Keyword arguments:
 
         d8_pntr -- Input raster D8 pointer file. 
-        streams -- Input raster streams file (1=stream, 0=non-stream). 
-        watershed -- Optional watershed mask raster file (1=inside, 0=outside). 
-        output -- Output GeoJSON pour point file. (required)
+        streams -- Input raster streams file (1=stream, 0=non-stream). Optional when flow_accum is supplied. 
+        watershed -- Optional watershed mask raster file (1=inside, 0=outside), or polygon Shapefile/GeoJSON in the D8 pointer's coordinate system. 
+        output -- Output GeoJSON pour point file, or a point Shapefile when the name ends in .shp. (required)
         esri_pntr -- D8 pointer uses the ESRI style scheme. 
-        requested_outlet_lng_lat -- Optional tuple or string "lon,lat" describing the requested outlet location in WGS84. 
-        requested_outlet_row_col -- Optional tuple or string "row,col" describing the requested outlet cell. 
+        auto_pntr -- Detect whether the D8 pointer uses the Whitebox or ESRI scheme from its agreement with the stream network; --esri_pntr is the fallback when detection is ambiguous. 
+        skip_crs_check -- Do not require the input rasters to share the EPSG code of the D8 pointer, e.g. when their codes are missing; their grids must still match. 
+        requested_outlet_lng_lat -- Optional tuple or string "lon,lat" describing the requested outlet location in WGS84, or a list of tuples (or "lon,lat;lon,lat") to trace several locations. 
+        requested_outlet_row_col -- Optional tuple or string "row,col" describing the requested outlet cell, or a list of tuples (or "row,col;row,col") to trace several cells. 
+        requested_outlet_xy -- Optional tuple or string "easting,northing" describing the requested outlet location in the coordinate system of the D8 pointer, or a list of tuples (or "x,y;x,y") to trace several locations. 
+        pour_pts -- Optional pour points file (Shapefile, GeoJSON, or raster) whose points are traced as requested outlets. 
+        flow_accum -- Optional flow accumulation raster used to derive streams when streams is not supplied, to snap requested outlets, and to report the drainage area at the outlet. 
+        sca -- The flow_accum values are contributing areas rather than cell counts. 
+        area_ratio_tolerance -- Optional fraction by which the outlet drainage area may differ from the watershed mask area before a warning is printed (default 0.25). 
+        accum_threshold -- Accumulation value above which cells are streams when deriving from flow_accum. 
+        stream_threshold -- Optional value above which a streams cell is a channel; nodata cells never are (default 0). 
+        min_stream_order -- Optional minimum streams value, e.g. a Strahler order, of channel cells; lower-valued cells are not streams (default 0, excluding nothing). 
+        snap_radius -- Optional radius, in cells, within which requested outlets are snapped to the cell of highest flow_accum before tracing. 
+        snap_to_stream -- When a requested outlet has no valid D8 pointer, prefer the nearest valid cell that is also a stream cell as the start of the trace. 
+        snap_max_radius -- Optional radius, in cells, within which snap_to_stream looks for a stream cell before falling back on the nearest valid cell; unlimited by default. 
+        max_candidates -- Optional maximum number of watershed cells, deepest first, from which an outlet is traced (default 512). 
+        mask_erosion -- Optional number of cells by which the watershed mask is eroded before ranking and tracing candidates (default 0). 
+        bbox -- Optional tuple or string "minx,miny,maxx,maxy" in the coordinate system of the D8 pointer to which the junction scan and the outlet search are limited. 
+        max_steps -- Optional maximum number of D8 steps in a single trace (default four times the number of grid cells). 
+        all_outlets -- Trace every watershed candidate and write each distinct outlet, ranked by the mask cells draining to it; requires watershed. 
+        score_candidates -- Optional number of further successful watershed candidates traced after the selected one to measure its support; requires watershed. 
+        outlet_within_mask -- Only accept outlets inside the watershed mask, falling back on the last stream cell inside it with a junction count of 1 where the trace leaves the mask; requires watershed. 
+        snap_to_junction -- Move an outlet traced past a nearby stream junction back to the cell immediately upstream of the junction. 
+        junction_window -- Optional radius, in cells, around the outlet within which snap_to_junction looks for a junction (default 3). 
+        dem -- Optional input DEM raster used to report the outlet elevation and the local channel slope upstream of the outlet. 
+        slope_steps -- Optional number of steps up the stream from the outlet over which the channel slope is measured (default 10); requires dem. 
+        downstream_preview -- Optional number of D8 steps to continue the trace downstream of the outlet (ignoring the mask), written as a LineString feature. 
+        trace_path -- Optional output GeoJSON file of the flow path traced from the start cell to the outlet, as a LineString. 
+        diagnostics -- Optional output JSON file listing every start cell traced and its result, written on success and on failure. 
+        summary_csv -- Optional output CSV file with one row of outlet attributes per identified outlet. 
+        report -- Optional output JSON report of the run, written on success and on failure, giving its failure_class and exit_code. 
+        error_json -- Optional output JSON file written only when the run fails, giving its failure_class, error_kind, parameters, and trace attempts. 
+        junctions -- Optional output raster of the stream junction counts used by the trace. 
+        output_raster -- Optional output raster aligned to the D8 pointer with 1 at each outlet cell and NoData elsewhere. 
+        compress -- Optional override of the compress_rasters setting for raster outputs (None uses the setting). 
         callback -- Custom function for handling tool text outputs.
         """
         if output is None:
@@ -5203,28 +5629,97 @@ Okay, that's it for now.
 
         args = []
         args.append("--d8_pntr='{}'".format(d8_pntr))
-        args.append("--streams='{}'".format(streams))
+        if streams is not None:
+            args.append("--streams='{}'".format(streams))
+        if flow_accum is not None:
+            args.append("--flow_accum='{}'".format(flow_accum))
+        if sca:
+            args.append("--sca")
+        if area_ratio_tolerance is not None:
+            args.append("--area_ratio_tolerance={}".format(area_ratio_tolerance))
+        if accum_threshold is not None:
+            args.append("--accum_threshold={}".format(accum_threshold))
+        if stream_threshold is not None:
+            args.append("--stream_threshold={}".format(stream_threshold))
+        if min_stream_order is not None:
+            args.append("--min_stream_order={}".format(min_stream_order))
         if watershed is not None:
             args.append("--watershed='{}'".format(watershed))
         args.append("--output='{}'".format(output))
+        def _pair_list(value):
+            if isinstance(value, (list, tuple)):
+                if value and isinstance(value[0], (list, tuple)):
+                    return ";".join("{},{}".format(a, b) for a, b in value)
+                a, b = value
+                return "{},{}".format(a, b)
+            return value
+
         if requested_outlet_lng_lat is not None:
-            if isinstance(requested_outlet_lng_lat, (list, tuple)):
-                lon, lat = requested_outlet_lng_lat
-                args.append("--requested_outlet_lng_lat='{},{}'".format(lon, lat))
-            else:
-                args.append(
-                    "--requested_outlet_lng_lat='{}'".format(requested_outlet_lng_lat)
-                )
+            args.append(
+                "--requested_outlet_lng_lat='{}'".format(_pair_list(requested_outlet_lng_lat))
+            )
         if requested_outlet_row_col is not None:
-            if isinstance(requested_outlet_row_col, (list, tuple)):
-                row, col = requested_outlet_row_col
-                args.append("--requested_outlet_row_col='{},{}'".format(row, col))
-            else:
-                args.append(
-                    "--requested_outlet_row_col='{}'".format(requested_outlet_row_col)
-                )
+            args.append(
+                "--requested_outlet_row_col='{}'".format(_pair_list(requested_outlet_row_col))
+            )
+        if requested_outlet_xy is not None:
+            args.append("--requested_outlet_xy='{}'".format(_pair_list(requested_outlet_xy)))
+        if pour_pts is not None:
+            args.append("--pour_pts='{}'".format(pour_pts))
+        if snap_radius is not None:
+            args.append("--snap_radius={}".format(snap_radius))
+        if snap_to_stream:
+            args.append("--snap_to_stream")
+        if snap_max_radius is not None:
+            args.append("--snap_max_radius={}".format(snap_max_radius))
+        if max_candidates is not None:
+            args.append("--max_candidates={}".format(max_candidates))
+        if mask_erosion is not None:
+            args.append("--mask_erosion={}".format(mask_erosion))
+        if bbox is not None:
+            if isinstance(bbox, (list, tuple)):
+                bbox = ",".join(str(v) for v in bbox)
+            args.append("--bbox='{}'".format(bbox))
+        if max_steps is not None:
+            args.append("--max_steps={}".format(max_steps))
+        if all_outlets:
+            args.append("--all_outlets")
+        if score_candidates is not None:
+            args.append("--score_candidates={}".format(score_candidates))
         if esri_pntr:
             args.append("--esri_pntr")
+        if auto_pntr:
+            args.append("--auto_pntr")
+        if skip_crs_check:
+            args.append("--skip_crs_check")
+        if outlet_within_mask:
+            args.append("--outlet_within_mask")
+        if snap_to_junction:
+            args.append("--snap_to_junction")
+        if junction_window is not None:
+            args.append("--junction_window={}".format(junction_window))
+        if dem is not None:
+            args.append("--dem='{}'".format(dem))
+        if slope_steps is not None:
+            args.append("--slope_steps={}".format(slope_steps))
+        if downstream_preview is not None:
+            args.append("--downstream_preview={}".format(downstream_preview))
+        if trace_path is not None:
+            args.append("--trace_path='{}'".format(trace_path))
+        if diagnostics is not None:
+            args.append("--diagnostics='{}'".format(diagnostics))
+        if summary_csv is not None:
+            args.append("--summary_csv='{}'".format(summary_csv))
+        if report is not None:
+            args.append("--report='{}'".format(report))
+        if error_json is not None:
+            args.append("--error_json='{}'".format(error_json))
+        if junctions is not None:
+            args.append("--junctions='{}'".format(junctions))
+        if output_raster is not None:
+            args.append("--output_raster='{}'".format(output_raster))
+        if compress is not None:
+            args.append("--compress={}".format('true' if compress else 'false'))
         return self.run_tool('find_outlet', args, callback)  # returns 1 if error
 
     def flatten_lakes(self, dem, lakes, output, callback=None):
@@ -5299,6 +5794,26 @@ Okay, that's it for now.
         if esri_pntr: args.append("--esri_pntr")
         return self.run_tool('flow_length_diff', args, callback)  # returns 1 if error
 
+    def flow_path_density(self, d8_pntr, max_distance, output, mask=None, esri_pntr=False, callback=None):
+        """Counts the cells whose D8 flow paths pass through each cell within a maximum flow path length.
+
+        Keyword arguments:
+
+        d8_pntr -- Input D8 pointer raster file. 
+        max_distance -- Maximum length of the flow path walked from each cell, in map units. 
+        mask -- Optional input mask raster; cells with positive values are processed. 
+        output -- Output flow path density raster file. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--max_distance={}".format(max_distance))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        args.append("--output='{}'".format(output))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('flow_path_density', args, callback)  # returns 1 if error
+
     def hillslopes(self, d8_pntr, streams, output, esri_pntr=False, callback=None):
         """Identifies the individual hillslopes draining to each link in a stream network.
 
@@ -5317,6 +5832,52 @@ Okay, that's it for now.
         if esri_pntr: args.append("--esri_pntr")
         return self.run_tool('hillslopes', args, callback)  # returns 1 if error
     
+    def hillslope_representative_profile_plot_data(self, subwta, dem, d8_pntr, out_dir, output, stations=21, esri_pntr=False, callback=None):
+        """Exports each TOPAZ hillslope's representative profile, resampled to fixed stations, as plot-ready CSV files.
+
+        Keyword arguments:
+
+        subwta -- Input TOPAZ-style hillslope and channel ID raster file. 
+        dem -- Input raster DEM file. 
+        d8_pntr -- Input D8 pointer raster file. 
+        out_dir -- Output directory for the per-hillslope CSV files. 
+        output -- Output combined long-format CSV file of all profiles. 
+        stations -- Number of equally spaced stations in each profile (at least 2). 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--subwta='{}'".format(subwta))
+        args.append("--dem='{}'".format(dem))
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--out_dir='{}'".format(out_dir))
+        args.append("--output='{}'".format(output))
+        args.append("--stations={}".format(stations))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('hillslope_representative_profile_plot_data', args, callback)  # returns 1 if error
+
+    def hillslope_width_function(self, subwta, d8_pntr, output, dist_to_chn=None, bins=10, esri_pntr=False, callback=None):
+        """Computes the width function of each TOPAZ hillslope, i.e. contributing width by normalized distance downslope.
+
+        Keyword arguments:
+
+        subwta -- Input TOPAZ-style hillslope and channel ID raster file. 
+        d8_pntr -- Input D8 pointer raster file. 
+        output -- Output tab-separated width function table. 
+        dist_to_chn -- Optional input raster of flow distances to the channel; measured along the pointer if unspecified. 
+        bins -- Number of normalized distance bins per hillslope. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--subwta='{}'".format(subwta))
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--output='{}'".format(output))
+        if dist_to_chn is not None: args.append("--dist_to_chn='{}'".format(dist_to_chn))
+        args.append("--bins={}".format(bins))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('hillslope_width_function', args, callback)  # returns 1 if error
+
     def hillslopes_topaz(
         self, 
         dem, 
@@ -5327,8 +5888,18 @@ Okay, that's it for now.
         chnjnt, 
         subwta, 
         netw, 
-        order, 
+        order=None, 
         esri_pntr=False, 
+        output_mode="all", 
+        strict_pour_pt=False, 
+        chn_order=None, 
+        validate_only=False, 
+        violations=None, 
+        topaz_start=24, 
+        topaz_stride=10, 
+        flow_accum=None, 
+        max_area_error=0.05, 
+        summary=None, 
         callback=None
     ):
         """Implements TOPAZ-style channel & hillslope IDs for a single watershed.
@@ -5341,10 +5912,20 @@ Okay, that's it for now.
         pour_pts -- Input pour points (outlet) file (vector or raster). 
         watershed -- Input watershed mask raster file (1=inside, 0=outside). 
         chnjnt -- Input channel junctions raster file (0=headwater, 1=mid-link, 2=junction). 
-        order -- Input stream order raster file (optional). 
+        order -- Optional input stream order raster file; the Strahler order is computed from the link network when it is not specified. 
         subwta -- Output raster file for TOPAZ identifiers. 
         netw -- Output TSV file for channel network table. 
         esri_pntr -- D8 pointer uses the ESRI style scheme (default: False). 
+        output_mode -- Cells written to the TOPAZ IDs raster: 'all', 'channels', or 'hillslopes' (default: 'all'). 
+        strict_pour_pt -- Fail unless the pour point falls exactly on a stream cell within the watershed, rather than using an adjacent stream cell (default: False). 
+        chn_order -- Optional output raster of each channel cell's WEPP routing sequence number (1 = most upstream, outlet last). 
+        validate_only -- Check an existing subwta raster and netw table against the inputs instead of regenerating them; no outputs are overwritten (default: False). 
+        violations -- Output TSV file of the violations found with validate_only. 
+        topaz_start -- TOPAZ ID of the outlet channel; must end in 4 (default: 24). 
+        topaz_stride -- Increment between successive channel TOPAZ IDs; must be a multiple of 10 (default: 10). 
+        flow_accum -- Optional flow accumulation raster (in cells) used to check each link's cumulative drainage area, reported in the area_error column of netw. 
+        max_area_error -- Relative discrepancy from flow_accum above which a warning is printed, as a fraction (default: 0.05). 
+        summary -- Optional output JSON summary of the run, written on success and on failure. 
         callback -- Custom function for handling tool text outputs.
         """
         args = []
@@ -5356,11 +5937,48 @@ Okay, that's it for now.
         args.append("--chnjnt='{}'".format(chnjnt))
         args.append("--subwta='{}'".format(subwta))
         args.append("--netw='{}'".format(netw))
-        args.append("--order='{}'".format(order))
+        if order is not None:
+            args.append("--order='{}'".format(order))
+        if chn_order is not None:
+            args.append("--chn_order='{}'".format(chn_order))
         if esri_pntr: 
             args.append("--esri_pntr")
+        args.append("--output_mode={}".format(output_mode))
+        if strict_pour_pt: 
+            args.append("--strict_pour_pt")
+        if validate_only: 
+            args.append("--validate_only")
+        if violations is not None:
+            args.append("--violations='{}'".format(violations))
+        args.append("--topaz_start={}".format(topaz_start))
+        args.append("--topaz_stride={}".format(topaz_stride))
+        if flow_accum is not None:
+            args.append("--flow_accum='{}'".format(flow_accum))
+            args.append("--max_area_error={}".format(max_area_error))
+        if summary is not None:
+            args.append("--summary='{}'".format(summary))
         return self.run_tool('hillslopes_topaz', args, callback)
     
+    def hydro_enforce_culverts(self, dem, culverts, output, max_length, report=None, callback=None):
+        """Breaches road embankments in a DEM at mapped culvert or crossing points.
+
+        Keyword arguments:
+
+        dem -- Input raster digital elevation model (DEM) file. 
+        culverts -- Input culvert/crossing points file (shapefile or GeoJSON). 
+        output -- Output raster file. 
+        report -- Output tab-separated report of carve depth and length per culvert. 
+        max_length -- Maximum length of a breach path through an embankment, in map units. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--dem='{}'".format(dem))
+        args.append("--culverts='{}'".format(culverts))
+        args.append("--output='{}'".format(output))
+        if report is not None: args.append("--report='{}'".format(report))
+        args.append("--max_length='{}'".format(max_length))
+        return self.run_tool('hydro_enforce_culverts', args, callback)  # returns 1 if error
+
     def hydrologic_connectivity(self, dem, output1, output2, exponent=1.0, threshold=None, callback=None):
         """This tool evaluates hydrologic connectivity within a DEM.
 
@@ -5381,6 +5999,24 @@ Okay, that's it for now.
         if threshold is not None: args.append("--threshold='{}'".format(threshold))
         return self.run_tool('hydrologic_connectivity', args, callback)  # returns 1 if error
 
+    def identify_flat_areas_and_pits(self, dem, output, watershed=None, summary=None, callback=None):
+        """Classifies the pits, flat areas, and depression candidates of a DEM and summarizes their number and area.
+
+        Keyword arguments:
+
+        dem -- Input raster DEM file. 
+        watershed -- Optional watershed raster; only its non-zero, non-NoData cells are analysed. 
+        output -- Output raster file (0=ok, 1=pit, 2=flat, 3=depression candidate). 
+        summary -- Optional output tab-separated table, or JSON file (*.json), summarizing each class. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--dem='{}'".format(dem))
+        if watershed is not None: args.append("--watershed='{}'".format(watershed))
+        args.append("--output='{}'".format(output))
+        if summary is not None: args.append("--summary='{}'".format(summary))
+        return self.run_tool('identify_flat_areas_and_pits', args, callback)  # returns 1 if error
+
     def impoundment_size_index(self, dem, damlength, out_mean=None, out_max=None, out_volume=None, out_area=None, out_dam_height=None, callback=None):
         """Calculates the impoundment size resulting from damming a DEM.
 
@@ -5459,6 +6095,30 @@ Okay, that's it for now.
         args.append("--snap_dist='{}'".format(snap_dist))
         return self.run_tool('jenson_snap_pour_points', args, callback)  # returns 1 if error
 
+    def label_hillslope_facets(self, subwta, output, output_table, dem=None, aspect=None, n_classes=4, min_area=0.0, callback=None):
+        """Splits each TOPAZ hillslope into facets of similar aspect, merging facets below a minimum area.
+
+        Keyword arguments:
+
+        subwta -- Input TOPAZ-style hillslope and channel ID raster file. 
+        output -- Output facet raster file, coded hillslope_id * 10 + facet_class. 
+        output_table -- Output tab-separated table of facet areas per hillslope. 
+        dem -- Input DEM raster file from which aspects are computed; required unless aspect is specified. 
+        aspect -- Input aspect raster file, in degrees clockwise from north with negative values for flat cells; used instead of dem. 
+        n_classes -- Number of equal aspect classes, from 1 to 9, the first centred on north. 
+        min_area -- Facets smaller than this area, in squared map units, are merged into the largest neighbouring facet of the same hillslope. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--subwta='{}'".format(subwta))
+        args.append("--output='{}'".format(output))
+        args.append("--output_table='{}'".format(output_table))
+        if dem is not None: args.append("--dem='{}'".format(dem))
+        if aspect is not None: args.append("--aspect='{}'".format(aspect))
+        args.append("--n_classes={}".format(n_classes))
+        args.append("--min_area={}".format(min_area))
+        return self.run_tool('label_hillslope_facets', args, callback)  # returns 1 if error
+
     def longest_flowpath(self, dem, basins, output, callback=None):
         """Delineates the longest flowpaths for a group of subbasins or watersheds.
 
@@ -5559,6 +6219,66 @@ Okay, that's it for now.
         args.append("--output='{}'".format(output))
         return self.run_tool('num_inflowing_neighbours', args, callback)  # returns 1 if error
 
+    def outlet_sensitivity_analysis(self, d8_pntr, streams, output, stream_threshold=0.0, requested_outlet_lng_lat=None, requested_outlet_row_col=None, pour_pts=None, jitter_cells=2, n_samples=25, seed=0, agreement=None, esri_pntr=False, callback=None):
+        """Perturbs a requested outlet and reports how the resolved outlet and its basin area vary.
+
+        Keyword arguments:
+
+        d8_pntr -- Input raster D8 pointer file. 
+        streams -- Input raster streams file (1=stream, 0=non-stream). 
+        stream_threshold -- Optional value above which a --streams cell is a channel; nodata cells never are (default 0). 
+        requested_outlet_lng_lat -- Requested outlet location specified as 'lon,lat' (WGS84). 
+        requested_outlet_row_col -- Requested outlet specified as 'row,col' in raster coordinates. 
+        pour_pts -- Requested outlet as a single pour point (Shapefile, GeoJSON, or raster) in the D8 pointer's coordinate system. 
+        jitter_cells -- Radius, in cells, within which the requested outlet is moved. 
+        n_samples -- Number of perturbed outlets to resolve. 
+        seed -- Seed of the random generator used to perturb the outlet. 
+        output -- Output report file; JSON if the name ends in .json, otherwise a tab-separated table of samples. 
+        agreement -- Optional output raster of the number of samples whose basin contains each cell. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--streams='{}'".format(streams))
+        args.append("--stream_threshold={}".format(stream_threshold))
+        if requested_outlet_lng_lat is not None: args.append("--requested_outlet_lng_lat='{}'".format(requested_outlet_lng_lat))
+        if requested_outlet_row_col is not None: args.append("--requested_outlet_row_col='{}'".format(requested_outlet_row_col))
+        if pour_pts is not None: args.append("--pour_pts='{}'".format(pour_pts))
+        args.append("--jitter_cells={}".format(jitter_cells))
+        args.append("--n_samples={}".format(n_samples))
+        args.append("--seed={}".format(seed))
+        args.append("--output='{}'".format(output))
+        if agreement is not None: args.append("--agreement='{}'".format(agreement))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('outlet_sensitivity_analysis', args, callback)  # returns 1 if error
+
+    def pour_point_from_largest_stream(self, d8_pntr, streams, boundary, output, order=None, flow_accum=None, n=1, esri_pntr=False, callback=None):
+        """Picks the pour point of the highest-order or largest stream leaving a boundary polygon or mask.
+
+        Keyword arguments:
+
+        d8_pntr -- Input D8 pointer raster file. 
+        streams -- Input raster streams file; stream cells have positive values. 
+        boundary -- Input boundary polygon vector (Shapefile or GeoJSON) or mask raster file. 
+        output -- Output pour point GeoJSON file. 
+        order -- Input stream order raster file, e.g. Strahler order. 
+        flow_accum -- Input flow accumulation raster file. 
+        n -- Number of top-ranked pour points to output. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        args.append("--streams='{}'".format(streams))
+        args.append("--boundary='{}'".format(boundary))
+        args.append("--output='{}'".format(output))
+        if order is not None: args.append("--order='{}'".format(order))
+        if flow_accum is not None: args.append("--flow_accum='{}'".format(flow_accum))
+        args.append("--n={}".format(n))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('pour_point_from_largest_stream', args, callback)  # returns 1 if error
+
     def qin_flow_accumulation(self, dem, output, out_type="specific contributing area", exponent=10.0, max_slope=45.0, threshold=None, log=False, clip=False, callback=None):
         """Calculates Qin et al. (2007) flow accumulation.
 
@@ -5777,6 +6497,30 @@ Okay, that's it for now.
         if esri_pntr: args.append("--esri_pntr")
         return self.run_tool('subbasins', args, callback)  # returns 1 if error
 
+    def subwta_to_wepp_management_grid(self, subwta, rules, output, stats=None, rasters=None, out_raster=None, on_unmatched="error", callback=None):
+        """Assigns WEPP management scenarios to TOPAZ hillslopes using an ordered rules file.
+
+        Keyword arguments:
+
+        subwta -- Input TOPAZ-style hillslope and channel ID raster file. 
+        stats -- Optional tab-separated per-hillslope statistics table with a topaz_id column. 
+        rasters -- Optional semicolon-separated list of name=file categorical rasters, e.g. 'landuse=nlcd.tif;soil=mukey.tif'. 
+        rules -- Input ordered management rules file, tab-separated or YAML (.yaml, .yml). 
+        output -- Output tab-separated per-hillslope management assignment table. 
+        out_raster -- Optional output raster of the management code of each hillslope. 
+        on_unmatched -- What to do with hillslopes that match no rule: fail ('error') or assign the 'default' rule. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--subwta='{}'".format(subwta))
+        if stats is not None: args.append("--stats='{}'".format(stats))
+        if rasters is not None: args.append("--rasters='{}'".format(rasters))
+        args.append("--rules='{}'".format(rules))
+        args.append("--output='{}'".format(output))
+        if out_raster is not None: args.append("--out_raster='{}'".format(out_raster))
+        args.append("--on_unmatched={}".format(on_unmatched))
+        return self.run_tool('subwta_to_wepp_management_grid', args, callback)  # returns 1 if error
+
     def trace_downslope_flowpaths(self, seed_pts, d8_pntr, output, esri_pntr=False, zero_background=False, callback=None):
         """Traces downslope flowpaths from one or more target sites (i.e. seed points).
 
@@ -5847,6 +6591,46 @@ Okay, that's it for now.
         if esri_pntr: args.append("--esri_pntr")
         return self.run_tool('watershed', args, callback)  # returns 1 if error
 
+    def watershed_area_report(self, watershed, output, dem=None, callback=None):
+        """Reports the area, perimeter, compactness, centroid, bounding box, and elevation range of each basin in a watershed raster.
+
+        Keyword arguments:
+
+        watershed -- Input watershed raster, either a binary mask or labelled basins. 
+        dem -- Optional input DEM raster used to report the elevation range of each basin. 
+        output -- Output tab-separated table, or JSON file (*.json), of basin metrics. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--watershed='{}'".format(watershed))
+        if dem is not None: args.append("--dem='{}'".format(dem))
+        args.append("--output='{}'".format(output))
+        return self.run_tool('watershed_area_report', args, callback)  # returns 1 if error
+
+    def wepp_channel_parameter_table(self, netw, coefficients, output, subwta=None, landuse=None, min_slope=0.001, clamp_slope=False, callback=None):
+        """Derives the WEPP channel slope, length, width, and Manning's n of each link of a HillslopesTopaz channel network table.
+
+        Keyword arguments:
+
+        netw -- Input channel network TSV file from HillslopesTopaz. 
+        coefficients -- Input tab-separated width regression coefficients and Manning's n lookup, with parameter, key, and value columns. 
+        output -- Output tab-separated WEPP channel parameter table. 
+        subwta -- Optional input TOPAZ-style hillslope and channel ID raster file; required with --landuse. 
+        landuse -- Optional input land use raster file, used for the Manning's n lookup. 
+        min_slope -- Channels with a slope below this value (m/m) are flagged. 
+        clamp_slope -- Set the slope of flagged channels to the minimum slope. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--netw='{}'".format(netw))
+        args.append("--coefficients='{}'".format(coefficients))
+        args.append("--output='{}'".format(output))
+        if subwta is not None: args.append("--subwta='{}'".format(subwta))
+        if landuse is not None: args.append("--landuse='{}'".format(landuse))
+        args.append("--min_slope={}".format(min_slope))
+        if clamp_slope: args.append("--clamp_slope")
+        return self.run_tool('wepp_channel_parameter_table', args, callback)  # returns 1 if error
+
     ##########################
     # Image Processing Tools #
     ##########################
@@ -6099,6 +6883,30 @@ Okay, that's it for now.
         args.append("--filtery={}".format(filtery))
         return self.run_tool('opening', args, callback)  # returns 1 if error
 
+    def raster_histogram_equalize(self, i, output, mode="percentile_stretch", low_percentile=2.0, high_percentile=98.0, mask=None, breakpoints=None, callback=None):
+        """Creates an 8-bit percentile-stretched or histogram-equalized raster for visualization.
+
+        Keyword arguments:
+
+        i -- Input raster file. 
+        mode -- Mapping mode; one of 'percentile_stretch' and 'hist_equalize'. 
+        low_percentile -- Percentile mapped onto the lowest output value (percentile_stretch). 
+        high_percentile -- Percentile mapped onto the highest output value (percentile_stretch). 
+        mask -- Optional mask raster; statistics are calculated from cells with positive values. 
+        output -- Output 8-bit raster file. 
+        breakpoints -- Optional output TSV file of the mapping; defaults to the output file with a .tsv extension. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input='{}'".format(i))
+        args.append("--mode={}".format(mode))
+        args.append("--low_percentile={}".format(low_percentile))
+        args.append("--high_percentile={}".format(high_percentile))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        args.append("--output='{}'".format(output))
+        if breakpoints is not None: args.append("--breakpoints='{}'".format(breakpoints))
+        return self.run_tool('raster_histogram_equalize', args, callback)  # returns 1 if error
+
     def remove_spurs(self, i, output, iterations=10, callback=None):
         """Removes the spurs (pruning operation) from a Boolean line image; intended to be used on the output of the LineThinning tool.
 
@@ -10035,6 +10843,34 @@ Okay, that's it for now.
         args.append("--output='{}'".format(output))
         return self.run_tool('tanh', args, callback)  # returns 1 if error
 
+    def temporal_raster_difference(self, input1, input2, output, mode="difference", mask=None, tolerance=0.0, summary=None, histogram=None, num_bins=50, callback=None):
+        """Calculates the change between two rasters from different times, with nodata-aware change statistics.
+
+        Keyword arguments:
+
+        input1 -- Input raster file from the earlier time. 
+        input2 -- Input raster file from the later time. 
+        output -- Output raster file. 
+        mode -- Change measure: 'difference' (input2 - input1), 'ratio' (input2 / input1), or 'percent_change'. 
+        mask -- Optional mask raster (e.g. a watershed); only cells with positive mask values are compared. 
+        tolerance -- Maximum departure from no change, in output units, for a cell to count as unchanged. 
+        summary -- Optional output tab-separated file of change statistics. 
+        histogram -- Optional output tab-separated histogram of the output values. 
+        num_bins -- Number of equal-width histogram bins. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--input1='{}'".format(input1))
+        args.append("--input2='{}'".format(input2))
+        args.append("--output='{}'".format(output))
+        args.append("--mode={}".format(mode))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        args.append("--tolerance={}".format(tolerance))
+        if summary is not None: args.append("--summary='{}'".format(summary))
+        if histogram is not None: args.append("--histogram='{}'".format(histogram))
+        args.append("--num_bins={}".format(num_bins))
+        return self.run_tool('temporal_raster_difference', args, callback)  # returns 1 if error
+
     def to_degrees(self, i, output, callback=None):
         """Converts a raster from radians to degrees.
 
@@ -10365,6 +11201,34 @@ Okay, that's it for now.
     # Stream Network Analysis #
     ###########################
 
+    def channel_cross_section_extractor(self, dem, output, spacing, width, streams=None, d8_pntr=None, channels=None, n_points=21, esri_pntr=False, callback=None):
+        """Samples DEM cross-sections perpendicular to channels at regular intervals.
+
+        Keyword arguments:
+
+        dem -- Input raster DEM file. 
+        streams -- Input raster streams file; requires --d8_pntr. 
+        d8_pntr -- Input raster D8 pointer file, used with --streams. 
+        channels -- Input channel lines vector file, digitized downstream; used instead of --streams. 
+        output -- Output table (*.tsv, *.txt) or lines vector (*.shp, *.geojson) of cross-sections. 
+        spacing -- Distance between sections along the channel, in map units. 
+        width -- Total length of each section, centred on the channel, in map units. 
+        n_points -- Number of evenly spaced points sampled across each section (at least 2). 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--dem='{}'".format(dem))
+        if streams is not None: args.append("--streams='{}'".format(streams))
+        if d8_pntr is not None: args.append("--d8_pntr='{}'".format(d8_pntr))
+        if channels is not None: args.append("--channels='{}'".format(channels))
+        args.append("--output='{}'".format(output))
+        args.append("--spacing='{}'".format(spacing))
+        args.append("--width='{}'".format(width))
+        args.append("--n_points={}".format(n_points))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('channel_cross_section_extractor', args, callback)  # returns 1 if error
+
     def distance_to_outlet(self, d8_pntr, streams, output, esri_pntr=False, zero_background=False, callback=None):
         """Calculates the distance of stream grid cells to the channel network outlet cell.
 
@@ -10695,7 +11559,25 @@ Okay, that's it for now.
         if zero_background: args.append("--zero_background")
         return self.run_tool('prune_strahler_stream_order', args, callback)  # returns 1 if error
 
-    def stream_junction_identifier(self, d8_pntr, streams, output, esri_pntr=False, callback=None):
+    def stream_buffer_zones(self, order, distances, output, mask=None, callback=None):
+        """Maps stream buffer zones whose width depends on stream order, coding each cell with the highest order whose buffer contains it.
+
+        Keyword arguments:
+
+        order -- Input raster stream order file, e.g. Strahler order. 
+        distances -- Input tab-separated table of the buffer distance, in map units, of each stream order. 
+        mask -- Optional input raster mask; cells that are nodata or 0 are nodata in the output. 
+        output -- Output raster file of buffer zones coded by stream order. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--order='{}'".format(order))
+        args.append("--distances='{}'".format(distances))
+        if mask is not None: args.append("--mask='{}'".format(mask))
+        args.append("--output='{}'".format(output))
+        return self.run_tool('stream_buffer_zones', args, callback)  # returns 1 if error
+
+    def stream_junction_identifier(self, d8_pntr, streams, output, esri_pntr=False, strict_pntr=False, callback=None):
         """Creates a stream map of channel inflow counts.
 
         Keyword arguments:
@@ -10705,6 +11587,7 @@ Okay, that's it for now.
         output    -- Output raster file (junction inflow counts 0–8, NoData elsewhere).
         esri_pntr -- D8 pointer uses the ESRI style scheme. 
         zero_background -- Flag indicating whether a background value of zero should be used. 
+        strict_pntr -- Raise an error, rather than a warning, when the stream network fits the other D8 pointer scheme much better than the selected one. 
         callback -- Custom function for handling tool text outputs.
         """
         args = []
@@ -10712,6 +11595,7 @@ Okay, that's it for now.
         args.append("--streams='{}'".format(streams))
         args.append("--output='{}'".format(output))
         if esri_pntr: args.append("--esri_pntr")
+        if strict_pntr: args.append("--strict_pntr")
         return self.run_tool('stream_junction_identifier', args, callback)  # returns 1 if error
     
     def stream_link_class(self, d8_pntr, streams, output, esri_pntr=False, zero_background=False, callback=None):
@@ -10774,6 +11658,28 @@ Okay, that's it for now.
         if zero_background: args.append("--zero_background")
         return self.run_tool('stream_link_length', args, callback)  # returns 1 if error
 
+    def stream_link_length_and_sinuosity(self, streams, d8_pntr, output, link_ids=None, output_raster=None, esri_pntr=False, callback=None):
+        """Measures the length, chord distance, and sinuosity of each link in a stream network.
+
+        Keyword arguments:
+
+        streams -- Input raster streams file. 
+        d8_pntr -- Input raster D8 pointer file. 
+        link_ids -- Optional input raster stream link ID file. 
+        output -- Output tab-separated table of link geometry metrics. 
+        output_raster -- Optional output raster in which each link's cells carry its sinuosity. 
+        esri_pntr -- D8 pointer uses the ESRI style scheme. 
+        callback -- Custom function for handling tool text outputs.
+        """
+        args = []
+        args.append("--streams='{}'".format(streams))
+        args.append("--d8_pntr='{}'".format(d8_pntr))
+        if link_ids is not None: args.append("--link_ids='{}'".format(link_ids))
+        args.append("--output='{}'".format(output))
+        if output_raster is not None: args.append("--output_raster='{}'".format(output_raster))
+        if esri_pntr: args.append("--esri_pntr")
+        return self.run_tool('stream_link_length_and_sinuosity', args, callback)  # returns 1 if error
+
     def stream_link_slope(self, d8_pntr, linkid, dem, output, esri_pntr=False, zero_background=False, callback=None):
         """Estimates the average slope of each link (or tributary) in a stream network.
 